@@ -88,7 +88,9 @@ export function useAutoUpdate() {
 
     // wait a bit after app startup to avoid interfering with initial load
     useTimeoutFn(() => {
-      checkForUpdates(false).catch(error => log.error(`${error}`))
+      loadCapabilities()
+        .then(capabilities => capabilities.autoUpdate ? checkForUpdates(false) : undefined)
+        .catch(error => log.error(`${error}`))
     }, 3_000)
   }
 
@@ -1,4 +1,4 @@
-import type { BranchError, BranchSyncStatus, Commit, CommitSyncStatus, GroupedBranchInfo, SyncEvent } from "~/utils/bindings"
+import type { BaselineAdvanced, BranchError, BranchSyncStatus, CiStatus, Commit, CommitOrder, CommitSyncStatus, GroupedBranchInfo, GroupingDiagnostic, NetworkStatus, PrefixTypo, ReviewSummary, SyncEvent } from "~/utils/bindings"
 import { commands } from "~/utils/bindings"
 import { Channel } from "@tauri-apps/api/core"
 import { UserError } from "~/composables/git/vcsRequest"
@@ -30,6 +30,8 @@ export interface RemoteStatus {
   commitsBehind: number
   myCommitsAhead: number
   lastPushTime: number // Unix timestamp, 0 = never pushed
+  review: ReviewSummary | null // open pull request activity, only with review status enabled
+  ciStatus: CiStatus | null // CI status of the pushed head commit, only with CI status enabled
 }
 
 // Reactive branch data that updates incrementally
@@ -55,11 +57,20 @@ export interface ReactiveBranch {
   allCommitsHaveIssueReferences: boolean
   // Most frequent author email in this branch's commits
   myEmail: string | null
+  // Pinned branches are ordered first by the backend
+  pinned: boolean
+  // Protected branches are never force-pushed or deleted
+  protected: boolean
+  // Authors other than the user whose commits are grouped in only-my-commits mode
+  otherAuthors: string[]
+  // Order in which the commits are applied to the branch
+  commitOrder: CommitOrder
 }
 
 // Create branch sync state
 export function createBranchSyncState(repository: ReturnType<typeof createRepositoryState>) {
   const { loadingPromise, vcsRequestFactory, selectedProject } = repository
+  const toast = useToast()
   // State - using explicit refs
   const syncError = shallowRef<string | null>(null)
   const isSyncing = shallowRef(false)
@@ -67,9 +78,19 @@ export function createBranchSyncState(repository: ReturnType<typeof createReposi
   const branchCollection = createReactiveIndexedCollection<string, ReactiveBranch>()
   const branches = branchCollection.array // Expose array for UI
   const unassignedCommits = ref<Commit[]>([])
+  // Commits with an ambiguous branch prefix, keyed by original commit hash
+  const groupingDiagnostics = shallowRef(new Map<string, GroupingDiagnostic>())
+  // Branches whose name is probably a misspelling of another branch name
+  const prefixTypos = shallowRef<PrefixTypo[]>([])
 
   // Repository-wide sync data
   const baselineBranch = shallowRef<string | null>(null)
+  // Progress of the fetch before the sync, null when not fetching
+  const fetchProgress = shallowRef<{ phase: string, percent: number | null } | null>(null)
+  // Reported by the remote status refresh, null until its first run
+  const networkStatus = shallowRef<NetworkStatus | null>(null)
+  // Remote baseline head the user was told about, so the same upstream commits are not announced twice
+  let reportedBaselineHead: string | null = null
 
   // Archived branches state
   const archivedBranches = createArchivedBranchesState(repository)
@@ -99,11 +120,11 @@ export function createBranchSyncState(repository: ReturnType<typeof createReposi
 
       // Start sync
       const vcsRequest = vcsRequestFactory.createRequest()
-      const result = await commands.syncBranches(vcsRequest, channel)
+      const result = await commands.syncBranches({ ...vcsRequest, commitFilter: null, eventFilter: null }, channel)
 
       // Check if the command returned an error via Result type
       if (result.status === "error") {
-        syncError.value = result.error || "Failed to sync branches"
+        syncError.value = result.error.message || "Failed to sync branches"
         console.error("Sync error:", result.error)
         return
       }
@@ -122,6 +143,7 @@ export function createBranchSyncState(repository: ReturnType<typeof createReposi
     }
     finally {
       isSyncing.value = false
+      fetchProgress.value = null
     }
   }
 
@@ -146,15 +168,44 @@ export function createBranchSyncState(repository: ReturnType<typeof createReposi
       case "unassignedCommits":
         handleUnassignedCommitsEvent(event.data)
         break
+      case "groupingDiagnostics":
+        groupingDiagnostics.value = new Map(event.data.diagnostics.map(diagnostic => [diagnostic.commitId, diagnostic]))
+        // Typos follow the diagnostics of the same sync
+        prefixTypos.value = []
+        break
+      case "possiblePrefixTypo":
+        handlePossiblePrefixTypoEvent(event.data)
+        break
       case "archivedBranchesFound":
         archivedBranches.updateFromArchivedNames(event.data.branchNames)
         break
       case "branchIntegrationDetected":
         archivedBranches.updateFromIntegrationInfo(event.data.info)
         break
+      case "branchAutoArchived":
+        handleBranchAutoArchivedEvent(event.data)
+        break
       case "remoteStatusUpdate":
         handleRemoteStatusUpdateEvent(event.data)
         break
+      case "uncommittedChangesRestored":
+        handleUncommittedChangesRestoredEvent(event.data)
+        break
+      case "syncPerformanceReport":
+        console.debug("Sync performance", event.data.report)
+        break
+      case "baselineAdvanced":
+        handleBaselineAdvancedEvent(event.data)
+        break
+      case "fetchProgress":
+        fetchProgress.value = event.data
+        break
+      case "fetchCompleted":
+        handleFetchCompletedEvent(event.data)
+        break
+      case "networkStatusChanged":
+        networkStatus.value = event.data.status
+        break
     }
   }
 
@@ -188,6 +239,10 @@ export function createBranchSyncState(repository: ReturnType<typeof createReposi
           allCommitsHaveIssueReferences: branch.allCommitsHaveIssueReferences,
           // Most frequent author email in this branch's commits
           myEmail: branch.myEmail ?? null,
+          pinned: branch.pinned,
+          protected: branch.protected,
+          otherAuthors: branch.otherAuthors,
+          commitOrder: branch.commitOrder,
           // Remote tracking (null = not yet loaded)
           remoteStatus: null as RemoteStatus | null,
           // Push state
@@ -249,6 +304,9 @@ export function createBranchSyncState(repository: ReturnType<typeof createReposi
         // update branch status and error info
         branch.statusText = "merge conflict"
       }
+      else if ("CaseCollision" in error) {
+        branch.statusText = "paths differ in case"
+      }
       else {
         branch.statusText = error.Generic
       }
@@ -293,7 +351,7 @@ export function createBranchSyncState(repository: ReturnType<typeof createReposi
           branch.statusText = "analyzing conflict…"
           break
         case "Error":
-          branch.statusText = "internal error"
+          branch.statusText = error && "CaseCollision" in error ? "paths differ in case" : "internal error"
           branch.hasError = true
           break
         default:
@@ -321,6 +379,112 @@ export function createBranchSyncState(repository: ReturnType<typeof createReposi
     }
   }
 
+  // Event handler for BranchAutoArchived events
+  function handleBranchAutoArchivedEvent(
+    data: Extract<SyncEvent, { type: "branchAutoArchived" }>["data"],
+  ) {
+    const branch = branchCollection.get(data.branchName)
+    if (branch) {
+      branch.status = "Integrated"
+      branch.statusText = data.prunedCommitCount > 0 ? "auto-archived, commits dropped" : "auto-archived"
+    }
+  }
+
+  // Event handler for UncommittedChangesRestored events, only conflicts need attention
+  function handleUncommittedChangesRestoredEvent(
+    data: Extract<SyncEvent, { type: "uncommittedChangesRestored" }>["data"],
+  ) {
+    if (data.conflictingFiles.length > 0) {
+      toast.add({
+        title: "Uncommitted changes not restored",
+        description: `Changes to ${data.conflictingFiles.join(", ")} conflict with the synced branch. Apply them with "git stash apply refs/branch-deck/autostash".`,
+        color: "warning",
+      })
+    }
+  }
+
+  // Event handler for BaselineAdvanced events, suggests a re-sync to pick up the new upstream commits
+  function handleBaselineAdvancedEvent(data: BaselineAdvanced) {
+    if (data.remoteHead === reportedBaselineHead) {
+      return
+    }
+    reportedBaselineHead = data.remoteHead
+    const commits = data.newCommitCount === 1 ? "1 new commit" : `${data.newCommitCount} new commits`
+    toast.add({
+      title: `${data.baselineBranch} has ${commits}`,
+      description: "The baseline advanced since the last sync. Sync again to rebuild the branches on top of it.",
+      color: "info",
+      actions: [
+        {
+          label: "Sync",
+          onClick: () => syncBranches(),
+        },
+      ],
+    })
+  }
+
+  // Event handler for FetchCompleted events, a failed fetch doesn't stop the sync
+  function handleFetchCompletedEvent(data: Extract<SyncEvent, { type: "fetchCompleted" }>["data"]) {
+    fetchProgress.value = null
+    if (data.error) {
+      toast.add({
+        title: "Fetch failed, synced offline",
+        description: `Branches were synced with the remote branches fetched earlier. ${data.error}`,
+        color: "warning",
+      })
+    }
+  }
+
+  // Checks the remote baseline for commits the last sync didn't include, e.g. when the window gets focus
+  async function checkBaselineUpdate() {
+    const repositoryPath = selectedProject.value?.path
+    if (!repositoryPath || isSyncing.value) {
+      return
+    }
+    const result = await commands.checkBaselineUpdate({ repositoryPath })
+    if (result.status === "error") {
+      // Not worth bothering the user, the remote may just be unreachable
+      console.warn("Failed to check the remote baseline", result.error)
+      return
+    }
+    if (result.data) {
+      handleBaselineAdvancedEvent(result.data)
+    }
+  }
+
+  // Event handler for PossiblePrefixTypo events, offers to move the commits to the suggested branch
+  function handlePossiblePrefixTypoEvent(typo: PrefixTypo) {
+    prefixTypos.value = [...prefixTypos.value, typo]
+    toast.add({
+      title: "Possible typo in branch name",
+      description: `"${typo.branchName}" looks like a misspelling of "${typo.suggestedBranchName}".`,
+      color: "warning",
+      duration: 0,
+      actions: [
+        {
+          label: `Move to ${typo.suggestedBranchName}`,
+          onClick: () => fixPrefixTypo(typo),
+        },
+      ],
+    })
+  }
+
+  // Rewords the prefix of the commits of the misspelled branch and syncs again
+  async function fixPrefixTypo(typo: PrefixTypo) {
+    const { repositoryPath } = vcsRequestFactory.createRequest()
+    const result = await commands.fixPrefixTypo({
+      repositoryPath,
+      branchName: typo.branchName,
+      suggestedBranchName: typo.suggestedBranchName,
+    })
+    if (result.status === "error") {
+      notifyError(`Failed to move commits to "${typo.suggestedBranchName}"`, result.error, toast)
+      return
+    }
+    prefixTypos.value = prefixTypos.value.filter(item => item.branchName !== typo.branchName)
+    await syncBranches()
+  }
+
   // Event handler for IssueNavigationConfig events
   function handleIssueNavigationConfigEvent(
     data: Extract<SyncEvent, { type: "issueNavigationConfig" }>["data"],
@@ -341,7 +505,7 @@ export function createBranchSyncState(repository: ReturnType<typeof createReposi
   function handleRemoteStatusUpdateEvent(
     data: Extract<SyncEvent, { type: "remoteStatusUpdate" }>["data"],
   ) {
-    const { branchName, remoteExists, unpushedCommits, commitsBehind, myUnpushedCount, lastPushTime } = data
+    const { branchName, remoteExists, unpushedCommits, commitsBehind, myUnpushedCount, lastPushTime, review, ciStatus } = data
     const branch = branchCollection.get(branchName)
     if (branch) {
       branch.remoteStatus = {
@@ -351,6 +515,8 @@ export function createBranchSyncState(repository: ReturnType<typeof createReposi
         commitsBehind,
         myCommitsAhead: myUnpushedCount ?? 0,
         lastPushTime: lastPushTime ?? 0,
+        review: review ?? null,
+        ciStatus: ciStatus ?? null,
       }
     }
   }
@@ -372,6 +538,10 @@ export function createBranchSyncState(repository: ReturnType<typeof createReposi
     branchItem.allCommitsHaveIssueReferences = branch.allCommitsHaveIssueReferences
     // Update author email
     branchItem.myEmail = branch.myEmail ?? null
+    branchItem.pinned = branch.pinned
+    branchItem.protected = branch.protected
+    branchItem.otherAuthors = branch.otherAuthors
+    branchItem.commitOrder = branch.commitOrder
     // Reset remote tracking info (null = not yet loaded)
     branchItem.remoteStatus = null
     // Reset push state
@@ -394,10 +564,16 @@ export function createBranchSyncState(repository: ReturnType<typeof createReposi
     hasCompletedSync: readonly(hasCompletedSync),
     branches,
     unassignedCommits,
+    groupingDiagnostics: readonly(groupingDiagnostics),
+    prefixTypos: readonly(prefixTypos),
     baselineBranch: readonly(baselineBranch),
+    fetchProgress: readonly(fetchProgress),
+    networkStatus: readonly(networkStatus),
 
     // Actions
     syncBranches,
+    fixPrefixTypo,
+    checkBaselineUpdate,
 
     // Archived branches (new composable)
     archivedBranches,
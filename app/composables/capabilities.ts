@@ -0,0 +1,18 @@
+import type { Capabilities } from "~/utils/bindings"
+
+let capabilitiesPromise: Promise<Capabilities> | null = null
+
+/**
+ * Optional subsystems of the backend build (auto-update, AI backends, hosting providers, ...), loaded once per window.
+ * Use it to hide features instead of calling commands that fail at runtime.
+ */
+export function loadCapabilities(): Promise<Capabilities> {
+  if (capabilitiesPromise === null) {
+    capabilitiesPromise = commands.getCapabilities()
+    // allow retrying after a failure
+    capabilitiesPromise.catch(() => {
+      capabilitiesPromise = null
+    })
+  }
+  return capabilitiesPromise
+}
@@ -0,0 +1,45 @@
+import { commands } from "~/utils/bindings"
+
+/**
+ * Handles the Export Crash Report menu item
+ * Crash reports are only written locally by the backend, the user decides whether to share the exported file
+ */
+export function useCrashReports() {
+  const toast = useToast()
+
+  async function exportLatestCrashReport() {
+    const reports = await commands.getCrashReports()
+    if (reports.status === "error") {
+      notifyError("Failed to read crash reports", reports.error, toast)
+      return
+    }
+    const latest = reports.data[0]
+    if (latest == null) {
+      toast.add({
+        title: "No crash reports",
+        description: "BranchDeck has not crashed recently.",
+        icon: "i-lucide-info",
+        color: "info",
+      })
+      return
+    }
+
+    const result = await commands.exportCrashReport(latest.id)
+    if (result.status === "error") {
+      notifyError("Failed to export crash report", result.error, toast)
+      return
+    }
+    if (result.data != null) {
+      toast.add({
+        title: "Crash report exported",
+        description: `${result.data}. Attach it to an issue to help fix the crash.`,
+        icon: "i-lucide-check",
+        color: "success",
+      })
+    }
+  }
+
+  scopedListen("export_crash_report", () => {
+    exportLatestCrashReport().catch(error => notifyError("Failed to export crash report", error, toast))
+  })
+}
@@ -0,0 +1,26 @@
+import { commands } from "~/utils/bindings"
+import type { CredentialPrompt } from "~/utils/bindings"
+import { Channel } from "@tauri-apps/api/core"
+import CredentialPromptModal from "~/components/CredentialPromptModal.vue"
+
+export function useCredentialPrompts() {
+  const overlay = useOverlay()
+
+  // git asks for credentials missing from the credential helpers, the answer is never stored by the app
+  const createCredentialPromptChannel = () => {
+    const credentialPrompts = new Channel<CredentialPrompt>()
+    credentialPrompts.onmessage = async (prompt) => {
+      const modal = overlay.create(CredentialPromptModal, {
+        props: {
+          prompt: prompt.prompt,
+          secret: prompt.secret,
+        },
+      })
+      const answer = await modal.open().result as string | null
+      await commands.answerCredentialPrompt(prompt.id, answer ?? null)
+    }
+    return credentialPrompts
+  }
+
+  return { createCredentialPromptChannel }
+}
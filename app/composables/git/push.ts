@@ -1,15 +1,30 @@
 import type { VcsRequestFactory } from "./vcsRequest"
 import type { ReactiveBranch } from "~/composables/branchSyncProvider"
 import { commands } from "~/utils/bindings"
+import type { PrePushEvent } from "~/utils/bindings"
+import { Channel } from "@tauri-apps/api/core"
+import { useCredentialPrompts } from "./credentialPrompts"
 // notifyError is auto-imported from shared-ui layer
 
 export function usePush(vcsRequestFactory: VcsRequestFactory, branches: Ref<ReactiveBranch[]>, baselineBranch: Ref<string | null>) {
   const toast = useToast()
+  const { createCredentialPromptChannel } = useCredentialPrompts()
 
   const findBranch = (branchName: string): ReactiveBranch | undefined => {
     return branches.value.find(branch => branch.name === branchName)
   }
 
+  // Review of the pushed branch, only queried with review status (branchdeck.reviewStatus) enabled
+  const refreshReview = async (repositoryPath: string, branchPrefix: string, branch: ReactiveBranch) => {
+    const result = await commands.getBranchReviewStatus({ repositoryPath, branchPrefix, branchNames: [branch.name] })
+    if (result.status === "error") {
+      console.warn("Failed to get the review status of the pushed branch", result.error)
+    }
+    else if (branch.remoteStatus) {
+      branch.remoteStatus.review = result.data[0]?.review ?? null
+    }
+  }
+
   const pushBranch = async (branchName: string) => {
     const branch = findBranch(branchName)
     if (branch) {
@@ -25,6 +40,14 @@ export function usePush(vcsRequestFactory: VcsRequestFactory, branches: Ref<Reac
         return { status: "error", error }
       }
 
+      // Pre-push check output goes to the console, a failure is reported with its last lines
+      const prePushOutput = new Channel<PrePushEvent>()
+      prePushOutput.onmessage = (event) => {
+        if (event.type === "Output") {
+          console.info(`[pre-push] ${event.data.line}`)
+        }
+      }
+
       const result = await commands.pushBranch({
         repositoryPath: request.repositoryPath,
         branchPrefix: request.branchPrefix,
@@ -32,7 +55,7 @@ export function usePush(vcsRequestFactory: VcsRequestFactory, branches: Ref<Reac
         totalCommits: branch.commitCount,
         myEmail: branch.myEmail,
         baselineBranch: baselineBranch.value!,
-      })
+      }, prePushOutput, createCredentialPromptChannel())
 
       if (result.status === "ok") {
         toast.add({
@@ -50,7 +73,27 @@ export function usePush(vcsRequestFactory: VcsRequestFactory, branches: Ref<Reac
           commitsBehind: remoteStatus.commitsBehind,
           myCommitsAhead: remoteStatus.myUnpushedCount ?? 0,
           lastPushTime: remoteStatus.lastPushTime ?? 0,
+          // Queried below, a pull request may already be open for the branch
+          review: branch.remoteStatus?.review ?? null,
+          // The pushed head has no CI status until the next refresh
+          ciStatus: null,
         }
+        void refreshReview(request.repositoryPath, request.branchPrefix, branch)
+      }
+      else if (result.error.code === "prePushCheckFailed") {
+        notifyError("Pre-push Check Failed", `${result.error.message}\n${result.error.context.output ?? ""}`.trim(), toast)
+      }
+      else if (result.error.code === "credentialsRequired") {
+        notifyError("Push Failed", `No credentials for ${result.error.context.host ?? "the remote"}. Store them in a git credential helper or enter them when asked.`, toast)
+      }
+      else if (result.error.code === "authenticationFailed") {
+        notifyError("Push Failed", `${result.error.context.host ?? "The remote"} refused the credentials, they were removed from the credential helper. Push again to enter new ones.`, toast)
+      }
+      else if (result.error.code === "sshAuthenticationFailed") {
+        const host = result.error.context.host ?? "The remote"
+        notifyError("Push Failed", result.error.context.reason === "hostKey"
+          ? `The host key of ${host} couldn't be verified. Confirm it when asked, or add it to ~/.ssh/known_hosts.`
+          : `${host} accepted none of the SSH keys. Add the key to the SSH agent or enter its passphrase when asked.`, toast)
       }
       else {
         notifyError("Push Failed", result.error, toast)
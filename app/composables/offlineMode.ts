@@ -0,0 +1,16 @@
+import { commands } from "~/utils/bindings"
+
+/**
+ * Applies the offline mode setting to the backend
+ * While it's on, remote status, hosting provider queries, the fetch before sync and model downloads are skipped
+ */
+export function useOfflineMode() {
+  const appSettings = useAppSettingsStore()
+
+  watch(() => appSettings.offlineMode, async (enabled) => {
+    const result = await commands.setOfflineMode(enabled)
+    if (result.status === "error") {
+      console.error("Failed to switch offline mode:", result.error)
+    }
+  }, { immediate: true })
+}
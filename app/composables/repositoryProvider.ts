@@ -1,6 +1,6 @@
 import { watchDebounced } from "@vueuse/core"
 import { commands } from "~/utils/bindings"
-import type { Result } from "~/utils/bindings"
+import type { BranchPrefixSource, CommandError, Result } from "~/utils/bindings"
 import { VcsRequestFactory } from "~/composables/git/vcsRequest"
 import { getErrorDetails } from "#layers/shared-ui/utils/errorHandling"
 
@@ -22,7 +22,9 @@ export function createRepositoryState() {
   // State
   const pathValidation = ref<PathValidation>({ valid: true })
   const isValidatingPath = ref(false)
-  const gitProvidedBranchPrefix = ref<Result<string, string>>({ status: "error", error: "Not loaded" })
+  const gitProvidedBranchPrefix = ref<Result<string, CommandError>>({ status: "error", error: { code: "internal", message: "Not loaded", context: {} } })
+  // Where the values of the git-provided prefix come from, in the order git reads them (the last one wins)
+  const branchPrefixSources = ref<BranchPrefixSource[]>([])
   const isLoadingBranchPrefix = ref(true)
   const loadingPromise = shallowRef<Promise<void> | null>(null)
 
@@ -105,7 +107,7 @@ export function createRepositoryState() {
       else {
         // Internal error (Result::Err from Rust)
         notifyInternalError(result.error, "Browse repository")
-        pathValidation.value = { valid: false, error: `Internal error: ${result.error}` }
+        pathValidation.value = { valid: false, error: `Internal error: ${result.error.message}` }
       }
     }
     catch (error) {
@@ -144,22 +146,25 @@ export function createRepositoryState() {
         pathValidation.value = {
           valid: false,
           path: currentPath,
-          error: result.error,
-          errorDetails: result.error,
+          error: result.error.message,
+          errorDetails: result.error.message,
         }
 
         // Set empty prefix - error is already in pathValidation for UI display
         gitProvidedBranchPrefix.value = { status: "ok", data: "" }
+        branchPrefixSources.value = []
         return
       }
 
+      const prefix = result.data.prefix
       if (project != null) {
-        project.cachedBranchPrefix = result.status === "ok" && result.data ? result.data : undefined
+        project.cachedBranchPrefix = prefix || undefined
       }
 
       // check if project changed while we were loading
       if (selectedProject.value?.path === currentPath) {
-        gitProvidedBranchPrefix.value = result
+        gitProvidedBranchPrefix.value = { status: "ok", data: prefix }
+        branchPrefixSources.value = result.data.sources
         // Repository is accessible - clear any previous errors
         pathValidation.value = { valid: true }
       }
@@ -180,6 +185,7 @@ export function createRepositoryState() {
 
       // Set empty prefix - error is already in pathValidation for UI display
       gitProvidedBranchPrefix.value = { status: "ok", data: "" }
+      branchPrefixSources.value = []
     }
     finally {
       // Only set loading to false if we're still on the same path
@@ -216,6 +222,7 @@ export function createRepositoryState() {
     pathValidation: readonly(pathValidation),
     isValidatingPath: readonly(isValidatingPath),
     gitProvidedBranchPrefix: readonly(gitProvidedBranchPrefix),
+    branchPrefixSources: readonly(branchPrefixSources),
     isLoadingBranchPrefix: readonly(isLoadingBranchPrefix),
     loadingPromise: readonly(loadingPromise),
 
@@ -0,0 +1,73 @@
+import { commands } from "~/utils/bindings"
+
+/**
+ * Handles the Export/Import Settings menu items
+ * The app settings come from and go to the app store, the backend handles the file and the git config
+ */
+export function useSettingsTransfer() {
+  const toast = useToast()
+
+  function repositoryPaths() {
+    return repositorySettings.map(project => project.path)
+  }
+
+  async function exportSettings() {
+    const result = await commands.exportSettings({
+      appSettings: JSON.stringify(appStore.getStoreSnapshot()),
+      repositoryPaths: repositoryPaths(),
+    })
+    if (result.status === "error") {
+      notifyError("Failed to export settings", result.error, toast)
+      return
+    }
+    if (result.data != null) {
+      toast.add({
+        title: "Settings exported",
+        description: result.data,
+        icon: "i-lucide-check",
+        color: "success",
+      })
+    }
+  }
+
+  async function importSettings() {
+    const result = await commands.importSettings({ repositoryPaths: repositoryPaths() })
+    if (result.status === "error") {
+      notifyError("Failed to import settings", result.error, toast)
+      return
+    }
+    const imported = result.data
+    if (imported == null) {
+      return
+    }
+
+    const appSettings = JSON.parse(imported.appSettings) as Record<string, unknown>
+    for (const [key, value] of Object.entries(appSettings)) {
+      await appStore.set(key, value)
+    }
+
+    const details = [`Updated git config of ${imported.appliedRepositories.length} repositories.`]
+    if (imported.unmatchedRepositories.length > 0) {
+      details.push(`Not found on this machine: ${imported.unmatchedRepositories.join(", ")}.`)
+    }
+    if (imported.skippedKeys.length > 0) {
+      details.push(`Not imported, set up by hand if you trust the file: ${imported.skippedKeys.join(", ")}.`)
+    }
+    if (imported.migratedFromVersion != null) {
+      details.push(`Converted from format version ${imported.migratedFromVersion}.`)
+    }
+    toast.add({
+      title: "Settings imported",
+      description: details.join(" "),
+      icon: "i-lucide-check",
+      color: "success",
+    })
+  }
+
+  scopedListen("export_settings", () => {
+    exportSettings().catch(error => notifyError("Failed to export settings", error, toast))
+  })
+  scopedListen("import_settings", () => {
+    importSettings().catch(error => notifyError("Failed to import settings", error, toast))
+  })
+}
@@ -0,0 +1,43 @@
+import { formatTimestamp } from "#layers/shared-ui/utils/time"
+import { commands } from "~/utils/bindings"
+import type { UsageStats } from "~/utils/bindings"
+
+/**
+ * Handles the Usage Statistics menu item
+ * The statistics are counted and stored locally by the backend, per repository
+ */
+export function useUsageStats(selectedProject: Ref<ProjectMetadata | null>) {
+  const toast = useToast()
+
+  function describe(stats: UsageStats) {
+    const counts = [
+      `${stats.syncs} syncs`,
+      `${stats.mergeConflicts} merge conflicts`,
+      `${stats.pushes} pushes`,
+      `${stats.aiSuggestionsAccepted} accepted AI suggestions`,
+    ].join(", ")
+    return stats.since == null ? "Nothing recorded yet." : `${counts} since ${formatTimestamp(stats.since)}.`
+  }
+
+  async function showUsageStats() {
+    const repositoryPath = selectedProject.value?.path
+    if (repositoryPath == null) {
+      return
+    }
+    const result = await commands.getUsageStats(repositoryPath)
+    if (result.status === "error") {
+      notifyError("Failed to read usage statistics", result.error, toast)
+      return
+    }
+    toast.add({
+      title: "Usage statistics",
+      description: describe(result.data),
+      icon: "i-lucide-chart-column",
+      color: "info",
+    })
+  }
+
+  scopedListen("show_usage_stats", () => {
+    showUsageStats().catch(error => notifyError("Failed to read usage statistics", error, toast))
+  })
+}
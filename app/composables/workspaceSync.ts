@@ -0,0 +1,129 @@
+import { Channel } from "@tauri-apps/api/core"
+import { commands } from "~/utils/bindings"
+import type { SyncSummary, WorkspaceSyncEvent } from "~/utils/bindings"
+
+export type WorkspaceRepositoryStatus = "pending" | "syncing" | "done" | "failed"
+
+// Overview of one repository during and after a workspace sync
+export interface WorkspaceRepositoryState {
+  status: WorkspaceRepositoryStatus
+  branchPrefix?: string
+  branchCount: number
+  summary?: SyncSummary
+}
+
+/**
+ * Workspaces (groups of repositories) and their bulk sync
+ * The grouping is persisted in the app store, the sync state is kept per repository path for one overview
+ */
+export function useWorkspaceSync() {
+  const workspaces = useWorkspacesStore()
+  const appSettings = useAppSettingsStore()
+
+  const repositoryStates = reactive(new Map<string, WorkspaceRepositoryState>())
+  const syncingWorkspace = ref<string | null>(null)
+
+  function findWorkspace(name: string) {
+    return workspaces.find(workspace => workspace.name === name)
+  }
+
+  function createWorkspace(name: string, repositoryPaths: string[] = []) {
+    if (findWorkspace(name) != null) {
+      throw new Error(`Workspace "${name}" already exists`)
+    }
+    workspaces.push({ name, repositoryPaths: [...new Set(repositoryPaths)] })
+  }
+
+  function deleteWorkspace(name: string) {
+    const index = workspaces.findIndex(workspace => workspace.name === name)
+    if (index !== -1) {
+      workspaces.splice(index, 1)
+    }
+  }
+
+  function addRepository(name: string, repositoryPath: string) {
+    const workspace = findWorkspace(name)
+    if (workspace != null && !workspace.repositoryPaths.includes(repositoryPath)) {
+      workspace.repositoryPaths.push(repositoryPath)
+    }
+  }
+
+  function removeRepository(name: string, repositoryPath: string) {
+    const workspace = findWorkspace(name)
+    if (workspace != null) {
+      workspace.repositoryPaths = workspace.repositoryPaths.filter(path => path !== repositoryPath)
+    }
+  }
+
+  function handleEvent(event: WorkspaceSyncEvent) {
+    switch (event.type) {
+      case "repositorySyncStarted": {
+        const state = repositoryStates.get(event.data.repositoryPath)
+        if (state != null) {
+          state.status = "syncing"
+          state.branchPrefix = event.data.branchPrefix
+        }
+        break
+      }
+      case "repositorySyncEvent": {
+        const state = repositoryStates.get(event.data.repositoryPath)
+        if (state != null && event.data.event.type === "branchesGrouped") {
+          state.branchCount = event.data.event.data.branches.length
+        }
+        break
+      }
+      case "repositorySyncFinished": {
+        const summary = event.data.summary
+        const state = repositoryStates.get(summary.repositoryPath)
+        if (state != null) {
+          state.status = summary.success ? "done" : "failed"
+          state.summary = summary
+        }
+        break
+      }
+    }
+  }
+
+  async function syncWorkspace(name: string): Promise<SyncSummary[]> {
+    const workspace = findWorkspace(name)
+    if (workspace == null || syncingWorkspace.value != null) {
+      return []
+    }
+
+    repositoryStates.clear()
+    for (const repositoryPath of workspace.repositoryPaths) {
+      repositoryStates.set(repositoryPath, { status: "pending", branchCount: 0 })
+    }
+
+    const channel = new Channel<WorkspaceSyncEvent>()
+    channel.onmessage = handleEvent
+
+    // The global prefix applies to every repository, otherwise each one uses its own git config
+    const branchPrefix = appSettings.globalUserBranchPrefix || null
+    syncingWorkspace.value = name
+    try {
+      const result = await commands.syncWorkspace({
+        repositories: workspace.repositoryPaths.map(repositoryPath => ({ repositoryPath, branchPrefix })),
+      }, channel)
+      if (result.status === "error") {
+        notifyError(`Failed to sync workspace "${name}"`, result.error)
+        return []
+      }
+      return result.data
+    }
+    finally {
+      syncingWorkspace.value = null
+    }
+  }
+
+  return {
+    workspaces: readonly(workspaces),
+    repositoryStates: readonly(repositoryStates),
+    syncingWorkspace: readonly(syncingWorkspace),
+    createWorkspace,
+    deleteWorkspace,
+    addRepository,
+    removeRepository,
+    syncWorkspace,
+  }
+}
@@ -7,11 +7,14 @@
 
 export const commands = {
 /**
- * Pushes a specific branch to the remote repository and returns updated remote status
+ * Pushes a specific branch to the remote repository and returns updated remote status.
+ * The pre-push check (if enabled) runs first, its output is streamed over the channel.
+ * Credentials missing from the git credential helpers are asked for over `credential_prompts`,
+ * answered with `answer_credential_prompt`.
  */
-async pushBranch(params: PushBranchParams) : Promise<Result<RemoteStatusUpdate, string>> {
+async pushBranch(params: PushBranchParams, prePushOutput: TAURI_CHANNEL<PrePushEvent>, credentialPrompts: TAURI_CHANNEL<CredentialPrompt>) : Promise<Result<RemoteStatusUpdate, CommandError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("push_branch", { params }) };
+    return { status: "ok", data: await TAURI_INVOKE("push_branch", { params, prePushOutput, credentialPrompts }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
@@ -20,7 +23,7 @@ async pushBranch(params: PushBranchParams) : Promise<Result<RemoteStatusUpdate,
 /**
  * Synchronizes branches by grouping commits by prefix and creating/updating branches
  */
-async syncBranches(params: SyncBranchesParams, progress: TAURI_CHANNEL<SyncEvent>) : Promise<Result<null, string>> {
+async syncBranches(params: SyncBranchesParams, progress: TAURI_CHANNEL<SyncEvent>) : Promise<Result<null, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("sync_branches", { params, progress }) };
 } catch (e) {
@@ -28,7 +31,10 @@ async syncBranches(params: SyncBranchesParams, progress: TAURI_CHANNEL<SyncEvent
     else return { status: "error", error: e  as any };
 }
 },
-async getBranchPrefixFromGitConfig(params: GetBranchPrefixParams) : Promise<Result<string, string>> {
+/**
+ * Returns the branch prefix with the config files its values come from, in the order git reads them
+ */
+async getBranchPrefixFromGitConfig(params: GetBranchPrefixParams) : Promise<Result<BranchPrefixResolution, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_branch_prefix_from_git_config", { params }) };
 } catch (e) {
@@ -39,7 +45,7 @@ async getBranchPrefixFromGitConfig(params: GetBranchPrefixParams) : Promise<Resu
 /**
  * Opens a native file dialog to browse for a git repository
  */
-async browseRepository() : Promise<Result<BrowseResult, string>> {
+async browseRepository() : Promise<Result<BrowseResult, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("browse_repository") };
 } catch (e) {
@@ -51,7 +57,7 @@ async browseRepository() : Promise<Result<BrowseResult, string>> {
  * Validates that a repository path exists and is a git repository
  * Returns empty string if valid, error message if invalid
  */
-async validateRepositoryPath(params: ValidateRepositoryPathParams) : Promise<Result<string, string>> {
+async validateRepositoryPath(params: ValidateRepositoryPathParams) : Promise<Result<string, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("validate_repository_path", { params }) };
 } catch (e) {
@@ -59,7 +65,7 @@ async validateRepositoryPath(params: ValidateRepositoryPathParams) : Promise<Res
     else return { status: "error", error: e  as any };
 }
 },
-async checkForUpdates() : Promise<Result<UpdateInfo, string>> {
+async checkForUpdates() : Promise<Result<UpdateInfo, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("check_for_updates") };
 } catch (e) {
@@ -67,7 +73,7 @@ async checkForUpdates() : Promise<Result<UpdateInfo, string>> {
     else return { status: "error", error: e  as any };
 }
 },
-async getUpdateStatus() : Promise<Result<UpdateInfo, string>> {
+async getUpdateStatus() : Promise<Result<UpdateInfo, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_update_status") };
 } catch (e) {
@@ -75,7 +81,7 @@ async getUpdateStatus() : Promise<Result<UpdateInfo, string>> {
     else return { status: "error", error: e  as any };
 }
 },
-async installUpdate() : Promise<Result<null, string>> {
+async installUpdate() : Promise<Result<null, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("install_update") };
 } catch (e) {
@@ -95,7 +101,7 @@ async openSubWindow(params: OpenSubWindowParams) : Promise<Result<null, WindowEr
  * Assigns commits to a branch by prepending a branch prefix to their messages.
  * Uses git plumbing commands to efficiently rewrite commit messages without touching the working directory.
  */
-async createBranchFromCommits(params: CreateBranchFromCommitsParams) : Promise<Result<RewordResult, string>> {
+async createBranchFromCommits(params: CreateBranchFromCommitsParams) : Promise<Result<RewordResult, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("create_branch_from_commits", { params }) };
 } catch (e) {
@@ -107,7 +113,7 @@ async createBranchFromCommits(params: CreateBranchFromCommitsParams) : Promise<R
  * Adds an issue reference to commits in a branch that don't already have one.
  * Updates commit messages from "(branch-name) message" to "(branch-name) ISSUE-123 message"
  */
-async addIssueReferenceToCommits(params: AddIssueReferenceParams) : Promise<Result<AddIssueReferenceResult, string>> {
+async addIssueReferenceToCommits(params: AddIssueReferenceParams) : Promise<Result<AddIssueReferenceResult, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("add_issue_reference_to_commits", { params }) };
 } catch (e) {
@@ -119,7 +125,7 @@ async addIssueReferenceToCommits(params: AddIssueReferenceParams) : Promise<Resu
  * Amend uncommitted changes to the original commit corresponding to a virtual branch tip.
  * This operation modifies the main branch history and requires a sync afterward to recreate virtual branches.
  */
-async amendUncommittedToBranch(params: AmendUncommittedToBranchParams) : Promise<Result<AmendCommandResult, string>> {
+async amendUncommittedToBranch(params: AmendUncommittedToBranchParams) : Promise<Result<AmendCommandResult, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("amend_uncommitted_to_branch", { params }) };
 } catch (e) {
@@ -127,7 +133,7 @@ async amendUncommittedToBranch(params: AmendUncommittedToBranchParams) : Promise
     else return { status: "error", error: e  as any };
 }
 },
-async suggestBranchNameStream(params: SuggestBranchNameParams, progress: TAURI_CHANNEL<SuggestionProgress>) : Promise<Result<null, string>> {
+async suggestBranchNameStream(params: SuggestBranchNameParams, progress: TAURI_CHANNEL<SuggestionProgress>) : Promise<Result<null, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("suggest_branch_name_stream", { params, progress }) };
 } catch (e) {
@@ -135,7 +141,7 @@ async suggestBranchNameStream(params: SuggestBranchNameParams, progress: TAURI_C
     else return { status: "error", error: e  as any };
 }
 },
-async getArchivedBranchCommits(repositoryPath: string, branchName: string) : Promise<Result<Commit[], string>> {
+async getArchivedBranchCommits(repositoryPath: string, branchName: string) : Promise<Result<Commit[], CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_archived_branch_commits", { repositoryPath, branchName }) };
 } catch (e) {
@@ -143,15 +149,42 @@ async getArchivedBranchCommits(repositoryPath: string, branchName: string) : Pro
     else return { status: "error", error: e  as any };
 }
 },
-async deleteArchivedBranch(params: DeleteArchivedBranchParams) : Promise<Result<null, string>> {
+/**
+ * Deletes an archived branch and, with `deleteRemote`, its remote branches.
+ * Credentials for deleting the remote branches are asked for over `credential_prompts`, like for `push_branch`.
+ */
+async deleteArchivedBranch(params: DeleteArchivedBranchParams, credentialPrompts: TAURI_CHANNEL<CredentialPrompt>) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_archived_branch", { params, credentialPrompts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Deletes archived branches matching the retention policy, or only reports them in dry-run mode
+ */
+async applyArchiveRetention(params: ApplyArchiveRetentionParams) : Promise<Result<ArchiveRetentionReport, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("apply_archive_retention", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Runs the full integration detection of archived branches whose detection was truncated by the budget,
+ * sending `branchIntegrationDetected` events over the channel
+ */
+async resumeIntegrationDetection(params: ResumeIntegrationDetectionParams, progress: TAURI_CHANNEL<SyncEvent>) : Promise<Result<null, CommandError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("delete_archived_branch", { params }) };
+    return { status: "ok", data: await TAURI_INVOKE("resume_integration_detection", { params, progress }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
-async unapplyBranch(params: UnapplyBranchParams) : Promise<Result<UnapplyBranchResult, string>> {
+async unapplyBranch(params: UnapplyBranchParams) : Promise<Result<UnapplyBranchResult, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("unapply_branch", { params }) };
 } catch (e) {
@@ -162,7 +195,7 @@ async unapplyBranch(params: UnapplyBranchParams) : Promise<Result<UnapplyBranchR
 /**
  * Get uncommitted changes with only file metadata (no content or diffs)
  */
-async getUncommittedChanges(params: GetUncommittedChangesParams) : Promise<Result<UncommittedChangesResult, string>> {
+async getUncommittedChanges(params: GetUncommittedChangesParams) : Promise<Result<UncommittedChangesResult, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_uncommitted_changes", { params }) };
 } catch (e) {
@@ -173,7 +206,7 @@ async getUncommittedChanges(params: GetUncommittedChangesParams) : Promise<Resul
 /**
  * Get file content for diff display when user expands a file in the UI
  */
-async getFileContentForDiff(params: GetFileContentForDiffParams) : Promise<Result<FileDiff, string>> {
+async getFileContentForDiff(params: GetFileContentForDiffParams) : Promise<Result<FileDiff, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_file_content_for_diff", { params }) };
 } catch (e) {
@@ -181,7 +214,7 @@ async getFileContentForDiff(params: GetFileContentForDiffParams) : Promise<Resul
     else return { status: "error", error: e  as any };
 }
 },
-async updateMenuCheckbox(menuId: string, checked: boolean) : Promise<Result<null, string>> {
+async updateMenuCheckbox(menuId: string, checked: boolean) : Promise<Result<null, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("update_menu_checkbox", { menuId, checked }) };
 } catch (e) {
@@ -189,7 +222,7 @@ async updateMenuCheckbox(menuId: string, checked: boolean) : Promise<Result<null
     else return { status: "error", error: e  as any };
 }
 },
-async downloadModel(progress: TAURI_CHANNEL<DownloadProgress>) : Promise<Result<null, string>> {
+async downloadModel(progress: TAURI_CHANNEL<DownloadProgress>) : Promise<Result<null, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("download_model", { progress }) };
 } catch (e) {
@@ -197,7 +230,7 @@ async downloadModel(progress: TAURI_CHANNEL<DownloadProgress>) : Promise<Result<
     else return { status: "error", error: e  as any };
 }
 },
-async checkModelStatus() : Promise<Result<ModelStatus, string>> {
+async checkModelStatus() : Promise<Result<ModelStatus, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("check_model_status") };
 } catch (e) {
@@ -205,7 +238,7 @@ async checkModelStatus() : Promise<Result<ModelStatus, string>> {
     else return { status: "error", error: e  as any };
 }
 },
-async cancelModelDownload() : Promise<Result<null, string>> {
+async cancelModelDownload() : Promise<Result<null, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("cancel_model_download") };
 } catch (e) {
@@ -213,164 +246,2613 @@ async cancelModelDownload() : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
-async clearModelCache(params: ClearModelCacheParams) : Promise<Result<CacheClearResult, string>> {
+async clearModelCache(params: ClearModelCacheParams) : Promise<Result<CacheClearResult, CommandError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("clear_model_cache", { params }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
-}
-}
-
-/** user-defined events **/
-
-
-
-/** user-defined constants **/
-
-
-
-/** user-defined types **/
-
-export type AddIssueReferenceParams = { repositoryPath: string; branchName: string; commits: CommitInfo[]; issueReference: string }
-export type AddIssueReferenceResult = { success: boolean; updatedCount: number; skippedCount: number }
+},
 /**
- * Result type for amend command that can be properly serialized by Tauri
+ * Dev-only: fabricate a scratch repository with a conflicting branch to explore the conflict viewer
  */
-export type AmendCommandResult = { status: "ok"; data: AmendResult } | { status: "branchError"; data: BranchError }
-export type AmendResult = { amendedCommitId: string; rebasedToCommit: string }
-export type AmendUncommittedToBranchParams = { repositoryPath: string; branchName: string; originalCommitId: string; files: string[] }
+async simulateConflict(params: SimulateConflictParams) : Promise<Result<SimulateConflictResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("simulate_conflict", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Branch operation errors.
+ * Returns pinned branches and the manual branch order persisted in the repository state
  */
-export type BranchError = { Generic: string } | { MergeConflict: MergeConflictInfo }
+async getBranchOrder(params: GetBranchOrderParams) : Promise<Result<BranchOrder, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_branch_order", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Unified branch integration info
+ * Pins or unpins a branch
  */
-export type BranchIntegrationInfo = { name: string; summary: string; status: BranchIntegrationStatus }
+async setBranchPinned(params: SetBranchPinnedParams) : Promise<Result<BranchOrder, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_branch_pinned", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Unified branch integration status
+ * Persists a manual display order for branches
  */
-export type BranchIntegrationStatus = { kind: "integrated"; integratedAt: number | null; confidence: IntegrationConfidence; commitCount: number } | { kind: "notIntegrated"; totalCommitCount: number; integratedCount: number; orphanedCount: number; integratedAt: number | null } | { kind: "partial"; missing: number }
+async reorderBranches(params: ReorderBranchesParams) : Promise<Result<BranchOrder, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reorder_branches", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Branch name suggestion
+ * Sets the order in which the commits of a branch are applied, effective on the next sync
  */
-export type BranchSuggestion = { name: string; reason: string | null }
+async setBranchCommitOrder(params: SetBranchCommitOrderParams) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_branch_commit_order", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Status of a branch synchronization operation.
+ * Returns the commits a commit is declared to depend on
  */
-export type BranchSyncStatus = "Created" | "Updated" | "Unchanged" | "Error" | "MergeConflict" | "AnalyzingConflict"
+async getCommitDependencies(params: GetCommitDependenciesParams) : Promise<Result<string[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_commit_dependencies", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Result of browsing for a repository
+ * Declares that a commit depends on other commits; sync refuses to sync a branch that doesn't contain them before it
  */
-export type BrowseResult = { path: string | null; valid: boolean; error: string | null }
-export type CacheClearResult = { cleared_models: string[]; total_size_mb: number; errors: string[] }
-export type ClearModelCacheParams = { keepCurrent: boolean }
+async setCommitDependencies(params: SetCommitDependenciesParams) : Promise<Result<string[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_commit_dependencies", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Struct to hold commit data returned by git CLI
+ * Exports a virtual branch as a `git format-patch` series or a single mbox file
  */
-export type Commit = { originalHash: string; strippedSubject: string; message: string; author: string; authorTime: number; committerTime: number }
+async exportBranchPatches(params: ExportBranchPatchesParams) : Promise<Result<ExportBranchPatchesResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_branch_patches", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Simple commit information with hash and message.
- * Used for passing commit data between frontend and backend.
+ * Applies a patch series onto the current branch with subjects prefixed to group into a virtual branch
  */
-export type CommitInfo = { hash: string; message: string }
+async applyPatchSeries(params: ApplyPatchSeriesParams) : Promise<Result<ApplyPatchSeriesResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("apply_patch_series", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Status of a commit synchronization.
+ * Returns the SMTP settings used to send patches (`sendemail.*` git config)
  */
-export type CommitSyncStatus = "Pending" | "Created" | "Unchanged" | "Error" | "Blocked"
+async getSmtpConfig(params: GetSmtpConfigParams) : Promise<Result<SmtpConfig, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_smtp_config", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Analysis results for a merge conflict, including missing commits and divergence information.
+ * Stores the SMTP settings in the repository config
  */
-export type ConflictAnalysis = { missingCommits: MissingCommit[]; mergeBaseHash: string; mergeBaseSubject: string; mergeBaseMessage: string; mergeBaseTime: number; mergeBaseAuthor: string; divergenceSummary: DivergenceSummary }
+async setSmtpConfig(params: SetSmtpConfigParams) : Promise<Result<SmtpConfig, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_smtp_config", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Represents details of a conflict during a cherry-pick operation.
- * 
- * Includes the path of the conflicted file, its status, and the diff details for the conflict.
+ * Returns the cover letter template of a branch
  */
-export type ConflictDetail = { file: string; status: string; fileDiff: FileDiff; baseFile: FileInfo | null; targetFile: FileInfo | null; cherryFile: FileInfo | null; baseToTargetDiff: FileDiff; baseToCherryDiff: FileDiff }
+async getCoverLetterTemplate(params: GetCoverLetterTemplateParams) : Promise<Result<string, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_cover_letter_template", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Information about a commit referenced in conflict markers
+ * Sets or resets the cover letter template of a branch
  */
-export type ConflictMarkerCommitInfo = { hash: string; message: string; author: string; authorTime: number; committerTime: number }
-export type CreateBranchFromCommitsParams = { repositoryPath: string; branchName: string; commitIds: string[] }
-export type DeleteArchivedBranchParams = { repositoryPath: string; branchName: string; branchPrefix: string }
+async setCoverLetterTemplate(params: SetCoverLetterTemplateParams) : Promise<Result<string, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_cover_letter_template", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Summary of how two branches have diverged from their common ancestor.
+ * Sends the patches of a virtual branch with `git send-email`, reporting each sent mail
  */
-export type DivergenceSummary = { commitsAheadInSource: number; commitsAheadInTarget: number; commonAncestorDistance: number }
+async sendBranchPatches(params: SendBranchPatchesParams, progress: TAURI_CHANNEL<SendEmailProgress>) : Promise<Result<SendBranchPatchesResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_branch_patches", { params, progress }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Progress events for model download operations
+ * Deletes many archived branches in one ref transaction, reporting progress over the channel
  */
-export type DownloadProgress = { type: "Started"; data: { totalFiles: number } } | { type: "FileStarted"; data: { fileName: string; fileSize: number | null } } | { type: "Progress"; data: { fileName: string; downloaded: number; total: number; bytesPerSecond: number | null; secondsRemaining: number | null } } | { type: "FileCompleted"; data: { fileName: string } } | { type: "Completed" } | { type: "Cancelled" } | { type: "Error"; data: { message: string } }
+async deleteArchivedBranches(params: DeleteArchivedBranchesParams, progress: TAURI_CHANNEL<DeleteArchivedBranchesProgress>) : Promise<Result<DeleteArchivedBranchesResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_archived_branches", { params, progress }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Represents the diff between two versions of a file.
+ * Starts periodically fetching the remote and sending updated remote status of all virtual branches over the channel.
+ * Replaces a refresh already running for the repository. Fails if the interval is out of range.
  */
-export type FileDiff = { oldFile: FileInfo; newFile: FileInfo; hunks: string[] }
+async startRemoteStatusRefresh(params: StartRemoteStatusRefreshParams, progress: TAURI_CHANNEL<SyncEvent>) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_remote_status_refresh", { params, progress }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Information about a file including its content and metadata.
+ * Stops the periodic remote status refresh of a repository, returns false if none was running
  */
-export type FileInfo = { fileName: string; fileLang: string; content: string }
-export type GetBranchPrefixParams = { repositoryPath: string }
-export type GetFileContentForDiffParams = { repositoryPath: string; filePath: string }
-export type GetUncommittedChangesParams = { repositoryPath: string }
-export type GroupedBranchInfo = { name: string; commits: Commit[]; latestCommitTime: number; summary: string; allCommitsHaveIssueReferences: boolean; 
+async stopRemoteStatusRefresh(params: StopRemoteStatusRefreshParams) : Promise<Result<boolean, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_remote_status_refresh", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Most frequent author email in this branch's commits
+ * Returns a page of archived branch commits without diffs, newest page first
  */
-myEmail: string | null }
+async getArchivedBranchCommitsPage(params: GetArchivedBranchCommitsPageParams) : Promise<Result<ArchivedBranchCommitsPage, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_archived_branch_commits_page", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Confidence level for integration detection
+ * Returns the file diffs of a single commit, loaded when the user expands it
  */
-export type IntegrationConfidence = "Exact" | "High"
-export type IssueNavigationConfig = { links: IssueNavigationLink[] }
-export type IssueNavigationLink = { issueRegexp: string; linkRegexp: string }
+async getCommitDiff(params: GetCommitDiffParams) : Promise<Result<FileDiff[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_commit_diff", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Details about a merge conflict encountered during a cherry-pick operation.
- * 
- * Contains information about the conflicting files, associated commit details, and conflict analysis results.
+ * Renames a virtual branch: rewrites the commit prefixes and migrates the virtual, archived and remote branches.
+ * Credentials for renaming the remote branch are asked for over `credential_prompts`, like for `push_branch`.
  */
-export type MergeConflictInfo = { commitMessage: string; commitHash: string; commitAuthorTime: number; commitCommitterTime: number; originalParentMessage: string; originalParentHash: string; originalParentAuthorTime: number; originalParentCommitterTime: number; targetBranchMessage: string; targetBranchHash: string; targetBranchAuthorTime: number; targetBranchCommitterTime: number; conflictingFiles: ConflictDetail[]; conflictAnalysis: ConflictAnalysis; conflictMarkerCommits: Partial<{ [key in string]: ConflictMarkerCommitInfo }> }
+async renameVirtualBranch(params: RenameVirtualBranchParams, credentialPrompts: TAURI_CHANNEL<CredentialPrompt>) : Promise<Result<RenameVirtualBranchResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rename_virtual_branch", { params, credentialPrompts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Represents a commit that exists in the source branch but is missing from the target branch.
- * These commits might be causing merge conflicts.
+ * Changes the messages of one or more commits without touching the working tree.
+ * Returns the old to new commit ID mapping so displayed hashes can be updated.
  */
-export type MissingCommit = { hash: string; subject: string; message: string; authorTime: number; committerTime: number; author: string; filesTouched: string[]; fileDiffs: FileDiff[] }
-export type ModelFilesStatus = { config: boolean; model: boolean; tokenizer: boolean }
-export type ModelStatus = { available: boolean; modelName: string; modelSize: string; filesPresent: ModelFilesStatus }
-export type OpenSubWindowParams = { windowId: string; url: string; title: string; width: number | null; height: number | null; data: string; storeCache: string }
-export type PushBranchParams = { repositoryPath: string; branchPrefix: string; branchName: string; totalCommits: number; myEmail: string | null; baselineBranch: string }
+async rewordCommits(params: RewordCommitsParams) : Promise<Result<RewordCommitsResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reword_commits", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Remote branch status information
+ * Suggests an improved conventional-commit message for a commit based on its diff
  */
-export type RemoteStatusUpdate = { branchName: string; remoteExists: boolean; unpushedCommits: string[]; commitsBehind: number; 
+async suggestCommitMessageStream(params: SuggestCommitMessageParams, progress: TAURI_CHANNEL<CommitMessageProgress>) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("suggest_commit_message_stream", { params, progress }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Number of commits ahead authored by the current user (derived during sync)
+ * Replaces the message of a commit with an accepted suggestion, returns the new commit ID
  */
-myUnpushedCount: number; 
+async applySuggestedMessage(params: ApplySuggestedMessageParams) : Promise<Result<string, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("apply_suggested_message", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Last time this branch was pushed to the remote (Unix timestamp, 0 = never pushed)
+ * Proposes an assignment of unassigned commits to new or existing branches.
+ * The plan is applied by assigning each group with `create_branch_from_commits`.
  */
-lastPushTime: number }
-export type RewordResult = { success: boolean; message: string; reworded_count: number }
+async suggestCommitGrouping(params: SuggestCommitGroupingParams, progress: TAURI_CHANNEL<CommitGroupingProgress>) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("suggest_commit_grouping", { params, progress }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Parameters for requesting branch name suggestions
+ * AI provider selected for the repository and whether its API key is stored
  */
-export type SuggestBranchNameParams = { repositoryPath: string; branchPrefix: string; commits: CommitInfo[] }
+async getAiProviderSettings(params: GetAiProviderSettingsParams) : Promise<Result<AiProviderSettingsInfo, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_ai_provider_settings", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Progress events for branch name suggestion generation
+ * Selects the AI provider used for suggestions in the repository
  */
-export type SuggestionProgress = { type: "Started"; data: { total: number } } | { type: "SuggestionReady"; data: { suggestion: BranchSuggestion; index: number } } | { type: "Completed" } | { type: "Cancelled" } | { type: "Error"; data: { message: string } } | { type: "ModelDownloadInProgress"; data: { model_name: string; model_size: string } }
-export type SyncBranchesParams = { repositoryPath: string; branchPrefix: string }
+async setAiProviderSettings(params: SetAiProviderSettingsParams) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_ai_provider_settings", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Progress events for sync operations
+ * Stores the API key of the provider endpoint in the git credential helper, an empty key removes it
  */
-export type SyncEvent = 
+async setAiProviderApiKey(params: SetAiProviderApiKeyParams) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_ai_provider_api_key", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Checks downloaded model files against their expected size and SHA256, reporting partial and corrupt files
+ */
+async verifyModelCache(params: VerifyModelCacheParams) : Promise<Result<ModelCacheReport, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("verify_model_cache", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Subscribes to the events of syncs of a repository, e.g. for a sub-window that doesn't start syncs itself.
+ * Events are filtered before they are sent. Returns the subscription ID for `unsubscribe_sync_events`.
+ */
+async subscribeSyncEvents(params: SubscribeSyncEventsParams, events: TAURI_CHANNEL<SyncEvent>) : Promise<Result<number, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("subscribe_sync_events", { params, events }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stops sending sync events to a subscriber, returns false if there was no such subscription
+ */
+async unsubscribeSyncEvents(subscriptionId: number) : Promise<Result<boolean, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unsubscribe_sync_events", { subscriptionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Predicts per-branch merge conflicts of a sync without creating or moving any refs
+ */
+async syncBranchesDryRun(params: SyncDryRunParams) : Promise<Result<SyncDryRunResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_branches_dry_run", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the hosting provider, API base URL and CA override of every remote on GitHub or GitLab (including self-hosted instances)
+ */
+async getRemoteHostingConfigs(params: GetRemoteHostingConfigsParams) : Promise<Result<RemoteHostingConfig[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_remote_hosting_configs", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Overrides the detected hosting provider settings of a remote
+ */
+async setRemoteHostingOverride(params: SetRemoteHostingOverrideParams) : Promise<Result<RemoteHostingConfig | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_remote_hosting_override", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Checks notes mappings, detection cache and commit dependencies for entries referencing missing objects and repairs them on request
+ */
+async verifyMetadata(params: VerifyMetadataParams) : Promise<Result<VerifyMetadataResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("verify_metadata", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists optional subsystems compiled into or enabled in this build
+ */
+async getCapabilities() : Promise<Capabilities> {
+    return await TAURI_INVOKE("get_capabilities");
+},
+/**
+ * Executes an ordered list of operations (rename, move commits, add issue references, sync, push) with a single undo snapshot.
+ * Progress of all operations is sent over one channel.
+ */
+async executeOperationPlan(params: ExecuteOperationPlanParams, progress: TAURI_CHANNEL<SyncEvent>) : Promise<Result<OperationPlanResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("execute_operation_plan", { params, progress }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the snapshot the last operation plan can be undone to, `None` if there is nothing to undo
+ */
+async getUndoSnapshot(params: UndoSnapshotParams) : Promise<Result<UndoSnapshot | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_undo_snapshot", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restores the local branches to the state before the last operation plan; pushed branches are not reverted on the remote
+ */
+async undoOperationPlan(params: UndoSnapshotParams) : Promise<Result<UndoSnapshot, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("undo_operation_plan", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Squashes the prefixed commits of a branch into a single commit on the current branch
+ */
+async squashBranchCommits(params: SquashBranchCommitsParams) : Promise<Result<SquashBranchCommitsResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("squash_branch_commits", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Rebuilds a virtual branch on another ref (e.g., a release branch) as `<prefix>/<ref>/<name>`.
+ * Commit and conflict events are sent over the channel as during sync.
+ */
+async rebaseBranchOnto(params: RebaseBranchOntoParams, progress: TAURI_CHANNEL<SyncEvent>) : Promise<Result<RebaseBranchOntoResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("rebase_branch_onto", { params, progress }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Compute the diff of a commit before and after amending uncommitted changes to it, without rewriting anything,
+ * so the amend can be confirmed first.
+ */
+async previewAmend(params: PreviewAmendParams) : Promise<Result<AmendPreview, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_amend", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Moves a commit to another virtual branch by changing its prefix.
+ * The commit is only reworded if both branches are predicted to sync without conflicts.
+ */
+async reassignCommit(params: ReassignCommitParams) : Promise<Result<ReassignCommitResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reassign_commit", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Drops commits from the current branch, keeping the working directory as is.
+ * The report lists the later commits that change, the affected virtual branches and which of them need a force push;
+ * with `dryRun` only the report is computed.
+ */
+async dropCommits(params: DropCommitsParams) : Promise<Result<DropCommitsResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("drop_commits", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the files changed by a virtual branch against the baseline with their line counts.
+ * The hunks of a file are only computed when it is requested, so the file list of a large branch loads fast.
+ */
+async getBranchDiff(params: GetBranchDiffParams) : Promise<Result<BranchDiff, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_branch_diff", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists the commits that differ between a virtual branch and its remote branch, with patch IDs telling rewritten commits
+ * from new ones. Uses the remote-tracking ref, so it's as fresh as the last refresh.
+ */
+async getBranchRemoteDivergence(params: GetBranchRemoteDivergenceParams) : Promise<Result<BranchRemoteDivergence, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_branch_remote_divergence", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sends a test message to the configured webhook (or the given URL), returns the payload format that was used
+ */
+async testWebhookConnection(params: TestWebhookConnectionParams) : Promise<Result<WebhookFormat, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_webhook_connection", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the recorded sync runs of a repository with their phase timings, oldest first
+ */
+async getSyncHistory(repositoryPath: string) : Promise<Result<SyncHistoryEntry[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_sync_history", { repositoryPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the latest git commands run by the app, oldest first
+ */
+async getGitAuditLog(limit: number | null) : Promise<Result<GitAuditEntry[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_git_audit_log", { limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Writes the git audit log to a file, e.g. to attach to a bug report about a wrong rewrite
+ */
+async exportGitAuditLog(path: string, format: AuditLogFormat) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_git_audit_log", { path, format }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the ref journal left behind by a sync interrupted by a crash, `None` if there is none
+ */
+async getInterruptedSync(repositoryPath: string) : Promise<Result<RefJournal | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_interrupted_sync", { repositoryPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Moves the refs of a sync interrupted by a crash back to where they were before it started, the checked out branch too.
+ * The next sync does this on its own except for the checked out branch, returns the rolled back journal or `None` if
+ * there was nothing to recover.
+ */
+async recoverInterruptedSync(repositoryPath: string) : Promise<Result<RefJournal | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("recover_interrupted_sync", { repositoryPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Inspects the repository for operations interrupted by a crash (sync moving refs, rewrite of the checked out branch,
+ * leftover lock files) and returns them with the ways to recover, empty if the repository is in a consistent state
+ */
+async recoverRepositoryState(repositoryPath: string) : Promise<Result<InterruptedOperation[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("recover_repository_state", { repositoryPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Recovers from an interrupted operation with one of the options returned by `recover_repository_state`
+ */
+async applyRecoveryOption(repositoryPath: string, option: RecoveryOption) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("apply_recovery_option", { repositoryPath, option }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Asks where to save and writes the app settings and the `branchdeck.*` git config to a JSON file.
+ * Returns the path of the file, `None` if the user cancelled.
+ */
+async exportSettings(params: ExportSettingsParams) : Promise<Result<string | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_settings", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Asks for a settings file, migrates it if it was written by an older version and applies its git config.
+ * The app settings are returned for the frontend to put into the app store, `None` if the user cancelled.
+ */
+async importSettings(params: ImportSettingsParams) : Promise<Result<ImportSettingsResult | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_settings", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Synchronizes every repository of a workspace one after another, events are tagged by repository.
+ * A failed repository doesn't stop the others, returns the summary of each repository in the order of `repositories`.
+ */
+async syncWorkspace(params: SyncWorkspaceParams, progress: TAURI_CHANNEL<WorkspaceSyncEvent>) : Promise<Result<SyncSummary[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_workspace", { params, progress }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Moves the commits of a probably misspelled branch (see the `possiblePrefixTypo` sync event) to the suggested branch
+ * by rewording their prefix. Returns the old to new commit ID mapping.
+ */
+async fixPrefixTypo(params: FixPrefixTypoParams) : Promise<Result<RewordCommitsResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fix_prefix_typo", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Records which version resolves a binary file conflict, the next sync applies it.
+ * A replacement with an empty path asks for the file; returns `None` if the user cancelled.
+ */
+async resolveBinaryConflict(params: ResolveBinaryConflictParams) : Promise<Result<ResolveBinaryConflictResult | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resolve_binary_conflict", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Opens a conflicting file in the merge tool configured for git and waits for it to exit.
+ * The merged file is recorded as the resolution of the conflict, the next sync applies it.
+ */
+async launchMergeTool(params: LaunchMergeToolParams) : Promise<Result<LaunchMergeToolResult, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("launch_merge_tool", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the pull request review status (comments, unresolved threads, approvals) of virtual branches pushed to origin.
+ * Branches have no review unless `branchdeck.reviewStatus` is enabled, like for the periodic remote status refresh.
+ */
+async getBranchReviewStatus(params: GetBranchReviewStatusParams) : Promise<Result<BranchReviewStatus[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_branch_review_status", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the crash reports written by the panic hook, newest first
+ */
+async getCrashReports() : Promise<Result<CrashReport[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_crash_reports") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Asks where to save and writes a crash report as text, e.g. to attach to an issue. Nothing is sent anywhere.
+ * Returns the path of the file, `None` if the user cancelled.
+ */
+async exportCrashReport(id: string) : Promise<Result<string | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_crash_report", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the local usage statistics of the repository. They are never sent anywhere.
+ */
+async getUsageStats(repositoryPath: string) : Promise<Result<UsageStats, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_stats", { repositoryPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Counts an operation only the frontend knows about, e.g. a branch created with a suggested name.
+ * Counters of operations the backend counts itself can't be recorded.
+ */
+async recordUsage(params: RecordUsageParams) : Promise<Result<null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("record_usage", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the graph of the virtual branches, their commits and the local commits they were copied from, for a graph view.
+ */
+async getBranchGraph(params: GetBranchGraphParams) : Promise<Result<BranchGraph, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_branch_graph", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the copies of an original commit on virtual branches, made by the latest and prior syncs
+ */
+async getCommitMapping(params: GetCommitMappingParams) : Promise<Result<CommitMapping, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_commit_mapping", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the original commits a virtual branch commit was copied from
+ */
+async getReverseMapping(params: GetCommitMappingParams) : Promise<Result<CommitMapping, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_reverse_mapping", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Checks whether the baseline advanced on the remote since the last sync (e.g. when the window gets focus),
+ * returns `None` if a re-sync wouldn't pick up new upstream commits
+ */
+async checkBaselineUpdate(params: CheckBaselineUpdateParams) : Promise<Result<BaselineAdvanced | null, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_baseline_update", { params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns whether network requests are made: offline mode, detected offline or online
+ */
+async getNetworkStatus() : Promise<Result<NetworkStatus, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_network_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Switches the offline mode for the whole app: remote status, hosting provider queries, the fetch before sync and model
+ * downloads are skipped while it's on
+ */
+async setOfflineMode(enabled: boolean) : Promise<Result<NetworkStatus, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_offline_mode", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Answers a credential prompt of `push_branch`, `null` cancels it. Returns false if the prompt is no longer pending.
+ */
+async answerCredentialPrompt(id: number, answer: string | null) : Promise<Result<boolean, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("answer_credential_prompt", { id, answer }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Lists the git executables found at the usual install locations and in `PATH`, with their versions
+ */
+async detectGitInstallations() : Promise<Result<GitInstallation[], CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("detect_git_installations") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Switches the git executable used for all repositories, `null` goes back to the auto-detected one.
+ * An executable that doesn't run or is older than the minimum version is rejected and the current one is kept.
+ */
+async setGitPath(path: string | null) : Promise<Result<GitInstallation, CommandError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_git_path", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+export type AddIssueReferenceParams = { repositoryPath: string; branchName: string; commits: CommitInfo[]; issueReference: string }
+export type AddIssueReferenceResult = { success: boolean; updatedCount: number; skippedCount: number }
+/**
+ * Backend generating AI suggestions
+ */
+export type AiProvider = 
+/**
+ * Bundled Qwen model running on this machine
+ */
+"local" | 
+/**
+ * OpenAI chat completions API, or any server compatible with it
+ */
+"openAi" | "anthropic" | 
+/**
+ * Ollama server, local or on another machine
+ */
+"ollama"
+/**
+ * Provider selected for a repository
+ */
+export type AiProviderSettings = { provider: AiProvider; 
+/**
+ * Provider endpoint, `None` uses the provider default
+ */
+endpoint: string | null; 
+/**
+ * Model name, `None` uses the provider default
+ */
+model: string | null }
+/**
+ * AI provider of a repository; the API key itself never leaves the backend
+ */
+export type AiProviderSettingsInfo = { settings: AiProviderSettings; hasApiKey: boolean }
+/**
+ * Result type for amend command that can be properly serialized by Tauri
+ */
+export type AmendCommandResult = { status: "ok"; data: AmendResult } | { status: "branchError"; data: BranchError }
+/**
+ * Would-be result of amending uncommitted changes to a commit, computed without touching refs, the index or the working tree
+ */
+export type AmendPreview = { 
+/**
+ * Tree of the commit after amending
+ */
+amendedTreeId: string; 
+/**
+ * Files of the commit changed by the amend, the commit as is vs. the commit after amending
+ */
+fileDiffs: FileDiff[]; 
+/**
+ * Files whose changes don't apply cleanly to the commit, the amend would stop with a conflict
+ */
+conflictingFiles: string[] }
+export type AmendResult = { amendedCommitId: string; rebasedToCommit: string }
+export type AmendUncommittedToBranchParams = { repositoryPath: string; branchName: string; originalCommitId: string; files: string[] }
+export type ApplyArchiveRetentionParams = { repositoryPath: string; branchPrefix: string; 
+/**
+ * Policy to apply; the repository configuration is used when not provided
+ */
+policy: ArchiveRetentionPolicy | null; 
+/**
+ * Only report which branches would be deleted
+ */
+dryRun: boolean }
+export type ApplyPatchSeriesParams = { repositoryPath: string; 
+/**
+ * `.patch` files (applied in the given order) or mbox files with several patches
+ */
+patchFiles: string[]; 
+/**
+ * Virtual branch name added to each subject as `(name)` so the commits group into that branch
+ */
+branchName: string }
+export type ApplyPatchSeriesResult = { 
+/**
+ * Created commits, oldest first
+ */
+commits: CommitInfo[] }
+/**
+ * Parameters for replacing a commit message with an accepted suggestion
+ */
+export type ApplySuggestedMessageParams = { repositoryPath: string; commitId: string; message: string }
+/**
+ * Rules deciding which archived branches get deleted.
+ * Persisted per repository in git config (`branchdeck.archiveMaxAgeDays`, `branchdeck.archiveMaxCount`, `branchdeck.archiveOnlyIfIntegrated`).
+ */
+export type ArchiveRetentionPolicy = { 
+/**
+ * Delete archived branches older than this many days (`None` disables the rule)
+ */
+maxAgeDays: number | null; 
+/**
+ * Keep at most this many archived branches under the branch prefix, newest first (`None` disables the rule)
+ */
+maxCountPerPrefix: number | null; 
+/**
+ * Only delete branches the detection cache reports as fully integrated
+ */
+onlyIfIntegrated: boolean }
+/**
+ * Outcome of applying the retention policy
+ */
+export type ArchiveRetentionReport = { policy: ArchiveRetentionPolicy; 
+/**
+ * Branches selected for deletion
+ */
+candidates: RetentionCandidate[]; 
+/**
+ * Number of deleted branches (always 0 in dry-run mode)
+ */
+deletedCount: number; dryRun: boolean }
+/**
+ * One page of the commits of an archived branch
+ */
+export type ArchivedBranchCommitsPage = { 
+/**
+ * Commits of the page, oldest first
+ */
+commits: Commit[]; 
+/**
+ * Number of commits on the whole branch
+ */
+totalCount: number }
+/**
+ * Remote branch still present for an archived branch
+ */
+export type ArchivedRemoteStatus = { 
+/**
+ * Archived branch (e.g., "user/archived/2025-08-11/feature")
+ */
+branchName: string; 
+/**
+ * Branch on the remote without the remote name (e.g., "user/virtual/feature")
+ */
+remoteBranch: string; 
+/**
+ * Whether the remote branch points at the archived commit (nothing would be lost by deleting it)
+ */
+sameCommit: boolean }
+/**
+ * Format of an exported audit log
+ */
+export type AuditLogFormat = 
+/**
+ * One JSON entry per line
+ */
+"json" | 
+/**
+ * Shell script running the commands again, with their recorded outcome as comments
+ */
+"script"
+/**
+ * Baseline branch that advanced on the remote since the last successful sync
+ */
+export type BaselineAdvanced = { 
+/**
+ * Remote-tracking branch (e.g., "origin/master")
+ */
+baselineBranch: string; 
+/**
+ * Baseline commit of the last successful sync
+ */
+lastSyncBaseline: string; remoteHead: string; 
+/**
+ * Commits on the remote baseline that the last sync didn't include
+ */
+newCommitCount: number }
+/**
+ * Version of a binary file that resolves its conflict
+ */
+export type BinaryConflictResolution = 
+/**
+ * Version of the branch the commit is applied onto
+ */
+{ type: "ours" } | 
+/**
+ * Version of the cherry-picked commit
+ */
+{ type: "theirs" } | 
+/**
+ * Version of the merge base
+ */
+{ type: "base" } | 
+/**
+ * Content of a local file
+ */
+{ type: "replacement"; data: string }
+/**
+ * Cumulative changes of a virtual branch, as the "Files changed" view of its pull request would show them
+ */
+export type BranchDiff = { 
+/**
+ * Merge base of the branch and the baseline, the old side of the diff
+ */
+baseCommit: string; headCommit: string; 
+/**
+ * Changed files sorted by path
+ */
+files: FileChangeStat[]; 
+/**
+ * Hunks of the requested file, `None` if no file was requested or it is unchanged
+ */
+fileDiff: FileDiff | null }
+/**
+ * Branch operation errors.
+ */
+export type BranchError = { Generic: string } | { MergeConflict: MergeConflictInfo } | { CaseCollision: CaseCollisionInfo }
+/**
+ * Directed acyclic graph of the virtual branches. Every edge connects nodes of the graph.
+ */
+export type BranchGraph = { baselineBranch: string; 
+/**
+ * Head commit of the baseline, also a node
+ */
+baselineHead: string; 
+/**
+ * Baseline commits first, then the local commits and each virtual branch followed by its commits, oldest first
+ */
+nodes: BranchGraphNode[]; edges: BranchGraphEdge[] }
+export type BranchGraphEdge = { from: string; to: string; kind: BranchGraphEdgeKind }
+export type BranchGraphEdgeKind = 
+/**
+ * From a commit to its parent
+ */
+"parent" | 
+/**
+ * From a local commit to its copy in a virtual branch
+ */
+"mapping" | 
+/**
+ * From a virtual branch to its head commit
+ */
+"head"
+export type BranchGraphNode = { 
+/**
+ * Commit hash, the full ref name for a virtual branch
+ */
+id: string; kind: BranchGraphNodeKind; 
+/**
+ * Commit subject or virtual branch name
+ */
+label: string; 
+/**
+ * Virtual branch of a copied commit or of the branch node itself, for a local commit the branch it is grouped into.
+ * A copied commit shared by stacked branches belongs to the one with the fewest commits, the bottom of the stack.
+ */
+branchName: string | null; 
+/**
+ * Every virtual branch a copied commit is part of, more than one for a commit shared by stacked branches
+ */
+branches: string[] }
+export type BranchGraphNodeKind = 
+/**
+ * Commit of the baseline that local or virtual branch commits are based on, or the head of the baseline
+ */
+"baseline" | 
+/**
+ * Local commit ahead of the baseline
+ */
+"original" | 
+/**
+ * Commit of a virtual branch
+ */
+"copied" | "virtualBranch"
+/**
+ * Unified branch integration info
+ */
+export type BranchIntegrationInfo = { name: string; summary: string; status: BranchIntegrationStatus; 
+/**
+ * Baseline history outside the integration lookback window wasn't searched,
+ * so commits integrated long ago may be reported as orphaned
+ */
+lookbackLimited: boolean; 
+/**
+ * The detection budget ran out before the deep detection steps, so the status may miss integrated commits.
+ * Never cached; resuming detection of the branch runs the full analysis.
+ */
+detectionTruncated: boolean; 
+/**
+ * `None` if no baseline commit matched, or for results cached by older versions
+ */
+evidence: IntegrationEvidence | null }
+/**
+ * Unified branch integration status
+ */
+export type BranchIntegrationStatus = { kind: "integrated"; integratedAt: number | null; confidence: IntegrationConfidence; commitCount: number } | { kind: "notIntegrated"; totalCommitCount: number; integratedCount: number; orphanedCount: number; integratedAt: number | null } | { kind: "partial"; missing: number }
+export type BranchOrder = { pinnedBranches: string[]; branchOrder: string[] }
+/**
+ * The branch prefix and every value of `branchdeck.branchPrefix` in the order git reads them, the last one wins
+ */
+export type BranchPrefixResolution = { 
+/**
+ * Empty if not configured
+ */
+prefix: string; sources: BranchPrefixSource[] }
+/**
+ * Where a value of `branchdeck.branchPrefix` comes from
+ */
+export type BranchPrefixSource = { 
+/**
+ * `system`, `global`, `local`, `worktree` or `command`; a file included with `include` or `includeIf` has the scope of the including file
+ */
+scope: string; 
+/**
+ * Config file setting the value, or the kind of origin if it's not a file (e.g. `command line:`)
+ */
+origin: string; value: string }
+/**
+ * Commits that differ between a local virtual branch and its remote-tracking ref
+ */
+export type BranchRemoteDivergence = { branchName: string; remoteExists: boolean; 
+/**
+ * Commits missing on the remote, oldest first
+ */
+localCommits: DivergentCommit[]; 
+/**
+ * Commits missing locally, oldest first
+ */
+remoteCommits: DivergentCommit[] }
+export type BranchReviewStatus = { branchName: string; 
+/**
+ * `None` if the branch has no open pull request
+ */
+review: ReviewSummary | null }
+/**
+ * Branch name suggestion
+ */
+export type BranchSuggestion = { name: string; reason: string | null }
+/**
+ * Predicted outcome of syncing one virtual branch
+ */
+export type BranchSyncPrediction = { branchName: string; commitCount: number; 
+/**
+ * First commit that would conflict; commits after it are not checked as they would be applied on top of it
+ */
+conflict: MergeConflictInfo | null; 
+/**
+ * Failure other than a conflict, e.g. a missing object
+ */
+error: string | null }
+/**
+ * Status of a branch synchronization operation.
+ */
+export type BranchSyncStatus = "Created" | "Updated" | "Unchanged" | "Error" | "MergeConflict" | "AnalyzingConflict" | 
+/**
+ * All commits are already in the baseline, so the branch was skipped or archived (see `branchdeck.emptyBranchPolicy`)
+ */
+"Integrated"
+/**
+ * Result of browsing for a repository
+ */
+export type BrowseResult = { path: string | null; valid: boolean; error: string | null }
+export type CacheClearResult = { cleared_models: string[]; total_size_mb: number; errors: string[] }
+/**
+ * Optional subsystems of this build, so the frontend can hide features instead of calling commands that fail at runtime
+ */
+export type Capabilities = { 
+/**
+ * Checking for and installing updates (`auto-update` feature)
+ */
+autoUpdate: boolean; 
+/**
+ * Exporting traces via OpenTelemetry (`telemetry` feature)
+ */
+telemetry: boolean; 
+/**
+ * Development-only commands such as `simulate_conflict`
+ */
+developmentTools: boolean; 
+/**
+ * AI backends that can be selected in the AI provider settings
+ */
+aiProviders: AiProvider[]; 
+/**
+ * Hosting providers recognized for remotes
+ */
+hostingProviders: HostingProviderKind[]; 
+/**
+ * Periodic fetch and remote status refresh (`start_remote_status_refresh`)
+ */
+remoteStatusRefresh: boolean }
+/**
+ * A commit can't be applied because the result would have paths that differ only in case,
+ * which a case-insensitive file system can't check out side by side (see [`crate::case_collision`])
+ */
+export type CaseCollisionInfo = { commitHash: string; commitMessage: string; 
+/**
+ * Groups of colliding paths, e.g. `["docs/README.md", "docs/Readme.md"]`
+ */
+collisions: string[][] }
+export type CheckBaselineUpdateParams = { repositoryPath: string }
+/**
+ * Combined state of the CI checks (GitHub) or of the latest pipeline (GitLab) of a commit
+ */
+export type CiState = 
+/**
+ * Checks are queued or running
+ */
+"pending" | "success" | "failure" | 
+/**
+ * The pipeline was canceled or skipped (GitLab)
+ */
+"cancelled"
+/**
+ * CI status of the head commit of a pushed branch
+ */
+export type CiStatus = { commitHash: string; state: CiState; 
+/**
+ * Names of the failed checks (GitHub) or jobs (GitLab)
+ */
+failedChecks: string[]; 
+/**
+ * Page with the details: the first failed check, the pipeline, or the checks of the commit
+ */
+url: string }
+export type ClearModelCacheParams = { keepCurrent: boolean }
+/**
+ * Error returned by commands: a code to branch on, a message to show and optional details
+ */
+export type CommandError = { code: ErrorCode; message: string; 
+/**
+ * Details depending on the code, e.g. `exitCode` of a failed git command
+ */
+context: Partial<{ [key in string]: string }> }
+/**
+ * Struct to hold commit data returned by git CLI
+ */
+export type Commit = { originalHash: string; strippedSubject: string; message: string; author: string; authorTime: number; committerTime: number }
+/**
+ * Optional limits selecting commits by date, count or paths, e.g. to pick the virtual branches worth rebuilding in a huge history
+ */
+export type CommitFilter = { 
+/**
+ * Only commits committed after this date, in any format `git log --since` accepts (e.g. "2025-01-01", "2 weeks ago")
+ */
+since: string | null; 
+/**
+ * Only the newest N commits
+ */
+maxCommits: number | null; 
+/**
+ * Only commits touching these paths (git pathspec)
+ */
+pathspec: string[] }
+/**
+ * Proposed assignment of unassigned commits to branch prefixes
+ */
+export type CommitGroupingPlan = { groups: SuggestedCommitGroup[]; 
+/**
+ * Commits the model didn't assign to any branch
+ */
+ungroupedCommitIds: string[] }
+/**
+ * Progress events for commit grouping suggestion generation
+ */
+export type CommitGroupingProgress = { type: "Started" } | { type: "PlanReady"; data: { plan: CommitGroupingPlan; reason: string | null } } | { type: "Completed" } | { type: "Cancelled" } | { type: "Error"; data: { message: string } } | { type: "ModelDownloadInProgress"; data: { model_name: string; model_size: string } }
+/**
+ * Simple commit information with hash and message.
+ * Used for passing commit data between frontend and backend.
+ */
+export type CommitInfo = { hash: string; message: string }
+export type CommitMapping = { 
+/**
+ * Full hash of the requested commit
+ */
+commitId: string; 
+/**
+ * Newest first, empty if the commit was never synced
+ */
+entries: CommitMappingEntry[] }
+export type CommitMappingEntry = { originalCommit: string; copiedCommit: string; 
+/**
+ * Unix timestamp of the sync that first recorded the mapping
+ */
+recordedAt: number; 
+/**
+ * Mapping of the latest sync, the others are from prior syncs
+ */
+current: boolean }
+/**
+ * Progress events for commit message suggestion generation
+ */
+export type CommitMessageProgress = { type: "Started" } | { type: "MessageReady"; data: { message: string; reason: string | null } } | { type: "Completed" } | { type: "Cancelled" } | { type: "Error"; data: { message: string } } | { type: "ModelDownloadInProgress"; data: { model_name: string; model_size: string } }
+/**
+ * Order of commits in a virtual branch
+ */
+export type CommitOrder = 
+/**
+ * Order of the commits in the source branch
+ */
+"source" | 
+/**
+ * Oldest author date first, e.g. to restore the original order after an interactive rebase
+ */
+"authorDate" | 
+/**
+ * Commits after the ones they depend on (see `git_ops::commit_dependencies`), otherwise in source order
+ */
+"topo"
+/**
+ * Status of a commit synchronization.
+ */
+export type CommitSyncStatus = "Pending" | "Created" | "Unchanged" | "Error" | "Blocked" | 
+/**
+ * Changes of the commit are already present in the new parent, so no (empty) commit was created
+ */
+"AlreadyApplied" | 
+/**
+ * Changes of the commit are already present in the new parent, the commit was copied as an empty commit
+ * (`branchdeck.emptyCommitPolicy` is `keepEmpty`)
+ */
+"KeptEmpty" | 
+/**
+ * Changes of the commit are already present in the new parent, the commit is reported as integrated
+ * (`branchdeck.emptyCommitPolicy` is `markIntegrated`)
+ */
+"Integrated"
+/**
+ * Analysis results for a merge conflict, including missing commits and divergence information.
+ */
+export type ConflictAnalysis = { missingCommits: MissingCommit[]; mergeBaseHash: string; mergeBaseSubject: string; mergeBaseMessage: string; mergeBaseTime: number; mergeBaseAuthor: string; divergenceSummary: DivergenceSummary }
+/**
+ * Kind of a conflict, ordered from the most trivial to resolve
+ */
+export type ConflictClass = 
+/**
+ * Both sides only differ in whitespace
+ */
+"formattingOnly" | 
+/**
+ * Both sides only add, remove or change import statements
+ */
+"importsOnly" | "logic"
+/**
+ * Classes of the conflict chunks of a file
+ */
+export type ConflictClassification = { 
+/**
+ * Least trivial class of the chunks
+ */
+class: ConflictClass; 
+/**
+ * Class of each chunk, in the order of the conflict markers
+ */
+chunks: ConflictClass[] }
+/**
+ * Represents details of a conflict during a cherry-pick operation.
+ * 
+ * Includes the path of the conflicted file, its status, and the diff details for the conflict.
+ */
+export type ConflictDetail = { file: string; status: string; fileDiff: FileDiff; baseFile: FileInfo | null; targetFile: FileInfo | null; cherryFile: FileInfo | null; baseToTargetDiff: FileDiff; baseToCherryDiff: FileDiff; 
+/**
+ * Binary content on any side: contents and diffs are left empty, the conflict is resolved by choosing a version
+ */
+isBinary: boolean; 
+/**
+ * Class of the conflict chunks (formatting-only, imports-only or logic), `None` for binary files
+ */
+classification: ConflictClassification | null }
+/**
+ * Information about a commit referenced in conflict markers
+ */
+export type ConflictMarkerCommitInfo = { hash: string; message: string; author: string; authorTime: number; committerTime: number }
+/**
+ * Kind of conflict to fabricate
+ */
+export type ConflictScenarioKind = 
+/**
+ * Both sides edit the same lines of a single file
+ */
+"editEdit" | 
+/**
+ * Unassigned commit edits a file that the branch commit deletes
+ */
+"editDelete" | 
+/**
+ * Both sides edit the same lines in several files
+ */
+"multipleFiles"
+export type CrashReport = { 
+/**
+ * Name of the report file without extension
+ */
+id: string; 
+/**
+ * Unix timestamp in milliseconds
+ */
+crashedAt: number; message: string; 
+/**
+ * `file:line:column` of the panic
+ */
+location: string | null; thread: string | null; backtrace: string; 
+/**
+ * Log events before the panic, oldest first
+ */
+recentEvents: string[]; appVersion: string; 
+/**
+ * Operating system name, version and architecture
+ */
+os: string; 
+/**
+ * `None` if git was not discovered yet
+ */
+gitVersion: string | null }
+export type CreateBranchFromCommitsParams = { repositoryPath: string; branchName: string; commitIds: string[] }
+/**
+ * Prompt of git or ssh, e.g. `Password for 'https://user@github.com': ` or `Enter passphrase for key '~/.ssh/id_ed25519': `
+ */
+export type CredentialPrompt = { id: number; prompt: string; 
+/**
+ * Password, token or key passphrase, entered in a masked field
+ */
+secret: boolean }
+export type DeleteArchivedBranchParams = { repositoryPath: string; branchName: string; branchPrefix: string; 
+/**
+ * Also delete the branches still present on the remote for this archived branch.
+ * Refused unless the branch is integrated and the remote has no commits beyond the archived branch.
+ */
+deleteRemote: boolean }
+export type DeleteArchivedBranchesParams = { repositoryPath: string; branchPrefix: string; 
+/**
+ * Full archived branch names (e.g., "user/archived/2025-08-11/feature")
+ */
+branchNames: string[] }
+/**
+ * Progress events for bulk deletion of archived branches
+ */
+export type DeleteArchivedBranchesProgress = { type: "Started"; data: { total: number } } | 
+/**
+ * All refs were deleted in a single transaction
+ */
+{ type: "BranchesDeleted"; data: { branchNames: string[] } } | { type: "Completed" } | { type: "Error"; data: { message: string } }
+export type DeleteArchivedBranchesResult = { deletedBranches: string[] }
+/**
+ * Side of a diff a line belongs to
+ */
+export type DiffSide = 
+/**
+ * Removed line, numbered in the old file
+ */
+"old" | 
+/**
+ * Added line, numbered in the new file
+ */
+"new"
+/**
+ * Summary of how two branches have diverged from their common ancestor.
+ */
+export type DivergenceSummary = { commitsAheadInSource: number; commitsAheadInTarget: number; commonAncestorDistance: number }
+/**
+ * Commit on only one side of a virtual branch and its remote counterpart
+ */
+export type DivergentCommit = { hash: string; subject: string; author: string; authorTime: number; 
+/**
+ * Stable patch ID, `None` for a commit without changes
+ */
+patchId: string | null; 
+/**
+ * The other side has a commit with the same patch ID, i.e., the commit was rewritten without changing its diff
+ */
+hasEquivalent: boolean }
+/**
+ * Progress events for model download operations
+ */
+export type DownloadProgress = { type: "Started"; data: { totalFiles: number } } | { type: "FileStarted"; data: { fileName: string; fileSize: number | null } } | { type: "Progress"; data: { fileName: string; downloaded: number; total: number; bytesPerSecond: number | null; secondsRemaining: number | null } } | 
+/**
+ * An interrupted download continues where it stopped
+ */
+{ type: "FileResumed"; data: { fileName: string; resumedFrom: number } } | 
+/**
+ * The downloaded file is checked against its expected size and SHA256
+ */
+{ type: "FileVerifying"; data: { fileName: string } } | { type: "FileCompleted"; data: { fileName: string } } | { type: "Completed" } | { type: "Cancelled" } | { type: "Error"; data: { message: string } }
+export type DropCommitsParams = { repositoryPath: string; branchPrefix: string; commitIds: string[]; 
+/**
+ * Only compute the report, HEAD is left as is
+ */
+dryRun: boolean }
+export type DropCommitsReport = { 
+/**
+ * Later commits whose tree changes, i.e., all commits after the oldest dropped one unless the dropped changes are undone later
+ */
+changedCommits: CommitInfo[]; 
+/**
+ * Later commit that can't be replayed without the dropped ones; the drop fails if set
+ */
+conflictingCommit: CommitInfo | null; 
+/**
+ * Branches in grouping order
+ */
+affectedBranches: DroppedBranchImpact[]; 
+/**
+ * Dropped commits not assigned to any virtual branch
+ */
+unassignedCount: number }
+export type DropCommitsResult = { report: DropCommitsReport; 
+/**
+ * New HEAD commit; `None` for a dry run or if a conflict was predicted
+ */
+newHead: string | null }
+/**
+ * Virtual branch losing some of its commits
+ */
+export type DroppedBranchImpact = { branchName: string; droppedCount: number; 
+/**
+ * Commits left in the branch; the next sync archives an empty branch according to the empty branch policy
+ */
+remainingCount: number; 
+/**
+ * Some dropped commits were pushed, so the remote branch can only be updated with a force push
+ */
+requiresForcePush: boolean } }
+/**
+ * Machine-readable error category, the UI branches on it instead of matching message text
+ */
+export type ErrorCode = 
+/**
+ * A git command exited with a failure status (context: `command`, `exitCode`, `stderr`)
+ */
+"gitCommandFailed" | 
+/**
+ * The repository path doesn't exist or isn't a git repository
+ */
+"repositoryNotAccessible" | 
+/**
+ * A commit can't be applied without conflicts (context: `commitHash`)
+ */
+"mergeConflict" | 
+/**
+ * Invalid input, e.g. a blank branch name
+ */
+"validation" | 
+/**
+ * A referenced branch, remote or commit doesn't exist
+ */
+"notFound" | 
+/**
+ * Loading or running the AI model failed
+ */
+"model" | 
+/**
+ * The operation was cancelled by the user
+ */
+"cancelled" | 
+/**
+ * Not available in this build
+ */
+"unsupported" | 
+/**
+ * The branch is protected against the operation (context: `branchName`, `rule`)
+ */
+"branchProtected" | 
+/**
+ * The pre-push hook or command failed (context: `command`, `exitCode`, `output` with the last lines)
+ */
+"prePushCheckFailed" | 
+/**
+ * A sync hook script failed (context: `hook`, `exitCode`, `output` with the last lines)
+ */
+"hookFailed" | 
+/**
+ * Skipped without trying the network: offline mode is on or no network connection was detected
+ */
+"offline" | 
+/**
+ * The credential helpers have no credentials for the remote host and none were entered (context: `host`)
+ */
+"credentialsRequired" | 
+/**
+ * The remote rejected the credentials, they were removed from the credential helpers (context: `host`)
+ */
+"authenticationFailed" | 
+/**
+ * The SSH server accepted none of the keys, e.g. a passphrase prompt was cancelled without an agent holding the key, or the host key couldn't be verified (context: `host`, `reason`: `publickey` or `hostKey`)
+ */
+"sshAuthenticationFailed" | 
+/**
+ * The git executable is older than the minimum version, the message says how to get a newer one (context: `gitPath`, `version`, `minimumVersion`)
+ */
+"unsupportedGitVersion" | 
+/**
+ * Applying a commit would create paths differing only in case, which a case-insensitive file system can't check out (context: `commitHash`, `paths` with one colliding path per line)
+ */
+"caseCollision" | 
+/**
+ * Anything else, the message is the only information
+ */
+"internal"
+export type ExecuteOperationPlanParams = { repositoryPath: string; branchPrefix: string; operations: PlanOperation[] }
+export type ExportBranchPatchesParams = { repositoryPath: string; branchPrefix: string; 
+/**
+ * Virtual branch name (without prefix)
+ */
+branchName: string; format: PatchExportFormat; 
+/**
+ * Output directory for a series, output file for an mbox
+ */
+outputPath: string; 
+/**
+ * Add a cover letter (patch 0) describing the series
+ */
+coverLetter: boolean; 
+/**
+ * Branch summary used as the cover letter subject (falls back to the branch name)
+ */
+summary: string | null; 
+/**
+ * Subject prefix, `PATCH` by default (e.g. `PATCH v2` or `RFC PATCH`)
+ */
+subjectPrefix: string | null; 
+/**
+ * Cover letter text (falls back to a generated description of the series)
+ */
+coverLetterBody: string | null; 
+/**
+ * Add `Message-ID` headers and make the patches replies to the first message (`--thread=shallow`)
+ */
+thread: boolean }
+export type ExportBranchPatchesResult = { 
+/**
+ * Written files: the patch files of a series (cover letter first) or the single mbox file
+ */
+files: string[]; 
+/**
+ * Number of exported commits (not counting the cover letter)
+ */
+patchCount: number }
+export type ExportSettingsParams = { 
+/**
+ * Content of the app store as a JSON object
+ */
+appSettings: string; 
+/**
+ * Repositories whose `branchdeck.*` config is exported
+ */
+repositoryPaths: string[] }
+/**
+ * How a file changed between two trees
+ */
+export type FileChangeKind = "added" | "deleted" | "modified" | 
+/**
+ * File mode or type changed, e.g., a file replaced by a symlink
+ */
+"typeChanged" | "renamed" | "copied"
+/**
+ * Changed file with its line counts, without content
+ */
+export type FileChangeStat = { path: string; kind: FileChangeKind; additions: number; deletions: number; 
+/**
+ * Binary files have no line counts
+ */
+binary: boolean; 
+/**
+ * Old path of a renamed or copied file
+ */
+rename: FileRename | null }
+/**
+ * Represents the diff between two versions of a file.
+ */
+export type FileDiff = { oldFile: FileInfo; newFile: FileInfo; hunks: string[]; 
+/**
+ * Hunks were computed ignoring whitespace changes, so whitespace-only changes are not shown
+ */
+whitespaceIgnored: boolean; 
+/**
+ * Set if the file was renamed or copied, `old_file` is then the file at the old path
+ */
+rename: FileRename | null; 
+/**
+ * Word-level changes of each hunk, only computed for the diffs of merge conflicts
+ */
+wordDiffs: HunkWordDiff[] | null }
+/**
+ * Information about a file including its content and metadata.
+ */
+export type FileInfo = { fileName: string; fileLang: string; content: string }
+/**
+ * Old path of a renamed or copied file, as detected by git (`-M` / `-C`)
+ */
+export type FileRename = { oldPath: string; 
+/**
+ * Similarity of the old and the new content in percent
+ */
+similarity: number; 
+/**
+ * The old path still exists, the file is a copy of it
+ */
+copied: boolean }
+export type FixPrefixTypoParams = { repositoryPath: string; 
+/**
+ * Misspelled branch name
+ */
+branchName: string; 
+/**
+ * Branch name the commits get instead
+ */
+suggestedBranchName: string }
+/**
+ * Parameters for reading the AI provider of a repository
+ */
+export type GetAiProviderSettingsParams = { repositoryPath: string }
+export type GetArchivedBranchCommitsPageParams = { repositoryPath: string; branchName: string; 
+/**
+ * Number of commits to skip, counted from the branch tip
+ */
+offset: number; 
+/**
+ * Maximum number of commits to return (50 by default)
+ */
+limit: number | null }
+export type GetBranchDiffParams = { repositoryPath: string; branchPrefix: string; branchName: string; 
+/**
+ * File to compute the hunks of; without it only the file list is returned
+ */
+filePath: string | null }
+export type GetBranchGraphParams = { repositoryPath: string; branchPrefix: string }
+export type GetBranchOrderParams = { repositoryPath: string }
+export type GetBranchPrefixParams = { repositoryPath: string }
+export type GetBranchRemoteDivergenceParams = { repositoryPath: string; branchPrefix: string; branchName: string }
+export type GetBranchReviewStatusParams = { repositoryPath: string; branchPrefix: string; 
+/**
+ * Virtual branch names (without the prefix)
+ */
+branchNames: string[] }
+export type GetCommitDependenciesParams = { repositoryPath: string; commitId: string }
+export type GetCommitDiffParams = { repositoryPath: string; commitId: string }
+export type GetCommitMappingParams = { repositoryPath: string; 
+/**
+ * Original commit for `get_commit_mapping`, copied commit for `get_reverse_mapping`; any revision git understands
+ */
+commitId: string }
+export type GetCoverLetterTemplateParams = { repositoryPath: string; branchName: string }
+export type GetFileContentForDiffParams = { repositoryPath: string; filePath: string }
+export type GetRemoteHostingConfigsParams = { repositoryPath: string }
+export type GetSmtpConfigParams = { repositoryPath: string }
+export type GetUncommittedChangesParams = { repositoryPath: string }
+/**
+ * One git invocation
+ */
+export type GitAuditEntry = { 
+/**
+ * Unix timestamp in milliseconds of the start of the command
+ */
+startedAt: number; repositoryPath: string; args: string[]; 
+/**
+ * Environment variables set for the command as `NAME=value`, only `NAME` for variables whose value may contain secrets
+ */
+env: string[]; 
+/**
+ * Input written to stdin, `None` if there was none or it wasn't recorded
+ */
+stdin: string | null; 
+/**
+ * Whether the replay script can run the command as it was run: the values of all environment variables and the whole
+ * input were recorded
+ */
+replayable: boolean; durationMs: number; 
+/**
+ * `None` if the command couldn't be started or was killed by a signal
+ */
+exitCode: number | null; 
+/**
+ * Beginning of stdout, see [`MAX_AUDIT_OUTPUT_BYTES`]
+ */
+stdout: string; 
+/**
+ * Beginning of stderr, see [`MAX_AUDIT_OUTPUT_BYTES`]
+ */
+stderr: string }
+/**
+ * Git executable found on this machine
+ */
+export type GitInstallation = { path: string; 
+/**
+ * Where it comes from, e.g. `Homebrew`, `Xcode Command Line Tools`, `Scoop` or `PATH`
+ */
+source: string; 
+/**
+ * `None` if it failed to run
+ */
+version: string | null; 
+/**
+ * Why it can't be used: it failed to run or is older than the minimum version
+ */
+error: string | null; 
+/**
+ * Merges with `merge-tree --write-tree` rather than the slower temporary index
+ */
+supportsMergeTreeWriteTree: boolean }
+export type GroupedBranchInfo = { name: string; commits: Commit[]; latestCommitTime: number; summary: string; allCommitsHaveIssueReferences: boolean; 
+/**
+ * Most frequent author email in this branch's commits
+ */
+myEmail: string | null; 
+/**
+ * Whether the branch is pinned (see `sync_core::branch_order`)
+ */
+pinned: boolean; 
+/**
+ * Whether the branch is protected against force-push and deletion (see `sync_core::branch_protection`)
+ */
+protected: boolean; 
+/**
+ * Authors other than the user whose commits are grouped in `branchdeck.onlyMyCommits` mode (see `sync_core::author_filter`)
+ */
+otherAuthors: string[]; 
+/**
+ * Order in which the commits are applied to the branch (see `sync_core::commit_order`)
+ */
+commitOrder: CommitOrder }
+/**
+ * Why the branch prefix of a commit subject is ambiguous
+ */
+export type GroupingAmbiguity = 
+/**
+ * Several parenthesized prefixes, only the first one is used
+ */
+"multiplePrefixes" | 
+/**
+ * A parenthesized prefix followed by an issue reference, the prefix takes precedence
+ */
+"prefixWithIssueReference" | 
+/**
+ * Nested or unclosed parentheses
+ */
+"malformedParentheses" | 
+/**
+ * Parentheses without a name, the commit is not grouped by them
+ */
+"emptyPrefix" | 
+/**
+ * A parenthesized name near the start of the subject that is not recognized as a prefix, e.g. `fix(ui): …`
+ */
+"prefixNotAtStart" | 
+/**
+ * The prefix was changed to a valid branch name
+ */
+"sanitizedPrefix" | 
+/**
+ * The commit touches no path in the scope of its branch (`branchdeck.pathScope`), so it is not grouped
+ */
+"outsidePathScope" | 
+/**
+ * The commit is grouped, but also touches paths outside the scope of its branch
+ */
+"partlyOutsidePathScope"
+/**
+ * How the branch prefix of a commit with an ambiguous subject was parsed
+ */
+export type GroupingDiagnostic = { commitId: string; subject: string; 
+/**
+ * Branch the commit is grouped into, `None` if it is unassigned
+ */
+branchName: string | null; ambiguities: GroupingAmbiguity[]; 
+/**
+ * Other branch names the subject could be read as
+ */
+alternatives: string[]; 
+/**
+ * How the prefix was parsed, for display
+ */
+explanation: string }
+/**
+ * Code hosting service behind a remote
+ */
+export type HostingProviderKind = "github" | "gitlab"
+/**
+ * Word-level changes of a hunk, only lines with changed tokens are listed
+ */
+export type HunkWordDiff = { lines: LineWordDiff[] }
+export type ImportSettingsParams = { 
+/**
+ * Local repositories the exported repository config is matched to
+ */
+repositoryPaths: string[] }
+/**
+ * What an import changed
+ */
+export type ImportSettingsResult = { 
+/**
+ * JSON object with the keys to put into the app store
+ */
+appSettings: string; 
+/**
+ * Version of the file if it was written by an older version of the app
+ */
+migratedFromVersion: number | null; 
+/**
+ * Local repositories whose config was updated
+ */
+appliedRepositories: string[]; 
+/**
+ * Paths (as exported) of the repositories not found among the local ones
+ */
+unmatchedRepositories: string[]; 
+/**
+ * Keys of the file that were not imported because they run commands or send data to a URL, to set up by hand
+ */
+skippedKeys: string[] }
+/**
+ * Confidence level for integration detection
+ */
+export type IntegrationConfidence = "Exact" | "High" | "Low"
+/**
+ * Why a branch got its integration status, so the user can verify it before deleting the branch
+ */
+export type IntegrationEvidence = { strategy: IntegrationStrategy; 
+/**
+ * Matched baseline commits, newest first. Capped, so it can be shorter than the integrated commit count.
+ * Empty for a fast-forward merge or when only the content matched.
+ */
+matchedCommits: MatchedCommit[] }
+/**
+ * How branch commits were matched to baseline history
+ */
+export type IntegrationStrategy = 
+/**
+ * The branch tip is reachable from the baseline (merge or fast-forward)
+ */
+"merge" | 
+/**
+ * Every branch commit has a patch-equivalent commit in the baseline
+ */
+"rebase" | 
+/**
+ * Only some branch commits have patch-equivalent commits in the baseline
+ */
+"patchId" | 
+/**
+ * The baseline contains the combined changes of the branch
+ */
+"squash" | 
+/**
+ * Branch commits match baseline commits by changed lines, ignoring whitespace, line offsets and context
+ */
+"fuzzyPatch"
+/**
+ * An operation interrupted by a crash
+ */
+export type InterruptedOperation = { kind: InterruptedOperationKind; 
+/**
+ * What was interrupted, for display
+ */
+description: string; 
+/**
+ * Ways to recover, the recommended one first
+ */
+options: RecoveryOption[] }
+export type InterruptedOperationKind = 
+/**
+ * A sync crashed while moving refs
+ */
+"sync" | 
+/**
+ * The checked out branch was rewritten (amend, reword, drop), but the index wasn't updated to the rewritten commit
+ */
+"rewrite" | 
+/**
+ * Lock and temporary index files left behind
+ */
+"tempFiles"
+export type IssueNavigationConfig = { links: IssueNavigationLink[] }
+export type IssueNavigationLink = { issueRegexp: string; linkRegexp: string }
+/**
+ * Changed tokens of a removed or added line
+ */
+export type LaunchMergeToolParams = { repositoryPath: string; 
+/**
+ * Conflicting commit (`commitHash` of the merge conflict)
+ */
+commitHash: string; 
+/**
+ * Commit it was applied onto (`targetBranchHash` of the merge conflict)
+ */
+targetCommitHash: string; 
+/**
+ * Path of the conflicting file
+ */
+file: string }
+export type LaunchMergeToolResult = { 
+/**
+ * Name of the tool that was run
+ */
+tool: string; 
+/**
+ * Blob the file gets in the resolved commit, `None` if the merge was not completed in the tool
+ */
+blobId: string | null }
+export type LineWordDiff = { side: DiffSide; 
+/**
+ * 1-based line number in the file of its side
+ */
+lineNumber: number; ranges: WordDiffRange[] }
+/**
+ * Baseline commit that integrated branch changes
+ */
+export type MatchedCommit = { id: string; subject: string; committedAt: number }
+/**
+ * Details about a merge conflict encountered during a cherry-pick operation.
+ * 
+ * Contains information about the conflicting files, associated commit details, and conflict analysis results.
+ */
+export type MergeConflictInfo = { commitMessage: string; commitHash: string; commitAuthorTime: number; commitCommitterTime: number; originalParentMessage: string; originalParentHash: string; originalParentAuthorTime: number; originalParentCommitterTime: number; targetBranchMessage: string; targetBranchHash: string; targetBranchAuthorTime: number; targetBranchCommitterTime: number; conflictingFiles: ConflictDetail[]; conflictAnalysis: ConflictAnalysis; conflictMarkerCommits: Partial<{ [key in string]: ConflictMarkerCommitInfo }> }
+export type MetadataIssue = { kind: MetadataIssueKind; 
+/**
+ * Notes ref or state ref holding the entry
+ */
+reference: string; 
+/**
+ * Annotated object of a note
+ */
+objectId: string | null; detail: string; repaired: boolean }
+export type MetadataIssueKind = 
+/**
+ * Note attached to an object that no longer exists (e.g. after `git gc` of a rewritten history)
+ */
+"orphanedNote" | 
+/**
+ * Sync mapping pointing to a rewritten commit that no longer exists, the next sync would copy the commit again anyway
+ */
+"danglingMapping" | 
+/**
+ * Detection cache entry that can't be parsed
+ */
+"invalidCacheEntry" | 
+/**
+ * Dependency on a commit that no longer exists
+ */
+"danglingDependency" | 
+/**
+ * The repository state can't be read, it's never repaired automatically as it holds user settings
+ */
+"invalidRepoState"
+/**
+ * Represents a commit that exists in the source branch but is missing from the target branch.
+ * These commits might be causing merge conflicts.
+ */
+export type MissingCommit = { hash: string; subject: string; message: string; authorTime: number; committerTime: number; author: string; 
+/**
+ * Conflicting files changed by the commit, by their path at that commit
+ */
+filesTouched: string[]; fileDiffs: FileDiff[] }
+/**
+ * Result of checking downloaded model files
+ */
+export type ModelCacheReport = { files: ModelFileStatus[]; 
+/**
+ * All files are present and not corrupt
+ */
+isComplete: boolean }
+/**
+ * State of a downloaded model file
+ */
+export type ModelFileState = 
+/**
+ * Size and SHA256 match the manifest
+ */
+{ type: "Valid" } | 
+/**
+ * Present, but no checksum is known
+ */
+{ type: "Unverified" } | { type: "Missing" } | 
+/**
+ * Interrupted download, resumed by the next download
+ */
+{ type: "Partial"; data: { downloaded: number; expected: number | null } } | { type: "Corrupt"; data: { reason: string } }
+export type ModelFileStatus = { fileName: string; state: ModelFileState }
+export type ModelFilesStatus = { config: boolean; model: boolean; tokenizer: boolean }
+export type ModelStatus = { available: boolean; modelName: string; modelSize: string; filesPresent: ModelFilesStatus }
+/**
+ * Whether network requests are made, see `sync_core::network`
+ */
+export type NetworkStatus = "online" | 
+/**
+ * No network connection was detected
+ */
+"offline" | 
+/**
+ * Offline mode was switched on by the user
+ */
+"offlineMode"
+export type OpenSubWindowParams = { windowId: string; url: string; title: string; width: number | null; height: number | null; data: string; storeCache: string }
+export type OperationPlanResult = { completedOperations: number; 
+/**
+ * New ID of every commit rewritten by the plan, keyed by its ID before the plan
+ */
+commitMapping: Partial<{ [key in string]: string }> }
+/**
+ * Output stream of the pre-push check
+ */
+export type OutputStream = "stdout" | "stderr"
+/**
+ * Output layout of an exported patch series
+ */
+export type PatchExportFormat = 
+/**
+ * One `NNNN-subject.patch` file per commit in the output directory
+ */
+"series" | 
+/**
+ * All patches in a single mbox file
+ */
+"mbox"
+export type PlanOperation = { type: "renameBranch"; oldName: string; newName: string } | 
+/**
+ * Replace the `(branch-name)` prefix of the commits (adding it if missing) with the target branch
+ */
+{ type: "moveCommits"; commitIds: string[]; targetBranch: string } | { type: "addIssueReference"; branchName: string; issueReference: string } | { type: "sync" } | { type: "push"; branchName: string }
+/**
+ * Progress of the pre-push check, streamed while the hook or command runs
+ */
+export type PrePushEvent = 
+/**
+ * The hook or command was started in a worktree of the virtual branch
+ */
+{ type: "Started"; data: { command: string } } | 
+/**
+ * A line written by the hook or command
+ */
+{ type: "Output"; data: { stream: OutputStream; line: string } } | { type: "Finished"; data: { exitCode: number } }
+/**
+ * Branch whose name is probably a misspelling of another branch name, e.g. `feature-auht` next to `feature-auth`
+ */
+export type PrefixTypo = { 
+/**
+ * Probably misspelled branch name
+ */
+branchName: string; 
+/**
+ * Branch the commits were probably meant for
+ */
+suggestedBranchName: string; 
+/**
+ * Commits grouped into the misspelled branch
+ */
+commitIds: string[] }
+export type PreviewAmendParams = { repositoryPath: string; originalCommitId: string; files: string[] }
+export type PushBranchParams = { repositoryPath: string; branchPrefix: string; branchName: string; totalCommits: number; myEmail: string | null; baselineBranch: string }
+export type ReassignCommitParams = { repositoryPath: string; commitId: string; targetBranch: string }
+export type ReassignCommitResult = { 
+/**
+ * Branch the commit leaves without it; `None` if the commit was unassigned
+ */
+source: BranchSyncPrediction | null; 
+/**
+ * Target branch with the commit
+ */
+target: BranchSyncPrediction; 
+/**
+ * New ID of the reworded commit; `None` if a predicted conflict or error prevented the rewrite
+ */
+newCommitId: string | null }
+export type RebaseBranchOntoParams = { repositoryPath: string; branchPrefix: string; branchName: string; 
+/**
+ * Ref to rebuild the branch on (e.g., "origin/release-1.2")
+ */
+onto: string }
+export type RebaseBranchOntoResult = { 
+/**
+ * Full name of the rebuilt branch (e.g., "user/release-1.2/feature-x"), created or updated unless a conflict was reported
+ */
+targetBranch: string; commitCount: number }
+export type RecordUsageParams = { repositoryPath: string; counter: UiUsageCounter }
+/**
+ * One way to recover from an interrupted operation, passed back as is to [`apply_recovery_option`]
+ */
+export type RecoveryOption = 
+/**
+ * Move the refs of the interrupted sync back to where they were before it started, the checked out branch too
+ */
+{ type: "rollBackSync" } | 
+/**
+ * Keep the refs as the interrupted sync left them, the next sync brings the rest up to date
+ */
+{ type: "keepSyncRefs" } | 
+/**
+ * Move the branch back to the commit before the rewrite
+ */
+{ type: "restorePreviousRef"; branchRef: string; previousCommit: string; rewrittenCommit: string } | 
+/**
+ * Finish the rewrite by updating the index to the rewritten commit, the working tree is left alone
+ */
+{ type: "continueRewrite"; branchRef: string; rewrittenCommit: string } | 
+/**
+ * Delete the lock and temporary files
+ */
+{ type: "removeTempFiles" }
+/**
+ * Refs as they were before an operation started
+ */
+export type RefJournal = { 
+/**
+ * Commit holding the journal, the journal ref is only deleted while it still points to it
+ */
+commitId: string; 
+/**
+ * What the journal was started for, for display
+ */
+description: string; 
+/**
+ * Process that started the operation, `None` for journals of older versions
+ */
+ownerPid: number | null; 
+/**
+ * Covered refs: every ref starting with one of them (e.g. `refs/heads/prefix/` or `refs/notes/commits`)
+ */
+refPrefixes: string[]; 
+/**
+ * Commit of every covered ref, keyed by full ref name
+ */
+refs: Partial<{ [key in string]: string }> }
+/**
+ * Resolved hosting provider settings of a remote, used by provider integrations to talk to the right instance
+ */
+export type RemoteHostingConfig = { remoteName: string; provider: HostingProviderKind; host: string; 
+/**
+ * Project path on the host, e.g. `develar/branch-deck` or `group/subgroup/project`
+ */
+projectPath: string; 
+/**
+ * Web URL of the project
+ */
+webUrl: string; 
+/**
+ * REST API base URL, e.g. `https://api.github.com` or `https://github.example.com/api/v3`
+ */
+apiBaseUrl: string; 
+/**
+ * PEM file with extra CA certificates for instances using a private CA
+ */
+caCertPath: string | null; 
+/**
+ * Whether the provider or API base URL comes from git config instead of detection
+ */
+overridden: boolean }
+/**
+ * Remote branch status information
+ */
+export type RemoteStatusUpdate = { branchName: string; remoteExists: boolean; unpushedCommits: string[]; commitsBehind: number; 
+/**
+ * Number of commits ahead authored by the current user (derived during sync)
+ */
+myUnpushedCount: number; 
+/**
+ * Last time this branch was pushed to the remote (Unix timestamp, 0 = never pushed)
+ */
+lastPushTime: number; 
+/**
+ * Review activity on the open pull request of the pushed branch.
+ * Only the background refresh queries the hosting provider, and only with `branchdeck.reviewStatus` enabled.
+ */
+review: ReviewSummary | null; 
+/**
+ * CI status of the pushed head commit.
+ * Only the background refresh queries the hosting provider, and only with `branchdeck.ciStatus` enabled.
+ */
+ciStatus: CiStatus | null }
+export type RenameVirtualBranchParams = { repositoryPath: string; branchPrefix: string; oldName: string; newName: string }
+export type RenameVirtualBranchResult = { 
+/**
+ * Number of commits whose `(old-name)` prefix was rewritten
+ */
+rewordedCount: number; 
+/**
+ * New names of the migrated archived branches (e.g., "user/archived/2025-08-11/new-name")
+ */
+archivedBranches: string[]; 
+/**
+ * Whether the branch was renamed on the remote as well
+ */
+remoteUpdated: boolean }
+export type ReorderBranchesParams = { repositoryPath: string; 
+/**
+ * Branch names in the desired display order
+ */
+branchNames: string[] }
+export type ResolveBinaryConflictParams = { repositoryPath: string; 
+/**
+ * Conflicting commit (`commitHash` of the merge conflict)
+ */
+commitHash: string; 
+/**
+ * Commit it was applied onto (`targetBranchHash` of the merge conflict)
+ */
+targetCommitHash: string; 
+/**
+ * Path of the conflicting binary file
+ */
+file: string; resolution: BinaryConflictResolution }
+export type ResolveBinaryConflictResult = { 
+/**
+ * Blob the file gets in the resolved commit
+ */
+blobId: string }
+export type ResumeIntegrationDetectionParams = { repositoryPath: string; 
+/**
+ * Full names of the archived branches
+ */
+branchNames: string[] }
+/**
+ * Archived branch selected for deletion
+ */
+export type RetentionCandidate = { branchName: string; 
+/**
+ * Archive date from the branch path (YYYY-MM-DD)
+ */
+archivedDate: string; reason: RetentionReason }
+/**
+ * Rule that selected an archived branch for deletion
+ */
+export type RetentionReason = "maxAge" | "maxCount"
+/**
+ * Whether a pull request can be merged as far as reviews are concerned
+ */
+export type ReviewDecision = "approved" | "changesRequested" | 
+/**
+ * More approvals are required
+ */
+"reviewRequired"
+/**
+ * Review activity on the open pull request (GitHub) or merge request (GitLab) of a branch
+ */
+export type ReviewSummary = { 
+/**
+ * Pull request number, merge request IID on GitLab
+ */
+number: number; url: string; 
+/**
+ * Comments on the pull request and in review threads
+ */
+commentCount: number; unresolvedThreadCount: number; 
+/**
+ * Reviewers whose latest review approves the changes
+ */
+approvalCount: number; 
+/**
+ * `None` if the provider doesn't tell, e.g. no approval rules on GitLab
+ */
+reviewDecision: ReviewDecision | null }
+export type RewordCommitParams = { commitId: string; newMessage: string }
+export type RewordCommitsParams = { repositoryPath: string; 
+/**
+ * New message for each commit, commits not listed keep their message
+ */
+rewrites: RewordCommitParams[] }
+export type RewordCommitsResult = { 
+/**
+ * Old commit ID to new commit ID for every reworded commit
+ */
+commitMapping: Partial<{ [key in string]: string }> }
+export type RewordResult = { success: boolean; message: string; reworded_count: number }
+export type SendBranchPatchesParams = { repositoryPath: string; branchPrefix: string; 
+/**
+ * Virtual branch name (without prefix)
+ */
+branchName: string; 
+/**
+ * Recipients, the configured defaults are used when empty
+ */
+to: string[]; 
+/**
+ * Carbon copy recipients, the configured defaults are used when empty
+ */
+cc: string[]; 
+/**
+ * Send a cover letter rendered from the branch template as the first message
+ */
+coverLetter: boolean; 
+/**
+ * Branch summary used for the `{summary}` placeholder (falls back to the branch name)
+ */
+summary: string | null; 
+/**
+ * Subject prefix, `PATCH` by default (e.g. `PATCH v2` or `RFC PATCH`)
+ */
+subjectPrefix: string | null }
+export type SendBranchPatchesResult = { 
+/**
+ * Sent mails in order, the first one starts the thread
+ */
+messages: SentMessage[] }
+/**
+ * Progress events for sending patches
+ */
+export type SendEmailProgress = { type: "Started"; data: { total: number } } | { type: "MessageSent"; data: { index: number; message: SentMessage } } | { type: "Completed" } | { type: "Error"; data: { message: string } }
+/**
+ * A mail handed to the SMTP client
+ */
+export type SentMessage = { subject: string; messageId: string; 
+/**
+ * Commit the patch was generated from, `None` for the cover letter
+ */
+commitId: string | null }
+/**
+ * Parameters for storing the API key of a provider endpoint
+ */
+export type SetAiProviderApiKeyParams = { repositoryPath: string; settings: AiProviderSettings; 
+/**
+ * `None` removes the stored key
+ */
+apiKey: string | null }
+/**
+ * Parameters for selecting the AI provider of a repository
+ */
+export type SetAiProviderSettingsParams = { repositoryPath: string; settings: AiProviderSettings }
+export type SetBranchCommitOrderParams = { repositoryPath: string; branchName: string; commitOrder: CommitOrder }
+export type SetBranchPinnedParams = { repositoryPath: string; branchName: string; pinned: boolean }
+export type SetCommitDependenciesParams = { repositoryPath: string; commitId: string; 
+/**
+ * Commits that must be synced before this one (an empty list removes all dependencies)
+ */
+dependsOn: string[] }
+export type SetCoverLetterTemplateParams = { repositoryPath: string; branchName: string; 
+/**
+ * Template with `{branch}`, `{summary}`, `{count}`, `{patches}` (e.g. "1 patch") and `{shortlog}` placeholders; `None` restores the default
+ */
+template: string | null }
+export type SetRemoteHostingOverrideParams = { repositoryPath: string; remoteName: string; 
+/**
+ * `None` detects the provider from the remote host
+ */
+provider: HostingProviderKind | null; 
+/**
+ * `None` uses the default API URL of the provider
+ */
+apiBaseUrl: string | null; 
+/**
+ * `None` falls back to git's `http.<url>.sslCAInfo`
+ */
+caCertPath: string | null }
+export type SetSmtpConfigParams = { repositoryPath: string; config: SmtpConfig }
+export type SimulateConflictParams = { scenario: ConflictScenarioKind; 
+/**
+ * Directory to create the scratch repository in (defaults to the system temp directory)
+ */
+parentDir: string | null; branchPrefix: string | null }
+export type SimulateConflictResult = { 
+/**
+ * Path of the scratch repository; syncing it reproduces the conflict
+ */
+repositoryPath: string; branchPrefix: string; 
+/**
+ * Virtual branch that fails to sync
+ */
+branchName: string; 
+/**
+ * Conflict details as the sync would report them
+ */
+conflict: MergeConflictInfo }
+/**
+ * SMTP settings, stored in the standard `sendemail.*` git config keys so `git send-email` uses them as is.
+ * The password is not stored: `git send-email` asks the git credential helper for it.
+ */
+export type SmtpConfig = { 
+/**
+ * SMTP host, or the absolute path of a sendmail-like program
+ */
+server: string | null; port: number | null; encryption: SmtpEncryption | null; user: string | null; 
+/**
+ * Sender address, the git identity is used when not set
+ */
+from: string | null; 
+/**
+ * Default recipients
+ */
+to: string[]; 
+/**
+ * Default carbon copy recipients
+ */
+cc: string[] }
+/**
+ * Connection security of the SMTP server (`sendemail.smtpEncryption`)
+ */
+export type SmtpEncryption = 
+/**
+ * STARTTLS
+ */
+"tls" | 
+/**
+ * SMTP over SSL
+ */
+"ssl"
+export type SquashBranchCommitsParams = { repositoryPath: string; branchName: string; 
+/**
+ * Message of the squashed commit (e.g., AI-generated), combined from the squashed commits if not set.
+ * The `(branch-name)` prefix is added if missing.
+ */
+message: string | null }
+export type SquashBranchCommitsResult = { squashedCount: number; squashedCommitId: string; message: string }
+export type StartRemoteStatusRefreshParams = { repositoryPath: string; branchPrefix: string; 
+/**
+ * Seconds between refreshes (5 minutes by default, from 1 to 30 minutes)
+ */
+intervalSeconds: number | null }
+export type StopRemoteStatusRefreshParams = { repositoryPath: string }
+export type SubscribeSyncEventsParams = { repositoryPath: string; filter: SyncEventFilter }
+/**
+ * Parameters for requesting branch name suggestions
+ */
+export type SuggestBranchNameParams = { repositoryPath: string; branchPrefix: string; commits: CommitInfo[] }
+/**
+ * Parameters for requesting a grouping of unassigned commits into branches
+ */
+export type SuggestCommitGroupingParams = { repositoryPath: string }
+/**
+ * Parameters for requesting an improved commit message
+ */
+export type SuggestCommitMessageParams = { repositoryPath: string; commitId: string }
+export type SuggestedCommitGroup = { branchName: string; 
+/**
+ * Commits are added to an existing branch rather than creating a new one
+ */
+isExistingBranch: boolean; 
+/**
+ * Commits in history order, ready for `create_branch_from_commits`
+ */
+commitIds: string[] }
+/**
+ * Progress events for branch name suggestion generation
+ */
+export type SuggestionProgress = { type: "Started"; data: { total: number } } | 
+/**
+ * Text generated so far by a remote provider, replaced by `SuggestionReady` for the same index
+ */
+{ type: "PartialSuggestion"; data: { text: string; index: number } } | { type: "SuggestionReady"; data: { suggestion: BranchSuggestion; index: number } } | { type: "Completed" } | { type: "Cancelled" } | { type: "Error"; data: { message: string } } | { type: "ModelDownloadInProgress"; data: { model_name: string; model_size: string } }
+export type SyncBranchesParams = { repositoryPath: string; branchPrefix: string; 
+/**
+ * Only rebuild the virtual branches with a commit matching the filter, their other commits are kept
+ */
+commitFilter: CommitFilter | null; 
+/**
+ * Only send matching events over the channel, all events when not set
+ */
+eventFilter: SyncEventFilter | null }
+export type SyncDryRunParams = { repositoryPath: string; 
+/**
+ * Only predict the branches with a commit matching the filter (date, count, paths), see `SyncOptions::commit_filter`
+ */
+commitFilter: CommitFilter | null }
+export type SyncDryRunResult = { baselineBranch: string; 
+/**
+ * Branches in grouping order
+ */
+branches: BranchSyncPrediction[] }
+/**
+ * Progress events for sync operations
+ */
+export type SyncEvent = 
 /**
  * Sent at the beginning with issue navigation configuration if found
  */
 { type: "issueNavigationConfig"; data: { config: IssueNavigationConfig | null } } | 
+/**
+ * Sent before fetching more history of a shallow clone to reach the merge base with the baseline
+ */
+{ type: "historyDeepening"; data: { remote: string; 
+/**
+ * Number of commits to fetch beyond the shallow boundary, `None` when fetching the whole history
+ */
+deepenBy: number | null } } | 
 /**
  * Sent immediately after grouping commits
  */
@@ -378,11 +2860,23 @@ export type SyncEvent =
 /**
  * Repository's baseline branch (e.g., "origin/master", "master")
  */
-baselineBranch: string } } | 
+baselineBranch: string; 
+/**
+ * Filter selecting the rebuilt branches, `None` when all branches were synced
+ */
+commitFilter: CommitFilter | null } } | 
 /**
  * Sent for commits that don't match any prefix pattern
  */
 { type: "unassignedCommits"; data: { commits: Commit[] } } | 
+/**
+ * Sent after grouping with the commits whose subject could have been grouped differently (empty if none)
+ */
+{ type: "groupingDiagnostics"; data: { diagnostics: GroupingDiagnostic[] } } | 
+/**
+ * Sent after grouping for each branch whose name is probably a misspelling of another one
+ */
+{ type: "possiblePrefixTypo"; data: PrefixTypo } | 
 /**
  * Sent when a commit is successfully cherry-picked
  */
@@ -407,19 +2901,280 @@ baselineBranch: string } } |
  * Sent immediately when archived branches are found (before expensive detection)
  */
 { type: "archivedBranchesFound"; data: { branchNames: string[] } } | 
+/**
+ * Sent when a branch fully integrated for the configured number of consecutive syncs is archived automatically
+ */
+{ type: "branchAutoArchived"; data: { branchName: string; 
+/**
+ * Name of the archived branch, `None` if there was no virtual branch to archive
+ */
+archivedBranch: string | null; 
+/**
+ * Consecutive syncs the branch was found fully integrated
+ */
+integratedSyncCount: number; 
+/**
+ * Commits of the branch dropped from HEAD, 0 unless pruning is enabled
+ */
+prunedCommitCount: number } } | 
 /**
  * Sent when remote branch status is checked
  */
-{ type: "remoteStatusUpdate"; data: RemoteStatusUpdate }
+{ type: "remoteStatusUpdate"; data: RemoteStatusUpdate } | 
+/**
+ * Sent after archiving when remote status collection for archived branches is enabled
+ */
+{ type: "archivedRemoteStatus"; data: { branches: ArchivedRemoteStatus[] } } | 
+/**
+ * Sent when a phase exceeds its time budget: the results sent so far are partial,
+ * the sync returns and the phase continues in the background
+ */
+{ type: "phaseContinuesInBackground"; data: { phase: SyncPhase; budgetMs: number } } | 
+/**
+ * Sent when a phase that continued in the background is done
+ */
+{ type: "backgroundPhaseCompleted"; data: { phase: SyncPhase; error: string | null } } | 
+/**
+ * Sent before each operation of an operation plan; events of the operation itself (e.g., a sync) follow
+ */
+{ type: "planOperationStarted"; data: { 
+/**
+ * 0-based position of the operation in the plan
+ */
+index: number; total: number; description: string } } | 
+/**
+ * Sent after each executed operation of an operation plan, the plan stops at the first error
+ */
+{ type: "planOperationCompleted"; data: { index: number; error: string | null } } | 
+/**
+ * Sent before the sync when uncommitted changes of tracked files were stashed (see `sync_core::auto_stash`)
+ */
+{ type: "uncommittedChangesStashed"; data: { stashCommit: string; fileCount: number } } | 
+/**
+ * Sent after the sync when the stashed changes were brought back
+ */
+{ type: "uncommittedChangesRestored"; data: { stashCommit: string; 
+/**
+ * Files conflicting with the new HEAD; if any, nothing was restored and the changes are kept in `refs/branch-deck/autostash`
+ */
+conflictingFiles: string[] } } | 
+/**
+ * Sent at the end of every sync run, successful or not; the report is also kept in the sync history
+ */
+{ type: "syncPerformanceReport"; data: { report: SyncPerformanceReport } } | 
+/**
+ * Sent by the remote status refresh when the baseline advanced on the remote since the last sync, once per new remote head
+ */
+{ type: "baselineAdvanced"; data: BaselineAdvanced } | 
+/**
+ * Sent when the phase or percentage of the fetch before the sync changes (see `sync_core::auto_fetch`)
+ */
+{ type: "fetchProgress"; data: { 
+/**
+ * Like "Receiving objects", without the "remote: " prefix of server-side phases
+ */
+phase: string; percent: number | null } } | 
+/**
+ * Sent when the fetch before the sync is done. On error the sync goes on offline with the remote-tracking refs fetched earlier.
+ */
+{ type: "fetchCompleted"; data: { error: string | null } } | 
+/**
+ * Sent by the remote status refresh when the network status changes; while offline the refresh is skipped
+ */
+{ type: "networkStatusChanged"; data: { status: NetworkStatus } }
+/**
+ * Which sync events a subscriber receives, e.g. a sub-window only interested in the conflicts of one branch
+ */
+export type SyncEventFilter = { 
+/**
+ * Only events of these virtual branches (e.g. "feature-auth"); `BranchesGrouped` is narrowed to them
+ * and repository-wide events are dropped. `None` keeps events of all branches.
+ */
+branchNames: string[] | null; 
+/**
+ * Only commit errors, blocked commits and failed or conflicted branch statuses
+ */
+errorsOnly: boolean }
+/**
+ * One sync run in the sync history
+ */
+export type SyncHistoryEntry = { 
+/**
+ * Unix timestamp of the end of the run
+ */
+timestamp: number; branchPrefix: string; success: boolean; 
+/**
+ * Commit of the baseline branch at the end of the run, `None` if it couldn't be resolved or for runs of older versions
+ */
+baselineCommit: string | null; report: SyncPerformanceReport }
+/**
+ * Durations of the phases of one sync run, in milliseconds
+ */
+export type SyncPerformanceReport = { totalMs: number; 
+/**
+ * Reading commits ahead of the baseline and grouping them into branches
+ */
+groupingMs: number; 
+/**
+ * Rebuilding the virtual branches, `None` if the phase continued in the background
+ */
+branchSyncMs: number | null; 
+/**
+ * Time spent copying commits, summed over branches synced in parallel
+ */
+cherryPickMs: number; 
+/**
+ * Time spent computing the remote status of branches, summed over branches synced in parallel
+ */
+remoteStatusMs: number; 
+/**
+ * Archiving and integration detection, `None` if skipped or continued in the background
+ */
+integrationDetectionMs: number | null; commitCount: number; branchCount: number }
+/**
+ * Sync phase that can continue in the background when it exceeds its time budget
+ */
+export type SyncPhase = 
+/**
+ * Rebuilding the virtual branches
+ */
+"branchSync" | 
+/**
+ * Archiving inactive branches and detecting integrated ones
+ */
+"integrationDetection"
+/**
+ * Outcome of one sync run, collected from its events
+ */
+export type SyncSummary = { repositoryPath: string; branchPrefix: string; success: boolean; error: string | null; createdBranches: string[]; updatedBranches: string[]; 
+/**
+ * Branches that failed to sync, mostly because of merge conflicts
+ */
+failedBranches: string[]; 
+/**
+ * Branches whose commits are all in the baseline
+ */
+integratedBranches: string[] }
+export type SyncWorkspaceParams = { 
+/**
+ * Repositories of the workspace, synced in this order
+ */
+repositories: WorkspaceRepository[] }
 export type TAURI_CHANNEL<TSend> = null
+/**
+ * Parameters of `test_webhook_connection`
+ */
+export type TestWebhookConnectionParams = { repositoryPath: string; 
+/**
+ * URL to test before it is saved, the configured webhook when not set
+ */
+url: string | null; 
+/**
+ * Format for `url`, derived from the URL when not set
+ */
+format: WebhookFormat | null }
+/**
+ * Operation only the frontend knows about, the only counters `record_usage` may be called with from there.
+ * The backend counts syncs, conflicts and pushes itself.
+ */
+export type UiUsageCounter = 
+/**
+ * A suggested branch name or commit message that was used
+ */
+"aiSuggestionAccepted"
 export type UnapplyBranchParams = { repositoryPath: string; branchName: string; branchPrefix: string; originalCommitIds: string[] }
 export type UnapplyBranchResult = { unappliedBranchName: string; commitsRemoved: string[] }
 export type UncommittedChangesResult = { hasChanges: boolean; files: UncommittedFileChange[] }
 export type UncommittedFileChange = { filePath: string; status: string; staged: boolean; unstaged: boolean }
+/**
+ * Local refs before a multi-step operation: the checked out branch, the branches under the prefix and the repository state.
+ * Remote branches are not part of it, a push can't be undone.
+ */
+export type UndoSnapshot = { 
+/**
+ * What the snapshot was taken for, for display
+ */
+description: string; branchPrefix: string; 
+/**
+ * Checked out branch (e.g., "refs/heads/master")
+ */
+headRef: string; headCommit: string; 
+/**
+ * Commit of every other snapshotted ref, keyed by full ref name
+ */
+refs: Partial<{ [key in string]: string }> }
+export type UndoSnapshotParams = { repositoryPath: string }
 export type UpdateInfo = { current_version: string; available_version: string; is_update_available: boolean; status: UpdateStatus }
 export type UpdateStatus = "Idle" | "Checking" | "Downloading" | "Downloaded" | "Installing" | { Error: string }
+export type UsageStats = { 
+/**
+ * Unix timestamp of the first recorded operation, `None` if nothing was recorded yet
+ */
+since: number | null; syncs: number; 
+/**
+ * Branches that hit a merge conflict, once per sync
+ */
+mergeConflicts: number; pushes: number; aiSuggestionsAccepted: number }
 export type ValidateRepositoryPathParams = { path: string }
+export type VerifyMetadataParams = { repositoryPath: string; 
+/**
+ * Remove or rewrite broken entries; otherwise only report them
+ */
+repair: boolean }
+export type VerifyMetadataResult = { checkedNotes: number; issues: MetadataIssue[]; repairedCount: number }
+/**
+ * Parameters for checking downloaded model files
+ */
+export type VerifyModelCacheParams = { 
+/**
+ * Delete corrupt files so the next download fetches them again
+ */
+repair: boolean }
+/**
+ * Payload format of the webhook
+ */
+export type WebhookFormat = 
+/**
+ * Slack incoming webhook: `{"text": ...}` with mrkdwn
+ */
+"slack" | 
+/**
+ * Microsoft Teams incoming webhook: `{"text": ...}` with markdown
+ */
+"teams" | 
+/**
+ * The [`SyncSummary`] as is
+ */
+"generic"
 export type WindowError = { message: string }
+/**
+ * Changed part of a line, in UTF-16 code units (JavaScript string indices), end exclusive
+ */
+export type WordDiffRange = { start: number; end: number }
+/**
+ * Member of a workspace
+ */
+export type WorkspaceRepository = { repositoryPath: string; 
+/**
+ * Branch prefix for this repository, `branchdeck.branchPrefix` from its git config when not set
+ */
+branchPrefix: string | null }
+/**
+ * Progress events of a workspace sync
+ */
+export type WorkspaceSyncEvent = 
+/**
+ * Sent before the sync of a repository starts
+ */
+{ type: "repositorySyncStarted"; data: { repositoryPath: string; branchPrefix: string } } | 
+/**
+ * Event of the sync of a repository
+ */
+{ type: "repositorySyncEvent"; data: { repositoryPath: string; event: SyncEvent } } | 
+/**
+ * Sent after the sync of a repository, whether it succeeded or not
+ */
+{ type: "repositorySyncFinished"; data: { summary: SyncSummary } }
 
 /** tauri-specta globals **/
 
@@ -1,10 +1,15 @@
-use anyhow::Result;
+use crate::common::BranchData;
+use anyhow::{Context, Result};
 use git_executor::git_command_executor::GitCommandExecutor;
 use git_ops::commit_list::{Commit, parse_single_commit};
+use git_ops::git_config::{get_config_bool, get_config_value};
 use git_ops::model::extract_branch_name_from_final;
+use git_ops::notes::{collect_branch_notes, remove_commit_notes};
+use git_ops::ref_transaction::RefTransaction;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::fmt::Write;
 use std::sync::{LazyLock, Mutex};
+use sync_types::branch_integration::BranchIntegrationStatus;
 use tracing::{debug, info, instrument, warn};
 
 // Global mutex to prevent race conditions when creating archive directories
@@ -119,10 +124,7 @@ pub fn batch_archive_inactive_branches(
   let _ = git_executor.execute_command(&["branch", &temp_branch, "HEAD"], repo_path);
   let _ = git_executor.execute_command(&["branch", "-D", &temp_branch], repo_path);
 
-  // Build batch commands efficiently
-  let mut batch_commands = String::with_capacity(archive_mappings.len() * 150 + 20);
-  batch_commands.push_str("start\n");
-
+  let mut transaction = RefTransaction::new();
   let mut newly_archived = HashMap::with_capacity(archive_mappings.len());
 
   for (original_branch, archived_branch) in &archive_mappings {
@@ -133,19 +135,17 @@ pub fn batch_archive_inactive_branches(
         commit_hash = %commit_hash,
         "Archiving branch with commit mapping"
       );
-      // Use write! for efficiency
-      writeln!(&mut batch_commands, "create refs/heads/{archived_branch} {commit_hash}")?;
-      writeln!(&mut batch_commands, "delete refs/heads/{original_branch} {commit_hash}")?;
+      transaction
+        .create(&format!("refs/heads/{archived_branch}"), commit_hash)
+        .delete(&format!("refs/heads/{original_branch}"), Some(commit_hash));
       newly_archived.insert(archived_branch.clone(), commit_hash.clone());
     } else {
       warn!(branch = %original_branch, "Branch not found in commit map, skipping archive");
     }
   }
 
-  batch_commands.push_str("commit\n");
-
   // Execute the batch update atomically
-  git_executor.execute_command_with_input(&["update-ref", "--stdin"], repo_path, &batch_commands)?;
+  transaction.commit(git_executor, repo_path, "branch-deck: archive")?;
 
   info!(
     archived_count = newly_archived.len(),
@@ -163,23 +163,66 @@ pub fn batch_archive_inactive_branches(
 #[instrument(skip(git_executor))]
 pub fn get_archived_branch_commits(git_executor: &GitCommandExecutor, repository_path: &str, branch_name: &str, baseline_branch: &str) -> Result<Vec<Commit>> {
   // The branch_name is the full branch path (e.g., "user/archived/2025-08-11/feature-auth")
-  let actual_branch_name = branch_name.to_string();
+  let range = archived_branch_range(git_executor, repository_path, branch_name, baseline_branch)?;
+  let commits = read_commits(git_executor, repository_path, &["--reverse"], &range)?;
+  debug!(branch = %branch_name, commit_count = commits.len(), "Retrieved commits from archived branch");
+  Ok(commits)
+}
+
+/// One page of the commits of an archived branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedBranchCommitsPage {
+  /// Commits of the page, oldest first
+  pub commits: Vec<Commit>,
+  /// Number of commits on the whole branch
+  pub total_count: u32,
+}
+
+/// Get a page of commits for an archived branch.
+/// Pages are counted from the branch tip: offset 0 returns the `limit` newest commits, so the first page is cheap even for long-lived branches.
+/// Commit diffs are not included, see `git_ops::conflict_analysis::get_commit_file_diffs`.
+#[instrument(skip(git_executor))]
+pub fn get_archived_branch_commits_page(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  branch_name: &str,
+  baseline_branch: &str,
+  offset: u32,
+  limit: u32,
+) -> Result<ArchivedBranchCommitsPage> {
+  let range = archived_branch_range(git_executor, repository_path, branch_name, baseline_branch)?;
+  let total_count = git_executor
+    .execute_command(&["rev-list", "--count", "--no-merges", &range], repository_path)?
+    .parse::<u32>()?;
+
+  let skip_arg = format!("--skip={offset}");
+  let max_count_arg = format!("--max-count={limit}");
+  // --skip and --max-count are applied before --reverse, so the page is selected from the tip and then ordered oldest first
+  let commits = read_commits(git_executor, repository_path, &[&skip_arg, &max_count_arg, "--reverse"], &range)?;
+  debug!(branch = %branch_name, offset, commit_count = commits.len(), total_count, "Retrieved page of commits from archived branch");
+  Ok(ArchivedBranchCommitsPage { commits, total_count })
+}
 
+/// Range of the commits made on an archived branch since it diverged from the baseline
+fn archived_branch_range(git_executor: &GitCommandExecutor, repository_path: &str, branch_name: &str, baseline_branch: &str) -> Result<String> {
   // Get the merge-base to find where the branch diverged from baseline
-  let merge_base = git_executor.execute_command(&["merge-base", baseline_branch, &actual_branch_name], repository_path)?;
+  let merge_base = git_executor.execute_command(&["merge-base", baseline_branch, branch_name], repository_path)?;
   let merge_base = merge_base.trim();
+  debug!(branch = %branch_name, merge_base = %merge_base, "Getting commits on branch since divergence");
+  Ok(format!("{merge_base}..{branch_name}"))
+}
 
-  // Get all commits from merge-base to branch tip
-  let range = format!("{merge_base}..{actual_branch_name}");
-  debug!(branch = %actual_branch_name, merge_base = %merge_base, range = %range, "Getting all commits on branch since divergence");
-  let args = vec![
+fn read_commits(git_executor: &GitCommandExecutor, repository_path: &str, extra_args: &[&str], range: &str) -> Result<Vec<Commit>> {
+  let mut args = vec![
     "--no-pager",
     "log",
-    "--reverse",
     "--no-merges",
     "--pretty=format:%H%x1f%B%x1f%an%x1f%ae%x1f%at%x1f%ct%x1f%P%x1f%T%x1f%N%x1e",
-    &range,
   ];
+  args.extend_from_slice(extra_args);
+  args.push(range);
 
   let output = git_executor.execute_command(&args, repository_path)?;
 
@@ -193,8 +236,6 @@ pub fn get_archived_branch_commits(git_executor: &GitCommandExecutor, repository
       commits.push(commit);
     }
   }
-
-  debug!(branch = %actual_branch_name, commit_count = commits.len(), "Retrieved commits from archived branch");
   Ok(commits)
 }
 
@@ -209,6 +250,12 @@ pub fn batch_delete_archived_branches(git_executor: &GitCommandExecutor, repo_pa
   // Lock to prevent race conditions during deletion
   let _guard = ARCHIVE_MUTEX.lock().map_err(|e| anyhow::anyhow!("Failed to acquire archive mutex: {}", e))?;
 
+  // Notes copied to the archived commits would otherwise outlive them
+  let noted_commits = collect_branch_notes(git_executor, repo_path, branches).unwrap_or_else(|e| {
+    tracing::warn!(error = %e, "Failed to collect notes of archived branches");
+    Vec::new()
+  });
+
   // Build args for a single git call: `git branch -D <branches...>`
   let mut args: Vec<&str> = Vec::with_capacity(2 + branches.len());
   args.push("branch");
@@ -217,8 +264,8 @@ pub fn batch_delete_archived_branches(git_executor: &GitCommandExecutor, repo_pa
     args.push(name.as_str());
   }
 
-  match git_executor.execute_command(&args, repo_path) {
-    Ok(_) => Ok(branches.len()),
+  let deleted = match git_executor.execute_command(&args, repo_path) {
+    Ok(_) => branches.len(),
     Err(e) => {
       // Fallback: attempt individual deletions to make best effort
       tracing::warn!(error = %e, "Batch delete via git branch -D failed, attempting individual deletions");
@@ -228,7 +275,210 @@ pub fn batch_delete_archived_branches(git_executor: &GitCommandExecutor, repo_pa
           fallback_deleted += 1;
         }
       }
-      Ok(fallback_deleted)
+      fallback_deleted
+    }
+  };
+
+  // Commits of branches that failed to delete are still reachable, keep their notes
+  let noted_commits: Vec<String> = if deleted == branches.len() {
+    noted_commits
+  } else {
+    // Without the reachable commits every note would look orphaned
+    let still_reachable: HashSet<String> = git_executor
+      .execute_command_lines(&["rev-list", "--all"], repo_path)
+      .context("Failed to list commits still reachable after deleting archived branches")?
+      .into_iter()
+      .collect();
+    noted_commits.into_iter().filter(|commit| !still_reachable.contains(commit)).collect()
+  };
+  if let Err(e) = remove_commit_notes(git_executor, repo_path, &noted_commits) {
+    tracing::warn!(error = %e, "Failed to remove notes of deleted archived branches");
+  }
+
+  Ok(deleted)
+}
+
+/// Default age after which archived branches are deleted
+pub const ARCHIVE_RETENTION_DAYS: u64 = 7;
+
+/// Rules deciding which archived branches get deleted.
+/// Persisted per repository in git config (`branchdeck.archiveMaxAgeDays`, `branchdeck.archiveMaxCount`, `branchdeck.archiveOnlyIfIntegrated`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveRetentionPolicy {
+  /// Delete archived branches older than this many days (`None` disables the rule)
+  pub max_age_days: Option<u32>,
+  /// Keep at most this many archived branches under the branch prefix, newest first (`None` disables the rule)
+  pub max_count_per_prefix: Option<u32>,
+  /// Only delete branches the detection cache reports as fully integrated
+  pub only_if_integrated: bool,
+}
+
+impl ArchiveRetentionPolicy {
+  /// Policy matching the built-in cleanup: integrated branches older than `retention_days`
+  pub fn with_max_age(retention_days: u64) -> Self {
+    Self {
+      max_age_days: Some(retention_days.min(u32::MAX as u64) as u32),
+      max_count_per_prefix: None,
+      only_if_integrated: true,
+    }
+  }
+}
+
+impl Default for ArchiveRetentionPolicy {
+  fn default() -> Self {
+    Self::with_max_age(ARCHIVE_RETENTION_DAYS)
+  }
+}
+
+/// Rule that selected an archived branch for deletion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum RetentionReason {
+  MaxAge,
+  MaxCount,
+}
+
+/// Archived branch selected for deletion
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionCandidate {
+  pub branch_name: String,
+  /// Archive date from the branch path (YYYY-MM-DD)
+  pub archived_date: String,
+  pub reason: RetentionReason,
+}
+
+/// Archived branch as seen by the retention rules
+#[derive(Debug, Clone)]
+pub struct ArchivedBranchEntry {
+  pub name: String,
+  pub archived_date: chrono::NaiveDate,
+  pub integrated: bool,
+}
+
+/// Parse the archive date from `<prefix>/archived/YYYY-MM-DD/<name>`
+pub fn parse_archive_date(branch_name: &str, branch_prefix: &str) -> Option<chrono::NaiveDate> {
+  let archive_prefix = format!("{branch_prefix}/archived/");
+  let date_part = branch_name.strip_prefix(&archive_prefix)?.split('/').next()?;
+  chrono::NaiveDate::parse_from_str(date_part, "%Y-%m-%d").ok()
+}
+
+/// Build retention entries from pre-fetched branch data.
+/// A branch counts as integrated when the detection cache on its tip commit says so; branches with unparsable dates are skipped.
+pub fn archived_branch_entries(branch_data: &BranchData, branch_prefix: &str) -> Vec<ArchivedBranchEntry> {
+  branch_data
+    .archived_all
+    .iter()
+    .filter_map(|name| {
+      let archived_date = parse_archive_date(name, branch_prefix)?;
+      let integrated = branch_data
+        .all_branch_commits
+        .get(name)
+        .and_then(|tip| branch_data.branch_notes.get(tip))
+        .is_some_and(|info| matches!(info.status, BranchIntegrationStatus::Integrated { .. }));
+      Some(ArchivedBranchEntry {
+        name: name.clone(),
+        archived_date,
+        integrated,
+      })
+    })
+    .collect()
+}
+
+/// Load the retention policy from git config, falling back to `default` for unset keys
+#[instrument(skip(git_executor))]
+pub fn load_archive_retention_policy(git_executor: &GitCommandExecutor, repo_path: &str, default: ArchiveRetentionPolicy) -> Result<ArchiveRetentionPolicy> {
+  // 0 disables a numeric rule
+  let parse_limit = |key: &str, fallback: Option<u32>| -> Result<Option<u32>> {
+    match get_config_value(git_executor, repo_path, key)? {
+      Some(value) => {
+        let value: u32 = value.parse().map_err(|e| anyhow::anyhow!("Invalid value of {key}: {e}"))?;
+        Ok(if value == 0 { None } else { Some(value) })
+      }
+      None => Ok(fallback),
     }
+  };
+
+  Ok(ArchiveRetentionPolicy {
+    max_age_days: parse_limit("branchdeck.archiveMaxAgeDays", default.max_age_days)?,
+    max_count_per_prefix: parse_limit("branchdeck.archiveMaxCount", default.max_count_per_prefix)?,
+    only_if_integrated: get_config_bool(git_executor, repo_path, "branchdeck.archiveOnlyIfIntegrated")?.unwrap_or(default.only_if_integrated),
+  })
+}
+
+/// Select archived branches to delete according to `policy`.
+/// Branches are ranked newest first for the count rule; the age rule takes precedence when both apply.
+pub fn evaluate_archive_retention(branches: &[ArchivedBranchEntry], policy: &ArchiveRetentionPolicy, today: chrono::NaiveDate) -> Vec<RetentionCandidate> {
+  let mut ranked: Vec<&ArchivedBranchEntry> = branches.iter().collect();
+  ranked.sort_by(|a, b| b.archived_date.cmp(&a.archived_date).then_with(|| a.name.cmp(&b.name)));
+
+  let mut candidates = Vec::new();
+  for (rank, branch) in ranked.into_iter().enumerate() {
+    if policy.only_if_integrated && !branch.integrated {
+      continue;
+    }
+
+    let too_old = policy.max_age_days.is_some_and(|days| (today - branch.archived_date).num_days() > days as i64);
+    let over_count = policy.max_count_per_prefix.is_some_and(|max| rank >= max as usize);
+    let reason = if too_old {
+      RetentionReason::MaxAge
+    } else if over_count {
+      RetentionReason::MaxCount
+    } else {
+      continue;
+    };
+
+    candidates.push(RetentionCandidate {
+      branch_name: branch.name.clone(),
+      archived_date: branch.archived_date.format("%Y-%m-%d").to_string(),
+      reason,
+    });
   }
+  candidates
+}
+
+/// Outcome of applying the retention policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveRetentionReport {
+  pub policy: ArchiveRetentionPolicy,
+  /// Branches selected for deletion
+  pub candidates: Vec<RetentionCandidate>,
+  /// Number of deleted branches (always 0 in dry-run mode)
+  pub deleted_count: u32,
+  pub dry_run: bool,
+}
+
+/// Apply the retention policy to archived branches under `branch_prefix`.
+/// In dry-run mode only reports what would be deleted.
+#[instrument(skip(git_executor))]
+pub fn apply_archive_retention(
+  git_executor: &GitCommandExecutor,
+  repo_path: &str,
+  branch_prefix: &str,
+  policy: &ArchiveRetentionPolicy,
+  dry_run: bool,
+) -> Result<ArchiveRetentionReport> {
+  let branch_data = super::common::get_all_branch_data(git_executor, repo_path, branch_prefix)?;
+  let entries = archived_branch_entries(&branch_data, branch_prefix);
+  let candidates = evaluate_archive_retention(&entries, policy, chrono::Utc::now().date_naive());
+  let deleted_count = if dry_run || candidates.is_empty() {
+    0
+  } else {
+    let names: Vec<String> = candidates.iter().map(|c| c.branch_name.clone()).collect();
+    batch_delete_archived_branches(git_executor, repo_path, &names)? as u32
+  };
+
+  info!(candidate_count = candidates.len(), deleted_count, dry_run, "Applied archive retention policy");
+  Ok(ArchiveRetentionReport {
+    policy: policy.clone(),
+    candidates,
+    deleted_count,
+    dry_run,
+  })
 }
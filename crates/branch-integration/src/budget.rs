@@ -0,0 +1,86 @@
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::git_config::get_config_value;
+use std::time::{Duration, Instant};
+use tracing::instrument;
+
+/// Git config key with the time budget of the deep detection steps in milliseconds (0 disables the limit)
+pub const DEEP_SCAN_BUDGET_MS_CONFIG_KEY: &str = "branchdeck.detectionDeepScanBudgetMs";
+/// Git config key with the maximum number of branch commits analyzed by the deep detection steps (0 disables the limit)
+pub const DEEP_SCAN_MAX_BRANCH_COMMITS_CONFIG_KEY: &str = "branchdeck.detectionMaxBranchCommits";
+
+pub const DEFAULT_DEEP_SCAN_BUDGET: Duration = Duration::from_secs(10);
+pub const DEFAULT_DEEP_SCAN_MAX_BRANCH_COMMITS: u32 = 200;
+
+/// Limits of the deep detection steps (fuzzy matching and squash detection), so detection never stalls the sync.
+/// The rebase scan of a branch always runs; once a limit is hit the deep steps are skipped and the result is flagged as truncated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetectionBudget {
+  /// Skip the deep steps of branches detected after this much time since detection started (`None` disables the limit)
+  pub max_duration: Option<Duration>,
+  /// Skip the deep steps of branches with more commits than this (`None` disables the limit)
+  pub max_branch_commits: Option<u32>,
+}
+
+impl DetectionBudget {
+  /// Run every enabled detection step regardless of time and branch size
+  pub fn unlimited() -> Self {
+    Self {
+      max_duration: None,
+      max_branch_commits: None,
+    }
+  }
+}
+
+impl Default for DetectionBudget {
+  fn default() -> Self {
+    Self {
+      max_duration: Some(DEFAULT_DEEP_SCAN_BUDGET),
+      max_branch_commits: Some(DEFAULT_DEEP_SCAN_MAX_BRANCH_COMMITS),
+    }
+  }
+}
+
+/// Load the detection budget from git config, falling back to `default` for unset keys
+#[instrument(skip(git_executor))]
+pub fn load_detection_budget(git_executor: &GitCommandExecutor, repo_path: &str, default: DetectionBudget) -> Result<DetectionBudget> {
+  // 0 disables a limit
+  let parse_limit = |key: &str, fallback: Option<u64>| -> Result<Option<u64>> {
+    match get_config_value(git_executor, repo_path, key)? {
+      Some(value) => {
+        let value: u64 = value.parse().map_err(|e| anyhow::anyhow!("Invalid value of {key}: {e}"))?;
+        Ok(if value == 0 { None } else { Some(value) })
+      }
+      None => Ok(fallback),
+    }
+  };
+
+  let max_branch_commits = parse_limit(DEEP_SCAN_MAX_BRANCH_COMMITS_CONFIG_KEY, default.max_branch_commits.map(u64::from))?
+    .map(|value| u32::try_from(value).map_err(|e| anyhow::anyhow!("Invalid value of {DEEP_SCAN_MAX_BRANCH_COMMITS_CONFIG_KEY}: {e}")))
+    .transpose()?;
+  Ok(DetectionBudget {
+    max_duration: parse_limit(DEEP_SCAN_BUDGET_MS_CONFIG_KEY, default.max_duration.map(|duration| duration.as_millis() as u64))?.map(Duration::from_millis),
+    max_branch_commits,
+  })
+}
+
+/// Budget of one detection run, with the time limit fixed relative to its start
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DeepScanLimit {
+  deadline: Option<Instant>,
+  max_branch_commits: Option<u32>,
+}
+
+impl DeepScanLimit {
+  pub(crate) fn start(budget: &DetectionBudget) -> Self {
+    Self {
+      deadline: budget.max_duration.map(|duration| Instant::now() + duration),
+      max_branch_commits: budget.max_branch_commits,
+    }
+  }
+
+  /// Whether the deep steps may still run for a branch with `branch_commit_count` commits
+  pub(crate) fn allows(&self, branch_commit_count: u32) -> bool {
+    self.deadline.is_none_or(|deadline| Instant::now() < deadline) && self.max_branch_commits.is_none_or(|max| branch_commit_count <= max)
+  }
+}
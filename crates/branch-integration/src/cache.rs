@@ -1,14 +1,15 @@
 use anyhow::{Context, Result};
 use git_executor::git_command_executor::GitCommandExecutor;
 use serde_json::{Value, json};
-use sync_types::branch_integration::{BranchIntegrationInfo, BranchIntegrationStatus, IntegrationConfidence};
+use sync_types::branch_integration::{BranchIntegrationInfo, BranchIntegrationStatus, IntegrationConfidence, IntegrationEvidence, IntegrationStrategy, MatchedCommit};
 use tracing::{debug, instrument, trace};
 
 // Git notes ref for detection cache - this is the namespace where notes are stored
 pub const NOTES_REF: &str = "refs/notes/branch-deck/detection";
 
-// Current detection cache version
-pub const DETECTION_CACHE_VERSION: u8 = 1;
+// Current detection cache version, entries of other versions are detected again.
+// 2: integration evidence and squash merges matched by tree are no longer of low confidence
+pub const DETECTION_CACHE_VERSION: u8 = 2;
 
 /// Serialize BranchIntegrationInfo to compact JSON for git notes storage
 pub fn serialize_for_cache(info: &BranchIntegrationInfo) -> Result<String> {
@@ -22,7 +23,8 @@ pub fn serialize_for_cache(info: &BranchIntegrationInfo) -> Result<String> {
         "k": "i",
         "c": match confidence {
           IntegrationConfidence::Exact => "e",
-          IntegrationConfidence::High => "h"
+          IntegrationConfidence::High => "h",
+          IntegrationConfidence::Low => "l"
         }
       });
       if *commit_count != 0 {
@@ -63,7 +65,7 @@ pub fn serialize_for_cache(info: &BranchIntegrationInfo) -> Result<String> {
     }
   };
 
-  let cache_entry = if info.summary.is_empty() {
+  let mut cache_entry = if info.summary.is_empty() {
     json!({
       "v": DETECTION_CACHE_VERSION,
       "s": status_json
@@ -75,15 +77,72 @@ pub fn serialize_for_cache(info: &BranchIntegrationInfo) -> Result<String> {
       "sum": info.summary
     })
   };
+  if info.lookback_limited {
+    cache_entry.as_object_mut().unwrap().insert("ll".to_string(), json!(true));
+  }
+  if let Some(evidence) = &info.evidence {
+    cache_entry.as_object_mut().unwrap().insert("e".to_string(), serialize_evidence(evidence));
+  }
 
   Ok(serde_json::to_string(&cache_entry)?)
 }
 
+/// `{"st": "r", "mc": [[id, committed_at, subject], ...]}`
+fn serialize_evidence(evidence: &IntegrationEvidence) -> Value {
+  let strategy = match evidence.strategy {
+    IntegrationStrategy::Merge => "m",
+    IntegrationStrategy::Rebase => "r",
+    IntegrationStrategy::PatchId => "p",
+    IntegrationStrategy::Squash => "s",
+    IntegrationStrategy::FuzzyPatch => "f",
+  };
+  let matched_commits: Vec<Value> = evidence
+    .matched_commits
+    .iter()
+    .map(|commit| json!([commit.id, commit.committed_at, commit.subject]))
+    .collect();
+  json!({"st": strategy, "mc": matched_commits})
+}
+
+fn deserialize_evidence(value: &Value) -> Option<IntegrationEvidence> {
+  let strategy = match value.get("st").and_then(|v| v.as_str())? {
+    "m" => IntegrationStrategy::Merge,
+    "r" => IntegrationStrategy::Rebase,
+    "p" => IntegrationStrategy::PatchId,
+    "s" => IntegrationStrategy::Squash,
+    "f" => IntegrationStrategy::FuzzyPatch,
+    _ => return None,
+  };
+  let matched_commits = value
+    .get("mc")
+    .and_then(|v| v.as_array())
+    .map(|commits| {
+      commits
+        .iter()
+        .filter_map(|commit| {
+          Some(MatchedCommit {
+            id: commit.get(0)?.as_str()?.to_string(),
+            committed_at: commit.get(1)?.as_u64()? as u32,
+            subject: commit.get(2).and_then(|v| v.as_str()).unwrap_or("").to_string(),
+          })
+        })
+        .collect()
+    })
+    .unwrap_or_default();
+  Some(IntegrationEvidence { strategy, matched_commits })
+}
+
 /// Deserialize compact JSON from git notes to BranchIntegrationInfo (with empty name)
 pub fn deserialize_from_cache(json: &str) -> Result<BranchIntegrationInfo> {
   let value: Value = serde_json::from_str(json)?;
+  let version = value.get("v").and_then(|v| v.as_u64());
+  if version != Some(u64::from(DETECTION_CACHE_VERSION)) {
+    return Err(anyhow::anyhow!("Outdated cache version {version:?}"));
+  }
 
   let summary = value.get("sum").and_then(|v| v.as_str()).unwrap_or("").to_string();
+  let lookback_limited = value.get("ll").and_then(|v| v.as_bool()).unwrap_or(false);
+  let evidence = value.get("e").and_then(deserialize_evidence);
 
   let status_value = value.get("s").ok_or_else(|| anyhow::anyhow!("Missing status field"))?;
 
@@ -95,6 +154,7 @@ pub fn deserialize_from_cache(json: &str) -> Result<BranchIntegrationInfo> {
 
       let confidence = match status_value.get("c").and_then(|v| v.as_str()) {
         Some("e") => IntegrationConfidence::Exact,
+        Some("l") => IntegrationConfidence::Low,
         Some("h") | None => IntegrationConfidence::High, // Default to High for backward compatibility
         _ => IntegrationConfidence::High,
       };
@@ -133,6 +193,10 @@ pub fn deserialize_from_cache(json: &str) -> Result<BranchIntegrationInfo> {
     name: String::new(), // Empty name - to be filled by caller
     summary,
     status,
+    lookback_limited,
+    // Truncated results are never cached
+    detection_truncated: false,
+    evidence,
   })
 }
 
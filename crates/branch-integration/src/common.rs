@@ -2,7 +2,7 @@ use super::cache::parse_cached_note;
 use anyhow::Result;
 use git_executor::git_command_executor::GitCommandExecutor;
 use std::collections::{HashMap, HashSet};
-use sync_types::branch_integration::BranchIntegrationInfo;
+use sync_types::branch_integration::{BranchIntegrationInfo, MatchedCommit};
 use tracing::{debug, instrument, warn};
 
 /// Get the committer timestamp of a commit
@@ -18,6 +18,21 @@ pub fn is_ancestor(git: &GitCommandExecutor, repo: &str, ancestor: &str, descend
   git.execute_command(&["merge-base", "--is-ancestor", ancestor, descendant], repo).is_ok()
 }
 
+/// Baseline commits kept as integration evidence, more would only bloat the cache note
+pub const MAX_MATCHED_COMMITS: usize = 20;
+
+/// Pretty format of commits parsed by [`parse_matched_commit`]
+pub const MATCHED_COMMIT_FORMAT: &str = "%H %ct %s";
+
+/// Parse a commit printed with [`MATCHED_COMMIT_FORMAT`]
+pub fn parse_matched_commit(line: &str) -> Option<MatchedCommit> {
+  let mut parts = line.splitn(3, ' ');
+  let id = parts.next()?.to_string();
+  let committed_at = parts.next()?.parse::<u32>().ok()?;
+  let subject = parts.next().unwrap_or_default().to_string();
+  Some(MatchedCommit { id, subject, committed_at })
+}
+
 /// List virtual branches that are not currently active (not in grouped_commits)
 /// Returns only branches that should be checked for integration detection
 #[instrument(skip(git, grouped_commits), fields(repo = %repo, branch_prefix = %branch_prefix), ret)]
@@ -55,6 +70,18 @@ pub fn list_inactive_virtual_branches(
   Ok(inactive_branches)
 }
 
+/// Return true if no branch other than the given virtual branch exists under the prefix.
+/// In that case there is nothing to archive, clean up or check for integration.
+#[instrument(skip(git), fields(repo = %repo, branch_prefix = %branch_prefix), ret)]
+pub fn is_only_prefixed_branch(git: &GitCommandExecutor, repo: &str, branch_prefix: &str, branch_name: &str) -> Result<bool> {
+  let branch_prefix = branch_prefix.trim_end_matches('/');
+  let own_branch = format!("{branch_prefix}/virtual/{branch_name}");
+
+  // Two refs are enough to tell whether anything else exists
+  let lines = git.execute_command_lines(&["for-each-ref", "--count=2", "--format=%(refname:short)", &format!("refs/heads/{branch_prefix}/")], repo)?;
+  Ok(lines.iter().filter(|name| !name.is_empty()).all(|name| name == &own_branch))
+}
+
 /// Structure to hold all branch data from a single git query
 pub struct BranchData {
   pub virtual_commits: HashMap<String, String>,             // virtual branch -> commit
@@ -1,10 +1,14 @@
-use super::{cache::CacheOps, common, merge, rebase, squash, strategy::DetectionStrategy};
+use super::archive::{ArchiveRetentionPolicy, archived_branch_entries, evaluate_archive_retention, load_archive_retention_policy};
+use super::budget::{DeepScanLimit, DetectionBudget, load_detection_budget};
+use super::lookback::{LookbackWindow, is_limited_by_lookback, load_lookback_window, resolve_lookback_boundary};
+use super::{cache::CacheOps, common, fuzzy, merge, rebase, squash, strategy::DetectionStrategy};
 use anyhow::Result;
 use git_executor::git_command_executor::GitCommandExecutor;
 use git_ops::commit_list::Commit;
 use indexmap::IndexMap;
 use std::collections::{HashMap, HashSet};
-use sync_types::branch_integration::{BranchIntegrationInfo, BranchIntegrationStatus};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use sync_types::branch_integration::{BranchIntegrationInfo, BranchIntegrationStatus, IntegrationEvidence};
 use sync_types::{ProgressReporter, SyncEvent};
 use sync_utils::issue_pattern::{find_issue_number, find_issue_range};
 use tokio::task::JoinSet;
@@ -16,8 +20,15 @@ pub struct DetectConfig<'a> {
   pub progress: &'a dyn ProgressReporter,
   pub strategy: DetectionStrategy,
   pub retention_days: u64,
+  /// Used for keys not set in git config
+  pub lookback: LookbackWindow,
+  /// Used for keys not set in git config
+  pub budget: DetectionBudget,
 }
 
+/// Lookback window of the last detection per repository that truncated results, so resuming scans the same baseline history
+static TRUNCATED_DETECTION_LOOKBACK: LazyLock<Mutex<HashMap<String, LookbackWindow>>> = LazyLock::new(Default::default);
+
 /// Parameters for parallel branch processing
 struct BranchProcessingParams<'a> {
   git_executor: &'a GitCommandExecutor,
@@ -28,12 +39,22 @@ struct BranchProcessingParams<'a> {
   cached_notes: &'a HashMap<String, BranchIntegrationInfo>,
   merged_branches: &'a HashSet<String>,
   strategy: DetectionStrategy,
+  lookback_boundary: Option<&'a str>,
+  deep_scan_limit: DeepScanLimit,
   progress: &'a dyn ProgressReporter,
 }
 
+/// Results of [`process_branches_parallel`]
+#[derive(Default)]
+struct ProcessedBranches {
+  caches_to_write: Vec<(String, BranchIntegrationInfo)>,
+  /// Some results were truncated by the detection budget
+  truncated: bool,
+}
+
 /// Process a list of branches in parallel and return collected cache writes
 #[instrument(skip(params), fields(branch_count = params.branches.len()))]
-async fn process_branches_parallel(params: BranchProcessingParams<'_>) -> Result<Vec<(String, BranchIntegrationInfo)>> {
+async fn process_branches_parallel(params: BranchProcessingParams<'_>) -> Result<ProcessedBranches> {
   let BranchProcessingParams {
     git_executor,
     repo_path,
@@ -43,15 +64,17 @@ async fn process_branches_parallel(params: BranchProcessingParams<'_>) -> Result
     cached_notes,
     merged_branches,
     strategy,
+    lookback_boundary,
+    deep_scan_limit,
     progress,
   } = params;
   if branches.is_empty() {
-    return Ok(Vec::new());
+    return Ok(ProcessedBranches::default());
   }
 
   // Process branches in parallel
   let mut set: JoinSet<std::result::Result<DetectionResult, anyhow::Error>> = JoinSet::new();
-  let mut all_caches_to_write = Vec::new();
+  let mut processed = ProcessedBranches::default();
 
   for archived_branch in branches {
     let repo = repo_path.to_string();
@@ -87,6 +110,8 @@ async fn process_branches_parallel(params: BranchProcessingParams<'_>) -> Result
       strategy: strategy_clone.clone(),
       repo: repo.clone(),
       baseline: baseline.clone(),
+      lookback_boundary: lookback_boundary.map(str::to_string),
+      deep_scan_limit,
     };
     set.spawn(run_branch_task(inputs, git_executor.clone()));
   }
@@ -95,10 +120,15 @@ async fn process_branches_parallel(params: BranchProcessingParams<'_>) -> Result
   while let Some(res) = set.join_next().await {
     match res {
       Ok(Ok(result)) => {
+        // A truncated result is not cached, so the next detection tries the deep steps again
+        let truncated = result.info.detection_truncated;
         // Send unified event immediately as each branch completes detection
         progress.send(SyncEvent::BranchIntegrationDetected { info: result.info })?;
-        // Collect cache to write later (always present for fresh detection)
-        all_caches_to_write.push(result.cache_to_write);
+        if truncated {
+          processed.truncated = true;
+        } else {
+          processed.caches_to_write.push(result.cache_to_write);
+        }
       }
       Ok(Err(e)) => {
         warn!(error = %e, "Task returned error during integration detection");
@@ -109,7 +139,7 @@ async fn process_branches_parallel(params: BranchProcessingParams<'_>) -> Result
     }
   }
 
-  Ok(all_caches_to_write)
+  Ok(processed)
 }
 
 // ===== Helpers extracted to simplify process_branches_parallel =====
@@ -122,6 +152,8 @@ struct BranchWorkInputs {
   strategy: DetectionStrategy,
   repo: String,
   baseline: String,
+  lookback_boundary: Option<String>,
+  deep_scan_limit: DeepScanLimit,
 }
 
 fn compute_summary_blocking(git: &GitCommandExecutor, repo: &str, branch_tip: &str, should_compute: bool) -> String {
@@ -159,9 +191,22 @@ async fn run_branch_task(inputs: BranchWorkInputs, git: GitCommandExecutor) -> s
   let branch_for_det = inputs.archived_branch.clone();
   let baseline_for_det = inputs.baseline.clone();
   let strategy_for_det = inputs.strategy.clone();
+  let boundary_for_det = inputs.lookback_boundary.clone();
   let is_merged = inputs.is_merged;
-
-  let det_handle = tokio::task::spawn_blocking(move || perform_fresh_detection(&git_for_det, &repo_for_det, &branch_for_det, &baseline_for_det, is_merged, strategy_for_det));
+  let deep_scan_limit = inputs.deep_scan_limit;
+
+  let det_handle = tokio::task::spawn_blocking(move || {
+    perform_fresh_detection(
+      &git_for_det,
+      &repo_for_det,
+      &branch_for_det,
+      &baseline_for_det,
+      is_merged,
+      strategy_for_det,
+      boundary_for_det.as_deref(),
+      deep_scan_limit,
+    )
+  });
 
   let sum_handle = if should_compute_summary {
     let git_for_sum = git.clone();
@@ -174,7 +219,7 @@ async fn run_branch_task(inputs: BranchWorkInputs, git: GitCommandExecutor) -> s
     None
   };
 
-  let status = det_handle.await.map_err(|e| anyhow::anyhow!("join error in detection: {}", e))??;
+  let detection = det_handle.await.map_err(|e| anyhow::anyhow!("join error in detection: {}", e))??;
 
   let summary = if let Some(h) = sum_handle {
     h.await.map_err(|e| anyhow::anyhow!("join error in summary: {}", e))?
@@ -182,7 +227,7 @@ async fn run_branch_task(inputs: BranchWorkInputs, git: GitCommandExecutor) -> s
     String::new()
   };
 
-  Ok(create_detection_result(status, inputs.archived_branch.clone(), inputs.branch_tip.clone(), summary))
+  Ok(create_detection_result(detection, inputs.archived_branch.clone(), inputs.branch_tip.clone(), summary))
 }
 
 /// Write all collected caches sequentially to avoid race conditions
@@ -250,42 +295,39 @@ pub async fn detect_integrated_branches(git_executor: &GitCommandExecutor, repo_
   // Step 0: Get ALL branch data including parsed cached notes in a single git call
   let branch_data = common::get_all_branch_data(git_executor, repo_path, branch_prefix)?;
 
-  // Step 0.5: Clean up old archived branches, but only those fully integrated
-  // Compute cutoff date based on retention
-  let cutoff_date = chrono::Utc::now() - chrono::Duration::days(config.retention_days as i64);
-  let archive_prefix = format!("{branch_prefix}/archived/");
-
-  // Build list of archived branches to delete: older than cutoff AND cache status Integrated
-  let mut branches_to_delete: Vec<String> = Vec::new();
-  for branch in &branch_data.archived_all {
-    // Extract date from branch path: <prefix>/archived/YYYY-MM-DD/...
-    if let Some(date_part) = branch.strip_prefix(&archive_prefix).and_then(|p| p.split('/').next())
-      && let Ok(branch_date) = chrono::NaiveDate::parse_from_str(date_part, "%Y-%m-%d")
-    {
-      let branch_datetime = branch_date.and_hms_opt(0, 0, 0).unwrap().and_local_timezone(chrono::Utc).single().unwrap();
-      if branch_datetime < cutoff_date {
-        // Check detection cache status on the branch tip commit
-        if let Some(tip_commit) = branch_data.all_branch_commits.get(branch)
-          && let Some(cache) = branch_data.branch_notes.get(tip_commit)
-          && matches!(cache.status, BranchIntegrationStatus::Integrated { .. })
-        {
-          branches_to_delete.push(branch.clone());
-        }
-      }
-    }
-  }
+  // Step 0.5: Clean up old archived branches according to the retention policy
+  // (by default only fully integrated ones older than the retention period)
+  let default_policy = ArchiveRetentionPolicy::with_max_age(config.retention_days);
+  let policy = load_archive_retention_policy(git_executor, repo_path, default_policy.clone()).unwrap_or_else(|e| {
+    warn!(error = %e, "Invalid archive retention config, using defaults");
+    default_policy
+  });
+  let entries = archived_branch_entries(&branch_data, branch_prefix);
+  let branches_to_delete: Vec<String> = evaluate_archive_retention(&entries, &policy, chrono::Utc::now().date_naive())
+    .into_iter()
+    .map(|candidate| candidate.branch_name)
+    .collect();
 
   if !branches_to_delete.is_empty() {
     let deleted = super::archive::batch_delete_archived_branches(git_executor, repo_path, &branches_to_delete)?;
     if deleted > 0 {
       info!(
         deleted_count = deleted,
-        retention_days = config.retention_days,
+        policy = ?policy,
         "Cleaned up old fully integrated archived branches"
       );
     }
   }
 
+  // Step 1: Limit how far back in baseline history the detectors look, and how long the deep steps may take
+  let lookback = load_lookback(git_executor, repo_path, config.lookback);
+  let lookback_boundary = resolve_boundary(git_executor, repo_path, baseline_branch, &lookback);
+  let budget = load_detection_budget(git_executor, repo_path, config.budget).unwrap_or_else(|e| {
+    warn!(error = %e, "Invalid integration detection budget config, using defaults");
+    config.budget
+  });
+  let deep_scan_limit = DeepScanLimit::start(&budget);
+
   // Step 2: Archive inactive branches using the pre-fetched data
   let newly_archived = archive_inactive_branches(git_executor, repo_path, branch_prefix, &branch_data, config.grouped_commits).await?;
 
@@ -316,7 +358,7 @@ pub async fn detect_integrated_branches(git_executor: &GitCommandExecutor, repo_
   if config.strategy == DetectionStrategy::Rebase {
     // Use the shared helper with empty merged_branches (rebase doesn't need merge detection)
     let empty_merged_branches = HashSet::new();
-    let all_caches_to_write = process_branches_parallel(BranchProcessingParams {
+    let processed = process_branches_parallel(BranchProcessingParams {
       git_executor,
       repo_path,
       baseline_branch,
@@ -325,30 +367,23 @@ pub async fn detect_integrated_branches(git_executor: &GitCommandExecutor, repo_
       cached_notes: &branch_data.branch_notes,
       merged_branches: &empty_merged_branches,
       strategy: DetectionStrategy::Rebase,
+      lookback_boundary: lookback_boundary.as_deref(),
+      deep_scan_limit,
       progress: config.progress,
     })
     .await?;
 
+    remember_truncated_lookback(repo_path, lookback, processed.truncated);
     // Write all caches sequentially to avoid race conditions
-    write_caches_sequentially(git_executor, repo_path, all_caches_to_write)?;
+    write_caches_sequentially(git_executor, repo_path, processed.caches_to_write)?;
 
     return Ok(());
   }
 
-  // Get list of branches merged into baseline using git's native detection
-  // This is only useful for merge-based workflows, not rebase workflows
-  // Note: Use archived branch names for the check
-  let merged_branches: HashSet<String> = if config.strategy == DetectionStrategy::Merge || config.strategy == DetectionStrategy::All {
-    let merged_branches_list = git_executor.execute_command_lines(&["branch", "--merged", baseline_branch, "--format=%(refname:short)"], repo_path)?;
-    let merged_branches: HashSet<String> = merged_branches_list.into_iter().collect();
-    debug!(baseline = %baseline_branch, count = merged_branches.len(), "Found branches merged into baseline");
-    merged_branches
-  } else {
-    HashSet::new()
-  };
+  let merged_branches = find_merged_branches(git_executor, repo_path, baseline_branch, &config.strategy)?;
 
   // Use the shared helper for processing branches
-  let all_caches_to_write = process_branches_parallel(BranchProcessingParams {
+  let processed = process_branches_parallel(BranchProcessingParams {
     git_executor,
     repo_path,
     baseline_branch,
@@ -357,16 +392,118 @@ pub async fn detect_integrated_branches(git_executor: &GitCommandExecutor, repo_
     cached_notes: &branch_data.branch_notes,
     merged_branches: &merged_branches,
     strategy: config.strategy,
+    lookback_boundary: lookback_boundary.as_deref(),
+    deep_scan_limit,
     progress: config.progress,
   })
   .await?;
 
+  remember_truncated_lookback(repo_path, lookback, processed.truncated);
   // Write all caches sequentially to avoid race conditions
-  write_caches_sequentially(git_executor, repo_path, all_caches_to_write)?;
+  write_caches_sequentially(git_executor, repo_path, processed.caches_to_write)?;
 
   Ok(())
 }
 
+/// Keep the lookback window of a detection that truncated results for [`resume_integration_detection`], forget it otherwise
+fn remember_truncated_lookback(repo_path: &str, lookback: LookbackWindow, truncated: bool) {
+  let mut windows = TRUNCATED_DETECTION_LOOKBACK.lock().unwrap_or_else(PoisonError::into_inner);
+  if truncated {
+    windows.insert(repo_path.to_string(), lookback);
+  } else {
+    windows.remove(repo_path);
+  }
+}
+
+/// Load the lookback window from git config, falling back to `default` for unset keys and invalid values
+fn load_lookback(git_executor: &GitCommandExecutor, repo_path: &str, default: LookbackWindow) -> LookbackWindow {
+  load_lookback_window(git_executor, repo_path, default).unwrap_or_else(|e| {
+    warn!(error = %e, "Invalid integration lookback config, using defaults");
+    default
+  })
+}
+
+/// Resolve the lookback boundary, falling back to no boundary on errors
+fn resolve_boundary(git_executor: &GitCommandExecutor, repo_path: &str, baseline_branch: &str, lookback: &LookbackWindow) -> Option<String> {
+  resolve_lookback_boundary(git_executor, repo_path, baseline_branch, lookback).unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to resolve integration lookback boundary, scanning the whole baseline history");
+    None
+  })
+}
+
+/// Get list of branches merged into baseline using git's native detection.
+/// This is only useful for merge-based workflows, not rebase workflows.
+fn find_merged_branches(git_executor: &GitCommandExecutor, repo_path: &str, baseline_branch: &str, strategy: &DetectionStrategy) -> Result<HashSet<String>> {
+  if *strategy != DetectionStrategy::Merge && *strategy != DetectionStrategy::All {
+    return Ok(HashSet::new());
+  }
+  // Note: Use archived branch names for the check
+  let merged_branches_list = git_executor.execute_command_lines(&["branch", "--merged", baseline_branch, "--format=%(refname:short)"], repo_path)?;
+  let merged_branches: HashSet<String> = merged_branches_list.into_iter().collect();
+  debug!(baseline = %baseline_branch, count = merged_branches.len(), "Found branches merged into baseline");
+  Ok(merged_branches)
+}
+
+/// Run the full detection for the given archived branches ignoring cached results, e.g. to finish branches whose detection
+/// was truncated. The branch commit limit of the budget is lifted, the time limit from git config still applies. The lookback
+/// window of the detection that truncated the results is reused, `default_lookback` with git config applies if there was none.
+/// Results are sent as [`SyncEvent::BranchIntegrationDetected`] and cached.
+#[instrument(skip(git_executor, branch_names, progress), fields(branch_count = branch_names.len(), strategy = ?strategy))]
+pub async fn resume_integration_detection(
+  git_executor: &GitCommandExecutor,
+  repo_path: &str,
+  baseline_branch: &str,
+  branch_names: Vec<String>,
+  strategy: DetectionStrategy,
+  default_lookback: LookbackWindow,
+  progress: &dyn ProgressReporter,
+) -> Result<()> {
+  if branch_names.is_empty() {
+    return Ok(());
+  }
+
+  let ref_names: Vec<String> = branch_names.iter().map(|name| format!("refs/heads/{name}")).collect();
+  let mut args = vec!["for-each-ref", "--format=%(refname:short) %(objectname)"];
+  args.extend(ref_names.iter().map(String::as_str));
+  let requested: HashSet<&String> = branch_names.iter().collect();
+  let branch_commits: HashMap<String, String> = git_executor
+    .execute_command_lines(&args, repo_path)?
+    .into_iter()
+    .filter_map(|line| line.split_once(' ').map(|(name, tip)| (name.to_string(), tip.to_string())))
+    // Patterns also match refs nested under the requested names
+    .filter(|(name, _)| requested.contains(name))
+    .collect();
+
+  let saved_lookback = TRUNCATED_DETECTION_LOOKBACK.lock().unwrap_or_else(PoisonError::into_inner).get(repo_path).copied();
+  let lookback = saved_lookback.unwrap_or_else(|| load_lookback(git_executor, repo_path, default_lookback));
+  let lookback_boundary = resolve_boundary(git_executor, repo_path, baseline_branch, &lookback);
+  let budget = load_detection_budget(git_executor, repo_path, DetectionBudget::default()).unwrap_or_else(|e| {
+    warn!(error = %e, "Invalid integration detection budget config, using defaults");
+    DetectionBudget::default()
+  });
+  let budget = DetectionBudget {
+    max_branch_commits: None,
+    ..budget
+  };
+  let merged_branches = find_merged_branches(git_executor, repo_path, baseline_branch, &strategy)?;
+  let processed = process_branches_parallel(BranchProcessingParams {
+    git_executor,
+    repo_path,
+    baseline_branch,
+    branches: branch_names,
+    branch_commits: &branch_commits,
+    cached_notes: &HashMap::new(),
+    merged_branches: &merged_branches,
+    strategy,
+    lookback_boundary: lookback_boundary.as_deref(),
+    deep_scan_limit: DeepScanLimit::start(&budget),
+    progress,
+  })
+  .await?;
+
+  write_caches_sequentially(git_executor, repo_path, processed.caches_to_write)
+}
+
 /// Result of single branch integration detection
 #[derive(Debug)]
 struct DetectionResult {
@@ -384,17 +521,30 @@ impl DetectionResult {
 }
 
 /// Create detection result based on integration/not-integrated info and branch tip
-fn create_detection_result(status: BranchIntegrationStatus, branch_name: String, branch_tip: String, summary: String) -> DetectionResult {
+fn create_detection_result(detection: FreshDetection, branch_name: String, branch_tip: String, summary: String) -> DetectionResult {
   let info = BranchIntegrationInfo {
     name: branch_name,
-    summary: summary.clone(),
-    status: status.clone(),
+    summary,
+    status: detection.status,
+    lookback_limited: detection.lookback_limited,
+    detection_truncated: detection.truncated,
+    evidence: detection.evidence,
   };
   DetectionResult::new(info, branch_tip)
 }
 
+/// Outcome of detection for a branch without a cached result
+#[derive(Debug)]
+struct FreshDetection {
+  status: BranchIntegrationStatus,
+  evidence: Option<IntegrationEvidence>,
+  /// The lookback window limited the analysis of a not integrated branch
+  lookback_limited: bool,
+  /// Deep steps were skipped because of the detection budget
+  truncated: bool,
+}
+
 /// Perform fresh detection using the specified strategy
-/// Returns (integrated_info, not_integrated_info) based on detection results
 fn perform_fresh_detection(
   git: &GitCommandExecutor,
   repo: &str,
@@ -402,26 +552,57 @@ fn perform_fresh_detection(
   baseline: &str,
   is_merged: bool,
   strategy: DetectionStrategy,
-) -> Result<BranchIntegrationStatus> {
+  lookback_boundary: Option<&str>,
+  deep_scan_limit: DeepScanLimit,
+) -> Result<FreshDetection> {
   // 1) Merge detection first if enabled and branch is known merged
   if (strategy == DetectionStrategy::Merge || strategy == DetectionStrategy::All)
     && is_merged
-    && let Some(merge_integrated) = merge::detect_merge_status(git, repo, branch_name, baseline, is_merged)?
+    && let Some((status, evidence)) = merge::detect_merge_status(git, repo, branch_name, baseline, is_merged)?
   {
-    return Ok(merge_integrated);
+    return Ok(FreshDetection {
+      status,
+      evidence: Some(evidence),
+      lookback_limited: false,
+      truncated: false,
+    });
   }
 
   // 2) Rebase/cherry-pick detection via marker scan
-  let (total_right, orphaned_right, integrated_right) = rebase::scan_right_side_marks(git, repo, baseline, branch_name)?;
-  let mut status = rebase::detect_rebase_status_with_marks(git, repo, branch_name, baseline, total_right, orphaned_right, integrated_right)?;
+  let (total_right, orphaned_right, integrated_right) = rebase::scan_right_side_marks(git, repo, baseline, branch_name, lookback_boundary)?;
+  let (mut status, mut evidence) = rebase::detect_rebase_status_with_marks(git, repo, branch_name, baseline, total_right, orphaned_right, integrated_right, lookback_boundary)?;
+
+  // The deep steps scale with the branch size and are skipped once the budget is exhausted
+  let mut truncated = false;
+
+  // 3) Orphaned commits may be cherry-picks whose patch-id changed with the context, part of the cherry-pick detection of every strategy
+  if matches!(status, BranchIntegrationStatus::NotIntegrated { orphaned_count: 1.., .. }) {
+    if deep_scan_limit.allows(total_right) {
+      (status, evidence) = fuzzy::refine_with_fuzzy_matches(git, repo, branch_name, baseline, status, evidence, lookback_boundary)?;
+    } else {
+      truncated = true;
+    }
+  }
 
-  // 3) Squash detection fallback for branches with no integrated commits
+  // 4) Squash detection fallback for branches with no integrated commits
   if matches!(status, BranchIntegrationStatus::NotIntegrated { integrated_count: 0, .. }) && (strategy == DetectionStrategy::Squash || strategy == DetectionStrategy::All) {
-    let right_count = orphaned_right as usize;
-    if let Some(squash_integrated) = squash::detect_squash_status(git, repo, branch_name, baseline, right_count)? {
-      status = squash_integrated;
+    if !truncated && deep_scan_limit.allows(total_right) {
+      let right_count = orphaned_right as usize;
+      if let Some((squash_status, squash_evidence)) = squash::detect_squash_status(git, repo, branch_name, baseline, right_count, lookback_boundary)? {
+        status = squash_status;
+        evidence = Some(squash_evidence);
+      }
+    } else {
+      truncated = true;
     }
   }
 
-  Ok(status)
+  // An integrated branch is found regardless of the older history
+  let lookback_limited = !matches!(status, BranchIntegrationStatus::Integrated { .. }) && is_limited_by_lookback(git, repo, branch_name, lookback_boundary)?;
+  Ok(FreshDetection {
+    status,
+    evidence,
+    lookback_limited,
+    truncated,
+  })
 }
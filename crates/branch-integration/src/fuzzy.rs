@@ -0,0 +1,265 @@
+use crate::common::{MATCHED_COMMIT_FORMAT, MAX_MATCHED_COMMITS, parse_matched_commit};
+use crate::lookback::exclude_boundary_arg;
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use std::collections::{BTreeMap, BTreeSet};
+use sync_types::branch_integration::{BranchIntegrationStatus, IntegrationConfidence, IntegrationEvidence, IntegrationStrategy, MatchedCommit};
+use tracing::{debug, info, instrument};
+
+/// Minimum share of changed lines two commits have in common to be considered the same change
+pub const FUZZY_MATCH_THRESHOLD: f64 = 0.9;
+
+/// Changed lines of a commit per file, with whitespace removed and sorted.
+/// Hunk positions and context lines are not part of it, so a cherry-pick applied at other line offsets
+/// or next to changed context gets the same fingerprint, unlike its patch-id.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct PatchFingerprint {
+  files: BTreeMap<String, Vec<String>>,
+}
+
+impl PatchFingerprint {
+  fn is_empty(&self) -> bool {
+    self.files.values().all(Vec::is_empty)
+  }
+
+  fn line_count(&self) -> usize {
+    self.files.values().map(Vec::len).sum()
+  }
+
+  /// Dice coefficient of the changed lines, 0 if other files are changed
+  fn similarity(&self, other: &Self) -> f64 {
+    if !self.files.keys().eq(other.files.keys()) {
+      return 0.0;
+    }
+    let total = self.line_count() + other.line_count();
+    if total == 0 {
+      return 0.0;
+    }
+    let common: usize = self.files.values().zip(other.files.values()).map(|(a, b)| count_common_sorted(a, b)).sum();
+    (2 * common) as f64 / total as f64
+  }
+}
+
+/// Size of the multiset intersection of two sorted lists
+fn count_common_sorted(a: &[String], b: &[String]) -> usize {
+  let (mut i, mut j, mut common) = (0, 0, 0);
+  while i < a.len() && j < b.len() {
+    match a[i].cmp(&b[j]) {
+      std::cmp::Ordering::Less => i += 1,
+      std::cmp::Ordering::Greater => j += 1,
+      std::cmp::Ordering::Equal => {
+        common += 1;
+        i += 1;
+        j += 1;
+      }
+    }
+  }
+  common
+}
+
+#[derive(Debug)]
+struct FingerprintedCommit {
+  commit: MatchedCommit,
+  fingerprint: PatchFingerprint,
+}
+
+/// Parse `git log -p -U0` output of commits printed with a NUL before [`MATCHED_COMMIT_FORMAT`]
+fn parse_fingerprinted_commits(output: &str) -> Vec<FingerprintedCommit> {
+  output
+    .split('\0')
+    .filter_map(|chunk| {
+      let (header, diff) = chunk.split_once('\n').unwrap_or((chunk, ""));
+      let commit = parse_matched_commit(header.trim_end())?;
+      Some(FingerprintedCommit {
+        commit,
+        fingerprint: parse_fingerprint(diff),
+      })
+    })
+    .collect()
+}
+
+fn parse_fingerprint(diff: &str) -> PatchFingerprint {
+  let mut fingerprint = PatchFingerprint::default();
+  let mut path: Option<&str> = None;
+  let mut in_hunk = false;
+  for line in diff.lines() {
+    if line.starts_with("diff --git ") {
+      path = None;
+      in_hunk = false;
+    } else if line.starts_with("@@") {
+      in_hunk = true;
+    } else if !in_hunk {
+      // File header, the new path wins unless the file was deleted
+      if let Some(old_path) = line.strip_prefix("--- a/") {
+        path = Some(old_path);
+      } else if let Some(new_path) = line.strip_prefix("+++ b/") {
+        path = Some(new_path);
+      }
+    } else if let Some(path) = path
+      && let Some(kind) = line.chars().next().filter(|kind| *kind == '+' || *kind == '-')
+    {
+      let normalized: String = line[1..].chars().filter(|c| !c.is_whitespace()).collect();
+      // Whitespace-only changes carry nothing to match on
+      let lines = fingerprint.files.entry(path.to_string()).or_default();
+      if !normalized.is_empty() {
+        lines.push(format!("{kind}{normalized}"));
+      }
+    }
+  }
+  for lines in fingerprint.files.values_mut() {
+    lines.sort_unstable();
+  }
+  fingerprint
+}
+
+/// Commits of one side of `baseline...branch` with their fingerprints, newest first
+fn list_fingerprinted_commits(git: &GitCommandExecutor, repo: &str, side: &str, extra_args: &[&str], range: &str, paths: &[String]) -> Result<Vec<FingerprintedCommit>> {
+  let format = format!("--format=%x00{MATCHED_COMMIT_FORMAT}");
+  let mut args = vec![
+    "-c",
+    "core.quotePath=false",
+    "log",
+    "--no-merges",
+    side,
+    "-p",
+    "-U0",
+    "--no-color",
+    "--no-ext-diff",
+    "--no-renames",
+    format.as_str(),
+  ];
+  args.extend(extra_args);
+  args.push(range);
+  if !paths.is_empty() {
+    args.push("--");
+    args.extend(paths.iter().map(String::as_str));
+  }
+  let output = git.execute_command(&args, repo)?;
+  Ok(parse_fingerprinted_commits(&output))
+}
+
+/// Baseline commits matched to orphaned branch commits, one baseline commit per branch commit
+#[derive(Debug)]
+struct FuzzyMatches {
+  matched_commits: Vec<MatchedCommit>,
+  /// Every match has the same changed lines, only context, line offsets or whitespace differ
+  all_exact: bool,
+}
+
+/// Match branch commits without a patch-equivalent baseline commit to baseline commits with (almost) the same changed lines
+#[instrument(skip(git))]
+fn find_fuzzy_matches(git: &GitCommandExecutor, repo: &str, branch_name: &str, baseline: &str, boundary: Option<&str>) -> Result<FuzzyMatches> {
+  let range = format!("{baseline}...{branch_name}");
+  let exclude = exclude_boundary_arg(boundary);
+  let mut orphaned_args = vec!["--cherry-pick"];
+  orphaned_args.extend(exclude.as_deref());
+  let orphaned: Vec<FingerprintedCommit> = list_fingerprinted_commits(git, repo, "--right-only", &orphaned_args, &range, &[])?
+    .into_iter()
+    .filter(|commit| !commit.fingerprint.is_empty())
+    .collect();
+  if orphaned.is_empty() {
+    return Ok(FuzzyMatches {
+      matched_commits: Vec::new(),
+      all_exact: true,
+    });
+  }
+
+  // Only baseline commits touching the same files can match, `--full-diff` keeps their other files in the fingerprint
+  let paths: Vec<String> = orphaned
+    .iter()
+    .flat_map(|commit| commit.fingerprint.files.keys().cloned())
+    .collect::<BTreeSet<_>>()
+    .into_iter()
+    .collect();
+  let mut extra_args = vec!["--full-diff"];
+  extra_args.extend(exclude.as_deref());
+  let candidates = list_fingerprinted_commits(git, repo, "--left-only", &extra_args, &range, &paths)?;
+  debug!(orphaned = orphaned.len(), candidates = candidates.len(), "Matching orphaned commits by fingerprint");
+
+  let mut used = vec![false; candidates.len()];
+  let mut matched_commits = Vec::new();
+  let mut all_exact = true;
+  for commit in &orphaned {
+    let best = candidates
+      .iter()
+      .enumerate()
+      .filter(|(index, _)| !used[*index])
+      .map(|(index, candidate)| (index, commit.fingerprint.similarity(&candidate.fingerprint)))
+      .filter(|(_, similarity)| *similarity >= FUZZY_MATCH_THRESHOLD)
+      // The newest one wins a tie
+      .max_by(|(a_index, a), (b_index, b)| a.total_cmp(b).then(b_index.cmp(a_index)));
+    if let Some((index, similarity)) = best {
+      used[index] = true;
+      all_exact &= commit.fingerprint == candidates[index].fingerprint;
+      debug!(commit = %commit.commit.id, matched = %candidates[index].commit.id, similarity, "Matched orphaned commit");
+      matched_commits.push(candidates[index].commit.clone());
+    }
+  }
+  Ok(FuzzyMatches { matched_commits, all_exact })
+}
+
+/// Recheck the orphaned commits of a not integrated branch, some of them may have been cherry-picked with drifted context.
+/// Returns the status unchanged if none of them match.
+pub fn refine_with_fuzzy_matches(
+  git: &GitCommandExecutor,
+  repo: &str,
+  branch_name: &str,
+  baseline: &str,
+  status: BranchIntegrationStatus,
+  evidence: Option<IntegrationEvidence>,
+  boundary: Option<&str>,
+) -> Result<(BranchIntegrationStatus, Option<IntegrationEvidence>)> {
+  let BranchIntegrationStatus::NotIntegrated {
+    total_commit_count,
+    integrated_count,
+    orphaned_count,
+    ..
+  } = status
+  else {
+    return Ok((status, evidence));
+  };
+  if orphaned_count == 0 {
+    return Ok((status, evidence));
+  }
+
+  let fuzzy = find_fuzzy_matches(git, repo, branch_name, baseline, boundary)?;
+  if fuzzy.matched_commits.is_empty() {
+    return Ok((status, evidence));
+  }
+
+  let fuzzy_count = (fuzzy.matched_commits.len() as u32).min(orphaned_count);
+  let mut matched_commits = evidence.map(|evidence| evidence.matched_commits).unwrap_or_default();
+  matched_commits.extend(fuzzy.matched_commits);
+  matched_commits.sort_by(|a, b| b.committed_at.cmp(&a.committed_at));
+  matched_commits.dedup_by(|a, b| a.id == b.id);
+  matched_commits.truncate(MAX_MATCHED_COMMITS);
+  let integrated_at = matched_commits.first().map(|commit| commit.committed_at);
+  let evidence = Some(IntegrationEvidence {
+    strategy: IntegrationStrategy::FuzzyPatch,
+    matched_commits,
+  });
+
+  if fuzzy_count == orphaned_count {
+    info!(name = %branch_name, method = "fuzzy-patch", exact = fuzzy.all_exact, "Branch fully integrated");
+    let confidence = if fuzzy.all_exact { IntegrationConfidence::High } else { IntegrationConfidence::Low };
+    return Ok((
+      BranchIntegrationStatus::Integrated {
+        integrated_at,
+        confidence,
+        commit_count: total_commit_count,
+      },
+      evidence,
+    ));
+  }
+
+  info!(name = %branch_name, matched = fuzzy_count, "Fuzzy matching found integrated commits");
+  Ok((
+    BranchIntegrationStatus::NotIntegrated {
+      total_commit_count,
+      integrated_count: integrated_count + fuzzy_count,
+      orphaned_count: orphaned_count - fuzzy_count,
+      integrated_at,
+    },
+    evidence,
+  ))
+}
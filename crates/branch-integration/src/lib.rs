@@ -1,7 +1,10 @@
 pub mod archive;
+pub mod budget;
 pub mod cache;
 pub mod common;
 pub mod detector;
+pub mod fuzzy;
+pub mod lookback;
 pub mod merge;
 pub mod rebase;
 pub mod squash;
@@ -0,0 +1,112 @@
+use crate::common::is_ancestor;
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::git_config::get_config_value;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+/// Git config key limiting the number of baseline commits scanned for integrated commits (0 disables the limit)
+pub const LOOKBACK_COMMITS_CONFIG_KEY: &str = "branchdeck.integrationLookbackCommits";
+/// Git config key limiting the age in days of baseline commits scanned for integrated commits (0 disables the limit)
+pub const LOOKBACK_DAYS_CONFIG_KEY: &str = "branchdeck.integrationLookbackDays";
+
+pub const DEFAULT_LOOKBACK_COMMITS: u32 = 10_000;
+pub const DEFAULT_LOOKBACK_DAYS: u32 = 365;
+
+/// How far back in baseline history the rebase and squash detectors look for integrated commits.
+/// Commits are counted along the first-parent chain of the baseline; the tighter rule wins when both are set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct LookbackWindow {
+  /// Scan at most this many baseline commits (`None` disables the rule)
+  pub max_commits: Option<u32>,
+  /// Skip baseline commits older than this many days (`None` disables the rule)
+  pub max_age_days: Option<u32>,
+}
+
+impl LookbackWindow {
+  /// Scan the whole baseline history
+  pub fn unlimited() -> Self {
+    Self {
+      max_commits: None,
+      max_age_days: None,
+    }
+  }
+}
+
+impl Default for LookbackWindow {
+  fn default() -> Self {
+    Self {
+      max_commits: Some(DEFAULT_LOOKBACK_COMMITS),
+      max_age_days: Some(DEFAULT_LOOKBACK_DAYS),
+    }
+  }
+}
+
+/// Load the lookback window from git config, falling back to `default` for unset keys
+#[instrument(skip(git_executor))]
+pub fn load_lookback_window(git_executor: &GitCommandExecutor, repo_path: &str, default: LookbackWindow) -> Result<LookbackWindow> {
+  // 0 disables a rule
+  let parse_limit = |key: &str, fallback: Option<u32>| -> Result<Option<u32>> {
+    match get_config_value(git_executor, repo_path, key)? {
+      Some(value) => {
+        let value: u32 = value.parse().map_err(|e| anyhow::anyhow!("Invalid value of {key}: {e}"))?;
+        Ok(if value == 0 { None } else { Some(value) })
+      }
+      None => Ok(fallback),
+    }
+  };
+
+  Ok(LookbackWindow {
+    max_commits: parse_limit(LOOKBACK_COMMITS_CONFIG_KEY, default.max_commits)?,
+    max_age_days: parse_limit(LOOKBACK_DAYS_CONFIG_KEY, default.max_age_days)?,
+  })
+}
+
+/// Newest baseline commit outside the window. It and its ancestors are excluded from the scans (`^<boundary>`).
+/// Returns `None` if the window covers the whole baseline history.
+#[instrument(skip(git_executor))]
+pub fn resolve_lookback_boundary(git_executor: &GitCommandExecutor, repo_path: &str, baseline: &str, window: &LookbackWindow) -> Result<Option<String>> {
+  let first_outside = |limit_arg: String| -> Result<Option<String>> {
+    let commit = git_executor.execute_command(&["rev-list", "--first-parent", "-n", "1", &limit_arg, baseline], repo_path)?;
+    Ok(if commit.is_empty() { None } else { Some(commit) })
+  };
+
+  let by_count = match window.max_commits {
+    Some(max_commits) => first_outside(format!("--skip={max_commits}"))?,
+    None => None,
+  };
+  let by_age = match window.max_age_days {
+    Some(days) => first_outside(format!("--before={days}.days.ago"))?,
+    None => None,
+  };
+
+  // Both boundaries are on the first-parent chain, the newer one gives the smaller window
+  let boundary = match (by_count, by_age) {
+    (Some(by_count), Some(by_age)) => {
+      if is_ancestor(git_executor, repo_path, &by_count, &by_age) {
+        Some(by_age)
+      } else {
+        Some(by_count)
+      }
+    }
+    (by_count, by_age) => by_count.or(by_age),
+  };
+  debug!(boundary = ?boundary, "Resolved integration lookback boundary");
+  Ok(boundary)
+}
+
+/// Whether the window hid baseline history the branch doesn't contain, i.e. commits that could have integrated it
+pub fn is_limited_by_lookback(git_executor: &GitCommandExecutor, repo_path: &str, branch_name: &str, boundary: Option<&str>) -> Result<bool> {
+  let Some(boundary) = boundary else {
+    return Ok(false);
+  };
+  let hidden = git_executor.execute_command(&["rev-list", "-n", "1", boundary, &format!("^{branch_name}")], repo_path)?;
+  Ok(!hidden.is_empty())
+}
+
+/// Argument appended to a rev-list/log range to exclude history outside the window
+pub fn exclude_boundary_arg(boundary: Option<&str>) -> Option<String> {
+  boundary.map(|boundary| format!("^{boundary}"))
+}
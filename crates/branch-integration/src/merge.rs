@@ -1,27 +1,35 @@
+use crate::common::{MATCHED_COMMIT_FORMAT, parse_matched_commit};
 use anyhow::Result;
 use git_executor::git_command_executor::GitCommandExecutor;
-use sync_types::branch_integration::{BranchIntegrationStatus, IntegrationConfidence};
+use sync_types::branch_integration::{BranchIntegrationStatus, IntegrationConfidence, IntegrationEvidence, IntegrationStrategy, MatchedCommit};
 use tracing::info;
 
 /// Find the merge commit that integrated a branch into baseline
-/// Returns the commit hash and timestamp of the merge commit that brought this branch in
-pub fn find_integration_commit(git_executor: &GitCommandExecutor, repo_path: &str, branch_name: &str, baseline_branch: &str) -> Option<(String, u32)> {
+/// Returns the merge commit that brought this branch in, `None` for a fast-forward merge
+pub fn find_integration_commit(git_executor: &GitCommandExecutor, repo_path: &str, branch_name: &str, baseline_branch: &str) -> Option<MatchedCommit> {
   let lines = git_executor
     .execute_command_lines(
-      &["log", "--merges", "--ancestry-path", "--format=%H %ct", &format!("{branch_name}..{baseline_branch}")],
+      &[
+        "log",
+        "--merges",
+        "--ancestry-path",
+        &format!("--format={MATCHED_COMMIT_FORMAT}"),
+        &format!("{branch_name}..{baseline_branch}"),
+      ],
       repo_path,
     )
     .ok()?;
-  lines.last().and_then(|line| {
-    let mut parts = line.split_whitespace();
-    let hash = parts.next()?.to_string();
-    let timestamp = parts.next()?.parse::<u32>().ok()?;
-    Some((hash, timestamp))
-  })
+  lines.last().map(String::as_str).and_then(parse_matched_commit)
 }
 
 /// Detect integration via merge commit detection
-pub fn detect_merge_status(git: &GitCommandExecutor, repo: &str, branch_name: &str, baseline: &str, is_merged: bool) -> Result<Option<BranchIntegrationStatus>> {
+pub fn detect_merge_status(
+  git: &GitCommandExecutor,
+  repo: &str,
+  branch_name: &str,
+  baseline: &str,
+  is_merged: bool,
+) -> Result<Option<(BranchIntegrationStatus, IntegrationEvidence)>> {
   if !is_merged {
     return Ok(None);
   }
@@ -32,11 +40,17 @@ pub fn detect_merge_status(git: &GitCommandExecutor, repo: &str, branch_name: &s
     .and_then(|output| output.trim().parse::<u32>().ok())
     .unwrap_or(0);
 
-  let integrated_at = find_integration_commit(git, repo, branch_name, baseline).map(|(_, timestamp)| timestamp);
+  let merge_commit = find_integration_commit(git, repo, branch_name, baseline);
+  let integrated_at = merge_commit.as_ref().map(|commit| commit.committed_at);
   info!(name = %branch_name, method = "git branch --merged", "Branch fully integrated");
-  Ok(Some(BranchIntegrationStatus::Integrated {
+  let status = BranchIntegrationStatus::Integrated {
     integrated_at,
     confidence: IntegrationConfidence::Exact,
     commit_count,
-  }))
+  };
+  let evidence = IntegrationEvidence {
+    strategy: IntegrationStrategy::Merge,
+    matched_commits: merge_commit.into_iter().collect(),
+  };
+  Ok(Some((status, evidence)))
 }
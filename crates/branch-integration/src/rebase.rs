@@ -1,12 +1,18 @@
+use crate::common::{MATCHED_COMMIT_FORMAT, MAX_MATCHED_COMMITS, parse_matched_commit};
+use crate::lookback::exclude_boundary_arg;
 use anyhow::Result;
 use git_executor::git_command_executor::GitCommandExecutor;
-use sync_types::branch_integration::{BranchIntegrationStatus, IntegrationConfidence};
+use sync_types::branch_integration::{BranchIntegrationStatus, IntegrationConfidence, IntegrationEvidence, IntegrationStrategy, MatchedCommit};
 use tracing::info;
 
-/// Scan right side (branch) with cherry-mark to derive counts in a single pass
-pub fn scan_right_side_marks(git: &GitCommandExecutor, repo: &str, baseline: &str, branch_name: &str) -> Result<(u32, u32, u32)> {
+/// Scan right side (branch) with cherry-mark to derive counts in a single pass.
+/// Baseline commits reachable from `boundary` (outside the lookback window) are not compared.
+pub fn scan_right_side_marks(git: &GitCommandExecutor, repo: &str, baseline: &str, branch_name: &str, boundary: Option<&str>) -> Result<(u32, u32, u32)> {
   let range = format!("{baseline}...{branch_name}");
-  let lines = git.execute_command_lines(&["rev-list", "--right-only", "--cherry-mark", "--no-merges", "--pretty=format:%m", &range], repo)?;
+  let exclude = exclude_boundary_arg(boundary);
+  let mut args = vec!["rev-list", "--right-only", "--cherry-mark", "--no-merges", "--pretty=format:%m", range.as_str()];
+  args.extend(exclude.as_deref());
+  let lines = git.execute_command_lines(&args, repo)?;
 
   let mut total_right: u32 = 0;
   let mut orphaned_right: u32 = 0;
@@ -31,29 +37,27 @@ pub fn scan_right_side_marks(git: &GitCommandExecutor, repo: &str, baseline: &st
   Ok((total_right, orphaned_right, integrated_right))
 }
 
-/// Get the timestamp of the most recent integrated commit using cherry-pick detection
-fn get_integration_timestamp(git: &GitCommandExecutor, repo: &str, baseline: &str, branch_name: &str) -> Option<u32> {
+/// Baseline commits patch-equivalent to branch commits (cherry-pick detection), newest first
+fn find_integrated_baseline_commits(git: &GitCommandExecutor, repo: &str, baseline: &str, branch_name: &str, boundary: Option<&str>) -> Vec<MatchedCommit> {
   let range = format!("{baseline}...{branch_name}");
-  let lines = git
-    .execute_command_lines(
-      &["rev-list", "--left-right", "--left-only", "--cherry-mark", "--no-merges", "--pretty=format:%m %ct", &range],
-      repo,
-    )
-    .ok()?;
-  for line in lines {
-    if line.starts_with("commit ") {
-      continue;
-    }
-    if let Some(rest) = line.strip_prefix("= ")
-      && let Ok(ts) = rest.parse::<u32>()
-    {
-      return Some(ts);
-    }
-  }
-  None
+  let format = format!("--pretty=format:%m {MATCHED_COMMIT_FORMAT}");
+  let exclude = exclude_boundary_arg(boundary);
+  let mut args = vec!["rev-list", "--left-right", "--left-only", "--cherry-mark", "--no-merges", format.as_str(), range.as_str()];
+  args.extend(exclude.as_deref());
+  let Ok(lines) = git.execute_command_lines(&args, repo) else {
+    return Vec::new();
+  };
+  lines
+    .iter()
+    .filter(|line| !line.starts_with("commit "))
+    .filter_map(|line| line.strip_prefix("= "))
+    .filter_map(parse_matched_commit)
+    .take(MAX_MATCHED_COMMITS)
+    .collect()
 }
 
 /// Detect integration via rebase/cherry-pick using single-pass marker scan counts
+#[allow(clippy::too_many_arguments)]
 pub fn detect_rebase_status_with_marks(
   git: &GitCommandExecutor,
   repo: &str,
@@ -62,20 +66,46 @@ pub fn detect_rebase_status_with_marks(
   total_right: u32,
   orphaned_right: u32,
   integrated_right: u32,
-) -> Result<BranchIntegrationStatus> {
-  if total_right == 0 || (orphaned_right == 0 && integrated_right > 0) {
-    let commit_count = total_right;
-    let integrated_at = if integrated_right > 0 {
-      get_integration_timestamp(git, repo, baseline, branch_name)
-    } else {
-      None
+  boundary: Option<&str>,
+) -> Result<(BranchIntegrationStatus, Option<IntegrationEvidence>)> {
+  let matched_commits = if integrated_right > 0 {
+    find_integrated_baseline_commits(git, repo, baseline, branch_name, boundary)
+  } else {
+    Vec::new()
+  };
+  let integrated_at = matched_commits.first().map(|commit| commit.committed_at);
+
+  if total_right == 0 {
+    // No non-merge commits on the branch side, the branch history is already part of the baseline
+    info!(name = %branch_name, method = "cherry-pick", "Branch fully integrated");
+    let evidence = IntegrationEvidence {
+      strategy: IntegrationStrategy::Merge,
+      matched_commits,
     };
+    return Ok((
+      BranchIntegrationStatus::Integrated {
+        integrated_at,
+        confidence: IntegrationConfidence::High,
+        commit_count: total_right,
+      },
+      Some(evidence),
+    ));
+  }
+
+  if orphaned_right == 0 && integrated_right > 0 {
     info!(name = %branch_name, method = "cherry-pick", "Branch fully integrated");
-    return Ok(BranchIntegrationStatus::Integrated {
-      integrated_at,
-      confidence: IntegrationConfidence::High,
-      commit_count,
-    });
+    let evidence = IntegrationEvidence {
+      strategy: IntegrationStrategy::Rebase,
+      matched_commits,
+    };
+    return Ok((
+      BranchIntegrationStatus::Integrated {
+        integrated_at,
+        confidence: IntegrationConfidence::High,
+        commit_count: total_right,
+      },
+      Some(evidence),
+    ));
   }
 
   let total_count = total_right;
@@ -83,18 +113,20 @@ pub fn detect_rebase_status_with_marks(
   let integrated_count = integrated_right;
 
   if orphaned_count > 0 || total_count > 0 {
-    let integrated_at = if integrated_count > 0 {
-      get_integration_timestamp(git, repo, baseline, branch_name)
-    } else {
-      None
-    };
     info!(name = %branch_name, total = total_count, integrated = integrated_count, orphaned = orphaned_count, "Branch partially orphaned - some commits integrated, some not");
-    return Ok(BranchIntegrationStatus::NotIntegrated {
-      total_commit_count: total_count,
-      integrated_count,
-      orphaned_count,
-      integrated_at,
+    let evidence = (!matched_commits.is_empty()).then(|| IntegrationEvidence {
+      strategy: IntegrationStrategy::PatchId,
+      matched_commits,
     });
+    return Ok((
+      BranchIntegrationStatus::NotIntegrated {
+        total_commit_count: total_count,
+        integrated_count,
+        orphaned_count,
+        integrated_at,
+      },
+      evidence,
+    ));
   }
 
   // This should never be reached given the logic above
@@ -1,6 +1,10 @@
+use crate::common::{MATCHED_COMMIT_FORMAT, parse_matched_commit};
+use crate::lookback::exclude_boundary_arg;
 use anyhow::Result;
 use git_executor::git_command_executor::GitCommandExecutor;
-use sync_types::branch_integration::{BranchIntegrationStatus, IntegrationConfidence};
+use git_ops::diff_options::DiffOptions;
+use git_ops::merge_tree::merge_trees;
+use sync_types::branch_integration::{BranchIntegrationStatus, IntegrationConfidence, IntegrationEvidence, IntegrationStrategy, MatchedCommit};
 use tracing::info;
 
 fn diff_is_clean(git: &GitCommandExecutor, repo: &str, base: &str, other: &str) -> bool {
@@ -13,47 +17,64 @@ fn get_tree_id(git: &GitCommandExecutor, repo: &str, reference: &str) -> Result<
   Ok(out.trim().to_string())
 }
 
-fn find_commit_time_by_subject(git: &GitCommandExecutor, repo: &str, baseline: &str, subject: &str) -> Option<u32> {
-  git
-    .execute_command(&["log", "--format=%ct", "-F", "--grep", subject.trim(), "-n", "1", baseline], repo)
-    .ok()
-    .and_then(|s| s.trim().parse::<u32>().ok())
+fn find_commit_by_subject(git: &GitCommandExecutor, repo: &str, baseline: &str, subject: &str, boundary: Option<&str>) -> Option<MatchedCommit> {
+  let format = format!("--format={MATCHED_COMMIT_FORMAT}");
+  let exclude = exclude_boundary_arg(boundary);
+  let mut args = vec!["log", format.as_str(), "-F", "--grep", subject.trim(), "-n", "1", baseline];
+  args.extend(exclude.as_deref());
+  git.execute_command(&args, repo).ok().and_then(|line| parse_matched_commit(line.trim()))
 }
 
-fn find_squash_timestamp(git_executor: &GitCommandExecutor, repo_path: &str, branch_name: &str, baseline_branch: &str) -> Option<u32> {
+/// Baseline commit with the subject of the branch tip, i.e. the squash commit
+fn find_squash_commit(git_executor: &GitCommandExecutor, repo_path: &str, branch_name: &str, baseline_branch: &str, boundary: Option<&str>) -> Option<MatchedCommit> {
   let subject = git_executor.execute_command(&["log", "-1", "--format=%s", branch_name], repo_path).ok()?;
-  find_commit_time_by_subject(git_executor, repo_path, baseline_branch, subject.trim())
+  find_commit_by_subject(git_executor, repo_path, baseline_branch, subject.trim(), boundary)
 }
 
-pub fn detect_squash_status(git: &GitCommandExecutor, repo: &str, branch_name: &str, baseline: &str, right_count: usize) -> Result<Option<BranchIntegrationStatus>> {
+/// Status and evidence of a squash-integrated branch.
+/// The baseline tree already has the whole branch, so the confidence is high even if no squash commit is found.
+fn squash_integrated(squash_commit: Option<MatchedCommit>, commit_count: u32) -> (BranchIntegrationStatus, IntegrationEvidence) {
+  let status = BranchIntegrationStatus::Integrated {
+    integrated_at: squash_commit.as_ref().map(|commit| commit.committed_at),
+    confidence: IntegrationConfidence::High,
+    commit_count,
+  };
+  let evidence = IntegrationEvidence {
+    strategy: IntegrationStrategy::Squash,
+    matched_commits: squash_commit.into_iter().collect(),
+  };
+  (status, evidence)
+}
+
+/// Detect a squash merge by comparing trees; `boundary` limits the search for the squash commit time
+pub fn detect_squash_status(
+  git: &GitCommandExecutor,
+  repo: &str,
+  branch_name: &str,
+  baseline: &str,
+  right_count: usize,
+  boundary: Option<&str>,
+) -> Result<Option<(BranchIntegrationStatus, IntegrationEvidence)>> {
   let diff_clean = diff_is_clean(git, repo, baseline, branch_name);
   if diff_clean {
-    let integrated_at = find_squash_timestamp(git, repo, branch_name, baseline);
+    let squash_commit = find_squash_commit(git, repo, branch_name, baseline, boundary);
     info!(name = %branch_name, method = "diff-clean", "Branch fully integrated");
-    return Ok(Some(BranchIntegrationStatus::Integrated {
-      integrated_at,
-      confidence: IntegrationConfidence::High,
-      commit_count: right_count as u32,
-    }));
+    return Ok(Some(squash_integrated(squash_commit, right_count as u32)));
   }
 
   let merge_base_result = git.execute_command(&["merge-base", baseline, branch_name], repo);
   if let Ok(merge_base_output) = merge_base_result {
     let merge_base = merge_base_output.trim();
     if !merge_base.is_empty()
-      && let Ok(merge_tree_output) = git.execute_command(&["merge-tree", "--write-tree", &format!("--merge-base={merge_base}"), baseline, branch_name], repo)
+      && let Ok(merge) = merge_trees(git, repo, merge_base, baseline, branch_name, &DiffOptions::default())
+      && !merge.has_conflicts()
     {
-      let merge_tree_hash = merge_tree_output.trim();
       if let Ok(baseline_tree) = get_tree_id(git, repo, baseline)
-        && merge_tree_hash == baseline_tree
+        && merge.tree_id == baseline_tree
       {
-        let integrated_at = find_squash_timestamp(git, repo, branch_name, baseline);
+        let squash_commit = find_squash_commit(git, repo, branch_name, baseline, boundary);
         info!(name = %branch_name, method = "merge-tree", "Branch fully integrated");
-        return Ok(Some(BranchIntegrationStatus::Integrated {
-          integrated_at,
-          confidence: IntegrationConfidence::High,
-          commit_count: right_count as u32,
-        }));
+        return Ok(Some(squash_integrated(squash_commit, right_count as u32)));
       }
     }
   }
@@ -1,7 +1,7 @@
 /// Detection strategy configuration
 #[derive(Debug, Clone, PartialEq, Default)]
 pub enum DetectionStrategy {
-  /// Default - fast rebase/cherry-pick detection only
+  /// Default - rebase/cherry-pick detection only, including cherry-picks whose patch-id changed with drifted context
   #[default]
   Rebase,
   /// Include merge commit detection
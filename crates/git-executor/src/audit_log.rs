@@ -0,0 +1,237 @@
+//! In-memory log of the git commands run by a [`GitCommandExecutor`](crate::git_command_executor::GitCommandExecutor).
+//! Only the latest commands are kept. The log can be exported to attach to a bug report about a wrong rewrite,
+//! either as JSON lines or as a shell script replaying the commands.
+//!
+//! Git processes not started through the executor's command methods are not recorded: the long-running
+//! `cat-file --batch` processes of the object lookups and the `git version` calls of [`GitInfo`](crate::git_info::GitInfo).
+//! The input and output of `git credential` are never recorded, they contain passwords.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Number of commands kept by default
+pub const DEFAULT_AUDIT_LOG_CAPACITY: usize = 1000;
+
+/// Bytes of stdout and stderr kept per command
+pub const MAX_AUDIT_OUTPUT_BYTES: usize = 2048;
+
+/// Bytes of stdin kept per command, a command with longer input is not replayable
+pub const MAX_AUDIT_INPUT_BYTES: usize = 64 * 1024;
+
+/// Environment variables whose values are recorded, the values of others may contain secrets (e.g. the filled credential)
+const RECORDED_ENV_VALUES: &[&str] = &[
+  "GIT_AUTHOR_DATE",
+  "GIT_AUTHOR_EMAIL",
+  "GIT_AUTHOR_NAME",
+  "GIT_COMMITTER_DATE",
+  "GIT_COMMITTER_EMAIL",
+  "GIT_COMMITTER_NAME",
+  "GIT_INDEX_FILE",
+  "GIT_NO_LAZY_FETCH",
+  "GIT_TERMINAL_PROMPT",
+];
+
+/// One git invocation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct GitAuditEntry {
+  /// Unix timestamp in milliseconds of the start of the command
+  pub started_at: u64,
+  pub repository_path: String,
+  pub args: Vec<String>,
+  /// Environment variables set for the command as `NAME=value`, only `NAME` for variables whose value may contain secrets
+  pub env: Vec<String>,
+  /// Input written to stdin, `None` if there was none or it wasn't recorded
+  pub stdin: Option<String>,
+  /// Whether the replay script can run the command as it was run: the values of all environment variables and the whole
+  /// input were recorded
+  pub replayable: bool,
+  pub duration_ms: u64,
+  /// `None` if the command couldn't be started or was killed by a signal
+  pub exit_code: Option<i32>,
+  /// Beginning of stdout, see [`MAX_AUDIT_OUTPUT_BYTES`]
+  pub stdout: String,
+  /// Beginning of stderr, see [`MAX_AUDIT_OUTPUT_BYTES`]
+  pub stderr: String,
+}
+
+/// Format of an exported audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum AuditLogFormat {
+  /// One JSON entry per line
+  Json,
+  /// Shell script running the commands again, with their recorded outcome as comments
+  Script,
+}
+
+/// Ring buffer of the latest git invocations
+#[derive(Debug)]
+pub struct GitAuditLog {
+  capacity: usize,
+  entries: Mutex<VecDeque<GitAuditEntry>>,
+}
+
+impl Default for GitAuditLog {
+  fn default() -> Self {
+    Self::new(DEFAULT_AUDIT_LOG_CAPACITY)
+  }
+}
+
+impl GitAuditLog {
+  #[must_use]
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      capacity,
+      entries: Mutex::new(VecDeque::with_capacity(capacity.min(DEFAULT_AUDIT_LOG_CAPACITY))),
+    }
+  }
+
+  pub(crate) fn record(&self, invocation: GitInvocation<'_>, started: SystemTime, duration: Duration, exit_code: Option<i32>, stdout: &[u8], stderr: &[u8]) {
+    if self.capacity == 0 {
+      return;
+    }
+    let env: Vec<String> = invocation
+      .env_vars
+      .iter()
+      .map(|(key, value)| if RECORDED_ENV_VALUES.contains(key) { format!("{key}={value}") } else { key.to_string() })
+      .collect();
+    let is_credential_command = git_subcommand(invocation.args) == Some("credential");
+    // NUL can't be passed in a shell string
+    let stdin = invocation
+      .input
+      .filter(|input| !is_credential_command && input.len() <= MAX_AUDIT_INPUT_BYTES && !input.contains('\0'))
+      .map(str::to_string);
+    let entry = GitAuditEntry {
+      started_at: started.duration_since(UNIX_EPOCH).map(|duration| duration.as_millis() as u64).unwrap_or_default(),
+      repository_path: invocation.repository_path.to_string(),
+      args: invocation.args.iter().map(|arg| arg.to_string()).collect(),
+      replayable: invocation.env_vars.iter().all(|(key, _)| RECORDED_ENV_VALUES.contains(key)) && stdin.is_some() == invocation.input.is_some(),
+      env,
+      stdin,
+      duration_ms: duration.as_millis() as u64,
+      exit_code,
+      stdout: if is_credential_command { String::new() } else { truncate_output(stdout) },
+      stderr: truncate_output(stderr),
+    };
+    let mut entries = self.entries.lock().unwrap();
+    if entries.len() == self.capacity {
+      entries.pop_front();
+    }
+    entries.push_back(entry);
+  }
+
+  /// Recorded commands, oldest first; `limit` keeps only the latest ones
+  pub fn entries(&self, limit: Option<usize>) -> Vec<GitAuditEntry> {
+    let entries = self.entries.lock().unwrap();
+    let skip = limit.map_or(0, |limit| entries.len().saturating_sub(limit));
+    entries.iter().skip(skip).cloned().collect()
+  }
+
+  pub fn clear(&self) {
+    self.entries.lock().unwrap().clear();
+  }
+
+  /// Render the recorded commands in the given format
+  pub fn export(&self, format: AuditLogFormat) -> String {
+    let entries = self.entries(None);
+    match format {
+      AuditLogFormat::Json => entries.iter().fold(String::new(), |mut out, entry| {
+        // Serializing plain strings and numbers can't fail
+        let _ = writeln!(out, "{}", serde_json::to_string(entry).unwrap_or_default());
+        out
+      }),
+      AuditLogFormat::Script => replay_script(&entries),
+    }
+  }
+}
+
+/// What is run, passed to [`GitAuditLog::record`]
+pub(crate) struct GitInvocation<'a> {
+  pub args: &'a [&'a str],
+  pub repository_path: &'a str,
+  pub env_vars: &'a [(&'a str, &'a str)],
+  /// Written to stdin
+  pub input: Option<&'a str>,
+}
+
+fn truncate_output(output: &[u8]) -> String {
+  let output = String::from_utf8_lossy(output);
+  let output = output.trim_end();
+  if output.len() <= MAX_AUDIT_OUTPUT_BYTES {
+    return output.to_string();
+  }
+  let mut end = MAX_AUDIT_OUTPUT_BYTES;
+  while !output.is_char_boundary(end) {
+    end -= 1;
+  }
+  format!("{}… ({} bytes truncated)", &output[..end], output.len() - end)
+}
+
+/// The git command run, e.g. `commit-tree` for `git -c user.name=x commit-tree`
+fn git_subcommand<'a>(args: &[&'a str]) -> Option<&'a str> {
+  let mut args = args.iter();
+  while let Some(arg) = args.next() {
+    match *arg {
+      // Options taking a separate value
+      "-c" | "-C" | "--git-dir" | "--work-tree" | "--namespace" | "--config-env" => {
+        args.next();
+      }
+      arg if arg.starts_with('-') => {}
+      arg => return Some(arg),
+    }
+  }
+  None
+}
+
+/// Shell script replaying the commands. Commands are not guarded: the script is meant to be run against a copy of the
+/// repository. Commands that can't be replayed as they were run (see [`GitAuditEntry::replayable`]) are only listed as
+/// comments, so later commands depending on them may fail or produce other commit IDs.
+fn replay_script(entries: &[GitAuditEntry]) -> String {
+  let mut script = String::from("#!/bin/sh\n# Git commands recorded by Branch Deck, run against a copy of the repository\n");
+  let mut current_path: Option<&str> = None;
+  for entry in entries {
+    if current_path != Some(entry.repository_path.as_str()) {
+      let _ = writeln!(script, "\ncd {}", shell_quote(&entry.repository_path));
+      current_path = Some(&entry.repository_path);
+    }
+    let exit_code = entry.exit_code.map_or_else(|| "none".to_string(), |code| code.to_string());
+    let _ = writeln!(script, "# {} ms, exit code {exit_code}", entry.duration_ms);
+    let mut command = String::new();
+    if let Some(stdin) = &entry.stdin {
+      let _ = write!(command, "printf '%s' {} | ", shell_quote(stdin));
+    }
+    for variable in &entry.env {
+      match variable.split_once('=') {
+        Some((name, value)) => {
+          let _ = write!(command, "{name}={} ", shell_quote(value));
+        }
+        None => {
+          let _ = write!(command, "{variable}=<not recorded> ");
+        }
+      }
+    }
+    let args: Vec<String> = entry.args.iter().map(|arg| shell_quote(arg)).collect();
+    let _ = write!(command, "git {}", args.join(" "));
+    if entry.replayable {
+      let _ = writeln!(script, "{command}");
+    } else {
+      // Multi-line input stays in the comment
+      let _ = writeln!(script, "# not replayable, environment or input not recorded: {}", command.replace('\n', "\n# "));
+    }
+  }
+  script
+}
+
+fn shell_quote(value: &str) -> String {
+  if !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:@^~%+,".contains(c)) {
+    value.to_string()
+  } else {
+    format!("'{}'", value.replace('\'', r"'\''"))
+  }
+}
@@ -0,0 +1,111 @@
+use crate::audit_log::{AuditLogFormat, GitAuditLog, GitInvocation, MAX_AUDIT_OUTPUT_BYTES};
+use crate::git_command_executor::GitCommandExecutor;
+use std::time::{Duration, SystemTime};
+
+fn record(log: &GitAuditLog, args: &[&str], exit_code: Option<i32>, stdout: &[u8]) {
+  record_with_input(log, args, &[("GIT_COMMITTER_DATE", "1700000000 +0000")], None, exit_code, stdout);
+}
+
+fn record_with_input(log: &GitAuditLog, args: &[&str], env_vars: &[(&str, &str)], input: Option<&str>, exit_code: Option<i32>, stdout: &[u8]) {
+  let invocation = GitInvocation {
+    args,
+    repository_path: "/tmp/my repo",
+    env_vars,
+    input,
+  };
+  log.record(invocation, SystemTime::now(), Duration::from_millis(5), exit_code, stdout, b"");
+}
+
+#[test]
+fn test_audit_log_keeps_latest_entries() {
+  let log = GitAuditLog::new(2);
+  record(&log, &["status"], Some(0), b"");
+  record(&log, &["rev-parse", "HEAD"], Some(0), b"abc\n");
+  record(&log, &["branch", "-f", "user/virtual/feature", "abc"], Some(0), b"");
+
+  let entries = log.entries(None);
+  assert_eq!(entries.iter().map(|entry| entry.args[0].as_str()).collect::<Vec<_>>(), vec!["rev-parse", "branch"]);
+  assert_eq!(entries[0].stdout, "abc");
+  assert_eq!(entries[0].env, vec!["GIT_COMMITTER_DATE=1700000000 +0000".to_string()]);
+  assert!(entries[0].replayable);
+  assert_eq!(log.entries(Some(1)).len(), 1);
+
+  log.clear();
+  assert!(log.entries(None).is_empty());
+}
+
+#[test]
+fn test_audit_log_truncates_output() {
+  let log = GitAuditLog::default();
+  record(&log, &["log"], Some(0), "é".repeat(MAX_AUDIT_OUTPUT_BYTES).as_bytes());
+
+  let stdout = &log.entries(None)[0].stdout;
+  assert!(stdout.len() < MAX_AUDIT_OUTPUT_BYTES + 32, "{}", stdout.len());
+  assert!(stdout.ends_with(&format!("… ({MAX_AUDIT_OUTPUT_BYTES} bytes truncated)")), "{stdout}");
+}
+
+#[test]
+fn test_audit_log_leaves_out_secrets() {
+  let log = GitAuditLog::default();
+  let url = "protocol=https\nhost=example.com\n";
+  let credential = "protocol=https\nhost=example.com\nusername=user\npassword=secret\n";
+  record_with_input(&log, &["credential", "fill"], &[], Some(url), Some(0), credential.as_bytes());
+  record_with_input(&log, &["-c", "log.showSignature=false", "credential", "approve"], &[], Some(credential), Some(0), b"");
+  record_with_input(&log, &["push", "origin"], &[("BRANCH_DECK_GIT_PASSWORD", "secret")], None, Some(0), b"");
+
+  let entries = log.entries(None);
+  assert_eq!(entries[0].stdout, "");
+  assert_eq!(entries[0].stdin, None);
+  assert_eq!(entries[1].stdin, None);
+  // Only the name of a variable that may hold a secret is kept
+  assert_eq!(entries[2].env, vec!["BRANCH_DECK_GIT_PASSWORD".to_string()]);
+  assert!(entries.iter().all(|entry| !entry.replayable));
+  assert!(!log.export(AuditLogFormat::Json).contains("secret"));
+}
+
+#[test]
+fn test_audit_log_replay_script() {
+  let log = GitAuditLog::default();
+  record(&log, &["commit-tree", "abc", "-m", "Fix user's bug"], Some(0), b"def");
+  record(&log, &["merge-tree", "--write-tree", "a", "b"], Some(1), b"");
+  let index_env = [("GIT_INDEX_FILE", "/tmp/index")];
+  record_with_input(&log, &["update-ref", "--stdin"], &index_env, Some("update refs/heads/feature abc\n"), Some(0), b"");
+  record_with_input(&log, &["push", "origin"], &[("BRANCH_DECK_GIT_PASSWORD", "secret")], None, Some(0), b"");
+
+  assert_eq!(
+    log.export(AuditLogFormat::Script),
+    r"#!/bin/sh
+# Git commands recorded by Branch Deck, run against a copy of the repository
+
+cd '/tmp/my repo'
+# 5 ms, exit code 0
+GIT_COMMITTER_DATE='1700000000 +0000' git commit-tree abc -m 'Fix user'\''s bug'
+# 5 ms, exit code 1
+GIT_COMMITTER_DATE='1700000000 +0000' git merge-tree --write-tree a b
+# 5 ms, exit code 0
+printf '%s' 'update refs/heads/feature abc
+' | GIT_INDEX_FILE=/tmp/index git update-ref --stdin
+# 5 ms, exit code 0
+# not replayable, environment or input not recorded: BRANCH_DECK_GIT_PASSWORD=<not recorded> git push origin
+"
+  );
+  assert_eq!(log.export(AuditLogFormat::Json).lines().count(), 4);
+}
+
+#[test]
+fn test_executor_records_commands() {
+  let git_executor = GitCommandExecutor::new();
+  let repository_path = std::env::temp_dir();
+  let repository_path = repository_path.to_str().unwrap();
+  git_executor.execute_command(&["--version"], repository_path).unwrap();
+  // Clones share the log
+  assert!(git_executor.clone().execute_command(&["no-such-command"], repository_path).is_err());
+
+  let entries = git_executor.audit_log().entries(None);
+  assert_eq!(entries.len(), 2);
+  assert_eq!(entries[0].args, vec!["--version".to_string()]);
+  assert_eq!(entries[0].exit_code, Some(0));
+  assert!(entries[0].stdout.starts_with("git version"), "{}", entries[0].stdout);
+  assert_ne!(entries[1].exit_code, Some(0));
+  assert!(!entries[1].stderr.is_empty());
+}
@@ -0,0 +1,293 @@
+use crate::long_paths::git_command;
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Stdio};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+/// Idle processes are stopped after this long, e.g. the ones of a repository that was closed
+pub(crate) const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Object header reported by `git cat-file --batch-check`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectInfo {
+  pub id: String,
+  /// Object type: `commit`, `tree`, `blob` or `tag`
+  pub kind: String,
+  pub size: usize,
+}
+
+/// Commit metadata parsed from a raw commit object
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitObject {
+  pub tree_id: String,
+  pub parent_ids: Vec<String>,
+  pub author_name: String,
+  pub author_email: String,
+  pub author_timestamp: u32,
+  pub committer_timestamp: u32,
+  /// Raw commit message (`%B`)
+  pub message: String,
+  /// Encoding of the author and message from the `encoding` header, `None` for UTF-8
+  pub encoding: Option<String>,
+}
+
+impl CommitObject {
+  /// Parse the content of a commit object as printed by `git cat-file commit`
+  pub fn parse(data: &[u8]) -> Result<Self> {
+    let text = String::from_utf8_lossy(data);
+    let (headers, message) = text.split_once("\n\n").unwrap_or((&text, ""));
+
+    let mut tree_id = None;
+    let mut parent_ids = Vec::new();
+    let mut author = None;
+    let mut committer_timestamp = None;
+    let mut encoding = None;
+    for line in headers.lines() {
+      // Continuation lines of multi-line headers (gpgsig, mergetag) start with a space
+      let Some((key, value)) = line.split_once(' ') else { continue };
+      match key {
+        "tree" => tree_id = Some(value.to_string()),
+        "parent" => parent_ids.push(value.to_string()),
+        "author" => author = Some(parse_identity(value)?),
+        "committer" => committer_timestamp = Some(parse_identity(value)?.2),
+        "encoding" => encoding = Some(value.to_string()),
+        _ => {}
+      }
+    }
+
+    let (author_name, author_email, author_timestamp) = author.ok_or_else(|| anyhow!("Commit object has no author"))?;
+    Ok(Self {
+      tree_id: tree_id.ok_or_else(|| anyhow!("Commit object has no tree"))?,
+      parent_ids,
+      author_name,
+      author_email,
+      author_timestamp,
+      committer_timestamp: committer_timestamp.ok_or_else(|| anyhow!("Commit object has no committer"))?,
+      message: message.to_string(),
+      encoding: encoding.filter(|encoding| !encoding.eq_ignore_ascii_case("utf-8") && !encoding.eq_ignore_ascii_case("utf8")),
+    })
+  }
+
+  /// Subject as formatted by `%s`: the first paragraph of the message joined into a single line
+  #[must_use]
+  pub fn subject(&self) -> String {
+    self
+      .message
+      .lines()
+      .skip_while(|line| line.trim().is_empty())
+      .take_while(|line| !line.trim().is_empty())
+      .map(str::trim_end)
+      .collect::<Vec<_>>()
+      .join(" ")
+  }
+}
+
+/// Parse `Name <email> timestamp timezone`
+fn parse_identity(value: &str) -> Result<(String, String, u32)> {
+  let (name, rest) = value.split_once('<').ok_or_else(|| anyhow!("Invalid identity: {value}"))?;
+  let (email, date) = rest.rsplit_once('>').ok_or_else(|| anyhow!("Invalid identity: {value}"))?;
+  let timestamp = date
+    .split_whitespace()
+    .next()
+    .and_then(|timestamp| timestamp.parse().ok())
+    .ok_or_else(|| anyhow!("Invalid identity timestamp: {value}"))?;
+  Ok((name.trim_end().to_string(), email.to_string(), timestamp))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum BatchMode {
+  /// `--batch-check`: object headers only
+  Check,
+  /// `--batch`: object headers followed by contents
+  Contents,
+}
+
+/// A long-lived `git cat-file --batch`/`--batch-check` process for one repository.
+/// Requests are written one object name per line and answered in order.
+struct CatFileProcess {
+  child: Child,
+  stdin: ChildStdin,
+  stdout: BufReader<ChildStdout>,
+  last_used: Instant,
+}
+
+impl CatFileProcess {
+  fn spawn(git_path: &str, repository_path: &str, mode: BatchMode) -> Result<Self> {
+    let mode_arg = match mode {
+      BatchMode::Check => "--batch-check",
+      BatchMode::Contents => "--batch",
+    };
+    let mut child = git_command(git_path, repository_path)
+      .args(["cat-file", mode_arg])
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::null())
+      .spawn()
+      .map_err(|e| anyhow!("Failed to spawn git cat-file: {e}"))?;
+    let stdin = child.stdin.take().ok_or_else(|| anyhow!("Failed to open cat-file stdin"))?;
+    let stdout = child.stdout.take().ok_or_else(|| anyhow!("Failed to open cat-file stdout"))?;
+    Ok(Self {
+      child,
+      stdin,
+      stdout: BufReader::new(stdout),
+      last_used: Instant::now(),
+    })
+  }
+
+  fn request(&mut self, object: &str, mode: BatchMode) -> Result<Option<(ObjectInfo, Vec<u8>)>> {
+    writeln!(self.stdin, "{object}")?;
+    self.stdin.flush()?;
+
+    let mut header = String::new();
+    if self.stdout.read_line(&mut header)? == 0 {
+      return Err(anyhow!("git cat-file exited unexpectedly"));
+    }
+    let header = header.trim_end_matches('\n');
+
+    let parts: Vec<&str> = header.split(' ').collect();
+    if let [id, kind, size] = parts[..]
+      && let Ok(size) = size.parse::<usize>()
+    {
+      let info = ObjectInfo {
+        id: id.to_string(),
+        kind: kind.to_string(),
+        size,
+      };
+      let mut contents = Vec::new();
+      if mode == BatchMode::Contents {
+        // Contents are followed by a newline
+        contents.resize(size + 1, 0);
+        self.stdout.read_exact(&mut contents)?;
+        contents.truncate(size);
+      }
+      return Ok(Some((info, contents)));
+    }
+
+    if header.ends_with(" missing") || header.ends_with(" ambiguous") {
+      Ok(None)
+    } else {
+      Err(anyhow!("Unexpected git cat-file response: {header:?}"))
+    }
+  }
+}
+
+impl Drop for CatFileProcess {
+  fn drop(&mut self) {
+    let _ = self.child.kill();
+    let _ = self.child.wait();
+  }
+}
+
+/// Idle cat-file processes keyed by repository and mode.
+/// A process is taken out of the pool for the duration of a request, so concurrent callers
+/// never share one; the pool grows to the peak number of concurrent lookups per repository.
+/// Processes idle for [`IDLE_TIMEOUT`] are stopped by a thread running while the pool has processes.
+#[derive(Default)]
+pub(crate) struct CatFilePool {
+  idle: Mutex<IdleProcesses>,
+}
+
+#[derive(Default)]
+struct IdleProcesses {
+  processes: HashMap<(String, BatchMode), Vec<CatFileProcess>>,
+  reaper_running: bool,
+}
+
+impl std::fmt::Debug for CatFilePool {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let processes = self.idle_count();
+    f.debug_struct("CatFilePool").field("idle_processes", &processes).finish()
+  }
+}
+
+impl CatFilePool {
+  pub(crate) fn request(self: &Arc<Self>, git_path: &str, repository_path: &str, object: &str, mode: BatchMode) -> Result<Option<(ObjectInfo, Vec<u8>)>> {
+    if object.contains('\n') {
+      return Err(anyhow!("Invalid object name: {object:?}"));
+    }
+
+    let key = (repository_path.to_string(), mode);
+    let pooled = self
+      .idle
+      .lock()
+      .map_err(|e| anyhow!("Failed to acquire lock: {}", e))?
+      .processes
+      .get_mut(&key)
+      .and_then(Vec::pop);
+
+    // A pooled process may have died (e.g. killed externally); retry once with a fresh one
+    let (mut process, result) = match pooled {
+      Some(mut process) => match process.request(object, mode) {
+        Ok(result) => (process, result),
+        Err(e) => {
+          tracing::debug!(error = %e, "cat-file process failed, restarting");
+          Self::spawn_and_request(git_path, repository_path, object, mode)?
+        }
+      },
+      None => Self::spawn_and_request(git_path, repository_path, object, mode)?,
+    };
+
+    process.last_used = Instant::now();
+    let mut idle = self.idle.lock().map_err(|e| anyhow!("Failed to acquire lock: {}", e))?;
+    idle.processes.entry(key).or_default().push(process);
+    if !idle.reaper_running {
+      idle.reaper_running = true;
+      drop(idle);
+      self.start_reaper();
+    }
+    Ok(result)
+  }
+
+  /// Stop the idle processes, e.g. after the git executable changed
+  pub(crate) fn clear(&self) {
+    if let Ok(mut idle) = self.idle.lock() {
+      idle.processes.clear();
+    }
+  }
+
+  /// Stop the processes unused since `IDLE_TIMEOUT` before `now`. Returns whether processes are left.
+  pub(crate) fn reap_idle(&self, now: Instant) -> bool {
+    let Ok(mut idle) = self.idle.lock() else {
+      return false;
+    };
+    idle.processes.retain(|_, processes| {
+      processes.retain(|process| now.saturating_duration_since(process.last_used) < IDLE_TIMEOUT);
+      !processes.is_empty()
+    });
+    // Checked under the lock, so a process pooled meanwhile starts a new reaper
+    idle.reaper_running = !idle.processes.is_empty();
+    idle.reaper_running
+  }
+
+  pub(crate) fn idle_count(&self) -> usize {
+    self.idle.lock().map(|idle| idle.processes.values().map(Vec::len).sum::<usize>()).unwrap_or(0)
+  }
+
+  /// The thread ends once no process is left or the pool is dropped
+  fn start_reaper(self: &Arc<Self>) {
+    let pool: Weak<Self> = Arc::downgrade(self);
+    let spawned = std::thread::Builder::new().name("cat-file-reaper".to_string()).spawn(move || {
+      loop {
+        std::thread::sleep(IDLE_TIMEOUT / 2);
+        match pool.upgrade() {
+          Some(pool) if pool.reap_idle(Instant::now()) => {}
+          _ => return,
+        }
+      }
+    });
+    if let Err(e) = spawned {
+      tracing::warn!(error = %e, "Failed to start the cat-file reaper, idle processes are stopped with the executor");
+      if let Ok(mut idle) = self.idle.lock() {
+        idle.reaper_running = false;
+      }
+    }
+  }
+
+  fn spawn_and_request(git_path: &str, repository_path: &str, object: &str, mode: BatchMode) -> Result<(CatFileProcess, Option<(ObjectInfo, Vec<u8>)>)> {
+    let mut process = CatFileProcess::spawn(git_path, repository_path, mode)?;
+    let result = process.request(object, mode)?;
+    Ok((process, result))
+  }
+}
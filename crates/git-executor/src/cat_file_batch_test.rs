@@ -0,0 +1,31 @@
+use crate::cat_file_batch::{BatchMode, CatFilePool, IDLE_TIMEOUT};
+use crate::git_command_executor::GitCommandExecutor;
+use std::sync::Arc;
+use std::time::Instant;
+
+#[test]
+fn test_idle_processes_are_stopped() {
+  let repository = std::env::temp_dir().join(format!("branch-deck-cat-file-{}", std::process::id()));
+  std::fs::create_dir_all(&repository).unwrap();
+  let repository_path = repository.to_str().unwrap();
+  GitCommandExecutor::new().execute_command(&["init", "--quiet"], repository_path).unwrap();
+
+  let pool = Arc::new(CatFilePool::default());
+  let result = (|| -> anyhow::Result<()> {
+    assert!(pool.request("git", repository_path, "HEAD", BatchMode::Check)?.is_none());
+    assert_eq!(pool.idle_count(), 1);
+    // Reused rather than spawned again
+    assert!(pool.request("git", repository_path, "HEAD", BatchMode::Check)?.is_none());
+    assert_eq!(pool.idle_count(), 1);
+
+    assert!(pool.reap_idle(Instant::now()));
+    assert_eq!(pool.idle_count(), 1);
+    assert!(!pool.reap_idle(Instant::now() + IDLE_TIMEOUT));
+    assert_eq!(pool.idle_count(), 0);
+    Ok(())
+  })();
+  drop(pool);
+  let _ = std::fs::remove_dir_all(&repository);
+
+  result.unwrap();
+}
@@ -1,13 +1,20 @@
+use crate::audit_log::{GitAuditLog, GitInvocation};
+use crate::cat_file_batch::{BatchMode, CatFilePool, CommitObject, ObjectInfo};
+use crate::git_error::GitCommandError;
 use crate::git_info::GitInfo;
+use crate::long_paths::git_command;
 use anyhow::{Result, anyhow};
 use std::io::Write;
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
 use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
 use tracing::instrument;
 
 #[derive(Clone, Debug)]
 pub struct GitCommandExecutor {
   info: Arc<Mutex<Option<GitInfo>>>,
+  cat_file: Arc<CatFilePool>,
+  audit_log: Arc<GitAuditLog>,
 }
 
 impl Default for GitCommandExecutor {
@@ -19,7 +26,16 @@ impl Default for GitCommandExecutor {
 impl GitCommandExecutor {
   #[must_use]
   pub fn new() -> Self {
-    Self { info: Arc::new(Mutex::new(None)) }
+    Self {
+      info: Arc::new(Mutex::new(None)),
+      cat_file: Arc::new(CatFilePool::default()),
+      audit_log: Arc::new(GitAuditLog::default()),
+    }
+  }
+
+  /// Latest git commands run by this executor and its clones
+  pub fn audit_log(&self) -> &GitAuditLog {
+    &self.audit_log
   }
 
   #[instrument(skip(self))]
@@ -34,6 +50,25 @@ impl GitCommandExecutor {
     guard.as_ref().ok_or_else(|| anyhow!("Git info should be initialized")).cloned()
   }
 
+  /// Use the git executable at `path` from now on, `None` goes back to the discovered one. Fails without changing the
+  /// executable if it doesn't run or is too old.
+  #[instrument(skip(self))]
+  pub fn set_git_path(&self, path: Option<&str>) -> Result<GitInfo> {
+    let info = match path {
+      Some(path) => {
+        let info = GitInfo::from_path(path).map_err(|e| anyhow!(e))?;
+        info.validate_minimum_version().map_err(|e| anyhow!(e))?;
+        info
+      }
+      None => GitInfo::discover().map_err(|e| anyhow!(e))?,
+    };
+    tracing::info!(git_version = %info.version, git_path = %info.path, "git executable changed");
+    *self.info.lock().map_err(|e| anyhow!("Failed to acquire lock: {}", e))? = Some(info.clone());
+    // Pooled processes were started with the previous executable
+    self.cat_file.clear();
+    Ok(info)
+  }
+
   // Helper method to validate repository path
   fn validate_path(repository_path: &str) -> Result<()> {
     if repository_path.is_empty() {
@@ -49,7 +84,15 @@ impl GitCommandExecutor {
     tracing::Span::current().record("success", false);
     tracing::error!(stderr = %stderr, "git command failed");
     let git_info = self.get_info()?;
-    Err(anyhow!("git command failed: {} {}\nError: {stderr}", git_info.path, args.join(" ")))
+    Err(
+      GitCommandError {
+        git_path: git_info.path,
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        exit_code: output.status.code(),
+        stderr,
+      }
+      .into(),
+    )
   }
 
   // Helper method to handle successful command output
@@ -64,6 +107,23 @@ impl GitCommandExecutor {
     args.contains(&"merge-tree") && status.code() == Some(1)
   }
 
+  /// Set the environment variables of a command. With `SSH_ASKPASS` set, ssh is made to use it even when the app has a
+  /// terminal (OpenSSH 8.4+ honors `SSH_ASKPASS_REQUIRE`) or no X display (older versions ask only with `DISPLAY` set),
+  /// so a key passphrase is never asked for where nobody can answer.
+  fn apply_env(command: &mut Command, env_vars: &[(&str, &str)]) {
+    for (key, value) in env_vars {
+      command.env(key, value);
+    }
+    if env_vars.iter().any(|(key, value)| *key == "SSH_ASKPASS" && !value.is_empty()) {
+      if !env_vars.iter().any(|(key, _)| *key == "SSH_ASKPASS_REQUIRE") {
+        command.env("SSH_ASKPASS_REQUIRE", "force");
+      }
+      if std::env::var_os("DISPLAY").is_none() {
+        command.env("DISPLAY", ":0");
+      }
+    }
+  }
+
   // Helper method to parse output into lines efficiently
   pub fn parse_lines(output: &[u8]) -> Vec<String> {
     output
@@ -81,16 +141,54 @@ impl GitCommandExecutor {
     Self::validate_path(repository_path)?;
     let git_info = self.get_info()?;
 
-    let output = Command::new(&git_info.path)
-      .args(args)
-      .current_dir(repository_path)
-      .output()
-      .map_err(|e| anyhow!("Failed to execute git command: {e}"))?;
+    let mut command = git_command(&git_info.path, repository_path);
+    command.args(args);
+    let output = self.run_audited(&mut command, args, repository_path, &[], None)?;
 
     let exit_code = output.status.code().unwrap_or(-1);
     Ok((output, exit_code))
   }
 
+  /// Run the git command, with `input` written to stdin, and record it in the audit log
+  fn run_audited(&self, command: &mut Command, args: &[&str], repository_path: &str, env_vars: &[(&str, &str)], input: Option<&str>) -> Result<Output> {
+    let started_at = SystemTime::now();
+    let started = Instant::now();
+    let result = match input {
+      None => command.output().map_err(|e| anyhow!("Failed to execute git command: {e}")),
+      Some(input) => Self::output_with_input(command, input),
+    };
+
+    let invocation = GitInvocation {
+      args,
+      repository_path,
+      env_vars,
+      input,
+    };
+    match &result {
+      Ok(output) => self
+        .audit_log
+        .record(invocation, started_at, started.elapsed(), output.status.code(), &output.stdout, &output.stderr),
+      Err(e) => self.audit_log.record(invocation, started_at, started.elapsed(), None, &[], e.to_string().as_bytes()),
+    }
+    result
+  }
+
+  fn output_with_input(command: &mut Command, input: &str) -> Result<Output> {
+    let mut child = command
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .spawn()
+      .map_err(|e| anyhow!("Failed to spawn git command: {e}"))?;
+
+    // Write input to stdin
+    if let Some(mut stdin) = child.stdin.take() {
+      stdin.write_all(input.as_bytes()).map_err(|e| anyhow!("Failed to write to stdin: {e}"))?;
+    }
+
+    child.wait_with_output().map_err(|e| anyhow!("Failed to execute git command: {e}"))
+  }
+
   #[instrument(
     skip(self),
     fields(
@@ -177,21 +275,9 @@ impl GitCommandExecutor {
     Self::validate_path(repository_path)?;
     let git_info = self.get_info()?;
 
-    let mut child = Command::new(&git_info.path)
-      .args(args)
-      .current_dir(repository_path)
-      .stdin(Stdio::piped())
-      .stdout(Stdio::piped())
-      .stderr(Stdio::piped())
-      .spawn()
-      .map_err(|e| anyhow!("Failed to spawn git command: {e}"))?;
-
-    // Write input to stdin
-    if let Some(mut stdin) = child.stdin.take() {
-      stdin.write_all(input.as_bytes()).map_err(|e| anyhow!("Failed to write to stdin: {e}"))?;
-    }
-
-    let output = child.wait_with_output().map_err(|e| anyhow!("Failed to execute git command: {e}"))?;
+    let mut command = git_command(&git_info.path, repository_path);
+    command.args(args);
+    let output = self.run_audited(&mut command, args, repository_path, &[], Some(input))?;
 
     if output.status.success() {
       Ok(Self::handle_success(&output))
@@ -212,15 +298,12 @@ impl GitCommandExecutor {
     Self::validate_path(repository_path)?;
     let git_info = self.get_info()?;
 
-    let mut cmd = Command::new(&git_info.path);
-    cmd.args(args).current_dir(repository_path);
+    let mut cmd = git_command(&git_info.path, repository_path);
+    cmd.args(args);
 
-    // Set environment variables
-    for (key, value) in env_vars {
-      cmd.env(key, value);
-    }
+    Self::apply_env(&mut cmd, env_vars);
 
-    let output = cmd.output().map_err(|e| anyhow!("Failed to execute git command: {e}"))?;
+    let output = self.run_audited(&mut cmd, args, repository_path, env_vars, None)?;
 
     if output.status.success() {
       Ok(Self::handle_success(&output))
@@ -242,27 +325,12 @@ impl GitCommandExecutor {
     Self::validate_path(repository_path)?;
     let git_info = self.get_info()?;
 
-    let mut cmd = Command::new(&git_info.path);
-    cmd
-      .args(args)
-      .current_dir(repository_path)
-      .stdin(Stdio::piped())
-      .stdout(Stdio::piped())
-      .stderr(Stdio::piped());
-
-    // Set environment variables
-    for (key, value) in env_vars {
-      cmd.env(key, value);
-    }
-
-    let mut child = cmd.spawn().map_err(|e| anyhow!("Failed to spawn git command: {e}"))?;
+    let mut cmd = git_command(&git_info.path, repository_path);
+    cmd.args(args);
 
-    // Write input to stdin
-    if let Some(mut stdin) = child.stdin.take() {
-      stdin.write_all(input.as_bytes()).map_err(|e| anyhow!("Failed to write to stdin: {e}"))?;
-    }
+    Self::apply_env(&mut cmd, env_vars);
 
-    let output = child.wait_with_output().map_err(|e| anyhow!("Failed to execute git command: {e}"))?;
+    let output = self.run_audited(&mut cmd, args, repository_path, env_vars, Some(input))?;
 
     if output.status.success() {
       Ok(Self::handle_success(&output))
@@ -288,9 +356,10 @@ impl GitCommandExecutor {
     Self::validate_path(repository_path)?;
     let git_info = self.get_info()?;
 
-    let mut child = Command::new(&git_info.path)
+    let started_at = SystemTime::now();
+    let started = Instant::now();
+    let mut child = git_command(&git_info.path, repository_path)
       .args(args)
-      .current_dir(repository_path)
       .stdout(Stdio::piped())
       .stderr(Stdio::piped())
       .spawn()
@@ -313,6 +382,20 @@ impl GitCommandExecutor {
     }
 
     let output = child.wait_with_output().map_err(|e| anyhow!("Failed to wait for git command: {e}"))?;
+    // Streamed stdout went to the handler and is not recorded
+    self.audit_log.record(
+      GitInvocation {
+        args,
+        repository_path,
+        env_vars: &[],
+        input: None,
+      },
+      started_at,
+      started.elapsed(),
+      output.status.code(),
+      &[],
+      &output.stderr,
+    );
 
     if output.status.success() {
       tracing::Span::current().record("success", true);
@@ -322,6 +405,98 @@ impl GitCommandExecutor {
     }
   }
 
+  /// Execute a git command reporting progress on stderr (e.g. `fetch --progress`), calling the handler with each progress line.
+  /// Progress is updated in place with carriage returns, every update is passed as a line of its own.
+  #[instrument(
+    skip(self, handler),
+    fields(
+      git_command = args.join(" "),
+      repository_path = repository_path,
+      success = tracing::field::Empty,
+    )
+  )]
+  pub fn execute_command_with_progress<F>(&self, args: &[&str], repository_path: &str, env_vars: &[(&str, &str)], mut handler: F) -> Result<String>
+  where
+    F: FnMut(&str),
+  {
+    use std::io::Read;
+
+    Self::validate_path(repository_path)?;
+    let git_info = self.get_info()?;
+
+    let started_at = SystemTime::now();
+    let started = Instant::now();
+    let mut command = git_command(&git_info.path, repository_path);
+    command.args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
+    Self::apply_env(&mut command, env_vars);
+    let mut child = command.spawn().map_err(|e| anyhow!("Failed to spawn git command: {e}"))?;
+
+    // Read stdout on another thread, so a full pipe doesn't block git while stderr is read
+    let stdout_reader = child.stdout.take().map(|mut stdout| {
+      std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        output
+      })
+    });
+
+    let mut stderr_output = Vec::new();
+    if let Some(mut stderr) = child.stderr.take() {
+      let mut buffer = [0u8; 4096];
+      let mut line = Vec::new();
+      loop {
+        match stderr.read(&mut buffer) {
+          Ok(0) => break, // EOF
+          Ok(n) => {
+            stderr_output.extend_from_slice(&buffer[..n]);
+            for &byte in &buffer[..n] {
+              if byte == b'\r' || byte == b'\n' {
+                if !line.is_empty() {
+                  handler(&String::from_utf8_lossy(&line));
+                  line.clear();
+                }
+              } else {
+                line.push(byte);
+              }
+            }
+          }
+          Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+          Err(e) => return Err(anyhow!("Failed to read stderr: {e}")),
+        }
+      }
+      if !line.is_empty() {
+        handler(&String::from_utf8_lossy(&line));
+      }
+    }
+
+    let status = child.wait().map_err(|e| anyhow!("Failed to wait for git command: {e}"))?;
+    let stdout = stdout_reader.and_then(|reader| reader.join().ok()).unwrap_or_default();
+    let output = Output {
+      status,
+      stdout,
+      stderr: stderr_output,
+    };
+    self.audit_log.record(
+      GitInvocation {
+        args,
+        repository_path,
+        env_vars,
+        input: None,
+      },
+      started_at,
+      started.elapsed(),
+      output.status.code(),
+      &output.stdout,
+      &output.stderr,
+    );
+
+    if output.status.success() {
+      Ok(Self::handle_success(&output))
+    } else {
+      self.handle_error(&output, args)
+    }
+  }
+
   /// Execute a git command and return output as lines, filtering empty lines
   #[instrument(
     skip(self),
@@ -384,12 +559,79 @@ impl GitCommandExecutor {
     Ok(counts)
   }
 
+  /// Look up an object header via a long-lived `git cat-file --batch-check` process.
+  /// `object` is any object name git understands (`HEAD`, `<commit>^`, `<rev>^{tree}`, `<tree>:<path>`).
+  /// Returns `None` when the object does not exist.
+  #[instrument(skip(self))]
+  pub fn cat_file_info(&self, repository_path: &str, object: &str) -> Result<Option<ObjectInfo>> {
+    Self::validate_path(repository_path)?;
+    let git_info = self.get_info()?;
+    let result = self.cat_file.request(&git_info.path, repository_path, object, BatchMode::Check)?;
+    Ok(result.map(|(info, _)| info))
+  }
+
+  /// Read an object header and contents via a long-lived `git cat-file --batch` process.
+  /// Returns `None` when the object does not exist.
+  #[instrument(skip(self))]
+  pub fn cat_file_contents(&self, repository_path: &str, object: &str) -> Result<Option<(ObjectInfo, Vec<u8>)>> {
+    Self::validate_path(repository_path)?;
+    let git_info = self.get_info()?;
+    self.cat_file.request(&git_info.path, repository_path, object, BatchMode::Contents)
+  }
+
+  /// Read at most `limit` bytes of a blob, the rest of a large blob is not read (e.g. to check whether it is binary).
+  /// A missing blob reads as empty.
+  #[instrument(skip(self))]
+  pub fn read_blob_prefix(&self, repository_path: &str, blob_id: &str, limit: usize) -> Result<Vec<u8>> {
+    use std::io::Read;
+    Self::validate_path(repository_path)?;
+    let git_info = self.get_info()?;
+    let mut child = git_command(&git_info.path, repository_path)
+      .args(["cat-file", "blob", blob_id])
+      .stdin(Stdio::null())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::null())
+      .spawn()
+      .map_err(|e| anyhow!("Failed to spawn git command: {e}"))?;
+    let mut content = Vec::with_capacity(limit);
+    let read = match child.stdout.take() {
+      Some(stdout) => stdout.take(limit as u64).read_to_end(&mut content).map(|_| ()),
+      None => Ok(()),
+    };
+    // Stops writing the rest of the blob
+    let _ = child.kill();
+    let _ = child.wait();
+    read.map_err(|e| anyhow!("Failed to read blob {blob_id}: {e}"))?;
+    Ok(content)
+  }
+
+  /// Read and parse a commit object via the long-lived `git cat-file --batch` process.
+  /// The author and message of a commit in another encoding (`i18n.commitEncoding`) are read again by `git show`, which
+  /// converts them to UTF-8.
+  #[instrument(skip(self))]
+  pub fn read_commit(&self, repository_path: &str, commit: &str) -> Result<CommitObject> {
+    let (info, data) = self
+      .cat_file_contents(repository_path, &format!("{commit}^{{commit}}"))?
+      .ok_or_else(|| anyhow!("Commit {commit} does not exist"))?;
+    let mut commit = CommitObject::parse(&data).map_err(|e| anyhow!("Failed to parse commit {}: {e}", info.id))?;
+    if commit.encoding.is_some() {
+      let output = self.execute_command_raw(&["show", "-s", "--encoding=UTF-8", "--format=%an%x00%B", &info.id], repository_path)?;
+      let (author_name, message) = output.split_once('\0').ok_or_else(|| anyhow!("Failed to read commit {}: unexpected output", info.id))?;
+      commit.author_name = author_name.to_string();
+      // `show` ends the message with a newline of its own
+      commit.message = message.strip_suffix('\n').unwrap_or(message).to_string();
+      commit.encoding = None;
+    }
+    Ok(commit)
+  }
+
   /// Resolve the tree object ID for a given revision (commit, ref, or symbolic like HEAD).
   /// This does not cache; callers can cache based on their own rules if needed.
   #[instrument(skip(self))]
   pub fn resolve_tree_id(&self, repository_path: &str, rev: &str) -> Result<String> {
-    let tree_ref = format!("{rev}^{{tree}}");
-    let out = self.execute_command(&["rev-parse", &tree_ref], repository_path)?;
-    Ok(out.trim().to_string())
+    let info = self
+      .cat_file_info(repository_path, &format!("{rev}^{{tree}}"))?
+      .ok_or_else(|| anyhow!("Failed to resolve tree of {rev}: no such revision"))?;
+    Ok(info.id)
   }
 }
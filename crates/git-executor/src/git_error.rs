@@ -0,0 +1,21 @@
+use std::fmt;
+
+/// A git command that exited with a failure status.
+/// Returned (wrapped in `anyhow::Error`) by [`crate::git_command_executor::GitCommandExecutor`], so callers can downcast it
+/// to get the exit code and stderr instead of parsing the message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitCommandError {
+  pub git_path: String,
+  pub args: Vec<String>,
+  /// `None` if git was killed by a signal
+  pub exit_code: Option<i32>,
+  pub stderr: String,
+}
+
+impl fmt::Display for GitCommandError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "git command failed: {} {}\nError: {}", self.git_path, self.args.join(" "), self.stderr)
+  }
+}
+
+impl std::error::Error for GitCommandError {}
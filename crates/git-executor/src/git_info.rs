@@ -1,5 +1,20 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// Oldest supported git version, the first with `cat-file --batch -Z` (2.38); the `zdiff3` conflict style needs 2.35
+pub const MIN_GIT_VERSION: (u32, u32) = (2, 38);
+/// First git version whose `merge-tree --write-tree` accepts `--merge-base`, older versions merge in a temporary index
+pub const MERGE_TREE_WRITE_TREE_VERSION: (u32, u32) = (2, 40);
+/// First git version whose `merge-tree` accepts strategy options (`-X`)
+pub const MERGE_TREE_STRATEGY_OPTIONS_VERSION: (u32, u32) = (2, 44);
+
+#[cfg(windows)]
+const GIT_EXECUTABLE: &str = "git.exe";
+#[cfg(not(windows))]
+const GIT_EXECUTABLE: &str = "git";
+
 #[derive(Debug, Clone)]
 pub struct GitInfo {
   pub version: String,
@@ -37,17 +52,24 @@ impl GitInfo {
     Ok((major, minor))
   }
 
-  /// Check if Git version meets minimum requirement (2.50.0)
-  pub fn validate_minimum_version(&self) -> Result<(), String> {
-    const MIN_MAJOR: u32 = 2;
-    const MIN_MINOR: u32 = 49;
+  /// Whether `git merge-tree --write-tree` accepts `--merge-base` (Git 2.40), older versions merge in a temporary index instead
+  pub fn supports_merge_tree_write_tree(&self) -> bool {
+    self.parse_version().is_ok_and(|version| version >= MERGE_TREE_WRITE_TREE_VERSION)
+  }
 
-    let (major, minor) = self.parse_version()?;
+  /// Whether `git merge-tree` accepts strategy options such as `-Xignore-space-change` (Git 2.44)
+  pub fn supports_merge_tree_strategy_options(&self) -> bool {
+    self.parse_version().is_ok_and(|version| version >= MERGE_TREE_STRATEGY_OPTIONS_VERSION)
+  }
 
-    if major < MIN_MAJOR || (major == MIN_MAJOR && minor < MIN_MINOR) {
+  /// Check if Git version meets the minimum requirement ([`MIN_GIT_VERSION`]), the error says how to get a newer one
+  pub fn validate_minimum_version(&self) -> Result<(), String> {
+    let (min_major, min_minor) = MIN_GIT_VERSION;
+    let version = self.parse_version()?;
+    if version < MIN_GIT_VERSION {
       Err(format!(
-        "Git version {}.{} is too old. Branch Deck requires Git {}.{} or newer. Please upgrade your Git installation.",
-        major, minor, MIN_MAJOR, MIN_MINOR
+        "Git {} at {} is too old, Branch Deck requires Git {min_major}.{min_minor} or newer. {UPGRADE_HINT}, or choose another git installation in the settings.",
+        self.version, self.path
       ))
     } else {
       Ok(())
@@ -55,6 +77,107 @@ impl GitInfo {
   }
 }
 
+#[cfg(target_os = "macos")]
+const UPGRADE_HINT: &str = "Install a newer git with `brew install git`";
+#[cfg(windows)]
+const UPGRADE_HINT: &str = "Install the latest Git for Windows from https://git-scm.com/download/win or with `scoop install git`";
+#[cfg(not(any(target_os = "macos", windows)))]
+const UPGRADE_HINT: &str = "Install a newer git with the package manager of your distribution";
+
+/// Git executable found on this machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct GitInstallation {
+  pub path: String,
+  /// Where it comes from, e.g. `Homebrew`, `Xcode Command Line Tools`, `Scoop` or `PATH`
+  pub source: String,
+  /// `None` if it failed to run
+  pub version: Option<String>,
+  /// Why it can't be used: it failed to run or is older than the minimum version
+  pub error: Option<String>,
+  /// Merges with `merge-tree --write-tree` rather than the slower temporary index
+  pub supports_merge_tree_write_tree: bool,
+}
+
+impl GitInstallation {
+  /// Run `git version` of the executable
+  pub fn probe(path: &str, source: &str) -> Self {
+    let (version, error, supports_merge_tree_write_tree) = match GitInfo::from_path(path) {
+      Ok(info) => (Some(info.version.clone()), info.validate_minimum_version().err(), info.supports_merge_tree_write_tree()),
+      Err(e) => (None, Some(e), false),
+    };
+    Self {
+      path: path.to_string(),
+      source: source.to_string(),
+      version,
+      error,
+      supports_merge_tree_write_tree,
+    }
+  }
+}
+
+/// Git executables at the usual install locations of the platform and in `PATH`, each listed once even if linked from
+/// several places
+pub fn detect_git_installations() -> Vec<GitInstallation> {
+  let mut seen = HashSet::new();
+  candidate_git_paths()
+    .into_iter()
+    .filter(|(path, _)| path.is_file())
+    .filter(|(path, _)| seen.insert(path.canonicalize().unwrap_or_else(|_| path.clone())))
+    .map(|(path, source)| GitInstallation::probe(&path.to_string_lossy(), source))
+    .collect()
+}
+
+fn candidate_git_paths() -> Vec<(PathBuf, &'static str)> {
+  let mut candidates: Vec<(PathBuf, &'static str)> = Vec::new();
+  #[cfg(target_os = "macos")]
+  candidates.extend(
+    [
+      ("/opt/homebrew/bin/git", "Homebrew"),
+      ("/usr/local/bin/git", "Homebrew"),
+      ("/Library/Developer/CommandLineTools/usr/bin/git", "Xcode Command Line Tools"),
+      ("/Applications/Xcode.app/Contents/Developer/usr/bin/git", "Xcode"),
+    ]
+    .map(|(path, source)| (PathBuf::from(path), source)),
+  );
+  #[cfg(windows)]
+  {
+    for (var, dir) in [("ProgramFiles", r"Git\cmd"), ("LOCALAPPDATA", r"Programs\Git\cmd")] {
+      if let Some(base) = std::env::var_os(var) {
+        candidates.push((Path::new(&base).join(dir).join(GIT_EXECUTABLE), "Git for Windows"));
+      }
+    }
+    let scoop = std::env::var_os("SCOOP")
+      .map(PathBuf::from)
+      .or_else(|| std::env::var_os("USERPROFILE").map(|home| Path::new(&home).join("scoop")));
+    if let Some(scoop) = scoop {
+      candidates.push((scoop.join(r"apps\git\current\cmd").join(GIT_EXECUTABLE), "Scoop"));
+    }
+  }
+  #[cfg(not(any(target_os = "macos", windows)))]
+  candidates.extend(
+    [
+      ("/usr/bin/git", "System"),
+      ("/usr/local/bin/git", "Local"),
+      ("/home/linuxbrew/.linuxbrew/bin/git", "Homebrew"),
+    ]
+    .map(|(path, source)| (PathBuf::from(path), source)),
+  );
+
+  if let Some(path) = std::env::var_os("PATH") {
+    for dir in std::env::split_paths(&path) {
+      let git = dir.join(GIT_EXECUTABLE);
+      // A shim opening the installer of the Command Line Tools if they are missing, the real binary is listed above
+      if cfg!(target_os = "macos") && git == Path::new("/usr/bin/git") {
+        continue;
+      }
+      candidates.push((git, "PATH"));
+    }
+  }
+  candidates
+}
+
 /// Execute a command and return its trimmed output as a string
 pub(crate) fn execute_command(command: &mut Command, error_msg: &str) -> Result<String, String> {
   let output = command.output().map_err(|e| format!("{error_msg}: {e}"))?;
@@ -87,14 +210,8 @@ fn get_git_path() -> Result<String, String> {
   // Fall back to system git
   let system_git = execute_command(Command::new("/bin/zsh").args(["-l", "-c", "which git"]), "Could not find git executable")?;
 
-  // Validate system git version
-  let info = GitInfo::from_path(&system_git)?;
-  info.validate_minimum_version().map_err(|e| {
-    format!(
-      "{}\nNote: Homebrew git not found or doesn't meet requirements. Please install/upgrade with: brew install git",
-      e
-    )
-  })?;
+  // Validate system git version, the error suggests Homebrew
+  GitInfo::from_path(&system_git)?.validate_minimum_version()?;
 
   Ok(system_git)
 }
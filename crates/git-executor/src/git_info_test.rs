@@ -0,0 +1,56 @@
+use crate::git_command_executor::GitCommandExecutor;
+use crate::git_info::{GitInfo, GitInstallation, detect_git_installations};
+use std::collections::HashSet;
+
+fn git_info(version: &str) -> GitInfo {
+  GitInfo {
+    version: version.to_string(),
+    path: "/usr/local/bin/git".to_string(),
+  }
+}
+
+#[test]
+fn test_minimum_version() {
+  assert!(git_info("2.38.0").validate_minimum_version().is_ok());
+  assert!(git_info("2.39.3 (Apple Git-146)").validate_minimum_version().is_ok());
+  assert!(git_info("2.37.7").validate_minimum_version().is_err());
+
+  let error = git_info("2.25.1").validate_minimum_version().unwrap_err();
+  assert!(error.contains("Git 2.25.1 at /usr/local/bin/git is too old"), "{error}");
+  assert!(error.contains("requires Git 2.38 or newer"), "{error}");
+}
+
+#[test]
+fn test_merge_tree_write_tree_support() {
+  assert!(!git_info("2.39.3 (Apple Git-146)").supports_merge_tree_write_tree());
+  assert!(git_info("2.40.0").supports_merge_tree_write_tree());
+  assert!(git_info("2.47.1.windows.1").supports_merge_tree_write_tree());
+
+  assert!(!git_info("2.43.0").supports_merge_tree_strategy_options());
+  assert!(git_info("2.44.0").supports_merge_tree_strategy_options());
+}
+
+#[test]
+fn test_detect_git_installations() {
+  let installations = detect_git_installations();
+  // The tests run git from PATH
+  assert!(installations.iter().any(|installation| installation.version.is_some()), "{installations:?}");
+  let paths: HashSet<&str> = installations.iter().map(|installation| installation.path.as_str()).collect();
+  assert_eq!(paths.len(), installations.len());
+}
+
+#[test]
+fn test_probe_missing_executable() {
+  let installation = GitInstallation::probe("/nonexistent/bin/git", "Custom");
+  assert_eq!(installation.version, None);
+  assert!(installation.error.is_some());
+}
+
+#[test]
+fn test_set_git_path_keeps_executable_on_error() {
+  let executor = GitCommandExecutor::new();
+  let before = executor.get_info().unwrap().path;
+
+  assert!(executor.set_git_path(Some("/nonexistent/bin/git")).is_err());
+  assert_eq!(executor.get_info().unwrap().path, before);
+}
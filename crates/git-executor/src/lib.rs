@@ -1,2 +1,15 @@
+pub mod audit_log;
+pub mod cat_file_batch;
 pub mod git_command_executor;
+pub mod git_error;
 pub mod git_info;
+pub mod long_paths;
+
+#[cfg(test)]
+mod audit_log_test;
+#[cfg(test)]
+mod cat_file_batch_test;
+#[cfg(test)]
+mod git_info_test;
+#[cfg(test)]
+mod long_paths_test;
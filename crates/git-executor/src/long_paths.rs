@@ -0,0 +1,32 @@
+//! Repositories at paths longer than the Windows `MAX_PATH` limit.
+//!
+//! Windows can't start a process in a working directory longer than [`MAX_WORKING_DIRECTORY_LEN`] characters, so git
+//! is started without one and gets the repository with `-C` instead. Git for Windows only accesses paths over
+//! `MAX_PATH` (260 characters) in the repository with `core.longpaths`, which is enabled for every command.
+//! Elsewhere git runs in the repository directory as is.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Longest working directory of a new process on Windows: `MAX_PATH` minus room for an 8.3 file name
+pub const MAX_WORKING_DIRECTORY_LEN: usize = 248;
+
+/// Whether the path is too long to be the working directory of a process on Windows
+pub fn is_long_path(path: &str) -> bool {
+  path.encode_utf16().count() >= MAX_WORKING_DIRECTORY_LEN
+}
+
+/// Git command running in the repository
+pub(crate) fn git_command(git_path: &str, repository_path: &str) -> Command {
+  let mut command = Command::new(git_path);
+  if cfg!(windows) {
+    command.args(["-c", "core.longpaths=true"]);
+    if is_long_path(repository_path) {
+      tracing::debug!(repository_path, "Long repository path, passing it with -C");
+      command.arg("-C").arg(Path::new(repository_path));
+      return command;
+    }
+  }
+  command.current_dir(repository_path);
+  command
+}
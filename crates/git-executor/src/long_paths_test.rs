@@ -0,0 +1,41 @@
+use crate::long_paths::{MAX_WORKING_DIRECTORY_LEN, is_long_path};
+
+#[test]
+fn test_is_long_path() {
+  assert!(!is_long_path(r"C:\Users\dev\projects\branch-deck"));
+  assert!(!is_long_path(&format!(r"C:\{}", "a".repeat(MAX_WORKING_DIRECTORY_LEN - 4))));
+  assert!(is_long_path(&format!(r"C:\{}", "a".repeat(MAX_WORKING_DIRECTORY_LEN))));
+  // Windows counts UTF-16 code units, not bytes
+  assert!(!is_long_path(&format!(r"C:\{}", "ä".repeat(MAX_WORKING_DIRECTORY_LEN / 2))));
+}
+
+#[cfg(windows)]
+#[test]
+fn test_repository_at_long_path() {
+  use crate::git_command_executor::GitCommandExecutor;
+
+  let root = std::env::temp_dir().join(format!("branch-deck-long-path-{}", std::process::id()));
+  let mut repository = root.clone();
+  while repository.as_os_str().len() < 300 {
+    repository.push("nested-directory-to-exceed-max-path");
+  }
+  std::fs::create_dir_all(&repository).unwrap();
+  let repository_path = repository.to_str().unwrap();
+  assert!(is_long_path(repository_path));
+
+  let git_executor = GitCommandExecutor::new();
+  let result = (|| -> anyhow::Result<String> {
+    git_executor.execute_command(&["init", "--quiet"], repository_path)?;
+    std::fs::write(repository.join("file.txt"), "content\n")?;
+    git_executor.execute_command(&["add", "file.txt"], repository_path)?;
+    git_executor.execute_command(
+      &["-c", "user.name=Test", "-c", "user.email=test@example.com", "commit", "--quiet", "-m", "Initial commit"],
+      repository_path,
+    )?;
+    let tree_id = git_executor.resolve_tree_id(repository_path, "HEAD")?;
+    git_executor.execute_command(&["ls-tree", "--name-only", &tree_id], repository_path)
+  })();
+  let _ = std::fs::remove_dir_all(&root);
+
+  assert_eq!(result.unwrap(), "file.txt");
+}
@@ -12,15 +12,31 @@ pub enum RewriteAction {
   Skip,
   /// Replace the commit's tree with the provided tree ID
   Modify(String),
+  /// Replace the commit's tree and message, e.g. with the combined changes of squashed commits
+  Replace { tree_id: String, message: String },
+}
+
+/// History rewritten by [`rewrite_commits`]
+#[derive(Debug)]
+pub struct RewrittenCommits {
+  pub new_head: String,
+  /// Old commit ID to new commit ID for every recreated commit, dropped commits are not included
+  pub commit_mapping: HashMap<String, String>,
 }
 
 use crate::cache::TreeIdCache;
+use crate::case_collision::{check_case_collisions, load_ignore_case};
 use crate::cherry_pick::get_commit_parent;
-use crate::commit_utils::{create_commit_with_metadata, prefetch_commit_infos_map};
+use crate::commit_dependencies::carry_over_commit_dependencies;
+use crate::commit_utils::{create_commit_with_metadata, prefetch_commit_infos_map, resolve_commit_signing};
+use crate::conflict_analysis::{FileDiff, get_tree_file_diffs};
 use crate::copy_commit::CopyCommitError;
+use crate::diff_options::{DiffOptions, load_diff_options};
 use crate::merge_conflict::{ConflictDetailsParams, ConflictFileInfo, extract_conflict_details};
+use crate::merge_tree::merge_trees;
 use crate::model::{BranchError, MergeConflictInfo};
 use crate::reword_commits::{get_commit_info, update_branch_ref as update_ref_plumbing};
+use crate::temp_dir::new_temp_path;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
@@ -35,7 +51,7 @@ fn rewrite_commits<F>(
   main_branch: &str,
   transform: F,
   cache: &TreeIdCache,
-) -> Result<String, CopyCommitError>
+) -> Result<RewrittenCommits, CopyCommitError>
 where
   F: Fn(&str) -> Result<RewriteAction, CopyCommitError>,
 {
@@ -66,14 +82,22 @@ where
 
   if commits_to_process.is_empty() {
     // Nothing to rewrite
-    return Ok(start_commit.to_string());
+    return Ok(RewrittenCommits {
+      new_head: start_commit.to_string(),
+      commit_mapping: HashMap::new(),
+    });
   }
 
+  // Rewritten commits are re-signed if the repository opted in
+  let signing = resolve_commit_signing(git_executor, repo_path).map_err(CopyCommitError::Other)?;
+  let ignore_case = load_ignore_case(git_executor, repo_path);
+
   // Start rewriting from the start commit
   let mut current_parent = start_commit.to_string();
 
   // Track if any commits were changed to determine when conflict detection is needed
   let mut has_changes = false;
+  let mut commit_mapping = HashMap::with_capacity(commits_to_process.len());
 
   // Process each commit
   for (commit, parent_of_commit) in &commits_to_process {
@@ -103,7 +127,7 @@ where
           let ours_tree = cache.get_tree_id(git_executor, repo_path, &current_parent)?;
           let theirs_tree = cache.get_tree_id(git_executor, repo_path, commit)?;
 
-          // Optimization: check if trees already match to avoid merge-tree
+          // Optimization: check if trees already match to avoid a three-way merge
           if base_tree == ours_tree {
             // Parent tree matches current tree, just reuse commit tree
             theirs_tree
@@ -114,33 +138,38 @@ where
             // The commit being replayed changed nothing relative to base; keep our tree
             ours_tree
           } else {
-            // Use merge-tree to compute the new tree
-            let merge_base_arg = format!("--merge-base={}", base_tree);
-            let (merged_out, status) = git_executor
-              .execute_command_with_status(&["merge-tree", "--write-tree", &merge_base_arg, &ours_tree, &theirs_tree], repo_path)
-              .map_err(CopyCommitError::Other)?;
-
-            if status == 1 {
-              // Conflict detected
+            // Use a three-way merge to compute the new tree
+            let merge = merge_trees(git_executor, repo_path, &base_tree, &ours_tree, &theirs_tree, &DiffOptions::default()).map_err(CopyCommitError::Other)?;
+            if merge.has_conflicts() {
               return Err(CopyCommitError::BranchError(BranchError::Generic(format!(
                 "Rewriting would create conflicts when replaying commit {}: {}",
                 &commit[..commit.len().min(8)],
                 commit_info.subject.trim()
               ))));
-            } else if status != 0 {
-              return Err(CopyCommitError::Other(anyhow!("git merge-tree failed while rewriting: {}", merged_out.trim())));
+            }
+            if ignore_case {
+              check_case_collisions(git_executor, repo_path, &ours_tree, &merge.tree_id, commit, commit_info.subject.trim())?;
             }
 
-            merged_out.trim().to_string()
+            merge.tree_id
           }
         };
 
-        current_parent =
-          create_commit_with_metadata(git_executor, repo_path, &new_tree, Some(&current_parent), &commit_info, &commit_info.message).map_err(CopyCommitError::Other)?;
+        current_parent = create_commit_with_metadata(
+          git_executor,
+          repo_path,
+          &new_tree,
+          Some(&current_parent),
+          &commit_info,
+          &commit_info.message,
+          signing.as_ref(),
+        )
+        .map_err(CopyCommitError::Other)?;
+        commit_mapping.insert(commit.clone(), current_parent.clone());
       }
 
-      RewriteAction::Modify(new_tree) => {
-        // Use the provided tree for this commit
+      RewriteAction::Modify(_) | RewriteAction::Replace { .. } => {
+        // Use the provided tree (and message) for this commit
         // Mark that we have changes since this commit was modified
         has_changes = true;
 
@@ -148,8 +177,14 @@ where
           Some(ci) => ci,
           None => get_commit_info(git_executor, repo_path, commit).map_err(CopyCommitError::Other)?,
         };
+        let (new_tree, message) = match action {
+          RewriteAction::Replace { tree_id, message } => (tree_id, message),
+          RewriteAction::Modify(tree_id) => (tree_id, commit_info.message.clone()),
+          RewriteAction::Keep | RewriteAction::Skip => unreachable!(),
+        };
         current_parent =
-          create_commit_with_metadata(git_executor, repo_path, &new_tree, Some(&current_parent), &commit_info, &commit_info.message).map_err(CopyCommitError::Other)?;
+          create_commit_with_metadata(git_executor, repo_path, &new_tree, Some(&current_parent), &commit_info, &message, signing.as_ref()).map_err(CopyCommitError::Other)?;
+        commit_mapping.insert(commit.clone(), current_parent.clone());
       }
     }
   }
@@ -174,7 +209,10 @@ where
     }
   }
 
-  Ok(current_parent)
+  Ok(RewrittenCommits {
+    new_head: current_parent,
+    commit_mapping,
+  })
 }
 
 // prefetch_commit_infos_map is defined in commit_utils
@@ -182,7 +220,7 @@ where
 /// Drop specified commits from HEAD while preserving working directory changes
 /// Uses the generic rewrite_commits function
 #[instrument(skip(git_executor))]
-pub fn drop_commits_from_head(git_executor: &GitCommandExecutor, repo_path: &str, commit_ids_to_drop: &[String], main_branch: &str) -> Result<String, CopyCommitError> {
+pub fn drop_commits_from_head(git_executor: &GitCommandExecutor, repo_path: &str, commit_ids_to_drop: &[String], main_branch: &str) -> Result<RewrittenCommits, CopyCommitError> {
   if commit_ids_to_drop.is_empty() {
     return Err(CopyCommitError::Other(anyhow!("No commits specified to drop")));
   }
@@ -214,14 +252,117 @@ pub fn drop_commits_from_head(git_executor: &GitCommandExecutor, repo_path: &str
   let cache = TreeIdCache::new();
 
   // Use the generic rewrite_commits function with a filter that skips commits to drop
-  rewrite_commits(
+  let rewritten = rewrite_commits(
     git_executor,
     repo_path,
     &base_commit,
     main_branch,
     |commit| if drop_set.contains(commit) { Ok(RewriteAction::Skip) } else { Ok(RewriteAction::Keep) },
     &cache,
-  )
+  )?;
+  carry_over_commit_dependencies(git_executor, repo_path, &rewritten.commit_mapping);
+  Ok(rewritten)
+}
+
+/// Result of squashing commits of HEAD
+#[derive(Debug)]
+pub struct SquashedCommits {
+  /// Commit with the combined changes, at the position of the oldest squashed commit
+  pub squashed_commit_id: String,
+  pub new_head: String,
+  /// Old commit ID to new commit ID, every squashed commit maps to the squashed one
+  pub commit_mapping: HashMap<String, String>,
+}
+
+/// Squash commits of HEAD into one commit at the position of the oldest of them, with the given message.
+/// Commits in between are kept and replayed on top of the combined changes.
+#[instrument(skip(git_executor, message))]
+pub fn squash_commits_in_head(
+  git_executor: &GitCommandExecutor,
+  repo_path: &str,
+  commit_ids: &[String],
+  message: &str,
+  main_branch: &str,
+) -> Result<SquashedCommits, CopyCommitError> {
+  if commit_ids.len() < 2 {
+    return Err(CopyCommitError::Other(anyhow!("At least two commits are required to squash")));
+  }
+
+  // Oldest first, so the changes are combined in their original order
+  let all_commits = git_executor
+    .execute_command_lines(&["rev-list", "--first-parent", "--reverse", "HEAD"], repo_path)
+    .map_err(CopyCommitError::Other)?;
+  let squash_set: HashSet<&str> = commit_ids.iter().map(|s| s.as_str()).collect();
+  let ordered: Vec<&str> = all_commits.iter().map(String::as_str).filter(|commit| squash_set.contains(commit)).collect();
+  if ordered.len() != squash_set.len() {
+    return Err(CopyCommitError::Other(anyhow!("Some of the commits to squash are not found in HEAD")));
+  }
+
+  let oldest_commit = ordered[0];
+  let base_commit =
+    get_commit_parent(git_executor, repo_path, oldest_commit).map_err(|e| CopyCommitError::Other(anyhow!("Failed to get parent of oldest commit to squash: {}", e)))?;
+
+  let cache = TreeIdCache::new();
+  let ignore_case = load_ignore_case(git_executor, repo_path);
+  // Apply the change of each commit on top of the previous ones
+  let mut squashed_tree = cache.get_tree_id(git_executor, repo_path, &base_commit)?;
+  for commit in &ordered {
+    let parent = get_commit_parent(git_executor, repo_path, commit).map_err(CopyCommitError::Other)?;
+    let parent_tree = cache.get_tree_id(git_executor, repo_path, &parent)?;
+    let commit_tree = cache.get_tree_id(git_executor, repo_path, commit)?;
+    if parent_tree == squashed_tree {
+      squashed_tree = commit_tree;
+      continue;
+    }
+
+    let merge = merge_trees(git_executor, repo_path, &parent_tree, &squashed_tree, &commit_tree, &DiffOptions::default()).map_err(CopyCommitError::Other)?;
+    if merge.has_conflicts() {
+      return Err(CopyCommitError::BranchError(BranchError::Generic(format!(
+        "Squashing would create conflicts when combining commit {}",
+        &commit[..commit.len().min(8)]
+      ))));
+    }
+    if ignore_case {
+      let subject = get_commit_info(git_executor, repo_path, commit).map(|info| info.subject).unwrap_or_default();
+      check_case_collisions(git_executor, repo_path, &squashed_tree, &merge.tree_id, commit, subject.trim())?;
+    }
+    squashed_tree = merge.tree_id;
+  }
+
+  let RewrittenCommits { new_head, mut commit_mapping } = rewrite_commits(
+    git_executor,
+    repo_path,
+    &base_commit,
+    main_branch,
+    |commit| {
+      if commit == oldest_commit {
+        Ok(RewriteAction::Replace {
+          tree_id: squashed_tree.clone(),
+          message: message.to_string(),
+        })
+      } else if squash_set.contains(commit) {
+        Ok(RewriteAction::Skip)
+      } else {
+        Ok(RewriteAction::Keep)
+      }
+    },
+    &cache,
+  )?;
+
+  let squashed_commit_id = commit_mapping
+    .get(oldest_commit)
+    .cloned()
+    .ok_or_else(|| CopyCommitError::Other(anyhow!("No commits found after squashing")))?;
+  for commit in &ordered[1..] {
+    commit_mapping.insert(commit.to_string(), squashed_commit_id.clone());
+  }
+  carry_over_commit_dependencies(git_executor, repo_path, &commit_mapping);
+
+  Ok(SquashedCommits {
+    squashed_commit_id,
+    new_head,
+    commit_mapping,
+  })
 }
 
 /// Parameters for amending uncommitted changes to a specific commit in main branch
@@ -238,6 +379,53 @@ pub struct AmendToCommitParams {
 pub struct AmendResult {
   pub amended_commit_id: String,
   pub rebased_to_commit: String,
+  /// Old commit ID to new commit ID for the amended commit and every descendant recreated on top of it,
+  /// empty if the history was rewritten by a fixup rebase
+  #[serde(skip)]
+  pub commit_mapping: HashMap<String, String>,
+}
+
+/// Would-be result of amending uncommitted changes to a commit, computed without touching refs, the index or the working tree
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct AmendPreview {
+  /// Tree of the commit after amending
+  pub amended_tree_id: String,
+  /// Files of the commit changed by the amend, the commit as is vs. the commit after amending
+  pub file_diffs: Vec<FileDiff>,
+  /// Files whose changes don't apply cleanly to the commit, the amend would stop with a conflict
+  pub conflicting_files: Vec<String>,
+}
+
+/// Preview [`amend_to_commit_in_main`]: the diff of the target commit's tree before and after amending the given files.
+/// HEAD and linear histories replace the files in the commit; otherwise the changes are applied as a patch, like the fixup rebase does.
+#[instrument(skip(git_executor, diff_options), fields(original_commit = %params.original_commit_id))]
+pub fn preview_amend(git_executor: &GitCommandExecutor, repo_path: &str, params: &AmendToCommitParams, diff_options: &DiffOptions) -> Result<AmendPreview, CopyCommitError> {
+  let AmendToCommitParams { original_commit_id, files } = params;
+  let cache = TreeIdCache::new();
+  let original_tree = cache.get_tree_id(git_executor, repo_path, original_commit_id)?;
+
+  let current_head = git_executor.execute_command(&["rev-parse", "HEAD"], repo_path)?.trim().to_string();
+  let (amended_tree_id, conflicting_files) = if current_head == *original_commit_id || is_linear_range(git_executor, repo_path, original_commit_id, "HEAD")? {
+    (compute_amended_tree(git_executor, repo_path, original_commit_id, files, &cache)?, Vec::new())
+  } else {
+    let changed_head_tree = compute_amended_tree(git_executor, repo_path, &current_head, files, &cache)?;
+    let merge = merge_trees(git_executor, repo_path, &current_head, &original_tree, &changed_head_tree, diff_options)?;
+    let conflicting_files: HashSet<String> = merge.conflicts.into_iter().map(|conflict| conflict.path).collect();
+    let mut conflicting_files: Vec<String> = conflicting_files.into_iter().collect();
+    conflicting_files.sort();
+    (merge.tree_id, conflicting_files)
+  };
+
+  let file_diffs = get_tree_file_diffs(git_executor, repo_path, &original_tree, &amended_tree_id, None, diff_options)?;
+
+  debug!(file_count = file_diffs.len(), conflict_count = conflicting_files.len(), "computed amend preview");
+  Ok(AmendPreview {
+    amended_tree_id,
+    file_diffs,
+    conflicting_files,
+  })
 }
 
 /// Amend uncommitted changes to a specific commit in current branch history
@@ -274,7 +462,10 @@ pub fn amend_to_commit_in_main(git_executor: &GitCommandExecutor, repo_path: &st
 
     let final_commit = git_executor.execute_command(&["rev-parse", "HEAD"], repo_path)?;
     let final_commit = final_commit.trim().to_string();
+    let commit_mapping = HashMap::from([(original_commit_id, final_commit.clone())]);
+    carry_over_commit_dependencies(git_executor, repo_path, &commit_mapping);
     return Ok(AmendResult {
+      commit_mapping,
       amended_commit_id: final_commit.clone(),
       rebased_to_commit: final_commit,
     });
@@ -286,7 +477,9 @@ pub fn amend_to_commit_in_main(git_executor: &GitCommandExecutor, repo_path: &st
   // Prefer a fast object-only rewrite for linear histories; fall back to fixup+autosquash otherwise
   let is_linear = is_linear_range(git_executor, repo_path, &original_commit_id, "HEAD")?;
   if is_linear {
-    return fast_amend_linear(git_executor, repo_path, &original_commit_id, &files, &cache);
+    let result = fast_amend_linear(git_executor, repo_path, &original_commit_id, &files, &cache)?;
+    carry_over_commit_dependencies(git_executor, repo_path, &result.commit_mapping);
+    return Ok(result);
   }
 
   // Fall back: fixup + autosquash rebase
@@ -374,11 +567,13 @@ pub fn amend_to_commit_in_main(git_executor: &GitCommandExecutor, repo_path: &st
   Ok(AmendResult {
     amended_commit_id,
     rebased_to_commit: final_commit,
+    // Which commits the rebase recreated is not known
+    commit_mapping: HashMap::new(),
   })
 }
 
 /// Check if amending to the given commit would create conflicts
-/// Uses conflict analysis and git merge-tree to detect actual conflicts
+/// Uses conflict analysis and a three-way tree merge to detect actual conflicts
 #[instrument(skip(git_executor), fields(original_commit = %original_commit_id))]
 pub fn check_amend_conflicts(git_executor: &GitCommandExecutor, repo_path: &str, main_branch: &str, original_commit_id: &str) -> Result<()> {
   // Get commits between original commit and main branch HEAD
@@ -401,26 +596,34 @@ pub fn check_amend_conflicts(git_executor: &GitCommandExecutor, repo_path: &str,
   let working_tree = git_executor.execute_command(&["write-tree"], repo_path)?;
   let working_tree = working_tree.trim();
 
+  // The amended files may only differ in line endings when core.autocrlf converts them
+  let merge_options = DiffOptions::for_conflict_checks(git_executor, repo_path);
   // For each affected commit, check if it would conflict with the amended version
   for commit_hash in affected_commits.iter().copied() {
     debug!(commit = %commit_hash, "checking for conflicts");
 
-    // Use git merge-tree to check if this commit would conflict
-    // when rebased onto the amended version
-    // Note: git merge-tree syntax is: git merge-tree --write-tree --merge-base=<base> <branch1> <branch2>
-    let merge_base_arg = format!("--merge-base={}", original_commit_id);
-    let merge_tree_args = vec![
-      "merge-tree",
-      "--write-tree",
-      &merge_base_arg,
-      &working_tree, // our amended version
-      commit_hash,   // the commit being rebased
-    ];
-
-    // Use status-aware execution: exit code 1 from merge-tree means conflicts
-    let (output_or_stderr, exit_code) = git_executor.execute_command_with_status(&merge_tree_args, repo_path)?;
-
-    if exit_code == 1 {
+    // Check if this commit would conflict when rebased onto the amended version
+    let has_conflicts = match merge_trees(git_executor, repo_path, original_commit_id, working_tree, commit_hash, &merge_options) {
+      Ok(merge) => merge.has_conflicts(),
+      Err(e) => {
+        // Some other failure: be conservative and report inability to guarantee safety
+        let commit_subject = git_executor
+          .execute_command(&["log", "-1", "--format=%s", commit_hash], repo_path)
+          .unwrap_or_default()
+          .trim()
+          .to_string();
+        let short_hash = if commit_hash.len() >= 8 { &commit_hash[..8] } else { commit_hash };
+        debug!(error = %e, "tree merge failed");
+        return Err(anyhow!(
+          "Cannot safely amend: commit {} ({}) may have conflicts. {} commit(s) would be rebased.",
+          short_hash,
+          commit_subject,
+          affected_commits.len()
+        ));
+      }
+    };
+
+    if has_conflicts {
       // Conflicts detected for this commit
       let commit_subject = git_executor
         .execute_command(&["log", "-1", "--format=%s", commit_hash], repo_path)
@@ -434,21 +637,6 @@ pub fn check_amend_conflicts(git_executor: &GitCommandExecutor, repo_path: &str,
         commit_subject,
         affected_commits.len() - 1
       ));
-    } else if exit_code != 0 {
-      // Some other failure: be conservative and report inability to guarantee safety
-      let commit_subject = git_executor
-        .execute_command(&["log", "-1", "--format=%s", commit_hash], repo_path)
-        .unwrap_or_default()
-        .trim()
-        .to_string();
-      let short_hash = if commit_hash.len() >= 8 { &commit_hash[..8] } else { commit_hash };
-      debug!(exit_code, output = %output_or_stderr, "merge-tree returned unexpected status");
-      return Err(anyhow!(
-        "Cannot safely amend: commit {} ({}) may have conflicts. {} commit(s) would be rebased.",
-        short_hash,
-        commit_subject,
-        affected_commits.len()
-      ));
     }
   }
 
@@ -520,6 +708,7 @@ fn extract_amend_conflict_info(git_executor: &GitCommandExecutor, repo_path: &st
   let current_head = current_head.trim().to_string();
 
   // Step 4: Extract detailed conflict information using existing function
+  let diff_options = load_diff_options(git_executor, repo_path);
   let (detailed_conflicts, conflict_marker_commits) = extract_conflict_details(ConflictDetailsParams {
     git_executor,
     repo_path,
@@ -528,11 +717,12 @@ fn extract_amend_conflict_info(git_executor: &GitCommandExecutor, repo_path: &st
     parent_commit_id: original_commit_id,
     target_commit_id: &current_head,
     cherry_commit_id: &current_head, // In amend case, this is the same as target
+    diff_options: &diff_options,
   })?;
 
   // Step 5: Analyze conflicts to find missing commits (commits between original and current HEAD)
   let conflicting_paths: Vec<PathBuf> = conflict_files.keys().cloned().collect();
-  let conflict_analysis = match crate::conflict_analysis::analyze_conflict(git_executor, repo_path, original_commit_id, &current_head, &conflicting_paths) {
+  let conflict_analysis = match crate::conflict_analysis::analyze_conflict(git_executor, repo_path, original_commit_id, &current_head, &conflicting_paths, &diff_options) {
     Ok(analysis) => analysis,
     Err(e) => {
       debug!(error = %e, "failed to analyze amend conflicts");
@@ -629,11 +819,10 @@ struct TempIndexGuard {
 }
 
 impl TempIndexGuard {
-  fn new() -> Self {
-    let tdir = std::env::temp_dir();
-    let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos();
-    let path = tdir.join(format!("branchdeck_amend_{nanos}.idx"));
-    Self { path }
+  fn new(repo_path: &str) -> Self {
+    let mut path = new_temp_path(repo_path, "amend").into_os_string();
+    path.push(".idx");
+    Self { path: path.into() }
   }
 
   fn path_str(&self) -> &str {
@@ -660,7 +849,7 @@ fn compute_amended_tree(git_executor: &GitCommandExecutor, repo_path: &str, orig
   let original_tree = cache.get_tree_id(git_executor, repo_path, original_commit_id)?;
 
   // Create temporary index file with RAII cleanup
-  let tmp_idx = TempIndexGuard::new();
+  let tmp_idx = TempIndexGuard::new(repo_path);
 
   // Start with the original commit's tree in the temporary index
   git_executor
@@ -721,8 +910,9 @@ fn fast_amend_linear(git_executor: &GitCommandExecutor, repo_path: &str, origina
     // For root commits, we need to handle this specially since rewrite_commits expects a parent
     // First create the amended root commit
     let original_commit = get_commit_info(git_executor, repo_path, original_commit_id).map_err(CopyCommitError::Other)?;
+    let signing = resolve_commit_signing(git_executor, repo_path).map_err(CopyCommitError::Other)?;
     let amended_commit_id =
-      create_commit_with_metadata(git_executor, repo_path, &amended_tree, None, &original_commit, &original_commit.message).map_err(CopyCommitError::Other)?;
+      create_commit_with_metadata(git_executor, repo_path, &amended_tree, None, &original_commit, &original_commit.message, signing.as_ref()).map_err(CopyCommitError::Other)?;
 
     // Check if there are any descendants
     let range = format!("{}..HEAD", original_commit_id);
@@ -742,6 +932,7 @@ fn fast_amend_linear(git_executor: &GitCommandExecutor, repo_path: &str, origina
       }
 
       return Ok(AmendResult {
+        commit_mapping: HashMap::from([(original_commit_id.to_string(), amended_commit_id.clone())]),
         amended_commit_id: amended_commit_id.clone(),
         rebased_to_commit: amended_commit_id,
       });
@@ -752,6 +943,7 @@ fn fast_amend_linear(git_executor: &GitCommandExecutor, repo_path: &str, origina
     let desc_range = format!("{}..HEAD", original_commit_id);
     let info_map = prefetch_commit_infos_map(git_executor, repo_path, &desc_range)?;
     let mut current_parent = amended_commit_id.clone();
+    let mut commit_mapping = HashMap::from([(original_commit_id.to_string(), amended_commit_id.clone())]);
     for commit in descendants {
       // Keep each descendant by rewriting it with the new parent
       let commit_info = match info_map.get(&commit).cloned() {
@@ -762,7 +954,9 @@ fn fast_amend_linear(git_executor: &GitCommandExecutor, repo_path: &str, origina
       // Get the tree of this commit
       let tree = cache.get_tree_id(git_executor, repo_path, &commit)?;
 
-      current_parent = create_commit_with_metadata(git_executor, repo_path, &tree, Some(&current_parent), &commit_info, &commit_info.message).map_err(CopyCommitError::Other)?;
+      current_parent =
+        create_commit_with_metadata(git_executor, repo_path, &tree, Some(&current_parent), &commit_info, &commit_info.message, signing.as_ref()).map_err(CopyCommitError::Other)?;
+      commit_mapping.insert(commit, current_parent.clone());
     }
 
     // Update the branch ref to the new tip
@@ -778,6 +972,7 @@ fn fast_amend_linear(git_executor: &GitCommandExecutor, repo_path: &str, origina
     return Ok(AmendResult {
       amended_commit_id,
       rebased_to_commit: current_parent,
+      commit_mapping,
     });
   }
 
@@ -787,7 +982,7 @@ fn fast_amend_linear(git_executor: &GitCommandExecutor, repo_path: &str, origina
   // Use the generic rewrite_commits function with a transform that modifies the target commit
   let original_commit_id_owned = original_commit_id.to_string();
   let amended_tree_clone = amended_tree.clone();
-  let new_head = rewrite_commits(
+  let RewrittenCommits { new_head, commit_mapping } = rewrite_commits(
     git_executor,
     repo_path,
     &parent,
@@ -802,21 +997,15 @@ fn fast_amend_linear(git_executor: &GitCommandExecutor, repo_path: &str, origina
     cache,
   )?;
 
-  // Find the amended commit ID in the rewritten history
-  // The amended commit should be the first one after parent
-  let range = format!("{}..{}", parent, new_head);
-  let commits = git_executor
-    .execute_command_lines(&["rev-list", "--first-parent", "--reverse", &range], repo_path)
-    .map_err(CopyCommitError::Other)?;
-
-  let amended_commit_id = commits
-    .first()
-    .ok_or_else(|| CopyCommitError::Other(anyhow!("No commits found after rewriting")))?
-    .to_string();
+  let amended_commit_id = commit_mapping
+    .get(original_commit_id)
+    .cloned()
+    .ok_or_else(|| CopyCommitError::Other(anyhow!("No commits found after rewriting")))?;
 
   Ok(AmendResult {
     amended_commit_id,
     rebased_to_commit: new_head,
+    commit_mapping,
   })
 }
 
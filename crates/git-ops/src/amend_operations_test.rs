@@ -1,4 +1,5 @@
-use super::amend_operations::{AmendToCommitParams, amend_to_commit_in_main, check_amend_conflicts};
+use super::amend_operations::{AmendToCommitParams, amend_to_commit_in_main, check_amend_conflicts, preview_amend};
+use crate::diff_options::DiffOptions;
 use anyhow::Result;
 use git_executor::git_command_executor::GitCommandExecutor;
 use std::fs;
@@ -210,6 +211,29 @@ fn test_conflict_check_with_file_conflicts() -> Result<()> {
   Ok(())
 }
 
+#[test]
+fn test_conflict_check_ignores_line_endings_with_autocrlf() -> Result<()> {
+  let repo = TestRepository::new()?;
+  // Older gits merge without -Xignore-cr-at-eol
+  if !repo.git.get_info()?.supports_merge_tree_strategy_options() {
+    return Ok(());
+  }
+  // Commit the line endings as written, whatever the machine's config is
+  repo.git.execute_command(&["config", "core.autocrlf", "false"], &repo.path)?;
+
+  let commit1 = repo.commit_file("file1.txt", "line 1\nline 2\nline 3\n", "Initial commit")?;
+  // Only the line endings change
+  repo.commit_file("file1.txt", "line 1\r\nline 2\r\nline 3\r\n", "Convert to CRLF")?;
+  repo.modify_file("file1.txt", "line 1\nmodified line 2\nline 3\n")?;
+
+  assert!(check_amend_conflicts(&repo.git, &repo.path, "master", &commit1).is_err());
+
+  repo.git.execute_command(&["config", "core.autocrlf", "true"], &repo.path)?;
+  check_amend_conflicts(&repo.git, &repo.path, "master", &commit1)?;
+
+  Ok(())
+}
+
 #[test]
 fn test_working_directory_preserved() -> Result<()> {
   let repo = TestRepository::new()?;
@@ -496,6 +520,7 @@ fn test_amend_conflict_returns_merge_conflict_info() -> Result<()> {
           assert!(msg.contains("conflict") || msg.contains("Rebase"));
           println!("✓ Conflicts properly detected with message: {}", msg);
         }
+        crate::model::BranchError::CaseCollision(info) => panic!("Unexpected case collision: {}", info.explanation()),
       }
     }
     crate::copy_commit::CopyCommitError::Other(other_err) => {
@@ -508,3 +533,35 @@ fn test_amend_conflict_returns_merge_conflict_info() -> Result<()> {
 
   Ok(())
 }
+
+#[test]
+fn test_preview_amend_matches_amend() -> Result<()> {
+  let repo = TestRepository::new()?;
+  let commit1 = repo.commit_file("file1.txt", "initial content\n", "Initial commit")?;
+  let head = repo.commit_file("file2.txt", "second file\n", "Add second file")?;
+  repo.modify_file_without_staging("file1.txt", "amended content\n")?;
+
+  let params = AmendToCommitParams {
+    original_commit_id: commit1.clone(),
+    files: vec!["file1.txt".to_string()],
+  };
+  let preview = preview_amend(&repo.git, &repo.path, &params, &DiffOptions::default())?;
+
+  assert!(preview.conflicting_files.is_empty());
+  assert_eq!(preview.file_diffs.len(), 1);
+  let file_diff = &preview.file_diffs[0];
+  assert_eq!(file_diff.old_file.file_name, "file1.txt");
+  assert_eq!(file_diff.old_file.content, "initial content\n");
+  assert_eq!(file_diff.new_file.content, "amended content\n");
+  assert!(file_diff.hunks[0].contains("+amended content"));
+
+  // Nothing is rewritten and the change is still uncommitted
+  assert_eq!(repo.git.execute_command(&["rev-parse", "HEAD"], &repo.path)?, head);
+  assert_eq!(repo.git.execute_command(&["status", "--porcelain"], &repo.path)?, "M file1.txt");
+
+  let result = amend_to_commit_in_main(&repo.git, &repo.path, params)?;
+  let amended_tree = repo.git.execute_command(&["rev-parse", &format!("{}^{{tree}}", result.amended_commit_id)], &repo.path)?;
+  assert_eq!(amended_tree, preview.amended_tree_id);
+
+  Ok(())
+}
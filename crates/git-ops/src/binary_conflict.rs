@@ -0,0 +1,273 @@
+//! Binary file conflicts: detection, and resolutions chosen by the user.
+//!
+//! A binary file can't be merged line by line, so a resolution is either one of the versions (ours, theirs or base) or a
+//! replacement file. It is recorded under [`BINARY_RESOLUTION_REF_PREFIX`], keyed by the path and the conflicting
+//! versions, and applied by every later cherry-pick that runs into the same conflict, similar to `git rerere`. Text
+//! files merged with an external merge tool (see [`crate::merge_tool`]) are recorded the same way.
+//!
+//! The ref points to a tag of the resolved blob, dated when the resolution was last recorded or applied. Resolutions
+//! not used for [`BINARY_RESOLUTION_RETENTION_DAYS`] are pruned, the commits running into them are gone by then.
+
+use crate::cherry_pick::get_commit_parent;
+use crate::diff_options::DiffOptions;
+use crate::merge_tree::{ConflictEntry, TreeMerge, merge_trees};
+use crate::ref_transaction::RefTransaction;
+use crate::temp_dir::TempDirGuard;
+use anyhow::{Result, anyhow, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, instrument, warn};
+
+/// Refs pointing to the resolved blobs, which also keeps replacement blobs from being garbage collected
+pub const BINARY_RESOLUTION_REF_PREFIX: &str = "refs/branch-deck/binary-resolutions/";
+
+/// Resolutions neither recorded nor applied for this many days are pruned
+pub const BINARY_RESOLUTION_RETENTION_DAYS: u64 = 30;
+
+/// An applied resolution is dated again at most once a day, not on every sync
+const RESOLUTION_REFRESH_SECS: u64 = 24 * 60 * 60;
+
+/// Git treats content with a NUL byte in its first 8000 bytes as binary
+const BINARY_CHECK_LENGTH: usize = 8000;
+
+/// Version of a binary file that resolves its conflict
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+pub enum BinaryConflictResolution {
+  /// Version of the branch the commit is applied onto
+  Ours,
+  /// Version of the cherry-picked commit
+  Theirs,
+  /// Version of the merge base
+  Base,
+  /// Content of a local file
+  Replacement(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveBinaryConflictParams {
+  pub repository_path: String,
+  /// Conflicting commit (`commitHash` of the merge conflict)
+  pub commit_hash: String,
+  /// Commit it was applied onto (`targetBranchHash` of the merge conflict)
+  pub target_commit_hash: String,
+  /// Path of the conflicting binary file
+  pub file: String,
+  pub resolution: BinaryConflictResolution,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveBinaryConflictResult {
+  /// Blob the file gets in the resolved commit
+  pub blob_id: String,
+}
+
+pub fn is_binary_content(content: &[u8]) -> bool {
+  content[..content.len().min(BINARY_CHECK_LENGTH)].contains(&0)
+}
+
+/// Whether any of the given blobs is binary; missing blobs (deleted or added on one side) are skipped
+pub fn is_binary_conflict<'a>(git_executor: &GitCommandExecutor, repo_path: &str, blob_ids: impl IntoIterator<Item = &'a str>) -> bool {
+  blob_ids.into_iter().any(|blob_id| is_binary_blob(git_executor, repo_path, blob_id))
+}
+
+/// Only the beginning git checks is read, a large binary file is not loaded into memory
+fn is_binary_blob(git_executor: &GitCommandExecutor, repo_path: &str, blob_id: &str) -> bool {
+  let content = match git_executor.cat_file_info(repo_path, blob_id) {
+    Ok(Some(info)) if info.size <= BINARY_CHECK_LENGTH => git_executor.cat_file_contents(repo_path, blob_id).map(|object| object.map(|(_, content)| content)),
+    Ok(Some(_)) => git_executor.read_blob_prefix(repo_path, blob_id, BINARY_CHECK_LENGTH).map(Some),
+    Ok(None) | Err(_) => return false,
+  };
+  matches!(content, Ok(Some(content)) if is_binary_content(&content))
+}
+
+/// Stages of each conflicted path by index stage (1 base, 2 ours, 3 theirs)
+fn conflicts_by_path(conflicts: &[ConflictEntry]) -> BTreeMap<&str, [Option<&ConflictEntry>; 3]> {
+  let mut paths: BTreeMap<&str, [Option<&ConflictEntry>; 3]> = BTreeMap::new();
+  for conflict in conflicts.iter().filter(|conflict| (1..=3).contains(&conflict.stage)) {
+    paths.entry(conflict.path.as_str()).or_default()[usize::from(conflict.stage - 1)] = Some(conflict);
+  }
+  paths
+}
+
+/// Ref of the resolution of a path with the given conflicting versions
+fn resolution_ref(git_executor: &GitCommandExecutor, repo_path: &str, path: &str, stages: &[Option<&ConflictEntry>; 3]) -> Result<String> {
+  let mut key = path.to_string();
+  for stage in stages {
+    key.push('\n');
+    key.push_str(stage.map_or("-", |entry| entry.object_id.as_str()));
+  }
+  let key_id = git_executor.execute_command_with_input(&["hash-object", "--stdin"], repo_path, &key)?;
+  Ok(format!("{BINARY_RESOLUTION_REF_PREFIX}{}", key_id.trim()))
+}
+
+/// Record the resolution of a binary conflict of a commit applied onto another one.
+/// The merge is repeated to find the conflicting versions, the next sync applies the resolution.
+#[instrument(skip(git_executor, params), fields(commit = %params.commit_hash, target = %params.target_commit_hash, file = %params.file))]
+pub fn resolve_binary_conflict(git_executor: &GitCommandExecutor, params: ResolveBinaryConflictParams) -> Result<ResolveBinaryConflictResult> {
+  let ResolveBinaryConflictParams {
+    repository_path,
+    commit_hash,
+    target_commit_hash,
+    file,
+    resolution,
+  } = params;
+
+  let (_, stages) = find_conflict_stages(git_executor, &repository_path, &commit_hash, &target_commit_hash, &file)?;
+  let stages = stages.each_ref().map(Option::as_ref);
+  if !is_binary_conflict(git_executor, &repository_path, stages.iter().flatten().map(|entry| entry.object_id.as_str())) {
+    bail!("'{file}' is not a binary file, its conflict has to be resolved in the commit");
+  }
+
+  let stage_blob = |stage: usize, name: &str| stages[stage].map(|entry| entry.object_id.clone()).ok_or_else(|| anyhow!("'{file}' has no {name} version"));
+  let blob_id = match &resolution {
+    BinaryConflictResolution::Base => stage_blob(0, "base")?,
+    BinaryConflictResolution::Ours => stage_blob(1, "our")?,
+    BinaryConflictResolution::Theirs => stage_blob(2, "their")?,
+    BinaryConflictResolution::Replacement(replacement_path) => {
+      if !Path::new(replacement_path).is_file() {
+        bail!("Replacement file {replacement_path} does not exist");
+      }
+      // Filters of the path (line endings, LFS) apply as when the file is added
+      git_executor.execute_command(&["hash-object", "-w", &format!("--path={file}"), "--", replacement_path], &repository_path)?
+    }
+  };
+
+  record_resolution(git_executor, &repository_path, &file, &stages, &blob_id)?;
+  info!(%blob_id, ?resolution, "Recorded binary conflict resolution");
+  Ok(ResolveBinaryConflictResult { blob_id })
+}
+
+/// Repeat the merge of a commit applied onto another one, returns the merge and the stages of the conflicting file
+pub(crate) fn find_conflict_stages(
+  git_executor: &GitCommandExecutor,
+  repo_path: &str,
+  commit_hash: &str,
+  target_commit_hash: &str,
+  file: &str,
+) -> Result<(TreeMerge, [Option<ConflictEntry>; 3])> {
+  let parent_id = get_commit_parent(git_executor, repo_path, commit_hash)?;
+  let merge = merge_trees(git_executor, repo_path, &parent_id, target_commit_hash, commit_hash, &DiffOptions::default())?;
+  let stages = conflicts_by_path(&merge.conflicts)
+    .get(file)
+    .map(|stages| stages.map(|entry| entry.cloned()))
+    .ok_or_else(|| anyhow!("'{file}' doesn't conflict when applying {commit_hash} onto {target_commit_hash}"))?;
+  Ok((merge, stages))
+}
+
+/// Record the blob resolving the conflict of a path with the given versions, applied by the next cherry-pick running
+/// into it
+pub(crate) fn record_resolution(git_executor: &GitCommandExecutor, repo_path: &str, path: &str, stages: &[Option<&ConflictEntry>; 3], blob_id: &str) -> Result<()> {
+  let ref_name = resolution_ref(git_executor, repo_path, path, stages)?;
+  write_resolution_ref(git_executor, repo_path, &ref_name, path, blob_id)
+}
+
+fn unix_now() -> u64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Point the ref to a tag of the blob dated now
+fn write_resolution_ref(git_executor: &GitCommandExecutor, repo_path: &str, ref_name: &str, path: &str, blob_id: &str) -> Result<()> {
+  let tag = format!(
+    "object {blob_id}\ntype blob\ntag binary-resolution\ntagger Branch Deck <branch-deck> {} +0000\n\nResolution of {path}\n",
+    unix_now()
+  );
+  let tag_id = git_executor.execute_command_with_input(&["hash-object", "-t", "tag", "-w", "--stdin"], repo_path, &tag)?;
+  git_executor.execute_command(&["update-ref", ref_name, tag_id.trim()], repo_path)?;
+  Ok(())
+}
+
+/// Date of the resolution tag, `None` for a ref pointing to the blob itself
+fn resolution_date(tag: &[u8]) -> Option<u64> {
+  let tag = String::from_utf8_lossy(tag);
+  let tagger = tag.lines().take_while(|line| !line.is_empty()).find_map(|line| line.strip_prefix("tagger "))?;
+  tagger.rsplit(' ').nth(1)?.parse().ok()
+}
+
+/// Remove the resolutions neither recorded nor applied within the retention period, returns how many were removed
+#[instrument(skip(git_executor))]
+pub fn prune_binary_resolutions(git_executor: &GitCommandExecutor, repo_path: &str, retention_days: u64) -> Result<usize> {
+  let refs = git_executor.execute_command_lines(&["for-each-ref", "--format=%(refname) %(creatordate:unix)", BINARY_RESOLUTION_REF_PREFIX], repo_path)?;
+  let cutoff = unix_now().saturating_sub(retention_days * 24 * 60 * 60);
+  let mut transaction = RefTransaction::new();
+  for line in &refs {
+    let (ref_name, date) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+    // Refs without a date point to the blob itself, recorded before resolutions were dated
+    if !ref_name.is_empty() && !date.parse::<u64>().is_ok_and(|date| date >= cutoff) {
+      transaction.delete(ref_name, None);
+    }
+  }
+  if transaction.is_empty() {
+    return Ok(0);
+  }
+  transaction.commit(git_executor, repo_path, "branch-deck: prune binary conflict resolutions")?;
+  info!(count = transaction.len(), "Pruned unused binary conflict resolutions");
+  Ok(transaction.len())
+}
+
+/// Apply the recorded resolutions to the conflicts of a merge. Returns the merge with the resolved paths replaced in
+/// the tree and removed from the conflicts, or `None` if no conflict has a recorded resolution.
+#[instrument(skip(git_executor, merge), fields(conflict_count = merge.conflicts.len()))]
+pub fn apply_binary_resolutions(git_executor: &GitCommandExecutor, repo_path: &str, merge: &TreeMerge) -> Result<Option<TreeMerge>> {
+  let mut index_info = String::new();
+  let mut resolved_paths = Vec::new();
+  for (path, stages) in conflicts_by_path(&merge.conflicts) {
+    let ref_name = resolution_ref(git_executor, repo_path, path, &stages)?;
+    let Some(resolution) = git_executor.cat_file_info(repo_path, &ref_name)? else {
+      continue;
+    };
+    let Some(blob) = git_executor.cat_file_info(repo_path, &format!("{ref_name}^{{blob}}"))? else {
+      continue;
+    };
+    // Dated again while in use, so it's not pruned
+    let date = match resolution.kind.as_str() {
+      "tag" => git_executor.cat_file_contents(repo_path, &ref_name)?.and_then(|(_, tag)| resolution_date(&tag)),
+      _ => None,
+    };
+    if date.is_none_or(|date| date + RESOLUTION_REFRESH_SECS < unix_now())
+      && let Err(e) = write_resolution_ref(git_executor, repo_path, &ref_name, path, &blob.id)
+    {
+      warn!(path, error = %e, "Failed to update the date of a binary conflict resolution");
+    }
+    // The mode of the chosen version, a replacement keeps ours
+    let mode = stages
+      .iter()
+      .flatten()
+      .find(|entry| entry.object_id == blob.id)
+      .copied()
+      .or(stages[1])
+      .or(stages[2])
+      .map_or("100644", |entry| entry.mode.as_str());
+    index_info.push_str(&format!("{mode} {}\t{path}\0", blob.id));
+    resolved_paths.push(path);
+  }
+  if resolved_paths.is_empty() {
+    return Ok(None);
+  }
+
+  let temp_dir = TempDirGuard::new(repo_path, "binary_resolution")?;
+  let index_path = temp_dir.join("index");
+  let env = [("GIT_INDEX_FILE", index_path.as_str())];
+  git_executor.execute_command_with_env(&["read-tree", &merge.tree_id], repo_path, &env)?;
+  git_executor.execute_command_with_env_and_stdin(&["update-index", "-z", "--index-info"], repo_path, &env, &index_info)?;
+  let tree_id = git_executor.execute_command_with_env(&["write-tree"], repo_path, &env)?;
+
+  debug!(resolved = ?resolved_paths, "applied recorded binary conflict resolutions");
+  Ok(Some(TreeMerge {
+    tree_id,
+    conflicts: merge
+      .conflicts
+      .iter()
+      .filter(|conflict| !resolved_paths.contains(&conflict.path.as_str()))
+      .cloned()
+      .collect(),
+  }))
+}
@@ -0,0 +1,159 @@
+use crate::binary_conflict::{
+  BINARY_RESOLUTION_REF_PREFIX, BinaryConflictResolution, ResolveBinaryConflictParams, is_binary_conflict, is_binary_content, prune_binary_resolutions, resolve_binary_conflict,
+};
+use crate::cache::TreeIdCache;
+use crate::cherry_pick::perform_fast_cherry_pick_with_context;
+use crate::copy_commit::CopyCommitError;
+use crate::diff_options::DiffOptions;
+use crate::model::BranchError;
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::{ConflictTestBuilder, TestRepo};
+
+#[test]
+fn test_is_binary_content() {
+  assert!(is_binary_content(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"));
+  assert!(!is_binary_content(b"fn main() {}\n"));
+  assert!(!is_binary_content(b""));
+  // Only the beginning is checked, like git does
+  let mut late_nul = vec![b'a'; 9000];
+  late_nul.push(0);
+  assert!(!is_binary_content(&late_nul));
+}
+
+#[test]
+fn test_is_binary_conflict_of_large_blob() {
+  let test_repo = TestRepo::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+  let write_blob = |content: &[u8]| {
+    let path = test_repo.path().join("blob");
+    std::fs::write(&path, content).unwrap();
+    git_executor.execute_command(&["hash-object", "-w", "--", path.to_str().unwrap()], repo_path).unwrap()
+  };
+
+  let mut binary = b"PNG\0".to_vec();
+  binary.resize(100_000, b'a');
+  let mut late_nul = vec![b'a'; 100_000];
+  late_nul.push(0);
+  let text = write_blob(&late_nul);
+  assert!(is_binary_conflict(&git_executor, repo_path, [write_blob(&binary).as_str()]));
+  assert!(!is_binary_conflict(&git_executor, repo_path, [text.as_str()]));
+  // Missing on one side
+  assert!(!is_binary_conflict(&git_executor, repo_path, ["0123456789abcdef0123456789abcdef01234567", text.as_str()]));
+}
+
+#[test]
+fn test_binary_conflict_resolution() {
+  let test_repo = TestRepo::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+
+  let scenario = ConflictTestBuilder::new(&test_repo)
+    .with_initial_state(vec![("logo.png", "PNG\0base"), ("README.md", "# Logo\n")], "Initial commit")
+    .with_target_changes(vec![("logo.png", "PNG\0target")], "Target: New logo")
+    .with_cherry_changes(vec![("logo.png", "PNG\0cherry"), ("README.md", "# New logo\n")], "Cherry-pick: Another logo")
+    .build();
+  let cherry_pick = || {
+    perform_fast_cherry_pick_with_context(
+      &git_executor,
+      repo_path,
+      &scenario.cherry_commit,
+      &scenario.target_commit,
+      None,
+      &TreeIdCache::new(),
+      &DiffOptions::default(),
+    )
+  };
+
+  let conflict = match cherry_pick() {
+    Err(CopyCommitError::BranchError(BranchError::MergeConflict(conflict))) => conflict,
+    other => panic!("Expected a merge conflict, got: {other:?}"),
+  };
+  let files: Vec<(&str, bool, bool)> = conflict
+    .conflicting_files
+    .iter()
+    .map(|file| (file.file.as_str(), file.is_binary, file.base_to_cherry_diff.hunks.is_empty()))
+    .collect();
+  assert_eq!(files, vec![("logo.png", true, true)]);
+
+  let params = |resolution| ResolveBinaryConflictParams {
+    repository_path: repo_path.to_string(),
+    commit_hash: scenario.cherry_commit.clone(),
+    target_commit_hash: scenario.target_commit.clone(),
+    file: "logo.png".to_string(),
+    resolution,
+  };
+  let result = resolve_binary_conflict(&git_executor, params(BinaryConflictResolution::Theirs)).unwrap();
+  assert_eq!(result.blob_id, test_repo.rev_parse(&format!("{}:logo.png", scenario.cherry_commit)).unwrap());
+
+  // The recorded resolution is applied, the rest of the commit is merged as usual
+  let tree_id = cherry_pick().unwrap();
+  assert_eq!(test_repo.rev_parse(&format!("{tree_id}:logo.png")).unwrap(), result.blob_id);
+  assert_eq!(
+    test_repo.rev_parse(&format!("{tree_id}:README.md")).unwrap(),
+    test_repo.rev_parse(&format!("{}:README.md", scenario.cherry_commit)).unwrap()
+  );
+
+  // Recording another version replaces the resolution
+  let replacement_path = test_repo.path().join("replacement.png");
+  std::fs::write(&replacement_path, b"PNG\0replacement").unwrap();
+  let replacement = resolve_binary_conflict(
+    &git_executor,
+    params(BinaryConflictResolution::Replacement(replacement_path.to_string_lossy().into_owned())),
+  )
+  .unwrap();
+  let tree_id = cherry_pick().unwrap();
+  assert_eq!(test_repo.rev_parse(&format!("{tree_id}:logo.png")).unwrap(), replacement.blob_id);
+
+  // Text conflicts are resolved in the commit
+  let error = resolve_binary_conflict(
+    &git_executor,
+    ResolveBinaryConflictParams {
+      file: "README.md".to_string(),
+      ..params(BinaryConflictResolution::Ours)
+    },
+  )
+  .unwrap_err();
+  assert!(error.to_string().contains("doesn't conflict"), "{error}");
+}
+
+#[test]
+fn test_prune_binary_resolutions() {
+  let test_repo = TestRepo::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+
+  let scenario = ConflictTestBuilder::new(&test_repo)
+    .with_initial_state(vec![("logo.png", "PNG\0base")], "Initial commit")
+    .with_target_changes(vec![("logo.png", "PNG\0target")], "Target: New logo")
+    .with_cherry_changes(vec![("logo.png", "PNG\0cherry")], "Cherry-pick: Another logo")
+    .build();
+  resolve_binary_conflict(
+    &git_executor,
+    ResolveBinaryConflictParams {
+      repository_path: repo_path.to_string(),
+      commit_hash: scenario.cherry_commit.clone(),
+      target_commit_hash: scenario.target_commit.clone(),
+      file: "logo.png".to_string(),
+      resolution: BinaryConflictResolution::Ours,
+    },
+  )
+  .unwrap();
+  // Recorded before resolutions were dated
+  let legacy_ref = format!("{BINARY_RESOLUTION_REF_PREFIX}legacy");
+  let blob = test_repo.rev_parse(&format!("{}:logo.png", scenario.target_commit)).unwrap();
+  git_executor.execute_command(&["update-ref", &legacy_ref, &blob], repo_path).unwrap();
+  let resolution_refs = || {
+    git_executor
+      .execute_command_lines(&["for-each-ref", "--format=%(objecttype)", BINARY_RESOLUTION_REF_PREFIX], repo_path)
+      .unwrap()
+  };
+  assert_eq!(resolution_refs(), vec!["tag".to_string(), "blob".to_string()]);
+
+  // The recorded resolution is recent
+  assert_eq!(prune_binary_resolutions(&git_executor, repo_path, 30).unwrap(), 1);
+  assert_eq!(resolution_refs(), vec!["tag".to_string()]);
+  assert_eq!(prune_binary_resolutions(&git_executor, repo_path, 30).unwrap(), 0);
+}
@@ -1,22 +1,179 @@
 use crate::copy_commit::CopyCommitError;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use dashmap::DashMap;
 use git_executor::git_command_executor::GitCommandExecutor;
-use std::sync::Arc;
-use tracing::{debug, instrument};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, instrument, warn};
 
-/// Thread-safe cache for tree IDs to avoid redundant git rev-parse calls
+/// Git config key enabling the tree ID and cherry-pick cache persisted across syncs
+pub const PERSISTENT_TREE_CACHE_CONFIG_KEY: &str = "branchdeck.persistentTreeCache";
+
+/// First line of the cache files, bumped when the format changes
+const CACHE_FORMAT_HEADER: &str = "branch-deck tree-ids v1";
+const CHERRY_PICK_FORMAT_HEADER: &str = "branch-deck cherry-picks v1";
+
+/// The file is started over once it grows beyond this, entries of rewritten history are never used again
+const MAX_PERSISTED_ENTRIES: usize = 1_000_000;
+
+/// Thread-safe cache for tree IDs to avoid redundant tree lookups
 /// Cache is per-sync operation to ensure fresh data
 /// Uses DashMap for high-performance concurrent access with lock-free reads
 #[derive(Clone)]
 pub struct TreeIdCache {
   cache: Arc<DashMap<String, String>>,
+  /// Clean cherry-pick results: (source commit, target tree) -> resulting tree
+  cherry_picks: Arc<DashMap<(String, String), String>>,
+  persistent: Option<Arc<PersistentTreeIdCache>>,
+}
+
+/// On-disk layer under `.git/branch-deck/cache`. The tree of a commit never changes, and neither does the result of
+/// cherry-picking a commit onto a tree, so entries keyed by full object IDs stay valid; the files are only dropped when gc
+/// or repack removes a pack they were written against, which keeps their size in check without tracking which commits are
+/// still reachable.
+struct PersistentTreeIdCache {
+  pack_dir: PathBuf,
+  tree_ids: PersistentCacheFile,
+  cherry_picks: PersistentCacheFile,
+}
+
+struct PersistentCacheFile {
+  path: PathBuf,
+  header: &'static str,
+  /// Whether the file must be written from scratch instead of appended to
+  rewrite: AtomicBool,
+  /// Lines of entries resolved in this run that are not in the file yet
+  pending: Mutex<Vec<String>>,
+}
+
+impl PersistentCacheFile {
+  fn new(cache_dir: &Path, name: &str, header: &'static str) -> Self {
+    Self {
+      path: cache_dir.join(name),
+      header,
+      rewrite: AtomicBool::new(true),
+      pending: Mutex::new(Vec::new()),
+    }
+  }
+
+  /// Content of the file, `None` if it doesn't exist or can't be read
+  fn read(&self) -> Option<String> {
+    match std::fs::read_to_string(&self.path) {
+      Ok(content) => Some(content),
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+      Err(e) => {
+        warn!(error = %e, path = %self.path.display(), "Failed to read persisted cache");
+        None
+      }
+    }
+  }
+
+  /// Keep appending to the loaded file, unless it grew too large and starts over with the entries of this run
+  fn loaded(&self, entry_count: usize) {
+    self.rewrite.store(entry_count > MAX_PERSISTED_ENTRIES, Ordering::Release);
+  }
+
+  fn save(&self, pack_dir: &Path) -> Result<()> {
+    let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+    let rewrite = self.rewrite.swap(false, Ordering::AcqRel);
+    if pending.is_empty() && !rewrite {
+      return Ok(());
+    }
+
+    if let Some(parent) = self.path.parent() {
+      std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    let mut content = String::with_capacity(pending.len() * 123);
+    for line in &pending {
+      content.push_str(line);
+      content.push('\n');
+    }
+
+    if rewrite {
+      // Written against the current packs, replacing the file atomically
+      content.insert_str(0, &format!("{}\npacks {}\n", self.header, list_packs(pack_dir).join(" ")));
+      let temp_path = self.path.with_extension("tmp");
+      std::fs::write(&temp_path, content).with_context(|| format!("Failed to write {}", temp_path.display()))?;
+      std::fs::rename(&temp_path, &self.path).with_context(|| format!("Failed to write {}", self.path.display()))?;
+      debug!(entries = pending.len(), path = %self.path.display(), "Rewrote persisted cache");
+    } else {
+      let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&self.path)
+        .with_context(|| format!("Failed to open {}", self.path.display()))?;
+      file.write_all(content.as_bytes())?;
+      debug!(entries = pending.len(), path = %self.path.display(), "Appended persisted cache");
+    }
+    Ok(())
+  }
 }
 
 impl TreeIdCache {
   /// Create a new empty cache
   pub fn new() -> Self {
-    Self { cache: Arc::new(DashMap::new()) }
+    Self {
+      cache: Arc::new(DashMap::new()),
+      cherry_picks: Arc::new(DashMap::new()),
+      persistent: None,
+    }
+  }
+
+  /// Create a cache backed by the files under `.git/branch-deck/cache`, see [`TreeIdCache::save`].
+  /// A missing, outdated or unreadable file results in an empty cache.
+  #[instrument(skip(git_executor))]
+  pub fn load_persistent(git_executor: &GitCommandExecutor, repo_path: &str) -> Self {
+    let git_dir = match resolve_common_git_dir(git_executor, repo_path) {
+      Ok(git_dir) => git_dir,
+      Err(e) => {
+        warn!(error = %e, "Failed to locate git directory, tree IDs are not persisted");
+        return Self::new();
+      }
+    };
+
+    let cache_dir = git_dir.join("branch-deck").join("cache");
+    let persistent = PersistentTreeIdCache {
+      pack_dir: git_dir.join("objects").join("pack"),
+      tree_ids: PersistentCacheFile::new(&cache_dir, "tree-ids", CACHE_FORMAT_HEADER),
+      cherry_picks: PersistentCacheFile::new(&cache_dir, "cherry-picks", CHERRY_PICK_FORMAT_HEADER),
+    };
+    let packs = list_packs(&persistent.pack_dir);
+
+    let cache = DashMap::new();
+    if let Some(content) = persistent.tree_ids.read() {
+      match parse_cache_file(&content, &packs) {
+        Some(entries) => {
+          for (commit_id, tree_id) in entries {
+            cache.insert(commit_id.to_string(), tree_id.to_string());
+          }
+          persistent.tree_ids.loaded(cache.len());
+          debug!(entries = cache.len(), "Loaded persisted tree IDs");
+        }
+        None => debug!("Persisted tree IDs are outdated, starting over"),
+      }
+    }
+
+    let cherry_picks = DashMap::new();
+    if let Some(content) = persistent.cherry_picks.read() {
+      match parse_cherry_pick_file(&content, &packs) {
+        Some(entries) => {
+          for (commit_id, target_tree_id, tree_id) in entries {
+            cherry_picks.insert((commit_id.to_string(), target_tree_id.to_string()), tree_id.to_string());
+          }
+          persistent.cherry_picks.loaded(cherry_picks.len());
+          debug!(entries = cherry_picks.len(), "Loaded persisted cherry-pick results");
+        }
+        None => debug!("Persisted cherry-pick results are outdated, starting over"),
+      }
+    }
+
+    Self {
+      cache: Arc::new(cache),
+      cherry_picks: Arc::new(cherry_picks),
+      persistent: Some(Arc::new(persistent)),
+    }
   }
 
   fn is_cacheable_key(commit_id: &str) -> bool {
@@ -46,15 +203,116 @@ impl TreeIdCache {
     // Store in cache (minimal locking) only for stable keys
     if cacheable {
       self.cache.insert(commit_id.to_string(), tree_id.clone());
+      // Short IDs may become ambiguous later, only full IDs are persisted
+      if let Some(persistent) = &self.persistent
+        && is_full_object_id(commit_id)
+      {
+        persistent.tree_ids.pending.lock().unwrap().push(format!("{commit_id} {tree_id}"));
+      }
     }
 
     Ok(tree_id)
   }
 
+  /// Tree resulting from a clean cherry-pick of `commit_id` onto a commit with tree `target_tree_id`, if computed before
+  pub fn get_cherry_pick(&self, commit_id: &str, target_tree_id: &str) -> Option<String> {
+    self.cherry_picks.get(&(commit_id.to_string(), target_tree_id.to_string())).map(|tree_id| tree_id.clone())
+  }
+
+  /// Remember the tree of a clean cherry-pick; conflicts are not cached, their details are computed on every run
+  pub fn insert_cherry_pick(&self, commit_id: &str, target_tree_id: &str, tree_id: &str) {
+    if !is_full_object_id(commit_id) {
+      return;
+    }
+    let previous = self.cherry_picks.insert((commit_id.to_string(), target_tree_id.to_string()), tree_id.to_string());
+    if previous.is_none()
+      && let Some(persistent) = &self.persistent
+    {
+      persistent.cherry_picks.pending.lock().unwrap().push(format!("{commit_id} {target_tree_id} {tree_id}"));
+    }
+  }
+
   /// Get cache statistics for debugging
   pub fn stats(&self) -> (usize, usize) {
     (self.cache.len(), self.cache.capacity())
   }
+
+  /// Write tree IDs and cherry-pick results computed in this run to the persistent cache; does nothing for an in-memory cache
+  #[instrument(skip(self))]
+  pub fn save(&self) -> Result<()> {
+    let Some(persistent) = &self.persistent else {
+      return Ok(());
+    };
+    persistent.tree_ids.save(&persistent.pack_dir)?;
+    persistent.cherry_picks.save(&persistent.pack_dir)
+  }
+}
+
+fn is_full_object_id(id: &str) -> bool {
+  // SHA-1 or SHA-256 repositories
+  (id.len() == 40 || id.len() == 64) && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Git directory shared by all worktrees, where objects live
+pub fn resolve_common_git_dir(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<PathBuf> {
+  let output = git_executor.execute_command(&["rev-parse", "--git-common-dir"], repo_path)?;
+  let git_dir = PathBuf::from(output.trim());
+  // Relative to the repository path unless it's the repository root itself
+  Ok(if git_dir.is_absolute() { git_dir } else { Path::new(repo_path).join(git_dir) })
+}
+
+/// Sorted pack file names, a pack disappears when gc or repack consolidates it
+fn list_packs(pack_dir: &Path) -> Vec<String> {
+  let mut packs: Vec<String> = std::fs::read_dir(pack_dir)
+    .map(|entries| {
+      entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".pack"))
+        .collect()
+    })
+    .unwrap_or_default();
+  packs.sort();
+  packs
+}
+
+/// Entry lines of a cache file, `None` if it has another format version or any of its packs is gone
+fn cache_file_lines<'a>(content: &'a str, header: &str, current_packs: &[String]) -> Option<std::str::Lines<'a>> {
+  let mut lines = content.lines();
+  if lines.next()? != header {
+    return None;
+  }
+  let packs = lines.next()?.strip_prefix("packs")?;
+  if !packs.split_whitespace().all(|pack| current_packs.iter().any(|current| current == pack)) {
+    return None;
+  }
+  Some(lines)
+}
+
+/// Entries of the tree ID cache file, `None` if it has another format version or any of its packs is gone
+pub(crate) fn parse_cache_file<'a>(content: &'a str, current_packs: &[String]) -> Option<Vec<(&'a str, &'a str)>> {
+  Some(
+    cache_file_lines(content, CACHE_FORMAT_HEADER, current_packs)?
+      .filter_map(|line| line.split_once(' '))
+      // A line cut off by an interrupted write is skipped
+      .filter(|(commit_id, tree_id)| is_full_object_id(commit_id) && is_full_object_id(tree_id) && commit_id.len() == tree_id.len())
+      .collect(),
+  )
+}
+
+/// Entries of the cherry-pick cache file: source commit, target tree and resulting tree
+pub(crate) fn parse_cherry_pick_file<'a>(content: &'a str, current_packs: &[String]) -> Option<Vec<(&'a str, &'a str, &'a str)>> {
+  Some(
+    cache_file_lines(content, CHERRY_PICK_FORMAT_HEADER, current_packs)?
+      .filter_map(|line| {
+        let mut parts = line.split(' ');
+        let entry = (parts.next()?, parts.next()?, parts.next()?);
+        parts.next().is_none().then_some(entry)
+      })
+      // A line cut off by an interrupted write is skipped
+      .filter(|(commit_id, target_tree_id, tree_id)| [commit_id, target_tree_id, tree_id].iter().all(|id| is_full_object_id(id) && id.len() == commit_id.len()))
+      .collect(),
+  )
 }
 
 impl Default for TreeIdCache {
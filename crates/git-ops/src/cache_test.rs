@@ -0,0 +1,121 @@
+use crate::cache::{TreeIdCache, parse_cache_file, parse_cherry_pick_file};
+use crate::cherry_pick::perform_fast_cherry_pick_with_context;
+use crate::diff_options::DiffOptions;
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+const COMMIT_ID: &str = "1111111111111111111111111111111111111111";
+const TREE_ID: &str = "2222222222222222222222222222222222222222";
+
+fn cache_file(test_repo: &TestRepo) -> std::path::PathBuf {
+  test_repo.path().join(".git/branch-deck/cache/tree-ids")
+}
+
+#[test]
+fn test_parse_cache_file() {
+  let packs = vec!["pack-a.pack".to_string(), "pack-b.pack".to_string()];
+  let content = format!("branch-deck tree-ids v1\npacks pack-a.pack\n{COMMIT_ID} {TREE_ID}\n{COMMIT_ID} 22222\n");
+  // The line cut off by an interrupted write is skipped
+  assert_eq!(parse_cache_file(&content, &packs), Some(vec![(COMMIT_ID, TREE_ID)]));
+
+  // A pack the file was written against was removed by gc
+  assert_eq!(parse_cache_file(&content, &["pack-b.pack".to_string()]), None);
+  // Another format version
+  assert_eq!(parse_cache_file(&format!("branch-deck tree-ids v0\npacks\n{COMMIT_ID} {TREE_ID}\n"), &packs), None);
+  assert_eq!(parse_cache_file("", &packs), None);
+}
+
+#[test]
+fn test_persistent_tree_id_cache() {
+  let test_repo = TestRepo::new();
+  let commit_id = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let tree_id = test_repo.rev_parse(&format!("{commit_id}^{{tree}}")).unwrap();
+
+  let cache = TreeIdCache::load_persistent(&git_executor, repo_path);
+  assert_eq!(cache.stats().0, 0);
+  assert_eq!(cache.get_tree_id(&git_executor, repo_path, &commit_id).unwrap(), tree_id);
+  // Short IDs are cached in memory only
+  cache.get_tree_id(&git_executor, repo_path, &commit_id[..7]).unwrap();
+  cache.save().unwrap();
+
+  let content = std::fs::read_to_string(cache_file(&test_repo)).unwrap();
+  assert_eq!(content, format!("branch-deck tree-ids v1\npacks \n{commit_id} {tree_id}\n"));
+
+  // The next run starts with the persisted entry and appends new ones
+  let second_commit_id = test_repo.create_commit("Second commit", "file.txt", "content");
+  let cache = TreeIdCache::load_persistent(&git_executor, repo_path);
+  assert_eq!(cache.stats().0, 1);
+  cache.get_tree_id(&git_executor, repo_path, &second_commit_id).unwrap();
+  cache.save().unwrap();
+  assert_eq!(TreeIdCache::load_persistent(&git_executor, repo_path).stats().0, 2);
+}
+
+#[test]
+fn test_persistent_tree_id_cache_invalidated_by_gc() {
+  let test_repo = TestRepo::new();
+  let commit_id = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  git_executor.execute_command(&["gc", "--quiet"], repo_path).unwrap();
+
+  let cache = TreeIdCache::load_persistent(&git_executor, repo_path);
+  cache.get_tree_id(&git_executor, repo_path, &commit_id).unwrap();
+  cache.save().unwrap();
+  assert_eq!(TreeIdCache::load_persistent(&git_executor, repo_path).stats().0, 1);
+
+  // gc consolidates all objects into a new pack and removes the old one
+  test_repo.create_commit("Second commit", "file.txt", "content");
+  git_executor.execute_command(&["gc", "--quiet"], repo_path).unwrap();
+  assert_eq!(TreeIdCache::load_persistent(&git_executor, repo_path).stats().0, 0);
+}
+
+#[test]
+fn test_in_memory_cache_is_not_persisted() {
+  let test_repo = TestRepo::new();
+  let commit_id = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let cache = TreeIdCache::new();
+  cache.get_tree_id(&git_executor, repo_path, &commit_id).unwrap();
+  cache.save().unwrap();
+  assert!(!cache_file(&test_repo).exists());
+}
+
+#[test]
+fn test_parse_cherry_pick_file() {
+  let packs = vec!["pack-a.pack".to_string()];
+  let content = format!("branch-deck cherry-picks v1\npacks pack-a.pack\n{COMMIT_ID} {TREE_ID} {TREE_ID}\n{COMMIT_ID} {TREE_ID}\n{COMMIT_ID} {TREE_ID} 222\n");
+  assert_eq!(parse_cherry_pick_file(&content, &packs), Some(vec![(COMMIT_ID, TREE_ID, TREE_ID)]));
+  // Tree ID cache files are not mistaken for cherry-pick results
+  assert_eq!(parse_cherry_pick_file(&format!("branch-deck tree-ids v1\npacks\n{COMMIT_ID} {TREE_ID}\n"), &packs), None);
+}
+
+#[test]
+fn test_persistent_cherry_pick_results() {
+  let test_repo = TestRepo::new();
+  let base = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  let cherry = test_repo.create_commit("Feature", "feature.txt", "feature");
+  test_repo.reset_hard(&base).unwrap();
+  let target = test_repo.create_commit("Other", "other.txt", "other");
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let target_tree_id = test_repo.rev_parse(&format!("{target}^{{tree}}")).unwrap();
+
+  let cache = TreeIdCache::load_persistent(&git_executor, repo_path);
+  let tree_id = perform_fast_cherry_pick_with_context(&git_executor, repo_path, &cherry, &target, None, &cache, &DiffOptions::default()).unwrap();
+  assert_eq!(cache.get_cherry_pick(&cherry, &target_tree_id), Some(tree_id.clone()));
+  cache.save().unwrap();
+
+  // The next run reuses the result without merging again
+  let cache = TreeIdCache::load_persistent(&git_executor, repo_path);
+  assert_eq!(cache.get_cherry_pick(&cherry, &target_tree_id), Some(tree_id.clone()));
+  assert_eq!(
+    perform_fast_cherry_pick_with_context(&git_executor, repo_path, &cherry, &target, None, &cache, &DiffOptions::default()).unwrap(),
+    tree_id
+  );
+}
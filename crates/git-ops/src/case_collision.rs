@@ -0,0 +1,111 @@
+//! Paths differing only in case, e.g. `Readme.md` and `README.md` after a commit renamed the file while the new parent
+//! still has it under the old name. Git stores such a tree fine, but a case-insensitive file system (the default on
+//! macOS and Windows) can check out only one of the paths: the other is overwritten and shows up as a change nobody made.
+//!
+//! In repositories on such a file system (`core.ignorecase`), copied and rewritten commits are checked for paths they
+//! would newly collide, failing with [`BranchError::CaseCollision`] instead of creating the tree.
+
+use crate::copy_commit::CopyCommitError;
+use crate::git_config::get_config_bool;
+use crate::model::{BranchError, CaseCollisionInfo};
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use tracing::{debug, instrument, warn};
+
+/// Git config key set by `git init` and `git clone` when the file system is case-insensitive
+pub const IGNORE_CASE_CONFIG_KEY: &str = "core.ignorecase";
+
+/// Whether the repository is on a case-insensitive file system, so paths differing only in case collide
+#[instrument(skip(git_executor))]
+pub fn load_ignore_case(git_executor: &GitCommandExecutor, repository_path: &str) -> bool {
+  match get_config_bool(git_executor, repository_path, IGNORE_CASE_CONFIG_KEY) {
+    Ok(value) => value.unwrap_or(false),
+    Err(e) => {
+      warn!(error = %e, "Invalid {IGNORE_CASE_CONFIG_KEY} value, paths differing in case are not checked");
+      false
+    }
+  }
+}
+
+/// Groups of paths in `tree_id` that are the same ignoring case and include a path `base_tree_id` doesn't have.
+/// Directories count as paths too, `Docs/a.md` collides with `docs/b.md`. Collisions already in the base are not reported.
+#[instrument(skip(git_executor))]
+pub fn find_new_case_collisions(git_executor: &GitCommandExecutor, repo_path: &str, base_tree_id: &str, tree_id: &str) -> Result<Vec<Vec<String>>> {
+  let added = git_executor.execute_command(
+    &["diff-tree", "-r", "--no-renames", "--name-only", "-z", "--diff-filter=A", base_tree_id, tree_id],
+    repo_path,
+  )?;
+  let added: Vec<&str> = added.split('\0').filter(|path| !path.is_empty()).collect();
+  if added.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  // Only the directories on the way to the added paths can get a new collision, listing the whole tree is slow in large
+  // repositories. A directory listed along with its parent is shown by its entries instead, so parents are derived.
+  let directories: BTreeSet<String> = added
+    .iter()
+    .flat_map(|path| path.match_indices('/').map(|(index, _)| format!("{}/", &path[..index])))
+    .collect();
+  let mut entries = git_executor.execute_command(&["ls-tree", "--full-tree", "--name-only", "-z", tree_id], repo_path)?;
+  if !directories.is_empty() {
+    let mut args = vec!["ls-tree", "--full-tree", "--name-only", "-z", tree_id, "--"];
+    args.extend(directories.iter().map(String::as_str));
+    entries.push('\0');
+    entries.push_str(&git_executor.execute_command(&args, repo_path)?);
+  }
+  let mut paths = HashSet::new();
+  for path in entries.split('\0').filter(|path| !path.is_empty()) {
+    paths.extend(path.match_indices('/').map(|(index, _)| &path[..index]).chain([path]));
+  }
+  let mut by_folded_path: HashMap<String, Vec<&str>> = HashMap::new();
+  for path in paths {
+    by_folded_path.entry(path.to_lowercase()).or_default().push(path);
+  }
+
+  let mut checked = HashSet::new();
+  let mut collisions = BTreeSet::new();
+  for path in added {
+    // The file itself and every directory containing it
+    let prefixes = path.match_indices('/').map(|(index, _)| &path[..index]).chain([path]);
+    for prefix in prefixes {
+      let folded = prefix.to_lowercase();
+      if !checked.insert(folded.clone()) {
+        continue;
+      }
+      if let Some(paths) = by_folded_path.get(&folded).filter(|paths| paths.len() > 1) {
+        let mut group: Vec<String> = paths.iter().map(|path| path.to_string()).collect();
+        group.sort();
+        collisions.insert(group);
+      }
+    }
+  }
+  Ok(collisions.into_iter().collect())
+}
+
+/// Fail with [`BranchError::CaseCollision`] if the commit's new tree has paths differing only in case that its new parent
+/// tree doesn't. Lookup failures are logged and not returned, the check must never block a rewrite git itself allows.
+pub fn check_case_collisions(
+  git_executor: &GitCommandExecutor,
+  repo_path: &str,
+  parent_tree_id: &str,
+  tree_id: &str,
+  commit_hash: &str,
+  commit_message: &str,
+) -> Result<(), CopyCommitError> {
+  match find_new_case_collisions(git_executor, repo_path, parent_tree_id, tree_id) {
+    Ok(collisions) if collisions.is_empty() => Ok(()),
+    Ok(collisions) => {
+      debug!(commit_hash, ?collisions, "paths differ only in case");
+      Err(CopyCommitError::BranchError(BranchError::CaseCollision(CaseCollisionInfo {
+        commit_hash: commit_hash.to_string(),
+        commit_message: commit_message.to_string(),
+        collisions,
+      })))
+    }
+    Err(e) => {
+      warn!(commit_hash, error = %e, "Failed to check paths differing in case");
+      Ok(())
+    }
+  }
+}
@@ -0,0 +1,101 @@
+use crate::case_collision::{IGNORE_CASE_CONFIG_KEY, check_case_collisions, find_new_case_collisions, load_ignore_case};
+use crate::copy_commit::CopyCommitError;
+use crate::model::BranchError;
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// Tree with the given files, written without a working tree, so paths differing in case can be created on any file system
+fn write_tree(git_executor: &GitCommandExecutor, repo_path: &str, files: &[&str]) -> String {
+  let blob = git_executor.execute_command_with_input(&["hash-object", "-w", "--stdin"], repo_path, "content\n").unwrap();
+  let mut index_info = String::new();
+  for file in files {
+    index_info.push_str(&format!("100644 {blob}\t{file}\n"));
+  }
+  let index_dir = TempDir::new().unwrap();
+  let index_file = index_dir.path().join("index");
+  let env = [("GIT_INDEX_FILE", index_file.to_str().unwrap())];
+  git_executor
+    .execute_command_with_env_and_stdin(&["-c", "core.ignorecase=false", "update-index", "--add", "--index-info"], repo_path, &env, &index_info)
+    .unwrap();
+  git_executor.execute_command_with_env(&["write-tree"], repo_path, &env).unwrap()
+}
+
+#[test]
+fn test_find_new_case_collisions() {
+  let test_repo = TestRepo::new();
+  let git_executor = &GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let base = write_tree(git_executor, repo_path, &["Readme.md", "src/lib.rs"]);
+  let renamed = write_tree(git_executor, repo_path, &["README.md", "src/lib.rs"]);
+  assert_eq!(find_new_case_collisions(git_executor, repo_path, &base, &renamed).unwrap(), Vec::<Vec<String>>::new());
+
+  let both = write_tree(git_executor, repo_path, &["README.md", "Readme.md", "src/lib.rs"]);
+  assert_eq!(
+    find_new_case_collisions(git_executor, repo_path, &base, &both).unwrap(),
+    vec![vec!["README.md".to_string(), "Readme.md".to_string()]]
+  );
+
+  // Directories collide too
+  let directories = write_tree(git_executor, repo_path, &["Readme.md", "Src/main.rs", "src/lib.rs"]);
+  assert_eq!(
+    find_new_case_collisions(git_executor, repo_path, &base, &directories).unwrap(),
+    vec![vec!["Src".to_string(), "src".to_string()]]
+  );
+
+  // Within a nested directory
+  let nested_base = write_tree(git_executor, repo_path, &["docs/api/Guide.md", "docs/readme.md"]);
+  let nested = write_tree(git_executor, repo_path, &["docs/api/Guide.md", "docs/api/guide.md", "docs/readme.md"]);
+  assert_eq!(
+    find_new_case_collisions(git_executor, repo_path, &nested_base, &nested).unwrap(),
+    vec![vec!["docs/api/Guide.md".to_string(), "docs/api/guide.md".to_string()]]
+  );
+  let nested_directories = write_tree(git_executor, repo_path, &["docs/API/index.md", "docs/api/Guide.md", "docs/readme.md"]);
+  assert_eq!(
+    find_new_case_collisions(git_executor, repo_path, &nested_base, &nested_directories).unwrap(),
+    vec![vec!["docs/API".to_string(), "docs/api".to_string()]]
+  );
+
+  // A collision the base already has is not reported again
+  let unrelated = write_tree(git_executor, repo_path, &["README.md", "Readme.md", "notes.md", "src/lib.rs"]);
+  assert_eq!(find_new_case_collisions(git_executor, repo_path, &both, &unrelated).unwrap(), Vec::<Vec<String>>::new());
+}
+
+#[test]
+fn test_check_case_collisions_error() {
+  let test_repo = TestRepo::new();
+  let git_executor = &GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let base = write_tree(git_executor, repo_path, &["Readme.md"]);
+  let tree = write_tree(git_executor, repo_path, &["README.md", "Readme.md"]);
+  let commit_hash = "0123456789abcdef0123456789abcdef01234567";
+
+  match check_case_collisions(git_executor, repo_path, &base, &tree, commit_hash, "Rename readme") {
+    Err(CopyCommitError::BranchError(BranchError::CaseCollision(info))) => {
+      assert_eq!(info.commit_hash, commit_hash);
+      assert_eq!(info.collisions, vec![vec!["README.md".to_string(), "Readme.md".to_string()]]);
+      assert_eq!(
+        info.explanation(),
+        "Commit 01234567 (Rename readme) would create paths that differ only in case, which can't coexist on a case-insensitive file system: README.md and Readme.md"
+      );
+    }
+    result => panic!("Expected a case collision, got {result:?}"),
+  }
+  assert!(check_case_collisions(git_executor, repo_path, &base, &base, commit_hash, "Nothing").is_ok());
+}
+
+#[test]
+fn test_load_ignore_case() {
+  let test_repo = TestRepo::new();
+  let git_executor = &GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  test_repo.set_config(IGNORE_CASE_CONFIG_KEY, "true").unwrap();
+  assert!(load_ignore_case(git_executor, repo_path));
+  test_repo.set_config(IGNORE_CASE_CONFIG_KEY, "false").unwrap();
+  assert!(!load_ignore_case(git_executor, repo_path));
+}
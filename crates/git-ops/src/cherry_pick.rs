@@ -1,7 +1,10 @@
+use crate::binary_conflict::apply_binary_resolutions;
 use crate::cache::TreeIdCache;
 use crate::commit_list::Commit;
 use crate::copy_commit::CopyCommitError;
+use crate::diff_options::DiffOptions;
 use crate::merge_conflict::{ConflictDetailsParams, ConflictFileInfo, extract_conflict_details, get_commit_info_batch};
+use crate::merge_tree::merge_trees;
 use crate::model::{BranchError, BranchSyncStatus, MergeConflictInfo};
 use crate::progress::CherryPickProgress;
 use anyhow::{Result, anyhow};
@@ -10,10 +13,10 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use tracing::{debug, instrument};
 
-/// Cherry-pick implementation using Git CLI commands (git merge-tree)
+/// Cherry-pick implementation using Git CLI commands (see [`merge_trees`])
 /// This performs the cherry-pick without touching the working directory
 /// This version uses git CLI exclusively for better performance
-#[instrument(skip(git_executor, progress, tree_id_cache), fields(cherry_id = %cherry_commit_id, target_id = %target_commit_id))]
+#[instrument(skip(git_executor, progress, tree_id_cache, diff_options), fields(cherry_id = %cherry_commit_id, target_id = %target_commit_id))]
 pub fn perform_fast_cherry_pick_with_context(
   git_executor: &GitCommandExecutor,
   repo_path: &str,
@@ -21,6 +24,7 @@ pub fn perform_fast_cherry_pick_with_context(
   target_commit_id: &str,
   progress: Option<&CherryPickProgress>,
   tree_id_cache: &TreeIdCache,
+  diff_options: &DiffOptions,
 ) -> Result<String, CopyCommitError> {
   // Get commit information using git CLI
   let cherry_parent_id = get_commit_parent(git_executor, repo_path, cherry_commit_id)?;
@@ -34,78 +38,48 @@ pub fn perform_fast_cherry_pick_with_context(
     return tree_id_cache.get_tree_id(git_executor, repo_path, cherry_commit_id);
   }
 
+  // Whitespace-insensitive merges may give another tree, only results of the default merge are cached
+  let mut cacheable = diff_options.merge_args().is_empty();
+  if cacheable && let Some(tree_id) = tree_id_cache.get_cherry_pick(cherry_commit_id, &target_tree_id) {
+    debug!("reusing cached cherry-pick result");
+    return Ok(tree_id);
+  }
+
   debug!(
     base_parent = %cherry_parent_tree_id,
     ours_target = %target_tree_id,
     cherry_commit = %cherry_commit_id,
-    "running git merge-tree"
+    "merging trees"
   );
 
-  // Use git merge-tree for 3-way merge
-  let args = vec![
-    "-c",
-    "merge.conflictStyle=zdiff3", // Set conflict style to include base content
-    "merge-tree",
-    "--write-tree",
-    "-z", // Use NUL character as separator for better parsing
-    "--merge-base",
-    &cherry_parent_id,
-    target_commit_id,
-    cherry_commit_id,
-  ];
-
-  let output = git_executor
-    .execute_command(&args, repo_path)
-    .map_err(|e| CopyCommitError::Other(anyhow!("Failed to execute git merge-tree: {}", e)))?;
-
-  debug!(output_length = output.len(), "git merge-tree completed");
-
-  // Check if command produced output
-  if output.is_empty() {
-    return Err(CopyCommitError::Other(anyhow!("git merge-tree did not produce output")));
+  let mut merge = merge_trees(git_executor, repo_path, &cherry_parent_id, target_commit_id, cherry_commit_id, diff_options).map_err(CopyCommitError::Other)?;
+  if merge.has_conflicts()
+    && let Some(resolved) = apply_binary_resolutions(git_executor, repo_path, &merge).map_err(CopyCommitError::Other)?
+  {
+    // A resolution may be recorded again with another version, so the result is not cached
+    cacheable = false;
+    merge = resolved;
   }
-
-  // Parse the NUL-separated output
-  let parts: Vec<&str> = output.trim_end_matches('\0').split('\0').collect();
-
-  if parts.is_empty() || parts[0].is_empty() {
-    return Err(CopyCommitError::Other(anyhow!("No output from git merge-tree")));
-  }
-
-  let tree_oid = parts[0];
+  let tree_oid = merge.tree_id.as_str();
 
   // Check if there were conflicts by looking for file entries
-  if parts.len() > 1 {
+  if merge.has_conflicts() {
     let mut conflict_files: HashMap<PathBuf, ConflictFileInfo> = HashMap::new();
-
-    // Parse file entries (mode object stage\tfilename)
-    for part in parts.iter().skip(1).take_while(|p| !p.is_empty()) {
-      // File entries have format: "<mode> <object> <stage>\t<filename>"
-      if let Some(tab_pos) = part.find('\t') {
-        let (prefix, filename) = part.split_at(tab_pos);
-        let filename = &filename[1..]; // Skip the tab
-        let path = PathBuf::from(filename);
-
-        // Parse mode, object, stage
-        let prefix_parts: Vec<&str> = prefix.split_whitespace().collect();
-        if prefix_parts.len() == 3 {
-          let object_id = prefix_parts[1].to_string();
-          let stage = prefix_parts[2];
-
-          let entry = conflict_files.entry(path.clone()).or_insert(ConflictFileInfo {
-            path,
-            base_oid: None,
-            ours_oid: None,
-            theirs_oid: None,
-          });
-
-          match stage {
-            "1" => entry.base_oid = Some(object_id),
-            "2" => entry.ours_oid = Some(object_id),
-            "3" => entry.theirs_oid = Some(object_id),
-            _ => {}
-          }
-        }
+    for conflict in &merge.conflicts {
+      let path = PathBuf::from(&conflict.path);
+      let entry = conflict_files.entry(path.clone()).or_insert(ConflictFileInfo {
+        path,
+        base_oid: None,
+        ours_oid: None,
+        theirs_oid: None,
+      });
+
+      let object_id = Some(conflict.object_id.clone());
+      match conflict.stage {
+        1 => entry.base_oid = object_id,
+        2 => entry.ours_oid = object_id,
+        3 => entry.theirs_oid = object_id,
+        _ => {}
       }
     }
 
@@ -120,15 +94,16 @@ pub fn perform_fast_cherry_pick_with_context(
         git_executor,
         repo_path,
         conflict_files: &conflict_files,
-        merge_tree_oid: parts[0], // merge_tree_oid
+        merge_tree_oid: tree_oid,
         parent_commit_id: &cherry_parent_id,
         target_commit_id,
         cherry_commit_id,
+        diff_options,
       })?;
 
       // Analyze the conflict to find missing commits
       let conflicting_paths: Vec<PathBuf> = conflict_files.keys().cloned().collect();
-      let conflict_analysis = match crate::conflict_analysis::analyze_conflict(git_executor, repo_path, &cherry_parent_id, target_commit_id, &conflicting_paths) {
+      let conflict_analysis = match crate::conflict_analysis::analyze_conflict(git_executor, repo_path, &cherry_parent_id, target_commit_id, &conflicting_paths, diff_options) {
         Ok(analysis) => analysis,
         Err(e) => {
           // If conflict analysis fails, create a default analysis with empty data
@@ -190,51 +165,41 @@ pub fn perform_fast_cherry_pick_with_context(
   }
 
   // No conflicts, return the merged tree ID
+  if cacheable {
+    tree_id_cache.insert_cherry_pick(cherry_commit_id, &target_tree_id, tree_oid);
+  }
   Ok(tree_oid.to_string())
 }
 
-/// Get the parent commit ID using git CLI
+/// Get the parent commit ID via the long-lived `git cat-file --batch-check` process
 #[instrument(skip(git_executor), fields(commit_id = %commit_id))]
 pub fn get_commit_parent(git_executor: &GitCommandExecutor, repo_path: &str, commit_id: &str) -> Result<String, CopyCommitError> {
   let parent_ref = [commit_id, "^"].concat();
-  let args = vec!["rev-parse", &parent_ref];
-  let output = git_executor
-    .execute_command(&args, repo_path)
-    .map_err(|e| CopyCommitError::Other(anyhow!("Failed to get parent for {}: {}", commit_id, e)))?;
-  Ok(output.trim().to_string())
+  let info = git_executor
+    .cat_file_info(repo_path, &parent_ref)
+    .map_err(|e| CopyCommitError::Other(anyhow!("Failed to get parent for {}: {}", commit_id, e)))?
+    .ok_or_else(|| CopyCommitError::Other(anyhow!("Failed to get parent for {}: commit has no parent", commit_id)))?;
+  Ok(info.id)
 }
 
-/// Get basic commit information using git CLI
+/// Get basic commit information via the long-lived `git cat-file --batch` process
 #[instrument(skip(git_executor), fields(commit_id = %commit_id))]
 pub fn get_commit_info(git_executor: &GitCommandExecutor, repo_path: &str, commit_id: &str) -> Result<Commit, CopyCommitError> {
-  // Fetch subject and both timestamps in a single call for performance
-  // Format: %s<NULL>%at<NULL>%ct
-  let output = git_executor
-    .execute_command(&["log", "-1", "--format=%s%x00%at%x00%ct", commit_id], repo_path)
+  let commit = git_executor
+    .read_commit(repo_path, commit_id)
     .map_err(|e| CopyCommitError::Other(anyhow!("Failed to get commit info for {}: {}", commit_id, e)))?;
-
-  let mut parts = output.split('\0');
-  let subject = parts.next().unwrap_or("").trim().to_string();
-  let author_ts_str = parts.next().unwrap_or("").trim();
-  let committer_ts_str = parts.next().unwrap_or("").trim();
-
-  let author_timestamp: u32 = author_ts_str
-    .parse()
-    .map_err(|e| CopyCommitError::Other(anyhow!("Invalid author timestamp for commit {}: {}", commit_id, e)))?;
-  let committer_timestamp: u32 = committer_ts_str
-    .parse()
-    .map_err(|e| CopyCommitError::Other(anyhow!("Invalid committer timestamp for commit {}: {}", commit_id, e)))?;
+  let subject = commit.subject();
 
   Ok(Commit {
     id: commit_id.to_string(),
     subject: subject.clone(),
-    message: subject.clone(),    // For error reporting, subject is sufficient
-    author_name: String::new(),  // Not available from this query
-    author_email: String::new(), // Not available from this query
-    author_timestamp,
-    committer_timestamp,
-    parent_id: None,
-    tree_id: String::new(),
+    message: subject.clone(), // For error reporting, subject is sufficient
+    author_name: commit.author_name,
+    author_email: commit.author_email,
+    author_timestamp: commit.author_timestamp,
+    committer_timestamp: commit.committer_timestamp,
+    parent_id: commit.parent_ids.into_iter().next(),
+    tree_id: commit.tree_id,
     note: None,
     stripped_subject: subject,
     mapped_commit_id: None,
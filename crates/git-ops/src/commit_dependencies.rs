@@ -0,0 +1,151 @@
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tracing::{debug, instrument, warn};
+
+/// Notes ref holding hard dependencies between commits.
+/// The note of a commit lists the full hashes of the commits it depends on, one per line.
+pub const DEPENDENCIES_NOTES_REF: &str = "refs/notes/branch-deck/dependencies";
+
+fn parse_dependencies(note: &str) -> Vec<String> {
+  note.lines().map(str::trim).filter(|line| !line.is_empty()).map(str::to_string).collect()
+}
+
+/// Resolve a commit-ish to a full commit hash
+fn resolve_commit(git_executor: &GitCommandExecutor, repo_path: &str, commit: &str) -> Result<String> {
+  if commit.starts_with('-') {
+    return Err(anyhow!("Invalid commit: {commit}"));
+  }
+  match git_executor.cat_file_info(repo_path, &format!("{commit}^{{commit}}")) {
+    Ok(Some(info)) => Ok(info.id),
+    _ => Err(anyhow!("Commit {commit} does not exist")),
+  }
+}
+
+/// Get the commits a commit depends on
+#[instrument(skip(git_executor))]
+pub fn get_commit_dependencies(git_executor: &GitCommandExecutor, repo_path: &str, commit_id: &str) -> Result<Vec<String>> {
+  let (output, exit_code) = git_executor.execute_command_with_status(&["notes", "--ref", DEPENDENCIES_NOTES_REF, "show", commit_id], repo_path)?;
+  // Exit code 1 means the commit has no note
+  Ok(if exit_code == 0 { parse_dependencies(&output) } else { Vec::new() })
+}
+
+/// Replace the dependencies of a commit; an empty list removes the note.
+/// Returns the stored dependencies as full hashes.
+#[instrument(skip(git_executor))]
+pub fn set_commit_dependencies(git_executor: &GitCommandExecutor, repo_path: &str, commit_id: &str, depends_on: &[String]) -> Result<Vec<String>> {
+  let commit_id = resolve_commit(git_executor, repo_path, commit_id)?;
+
+  let mut dependencies: Vec<String> = Vec::with_capacity(depends_on.len());
+  for dependency in depends_on {
+    let dependency = resolve_commit(git_executor, repo_path, dependency)?;
+    if dependency == commit_id {
+      return Err(anyhow!("Commit {commit_id} cannot depend on itself"));
+    }
+    if !dependencies.contains(&dependency) {
+      dependencies.push(dependency);
+    }
+  }
+
+  if dependencies.is_empty() {
+    git_executor.execute_command(&["notes", "--ref", DEPENDENCIES_NOTES_REF, "remove", "--ignore-missing", &commit_id], repo_path)?;
+  } else {
+    let note = dependencies.join("\n");
+    git_executor.execute_command(&["notes", "--ref", DEPENDENCIES_NOTES_REF, "add", "-f", "-m", &note, &commit_id], repo_path)?;
+  }
+  debug!(commit_id, count = dependencies.len(), "Updated commit dependencies");
+  Ok(dependencies)
+}
+
+/// Load dependencies of the given commits in one pass over the notes ref.
+/// Commits without dependencies are not included in the result.
+#[instrument(skip(git_executor, commit_ids), fields(commit_count = commit_ids.len()))]
+pub fn load_commit_dependencies(git_executor: &GitCommandExecutor, repo_path: &str, commit_ids: &HashSet<&str>) -> Result<HashMap<String, Vec<String>>> {
+  let mut result = HashMap::new();
+  if commit_ids.is_empty() {
+    return Ok(result);
+  }
+
+  // Fails if the notes ref doesn't exist yet, which simply means there are no dependencies
+  let Ok(lines) = git_executor.execute_command_lines(&["notes", "--ref", DEPENDENCIES_NOTES_REF, "list"], repo_path) else {
+    return Ok(result);
+  };
+
+  // Each line is "<note blob> <annotated commit>"
+  for line in lines {
+    let Some((note_blob, commit_id)) = line.split_once(' ') else {
+      continue;
+    };
+    if !commit_ids.contains(commit_id) {
+      continue;
+    }
+
+    let note = git_executor.execute_command(&["cat-file", "blob", note_blob], repo_path)?;
+    let dependencies = parse_dependencies(&note);
+    if !dependencies.is_empty() {
+      result.insert(commit_id.to_string(), dependencies);
+    }
+  }
+  Ok(result)
+}
+
+/// Move the dependencies to the new commits after a history rewrite (reword, amend, squash), as notes are keyed by commit hash.
+/// The note of a rewritten commit moves to its new commit and dependencies on rewritten commits are updated in every note.
+/// Notes of commits squashed into one are merged, dependencies on the squashed commit itself are dropped.
+/// Failures are logged and not returned, the history is already rewritten.
+#[instrument(skip(git_executor, commit_mapping), fields(rewritten_count = commit_mapping.len()))]
+pub fn carry_over_commit_dependencies(git_executor: &GitCommandExecutor, repo_path: &str, commit_mapping: &HashMap<String, String>) {
+  if commit_mapping.is_empty() {
+    return;
+  }
+  if let Err(e) = rewrite_dependency_notes(git_executor, repo_path, commit_mapping) {
+    warn!(error = %e, "Failed to carry over commit dependencies to the rewritten commits");
+  }
+}
+
+fn rewrite_dependency_notes(git_executor: &GitCommandExecutor, repo_path: &str, commit_mapping: &HashMap<String, String>) -> Result<()> {
+  // Fails if the notes ref doesn't exist yet, then there is nothing to carry over
+  let Ok(lines) = git_executor.execute_command_lines(&["notes", "--ref", DEPENDENCIES_NOTES_REF, "list"], repo_path) else {
+    return Ok(());
+  };
+
+  let mut removed_notes = Vec::new();
+  let mut new_notes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+  // Each line is "<note blob> <annotated commit>"
+  for line in lines {
+    let Some((note_blob, commit_id)) = line.split_once(' ') else {
+      continue;
+    };
+    let note = git_executor.execute_command(&["cat-file", "blob", note_blob], repo_path)?;
+    let dependencies = parse_dependencies(&note);
+    let new_commit_id = commit_mapping.get(commit_id).map_or(commit_id, String::as_str);
+    if new_commit_id == commit_id && !dependencies.iter().any(|dependency| commit_mapping.contains_key(dependency)) {
+      continue;
+    }
+
+    if new_commit_id != commit_id {
+      removed_notes.push(commit_id.to_string());
+    }
+    let new_dependencies = new_notes.entry(new_commit_id.to_string()).or_default();
+    for dependency in &dependencies {
+      let dependency = commit_mapping.get(dependency).unwrap_or(dependency);
+      if dependency != new_commit_id && !new_dependencies.contains(dependency) {
+        new_dependencies.push(dependency.clone());
+      }
+    }
+  }
+
+  for commit_id in &removed_notes {
+    git_executor.execute_command(&["notes", "--ref", DEPENDENCIES_NOTES_REF, "remove", "--ignore-missing", commit_id], repo_path)?;
+  }
+  for (commit_id, dependencies) in &new_notes {
+    if dependencies.is_empty() {
+      git_executor.execute_command(&["notes", "--ref", DEPENDENCIES_NOTES_REF, "remove", "--ignore-missing", commit_id], repo_path)?;
+    } else {
+      let note = dependencies.join("\n");
+      git_executor.execute_command(&["notes", "--ref", DEPENDENCIES_NOTES_REF, "add", "-f", "-m", &note, commit_id], repo_path)?;
+    }
+  }
+  debug!(moved_count = removed_notes.len(), updated_count = new_notes.len(), "Carried over commit dependencies");
+  Ok(())
+}
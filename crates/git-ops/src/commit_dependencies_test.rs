@@ -0,0 +1,129 @@
+use crate::amend_operations::squash_commits_in_head;
+use crate::commit_dependencies::{carry_over_commit_dependencies, get_commit_dependencies, load_commit_dependencies, set_commit_dependencies};
+use crate::reword_commits::{RewordCommitParams, reword_commits_batch};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use std::collections::{HashMap, HashSet};
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_set_and_get_commit_dependencies() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let first = test_repo.create_commit("Add parser", "parser.rs", "parser");
+  let second = test_repo.create_commit("Use parser", "main.rs", "main");
+
+  assert_eq!(get_commit_dependencies(&git_executor, repo_path, &second).unwrap(), Vec::<String>::new());
+
+  // Short hashes are resolved to full ones, duplicates are dropped
+  let stored = set_commit_dependencies(&git_executor, repo_path, &second, &[first[..7].to_string(), first.clone()]).unwrap();
+  assert_eq!(stored, vec![first.clone()]);
+  assert_eq!(get_commit_dependencies(&git_executor, repo_path, &second).unwrap(), vec![first.clone()]);
+
+  // User notes are not affected
+  assert!(test_repo.log(&["-1", "--pretty=format:%N", &second]).unwrap().is_empty());
+
+  // Empty list removes the dependencies
+  set_commit_dependencies(&git_executor, repo_path, &second, &[]).unwrap();
+  assert_eq!(get_commit_dependencies(&git_executor, repo_path, &second).unwrap(), Vec::<String>::new());
+}
+
+#[test]
+fn test_set_commit_dependencies_rejects_invalid() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let commit = test_repo.create_commit("Add parser", "parser.rs", "parser");
+
+  let error = set_commit_dependencies(&git_executor, repo_path, &commit, std::slice::from_ref(&commit)).unwrap_err();
+  assert!(error.to_string().contains("cannot depend on itself"), "unexpected error: {error}");
+
+  let error = set_commit_dependencies(&git_executor, repo_path, &commit, &["0123456789abcdef0123456789abcdef01234567".to_string()]).unwrap_err();
+  assert!(error.to_string().contains("does not exist"), "unexpected error: {error}");
+}
+
+#[test]
+fn test_load_commit_dependencies_filters_commits() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  // No notes ref yet
+  assert_eq!(load_commit_dependencies(&git_executor, repo_path, &HashSet::from(["HEAD"])).unwrap(), HashMap::new());
+
+  let first = test_repo.create_commit("Add parser", "parser.rs", "parser");
+  let second = test_repo.create_commit("Use parser", "main.rs", "main");
+  let third = test_repo.create_commit("Test parser", "test.rs", "test");
+  set_commit_dependencies(&git_executor, repo_path, &second, std::slice::from_ref(&first)).unwrap();
+  set_commit_dependencies(&git_executor, repo_path, &third, &[first.clone(), second.clone()]).unwrap();
+
+  let loaded = load_commit_dependencies(&git_executor, repo_path, &HashSet::from([first.as_str(), third.as_str()])).unwrap();
+  assert_eq!(loaded, HashMap::from([(third.clone(), vec![first, second])]));
+}
+
+#[test]
+fn test_dependencies_follow_reworded_commits() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  test_repo.create_commit("Initial commit", "README.md", "readme");
+  let first = test_repo.create_commit("Add parser", "parser.rs", "parser");
+  let second = test_repo.create_commit("Use parser", "main.rs", "main");
+  let third = test_repo.create_commit("Test parser", "test.rs", "test");
+  set_commit_dependencies(&git_executor, repo_path, &second, std::slice::from_ref(&first)).unwrap();
+  set_commit_dependencies(&git_executor, repo_path, &third, &[first.clone(), second.clone()]).unwrap();
+
+  // Rewording the first commit recreates the others on top of it
+  let rewrites = vec![RewordCommitParams {
+    commit_id: first.clone(),
+    new_message: "(parser) Add parser".to_string(),
+  }];
+  let mapping = reword_commits_batch(&git_executor, repo_path, rewrites).unwrap();
+  let (new_first, new_second, new_third) = (&mapping[&first], &mapping[&second], &mapping[&third]);
+
+  assert_eq!(get_commit_dependencies(&git_executor, repo_path, new_second).unwrap(), vec![new_first.clone()]);
+  assert_eq!(
+    get_commit_dependencies(&git_executor, repo_path, new_third).unwrap(),
+    vec![new_first.clone(), new_second.clone()]
+  );
+  // Moved rather than copied
+  assert_eq!(get_commit_dependencies(&git_executor, repo_path, &second).unwrap(), Vec::<String>::new());
+  assert_eq!(get_commit_dependencies(&git_executor, repo_path, &third).unwrap(), Vec::<String>::new());
+}
+
+#[test]
+fn test_dependencies_of_squashed_commits_are_merged() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  test_repo.create_commit("Initial commit", "README.md", "readme");
+  let base = test_repo.create_commit("Add lexer", "lexer.rs", "lexer");
+  let first = test_repo.create_commit("Add parser", "parser.rs", "parser");
+  let second = test_repo.create_commit("Fix parser", "parser.rs", "fixed parser");
+  let third = test_repo.create_commit("Use parser", "main.rs", "main");
+  set_commit_dependencies(&git_executor, repo_path, &first, std::slice::from_ref(&base)).unwrap();
+  set_commit_dependencies(&git_executor, repo_path, &second, std::slice::from_ref(&first)).unwrap();
+  set_commit_dependencies(&git_executor, repo_path, &third, std::slice::from_ref(&second)).unwrap();
+
+  let current_branch = test_repo.current_branch().unwrap();
+  let squashed = squash_commits_in_head(&git_executor, repo_path, &[first.clone(), second.clone()], "Add parser", &current_branch).unwrap();
+  // The dependency of the squashed commits on each other is gone
+  assert_eq!(get_commit_dependencies(&git_executor, repo_path, &squashed.squashed_commit_id).unwrap(), vec![base]);
+  assert_eq!(
+    get_commit_dependencies(&git_executor, repo_path, &squashed.new_head).unwrap(),
+    vec![squashed.squashed_commit_id.clone()]
+  );
+
+  // Nothing to carry over for an empty mapping
+  carry_over_commit_dependencies(&git_executor, repo_path, &HashMap::new());
+  assert_eq!(
+    get_commit_dependencies(&git_executor, repo_path, &squashed.new_head).unwrap(),
+    vec![squashed.squashed_commit_id]
+  );
+}
@@ -3,6 +3,7 @@ use git_executor::git_command_executor::GitCommandExecutor;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "specta")]
 use specta::Type;
+use std::num::NonZeroUsize;
 use tracing::{debug, instrument};
 
 /// Struct to hold commit data returned by git CLI
@@ -34,6 +35,28 @@ pub struct Commit {
   pub mapped_commit_id: Option<String>, // Extracted from note if it has v-commit-v1: prefix
 }
 
+/// Commits per page of the commit scan, see [`get_paged_commit_list_with_handler`]
+pub const DEFAULT_COMMIT_PAGE_SIZE: NonZeroUsize = NonZeroUsize::new(5000).unwrap();
+
+/// Optional limits selecting commits by date, count or paths, e.g. to pick the virtual branches worth rebuilding in a huge history
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct CommitFilter {
+  /// Only commits committed after this date, in any format `git log --since` accepts (e.g. "2025-01-01", "2 weeks ago")
+  pub since: Option<String>,
+  /// Only the newest N commits
+  pub max_commits: Option<u32>,
+  /// Only commits touching these paths (git pathspec)
+  pub pathspec: Vec<String>,
+}
+
+impl CommitFilter {
+  pub fn is_empty(&self) -> bool {
+    self.since.is_none() && self.max_commits.is_none() && self.pathspec.is_empty()
+  }
+}
+
 /// Get list of commits between baseline branch and HEAD
 /// This uses streaming to be memory efficient for repositories with many commits
 #[instrument(skip(git_executor))]
@@ -51,13 +74,27 @@ pub fn get_commit_list(git_executor: &GitCommandExecutor, repo_path: &str, basel
 /// Get list of commits between baseline branch and HEAD with a custom handler
 /// This is the most memory efficient approach as it processes commits one by one
 #[instrument(skip(git_executor, commit_handler))]
-pub fn get_commit_list_with_handler<F>(git_executor: &GitCommandExecutor, repo_path: &str, baseline_branch: &str, mut commit_handler: F) -> Result<()>
+pub fn get_commit_list_with_handler<F>(git_executor: &GitCommandExecutor, repo_path: &str, baseline_branch: &str, commit_handler: F) -> Result<()>
 where
   F: FnMut(Commit) -> Result<()>,
 {
+  get_filtered_commit_list_with_handler(git_executor, repo_path, baseline_branch, &CommitFilter::default(), commit_handler)
+}
+
+/// Same as [`get_commit_list_with_handler`], limited by the given filter
+#[instrument(skip(git_executor, commit_handler))]
+pub fn get_filtered_commit_list_with_handler<F>(git_executor: &GitCommandExecutor, repo_path: &str, baseline_branch: &str, filter: &CommitFilter, commit_handler: F) -> Result<()>
+where
+  F: FnMut(Commit) -> Result<()>,
+{
+  stream_commit_list(git_executor, repo_path, baseline_branch, filter, &[], commit_handler)
+}
+
+/// Current branch and the range of the commits ahead of the baseline
+fn commit_range(git_executor: &GitCommandExecutor, repo_path: &str, baseline_branch: &str) -> Result<(String, String)> {
   // Check if we're on the baseline branch itself (for local repos without remotes)
   let current_branch = git_executor.execute_command(&["--no-pager", "rev-parse", "--abbrev-ref", "HEAD"], repo_path)?;
-  let current_branch = current_branch.trim();
+  let current_branch = current_branch.trim().to_string();
 
   // If we're on the baseline branch and it's a local branch (no remote prefix),
   // get all commits except the first one
@@ -69,16 +106,47 @@ where
   } else {
     format!("{baseline_branch}..HEAD")
   };
+  Ok((current_branch, range))
+}
+
+/// Commits ahead of the baseline matching the filter and, unless empty, containing one of `message_patterns` in their message
+fn stream_commit_list<F>(
+  git_executor: &GitCommandExecutor,
+  repo_path: &str,
+  baseline_branch: &str,
+  filter: &CommitFilter,
+  message_patterns: &[&str],
+  mut commit_handler: F,
+) -> Result<()>
+where
+  F: FnMut(Commit) -> Result<()>,
+{
+  let (current_branch, range) = commit_range(git_executor, repo_path, baseline_branch)?;
 
   // Use a more robust delimiter-based format
-  let args = vec![
+  let mut args = vec![
     "--no-pager",
     "log",
     "--reverse",
     "--no-merges",
     "--pretty=format:%H%x1f%B%x1f%an%x1f%ae%x1f%at%x1f%ct%x1f%P%x1f%T%x1f%N%x1e",
-    &range,
   ];
+  // --max-count is applied before --reverse, so the newest commits are kept
+  let since_arg = filter.since.as_ref().map(|since| format!("--since={since}"));
+  let max_count_arg = filter.max_commits.map(|max_commits| format!("--max-count={max_commits}"));
+  args.extend(since_arg.as_deref());
+  args.extend(max_count_arg.as_deref());
+  // Several patterns match a commit containing any of them
+  let grep_args: Vec<String> = message_patterns.iter().map(|pattern| format!("--grep={pattern}")).collect();
+  if !grep_args.is_empty() {
+    args.push("--fixed-strings");
+    args.extend(grep_args.iter().map(String::as_str));
+  }
+  args.push(&range);
+  if !filter.pathspec.is_empty() {
+    args.push("--");
+    args.extend(filter.pathspec.iter().map(String::as_str));
+  }
 
   // Buffer to accumulate partial records
   let mut buffer = Vec::new();
@@ -133,6 +201,94 @@ where
   Ok(())
 }
 
+/// Same as [`get_filtered_commit_list_with_handler`], handing commits over in pages of `page_size` (the last one may be smaller),
+/// so callers can report progress while `git log` is still streaming a huge history
+#[instrument(skip(git_executor, page_handler))]
+pub fn get_paged_commit_list_with_handler<F>(
+  git_executor: &GitCommandExecutor,
+  repo_path: &str,
+  baseline_branch: &str,
+  filter: &CommitFilter,
+  page_size: NonZeroUsize,
+  page_handler: F,
+) -> Result<()>
+where
+  F: FnMut(Vec<Commit>) -> Result<()>,
+{
+  page_commit_list(git_executor, repo_path, baseline_branch, filter, &[], page_size, page_handler)
+}
+
+/// Same as [`get_paged_commit_list_with_handler`], but only the commits that may belong to one of the branches are read:
+/// the commits whose message contains the longest hyphen-separated part of the branch name. The commit prefix is
+/// turned into the branch name by replacing characters with hyphens, so this part is in every commit of the branch;
+/// other commits may contain it too, the caller groups them and picks the branches. Nothing is read without branches.
+#[instrument(skip(git_executor, page_handler))]
+pub fn get_paged_branch_commit_list_with_handler<F>(
+  git_executor: &GitCommandExecutor,
+  repo_path: &str,
+  baseline_branch: &str,
+  branch_names: &[String],
+  page_size: NonZeroUsize,
+  page_handler: F,
+) -> Result<()>
+where
+  F: FnMut(Vec<Commit>) -> Result<()>,
+{
+  if branch_names.is_empty() {
+    return Ok(());
+  }
+  let message_patterns: Vec<&str> = branch_names
+    .iter()
+    .map(|branch_name| branch_name.split('-').max_by_key(|part| part.len()).unwrap_or(branch_name.as_str()))
+    .collect();
+  page_commit_list(
+    git_executor,
+    repo_path,
+    baseline_branch,
+    &CommitFilter::default(),
+    &message_patterns,
+    page_size,
+    page_handler,
+  )
+}
+
+fn page_commit_list<F>(
+  git_executor: &GitCommandExecutor,
+  repo_path: &str,
+  baseline_branch: &str,
+  filter: &CommitFilter,
+  message_patterns: &[&str],
+  page_size: NonZeroUsize,
+  mut page_handler: F,
+) -> Result<()>
+where
+  F: FnMut(Vec<Commit>) -> Result<()>,
+{
+  let page_size = page_size.get();
+  let mut page = Vec::with_capacity(page_size);
+  stream_commit_list(git_executor, repo_path, baseline_branch, filter, message_patterns, |commit| {
+    page.push(commit);
+    if page.len() == page_size {
+      page_handler(std::mem::replace(&mut page, Vec::with_capacity(page_size)))?;
+    }
+    Ok(())
+  })?;
+
+  if !page.is_empty() {
+    page_handler(page)?;
+  }
+  Ok(())
+}
+
+/// The oldest commit ahead of the baseline, merges aside; `None` if there is none.
+/// Only commit IDs are listed, for scans that don't read every commit.
+#[instrument(skip(git_executor))]
+pub fn get_oldest_commit_id(git_executor: &GitCommandExecutor, repo_path: &str, baseline_branch: &str) -> Result<Option<String>> {
+  let (_, range) = commit_range(git_executor, repo_path, baseline_branch)?;
+  let commit_ids = git_executor.execute_command_lines(&["--no-pager", "rev-list", "--reverse", "--no-merges", &range], repo_path)?;
+  Ok(commit_ids.into_iter().next())
+}
+
 /// Find the position of record separator (0x1e) in the buffer
 fn find_record_separator(buffer: &[u8]) -> Option<usize> {
   buffer.iter().position(|&b| b == 0x1e)
@@ -178,7 +334,11 @@ pub fn parse_single_commit(record: &str) -> Result<Commit> {
   let (note, mapped_commit_id) = if let Some(note_content) = note_field {
     if !note_content.is_empty() {
       let trimmed = note_content.trim();
-      let mapped_id = trimmed.strip_prefix("v-commit-v1:").map(|stripped| stripped.trim().to_string());
+      // Mapping line may be followed by the user's own note
+      let mapped_id = trimmed
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("v-commit-v1:"))
+        .map(|stripped| stripped.trim().to_string());
       (Some(trimmed.to_string()), mapped_id)
     } else {
       (None, None)
@@ -422,3 +422,84 @@ fn test_multiple_commits_with_notes() {
   assert_eq!(commits[2].note, Some("v-commit-v1:ghi789".to_string()));
   assert!(!commits[2].id.contains('\n'), "Commit ID should not contain newlines");
 }
+
+#[test]
+fn test_get_commit_list_with_filter() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let initial_commit = test_repo.create_commit_with_timestamp("Initial commit", "README.md", "# Test", Some(1_700_000_000));
+  test_repo.create_branch_at("origin/master", &initial_commit).unwrap();
+  test_repo.create_commit_with_timestamp("(docs) Old docs", "docs/old.md", "old", Some(1_700_000_100));
+  test_repo.create_commit_with_timestamp("(parser) Add parser", "src/parser.rs", "parser", Some(1_800_000_000));
+  test_repo.create_commit_with_timestamp("(docs) New docs", "docs/new.md", "new", Some(1_800_000_100));
+
+  let subjects = |filter: CommitFilter| {
+    let mut subjects = Vec::new();
+    get_filtered_commit_list_with_handler(&git_executor, repo_path, "origin/master", &filter, |commit| {
+      subjects.push(commit.subject);
+      Ok(())
+    })
+    .unwrap();
+    subjects
+  };
+
+  assert_eq!(
+    subjects(CommitFilter {
+      pathspec: vec!["docs".to_string()],
+      ..Default::default()
+    }),
+    vec!["(docs) Old docs", "(docs) New docs"]
+  );
+  // The newest commits are kept, still oldest first
+  assert_eq!(
+    subjects(CommitFilter {
+      max_commits: Some(2),
+      ..Default::default()
+    }),
+    vec!["(parser) Add parser", "(docs) New docs"]
+  );
+  assert_eq!(
+    subjects(CommitFilter {
+      since: Some("@1750000000".to_string()),
+      pathspec: vec!["docs".to_string()],
+      ..Default::default()
+    }),
+    vec!["(docs) New docs"]
+  );
+  assert!(CommitFilter::default().is_empty());
+}
+
+#[test]
+fn test_get_branch_commit_list() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let initial_commit = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_branch_at("origin/master", &initial_commit).unwrap();
+  test_repo.create_commit("(parser) Add parser", "src/parser.rs", "parser");
+  test_repo.create_commit("(docs) Describe the API", "docs/api.md", "API");
+  test_repo.create_commit("(user session) Keep the session", "src/session.rs", "session");
+  test_repo.create_commit("Fix typo", "README.md", "# Test!");
+
+  let subjects = |branch_names: &[&str]| {
+    let branch_names: Vec<String> = branch_names.iter().map(|branch_name| branch_name.to_string()).collect();
+    let mut subjects = Vec::new();
+    get_paged_branch_commit_list_with_handler(&git_executor, repo_path, "origin/master", &branch_names, DEFAULT_COMMIT_PAGE_SIZE, |page| {
+      subjects.extend(page.into_iter().map(|commit| commit.subject));
+      Ok(())
+    })
+    .unwrap();
+    subjects
+  };
+
+  // The prefix `user session` becomes the branch `user-session`
+  assert_eq!(subjects(&["parser", "user-session"]), vec!["(parser) Add parser", "(user session) Keep the session"]);
+  assert_eq!(subjects(&[]), Vec::<String>::new());
+  assert_eq!(
+    get_oldest_commit_id(&git_executor, repo_path, "origin/master").unwrap(),
+    Some(test_repo.rev_parse("HEAD~3").unwrap())
+  );
+}
@@ -1,8 +1,81 @@
 use crate::commit_list::{self, Commit};
+use crate::git_config::{get_config_bool, get_config_value};
 use anyhow::Result;
 use git_executor::git_command_executor::GitCommandExecutor;
 use std::collections::HashMap;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
+
+/// Git config key that enables signing of commits created by Branch Deck (sync, amend, reword)
+pub const SIGN_COMMITS_CONFIG_KEY: &str = "branchdeck.signCommits";
+
+/// Signing settings for rewritten commits, resolved from the repository config
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitSigning {
+  /// Value of `gpg.format` (`openpgp`, `ssh` or `x509`)
+  pub format: String,
+  /// Value of `user.signingkey`; `None` lets gpg pick the key matching the committer email
+  pub key: Option<String>,
+}
+
+impl CommitSigning {
+  fn sign_arg(&self) -> String {
+    match &self.key {
+      Some(key) => format!("-S{key}"),
+      None => "-S".to_string(),
+    }
+  }
+}
+
+/// Resolve commit signing settings for the repository.
+/// Returns `None` unless `branchdeck.signCommits` is enabled, or when no usable key is configured.
+#[instrument(skip(git_executor))]
+pub fn resolve_commit_signing(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<Option<CommitSigning>> {
+  if get_config_bool(git_executor, repo_path, SIGN_COMMITS_CONFIG_KEY)? != Some(true) {
+    return Ok(None);
+  }
+
+  let format = get_config_value(git_executor, repo_path, "gpg.format")?.unwrap_or_else(|| "openpgp".to_string());
+  let key = get_config_value(git_executor, repo_path, "user.signingkey")?.filter(|key| !key.is_empty());
+  // Only gpg can derive the key from the committer identity
+  if key.is_none() && format != "openpgp" {
+    warn!(format = %format, "commit signing is enabled but user.signingkey is not set, commits will not be signed");
+    return Ok(None);
+  }
+  Ok(Some(CommitSigning { format, key }))
+}
+
+/// Create a commit object using `git commit-tree`, signing it when `signing` is set.
+/// If signing fails (missing key, locked agent), the commit is created unsigned instead of failing the operation.
+pub fn commit_tree(
+  git_executor: &GitCommandExecutor,
+  repo_path: &str,
+  tree_id: &str,
+  parent_id: Option<&str>,
+  message: &str,
+  env_vars: &[(&str, &str)],
+  signing: Option<&CommitSigning>,
+) -> Result<String> {
+  let mut args = vec!["commit-tree", tree_id];
+  if let Some(parent) = parent_id {
+    args.push("-p");
+    args.push(parent);
+  }
+  args.push("-m");
+  args.push(message);
+
+  if let Some(signing) = signing {
+    let sign_arg = signing.sign_arg();
+    let mut signed_args = args.clone();
+    signed_args.insert(1, sign_arg.as_str());
+    match git_executor.execute_command_with_env(&signed_args, repo_path, env_vars) {
+      Ok(output) => return Ok(output.trim().to_string()),
+      Err(e) => warn!(error = %e, format = %signing.format, "failed to sign commit, creating it unsigned"),
+    }
+  }
+
+  let output = git_executor.execute_command_with_env(&args, repo_path, env_vars)?;
+  Ok(output.trim().to_string())
+}
 
 /// Prefetch commit metadata for a range and return as a map keyed by commit hash.
 /// Uses a delimiter-based pretty format and the shared parser for robustness.
@@ -33,18 +106,17 @@ pub fn prefetch_commit_infos_map(git_executor: &GitCommandExecutor, repo_path: &
 
 /// Create a commit from a tree using metadata from an existing Commit object.
 /// Allows overriding parent and message while preserving author/committer info.
-#[instrument(skip(git_executor, commit))]
-pub fn create_commit_with_metadata(git_executor: &GitCommandExecutor, repo_path: &str, tree_id: &str, parent_id: Option<&str>, commit: &Commit, message: &str) -> Result<String> {
-  let mut args = vec!["commit-tree", tree_id];
-
-  if let Some(parent) = parent_id.or(commit.parent_id.as_deref()) {
-    args.push("-p");
-    args.push(parent);
-  }
-
-  args.push("-m");
-  args.push(message);
-
+/// The commit is signed when `signing` is set (see [`resolve_commit_signing`]).
+#[instrument(skip(git_executor, commit, signing))]
+pub fn create_commit_with_metadata(
+  git_executor: &GitCommandExecutor,
+  repo_path: &str,
+  tree_id: &str,
+  parent_id: Option<&str>,
+  commit: &Commit,
+  message: &str,
+  signing: Option<&CommitSigning>,
+) -> Result<String> {
   let author_date = commit.author_timestamp.to_string();
   let committer_date = commit.committer_timestamp.to_string();
 
@@ -57,6 +129,105 @@ pub fn create_commit_with_metadata(git_executor: &GitCommandExecutor, repo_path:
     ("GIT_COMMITTER_DATE", &committer_date),
   ];
 
-  let output = git_executor.execute_command_with_env(&args, repo_path, &env_vars)?;
-  Ok(output.trim().to_string())
+  commit_tree(git_executor, repo_path, tree_id, parent_id.or(commit.parent_id.as_deref()), message, &env_vars, signing)
+}
+
+/// Git config key (multi-valued) with trailers added to commits copied to virtual branches.
+/// `{branch}` is replaced with the virtual branch name, e.g. `Branch-Deck-Branch: {branch}`.
+pub const COMMIT_TRAILER_CONFIG_KEY: &str = "branchdeck.commitTrailer";
+
+/// A `Key: value` line of the trailer block at the end of a commit message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trailer {
+  pub key: String,
+  pub value: String,
+}
+
+impl Trailer {
+  /// Parse a `Key: value` line, the key may only contain alphanumerics and dashes (as in `Co-authored-by`)
+  pub fn parse(line: &str) -> Option<Trailer> {
+    let (key, value) = line.split_once(':')?;
+    let value = value.trim();
+    if key.is_empty() || value.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+      return None;
+    }
+    Some(Trailer {
+      key: key.to_string(),
+      value: value.to_string(),
+    })
+  }
+
+  fn is_same(&self, other: &Trailer) -> bool {
+    self.key.eq_ignore_ascii_case(&other.key) && self.value == other.value
+  }
+}
+
+impl std::fmt::Display for Trailer {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}: {}", self.key, self.value)
+  }
+}
+
+/// Last paragraph of the message if it is a trailer block (every line is a trailer or an indented continuation).
+/// The subject is never a trailer block.
+fn trailer_block(message: &str) -> Option<&str> {
+  let message = message.trim_end();
+  let start = message.rfind("\n\n")? + 2;
+  let block = &message[start..];
+  let mut lines = block.lines();
+  if !lines.next().is_some_and(|line| Trailer::parse(line).is_some()) {
+    return None;
+  }
+  lines.all(|line| line.starts_with([' ', '\t']) || Trailer::parse(line).is_some()).then_some(block)
+}
+
+/// Trailers of the message (`Co-authored-by`, `Reviewed-by`, ...) in their original order
+pub fn parse_trailers(message: &str) -> Vec<Trailer> {
+  trailer_block(message).map(|block| block.lines().filter_map(Trailer::parse).collect()).unwrap_or_default()
+}
+
+/// Append trailers to the message, extending its trailer block if it has one.
+/// Trailers the message already has are not repeated, so the result is stable when applied again.
+pub fn add_trailers(message: &str, trailers: &[Trailer]) -> String {
+  let existing = parse_trailers(message);
+  let mut new_trailers: Vec<&Trailer> = Vec::new();
+  for trailer in trailers {
+    if !existing.iter().chain(new_trailers.iter().copied()).any(|it| it.is_same(trailer)) {
+      new_trailers.push(trailer);
+    }
+  }
+  if new_trailers.is_empty() {
+    return message.to_string();
+  }
+
+  let mut result = message.trim_end().to_string();
+  result.push_str(if trailer_block(message).is_some() { "\n" } else { "\n\n" });
+  let lines: Vec<String> = new_trailers.iter().map(|trailer| trailer.to_string()).collect();
+  result.push_str(&lines.join("\n"));
+  result
+}
+
+/// Trailer templates configured for the repository (see [`COMMIT_TRAILER_CONFIG_KEY`])
+#[instrument(skip(git_executor))]
+pub fn load_commit_trailer_templates(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<Vec<String>> {
+  let (output, exit_code) = git_executor.execute_command_with_status(&["config", "--get-all", COMMIT_TRAILER_CONFIG_KEY], repo_path)?;
+  Ok(if exit_code == 0 {
+    output.lines().map(str::trim).filter(|line| !line.is_empty()).map(str::to_string).collect()
+  } else {
+    Vec::new()
+  })
+}
+
+/// Trailers for commits of `branch_name`, templates that are not valid trailers are ignored
+pub fn render_trailer_templates(templates: &[String], branch_name: &str) -> Vec<Trailer> {
+  templates
+    .iter()
+    .filter_map(|template| {
+      let trailer = Trailer::parse(&template.replace("{branch}", branch_name));
+      if trailer.is_none() {
+        warn!(template, "ignoring invalid commit trailer template");
+      }
+      trailer
+    })
+    .collect()
 }
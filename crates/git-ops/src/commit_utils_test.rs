@@ -0,0 +1,132 @@
+use crate::commit_utils::{
+  COMMIT_TRAILER_CONFIG_KEY, CommitSigning, SIGN_COMMITS_CONFIG_KEY, Trailer, add_trailers, create_commit_with_metadata, load_commit_trailer_templates, parse_trailers,
+  prefetch_commit_infos_map, render_trailer_templates, resolve_commit_signing,
+};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_resolve_commit_signing_disabled_by_default() {
+  let test_repo = TestRepo::new();
+  test_repo.set_config("user.signingkey", "ABCDEF").unwrap();
+
+  let signing = resolve_commit_signing(&GitCommandExecutor::new(), test_repo.path().to_str().unwrap()).unwrap();
+  assert_eq!(signing, None);
+}
+
+#[test]
+fn test_resolve_commit_signing_uses_repo_settings() {
+  let test_repo = TestRepo::new();
+  test_repo.set_config(SIGN_COMMITS_CONFIG_KEY, "true").unwrap();
+  test_repo.set_config("gpg.format", "ssh").unwrap();
+  test_repo.set_config("user.signingkey", "~/.ssh/id_ed25519.pub").unwrap();
+
+  let signing = resolve_commit_signing(&GitCommandExecutor::new(), test_repo.path().to_str().unwrap()).unwrap();
+  assert_eq!(
+    signing,
+    Some(CommitSigning {
+      format: "ssh".to_string(),
+      key: Some("~/.ssh/id_ed25519.pub".to_string()),
+    })
+  );
+}
+
+#[test]
+fn test_resolve_commit_signing_ssh_without_key() {
+  let test_repo = TestRepo::new();
+  test_repo.set_config(SIGN_COMMITS_CONFIG_KEY, "true").unwrap();
+  test_repo.set_config("gpg.format", "ssh").unwrap();
+
+  // ssh signing requires an explicit key, so signing is disabled instead of failing every commit
+  let signing = resolve_commit_signing(&GitCommandExecutor::new(), test_repo.path().to_str().unwrap()).unwrap();
+  assert_eq!(signing, None);
+}
+
+#[test]
+fn test_create_commit_falls_back_to_unsigned() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "file.txt", "initial");
+  let commit_id = test_repo.create_commit("Second commit", "file.txt", "changed");
+  let repo_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+
+  let commits = prefetch_commit_infos_map(&git_executor, repo_path, "HEAD~1..HEAD").unwrap();
+  let commit = &commits[&commit_id];
+
+  // Key that doesn't exist: signing fails, the commit is still created
+  let signing = CommitSigning {
+    format: "ssh".to_string(),
+    key: Some("/nonexistent/branch-deck-test-key.pub".to_string()),
+  };
+  let new_id = create_commit_with_metadata(&git_executor, repo_path, &commit.tree_id, None, commit, "Reworded commit", Some(&signing)).unwrap();
+
+  assert_eq!(new_id, git_executor.execute_command(&["rev-parse", "--verify", &new_id], repo_path).unwrap().trim());
+  let raw = git_executor.execute_command(&["cat-file", "commit", &new_id], repo_path).unwrap();
+  assert!(!raw.contains("gpgsig"));
+  assert!(raw.ends_with("Reworded commit"));
+  // Same metadata as the original commit
+  assert_eq!(
+    git_executor.execute_command(&["show", "-s", "--format=%an <%ae> %at", &new_id], repo_path).unwrap().trim(),
+    format!("{} <{}> {}", commit.author_name, commit.author_email, commit.author_timestamp)
+  );
+}
+
+fn trailer(key: &str, value: &str) -> Trailer {
+  Trailer {
+    key: key.to_string(),
+    value: value.to_string(),
+  }
+}
+
+#[test]
+fn test_parse_trailers() {
+  let message = "Add parser\n\nKey: value in the body\nis not a trailer block\n\nCo-authored-by: Jane <jane@example.com>\nReviewed-by: John <john@example.com>\n";
+  assert_eq!(
+    parse_trailers(message),
+    vec![trailer("Co-authored-by", "Jane <jane@example.com>"), trailer("Reviewed-by", "John <john@example.com>")]
+  );
+
+  // The subject and regular paragraphs are not trailers
+  assert_eq!(parse_trailers("Fix: crash on start"), vec![]);
+  assert_eq!(parse_trailers("Add parser\n\nNote: works\nfor nested input"), vec![]);
+}
+
+#[test]
+fn test_add_trailers_round_trip() {
+  let injected = [trailer("Branch-Deck-Branch", "parser")];
+
+  // A new trailer block is started after the body
+  let message = add_trailers("Add parser\n\nSupports nested input\n", &injected);
+  assert_eq!(message, "Add parser\n\nSupports nested input\n\nBranch-Deck-Branch: parser");
+
+  // Existing trailers are kept and extended
+  let message = add_trailers("Add parser\n\nCo-authored-by: Jane <jane@example.com>", &injected);
+  assert_eq!(message, "Add parser\n\nCo-authored-by: Jane <jane@example.com>\nBranch-Deck-Branch: parser");
+  assert_eq!(
+    parse_trailers(&message),
+    vec![trailer("Co-authored-by", "Jane <jane@example.com>"), trailer("Branch-Deck-Branch", "parser")]
+  );
+
+  // Adding the same trailers again doesn't change the message
+  assert_eq!(add_trailers(&message, &injected), message);
+  assert_eq!(add_trailers("Add parser", &[]), "Add parser");
+}
+
+#[test]
+fn test_load_and_render_trailer_templates() {
+  let test_repo = TestRepo::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+  assert_eq!(load_commit_trailer_templates(&git_executor, repo_path).unwrap(), Vec::<String>::new());
+
+  test_repo.set_config(COMMIT_TRAILER_CONFIG_KEY, "Branch-Deck-Branch: {branch}").unwrap();
+  git_executor
+    .execute_command(&["config", "--add", COMMIT_TRAILER_CONFIG_KEY, "not a trailer"], repo_path)
+    .unwrap();
+  let templates = load_commit_trailer_templates(&git_executor, repo_path).unwrap();
+  assert_eq!(templates, vec!["Branch-Deck-Branch: {branch}".to_string(), "not a trailer".to_string()]);
+
+  assert_eq!(render_trailer_templates(&templates, "feature-x"), vec![trailer("Branch-Deck-Branch", "feature-x")]);
+}
@@ -1,3 +1,6 @@
+use crate::diff_options::DiffOptions;
+use crate::file_language::detect_file_language;
+use crate::word_diff::HunkWordDiff;
 use anyhow::{Result, anyhow};
 use git_executor::git_command_executor::GitCommandExecutor;
 use serde::{Deserialize, Serialize};
@@ -19,6 +22,7 @@ pub struct MissingCommit {
   pub author_time: u32,
   pub committer_time: u32,
   pub author: String,
+  /// Conflicting files changed by the commit, by their path at that commit
   pub files_touched: Vec<String>,
   pub file_diffs: Vec<FileDiff>,
 }
@@ -31,6 +35,27 @@ pub struct FileDiff {
   pub old_file: FileInfo,
   pub new_file: FileInfo,
   pub hunks: Vec<String>, // Array of unified diff hunks for git-diff-view
+  /// Hunks were computed ignoring whitespace changes, so whitespace-only changes are not shown
+  #[serde(default)]
+  pub whitespace_ignored: bool,
+  /// Set if the file was renamed or copied, `old_file` is then the file at the old path
+  #[serde(default)]
+  pub rename: Option<FileRename>,
+  /// Word-level changes of each hunk, only computed for the diffs of merge conflicts
+  #[serde(default)]
+  pub word_diffs: Option<Vec<HunkWordDiff>>,
+}
+
+/// Old path of a renamed or copied file, as detected by git (`-M` / `-C`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct FileRename {
+  pub old_path: String,
+  /// Similarity of the old and the new content in percent
+  pub similarity: u32,
+  /// The old path still exists, the file is a copy of it
+  pub copied: bool,
 }
 
 /// Information about a file including its content and metadata.
@@ -75,6 +100,7 @@ pub fn analyze_conflict(
   original_parent_hash: &str,
   target_commit_hash: &str,
   conflicting_files: &[PathBuf],
+  diff_options: &DiffOptions,
 ) -> Result<ConflictAnalysis> {
   // Find merge base
   let merge_base = find_merge_base(git_executor, repo_path, original_parent_hash, target_commit_hash)?;
@@ -83,7 +109,7 @@ pub fn analyze_conflict(
   let merge_base_info = get_commit_info(git_executor, repo_path, &merge_base)?;
 
   // Find missing commits that touch conflicting files
-  let missing_commits = find_missing_commits_for_conflicts(git_executor, repo_path, original_parent_hash, target_commit_hash, conflicting_files)?;
+  let missing_commits = find_missing_commits_for_conflicts(git_executor, repo_path, original_parent_hash, target_commit_hash, conflicting_files, diff_options)?;
 
   // Calculate divergence summary
   let divergence_summary = calculate_divergence(git_executor, repo_path, &merge_base, original_parent_hash, target_commit_hash)?;
@@ -99,7 +125,8 @@ pub fn analyze_conflict(
   })
 }
 
-/// Find commits that are in the source history but not in target that touch specific files
+/// Find commits that are in the source history but not in target that touch specific files.
+/// Renames are followed back in history, so commits that changed a conflicting file under its old path are found too.
 #[instrument(skip(git_executor, conflicting_files))]
 pub(crate) fn find_missing_commits_for_conflicts(
   git_executor: &GitCommandExecutor,
@@ -107,6 +134,7 @@ pub(crate) fn find_missing_commits_for_conflicts(
   original_parent_hash: &str,
   target_commit_hash: &str,
   conflicting_files: &[PathBuf],
+  diff_options: &DiffOptions,
 ) -> Result<Vec<MissingCommit>> {
   let mut missing_commits = Vec::new();
 
@@ -117,106 +145,131 @@ pub(crate) fn find_missing_commits_for_conflicts(
     return Ok(missing_commits);
   }
 
-  // Use a single git log command to get commits and their file changes
-  // This combines rev-list and diff-tree functionality
+  // Use a single git log command to get commits and their file changes.
+  // There is no pathspec, it would hide the other side of a rename, so the files are filtered below.
   let exclude_target = format!("^{target_commit_hash}");
+  let rename_args = diff_options.rename_args();
   let mut args = vec![
     "log",
+    "-z",
     "--format=COMMIT:%H%x00%at%x00%ct%x00%an%x00%s%x00%B", // Use null bytes as delimiters for machine-readable parsing
-    "--name-only",                                         // Show file names changed in each commit
+    "--name-status",                                       // Show files changed in each commit, with the old path of renames
     "--no-merges",
-    original_parent_hash,
-    &exclude_target,
-    "--", // Separator for file paths
   ];
-
-  // Add file paths to filter commits
-  for file_path in &file_paths {
-    args.push(file_path);
-  }
+  args.extend(rename_args.iter().map(String::as_str));
+  args.extend([original_parent_hash, exclude_target.as_str()]);
 
   let output = git_executor.execute_command(&args, repo_path)?;
   if output.trim().is_empty() {
     return Ok(missing_commits);
   }
 
-  let conflicting_files_set: HashSet<String> = file_paths.into_iter().collect();
-
-  // Define type alias for commit data to reduce complexity
-  type CommitData = (String, u32, u32, String, String, String);
-  type CommitWithFiles = (String, u32, u32, String, String, String, Vec<String>);
-
-  // Parse the combined output and collect commit data
-  let mut current_commit: Option<CommitData> = None;
-  let mut current_files: Vec<String> = Vec::new();
-  let mut commits_to_process: Vec<CommitWithFiles> = Vec::new();
-
-  for line in output.lines() {
-    if let Some(commit_data) = line.strip_prefix("COMMIT:") {
-      // Process previous commit if exists
-      if let Some((hash, author_time, committer_time, author, subject, message)) = current_commit.take()
-        && !current_files.is_empty()
-      {
-        commits_to_process.push((hash, author_time, committer_time, author, subject, message, current_files.clone()));
+  // Paths of the conflicting files, extended with the old paths of renames and copies while going back in history
+  let mut tracked_paths: HashSet<String> = file_paths.into_iter().collect();
+
+  // Format: COMMIT:hash\0author_time\0committer_time\0author\0subject\0message\0, followed by the changed files
+  // as \n<status>\0<path>\0 (<status>\0<old path>\0<new path>\0 for renames and copies)
+  let mut fields = output.split('\0');
+  let mut next_field = fields.next();
+  while let Some(field) = next_field {
+    let Some(hash) = field.strip_prefix("COMMIT:") else {
+      next_field = fields.next();
+      continue;
+    };
+    let (Some(author_time), Some(committer_time), Some(author), Some(subject), Some(message)) = (fields.next(), fields.next(), fields.next(), fields.next(), fields.next()) else {
+      return Err(anyhow!("Failed to parse git log output of commit {hash}"));
+    };
+
+    let mut files_touched = Vec::new();
+    let mut old_paths = Vec::new();
+    next_field = fields.next();
+    while let Some(status) = next_field.filter(|field| !field.starts_with("COMMIT:")) {
+      let status = status.trim_start_matches('\n');
+      // The output ends with an empty field
+      if status.is_empty() {
+        next_field = fields.next();
+        continue;
       }
+      let changed_file = parse_changed_file(status, &mut fields).ok_or_else(|| anyhow!("Failed to parse changed files of commit {hash}"))?;
+      next_field = fields.next();
 
-      // Parse new commit line with null-delimited format
-      // Format: hash\0author_time\0committer_time\0author\0subject\0message
-      let parts: Vec<&str> = commit_data.split('\0').collect();
-      if parts.len() >= 6 {
-        let hash = parts[0].to_string();
-        let author_time = parts[1].parse::<u32>().unwrap_or(0);
-        let committer_time = parts[2].parse::<u32>().unwrap_or(0);
-        let author = parts[3].to_string();
-        let subject = parts[4].to_string();
-        let message = parts[5].to_string();
-
-        current_commit = Some((hash, author_time, committer_time, author, subject, message));
-        current_files.clear();
-      }
-    } else if !line.is_empty() && !line.starts_with("commit ") {
-      // This is a file name
-      if conflicting_files_set.contains(line) {
-        current_files.push(line.to_string());
+      if tracked_paths.contains(&changed_file.path) {
+        // Older commits changed the content under its old path
+        if let Some(rename) = &changed_file.rename {
+          old_paths.push(rename.old_path.clone());
+        }
+        files_touched.push(changed_file.path);
+      } else if changed_file
+        .rename
+        .as_ref()
+        .is_some_and(|rename| !rename.copied && tracked_paths.contains(&rename.old_path))
+      {
+        // A conflicting file was moved away
+        files_touched.push(changed_file.path);
       }
     }
-  }
+    tracked_paths.extend(old_paths);
 
-  // Process the last commit
-  if let Some((hash, author_time, committer_time, author, subject, message)) = current_commit
-    && !current_files.is_empty()
-  {
-    commits_to_process.push((hash, author_time, committer_time, author, subject, message, current_files));
+    if files_touched.is_empty() {
+      continue;
+    }
+    debug!(commit_hash = %hash, ?files_touched, "found missing commit that touches conflicting files");
+    missing_commits.push(MissingCommit {
+      hash: hash.to_string(),
+      subject: subject.to_string(),
+      message: message.trim_end().to_string(),
+      author_time: author_time.parse::<u32>().unwrap_or(0),
+      committer_time: committer_time.parse::<u32>().unwrap_or(0),
+      author: author.to_string(),
+      files_touched,
+      file_diffs: Vec::new(),
+    });
   }
 
   // Batch get all file diffs
-  if !commits_to_process.is_empty() {
-    let commit_files_map: Vec<(String, Vec<String>)> = commits_to_process.iter().map(|(hash, _, _, _, _, _, files)| (hash.clone(), files.clone())).collect();
-
-    let all_file_diffs = batch_get_file_diffs(git_executor, repo_path, &commit_files_map)?;
-
-    // Build the final missing commits with their diffs
-    for (hash, author_time, committer_time, author, subject, message, files_touched) in commits_to_process {
-      let file_diffs = all_file_diffs.get(&hash).cloned().unwrap_or_default();
-
-      debug!(commit_hash = %hash, ?files_touched, "found missing commit that touches conflicting files");
-
-      missing_commits.push(MissingCommit {
-        hash,
-        subject,
-        message,
-        author_time,
-        committer_time,
-        author,
-        files_touched,
-        file_diffs,
-      });
+  if !missing_commits.is_empty() {
+    let commit_files_map: Vec<(String, Vec<String>)> = missing_commits.iter().map(|commit| (commit.hash.clone(), commit.files_touched.clone())).collect();
+    let mut all_file_diffs = batch_get_file_diffs(git_executor, repo_path, &commit_files_map, diff_options)?;
+    for commit in &mut missing_commits {
+      commit.file_diffs = all_file_diffs.remove(&commit.hash).unwrap_or_default();
     }
   }
 
   Ok(missing_commits)
 }
 
+/// File listed by `--name-status -z`
+#[derive(Debug)]
+struct ChangedFile {
+  status: char,
+  path: String,
+  rename: Option<FileRename>,
+}
+
+/// Parse a `--name-status -z` entry from its status (e.g. `M` or `R086`) and the fields that follow it.
+/// Renames and copies have the old and the new path, other changes a single path.
+fn parse_changed_file<'a>(status: &str, fields: &mut impl Iterator<Item = &'a str>) -> Option<ChangedFile> {
+  let kind = status.chars().next()?;
+  let first_path = fields.next()?;
+  if matches!(kind, 'R' | 'C') {
+    let path = fields.next()?;
+    return Some(ChangedFile {
+      status: kind,
+      path: path.to_string(),
+      rename: Some(FileRename {
+        old_path: first_path.to_string(),
+        similarity: status[1..].parse().unwrap_or(0),
+        copied: kind == 'C',
+      }),
+    });
+  }
+  Some(ChangedFile {
+    status: kind,
+    path: first_path.to_string(),
+    rename: None,
+  })
+}
+
 /// Find the merge base between two commits
 #[instrument(skip(git_executor))]
 pub(crate) fn find_merge_base(git_executor: &GitCommandExecutor, repo_path: &str, commit1: &str, commit2: &str) -> Result<String> {
@@ -319,13 +372,186 @@ pub(crate) fn batch_rev_parse(git_executor: &GitCommandExecutor, repo_path: &str
   Ok(result)
 }
 
+/// Get the diffs of all files changed by a single commit, used to show a commit diff on demand
+#[instrument(skip(git_executor))]
+pub fn get_commit_file_diffs(git_executor: &GitCommandExecutor, repo_path: &str, commit_hash: &str, diff_options: &DiffOptions) -> Result<Vec<FileDiff>> {
+  // --root lists the files of a commit without parent as added, renamed files are listed by their new path
+  let rename_args = diff_options.rename_args();
+  let mut args = vec!["diff-tree", "--no-commit-id", "--name-only", "-r", "--root"];
+  args.extend(rename_args.iter().map(String::as_str));
+  args.push(commit_hash);
+  let files = git_executor.execute_command_lines(&args, repo_path)?;
+  if files.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  let commit_files_map = [(commit_hash.to_string(), files)];
+  let mut diffs = batch_get_file_diffs(git_executor, repo_path, &commit_files_map, diff_options)?;
+  Ok(diffs.remove(commit_hash).unwrap_or_default())
+}
+
+/// Get the diffs of the files that differ between two tree-ish objects, limited to `path` if given
+#[instrument(skip(git_executor, diff_options))]
+pub fn get_tree_file_diffs(
+  git_executor: &GitCommandExecutor,
+  repo_path: &str,
+  old_tree: &str,
+  new_tree: &str,
+  path: Option<&str>,
+  diff_options: &DiffOptions,
+) -> Result<Vec<FileDiff>> {
+  // Without a pathspec, it would hide the other side of a rename
+  let rename_args = diff_options.rename_args();
+  let mut args = vec!["diff-tree", "-r", "--name-status", "-z"];
+  args.extend(rename_args.iter().map(String::as_str));
+  args.extend([old_tree, new_tree]);
+  let output = git_executor.execute_command(&args, repo_path)?;
+  let is_in_path = |file: &str| path.is_none_or(|path| file == path || file.strip_prefix(path).is_some_and(|rest| rest.starts_with('/')));
+
+  let mut changed_files = Vec::new();
+  let mut renames = HashMap::new();
+  let mut fields = output.split('\0').filter(|field| !field.is_empty());
+  while let Some(status) = fields.next() {
+    let changed_file = parse_changed_file(status, &mut fields).ok_or_else(|| anyhow!("Failed to parse git diff-tree output: {output}"))?;
+    if !is_in_path(&changed_file.path) {
+      continue;
+    }
+    if let Some(rename) = changed_file.rename {
+      renames.insert(changed_file.path.clone(), rename);
+    }
+    changed_files.push(changed_file.path);
+  }
+  if changed_files.is_empty() {
+    return Ok(Vec::new());
+  }
+  let old_paths = old_paths_of(&changed_files, &renames);
+
+  let mut diff_args = vec!["diff".to_string(), "--no-color".to_string()];
+  diff_args.extend(diff_options.to_args());
+  diff_args.extend(rename_args);
+  diff_args.extend([old_tree.to_string(), new_tree.to_string(), "--".to_string()]);
+  diff_args.extend(changed_files.iter().chain(&old_paths).cloned());
+  let diff_args: Vec<&str> = diff_args.iter().map(String::as_str).collect();
+  let diff_output = git_executor.execute_command(&diff_args, repo_path)?;
+
+  let old_contents = get_files_content_at_commit(git_executor, repo_path, old_tree, &old_paths)?;
+  let new_contents = get_files_content_at_commit(git_executor, repo_path, new_tree, &changed_files)?;
+  Ok(build_file_diffs(
+    &changed_files,
+    &renames,
+    &old_contents,
+    &new_contents,
+    &split_diff_by_file(&diff_output, &changed_files),
+    diff_options,
+  ))
+}
+
+/// Paths the files have in the old tree: the old path of renamed and copied files, the same path otherwise
+fn old_paths_of(files: &[String], renames: &HashMap<String, FileRename>) -> Vec<String> {
+  let mut old_paths: Vec<String> = files.iter().map(|file| renames.get(file).map_or(file, |rename| &rename.old_path).clone()).collect();
+  old_paths.sort();
+  old_paths.dedup();
+  old_paths
+}
+
+/// How a file changed between two trees
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub enum FileChangeKind {
+  Added,
+  Deleted,
+  Modified,
+  /// File mode or type changed, e.g., a file replaced by a symlink
+  TypeChanged,
+  Renamed,
+  Copied,
+}
+
+/// Changed file with its line counts, without content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct FileChangeStat {
+  pub path: String,
+  pub kind: FileChangeKind,
+  pub additions: u32,
+  pub deletions: u32,
+  /// Binary files have no line counts
+  pub binary: bool,
+  /// Old path of a renamed or copied file
+  pub rename: Option<FileRename>,
+}
+
+/// List the files that differ between two tree-ish objects with their added and deleted line counts, sorted by path.
+/// Renamed and copied files are listed once by their new path, the counts are relative to the old path.
+#[instrument(skip(git_executor, diff_options))]
+pub fn get_file_change_stats(git_executor: &GitCommandExecutor, repo_path: &str, old_tree: &str, new_tree: &str, diff_options: &DiffOptions) -> Result<Vec<FileChangeStat>> {
+  let rename_args = diff_options.rename_args();
+  let mut status_args = vec!["diff-tree", "-r", "--name-status", "-z"];
+  status_args.extend(rename_args.iter().map(String::as_str));
+  status_args.extend([old_tree, new_tree]);
+  let status_output = git_executor.execute_command(&status_args, repo_path)?;
+  let mut numstat_args = vec!["diff".to_string(), "--numstat".to_string(), "-z".to_string()];
+  numstat_args.extend(diff_options.to_args());
+  numstat_args.extend(rename_args);
+  numstat_args.extend([old_tree.to_string(), new_tree.to_string()]);
+  let numstat_args: Vec<&str> = numstat_args.iter().map(String::as_str).collect();
+  let numstat_output = git_executor.execute_command(&numstat_args, repo_path)?;
+
+  // Format: <added>\t<deleted>\t<path>\0, or <added>\t<deleted>\t\0<old path>\0<new path>\0 for renames and copies,
+  // with `-` counts for binary files
+  let mut line_counts: HashMap<&str, (Option<u32>, Option<u32>)> = HashMap::new();
+  let mut entries = numstat_output.split('\0').filter(|entry| !entry.is_empty());
+  while let Some(entry) = entries.next() {
+    let mut parts = entry.splitn(3, '\t');
+    if let (Some(additions), Some(deletions), Some(path)) = (parts.next(), parts.next(), parts.next()) {
+      let path = if path.is_empty() { entries.nth(1) } else { Some(path) };
+      if let Some(path) = path {
+        line_counts.insert(path, (additions.parse().ok(), deletions.parse().ok()));
+      }
+    }
+  }
+
+  // Format: <status>\0<path>\0, or <status><similarity>\0<old path>\0<new path>\0 for renames and copies
+  let mut stats = Vec::new();
+  let mut fields = status_output.split('\0').filter(|field| !field.is_empty());
+  while let Some(status) = fields.next() {
+    let Some(changed_file) = parse_changed_file(status, &mut fields) else {
+      break;
+    };
+    let kind = match changed_file.status {
+      'A' => FileChangeKind::Added,
+      'D' => FileChangeKind::Deleted,
+      'T' => FileChangeKind::TypeChanged,
+      'R' => FileChangeKind::Renamed,
+      'C' => FileChangeKind::Copied,
+      _ => FileChangeKind::Modified,
+    };
+    // Files with whitespace-only changes are not counted when whitespace is ignored
+    let (additions, deletions) = line_counts.get(changed_file.path.as_str()).copied().unwrap_or((Some(0), Some(0)));
+    stats.push(FileChangeStat {
+      path: changed_file.path,
+      kind,
+      additions: additions.unwrap_or(0),
+      deletions: deletions.unwrap_or(0),
+      binary: additions.is_none() && deletions.is_none(),
+      rename: changed_file.rename,
+    });
+  }
+  stats.sort_by(|a, b| a.path.cmp(&b.path));
+  Ok(stats)
+}
+
 /// Batch get file diffs for multiple commits
-/// This function optimizes getting diffs for multiple commits by batching operations
+/// This function optimizes getting diffs for multiple commits by batching operations.
+/// Files are given by their path at the commit, renamed and copied files are diffed against their old path.
 #[instrument(skip(git_executor, commit_files_map))]
 pub(crate) fn batch_get_file_diffs(
   git_executor: &GitCommandExecutor,
   repo_path: &str,
   commit_files_map: &[(String, Vec<String>)], // (commit_hash, files)
+  diff_options: &DiffOptions,
 ) -> Result<HashMap<String, Vec<FileDiff>>> {
   let mut result = HashMap::new();
 
@@ -353,6 +579,13 @@ pub(crate) fn batch_get_file_diffs(
     }
   }
 
+  // Renamed and copied files have their old content at another path in the parent
+  let mut renames_by_commit: HashMap<&str, HashMap<String, FileRename>> = HashMap::new();
+  for commit_hash in all_files_by_commit.keys() {
+    renames_by_commit.insert(commit_hash, find_commit_renames(git_executor, repo_path, commit_hash, diff_options)?);
+  }
+  let no_renames = HashMap::new();
+
   // Batch fetch all file contents for all commits at once
   let mut all_contents: HashMap<String, HashMap<String, String>> = HashMap::new();
 
@@ -363,88 +596,145 @@ pub(crate) fn batch_get_file_diffs(
   }
 
   // Also fetch parent contents
+  let all_old_files: Vec<String> = commit_files_map
+    .iter()
+    .flat_map(|(commit_hash, files)| old_paths_of(files, renames_by_commit.get(commit_hash.as_str()).unwrap_or(&no_renames)))
+    .collect::<HashSet<_>>()
+    .into_iter()
+    .collect();
   for parent_hash in &parent_commits {
-    let all_files: Vec<String> = all_files_by_commit
-      .values()
-      .flat_map(|files| files.iter().cloned())
-      .collect::<HashSet<_>>()
-      .into_iter()
-      .collect();
-    let contents = get_files_content_at_commit(git_executor, repo_path, parent_hash, &all_files)?;
+    let contents = get_files_content_at_commit(git_executor, repo_path, parent_hash, &all_old_files)?;
     all_contents.insert(parent_hash.clone(), contents);
   }
 
   // Now generate diffs for each commit
+  let diff_args = diff_options.to_args();
+  let rename_args = diff_options.rename_args();
   for (commit_hash, files) in commit_files_map {
     let parent_ref = format!("{commit_hash}^");
     let parent_hash = resolved_parents
       .get(&parent_ref)
       .cloned()
       .unwrap_or_else(|| "4b825dc642cb6eb9a060e54bf8d69288fbee4904".to_string());
+    let renames = renames_by_commit.get(commit_hash.as_str()).unwrap_or(&no_renames);
+    let old_paths = old_paths_of(files, renames);
 
-    // Get the unified diff for all files at once
-    let mut show_args = vec!["-c", "merge.conflictStyle=zdiff3", "show", "--no-color", "--format=", "--unified=3", commit_hash, "--"];
-    show_args.extend(files.iter().map(|s| s.as_str()));
+    // Get the unified diff for all files at once, the old paths are needed to detect renames
+    let mut show_args = vec!["-c", "merge.conflictStyle=zdiff3", "show", "--no-color", "--format="];
+    show_args.extend(diff_args.iter().map(|s| s.as_str()));
+    show_args.extend(rename_args.iter().map(|s| s.as_str()));
+    show_args.extend([commit_hash.as_str(), "--"]);
+    show_args.extend(files.iter().chain(&old_paths).map(|s| s.as_str()));
 
     let diff_output = git_executor.execute_command(&show_args, repo_path)?;
+    let file_to_diff = split_diff_by_file(&diff_output, files);
 
-    // Parse the diff output
-    let mut file_to_diff: HashMap<String, String> = HashMap::new();
-    let mut current_file_diff = String::new();
-    let mut current_file: Option<&str> = None;
+    let empty_map = HashMap::new();
+    let parent_contents = all_contents.get(&parent_hash).unwrap_or(&empty_map);
+    let current_contents = all_contents.get(commit_hash).unwrap_or(&empty_map);
+    let file_diffs = build_file_diffs(files, renames, parent_contents, current_contents, &file_to_diff, diff_options);
+    result.insert(commit_hash.clone(), file_diffs);
+  }
 
-    for line in diff_output.lines() {
-      if line.starts_with("diff --git") {
-        if let Some(file) = current_file {
-          file_to_diff.insert(file.to_string(), current_file_diff.clone());
-        }
-        if let Some(file) = files.iter().find(|f| line.contains(f.as_str())) {
-          current_file = Some(file);
-          current_file_diff.clear();
-        }
-      }
-      if current_file.is_some() {
-        current_file_diff.push_str(line);
-        current_file_diff.push('\n');
-      }
-    }
+  Ok(result)
+}
+
+/// Renamed and copied files of a commit compared to its first parent, keyed by their new path
+#[instrument(skip(git_executor, diff_options))]
+fn find_commit_renames(git_executor: &GitCommandExecutor, repo_path: &str, commit_hash: &str, diff_options: &DiffOptions) -> Result<HashMap<String, FileRename>> {
+  let mut renames = HashMap::new();
+  if diff_options.rename_similarity == 0 {
+    return Ok(renames);
+  }
 
-    if let Some(file) = current_file {
-      file_to_diff.insert(file.to_string(), current_file_diff);
+  let rename_args = diff_options.rename_args();
+  let mut args = vec!["diff-tree", "--no-commit-id", "-r", "--root", "--name-status", "-z"];
+  args.extend(rename_args.iter().map(String::as_str));
+  args.push(commit_hash);
+  let output = git_executor.execute_command(&args, repo_path)?;
+
+  let mut fields = output.split('\0').filter(|field| !field.is_empty());
+  while let Some(status) = fields.next() {
+    let changed_file = parse_changed_file(status, &mut fields).ok_or_else(|| anyhow!("Failed to parse changed files of commit {commit_hash}"))?;
+    if let Some(rename) = changed_file.rename {
+      renames.insert(changed_file.path, rename);
     }
+  }
+  Ok(renames)
+}
 
-    // Build FileDiff objects
-    let mut file_diffs = Vec::new();
-    let empty_map = HashMap::new();
-    let parent_contents = all_contents.get(&parent_hash).unwrap_or(&empty_map);
-    let current_contents = all_contents.get(commit_hash).unwrap_or(&empty_map);
+/// Split a multi-file unified diff into the diff of each of the given files, matched by their new path
+fn split_diff_by_file(diff_output: &str, files: &[String]) -> HashMap<String, String> {
+  let mut file_to_diff: HashMap<String, String> = HashMap::new();
+  let mut current_file_diff = String::new();
+  let mut current_file: Option<&str> = None;
 
-    for file in files {
-      let ext = file.split('.').next_back().unwrap_or("").to_string();
-      let old_content = parent_contents.get(file).cloned().unwrap_or_default();
-      let new_content = current_contents.get(file).cloned().unwrap_or_default();
-      let diff = file_to_diff.get(file).cloned().unwrap_or_default();
-      let hunks = if diff.trim().is_empty() { vec![] } else { vec![diff] };
-
-      file_diffs.push(FileDiff {
-        old_file: FileInfo {
-          file_name: file.clone(),
-          file_lang: ext.clone(),
-          content: old_content,
-        },
-        new_file: FileInfo {
-          file_name: file.clone(),
-          file_lang: ext,
-          content: new_content,
-        },
-        hunks,
-      });
+  for line in diff_output.lines() {
+    if line.starts_with("diff --git") {
+      if let Some(file) = current_file.take() {
+        file_to_diff.insert(file.to_string(), current_file_diff.clone());
+      }
+      // The header of a rename has both paths, `diff --git a/<old path> b/<new path>`
+      let file = files
+        .iter()
+        .find(|f| line.ends_with(&format!(" b/{f}")))
+        .or_else(|| files.iter().find(|f| line.contains(f.as_str())));
+      if let Some(file) = file {
+        current_file = Some(file);
+        current_file_diff.clear();
+      }
+    }
+    if current_file.is_some() {
+      current_file_diff.push_str(line);
+      current_file_diff.push('\n');
     }
+  }
 
-    result.insert(commit_hash.clone(), file_diffs);
+  if let Some(file) = current_file {
+    file_to_diff.insert(file.to_string(), current_file_diff);
   }
+  file_to_diff
+}
 
-  Ok(result)
+/// Build the `FileDiff` of each file from its old and new content and its part of the unified diff.
+/// Old contents are keyed by the old path, which differs from the new one for renamed and copied files.
+fn build_file_diffs(
+  files: &[String],
+  renames: &HashMap<String, FileRename>,
+  old_contents: &HashMap<String, String>,
+  new_contents: &HashMap<String, String>,
+  file_to_diff: &HashMap<String, String>,
+  diff_options: &DiffOptions,
+) -> Vec<FileDiff> {
+  let mut file_diffs = Vec::with_capacity(files.len());
+  for file in files {
+    let rename = renames.get(file).cloned();
+    let old_path = rename.as_ref().map_or(file, |rename| &rename.old_path);
+    let old_content = old_contents.get(old_path).cloned().unwrap_or_default();
+    let new_content = new_contents.get(file).cloned().unwrap_or_default();
+    // Deleted files have no new content
+    let file_lang = detect_file_language(file, if new_content.is_empty() { &old_content } else { &new_content });
+    let diff = file_to_diff.get(file).cloned().unwrap_or_default();
+    let hunks = if diff.trim().is_empty() { vec![] } else { vec![diff] };
+
+    file_diffs.push(FileDiff {
+      old_file: FileInfo {
+        file_name: old_path.clone(),
+        file_lang: file_lang.clone(),
+        content: old_content,
+      },
+      new_file: FileInfo {
+        file_name: file.clone(),
+        file_lang,
+        content: new_content,
+      },
+      hunks,
+      whitespace_ignored: diff_options.ignore_whitespace,
+      rename,
+      word_diffs: None,
+    });
+  }
+  file_diffs
 }
 
 /// Get content of multiple files at a specific commit using batch operation
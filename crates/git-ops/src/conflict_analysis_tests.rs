@@ -1,4 +1,8 @@
 use super::conflict_analysis::*;
+use super::diff_options::{
+  AUTOCRLF_CONFIG_KEY, DIFF_ALGORITHM_CONFIG_KEY, DIFF_CONTEXT_LINES_CONFIG_KEY, DIFF_IGNORE_WHITESPACE_CONFIG_KEY, DiffAlgorithm, DiffOptions, RENAME_SIMILARITY_CONFIG_KEY,
+  load_diff_options,
+};
 use anyhow::{Result, anyhow};
 use git_executor::git_command_executor::GitCommandExecutor;
 use pretty_assertions::assert_eq;
@@ -85,7 +89,7 @@ fn test_batch_get_file_diffs_single_commit() {
   // Get file diffs using batch function
   let files = vec!["test.kt".to_string()];
   let commit_files_map = vec![(commit2.clone(), files)];
-  let result = batch_get_file_diffs(git_executor, test_repo.path().to_str().unwrap(), &commit_files_map);
+  let result = batch_get_file_diffs(git_executor, test_repo.path().to_str().unwrap(), &commit_files_map, &DiffOptions::default());
 
   if let Err(e) = &result {
     tracing::error!(error = %e, "Error in test_batch_get_file_diffs_single_commit");
@@ -101,8 +105,8 @@ fn test_batch_get_file_diffs_single_commit() {
   let diff = &diffs[0];
   assert_eq!(diff.old_file.file_name, "test.kt");
   assert_eq!(diff.new_file.file_name, "test.kt");
-  assert_eq!(diff.old_file.file_lang, "kt");
-  assert_eq!(diff.new_file.file_lang, "kt");
+  assert_eq!(diff.old_file.file_lang, "kotlin");
+  assert_eq!(diff.new_file.file_lang, "kotlin");
   assert_eq!(diff.old_file.content, "class Test {}");
   assert_eq!(diff.new_file.content, "class Test {\n  fun hello() {}\n}");
 }
@@ -121,7 +125,7 @@ fn test_batch_get_file_diffs_with_hunk_format() {
   // Get file diffs using batch function
   let files = vec!["test.rs".to_string()];
   let commit_files_map = vec![(commit2.clone(), files)];
-  let result = batch_get_file_diffs(git_executor, test_repo.path().to_str().unwrap(), &commit_files_map);
+  let result = batch_get_file_diffs(git_executor, test_repo.path().to_str().unwrap(), &commit_files_map, &DiffOptions::default());
 
   assert!(result.is_ok());
   let all_diffs = result.unwrap();
@@ -177,7 +181,7 @@ fn test_get_file_diffs_multiple_files() {
   // Get diffs for both files
   let files = vec!["file1.txt".to_string(), "file2.txt".to_string()];
   let commit_files_map = vec![(commit_hash.clone(), files)];
-  let result = batch_get_file_diffs(git_executor, test_repo.path().to_str().unwrap(), &commit_files_map);
+  let result = batch_get_file_diffs(git_executor, test_repo.path().to_str().unwrap(), &commit_files_map, &DiffOptions::default());
 
   if let Err(e) = &result {
     tracing::error!(error = %e, "Error in test_get_file_diffs_multiple_files");
@@ -247,7 +251,7 @@ fn test_get_file_diffs_new_file() {
   // Get diff for the new file
   let files = vec!["new_file.txt".to_string()];
   let commit_files_map = vec![(commit_hash.clone(), files)];
-  let result = batch_get_file_diffs(git_executor, test_repo.path().to_str().unwrap(), &commit_files_map);
+  let result = batch_get_file_diffs(git_executor, test_repo.path().to_str().unwrap(), &commit_files_map, &DiffOptions::default());
 
   // Should succeed with empty old content
   assert!(result.is_ok());
@@ -304,7 +308,15 @@ fn test_null_terminated_parsing_in_find_missing_commits() {
 
   // Test find_missing_commits_for_conflicts with files that were touched
   let conflicting_files = vec![PathBuf::from("file1.txt")];
-  let missing = find_missing_commits_for_conflicts(git_executor, test_repo.path().to_str().unwrap(), &branch_commit, &commit3, &conflicting_files).unwrap();
+  let missing = find_missing_commits_for_conflicts(
+    git_executor,
+    test_repo.path().to_str().unwrap(),
+    &branch_commit,
+    &commit3,
+    &conflicting_files,
+    &DiffOptions::default(),
+  )
+  .unwrap();
 
   // Should find the branch commit as missing
   assert_eq!(missing.len(), 1, "Should find 1 missing commit");
@@ -347,7 +359,15 @@ fn test_find_missing_commits_with_author_and_committer_times() {
 
   // Test find_missing_commits_for_conflicts
   let conflicting_files = vec![PathBuf::from("file1.txt")];
-  let missing = find_missing_commits_for_conflicts(git_executor, test_repo.path().to_str().unwrap(), &branch_commit, "HEAD", &conflicting_files).unwrap();
+  let missing = find_missing_commits_for_conflicts(
+    git_executor,
+    test_repo.path().to_str().unwrap(),
+    &branch_commit,
+    "HEAD",
+    &conflicting_files,
+    &DiffOptions::default(),
+  )
+  .unwrap();
 
   // Verify we capture both timestamps
   assert!(!missing.is_empty(), "Should find missing commits");
@@ -409,12 +429,28 @@ fn test_different_author_and_committer_times() {
 
   // Find missing commits
   let conflicting_files = vec![PathBuf::from("file.txt")];
-  let missing = find_missing_commits_for_conflicts(git_executor, test_repo.path().to_str().unwrap(), &branch_commit, &initial, &conflicting_files).unwrap();
+  let missing = find_missing_commits_for_conflicts(
+    git_executor,
+    test_repo.path().to_str().unwrap(),
+    &branch_commit,
+    &initial,
+    &conflicting_files,
+    &DiffOptions::default(),
+  )
+  .unwrap();
 
   // We might find the test commit if it modified file.txt, but if not, let's check with test.txt
   if missing.is_empty() {
     let conflicting_files = vec![PathBuf::from("test.txt")];
-    let missing = find_missing_commits_for_conflicts(git_executor, test_repo.path().to_str().unwrap(), &branch_commit, &initial, &conflicting_files).unwrap();
+    let missing = find_missing_commits_for_conflicts(
+      git_executor,
+      test_repo.path().to_str().unwrap(),
+      &branch_commit,
+      &initial,
+      &conflicting_files,
+      &DiffOptions::default(),
+    )
+    .unwrap();
 
     // Should find the test commit
     assert!(!missing.is_empty(), "Should find at least one missing commit");
@@ -493,7 +529,15 @@ fn test_cherry_pick_preserves_author_time() {
   test_repo.checkout(&base).unwrap();
 
   let conflicting_files = vec![PathBuf::from("feature.txt")];
-  let missing = find_missing_commits_for_conflicts(git_executor, test_repo.path().to_str().unwrap(), &source_head, &base, &conflicting_files).unwrap();
+  let missing = find_missing_commits_for_conflicts(
+    git_executor,
+    test_repo.path().to_str().unwrap(),
+    &source_head,
+    &base,
+    &conflicting_files,
+    &DiffOptions::default(),
+  )
+  .unwrap();
 
   // Verify the commit has preserved author time but different committer time
   assert_eq!(missing.len(), 1, "Should find exactly one missing commit");
@@ -555,7 +599,15 @@ fn test_rebase_changes_committer_time_only() {
   test_repo.checkout(&base1).unwrap();
 
   let conflicting_files = vec![PathBuf::from("feature1.txt")];
-  let missing = find_missing_commits_for_conflicts(git_executor, test_repo.path().to_str().unwrap(), &feature_head, &base1, &conflicting_files).unwrap();
+  let missing = find_missing_commits_for_conflicts(
+    git_executor,
+    test_repo.path().to_str().unwrap(),
+    &feature_head,
+    &base1,
+    &conflicting_files,
+    &DiffOptions::default(),
+  )
+  .unwrap();
 
   // Verify we found at least one feature commit
   assert!(!missing.is_empty(), "Should find at least one feature commit");
@@ -795,7 +847,15 @@ fn test_author_names_with_spaces() {
   test_repo.checkout(&initial_commit).unwrap();
 
   let conflicting_files = vec![PathBuf::from("test.txt")];
-  let missing = find_missing_commits_for_conflicts(git_executor, test_repo.path().to_str().unwrap(), &branch_commit, &initial_commit, &conflicting_files).unwrap();
+  let missing = find_missing_commits_for_conflicts(
+    git_executor,
+    test_repo.path().to_str().unwrap(),
+    &branch_commit,
+    &initial_commit,
+    &conflicting_files,
+    &DiffOptions::default(),
+  )
+  .unwrap();
 
   // Verify the author name is correctly parsed
   assert!(!missing.is_empty(), "Should find missing commits");
@@ -805,3 +865,226 @@ fn test_author_names_with_spaces() {
   assert!(mary_jane_commit.is_some(), "Should find commit by Mary Jane Watson");
   assert_eq!(mary_jane_commit.unwrap().author, "Mary Jane Watson", "Author name with spaces should be correctly parsed");
 }
+
+#[test]
+fn test_get_commit_file_diffs() {
+  let test_repo = TestRepo::new();
+  let git_executor = &GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let root_commit = test_repo.create_commit("Initial commit", "test.kt", "class Test {}");
+  let commit = test_repo.create_commit("Add method", "test.kt", "class Test {\n  fun hello() {}\n}");
+
+  let diffs = get_commit_file_diffs(git_executor, repo_path, &commit, &DiffOptions::default()).unwrap();
+  assert_eq!(diffs.len(), 1);
+  assert_eq!(diffs[0].new_file.file_name, "test.kt");
+  assert_eq!(diffs[0].old_file.content, "class Test {}");
+  assert_eq!(diffs[0].new_file.content, "class Test {\n  fun hello() {}\n}");
+  assert!(!diffs[0].hunks.is_empty());
+
+  // Files of the root commit are reported as added
+  let diffs = get_commit_file_diffs(git_executor, repo_path, &root_commit, &DiffOptions::default()).unwrap();
+  assert_eq!(diffs.len(), 1);
+  assert_eq!(diffs[0].old_file.content, "");
+  assert_eq!(diffs[0].new_file.content, "class Test {}");
+}
+
+#[test]
+fn test_get_commit_file_diffs_with_diff_options() {
+  let test_repo = TestRepo::new();
+  let git_executor = &GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  test_repo.create_commit("Initial commit", "test.txt", "a\nb\nc\nd\ne\nf\ng\n");
+  let commit = test_repo.create_commit("Change middle line", "test.txt", "a\nb\nc\nD\ne\nf\ng\n");
+
+  let diffs = get_commit_file_diffs(git_executor, repo_path, &commit, &DiffOptions::default()).unwrap();
+  assert!(diffs[0].hunks[0].contains("@@ -1,7 +1,7 @@"), "{}", diffs[0].hunks[0]);
+
+  let options = DiffOptions {
+    context_lines: 0,
+    algorithm: DiffAlgorithm::Histogram,
+    ..DiffOptions::default()
+  };
+  let diffs = get_commit_file_diffs(git_executor, repo_path, &commit, &options).unwrap();
+  assert!(diffs[0].hunks[0].contains("@@ -4 +4 @@"), "{}", diffs[0].hunks[0]);
+  assert!(!diffs[0].hunks[0].lines().any(|line| line == " c"), "{}", diffs[0].hunks[0]);
+}
+
+#[test]
+fn test_get_commit_file_diffs_ignoring_whitespace() {
+  let test_repo = TestRepo::new();
+  let git_executor = &GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  test_repo.create_commit("Initial commit", "test.txt", "a\n  b\n");
+  let commit = test_repo.create_commit("Reindent", "test.txt", "a\n    b\n");
+
+  let diffs = get_commit_file_diffs(git_executor, repo_path, &commit, &DiffOptions::default()).unwrap();
+  assert_eq!(diffs[0].hunks.len(), 1);
+  assert!(!diffs[0].whitespace_ignored);
+
+  let options = DiffOptions {
+    ignore_whitespace: true,
+    ..DiffOptions::default()
+  };
+  let diffs = get_commit_file_diffs(git_executor, repo_path, &commit, &options).unwrap();
+  // The file is still listed, but the whitespace-only change produces no hunks
+  assert_eq!(diffs.len(), 1);
+  assert!(diffs[0].hunks.is_empty(), "{:?}", diffs[0].hunks);
+  assert!(diffs[0].whitespace_ignored);
+}
+
+#[test]
+fn test_load_diff_options() {
+  let test_repo = TestRepo::new();
+  let git_executor = &GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  // Git for Windows enables it system-wide
+  test_repo.set_config(AUTOCRLF_CONFIG_KEY, "false").unwrap();
+
+  assert_eq!(load_diff_options(git_executor, repo_path), DiffOptions::default());
+
+  git_executor.execute_command(&["config", DIFF_CONTEXT_LINES_CONFIG_KEY, "10"], repo_path).unwrap();
+  git_executor.execute_command(&["config", DIFF_ALGORITHM_CONFIG_KEY, "Patience"], repo_path).unwrap();
+  git_executor.execute_command(&["config", DIFF_IGNORE_WHITESPACE_CONFIG_KEY, "true"], repo_path).unwrap();
+  git_executor.execute_command(&["config", RENAME_SIMILARITY_CONFIG_KEY, "75%"], repo_path).unwrap();
+  assert_eq!(
+    load_diff_options(git_executor, repo_path),
+    DiffOptions {
+      context_lines: 10,
+      algorithm: DiffAlgorithm::Patience,
+      ignore_whitespace: true,
+      ignore_whitespace_in_merges: false,
+      ignore_cr_at_eol: false,
+      ignore_cr_at_eol_in_merges: false,
+      rename_similarity: 75,
+    }
+  );
+
+  // Invalid values fall back to the defaults
+  git_executor.execute_command(&["config", DIFF_CONTEXT_LINES_CONFIG_KEY, "many"], repo_path).unwrap();
+  git_executor.execute_command(&["config", DIFF_ALGORITHM_CONFIG_KEY, "myers2"], repo_path).unwrap();
+  git_executor
+    .execute_command(&["config", DIFF_IGNORE_WHITESPACE_CONFIG_KEY, "sometimes"], repo_path)
+    .unwrap();
+  git_executor.execute_command(&["config", RENAME_SIMILARITY_CONFIG_KEY, "150"], repo_path).unwrap();
+  assert_eq!(load_diff_options(git_executor, repo_path), DiffOptions::default());
+
+  // CRLF-only differences are ignored when git converts line endings
+  test_repo.set_config(AUTOCRLF_CONFIG_KEY, "input").unwrap();
+  let options = load_diff_options(git_executor, repo_path);
+  assert!(options.ignore_cr_at_eol);
+  assert!(options.to_args().contains(&"--ignore-cr-at-eol".to_string()));
+  // Applied commits keep their line endings, only conflict checks ignore them
+  assert_eq!(options.merge_args(), Vec::<&str>::new());
+  assert_eq!(DiffOptions::for_conflict_checks(git_executor, repo_path).merge_args(), vec!["-Xignore-cr-at-eol"]);
+}
+
+/// Content similar enough to be detected as a rename after a one-line change
+const RENAMED_CONTENT: &str = "fn parse() {\n  let a = 1;\n  let b = 2;\n  let c = 3;\n  a + b + c\n}\n";
+
+#[test]
+fn test_find_missing_commits_follows_renames() {
+  let test_repo = TestRepo::new();
+  let git_executor = &GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let base = test_repo.create_commit("Add parser", "old.rs", RENAMED_CONTENT);
+  let edit = test_repo.create_commit("Change parser", "old.rs", &RENAMED_CONTENT.replace("a = 1", "a = 10"));
+  git_executor.execute_command(&["mv", "old.rs", "new.rs"], repo_path).unwrap();
+  let moved_content = RENAMED_CONTENT.replace("a = 1", "a = 10").replace("c = 3", "c = 30");
+  let moved = test_repo.create_commit("Move parser", "new.rs", &moved_content);
+
+  let missing = find_missing_commits_for_conflicts(git_executor, repo_path, &moved, &base, &[PathBuf::from("new.rs")], &DiffOptions::default()).unwrap();
+
+  let hashes: Vec<&str> = missing.iter().map(|commit| commit.hash.as_str()).collect();
+  assert_eq!(hashes, vec![moved.as_str(), edit.as_str()]);
+
+  // The move is a rename with its actual changes, not an added file
+  assert_eq!(missing[0].files_touched, vec!["new.rs"]);
+  let diff = &missing[0].file_diffs[0];
+  let rename = diff.rename.as_ref().expect("rename should be detected");
+  assert_eq!(rename.old_path, "old.rs");
+  assert!(!rename.copied);
+  assert!(rename.similarity >= 50, "{rename:?}");
+  assert_eq!(diff.old_file.file_name, "old.rs");
+  assert_eq!(diff.new_file.file_name, "new.rs");
+  assert_eq!(diff.old_file.content, RENAMED_CONTENT.replace("a = 1", "a = 10"));
+  assert!(diff.hunks[0].contains("rename from old.rs"), "{}", diff.hunks[0]);
+  assert!(diff.hunks[0].contains("-  let c = 3;\n+  let c = 30;"), "{}", diff.hunks[0]);
+
+  // The older commit changed the file under its old path
+  assert_eq!(missing[1].files_touched, vec!["old.rs"]);
+  assert!(missing[1].file_diffs[0].rename.is_none());
+}
+
+#[test]
+fn test_commit_file_diffs_with_and_without_rename_detection() {
+  let test_repo = TestRepo::new();
+  let git_executor = &GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let base = test_repo.create_commit("Add parser", "old.rs", RENAMED_CONTENT);
+  git_executor.execute_command(&["mv", "old.rs", "new.rs"], repo_path).unwrap();
+  let moved = test_repo.create_commit("Move parser", "new.rs", RENAMED_CONTENT);
+
+  let diffs = get_commit_file_diffs(git_executor, repo_path, &moved, &DiffOptions::default()).unwrap();
+  assert_eq!(diffs.len(), 1);
+  assert_eq!(diffs[0].rename.as_ref().map(|rename| (rename.old_path.as_str(), rename.similarity)), Some(("old.rs", 100)));
+  assert_eq!(diffs[0].old_file.content, RENAMED_CONTENT);
+
+  let options = DiffOptions {
+    rename_similarity: 0,
+    ..DiffOptions::default()
+  };
+  let diffs = get_commit_file_diffs(git_executor, repo_path, &moved, &options).unwrap();
+  let files: Vec<&str> = diffs.iter().map(|diff| diff.new_file.file_name.as_str()).collect();
+  assert_eq!(files, vec!["new.rs", "old.rs"]);
+  assert!(diffs.iter().all(|diff| diff.rename.is_none()));
+
+  let stats = get_file_change_stats(git_executor, repo_path, &base, &moved, &options).unwrap();
+  let kinds: Vec<FileChangeKind> = stats.iter().map(|stat| stat.kind).collect();
+  assert_eq!(kinds, vec![FileChangeKind::Added, FileChangeKind::Deleted]);
+}
+
+#[test]
+fn test_file_change_stats_with_renames_and_copies() {
+  let test_repo = TestRepo::new();
+  let git_executor = &GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let base = test_repo.create_commit("Add parser", "old.rs", RENAMED_CONTENT);
+  git_executor.execute_command(&["mv", "old.rs", "new.rs"], repo_path).unwrap();
+  test_repo.create_commit("Move parser", "new.rs", &RENAMED_CONTENT.replace("c = 3", "c = 30"));
+  // A copy is detected when its source is changed in the same diff
+  let head = test_repo.create_commit_with_files("Copy parser", &[("copy.rs", RENAMED_CONTENT), ("new.rs", &RENAMED_CONTENT.replace("c = 3", "c = 31"))]);
+
+  let stats = get_file_change_stats(git_executor, repo_path, &base, &head, &DiffOptions::default()).unwrap();
+  let summary: Vec<(&str, FileChangeKind, Option<&str>, u32, u32)> = stats
+    .iter()
+    .map(|stat| {
+      (
+        stat.path.as_str(),
+        stat.kind,
+        stat.rename.as_ref().map(|rename| rename.old_path.as_str()),
+        stat.additions,
+        stat.deletions,
+      )
+    })
+    .collect();
+  assert_eq!(
+    summary,
+    vec![
+      ("copy.rs", FileChangeKind::Copied, Some("old.rs"), 0, 0),
+      ("new.rs", FileChangeKind::Renamed, Some("old.rs"), 1, 1),
+    ]
+  );
+
+  // A diff limited to the new path still shows the rename
+  let diffs = get_tree_file_diffs(git_executor, repo_path, &base, &head, Some("new.rs"), &DiffOptions::default()).unwrap();
+  assert_eq!(diffs.len(), 1);
+  assert_eq!(diffs[0].old_file.file_name, "old.rs");
+  assert_eq!(diffs[0].old_file.content, RENAMED_CONTENT);
+  assert!(diffs[0].hunks[0].contains("rename to new.rs"), "{}", diffs[0].hunks[0]);
+}
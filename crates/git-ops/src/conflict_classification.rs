@@ -0,0 +1,196 @@
+//! Classification of conflict chunks, so trivial conflicts can be told apart from the ones that need thought.
+//!
+//! A chunk is formatting-only if both sides are the same once whitespace is removed, and imports-only if every
+//! non-blank line of both sides belongs to an import statement. Import statements are recognized line by line from
+//! language keywords; with the `tree-sitter` feature, files of supported languages are parsed instead, which also
+//! covers multi-line imports. Everything else is a logic conflict.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ops::Range;
+
+#[cfg(feature = "specta")]
+use specta::Type;
+
+/// Kind of a conflict, ordered from the most trivial to resolve
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictClass {
+  /// Both sides only differ in whitespace
+  FormattingOnly,
+  /// Both sides only add, remove or change import statements
+  ImportsOnly,
+  Logic,
+}
+
+/// Classes of the conflict chunks of a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictClassification {
+  /// Least trivial class of the chunks
+  pub class: ConflictClass,
+  /// Class of each chunk, in the order of the conflict markers
+  pub chunks: Vec<ConflictClass>,
+}
+
+/// Lines of a conflict chunk in the file with the ours and with the theirs side of every chunk
+#[derive(Debug)]
+struct ConflictChunk {
+  ours: Range<usize>,
+  theirs: Range<usize>,
+}
+
+/// Classify the conflict chunks of file content with conflict markers, `None` if it has no conflict markers
+pub fn classify_conflict(conflict_content: &str, file_lang: &str) -> Option<ConflictClassification> {
+  let (ours_lines, theirs_lines, chunks) = split_conflict_sides(conflict_content);
+  if chunks.is_empty() {
+    return None;
+  }
+
+  let ours_imports = import_lines(file_lang, &ours_lines);
+  let theirs_imports = import_lines(file_lang, &theirs_lines);
+  let is_imports_only = |lines: &[&str], range: &Range<usize>, imports: &HashSet<usize>| range.clone().all(|index| lines[index].trim().is_empty() || imports.contains(&index));
+
+  let chunks: Vec<ConflictClass> = chunks
+    .iter()
+    .map(|chunk| {
+      if without_whitespace(&ours_lines[chunk.ours.clone()]) == without_whitespace(&theirs_lines[chunk.theirs.clone()]) {
+        ConflictClass::FormattingOnly
+      } else if is_imports_only(&ours_lines, &chunk.ours, &ours_imports) && is_imports_only(&theirs_lines, &chunk.theirs, &theirs_imports) {
+        ConflictClass::ImportsOnly
+      } else {
+        ConflictClass::Logic
+      }
+    })
+    .collect();
+  Some(ConflictClassification {
+    class: chunks.iter().copied().max().unwrap_or(ConflictClass::Logic),
+    chunks,
+  })
+}
+
+/// Split content with conflict markers (with or without the base part of `diff3` / `zdiff3`) into the file with the
+/// ours side of every chunk, the file with the theirs side of every chunk, and the lines of the chunks in them
+fn split_conflict_sides(content: &str) -> (Vec<&str>, Vec<&str>, Vec<ConflictChunk>) {
+  enum Part {
+    Both,
+    Ours,
+    Base,
+    Theirs,
+  }
+
+  let mut ours_lines = Vec::new();
+  let mut theirs_lines = Vec::new();
+  let mut chunks = Vec::new();
+  let mut part = Part::Both;
+  let (mut ours_start, mut theirs_start) = (0, 0);
+  for line in content.lines() {
+    match part {
+      Part::Both if is_marker(line, "<<<<<<<") => {
+        part = Part::Ours;
+        ours_start = ours_lines.len();
+        theirs_start = theirs_lines.len();
+      }
+      Part::Ours if is_marker(line, "|||||||") => part = Part::Base,
+      Part::Ours | Part::Base if is_marker(line, "=======") => part = Part::Theirs,
+      Part::Theirs if is_marker(line, ">>>>>>>") => {
+        part = Part::Both;
+        chunks.push(ConflictChunk {
+          ours: ours_start..ours_lines.len(),
+          theirs: theirs_start..theirs_lines.len(),
+        });
+      }
+      Part::Both => {
+        ours_lines.push(line);
+        theirs_lines.push(line);
+      }
+      Part::Ours => ours_lines.push(line),
+      Part::Base => {}
+      Part::Theirs => theirs_lines.push(line),
+    }
+  }
+  (ours_lines, theirs_lines, chunks)
+}
+
+fn is_marker(line: &str, marker: &str) -> bool {
+  line.strip_prefix(marker).is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+}
+
+fn without_whitespace(lines: &[&str]) -> String {
+  lines.iter().flat_map(|line| line.chars()).filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Indices of the lines that belong to import statements
+fn import_lines(file_lang: &str, lines: &[&str]) -> HashSet<usize> {
+  #[cfg(feature = "tree-sitter")]
+  if let Some(import_lines) = syntax::import_lines(file_lang, &lines.join("\n")) {
+    return import_lines;
+  }
+
+  lines
+    .iter()
+    .enumerate()
+    .filter(|(_, line)| is_import_line(file_lang, line.trim()))
+    .map(|(index, _)| index)
+    .collect()
+}
+
+/// Whether a line is a complete import statement of the language (`file_lang` is a highlight.js language name)
+fn is_import_line(file_lang: &str, line: &str) -> bool {
+  let starts_with_any = |prefixes: &[&str]| prefixes.iter().any(|prefix| line.starts_with(prefix));
+  match file_lang {
+    "rust" => starts_with_any(&["use ", "pub use ", "pub(crate) use ", "extern crate "]) && line.ends_with(';'),
+    "typescript" | "javascript" => line.starts_with("import ") || (line.starts_with("export ") && line.contains(" from ")),
+    "python" => line.starts_with("import ") || (line.starts_with("from ") && line.contains(" import ")),
+    "java" | "kotlin" | "scala" | "groovy" | "swift" | "dart" => line.starts_with("import "),
+    "csharp" => line.starts_with("using ") && line.ends_with(';'),
+    // Also the lines of an `import ( ... )` block: a quoted path with an optional alias
+    "go" => line.starts_with("import ") || is_go_import_spec(line),
+    "c" | "cpp" | "objectivec" => starts_with_any(&["#include ", "#include<", "#import "]),
+    "php" => line.starts_with("use ") && line.ends_with(';'),
+    "ruby" => starts_with_any(&["require ", "require_relative "]),
+    "css" | "scss" | "less" => starts_with_any(&["@import ", "@use "]),
+    _ => false,
+  }
+}
+
+fn is_go_import_spec(line: &str) -> bool {
+  let mut parts = line.split_whitespace().rev();
+  let is_path = parts.next().is_some_and(|path| path.len() > 1 && path.starts_with('"') && path.ends_with('"'));
+  let alias = parts.next();
+  is_path && parts.next().is_none() && alias.is_none_or(|alias| alias.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.'))
+}
+
+#[cfg(feature = "tree-sitter")]
+mod syntax {
+  use std::collections::HashSet;
+  use tree_sitter::{Language, Parser};
+
+  /// Lines covered by top-level import statements, `None` if the language is not supported
+  pub(super) fn import_lines(file_lang: &str, text: &str) -> Option<HashSet<usize>> {
+    let (language, import_kinds): (Language, &[&str]) = match file_lang {
+      "rust" => (tree_sitter_rust::LANGUAGE.into(), &["use_declaration", "extern_crate_declaration"]),
+      "javascript" => (tree_sitter_javascript::LANGUAGE.into(), &["import_statement"]),
+      "typescript" => (tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(), &["import_statement"]),
+      "python" => (
+        tree_sitter_python::LANGUAGE.into(),
+        &["import_statement", "import_from_statement", "future_import_statement"],
+      ),
+      _ => return None,
+    };
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(text, None)?;
+
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let lines = root
+      .children(&mut cursor)
+      .filter(|node| import_kinds.contains(&node.kind()) && !node.has_error())
+      .flat_map(|node| node.start_position().row..=node.end_position().row)
+      .collect();
+    Some(lines)
+  }
+}
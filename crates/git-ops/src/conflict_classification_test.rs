@@ -0,0 +1,106 @@
+use crate::conflict_classification::{ConflictClass, ConflictClassification, classify_conflict};
+use pretty_assertions::assert_eq;
+use test_log::test;
+
+#[test]
+fn test_classify_conflict_chunks() {
+  let content = "\
+use std::fmt;
+<<<<<<< HEAD
+use std::collections::HashMap;
+||||||| base
+=======
+use std::collections::HashSet;
+>>>>>>> feature
+
+fn main() {
+<<<<<<< HEAD
+  let total = compute(1, 2);
+=======
+  let total = compute(1,2);
+>>>>>>> feature
+<<<<<<< HEAD
+  println!(\"{total}\");
+=======
+  eprintln!(\"{total}\");
+>>>>>>> feature
+}
+";
+
+  assert_eq!(
+    classify_conflict(content, "rust"),
+    Some(ConflictClassification {
+      class: ConflictClass::Logic,
+      chunks: vec![ConflictClass::ImportsOnly, ConflictClass::FormattingOnly, ConflictClass::Logic],
+    })
+  );
+}
+
+#[test]
+fn test_classify_conflict_uses_least_trivial_class() {
+  let content = "\
+<<<<<<< HEAD
+import os
+=======
+import sys
+from pathlib import Path
+>>>>>>> feature
+
+<<<<<<< HEAD
+def run():
+=======
+def run( ):
+>>>>>>> feature
+  pass
+";
+
+  assert_eq!(
+    classify_conflict(content, "python"),
+    Some(ConflictClassification {
+      class: ConflictClass::ImportsOnly,
+      chunks: vec![ConflictClass::ImportsOnly, ConflictClass::FormattingOnly],
+    })
+  );
+}
+
+#[test]
+fn test_classify_conflict_without_import_syntax_or_markers() {
+  // Languages without known import syntax only have formatting and logic conflicts
+  let content = "<<<<<<< HEAD\nimport a\n=======\nimport b\n>>>>>>> feature\n";
+  assert_eq!(
+    classify_conflict(content, "plaintext"),
+    Some(ConflictClassification {
+      class: ConflictClass::Logic,
+      chunks: vec![ConflictClass::Logic],
+    })
+  );
+
+  assert_eq!(classify_conflict("fn main() {}\n=======\n", "rust"), None);
+}
+
+#[cfg(feature = "tree-sitter")]
+#[test]
+fn test_classify_multi_line_imports() {
+  let content = "\
+<<<<<<< HEAD
+use std::collections::{
+  HashMap,
+  HashSet,
+};
+=======
+use std::collections::{
+  BTreeMap,
+};
+>>>>>>> feature
+
+fn main() {}
+";
+
+  assert_eq!(
+    classify_conflict(content, "rust"),
+    Some(ConflictClassification {
+      class: ConflictClass::ImportsOnly,
+      chunks: vec![ConflictClass::ImportsOnly],
+    })
+  );
+}
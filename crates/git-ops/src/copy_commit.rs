@@ -1,7 +1,13 @@
 use crate::cache::TreeIdCache;
+use crate::case_collision::check_case_collisions;
 use crate::commit_list::Commit;
+use crate::commit_utils::{CommitSigning, Trailer, add_trailers, commit_tree};
+use crate::diff_options::DiffOptions;
+use crate::empty_commit_policy::EmptyCommitPolicy;
+use crate::error::CommandError;
 use crate::model::{BranchError, CommitSyncStatus};
-use crate::notes::CommitNoteInfo;
+use crate::notes::{CommitNoteInfo, user_note_content};
+use crate::partial_clone::{PartialCloneInfo, ensure_cherry_pick_objects};
 use crate::progress::ProgressCallback;
 use anyhow::anyhow;
 use git_executor::git_command_executor::GitCommandExecutor;
@@ -38,6 +44,17 @@ impl std::fmt::Display for CopyCommitError {
 
 impl std::error::Error for CopyCommitError {}
 
+impl CopyCommitError {
+  /// Wrap into `anyhow::Error` with the context of the operation. Branch errors become coded errors, so e.g. a case
+  /// collision reaches the UI with its paths instead of as a debug string.
+  pub fn into_anyhow(self, context: &'static str) -> anyhow::Error {
+    match self {
+      CopyCommitError::BranchError(error) => CommandError::from(error).into_anyhow(),
+      CopyCommitError::Other(error) => error.context(context),
+    }
+  }
+}
+
 // Progress information for logging and user feedback
 #[derive(Debug)]
 pub struct ProgressInfo<'a> {
@@ -60,10 +77,30 @@ pub struct CreateCommitParams<'a> {
   pub git_executor: &'a GitCommandExecutor,
   pub tree_id_cache: &'a TreeIdCache,
   pub existing_virtual_commits: Option<&'a HashSet<String>>, // For efficient batch verification
+  pub partial_clone: Option<&'a PartialCloneInfo>,           // Set for partial clones to fetch missing blobs before merging
+  pub signing: Option<&'a CommitSigning>,                    // Set when the repository opted in to signing rewritten commits
+  pub diff_options: &'a DiffOptions,                         // Context lines and algorithm for conflict diffs
+  pub trailers: &'a [Trailer],                               // Added to the message, existing trailers are kept as is
+  pub empty_commit_policy: EmptyCommitPolicy,                // What to do with a commit whose changes are already applied
+  pub ignore_case: bool,                                     // Set on a case-insensitive file system to reject paths colliding in case
+}
+
+// Check via patch-id whether an equivalent of the commit was already applied between its original parent and the new parent
+fn is_patch_already_applied(git_executor: &GitCommandExecutor, repo_path: &str, commit_id: &str, parent_id: &str, new_parent_oid: &str) -> bool {
+  // `git cherry` prefixes commits with an equivalent in upstream with "-"
+  match git_executor.execute_command_lines(&["cherry", new_parent_oid, commit_id, parent_id], repo_path) {
+    Ok(lines) => lines.iter().any(|line| line.starts_with("- ")),
+    Err(e) => {
+      debug!(commit_id, error = %e, "failed to compare patch-id");
+      false
+    }
+  }
 }
 
 // Create or update a commit based on an original commit
-// Returns new commit hash, sync status, and note info for later writing
+// Returns new commit hash, sync status, and note info for later writing.
+// Commits already applied to the new parent are handled according to the empty commit policy: skipped ones return the new
+// parent hash with `AlreadyApplied` or `Integrated` status and no note info, kept ones are copied as empty commits.
 #[instrument(skip(params), fields(commit_id = %params.commit.id, branch = %params.progress_info.branch_name))]
 pub fn create_or_update_commit(params: CreateCommitParams<'_>) -> Result<(String, CommitSyncStatus, Option<CommitNoteInfo>), CopyCommitError> {
   let CreateCommitParams {
@@ -77,6 +114,12 @@ pub fn create_or_update_commit(params: CreateCommitParams<'_>) -> Result<(String
     git_executor,
     tree_id_cache,
     existing_virtual_commits,
+    partial_clone,
+    signing,
+    diff_options,
+    trailers,
+    empty_commit_policy,
+    ignore_case,
   } = params;
 
   if reuse_if_possible {
@@ -86,7 +129,7 @@ pub fn create_or_update_commit(params: CreateCommitParams<'_>) -> Result<(String
       let mapped_exists = if let Some(existing_commits) = existing_virtual_commits {
         existing_commits.contains(mapped_id)
       } else {
-        git_executor.execute_command(&["rev-parse", "--verify", mapped_id], repo_path).is_ok()
+        matches!(git_executor.cat_file_info(repo_path, mapped_id), Ok(Some(_)))
       };
 
       if mapped_exists {
@@ -99,6 +142,7 @@ pub fn create_or_update_commit(params: CreateCommitParams<'_>) -> Result<(String
           author_email: commit.author_email.clone(),
           tree_id: commit.tree_id.clone(),
           subject: commit.stripped_subject.clone(),
+          user_note: commit.note.as_deref().and_then(user_note_content),
         };
         return Ok((
           mapped_id.clone(),
@@ -127,11 +171,53 @@ pub fn create_or_update_commit(params: CreateCommitParams<'_>) -> Result<(String
     commit.tree_id.clone()
   } else {
     debug!(commit_id = %commit.id, "parent tree differs, performing merge");
+    // In a partial clone, merge-tree would otherwise fetch each missing blob one by one
+    if let (Some(info), Some(parent_id)) = (partial_clone, &commit.parent_id) {
+      ensure_cherry_pick_objects(git_executor, repo_path, info, parent_id, &new_parent_oid, &commit.id)?;
+    }
     // Use cherry-pick for efficient 3-way merge with conflict handling
     use crate::cherry_pick::perform_fast_cherry_pick_with_context;
     use crate::progress::CherryPickProgress;
     let cherry_progress = CherryPickProgress::new(progress, progress_info.branch_name, task_index);
-    perform_fast_cherry_pick_with_context(git_executor, repo_path, &commit.id, &new_parent_oid, Some(&cherry_progress), tree_id_cache)?
+    match perform_fast_cherry_pick_with_context(git_executor, repo_path, &commit.id, &new_parent_oid, Some(&cherry_progress), tree_id_cache, diff_options) {
+      Ok(tree_id) => {
+        // The original tree is checked out fine, only a merge can bring paths differing in case together
+        if ignore_case {
+          check_case_collisions(git_executor, repo_path, &new_parent_tree_id, &tree_id, &commit.id, &commit.stripped_subject)?;
+        }
+        tree_id
+      }
+      // Same as `git rebase`: a conflicting commit whose equivalent patch is already upstream counts as already applied
+      Err(CopyCommitError::BranchError(BranchError::MergeConflict(info)))
+        if commit
+          .parent_id
+          .as_deref()
+          .is_some_and(|parent_id| is_patch_already_applied(git_executor, repo_path, &commit.id, parent_id, &new_parent_oid)) =>
+      {
+        debug!(commit_id = %commit.id, conflicting_files = info.conflicting_files.len(), "equivalent patch already applied to conflicting commit");
+        new_parent_tree_id.clone()
+      }
+      Err(e) => return Err(e),
+    }
+  };
+
+  // A non-empty commit whose cherry-pick leaves the new parent tree untouched is already applied;
+  // intentionally empty commits are still copied
+  let is_already_applied = tree_id == new_parent_tree_id && commit.tree_id != original_parent_tree_id;
+  let created_status = match (is_already_applied, empty_commit_policy) {
+    (false, _) => CommitSyncStatus::Created,
+    (true, EmptyCommitPolicy::KeepEmpty) => {
+      debug!(commit_id = %commit.id, "changes already present in new parent, keeping empty commit");
+      CommitSyncStatus::KeptEmpty
+    }
+    (true, EmptyCommitPolicy::Skip) => {
+      debug!(commit_id = %commit.id, "changes already present in new parent, skipping empty commit");
+      return Ok((new_parent_oid, CommitSyncStatus::AlreadyApplied, None));
+    }
+    (true, EmptyCommitPolicy::MarkIntegrated) => {
+      debug!(commit_id = %commit.id, "changes already present in new parent, marking commit integrated");
+      return Ok((new_parent_oid, CommitSyncStatus::Integrated, None));
+    }
   };
 
   // Reconstruct message with stripped subject for the actual git commit
@@ -143,9 +229,7 @@ pub fn create_or_update_commit(params: CreateCommitParams<'_>) -> Result<(String
     // Single line message: use the stripped subject
     commit.stripped_subject.clone()
   };
-
-  // Create new commit using git commit-tree
-  let commit_args = vec!["commit-tree", &tree_id, "-p", &new_parent_oid, "-m", &commit_message];
+  let commit_message = add_trailers(&commit_message, trailers);
 
   // Use Unix timestamp directly (Git accepts this format)
   let author_date = commit.author_timestamp.to_string();
@@ -158,12 +242,10 @@ pub fn create_or_update_commit(params: CreateCommitParams<'_>) -> Result<(String
     ("GIT_COMMITTER_EMAIL", commit.author_email.as_str()),
   ];
 
-  let output = git_executor
-    .execute_command_with_env(&commit_args, repo_path, &env_vars)
+  // Create new commit using git commit-tree
+  let new_commit_hash = commit_tree(git_executor, repo_path, &tree_id, Some(&new_parent_oid), &commit_message, &env_vars, signing)
     .map_err(|e| CopyCommitError::Other(anyhow!("Failed to create commit: {}", e)))?;
 
-  let new_commit_hash = output.trim().to_string();
-
   // Prepare note info for later batch writing
   // Use stripped_subject if available (without branch prefix), otherwise use original subject
   let note_info = CommitNoteInfo {
@@ -177,7 +259,8 @@ pub fn create_or_update_commit(params: CreateCommitParams<'_>) -> Result<(String
     } else {
       commit.subject.clone()
     },
+    user_note: commit.note.as_deref().and_then(user_note_content),
   };
 
-  Ok((new_commit_hash, CommitSyncStatus::Created, Some(note_info)))
+  Ok((new_commit_hash, created_status, Some(note_info)))
 }
@@ -1,6 +1,7 @@
 use crate::cache::TreeIdCache;
 use crate::cherry_pick::perform_fast_cherry_pick_with_context;
 use crate::copy_commit::CopyCommitError;
+use crate::diff_options::DiffOptions;
 use crate::model::{BranchError, MergeConflictInfo};
 use git_executor::git_command_executor::GitCommandExecutor;
 
@@ -57,6 +58,7 @@ fn test_perform_merge_displays_conflict_diffs() {
     &scenario.target_commit,
     None,
     &cache,
+    &DiffOptions::default(),
   );
 
   // Test that the error is reported with structured data
@@ -104,6 +106,7 @@ fn test_perform_merge_with_context_lines() {
     &scenario.target_commit,
     None,
     &cache,
+    &DiffOptions::default(),
   );
 
   // Test that the error shows context lines
@@ -133,7 +136,15 @@ fn test_perform_merge_success_no_conflicts() {
 
   // Attempt the fast cherry-pick, which should succeed
   let cache = TreeIdCache::new();
-  let result = perform_fast_cherry_pick_with_context(git_executor, test_repo.path().to_str().unwrap(), &cherry_hash, &base_hash, None, &cache);
+  let result = perform_fast_cherry_pick_with_context(
+    git_executor,
+    test_repo.path().to_str().unwrap(),
+    &cherry_hash,
+    &base_hash,
+    None,
+    &cache,
+    &DiffOptions::default(),
+  );
 
   // Should succeed without conflicts
   assert!(result.is_ok());
@@ -182,7 +193,15 @@ fn test_perform_merge_isolates_specific_commit_changes() {
 
   // Perform the fast cherry-pick
   let cache = TreeIdCache::new();
-  let result = perform_fast_cherry_pick_with_context(git_executor, test_repo.path().to_str().unwrap(), &commit_258_hash, &initial_hash, None, &cache);
+  let result = perform_fast_cherry_pick_with_context(
+    git_executor,
+    test_repo.path().to_str().unwrap(),
+    &commit_258_hash,
+    &initial_hash,
+    None,
+    &cache,
+    &DiffOptions::default(),
+  );
 
   // Should succeed without conflicts
   assert!(result.is_ok(), "Merge should succeed without conflicts");
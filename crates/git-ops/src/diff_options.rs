@@ -0,0 +1,198 @@
+use crate::git_config::{get_config_bool, get_config_value};
+use git_executor::git_command_executor::GitCommandExecutor;
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, warn};
+
+#[cfg(feature = "specta")]
+use specta::Type;
+
+/// Git config key holding the number of context lines shown around changes
+pub const DIFF_CONTEXT_LINES_CONFIG_KEY: &str = "branchdeck.diffContextLines";
+/// Git config key holding the diff algorithm
+pub const DIFF_ALGORITHM_CONFIG_KEY: &str = "branchdeck.diffAlgorithm";
+/// Git config key to ignore changes in the amount of whitespace in diffs
+pub const DIFF_IGNORE_WHITESPACE_CONFIG_KEY: &str = "branchdeck.diffIgnoreWhitespace";
+/// Git config key to also ignore whitespace changes when applying commits (`-Xignore-space-change`)
+pub const MERGE_IGNORE_WHITESPACE_CONFIG_KEY: &str = "branchdeck.mergeIgnoreWhitespace";
+/// Git config key holding the minimal similarity in percent of a renamed or copied file, `0` disables rename detection
+pub const RENAME_SIMILARITY_CONFIG_KEY: &str = "branchdeck.renameSimilarity";
+
+/// Git config key converting line endings on checkout and commit, files then differ from the committed version in CRLF only
+pub const AUTOCRLF_CONFIG_KEY: &str = "core.autocrlf";
+
+/// Default number of context lines, same as git
+pub const DEFAULT_DIFF_CONTEXT_LINES: u32 = 3;
+/// Default rename similarity, same as git
+pub const DEFAULT_RENAME_SIMILARITY: u32 = 50;
+
+/// Diff algorithm passed to git as `--diff-algorithm`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub enum DiffAlgorithm {
+  /// Whatever git uses (`diff.algorithm` config or Myers)
+  #[default]
+  Default,
+  Histogram,
+  Patience,
+  Minimal,
+}
+
+impl DiffAlgorithm {
+  /// Parse a config value (`default`, `histogram`, `patience` or `minimal`)
+  pub fn from_config_value(value: &str) -> Option<Self> {
+    match value.trim().to_ascii_lowercase().as_str() {
+      "default" => Some(Self::Default),
+      "histogram" => Some(Self::Histogram),
+      "patience" => Some(Self::Patience),
+      "minimal" => Some(Self::Minimal),
+      _ => None,
+    }
+  }
+}
+
+/// How conflict and commit file diffs are generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct DiffOptions {
+  pub context_lines: u32,
+  pub algorithm: DiffAlgorithm,
+  /// Ignore changes in the amount of whitespace (`--ignore-space-change`)
+  pub ignore_whitespace: bool,
+  /// Resolve commits whose changes only differ in whitespace from the target instead of reporting a conflict.
+  /// The resulting tree keeps the target's whitespace for such lines. Needs git 2.44, see [`crate::merge_tree::merge_trees`].
+  pub ignore_whitespace_in_merges: bool,
+  /// Ignore a carriage return at the end of lines in diffs (`--ignore-cr-at-eol`), so lines differing only in CRLF
+  /// versus LF are not shown as changes. Enabled by `core.autocrlf`.
+  pub ignore_cr_at_eol: bool,
+  /// Also don't report lines differing only in CRLF versus LF as conflicts (`-Xignore-cr-at-eol`). Only for predicting
+  /// conflicts, see [`DiffOptions::for_conflict_checks`].
+  pub ignore_cr_at_eol_in_merges: bool,
+  /// Minimal similarity in percent for a deleted and an added file to be reported as a rename or copy, `0` disables it
+  pub rename_similarity: u32,
+}
+
+impl Default for DiffOptions {
+  fn default() -> Self {
+    Self {
+      context_lines: DEFAULT_DIFF_CONTEXT_LINES,
+      algorithm: DiffAlgorithm::Default,
+      ignore_whitespace: false,
+      ignore_whitespace_in_merges: false,
+      ignore_cr_at_eol: false,
+      ignore_cr_at_eol_in_merges: false,
+      rename_similarity: DEFAULT_RENAME_SIMILARITY,
+    }
+  }
+}
+
+impl DiffOptions {
+  /// Arguments for `git diff` / `git show`
+  pub fn to_args(&self) -> Vec<String> {
+    let mut args = vec![format!("--unified={}", self.context_lines)];
+    match self.algorithm {
+      DiffAlgorithm::Default => {}
+      DiffAlgorithm::Histogram => args.push("--diff-algorithm=histogram".to_string()),
+      DiffAlgorithm::Patience => args.push("--diff-algorithm=patience".to_string()),
+      DiffAlgorithm::Minimal => args.push("--diff-algorithm=minimal".to_string()),
+    }
+    if self.ignore_whitespace {
+      args.push("--ignore-space-change".to_string());
+    }
+    if self.ignore_cr_at_eol {
+      args.push("--ignore-cr-at-eol".to_string());
+    }
+    args
+  }
+
+  /// Rename and copy detection arguments (`-M` / `-C`) for commands listing or diffing changed files.
+  /// Only files modified in the same change are considered as copy sources.
+  pub fn rename_args(&self) -> Vec<String> {
+    if self.rename_similarity == 0 {
+      vec!["--no-renames".to_string()]
+    } else {
+      vec![format!("--find-renames={}%", self.rename_similarity), format!("--find-copies={}%", self.rename_similarity)]
+    }
+  }
+
+  /// Arguments for `git merge-tree`
+  pub fn merge_args(&self) -> Vec<&'static str> {
+    let mut args = Vec::new();
+    if self.ignore_whitespace_in_merges {
+      args.push("-Xignore-space-change");
+    }
+    if self.ignore_cr_at_eol_in_merges {
+      args.push("-Xignore-cr-at-eol");
+    }
+    args
+  }
+
+  /// Options for checking whether a rewrite would conflict: the defaults, but with `core.autocrlf` lines differing only in
+  /// line endings don't conflict. Never use them for trees that get committed, the merge would keep one side's line
+  /// endings and silently drop a commit converting CRLF to LF or back.
+  pub fn for_conflict_checks(git_executor: &GitCommandExecutor, repository_path: &str) -> Self {
+    Self {
+      ignore_cr_at_eol_in_merges: is_autocrlf_enabled(git_executor, repository_path),
+      ..Self::default()
+    }
+  }
+}
+
+/// Whether `core.autocrlf` is `true` or `input`, i.e. the working tree may have other line endings than the commits
+#[instrument(skip(git_executor))]
+pub fn is_autocrlf_enabled(git_executor: &GitCommandExecutor, repository_path: &str) -> bool {
+  match get_config_value(git_executor, repository_path, AUTOCRLF_CONFIG_KEY) {
+    Ok(Some(value)) => matches!(value.trim().to_ascii_lowercase().as_str(), "true" | "input" | "yes" | "on" | "1"),
+    Ok(None) => false,
+    Err(e) => {
+      warn!(error = %e, "Failed to read {AUTOCRLF_CONFIG_KEY}, CRLF-only differences are shown");
+      false
+    }
+  }
+}
+
+/// Read diff options from git config, falling back to the defaults for missing or invalid values
+#[instrument(skip(git_executor))]
+pub fn load_diff_options(git_executor: &GitCommandExecutor, repository_path: &str) -> DiffOptions {
+  let mut options = DiffOptions::default();
+
+  match get_config_value(git_executor, repository_path, DIFF_CONTEXT_LINES_CONFIG_KEY) {
+    Ok(Some(value)) => match value.trim().parse::<u32>() {
+      Ok(context_lines) => options.context_lines = context_lines,
+      Err(_) => warn!(value, "Invalid {DIFF_CONTEXT_LINES_CONFIG_KEY} value, using {DEFAULT_DIFF_CONTEXT_LINES} context lines"),
+    },
+    Ok(None) => {}
+    Err(e) => warn!(error = %e, "Failed to read diff context lines, using the default"),
+  }
+
+  match get_config_value(git_executor, repository_path, DIFF_ALGORITHM_CONFIG_KEY) {
+    Ok(Some(value)) => match DiffAlgorithm::from_config_value(&value) {
+      Some(algorithm) => options.algorithm = algorithm,
+      None => warn!(value, "Unknown {DIFF_ALGORITHM_CONFIG_KEY} value, using the default diff algorithm"),
+    },
+    Ok(None) => {}
+    Err(e) => warn!(error = %e, "Failed to read diff algorithm, using the default"),
+  }
+
+  match get_config_value(git_executor, repository_path, RENAME_SIMILARITY_CONFIG_KEY) {
+    Ok(Some(value)) => match value.trim().trim_end_matches('%').parse::<u32>() {
+      Ok(similarity) if similarity <= 100 => options.rename_similarity = similarity,
+      _ => warn!(value, "Invalid {RENAME_SIMILARITY_CONFIG_KEY} value, using {DEFAULT_RENAME_SIMILARITY}% similarity"),
+    },
+    Ok(None) => {}
+    Err(e) => warn!(error = %e, "Failed to read rename similarity, using the default"),
+  }
+
+  match get_config_bool(git_executor, repository_path, DIFF_IGNORE_WHITESPACE_CONFIG_KEY) {
+    Ok(value) => options.ignore_whitespace = value.unwrap_or(false),
+    Err(e) => warn!(error = %e, "Invalid {DIFF_IGNORE_WHITESPACE_CONFIG_KEY} value, whitespace changes are shown"),
+  }
+  match get_config_bool(git_executor, repository_path, MERGE_IGNORE_WHITESPACE_CONFIG_KEY) {
+    Ok(value) => options.ignore_whitespace_in_merges = value.unwrap_or(false),
+    Err(e) => warn!(error = %e, "Invalid {MERGE_IGNORE_WHITESPACE_CONFIG_KEY} value, whitespace changes can conflict"),
+  }
+  options.ignore_cr_at_eol = is_autocrlf_enabled(git_executor, repository_path);
+
+  options
+}
@@ -0,0 +1,47 @@
+use crate::git_config::get_config_value;
+use git_executor::git_command_executor::GitCommandExecutor;
+use tracing::{instrument, warn};
+
+/// Git config key holding the per-repository empty commit policy
+pub const EMPTY_COMMIT_POLICY_CONFIG_KEY: &str = "branchdeck.emptyCommitPolicy";
+
+/// What sync does with a prefixed commit that becomes empty when copied onto its new parent,
+/// because its changes are already applied upstream. Intentionally empty commits are always copied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmptyCommitPolicy {
+  /// Leave the commit out of the virtual branch, like `git rebase` does (`AlreadyApplied` status)
+  #[default]
+  Skip,
+  /// Copy the commit as an empty commit, so the virtual branch keeps all commits of the group (`KeptEmpty` status)
+  KeepEmpty,
+  /// Leave the commit out of the virtual branch and report it as integrated into the baseline (`Integrated` status)
+  MarkIntegrated,
+}
+
+impl EmptyCommitPolicy {
+  /// Parse a config value (`skip`, `keepEmpty` or `markIntegrated`)
+  pub fn from_config_value(value: &str) -> Option<Self> {
+    match value.trim().to_ascii_lowercase().as_str() {
+      "skip" => Some(Self::Skip),
+      "keepempty" => Some(Self::KeepEmpty),
+      "markintegrated" => Some(Self::MarkIntegrated),
+      _ => None,
+    }
+  }
+}
+
+/// Read the empty commit policy from git config, falling back to the default for missing or unknown values
+#[instrument(skip(git_executor))]
+pub fn load_empty_commit_policy(git_executor: &GitCommandExecutor, repository_path: &str) -> EmptyCommitPolicy {
+  match get_config_value(git_executor, repository_path, EMPTY_COMMIT_POLICY_CONFIG_KEY) {
+    Ok(Some(value)) => EmptyCommitPolicy::from_config_value(&value).unwrap_or_else(|| {
+      warn!(value, "Unknown {EMPTY_COMMIT_POLICY_CONFIG_KEY} value, skipping already applied commits");
+      EmptyCommitPolicy::default()
+    }),
+    Ok(None) => EmptyCommitPolicy::default(),
+    Err(e) => {
+      warn!(error = %e, "Failed to read empty commit policy, skipping already applied commits");
+      EmptyCommitPolicy::default()
+    }
+  }
+}
@@ -0,0 +1,177 @@
+use crate::copy_commit::CopyCommitError;
+use crate::model::BranchError;
+use git_executor::git_error::GitCommandError;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "specta")]
+use specta::Type;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Machine-readable error category, the UI branches on it instead of matching message text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorCode {
+  /// A git command exited with a failure status (context: `command`, `exitCode`, `stderr`)
+  GitCommandFailed,
+  /// The repository path doesn't exist or isn't a git repository
+  RepositoryNotAccessible,
+  /// A commit can't be applied without conflicts (context: `commitHash`)
+  MergeConflict,
+  /// Invalid input, e.g. a blank branch name
+  Validation,
+  /// A referenced branch, remote or commit doesn't exist
+  NotFound,
+  /// Loading or running the AI model failed
+  Model,
+  /// The operation was cancelled by the user
+  Cancelled,
+  /// Not available in this build
+  Unsupported,
+  /// The branch is protected against the operation (context: `branchName`, `rule`)
+  BranchProtected,
+  /// The pre-push hook or command failed (context: `command`, `exitCode`, `output` with the last lines)
+  PrePushCheckFailed,
+  /// A sync hook script failed (context: `hook`, `exitCode`, `output` with the last lines)
+  HookFailed,
+  /// Skipped without trying the network: offline mode is on or no network connection was detected
+  Offline,
+  /// The credential helpers have no credentials for the remote host and none were entered (context: `host`)
+  CredentialsRequired,
+  /// The remote rejected the credentials, they were removed from the credential helpers (context: `host`)
+  AuthenticationFailed,
+  /// The SSH server accepted none of the keys, e.g. a passphrase prompt was cancelled without an agent holding the key, or the host key couldn't be verified (context: `host`, `reason`: `publickey` or `hostKey`)
+  SshAuthenticationFailed,
+  /// The git executable is older than the minimum version, the message says how to get a newer one (context: `gitPath`, `version`, `minimumVersion`)
+  UnsupportedGitVersion,
+  /// Applying a commit would create paths differing only in case, which a case-insensitive file system can't check out (context: `commitHash`, `paths` with one colliding path per line)
+  CaseCollision,
+  /// Anything else, the message is the only information
+  Internal,
+}
+
+/// Error returned by commands: a code to branch on, a message to show and optional details
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct CommandError {
+  pub code: ErrorCode,
+  pub message: String,
+  /// Details depending on the code, e.g. `exitCode` of a failed git command
+  #[serde(default)]
+  pub context: BTreeMap<String, String>,
+}
+
+impl CommandError {
+  pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+    Self {
+      code,
+      message: message.into(),
+      context: BTreeMap::new(),
+    }
+  }
+
+  pub fn validation(message: impl Into<String>) -> Self {
+    Self::new(ErrorCode::Validation, message)
+  }
+
+  pub fn not_found(message: impl Into<String>) -> Self {
+    Self::new(ErrorCode::NotFound, message)
+  }
+
+  pub fn internal(message: impl Into<String>) -> Self {
+    Self::new(ErrorCode::Internal, message)
+  }
+
+  #[must_use]
+  pub fn with_context(mut self, key: &str, value: impl Into<String>) -> Self {
+    self.context.insert(key.to_string(), value.into());
+    self
+  }
+
+  fn with_message(mut self, message: String) -> Self {
+    self.message = message;
+    self
+  }
+
+  /// Wrap into `anyhow::Error`, so core functions can raise a coded error that survives until the command boundary
+  pub fn into_anyhow(self) -> anyhow::Error {
+    anyhow::Error::new(self)
+  }
+}
+
+impl fmt::Display for CommandError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.message)
+  }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<anyhow::Error> for CommandError {
+  /// Classify by the first coded or git error in the chain, the message keeps the whole chain
+  fn from(error: anyhow::Error) -> Self {
+    let message = format!("{error:#}");
+    for cause in error.chain() {
+      if let Some(coded) = cause.downcast_ref::<CommandError>() {
+        return Self { message, ..coded.clone() };
+      }
+      if let Some(git_error) = cause.downcast_ref::<GitCommandError>() {
+        return Self::from(git_error).with_message(message);
+      }
+    }
+    Self::internal(message)
+  }
+}
+
+impl From<&GitCommandError> for CommandError {
+  fn from(error: &GitCommandError) -> Self {
+    let command = Self::new(ErrorCode::GitCommandFailed, error.to_string())
+      .with_context("command", format!("git {}", error.args.join(" ")))
+      .with_context("stderr", error.stderr.clone());
+    match error.exit_code {
+      Some(exit_code) => command.with_context("exitCode", exit_code.to_string()),
+      None => command,
+    }
+  }
+}
+
+impl From<BranchError> for CommandError {
+  fn from(error: BranchError) -> Self {
+    match error {
+      BranchError::Generic(message) => Self::internal(message),
+      BranchError::MergeConflict(info) => {
+        Self::new(ErrorCode::MergeConflict, format!("Commit {} conflicts: {}", info.commit_hash, info.commit_message)).with_context("commitHash", info.commit_hash)
+      }
+      BranchError::CaseCollision(info) => {
+        let paths: Vec<&str> = info.collisions.iter().flatten().map(String::as_str).collect();
+        let paths = paths.join("\n");
+        Self::new(ErrorCode::CaseCollision, info.explanation())
+          .with_context("commitHash", info.commit_hash)
+          .with_context("paths", paths)
+      }
+    }
+  }
+}
+
+impl From<CopyCommitError> for CommandError {
+  fn from(error: CopyCommitError) -> Self {
+    match error {
+      CopyCommitError::BranchError(error) => error.into(),
+      CopyCommitError::Other(error) => error.into(),
+    }
+  }
+}
+
+impl From<tauri::Error> for CommandError {
+  fn from(error: tauri::Error) -> Self {
+    Self::internal(format!("Tauri error: {error}"))
+  }
+}
+
+/// Plain string errors of helpers that predate error codes
+impl From<String> for CommandError {
+  fn from(message: String) -> Self {
+    Self::internal(message)
+  }
+}
@@ -0,0 +1,46 @@
+use crate::error::{CommandError, ErrorCode};
+use anyhow::Context;
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_git_failure_maps_to_git_command_failed() {
+  let repo = TestRepo::new();
+  repo.create_commit("Initial commit", "file.txt", "content");
+
+  let git_executor = GitCommandExecutor::new();
+  let error = git_executor
+    .execute_command(&["rev-parse", "--verify", "refs/heads/missing"], repo.path().to_str().unwrap())
+    .context("Failed to resolve branch")
+    .unwrap_err();
+
+  let command_error = CommandError::from(error);
+  assert_eq!(command_error.code, ErrorCode::GitCommandFailed);
+  assert_eq!(command_error.context.get("command").map(String::as_str), Some("git rev-parse --verify refs/heads/missing"));
+  assert_eq!(command_error.context.get("exitCode").map(String::as_str), Some("128"));
+  // The message keeps the whole chain for display
+  assert!(command_error.message.starts_with("Failed to resolve branch: "), "{}", command_error.message);
+}
+
+#[test]
+fn test_coded_error_survives_context() {
+  let error = Err::<(), _>(
+    CommandError::not_found("Remote 'upstream' does not exist")
+      .with_context("remoteName", "upstream")
+      .into_anyhow(),
+  )
+  .context("Failed to update remote settings")
+  .unwrap_err();
+
+  let command_error = CommandError::from(error);
+  assert_eq!(command_error.code, ErrorCode::NotFound);
+  assert_eq!(command_error.message, "Failed to update remote settings: Remote 'upstream' does not exist");
+  assert_eq!(command_error.context.get("remoteName").map(String::as_str), Some("upstream"));
+}
+
+#[test]
+fn test_uncoded_error_is_internal() {
+  let command_error = CommandError::from(anyhow::anyhow!("Something went wrong"));
+  assert_eq!(command_error, CommandError::internal("Something went wrong"));
+}
@@ -0,0 +1,167 @@
+//! Language of a file for syntax highlighting in diffs (`FileInfo::file_lang`).
+//!
+//! Names are highlight.js language names, which is what git-diff-view uses.
+
+use std::path::Path;
+
+/// Language used when neither the path nor the content tells anything
+pub const PLAIN_TEXT_LANG: &str = "plaintext";
+
+/// Files recognized by their whole name, compared case-insensitively
+const FILE_NAME_LANGUAGES: &[(&str, &str)] = &[
+  ("dockerfile", "dockerfile"),
+  ("containerfile", "dockerfile"),
+  ("makefile", "makefile"),
+  ("gnumakefile", "makefile"),
+  ("cmakelists.txt", "cmake"),
+  ("gemfile", "ruby"),
+  ("rakefile", "ruby"),
+  ("podfile", "ruby"),
+  ("jenkinsfile", "groovy"),
+  ("vagrantfile", "ruby"),
+  (".bashrc", "bash"),
+  (".bash_profile", "bash"),
+  (".zshrc", "bash"),
+  (".profile", "bash"),
+  (".gitconfig", "ini"),
+  (".editorconfig", "ini"),
+];
+
+/// File extensions (lowercase, without the dot)
+const EXTENSION_LANGUAGES: &[(&str, &str)] = &[
+  ("rs", "rust"),
+  ("ts", "typescript"),
+  ("tsx", "typescript"),
+  ("mts", "typescript"),
+  ("cts", "typescript"),
+  ("js", "javascript"),
+  ("jsx", "javascript"),
+  ("mjs", "javascript"),
+  ("cjs", "javascript"),
+  ("vue", "xml"),
+  ("html", "xml"),
+  ("htm", "xml"),
+  ("xml", "xml"),
+  ("svg", "xml"),
+  ("css", "css"),
+  ("scss", "scss"),
+  ("less", "less"),
+  ("json", "json"),
+  ("jsonc", "json"),
+  ("yaml", "yaml"),
+  ("yml", "yaml"),
+  ("toml", "ini"),
+  ("ini", "ini"),
+  ("properties", "properties"),
+  ("md", "markdown"),
+  ("markdown", "markdown"),
+  ("kt", "kotlin"),
+  ("kts", "kotlin"),
+  ("java", "java"),
+  ("groovy", "groovy"),
+  ("gradle", "groovy"),
+  ("scala", "scala"),
+  ("go", "go"),
+  ("py", "python"),
+  ("pyi", "python"),
+  ("rb", "ruby"),
+  ("php", "php"),
+  ("pl", "perl"),
+  ("lua", "lua"),
+  ("swift", "swift"),
+  ("m", "objectivec"),
+  ("mm", "objectivec"),
+  ("c", "c"),
+  ("h", "c"),
+  ("cc", "cpp"),
+  ("cpp", "cpp"),
+  ("cxx", "cpp"),
+  ("hpp", "cpp"),
+  ("hh", "cpp"),
+  ("cs", "csharp"),
+  ("fs", "fsharp"),
+  ("dart", "dart"),
+  ("ex", "elixir"),
+  ("exs", "elixir"),
+  ("erl", "erlang"),
+  ("hs", "haskell"),
+  ("clj", "clojure"),
+  ("sh", "bash"),
+  ("bash", "bash"),
+  ("zsh", "bash"),
+  ("fish", "bash"),
+  ("ps1", "powershell"),
+  ("bat", "dos"),
+  ("cmd", "dos"),
+  ("sql", "sql"),
+  ("graphql", "graphql"),
+  ("proto", "protobuf"),
+  ("dockerfile", "dockerfile"),
+  ("mk", "makefile"),
+  ("cmake", "cmake"),
+  ("diff", "diff"),
+  ("patch", "diff"),
+  ("tf", "hcl"),
+  ("nix", "nix"),
+  ("zig", "zig"),
+  ("r", "r"),
+  ("txt", "plaintext"),
+];
+
+/// Interpreters named in a shebang line
+const INTERPRETER_LANGUAGES: &[(&str, &str)] = &[
+  ("sh", "bash"),
+  ("bash", "bash"),
+  ("zsh", "bash"),
+  ("dash", "bash"),
+  ("ksh", "bash"),
+  ("python", "python"),
+  ("ruby", "ruby"),
+  ("perl", "perl"),
+  ("node", "javascript"),
+  ("deno", "typescript"),
+  ("bun", "typescript"),
+  ("php", "php"),
+  ("lua", "lua"),
+  ("kotlin", "kotlin"),
+  ("pwsh", "powershell"),
+];
+
+/// Language of a file, detected from its name or extension, falling back to the content (shebang line)
+pub fn detect_file_language(file_path: &str, content: &str) -> String {
+  language_from_path(file_path)
+    .or_else(|| language_from_content(content))
+    .unwrap_or(PLAIN_TEXT_LANG)
+    .to_string()
+}
+
+/// Language from the file name (`Dockerfile`, `Makefile`) or the extension
+pub fn language_from_path(file_path: &str) -> Option<&'static str> {
+  let file_name = Path::new(file_path).file_name()?.to_str()?.to_ascii_lowercase();
+  if let Some(&(_, lang)) = FILE_NAME_LANGUAGES.iter().find(|(name, _)| *name == file_name) {
+    return Some(lang);
+  }
+  // `Dockerfile.dev`, `Makefile.am`
+  if let Some((stem, _)) = file_name.split_once('.')
+    && matches!(stem, "dockerfile" | "containerfile" | "makefile")
+  {
+    return language_from_path(stem);
+  }
+
+  let extension = Path::new(&file_name).extension()?.to_str()?;
+  EXTENSION_LANGUAGES.iter().find(|(ext, _)| *ext == extension).map(|(_, lang)| *lang)
+}
+
+/// Language from the shebang line (`#!/usr/bin/env python3`, `#!/bin/sh -e`)
+pub fn language_from_content(content: &str) -> Option<&'static str> {
+  let shebang = content.lines().next()?.strip_prefix("#!")?;
+  let mut words = shebang.split_whitespace();
+  let mut interpreter = words.next()?.rsplit('/').next()?;
+  if interpreter == "env" {
+    // Skip options like `env -S`
+    interpreter = words.find(|word| !word.starts_with('-'))?;
+  }
+  // python3, python3.12
+  let interpreter = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+  INTERPRETER_LANGUAGES.iter().find(|(name, _)| *name == interpreter).map(|(_, lang)| *lang)
+}
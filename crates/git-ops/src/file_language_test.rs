@@ -0,0 +1,34 @@
+use crate::file_language::{PLAIN_TEXT_LANG, detect_file_language, language_from_content, language_from_path};
+use pretty_assertions::assert_eq;
+use test_log::test;
+
+#[test]
+fn test_language_from_path() {
+  assert_eq!(language_from_path("src/main.rs"), Some("rust"));
+  assert_eq!(language_from_path("app/components/Card.VUE"), Some("xml"));
+  assert_eq!(language_from_path("build.gradle.kts"), Some("kotlin"));
+  assert_eq!(language_from_path("docker/Dockerfile"), Some("dockerfile"));
+  assert_eq!(language_from_path("Dockerfile.dev"), Some("dockerfile"));
+  assert_eq!(language_from_path("Makefile"), Some("makefile"));
+  assert_eq!(language_from_path("CMakeLists.txt"), Some("cmake"));
+  assert_eq!(language_from_path("scripts/release"), None);
+  assert_eq!(language_from_path("data.unknown"), None);
+}
+
+#[test]
+fn test_language_from_content() {
+  assert_eq!(language_from_content("#!/bin/sh -e\necho hi\n"), Some("bash"));
+  assert_eq!(language_from_content("#!/usr/bin/env python3\nprint()\n"), Some("python"));
+  assert_eq!(language_from_content("#!/usr/bin/env -S deno run\n"), Some("typescript"));
+  assert_eq!(language_from_content("#!/usr/local/bin/python3.12\n"), Some("python"));
+  assert_eq!(language_from_content("echo hi\n#!/bin/sh\n"), None);
+  assert_eq!(language_from_content(""), None);
+}
+
+#[test]
+fn test_detect_file_language() {
+  // The path wins over the content
+  assert_eq!(detect_file_language("run.rb", "#!/bin/bash\n"), "ruby");
+  assert_eq!(detect_file_language("scripts/release", "#!/usr/bin/env node\n"), "javascript");
+  assert_eq!(detect_file_language("LICENSE", "MIT License\n"), PLAIN_TEXT_LANG);
+}
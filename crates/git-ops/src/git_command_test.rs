@@ -1,4 +1,6 @@
+use git_executor::cat_file_batch::CommitObject;
 use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
 use test_utils::git_test_utils::TestRepo;
 
 #[test]
@@ -55,3 +57,126 @@ fn test_execute_command_lines_performance() {
     assert!(commit.chars().all(|c| c.is_ascii_hexdigit()));
   }
 }
+
+#[test]
+fn test_cat_file_batch_lookups() {
+  let repo = TestRepo::new();
+  let first = repo.create_commit("First commit", "file1.txt", "content1\n");
+  let second = repo.create_commit_with_timestamp("Second commit\nwrapped subject\n\nBody", "dir/file 2.txt", "content2", Some(1_700_000_000));
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = repo.path().to_str().unwrap();
+
+  let parent = git_executor.cat_file_info(repo_path, &format!("{second}^")).unwrap().unwrap();
+  assert_eq!(parent.id, first);
+  assert_eq!(parent.kind, "commit");
+  assert_eq!(
+    git_executor.resolve_tree_id(repo_path, &second).unwrap(),
+    repo.rev_parse(&format!("{second}^{{tree}}")).unwrap()
+  );
+
+  let (blob, content) = git_executor.cat_file_contents(repo_path, "HEAD:dir/file 2.txt").unwrap().unwrap();
+  assert_eq!(blob.kind, "blob");
+  assert_eq!(content, b"content2");
+  // Contents ending with a newline are read exactly, and the same process serves the next request
+  assert_eq!(git_executor.cat_file_contents(repo_path, &format!("{first}:file1.txt")).unwrap().unwrap().1, b"content1\n");
+
+  // Missing objects don't break the process
+  assert_eq!(git_executor.cat_file_info(repo_path, &format!("{first}^")).unwrap(), None);
+  assert_eq!(git_executor.cat_file_contents(repo_path, "HEAD:missing.txt").unwrap(), None);
+  assert!(git_executor.cat_file_info(repo_path, "HEAD\nHEAD").is_err());
+
+  let commit = git_executor.read_commit(repo_path, "HEAD").unwrap();
+  assert_eq!(commit.parent_ids, vec![first]);
+  assert_eq!(commit.author_timestamp, 1_700_000_000);
+  assert_eq!(commit.committer_timestamp, 1_700_000_000);
+  assert_eq!(commit.subject(), "Second commit wrapped subject");
+  assert_eq!(commit.subject(), repo.log(&["-1", "--format=%s"]).unwrap().trim());
+  assert!(git_executor.read_commit(repo_path, "refs/heads/does-not-exist").is_err());
+}
+
+#[test]
+fn test_cat_file_batch_sees_new_objects() {
+  let repo = TestRepo::new();
+  repo.create_commit("First commit", "file1.txt", "content1");
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = repo.path().to_str().unwrap();
+  git_executor.resolve_tree_id(repo_path, "HEAD").unwrap();
+
+  // Objects written after the process started are visible to it
+  let second = repo.create_commit("Second commit", "file2.txt", "content2");
+  assert_eq!(git_executor.cat_file_info(repo_path, "HEAD").unwrap().unwrap().id, second);
+
+  // Concurrent callers each get their own process
+  std::thread::scope(|scope| {
+    for _ in 0..4 {
+      scope.spawn(|| {
+        for _ in 0..10 {
+          assert_eq!(git_executor.read_commit(repo_path, &second).unwrap().subject(), "Second commit");
+        }
+      });
+    }
+  });
+}
+
+#[test]
+fn test_parse_commit_object() {
+  let data = b"tree 1111111111111111111111111111111111111111
+parent 2222222222222222222222222222222222222222
+parent 3333333333333333333333333333333333333333
+author Jane Doe <jane@example.com> 1700000000 +0100
+committer John Roe <john@example.com> 1700000100 -0500
+gpgsig -----BEGIN PGP SIGNATURE-----
+ 
+ iQEzBAABCAAdFiEE
+ -----END PGP SIGNATURE-----
+
+Merge branch 'feature'
+
+Details
+";
+  let commit = CommitObject::parse(data).unwrap();
+  assert_eq!(
+    commit,
+    CommitObject {
+      tree_id: "1111111111111111111111111111111111111111".to_string(),
+      parent_ids: vec![
+        "2222222222222222222222222222222222222222".to_string(),
+        "3333333333333333333333333333333333333333".to_string()
+      ],
+      author_name: "Jane Doe".to_string(),
+      author_email: "jane@example.com".to_string(),
+      author_timestamp: 1_700_000_000,
+      committer_timestamp: 1_700_000_100,
+      message: "Merge branch 'feature'\n\nDetails\n".to_string(),
+      encoding: None,
+    }
+  );
+  assert_eq!(commit.subject(), "Merge branch 'feature'");
+  assert!(CommitObject::parse(b"tree 1111111111111111111111111111111111111111\n\nNo author").is_err());
+}
+
+#[test]
+fn test_read_commit_in_other_encoding() {
+  let repo = TestRepo::new();
+  repo.create_commit("First commit", "file1.txt", "content1");
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = repo.path().to_str().unwrap();
+  let tree_id = git_executor.resolve_tree_id(repo_path, "HEAD").unwrap();
+
+  // Created with `i18n.commitEncoding=ISO-8859-1`, the author and message are stored in Latin-1
+  let mut data = format!("tree {tree_id}\n").into_bytes();
+  data.extend_from_slice(b"author Ren\xe9 <rene@example.com> 1700000000 +0000\n");
+  data.extend_from_slice(b"committer Ren\xe9 <rene@example.com> 1700000000 +0000\n");
+  data.extend_from_slice(b"encoding ISO-8859-1\n\nCaf\xe9 au lait\n\nD\xe9tails\n");
+  let object_path = repo.path().join("commit-object");
+  std::fs::write(&object_path, data).unwrap();
+  let commit_id = git_executor
+    .execute_command(&["hash-object", "-t", "commit", "-w", "--", object_path.to_str().unwrap()], repo_path)
+    .unwrap();
+
+  let commit = git_executor.read_commit(repo_path, &commit_id).unwrap();
+  assert_eq!(commit.author_name, "René");
+  assert_eq!(commit.message, "Café au lait\n\nDétails\n");
+  assert_eq!(commit.subject(), "Café au lait");
+  assert_eq!(commit.encoding, None);
+}
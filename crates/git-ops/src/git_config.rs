@@ -0,0 +1,42 @@
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use tracing::instrument;
+
+/// Read a single git config value using git's regular precedence (local → global → system).
+/// Returns `None` when the key is not set.
+#[instrument(skip(git_executor))]
+pub fn get_config_value(git_executor: &GitCommandExecutor, repo_path: &str, key: &str) -> Result<Option<String>> {
+  let (output, exit_code) = git_executor.execute_command_with_status(&["config", "--get", key], repo_path)?;
+  match exit_code {
+    0 => Ok(Some(output.trim().to_string())),
+    1 => Ok(None),
+    code => Err(anyhow!("git config --get {} failed with exit code {}: {}", key, code, output)),
+  }
+}
+
+/// Read a boolean git config value (accepts true/false/yes/no/on/off/1/0 like git does).
+/// Returns `None` when the key is not set.
+#[instrument(skip(git_executor))]
+pub fn get_config_bool(git_executor: &GitCommandExecutor, repo_path: &str, key: &str) -> Result<Option<bool>> {
+  let (output, exit_code) = git_executor.execute_command_with_status(&["config", "--type=bool", "--get", key], repo_path)?;
+  match exit_code {
+    0 => Ok(Some(output.trim() == "true")),
+    1 => Ok(None),
+    code => Err(anyhow!("git config --get {} failed with exit code {}: {}", key, code, output)),
+  }
+}
+
+/// Write a value to the repository config (`.git/config`), `None` removes the key
+#[instrument(skip(git_executor))]
+pub fn set_local_config_value(git_executor: &GitCommandExecutor, repo_path: &str, key: &str, value: Option<&str>) -> Result<()> {
+  let (output, exit_code) = match value {
+    Some(value) => git_executor.execute_command_with_status(&["config", "--local", key, value], repo_path)?,
+    None => git_executor.execute_command_with_status(&["config", "--local", "--unset", key], repo_path)?,
+  };
+  match exit_code {
+    0 => Ok(()),
+    // Unsetting a key that isn't set
+    5 if value.is_none() => Ok(()),
+    code => Err(anyhow!("git config {} failed with exit code {}: {}", key, code, output)),
+  }
+}
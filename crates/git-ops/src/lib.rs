@@ -1,30 +1,96 @@
 pub mod amend_operations;
+pub mod binary_conflict;
 pub mod cache;
+pub mod case_collision;
 pub mod cherry_pick;
+pub mod commit_dependencies;
 pub mod commit_list;
 pub mod commit_utils;
 pub mod conflict_analysis;
+pub mod conflict_classification;
 pub mod copy_commit;
+pub mod diff_options;
+pub mod empty_commit_policy;
+pub mod error;
+pub mod file_language;
+pub mod git_config;
 pub mod merge_conflict;
+pub mod merge_tool;
+pub mod merge_tree;
 pub mod model;
 pub mod notes;
+pub mod partial_clone;
+pub mod patch_export;
+pub mod patch_import;
 pub mod progress;
+pub mod ref_transaction;
+pub mod repository_lock;
 pub mod reword_commits;
+pub mod temp_dir;
+pub mod word_diff;
 
 #[cfg(test)]
 mod amend_operations_test;
 
+#[cfg(test)]
+mod binary_conflict_test;
+
+#[cfg(test)]
+mod cache_test;
+
+#[cfg(test)]
+mod case_collision_test;
+
+#[cfg(test)]
+mod commit_dependencies_test;
+
+#[cfg(test)]
+mod commit_utils_test;
+
 #[cfg(test)]
 mod conflict_analysis_tests;
 
+#[cfg(test)]
+mod conflict_classification_test;
+
 #[cfg(test)]
 mod copy_commit_test;
 
+#[cfg(test)]
+mod error_test;
+
+#[cfg(test)]
+mod file_language_test;
+
 #[cfg(test)]
 mod git_command_test;
 
 #[cfg(test)]
 mod merge_conflict_tests;
 
+#[cfg(test)]
+mod merge_tool_test;
+
+#[cfg(test)]
+mod merge_tree_test;
+
 #[cfg(test)]
 mod notes_test;
+
+#[cfg(test)]
+mod partial_clone_test;
+
+#[cfg(test)]
+mod patch_export_test;
+
+#[cfg(test)]
+mod patch_import_test;
+
+#[cfg(test)]
+mod ref_transaction_test;
+
+#[cfg(test)]
+mod repository_lock_test;
+
+#[cfg(test)]
+mod word_diff_test;
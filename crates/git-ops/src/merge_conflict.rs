@@ -1,6 +1,11 @@
-use crate::conflict_analysis::{FileInfo, get_files_content_at_commit};
+use crate::binary_conflict::is_binary_conflict;
+use crate::conflict_analysis::{FileDiff, FileInfo, get_files_content_at_commit};
+use crate::conflict_classification::classify_conflict;
 use crate::copy_commit::CopyCommitError;
+use crate::diff_options::DiffOptions;
+use crate::file_language::detect_file_language;
 use crate::model::{ConflictDetail, ConflictMarkerCommitInfo};
+use crate::word_diff::compute_word_diffs;
 use anyhow::{Result, anyhow};
 use git_executor::git_command_executor::GitCommandExecutor;
 use std::collections::HashMap;
@@ -27,23 +32,17 @@ pub fn generate_diff_hunks(
   file_path: &str,
   from_content: &str,
   to_content: &str,
-  file_ext: &str,
+  file_lang: &str,
+  diff_options: &DiffOptions,
 ) -> Result<crate::conflict_analysis::FileDiff, CopyCommitError> {
   let mut hunks = Vec::new();
 
   // Use git diff to generate proper hunks if contents are different
   if from_content != to_content {
-    let args = vec![
-      "-c",
-      "merge.conflictStyle=zdiff3",
-      "diff",
-      "--no-color",
-      "--unified=3",
-      from_commit,
-      to_commit,
-      "--",
-      file_path,
-    ];
+    let diff_args = diff_options.to_args();
+    let mut args = vec!["-c", "merge.conflictStyle=zdiff3", "diff", "--no-color"];
+    args.extend(diff_args.iter().map(|s| s.as_str()));
+    args.extend([from_commit, to_commit, "--", file_path]);
     let diff_output = git_executor
       .execute_command(&args, repo_path)
       .map_err(|e| CopyCommitError::Other(anyhow!("git diff failed: {}", e)))?;
@@ -51,8 +50,9 @@ pub fn generate_diff_hunks(
     // Parse hunks using helper function
     hunks = parse_diff_hunks(&diff_output, file_path)?;
 
-    // If no hunks were found from git diff but content is different, create a manual diff
-    if hunks.is_empty() {
+    // If no hunks were found from git diff but content is different, create a manual diff.
+    // With ignored whitespace, no hunks is the expected result for whitespace-only changes.
+    if hunks.is_empty() && !diff_options.ignore_whitespace {
       let from_lines = from_content.lines().collect::<Vec<_>>();
       let to_lines = to_content.lines().collect::<Vec<_>>();
 
@@ -100,15 +100,18 @@ pub fn generate_diff_hunks(
   Ok(crate::conflict_analysis::FileDiff {
     old_file: crate::conflict_analysis::FileInfo {
       file_name: file_path.to_string(),
-      file_lang: file_ext.to_string(),
+      file_lang: file_lang.to_string(),
       content: from_content.to_string(),
     },
     new_file: crate::conflict_analysis::FileInfo {
       file_name: file_path.to_string(),
-      file_lang: file_ext.to_string(),
+      file_lang: file_lang.to_string(),
       content: to_content.to_string(),
     },
+    word_diffs: Some(compute_word_diffs(&hunks)),
     hunks,
+    whitespace_ignored: diff_options.ignore_whitespace,
+    rename: None,
   })
 }
 
@@ -150,23 +153,17 @@ pub fn get_commit_info_batch(git_executor: &GitCommandExecutor, repo_path: &str,
 /// Get merge conflict content with conflict markers for a specific file using the merge tree
 #[instrument(skip(git_executor))]
 pub fn get_merge_conflict_content_from_tree(git_executor: &GitCommandExecutor, repo_path: &str, merge_tree_oid: &str, file_path: &str) -> Result<String, CopyCommitError> {
-  // Use git cat-file to extract the file content from the merged tree
+  // Read the file content from the merged tree via the long-lived cat-file process
   // This tree contains conflict markers for conflicted files
   let object_path = format!("{merge_tree_oid}:{file_path}");
-  let args = vec!["cat-file", "-p", &object_path];
-
-  let output = match git_executor.execute_command(&args, repo_path) {
-    Ok(content) => content,
-    Err(e) => {
-      let error_msg = e.to_string();
-      return if error_msg.contains("does not exist") {
-        // File doesn't exist in merge tree (likely renamed/moved) - return empty content
-        debug!(file_path = %file_path, merge_tree_oid = %merge_tree_oid, "File not found in merge tree, returning empty content");
-        Ok(String::new())
-      } else {
-        Err(CopyCommitError::Other(anyhow::anyhow!("Failed to get file content from merge tree: {}", e)))
-      };
+  let output = match git_executor.cat_file_contents(repo_path, &object_path) {
+    Ok(Some((_, content))) => String::from_utf8_lossy(&content).trim().to_string(),
+    Ok(None) => {
+      // File doesn't exist in merge tree (likely renamed/moved) - return empty content
+      debug!(file_path = %file_path, merge_tree_oid = %merge_tree_oid, "File not found in merge tree, returning empty content");
+      return Ok(String::new());
     }
+    Err(e) => return Err(CopyCommitError::Other(anyhow::anyhow!("Failed to get file content from merge tree: {}", e))),
   };
 
   debug!(content_length = output.len(), "retrieved conflict content");
@@ -181,18 +178,20 @@ fn generate_conflict_diff_hunks(
   target_commit_id: &str,
   merge_tree_oid: &str,
   file_path: &str,
+  diff_options: &DiffOptions,
 ) -> Result<Vec<String>, CopyCommitError> {
   // First check if the file exists in the target commit
   let target_file_ref = format!("{}:{}", target_commit_id, file_path);
-  let check_args = vec!["cat-file", "-e", &target_file_ref];
-  let file_exists_in_target = git_executor.execute_command(&check_args, repo_path).is_ok();
+  let file_exists_in_target = matches!(git_executor.cat_file_info(repo_path, &target_file_ref), Ok(Some(_)));
 
   if !file_exists_in_target {
     // File doesn't exist in target (new file conflict) - show everything as additions
     let conflict_file_ref = format!("{}:{}", merge_tree_oid, file_path);
-    let conflict_content = git_executor
-      .execute_command(&["cat-file", "-p", &conflict_file_ref], repo_path)
-      .map_err(|e| CopyCommitError::Other(anyhow!("Failed to get conflict content: {}", e)))?;
+    let (_, conflict_content) = git_executor
+      .cat_file_contents(repo_path, &conflict_file_ref)
+      .map_err(|e| CopyCommitError::Other(anyhow!("Failed to get conflict content: {}", e)))?
+      .ok_or_else(|| CopyCommitError::Other(anyhow!("Failed to get conflict content: {} does not exist", conflict_file_ref)))?;
+    let conflict_content = String::from_utf8_lossy(&conflict_content).trim().to_string();
 
     if conflict_content.is_empty() {
       return Ok(vec![]);
@@ -213,15 +212,10 @@ fn generate_conflict_diff_hunks(
 
   // File exists in target, do normal diff
   let conflict_file_ref = format!("{}:{}", merge_tree_oid, file_path);
-  let diff_args = vec![
-    "-c",
-    "merge.conflictStyle=zdiff3",
-    "diff",
-    "--no-color",
-    "--unified=3",
-    &target_file_ref,
-    &conflict_file_ref,
-  ];
+  let option_args = diff_options.to_args();
+  let mut diff_args = vec!["-c", "merge.conflictStyle=zdiff3", "diff", "--no-color"];
+  diff_args.extend(option_args.iter().map(|s| s.as_str()));
+  diff_args.extend([target_file_ref.as_str(), conflict_file_ref.as_str()]);
 
   let diff_output = git_executor
     .execute_command(&diff_args, repo_path)
@@ -279,6 +273,7 @@ pub struct ConflictDetailsParams<'a> {
   pub parent_commit_id: &'a str,
   pub target_commit_id: &'a str,
   pub cherry_commit_id: &'a str,
+  pub diff_options: &'a DiffOptions,
 }
 
 /// Extract conflict details with actual merge conflicts and conflict markers
@@ -328,6 +323,13 @@ pub fn extract_conflict_details(params: ConflictDetailsParams) -> Result<(Vec<Co
   for info in params.conflict_files.values() {
     let file_path = info.path.display().to_string();
 
+    let stage_oids = [&info.base_oid, &info.ours_oid, &info.theirs_oid];
+    if is_binary_conflict(params.git_executor, params.repo_path, stage_oids.into_iter().flatten().map(String::as_str)) {
+      debug!(file = %file_path, "binary conflict, skipping diffs");
+      conflict_details.push(binary_conflict_detail(file_path, params.diff_options));
+      continue;
+    }
+
     // Get the actual merge conflict content with conflict markers from the merge tree
 
     let conflict_content = get_merge_conflict_content_from_tree(params.git_executor, params.repo_path, params.merge_tree_oid, &file_path)?; // Don't hide errors - if we detected conflict, content must exist
@@ -340,12 +342,19 @@ pub fn extract_conflict_details(params: ConflictDetailsParams) -> Result<(Vec<Co
       // This will show conflict markers as additions, not deletions
       let original_content = String::new(); // Show empty as "before" so conflict markers appear as additions
 
-      let file_ext = file_path.split('.').next_back().unwrap_or("txt").to_string();
+      let file_lang = detect_file_language(&file_path, &conflict_content);
 
       // Use git diff --cc with a temporary merge commit to get proper 3-way conflict diffs
       let hunks = if original_content != conflict_content {
         // Generate diff directly without creating temporary commit objects
-        generate_conflict_diff_hunks(params.git_executor, params.repo_path, params.target_commit_id, params.merge_tree_oid, &file_path)?
+        generate_conflict_diff_hunks(
+          params.git_executor,
+          params.repo_path,
+          params.target_commit_id,
+          params.merge_tree_oid,
+          &file_path,
+          params.diff_options,
+        )?
       } else {
         // If same content, show as context
         let lines = conflict_content.lines().collect::<Vec<_>>();
@@ -367,18 +376,23 @@ pub fn extract_conflict_details(params: ConflictDetailsParams) -> Result<(Vec<Co
       crate::conflict_analysis::FileDiff {
         old_file: FileInfo {
           file_name: file_path.clone(),
-          file_lang: file_ext.clone(),
+          file_lang: file_lang.clone(),
           content: original_content,
         },
         new_file: FileInfo {
           file_name: file_path.clone(),
-          file_lang: file_ext,
+          file_lang,
           content: conflict_content,
         },
+        word_diffs: Some(compute_word_diffs(&hunks)),
         hunks,
+        whitespace_ignored: params.diff_options.ignore_whitespace,
+        rename: None,
       }
     };
 
+    let classification = classify_conflict(&file_diff.new_file.content, &file_diff.new_file.file_lang);
+
     // Generate individual file info for 3-way merge view
     // The merge_base_id was already calculated above, reuse it
 
@@ -402,23 +416,23 @@ pub fn extract_conflict_details(params: ConflictDetailsParams) -> Result<(Vec<Co
       .unwrap_or_default();
 
     // Create FileInfo structs for each version
-    let file_ext = file_path.split('.').next_back().unwrap_or("txt").to_string();
+    let file_lang = detect_file_language(&file_path, &target_content);
 
     let base_file = Some(FileInfo {
       file_name: file_path.clone(),
-      file_lang: file_ext.clone(),
+      file_lang: file_lang.clone(),
       content: base_content.clone(),
     });
 
     let target_file = Some(FileInfo {
       file_name: file_path.clone(),
-      file_lang: file_ext.clone(),
+      file_lang: file_lang.clone(),
       content: target_content.clone(),
     });
 
     let cherry_file = Some(FileInfo {
       file_name: file_path.clone(),
-      file_lang: file_ext.clone(),
+      file_lang: file_lang.clone(),
       content: cherry_content.clone(),
     });
 
@@ -431,7 +445,8 @@ pub fn extract_conflict_details(params: ConflictDetailsParams) -> Result<(Vec<Co
       &file_path,
       &base_content,
       &target_content,
-      &file_ext,
+      &file_lang,
+      params.diff_options,
     )?;
     let base_to_cherry_diff = generate_diff_hunks(
       params.git_executor,
@@ -441,7 +456,8 @@ pub fn extract_conflict_details(params: ConflictDetailsParams) -> Result<(Vec<Co
       &file_path,
       &base_content,
       &cherry_content,
-      &file_ext,
+      &file_lang,
+      params.diff_options,
     )?;
 
     conflict_details.push(ConflictDetail {
@@ -453,8 +469,39 @@ pub fn extract_conflict_details(params: ConflictDetailsParams) -> Result<(Vec<Co
       cherry_file,
       base_to_target_diff,
       base_to_cherry_diff,
+      is_binary: false,
+      classification,
     });
   }
 
   Ok((conflict_details, commit_info_map))
 }
+
+/// Binary content would be garbage as text, so only the path is reported; versions are chosen with `resolve_binary_conflict`
+fn binary_conflict_detail(file_path: String, diff_options: &DiffOptions) -> ConflictDetail {
+  let file_info = FileInfo {
+    file_name: file_path.clone(),
+    file_lang: "binary".to_string(),
+    content: String::new(),
+  };
+  let empty_diff = FileDiff {
+    old_file: file_info.clone(),
+    new_file: file_info.clone(),
+    hunks: Vec::new(),
+    whitespace_ignored: diff_options.ignore_whitespace,
+    rename: None,
+    word_diffs: None,
+  };
+  ConflictDetail {
+    file: file_path,
+    status: "modified".to_string(),
+    file_diff: empty_diff.clone(),
+    base_file: None,
+    target_file: None,
+    cherry_file: None,
+    base_to_target_diff: empty_diff.clone(),
+    base_to_cherry_diff: empty_diff,
+    is_binary: true,
+    classification: None,
+  }
+}
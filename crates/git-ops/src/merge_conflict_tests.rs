@@ -1,6 +1,7 @@
 use super::cache::TreeIdCache;
 use super::cherry_pick::perform_fast_cherry_pick_with_context;
 use super::copy_commit::CopyCommitError;
+use super::diff_options::DiffOptions;
 use super::model::BranchError;
 use git_executor::git_command_executor::GitCommandExecutor;
 use pretty_assertions::assert_eq;
@@ -28,6 +29,7 @@ fn test_conflict_hunks_extraction() {
     &scenario.target_commit,
     None,
     &cache,
+    &DiffOptions::default(),
   );
 
   // Verify it's a conflict
@@ -97,6 +99,7 @@ fn test_delete_modify_conflict() {
     &scenario.target_commit,
     None,
     &cache,
+    &DiffOptions::default(),
   );
 
   // Verify it's a conflict
@@ -152,12 +155,48 @@ fn test_no_conflict_no_hunks() {
 
   // Attempt cherry-pick - should succeed without conflicts
   let cache = TreeIdCache::new();
-  let result = perform_fast_cherry_pick_with_context(git_executor, test_repo.path().to_str().unwrap(), &cherry_commit_hash, &target_commit_hash, None, &cache);
+  let result = perform_fast_cherry_pick_with_context(
+    git_executor,
+    test_repo.path().to_str().unwrap(),
+    &cherry_commit_hash,
+    &target_commit_hash,
+    None,
+    &cache,
+    &DiffOptions::default(),
+  );
 
   // Should succeed
   assert!(result.is_ok(), "Cherry-pick should succeed without conflicts");
 }
 
+#[test]
+fn test_whitespace_only_conflict_resolved_when_ignoring_whitespace() {
+  let test_repo = TestRepo::new();
+  let git_executor = &GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let initial_commit_hash = test_repo.create_commit("Initial commit", "main.rs", "fn main() {\n  run();\n}\n");
+  // Target only re-indents the line
+  let target_commit_hash = test_repo.create_commit("Reindent", "main.rs", "fn main() {\n    run();\n}\n");
+  test_repo.reset_hard(&initial_commit_hash).unwrap();
+  let cherry_commit_hash = test_repo.create_commit("Pass args", "main.rs", "fn main() {\n  run(args);\n}\n");
+
+  let cache = TreeIdCache::new();
+  let result = perform_fast_cherry_pick_with_context(git_executor, repo_path, &cherry_commit_hash, &target_commit_hash, None, &cache, &DiffOptions::default());
+  assert!(matches!(result, Err(CopyCommitError::BranchError(BranchError::MergeConflict(_)))), "{result:?}");
+
+  // Older gits merge without strategy options, the conflict stays
+  if !git_executor.get_info().unwrap().supports_merge_tree_strategy_options() {
+    return;
+  }
+  let options = DiffOptions {
+    ignore_whitespace_in_merges: true,
+    ..DiffOptions::default()
+  };
+  let result = perform_fast_cherry_pick_with_context(git_executor, repo_path, &cherry_commit_hash, &target_commit_hash, None, &cache, &options);
+  assert!(result.is_ok(), "Whitespace-only divergence should not conflict: {result:?}");
+}
+
 #[test]
 fn test_get_merge_conflict_content_with_markers() {
   let test_repo = TestRepo::new();
@@ -179,6 +218,7 @@ fn test_get_merge_conflict_content_with_markers() {
     &scenario.target_commit,
     None,
     &cache,
+    &DiffOptions::default(),
   );
 
   // Verify it's a conflict and check the file_diff content
@@ -245,6 +285,7 @@ fn test_conflict_detail_file_diff_structure() {
     &scenario.target_commit,
     None,
     &cache,
+    &DiffOptions::default(),
   );
 
   // Verify conflict structure
@@ -258,7 +299,7 @@ fn test_conflict_detail_file_diff_structure() {
     // old_file should be empty (to show conflict markers as additions)
     assert_eq!(file_diff.old_file.content, "");
     assert_eq!(file_diff.old_file.file_name, "main.js");
-    assert_eq!(file_diff.old_file.file_lang, "js");
+    assert_eq!(file_diff.old_file.file_lang, "javascript");
 
     // new_file should contain the conflict content with markers
     let conflict_content = &file_diff.new_file.content;
@@ -330,6 +371,7 @@ fn test_multiple_conflicting_files_with_markers() {
     &scenario.target_commit,
     None,
     &cache,
+    &DiffOptions::default(),
   );
 
   // Verify multiple conflicts
@@ -385,6 +427,7 @@ fn test_conflict_with_empty_file() {
     &scenario.target_commit,
     None,
     &cache,
+    &DiffOptions::default(),
   );
 
   // Verify conflict with proper content
@@ -492,6 +535,7 @@ init {
     &scenario.target_commit,
     None,
     &cache,
+    &DiffOptions::default(),
   );
 
   // Should produce conflicts
@@ -596,6 +640,7 @@ return result;
     &scenario.target_commit,
     None,
     &cache,
+    &DiffOptions::default(),
   );
 
   // Should produce conflicts
@@ -721,6 +766,7 @@ init {
     &scenario.target_commit,
     None,
     &cache,
+    &DiffOptions::default(),
   );
 
   // Should produce conflicts
@@ -0,0 +1,225 @@
+//! Resolving a conflict with the external merge tool configured for git (`merge.tool`).
+//!
+//! The base, ours and theirs versions of the file, and the merged version with conflict markers, are written to a
+//! temporary directory and the tool is run on them like `git mergetool` does. Once the tool exits, the merged file is
+//! recorded as the resolution of the conflict (see [`crate::binary_conflict`]) and applied by the next sync.
+//!
+//! Tools with a `mergetool.<tool>.cmd` are run by `sh` with `BASE`, `LOCAL`, `REMOTE` and `MERGED` in the environment,
+//! other tools are run directly with the arguments of a known tool. `mergetool.<tool>.path` overrides the executable.
+//! On Windows, `sh` is the one bundled with git for Windows, which is usually not on the `PATH`.
+
+use crate::binary_conflict::{find_conflict_stages, record_resolution};
+use crate::git_config::{get_config_bool, get_config_value};
+use crate::temp_dir::TempDirGuard;
+use anyhow::{Result, anyhow, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tracing::{info, instrument, warn};
+
+#[cfg(feature = "specta")]
+use specta::Type;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchMergeToolParams {
+  pub repository_path: String,
+  /// Conflicting commit (`commitHash` of the merge conflict)
+  pub commit_hash: String,
+  /// Commit it was applied onto (`targetBranchHash` of the merge conflict)
+  pub target_commit_hash: String,
+  /// Path of the conflicting file
+  pub file: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchMergeToolResult {
+  /// Name of the tool that was run
+  pub tool: String,
+  /// Blob the file gets in the resolved commit, `None` if the merge was not completed in the tool
+  pub blob_id: Option<String>,
+}
+
+/// How a merge tool is run
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ToolCommand {
+  /// Shell command line from `mergetool.<tool>.cmd`
+  Shell(String),
+  /// Executable and its arguments, `BASE`, `LOCAL`, `REMOTE` and `MERGED` stand for the paths of the files
+  Program { program: String, args: Vec<&'static str> },
+}
+
+/// Arguments of the known merge tools, in the order of the placeholders of [`ToolCommand::Program`]
+fn known_tool_args(tool: &str) -> Option<(&'static str, Vec<&'static str>)> {
+  let tool = match tool {
+    "kdiff3" => (
+      "kdiff3",
+      vec!["--L1", "Base", "--L2", "Target", "--L3", "Incoming", "-o", "MERGED", "BASE", "LOCAL", "REMOTE"],
+    ),
+    "meld" => ("meld", vec!["LOCAL", "BASE", "REMOTE", "--output", "MERGED"]),
+    "idea" | "intellij" => ("idea", vec!["merge", "LOCAL", "REMOTE", "BASE", "MERGED"]),
+    "vscode" => ("code", vec!["--wait", "--merge", "LOCAL", "REMOTE", "BASE", "MERGED"]),
+    "opendiff" => ("opendiff", vec!["LOCAL", "REMOTE", "-ancestor", "BASE", "-merge", "MERGED"]),
+    "bc" | "bc3" | "bc4" => ("bcomp", vec!["LOCAL", "REMOTE", "BASE", "MERGED"]),
+    "p4merge" => ("p4merge", vec!["BASE", "LOCAL", "REMOTE", "MERGED"]),
+    "tortoisemerge" => ("tortoisemerge", vec!["-base:BASE", "-mine:LOCAL", "-theirs:REMOTE", "-merged:MERGED"]),
+    _ => return None,
+  };
+  Some(tool)
+}
+
+/// Configured merge tool and how to run it, whether its exit code tells if the merge was completed
+fn resolve_tool_command(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<(String, ToolCommand, bool)> {
+  let tool = get_config_value(git_executor, repo_path, "merge.tool")?
+    .filter(|tool| !tool.is_empty())
+    .ok_or_else(|| anyhow!("No merge tool is configured, set one with `git config --global merge.tool <tool>`"))?;
+
+  if let Some(cmd) = get_config_value(git_executor, repo_path, &format!("mergetool.{tool}.cmd"))? {
+    // Like git, the exit code of a custom command is not trusted unless configured
+    let trust_exit_code = get_config_bool(git_executor, repo_path, &format!("mergetool.{tool}.trustExitCode"))?.unwrap_or(false);
+    return Ok((tool, ToolCommand::Shell(cmd), trust_exit_code));
+  }
+
+  let (program, args) = known_tool_args(&tool).ok_or_else(|| anyhow!("Unknown merge tool '{tool}', configure its command in mergetool.{tool}.cmd"))?;
+  let program = get_config_value(git_executor, repo_path, &format!("mergetool.{tool}.path"))?.unwrap_or_else(|| program.to_string());
+  let trust_exit_code = get_config_bool(git_executor, repo_path, &format!("mergetool.{tool}.trustExitCode"))?.unwrap_or(true);
+  Ok((tool, ToolCommand::Program { program, args }, trust_exit_code))
+}
+
+/// Paths of the files the tool works on
+struct MergeFiles {
+  base: String,
+  local: String,
+  remote: String,
+  merged: String,
+}
+
+impl MergeFiles {
+  fn path_of(&self, placeholder: &str) -> Option<&str> {
+    match placeholder {
+      "BASE" => Some(&self.base),
+      "LOCAL" => Some(&self.local),
+      "REMOTE" => Some(&self.remote),
+      "MERGED" => Some(&self.merged),
+      _ => None,
+    }
+  }
+
+  /// Replace the placeholder in an argument, also when it follows an option name (`-base:BASE`)
+  fn expand_arg(&self, arg: &str) -> String {
+    if let Some(path) = self.path_of(arg) {
+      return path.to_string();
+    }
+    if let Some((option, placeholder)) = arg.split_once(':')
+      && let Some(path) = self.path_of(placeholder)
+    {
+      return format!("{option}:{path}");
+    }
+    arg.to_string()
+  }
+
+  fn command(&self, git_executor: &GitCommandExecutor, repo_path: &str, tool_command: &ToolCommand) -> Command {
+    let mut command = match tool_command {
+      ToolCommand::Shell(cmd) => {
+        // `mergetool.<tool>.cmd` is a shell command line, git for Windows runs it with its bundled `sh` too
+        let mut command = Command::new(git_shell(git_executor, repo_path));
+        command.args(["-c", cmd.as_str()]);
+        command
+      }
+      ToolCommand::Program { program, args } => {
+        let mut command = Command::new(program);
+        command.args(args.iter().map(|arg| self.expand_arg(arg)));
+        command
+      }
+    };
+    command.envs([("BASE", &self.base), ("LOCAL", &self.local), ("REMOTE", &self.remote), ("MERGED", &self.merged)]);
+    command
+  }
+}
+
+/// `sh` bundled with git for Windows, found above the exec path of git (`<git>/mingw64/libexec/git-core`)
+#[cfg(windows)]
+pub(crate) fn git_shell(git_executor: &GitCommandExecutor, repo_path: &str) -> String {
+  let exec_path = match git_executor.execute_command(&["--exec-path"], repo_path) {
+    Ok(exec_path) => exec_path,
+    Err(e) => {
+      warn!(error = %e, "Failed to get the exec path of git, running sh from PATH");
+      return "sh".to_string();
+    }
+  };
+  Path::new(exec_path.trim())
+    .ancestors()
+    .flat_map(|dir| [dir.join("usr").join("bin").join("sh.exe"), dir.join("bin").join("sh.exe")])
+    .find(|path| path.is_file())
+    .map_or_else(|| "sh".to_string(), |path| path.to_string_lossy().into_owned())
+}
+
+#[cfg(not(windows))]
+pub(crate) fn git_shell(_git_executor: &GitCommandExecutor, _repo_path: &str) -> String {
+  "sh".to_string()
+}
+
+/// Run the configured merge tool on a conflicting file of a commit applied onto another one and wait for it to exit.
+/// The merged file is recorded as the resolution of the conflict, the next sync applies it.
+#[instrument(skip(git_executor, params), fields(commit = %params.commit_hash, target = %params.target_commit_hash, file = %params.file))]
+pub fn launch_merge_tool(git_executor: &GitCommandExecutor, params: LaunchMergeToolParams) -> Result<LaunchMergeToolResult> {
+  let LaunchMergeToolParams {
+    repository_path,
+    commit_hash,
+    target_commit_hash,
+    file,
+  } = params;
+
+  let (tool, tool_command, trust_exit_code) = resolve_tool_command(git_executor, &repository_path)?;
+  let (merge, stages) = find_conflict_stages(git_executor, &repository_path, &commit_hash, &target_commit_hash, &file)?;
+
+  // Files are named after the conflicting one, so the tool can detect its type
+  let temp_dir = TempDirGuard::new(&repository_path, "merge_tool")?;
+  let file_name = Path::new(&file).file_name().map_or_else(|| file.clone(), |name| name.to_string_lossy().into_owned());
+  let files = MergeFiles {
+    base: temp_dir.join(&format!("BASE_{file_name}")),
+    local: temp_dir.join(&format!("LOCAL_{file_name}")),
+    remote: temp_dir.join(&format!("REMOTE_{file_name}")),
+    merged: temp_dir.join(&file_name),
+  };
+  let blob_content = |object: &str| -> Result<Vec<u8>> { Ok(git_executor.cat_file_contents(&repository_path, object)?.map(|(_, content)| content).unwrap_or_default()) };
+  // A version missing on a side (deleted or added) is an empty file
+  for (stage, path) in stages.iter().zip([&files.base, &files.local, &files.remote]) {
+    let content = match stage {
+      Some(entry) => blob_content(&entry.object_id)?,
+      None => Vec::new(),
+    };
+    fs::write(path, content)?;
+  }
+  let merged_content = blob_content(&format!("{}:{file}", merge.tree_id))?;
+  fs::write(&files.merged, &merged_content)?;
+
+  info!(%tool, "Launching merge tool");
+  let status = files
+    .command(git_executor, &repository_path, &tool_command)
+    .current_dir(&repository_path)
+    .status()
+    .map_err(|e| anyhow!("Failed to launch merge tool '{tool}': {e}"))?;
+  let result = fs::read(&files.merged)?;
+  let completed = if trust_exit_code { status.success() } else { result != merged_content };
+  if !completed {
+    warn!(%tool, ?status, "Merge was not completed in the merge tool");
+    return Ok(LaunchMergeToolResult { tool, blob_id: None });
+  }
+  if String::from_utf8_lossy(&result)
+    .lines()
+    .any(|line| line.starts_with("<<<<<<< ") || line.starts_with(">>>>>>> "))
+  {
+    bail!("'{file}' still has conflict markers after merging in '{tool}'");
+  }
+
+  let blob_id = git_executor.execute_command(&["hash-object", "-w", "--", &files.merged], &repository_path)?;
+  record_resolution(git_executor, &repository_path, &file, &stages.each_ref().map(Option::as_ref), &blob_id)?;
+  info!(%tool, %blob_id, "Recorded merge tool resolution");
+  Ok(LaunchMergeToolResult { tool, blob_id: Some(blob_id) })
+}
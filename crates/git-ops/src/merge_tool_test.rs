@@ -0,0 +1,88 @@
+use crate::cache::TreeIdCache;
+use crate::cherry_pick::perform_fast_cherry_pick_with_context;
+use crate::copy_commit::CopyCommitError;
+use crate::diff_options::DiffOptions;
+use crate::merge_tool::{LaunchMergeToolParams, launch_merge_tool};
+use crate::model::BranchError;
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::{ConflictTestBuilder, TestRepo};
+
+#[cfg(unix)]
+#[test]
+fn test_merge_tool_resolution() {
+  let test_repo = TestRepo::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+
+  let scenario = ConflictTestBuilder::new(&test_repo)
+    .with_initial_state(vec![("config.txt", "value = base\n")], "Initial commit")
+    .with_target_changes(vec![("config.txt", "value = target\n")], "Target: Change value")
+    .with_cherry_changes(vec![("config.txt", "value = cherry\n")], "Cherry-pick: Another value")
+    .build();
+  let cherry_pick = || {
+    perform_fast_cherry_pick_with_context(
+      &git_executor,
+      repo_path,
+      &scenario.cherry_commit,
+      &scenario.target_commit,
+      None,
+      &TreeIdCache::new(),
+      &DiffOptions::default(),
+    )
+  };
+  assert!(matches!(cherry_pick(), Err(CopyCommitError::BranchError(BranchError::MergeConflict(_)))));
+
+  let params = || LaunchMergeToolParams {
+    repository_path: repo_path.to_string(),
+    commit_hash: scenario.cherry_commit.clone(),
+    target_commit_hash: scenario.target_commit.clone(),
+    file: "config.txt".to_string(),
+  };
+  let error = launch_merge_tool(&git_executor, params()).unwrap_err();
+  assert!(error.to_string().contains("No merge tool is configured"), "{error}");
+
+  // The exit code of a custom command is not trusted, an unchanged merged file means the merge was not completed
+  test_repo.set_config("merge.tool", "fake").unwrap();
+  test_repo.set_config("mergetool.fake.cmd", "true").unwrap();
+  let result = launch_merge_tool(&git_executor, params()).unwrap();
+  assert_eq!((result.tool.as_str(), result.blob_id), ("fake", None));
+
+  // Leaving conflict markers is not a resolution
+  test_repo
+    .set_config("mergetool.fake.cmd", "cat \"$MERGED\" \"$MERGED\" > \"$MERGED.tmp\" && mv \"$MERGED.tmp\" \"$MERGED\"")
+    .unwrap();
+  let error = launch_merge_tool(&git_executor, params()).unwrap_err();
+  assert!(error.to_string().contains("still has conflict markers"), "{error}");
+
+  test_repo
+    .set_config(
+      "mergetool.fake.cmd",
+      "printf 'base: %s, ours: %s, theirs: %s' \"$(cat \"$BASE\")\" \"$(cat \"$LOCAL\")\" \"$(cat \"$REMOTE\")\" > \"$MERGED\"",
+    )
+    .unwrap();
+  let result = launch_merge_tool(&git_executor, params()).unwrap();
+  let blob_id = result.blob_id.unwrap();
+  assert_eq!(
+    blob_id,
+    git_executor
+      .execute_command_with_input(&["hash-object", "--stdin"], repo_path, "base: value = base, ours: value = target, theirs: value = cherry")
+      .unwrap()
+  );
+
+  // The recorded resolution is applied by the next cherry-pick
+  let tree_id = cherry_pick().unwrap();
+  assert_eq!(test_repo.rev_parse(&format!("{tree_id}:config.txt")).unwrap(), blob_id);
+}
+
+#[cfg(windows)]
+#[test]
+fn test_git_shell() {
+  use crate::merge_tool::git_shell;
+
+  let test_repo = TestRepo::new();
+  let shell = git_shell(&GitCommandExecutor::new(), test_repo.path().to_str().unwrap());
+  assert!(shell.ends_with("sh.exe"), "{shell}");
+  assert!(std::path::Path::new(&shell).is_file(), "{shell}");
+}
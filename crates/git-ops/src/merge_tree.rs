@@ -0,0 +1,205 @@
+use crate::diff_options::DiffOptions;
+use crate::temp_dir::TempDirGuard;
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use std::collections::BTreeMap;
+use std::fs;
+use tracing::{debug, instrument, warn};
+
+const NULL_OBJECT_ID: &str = "0000000000000000000000000000000000000000";
+
+/// Unmerged index entry of a conflicted path, as listed by `merge-tree -z` or `ls-files -u`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictEntry {
+  pub mode: String,
+  pub object_id: String,
+  /// 1 for the merge base, 2 for ours, 3 for theirs
+  pub stage: u8,
+  pub path: String,
+}
+
+impl ConflictEntry {
+  /// Parse `<mode> <object> <stage>\t<path>`
+  fn parse(entry: &str) -> Option<Self> {
+    let (prefix, path) = entry.split_once('\t')?;
+    let mut parts = prefix.split_whitespace();
+    let mode = parts.next()?.to_string();
+    let object_id = parts.next()?.to_string();
+    let stage = parts.next()?.parse().ok()?;
+    Some(Self {
+      mode,
+      object_id,
+      stage,
+      path: path.to_string(),
+    })
+  }
+}
+
+/// Result of a three-way tree merge: the merged tree, with conflict markers in conflicted files, and the unmerged entries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeMerge {
+  pub tree_id: String,
+  pub conflicts: Vec<ConflictEntry>,
+}
+
+impl TreeMerge {
+  pub fn has_conflicts(&self) -> bool {
+    !self.conflicts.is_empty()
+  }
+}
+
+/// Three-way merge of `ours` and `theirs` (any tree-ish) against `merge_base` without touching the working directory or the index.
+/// Uses `git merge-tree --write-tree` where available; older gits (before 2.40, without `--merge-base`) merge in a temporary index instead.
+/// Merge strategy options ([`DiffOptions::merge_args`]) need git 2.44, older gits and the fallback merge without them.
+#[instrument(skip(git_executor, diff_options))]
+pub fn merge_trees(git_executor: &GitCommandExecutor, repo_path: &str, merge_base: &str, ours: &str, theirs: &str, diff_options: &DiffOptions) -> Result<TreeMerge> {
+  let info = git_executor.get_info()?;
+  if info.supports_merge_tree_write_tree() {
+    let merge_args = if info.supports_merge_tree_strategy_options() {
+      diff_options.merge_args()
+    } else {
+      if !diff_options.merge_args().is_empty() {
+        debug!(git_version = %info.version, "git merge-tree doesn't accept strategy options, merging without them");
+      }
+      Vec::new()
+    };
+    merge_trees_with_merge_tree(git_executor, repo_path, merge_base, ours, theirs, &merge_args)
+  } else {
+    merge_trees_with_index(git_executor, repo_path, merge_base, ours, theirs)
+  }
+}
+
+fn merge_trees_with_merge_tree(git_executor: &GitCommandExecutor, repo_path: &str, merge_base: &str, ours: &str, theirs: &str, merge_args: &[&str]) -> Result<TreeMerge> {
+  let mut args = vec![
+    "-c",
+    "merge.conflictStyle=zdiff3", // Set conflict style to include base content
+    "merge-tree",
+    "--write-tree",
+    "-z", // Use NUL character as separator for better parsing
+  ];
+  args.extend(merge_args);
+  args.extend(["--merge-base", merge_base, ours, theirs]);
+
+  // Exit code 1 (conflicts) is accepted by the executor, the conflicts are listed in the output
+  let output = git_executor
+    .execute_command(&args, repo_path)
+    .map_err(|e| anyhow!("Failed to execute git merge-tree: {}", e))?;
+  debug!(output_length = output.len(), "git merge-tree completed");
+
+  // Tree ID, conflicted file entries, an empty separator and informational messages
+  let mut parts = output.trim_end_matches('\0').split('\0');
+  let tree_id = parts.next().filter(|tree_id| !tree_id.is_empty()).ok_or_else(|| anyhow!("No output from git merge-tree"))?;
+  let conflicts = parts.take_while(|part| !part.is_empty()).filter_map(ConflictEntry::parse).collect();
+  Ok(TreeMerge {
+    tree_id: tree_id.to_string(),
+    conflicts,
+  })
+}
+
+/// Stages of an unmerged path by index stage (1 base, 2 ours, 3 theirs)
+#[derive(Default)]
+struct UnmergedPath {
+  stages: [Option<ConflictEntry>; 3],
+}
+
+impl UnmergedPath {
+  fn stage(&self, stage: u8) -> Option<&ConflictEntry> {
+    self.stages[usize::from(stage - 1)].as_ref()
+  }
+}
+
+/// Merge in a temporary index: `read-tree -m` resolves trivial cases, `merge-file` merges the content of paths changed on both sides.
+/// `merge-file` has no whitespace or line ending options, so `ignore_whitespace_in_merges` and `ignore_cr_at_eol` don't apply here.
+/// Renames are not detected: `read-tree` compares paths, so a file renamed on one side and changed on the other is reported
+/// as a modify/delete conflict, where `merge-tree` would merge the change into the renamed file.
+pub(crate) fn merge_trees_with_index(git_executor: &GitCommandExecutor, repo_path: &str, merge_base: &str, ours: &str, theirs: &str) -> Result<TreeMerge> {
+  debug!("git merge-tree --write-tree is not supported, merging in a temporary index");
+  let temp_dir = TempDirGuard::new(repo_path, "merge")?;
+  let index_path = temp_dir.join("index");
+  let env = [("GIT_INDEX_FILE", index_path.as_str())];
+
+  git_executor.execute_command_with_env(&["read-tree", "-i", "-m", "--aggressive", merge_base, ours, theirs], repo_path, &env)?;
+  let unmerged_output = git_executor.execute_command_with_env(&["ls-files", "-u", "-z"], repo_path, &env)?;
+
+  let mut unmerged: BTreeMap<String, UnmergedPath> = BTreeMap::new();
+  for entry in unmerged_output.split('\0').filter_map(ConflictEntry::parse) {
+    if (1..=3).contains(&entry.stage) {
+      let path = unmerged.entry(entry.path.clone()).or_default();
+      let index = usize::from(entry.stage - 1);
+      path.stages[index] = Some(entry);
+    }
+  }
+
+  let mut index_info = String::new();
+  let mut conflicts = Vec::new();
+  for (path, stages) in &unmerged {
+    let (mode, object_id, clean) = resolve_unmerged_path(git_executor, repo_path, &temp_dir, stages)?;
+    // A mode 0 entry drops all stages of the path, the resolved content is added at stage 0
+    index_info.push_str(&format!("0 {NULL_OBJECT_ID}\t{path}\0{mode} {object_id}\t{path}\0"));
+    if !clean {
+      conflicts.extend(stages.stages.iter().flatten().cloned());
+    }
+  }
+  if !index_info.is_empty() {
+    git_executor.execute_command_with_env_and_stdin(&["update-index", "-z", "--index-info"], repo_path, &env, &index_info)?;
+  }
+
+  let tree_id = git_executor.execute_command_with_env(&["write-tree"], repo_path, &env)?;
+  debug!(conflict_count = unmerged.len(), "merged trees in a temporary index");
+  Ok(TreeMerge { tree_id, conflicts })
+}
+
+/// Content to keep for an unmerged path and whether it merged cleanly. Like `merge-tree`, conflicted text files get conflict
+/// markers, and the surviving side is kept when the other one deleted the path.
+fn resolve_unmerged_path(git_executor: &GitCommandExecutor, repo_path: &str, temp_dir: &TempDirGuard, path: &UnmergedPath) -> Result<(String, String, bool)> {
+  let (ours, theirs) = match (path.stage(2), path.stage(3)) {
+    (Some(ours), Some(theirs)) => (ours, theirs),
+    (Some(remaining), None) | (None, Some(remaining)) => return Ok((remaining.mode.clone(), remaining.object_id.clone(), false)),
+    (None, None) => return Err(anyhow!("Unmerged path without our and their version")),
+  };
+  let base = path.stage(1);
+
+  let is_regular_file = |entry: &ConflictEntry| entry.mode.starts_with("100");
+  // A mode change on one side wins, as with file content
+  let mode = match base {
+    Some(base) if base.mode == ours.mode => theirs.mode.clone(),
+    _ => ours.mode.clone(),
+  };
+  if !is_regular_file(ours) || !is_regular_file(theirs) {
+    // Symlinks and submodules can't be merged line by line
+    return Ok((ours.mode.clone(), ours.object_id.clone(), false));
+  }
+
+  let ours_path = write_blob(git_executor, repo_path, temp_dir, "ours", Some(&ours.object_id))?;
+  let base_path = write_blob(git_executor, repo_path, temp_dir, "base", base.map(|base| base.object_id.as_str()))?;
+  let theirs_path = write_blob(git_executor, repo_path, temp_dir, "theirs", Some(&theirs.object_id))?;
+
+  // Merges into the "ours" file, the exit code is the number of conflicts (negative on errors, e.g. for binary files)
+  let (output, exit_code) = git_executor.execute_command_with_status(
+    &["merge-file", "--diff3", "-L", "ours", "-L", "base", "-L", "theirs", &ours_path, &base_path, &theirs_path],
+    repo_path,
+  )?;
+  if !(0..128).contains(&exit_code) {
+    warn!(path = %ours.path, exit_code, output, "git merge-file failed, keeping our version");
+    return Ok((ours.mode.clone(), ours.object_id.clone(), false));
+  }
+
+  let object_id = git_executor.execute_command(&["hash-object", "-w", &ours_path], repo_path)?;
+  Ok((mode, object_id, exit_code == 0))
+}
+
+/// Write a blob to a file of the temporary directory, an empty file for a missing blob (path added on both sides)
+fn write_blob(git_executor: &GitCommandExecutor, repo_path: &str, temp_dir: &TempDirGuard, name: &str, object_id: Option<&str>) -> Result<String> {
+  let content = match object_id {
+    Some(object_id) => {
+      let (_, content) = git_executor
+        .cat_file_contents(repo_path, object_id)?
+        .ok_or_else(|| anyhow!("Blob {object_id} does not exist"))?;
+      content
+    }
+    None => Vec::new(),
+  };
+  let path = temp_dir.join(name);
+  fs::write(&path, content)?;
+  Ok(path)
+}
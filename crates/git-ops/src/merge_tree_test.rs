@@ -0,0 +1,70 @@
+use super::diff_options::DiffOptions;
+use super::merge_tree::{merge_trees, merge_trees_with_index};
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// Base commit with two files, "ours" and "theirs" branching off it
+fn setup(repo: &TestRepo, ours: &[(&str, &str)], theirs: &[(&str, &str)]) -> (String, String, String) {
+  let base = repo.create_commit_with_files("Base", &[("a.txt", "1\n2\n3\n4\n5\n"), ("b.txt", "b\n")]);
+  let ours = repo.create_commit_with_files("Ours", ours);
+  repo.reset_hard(&base).unwrap();
+  let theirs = repo.create_commit_with_files("Theirs", theirs);
+  (base, ours, theirs)
+}
+
+fn read_blob(repo: &TestRepo, tree_id: &str, path: &str) -> String {
+  let repo_path = repo.path().to_str().unwrap();
+  repo
+    .git_executor()
+    .execute_command_raw(&["cat-file", "-p", &format!("{tree_id}:{path}")], repo_path)
+    .unwrap()
+}
+
+#[test]
+fn test_index_merge_of_changes_in_different_hunks() {
+  let repo = TestRepo::new();
+  let (base, ours, theirs) = setup(&repo, &[("a.txt", "one\n2\n3\n4\n5\n")], &[("a.txt", "1\n2\n3\n4\nfive\n"), ("c.txt", "c\n")]);
+  let repo_path = repo.path().to_str().unwrap();
+
+  let merge = merge_trees_with_index(repo.git_executor(), repo_path, &base, &ours, &theirs).unwrap();
+  assert!(!merge.has_conflicts());
+  assert_eq!(read_blob(&repo, &merge.tree_id, "a.txt"), "one\n2\n3\n4\nfive\n");
+  assert_eq!(read_blob(&repo, &merge.tree_id, "c.txt"), "c\n");
+
+  // Same tree as merge-tree produces on gits supporting it
+  let merge_tree = merge_trees(repo.git_executor(), repo_path, &base, &ours, &theirs, &DiffOptions::default()).unwrap();
+  assert_eq!(merge_tree.tree_id, merge.tree_id);
+}
+
+#[test]
+fn test_index_merge_of_conflicting_changes() {
+  let repo = TestRepo::new();
+  let (base, ours, theirs) = setup(&repo, &[("a.txt", "1\n2\nours\n4\n5\n")], &[("a.txt", "1\n2\ntheirs\n4\n5\n"), ("b.txt", "b changed\n")]);
+  let repo_path = repo.path().to_str().unwrap();
+
+  let merge = merge_trees_with_index(repo.git_executor(), repo_path, &base, &ours, &theirs).unwrap();
+  let stages: Vec<(u8, &str)> = merge.conflicts.iter().map(|entry| (entry.stage, entry.path.as_str())).collect();
+  assert_eq!(stages, vec![(1, "a.txt"), (2, "a.txt"), (3, "a.txt")]);
+
+  // Conflict markers end up in the tree, non-conflicting changes are merged
+  let content = read_blob(&repo, &merge.tree_id, "a.txt");
+  assert!(content.contains("<<<<<<< ours\nours\n||||||| base\n3\n=======\ntheirs\n>>>>>>> theirs\n"), "{content}");
+  assert_eq!(read_blob(&repo, &merge.tree_id, "b.txt"), "b changed\n");
+}
+
+#[test]
+fn test_index_merge_keeps_modified_side_of_modify_delete_conflict() {
+  let repo = TestRepo::new();
+  let (base, ours, _) = setup(&repo, &[("b.txt", "b modified\n")], &[("c.txt", "c\n")]);
+  let repo_path = repo.path().to_str().unwrap();
+  repo.git_executor().execute_command(&["rm", "-q", "b.txt"], repo_path).unwrap();
+  repo.git_executor().execute_command(&["commit", "-q", "-m", "Delete b"], repo_path).unwrap();
+  let theirs = repo.head();
+
+  let merge = merge_trees_with_index(repo.git_executor(), repo_path, &base, &ours, &theirs).unwrap();
+  let stages: Vec<(u8, &str)> = merge.conflicts.iter().map(|entry| (entry.stage, entry.path.as_str())).collect();
+  assert_eq!(stages, vec![(1, "b.txt"), (2, "b.txt")]);
+  assert_eq!(read_blob(&repo, &merge.tree_id, "b.txt"), "b modified\n");
+  assert_eq!(read_blob(&repo, &merge.tree_id, "c.txt"), "c\n");
+}
@@ -22,6 +22,8 @@ pub enum BranchSyncStatus {
   Error,
   MergeConflict,
   AnalyzingConflict,
+  /// All commits are already in the baseline, so the branch was skipped or archived (see `branchdeck.emptyBranchPolicy`)
+  Integrated,
 }
 
 /// Status of a commit synchronization.
@@ -33,6 +35,14 @@ pub enum CommitSyncStatus {
   Unchanged,
   Error,
   Blocked,
+  /// Changes of the commit are already present in the new parent, so no (empty) commit was created
+  AlreadyApplied,
+  /// Changes of the commit are already present in the new parent, the commit was copied as an empty commit
+  /// (`branchdeck.emptyCommitPolicy` is `keepEmpty`)
+  KeptEmpty,
+  /// Changes of the commit are already present in the new parent, the commit is reported as integrated
+  /// (`branchdeck.emptyCommitPolicy` is `markIntegrated`)
+  Integrated,
 }
 
 /// Represents details of a conflict during a cherry-pick operation.
@@ -50,6 +60,12 @@ pub struct ConflictDetail {
   pub cherry_file: Option<crate::conflict_analysis::FileInfo>, // Cherry-pick version (theirs)
   pub base_to_target_diff: crate::conflict_analysis::FileDiff, // Base -> Target diff with hunks (for 3-way view)
   pub base_to_cherry_diff: crate::conflict_analysis::FileDiff, // Base -> Cherry diff with hunks (for 3-way view)
+  /// Binary content on any side: contents and diffs are left empty, the conflict is resolved by choosing a version
+  #[serde(default)]
+  pub is_binary: bool,
+  /// Class of the conflict chunks (formatting-only, imports-only or logic), `None` for binary files
+  #[serde(default)]
+  pub classification: Option<crate::conflict_classification::ConflictClassification>,
 }
 
 /// Details about a merge conflict encountered during a cherry-pick operation.
@@ -90,12 +106,37 @@ pub struct ConflictMarkerCommitInfo {
   pub committer_time: u32,
 }
 
+/// A commit can't be applied because the result would have paths that differ only in case,
+/// which a case-insensitive file system can't check out side by side (see [`crate::case_collision`])
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct CaseCollisionInfo {
+  pub commit_hash: String,
+  pub commit_message: String,
+  /// Groups of colliding paths, e.g. `["docs/README.md", "docs/Readme.md"]`
+  pub collisions: Vec<Vec<String>>,
+}
+
+impl CaseCollisionInfo {
+  pub fn explanation(&self) -> String {
+    let collisions: Vec<String> = self.collisions.iter().map(|paths| paths.join(" and ")).collect();
+    format!(
+      "Commit {} ({}) would create paths that differ only in case, which can't coexist on a case-insensitive file system: {}",
+      &self.commit_hash[..self.commit_hash.len().min(8)],
+      self.commit_message,
+      collisions.join(", ")
+    )
+  }
+}
+
 /// Branch operation errors.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(Type))]
 pub enum BranchError {
   Generic(String),
   MergeConflict(Box<MergeConflictInfo>),
+  CaseCollision(CaseCollisionInfo),
 }
 
 pub fn to_final_branch_name(branch_prefix: &str, branch_name: &str) -> anyhow::Result<String> {
@@ -142,6 +183,38 @@ pub fn extract_branch_name_from_unapplied(full_branch_name: &str, branch_prefix:
   full_branch_name.strip_prefix(&prefix).map(|s| s.to_string())
 }
 
+/// Namespaces under the branch prefix used by Branch Deck itself
+const RESERVED_NAMESPACES: [&str; 3] = ["virtual", "archived", "unapplied"];
+
+/// Create the name of a branch rebuilt on another ref from branch prefix, ref and simple name
+/// e.g., "user", "origin/release/1.2", "feature-x" -> "user/release-1.2/feature-x"
+pub fn to_rebased_branch_name(branch_prefix: &str, onto: &str, branch_name: &str) -> anyhow::Result<String> {
+  let prefix = branch_prefix.trim_end_matches('/').trim();
+  ensure!(!prefix.is_empty(), "branch prefix cannot be blank");
+
+  let name = branch_name.trim_end_matches('/').trim();
+  ensure!(!name.is_empty(), "branch name cannot be blank");
+  let sanitized_name = sanitize_branch_name(name);
+  ensure!(sanitized_name == name, "Branch name must be pre-sanitized. Got '{}', expected '{}'", name, sanitized_name);
+
+  // Only the branch part of the ref, without "refs/heads/", "refs/remotes/<remote>/" or "origin/"
+  let onto = onto.trim();
+  let onto = onto
+    .strip_prefix("refs/heads/")
+    .or_else(|| onto.strip_prefix("refs/remotes/").and_then(|rest| rest.split_once('/')).map(|(_, branch)| branch))
+    .or_else(|| onto.strip_prefix("origin/"))
+    .unwrap_or(onto);
+  let namespace = sanitize_branch_name(&onto.replace('/', "-"));
+  ensure!(!namespace.is_empty(), "ref name cannot be blank");
+  ensure!(
+    !RESERVED_NAMESPACES.contains(&namespace.as_str()),
+    "'{}' is reserved and can't be used as the name of the ref",
+    namespace
+  );
+
+  Ok(format!("{prefix}/{namespace}/{name}"))
+}
+
 /// Sanitizes a branch name to make it valid for Git references
 /// Git reference names cannot contain spaces, certain special characters, etc.
 pub fn sanitize_branch_name(name: &str) -> String {
@@ -169,6 +242,24 @@ pub fn sanitize_branch_name(name: &str) -> String {
     .to_string()
 }
 
+/// Validate a branch name used in the `(branch-name)` commit prefix
+pub fn validate_branch_name(branch_name: &str) -> Result<(), String> {
+  if branch_name.is_empty() {
+    return Err("Branch name cannot be empty".to_string());
+  }
+
+  // Check for invalid characters (allow dots for version numbers like "1.21.2")
+  if !branch_name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.') {
+    return Err("Branch name can only contain letters, numbers, hyphens, underscores, and dots".to_string());
+  }
+
+  // Git-specific rule: cannot start with a dot
+  if branch_name.starts_with('.') {
+    return Err("Branch name cannot start with a dot".to_string());
+  }
+  Ok(())
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
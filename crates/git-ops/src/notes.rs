@@ -1,9 +1,13 @@
 use git_executor::git_command_executor::GitCommandExecutor;
+use std::collections::HashSet;
 use std::sync::Mutex;
-use tracing::instrument;
+use tracing::{debug, instrument};
 
 pub const PREFIX: &str = "v-commit-v1:";
 
+/// Git config key that enables copying user notes (`refs/notes/commits`) to rewritten commits during sync
+pub const COPY_NOTES_CONFIG_KEY: &str = "branchdeck.copyNotes";
+
 /// Information needed to write a git note after successful branch sync
 #[derive(Debug, Clone)]
 pub struct CommitNoteInfo {
@@ -13,6 +17,16 @@ pub struct CommitNoteInfo {
   pub author_email: String,
   pub tree_id: String,
   pub subject: String,
+  /// User-written note of the original commit (without the mapping line)
+  pub user_note: Option<String>,
+}
+
+/// Extract the user-written part of a note, dropping Branch Deck's mapping line.
+/// Returns `None` if nothing else is left.
+pub fn user_note_content(note: &str) -> Option<String> {
+  let content = note.lines().filter(|line| !line.trim_start().starts_with(PREFIX)).collect::<Vec<_>>().join("\n");
+  let content = content.trim();
+  if content.is_empty() { None } else { Some(content.to_string()) }
 }
 
 /// Write git notes for all commits in a batch using git CLI for better performance
@@ -27,7 +41,11 @@ pub fn write_commit_notes(git_executor: &GitCommandExecutor, repo_path: &str, no
 
   // Write each note using git notes add
   for note_info in notes {
-    let note_content = format!("{}{}", PREFIX, note_info.new_oid);
+    // Keep the user's note on the original commit, the mapping line goes first
+    let note_content = match &note_info.user_note {
+      Some(user_note) => format!("{}{}\n\n{}", PREFIX, note_info.new_oid, user_note),
+      None => format!("{}{}", PREFIX, note_info.new_oid),
+    };
 
     // Use git notes add to properly create/update the note
     // -f flag forces overwrite if note already exists
@@ -38,3 +56,64 @@ pub fn write_commit_notes(git_executor: &GitCommandExecutor, repo_path: &str, no
 
   Ok(())
 }
+
+/// Copy user notes of original commits to their rewritten counterparts.
+/// Returns the number of copied notes.
+#[instrument(skip(git_executor, notes, git_notes_mutex), fields(notes_count = notes.len()))]
+pub fn copy_user_notes(git_executor: &GitCommandExecutor, repo_path: &str, notes: &[CommitNoteInfo], git_notes_mutex: &Mutex<()>) -> Result<usize, anyhow::Error> {
+  let _lock = git_notes_mutex.lock().unwrap();
+
+  let mut copied = 0;
+  for note_info in notes {
+    if let Some(user_note) = &note_info.user_note
+      && note_info.new_oid != note_info.original_oid
+    {
+      git_executor.execute_command(&["notes", "add", "-f", "-m", user_note, &note_info.new_oid], repo_path)?;
+      copied += 1;
+    }
+  }
+  Ok(copied)
+}
+
+/// Collect commits that are reachable only from the given branches and have a note attached.
+/// Call before deleting the branches, then pass the result to [`remove_commit_notes`].
+#[instrument(skip(git_executor))]
+pub fn collect_branch_notes(git_executor: &GitCommandExecutor, repo_path: &str, branch_names: &[String]) -> Result<Vec<String>, anyhow::Error> {
+  if branch_names.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  // Output format: "<note blob> <annotated commit>"
+  let annotated: HashSet<String> = git_executor
+    .execute_command_lines(&["notes", "list"], repo_path)?
+    .into_iter()
+    .filter_map(|line| line.split_whitespace().nth(1).map(|oid| oid.to_string()))
+    .collect();
+  if annotated.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  let refs: Vec<String> = branch_names.iter().map(|name| format!("refs/heads/{name}")).collect();
+  let excludes: Vec<String> = refs.iter().map(|r| format!("--exclude={r}")).collect();
+  let mut args: Vec<&str> = vec!["rev-list"];
+  args.extend(refs.iter().map(String::as_str));
+  args.push("--not");
+  args.extend(excludes.iter().map(String::as_str));
+  args.push("--all");
+
+  let commits = git_executor.execute_command_lines(&args, repo_path)?;
+  Ok(commits.into_iter().filter(|commit| annotated.contains(commit)).collect())
+}
+
+/// Remove notes from the given commits (no-op for an empty list)
+#[instrument(skip(git_executor, commit_ids), fields(commit_count = commit_ids.len()))]
+pub fn remove_commit_notes(git_executor: &GitCommandExecutor, repo_path: &str, commit_ids: &[String]) -> Result<(), anyhow::Error> {
+  if commit_ids.is_empty() {
+    return Ok(());
+  }
+
+  let input = commit_ids.join("\n") + "\n";
+  git_executor.execute_command_with_input(&["notes", "remove", "--ignore-missing", "--stdin"], repo_path, &input)?;
+  debug!(removed = commit_ids.len(), "removed notes of deleted branch commits");
+  Ok(())
+}
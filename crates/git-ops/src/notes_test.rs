@@ -1,4 +1,5 @@
-use crate::notes::{CommitNoteInfo, PREFIX, write_commit_notes};
+use crate::commit_list::get_commit_list;
+use crate::notes::{CommitNoteInfo, PREFIX, collect_branch_notes, copy_user_notes, remove_commit_notes, user_note_content, write_commit_notes};
 use git_executor::git_command_executor::GitCommandExecutor;
 use pretty_assertions::assert_eq;
 use std::sync::Mutex;
@@ -30,6 +31,7 @@ fn test_write_single_commit_note() {
     author_email: "test@example.com".to_string(),
     tree_id: String::new(),
     subject: "Test commit".to_string(),
+    user_note: None,
   }];
 
   let result = write_commit_notes(&git_executor, test_repo.path().to_str().unwrap(), notes, &mutex);
@@ -65,6 +67,7 @@ fn test_write_multiple_commit_notes() {
       author_email: format!("author{i}@example.com"),
       tree_id: String::new(),
       subject: format!("Commit {i}"),
+      user_note: None,
     });
   }
 
@@ -104,6 +107,7 @@ fn test_write_commit_notes_with_special_characters() {
     author_email: "special.chars+test@example.com".to_string(),
     tree_id: String::new(),
     subject: "Special commit".to_string(),
+    user_note: None,
   }];
 
   let result = write_commit_notes(&git_executor, test_repo.path().to_str().unwrap(), notes, &mutex);
@@ -141,6 +145,7 @@ fn test_concurrent_note_writing() {
       author_email: format!("batch{i}@example.com"),
       tree_id: String::new(),
       subject: format!("Batch commit {i}"),
+      user_note: None,
     });
 
     expected_mappings.push((original, new));
@@ -176,6 +181,7 @@ fn test_write_commit_notes_overwrites_existing() {
     author_email: "test@example.com".to_string(),
     tree_id: String::new(),
     subject: "Initial note".to_string(),
+    user_note: None,
   }];
 
   write_commit_notes(&git_executor, test_repo.path().to_str().unwrap(), notes, &mutex).unwrap();
@@ -192,6 +198,7 @@ fn test_write_commit_notes_overwrites_existing() {
     author_email: "test@example.com".to_string(),
     tree_id: String::new(),
     subject: "Overwritten note".to_string(),
+    user_note: None,
   }];
 
   write_commit_notes(&git_executor, test_repo.path().to_str().unwrap(), notes, &mutex).unwrap();
@@ -224,6 +231,7 @@ fn test_write_notes_batch_mode_edge_cases() {
       author_email: "test@example.com".to_string(),
       tree_id: String::new(),
       subject: format!("Edge case {i}"),
+      user_note: None,
     });
   }
 
@@ -237,3 +245,91 @@ fn test_write_notes_batch_mode_edge_cases() {
     assert_eq!(note_content.trim(), format!("{PREFIX}{new}"));
   }
 }
+
+fn note_info(original_oid: &str, new_oid: &str, user_note: Option<&str>) -> CommitNoteInfo {
+  CommitNoteInfo {
+    original_oid: original_oid.to_string(),
+    new_oid: new_oid.to_string(),
+    author: "Author".to_string(),
+    author_email: "test@example.com".to_string(),
+    tree_id: String::new(),
+    subject: "Subject".to_string(),
+    user_note: user_note.map(|note| note.to_string()),
+  }
+}
+
+#[test]
+fn test_user_note_content() {
+  assert_eq!(user_note_content(&format!("{PREFIX}abc")), None);
+  assert_eq!(user_note_content(&format!("{PREFIX}abc\n\nReviewed-by: Alice")), Some("Reviewed-by: Alice".to_string()));
+  assert_eq!(user_note_content("Just a note"), Some("Just a note".to_string()));
+}
+
+#[test]
+fn test_write_commit_notes_preserves_user_note() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let mutex = Mutex::new(());
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_branch("feature").unwrap();
+  test_repo.checkout("feature").unwrap();
+  let original = test_repo.create_commit("(feature) Change", "test.txt", "v1");
+  let rewritten = test_repo.create_commit("Rewritten", "test.txt", "v2");
+
+  write_commit_notes(&git_executor, repo_path, vec![note_info(&original, &rewritten, Some("Reviewed-by: Alice"))], &mutex).unwrap();
+  assert_eq!(test_repo.show_note(&original).unwrap().trim(), format!("{PREFIX}{rewritten}\n\nReviewed-by: Alice"));
+
+  // Mapping is still recognized when a user note follows it
+  let commits = get_commit_list(&git_executor, repo_path, "master").unwrap();
+  let commit = commits.iter().find(|c| c.id == original).unwrap();
+  assert_eq!(commit.mapped_commit_id.as_deref(), Some(rewritten.as_str()));
+  assert_eq!(commit.note.as_deref().and_then(user_note_content), Some("Reviewed-by: Alice".to_string()));
+}
+
+#[test]
+fn test_copy_user_notes() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let mutex = Mutex::new(());
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let original1 = test_repo.create_commit("First", "a.txt", "a");
+  let original2 = test_repo.create_commit("Second", "b.txt", "b");
+  let new1 = test_repo.create_commit("First rewritten", "a.txt", "a2");
+  let new2 = test_repo.create_commit("Second rewritten", "b.txt", "b2");
+
+  let notes = vec![note_info(&original1, &new1, Some("Tested on CI")), note_info(&original2, &new2, None)];
+  let copied = copy_user_notes(&git_executor, repo_path, &notes, &mutex).unwrap();
+
+  assert_eq!(copied, 1);
+  assert_eq!(test_repo.show_note(&new1).unwrap().trim(), "Tested on CI");
+  assert!(test_repo.show_note(&new2).is_err());
+}
+
+#[test]
+fn test_remove_notes_of_deleted_branch() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let shared = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.add_note(&shared, "Shared note").unwrap();
+  test_repo.create_branch("user/archived/2025-01-01/feature").unwrap();
+  test_repo.checkout("user/archived/2025-01-01/feature").unwrap();
+  let branch_commit = test_repo.create_commit("Feature", "feature.txt", "feature");
+  test_repo.add_note(&branch_commit, "Copied note").unwrap();
+  test_repo.checkout("master").unwrap();
+
+  let branch_names = vec!["user/archived/2025-01-01/feature".to_string()];
+  let noted = collect_branch_notes(&git_executor, repo_path, &branch_names).unwrap();
+  // Commit reachable from master keeps its note
+  assert_eq!(noted, vec![branch_commit.clone()]);
+
+  git_executor.execute_command(&["branch", "-D", &branch_names[0]], repo_path).unwrap();
+  remove_commit_notes(&git_executor, repo_path, &noted).unwrap();
+
+  assert!(test_repo.show_note(&branch_commit).is_err());
+  assert_eq!(test_repo.show_note(&shared).unwrap().trim(), "Shared note");
+}
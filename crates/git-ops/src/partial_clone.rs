@@ -0,0 +1,181 @@
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use std::collections::HashSet;
+use tracing::{debug, info, instrument};
+
+/// Git config key that enables batch prefetching of missing objects for a whole sync run
+pub const PREFETCH_CONFIG_KEY: &str = "branchdeck.prefetchMissingObjects";
+
+/// Information about a partial clone (repository with promisor remotes)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialCloneInfo {
+  /// Remotes marked with `remote.<name>.promisor=true`, in config order
+  pub promisor_remotes: Vec<String>,
+  /// Whether `core.sparseCheckout` is enabled for the working tree
+  pub sparse_checkout: bool,
+}
+
+impl PartialCloneInfo {
+  /// Remote used to fetch missing objects (the first promisor remote)
+  pub fn fetch_remote(&self) -> &str {
+    self.promisor_remotes.first().map(String::as_str).unwrap_or("origin")
+  }
+}
+
+/// Detect whether the repository is a partial clone.
+/// Returns `None` for regular (full) clones.
+#[instrument(skip(git_executor))]
+pub fn detect_partial_clone(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<Option<PartialCloneInfo>> {
+  // Output format: "remote.origin.promisor true"
+  let (output, exit_code) = git_executor.execute_command_with_status(&["config", "--type=bool", "--get-regexp", r"^remote\..*\.promisor$"], repo_path)?;
+  if exit_code != 0 {
+    return Ok(None);
+  }
+
+  let promisor_remotes: Vec<String> = output
+    .lines()
+    .filter_map(|line| {
+      let (key, value) = line.trim().split_once(' ')?;
+      if value != "true" {
+        return None;
+      }
+      key.strip_prefix("remote.")?.strip_suffix(".promisor").map(|name| name.to_string())
+    })
+    .collect();
+
+  if promisor_remotes.is_empty() {
+    return Ok(None);
+  }
+
+  let (sparse_output, sparse_exit_code) = git_executor.execute_command_with_status(&["config", "--type=bool", "--get", "core.sparseCheckout"], repo_path)?;
+  let sparse_checkout = sparse_exit_code == 0 && sparse_output.trim() == "true";
+
+  debug!(remotes = ?promisor_remotes, sparse_checkout, "detected partial clone");
+  Ok(Some(PartialCloneInfo {
+    promisor_remotes,
+    sparse_checkout,
+  }))
+}
+
+/// Return the subset of `object_ids` that are not present in the local object database.
+/// Lazy fetching is disabled for the check, so this never hits the network.
+#[instrument(skip(git_executor, object_ids), fields(object_count = object_ids.len()))]
+pub fn find_missing_objects(git_executor: &GitCommandExecutor, repo_path: &str, object_ids: &[String]) -> Result<Vec<String>> {
+  if object_ids.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  let input = object_ids.join("\n") + "\n";
+  let output = git_executor.execute_command_with_env_and_stdin(&["cat-file", "--batch-check"], repo_path, &[("GIT_NO_LAZY_FETCH", "1")], &input)?;
+
+  // Missing objects are reported as "<oid> missing"
+  Ok(output.lines().filter_map(|line| line.trim().strip_suffix(" missing").map(|oid| oid.to_string())).collect())
+}
+
+/// Fetch the given objects from the promisor remote in a single request.
+#[instrument(skip(git_executor, info, object_ids), fields(remote = %info.fetch_remote(), object_count = object_ids.len()))]
+pub fn fetch_objects(git_executor: &GitCommandExecutor, repo_path: &str, info: &PartialCloneInfo, object_ids: &[String]) -> Result<()> {
+  if object_ids.is_empty() {
+    return Ok(());
+  }
+
+  // Same invocation git itself uses for lazy fetches, but batched
+  let input = object_ids.join("\n") + "\n";
+  git_executor.execute_command_with_input(
+    &[
+      "-c",
+      "fetch.negotiationAlgorithm=noop",
+      "fetch",
+      info.fetch_remote(),
+      "--no-tags",
+      "--no-write-fetch-head",
+      "--recurse-submodules=no",
+      "--filter=blob:none",
+      "--stdin",
+    ],
+    repo_path,
+    &input,
+  )?;
+  Ok(())
+}
+
+/// Collect blob IDs at the given paths for each revision (`git ls-tree` works without blobs in a partial clone).
+fn collect_blob_ids(git_executor: &GitCommandExecutor, repo_path: &str, revs: &[&str], paths: &[String], object_ids: &mut HashSet<String>) -> Result<()> {
+  if paths.is_empty() {
+    return Ok(());
+  }
+
+  for rev in revs {
+    let mut args = vec!["ls-tree", "-r", "--object-only", rev, "--"];
+    args.extend(paths.iter().map(String::as_str));
+    for oid in git_executor.execute_command_lines(&args, repo_path)? {
+      object_ids.insert(oid);
+    }
+  }
+  Ok(())
+}
+
+/// Make sure all blobs `git merge-tree` needs for cherry-picking `cherry_commit_id` onto `target_commit_id` are available locally.
+/// Only paths touched by the cherry-picked commit are considered: unchanged paths are resolved by tree ID alone.
+/// Returns the number of fetched objects.
+#[instrument(skip(git_executor, info))]
+pub fn ensure_cherry_pick_objects(
+  git_executor: &GitCommandExecutor,
+  repo_path: &str,
+  info: &PartialCloneInfo,
+  base_commit_id: &str,
+  target_commit_id: &str,
+  cherry_commit_id: &str,
+) -> Result<usize> {
+  let paths = git_executor.execute_command_lines(
+    &[
+      "--no-pager",
+      "diff-tree",
+      "-r",
+      "--no-commit-id",
+      "--name-only",
+      "--no-renames",
+      base_commit_id,
+      cherry_commit_id,
+    ],
+    repo_path,
+  )?;
+
+  let mut object_ids = HashSet::new();
+  collect_blob_ids(git_executor, repo_path, &[base_commit_id, target_commit_id, cherry_commit_id], &paths, &mut object_ids)?;
+
+  let object_ids: Vec<String> = object_ids.into_iter().collect();
+  let missing = find_missing_objects(git_executor, repo_path, &object_ids)?;
+  if !missing.is_empty() {
+    debug!(missing_count = missing.len(), "fetching missing objects before merge-tree");
+    fetch_objects(git_executor, repo_path, info, &missing)?;
+  }
+  Ok(missing.len())
+}
+
+/// Prefetch, in one batch, all objects needed to sync the commits in `<parent_commit_id>..<head>`.
+/// Covers blobs introduced by those commits and the baseline versions of every touched path.
+/// Returns the number of fetched objects.
+#[instrument(skip(git_executor, info))]
+pub fn prefetch_objects_for_range(git_executor: &GitCommandExecutor, repo_path: &str, info: &PartialCloneInfo, parent_commit_id: &str, head: &str) -> Result<usize> {
+  let range = format!("{parent_commit_id}..{head}");
+
+  // Objects introduced by the commits themselves; missing ones are prefixed with '?'
+  let mut missing: HashSet<String> = git_executor
+    .execute_command_lines(&["rev-list", "--objects", "--no-object-names", "--missing=print", &range], repo_path)?
+    .into_iter()
+    .filter_map(|line| line.strip_prefix('?').map(|oid| oid.to_string()))
+    .collect();
+
+  // Baseline versions of touched paths are needed as merge bases
+  let paths = git_executor.execute_command_lines(&["--no-pager", "diff", "--name-only", "--no-renames", parent_commit_id, head], repo_path)?;
+  let mut base_object_ids = HashSet::new();
+  collect_blob_ids(git_executor, repo_path, &[parent_commit_id], &paths, &mut base_object_ids)?;
+  let base_object_ids: Vec<String> = base_object_ids.into_iter().collect();
+  missing.extend(find_missing_objects(git_executor, repo_path, &base_object_ids)?);
+
+  let missing: Vec<String> = missing.into_iter().collect();
+  fetch_objects(git_executor, repo_path, info, &missing)?;
+  info!(fetched_count = missing.len(), range = %range, "prefetched missing objects for sync");
+  Ok(missing.len())
+}
@@ -0,0 +1,87 @@
+use crate::partial_clone::{detect_partial_clone, ensure_cherry_pick_objects, find_missing_objects, prefetch_objects_for_range};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// Create a blobless clone of `source` (no blobs fetched except for the checked out HEAD)
+fn create_blobless_clone(source: &TestRepo) -> TestRepo {
+  source.set_config("uploadpack.allowFilter", "true").unwrap();
+  source.set_config("uploadpack.allowAnySHA1InWant", "true").unwrap();
+
+  let clone = TestRepo::new_empty();
+  let source_url = format!("file://{}", source.path().to_str().unwrap());
+  clone
+    .git_executor()
+    .execute_command(&["clone", "--filter=blob:none", "--no-checkout", &source_url, "."], clone.path().to_str().unwrap())
+    .unwrap();
+  clone
+}
+
+#[test]
+fn test_detect_partial_clone_regular_repo() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "file.txt", "content");
+
+  let info = detect_partial_clone(&GitCommandExecutor::new(), test_repo.path().to_str().unwrap()).unwrap();
+  assert_eq!(info, None);
+}
+
+#[test]
+fn test_detect_partial_clone_blobless_clone() {
+  let source = TestRepo::new();
+  source.create_commit("Initial commit", "file.txt", "content");
+
+  let clone = create_blobless_clone(&source);
+  let info = detect_partial_clone(&GitCommandExecutor::new(), clone.path().to_str().unwrap())
+    .unwrap()
+    .expect("partial clone expected");
+  assert_eq!(info.promisor_remotes, vec!["origin".to_string()]);
+  assert_eq!(info.fetch_remote(), "origin");
+  assert!(!info.sparse_checkout);
+}
+
+#[test]
+fn test_ensure_cherry_pick_objects_fetches_missing_blobs() {
+  let source = TestRepo::new();
+  let base = source.create_commit("Initial commit", "file.txt", "line 1\n");
+  let default_branch = source.current_branch().unwrap();
+  source.checkout_new_branch("feature").unwrap();
+  let cherry = source.create_commit("Cherry change", "file.txt", "line 1\nline 2\n");
+  source.checkout(&default_branch).unwrap();
+  let target = source.create_commit("Target change", "other.txt", "other\n");
+
+  let clone = create_blobless_clone(&source);
+  let repo_path = clone.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+
+  let info = detect_partial_clone(&git_executor, repo_path).unwrap().unwrap();
+  let cherry_blob = git_executor.execute_command(&["rev-parse", &format!("{cherry}:file.txt")], repo_path).unwrap();
+  assert_eq!(
+    find_missing_objects(&git_executor, repo_path, std::slice::from_ref(&cherry_blob)).unwrap(),
+    vec![cherry_blob.clone()]
+  );
+
+  let fetched = ensure_cherry_pick_objects(&git_executor, repo_path, &info, &base, &target, &cherry).unwrap();
+  assert!(fetched > 0);
+  assert!(find_missing_objects(&git_executor, repo_path, &[cherry_blob]).unwrap().is_empty());
+}
+
+#[test]
+fn test_prefetch_objects_for_range() {
+  let source = TestRepo::new();
+  let base = source.create_commit("Initial commit", "file.txt", "base\n");
+  source.create_commit("(feature) First", "a.txt", "a\n");
+  let head = source.create_commit("(feature) Second", "file.txt", "changed\n");
+
+  let clone = create_blobless_clone(&source);
+  let repo_path = clone.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+  let info = detect_partial_clone(&git_executor, repo_path).unwrap().unwrap();
+
+  let fetched = prefetch_objects_for_range(&git_executor, repo_path, &info, &base, &head).unwrap();
+  assert!(fetched > 0);
+
+  // Second run has nothing left to fetch
+  assert_eq!(prefetch_objects_for_range(&git_executor, repo_path, &info, &base, &head).unwrap(), 0);
+}
@@ -0,0 +1,149 @@
+use crate::model::to_final_branch_name;
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{info, instrument};
+
+/// Placeholders `git format-patch --cover-letter` leaves for the author to fill in
+const COVER_SUBJECT_PLACEHOLDER: &str = "*** SUBJECT HERE ***";
+const COVER_BLURB_PLACEHOLDER: &str = "*** BLURB HERE ***";
+
+/// Output layout of an exported patch series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum PatchExportFormat {
+  /// One `NNNN-subject.patch` file per commit in the output directory
+  Series,
+  /// All patches in a single mbox file
+  Mbox,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ExportBranchPatchesParams {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  /// Virtual branch name (without prefix)
+  pub branch_name: String,
+  pub format: PatchExportFormat,
+  /// Output directory for a series, output file for an mbox
+  pub output_path: String,
+  /// Add a cover letter (patch 0) describing the series
+  pub cover_letter: bool,
+  /// Branch summary used as the cover letter subject (falls back to the branch name)
+  pub summary: Option<String>,
+  /// Subject prefix, `PATCH` by default (e.g. `PATCH v2` or `RFC PATCH`)
+  pub subject_prefix: Option<String>,
+  /// Cover letter text (falls back to a generated description of the series)
+  pub cover_letter_body: Option<String>,
+  /// Add `Message-ID` headers and make the patches replies to the first message (`--thread=shallow`)
+  pub thread: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ExportBranchPatchesResult {
+  /// Written files: the patch files of a series (cover letter first) or the single mbox file
+  pub files: Vec<String>,
+  /// Number of exported commits (not counting the cover letter)
+  pub patch_count: u32,
+}
+
+/// Default cover letter text describing the series
+fn default_cover_letter_body(branch_name: &str, patch_count: usize) -> String {
+  format!(
+    "This series contains {patch_count} patch{} from branch \"{branch_name}\".",
+    if patch_count == 1 { "" } else { "es" }
+  )
+}
+
+/// Fill in the cover letter template generated by git
+fn fill_cover_letter(content: &str, subject: &str, body: &str) -> String {
+  content.replacen(COVER_SUBJECT_PLACEHOLDER, subject, 1).replacen(COVER_BLURB_PLACEHOLDER, body, 1)
+}
+
+/// Export the commits of a virtual branch that are not in the baseline as `git format-patch` output,
+/// so the branch can be sent to mailing-list-based projects without pushing.
+#[instrument(skip(git_executor))]
+pub fn export_branch_patches(git_executor: &GitCommandExecutor, params: ExportBranchPatchesParams, baseline_branch: &str) -> Result<ExportBranchPatchesResult> {
+  let ExportBranchPatchesParams {
+    repository_path,
+    branch_prefix,
+    branch_name,
+    format,
+    output_path,
+    cover_letter,
+    summary,
+    subject_prefix,
+    cover_letter_body,
+    thread,
+  } = params;
+
+  let full_branch_name = to_final_branch_name(&branch_prefix, &branch_name)?;
+  if output_path.trim().is_empty() {
+    return Err(anyhow!("Output path is not specified"));
+  }
+  // Relative paths are resolved against the repository, like git does
+  let output_path = Path::new(&repository_path).join(&output_path).to_string_lossy().into_owned();
+
+  let range = format!("{baseline_branch}..refs/heads/{full_branch_name}");
+  let patch_count: usize = git_executor
+    .execute_command(&["rev-list", "--count", "--no-merges", &range], &repository_path)?
+    .trim()
+    .parse()?;
+  if patch_count == 0 {
+    return Err(anyhow!("Branch \"{branch_name}\" has no commits to export"));
+  }
+
+  let subject_prefix = format!("--subject-prefix={}", subject_prefix.as_deref().map(str::trim).filter(|p| !p.is_empty()).unwrap_or("PATCH"));
+  let mut args = vec!["format-patch", "--no-merges", subject_prefix.as_str()];
+  if cover_letter {
+    args.push("--cover-letter");
+  }
+  if thread {
+    args.push("--thread=shallow");
+  }
+
+  let cover_subject = summary.as_deref().map(str::trim).filter(|s| !s.is_empty()).unwrap_or(&branch_name).to_string();
+  let cover_body = cover_letter_body
+    .filter(|body| !body.trim().is_empty())
+    .unwrap_or_else(|| default_cover_letter_body(&branch_name, patch_count));
+  let files = match format {
+    PatchExportFormat::Series => {
+      std::fs::create_dir_all(&output_path)?;
+      args.extend(["-o", output_path.as_str(), range.as_str()]);
+      // format-patch prints the written files, cover letter first
+      let files = git_executor.execute_command_lines(&args, &repository_path)?;
+      if cover_letter && let Some(cover_file) = files.first() {
+        let content = std::fs::read_to_string(cover_file)?;
+        std::fs::write(cover_file, fill_cover_letter(&content, &cover_subject, &cover_body))?;
+      }
+      files
+    }
+    PatchExportFormat::Mbox => {
+      args.extend(["--stdout", range.as_str()]);
+      let mut content = git_executor.execute_command(&args, &repository_path)?;
+      if cover_letter {
+        content = fill_cover_letter(&content, &cover_subject, &cover_body);
+      }
+      if let Some(parent) = Path::new(&output_path).parent()
+        && !parent.as_os_str().is_empty()
+      {
+        std::fs::create_dir_all(parent)?;
+      }
+      // The executor trims the output, but an mbox must end with a newline
+      std::fs::write(&output_path, content + "\n")?;
+      vec![output_path]
+    }
+  };
+
+  info!(branch = %full_branch_name, patch_count, ?format, "Exported branch patches");
+  Ok(ExportBranchPatchesResult {
+    files,
+    patch_count: patch_count as u32,
+  })
+}
@@ -0,0 +1,94 @@
+use crate::patch_export::{ExportBranchPatchesParams, PatchExportFormat, export_branch_patches};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// Repository with a two-commit virtual branch `user/virtual/parser`; returns the baseline commit
+fn setup_branch(test_repo: &TestRepo) -> String {
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.create_commit("Add parser", "parser.rs", "fn parse() {}\n");
+  test_repo.create_commit("Test parser", "parser_test.rs", "fn test_parse() {}\n");
+  let head = test_repo.head();
+  test_repo.create_branch_at("user/virtual/parser", &head).unwrap();
+  baseline
+}
+
+fn params(test_repo: &TestRepo, format: PatchExportFormat, output_path: &str) -> ExportBranchPatchesParams {
+  ExportBranchPatchesParams {
+    repository_path: test_repo.path().to_str().unwrap().to_string(),
+    branch_prefix: "user".to_string(),
+    branch_name: "parser".to_string(),
+    format,
+    output_path: output_path.to_string(),
+    cover_letter: true,
+    summary: Some("Add a parser".to_string()),
+    subject_prefix: None,
+    cover_letter_body: None,
+    thread: false,
+  }
+}
+
+#[test]
+fn test_export_branch_patches_series_with_cover_letter() {
+  let test_repo = TestRepo::new();
+  let baseline = setup_branch(&test_repo);
+  let output_dir = tempfile::tempdir().unwrap();
+
+  let result = export_branch_patches(
+    &GitCommandExecutor::new(),
+    params(&test_repo, PatchExportFormat::Series, output_dir.path().to_str().unwrap()),
+    &baseline,
+  )
+  .unwrap();
+
+  assert_eq!(result.patch_count, 2);
+  let file_names: Vec<String> = result
+    .files
+    .iter()
+    .map(|file| std::path::Path::new(file).file_name().unwrap().to_string_lossy().into_owned())
+    .collect();
+  assert_eq!(file_names, vec!["0000-cover-letter.patch", "0001-Add-parser.patch", "0002-Test-parser.patch"]);
+
+  let cover_letter = std::fs::read_to_string(&result.files[0]).unwrap();
+  assert!(cover_letter.contains("Subject: [PATCH 0/2] Add a parser"), "cover letter: {cover_letter}");
+  assert!(
+    cover_letter.contains("This series contains 2 patches from branch \"parser\"."),
+    "cover letter: {cover_letter}"
+  );
+  assert!(!cover_letter.contains("*** "), "placeholders must be replaced: {cover_letter}");
+}
+
+#[test]
+fn test_export_branch_patches_mbox() {
+  let test_repo = TestRepo::new();
+  let baseline = setup_branch(&test_repo);
+  let output_dir = tempfile::tempdir().unwrap();
+  let mbox_path = output_dir.path().join("out/parser.mbox");
+
+  let mut export_params = params(&test_repo, PatchExportFormat::Mbox, mbox_path.to_str().unwrap());
+  export_params.cover_letter = false;
+  export_params.subject_prefix = Some("RFC PATCH".to_string());
+  let result = export_branch_patches(&GitCommandExecutor::new(), export_params, &baseline).unwrap();
+
+  assert_eq!(result.files, vec![mbox_path.to_str().unwrap().to_string()]);
+  let mbox = std::fs::read_to_string(&mbox_path).unwrap();
+  let subjects: Vec<&str> = mbox.lines().filter(|line| line.starts_with("Subject: ")).collect();
+  assert_eq!(subjects, vec!["Subject: [RFC PATCH 1/2] Add parser", "Subject: [RFC PATCH 2/2] Test parser"]);
+  assert!(mbox.ends_with('\n'));
+}
+
+#[test]
+fn test_export_branch_patches_without_commits() {
+  let test_repo = TestRepo::new();
+  setup_branch(&test_repo);
+  let output_dir = tempfile::tempdir().unwrap();
+
+  let error = export_branch_patches(
+    &GitCommandExecutor::new(),
+    params(&test_repo, PatchExportFormat::Series, output_dir.path().to_str().unwrap()),
+    "user/virtual/parser",
+  )
+  .unwrap_err();
+  assert_eq!(error.to_string(), "Branch \"parser\" has no commits to export");
+}
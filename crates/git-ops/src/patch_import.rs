@@ -0,0 +1,167 @@
+use crate::commit_utils::{commit_tree, resolve_commit_signing};
+use crate::model::CommitInfo;
+use crate::temp_dir::TempDirGuard;
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, instrument};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyPatchSeriesParams {
+  pub repository_path: String,
+  /// `.patch` files (applied in the given order) or mbox files with several patches
+  pub patch_files: Vec<String>,
+  /// Virtual branch name added to each subject as `(name)` so the commits group into that branch
+  pub branch_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyPatchSeriesResult {
+  /// Created commits, oldest first
+  pub commits: Vec<CommitInfo>,
+}
+
+/// Metadata extracted from a mail by `git mailinfo`
+#[derive(Debug, Default)]
+struct MailInfo {
+  author: String,
+  email: String,
+  date: String,
+  subject: String,
+}
+
+fn parse_mail_info(output: &str) -> MailInfo {
+  let mut info = MailInfo::default();
+  for line in output.lines() {
+    let Some((key, value)) = line.split_once(": ") else {
+      continue;
+    };
+    let value = value.trim().to_string();
+    match key {
+      "Author" => info.author = value,
+      "Email" => info.email = value,
+      "Date" => info.date = value,
+      "Subject" => info.subject = value,
+      _ => {}
+    }
+  }
+  info
+}
+
+/// Add the `(branch_name)` prefix to a subject unless it is already there
+fn prefixed_subject(subject: &str, branch_name: &str) -> String {
+  let prefix = format!("({branch_name})");
+  if subject.starts_with(&prefix) {
+    subject.to_string()
+  } else {
+    format!("{prefix} {subject}")
+  }
+}
+
+/// Apply a patch series on top of the current branch, like `git am`, prefixing every subject with `(branch_name)`.
+/// Commits are created with plumbing against a temporary index, so the branch is only moved when all patches apply.
+/// Cover letters (mails without a diff) are skipped.
+#[instrument(skip(git_executor))]
+pub fn apply_patch_series(git_executor: &GitCommandExecutor, params: ApplyPatchSeriesParams) -> Result<ApplyPatchSeriesResult> {
+  let ApplyPatchSeriesParams {
+    repository_path,
+    patch_files,
+    branch_name,
+  } = params;
+
+  let branch_name = branch_name.trim();
+  if branch_name.is_empty() || branch_name.contains(['(', ')']) {
+    return Err(anyhow!("Invalid branch name: \"{branch_name}\""));
+  }
+  if patch_files.is_empty() {
+    return Err(anyhow!("No patch files specified"));
+  }
+
+  // Relative paths are resolved against the repository, like git does
+  let patch_files: Vec<String> = patch_files
+    .iter()
+    .map(|file| Path::new(&repository_path).join(file).to_string_lossy().into_owned())
+    .collect();
+  for file in &patch_files {
+    if !Path::new(file).is_file() {
+      return Err(anyhow!("Patch file {file} does not exist"));
+    }
+  }
+
+  // Detached HEAD has no branch to apply to
+  git_executor
+    .execute_command(&["symbolic-ref", "--quiet", "HEAD"], &repository_path)
+    .map_err(|_| anyhow!("Cannot apply patches in detached HEAD state"))?;
+  let head = git_executor.execute_command(&["rev-parse", "HEAD"], &repository_path)?;
+
+  // Split all inputs into single mails (numbered in input order); -b treats a file without a "From " line as one mail
+  let temp_dir = TempDirGuard::new(&repository_path, "patches")?;
+  let mails_dir = temp_dir.join("mails");
+  fs::create_dir_all(&mails_dir)?;
+  let output_arg = format!("-o{mails_dir}");
+  let mut split_args = vec!["mailsplit", "-b", output_arg.as_str()];
+  split_args.extend(patch_files.iter().map(String::as_str));
+  git_executor.execute_command(&split_args, &repository_path)?;
+
+  let mut mails: Vec<PathBuf> = fs::read_dir(&mails_dir)?.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect();
+  mails.sort();
+
+  let index_file = temp_dir.join("index");
+  let index_env = [("GIT_INDEX_FILE", index_file.as_str())];
+  git_executor.execute_command_with_env(&["read-tree", &head], &repository_path, &index_env)?;
+
+  let signing = resolve_commit_signing(git_executor, &repository_path)?;
+  let msg_file = temp_dir.join("msg");
+  let patch_file = temp_dir.join("patch");
+  let mut parent = head.clone();
+  let mut commits = Vec::new();
+  for mail in &mails {
+    let mail_content = fs::read_to_string(mail)?;
+    let info = parse_mail_info(&git_executor.execute_command_with_input(&["mailinfo", &msg_file, &patch_file], &repository_path, &mail_content)?);
+    if fs::metadata(&patch_file)?.len() == 0 {
+      debug!(subject = %info.subject, "Skipping mail without a diff (cover letter)");
+      continue;
+    }
+    if info.subject.is_empty() {
+      return Err(anyhow!("Patch {} has no subject", commits.len() + 1));
+    }
+
+    git_executor
+      .execute_command_with_env(&["apply", "--cached", &patch_file], &repository_path, &index_env)
+      .map_err(|e| anyhow!("Patch \"{}\" does not apply: {e}", info.subject))?;
+    let tree = git_executor.execute_command_with_env(&["write-tree"], &repository_path, &index_env)?;
+
+    let body = fs::read_to_string(&msg_file)?;
+    let subject = prefixed_subject(&info.subject, branch_name);
+    let message = if body.trim().is_empty() { subject } else { format!("{subject}\n\n{}", body.trim()) };
+    // Missing headers fall back to the configured identity and the current time
+    let env_vars: Vec<(&str, &str)> = [
+      ("GIT_AUTHOR_NAME", info.author.as_str()),
+      ("GIT_AUTHOR_EMAIL", info.email.as_str()),
+      ("GIT_AUTHOR_DATE", info.date.as_str()),
+    ]
+    .into_iter()
+    .filter(|(_, value)| !value.is_empty())
+    .collect();
+    parent = commit_tree(git_executor, &repository_path, &tree, Some(&parent), &message, &env_vars, signing.as_ref())?;
+    commits.push(CommitInfo { hash: parent.clone(), message });
+  }
+
+  if commits.is_empty() {
+    return Err(anyhow!("No patches found in the given files"));
+  }
+
+  // Move the branch and update the working tree; --keep refuses to overwrite local changes to the patched files
+  git_executor
+    .execute_command(&["reset", "--keep", "-q", &parent], &repository_path)
+    .map_err(|e| anyhow!("Failed to update the working tree, commit or stash local changes first: {e}"))?;
+
+  info!(count = commits.len(), branch_name, "Applied patch series");
+  Ok(ApplyPatchSeriesResult { commits })
+}
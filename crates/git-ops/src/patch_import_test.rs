@@ -0,0 +1,79 @@
+use crate::patch_import::{ApplyPatchSeriesParams, apply_patch_series};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// Create two commits, write them as patches with `git format-patch` and reset back to the baseline.
+/// Returns the baseline commit and the written files.
+fn create_patches(test_repo: &TestRepo, output_dir: &std::path::Path, extra_args: &[&str]) -> (String, Vec<String>) {
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.create_commit("Add parser", "parser.rs", "fn parse() {}\n");
+  test_repo.create_commit("Test parser", "parser_test.rs", "fn test_parse() {}\n");
+
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let mut args = vec!["format-patch", "-o", output_dir.to_str().unwrap()];
+  args.extend_from_slice(extra_args);
+  args.push("HEAD~2..HEAD");
+  let files = git_executor.execute_command_lines(&args, repo_path).unwrap();
+  test_repo.reset_hard(&baseline).unwrap();
+  (baseline, files)
+}
+
+fn params(test_repo: &TestRepo, patch_files: Vec<String>) -> ApplyPatchSeriesParams {
+  ApplyPatchSeriesParams {
+    repository_path: test_repo.path().to_str().unwrap().to_string(),
+    patch_files,
+    branch_name: "parser".to_string(),
+  }
+}
+
+#[test]
+fn test_apply_patch_series_prefixes_subjects() {
+  let test_repo = TestRepo::new();
+  let output_dir = tempfile::tempdir().unwrap();
+  let (baseline, files) = create_patches(&test_repo, output_dir.path(), &["--cover-letter"]);
+  assert_eq!(files.len(), 3);
+
+  let result = apply_patch_series(&GitCommandExecutor::new(), params(&test_repo, files)).unwrap();
+
+  // The cover letter is skipped
+  let messages: Vec<&str> = result.commits.iter().map(|commit| commit.message.as_str()).collect();
+  assert_eq!(messages, vec!["(parser) Add parser", "(parser) Test parser"]);
+  assert_eq!(result.commits.last().unwrap().hash, test_repo.head());
+
+  let log = test_repo.log(&["--reverse", "--pretty=format:%s|%an", &format!("{baseline}..HEAD")]).unwrap();
+  assert_eq!(log.lines().collect::<Vec<_>>(), vec!["(parser) Add parser|Test User", "(parser) Test parser|Test User"]);
+  // Working tree is updated
+  assert_eq!(std::fs::read_to_string(test_repo.path().join("parser_test.rs")).unwrap(), "fn test_parse() {}\n");
+}
+
+#[test]
+fn test_apply_patch_series_from_mbox() {
+  let test_repo = TestRepo::new();
+  let output_dir = tempfile::tempdir().unwrap();
+  let (_, files) = create_patches(&test_repo, output_dir.path(), &[]);
+
+  // Concatenated patches form an mbox
+  let mbox_path = output_dir.path().join("series.mbox");
+  let mbox: String = files.iter().map(|file| std::fs::read_to_string(file).unwrap()).collect();
+  std::fs::write(&mbox_path, mbox).unwrap();
+
+  let result = apply_patch_series(&GitCommandExecutor::new(), params(&test_repo, vec![mbox_path.to_string_lossy().into_owned()])).unwrap();
+  let messages: Vec<&str> = result.commits.iter().map(|commit| commit.message.as_str()).collect();
+  assert_eq!(messages, vec!["(parser) Add parser", "(parser) Test parser"]);
+}
+
+#[test]
+fn test_apply_patch_series_keeps_branch_when_patch_does_not_apply() {
+  let test_repo = TestRepo::new();
+  let output_dir = tempfile::tempdir().unwrap();
+  let (_, files) = create_patches(&test_repo, output_dir.path(), &[]);
+  // The second patch creates a file that already exists
+  let head = test_repo.create_commit("Conflicting test", "parser_test.rs", "other\n");
+
+  let error = apply_patch_series(&GitCommandExecutor::new(), params(&test_repo, files)).unwrap_err();
+  assert!(error.to_string().contains("Patch \"Test parser\" does not apply"), "{error}");
+  assert_eq!(test_repo.head(), head);
+}
@@ -0,0 +1,406 @@
+//! Atomic updates of several refs through `git update-ref --stdin`, and a journal of the refs an operation may move.
+//! The journal is recorded before an operation like sync starts moving refs and dropped once it's done. If the app
+//! crashes in between, the journal is left behind and [`roll_back_ref_journal`] moves the refs back to where they were,
+//! so the refs are never left half updated. The journal records the process that started it: it is only rolled back
+//! under the [`RepositoryLock`] and once that process is gone.
+//!
+//! While a journal of this process is open, every ref update is appended to a writes file before it is applied. The
+//! rollback only moves refs that still point to a commit the operation wrote: a ref moved by the user since the crash
+//! (e.g. a commit on the checked out branch or a branch created under the prefix) is the user's and is left alone.
+
+use crate::cache::resolve_common_git_dir;
+use crate::repository_lock::{RepositoryLock, is_process_alive};
+use anyhow::{Context, Result, anyhow, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use tracing::{debug, info, instrument, warn};
+
+/// Ref holding the journal of the operation in progress.
+/// Points to a commit whose message is the description of the operation and whose tree contains a single `refs` file.
+pub const REF_JOURNAL_REF: &str = "refs/branch-deck/ref-journal";
+
+const JOURNAL_FILE: &str = "refs";
+
+/// Ref updates of the operation of the open journal, one `<oid> <ref>` line per update (`-` instead of the oid for a
+/// deletion), in the `branch-deck` directory of the common git dir
+const JOURNAL_WRITES_FILE: &str = "ref-journal-writes";
+
+const DELETED_REF: &str = "-";
+
+/// Writes files of the journals opened by this process, keyed by repository path
+static OPEN_JOURNALS: LazyLock<Mutex<HashMap<String, PathBuf>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RefCommand {
+  Update { ref_name: String, new_oid: String, old_oid: Option<String> },
+  Create { ref_name: String, new_oid: String },
+  Delete { ref_name: String, old_oid: Option<String> },
+}
+
+/// Ref updates applied all at once: if one of them fails (e.g. a ref was moved in the meantime), none is applied
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RefTransaction {
+  commands: Vec<RefCommand>,
+}
+
+impl RefTransaction {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Point `ref_name` to `new_oid`; with `old_oid` the ref must currently point to it
+  pub fn update(&mut self, ref_name: &str, new_oid: &str, old_oid: Option<&str>) -> &mut Self {
+    self.commands.push(RefCommand::Update {
+      ref_name: ref_name.to_string(),
+      new_oid: new_oid.to_string(),
+      old_oid: old_oid.map(str::to_string),
+    });
+    self
+  }
+
+  /// Create `ref_name` pointing to `new_oid`, the ref must not exist yet
+  pub fn create(&mut self, ref_name: &str, new_oid: &str) -> &mut Self {
+    self.commands.push(RefCommand::Create {
+      ref_name: ref_name.to_string(),
+      new_oid: new_oid.to_string(),
+    });
+    self
+  }
+
+  /// Delete `ref_name`; with `old_oid` the ref must currently point to it
+  pub fn delete(&mut self, ref_name: &str, old_oid: Option<&str>) -> &mut Self {
+    self.commands.push(RefCommand::Delete {
+      ref_name: ref_name.to_string(),
+      old_oid: old_oid.map(str::to_string),
+    });
+    self
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.commands.is_empty()
+  }
+
+  pub fn len(&self) -> usize {
+    self.commands.len()
+  }
+
+  /// Input of `git update-ref --stdin`
+  fn to_stdin(&self) -> String {
+    let mut input = String::with_capacity(self.commands.len() * 100 + 14);
+    input.push_str("start\n");
+    for command in &self.commands {
+      // Writing to a String can't fail
+      let _ = match command {
+        RefCommand::Update { ref_name, new_oid, old_oid } => writeln!(input, "update {ref_name} {new_oid} {}", old_oid.as_deref().unwrap_or("")),
+        RefCommand::Create { ref_name, new_oid } => writeln!(input, "create {ref_name} {new_oid}"),
+        RefCommand::Delete { ref_name, old_oid } => writeln!(input, "delete {ref_name} {}", old_oid.as_deref().unwrap_or("")),
+      };
+    }
+    input.push_str("commit\n");
+    input
+  }
+
+  /// Apply every update, `message` goes to the reflog
+  #[instrument(skip(self, git_executor), fields(command_count = self.commands.len()))]
+  pub fn commit(&self, git_executor: &GitCommandExecutor, repo_path: &str, message: &str) -> Result<()> {
+    if self.commands.is_empty() {
+      return Ok(());
+    }
+    record_ref_writes(
+      repo_path,
+      self.commands.iter().map(|command| match command {
+        RefCommand::Update { ref_name, new_oid, .. } | RefCommand::Create { ref_name, new_oid } => (ref_name.as_str(), Some(new_oid.as_str())),
+        RefCommand::Delete { ref_name, .. } => (ref_name.as_str(), None),
+      }),
+    )?;
+    git_executor
+      .execute_command_with_input(&["update-ref", "-m", message, "--stdin"], repo_path, &self.to_stdin())
+      .with_context(|| format!("Failed to update {} refs, none was changed", self.commands.len()))?;
+    debug!("Ref transaction committed");
+    Ok(())
+  }
+}
+
+/// Refs as they were before an operation started
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct RefJournal {
+  /// Commit holding the journal, the journal ref is only deleted while it still points to it
+  pub commit_id: String,
+  /// What the journal was started for, for display
+  pub description: String,
+  /// Process that started the operation, `None` for journals of older versions
+  pub owner_pid: Option<u32>,
+  /// Covered refs: every ref starting with one of them (e.g. `refs/heads/prefix/` or `refs/notes/commits`)
+  pub ref_prefixes: Vec<String>,
+  /// Commit of every covered ref, keyed by full ref name
+  pub refs: BTreeMap<String, String>,
+}
+
+impl RefJournal {
+  fn to_file(&self) -> String {
+    let mut content = String::new();
+    if let Some(owner_pid) = self.owner_pid {
+      let _ = writeln!(content, "owner {owner_pid}");
+    }
+    for prefix in &self.ref_prefixes {
+      let _ = writeln!(content, "prefix {prefix}");
+    }
+    for (ref_name, oid) in &self.refs {
+      let _ = writeln!(content, "ref {oid} {ref_name}");
+    }
+    content
+  }
+
+  fn from_file(commit_id: String, description: String, content: &str) -> Result<Self> {
+    let mut journal = RefJournal {
+      commit_id,
+      description,
+      owner_pid: None,
+      ref_prefixes: Vec::new(),
+      refs: BTreeMap::new(),
+    };
+    for line in content.lines().filter(|line| !line.is_empty()) {
+      if let Some(owner_pid) = line.strip_prefix("owner ") {
+        journal.owner_pid = Some(owner_pid.parse().with_context(|| format!("Invalid owner in {REF_JOURNAL_REF}:{JOURNAL_FILE}: {line}"))?);
+      } else if let Some(prefix) = line.strip_prefix("prefix ") {
+        journal.ref_prefixes.push(prefix.to_string());
+      } else if let Some((oid, ref_name)) = line.strip_prefix("ref ").and_then(|entry| entry.split_once(' ')) {
+        journal.refs.insert(ref_name.to_string(), oid.to_string());
+      } else {
+        bail!("Invalid line in {REF_JOURNAL_REF}:{JOURNAL_FILE}: {line}");
+      }
+    }
+    Ok(journal)
+  }
+}
+
+/// Append the updates (`None` for a deletion) to the writes file of the open journal of the repository, if any.
+/// Called before the refs are moved, so a crash in between only makes the rollback consider one more commit.
+pub(crate) fn record_ref_writes<'a>(repo_path: &str, writes: impl IntoIterator<Item = (&'a str, Option<&'a str>)>) -> Result<()> {
+  let open_journals = OPEN_JOURNALS.lock().unwrap_or_else(PoisonError::into_inner);
+  let Some(writes_file) = open_journals.get(repo_path) else {
+    return Ok(());
+  };
+  let mut content = String::new();
+  for (ref_name, oid) in writes {
+    let _ = writeln!(content, "{} {ref_name}", oid.unwrap_or(DELETED_REF));
+  }
+  fs::OpenOptions::new()
+    .append(true)
+    .create(true)
+    .open(writes_file)
+    .and_then(|mut file| file.write_all(content.as_bytes()))
+    .with_context(|| format!("Failed to record ref updates in {}", writes_file.display()))
+}
+
+fn journal_writes_file(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<PathBuf> {
+  Ok(resolve_common_git_dir(git_executor, repo_path)?.join("branch-deck").join(JOURNAL_WRITES_FILE))
+}
+
+/// Stop recording the ref updates of this process and remove the writes file
+fn close_journal_writes(git_executor: &GitCommandExecutor, repo_path: &str) {
+  OPEN_JOURNALS.lock().unwrap_or_else(PoisonError::into_inner).remove(repo_path);
+  let writes_file = match journal_writes_file(git_executor, repo_path) {
+    Ok(writes_file) => writes_file,
+    Err(e) => {
+      warn!(error = %e, "Failed to resolve the ref journal writes file");
+      return;
+    }
+  };
+  if let Err(e) = fs::remove_file(&writes_file)
+    && e.kind() != std::io::ErrorKind::NotFound
+  {
+    warn!(path = %writes_file.display(), error = %e, "Failed to remove the ref journal writes file");
+  }
+}
+
+/// Refs and the commits the operation of the journal set them to, `None` for a deleted ref
+fn read_journal_writes(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<HashSet<(String, Option<String>)>> {
+  let writes_file = journal_writes_file(git_executor, repo_path)?;
+  let content = match fs::read_to_string(&writes_file) {
+    Ok(content) => content,
+    // Nothing was written before the crash, or the journal is of an older version
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashSet::new()),
+    Err(e) => return Err(e).with_context(|| format!("Failed to read {}", writes_file.display())),
+  };
+  Ok(
+    content
+      .lines()
+      .filter_map(|line| line.split_once(' '))
+      .map(|(oid, ref_name)| (ref_name.to_string(), Some(oid).filter(|oid| *oid != DELETED_REF).map(str::to_string)))
+      .collect(),
+  )
+}
+
+/// Full names and commits of the refs starting with one of the prefixes
+fn list_refs(git_executor: &GitCommandExecutor, repo_path: &str, ref_prefixes: &[&str]) -> Result<BTreeMap<String, String>> {
+  let mut args = vec!["for-each-ref", "--format=%(refname) %(objectname)"];
+  args.extend_from_slice(ref_prefixes);
+  let lines = git_executor.execute_command_lines(&args, repo_path)?;
+  Ok(
+    lines
+      .iter()
+      .filter_map(|line| line.split_once(' '))
+      .map(|(name, oid)| (name.to_string(), oid.to_string()))
+      .collect(),
+  )
+}
+
+/// Record the current refs under `ref_prefixes` as the journal of the operation of this process that is about to move
+/// them. Fails if the journal of another operation is still there, it is either running or was interrupted.
+#[instrument(skip(git_executor, _lock))]
+pub fn begin_ref_journal(git_executor: &GitCommandExecutor, repo_path: &str, _lock: &RepositoryLock, description: &str, ref_prefixes: &[&str]) -> Result<RefJournal> {
+  write_ref_journal(git_executor, repo_path, description, ref_prefixes, std::process::id())
+}
+
+pub(crate) fn write_ref_journal(git_executor: &GitCommandExecutor, repo_path: &str, description: &str, ref_prefixes: &[&str], owner_pid: u32) -> Result<RefJournal> {
+  let writes_file = journal_writes_file(git_executor, repo_path)?;
+  let mut journal = RefJournal {
+    commit_id: String::new(),
+    description: description.to_string(),
+    owner_pid: Some(owner_pid),
+    ref_prefixes: ref_prefixes.iter().map(|prefix| prefix.to_string()).collect(),
+    refs: list_refs(git_executor, repo_path, ref_prefixes)?,
+  };
+
+  let blob_id = git_executor.execute_command_with_input(&["hash-object", "-w", "--stdin"], repo_path, &journal.to_file())?;
+  let tree_id = git_executor.execute_command_with_input(&["mktree"], repo_path, &format!("100644 blob {}\t{JOURNAL_FILE}\n", blob_id.trim()))?;
+  journal.commit_id = git_executor
+    .execute_command(&["commit-tree", tree_id.trim(), "-m", description], repo_path)?
+    .trim()
+    .to_string();
+  RefTransaction::new()
+    .create(REF_JOURNAL_REF, &journal.commit_id)
+    .commit(git_executor, repo_path, &format!("branch-deck: begin {description}"))
+    .map_err(|e| anyhow!("Another operation is in progress or was interrupted, recover it first: {e:#}"))?;
+  // Left behind by an interrupted operation that was recovered by keeping its refs
+  if let Some(parent) = writes_file.parent() {
+    fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+  }
+  fs::write(&writes_file, "").with_context(|| format!("Failed to create {}", writes_file.display()))?;
+  OPEN_JOURNALS.lock().unwrap_or_else(PoisonError::into_inner).insert(repo_path.to_string(), writes_file);
+  debug!(ref_count = journal.refs.len(), "Ref journal started");
+  Ok(journal)
+}
+
+/// Drop the journal once the operation is done, its ref updates are kept.
+/// The journal ref is left alone if it was replaced by the journal of another operation in the meantime.
+#[instrument(skip(git_executor, journal), fields(commit_id = %journal.commit_id))]
+pub fn finish_ref_journal(git_executor: &GitCommandExecutor, repo_path: &str, journal: &RefJournal) -> Result<()> {
+  git_executor.execute_command(&["update-ref", "-d", REF_JOURNAL_REF, &journal.commit_id], repo_path)?;
+  close_journal_writes(git_executor, repo_path);
+  debug!("Ref journal finished");
+  Ok(())
+}
+
+/// The journal, `None` if no operation is in progress
+fn read_ref_journal(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<Option<RefJournal>> {
+  let (output, exit_code) = git_executor.execute_command_with_status(&["rev-parse", "--verify", "--quiet", &format!("{REF_JOURNAL_REF}^{{commit}}")], repo_path)?;
+  if exit_code != 0 {
+    return Ok(None);
+  }
+  let commit_id = output.trim().to_string();
+  let description = git_executor.execute_command(&["show", "-s", "--format=%B", &commit_id], repo_path)?.trim().to_string();
+  let content = git_executor.execute_command(&["cat-file", "blob", &format!("{commit_id}:{JOURNAL_FILE}")], repo_path)?;
+  Ok(Some(RefJournal::from_file(commit_id, description, &content)?))
+}
+
+/// The journal of the operation in progress or interrupted, `None` if there is none
+#[instrument(skip(git_executor))]
+pub fn load_ref_journal(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<Option<RefJournal>> {
+  read_ref_journal(git_executor, repo_path)
+}
+
+/// Updates that move the refs written by the operation of the journal back: a covered ref is only moved if it still
+/// points to a commit the operation set it to (or is still deleted by it), and a ref created since is only deleted if
+/// the operation created it. Also returns the checked out branch if it is moved back.
+fn plan_roll_back(git_executor: &GitCommandExecutor, repo_path: &str, journal: &RefJournal) -> Result<(RefTransaction, Option<String>)> {
+  let written = read_journal_writes(git_executor, repo_path)?;
+  let is_written = |ref_name: &str, oid: Option<&String>| written.contains(&(ref_name.to_string(), oid.cloned()));
+  let ref_prefixes: Vec<&str> = journal.ref_prefixes.iter().map(String::as_str).collect();
+  let current_refs = list_refs(git_executor, repo_path, &ref_prefixes)?;
+
+  let mut transaction = RefTransaction::new();
+  let mut moved_refs = Vec::new();
+  for (ref_name, oid) in &journal.refs {
+    let current_oid = current_refs.get(ref_name);
+    if current_oid == Some(oid) {
+      continue;
+    }
+    if !is_written(ref_name, current_oid) {
+      debug!(ref_name, "Ref was moved since the interrupted operation, left alone");
+      continue;
+    }
+    match current_oid {
+      Some(current_oid) => transaction.update(ref_name, oid, Some(current_oid)),
+      None => transaction.create(ref_name, oid),
+    };
+    moved_refs.push(ref_name.as_str());
+  }
+  for (ref_name, current_oid) in current_refs.iter().filter(|(ref_name, _)| !journal.refs.contains_key(*ref_name)) {
+    if is_written(ref_name, Some(current_oid)) {
+      transaction.delete(ref_name, Some(current_oid));
+    }
+  }
+
+  let (head_ref, exit_code) = git_executor.execute_command_with_status(&["symbolic-ref", "--quiet", "HEAD"], repo_path)?;
+  let head_ref = head_ref.trim();
+  let checked_out_branch = (exit_code == 0 && moved_refs.contains(&head_ref)).then(|| head_ref.to_string());
+  Ok((transaction, checked_out_branch))
+}
+
+/// The checked out branch if rolling back the journal moves it, i.e. it still points to a commit the interrupted
+/// operation set it to
+pub fn checked_out_branch_to_roll_back(git_executor: &GitCommandExecutor, repo_path: &str, journal: &RefJournal) -> Result<Option<String>> {
+  Ok(plan_roll_back(git_executor, repo_path, journal)?.1)
+}
+
+/// Move the covered refs the operation wrote back to where they were when the journal was started and delete the refs
+/// it created, together with the journal itself in a single transaction. Returns `None` if there was no journal.
+/// Refs moved since by someone else are left alone, refs the operation moved without a [`RefTransaction`] are kept too.
+/// Fails if the process that started the journal is still running: its operation is not interrupted, it's in progress.
+/// A journal of this process is left behind by an operation that failed to finish it, the lock rules out a running one.
+///
+/// Moving the checked out branch back leaves the index and working tree out of date, so it's only done with
+/// `restore_checked_out_branch`, when the user picked it; the index is then reset to the restored commit. Otherwise it
+/// fails without moving anything and the journal stays for the recovery to show.
+#[instrument(skip(git_executor, _lock))]
+pub fn roll_back_ref_journal(git_executor: &GitCommandExecutor, repo_path: &str, _lock: &RepositoryLock, restore_checked_out_branch: bool) -> Result<Option<RefJournal>> {
+  let Some(journal) = read_ref_journal(git_executor, repo_path)? else {
+    return Ok(None);
+  };
+  if let Some(owner_pid) = journal.owner_pid
+    && owner_pid != std::process::id()
+    && is_process_alive(owner_pid)
+  {
+    bail!("\"{}\" is still in progress in process {owner_pid}", journal.description);
+  }
+
+  let (mut transaction, checked_out_branch) = plan_roll_back(git_executor, repo_path, &journal)?;
+  if let Some(branch_ref) = &checked_out_branch
+    && !restore_checked_out_branch
+  {
+    let description = &journal.description;
+    bail!("\"{description}\" was interrupted after moving the checked out branch {branch_ref}, recover it first");
+  }
+  // The rollback itself is not recorded, the writes file goes away with the journal
+  OPEN_JOURNALS.lock().unwrap_or_else(PoisonError::into_inner).remove(repo_path);
+  let restored_count = transaction.len();
+  transaction.delete(REF_JOURNAL_REF, Some(&journal.commit_id));
+  transaction.commit(git_executor, repo_path, &format!("branch-deck: roll back {}", journal.description))?;
+  close_journal_writes(git_executor, repo_path);
+  if let Some(branch_ref) = &checked_out_branch {
+    git_executor.execute_command(&["reset", "--mixed", "-q", branch_ref], repo_path)?;
+  }
+
+  info!(description = %journal.description, restored_count, "Rolled back interrupted ref updates");
+  Ok(Some(journal))
+}
@@ -0,0 +1,201 @@
+use crate::ref_transaction::{
+  REF_JOURNAL_REF, RefTransaction, begin_ref_journal, checked_out_branch_to_roll_back, finish_ref_journal, load_ref_journal, roll_back_ref_journal, write_ref_journal,
+};
+use crate::repository_lock::lock_repository;
+use pretty_assertions::assert_eq;
+use std::process::{Command, Stdio};
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_transaction_applies_all_updates() {
+  let test_repo = TestRepo::new();
+  let first = test_repo.create_commit("First", "a.txt", "a");
+  let second = test_repo.create_commit("Second", "b.txt", "b");
+  test_repo.create_branch_at("user/virtual/old", &first).unwrap();
+  test_repo.create_branch_at("user/virtual/moved", &first).unwrap();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  RefTransaction::new()
+    .update("refs/heads/user/virtual/moved", &second, Some(&first))
+    .create("refs/heads/user/virtual/new", &second)
+    .delete("refs/heads/user/virtual/old", Some(&first))
+    .commit(test_repo.git_executor(), repo_path, "test")
+    .unwrap();
+
+  assert_eq!(test_repo.rev_parse("user/virtual/moved").unwrap(), second);
+  assert_eq!(test_repo.rev_parse("user/virtual/new").unwrap(), second);
+  assert!(!test_repo.branch_exists("user/virtual/old"));
+}
+
+#[test]
+fn test_transaction_applies_nothing_if_one_update_fails() {
+  let test_repo = TestRepo::new();
+  let first = test_repo.create_commit("First", "a.txt", "a");
+  let second = test_repo.create_commit("Second", "b.txt", "b");
+  test_repo.create_branch_at("user/virtual/a", &first).unwrap();
+  test_repo.create_branch_at("user/virtual/b", &second).unwrap();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  // `b` is not at the expected commit anymore
+  let result = RefTransaction::new()
+    .update("refs/heads/user/virtual/a", &second, Some(&first))
+    .update("refs/heads/user/virtual/b", &first, Some(&first))
+    .commit(test_repo.git_executor(), repo_path, "test");
+
+  assert!(result.is_err());
+  assert_eq!(test_repo.rev_parse("user/virtual/a").unwrap(), first);
+  assert_eq!(test_repo.rev_parse("user/virtual/b").unwrap(), second);
+}
+
+#[test]
+fn test_roll_back_interrupted_journal() {
+  let test_repo = TestRepo::new();
+  let first = test_repo.create_commit("First", "a.txt", "a");
+  let second = test_repo.create_commit("Second", "b.txt", "b");
+  test_repo.create_branch_at("user/virtual/moved", &first).unwrap();
+  test_repo.create_branch_at("user/virtual/deleted", &first).unwrap();
+  test_repo.create_branch_at("other", &first).unwrap();
+  let git_executor = test_repo.git_executor();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let lock = lock_repository(git_executor, repo_path).unwrap();
+  let journal = begin_ref_journal(git_executor, repo_path, &lock, "sync user", &["refs/heads/user/"]).unwrap();
+  assert_eq!(journal.refs.len(), 2);
+  assert_eq!(journal.owner_pid, Some(std::process::id()));
+
+  // Half of the updates happened before the crash
+  RefTransaction::new()
+    .update("refs/heads/user/virtual/moved", &second, None)
+    .create("refs/heads/user/virtual/created", &second)
+    .delete("refs/heads/user/virtual/deleted", None)
+    .update("refs/heads/other", &second, None)
+    .commit(git_executor, repo_path, "test")
+    .unwrap();
+
+  let rolled_back = roll_back_ref_journal(git_executor, repo_path, &lock, false).unwrap();
+  assert_eq!(rolled_back, Some(journal));
+  assert_eq!(test_repo.rev_parse("user/virtual/moved").unwrap(), first);
+  assert_eq!(test_repo.rev_parse("user/virtual/deleted").unwrap(), first);
+  assert!(!test_repo.branch_exists("user/virtual/created"));
+  // Refs outside of the journal are left alone
+  assert_eq!(test_repo.rev_parse("other").unwrap(), second);
+  assert!(test_repo.rev_parse(REF_JOURNAL_REF).is_err());
+  assert_eq!(roll_back_ref_journal(git_executor, repo_path, &lock, false).unwrap(), None);
+}
+
+#[test]
+fn test_finished_journal_keeps_updates() {
+  let test_repo = TestRepo::new();
+  let first = test_repo.create_commit("First", "a.txt", "a");
+  let second = test_repo.create_commit("Second", "b.txt", "b");
+  test_repo.create_branch_at("user/virtual/a", &first).unwrap();
+  let git_executor = test_repo.git_executor();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let lock = lock_repository(git_executor, repo_path).unwrap();
+  let journal = begin_ref_journal(git_executor, repo_path, &lock, "sync user", &["refs/heads/user/"]).unwrap();
+  // Only one operation at a time
+  assert!(begin_ref_journal(git_executor, repo_path, &lock, "sync user", &["refs/heads/user/"]).is_err());
+
+  RefTransaction::new()
+    .update("refs/heads/user/virtual/a", &second, Some(&first))
+    .commit(git_executor, repo_path, "test")
+    .unwrap();
+  finish_ref_journal(git_executor, repo_path, &journal).unwrap();
+
+  assert_eq!(load_ref_journal(git_executor, repo_path).unwrap(), None);
+  assert_eq!(roll_back_ref_journal(git_executor, repo_path, &lock, false).unwrap(), None);
+  assert_eq!(test_repo.rev_parse("user/virtual/a").unwrap(), second);
+
+  // Finishing again doesn't delete the journal of the next operation
+  let next_journal = begin_ref_journal(git_executor, repo_path, &lock, "sync user", &["refs/heads/user/"]).unwrap();
+  assert!(finish_ref_journal(git_executor, repo_path, &journal).is_err());
+  assert_eq!(load_ref_journal(git_executor, repo_path).unwrap(), Some(next_journal));
+}
+
+#[test]
+fn test_journal_of_running_process_is_not_rolled_back() {
+  let test_repo = TestRepo::new();
+  let first = test_repo.create_commit("First", "a.txt", "a");
+  let second = test_repo.create_commit("Second", "b.txt", "b");
+  test_repo.create_branch_at("user/virtual/a", &first).unwrap();
+  let git_executor = test_repo.git_executor();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  // Another process syncing the repository, it waits for input until killed
+  let mut other_process = Command::new("git")
+    .args(["cat-file", "--batch"])
+    .current_dir(repo_path)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::null())
+    .spawn()
+    .unwrap();
+  let journal = write_ref_journal(git_executor, repo_path, "sync user", &["refs/heads/user/"], other_process.id()).unwrap();
+  RefTransaction::new()
+    .update("refs/heads/user/virtual/a", &second, None)
+    .commit(git_executor, repo_path, "test")
+    .unwrap();
+
+  let lock = lock_repository(git_executor, repo_path).unwrap();
+  let result = roll_back_ref_journal(git_executor, repo_path, &lock, false);
+  assert!(result.is_err_and(|e| e.to_string().contains("still in progress")));
+  assert_eq!(test_repo.rev_parse("user/virtual/a").unwrap(), second);
+
+  other_process.kill().unwrap();
+  other_process.wait().unwrap();
+  assert_eq!(roll_back_ref_journal(git_executor, repo_path, &lock, false).unwrap(), Some(journal));
+  assert_eq!(test_repo.rev_parse("user/virtual/a").unwrap(), first);
+}
+
+#[test]
+fn test_roll_back_leaves_refs_moved_since_alone() {
+  let test_repo = TestRepo::new();
+  let first = test_repo.create_commit("First", "a.txt", "a");
+  let second = test_repo.create_commit("Second", "b.txt", "b");
+  test_repo.create_branch_at("user/virtual/a", &first).unwrap();
+  test_repo.create_branch_at("user/virtual/b", &first).unwrap();
+  let git_executor = test_repo.git_executor();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let lock = lock_repository(git_executor, repo_path).unwrap();
+  begin_ref_journal(git_executor, repo_path, &lock, "sync user", &["refs/heads/user/"]).unwrap();
+  RefTransaction::new()
+    .update("refs/heads/user/virtual/a", &second, None)
+    .update("refs/heads/user/virtual/b", &second, None)
+    .commit(git_executor, repo_path, "test")
+    .unwrap();
+
+  // After the crash, the user moves one of the branches and creates another one under the prefix
+  let third = test_repo.create_commit("Third", "c.txt", "c");
+  test_repo.create_branch_at("user/virtual/a", &third).unwrap();
+  test_repo.create_branch_at("user/mine", &third).unwrap();
+
+  assert!(roll_back_ref_journal(git_executor, repo_path, &lock, false).unwrap().is_some());
+  assert_eq!(test_repo.rev_parse("user/virtual/a").unwrap(), third);
+  assert_eq!(test_repo.rev_parse("user/virtual/b").unwrap(), first);
+  assert_eq!(test_repo.rev_parse("user/mine").unwrap(), third);
+}
+
+#[test]
+fn test_roll_back_of_checked_out_branch_is_explicit() {
+  let test_repo = TestRepo::new();
+  let first = test_repo.create_commit("First", "a.txt", "a");
+  let second = test_repo.create_commit("Second", "b.txt", "b");
+  let head_ref = format!("refs/heads/{}", test_repo.current_branch().unwrap());
+  let git_executor = test_repo.git_executor();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let lock = lock_repository(git_executor, repo_path).unwrap();
+  let journal = begin_ref_journal(git_executor, repo_path, &lock, "sync user", &[&head_ref]).unwrap();
+  // Integrated commit dropped from the checked out branch
+  RefTransaction::new().update(&head_ref, &first, None).commit(git_executor, repo_path, "test").unwrap();
+
+  assert_eq!(checked_out_branch_to_roll_back(git_executor, repo_path, &journal).unwrap(), Some(head_ref.clone()));
+  let result = roll_back_ref_journal(git_executor, repo_path, &lock, false);
+  assert!(result.is_err_and(|e| e.to_string().contains("checked out branch")));
+  assert_eq!(test_repo.head(), first);
+  assert_eq!(load_ref_journal(git_executor, repo_path).unwrap(), Some(journal.clone()));
+
+  assert_eq!(roll_back_ref_journal(git_executor, repo_path, &lock, true).unwrap(), Some(journal));
+  assert_eq!(test_repo.head(), second);
+}
@@ -0,0 +1,123 @@
+//! Lock of a repository held while an operation moves its refs, e.g. a sync together with the phases it continues in
+//! the background. It is held in the process and in `.git/branch-deck/lock` with the process ID, so neither another
+//! window nor another instance of the app works on the same refs at the same time. The lock file of a process that is
+//! gone was left behind by a crash and is taken over.
+
+use crate::cache::resolve_common_git_dir;
+use anyhow::{Context, Result, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use tracing::{debug, instrument, warn};
+
+const LOCK_FILE: &str = "lock";
+
+/// Common git directories of the repositories locked by this process
+static LOCKED_REPOSITORIES: LazyLock<Mutex<HashSet<PathBuf>>> = LazyLock::new(Default::default);
+
+/// Held until dropped
+#[derive(Debug)]
+pub struct RepositoryLock {
+  git_dir: PathBuf,
+  lock_file: PathBuf,
+  lock_file_created: bool,
+}
+
+/// Lock the repository, failing if another operation of this or another running process holds the lock
+#[instrument(skip(git_executor))]
+pub fn lock_repository(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<RepositoryLock> {
+  let git_dir = resolve_common_git_dir(git_executor, repo_path)?;
+  // Worktrees and different spellings of the path share the lock
+  let git_dir = fs::canonicalize(&git_dir).unwrap_or(git_dir);
+  if !LOCKED_REPOSITORIES.lock().unwrap_or_else(PoisonError::into_inner).insert(git_dir.clone()) {
+    bail!("Another operation on this repository is in progress, try again once it is done");
+  }
+
+  // From here on, dropping the lock releases the part held in the process
+  let mut lock = RepositoryLock {
+    lock_file: git_dir.join("branch-deck").join(LOCK_FILE),
+    git_dir,
+    lock_file_created: false,
+  };
+  lock.create_lock_file()?;
+  debug!("Repository locked");
+  Ok(lock)
+}
+
+impl RepositoryLock {
+  fn create_lock_file(&mut self) -> Result<()> {
+    if let Some(parent) = self.lock_file.parent() {
+      fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    // Second attempt after taking over the lock file of a process that is gone
+    for _ in 0..2 {
+      match OpenOptions::new().write(true).create_new(true).open(&self.lock_file) {
+        Ok(mut file) => {
+          self.lock_file_created = true;
+          write!(file, "{}", std::process::id()).with_context(|| format!("Failed to write {}", self.lock_file.display()))?;
+          return Ok(());
+        }
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+          let owner_pid = fs::read_to_string(&self.lock_file).ok().and_then(|content| content.trim().parse::<u32>().ok());
+          if let Some(owner_pid) = owner_pid
+            && owner_pid != std::process::id()
+            && is_process_alive(owner_pid)
+          {
+            bail!("Another Branch Deck process ({owner_pid}) is working on this repository, try again once it is done");
+          }
+          warn!(?owner_pid, lock_file = %self.lock_file.display(), "Taking over the lock left behind by a process that is gone");
+          if let Err(e) = fs::remove_file(&self.lock_file)
+            && e.kind() != ErrorKind::NotFound
+          {
+            return Err(e).with_context(|| format!("Failed to remove {}", self.lock_file.display()));
+          }
+        }
+        Err(e) => return Err(e).with_context(|| format!("Failed to create {}", self.lock_file.display())),
+      }
+    }
+    bail!("Failed to lock the repository, {} keeps being recreated", self.lock_file.display())
+  }
+}
+
+impl Drop for RepositoryLock {
+  fn drop(&mut self) {
+    if self.lock_file_created
+      && let Err(e) = fs::remove_file(&self.lock_file)
+    {
+      warn!(lock_file = %self.lock_file.display(), error = %e, "Failed to remove repository lock file");
+    }
+    LOCKED_REPOSITORIES.lock().unwrap_or_else(PoisonError::into_inner).remove(&self.git_dir);
+  }
+}
+
+/// Whether a process with the ID is running. A process of another user may be reported as gone.
+pub fn is_process_alive(pid: u32) -> bool {
+  if pid == std::process::id() {
+    return true;
+  }
+  let alive = if cfg!(windows) {
+    Command::new("tasklist")
+      .args(["/FI", &format!("PID eq {pid}"), "/NH", "/FO", "CSV"])
+      .stdin(Stdio::null())
+      .stderr(Stdio::null())
+      .output()
+      .map(|output| String::from_utf8_lossy(&output.stdout).contains(&format!("\"{pid}\"")))
+  } else {
+    // Signal 0 only checks that the process exists
+    Command::new("kill")
+      .args(["-0", &pid.to_string()])
+      .stdin(Stdio::null())
+      .stdout(Stdio::null())
+      .stderr(Stdio::null())
+      .status()
+      .map(|status| status.success())
+  };
+  alive.unwrap_or_else(|e| {
+    warn!(pid, error = %e, "Failed to check whether process is running, assuming it is");
+    true
+  })
+}
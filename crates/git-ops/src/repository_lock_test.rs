@@ -0,0 +1,52 @@
+use crate::repository_lock::{is_process_alive, lock_repository};
+use pretty_assertions::assert_eq;
+use std::fs;
+use std::process::{Command, Stdio};
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_repository_locked_once() {
+  let test_repo = TestRepo::new();
+  let git_executor = test_repo.git_executor();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let lock_file = test_repo.path().join(".git/branch-deck/lock");
+
+  let lock = lock_repository(git_executor, repo_path).unwrap();
+  assert_eq!(fs::read_to_string(&lock_file).unwrap(), std::process::id().to_string());
+  assert!(lock_repository(git_executor, repo_path).is_err());
+
+  drop(lock);
+  assert!(!lock_file.exists());
+  drop(lock_repository(git_executor, repo_path).unwrap());
+}
+
+#[test]
+fn test_lock_file_of_other_process() {
+  let test_repo = TestRepo::new();
+  let git_executor = test_repo.git_executor();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let lock_file = test_repo.path().join(".git/branch-deck/lock");
+  fs::create_dir_all(lock_file.parent().unwrap()).unwrap();
+
+  // Another instance of the app, it waits for input until killed
+  let mut other_process = Command::new("git")
+    .args(["cat-file", "--batch"])
+    .current_dir(repo_path)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::null())
+    .spawn()
+    .unwrap();
+  let other_pid = other_process.id();
+  assert!(is_process_alive(other_pid));
+  fs::write(&lock_file, other_pid.to_string()).unwrap();
+  let error = lock_repository(git_executor, repo_path).unwrap_err();
+  assert!(error.to_string().contains(&other_pid.to_string()), "{error}");
+
+  // Left behind by a crash
+  other_process.kill().unwrap();
+  other_process.wait().unwrap();
+  assert!(!is_process_alive(other_pid));
+  let lock = lock_repository(git_executor, repo_path).unwrap();
+  assert_eq!(fs::read_to_string(&lock_file).unwrap(), std::process::id().to_string());
+  drop(lock);
+}
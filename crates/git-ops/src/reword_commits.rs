@@ -1,19 +1,25 @@
+use crate::commit_dependencies::carry_over_commit_dependencies;
 use crate::commit_list::Commit;
 use crate::commit_utils::create_commit_with_metadata;
 use crate::commit_utils::prefetch_commit_infos_map;
+use crate::commit_utils::resolve_commit_signing;
+use crate::ref_transaction::record_ref_writes;
 use anyhow::{Result, anyhow};
 use git_executor::git_command_executor::GitCommandExecutor;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use tracing::{debug, info, instrument};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
 pub struct RewordCommitParams {
   pub commit_id: String,
   pub new_message: String,
 }
 
 /// Reword multiple commits efficiently using git plumbing commands.
-/// Returns a mapping of old commit IDs to new commit IDs.
+/// Returns a mapping of old commit IDs to new commit IDs for the reworded commits and the descendants recreated on top of them.
 #[instrument(skip(git_executor))]
 pub fn reword_commits_batch(git_executor: &GitCommandExecutor, repo_path: &str, rewrites: Vec<RewordCommitParams>) -> Result<HashMap<String, String>> {
   if rewrites.is_empty() {
@@ -31,9 +37,18 @@ pub fn reword_commits_batch(git_executor: &GitCommandExecutor, repo_path: &str,
   // Get all commits from the oldest rewrite to HEAD with their parents, and the range used
   let (commits_to_process, original_tip, process_range) = get_commits_to_process(git_executor, repo_path, &rewrite_map)?;
 
+  // Fail before writing anything if some commits are not part of the current branch (e.g., only reachable through a merge)
+  let processed_ids: HashSet<&str> = commits_to_process.iter().map(|(id, _)| id.as_str()).collect();
+  if let Some(missing) = rewrite_map.keys().find(|id| !processed_ids.contains(id.as_str())) {
+    return Err(anyhow!("Commit {missing} is not on the first-parent history of the current branch"));
+  }
+
   // Prefetch commit infos for the entire range in one go
   let commit_info_map = prefetch_commit_infos_map(git_executor, repo_path, &process_range)?;
 
+  // Reworded commits are re-signed if the repository opted in
+  let signing = resolve_commit_signing(git_executor, repo_path)?;
+
   // Process commits from oldest to newest, creating new commits as needed
   let mut id_mapping: HashMap<String, String> = HashMap::new();
 
@@ -55,7 +70,15 @@ pub fn reword_commits_batch(git_executor: &GitCommandExecutor, repo_path: &str,
       let message = rewrite_map.get(commit_id).cloned().unwrap_or(commit_info.message.clone());
 
       // Create new commit
-      let new_commit_id = create_commit_with_metadata(git_executor, repo_path, &commit_info.tree_id, new_parent_id.as_deref(), &commit_info, &message)?;
+      let new_commit_id = create_commit_with_metadata(
+        git_executor,
+        repo_path,
+        &commit_info.tree_id,
+        new_parent_id.as_deref(),
+        &commit_info,
+        &message,
+        signing.as_ref(),
+      )?;
 
       id_mapping.insert(commit_id.clone(), new_commit_id.clone());
 
@@ -70,11 +93,12 @@ pub fn reword_commits_batch(git_executor: &GitCommandExecutor, repo_path: &str,
   // Update the branch to point to the new tip
   let new_tip = id_mapping.get(&original_tip).cloned().unwrap_or(original_tip);
   update_branch_ref(git_executor, repo_path, &current_branch, &new_tip)?;
+  carry_over_commit_dependencies(git_executor, repo_path, &id_mapping);
 
   info!("Successfully reworded {} commits", rewrite_map.len());
 
-  // Return only the mapping for commits that were actually reworded
-  Ok(id_mapping.into_iter().filter(|(old_id, _)| rewrite_map.contains_key(old_id)).collect())
+  // Reworded commits and the descendants recreated on top of them
+  Ok(id_mapping)
 }
 
 fn get_current_branch(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<String> {
@@ -129,38 +153,20 @@ fn get_commits_to_process(git_executor: &GitCommandExecutor, repo_path: &str, re
 }
 
 pub fn get_commit_info(git_executor: &GitCommandExecutor, repo_path: &str, commit_id: &str) -> Result<Commit> {
-  // Use git show with format to get all commit info at once
-  let format = "%an%n%ae%n%at%n%ct%n%T%n%P%n%B";
-  let format_arg = format!("--format={format}");
-  let args = vec!["show", "-s", &format_arg, commit_id];
-
-  let output = git_executor.execute_command(&args, repo_path)?;
-
-  let lines: Vec<&str> = output.lines().collect();
-  if lines.len() < 7 {
-    return Err(anyhow!("Invalid commit info format"));
-  }
-
-  // Parse the output
-  let author_name = lines[0].to_string();
-  let author_email = lines[1].to_string();
-  let author_timestamp: u32 = lines[2].parse().map_err(|_| anyhow!("Invalid author time"))?;
-  let committer_timestamp: u32 = lines[3].parse().map_err(|_| anyhow!("Invalid committer time"))?;
-  let tree_id = lines[4].to_string();
-  let parent_id = if lines[5].is_empty() { None } else { Some(lines[5].to_string()) };
-  let message = lines[6..].join("\n");
+  let commit = git_executor.read_commit(repo_path, commit_id)?;
+  let message = commit.message.trim_end().to_string();
   let subject = message.lines().next().unwrap_or("").to_string();
 
   Ok(Commit {
     id: commit_id.to_string(),
     subject: subject.clone(),
     message,
-    author_name,
-    author_email,
-    author_timestamp,
-    committer_timestamp,
-    parent_id,
-    tree_id,
+    author_name: commit.author_name,
+    author_email: commit.author_email,
+    author_timestamp: commit.author_timestamp,
+    committer_timestamp: commit.committer_timestamp,
+    parent_id: commit.parent_ids.into_iter().next(),
+    tree_id: commit.tree_id,
     note: None,
     stripped_subject: subject, // Same as subject since we're not stripping
     mapped_commit_id: None,    // Not relevant for rewording
@@ -169,9 +175,14 @@ pub fn get_commit_info(git_executor: &GitCommandExecutor, repo_path: &str, commi
 
 // create_commit_with_info replaced by commit_utils::create_commit_with_metadata
 
+/// Reflog message of a branch moved by a history rewrite, used to detect a rewrite interrupted before the index was refreshed
+pub const REWRITE_REFLOG_MESSAGE: &str = "branch-deck: rewrite";
+
 pub fn update_branch_ref(git_executor: &GitCommandExecutor, repo_path: &str, branch_name: &str, new_commit_id: &str) -> Result<()> {
   let ref_name = format!("refs/heads/{branch_name}");
-  let args = vec!["update-ref", &ref_name, new_commit_id];
+  let args = vec!["update-ref", "-m", REWRITE_REFLOG_MESSAGE, &ref_name, new_commit_id];
+  // The checked out branch is rewritten during a sync too (e.g. integrated commits dropped by auto-archive)
+  record_ref_writes(repo_path, [(ref_name.as_str(), Some(new_commit_id))])?;
 
   git_executor.execute_command(&args, repo_path)?;
 
@@ -0,0 +1,58 @@
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Prefix of the names of temporary files and directories, so ones left behind by a crash can be found
+pub const TEMP_NAME_PREFIX: &str = "branchdeck_";
+
+/// Prefix of the names of temporary files and directories created for the repository, followed by the ID of the process
+/// that created them. The temp directory is shared by all repositories and all running instances of the app.
+pub fn repository_temp_prefix(repository_path: &str) -> String {
+  let path = fs::canonicalize(repository_path).unwrap_or_else(|_| PathBuf::from(repository_path));
+  // FNV-1a, stable across builds unlike the std hasher, so another version of the app finds the same names
+  let hash = path
+    .to_string_lossy()
+    .bytes()
+    .fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3));
+  format!("{TEMP_NAME_PREFIX}{:08x}_", (hash ^ (hash >> 32)) as u32)
+}
+
+/// ID of the process that created the temporary entry, `None` if the name doesn't start with `prefix`
+pub fn temp_name_owner_pid(file_name: &str, prefix: &str) -> Option<u32> {
+  file_name.strip_prefix(prefix)?.split('_').next()?.parse().ok()
+}
+
+/// Unique path in the temp directory for a temporary entry of the repository
+pub fn new_temp_path(repository_path: &str, name: &str) -> PathBuf {
+  let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos();
+  let prefix = repository_temp_prefix(repository_path);
+  std::env::temp_dir().join(format!("{prefix}{}_{name}_{nanos}", std::process::id()))
+}
+
+/// RAII guard for a temporary working directory, removed with its content on drop
+pub struct TempDirGuard {
+  path: PathBuf,
+}
+
+impl TempDirGuard {
+  pub fn new(repository_path: &str, name: &str) -> Result<Self> {
+    let path = new_temp_path(repository_path, name);
+    fs::create_dir_all(&path)?;
+    Ok(Self { path })
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Path of an entry in the directory
+  pub fn join(&self, name: &str) -> String {
+    self.path.join(name).to_string_lossy().into_owned()
+  }
+}
+
+impl Drop for TempDirGuard {
+  fn drop(&mut self) {
+    let _ = fs::remove_dir_all(&self.path);
+  }
+}
@@ -0,0 +1,231 @@
+//! Word-level (intra-line) changes of diff hunks, so a viewer can highlight the changed tokens instead of whole lines.
+//!
+//! In each run of removed lines followed by added lines, the lines are paired in order and each pair is diffed by
+//! tokens: words (letters, digits and `_`), whitespace runs and single other characters. Pairs that have too little in
+//! common are left without ranges: the whole line changed, and highlighting almost every token would only add noise.
+
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+#[cfg(feature = "specta")]
+use specta::Type;
+
+/// Lines that are at least this similar get word-level ranges
+const MIN_LINE_SIMILARITY: f32 = 0.5;
+
+/// Longer lines (e.g., minified code) are not diffed by words
+const MAX_LINE_LENGTH: usize = 1000;
+
+/// Side of a diff a line belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub enum DiffSide {
+  /// Removed line, numbered in the old file
+  Old,
+  /// Added line, numbered in the new file
+  New,
+}
+
+/// Changed part of a line, in UTF-16 code units (JavaScript string indices), end exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct WordDiffRange {
+  pub start: u32,
+  pub end: u32,
+}
+
+/// Changed tokens of a removed or added line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct LineWordDiff {
+  pub side: DiffSide,
+  /// 1-based line number in the file of its side
+  pub line_number: u32,
+  pub ranges: Vec<WordDiffRange>,
+}
+
+/// Word-level changes of a hunk, only lines with changed tokens are listed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct HunkWordDiff {
+  pub lines: Vec<LineWordDiff>,
+}
+
+/// Word-level changes of each hunk, in the order of the hunks.
+/// A hunk may contain several `@@` sections, headers before the first one are skipped.
+pub fn compute_word_diffs(hunks: &[String]) -> Vec<HunkWordDiff> {
+  hunks.iter().map(|hunk| compute_hunk_word_diff(hunk)).collect()
+}
+
+fn compute_hunk_word_diff(hunk: &str) -> HunkWordDiff {
+  let mut lines = Vec::new();
+  let mut old_line = 0;
+  let mut new_line = 0;
+  let mut in_section = false;
+  // Current run of removed lines followed by added lines, with their line numbers
+  let mut removed: Vec<(u32, &str)> = Vec::new();
+  let mut added: Vec<(u32, &str)> = Vec::new();
+
+  for line in hunk.lines() {
+    if let Some((old_start, new_start)) = parse_section_header(line) {
+      diff_changed_lines(&removed, &added, &mut lines);
+      removed.clear();
+      added.clear();
+      old_line = old_start;
+      new_line = new_start;
+      in_section = true;
+      continue;
+    }
+    if !in_section {
+      continue;
+    }
+
+    if let Some(text) = line.strip_prefix('-') {
+      // Removed lines after added ones start a new run
+      if !added.is_empty() {
+        diff_changed_lines(&removed, &added, &mut lines);
+        removed.clear();
+        added.clear();
+      }
+      removed.push((old_line, text));
+      old_line += 1;
+    } else if let Some(text) = line.strip_prefix('+') {
+      added.push((new_line, text));
+      new_line += 1;
+    } else if line.starts_with('\\') {
+      // "\ No newline at end of file"
+    } else {
+      diff_changed_lines(&removed, &added, &mut lines);
+      removed.clear();
+      added.clear();
+      old_line += 1;
+      new_line += 1;
+    }
+  }
+  diff_changed_lines(&removed, &added, &mut lines);
+  HunkWordDiff { lines }
+}
+
+/// Start lines of the old and new side from a `@@ -a,b +c,d @@` header
+fn parse_section_header(line: &str) -> Option<(u32, u32)> {
+  let ranges = line.strip_prefix("@@ -")?;
+  let (old_range, rest) = ranges.split_once(" +")?;
+  let (new_range, _) = rest.split_once(" @@")?;
+  let start = |range: &str| range.split(',').next()?.parse::<u32>().ok();
+  Some((start(old_range)?, start(new_range)?))
+}
+
+/// Pair removed and added lines in order and add the word-level ranges of similar pairs
+fn diff_changed_lines(removed: &[(u32, &str)], added: &[(u32, &str)], lines: &mut Vec<LineWordDiff>) {
+  for (&(old_number, old_text), &(new_number, new_text)) in removed.iter().zip(added) {
+    if old_text == new_text || old_text.len() > MAX_LINE_LENGTH || new_text.len() > MAX_LINE_LENGTH {
+      continue;
+    }
+    let Some((old_ranges, new_ranges)) = diff_words(old_text, new_text) else {
+      continue;
+    };
+    if !old_ranges.is_empty() {
+      lines.push(LineWordDiff {
+        side: DiffSide::Old,
+        line_number: old_number,
+        ranges: old_ranges,
+      });
+    }
+    if !new_ranges.is_empty() {
+      lines.push(LineWordDiff {
+        side: DiffSide::New,
+        line_number: new_number,
+        ranges: new_ranges,
+      });
+    }
+  }
+}
+
+/// Ranges of the removed and of the inserted words, `None` if the lines are too different
+pub(crate) fn diff_words(old_text: &str, new_text: &str) -> Option<(Vec<WordDiffRange>, Vec<WordDiffRange>)> {
+  let old_tokens = tokenize(old_text);
+  let new_tokens = tokenize(new_text);
+  let diff = TextDiff::configure().diff_slices(&old_tokens, &new_tokens);
+  if diff.ratio() < MIN_LINE_SIMILARITY {
+    return None;
+  }
+
+  let mut old_ranges = Vec::new();
+  let mut new_ranges = Vec::new();
+  let mut old_offset = 0;
+  let mut new_offset = 0;
+  for change in diff.iter_all_changes() {
+    let length = change.value().encode_utf16().count() as u32;
+    match change.tag() {
+      ChangeTag::Equal => {
+        old_offset += length;
+        new_offset += length;
+      }
+      ChangeTag::Delete => {
+        push_range(&mut old_ranges, old_offset, old_offset + length);
+        old_offset += length;
+      }
+      ChangeTag::Insert => {
+        push_range(&mut new_ranges, new_offset, new_offset + length);
+        new_offset += length;
+      }
+    }
+  }
+  Some((old_ranges, new_ranges))
+}
+
+/// Add a range, extending the last one if they are adjacent
+fn push_range(ranges: &mut Vec<WordDiffRange>, start: u32, end: u32) {
+  match ranges.last_mut() {
+    Some(last) if last.end == start => last.end = end,
+    _ => ranges.push(WordDiffRange { start, end }),
+  }
+}
+
+/// Split a line into words, whitespace runs and single other characters (punctuation, symbols)
+fn tokenize(text: &str) -> Vec<&str> {
+  let mut tokens = Vec::new();
+  let mut chars = text.char_indices().peekable();
+  while let Some((start, c)) = chars.next() {
+    let end = match char_class(c) {
+      CharClass::Other => start + c.len_utf8(),
+      class => end_of_run(&mut chars, class, start + c.len_utf8()),
+    };
+    tokens.push(&text[start..end]);
+  }
+  tokens
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+  Word,
+  Whitespace,
+  Other,
+}
+
+fn char_class(c: char) -> CharClass {
+  if c.is_alphanumeric() || c == '_' {
+    CharClass::Word
+  } else if c.is_whitespace() {
+    CharClass::Whitespace
+  } else {
+    CharClass::Other
+  }
+}
+
+/// Consume the following characters of the same class, returns the end of the run
+fn end_of_run(chars: &mut Peekable<CharIndices>, class: CharClass, mut end: usize) -> usize {
+  while let Some(&(index, c)) = chars.peek()
+    && char_class(c) == class
+  {
+    end = index + c.len_utf8();
+    chars.next();
+  }
+  end
+}
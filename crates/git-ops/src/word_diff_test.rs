@@ -0,0 +1,59 @@
+use crate::word_diff::{DiffSide, HunkWordDiff, LineWordDiff, WordDiffRange, compute_word_diffs, diff_words};
+use pretty_assertions::assert_eq;
+use test_log::test;
+
+fn range(start: u32, end: u32) -> WordDiffRange {
+  WordDiffRange { start, end }
+}
+
+#[test]
+fn test_word_diffs_of_changed_lines() {
+  let hunk = "--- a/test.rs\n+++ b/test.rs\n@@ -3,4 +3,4 @@\n fn main() {\n-  let count = compute(1);\n+  let total = compute(1);\n   run();\n-  println!(\"done\");\n+  println!(\"finished\");\n }";
+
+  assert_eq!(
+    compute_word_diffs(&[hunk.to_string()]),
+    vec![HunkWordDiff {
+      lines: vec![
+        LineWordDiff {
+          side: DiffSide::Old,
+          line_number: 4,
+          ranges: vec![range(6, 11)],
+        },
+        LineWordDiff {
+          side: DiffSide::New,
+          line_number: 4,
+          ranges: vec![range(6, 11)],
+        },
+        LineWordDiff {
+          side: DiffSide::Old,
+          line_number: 6,
+          ranges: vec![range(12, 16)],
+        },
+        LineWordDiff {
+          side: DiffSide::New,
+          line_number: 6,
+          ranges: vec![range(12, 20)],
+        },
+      ],
+    }]
+  );
+}
+
+#[test]
+fn test_word_diffs_skip_unpaired_and_dissimilar_lines() {
+  // The extra added line has no removed counterpart, the first pair has nothing in common
+  let hunk = "@@ -1,1 +1,2 @@\n-alpha beta gamma\n+one two three\n+four";
+  assert_eq!(compute_word_diffs(&[hunk.to_string()]), vec![HunkWordDiff::default()]);
+
+  // Without any change there is nothing to highlight
+  let context_only = "@@ -1,2 +1,2 @@\n a\n b";
+  assert_eq!(compute_word_diffs(&[context_only.to_string()]), vec![HunkWordDiff::default()]);
+}
+
+#[test]
+fn test_word_diff_ranges_are_utf16_offsets() {
+  let (old_ranges, new_ranges) = diff_words("let naïve = \"😀\" + x;", "let naïve = \"😀\" + y;").unwrap();
+  // The emoji takes two UTF-16 code units
+  assert_eq!(old_ranges, vec![range(19, 20)]);
+  assert_eq!(new_ranges, vec![range(19, 20)]);
+}
@@ -0,0 +1,315 @@
+//! CI status of pushed branches from the hosting provider: the check rollup of the head commit on GitHub, its latest
+//! pipeline on GitLab.
+//!
+//! Opt-in per repository (`branchdeck.ciStatus`) like the review status, with the token from the git credential helper.
+//! Statuses are cached by commit in [`CiStatusCache`], and nothing is queried until the rate limit reported by the
+//! provider resets.
+
+use crate::http_client::{build_http_client, check_status, get_host_token};
+use crate::review_status::{GITHUB_BRANCHES_PER_QUERY, github_graphql_url, gitlab_project_url};
+use anyhow::{Result, anyhow, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::git_config::get_config_bool;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sync_core::ci_status::{CI_STATUS_CONFIG_KEY, CiStatusCache};
+use sync_core::hosting_provider::{HostingProviderKind, RemoteHostingConfig, resolve_remote_hosting_config};
+use sync_core::network::ensure_url_reachable_async;
+use sync_core::remote_status::RemoteHeads;
+use sync_types::{CiState, CiStatus, RemoteStatusUpdate};
+use tracing::{debug, instrument, warn};
+
+/// Checks read per commit
+const MAX_CHECK_CONTEXTS: u32 = 100;
+/// Wait after a rate limit response that doesn't tell when the limit resets
+const DEFAULT_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// GitHub check run conclusions counted as failed
+const FAILED_CHECK_CONCLUSIONS: &[&str] = &["FAILURE", "TIMED_OUT", "CANCELLED", "ACTION_REQUIRED", "STARTUP_FAILURE"];
+/// GitHub commit status states counted as failed
+const FAILED_STATUS_STATES: &[&str] = &["FAILURE", "ERROR"];
+
+/// The provider rejected a request because of its rate limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimited {
+  pub retry_after: Duration,
+}
+
+impl fmt::Display for RateLimited {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Rate limited by the hosting provider, retry in {}s", self.retry_after.as_secs())
+  }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// Queries the CI status of commits on one remote
+pub struct CiClient {
+  hosting: RemoteHostingConfig,
+  token: Option<String>,
+  client: reqwest::Client,
+}
+
+/// Client for the remote if CI status is enabled and the remote is on a known provider
+#[instrument(skip(git_executor))]
+pub fn load_ci_client(git_executor: &GitCommandExecutor, repository_path: &str, remote_name: &str) -> Result<Option<CiClient>> {
+  if !get_config_bool(git_executor, repository_path, CI_STATUS_CONFIG_KEY)?.unwrap_or(false) {
+    return Ok(None);
+  }
+  let Some(hosting) = resolve_remote_hosting_config(git_executor, repository_path, remote_name)? else {
+    debug!(remote_name, "Remote is not on a known hosting provider, skipping CI status");
+    return Ok(None);
+  };
+  let token = get_host_token(git_executor, repository_path, &hosting)?;
+  CiClient::new(hosting, token).map(Some)
+}
+
+impl CiClient {
+  pub fn new(hosting: RemoteHostingConfig, token: Option<String>) -> Result<Self> {
+    let client = build_http_client(hosting.ca_cert_path.as_deref())?;
+    Ok(Self { hosting, token, client })
+  }
+
+  /// CI statuses of the given commits, keyed by commit hash. Commits without CI are left out.
+  /// Fails with [`RateLimited`] if the provider rejected a request because of its rate limit, with `ErrorCode::Offline` without network.
+  #[instrument(skip(self, commits), fields(provider = ?self.hosting.provider, commit_count = commits.len()))]
+  pub async fn fetch_ci_statuses(&self, commits: &[String]) -> Result<HashMap<String, CiStatus>> {
+    if commits.is_empty() {
+      return Ok(HashMap::new());
+    }
+    ensure_url_reachable_async(self.hosting.api_base_url.clone()).await?;
+    match self.hosting.provider {
+      HostingProviderKind::GitHub => self.fetch_github(commits).await,
+      HostingProviderKind::GitLab => self.fetch_gitlab(commits).await,
+    }
+  }
+
+  async fn fetch_github(&self, commits: &[String]) -> Result<HashMap<String, CiStatus>> {
+    // The GraphQL API doesn't allow anonymous requests
+    let Some(token) = &self.token else {
+      debug!("No GitHub token in the credential helper, skipping CI status");
+      return Ok(HashMap::new());
+    };
+    let (owner, name) = self
+      .hosting
+      .project_path
+      .split_once('/')
+      .ok_or_else(|| anyhow!("Invalid GitHub repository path {}", self.hosting.project_path))?;
+    let endpoint = github_graphql_url(&self.hosting.api_base_url);
+
+    let mut statuses = HashMap::new();
+    for chunk in commits.chunks(GITHUB_BRANCHES_PER_QUERY) {
+      let mut variables = json!({"owner": owner, "name": name});
+      for (index, commit) in chunk.iter().enumerate() {
+        variables[format!("c{index}")] = json!(commit);
+      }
+      let request = self
+        .client
+        .post(&endpoint)
+        .bearer_auth(token)
+        .json(&json!({"query": github_ci_query(chunk.len()), "variables": variables}));
+      let response = self.send(request).await?;
+      statuses.extend(parse_github_ci_response(&response, chunk, &self.hosting.web_url)?);
+    }
+    Ok(statuses)
+  }
+
+  async fn fetch_gitlab(&self, commits: &[String]) -> Result<HashMap<String, CiStatus>> {
+    let project_url = gitlab_project_url(&self.hosting)?;
+
+    let mut statuses = HashMap::new();
+    for commit in commits {
+      let pipelines = self.gitlab_get(&format!("{project_url}/pipelines"), &[("sha", commit.as_str()), ("per_page", "1")]).await?;
+      let Some(pipeline) = pipelines.as_array().and_then(|pipelines| pipelines.first()) else {
+        continue;
+      };
+      let state = gitlab_pipeline_state(pipeline["status"].as_str().unwrap_or_default());
+      let failed_jobs = if state == CiState::Failure {
+        let id = pipeline["id"].as_u64().ok_or_else(|| anyhow!("Pipeline without ID"))?;
+        self
+          .gitlab_get(&format!("{project_url}/pipelines/{id}/jobs"), &[("scope[]", "failed"), ("per_page", "100")])
+          .await?
+      } else {
+        Value::Null
+      };
+      statuses.insert(commit.clone(), parse_gitlab_pipeline(commit, pipeline, &failed_jobs));
+    }
+    Ok(statuses)
+  }
+
+  async fn gitlab_get(&self, url: &str, query: &[(&str, &str)]) -> Result<Value> {
+    let mut request = self.client.get(url).query(query);
+    if let Some(token) = &self.token {
+      request = request.bearer_auth(token);
+    }
+    self.send(request).await
+  }
+
+  /// Send a request and parse the JSON response, a rate limit response fails with [`RateLimited`]
+  async fn send(&self, request: reqwest::RequestBuilder) -> Result<Value> {
+    let response = request.send().await?;
+    let header = |name: &str| response.headers().get(name).and_then(|value| value.to_str().ok()).map(str::to_string);
+    let status = response.status().as_u16();
+    // GitHub answers 403 once the primary rate limit is used up, and with `Retry-After` for its secondary rate limits
+    if status == 429 || (status == 403 && (header("x-ratelimit-remaining").as_deref() == Some("0") || header("retry-after").is_some())) {
+      let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+      let retry_after = rate_limit_wait(
+        header("retry-after").as_deref(),
+        header("x-ratelimit-reset").or_else(|| header("ratelimit-reset")).as_deref(),
+        now,
+      );
+      return Err(RateLimited { retry_after }.into());
+    }
+    Ok(check_status(response, &self.hosting)?.json().await?)
+  }
+}
+
+/// Wait until the rate limit resets, from `Retry-After` (seconds) or the reset time (Unix seconds)
+pub(crate) fn rate_limit_wait(retry_after: Option<&str>, reset_time: Option<&str>, now: u64) -> Duration {
+  if let Some(seconds) = retry_after.and_then(|value| value.trim().parse::<u64>().ok()) {
+    return Duration::from_secs(seconds);
+  }
+  match reset_time.and_then(|value| value.trim().parse::<u64>().ok()) {
+    Some(reset_time) if reset_time > now => Duration::from_secs(reset_time - now),
+    _ => DEFAULT_RATE_LIMIT_WAIT,
+  }
+}
+
+/// Query looking up the check rollup of `commit_count` commits passed as `$c0`, `$c1`, …
+pub(crate) fn github_ci_query(commit_count: usize) -> String {
+  let variables: String = (0..commit_count).map(|index| format!(", $c{index}: GitObjectID!")).collect();
+  let lookups: String = (0..commit_count).map(|index| format!(" c{index}: object(oid: $c{index}) {{ ...ci }}")).collect();
+  format!(
+    "query($owner: String!, $name: String!{variables}) {{ repository(owner: $owner, name: $name) {{{lookups} }} }} \
+     fragment ci on Commit {{ statusCheckRollup {{ state contexts(first: {MAX_CHECK_CONTEXTS}) {{ nodes {{ __typename \
+     ... on CheckRun {{ name conclusion detailsUrl }} ... on StatusContext {{ context state targetUrl }} }} }} }} }}"
+  )
+}
+
+pub(crate) fn parse_github_ci_response(response: &Value, commits: &[String], web_url: &str) -> Result<HashMap<String, CiStatus>> {
+  let repository = &response["data"]["repository"];
+  if repository.is_null() {
+    let message = response["errors"][0]["message"].as_str().unwrap_or("no repository in the response");
+    bail!("GitHub GraphQL query failed: {message}");
+  }
+
+  let mut statuses = HashMap::new();
+  for (index, commit) in commits.iter().enumerate() {
+    let rollup = &repository[format!("c{index}")]["statusCheckRollup"];
+    // Unknown commit or no checks
+    let Some(rollup_state) = rollup["state"].as_str() else {
+      continue;
+    };
+    let state = match rollup_state {
+      "SUCCESS" => CiState::Success,
+      "FAILURE" | "ERROR" => CiState::Failure,
+      // PENDING, EXPECTED
+      _ => CiState::Pending,
+    };
+    let contexts = rollup["contexts"]["nodes"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let failed: Vec<(&str, Option<&str>)> = contexts
+      .iter()
+      .filter_map(|context| match context["__typename"].as_str()? {
+        "CheckRun" if FAILED_CHECK_CONCLUSIONS.contains(&context["conclusion"].as_str()?) => Some((context["name"].as_str()?, context["detailsUrl"].as_str())),
+        "StatusContext" if FAILED_STATUS_STATES.contains(&context["state"].as_str()?) => Some((context["context"].as_str()?, context["targetUrl"].as_str())),
+        _ => None,
+      })
+      .collect();
+    let url = failed
+      .iter()
+      .find_map(|(_, url)| *url)
+      .map_or_else(|| format!("{web_url}/commit/{commit}/checks"), str::to_string);
+    statuses.insert(
+      commit.clone(),
+      CiStatus {
+        commit_hash: commit.clone(),
+        state,
+        failed_checks: failed.iter().map(|(name, _)| name.to_string()).collect(),
+        url,
+      },
+    );
+  }
+  Ok(statuses)
+}
+
+pub(crate) fn gitlab_pipeline_state(status: &str) -> CiState {
+  match status {
+    "success" => CiState::Success,
+    "failed" => CiState::Failure,
+    // Nothing ran
+    "canceled" | "skipped" => CiState::Cancelled,
+    // created, waiting_for_resource, preparing, pending, running, manual, scheduled
+    _ => CiState::Pending,
+  }
+}
+
+/// Status of a commit from its latest pipeline and the failed jobs of the pipeline
+pub(crate) fn parse_gitlab_pipeline(commit: &str, pipeline: &Value, failed_jobs: &Value) -> CiStatus {
+  let failed_jobs = failed_jobs.as_array().map(Vec::as_slice).unwrap_or_default();
+  CiStatus {
+    commit_hash: commit.to_string(),
+    state: gitlab_pipeline_state(pipeline["status"].as_str().unwrap_or_default()),
+    failed_checks: failed_jobs.iter().filter_map(|job| job["name"].as_str()).map(str::to_string).collect(),
+    url: pipeline["web_url"].as_str().unwrap_or_default().to_string(),
+  }
+}
+
+/// Fill in the CI statuses of pushed branches, pushed as `<prefix>/virtual/<branch name>` with the given remote heads.
+/// Only commits without a final status in the cache are queried; while rate limited, the cached statuses are used.
+/// The cache is not locked while querying, a sync reads it meanwhile.
+pub async fn attach_ci_statuses(
+  client: &CiClient,
+  cache: &Mutex<CiStatusCache>,
+  branch_prefix: &str,
+  remote_heads: &RemoteHeads,
+  updates: &mut [RemoteStatusUpdate],
+) -> Result<()> {
+  let remote_branch = |branch_name: &str| format!("{branch_prefix}/virtual/{branch_name}");
+  let heads: Vec<(usize, &str)> = updates
+    .iter()
+    .enumerate()
+    .filter(|(_, update)| update.remote_exists)
+    .filter_map(|(index, update)| Some((index, remote_heads.get(&remote_branch(&update.branch_name))?.as_str())))
+    .collect();
+  let lock_cache = || cache.lock().unwrap_or_else(PoisonError::into_inner);
+
+  let now = Instant::now();
+  let mut result = Ok(());
+  let commits = {
+    let cache = lock_cache();
+    if cache.is_rate_limited(now) {
+      debug!("Rate limited by the hosting provider, using cached CI statuses");
+      None
+    } else {
+      Some(cache.commits_to_query(heads.iter().map(|(_, commit)| *commit)))
+    }
+  };
+  if let Some(commits) = commits {
+    match client.fetch_ci_statuses(&commits).await {
+      Ok(statuses) => {
+        let mut cache = lock_cache();
+        statuses.into_values().for_each(|status| cache.insert(status));
+      }
+      Err(e) => match e.downcast_ref::<RateLimited>() {
+        Some(rate_limited) => {
+          warn!(
+            retry_after_secs = rate_limited.retry_after.as_secs(),
+            "Rate limited by the hosting provider, pausing CI status queries"
+          );
+          lock_cache().set_rate_limited(now, rate_limited.retry_after);
+        }
+        None => result = Err(e),
+      },
+    }
+  }
+
+  let mut cache = lock_cache();
+  cache.retain_commits(&heads.iter().map(|(_, commit)| *commit).collect());
+  for (index, commit) in heads {
+    updates[index].ci_status = cache.get(commit).cloned();
+  }
+  result
+}
@@ -0,0 +1,115 @@
+use crate::ci_status::{github_ci_query, load_ci_client, parse_github_ci_response, parse_gitlab_pipeline, rate_limit_wait};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use std::time::Duration;
+use sync_core::ci_status::CI_STATUS_CONFIG_KEY;
+use sync_types::{CiState, CiStatus};
+use test_utils::git_test_utils::TestRepo;
+
+const WEB_URL: &str = "https://github.com/owner/repo";
+
+#[test]
+fn test_github_ci_query_declares_every_commit() {
+  let query = github_ci_query(2);
+  assert!(query.starts_with("query($owner: String!, $name: String!, $c0: GitObjectID!, $c1: GitObjectID!)"), "{query}");
+  assert!(query.contains("c0: object(oid: $c0) { ...ci }"), "{query}");
+  assert!(query.contains("c1: object(oid: $c1) { ...ci }"), "{query}");
+  assert!(!query.contains("$c2"), "{query}");
+}
+
+#[test]
+fn test_parse_github_ci_response() {
+  let response = json!({
+    "data": {
+      "repository": {
+        "c0": {
+          "statusCheckRollup": {
+            "state": "FAILURE",
+            "contexts": {"nodes": [
+              {"__typename": "CheckRun", "name": "build", "conclusion": "SUCCESS", "detailsUrl": "https://github.com/owner/repo/runs/1"},
+              {"__typename": "CheckRun", "name": "test", "conclusion": "FAILURE", "detailsUrl": "https://github.com/owner/repo/runs/2"},
+              {"__typename": "StatusContext", "context": "ci/legacy", "state": "ERROR", "targetUrl": null}
+            ]}
+          }
+        },
+        "c1": {"statusCheckRollup": {"state": "PENDING", "contexts": {"nodes": []}}},
+        // Commit without checks
+        "c2": {"statusCheckRollup": null}
+      }
+    }
+  });
+  let commits = vec!["a1".to_string(), "b2".to_string(), "c3".to_string()];
+  let statuses = parse_github_ci_response(&response, &commits, WEB_URL).unwrap();
+  assert_eq!(statuses.len(), 2);
+  assert_eq!(
+    statuses["a1"],
+    CiStatus {
+      commit_hash: "a1".to_string(),
+      state: CiState::Failure,
+      failed_checks: vec!["test".to_string(), "ci/legacy".to_string()],
+      url: "https://github.com/owner/repo/runs/2".to_string(),
+    }
+  );
+  assert_eq!(
+    statuses["b2"],
+    CiStatus {
+      commit_hash: "b2".to_string(),
+      state: CiState::Pending,
+      failed_checks: Vec::new(),
+      url: "https://github.com/owner/repo/commit/b2/checks".to_string(),
+    }
+  );
+}
+
+#[test]
+fn test_parse_github_ci_response_with_errors() {
+  let response = json!({"data": {"repository": null}, "errors": [{"message": "Could not resolve to a Repository"}]});
+  let error = parse_github_ci_response(&response, &["a1".to_string()], WEB_URL).unwrap_err();
+  assert_eq!(error.to_string(), "GitHub GraphQL query failed: Could not resolve to a Repository");
+}
+
+#[test]
+fn test_parse_gitlab_pipeline() {
+  let pipeline = json!({"id": 12, "status": "failed", "web_url": "https://gitlab.com/group/project/-/pipelines/12"});
+  let failed_jobs = json!([{"name": "lint"}, {"name": "test"}]);
+  assert_eq!(
+    parse_gitlab_pipeline("a1", &pipeline, &failed_jobs),
+    CiStatus {
+      commit_hash: "a1".to_string(),
+      state: CiState::Failure,
+      failed_checks: vec!["lint".to_string(), "test".to_string()],
+      url: "https://gitlab.com/group/project/-/pipelines/12".to_string(),
+    }
+  );
+
+  let states: Vec<CiState> = ["success", "running", "manual", "canceled", "skipped"]
+    .iter()
+    .map(|status| parse_gitlab_pipeline("a1", &json!({"status": status}), &json!(null)).state)
+    .collect();
+  assert_eq!(states, vec![CiState::Success, CiState::Pending, CiState::Pending, CiState::Cancelled, CiState::Cancelled]);
+}
+
+#[test]
+fn test_rate_limit_wait() {
+  assert_eq!(rate_limit_wait(Some("120"), Some("2000"), 1000), Duration::from_secs(120));
+  assert_eq!(rate_limit_wait(None, Some("1300"), 1000), Duration::from_secs(300));
+  // A reset time in the past or no hint at all
+  assert_eq!(rate_limit_wait(None, Some("900"), 1000), Duration::from_secs(60));
+  assert_eq!(rate_limit_wait(None, None, 1000), Duration::from_secs(60));
+}
+
+#[test]
+fn test_ci_client_requires_opt_in() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.add_remote("origin", "https://github.com/owner/repo.git").unwrap();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  assert!(load_ci_client(&git_executor, repo_path, "origin").unwrap().is_none());
+
+  // Enabled, but the remote is not on a known provider
+  test_repo.set_config(CI_STATUS_CONFIG_KEY, "true").unwrap();
+  test_repo.add_remote("mirror", "ssh://git@example.com/repo.git").unwrap();
+  assert!(load_ci_client(&git_executor, repo_path, "mirror").unwrap().is_none());
+}
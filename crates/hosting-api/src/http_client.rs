@@ -0,0 +1,96 @@
+//! HTTP client shared by the hosting provider queries and the webhook, and the token for the provider API.
+//!
+//! The token is taken from the git credential helper for the remote host, i.e. the credentials git itself uses for HTTPS
+//! pushes. It is cached per host, so the helper isn't asked on every refresh of the remote status.
+
+use anyhow::{Context, Result};
+use git_executor::git_command_executor::GitCommandExecutor;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use sync_core::hosting_provider::RemoteHostingConfig;
+use tracing::debug;
+
+const USER_AGENT: &str = "branch-deck";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// A token stored in or removed from the credential helper is picked up after this time at the latest
+pub const TOKEN_CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Never let git or the credential helper ask the user, a missing token only limits what can be queried
+const NON_INTERACTIVE_ENV: &[(&str, &str)] = &[("GIT_TERMINAL_PROMPT", "0"), ("GIT_ASKPASS", ""), ("GCM_INTERACTIVE", "never")];
+
+/// Tokens by `protocol://host[:port]` with the time they were read, `None` if the credential helper has none
+static HOST_TOKENS: LazyLock<Mutex<HashMap<String, (Option<String>, Instant)>>> = LazyLock::new(Default::default);
+
+/// HTTP client with the timeouts and user agent of the app, trusting the extra CA certificates of a private instance
+pub fn build_http_client(ca_cert_path: Option<&str>) -> Result<reqwest::Client> {
+  let mut builder = reqwest::Client::builder().user_agent(USER_AGENT).connect_timeout(CONNECT_TIMEOUT).timeout(REQUEST_TIMEOUT);
+  if let Some(path) = ca_cert_path {
+    let pem = std::fs::read(path).with_context(|| format!("Failed to read CA certificates from {path}"))?;
+    for certificate in reqwest::Certificate::from_pem_bundle(&pem).with_context(|| format!("Invalid CA certificates in {path}"))? {
+      builder = builder.add_root_certificate(certificate);
+    }
+  }
+  builder.build().context("Failed to create HTTP client")
+}
+
+/// Token for the remote host from the git credential helper, `None` if none is stored. Cached for [`TOKEN_CACHE_TTL`].
+pub fn get_host_token(git_executor: &GitCommandExecutor, repository_path: &str, hosting: &RemoteHostingConfig) -> Result<Option<String>> {
+  let url = reqwest::Url::parse(&hosting.web_url).with_context(|| format!("Invalid web URL {}", hosting.web_url))?;
+  let key = token_key(&url, hosting);
+  if let Some((token, read_at)) = HOST_TOKENS.lock().unwrap_or_else(PoisonError::into_inner).get(&key)
+    && read_at.elapsed() < TOKEN_CACHE_TTL
+  {
+    return Ok(token.clone());
+  }
+
+  let token = read_host_token(git_executor, repository_path, &url, hosting);
+  HOST_TOKENS.lock().unwrap_or_else(PoisonError::into_inner).insert(key, (token.clone(), Instant::now()));
+  Ok(token)
+}
+
+/// Forget the cached token of the host, so the next request reads it again from the credential helper
+pub fn forget_host_token(hosting: &RemoteHostingConfig) {
+  if let Ok(url) = reqwest::Url::parse(&hosting.web_url) {
+    HOST_TOKENS.lock().unwrap_or_else(PoisonError::into_inner).remove(&token_key(&url, hosting));
+  }
+}
+
+/// Fail for an error status. A rejected token is forgotten, e.g. after it was revoked or replaced in the credential helper.
+pub fn check_status(response: reqwest::Response, hosting: &RemoteHostingConfig) -> Result<reqwest::Response> {
+  if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+    debug!(host = %hosting.host, "Token rejected by the hosting provider, it is read again for the next request");
+    forget_host_token(hosting);
+  }
+  Ok(response.error_for_status()?)
+}
+
+fn token_key(url: &reqwest::Url, hosting: &RemoteHostingConfig) -> String {
+  match url.port() {
+    Some(port) => format!("{}://{}:{port}", url.scheme(), hosting.host),
+    None => format!("{}://{}", url.scheme(), hosting.host),
+  }
+}
+
+fn read_host_token(git_executor: &GitCommandExecutor, repository_path: &str, url: &reqwest::Url, hosting: &RemoteHostingConfig) -> Option<String> {
+  let host = match url.port() {
+    Some(port) => format!("{}:{port}", hosting.host),
+    None => hosting.host.clone(),
+  };
+  let input = format!("protocol={}\nhost={host}\n\n", url.scheme());
+  let output = git_executor.execute_command_with_env_and_stdin(&["-c", "credential.interactive=false", "credential", "fill"], repository_path, NON_INTERACTIVE_ENV, &input);
+  // Without stored credentials git fails trying to prompt for them
+  let output = match output {
+    Ok(output) => output,
+    Err(e) => {
+      debug!(error = %e, "No credentials for the remote host in the credential helper");
+      return None;
+    }
+  };
+  output
+    .lines()
+    .find_map(|line| line.strip_prefix("password="))
+    .filter(|token| !token.is_empty())
+    .map(str::to_string)
+}
@@ -0,0 +1,37 @@
+use crate::http_client::{forget_host_token, get_host_token};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use std::fs;
+use sync_core::hosting_provider::{HostingProviderKind, RemoteHostingConfig};
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_host_token_is_cached() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repository_path = test_repo.path().to_str().unwrap();
+  // Records every call, so it can be checked how often the credential helper is asked
+  let calls_file = test_repo.path().join("credential-calls");
+  let helper = format!("!f() {{ echo \"$1\" >> '{}'; echo password=secret; }}; f", calls_file.to_str().unwrap().replace('\\', "/"));
+  test_repo.set_config("credential.helper", &helper).unwrap();
+  let hosting = RemoteHostingConfig {
+    remote_name: "origin".to_string(),
+    provider: HostingProviderKind::GitHub,
+    // Not shared with other tests, the cache is per process
+    host: "token-cache.example.com".to_string(),
+    project_path: "owner/repo".to_string(),
+    web_url: "https://token-cache.example.com/owner/repo".to_string(),
+    api_base_url: "https://token-cache.example.com/api/v3".to_string(),
+    ca_cert_path: None,
+    overridden: false,
+  };
+  let calls = || fs::read_to_string(&calls_file).unwrap_or_default().lines().count();
+
+  assert_eq!(get_host_token(&git_executor, repository_path, &hosting).unwrap().as_deref(), Some("secret"));
+  assert_eq!(get_host_token(&git_executor, repository_path, &hosting).unwrap().as_deref(), Some("secret"));
+  assert_eq!(calls(), 1);
+
+  forget_host_token(&hosting);
+  assert_eq!(get_host_token(&git_executor, repository_path, &hosting).unwrap().as_deref(), Some("secret"));
+  assert_eq!(calls(), 2);
+}
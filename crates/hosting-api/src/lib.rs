@@ -0,0 +1,13 @@
+pub mod ci_status;
+pub mod http_client;
+pub mod review_status;
+pub mod webhook;
+
+#[cfg(test)]
+mod ci_status_test;
+#[cfg(test)]
+mod http_client_test;
+#[cfg(test)]
+mod review_status_test;
+#[cfg(test)]
+mod webhook_test;
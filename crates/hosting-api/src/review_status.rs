@@ -0,0 +1,315 @@
+//! Review activity of pushed branches from the hosting provider, so the branch list doubles as a review inbox.
+//!
+//! Opt-in per repository (`branchdeck.reviewStatus`) for both the periodic refresh and `get_branch_review_status`, as
+//! it sends branch names to the provider API. The token is taken from the git credential helper, see
+//! [`crate::http_client::get_host_token`].
+//!
+//! Only pull requests from the repository itself count: a fork may have a branch of the same name.
+
+use crate::http_client::{build_http_client, check_status, get_host_token};
+use anyhow::{Context, Result, anyhow, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::git_config::get_config_bool;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use sync_core::hosting_provider::{HostingProviderKind, RemoteHostingConfig, resolve_remote_hosting_config};
+use sync_core::network::ensure_url_reachable_async;
+use sync_core::review_status::REVIEW_STATUS_CONFIG_KEY;
+use sync_types::{RemoteStatusUpdate, ReviewDecision, ReviewSummary};
+use tracing::{debug, instrument};
+
+/// Branches queried in one GraphQL request, each one adds a pull request lookup to the query cost
+pub const GITHUB_BRANCHES_PER_QUERY: usize = 50;
+/// Open pull requests read per branch name, including those from forks that are skipped
+const MAX_PULL_REQUESTS_PER_BRANCH: u32 = 10;
+/// Review threads read per pull request
+const MAX_REVIEW_THREADS: u32 = 100;
+/// Latest reviews read per pull request, one per reviewer
+const MAX_REVIEWS: u32 = 100;
+
+/// Queries open pull requests of branches on one remote
+pub struct ReviewClient {
+  hosting: RemoteHostingConfig,
+  token: Option<String>,
+  client: reqwest::Client,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct GetBranchReviewStatusParams {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  /// Virtual branch names (without the prefix)
+  pub branch_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct BranchReviewStatus {
+  pub branch_name: String,
+  /// `None` if the branch has no open pull request
+  pub review: Option<ReviewSummary>,
+}
+
+/// Client for the remote if review status is enabled and the remote is on a known provider
+#[instrument(skip(git_executor))]
+pub fn load_review_client(git_executor: &GitCommandExecutor, repository_path: &str, remote_name: &str) -> Result<Option<ReviewClient>> {
+  if !get_config_bool(git_executor, repository_path, REVIEW_STATUS_CONFIG_KEY)?.unwrap_or(false) {
+    return Ok(None);
+  }
+  let Some(hosting) = resolve_remote_hosting_config(git_executor, repository_path, remote_name)? else {
+    debug!(remote_name, "Remote is not on a known hosting provider, skipping review status");
+    return Ok(None);
+  };
+  let token = get_host_token(git_executor, repository_path, &hosting)?;
+  ReviewClient::new(hosting, token).map(Some)
+}
+
+/// Project URL in the GitLab REST API, the project path is a single, encoded path segment
+pub(crate) fn gitlab_project_url(hosting: &RemoteHostingConfig) -> Result<reqwest::Url> {
+  let mut project_url = reqwest::Url::parse(&hosting.api_base_url).with_context(|| format!("Invalid API base URL {}", hosting.api_base_url))?;
+  project_url
+    .path_segments_mut()
+    .map_err(|_| anyhow!("Invalid API base URL {}", hosting.api_base_url))?
+    .push("projects")
+    .push(&hosting.project_path);
+  Ok(project_url)
+}
+
+impl ReviewClient {
+  pub fn new(hosting: RemoteHostingConfig, token: Option<String>) -> Result<Self> {
+    let client = build_http_client(hosting.ca_cert_path.as_deref())?;
+    Ok(Self { hosting, token, client })
+  }
+
+  /// Review summaries of the open pull requests of the given remote branches, keyed by branch name.
+  /// Branches without an open pull request are left out. Fails with `ErrorCode::Offline` without network.
+  #[instrument(skip(self, branches), fields(provider = ?self.hosting.provider, branch_count = branches.len()))]
+  pub async fn fetch_review_summaries(&self, branches: &[String]) -> Result<HashMap<String, ReviewSummary>> {
+    if branches.is_empty() {
+      return Ok(HashMap::new());
+    }
+    ensure_url_reachable_async(self.hosting.api_base_url.clone()).await?;
+    match self.hosting.provider {
+      HostingProviderKind::GitHub => self.fetch_github(branches).await,
+      HostingProviderKind::GitLab => self.fetch_gitlab(branches).await,
+    }
+  }
+
+  async fn fetch_github(&self, branches: &[String]) -> Result<HashMap<String, ReviewSummary>> {
+    // The GraphQL API doesn't allow anonymous requests
+    let Some(token) = &self.token else {
+      debug!("No GitHub token in the credential helper, skipping review status");
+      return Ok(HashMap::new());
+    };
+    let (owner, name) = self
+      .hosting
+      .project_path
+      .split_once('/')
+      .ok_or_else(|| anyhow!("Invalid GitHub repository path {}", self.hosting.project_path))?;
+    let endpoint = github_graphql_url(&self.hosting.api_base_url);
+
+    let mut summaries = HashMap::new();
+    for chunk in branches.chunks(GITHUB_BRANCHES_PER_QUERY) {
+      let mut variables = json!({"owner": owner, "name": name});
+      for (index, branch) in chunk.iter().enumerate() {
+        variables[format!("b{index}")] = json!(branch);
+      }
+      let response = self
+        .client
+        .post(&endpoint)
+        .bearer_auth(token)
+        .json(&json!({"query": github_query(chunk.len()), "variables": variables}))
+        .send()
+        .await?;
+      let response: Value = check_status(response, &self.hosting)?.json().await?;
+      summaries.extend(parse_github_response(&response, chunk, owner)?);
+    }
+    Ok(summaries)
+  }
+
+  async fn fetch_gitlab(&self, branches: &[String]) -> Result<HashMap<String, ReviewSummary>> {
+    let project_url = gitlab_project_url(&self.hosting)?;
+
+    let mut summaries = HashMap::new();
+    for branch in branches {
+      let merge_requests = self
+        .gitlab_get(
+          &format!("{project_url}/merge_requests"),
+          &[
+            ("source_branch", branch.as_str()),
+            ("state", "opened"),
+            ("per_page", &MAX_PULL_REQUESTS_PER_BRANCH.to_string()),
+          ],
+        )
+        .await?;
+      let Some(merge_request) = find_gitlab_merge_request(&merge_requests) else {
+        continue;
+      };
+      let iid = merge_request["iid"].as_u64().ok_or_else(|| anyhow!("Merge request without IID"))?;
+      let discussions = self.gitlab_get(&format!("{project_url}/merge_requests/{iid}/discussions"), &[("per_page", "100")]).await?;
+      let approvals = self.gitlab_get(&format!("{project_url}/merge_requests/{iid}/approvals"), &[]).await?;
+      summaries.insert(branch.clone(), parse_gitlab_merge_request(merge_request, &discussions, &approvals)?);
+    }
+    Ok(summaries)
+  }
+
+  async fn gitlab_get(&self, url: &str, query: &[(&str, &str)]) -> Result<Value> {
+    let mut request = self.client.get(url).query(query);
+    if let Some(token) = &self.token {
+      request = request.bearer_auth(token);
+    }
+    Ok(check_status(request.send().await?, &self.hosting)?.json().await?)
+  }
+}
+
+/// GraphQL endpoint next to the REST API base URL
+pub(crate) fn github_graphql_url(api_base_url: &str) -> String {
+  match api_base_url.strip_suffix("/api/v3") {
+    // GitHub Enterprise Server
+    Some(web_base_url) => format!("{web_base_url}/api/graphql"),
+    None => format!("{api_base_url}/graphql"),
+  }
+}
+
+/// Query looking up the open pull request of `branch_count` branches passed as `$b0`, `$b1`, …
+pub(crate) fn github_query(branch_count: usize) -> String {
+  let variables: String = (0..branch_count).map(|index| format!(", $b{index}: String!")).collect();
+  let lookups: String = (0..branch_count)
+    .map(|index| format!(" b{index}: pullRequests(headRefName: $b{index}, states: OPEN, first: {MAX_PULL_REQUESTS_PER_BRANCH}) {{ ...review }}"))
+    .collect();
+  format!(
+    "query($owner: String!, $name: String!{variables}) {{ repository(owner: $owner, name: $name) {{{lookups} }} }} \
+     fragment review on PullRequestConnection {{ nodes {{ number url reviewDecision headRepositoryOwner {{ login }} comments {{ totalCount }} \
+     reviewThreads(first: {MAX_REVIEW_THREADS}) {{ nodes {{ isResolved comments {{ totalCount }} }} }} \
+     latestOpinionatedReviews(first: {MAX_REVIEWS}) {{ nodes {{ state }} }} }} }}"
+  )
+}
+
+/// Summaries of the pull requests found for the branches, from branches of `owner`'s repository only
+pub(crate) fn parse_github_response(response: &Value, branches: &[String], owner: &str) -> Result<HashMap<String, ReviewSummary>> {
+  let repository = &response["data"]["repository"];
+  if repository.is_null() {
+    let message = response["errors"][0]["message"].as_str().unwrap_or("no repository in the response");
+    bail!("GitHub GraphQL query failed: {message}");
+  }
+
+  let mut summaries = HashMap::new();
+  for (index, branch) in branches.iter().enumerate() {
+    let pull_requests = repository[format!("b{index}")]["nodes"].as_array().map(Vec::as_slice).unwrap_or_default();
+    // The owner of a deleted fork is null
+    let Some(pull_request) = pull_requests
+      .iter()
+      .find(|pull_request| pull_request["headRepositoryOwner"]["login"].as_str().is_some_and(|login| login.eq_ignore_ascii_case(owner)))
+    else {
+      continue;
+    };
+    let threads = pull_request["reviewThreads"]["nodes"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let thread_comment_count: u64 = threads.iter().filter_map(|thread| thread["comments"]["totalCount"].as_u64()).sum();
+    let comment_count = pull_request["comments"]["totalCount"].as_u64().unwrap_or(0) + thread_comment_count;
+    let reviews = pull_request["latestOpinionatedReviews"]["nodes"].as_array().map(Vec::as_slice).unwrap_or_default();
+    let review_decision = match pull_request["reviewDecision"].as_str() {
+      Some("APPROVED") => Some(ReviewDecision::Approved),
+      Some("CHANGES_REQUESTED") => Some(ReviewDecision::ChangesRequested),
+      Some("REVIEW_REQUIRED") => Some(ReviewDecision::ReviewRequired),
+      // No required reviews
+      _ => None,
+    };
+    summaries.insert(
+      branch.clone(),
+      ReviewSummary {
+        number: pull_request["number"].as_u64().ok_or_else(|| anyhow!("Pull request without number"))?,
+        url: pull_request["url"].as_str().unwrap_or_default().to_string(),
+        comment_count: comment_count as u32,
+        unresolved_thread_count: threads.iter().filter(|thread| thread["isResolved"] == false).count() as u32,
+        approval_count: reviews.iter().filter(|review| review["state"] == "APPROVED").count() as u32,
+        review_decision,
+      },
+    );
+  }
+  Ok(summaries)
+}
+
+/// First merge request whose source branch is in the project itself rather than in a fork
+pub(crate) fn find_gitlab_merge_request(merge_requests: &Value) -> Option<&Value> {
+  merge_requests
+    .as_array()?
+    .iter()
+    .find(|merge_request| merge_request["source_project_id"].is_u64() && merge_request["source_project_id"] == merge_request["project_id"])
+}
+
+/// Summary of a merge request from its `user_notes_count`, its discussions and its approvals; a discussion is unresolved
+/// while any of its resolvable notes is unresolved
+pub(crate) fn parse_gitlab_merge_request(merge_request: &Value, discussions: &Value, approvals: &Value) -> Result<ReviewSummary> {
+  let discussions = discussions.as_array().map(Vec::as_slice).unwrap_or_default();
+  let unresolved_thread_count = discussions
+    .iter()
+    .filter(|discussion| {
+      discussion["notes"]
+        .as_array()
+        .is_some_and(|notes| notes.iter().any(|note| note["resolvable"] == true && note["resolved"] == false))
+    })
+    .count();
+  let approval_count = approvals["approved_by"].as_array().map_or(0, Vec::len);
+  let approvals_left = approvals["approvals_left"].as_u64().unwrap_or(0);
+  let review_decision = if merge_request["detailed_merge_status"] == "requested_changes" {
+    Some(ReviewDecision::ChangesRequested)
+  } else if approvals_left > 0 {
+    Some(ReviewDecision::ReviewRequired)
+  } else if approval_count > 0 || approvals["approvals_required"].as_u64().unwrap_or(0) > 0 {
+    Some(ReviewDecision::Approved)
+  } else {
+    // No approval rules and no approvals
+    None
+  };
+  Ok(ReviewSummary {
+    number: merge_request["iid"].as_u64().ok_or_else(|| anyhow!("Merge request without IID"))?,
+    url: merge_request["web_url"].as_str().unwrap_or_default().to_string(),
+    comment_count: merge_request["user_notes_count"].as_u64().unwrap_or(0) as u32,
+    unresolved_thread_count: unresolved_thread_count as u32,
+    approval_count: approval_count as u32,
+    review_decision,
+  })
+}
+
+/// Fill in the review summaries of pushed branches, pushed as `<prefix>/virtual/<branch name>`
+pub async fn attach_review_summaries(client: &ReviewClient, branch_prefix: &str, updates: &mut [RemoteStatusUpdate]) -> Result<()> {
+  let remote_branch = |branch_name: &str| format!("{branch_prefix}/virtual/{branch_name}");
+  let pushed: Vec<String> = updates
+    .iter()
+    .filter(|update| update.remote_exists)
+    .map(|update| remote_branch(&update.branch_name))
+    .collect();
+  let mut summaries = client.fetch_review_summaries(&pushed).await?;
+  for update in updates.iter_mut() {
+    update.review = summaries.remove(&remote_branch(&update.branch_name));
+  }
+  Ok(())
+}
+
+/// Review status of the given virtual branches, queried on demand, e.g. right after a push.
+/// Every branch is listed, without a review if it has no open pull request or there is no client for the remote
+/// (review status is not enabled or the remote is not on a known provider).
+#[instrument(skip(client, branch_names), fields(branch_count = branch_names.len()))]
+pub async fn fetch_branch_review_status(client: Option<&ReviewClient>, branch_prefix: &str, branch_names: Vec<String>) -> Result<Vec<BranchReviewStatus>> {
+  let remote_branch = |branch_name: &str| format!("{branch_prefix}/virtual/{branch_name}");
+  let mut summaries = match client {
+    Some(client) => {
+      let branches: Vec<String> = branch_names.iter().map(|branch_name| remote_branch(branch_name)).collect();
+      client.fetch_review_summaries(&branches).await?
+    }
+    None => HashMap::new(),
+  };
+  Ok(
+    branch_names
+      .into_iter()
+      .map(|branch_name| BranchReviewStatus {
+        review: summaries.remove(&remote_branch(&branch_name)),
+        branch_name,
+      })
+      .collect(),
+  )
+}
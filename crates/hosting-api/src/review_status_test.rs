@@ -0,0 +1,156 @@
+use crate::review_status::{
+  BranchReviewStatus, fetch_branch_review_status, find_gitlab_merge_request, github_graphql_url, github_query, load_review_client, parse_github_response,
+  parse_gitlab_merge_request,
+};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use sync_core::review_status::REVIEW_STATUS_CONFIG_KEY;
+use sync_types::{ReviewDecision, ReviewSummary};
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_github_graphql_url() {
+  assert_eq!(github_graphql_url("https://api.github.com"), "https://api.github.com/graphql");
+  assert_eq!(github_graphql_url("https://github.example.com/api/v3"), "https://github.example.com/api/graphql");
+}
+
+#[test]
+fn test_github_query_declares_every_branch() {
+  let query = github_query(2);
+  assert!(query.starts_with("query($owner: String!, $name: String!, $b0: String!, $b1: String!)"), "{query}");
+  assert!(query.contains("b0: pullRequests(headRefName: $b0, states: OPEN, first: 10)"), "{query}");
+  assert!(query.contains("b1: pullRequests(headRefName: $b1, states: OPEN, first: 10)"), "{query}");
+  assert!(!query.contains("$b2"), "{query}");
+}
+
+#[test]
+fn test_parse_github_response() {
+  let response = json!({
+    "data": {
+      "repository": {
+        "b0": {
+          "nodes": [{
+            // Same-named branch of a fork
+            "number": 43,
+            "url": "https://github.com/someone/repo/pull/43",
+            "headRepositoryOwner": {"login": "someone"},
+            "reviewDecision": "APPROVED",
+            "comments": {"totalCount": 0},
+            "reviewThreads": {"nodes": []},
+            "latestOpinionatedReviews": {"nodes": []}
+          }, {
+            "number": 42,
+            "url": "https://github.com/owner/repo/pull/42",
+            "headRepositoryOwner": {"login": "Owner"},
+            "reviewDecision": "CHANGES_REQUESTED",
+            "comments": {"totalCount": 2},
+            "reviewThreads": {"nodes": [
+              {"isResolved": false, "comments": {"totalCount": 3}},
+              {"isResolved": true, "comments": {"totalCount": 1}},
+              {"isResolved": false, "comments": {"totalCount": 1}}
+            ]},
+            "latestOpinionatedReviews": {"nodes": [{"state": "APPROVED"}, {"state": "CHANGES_REQUESTED"}, {"state": "APPROVED"}]}
+          }]
+        },
+        "b1": {"nodes": [{"number": 44, "url": "https://github.com/someone/repo/pull/44", "headRepositoryOwner": {"login": "someone"}}]}
+      }
+    }
+  });
+  let branches = vec!["user/virtual/auth".to_string(), "user/virtual/ui".to_string()];
+  let summaries = parse_github_response(&response, &branches, "owner").unwrap();
+  assert_eq!(summaries.len(), 1);
+  assert_eq!(
+    summaries["user/virtual/auth"],
+    ReviewSummary {
+      number: 42,
+      url: "https://github.com/owner/repo/pull/42".to_string(),
+      comment_count: 7,
+      unresolved_thread_count: 2,
+      approval_count: 2,
+      review_decision: Some(ReviewDecision::ChangesRequested),
+    }
+  );
+}
+
+#[test]
+fn test_parse_github_response_with_errors() {
+  let response = json!({"data": {"repository": null}, "errors": [{"message": "Could not resolve to a Repository"}]});
+  let error = parse_github_response(&response, &["user/virtual/auth".to_string()], "owner").unwrap_err();
+  assert_eq!(error.to_string(), "GitHub GraphQL query failed: Could not resolve to a Repository");
+}
+
+#[test]
+fn test_find_gitlab_merge_request() {
+  let merge_requests = json!([
+    // From a fork
+    {"iid": 8, "project_id": 1, "source_project_id": 2},
+    {"iid": 7, "project_id": 1, "source_project_id": 1}
+  ]);
+  assert_eq!(find_gitlab_merge_request(&merge_requests).unwrap()["iid"], 7);
+  assert!(find_gitlab_merge_request(&json!([{"iid": 8, "project_id": 1, "source_project_id": 2}])).is_none());
+  assert!(find_gitlab_merge_request(&json!([])).is_none());
+}
+
+#[test]
+fn test_parse_gitlab_merge_request() {
+  let merge_request = json!({"iid": 7, "web_url": "https://gitlab.com/group/project/-/merge_requests/7", "user_notes_count": 5});
+  let discussions = json!([
+    // Plain comment
+    {"notes": [{"resolvable": false, "resolved": false}]},
+    {"notes": [{"resolvable": true, "resolved": false}, {"resolvable": true, "resolved": false}]},
+    {"notes": [{"resolvable": true, "resolved": true}]}
+  ]);
+  let approvals = json!({"approved": false, "approvals_required": 2, "approvals_left": 1, "approved_by": [{"user": {"username": "reviewer"}}]});
+  assert_eq!(
+    parse_gitlab_merge_request(&merge_request, &discussions, &approvals).unwrap(),
+    ReviewSummary {
+      number: 7,
+      url: "https://gitlab.com/group/project/-/merge_requests/7".to_string(),
+      comment_count: 5,
+      unresolved_thread_count: 1,
+      approval_count: 1,
+      review_decision: Some(ReviewDecision::ReviewRequired),
+    }
+  );
+
+  // Without approval rules, a merge request is approved only once someone approves it
+  let approvals = json!({"approved": true, "approvals_required": 0, "approvals_left": 0, "approved_by": []});
+  let summary = parse_gitlab_merge_request(&merge_request, &json!([]), &approvals).unwrap();
+  assert_eq!((summary.approval_count, summary.review_decision), (0, None));
+}
+
+#[test]
+fn test_review_client_requires_opt_in() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.add_remote("origin", "https://github.com/owner/repo.git").unwrap();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  assert!(load_review_client(&git_executor, repo_path, "origin").unwrap().is_none());
+
+  // Enabled, but the remote is not on a known provider
+  test_repo.set_config(REVIEW_STATUS_CONFIG_KEY, "true").unwrap();
+  test_repo.add_remote("mirror", "ssh://git@example.com/repo.git").unwrap();
+  assert!(load_review_client(&git_executor, repo_path, "mirror").unwrap().is_none());
+}
+
+#[test(tokio::test)]
+async fn test_branch_review_status_without_client() {
+  // Review status is not enabled, every branch is listed without a review
+  let statuses = fetch_branch_review_status(None, "user", vec!["auth".to_string(), "ui".to_string()]).await.unwrap();
+  assert_eq!(
+    statuses,
+    vec![
+      BranchReviewStatus {
+        branch_name: "auth".to_string(),
+        review: None,
+      },
+      BranchReviewStatus {
+        branch_name: "ui".to_string(),
+        review: None,
+      },
+    ]
+  );
+}
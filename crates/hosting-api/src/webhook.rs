@@ -0,0 +1,162 @@
+//! Posting of sync summaries to the webhook configured with `branchdeck.webhookUrl`, see [`sync_core::webhook`].
+
+use crate::http_client::build_http_client;
+use anyhow::{Context, Result, anyhow, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::git_config::get_config_value;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::time::Duration;
+use sync_core::webhook::{SyncSummary, SyncSummaryNotifier, WEBHOOK_FORMAT_CONFIG_KEY, WEBHOOK_URL_CONFIG_KEY, WebhookFormat, summary_payload};
+use tracing::{debug, instrument, warn};
+
+/// Attempts per notification, failed attempts are retried with an exponential backoff
+const MAX_ATTEMPTS: u32 = 3;
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Parameters of `test_webhook_connection`
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct TestWebhookConnectionParams {
+  pub repository_path: String,
+  /// URL to test before it is saved, the configured webhook when not set
+  pub url: Option<String>,
+  /// Format for `url`, derived from the URL when not set
+  pub format: Option<WebhookFormat>,
+}
+
+/// Posts sync summaries to the configured webhook
+pub struct WebhookNotifier {
+  url: reqwest::Url,
+  format: WebhookFormat,
+  client: reqwest::Client,
+  retry_delay: Duration,
+}
+
+/// Notifier of the repository, `None` if no webhook is configured
+#[instrument(skip(git_executor))]
+pub fn load_webhook_notifier(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<Option<WebhookNotifier>> {
+  let Some(url) = get_config_value(git_executor, repository_path, WEBHOOK_URL_CONFIG_KEY)?.filter(|url| !url.trim().is_empty()) else {
+    return Ok(None);
+  };
+  let url = reqwest::Url::parse(url.trim()).with_context(|| format!("Invalid {WEBHOOK_URL_CONFIG_KEY}"))?;
+  let format = match get_config_value(git_executor, repository_path, WEBHOOK_FORMAT_CONFIG_KEY)? {
+    Some(value) => WebhookFormat::from_config_value(&value).ok_or_else(|| anyhow!("Unknown {WEBHOOK_FORMAT_CONFIG_KEY} '{value}'"))?,
+    None => WebhookFormat::detect(url.host_str().unwrap_or_default()),
+  };
+  WebhookNotifier::new(url, format).map(Some)
+}
+
+/// Posts the summary of every sync to the webhook of its repository, if one is configured
+pub struct WebhookSummaryNotifier {
+  git_executor: GitCommandExecutor,
+}
+
+impl WebhookSummaryNotifier {
+  pub fn new(git_executor: GitCommandExecutor) -> Self {
+    Self { git_executor }
+  }
+}
+
+impl SyncSummaryNotifier for WebhookSummaryNotifier {
+  fn notify(&self, summary: SyncSummary) {
+    // A broken webhook config must not fail the sync
+    let notifier = match load_webhook_notifier(&self.git_executor, &summary.repository_path) {
+      Ok(Some(notifier)) => notifier,
+      Ok(None) => return,
+      Err(e) => {
+        warn!(error = %e, "Failed to load webhook config");
+        return;
+      }
+    };
+    // Retries may take a while, nothing waits for the notification
+    tokio::spawn(async move {
+      if let Err(e) = notifier.send_summary(&summary).await {
+        warn!(error = %e, "Failed to send sync summary to the webhook");
+      }
+    });
+  }
+}
+
+/// Send a test message to the webhook, retried like a sync summary.
+/// Returns the format of the payload that was sent.
+#[instrument(skip(git_executor), fields(repository_path = %params.repository_path))]
+pub async fn test_webhook_connection_core(git_executor: &GitCommandExecutor, params: TestWebhookConnectionParams) -> Result<WebhookFormat> {
+  let notifier = match params.url.as_deref().map(str::trim).filter(|url| !url.is_empty()) {
+    Some(url) => {
+      let url = reqwest::Url::parse(url).context("Invalid webhook URL")?;
+      let format = params.format.unwrap_or_else(|| WebhookFormat::detect(url.host_str().unwrap_or_default()));
+      WebhookNotifier::new(url, format)?
+    }
+    None => load_webhook_notifier(git_executor, &params.repository_path)?.ok_or_else(|| anyhow!("No webhook is configured ({WEBHOOK_URL_CONFIG_KEY})"))?,
+  };
+  notifier.send_test_message().await?;
+  Ok(notifier.format())
+}
+
+impl WebhookNotifier {
+  pub fn new(url: reqwest::Url, format: WebhookFormat) -> Result<Self> {
+    let client = build_http_client(None)?;
+    Ok(Self {
+      url,
+      format,
+      client,
+      retry_delay: INITIAL_RETRY_DELAY,
+    })
+  }
+
+  /// Delay before the first retry, doubled for every following one
+  pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
+    self.retry_delay = retry_delay;
+    self
+  }
+
+  pub fn format(&self) -> WebhookFormat {
+    self.format
+  }
+
+  #[instrument(skip_all, fields(format = ?self.format))]
+  pub async fn send_summary(&self, summary: &SyncSummary) -> Result<()> {
+    self.post(&summary_payload(self.format, summary)).await
+  }
+
+  /// Send a test message, so the URL can be checked without running a sync
+  pub async fn send_test_message(&self) -> Result<()> {
+    let payload = match self.format {
+      WebhookFormat::Slack | WebhookFormat::Teams => json!({ "text": "Branch Deck webhook test" }),
+      WebhookFormat::Generic => json!({ "test": true }),
+    };
+    self.post(&payload).await
+  }
+
+  /// Post the payload, retrying connection errors, rate limiting and server errors
+  async fn post(&self, payload: &Value) -> Result<()> {
+    let mut delay = self.retry_delay;
+    let mut attempt = 1;
+    loop {
+      let error = match self.client.post(self.url.clone()).json(payload).send().await {
+        Ok(response) if response.status().is_success() => {
+          debug!(attempt, "Webhook notification sent");
+          return Ok(());
+        }
+        Ok(response) => {
+          let status = response.status();
+          let body = response.text().await.unwrap_or_default();
+          if !(status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS) {
+            bail!("Webhook rejected the notification with status {status}: {body}");
+          }
+          anyhow!("Webhook failed with status {status}: {body}")
+        }
+        Err(e) => anyhow!(e).context("Failed to send webhook notification"),
+      };
+      if attempt >= MAX_ATTEMPTS {
+        return Err(error);
+      }
+      warn!(attempt, error = %error, "Webhook notification failed, retrying");
+      tokio::time::sleep(delay).await;
+      delay *= 2;
+      attempt += 1;
+    }
+  }
+}
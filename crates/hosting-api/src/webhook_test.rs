@@ -0,0 +1,90 @@
+use crate::webhook::{WebhookNotifier, load_webhook_notifier};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::time::Duration;
+use sync_core::webhook::{SyncSummary, WebhookFormat};
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// Serves one response per status code in order, returns the URL and a handle joined to the received request bodies
+fn serve(statuses: &'static [u16]) -> (reqwest::Url, std::thread::JoinHandle<Vec<String>>) {
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let url = reqwest::Url::parse(&format!("http://{}/hook", listener.local_addr().unwrap())).unwrap();
+  let handle = std::thread::spawn(move || {
+    let mut bodies = Vec::new();
+    for status in statuses {
+      let (stream, _) = listener.accept().unwrap();
+      let mut reader = BufReader::new(stream);
+      let mut content_length = 0;
+      loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line.trim().is_empty() {
+          break;
+        }
+        if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+          content_length = value.trim().parse().unwrap();
+        }
+      }
+      let mut body = vec![0; content_length];
+      reader.read_exact(&mut body).unwrap();
+      bodies.push(String::from_utf8(body).unwrap());
+      let response = format!("HTTP/1.1 {status} Status\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+      reader.get_mut().write_all(response.as_bytes()).unwrap();
+    }
+    bodies
+  });
+  (url, handle)
+}
+
+fn summary() -> SyncSummary {
+  SyncSummary {
+    success: true,
+    created_branches: vec!["feature".to_string()],
+    failed_branches: vec!["fix".to_string()],
+    ..SyncSummary::new("/repo", "user")
+  }
+}
+
+#[test]
+fn test_load_webhook_notifier() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repository_path = test_repo.path().to_str().unwrap();
+  assert!(load_webhook_notifier(&git_executor, repository_path).unwrap().is_none());
+
+  test_repo.set_config("branchdeck.webhookUrl", "https://hooks.slack.com/services/T0/B0/x").unwrap();
+  assert_eq!(load_webhook_notifier(&git_executor, repository_path).unwrap().unwrap().format(), WebhookFormat::Slack);
+
+  test_repo.set_config("branchdeck.webhookFormat", "generic").unwrap();
+  assert_eq!(load_webhook_notifier(&git_executor, repository_path).unwrap().unwrap().format(), WebhookFormat::Generic);
+
+  test_repo.set_config("branchdeck.webhookFormat", "email").unwrap();
+  assert!(load_webhook_notifier(&git_executor, repository_path).is_err());
+}
+
+#[test(tokio::test)]
+async fn test_webhook_retries_server_errors() {
+  let (url, server) = serve(&[503, 200]);
+  let notifier = WebhookNotifier::new(url, WebhookFormat::Generic).unwrap().with_retry_delay(Duration::from_millis(10));
+
+  notifier.send_summary(&summary()).await.unwrap();
+
+  let bodies = server.join().unwrap();
+  assert_eq!(bodies.len(), 2);
+  assert_eq!(bodies[0], bodies[1]);
+  assert!(bodies[0].contains(r#""createdBranches":["feature"]"#), "{}", bodies[0]);
+}
+
+#[test(tokio::test)]
+async fn test_webhook_does_not_retry_rejected_notification() {
+  let (url, server) = serve(&[404]);
+  let notifier = WebhookNotifier::new(url, WebhookFormat::Slack).unwrap().with_retry_delay(Duration::from_millis(10));
+
+  let error = notifier.send_test_message().await.unwrap_err();
+
+  assert!(error.to_string().contains("404"), "{error}");
+  assert_eq!(server.join().unwrap(), vec![r#"{"text":"Branch Deck webhook test"}"#.to_string()]);
+}
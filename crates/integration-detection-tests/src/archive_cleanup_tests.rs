@@ -57,6 +57,9 @@ async fn test_detection_cleanup_respects_cached_status() -> Result<()> {
       confidence: IntegrationConfidence::High,
       commit_count: 1,
     },
+    lookback_limited: false,
+    detection_truncated: false,
+    evidence: None,
   };
   // Write integrated cache directly
 
@@ -69,6 +72,9 @@ async fn test_detection_cleanup_respects_cached_status() -> Result<()> {
       orphaned_count: 2,
       integrated_at: None,
     },
+    lookback_limited: false,
+    detection_truncated: false,
+    evidence: None,
   };
   // Write not-integrated cache directly
 
@@ -85,6 +91,8 @@ async fn test_detection_cleanup_respects_cached_status() -> Result<()> {
     progress: &progress,
     strategy: DetectionStrategy::Rebase,
     retention_days: 7,
+    lookback: Default::default(),
+    budget: Default::default(),
   };
   detect_integrated_branches(&git_executor, repo_path, "user", &baseline, cfg).await.unwrap();
 
@@ -97,3 +105,94 @@ async fn test_detection_cleanup_respects_cached_status() -> Result<()> {
 
   Ok(())
 }
+
+/// Retention policy: dry run only reports candidates, a real run deletes them; count rule keeps the newest branches
+#[test(tokio::test)]
+async fn test_apply_archive_retention_dry_run_and_max_count() -> Result<()> {
+  use branch_integration::archive::{ArchiveRetentionPolicy, RetentionCandidate, RetentionReason};
+  use pretty_assertions::assert_eq;
+  use sync_core::archive_retention::{ApplyArchiveRetentionParams, apply_archive_retention_core};
+
+  let (_upstream_repo, local_repo, git_executor) = crate::test_helpers::setup_test_repos();
+  let commit_a = local_repo.create_commit("A", "a.txt", "a");
+
+  let date = |days: i64| (chrono::Utc::now() - chrono::Duration::days(days)).format("%Y-%m-%d").to_string();
+  let newest = format!("user/archived/{}/newest", date(1));
+  let middle = format!("user/archived/{}/middle", date(3));
+  let oldest = format!("user/archived/{}/oldest", date(30));
+  for name in [&newest, &middle, &oldest] {
+    local_repo.create_branch_at(name, &commit_a).unwrap();
+  }
+
+  let repo_path = local_repo.path().to_str().unwrap().to_string();
+  let policy = ArchiveRetentionPolicy {
+    max_age_days: Some(14),
+    max_count_per_prefix: Some(1),
+    only_if_integrated: false,
+  };
+  let params = |dry_run: bool| ApplyArchiveRetentionParams {
+    repository_path: repo_path.clone(),
+    branch_prefix: "user".to_string(),
+    policy: Some(policy.clone()),
+    dry_run,
+  };
+
+  let expected_candidates = vec![
+    RetentionCandidate {
+      branch_name: middle.clone(),
+      archived_date: date(3),
+      reason: RetentionReason::MaxCount,
+    },
+    RetentionCandidate {
+      branch_name: oldest.clone(),
+      archived_date: date(30),
+      reason: RetentionReason::MaxAge,
+    },
+  ];
+
+  let report = apply_archive_retention_core(&git_executor, params(true))?;
+  assert_eq!(report.candidates, expected_candidates);
+  assert_eq!(report.deleted_count, 0);
+  assert_eq!(local_repo.list_branches("user/archived/*").unwrap().len(), 3, "dry run must not delete anything");
+
+  let report = apply_archive_retention_core(&git_executor, params(false))?;
+  assert_eq!(report.candidates, expected_candidates);
+  assert_eq!(report.deleted_count, 2);
+  assert_eq!(local_repo.list_branches("user/archived/*").unwrap(), vec![newest]);
+
+  Ok(())
+}
+
+/// Retention rules are read from git config when no policy is passed; integrated-only filter is on by default
+#[test(tokio::test)]
+async fn test_apply_archive_retention_uses_git_config() -> Result<()> {
+  use pretty_assertions::assert_eq;
+  use sync_core::archive_retention::{ApplyArchiveRetentionParams, apply_archive_retention_core};
+
+  let (_upstream_repo, local_repo, git_executor) = crate::test_helpers::setup_test_repos();
+  let commit_a = local_repo.create_commit("A", "a.txt", "a");
+
+  let old_date = (chrono::Utc::now() - chrono::Duration::days(5)).format("%Y-%m-%d").to_string();
+  let old_branch = format!("user/archived/{}/not-integrated", old_date);
+  local_repo.create_branch_at(&old_branch, &commit_a).unwrap();
+
+  let repo_path = local_repo.path().to_str().unwrap();
+  git_executor.execute_command(&["config", "branchdeck.archiveMaxAgeDays", "3"], repo_path)?;
+  let params = || ApplyArchiveRetentionParams {
+    repository_path: repo_path.to_string(),
+    branch_prefix: "user".to_string(),
+    policy: None,
+    dry_run: true,
+  };
+
+  let report = apply_archive_retention_core(&git_executor, params())?;
+  assert_eq!(report.policy.max_age_days, Some(3));
+  assert!(report.candidates.is_empty(), "branch without integrated status must be kept by default");
+
+  git_executor.execute_command(&["config", "branchdeck.archiveOnlyIfIntegrated", "false"], repo_path)?;
+  let report = apply_archive_retention_core(&git_executor, params())?;
+  let names: Vec<String> = report.candidates.into_iter().map(|c| c.branch_name).collect();
+  assert_eq!(names, vec![old_branch]);
+
+  Ok(())
+}
@@ -0,0 +1,218 @@
+//! Tests for the detection budget truncating the deep detection steps and resuming them on demand
+
+use super::test_helpers::setup_test_repos;
+use branch_integration::budget::{DEEP_SCAN_BUDGET_MS_CONFIG_KEY, DEEP_SCAN_MAX_BRANCH_COMMITS_CONFIG_KEY, DetectionBudget, load_detection_budget};
+use branch_integration::cache::CacheOps;
+use branch_integration::detector::{DetectConfig, detect_integrated_branches, resume_integration_detection};
+use branch_integration::lookback::LookbackWindow;
+use branch_integration::strategy::DetectionStrategy;
+use pretty_assertions::assert_eq;
+use std::time::Duration;
+use sync_test_utils::TestReporter;
+use sync_types::SyncEvent;
+use sync_types::branch_integration::{BranchIntegrationInfo, BranchIntegrationStatus};
+use test_log::test;
+
+const ARCHIVED_BRANCH: &str = "user/archived/2025-01-01/feature";
+
+fn detected_infos(progress: &TestReporter) -> Vec<BranchIntegrationInfo> {
+  progress
+    .get_events()
+    .into_iter()
+    .filter_map(|event| match event {
+      SyncEvent::BranchIntegrationDetected { info } => Some(info),
+      _ => None,
+    })
+    .collect()
+}
+
+#[test(tokio::test)]
+async fn test_exhausted_budget_truncates_and_resume_completes_detection() -> anyhow::Result<()> {
+  let (_upstream_repo, local_repo, git_executor) = setup_test_repos();
+  let repo_path = local_repo.path().to_str().unwrap();
+  let baseline = local_repo.current_branch().unwrap();
+  local_repo.create_commit("Add config", "config.txt", "a\nb\nc\nd\ne\nf\ng\n");
+
+  local_repo.checkout_new_branch("feature-work").unwrap();
+  let feature_tip = local_repo.create_commit("Change e", "config.txt", "a\nb\nc\nd\ne = 1\nf\ng\n");
+  local_repo.create_branch_at(ARCHIVED_BRANCH, &feature_tip).unwrap();
+
+  // Only fuzzy matching finds the cherry-pick, its patch-id differs
+  local_repo.checkout(&baseline).unwrap();
+  local_repo.create_commit("Change context", "config.txt", "top\na\nb\nc\nd changed\ne\nf\ng\n");
+  local_repo.create_commit("Change e", "config.txt", "top\na\nb\nc\nd changed\n  e  =  1\nf\ng\n");
+
+  let grouped_commits = indexmap::IndexMap::new();
+  let progress = TestReporter::new();
+  let cfg = DetectConfig {
+    grouped_commits: &grouped_commits,
+    progress: &progress,
+    strategy: DetectionStrategy::Rebase,
+    retention_days: 7,
+    lookback: Default::default(),
+    budget: DetectionBudget {
+      max_duration: Some(Duration::ZERO),
+      max_branch_commits: None,
+    },
+  };
+  detect_integrated_branches(&git_executor, repo_path, "user", &baseline, cfg).await?;
+
+  let infos = detected_infos(&progress);
+  assert_eq!(infos.len(), 1);
+  assert!(infos[0].detection_truncated);
+  assert!(
+    matches!(infos[0].status, BranchIntegrationStatus::NotIntegrated { orphaned_count: 1, .. }),
+    "{:?}",
+    infos[0].status
+  );
+  // Not cached, so the next detection tries again
+  let cache_ops = CacheOps::new(&git_executor, repo_path);
+  assert!(cache_ops.read(&feature_tip).is_none());
+
+  let progress = TestReporter::new();
+  resume_integration_detection(
+    &git_executor,
+    repo_path,
+    &baseline,
+    vec![ARCHIVED_BRANCH.to_string()],
+    DetectionStrategy::Rebase,
+    Default::default(),
+    &progress,
+  )
+  .await?;
+
+  let infos = detected_infos(&progress);
+  assert_eq!(infos.len(), 1);
+  assert_eq!(infos[0].name, ARCHIVED_BRANCH);
+  assert!(!infos[0].detection_truncated);
+  assert!(
+    matches!(infos[0].status, BranchIntegrationStatus::Integrated { commit_count: 1, .. }),
+    "{:?}",
+    infos[0].status
+  );
+  assert!(matches!(
+    cache_ops.read(&feature_tip).map(|info| info.status),
+    Some(BranchIntegrationStatus::Integrated { .. })
+  ));
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_branch_over_commit_limit_skips_deep_steps() -> anyhow::Result<()> {
+  let (_upstream_repo, local_repo, git_executor) = setup_test_repos();
+  let repo_path = local_repo.path().to_str().unwrap();
+  let baseline = local_repo.current_branch().unwrap();
+
+  local_repo.checkout_new_branch("feature-work").unwrap();
+  local_repo.create_commit("Add one", "one.txt", "one");
+  let feature_tip = local_repo.create_commit("Add two", "two.txt", "two");
+  local_repo.create_branch_at(ARCHIVED_BRANCH, &feature_tip).unwrap();
+  local_repo.checkout(&baseline).unwrap();
+
+  let detect = |max_branch_commits: u32| {
+    let git_executor = git_executor.clone();
+    let baseline = baseline.clone();
+    async move {
+      let grouped_commits = indexmap::IndexMap::new();
+      let progress = TestReporter::new();
+      let cfg = DetectConfig {
+        grouped_commits: &grouped_commits,
+        progress: &progress,
+        strategy: DetectionStrategy::Squash,
+        retention_days: 7,
+        lookback: Default::default(),
+        budget: DetectionBudget {
+          max_duration: None,
+          max_branch_commits: Some(max_branch_commits),
+        },
+      };
+      detect_integrated_branches(&git_executor, repo_path, "user", &baseline, cfg).await?;
+      anyhow::Ok(detected_infos(&progress).remove(0))
+    }
+  };
+
+  assert!(detect(1).await?.detection_truncated);
+  let info = detect(2).await?;
+  assert!(!info.detection_truncated);
+  assert!(matches!(info.status, BranchIntegrationStatus::NotIntegrated { orphaned_count: 2, .. }), "{:?}", info.status);
+  Ok(())
+}
+
+#[test]
+fn test_load_detection_budget() {
+  let (_upstream_repo, local_repo, git_executor) = setup_test_repos();
+  let repo_path = local_repo.path().to_str().unwrap();
+  assert_eq!(
+    load_detection_budget(&git_executor, repo_path, DetectionBudget::default()).unwrap(),
+    DetectionBudget::default()
+  );
+
+  local_repo.set_config(DEEP_SCAN_BUDGET_MS_CONFIG_KEY, "0").unwrap();
+  local_repo.set_config(DEEP_SCAN_MAX_BRANCH_COMMITS_CONFIG_KEY, "50").unwrap();
+  assert_eq!(
+    load_detection_budget(&git_executor, repo_path, DetectionBudget::default()).unwrap(),
+    DetectionBudget {
+      max_duration: None,
+      max_branch_commits: Some(50),
+    }
+  );
+
+  local_repo.set_config(DEEP_SCAN_MAX_BRANCH_COMMITS_CONFIG_KEY, "many").unwrap();
+  assert!(load_detection_budget(&git_executor, repo_path, DetectionBudget::default()).is_err());
+}
+
+#[test(tokio::test)]
+async fn test_resume_uses_lookback_window_of_truncated_detection() -> anyhow::Result<()> {
+  let (_upstream_repo, local_repo, git_executor) = setup_test_repos();
+  let repo_path = local_repo.path().to_str().unwrap();
+  let baseline = local_repo.current_branch().unwrap();
+  local_repo.create_commit("Add config", "config.txt", "a\nb\nc\nd\ne\nf\ng\n");
+
+  local_repo.checkout_new_branch("feature-work").unwrap();
+  let feature_tip = local_repo.create_commit("Change e", "config.txt", "a\nb\nc\nd\ne = 1\nf\ng\n");
+  local_repo.create_branch_at(ARCHIVED_BRANCH, &feature_tip).unwrap();
+
+  // The cherry-pick is the second newest baseline commit, outside a window of one commit
+  local_repo.checkout(&baseline).unwrap();
+  local_repo.create_commit("Change context", "config.txt", "top\na\nb\nc\nd changed\ne\nf\ng\n");
+  local_repo.create_commit("Change e", "config.txt", "top\na\nb\nc\nd changed\n  e  =  1\nf\ng\n");
+  local_repo.create_commit("Add notes", "notes.txt", "notes\n");
+
+  let grouped_commits = indexmap::IndexMap::new();
+  let progress = TestReporter::new();
+  let cfg = DetectConfig {
+    grouped_commits: &grouped_commits,
+    progress: &progress,
+    strategy: DetectionStrategy::Rebase,
+    retention_days: 7,
+    lookback: LookbackWindow {
+      max_commits: Some(1),
+      max_age_days: None,
+    },
+    budget: DetectionBudget {
+      max_duration: Some(Duration::ZERO),
+      max_branch_commits: None,
+    },
+  };
+  detect_integrated_branches(&git_executor, repo_path, "user", &baseline, cfg).await?;
+  assert!(detected_infos(&progress)[0].detection_truncated);
+
+  // The default window would find the cherry-pick
+  let progress = TestReporter::new();
+  resume_integration_detection(
+    &git_executor,
+    repo_path,
+    &baseline,
+    vec![ARCHIVED_BRANCH.to_string()],
+    DetectionStrategy::Rebase,
+    LookbackWindow::default(),
+    &progress,
+  )
+  .await?;
+
+  let infos = detected_infos(&progress);
+  assert!(!infos[0].detection_truncated);
+  assert!(infos[0].lookback_limited);
+  assert!(matches!(infos[0].status, BranchIntegrationStatus::NotIntegrated { .. }), "{:?}", infos[0].status);
+  Ok(())
+}
@@ -1,7 +1,7 @@
 //! Cache-specific unit tests for integration detection
 
 use branch_integration::cache::{DETECTION_CACHE_VERSION, parse_cached_note, serialize_for_cache};
-use sync_types::branch_integration::{BranchIntegrationInfo, BranchIntegrationStatus, IntegrationConfidence};
+use sync_types::branch_integration::{BranchIntegrationInfo, BranchIntegrationStatus, IntegrationConfidence, IntegrationEvidence, IntegrationStrategy, MatchedCommit};
 use test_log::test;
 
 /// Test cache JSON serialization and parsing
@@ -15,6 +15,9 @@ fn test_cache_serialization() {
       confidence: IntegrationConfidence::High,
       commit_count: 3,
     },
+    lookback_limited: false,
+    detection_truncated: false,
+    evidence: None,
   };
 
   // Test JSON serialization with short field names
@@ -60,6 +63,9 @@ fn test_cache_omits_zero_values() {
       orphaned_count: 0,     // should be omitted
       integrated_at: None,   // should be omitted
     },
+    lookback_limited: false,
+    detection_truncated: false,
+    evidence: None,
   };
 
   let json = serialize_for_cache(&info).unwrap();
@@ -99,6 +105,9 @@ fn test_cache_omits_zero_values() {
       confidence: IntegrationConfidence::High,
       commit_count: 0, // should be omitted
     },
+    lookback_limited: false,
+    detection_truncated: false,
+    evidence: None,
   };
 
   let zero_json = serialize_for_cache(&zero_commits_info).unwrap();
@@ -125,6 +134,9 @@ fn test_cache_exact_confidence() {
       confidence: IntegrationConfidence::Exact,
       commit_count: 5,
     },
+    lookback_limited: false,
+    detection_truncated: false,
+    evidence: None,
   };
 
   let json = serialize_for_cache(&info).unwrap();
@@ -150,6 +162,9 @@ fn test_not_integrated_cache() {
       orphaned_count: 3,
       integrated_at: Some(1234567890),
     },
+    lookback_limited: false,
+    detection_truncated: false,
+    evidence: None,
   };
 
   let json = serialize_for_cache(&info).unwrap();
@@ -182,6 +197,9 @@ fn test_partial_cache() {
     name: "test-branch".to_string(),
     summary: "partial".to_string(),
     status: BranchIntegrationStatus::Partial { missing: 2 },
+    lookback_limited: false,
+    detection_truncated: false,
+    evidence: None,
   };
 
   let json = serialize_for_cache(&info).unwrap();
@@ -207,6 +225,9 @@ fn test_json_size_optimization() {
       confidence: IntegrationConfidence::High,
       commit_count: 5,
     },
+    lookback_limited: false,
+    detection_truncated: false,
+    evidence: None,
   };
 
   // Serialize to compact JSON (no spaces)
@@ -221,3 +242,81 @@ fn test_json_size_optimization() {
   // Verify size is reasonable (should be much smaller than verbose field names)
   assert!(json.len() < 150, "Optimized JSON should be compact, got {} bytes", json.len());
 }
+
+/// Test that the lookback flag is stored only when set
+#[test]
+fn test_lookback_limited_cache() {
+  let mut info = BranchIntegrationInfo {
+    name: "test-branch".to_string(),
+    summary: String::new(),
+    status: BranchIntegrationStatus::NotIntegrated {
+      total_commit_count: 2,
+      integrated_count: 0,
+      orphaned_count: 2,
+      integrated_at: None,
+    },
+    lookback_limited: true,
+    detection_truncated: false,
+    evidence: None,
+  };
+
+  let json = serialize_for_cache(&info).unwrap();
+  assert!(json.contains("\"ll\":true"));
+  assert!(parse_cached_note(&json).unwrap().lookback_limited);
+
+  info.lookback_limited = false;
+  let json = serialize_for_cache(&info).unwrap();
+  assert!(!json.contains("\"ll\""));
+  assert!(!parse_cached_note(&json).unwrap().lookback_limited);
+}
+
+/// Test that evidence and low confidence survive the cache round trip
+#[test]
+fn test_evidence_cache() {
+  let evidence = IntegrationEvidence {
+    strategy: IntegrationStrategy::Squash,
+    matched_commits: vec![MatchedCommit {
+      id: "abc123".to_string(),
+      subject: "Add parser".to_string(),
+      committed_at: 1703116800,
+    }],
+  };
+  let info = BranchIntegrationInfo {
+    name: "test-branch".to_string(),
+    summary: String::new(),
+    status: BranchIntegrationStatus::Integrated {
+      integrated_at: None,
+      confidence: IntegrationConfidence::Low,
+      commit_count: 2,
+    },
+    lookback_limited: false,
+    detection_truncated: false,
+    evidence: Some(evidence.clone()),
+  };
+
+  let json = serialize_for_cache(&info).unwrap();
+  assert!(json.contains(r#""st":"s""#), "{json}");
+  assert!(json.contains(r#""mc":[["abc123",1703116800,"Add parser"]]"#), "{json}");
+
+  let parsed = parse_cached_note(&json).unwrap();
+  assert_eq!(parsed.evidence, Some(evidence));
+  assert!(matches!(
+    parsed.status,
+    BranchIntegrationStatus::Integrated {
+      confidence: IntegrationConfidence::Low,
+      ..
+    }
+  ));
+
+  // Notes written without evidence
+  let parsed = parse_cached_note(&format!(r#"{{"v":{DETECTION_CACHE_VERSION},"s":{{"k":"i","c":"h"}}}}"#)).unwrap();
+  assert_eq!(parsed.evidence, None);
+}
+
+#[test]
+fn test_outdated_cache_version_is_ignored() {
+  // Written before evidence was recorded and squash merges matched by tree could be of high confidence
+  assert!(parse_cached_note(r#"{"v":1,"s":{"k":"i","c":"l"}}"#).is_none());
+  assert!(parse_cached_note(r#"{"s":{"k":"i","c":"h"}}"#).is_none());
+  assert!(parse_cached_note(&format!(r#"{{"v":{DETECTION_CACHE_VERSION},"s":{{"k":"i","c":"l"}}}}"#)).is_some());
+}
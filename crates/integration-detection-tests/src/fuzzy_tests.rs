@@ -0,0 +1,105 @@
+//! Tests for fuzzy matching of cherry-picked commits whose patch-id changed
+
+use super::test_helpers::setup_test_repos;
+use branch_integration::detector::{DetectConfig, detect_integrated_branches};
+use branch_integration::rebase::scan_right_side_marks;
+use branch_integration::strategy::DetectionStrategy;
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use sync_test_utils::TestReporter;
+use sync_types::SyncEvent;
+use sync_types::branch_integration::{BranchIntegrationInfo, BranchIntegrationStatus, IntegrationConfidence, IntegrationStrategy};
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+async fn detect(git_executor: &GitCommandExecutor, local_repo: &TestRepo, baseline: &str, strategy: DetectionStrategy) -> anyhow::Result<BranchIntegrationInfo> {
+  let grouped_commits = indexmap::IndexMap::new();
+  let progress = TestReporter::new();
+  let cfg = DetectConfig {
+    grouped_commits: &grouped_commits,
+    progress: &progress,
+    strategy,
+    retention_days: 7,
+    lookback: Default::default(),
+    budget: Default::default(),
+  };
+  detect_integrated_branches(git_executor, local_repo.path().to_str().unwrap(), "user", baseline, cfg).await?;
+  Ok(
+    progress
+      .get_events()
+      .into_iter()
+      .find_map(|event| match event {
+        SyncEvent::BranchIntegrationDetected { info } => Some(info),
+        _ => None,
+      })
+      .expect("detection result for the archived branch"),
+  )
+}
+
+/// The baseline got the branch change with other indentation, next to a changed line and at another offset
+#[test(tokio::test)]
+async fn test_fuzzy_detection_matches_cherry_pick_with_drifted_context() -> anyhow::Result<()> {
+  let (_upstream_repo, local_repo, git_executor) = setup_test_repos();
+  let baseline = local_repo.current_branch().unwrap();
+  local_repo.create_commit("Add config", "config.txt", "a\nb\nc\nd\ne\nf\ng\n");
+
+  local_repo.checkout_new_branch("feature-work").unwrap();
+  let feature_tip = local_repo.create_commit("Change e", "config.txt", "a\nb\nc\nd\ne = 1\nf\ng\n");
+  local_repo.create_branch_at("user/archived/2025-01-01/feature", &feature_tip).unwrap();
+
+  local_repo.checkout(&baseline).unwrap();
+  local_repo.create_commit("Change context", "config.txt", "top\na\nb\nc\nd changed\ne\nf\ng\n");
+  let picked = local_repo.create_commit("Change e", "config.txt", "top\na\nb\nc\nd changed\n  e  =  1\nf\ng\n");
+
+  // The patch-id differs, so the commit looks orphaned to the rebase detector
+  let marks = scan_right_side_marks(&git_executor, local_repo.path().to_str().unwrap(), &baseline, "user/archived/2025-01-01/feature", None)?;
+  assert_eq!(marks, (1, 1, 0));
+
+  let info = detect(&git_executor, &local_repo, &baseline, DetectionStrategy::Rebase).await?;
+  assert!(
+    matches!(
+      info.status,
+      BranchIntegrationStatus::Integrated {
+        confidence: IntegrationConfidence::High,
+        commit_count: 1,
+        ..
+      }
+    ),
+    "{:?}",
+    info.status
+  );
+  let evidence = info.evidence.expect("fuzzy match evidence");
+  assert_eq!(evidence.strategy, IntegrationStrategy::FuzzyPatch);
+  assert_eq!(evidence.matched_commits.iter().map(|commit| commit.id.as_str()).collect::<Vec<_>>(), vec![picked.as_str()]);
+  Ok(())
+}
+
+/// A baseline commit sharing only some of the changed lines is not a match
+#[test(tokio::test)]
+async fn test_fuzzy_detection_ignores_different_changes_to_same_file() -> anyhow::Result<()> {
+  let (_upstream_repo, local_repo, git_executor) = setup_test_repos();
+  let baseline = local_repo.current_branch().unwrap();
+  local_repo.create_commit("Add config", "config.txt", "a\nb\nc\nd\ne\nf\ng\n");
+
+  local_repo.checkout_new_branch("feature-work").unwrap();
+  let feature_tip = local_repo.create_commit("Change b and e", "config.txt", "a\nb = 2\nc\nd\ne = 1\nf\ng\n");
+  local_repo.create_branch_at("user/archived/2025-01-01/feature", &feature_tip).unwrap();
+
+  local_repo.checkout(&baseline).unwrap();
+  local_repo.create_commit("Change e", "config.txt", "a\nb\nc\nd\ne = 1\nf\ng\n");
+
+  let info = detect(&git_executor, &local_repo, &baseline, DetectionStrategy::Rebase).await?;
+  assert!(
+    matches!(
+      info.status,
+      BranchIntegrationStatus::NotIntegrated {
+        orphaned_count: 1,
+        integrated_count: 0,
+        ..
+      }
+    ),
+    "{:?}",
+    info.status
+  );
+  Ok(())
+}
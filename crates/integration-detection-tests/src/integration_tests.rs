@@ -1,5 +1,5 @@
 use super::test_helpers::{setup_test_repos, sync_branches_core_with_strategy, verify_detection_cache_works};
-use branch_integration::archive::get_archived_branch_commits;
+use branch_integration::archive::{get_archived_branch_commits, get_archived_branch_commits_page};
 use branch_integration::strategy::DetectionStrategy;
 use git_executor::git_command_executor::GitCommandExecutor;
 use git_ops::model::to_final_branch_name;
@@ -246,6 +246,12 @@ pub fn test_integration_workflow_helper(update_method: UpdateMethod, integration
         i, branch_name
       );
     }
+
+    // The first page holds the newest commit, the whole branch is still counted
+    let page = get_archived_branch_commits_page(&git_executor, local_repo.path().to_str().unwrap(), full_archived_branch_name, "origin/master", 0, 1).unwrap();
+    assert_eq!(page.total_count as usize, commits.len());
+    assert_eq!(page.commits.len(), 1);
+    assert_eq!(page.commits[0].id, retrieved.last().unwrap().id);
   }
 
   // Original virtual branches should no longer exist
@@ -366,6 +372,7 @@ fn test_integration_timestamp_accuracy() {
 fn test_confidence_ordering() {
   use sync_types::branch_integration::IntegrationConfidence::*;
   assert!(Exact > High);
+  assert!(High > Low);
   assert_eq!(Exact, Exact);
   assert_eq!(High, High);
 }
@@ -18,6 +18,12 @@ pub mod merge_tests;
 #[cfg(test)]
 pub mod squash_tests;
 
+#[cfg(test)]
+pub mod fuzzy_tests;
+
+#[cfg(test)]
+pub mod budget_tests;
+
 #[cfg(test)]
 pub mod archive_cleanup_tests;
 
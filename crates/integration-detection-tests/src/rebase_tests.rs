@@ -45,6 +45,8 @@ async fn test_true_orphan_case_when_original_commits_removed_from_head() {
       progress: &progress,
       strategy: DetectionStrategy::Rebase,
       retention_days: 7,
+      lookback: Default::default(),
+      budget: Default::default(),
     },
   )
   .await;
@@ -370,6 +372,9 @@ async fn test_archive_and_cleanup_with_production_path() -> anyhow::Result<()> {
       confidence: IntegrationConfidence::High,
       commit_count: 1,
     },
+    lookback_limited: false,
+    detection_truncated: false,
+    evidence: None,
   };
   // Write integrated cache directly
 
@@ -383,6 +388,9 @@ async fn test_archive_and_cleanup_with_production_path() -> anyhow::Result<()> {
       orphaned_count: 1,
       integrated_at: None,
     },
+    lookback_limited: false,
+    detection_truncated: false,
+    evidence: None,
   };
   // Write not-integrated cache directly
 
@@ -422,3 +430,133 @@ async fn test_archive_and_cleanup_with_production_path() -> anyhow::Result<()> {
 
   Ok(())
 }
+
+/// Commits integrated before the lookback window aren't found, and the result says the window limited the analysis
+#[test(tokio::test)]
+async fn test_lookback_window_limits_rebase_detection() -> anyhow::Result<()> {
+  use branch_integration::detector::DetectConfig;
+  use branch_integration::lookback::{LookbackWindow, load_lookback_window, resolve_lookback_boundary};
+  use sync_types::branch_integration::BranchIntegrationStatus;
+
+  let (_upstream_repo, local_repo, git_executor) = setup_test_repos();
+  let repo_path = local_repo.path().to_str().unwrap();
+  let baseline = local_repo.current_branch().unwrap();
+
+  // Feature commit rebased onto the baseline, followed by more baseline commits
+  local_repo.checkout_new_branch("feature-work").unwrap();
+  let feature_tip = local_repo.create_commit("Add feature", "feature.txt", "feature");
+  local_repo.create_branch_at("user/archived/2025-01-01/feature", &feature_tip).unwrap();
+  local_repo.checkout(&baseline).unwrap();
+  local_repo.cherry_pick(&feature_tip).unwrap();
+  for n in 1..=3 {
+    local_repo.create_commit(&format!("Baseline change {n}"), &format!("change{n}.txt"), "change");
+  }
+
+  let grouped_commits = indexmap::IndexMap::new();
+  let progress = TestReporter::new();
+  let lookback = LookbackWindow {
+    max_commits: Some(2),
+    max_age_days: None,
+  };
+  let cfg = DetectConfig {
+    grouped_commits: &grouped_commits,
+    progress: &progress,
+    strategy: DetectionStrategy::Rebase,
+    retention_days: 7,
+    lookback,
+    budget: Default::default(),
+  };
+  detect_integrated_branches(&git_executor, repo_path, "user", &baseline, cfg).await?;
+
+  let info = progress
+    .get_events()
+    .into_iter()
+    .find_map(|event| match event {
+      SyncEvent::BranchIntegrationDetected { info } => Some(info),
+      _ => None,
+    })
+    .expect("detection result for the archived branch");
+  assert!(matches!(info.status, BranchIntegrationStatus::NotIntegrated { orphaned_count: 1, .. }), "{:?}", info.status);
+  assert!(info.lookback_limited, "the integrated commit is outside the window");
+
+  // 0 disables both rules, the whole history is scanned again
+  local_repo.set_config("branchdeck.integrationLookbackCommits", "0").unwrap();
+  local_repo.set_config("branchdeck.integrationLookbackDays", "0").unwrap();
+  let lookback = load_lookback_window(&git_executor, repo_path, lookback)?;
+  assert_eq!(lookback, LookbackWindow::unlimited());
+  assert_eq!(resolve_lookback_boundary(&git_executor, repo_path, &baseline, &lookback)?, None);
+
+  Ok(())
+}
+
+/// Fully and partially integrated branches report the baseline commits their commits were matched to
+#[test(tokio::test)]
+async fn test_rebase_detection_evidence() -> anyhow::Result<()> {
+  use branch_integration::detector::DetectConfig;
+  use sync_types::branch_integration::{BranchIntegrationStatus, IntegrationEvidence, IntegrationStrategy};
+
+  let (_upstream_repo, local_repo, git_executor) = setup_test_repos();
+  let repo_path = local_repo.path().to_str().unwrap();
+  let baseline = local_repo.current_branch().unwrap();
+
+  local_repo.checkout_new_branch("feature-work").unwrap();
+  let rebased_tip = local_repo.create_commit("Add rebased feature", "rebased.txt", "rebased");
+  local_repo.create_branch_at("user/archived/2025-01-01/rebased", &rebased_tip).unwrap();
+  let partial_first = local_repo.create_commit("Add partial feature", "partial.txt", "partial");
+  let partial_tip = local_repo.create_commit("Extend partial feature", "partial.txt", "partial extended");
+  local_repo.create_branch_at("user/archived/2025-01-01/partial", &partial_tip).unwrap();
+
+  local_repo.checkout(&baseline).unwrap();
+  let rebased_on_baseline = local_repo.cherry_pick(&rebased_tip).unwrap();
+  let partial_on_baseline = local_repo.cherry_pick(&partial_first).unwrap();
+
+  let grouped_commits = indexmap::IndexMap::new();
+  let progress = TestReporter::new();
+  let cfg = DetectConfig {
+    grouped_commits: &grouped_commits,
+    progress: &progress,
+    strategy: DetectionStrategy::Rebase,
+    retention_days: 7,
+    lookback: Default::default(),
+    budget: Default::default(),
+  };
+  detect_integrated_branches(&git_executor, repo_path, "user", &baseline, cfg).await?;
+
+  let events = progress.get_events();
+  let find_info = |name: &str| {
+    events
+      .iter()
+      .find_map(|event| match event {
+        SyncEvent::BranchIntegrationDetected { info } if info.name.ends_with(name) => Some(info.clone()),
+        _ => None,
+      })
+      .unwrap_or_else(|| panic!("detection result for {name}"))
+  };
+  let matched_ids = |evidence: &IntegrationEvidence| evidence.matched_commits.iter().map(|commit| commit.id.clone()).collect::<Vec<_>>();
+
+  let rebased = find_info("/rebased");
+  assert!(matches!(rebased.status, BranchIntegrationStatus::Integrated { .. }), "{:?}", rebased.status);
+  let evidence = rebased.evidence.expect("evidence of the rebased branch");
+  assert_eq!(evidence.strategy, IntegrationStrategy::Rebase);
+  assert_eq!(matched_ids(&evidence), vec![rebased_on_baseline.clone()]);
+
+  // The partial branch is built on top of the rebased one
+  let partial = find_info("/partial");
+  assert!(
+    matches!(
+      partial.status,
+      BranchIntegrationStatus::NotIntegrated {
+        integrated_count: 2,
+        orphaned_count: 1,
+        ..
+      }
+    ),
+    "{:?}",
+    partial.status
+  );
+  let evidence = partial.evidence.expect("evidence of the partially integrated branch");
+  assert_eq!(evidence.strategy, IntegrationStrategy::PatchId);
+  assert_eq!(matched_ids(&evidence), vec![partial_on_baseline, rebased_on_baseline]);
+
+  Ok(())
+}
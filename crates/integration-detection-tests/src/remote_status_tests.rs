@@ -1,10 +1,16 @@
 use super::test_helpers::{setup_test_repos, sync_branches_core_with_strategy};
+use branch_integration::cache::CacheOps;
 use branch_integration::strategy::DetectionStrategy;
 use git_executor::git_command_executor::GitCommandExecutor;
 use pretty_assertions::assert_eq;
-use sync_core::remote_status::compute_remote_status_for_branch;
+use sync_core::credentials::AskpassEnv;
+use sync_core::delete_archived_branch::{DeleteArchivedBranchParams, delete_archived_branch_core};
+use sync_core::remote_status::{
+  compute_remote_status_for_archived_branches, compute_remote_status_for_branch, compute_remote_status_for_virtual_branches, refresh_remote_status_for_virtual_branches,
+};
 use sync_test_utils::TestReporter;
-use sync_types::SyncEvent;
+use sync_types::branch_integration::{BranchIntegrationInfo, BranchIntegrationStatus, IntegrationConfidence};
+use sync_types::{ArchivedRemoteStatus, SyncEvent};
 use test_log::test;
 use test_utils::git_test_utils::TestRepo;
 
@@ -275,6 +281,68 @@ async fn test_compute_remote_status_directly() {
   assert_eq!(result.commits_behind, 0, "Should be up to date after push");
 }
 
+/// Remote status of all virtual branches, as computed by the background refresh
+#[test]
+fn test_compute_remote_status_for_virtual_branches() {
+  let scenario = TestScenario::new();
+  scenario.setup_development_branch();
+
+  let commits = scenario.create_commits(&[
+    ("(pushed) Pushed change".to_string(), "pushed.rs", "// Pushed"),
+    ("(local) Local change".to_string(), "local.rs", "// Local"),
+  ]);
+  scenario.local_repo.create_branch_at("user/virtual/pushed", &commits[0]).unwrap();
+  scenario.local_repo.push("origin", "user/virtual/pushed").unwrap();
+  scenario.local_repo.create_branch_at("user/virtual/local", &commits[1]).unwrap();
+
+  let statuses = compute_remote_status_for_virtual_branches(
+    &scenario.git_executor,
+    scenario.local_repo.path().to_str().unwrap(),
+    "user",
+    "origin/main",
+    Some(&scenario.user_email),
+  )
+  .unwrap();
+
+  let summary: Vec<(&str, bool, usize, u32)> = statuses
+    .iter()
+    .map(|status| (status.branch_name.as_str(), status.remote_exists, status.unpushed_commits.len(), status.my_unpushed_count))
+    .collect();
+  // Branches come in ref order; the local branch contains both commits
+  assert_eq!(summary, vec![("local", false, 0, 2), ("pushed", true, 0, 0)]);
+}
+
+/// The background refresh learns about remote changes from a single ls-remote: deleted remote branches are pruned
+/// locally and only heads with unknown commits are fetched
+#[test]
+fn test_refresh_remote_status_from_remote_heads() {
+  let scenario = TestScenario::new();
+  scenario.setup_development_branch();
+  let repo_path = scenario.local_repo.path().to_str().unwrap();
+
+  let commits = scenario.create_commits(&[
+    ("(pushed) Pushed change".to_string(), "pushed.rs", "// Pushed"),
+    ("(deleted) Deleted change".to_string(), "deleted.rs", "// Deleted"),
+  ]);
+  scenario.local_repo.create_branch_at("user/virtual/pushed", &commits[0]).unwrap();
+  scenario.local_repo.create_branch_at("user/virtual/deleted", &commits[1]).unwrap();
+  scenario.local_repo.push("origin", "user/virtual/pushed").unwrap();
+  scenario.local_repo.push("origin", "user/virtual/deleted").unwrap();
+
+  // Changed on the remote from another machine
+  scenario.upstream_repo.delete_branch("user/virtual/deleted").unwrap();
+  scenario.upstream_repo.checkout_new_branch("elsewhere").unwrap();
+  let foreign_commit = scenario.upstream_repo.create_commit("(pushed) Pushed from elsewhere", "elsewhere.rs", "// Elsewhere");
+  scenario.upstream_repo.create_branch_at("user/virtual/foreign", &foreign_commit).unwrap();
+
+  let statuses = refresh_remote_status_for_virtual_branches(&scenario.git_executor, repo_path, "user", "origin/main", Some(&scenario.user_email)).unwrap();
+  let summary: Vec<(&str, bool)> = statuses.iter().map(|status| (status.branch_name.as_str(), status.remote_exists)).collect();
+  assert_eq!(summary, vec![("deleted", false), ("pushed", true)]);
+
+  assert!(scenario.local_repo.rev_parse("refs/remotes/origin/user/virtual/deleted").is_err());
+  assert_eq!(scenario.local_repo.rev_parse("refs/remotes/origin/user/virtual/foreign").unwrap(), foreign_commit);
+}
+
 /// Test scenario where a NEW commit is added after baseline merge.
 #[test(tokio::test)]
 async fn test_new_commit_after_baseline_merge() {
@@ -445,3 +513,156 @@ async fn test_unrelated_commits_from_same_author_not_counted() {
     "BAZEL commits are in baseline, parallel commit is patch-equivalent - should count 0",
   );
 }
+
+/// Push `user/virtual/{name}` branches and archive `feature` and `other` locally, recreating `other` as an active branch
+fn setup_archived_branches(scenario: &TestScenario) {
+  let commits = scenario.create_commits(&[
+    ("(feature) Add feature".to_string(), "feature.rs", "// Feature"),
+    ("(other) Add other".to_string(), "other.rs", "// Other"),
+  ]);
+  for (name, commit) in ["feature", "other"].iter().zip(&commits) {
+    let branch = format!("user/virtual/{name}");
+    scenario.local_repo.create_branch_at(&branch, commit).unwrap();
+    scenario.local_repo.push("origin", &branch).unwrap();
+  }
+
+  scenario.local_repo.rename_branch("user/virtual/feature", "user/archived/2025-01-01/feature").unwrap();
+  scenario.local_repo.rename_branch("user/virtual/other", "user/archived/2025-01-02/other-1").unwrap();
+  scenario.local_repo.create_branch_at("user/virtual/other", &commits[1]).unwrap();
+}
+
+fn archived_remote_status(scenario: &TestScenario) -> Vec<(String, String, bool)> {
+  compute_remote_status_for_archived_branches(&scenario.git_executor, scenario.local_repo.path().to_str().unwrap(), "user")
+    .unwrap()
+    .into_iter()
+    .map(
+      |ArchivedRemoteStatus {
+         branch_name,
+         remote_branch,
+         same_commit,
+       }| (branch_name, remote_branch, same_commit),
+    )
+    .collect()
+}
+
+/// Archived branches report the virtual branch still present on the remote, unless the name is in use again
+#[test]
+fn test_archived_branches_with_remote_counterparts() {
+  let scenario = TestScenario::new();
+  scenario.setup_development_branch();
+  setup_archived_branches(&scenario);
+
+  assert_eq!(
+    archived_remote_status(&scenario),
+    vec![("user/archived/2025-01-01/feature".to_string(), "user/virtual/feature".to_string(), true)]
+  );
+
+  // The remote branch moved on after archiving
+  scenario.upstream_repo.checkout("user/virtual/feature").unwrap();
+  scenario.upstream_repo.create_commit("(feature) Remote change", "remote.rs", "// Remote");
+  scenario.local_repo.fetch_prune("origin").unwrap();
+  assert_eq!(
+    archived_remote_status(&scenario),
+    vec![("user/archived/2025-01-01/feature".to_string(), "user/virtual/feature".to_string(), false)]
+  );
+}
+
+/// A numeric suffix is only a collision suffix if the archived branch it collided with exists
+#[test]
+fn test_archived_branch_with_numeric_suffix_of_its_own() {
+  let scenario = TestScenario::new();
+  scenario.setup_development_branch();
+  let commits = scenario.create_commits(&[
+    ("(api) Add API".to_string(), "api.rs", "// API"),
+    ("(api-2) Add API v2".to_string(), "api2.rs", "// API v2"),
+  ]);
+  // `api` is only on the remote, e.g. synced from another machine
+  scenario.local_repo.create_branch_at("user/virtual/api", &commits[0]).unwrap();
+  scenario.local_repo.push("origin", "user/virtual/api").unwrap();
+  scenario.local_repo.delete_branch("user/virtual/api").unwrap();
+  scenario.local_repo.create_branch_at("user/archived/2025-01-01/api-2", &commits[1]).unwrap();
+  assert_eq!(archived_remote_status(&scenario), Vec::new());
+
+  // Archived twice on the same day, so `api-2` is the third `api`
+  scenario.local_repo.create_branch_at("user/archived/2025-01-01/api", &commits[0]).unwrap();
+  assert_eq!(
+    archived_remote_status(&scenario),
+    vec![("user/archived/2025-01-01/api-2".to_string(), "user/virtual/api".to_string(), false)]
+  );
+}
+
+fn delete_archived_feature(scenario: &TestScenario) -> anyhow::Result<()> {
+  delete_archived_branch_core(
+    &scenario.git_executor,
+    DeleteArchivedBranchParams {
+      repository_path: scenario.local_repo.path().to_str().unwrap().to_string(),
+      branch_name: "user/archived/2025-01-01/feature".to_string(),
+      branch_prefix: "user".to_string(),
+      delete_remote: true,
+    },
+    &AskpassEnv::none(),
+  )
+}
+
+/// Mark the archived `feature` branch as integrated in the detection cache
+fn mark_feature_integrated(scenario: &TestScenario) {
+  let tip = scenario.local_repo.rev_parse("user/archived/2025-01-01/feature").unwrap();
+  let info = BranchIntegrationInfo {
+    name: "user/archived/2025-01-01/feature".to_string(),
+    summary: String::new(),
+    status: BranchIntegrationStatus::Integrated {
+      integrated_at: None,
+      confidence: IntegrationConfidence::Exact,
+      commit_count: 1,
+    },
+    lookback_limited: false,
+    detection_truncated: false,
+    evidence: None,
+  };
+  CacheOps::new(&scenario.git_executor, scenario.local_repo.path().to_str().unwrap())
+    .write(&tip, &info)
+    .unwrap();
+}
+
+/// Deleting an archived branch with `delete_remote` removes its remote counterpart too
+#[test]
+fn test_delete_archived_branch_with_remote() {
+  let scenario = TestScenario::new();
+  scenario.setup_development_branch();
+  setup_archived_branches(&scenario);
+  mark_feature_integrated(&scenario);
+
+  delete_archived_feature(&scenario).unwrap();
+
+  assert!(!scenario.local_repo.branch_exists("user/archived/2025-01-01/feature"));
+  assert!(!scenario.upstream_repo.branch_exists("user/virtual/feature"));
+  // The remote branch of the active virtual branch is kept
+  assert!(scenario.upstream_repo.branch_exists("user/virtual/other"));
+  assert_eq!(archived_remote_status(&scenario), Vec::new());
+}
+
+/// The remote branch is only deleted for integrated branches without remote-only commits
+#[test]
+fn test_delete_archived_branch_keeps_unintegrated_remote() {
+  let scenario = TestScenario::new();
+  scenario.setup_development_branch();
+  setup_archived_branches(&scenario);
+
+  let error = delete_archived_feature(&scenario).unwrap_err();
+  assert_eq!(error.to_string(), "Branch is not integrated, the remote branch is kept");
+
+  // Integrated, but the remote branch got a commit after archiving
+  mark_feature_integrated(&scenario);
+  scenario.upstream_repo.checkout("user/virtual/feature").unwrap();
+  scenario.upstream_repo.create_commit("(feature) Remote change", "remote.rs", "// Remote");
+  scenario.local_repo.fetch_prune("origin").unwrap();
+  let error = delete_archived_feature(&scenario).unwrap_err();
+  assert_eq!(
+    error.to_string(),
+    "Remote branch user/virtual/feature has 1 commits that are not in the archived branch, the remote branch is kept"
+  );
+
+  // Nothing was deleted
+  assert!(scenario.local_repo.branch_exists("user/archived/2025-01-01/feature"));
+  assert!(scenario.upstream_repo.branch_exists("user/virtual/feature"));
+}
@@ -7,7 +7,7 @@ use super::test_helpers::{
 use branch_integration::strategy::DetectionStrategy;
 use sync_test_utils::TestReporter;
 use sync_types::SyncEvent;
-use sync_types::branch_integration::IntegrationConfidence;
+use sync_types::branch_integration::{IntegrationConfidence, IntegrationStrategy};
 use test_log::test;
 
 #[test]
@@ -168,6 +168,12 @@ fn test_squash_merge_detection() {
     if let sync_types::branch_integration::BranchIntegrationStatus::Integrated { confidence, integrated_at, .. } = &integration_info.status {
       assert_eq!(*confidence, IntegrationConfidence::High);
 
+      // The squash commit found by its subject is the evidence
+      let evidence = integration_info.evidence.as_ref().expect("Should report squash evidence");
+      assert_eq!(evidence.strategy, IntegrationStrategy::Squash);
+      assert_eq!(evidence.matched_commits.len(), 1);
+      assert_eq!(Some(evidence.matched_commits[0].committed_at), *integrated_at);
+
       // Verify timestamp is close to squash merge time
       if let Some(integrated_date) = integrated_at {
         let timestamp_diff = (*integrated_date as i64 - squash_commit as i64).abs();
@@ -199,3 +205,52 @@ fn test_squash_merge_detection() {
     verify_detection_cache_works(&local_repo, &git_executor, "user", DetectionStrategy::All, 1).await.unwrap();
   });
 }
+
+#[test]
+fn test_squash_merge_with_other_message_detection() {
+  // The tree matches, so the squash commit not being found by its message doesn't lower the confidence
+  let rt = tokio::runtime::Runtime::new().unwrap();
+  let (upstream_repo, local_repo, git_executor) = setup_test_repos();
+  let repo_path = local_repo.path().to_str().unwrap();
+
+  local_repo.checkout_new_branch("local-dev").unwrap();
+  local_repo.create_commit("(feature-squash) Add function one", "feature.js", "function one() {}");
+  local_repo.create_commit("(feature-squash) Add function two", "feature.js", "function one() {}\nfunction two() {}");
+  let result = rt.block_on(async { sync_branches_core_with_strategy(&git_executor, repo_path, "user", TestReporter::new(), DetectionStrategy::All).await });
+  assert!(result.is_ok());
+  local_repo.push("origin", "user/virtual/feature-squash").unwrap();
+
+  upstream_repo.checkout("main").unwrap();
+  let squash_timestamp = chrono::Utc::now().timestamp() - 180;
+  upstream_repo
+    .merge_squash_with_timestamp("user/virtual/feature-squash", "Feature functions (#42)", squash_timestamp)
+    .unwrap();
+  upstream_repo.delete_branch("user/virtual/feature-squash").unwrap();
+
+  local_repo.fetch_prune("origin").unwrap();
+  local_repo.checkout("main").unwrap();
+  local_repo.pull().unwrap();
+
+  let progress_reporter = TestReporter::new();
+  let result = rt.block_on(async { sync_branches_core_with_strategy(&git_executor, repo_path, "user", progress_reporter.clone(), DetectionStrategy::All).await });
+  assert!(result.is_ok(), "Second sync failed: {result:?}");
+
+  let integration_info = progress_reporter
+    .get_events()
+    .into_iter()
+    .find_map(|event| match event {
+      SyncEvent::BranchIntegrationDetected { info } if info.name.contains("feature-squash") => Some(info),
+      _ => None,
+    })
+    .expect("Should detect squash-merged branch as integrated");
+  match &integration_info.status {
+    sync_types::branch_integration::BranchIntegrationStatus::Integrated { confidence, integrated_at, .. } => {
+      assert_eq!(*confidence, IntegrationConfidence::High);
+      assert_eq!(*integrated_at, None);
+    }
+    status => panic!("Expected Integrated status for squash branch, got {status:?}"),
+  }
+  let evidence = integration_info.evidence.as_ref().expect("Should report squash evidence");
+  assert_eq!(evidence.strategy, IntegrationStrategy::Squash);
+  assert!(evidence.matched_commits.is_empty());
+}
@@ -69,6 +69,7 @@ pub async fn sync_branches_core_with_strategy_and_retention<P: ProgressReporter
       cached_issue_config: None,
       detection_strategy: strategy,
       archive_retention_days: retention_days,
+      ..Default::default()
     },
   )
   .await
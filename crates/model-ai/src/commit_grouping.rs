@@ -0,0 +1,60 @@
+use git_ops::model::{sanitize_branch_name, validate_branch_name};
+use indexmap::IndexMap;
+use std::collections::HashSet;
+use sync_types::commit_grouping::{CommitGroupingInput, CommitGroupingPlan, SuggestedCommitGroup};
+
+/// Parse the model response into a plan.
+///
+/// The expected format is one group per line, `branch-name: 1, 3, 4`, where numbers refer to the 1-based position of
+/// the commit in `input.commits`. Invalid names, unknown numbers and commits already assigned to another group are ignored.
+pub fn parse_commit_grouping_plan(response: &str, input: &CommitGroupingInput) -> CommitGroupingPlan {
+  let response = match response.rfind("</think>") {
+    Some(end_pos) => &response[end_pos + 8..],
+    None => response,
+  };
+
+  let mut assigned = HashSet::new();
+  let mut groups: IndexMap<String, Vec<usize>> = IndexMap::new();
+  for line in response.lines() {
+    let line = line.trim().trim_start_matches(['-', '*']).trim();
+    let Some((name, numbers)) = line.split_once(':') else {
+      continue;
+    };
+    let name = sanitize_branch_name(name.trim().trim_matches(['(', ')', '`', '"', '\'']).trim());
+    if validate_branch_name(&name).is_err() {
+      continue;
+    }
+
+    let indices: Vec<usize> = numbers
+      .split(|c: char| c == ',' || c.is_whitespace())
+      .filter_map(|n| n.trim().trim_start_matches('#').parse::<usize>().ok())
+      .filter(|&n| n >= 1 && n <= input.commits.len())
+      .map(|n| n - 1)
+      .filter(|&index| assigned.insert(index))
+      .collect();
+    if !indices.is_empty() {
+      groups.entry(name).or_default().extend(indices);
+    }
+  }
+
+  let groups = groups
+    .into_iter()
+    .map(|(branch_name, mut indices)| {
+      indices.sort_unstable();
+      SuggestedCommitGroup {
+        is_existing_branch: input.existing_branches.contains(&branch_name),
+        commit_ids: indices.into_iter().map(|index| input.commits[index].id.clone()).collect(),
+        branch_name,
+      }
+    })
+    .collect();
+  let ungrouped_commit_ids = input
+    .commits
+    .iter()
+    .enumerate()
+    .filter(|(index, _)| !assigned.contains(index))
+    .map(|(_, commit)| commit.id.clone())
+    .collect();
+
+  CommitGroupingPlan { groups, ungrouped_commit_ids }
+}
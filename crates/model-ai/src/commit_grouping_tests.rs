@@ -0,0 +1,54 @@
+use crate::commit_grouping::parse_commit_grouping_plan;
+use pretty_assertions::assert_eq;
+use sync_types::commit_grouping::{CommitGroupingInput, CommitGroupingPlan, SuggestedCommitGroup, UngroupedCommit};
+
+fn grouping_input(commit_count: usize, existing_branches: &[&str]) -> CommitGroupingInput {
+  CommitGroupingInput {
+    commits: (1..=commit_count)
+      .map(|n| UngroupedCommit {
+        id: format!("commit{n}"),
+        subject: format!("Change {n}"),
+        files: Vec::new(),
+      })
+      .collect(),
+    existing_branches: existing_branches.iter().map(|name| name.to_string()).collect(),
+  }
+}
+
+#[test]
+fn test_parse_commit_grouping_plan() {
+  let input = grouping_input(5, &["lexer"]);
+  let response = "<think>\n</think>\n```\n- parser: 1, 3\nlexer: 2\n(parser): 5, 3, 9\nnot a group\nbad name!: 4\n```";
+
+  assert_eq!(
+    parse_commit_grouping_plan(response, &input),
+    CommitGroupingPlan {
+      groups: vec![
+        SuggestedCommitGroup {
+          branch_name: "parser".to_string(),
+          is_existing_branch: false,
+          commit_ids: vec!["commit1".to_string(), "commit3".to_string(), "commit5".to_string()],
+        },
+        SuggestedCommitGroup {
+          branch_name: "lexer".to_string(),
+          is_existing_branch: true,
+          commit_ids: vec!["commit2".to_string()],
+        },
+      ],
+      ungrouped_commit_ids: vec!["commit4".to_string()],
+    }
+  );
+}
+
+#[test]
+fn test_parse_commit_grouping_plan_without_groups() {
+  let input = grouping_input(2, &[]);
+
+  assert_eq!(
+    parse_commit_grouping_plan("I can't group these commits.", &input),
+    CommitGroupingPlan {
+      groups: Vec::new(),
+      ungrouped_commit_ids: vec!["commit1".to_string(), "commit2".to_string()],
+    }
+  );
+}
@@ -1,9 +1,12 @@
+use crate::manifest::{FileVerification, ManifestEntry, ModelManifest, PARTIAL_DOWNLOAD_EXTENSION, fetch_manifest, verify_file};
 use crate::path_provider::ModelPathProvider;
-use anyhow::Result;
+use crate::types::{ModelCacheReport, ModelFileState, ModelFileStatus};
+use anyhow::{Context, Result};
 use futures_util::StreamExt;
 use model_core::config::ModelConfig;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
@@ -14,6 +17,10 @@ pub trait ProgressReporter: Send + Sync {
   fn report_started(&self, total_files: u32) -> Result<()>;
   fn report_file_started(&self, file_name: &str, file_size: Option<u32>) -> Result<()>;
   fn report_progress(&self, file_name: &str, downloaded: u32, total: u32, bytes_per_second: Option<u32>, seconds_remaining: Option<u32>) -> Result<()>;
+  /// An interrupted download continues from `resumed_from` bytes
+  fn report_file_resumed(&self, file_name: &str, resumed_from: u32) -> Result<()>;
+  /// The downloaded file is being checked against the manifest
+  fn report_file_verifying(&self, file_name: &str) -> Result<()>;
   fn report_file_completed(&self, file_name: &str) -> Result<()>;
   fn report_completed(&self) -> Result<()>;
   fn report_error(&self, message: &str) -> Result<()>;
@@ -56,6 +63,16 @@ impl ProgressReporter for ConsoleProgressReporter {
     Ok(())
   }
 
+  fn report_file_resumed(&self, file_name: &str, resumed_from: u32) -> Result<()> {
+    println!("  ↻ Resuming {} from {:.2} MB", file_name, resumed_from as f64 / 1_000_000.0);
+    Ok(())
+  }
+
+  fn report_file_verifying(&self, file_name: &str) -> Result<()> {
+    println!("\r  ⋯ Verifying {file_name}");
+    Ok(())
+  }
+
   fn report_file_completed(&self, file_name: &str) -> Result<()> {
     println!("\r  ✓ {file_name} downloaded");
     Ok(())
@@ -106,82 +123,87 @@ fn create_http_client() -> Result<ClientWithMiddleware> {
   Ok(client)
 }
 
-/// Downloads a single file with progress tracking
-#[instrument(skip(client, progress, cancelled), fields(filename = %filename, url = %url))]
+/// Log a download failure and report it to the UI
+fn report_failure(progress: &dyn ProgressReporter, msg: String) -> anyhow::Error {
+  error!("{}", msg);
+  let _ = progress.report_error(&msg);
+  anyhow::anyhow!("{}", msg)
+}
+
+fn partial_download_path(file_path: &Path) -> PathBuf {
+  file_path.with_extension(PARTIAL_DOWNLOAD_EXTENSION)
+}
+
+/// Downloads a single file with progress tracking, resuming an interrupted download.
+/// The file is verified against its manifest entry before it is moved into place; returns its SHA256.
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(client, manifest_entry, progress, cancelled), fields(filename = %filename, url = %url))]
 async fn download_file(
   client: &ClientWithMiddleware,
   url: &str,
-  file_path: &std::path::Path,
+  file_path: &Path,
   filename: &str,
   expected_size: Option<u32>,
+  manifest_entry: Option<&ManifestEntry>,
   progress: &dyn ProgressReporter,
   cancelled: Option<Arc<AtomicBool>>,
-) -> Result<()> {
+) -> Result<String> {
+  // Progress is only reported for the large model file
+  let report_progress = expected_size.is_some();
+  let temp_path = partial_download_path(file_path);
+
   // Check if we can resume a partial download
-  let mut resume_from = 0u64;
-  let temp_path = file_path.with_extension("download");
-
-  if temp_path.exists() {
-    match tokio::fs::metadata(&temp_path).await {
-      Ok(metadata) => {
-        resume_from = metadata.len();
-        info!("Found partial download for {}, resuming from byte {}", filename, resume_from);
-      }
-      Err(e) => {
-        warn!("Failed to check partial download metadata: {}", e);
-        // Delete corrupt partial file
-        let _ = tokio::fs::remove_file(&temp_path).await;
-      }
-    }
+  let mut resume_from = match tokio::fs::metadata(&temp_path).await {
+    Ok(metadata) => metadata.len(),
+    Err(_) => 0,
+  };
+  if let Some(entry) = manifest_entry
+    && resume_from > entry.size
+  {
+    warn!("Partial download of {} is larger than the file, starting over", filename);
+    resume_from = 0;
   }
 
-  // Build request with optional range header for resume
-  let mut request = client.get(url);
-  if resume_from > 0 {
-    request = request.header("Range", format!("bytes={resume_from}-"));
-  }
+  // A partial file of the full size only needs verification
+  let already_complete = resume_from > 0 && manifest_entry.is_some_and(|entry| entry.size == resume_from);
+  if !already_complete {
+    // Build request with optional range header for resume
+    let mut request = client.get(url);
+    if resume_from > 0 {
+      request = request.header("Range", format!("bytes={resume_from}-"));
+    }
 
-  // Execute request
-  let response = request.send().await.map_err(|e| {
-    let msg = format!("Failed to download {filename}: {e}");
-    error!("{}", msg);
-    let _ = progress.report_error(&msg);
-    anyhow::anyhow!("{}", msg)
-  })?;
-
-  // Check response status
-  let status = response.status();
-  if !status.is_success() && status.as_u16() != 206 {
-    // 206 is Partial Content for resumed downloads
-    let msg = format!("Failed to download {filename}: HTTP {status}");
-    error!("{}", msg);
-    let _ = progress.report_error(&msg);
-    return Err(anyhow::anyhow!("{}", msg));
-  }
+    let response = request.send().await.map_err(|e| report_failure(progress, format!("Failed to download {filename}: {e}")))?;
 
-  // Get content length
-  let content_length = response.content_length().unwrap_or(0);
-  let total_size = if resume_from > 0 && status.as_u16() == 206 {
-    // For resumed downloads, add the already downloaded bytes
-    content_length + resume_from
-  } else {
-    content_length
-  };
+    let status = response.status();
+    if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+      // The partial file doesn't fit the file on the server anymore
+      let _ = tokio::fs::remove_file(&temp_path).await;
+      return Err(report_failure(progress, format!("Failed to resume download of {filename}, please retry")));
+    }
+    if !status.is_success() {
+      return Err(report_failure(progress, format!("Failed to download {filename}: HTTP {status}")));
+    }
+
+    // The server may ignore the range and send the whole file
+    if resume_from > 0 && status != reqwest::StatusCode::PARTIAL_CONTENT {
+      info!("Server doesn't support resuming {}, starting over", filename);
+      resume_from = 0;
+    } else if resume_from > 0 {
+      info!("Found partial download for {}, resuming from byte {}", filename, resume_from);
+      let _ = progress.report_file_resumed(filename, resume_from.min(u32::MAX as u64) as u32);
+    }
+
+    let content_length = response.content_length().unwrap_or(0);
+    let total_size = content_length + resume_from;
 
-  // For large files, download with streaming progress
-  if expected_size.is_some() && total_size > 0 {
     // Open file for writing (append if resuming)
     let mut file = if resume_from > 0 {
-      tokio::fs::OpenOptions::new().write(true).append(true).open(&temp_path).await
+      tokio::fs::OpenOptions::new().append(true).open(&temp_path).await
     } else {
       tokio::fs::File::create(&temp_path).await
     }
-    .map_err(|e| {
-      let msg = format!("Failed to create file {filename}: {e}");
-      error!("{}", msg);
-      let _ = progress.report_error(&msg);
-      anyhow::anyhow!("{}", msg)
-    })?;
+    .map_err(|e| report_failure(progress, format!("Failed to create file {filename}: {e}")))?;
 
     // Get response stream
     let mut stream = response.bytes_stream();
@@ -191,7 +213,7 @@ async fn download_file(
     let mut last_percentage = if total_size > 0 { (resume_from * 100 / total_size) as u32 } else { 0 };
 
     // Send initial progress if resuming
-    if resume_from > 0 {
+    if report_progress && resume_from > 0 {
       let _ = progress.report_progress(filename, downloaded.min(u32::MAX as u64) as u32, total_size.min(u32::MAX as u64) as u32, None, None);
     }
 
@@ -199,28 +221,22 @@ async fn download_file(
 
     // Stream download with progress updates
     while let Some(chunk_result) = stream.next().await {
-      // Check for cancellation before processing each chunk
+      // Check for cancellation before processing each chunk, the partial file is kept for resuming
       if let Some(ref cancel_flag) = cancelled
         && cancel_flag.load(Ordering::Relaxed)
       {
         info!("Download cancelled for {}", filename);
+        let _ = file.flush().await;
         return Err(anyhow::anyhow!("Download cancelled"));
       }
 
-      let chunk = chunk_result.map_err(|e| {
-        let msg = format!("Failed to download chunk for {filename}: {e}");
-        error!("{}", msg);
-        let _ = progress.report_error(&msg);
-        anyhow::anyhow!("{}", msg)
-      })?;
+      let chunk = chunk_result.map_err(|e| report_failure(progress, format!("Failed to download chunk for {filename}: {e}")))?;
 
       // Write chunk to file
-      file.write_all(&chunk).await.map_err(|e| {
-        let msg = format!("Failed to write chunk for {filename}: {e}");
-        error!("{}", msg);
-        let _ = progress.report_error(&msg);
-        anyhow::anyhow!("{}", msg)
-      })?;
+      file
+        .write_all(&chunk)
+        .await
+        .map_err(|e| report_failure(progress, format!("Failed to write chunk for {filename}: {e}")))?;
 
       downloaded += chunk.len() as u64;
 
@@ -228,7 +244,7 @@ async fn download_file(
       let current_percentage = if total_size > 0 { (downloaded * 100 / total_size) as u32 } else { 0 };
 
       // Send progress update every 1000ms and when percentage changes by at least 1%
-      if last_progress_update.elapsed() > Duration::from_millis(1000) && (current_percentage > last_percentage || current_percentage == 100) {
+      if report_progress && last_progress_update.elapsed() > Duration::from_millis(1000) && (current_percentage > last_percentage || current_percentage == 100) {
         last_percentage = current_percentage;
 
         // Calculate speed and time remaining
@@ -263,46 +279,66 @@ async fn download_file(
     }
 
     // Final progress update
-    let _ = progress.report_progress(filename, downloaded.min(u32::MAX as u64) as u32, total_size.min(u32::MAX as u64) as u32, None, Some(0));
+    if report_progress {
+      let _ = progress.report_progress(filename, downloaded.min(u32::MAX as u64) as u32, total_size.min(u32::MAX as u64) as u32, None, Some(0));
+    }
 
     // Flush file
-    file.flush().await.map_err(|e| {
-      let msg = format!("Failed to flush file {filename}: {e}");
-      error!("{}", msg);
-      let _ = progress.report_error(&msg);
-      anyhow::anyhow!("{}", msg)
-    })?;
+    file.flush().await.map_err(|e| report_failure(progress, format!("Failed to flush file {filename}: {e}")))?;
+  }
 
-    // Move temp file to final location
-    tokio::fs::rename(&temp_path, file_path).await.map_err(|e| {
-      let msg = format!("Failed to move downloaded file {filename}: {e}");
-      error!("{}", msg);
-      let _ = progress.report_error(&msg);
-      anyhow::anyhow!("{}", msg)
-    })?;
+  // Verify before the file is used, hashing a model file takes a while
+  if report_progress {
+    let _ = progress.report_file_verifying(filename);
+  }
+  let verify_path = temp_path.clone();
+  let verify_entry = manifest_entry.cloned();
+  let verification = tokio::task::spawn_blocking(move || verify_file(&verify_path, verify_entry.as_ref()))
+    .await
+    .map_err(|e| anyhow::anyhow!("Verification task failed: {e}"))?
+    .map_err(|e| report_failure(progress, format!("Failed to verify {filename}: {e}")))?;
+  let sha256 = match verification {
+    FileVerification::Valid { sha256 } => sha256,
+    FileVerification::Corrupt { reason } => {
+      // Resuming a corrupt file is pointless
+      let _ = tokio::fs::remove_file(&temp_path).await;
+      return Err(report_failure(progress, format!("Downloaded {filename} is corrupt ({reason}), please retry")));
+    }
+  };
 
+  // Move temp file to final location
+  tokio::fs::rename(&temp_path, file_path)
+    .await
+    .map_err(|e| report_failure(progress, format!("Failed to move downloaded file {filename}: {e}")))?;
+
+  if report_progress {
     let _ = progress.report_file_completed(filename);
-  } else {
-    // For small files, download without progress tracking
-    let bytes = response.bytes().await.map_err(|e| {
-      let msg = format!("Failed to read response for {filename}: {e}");
-      error!("{}", msg);
-      let _ = progress.report_error(&msg);
-      anyhow::anyhow!("{}", msg)
-    })?;
+  }
+  Ok(sha256)
+}
 
-    tokio::fs::write(file_path, &bytes).await.map_err(|e| {
-      let msg = format!("Failed to write {filename}: {e}");
-      error!("{}", msg);
-      let _ = progress.report_error(&msg);
-      anyhow::anyhow!("{}", msg)
-    })?;
+/// Manifest of the model files, fetching entries missing from the stored one. Without network access the
+/// files are downloaded unverified, their checksums are still recorded.
+async fn load_or_fetch_manifest(client: &ClientWithMiddleware, model_path: &Path, files: &[(&'static str, &'static str, Option<u32>)]) -> ModelManifest {
+  let mut manifest = ModelManifest::load(model_path).unwrap_or_default();
+  let missing: Vec<(&str, &str)> = files
+    .iter()
+    .filter(|(filename, _, _)| !manifest.files.contains_key(*filename))
+    .map(|(filename, url, _)| (*filename, *url))
+    .collect();
+  if missing.is_empty() {
+    return manifest;
   }
 
-  Ok(())
+  match fetch_manifest(client, &missing).await {
+    Ok(fetched) => manifest.files.extend(fetched.files),
+    Err(e) => warn!(error = %e, "Failed to fetch model manifest, downloaded files are not verified against it"),
+  }
+  manifest
 }
 
-/// Download model files with optional cancellation support
+/// Download model files with optional cancellation support.
+/// Interrupted downloads are resumed on the next call.
 #[instrument(skip(provider, progress, cancelled), fields(model = %model_config.model_name()))]
 pub async fn download_model_files(model_config: &ModelConfig, provider: &dyn ModelPathProvider, progress: &dyn ProgressReporter, cancelled: Option<Arc<AtomicBool>>) -> Result<()> {
   let cache_dir = provider.get_cache_dir()?;
@@ -315,16 +351,15 @@ pub async fn download_model_files(model_config: &ModelConfig, provider: &dyn Mod
   progress.report_started(files.len() as u32)?;
 
   // Create model directory if it doesn't exist
-  tokio::fs::create_dir_all(&model_path).await.map_err(|e| {
-    let msg = format!("Failed to create model directory: {e}");
-    error!("{}", msg);
-    let _ = progress.report_error(&msg);
-    anyhow::anyhow!("{}", msg)
-  })?;
+  tokio::fs::create_dir_all(&model_path)
+    .await
+    .map_err(|e| report_failure(progress, format!("Failed to create model directory: {e}")))?;
 
   // Create HTTP client with retry middleware
   let client = create_http_client()?;
 
+  let mut manifest = load_or_fetch_manifest(&client, &model_path, &files).await;
+
   for (filename, url, expected_size) in files {
     let file_path = model_path.join(filename);
 
@@ -349,20 +384,15 @@ pub async fn download_model_files(model_config: &ModelConfig, provider: &dyn Mod
       return Err(anyhow::anyhow!("Download cancelled"));
     }
 
-    // Download file with retry and resume support
-    match download_file(&client, url, &file_path, filename, expected_size, progress, cancelled.clone()).await {
-      Ok(_) => {
-        info!("Successfully downloaded {}", filename);
-      }
-      Err(e) => {
-        // Clean up partial downloads on failure
-        let temp_path = file_path.with_extension("download");
-        if temp_path.exists() {
-          warn!("Cleaning up partial download for {}", filename);
-          let _ = tokio::fs::remove_file(&temp_path).await;
-        }
-        return Err(e);
-      }
+    // Download file with retry and resume support, a failed download is resumed next time
+    let sha256 = download_file(&client, url, &file_path, filename, expected_size, manifest.files.get(filename), progress, cancelled.clone()).await?;
+    info!("Successfully downloaded {}", filename);
+
+    // Record the checksum, small files have none in the fetched manifest
+    let size = tokio::fs::metadata(&file_path).await?.len();
+    manifest.files.entry(filename.to_string()).or_insert(ManifestEntry { size, sha256: None }).sha256 = Some(sha256);
+    if let Err(e) = manifest.save(&model_path) {
+      warn!(error = %e, "Failed to save model manifest");
     }
   }
 
@@ -370,3 +400,44 @@ pub async fn download_model_files(model_config: &ModelConfig, provider: &dyn Mod
   info!("All model files downloaded successfully");
   Ok(())
 }
+
+/// Check downloaded model files against the manifest. Blocking, hashes every file.
+/// With `repair`, corrupt files are deleted so the next download fetches them again.
+#[instrument(skip(provider), fields(model = %model_config.model_name()))]
+pub fn verify_model_cache(model_config: &ModelConfig, provider: &dyn ModelPathProvider, repair: bool) -> Result<ModelCacheReport> {
+  let model_path = provider.get_cache_dir()?.join("models").join(model_config.model_id());
+  let manifest = ModelManifest::load(&model_path).unwrap_or_default();
+
+  let mut files = Vec::new();
+  for (filename, _, _) in model_config.download_urls() {
+    let file_path = model_path.join(filename);
+    let entry = manifest.files.get(filename);
+    let state = if file_path.exists() {
+      match verify_file(&file_path, entry)? {
+        FileVerification::Valid { .. } if entry.is_some_and(|entry| entry.sha256.is_some()) => ModelFileState::Valid,
+        FileVerification::Valid { .. } => ModelFileState::Unverified,
+        FileVerification::Corrupt { reason } => {
+          warn!(filename, %reason, "Corrupt model file");
+          if repair {
+            std::fs::remove_file(&file_path).with_context(|| format!("Failed to delete {}", file_path.display()))?;
+          }
+          ModelFileState::Corrupt { reason }
+        }
+      }
+    } else if let Ok(metadata) = std::fs::metadata(partial_download_path(&file_path)) {
+      ModelFileState::Partial {
+        downloaded: metadata.len().min(u32::MAX as u64) as u32,
+        expected: entry.map(|entry| entry.size.min(u32::MAX as u64) as u32),
+      }
+    } else {
+      ModelFileState::Missing
+    };
+    files.push(ModelFileStatus {
+      file_name: filename.to_string(),
+      state,
+    });
+  }
+
+  let is_complete = files.iter().all(|file| matches!(file.state, ModelFileState::Valid | ModelFileState::Unverified));
+  Ok(ModelCacheReport { files, is_complete })
+}
@@ -1,18 +1,29 @@
+use crate::commit_grouping::parse_commit_grouping_plan;
 use crate::path_provider::ModelPathProvider;
+use crate::types::CommitGroupingSuggestion;
 use anyhow::{Context, Result};
-use model_core::BranchNameResult;
 use model_core::config::ModelConfig;
 use model_core::generator_type::GeneratorType;
+use model_core::prompt::{create_commit_grouping_prompt, create_commit_message_prompt};
 use model_core::quantized_qwen3::QuantizedQwen3BranchGenerator;
 use model_core::qwen3::Qwen3BranchGenerator;
 use model_core::qwen25::Qwen25BranchGenerator;
+use model_core::{BranchNameResult, CommitMessageResult};
+use std::fmt::Write;
 use std::path::PathBuf;
 use std::sync::Arc;
+use sync_types::commit_grouping::CommitGroupingInput;
 use tokio::sync::Mutex;
 use tracing::info;
 
 // Generation constants - standard parameters: Temperature=0.7, TopP=0.8, TopK=20, MinP=0
 pub const MAX_NEW_TOKENS: usize = 1000; // Increased to allow for thinking tags and complete generation
+/// Commit messages are short, a subject line and an optional body
+pub const MAX_COMMIT_MESSAGE_TOKENS: usize = 400;
+/// Larger diffs are truncated to keep prompt processing fast on CPU
+pub const MAX_COMMIT_DIFF_CHARS: usize = 12_000;
+/// A grouping is a few short lines, one per branch
+pub const MAX_COMMIT_GROUPING_TOKENS: usize = 600;
 
 // Confidence scores for suggestions
 
@@ -251,4 +262,64 @@ impl ModelBasedBranchGenerator {
 
     generator.generate_branch_name(&prompt, MAX_NEW_TOKENS, previous_suggestion.is_some()).await
   }
+
+  /// Generate an improved commit message from the original message and the commit diff
+  pub async fn generate_commit_message(&mut self, original_message: &str, diff: &str) -> Result<CommitMessageResult> {
+    let generator = self.generator.as_mut().ok_or_else(|| anyhow::anyhow!("Model not loaded"))?;
+
+    let diff = truncate_diff(diff, MAX_COMMIT_DIFF_CHARS);
+    let prompt = create_commit_message_prompt(original_message, &diff)?;
+    info!(
+      "Generated commit message prompt with {} characters (model: {})",
+      prompt.len(),
+      self.model_config.model_name()
+    );
+
+    generator.generate_commit_message(&prompt, MAX_COMMIT_MESSAGE_TOKENS).await
+  }
+
+  /// Propose an assignment of unassigned commits to new or existing branches
+  pub async fn generate_commit_grouping(&mut self, input: &CommitGroupingInput) -> Result<CommitGroupingSuggestion> {
+    let generator = self.generator.as_mut().ok_or_else(|| anyhow::anyhow!("Model not loaded"))?;
+
+    let prompt = create_commit_grouping_prompt(&format_commits_for_grouping(input), &input.existing_branches)?;
+    info!(
+      "Generated commit grouping prompt with {} characters for {} commits (model: {})",
+      prompt.len(),
+      input.commits.len(),
+      self.model_config.model_name()
+    );
+
+    let result = generator.generate_commit_grouping(&prompt, MAX_COMMIT_GROUPING_TOKENS).await?;
+    Ok(CommitGroupingSuggestion {
+      plan: parse_commit_grouping_plan(&result.response, input),
+      generation_time_ms: result.generation_time_ms,
+    })
+  }
+}
+
+/// Numbered commit list for the grouping prompt, the numbers are what the model answers with
+pub(crate) fn format_commits_for_grouping(input: &CommitGroupingInput) -> String {
+  let mut text = String::new();
+  for (index, commit) in input.commits.iter().enumerate() {
+    let _ = write!(text, "{}. {}", index + 1, commit.subject);
+    if !commit.files.is_empty() {
+      let _ = write!(text, " ({})", commit.files.join(", "));
+    }
+    text.push('\n');
+  }
+  text
+}
+
+/// Cut the diff at a line boundary, noting that the rest was omitted
+pub(crate) fn truncate_diff(diff: &str, max_chars: usize) -> String {
+  if diff.len() <= max_chars {
+    return diff.to_string();
+  }
+  let mut end = max_chars;
+  while !diff.is_char_boundary(end) {
+    end -= 1;
+  }
+  let end = diff[..end].rfind('\n').unwrap_or(end);
+  format!("{}\n[diff truncated]", &diff[..end])
 }
@@ -1,10 +1,22 @@
+pub mod commit_grouping;
 pub mod download;
 pub mod generator;
+pub mod manifest;
 pub mod path_provider;
+pub mod remote;
 pub mod types;
 
 #[cfg(test)]
 mod test_utils;
 
+#[cfg(test)]
+mod commit_grouping_tests;
+
+#[cfg(test)]
+mod manifest_tests;
+
 #[cfg(test)]
 mod model_generation_tests;
+
+#[cfg(test)]
+mod remote_tests;
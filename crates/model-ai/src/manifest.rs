@@ -0,0 +1,164 @@
+//! Expected sizes and SHA256 checksums of model files.
+//!
+//! Checksums come from the Hugging Face file listing (LFS files carry their SHA256). Small files are not stored in LFS,
+//! their checksum is recorded after the first verified download. The manifest is kept next to the model files.
+
+use anyhow::{Context, Result, anyhow, bail};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+use tracing::{debug, instrument, warn};
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Extension of a file being downloaded, kept between attempts to resume the download
+pub const PARTIAL_DOWNLOAD_EXTENSION: &str = "download";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+  pub size: u64,
+  /// Lowercase hex, `None` until known
+  pub sha256: Option<String>,
+}
+
+/// Expected model files by file name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelManifest {
+  pub files: BTreeMap<String, ManifestEntry>,
+}
+
+impl ModelManifest {
+  pub fn load(model_path: &Path) -> Option<Self> {
+    let json = std::fs::read_to_string(model_path.join(MANIFEST_FILE_NAME)).ok()?;
+    serde_json::from_str(&json).inspect_err(|e| warn!(error = %e, "Ignoring invalid model manifest")).ok()
+  }
+
+  pub fn save(&self, model_path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(self)?;
+    std::fs::write(model_path.join(MANIFEST_FILE_NAME), json).context("Failed to write model manifest")
+  }
+}
+
+/// Repository, revision and path of a `https://huggingface.co/{owner}/{repo}/resolve/{revision}/{path}` URL
+pub(crate) fn parse_huggingface_url(url: &str) -> Option<(String, String, String)> {
+  let rest = url.strip_prefix("https://huggingface.co/")?;
+  let mut parts = rest.splitn(5, '/');
+  let owner = parts.next()?;
+  let repo = parts.next()?;
+  if parts.next()? != "resolve" {
+    return None;
+  }
+  let revision = parts.next()?;
+  let path = parts.next()?;
+  Some((format!("{owner}/{repo}"), revision.to_string(), path.to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+struct TreeEntry {
+  path: String,
+  size: u64,
+  lfs: Option<LfsInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LfsInfo {
+  oid: String,
+}
+
+/// Manifest entries of `paths` from a `GET /api/models/{repo}/tree/{revision}` response
+pub(crate) fn parse_huggingface_tree(json: &str, paths: &[&str]) -> Result<BTreeMap<String, ManifestEntry>> {
+  let entries: Vec<TreeEntry> = serde_json::from_str(json).context("Invalid Hugging Face file listing")?;
+  Ok(
+    entries
+      .into_iter()
+      .filter(|entry| paths.contains(&entry.path.as_str()))
+      .map(|entry| {
+        let manifest_entry = ManifestEntry {
+          size: entry.size,
+          sha256: entry.lfs.map(|lfs| lfs.oid.to_ascii_lowercase()),
+        };
+        (entry.path, manifest_entry)
+      })
+      .collect(),
+  )
+}
+
+/// Fetch expected sizes and checksums of the files (file name, URL) from Hugging Face
+#[instrument(skip_all)]
+pub async fn fetch_manifest(client: &ClientWithMiddleware, files: &[(&str, &str)]) -> Result<ModelManifest> {
+  // Files of one listing request, the tokenizer of a GGUF model lives in another repository
+  let mut listings: BTreeMap<(String, String), Vec<(&str, String)>> = BTreeMap::new();
+  for (file_name, url) in files {
+    let (repo, revision, path) = parse_huggingface_url(url).ok_or_else(|| anyhow!("Not a Hugging Face file URL: {url}"))?;
+    listings.entry((repo, revision)).or_default().push((file_name, path));
+  }
+
+  let mut manifest = ModelManifest::default();
+  for ((repo, revision), repo_files) in listings {
+    let url = format!("https://huggingface.co/api/models/{repo}/tree/{revision}");
+    let response = client.get(&url).send().await.with_context(|| format!("Failed to fetch {url}"))?;
+    if !response.status().is_success() {
+      bail!("Failed to fetch {url}: HTTP {}", response.status());
+    }
+    let json = response.text().await?;
+    let paths: Vec<&str> = repo_files.iter().map(|(_, path)| path.as_str()).collect();
+    let mut entries = parse_huggingface_tree(&json, &paths)?;
+    for (file_name, path) in repo_files {
+      if let Some(entry) = entries.remove(&path) {
+        manifest.files.insert(file_name.to_string(), entry);
+      }
+    }
+  }
+  debug!(files = manifest.files.len(), "Fetched model manifest");
+  Ok(manifest)
+}
+
+/// SHA256 of a file as lowercase hex. Blocking, a model file takes seconds to hash.
+pub fn sha256_file(path: &Path) -> Result<String> {
+  let mut file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+  let mut hasher = Sha256::new();
+  let mut buffer = vec![0u8; 1024 * 1024];
+  loop {
+    let read = file.read(&mut buffer)?;
+    if read == 0 {
+      break;
+    }
+    hasher.update(&buffer[..read]);
+  }
+  Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FileVerification {
+  /// Matches the manifest entry (if any); `sha256` is recorded for entries without a checksum
+  Valid {
+    sha256: String,
+  },
+  Corrupt {
+    reason: String,
+  },
+}
+
+/// Check a file against its manifest entry
+pub fn verify_file(path: &Path, entry: Option<&ManifestEntry>) -> Result<FileVerification> {
+  let size = std::fs::metadata(path).with_context(|| format!("Failed to read {}", path.display()))?.len();
+  if let Some(entry) = entry
+    && entry.size != size
+  {
+    return Ok(FileVerification::Corrupt {
+      reason: format!("size is {size} bytes, expected {}", entry.size),
+    });
+  }
+
+  let sha256 = sha256_file(path)?;
+  match entry.and_then(|entry| entry.sha256.as_deref()) {
+    Some(expected) if expected != sha256 => Ok(FileVerification::Corrupt {
+      reason: format!("SHA256 is {sha256}, expected {expected}"),
+    }),
+    _ => Ok(FileVerification::Valid { sha256 }),
+  }
+}
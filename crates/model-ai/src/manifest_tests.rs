@@ -0,0 +1,105 @@
+use crate::manifest::{FileVerification, ManifestEntry, ModelManifest, parse_huggingface_tree, parse_huggingface_url, sha256_file, verify_file};
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+// SHA256 of "hello world"
+const HELLO_SHA256: &str = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+#[test]
+fn test_parse_huggingface_url() {
+  assert_eq!(
+    parse_huggingface_url("https://huggingface.co/Qwen/Qwen3-1.7B-GGUF/resolve/main/Qwen3-1.7B-Q8_0.gguf"),
+    Some(("Qwen/Qwen3-1.7B-GGUF".to_string(), "main".to_string(), "Qwen3-1.7B-Q8_0.gguf".to_string()))
+  );
+  assert_eq!(
+    parse_huggingface_url("https://huggingface.co/owner/repo/resolve/abc123/nested/dir/file.json"),
+    Some(("owner/repo".to_string(), "abc123".to_string(), "nested/dir/file.json".to_string()))
+  );
+  assert_eq!(parse_huggingface_url("https://huggingface.co/owner/repo/blob/main/file.json"), None);
+  assert_eq!(parse_huggingface_url("https://example.com/owner/repo/resolve/main/file.json"), None);
+}
+
+#[test]
+fn test_parse_huggingface_tree() {
+  let json = r#"[
+    {"type":"file","oid":"0a1b","size":1024,"path":"tokenizer.json"},
+    {"type":"file","oid":"2c3d","size":1834426016,"path":"model.gguf","lfs":{"oid":"ABCDEF0123","size":1834426016,"pointerSize":135}},
+    {"type":"file","oid":"4e5f","size":10,"path":"README.md"}
+  ]"#;
+
+  let entries = parse_huggingface_tree(json, &["model.gguf", "tokenizer.json"]).unwrap();
+  assert_eq!(entries.len(), 2);
+  assert_eq!(
+    entries["model.gguf"],
+    ManifestEntry {
+      size: 1834426016,
+      sha256: Some("abcdef0123".to_string()),
+    }
+  );
+  // Git object id of a non-LFS file is not a SHA256 of its content
+  assert_eq!(entries["tokenizer.json"], ManifestEntry { size: 1024, sha256: None });
+
+  assert!(parse_huggingface_tree("{\"error\":\"Repository not found\"}", &["model.gguf"]).is_err());
+}
+
+#[test]
+fn test_verify_file() {
+  let dir = TempDir::new().unwrap();
+  let path = dir.path().join("model.gguf");
+  std::fs::write(&path, "hello world").unwrap();
+  assert_eq!(sha256_file(&path).unwrap(), HELLO_SHA256);
+
+  let valid = FileVerification::Valid { sha256: HELLO_SHA256.to_string() };
+  assert_eq!(verify_file(&path, None).unwrap(), valid);
+  assert_eq!(verify_file(&path, Some(&ManifestEntry { size: 11, sha256: None })).unwrap(), valid);
+  assert_eq!(
+    verify_file(
+      &path,
+      Some(&ManifestEntry {
+        size: 11,
+        sha256: Some(HELLO_SHA256.to_string()),
+      })
+    )
+    .unwrap(),
+    valid
+  );
+
+  assert_eq!(
+    verify_file(&path, Some(&ManifestEntry { size: 20, sha256: None })).unwrap(),
+    FileVerification::Corrupt {
+      reason: "size is 11 bytes, expected 20".to_string(),
+    }
+  );
+  assert!(matches!(
+    verify_file(
+      &path,
+      Some(&ManifestEntry {
+        size: 11,
+        sha256: Some("0".repeat(64)),
+      })
+    )
+    .unwrap(),
+    FileVerification::Corrupt { .. }
+  ));
+}
+
+#[test]
+fn test_manifest_roundtrip() {
+  let dir = TempDir::new().unwrap();
+  assert_eq!(ModelManifest::load(dir.path()), None);
+
+  let mut manifest = ModelManifest::default();
+  manifest.files.insert(
+    "model.gguf".to_string(),
+    ManifestEntry {
+      size: 11,
+      sha256: Some(HELLO_SHA256.to_string()),
+    },
+  );
+  manifest.save(dir.path()).unwrap();
+  assert_eq!(ModelManifest::load(dir.path()), Some(manifest));
+
+  // A broken manifest is ignored and fetched again
+  std::fs::write(dir.path().join("manifest.json"), "not json").unwrap();
+  assert_eq!(ModelManifest::load(dir.path()), None);
+}
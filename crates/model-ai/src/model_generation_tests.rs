@@ -1,3 +1,4 @@
+use crate::generator::{format_commits_for_grouping, truncate_diff};
 use crate::test_utils::{CommitDiff, FileDiff, convert_to_raw_git_format};
 use anyhow::Result;
 use git_ops::model::CommitInfo;
@@ -9,6 +10,7 @@ use model_core::qwen25::Qwen25BranchGenerator;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Instant;
+use sync_types::commit_grouping::{CommitGroupingInput, UngroupedCommit};
 
 // Test configuration
 const MAX_NEW_TOKENS: usize = 1000; // Increased to allow for thinking tags and complete generation
@@ -709,3 +711,30 @@ const VALIDATORS_MOD_PATCH: &str = r#"@@ -1,2 +1,5 @@
 +pub mod user;
 +
 +pub use user::UserValidator;"#;
+
+#[test]
+fn test_truncate_diff() {
+  let diff = "--- a/lib.rs\n+++ b/lib.rs\n+fn one() {}\n+fn two() {}\n";
+  assert_eq!(truncate_diff(diff, 100), diff);
+  assert_eq!(truncate_diff(diff, 30), "--- a/lib.rs\n+++ b/lib.rs\n[diff truncated]");
+}
+
+#[test]
+fn test_format_commits_for_grouping() {
+  let input = CommitGroupingInput {
+    commits: vec![
+      UngroupedCommit {
+        id: "abc123".to_string(),
+        subject: "Add parser".to_string(),
+        files: vec!["src/parser.rs".to_string(), "src/lib.rs".to_string()],
+      },
+      UngroupedCommit {
+        id: "def456".to_string(),
+        subject: "Bump version".to_string(),
+        files: Vec::new(),
+      },
+    ],
+    existing_branches: Vec::new(),
+  };
+  assert_eq!(format_commits_for_grouping(&input), "1. Add parser (src/parser.rs, src/lib.rs)\n2. Bump version\n");
+}
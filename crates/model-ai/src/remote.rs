@@ -0,0 +1,415 @@
+//! Remote LLM providers for users who can't spare the GPU/CPU for the local model.
+//!
+//! The provider is selected per repository in git config. API keys are never written to git config:
+//! they are kept by the git credential helper (OS keychain with the usual helpers), keyed by the endpoint.
+
+use anyhow::{Context, Result, anyhow, bail};
+use futures_util::StreamExt;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::git_config::{get_config_value, set_local_config_value};
+use model_core::BranchNameResult;
+use model_core::prompt::create_branch_name_system_message;
+use model_core::utils::clean_branch_name;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+use tracing::{debug, instrument, warn};
+
+#[cfg(feature = "specta")]
+use specta::Type;
+
+/// Git config key selecting the provider (`local`, `openai`, `anthropic` or `ollama`)
+pub const AI_PROVIDER_CONFIG_KEY: &str = "branchdeck.aiProvider";
+/// Git config key overriding the provider endpoint, e.g. an OpenAI-compatible server
+pub const AI_ENDPOINT_CONFIG_KEY: &str = "branchdeck.aiEndpoint";
+/// Git config key overriding the model requested from the provider
+pub const AI_MODEL_CONFIG_KEY: &str = "branchdeck.aiModel";
+
+/// A branch name is a single short line
+pub const MAX_REMOTE_BRANCH_NAME_TOKENS: u32 = 100;
+
+/// Username the API key is stored under in the credential helper
+const API_KEY_CREDENTIAL_USERNAME: &str = "branch-deck-api-key";
+
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Backend generating AI suggestions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub enum AiProvider {
+  /// Bundled Qwen model running on this machine
+  #[default]
+  Local,
+  /// OpenAI chat completions API, or any server compatible with it
+  OpenAi,
+  Anthropic,
+  /// Ollama server, local or on another machine
+  Ollama,
+}
+
+impl AiProvider {
+  /// Parse a config value, case-insensitive
+  pub fn from_config_value(value: &str) -> Option<Self> {
+    match value.trim().to_ascii_lowercase().as_str() {
+      "local" => Some(Self::Local),
+      "openai" => Some(Self::OpenAi),
+      "anthropic" => Some(Self::Anthropic),
+      "ollama" => Some(Self::Ollama),
+      _ => None,
+    }
+  }
+
+  pub fn config_value(&self) -> &'static str {
+    match self {
+      Self::Local => "local",
+      Self::OpenAi => "openai",
+      Self::Anthropic => "anthropic",
+      Self::Ollama => "ollama",
+    }
+  }
+
+  pub fn is_remote(&self) -> bool {
+    *self != Self::Local
+  }
+
+  /// Ollama doesn't authenticate requests
+  pub fn requires_api_key(&self) -> bool {
+    matches!(self, Self::OpenAi | Self::Anthropic)
+  }
+
+  pub fn default_endpoint(&self) -> Option<&'static str> {
+    match self {
+      Self::Local => None,
+      Self::OpenAi => Some("https://api.openai.com/v1/chat/completions"),
+      Self::Anthropic => Some("https://api.anthropic.com/v1/messages"),
+      Self::Ollama => Some("http://localhost:11434/api/chat"),
+    }
+  }
+
+  /// Small, fast models are enough for branch names
+  pub fn default_model(&self) -> Option<&'static str> {
+    match self {
+      Self::Local => None,
+      Self::OpenAi => Some("gpt-4o-mini"),
+      Self::Anthropic => Some("claude-3-5-haiku-latest"),
+      Self::Ollama => Some("qwen2.5-coder:1.5b"),
+    }
+  }
+}
+
+/// Provider selected for a repository
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct AiProviderSettings {
+  pub provider: AiProvider,
+  /// Provider endpoint, `None` uses the provider default
+  pub endpoint: Option<String>,
+  /// Model name, `None` uses the provider default
+  pub model: Option<String>,
+}
+
+impl AiProviderSettings {
+  pub fn endpoint(&self) -> Option<&str> {
+    self.endpoint.as_deref().or(self.provider.default_endpoint())
+  }
+
+  pub fn model(&self) -> Option<&str> {
+    self.model.as_deref().or(self.provider.default_model())
+  }
+}
+
+/// Read the provider settings from git config, falling back to the local model for missing or invalid values
+#[instrument(skip(git_executor))]
+pub fn load_ai_provider_settings(git_executor: &GitCommandExecutor, repository_path: &str) -> AiProviderSettings {
+  let mut settings = AiProviderSettings::default();
+
+  match get_config_value(git_executor, repository_path, AI_PROVIDER_CONFIG_KEY) {
+    Ok(Some(value)) => match AiProvider::from_config_value(&value) {
+      Some(provider) => settings.provider = provider,
+      None => warn!(value, "Unknown {AI_PROVIDER_CONFIG_KEY} value, using the local model"),
+    },
+    Ok(None) => {}
+    Err(e) => warn!(error = %e, "Failed to read AI provider, using the local model"),
+  }
+
+  let read_optional = |key: &str| match get_config_value(git_executor, repository_path, key) {
+    Ok(value) => value.filter(|value| !value.is_empty()),
+    Err(e) => {
+      warn!(error = %e, key, "Failed to read AI provider setting, using the default");
+      None
+    }
+  };
+  settings.endpoint = read_optional(AI_ENDPOINT_CONFIG_KEY);
+  settings.model = read_optional(AI_MODEL_CONFIG_KEY);
+
+  settings
+}
+
+/// Store the provider settings in the repository config
+#[instrument(skip(git_executor))]
+pub fn save_ai_provider_settings(git_executor: &GitCommandExecutor, repository_path: &str, settings: &AiProviderSettings) -> Result<()> {
+  set_local_config_value(git_executor, repository_path, AI_PROVIDER_CONFIG_KEY, Some(settings.provider.config_value()))?;
+  set_local_config_value(
+    git_executor,
+    repository_path,
+    AI_ENDPOINT_CONFIG_KEY,
+    settings.endpoint.as_deref().filter(|v| !v.is_empty()),
+  )?;
+  set_local_config_value(git_executor, repository_path, AI_MODEL_CONFIG_KEY, settings.model.as_deref().filter(|v| !v.is_empty()))?;
+  Ok(())
+}
+
+/// `git credential` input identifying the API key of the endpoint
+fn api_key_credential(settings: &AiProviderSettings) -> Result<String> {
+  let endpoint = settings
+    .endpoint()
+    .ok_or_else(|| anyhow!("No endpoint configured for {}", settings.provider.config_value()))?;
+  let url = reqwest::Url::parse(endpoint).with_context(|| format!("Invalid endpoint {endpoint}"))?;
+  let host = url.host_str().ok_or_else(|| anyhow!("Endpoint {endpoint} has no host"))?;
+  let host = match url.port() {
+    Some(port) => format!("{host}:{port}"),
+    None => host.to_string(),
+  };
+  Ok(format!("protocol={}\nhost={host}\nusername={API_KEY_CREDENTIAL_USERNAME}\n", url.scheme()))
+}
+
+/// Never let git ask the user, a missing key is reported by the caller
+const NON_INTERACTIVE_ENV: &[(&str, &str)] = &[("GIT_TERMINAL_PROMPT", "0"), ("GIT_ASKPASS", "")];
+
+/// API key of the provider endpoint from the git credential helper, `None` if none is stored
+#[instrument(skip(git_executor))]
+pub fn get_ai_api_key(git_executor: &GitCommandExecutor, repository_path: &str, settings: &AiProviderSettings) -> Result<Option<String>> {
+  let input = api_key_credential(settings)? + "\n";
+  let output = git_executor.execute_command_with_env_and_stdin(&["-c", "credential.interactive=false", "credential", "fill"], repository_path, NON_INTERACTIVE_ENV, &input);
+  // Without a stored key git fails trying to prompt for it
+  let output = match output {
+    Ok(output) => output,
+    Err(e) => {
+      debug!(error = %e, "No API key in the credential helper");
+      return Ok(None);
+    }
+  };
+  Ok(
+    output
+      .lines()
+      .find_map(|line| line.strip_prefix("password="))
+      .filter(|key| !key.is_empty())
+      .map(str::to_string),
+  )
+}
+
+/// Store the API key of the provider endpoint in the git credential helper, `None` removes it
+#[instrument(skip(git_executor, api_key))]
+pub fn store_ai_api_key(git_executor: &GitCommandExecutor, repository_path: &str, settings: &AiProviderSettings, api_key: Option<&str>) -> Result<()> {
+  let credential = api_key_credential(settings)?;
+  match api_key.map(str::trim).filter(|key| !key.is_empty()) {
+    Some(api_key) => {
+      // `git credential approve` silently does nothing without a helper
+      if get_config_value(git_executor, repository_path, "credential.helper")?.is_none_or(|helper| helper.is_empty()) {
+        bail!("No git credential helper is configured to store the API key (git config credential.helper)");
+      }
+      let input = format!("{credential}password={api_key}\n\n");
+      git_executor.execute_command_with_env_and_stdin(&["credential", "approve"], repository_path, NON_INTERACTIVE_ENV, &input)?;
+    }
+    None => {
+      git_executor.execute_command_with_env_and_stdin(&["credential", "reject"], repository_path, NON_INTERACTIVE_ENV, &(credential + "\n"))?;
+    }
+  }
+  Ok(())
+}
+
+/// One parsed line of a streaming response
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum StreamEvent {
+  Text(String),
+  Done,
+  Error(String),
+  /// Keep-alives, role announcements, SSE `event:` lines
+  Ignored,
+}
+
+/// Parse a line of the provider's streaming format: server-sent events for OpenAI and Anthropic, JSON lines for Ollama
+pub(crate) fn parse_stream_line(provider: AiProvider, line: &str) -> StreamEvent {
+  let line = line.trim();
+  let payload = match provider {
+    AiProvider::OpenAi | AiProvider::Anthropic => match line.strip_prefix("data:") {
+      Some(data) => data.trim(),
+      None => return StreamEvent::Ignored,
+    },
+    AiProvider::Ollama | AiProvider::Local => line,
+  };
+  if payload.is_empty() {
+    return StreamEvent::Ignored;
+  }
+  if payload == "[DONE]" {
+    return StreamEvent::Done;
+  }
+
+  let Ok(value) = serde_json::from_str::<Value>(payload) else {
+    return StreamEvent::Ignored;
+  };
+  if let Some(message) = error_message(&value) {
+    return StreamEvent::Error(message);
+  }
+
+  let text = match provider {
+    AiProvider::OpenAi => value.pointer("/choices/0/delta/content").and_then(Value::as_str),
+    AiProvider::Anthropic => match value.get("type").and_then(Value::as_str) {
+      Some("content_block_delta") => value.pointer("/delta/text").and_then(Value::as_str),
+      Some("message_stop") => return StreamEvent::Done,
+      _ => None,
+    },
+    AiProvider::Ollama | AiProvider::Local => {
+      if value.get("done").and_then(Value::as_bool) == Some(true) {
+        return StreamEvent::Done;
+      }
+      value.pointer("/message/content").and_then(Value::as_str)
+    }
+  };
+  match text {
+    Some(text) if !text.is_empty() => StreamEvent::Text(text.to_string()),
+    _ => StreamEvent::Ignored,
+  }
+}
+
+/// `{"error": {"message": ...}}` (OpenAI, Anthropic) or `{"error": "..."}` (Ollama)
+fn error_message(value: &Value) -> Option<String> {
+  let error = value.get("error")?;
+  let message = error.get("message").and_then(Value::as_str).or(error.as_str()).unwrap_or("Unknown error");
+  Some(message.to_string())
+}
+
+/// Request body of a streaming chat request
+pub(crate) fn build_request_body(provider: AiProvider, model: &str, system: &str, user: &str, max_tokens: u32) -> Value {
+  match provider {
+    AiProvider::Anthropic => json!({
+      "model": model,
+      "max_tokens": max_tokens,
+      "stream": true,
+      "system": system,
+      "messages": [{"role": "user", "content": user}],
+    }),
+    AiProvider::Ollama | AiProvider::Local => json!({
+      "model": model,
+      "stream": true,
+      "options": {"num_predict": max_tokens},
+      "messages": [{"role": "system", "content": system}, {"role": "user", "content": user}],
+    }),
+    AiProvider::OpenAi => json!({
+      "model": model,
+      "max_tokens": max_tokens,
+      "stream": true,
+      "messages": [{"role": "system", "content": system}, {"role": "user", "content": user}],
+    }),
+  }
+}
+
+/// Generator calling a remote provider over HTTP
+pub struct RemoteGenerator {
+  provider: AiProvider,
+  endpoint: String,
+  model: String,
+  api_key: Option<String>,
+  client: reqwest::Client,
+}
+
+impl std::fmt::Debug for RemoteGenerator {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    // Never log the API key
+    f.debug_struct("RemoteGenerator")
+      .field("provider", &self.provider)
+      .field("endpoint", &self.endpoint)
+      .field("model", &self.model)
+      .field("has_api_key", &self.api_key.is_some())
+      .finish()
+  }
+}
+
+impl RemoteGenerator {
+  pub fn new(settings: &AiProviderSettings, api_key: Option<String>) -> Result<Self> {
+    let provider = settings.provider;
+    if !provider.is_remote() {
+      bail!("The local model is not a remote provider");
+    }
+    let api_key = api_key.filter(|key| !key.trim().is_empty());
+    if provider.requires_api_key() && api_key.is_none() {
+      bail!("No API key stored for {}", provider.config_value());
+    }
+
+    let endpoint = settings.endpoint().ok_or_else(|| anyhow!("No endpoint configured for {}", provider.config_value()))?;
+    let model = settings.model().ok_or_else(|| anyhow!("No model configured for {}", provider.config_value()))?;
+    let client = reqwest::Client::builder()
+      .connect_timeout(CONNECT_TIMEOUT)
+      .timeout(REQUEST_TIMEOUT)
+      .build()
+      .context("Failed to create HTTP client")?;
+    Ok(Self {
+      provider,
+      endpoint: endpoint.to_string(),
+      model: model.to_string(),
+      api_key,
+      client,
+    })
+  }
+
+  pub fn provider(&self) -> AiProvider {
+    self.provider
+  }
+
+  /// Generate a branch name from git output, `on_text` receives the text generated so far as it streams in
+  #[instrument(skip(self, git_output, on_text), fields(provider = ?self.provider, model = %self.model))]
+  pub async fn generate_branch_name(&self, git_output: &str, previous_suggestion: Option<&str>, on_text: impl FnMut(&str)) -> Result<BranchNameResult> {
+    let start_time = Instant::now();
+    let system = create_branch_name_system_message(previous_suggestion);
+    let text = self.stream_chat(&system, git_output.trim(), MAX_REMOTE_BRANCH_NAME_TOKENS, on_text).await?;
+    Ok(BranchNameResult {
+      name: clean_branch_name(&text)?,
+      generation_time_ms: start_time.elapsed().as_millis() as u64,
+    })
+  }
+
+  async fn stream_chat(&self, system: &str, user: &str, max_tokens: u32, mut on_text: impl FnMut(&str)) -> Result<String> {
+    let body = build_request_body(self.provider, &self.model, system, user, max_tokens);
+    let mut request = self.client.post(&self.endpoint).json(&body);
+    if let Some(api_key) = &self.api_key {
+      request = match self.provider {
+        AiProvider::Anthropic => request.header("x-api-key", api_key).header("anthropic-version", ANTHROPIC_API_VERSION),
+        _ => request.bearer_auth(api_key),
+      };
+    }
+
+    let response = request.send().await.with_context(|| format!("Failed to connect to {}", self.endpoint))?;
+    let status = response.status();
+    if !status.is_success() {
+      let body = response.text().await.unwrap_or_default();
+      let message = serde_json::from_str::<Value>(&body).ok().and_then(|value| error_message(&value)).unwrap_or(body);
+      bail!("{} request failed ({status}): {message}", self.provider.config_value());
+    }
+
+    let mut text = String::new();
+    let mut pending = Vec::new();
+    let mut stream = response.bytes_stream();
+    'stream: while let Some(chunk) = stream.next().await {
+      pending.extend_from_slice(&chunk.context("Failed to read the response")?);
+      while let Some(line_end) = pending.iter().position(|&b| b == b'\n') {
+        let line: Vec<u8> = pending.drain(..=line_end).collect();
+        match parse_stream_line(self.provider, &String::from_utf8_lossy(&line)) {
+          StreamEvent::Text(delta) => {
+            text.push_str(&delta);
+            on_text(&text);
+          }
+          StreamEvent::Done => break 'stream,
+          StreamEvent::Error(message) => bail!("{} error: {message}", self.provider.config_value()),
+          StreamEvent::Ignored => {}
+        }
+      }
+    }
+
+    debug!(text = %text, "Remote generation finished");
+    Ok(text)
+  }
+}
@@ -0,0 +1,155 @@
+use crate::remote::{
+  AiProvider, AiProviderSettings, RemoteGenerator, StreamEvent, build_request_body, get_ai_api_key, load_ai_provider_settings, parse_stream_line, save_ai_provider_settings,
+  store_ai_api_key,
+};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_parse_openai_stream() {
+  let provider = AiProvider::OpenAi;
+  assert_eq!(
+    parse_stream_line(provider, r#"data: {"choices":[{"index":0,"delta":{"content":"fix-"}}]}"#),
+    StreamEvent::Text("fix-".to_string())
+  );
+  assert_eq!(
+    parse_stream_line(provider, r#"data: {"choices":[{"index":0,"delta":{"role":"assistant"}}]}"#),
+    StreamEvent::Ignored
+  );
+  assert_eq!(parse_stream_line(provider, ": keep-alive"), StreamEvent::Ignored);
+  assert_eq!(parse_stream_line(provider, "data: [DONE]"), StreamEvent::Done);
+  assert_eq!(
+    parse_stream_line(provider, r#"data: {"error":{"message":"Rate limit reached"}}"#),
+    StreamEvent::Error("Rate limit reached".to_string())
+  );
+}
+
+#[test]
+fn test_parse_anthropic_stream() {
+  let provider = AiProvider::Anthropic;
+  assert_eq!(parse_stream_line(provider, "event: content_block_delta"), StreamEvent::Ignored);
+  assert_eq!(
+    parse_stream_line(provider, r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"parser"}}"#),
+    StreamEvent::Text("parser".to_string())
+  );
+  assert_eq!(parse_stream_line(provider, r#"data: {"type":"ping"}"#), StreamEvent::Ignored);
+  assert_eq!(parse_stream_line(provider, r#"data: {"type":"message_stop"}"#), StreamEvent::Done);
+  assert_eq!(
+    parse_stream_line(provider, r#"data: {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#),
+    StreamEvent::Error("Overloaded".to_string())
+  );
+}
+
+#[test]
+fn test_parse_ollama_stream() {
+  let provider = AiProvider::Ollama;
+  assert_eq!(
+    parse_stream_line(provider, r#"{"model":"qwen2.5-coder:1.5b","message":{"role":"assistant","content":"add"},"done":false}"#),
+    StreamEvent::Text("add".to_string())
+  );
+  assert_eq!(
+    parse_stream_line(provider, r#"{"message":{"role":"assistant","content":""},"done":true}"#),
+    StreamEvent::Done
+  );
+  assert_eq!(
+    parse_stream_line(provider, r#"{"error":"model not found"}"#),
+    StreamEvent::Error("model not found".to_string())
+  );
+}
+
+#[test]
+fn test_build_anthropic_request_body() {
+  assert_eq!(
+    build_request_body(AiProvider::Anthropic, "model", "system", "user", 100),
+    json!({
+      "model": "model",
+      "max_tokens": 100,
+      "stream": true,
+      "system": "system",
+      "messages": [{"role": "user", "content": "user"}],
+    })
+  );
+}
+
+#[test]
+fn test_remote_generator_requires_api_key() {
+  let settings = AiProviderSettings {
+    provider: AiProvider::OpenAi,
+    ..Default::default()
+  };
+  assert!(RemoteGenerator::new(&settings, None).is_err());
+  assert!(RemoteGenerator::new(&settings, Some(" ".to_string())).is_err());
+  assert!(RemoteGenerator::new(&settings, Some("key".to_string())).is_ok());
+
+  let settings = AiProviderSettings {
+    provider: AiProvider::Ollama,
+    ..Default::default()
+  };
+  assert!(RemoteGenerator::new(&settings, None).is_ok());
+  assert!(RemoteGenerator::new(&AiProviderSettings::default(), None).is_err());
+}
+
+#[test]
+fn test_save_and_load_ai_provider_settings() {
+  let test_repo = TestRepo::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+
+  assert_eq!(load_ai_provider_settings(&git_executor, repo_path), AiProviderSettings::default());
+
+  let settings = AiProviderSettings {
+    provider: AiProvider::Ollama,
+    endpoint: Some("http://gpu-box:11434/api/chat".to_string()),
+    model: None,
+  };
+  save_ai_provider_settings(&git_executor, repo_path, &settings).unwrap();
+  let loaded = load_ai_provider_settings(&git_executor, repo_path);
+  assert_eq!(loaded, settings);
+  assert_eq!(loaded.model(), Some("qwen2.5-coder:1.5b"));
+
+  // Back to the local model, the endpoint is removed
+  save_ai_provider_settings(&git_executor, repo_path, &AiProviderSettings::default()).unwrap();
+  assert_eq!(load_ai_provider_settings(&git_executor, repo_path), AiProviderSettings::default());
+
+  test_repo.set_config("branchdeck.aiProvider", "gemini").unwrap();
+  assert_eq!(load_ai_provider_settings(&git_executor, repo_path).provider, AiProvider::Local);
+}
+
+#[test]
+fn test_store_ai_api_key_in_credential_helper() {
+  let test_repo = TestRepo::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+  let settings = AiProviderSettings {
+    provider: AiProvider::OpenAi,
+    ..Default::default()
+  };
+
+  // Reset helpers of the user config, then use a plain file inside the repository
+  let credentials_file = test_repo.path().join(".git").join("test-credentials");
+  git_executor.execute_command(&["config", "credential.helper", ""], repo_path).unwrap();
+  git_executor
+    .execute_command(
+      &["config", "--add", "credential.helper", &format!("store --file={}", credentials_file.display())],
+      repo_path,
+    )
+    .unwrap();
+
+  assert_eq!(get_ai_api_key(&git_executor, repo_path, &settings).unwrap(), None);
+
+  store_ai_api_key(&git_executor, repo_path, &settings, Some("sk-test")).unwrap();
+  assert_eq!(get_ai_api_key(&git_executor, repo_path, &settings).unwrap(), Some("sk-test".to_string()));
+  assert!(!std::fs::read_to_string(test_repo.path().join(".git").join("config")).unwrap().contains("sk-test"));
+
+  // The key belongs to the endpoint
+  let other_endpoint = AiProviderSettings {
+    endpoint: Some("https://llm.example.com/v1/chat/completions".to_string()),
+    ..settings.clone()
+  };
+  assert_eq!(get_ai_api_key(&git_executor, repo_path, &other_endpoint).unwrap(), None);
+
+  store_ai_api_key(&git_executor, repo_path, &settings, None).unwrap();
+  assert_eq!(get_ai_api_key(&git_executor, repo_path, &settings).unwrap(), None);
+}
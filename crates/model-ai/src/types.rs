@@ -15,12 +15,27 @@ pub struct BranchSuggestion {
 #[cfg_attr(feature = "specta", derive(Type))]
 #[serde(tag = "type", content = "data")]
 pub enum SuggestionProgress {
-  Started { total: u32 },
-  SuggestionReady { suggestion: BranchSuggestion, index: u32 },
+  Started {
+    total: u32,
+  },
+  /// Text generated so far by a remote provider, replaced by `SuggestionReady` for the same index
+  PartialSuggestion {
+    text: String,
+    index: u32,
+  },
+  SuggestionReady {
+    suggestion: BranchSuggestion,
+    index: u32,
+  },
   Completed,
   Cancelled,
-  Error { message: String },
-  ModelDownloadInProgress { model_name: String, model_size: String },
+  Error {
+    message: String,
+  },
+  ModelDownloadInProgress {
+    model_name: String,
+    model_size: String,
+  },
 }
 
 /// Parameters for requesting branch name suggestions
@@ -33,6 +48,113 @@ pub struct SuggestBranchNameParams {
   pub commits: Vec<git_ops::model::CommitInfo>,
 }
 
+/// Parameters for reading the AI provider of a repository
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct GetAiProviderSettingsParams {
+  pub repository_path: String,
+}
+
+/// Parameters for selecting the AI provider of a repository
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SetAiProviderSettingsParams {
+  pub repository_path: String,
+  pub settings: crate::remote::AiProviderSettings,
+}
+
+/// Parameters for storing the API key of a provider endpoint
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SetAiProviderApiKeyParams {
+  pub repository_path: String,
+  pub settings: crate::remote::AiProviderSettings,
+  /// `None` removes the stored key
+  pub api_key: Option<String>,
+}
+
+/// AI provider of a repository; the API key itself never leaves the backend
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct AiProviderSettingsInfo {
+  pub settings: crate::remote::AiProviderSettings,
+  pub has_api_key: bool,
+}
+
+/// Progress events for commit message suggestion generation
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(tag = "type", content = "data")]
+pub enum CommitMessageProgress {
+  Started,
+  MessageReady { message: String, reason: Option<String> },
+  Completed,
+  Cancelled,
+  Error { message: String },
+  ModelDownloadInProgress { model_name: String, model_size: String },
+}
+
+/// Parameters for requesting an improved commit message
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestCommitMessageParams {
+  pub repository_path: String,
+  pub commit_id: String,
+}
+
+/// Parameters for replacing a commit message with an accepted suggestion
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ApplySuggestedMessageParams {
+  pub repository_path: String,
+  pub commit_id: String,
+  pub message: String,
+}
+
+/// Commit grouping proposed by the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct CommitGroupingSuggestion {
+  pub plan: sync_types::commit_grouping::CommitGroupingPlan,
+  pub generation_time_ms: u64,
+}
+
+/// Progress events for commit grouping suggestion generation
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(tag = "type", content = "data")]
+pub enum CommitGroupingProgress {
+  Started,
+  PlanReady {
+    plan: sync_types::commit_grouping::CommitGroupingPlan,
+    reason: Option<String>,
+  },
+  Completed,
+  Cancelled,
+  Error {
+    message: String,
+  },
+  ModelDownloadInProgress {
+    model_name: String,
+    model_size: String,
+  },
+}
+
+/// Parameters for requesting a grouping of unassigned commits into branches
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestCommitGroupingParams {
+  pub repository_path: String,
+}
+
 /// Progress events for model download operations
 #[derive(Debug, Clone, Serialize)]
 #[cfg_attr(feature = "specta", derive(Type))]
@@ -58,6 +180,18 @@ pub enum DownloadProgress {
     #[serde(rename = "secondsRemaining")]
     seconds_remaining: Option<u32>,
   },
+  /// An interrupted download continues where it stopped
+  FileResumed {
+    #[serde(rename = "fileName")]
+    file_name: String,
+    #[serde(rename = "resumedFrom")]
+    resumed_from: u32,
+  },
+  /// The downloaded file is checked against its expected size and SHA256
+  FileVerifying {
+    #[serde(rename = "fileName")]
+    file_name: String,
+  },
   FileCompleted {
     #[serde(rename = "fileName")]
     file_name: String,
@@ -68,3 +202,50 @@ pub enum DownloadProgress {
     message: String,
   },
 }
+
+/// Parameters for checking downloaded model files
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyModelCacheParams {
+  /// Delete corrupt files so the next download fetches them again
+  pub repair: bool,
+}
+
+/// State of a downloaded model file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(tag = "type", content = "data")]
+pub enum ModelFileState {
+  /// Size and SHA256 match the manifest
+  Valid,
+  /// Present, but no checksum is known
+  Unverified,
+  Missing,
+  /// Interrupted download, resumed by the next download
+  Partial {
+    downloaded: u32,
+    expected: Option<u32>,
+  },
+  Corrupt {
+    reason: String,
+  },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ModelFileStatus {
+  pub file_name: String,
+  pub state: ModelFileState,
+}
+
+/// Result of checking downloaded model files
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCacheReport {
+  pub files: Vec<ModelFileStatus>,
+  /// All files are present and not corrupt
+  pub is_complete: bool,
+}
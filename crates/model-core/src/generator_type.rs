@@ -1,7 +1,7 @@
-use crate::BranchNameResult;
 use crate::quantized_qwen3::QuantizedQwen3BranchGenerator;
 use crate::qwen3::Qwen3BranchGenerator;
 use crate::qwen25::Qwen25BranchGenerator;
+use crate::{BranchNameResult, CommitGroupingResult, CommitMessageResult};
 use anyhow::Result;
 use std::path::PathBuf;
 
@@ -23,6 +23,24 @@ impl GeneratorType {
     }
   }
 
+  /// Generate a commit message from a prompt created by `prompt::create_commit_message_prompt`
+  pub async fn generate_commit_message(&mut self, prompt: &str, max_tokens: usize) -> Result<CommitMessageResult> {
+    match self {
+      GeneratorType::Qwen25(generator) => generator.generate_commit_message(prompt, max_tokens).await,
+      GeneratorType::Qwen3(generator) => generator.generate_commit_message(prompt, max_tokens).await,
+      GeneratorType::QuantizedQwen3(generator) => generator.generate_commit_message(prompt, max_tokens).await,
+    }
+  }
+
+  /// Generate a commit grouping from a prompt created by `prompt::create_commit_grouping_prompt`
+  pub async fn generate_commit_grouping(&mut self, prompt: &str, max_tokens: usize) -> Result<CommitGroupingResult> {
+    match self {
+      GeneratorType::Qwen25(generator) => generator.generate_commit_grouping(prompt, max_tokens).await,
+      GeneratorType::Qwen3(generator) => generator.generate_commit_grouping(prompt, max_tokens).await,
+      GeneratorType::QuantizedQwen3(generator) => generator.generate_commit_grouping(prompt, max_tokens).await,
+    }
+  }
+
   /// Load model from specified directory
   pub async fn load_model(&mut self, model_path: PathBuf) -> Result<()> {
     match self {
@@ -22,6 +22,20 @@ pub struct BranchNameResult {
   pub generation_time_ms: u64,
 }
 
+/// Commit message generation result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitMessageResult {
+  pub message: String,
+  pub generation_time_ms: u64,
+}
+
+/// Commit grouping generation result, `response` is the raw model output parsed by the caller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitGroupingResult {
+  pub response: String,
+  pub generation_time_ms: u64,
+}
+
 // All model implementations are now in separate files for clarity:
 // - qwen25.rs: Qwen2.5-Coder models (uses candle_transformers::models::qwen2)
 // - qwen3.rs: Qwen3 models (uses candle_transformers::models::qwen3)
@@ -87,6 +87,22 @@ const CHATML_BASE_INSTRUCTIONS: &str = "Output only the branch name. Use lowerca
 /// Create a ChatML-formatted prompt for Qwen3 models
 /// This format uses conversation structure for better results with quantized models
 pub fn create_chatml_prompt(git_output: &str, previous_suggestion: Option<&str>) -> Result<String> {
+  let system_message = create_branch_name_system_message(previous_suggestion);
+  let prompt = format!(
+    r#"<|im_start|>system
+{} /no_think<|im_end|>
+<|im_start|>user
+{}<|im_end|>
+<|im_start|>assistant"#,
+    system_message,
+    git_output.trim()
+  );
+  Ok(prompt)
+}
+
+/// System message asking for a branch name, the git output is the user message.
+/// Shared by ChatML prompts and chat APIs of remote providers.
+pub fn create_branch_name_system_message(previous_suggestion: Option<&str>) -> String {
   let mut system_message = String::from(CHATML_BASE_ROLE);
 
   if let Some(prev) = previous_suggestion {
@@ -101,15 +117,50 @@ pub fn create_chatml_prompt(git_output: &str, previous_suggestion: Option<&str>)
 
   system_message.push(' ');
   system_message.push_str(CHATML_BASE_INSTRUCTIONS);
+  system_message
+}
+
+/// Maximum subject line length asked for in commit message prompts
+pub const MAX_COMMIT_SUBJECT_LENGTH: usize = 72;
 
+/// Create a ChatML-formatted prompt asking to improve a commit message based on the commit's diff.
+/// Used for all Qwen models, they are instruction-tuned with ChatML.
+pub fn create_commit_message_prompt(original_message: &str, diff: &str) -> Result<String> {
   let prompt = format!(
     r#"<|im_start|>system
-{} /no_think<|im_end|>
+You are a Git commit message writer. Rewrite the commit message as a conventional commit: "type(scope): summary" where type is one of feat, fix, refactor, perf, test, docs, build, ci, chore. The summary is imperative and at most {MAX_COMMIT_SUBJECT_LENGTH} characters. Add a short body only if the change needs an explanation. Keep issue references from the original message. Output only the commit message. /no_think<|im_end|>
 <|im_start|>user
+Original message:
+{}
+
+Diff:
 {}<|im_end|>
 <|im_start|>assistant"#,
-    system_message,
-    git_output.trim()
+    original_message.trim(),
+    diff.trim()
+  );
+  Ok(prompt)
+}
+
+/// Create a ChatML-formatted prompt asking to group numbered commits into branches.
+/// `commits` lists one commit per line as `N. subject (files)`, existing branches are preferred over new ones.
+pub fn create_commit_grouping_prompt(commits: &str, existing_branches: &[String]) -> Result<String> {
+  let existing_branches = if existing_branches.is_empty() {
+    "none".to_string()
+  } else {
+    existing_branches.join(", ")
+  };
+  let prompt = format!(
+    r#"<|im_start|>system
+You group Git commits into feature branches. Put commits that belong to the same change into one branch. Prefer an existing branch if a commit continues its work, otherwise invent a short kebab-case branch name (at most {MAX_BRANCH_NAME_LENGTH} characters). Output one line per branch in the format "branch-name: 1, 2, 5" using the commit numbers. Leave out commits that don't fit any branch. Output only these lines. /no_think<|im_end|>
+<|im_start|>user
+Existing branches: {}
+
+Commits:
+{}<|im_end|>
+<|im_start|>assistant"#,
+    existing_branches,
+    commits.trim()
   );
   Ok(prompt)
 }
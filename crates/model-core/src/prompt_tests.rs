@@ -1,4 +1,4 @@
-use crate::prompt::{MAX_BRANCH_NAME_LENGTH, create_chatml_prompt, create_generic_prompt};
+use crate::prompt::{MAX_BRANCH_NAME_LENGTH, create_chatml_prompt, create_commit_grouping_prompt, create_commit_message_prompt, create_generic_prompt};
 use crate::test_utils::{CommitDiff, CommitInfo, FileDiff, convert_to_raw_git_format};
 use pretty_assertions::assert_eq;
 use test_log::test;
@@ -241,3 +241,29 @@ fn test_create_chatml_prompt_alternative() {
   assert!(prompt.contains("<|im_start|>system"));
   assert!(prompt.ends_with("<|im_start|>assistant"));
 }
+
+#[test]
+fn test_create_commit_message_prompt() {
+  let prompt = create_commit_message_prompt(
+    "(parser) fixed stuff\n",
+    "--- a/parser.rs\n+++ b/parser.rs\n@@ -1 +1 @@\n-fn parse() {}\n+fn parse() -> Ast {}\n",
+  )
+  .unwrap();
+
+  assert!(prompt.starts_with("<|im_start|>system\n"));
+  assert!(prompt.contains("conventional commit"));
+  assert!(prompt.contains("Original message:\n(parser) fixed stuff\n\nDiff:\n--- a/parser.rs"));
+  assert!(prompt.ends_with("+fn parse() -> Ast {}<|im_end|>\n<|im_start|>assistant"));
+}
+
+#[test]
+fn test_create_commit_grouping_prompt() {
+  let commits = "1. Add parser (src/parser.rs)\n2. Fix lexer crash (src/lexer.rs)\n";
+
+  let prompt = create_commit_grouping_prompt(commits, &["lexer".to_string(), "docs".to_string()]).unwrap();
+  assert!(prompt.contains("\"branch-name: 1, 2, 5\""));
+  assert!(prompt.contains("Existing branches: lexer, docs\n\nCommits:\n1. Add parser (src/parser.rs)\n2. Fix lexer crash (src/lexer.rs)<|im_end|>"));
+
+  let prompt = create_commit_grouping_prompt(commits, &[]).unwrap();
+  assert!(prompt.contains("Existing branches: none\n"));
+}
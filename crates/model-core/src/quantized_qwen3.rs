@@ -1,6 +1,6 @@
-use crate::BranchNameResult;
 use crate::constants::LOGITS_PROCESSOR_SEED;
-use crate::utils::{clean_branch_name, detect_device, truncate_tokens_if_needed};
+use crate::utils::{GeneratedText, clean_branch_name, clean_commit_message, detect_device, truncate_tokens_if_needed};
+use crate::{BranchNameResult, CommitGroupingResult, CommitMessageResult};
 use anyhow::{Error as E, Result};
 use candle_core::quantized::gguf_file;
 use candle_core::{Device, Tensor};
@@ -77,12 +77,49 @@ impl QuantizedQwen3BranchGenerator {
   /// Generate branch name from commit message and optional diff
   #[instrument(skip(self, prompt), fields(prompt_len = prompt.len()))]
   pub async fn generate_branch_name(&mut self, prompt: &str, max_tokens: usize, is_alternative: bool) -> Result<BranchNameResult> {
+    let generated = self.generate_text(prompt, max_tokens, is_alternative).await?;
+
+    // Clean up the generated branch name
+    let cleaned_name = clean_branch_name(&generated.text)?;
+
+    let result = BranchNameResult {
+      name: cleaned_name,
+      generation_time_ms: generated.generation_time_ms,
+    };
+
+    debug!("Generated branch name: '{}' in {}ms", result.name, result.generation_time_ms);
+
+    Ok(result)
+  }
+
+  /// Generate an improved commit message from a ChatML prompt
+  #[instrument(skip(self, prompt), fields(prompt_len = prompt.len()))]
+  pub async fn generate_commit_message(&mut self, prompt: &str, max_tokens: usize) -> Result<CommitMessageResult> {
+    let generated = self.generate_text(prompt, max_tokens, false).await?;
+    Ok(CommitMessageResult {
+      message: clean_commit_message(&generated.text)?,
+      generation_time_ms: generated.generation_time_ms,
+    })
+  }
+
+  /// Generate a grouping of commits into branches, one `branch-name: numbers` line per branch
+  #[instrument(skip(self, prompt), fields(prompt_len = prompt.len()))]
+  pub async fn generate_commit_grouping(&mut self, prompt: &str, max_tokens: usize) -> Result<CommitGroupingResult> {
+    let generated = self.generate_text(prompt, max_tokens, false).await?;
+    Ok(CommitGroupingResult {
+      response: generated.text,
+      generation_time_ms: generated.generation_time_ms,
+    })
+  }
+
+  /// Generation stops at the end-of-message token only, so the text may span several lines
+  async fn generate_text(&mut self, prompt: &str, max_tokens: usize, is_alternative: bool) -> Result<GeneratedText> {
     let start_time = std::time::Instant::now();
 
     let model = self.model.as_mut().ok_or_else(|| anyhow::anyhow!("Model not loaded. Call load_model() first."))?;
     let tokenizer = self.tokenizer.as_ref().ok_or_else(|| anyhow::anyhow!("Tokenizer not loaded. Call load_model() first."))?;
 
-    debug!("Generating branch name with {} tokens max", max_tokens);
+    debug!("Generating text with {} tokens max", max_tokens);
 
     // Format prompt with /no_think and explicit instruction for brevity
     // Tokenize prompt
@@ -155,19 +192,10 @@ impl QuantizedQwen3BranchGenerator {
 
     debug!("Generated text: '{}' from {} tokens", generated_text, generated_tokens.len());
 
-    let generation_time = start_time.elapsed();
-
-    // Clean up the generated branch name
-    let cleaned_name = clean_branch_name(&generated_text)?;
-
-    let result = BranchNameResult {
-      name: cleaned_name,
-      generation_time_ms: generation_time.as_millis() as u64,
-    };
-
-    debug!("Generated branch name: '{}' in {}ms", result.name, result.generation_time_ms);
-
-    Ok(result)
+    Ok(GeneratedText {
+      text: generated_text,
+      generation_time_ms: start_time.elapsed().as_millis() as u64,
+    })
   }
 
   /// Check if model is loaded and ready
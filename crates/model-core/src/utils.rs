@@ -67,6 +67,36 @@ pub fn clean_branch_name(raw_name: &str) -> Result<String> {
   Ok(final_name.to_string())
 }
 
+/// Raw model output before it is cleaned up for a specific use
+pub(crate) struct GeneratedText {
+  pub text: String,
+  pub generation_time_ms: u64,
+}
+
+/// Clean up a generated commit message: drop thinking tags, code fences and surrounding quotes,
+/// and separate the subject from the body with a blank line
+#[instrument(level = "debug")]
+pub fn clean_commit_message(raw_message: &str) -> Result<String> {
+  let without_tags = match raw_message.rfind("</think>") {
+    Some(end_pos) => &raw_message[end_pos + 8..],
+    None => raw_message,
+  };
+
+  let lines: Vec<&str> = without_tags.lines().map(str::trim_end).filter(|line| !line.trim_start().starts_with("```")).collect();
+  let text = lines.join("\n");
+  let text = text.trim().trim_matches(&['"', '\''][..]).trim();
+
+  let mut message_lines = text.lines();
+  let subject = message_lines.next().unwrap_or("").trim();
+  if subject.is_empty() {
+    return Err(anyhow::anyhow!("Could not generate a valid commit message"));
+  }
+
+  let body = message_lines.collect::<Vec<_>>().join("\n");
+  let body = body.trim();
+  if body.is_empty() { Ok(subject.to_string()) } else { Ok(format!("{subject}\n\n{body}")) }
+}
+
 /// Detect the best available device for ML inference
 /// Tries CUDA (if feature enabled), Metal, then falls back to CPU
 ///
@@ -145,4 +175,15 @@ mod tests {
     assert_eq!(clean_branch_name("<think>some thinking</think>feature-branch").unwrap(), "feature-branch");
     assert_eq!(clean_branch_name("</think>config-refactor").unwrap(), "config-refactor");
   }
+
+  #[test]
+  fn test_clean_commit_message() {
+    assert_eq!(clean_commit_message("fix(auth): handle expired tokens").unwrap(), "fix(auth): handle expired tokens");
+    assert_eq!(
+      clean_commit_message("<think>\n\n</think>\n\n```\nfeat: add parser\nSupports nested blocks.  \n```\n").unwrap(),
+      "feat: add parser\n\nSupports nested blocks."
+    );
+    assert_eq!(clean_commit_message("\"docs: update README\"").unwrap(), "docs: update README");
+    assert!(clean_commit_message("</think>\n```\n```").is_err());
+  }
 }
@@ -1,7 +1,17 @@
 use crate::generator::ModelGeneratorState;
 use crate::path_provider::TauriModelPathProvider;
-use model_ai::types::DownloadProgress;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::{CommandError, ErrorCode};
+use git_ops::reword_commits::{RewordCommitParams, reword_commits_batch};
+use model_ai::remote::{get_ai_api_key, load_ai_provider_settings, save_ai_provider_settings, store_ai_api_key};
+use model_ai::types::{
+  AiProviderSettingsInfo, ApplySuggestedMessageParams, CommitGroupingProgress, CommitMessageProgress, DownloadProgress, GetAiProviderSettingsParams, ModelCacheReport,
+  SetAiProviderApiKeyParams, SetAiProviderSettingsParams, SuggestCommitGroupingParams, SuggestCommitMessageParams, VerifyModelCacheParams,
+};
 use serde::Serialize;
+use sync_core::commit_grouper::keep_grouping_prefix;
+use sync_core::network::ensure_online_async;
+use sync_core::usage_stats::{UsageCounter, record_usage};
 use tauri::{AppHandle, State};
 use tracing::instrument;
 
@@ -26,10 +36,13 @@ pub struct ModelFilesStatus {
 #[tauri::command]
 #[specta::specta]
 #[instrument(skip(model_state, app, progress))]
-pub async fn download_model(model_state: State<'_, ModelGeneratorState>, app: AppHandle, progress: tauri::ipc::Channel<DownloadProgress>) -> Result<(), String> {
+pub async fn download_model(model_state: State<'_, ModelGeneratorState>, app: AppHandle, progress: tauri::ipc::Channel<DownloadProgress>) -> Result<(), CommandError> {
   use crate::download::TauriProgressReporter;
   use std::sync::atomic::Ordering;
 
+  // Fail right away instead of after the connect timeouts of the download
+  ensure_online_async().await?;
+
   // Reset cancellation flag
   model_state.download_cancelled.store(false, Ordering::SeqCst);
 
@@ -51,9 +64,9 @@ pub async fn download_model(model_state: State<'_, ModelGeneratorState>, app: Ap
       // Check if this was a cancellation
       if model_state.download_cancelled.load(Ordering::SeqCst) {
         let _ = progress.send(DownloadProgress::Cancelled);
-        Err("Download cancelled".to_string())
+        Err(CommandError::new(ErrorCode::Cancelled, "Download cancelled"))
       } else {
-        Err(e.to_string())
+        Err(CommandError::new(ErrorCode::Model, e.to_string()))
       }
     }
   }
@@ -62,7 +75,7 @@ pub async fn download_model(model_state: State<'_, ModelGeneratorState>, app: Ap
 #[tauri::command]
 #[specta::specta]
 #[instrument(skip(model_state, app))]
-pub async fn check_model_status(model_state: State<'_, ModelGeneratorState>, app: AppHandle) -> Result<ModelStatus, String> {
+pub async fn check_model_status(model_state: State<'_, ModelGeneratorState>, app: AppHandle) -> Result<ModelStatus, CommandError> {
   let mut model_gen = model_state.generator.lock().await;
   let provider = TauriModelPathProvider::new(app);
   let model_path = model_gen.get_model_path(&provider).map_err(|e| format!("Failed to get model path: {e}"))?;
@@ -112,7 +125,7 @@ pub(crate) fn check_model_files_exist(model_config: &model_core::config::ModelCo
 #[tauri::command]
 #[specta::specta]
 #[instrument(skip(model_state))]
-pub async fn cancel_model_download(model_state: State<'_, ModelGeneratorState>) -> Result<(), String> {
+pub async fn cancel_model_download(model_state: State<'_, ModelGeneratorState>) -> Result<(), CommandError> {
   use std::sync::atomic::Ordering;
 
   // Set the cancellation flag
@@ -120,3 +133,211 @@ pub async fn cancel_model_download(model_state: State<'_, ModelGeneratorState>)
 
   Ok(())
 }
+
+/// Checks downloaded model files against their expected size and SHA256, reporting partial and corrupt files
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(model_state, app))]
+pub async fn verify_model_cache(model_state: State<'_, ModelGeneratorState>, app: AppHandle, params: VerifyModelCacheParams) -> Result<ModelCacheReport, CommandError> {
+  let model_config = model_state.generator.lock().await.get_model_config();
+  let provider = TauriModelPathProvider::new(app);
+
+  // Hashing the model file takes a while
+  tokio::task::spawn_blocking(move || model_ai::download::verify_model_cache(&model_config, &provider, params.repair))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+    .map_err(CommandError::from)
+}
+
+/// Suggests an improved conventional-commit message for a commit based on its diff
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(model_state, git_executor, app, params, progress))]
+pub async fn suggest_commit_message_stream(
+  model_state: State<'_, ModelGeneratorState>,
+  git_executor: State<'_, GitCommandExecutor>,
+  app: AppHandle,
+  params: SuggestCommitMessageParams,
+  progress: tauri::ipc::Channel<CommitMessageProgress>,
+) -> Result<(), CommandError> {
+  let generation_id_counter = {
+    let guard = model_state.generator.lock().await;
+    guard.get_current_generation_id()
+  };
+  let my_generation_id = generation_id_counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+
+  progress.send(CommitMessageProgress::Started).map_err(|e| format!("Failed to send progress: {e}"))?;
+
+  // Acquire lock - will wait if another request is running
+  let mut model_gen = model_state.generator.lock().await;
+  if my_generation_id != generation_id_counter.load(std::sync::atomic::Ordering::SeqCst) {
+    progress.send(CommitMessageProgress::Cancelled).ok();
+    return Ok(());
+  }
+
+  let provider = TauriModelPathProvider::new(app);
+  if let Err(e) = model_gen.ensure_model_loaded(&provider).await {
+    let error_str = e.to_string();
+    if error_str.contains("Model not downloaded") {
+      let model_config = model_gen.get_model_config();
+      progress
+        .send(CommitMessageProgress::ModelDownloadInProgress {
+          model_name: model_config.model_name().to_string(),
+          model_size: model_config.model_size().to_string(),
+        })
+        .ok();
+      return Ok(());
+    }
+    let error_message = format!("Failed to load model: {e}");
+    progress.send(CommitMessageProgress::Error { message: error_message.clone() }).ok();
+    return Err(CommandError::new(ErrorCode::Model, error_message));
+  }
+
+  match model_gen
+    .generate_commit_message_stream(&git_executor, &params.repository_path, &params.commit_id, &progress, my_generation_id)
+    .await
+  {
+    Ok(()) => {
+      progress.send(CommitMessageProgress::Completed).map_err(|e| format!("Failed to send completion: {e}"))?;
+      Ok(())
+    }
+    Err(e) => {
+      let error_message = format!("Failed to generate commit message: {e}");
+      progress.send(CommitMessageProgress::Error { message: error_message.clone() }).ok();
+      Err(CommandError::new(ErrorCode::Model, error_message))
+    }
+  }
+}
+
+/// Replaces the message of a commit with an accepted suggestion, returns the new commit ID
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor, params), fields(commit_id = %params.commit_id))]
+pub async fn apply_suggested_message(git_executor: State<'_, GitCommandExecutor>, params: ApplySuggestedMessageParams) -> Result<String, CommandError> {
+  let message = params.message.trim_end().to_string();
+  if message.trim().is_empty() {
+    return Err(CommandError::validation("Commit message must not be empty"));
+  }
+
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || {
+    // The model doesn't know about grouping, the commit must stay in its branch
+    let old_message = git
+      .execute_command(&["--no-pager", "log", "-1", "--format=%B", &params.commit_id], &params.repository_path)
+      .map_err(CommandError::from)?;
+    let rewrite = RewordCommitParams {
+      commit_id: params.commit_id.clone(),
+      new_message: keep_grouping_prefix(&old_message, &message),
+    };
+    let mut mapping = reword_commits_batch(&git, &params.repository_path, vec![rewrite]).map_err(CommandError::from)?;
+    let new_commit_id = mapping
+      .remove(&params.commit_id)
+      .ok_or_else(|| CommandError::not_found(format!("Commit {} was not reworded", params.commit_id)))?;
+    record_usage(&git, &params.repository_path, UsageCounter::AiSuggestionAccepted);
+    Ok(new_commit_id)
+  })
+  .await
+  .map_err(|e| format!("Task failed: {e}"))?
+}
+
+/// Proposes an assignment of unassigned commits to new or existing branches.
+/// The plan is applied by assigning each group with `create_branch_from_commits`.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(model_state, git_executor, app, params, progress))]
+pub async fn suggest_commit_grouping(
+  model_state: State<'_, ModelGeneratorState>,
+  git_executor: State<'_, GitCommandExecutor>,
+  app: AppHandle,
+  params: SuggestCommitGroupingParams,
+  progress: tauri::ipc::Channel<CommitGroupingProgress>,
+) -> Result<(), CommandError> {
+  let generation_id_counter = {
+    let guard = model_state.generator.lock().await;
+    guard.get_current_generation_id()
+  };
+  let my_generation_id = generation_id_counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+
+  progress.send(CommitGroupingProgress::Started).map_err(|e| format!("Failed to send progress: {e}"))?;
+
+  // Acquire lock - will wait if another request is running
+  let mut model_gen = model_state.generator.lock().await;
+  if my_generation_id != generation_id_counter.load(std::sync::atomic::Ordering::SeqCst) {
+    progress.send(CommitGroupingProgress::Cancelled).ok();
+    return Ok(());
+  }
+
+  let provider = TauriModelPathProvider::new(app);
+  if let Err(e) = model_gen.ensure_model_loaded(&provider).await {
+    let error_str = e.to_string();
+    if error_str.contains("Model not downloaded") {
+      let model_config = model_gen.get_model_config();
+      progress
+        .send(CommitGroupingProgress::ModelDownloadInProgress {
+          model_name: model_config.model_name().to_string(),
+          model_size: model_config.model_size().to_string(),
+        })
+        .ok();
+      return Ok(());
+    }
+    let error_message = format!("Failed to load model: {e}");
+    progress.send(CommitGroupingProgress::Error { message: error_message.clone() }).ok();
+    return Err(CommandError::new(ErrorCode::Model, error_message));
+  }
+
+  match model_gen
+    .generate_commit_grouping_stream(&git_executor, &params.repository_path, &progress, my_generation_id)
+    .await
+  {
+    Ok(()) => {
+      progress.send(CommitGroupingProgress::Completed).map_err(|e| format!("Failed to send completion: {e}"))?;
+      Ok(())
+    }
+    Err(e) => {
+      let error_message = format!("Failed to suggest commit grouping: {e}");
+      progress.send(CommitGroupingProgress::Error { message: error_message.clone() }).ok();
+      Err(CommandError::new(ErrorCode::Model, error_message))
+    }
+  }
+}
+
+/// AI provider selected for the repository and whether its API key is stored
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn get_ai_provider_settings(git_executor: State<'_, GitCommandExecutor>, params: GetAiProviderSettingsParams) -> Result<AiProviderSettingsInfo, CommandError> {
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || {
+    let settings = load_ai_provider_settings(&git, &params.repository_path);
+    let has_api_key = if settings.provider.is_remote() {
+      get_ai_api_key(&git, &params.repository_path, &settings).map_err(CommandError::from)?.is_some()
+    } else {
+      false
+    };
+    Ok(AiProviderSettingsInfo { settings, has_api_key })
+  })
+  .await
+  .map_err(|e| format!("Task failed: {e}"))?
+}
+
+/// Selects the AI provider used for suggestions in the repository
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn set_ai_provider_settings(git_executor: State<'_, GitCommandExecutor>, params: SetAiProviderSettingsParams) -> Result<(), CommandError> {
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || save_ai_provider_settings(&git, &params.repository_path, &params.settings).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
+
+/// Stores the API key of the provider endpoint in the git credential helper, an empty key removes it
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor, params), fields(provider = ?params.settings.provider))]
+pub async fn set_ai_provider_api_key(git_executor: State<'_, GitCommandExecutor>, params: SetAiProviderApiKeyParams) -> Result<(), CommandError> {
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || store_ai_api_key(&git, &params.repository_path, &params.settings, params.api_key.as_deref()).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
@@ -48,6 +48,20 @@ impl ProgressReporter for TauriProgressReporter {
     )
   }
 
+  fn report_file_resumed(&self, file_name: &str, resumed_from: u32) -> Result<()> {
+    send_progress!(
+      self.channel,
+      DownloadProgress::FileResumed {
+        file_name: file_name.to_string(),
+        resumed_from,
+      }
+    )
+  }
+
+  fn report_file_verifying(&self, file_name: &str) -> Result<()> {
+    send_progress!(self.channel, DownloadProgress::FileVerifying { file_name: file_name.to_string() })
+  }
+
   fn report_file_completed(&self, file_name: &str) -> Result<()> {
     send_progress!(self.channel, DownloadProgress::FileCompleted { file_name: file_name.to_string() })
   }
@@ -1,32 +1,45 @@
-use anyhow::Result;
+use crate::sidecar::{ModelSidecar, SidecarCommand, SidecarRequest, SidecarResponse};
+use anyhow::{Result, anyhow};
 use git_executor::git_command_executor::GitCommandExecutor;
 use git_ops::model::CommitInfo;
 use model_ai::generator::ModelBasedBranchGenerator as CoreGenerator;
 use model_ai::path_provider::ModelPathProvider;
+use model_ai::remote::RemoteGenerator;
+use model_ai::types::CommitGroupingSuggestion;
 use model_core::config::ModelConfig;
 use model_core::utils::clean_branch_name;
+use model_core::{BranchNameResult, CommitMessageResult};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64};
+use sync_core::commit_grouping::collect_commit_grouping_input;
+use sync_types::commit_grouping::CommitGroupingInput;
 use tokio::sync::Mutex;
 use tracing::info;
 
+/// Branch name generator running the model in a sidecar process, `core` is only used for model paths and configuration
 #[derive(Debug)]
 pub struct ModelBasedBranchGenerator {
   core: CoreGenerator,
+  sidecar: ModelSidecar,
+  // Model loaded by the currently running sidecar process
+  loaded: bool,
   current_generation_id: Arc<AtomicU64>,
 }
 
 impl ModelBasedBranchGenerator {
   pub fn new() -> Result<Self> {
-    Ok(Self {
-      core: CoreGenerator::new()?,
-      current_generation_id: Arc::new(AtomicU64::new(0)),
-    })
+    Self::with_config(ModelConfig::default())
   }
 
   pub fn with_config(model_config: ModelConfig) -> Result<Self> {
+    Self::with_sidecar_command(model_config, SidecarCommand::current_exe()?)
+  }
+
+  pub fn with_sidecar_command(model_config: ModelConfig, command: SidecarCommand) -> Result<Self> {
     Ok(Self {
       core: CoreGenerator::with_config(model_config)?,
+      sidecar: ModelSidecar::new(command),
+      loaded: false,
       current_generation_id: Arc::new(AtomicU64::new(0)),
     })
   }
@@ -44,6 +57,11 @@ impl ModelBasedBranchGenerator {
   }
 
   pub async fn set_model_config(&mut self, config: ModelConfig) -> Result<()> {
+    if self.core.get_model_config() != config {
+      // Free the memory of the previous model, the next load starts a fresh process
+      self.sidecar.shutdown().await;
+      self.loaded = false;
+    }
     self.core.set_model_config(config).await
   }
 
@@ -77,21 +95,172 @@ impl ModelBasedBranchGenerator {
     Ok(git_output)
   }
 
+  /// Message and patch of a single commit, the input for commit message suggestions
+  pub(crate) fn get_commit_message_and_diff(&self, git_executor: &GitCommandExecutor, commit_id: &str, repo_path: &str) -> Result<(String, String)> {
+    let message = git_executor.execute_command(&["--no-pager", "show", "-s", "--format=%B", commit_id], repo_path)?;
+    let diff = git_executor.execute_command(&["--no-pager", "show", "--format=", "--no-color", "--patch", commit_id], repo_path)?;
+    Ok((message, diff))
+  }
+
   pub async fn ensure_model_loaded(&mut self, provider: &dyn ModelPathProvider) -> Result<()> {
-    self.core.ensure_model_loaded(provider).await
+    if self.is_loaded() {
+      return Ok(());
+    }
+
+    let request = SidecarRequest::Load {
+      model_config: self.core.get_model_config(),
+      cache_dir: provider.get_cache_dir()?,
+    };
+    match self.sidecar.request(&request).await? {
+      SidecarResponse::Loaded => {
+        self.loaded = true;
+        Ok(())
+      }
+      SidecarResponse::Error { message } => Err(anyhow!(message)),
+      response => Err(anyhow!("Unexpected response from model process: {response:?}")),
+    }
   }
 
   pub fn is_loaded(&self) -> bool {
-    self.core.is_loaded()
+    // A crashed process is restarted without the model
+    self.loaded && self.sidecar.is_running()
+  }
+
+  async fn generate_branch_name(&mut self, git_output: &str, previous_suggestion: Option<&str>) -> Result<BranchNameResult> {
+    let request = SidecarRequest::Generate {
+      git_output: git_output.to_string(),
+      previous_suggestion: previous_suggestion.map(str::to_string),
+    };
+    match self.sidecar.request(&request).await? {
+      SidecarResponse::Generated { result } => Ok(result),
+      SidecarResponse::Error { message } => Err(anyhow!(message)),
+      response => Err(anyhow!("Unexpected response from model process: {response:?}")),
+    }
+  }
+
+  async fn generate_commit_message(&mut self, original_message: &str, diff: &str) -> Result<CommitMessageResult> {
+    let request = SidecarRequest::GenerateCommitMessage {
+      original_message: original_message.to_string(),
+      diff: diff.to_string(),
+    };
+    match self.sidecar.request(&request).await? {
+      SidecarResponse::GeneratedCommitMessage { result } => Ok(result),
+      SidecarResponse::Error { message } => Err(anyhow!(message)),
+      response => Err(anyhow!("Unexpected response from model process: {response:?}")),
+    }
   }
 
-  // Removed old complex loading methods - now using model-core directly
+  async fn generate_commit_grouping(&mut self, input: CommitGroupingInput) -> Result<CommitGroupingSuggestion> {
+    let request = SidecarRequest::GenerateCommitGrouping { input };
+    match self.sidecar.request(&request).await? {
+      SidecarResponse::GeneratedCommitGrouping { result } => Ok(result),
+      SidecarResponse::Error { message } => Err(anyhow!(message)),
+      response => Err(anyhow!("Unexpected response from model process: {response:?}")),
+    }
+  }
+
+  pub async fn generate_commit_grouping_stream(
+    &mut self,
+    git_executor: &GitCommandExecutor,
+    repository_path: &str,
+    progress: &tauri::ipc::Channel<model_ai::types::CommitGroupingProgress>,
+    my_generation_id: u64,
+  ) -> Result<()> {
+    use model_ai::types::CommitGroupingProgress;
+
+    if !self.is_loaded() {
+      return Err(anyhow!("Model not loaded"));
+    }
+
+    let input = collect_commit_grouping_input(git_executor, repository_path)?;
+    if input.commits.is_empty() {
+      return Err(anyhow!("No unassigned commits to group"));
+    }
+
+    if my_generation_id != self.current_generation_id.load(std::sync::atomic::Ordering::SeqCst) {
+      info!("Generation {} cancelled, newer generation exists", my_generation_id);
+      progress.send(CommitGroupingProgress::Cancelled).ok();
+      return Ok(());
+    }
+
+    let result = self.generate_commit_grouping(input).await?;
+    progress
+      .send(CommitGroupingProgress::PlanReady {
+        plan: result.plan,
+        reason: Some(format!("AI-generated in {}ms", result.generation_time_ms)),
+      })
+      .map_err(|e| anyhow!("Failed to send commit grouping suggestion: {e}"))?;
+
+    Ok(())
+  }
+
+  pub async fn generate_commit_message_stream(
+    &mut self,
+    git_executor: &GitCommandExecutor,
+    repository_path: &str,
+    commit_id: &str,
+    progress: &tauri::ipc::Channel<model_ai::types::CommitMessageProgress>,
+    my_generation_id: u64,
+  ) -> Result<()> {
+    use model_ai::types::CommitMessageProgress;
+
+    if commit_id.is_empty() {
+      return Err(anyhow!("No commit provided"));
+    }
+
+    if !self.is_loaded() {
+      return Err(anyhow!("Model not loaded"));
+    }
+
+    let (original_message, diff) = self.get_commit_message_and_diff(git_executor, commit_id, repository_path)?;
+
+    if my_generation_id != self.current_generation_id.load(std::sync::atomic::Ordering::SeqCst) {
+      info!("Generation {} cancelled, newer generation exists", my_generation_id);
+      progress.send(CommitMessageProgress::Cancelled).ok();
+      return Ok(());
+    }
+
+    let result = self.generate_commit_message(&original_message, &diff).await?;
+    progress
+      .send(CommitMessageProgress::MessageReady {
+        message: result.message,
+        reason: Some(format!("AI-generated in {}ms", result.generation_time_ms)),
+      })
+      .map_err(|e| anyhow!("Failed to send commit message suggestion: {e}"))?;
+
+    Ok(())
+  }
+
+  /// Generate with the remote provider if given (streaming partial text), otherwise with the local model
+  async fn generate_branch_name_with(
+    &mut self,
+    remote: Option<&RemoteGenerator>,
+    git_output: &str,
+    previous_suggestion: Option<&str>,
+    progress: &tauri::ipc::Channel<model_ai::types::SuggestionProgress>,
+    index: u32,
+  ) -> Result<BranchNameResult> {
+    use model_ai::types::SuggestionProgress;
+
+    match remote {
+      Some(remote) => {
+        remote
+          .generate_branch_name(git_output, previous_suggestion, |text| {
+            progress.send(SuggestionProgress::PartialSuggestion { text: text.to_string(), index }).ok();
+          })
+          .await
+      }
+      None => self.generate_branch_name(git_output, previous_suggestion).await,
+    }
+  }
 
+  /// `remote` replaces the local model, which then doesn't need to be loaded
   pub async fn generate_branch_names_stream(
     &mut self,
     git_executor: &GitCommandExecutor,
     commits: &[CommitInfo],
     repository_path: &str,
+    remote: Option<&RemoteGenerator>,
     progress: &tauri::ipc::Channel<model_ai::types::SuggestionProgress>,
     my_generation_id: u64,
   ) -> Result<()> {
@@ -104,7 +273,7 @@ impl ModelBasedBranchGenerator {
       return Err(anyhow::anyhow!("No valid commits provided (all have empty hashes)"));
     }
 
-    if !self.core.is_loaded() {
+    if remote.is_none() && !self.is_loaded() {
       return Err(anyhow::anyhow!("Model not loaded"));
     }
 
@@ -119,7 +288,7 @@ impl ModelBasedBranchGenerator {
     }
 
     // Generate primary suggestion
-    let result = self.core.generate_branch_name(&git_output, None).await?;
+    let result = self.generate_branch_name_with(remote, &git_output, None, progress, 0).await?;
 
     let cleaned_name = clean_branch_name(&result.name)?;
 
@@ -143,7 +312,7 @@ impl ModelBasedBranchGenerator {
       }
 
       // Generate alternative using the same git output but with context of the first suggestion
-      let fallback_result = self.core.generate_branch_name(&git_output, Some(&cleaned_name)).await;
+      let fallback_result = self.generate_branch_name_with(remote, &git_output, Some(&cleaned_name), progress, 1).await;
 
       if let Ok(fallback_result) = fallback_result
         && let Ok(fallback_name) = clean_branch_name(&fallback_result.name)
@@ -182,4 +182,21 @@ mod tests {
     assert!(git_output.contains("Refactor: Extract configuration constants"));
     assert!(git_output.contains("A\tpassword.js"));
   }
+
+  #[test]
+  fn test_get_commit_message_and_diff() {
+    let test_repo = TestRepo::new();
+    let executor = GitCommandExecutor::new();
+    test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+    let commit_hash = test_repo.create_commit("fixed parser\n\nHandles empty input", "parser.rs", "fn parse() {}\n");
+
+    let generator = ModelBasedBranchGenerator::new().unwrap();
+    let (message, diff) = generator.get_commit_message_and_diff(&executor, &commit_hash, test_repo.path().to_str().unwrap()).unwrap();
+
+    assert_eq!(message, "fixed parser\n\nHandles empty input");
+    assert!(diff.contains("+++ b/parser.rs"), "{diff}");
+    assert!(diff.contains("+fn parse() {}"), "{diff}");
+    // Only the patch, the message is passed separately
+    assert!(!diff.contains("fixed parser"), "{diff}");
+  }
 }
@@ -2,6 +2,7 @@ pub mod commands;
 pub mod download;
 pub mod generator;
 pub mod path_provider;
+pub mod sidecar;
 
 // No re-exports - import modules directly
 
@@ -10,3 +11,6 @@ mod commands_test;
 
 #[cfg(test)]
 mod generator_test;
+
+#[cfg(test)]
+mod sidecar_test;
@@ -0,0 +1,228 @@
+//! Model inference in a separate process, so an OOM or a GPU driver crash doesn't take down the app.
+//!
+//! The app re-runs its own executable with [`SIDECAR_ARG`]; requests and responses are exchanged as JSON lines over stdin/stdout.
+
+use anyhow::{Context, Result, anyhow, bail};
+use model_ai::generator::ModelBasedBranchGenerator as CoreGenerator;
+use model_ai::path_provider::ModelPathProvider;
+use model_ai::types::CommitGroupingSuggestion;
+use model_core::config::ModelConfig;
+use model_core::{BranchNameResult, CommitMessageResult};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use sync_types::commit_grouping::CommitGroupingInput;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tracing::{info, instrument, warn};
+
+/// Command line argument switching the executable into sidecar mode
+pub const SIDECAR_ARG: &str = "--model-sidecar";
+
+/// Loading a big model on CPU is slow, but a process not answering for this long is considered hung
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SidecarRequest {
+  /// Load the model (no-op if it is already loaded)
+  Load {
+    model_config: ModelConfig,
+    cache_dir: PathBuf,
+  },
+  Generate {
+    git_output: String,
+    previous_suggestion: Option<String>,
+  },
+  GenerateCommitMessage {
+    original_message: String,
+    diff: String,
+  },
+  GenerateCommitGrouping {
+    input: CommitGroupingInput,
+  },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SidecarResponse {
+  Loaded,
+  Generated { result: BranchNameResult },
+  GeneratedCommitMessage { result: CommitMessageResult },
+  GeneratedCommitGrouping { result: CommitGroupingSuggestion },
+  Error { message: String },
+}
+
+/// Entry point of the sidecar process, serves requests until the app closes stdin
+pub fn run_sidecar() -> Result<()> {
+  let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()?;
+  runtime.block_on(serve(BufReader::new(tokio::io::stdin()), tokio::io::stdout()))
+}
+
+/// Cache directory chosen by the app, the sidecar has no access to Tauri paths
+struct FixedCacheDir(PathBuf);
+
+impl ModelPathProvider for FixedCacheDir {
+  fn get_cache_dir(&self) -> Result<PathBuf> {
+    Ok(self.0.clone())
+  }
+}
+
+pub(crate) async fn serve<R: AsyncBufRead + Unpin, W: AsyncWrite + Unpin>(reader: R, mut writer: W) -> Result<()> {
+  let mut generator: Option<CoreGenerator> = None;
+  let mut lines = reader.lines();
+  while let Some(line) = lines.next_line().await? {
+    let response = match serde_json::from_str::<SidecarRequest>(&line) {
+      Ok(request) => handle_request(&mut generator, request)
+        .await
+        .unwrap_or_else(|e| SidecarResponse::Error { message: format!("{e:#}") }),
+      Err(e) => SidecarResponse::Error {
+        message: format!("Invalid request: {e}"),
+      },
+    };
+    write_message(&mut writer, &response).await?;
+  }
+  Ok(())
+}
+
+async fn handle_request(generator: &mut Option<CoreGenerator>, request: SidecarRequest) -> Result<SidecarResponse> {
+  match request {
+    SidecarRequest::Load { model_config, cache_dir } => {
+      // Switching the model drops the loaded one
+      if generator.as_ref().is_none_or(|g| g.get_model_config() != model_config) {
+        *generator = Some(CoreGenerator::with_config(model_config)?);
+      }
+      let generator = generator.as_mut().expect("generator is initialized above");
+      generator.ensure_model_loaded(&FixedCacheDir(cache_dir)).await?;
+      Ok(SidecarResponse::Loaded)
+    }
+    SidecarRequest::Generate { git_output, previous_suggestion } => {
+      let generator = generator.as_mut().filter(|g| g.is_loaded()).ok_or_else(|| anyhow!("Model not loaded"))?;
+      let result = generator.generate_branch_name(&git_output, previous_suggestion.as_deref()).await?;
+      Ok(SidecarResponse::Generated { result })
+    }
+    SidecarRequest::GenerateCommitMessage { original_message, diff } => {
+      let generator = generator.as_mut().filter(|g| g.is_loaded()).ok_or_else(|| anyhow!("Model not loaded"))?;
+      let result = generator.generate_commit_message(&original_message, &diff).await?;
+      Ok(SidecarResponse::GeneratedCommitMessage { result })
+    }
+    SidecarRequest::GenerateCommitGrouping { input } => {
+      let generator = generator.as_mut().filter(|g| g.is_loaded()).ok_or_else(|| anyhow!("Model not loaded"))?;
+      let result = generator.generate_commit_grouping(&input).await?;
+      Ok(SidecarResponse::GeneratedCommitGrouping { result })
+    }
+  }
+}
+
+async fn write_message<W: AsyncWrite + Unpin, T: Serialize>(writer: &mut W, message: &T) -> Result<()> {
+  let mut line = serde_json::to_string(message)?;
+  line.push('\n');
+  writer.write_all(line.as_bytes()).await?;
+  writer.flush().await?;
+  Ok(())
+}
+
+/// How to start the sidecar process
+#[derive(Debug, Clone)]
+pub struct SidecarCommand {
+  pub program: PathBuf,
+  pub args: Vec<String>,
+}
+
+impl SidecarCommand {
+  /// Run the current executable in sidecar mode
+  pub fn current_exe() -> Result<Self> {
+    Ok(Self {
+      program: std::env::current_exe().context("Failed to get current executable")?,
+      args: vec![SIDECAR_ARG.to_string()],
+    })
+  }
+}
+
+#[derive(Debug)]
+struct SidecarProcess {
+  child: Child,
+  stdin: ChildStdin,
+  stdout: Lines<BufReader<ChildStdout>>,
+}
+
+impl SidecarProcess {
+  async fn exchange(&mut self, request: &SidecarRequest) -> Result<SidecarResponse> {
+    write_message(&mut self.stdin, request).await?;
+    match self.stdout.next_line().await? {
+      Some(line) => serde_json::from_str(&line).with_context(|| format!("Invalid response from model process: {line}")),
+      None => {
+        let status = self.child.wait().await?;
+        bail!("Model process exited unexpectedly ({status})")
+      }
+    }
+  }
+}
+
+/// Client side of the sidecar. The process is started on first request and restarted after a crash.
+#[derive(Debug)]
+pub struct ModelSidecar {
+  command: SidecarCommand,
+  process: Option<SidecarProcess>,
+}
+
+impl ModelSidecar {
+  pub fn new(command: SidecarCommand) -> Self {
+    Self { command, process: None }
+  }
+
+  /// Whether the process is alive; a model is only loaded while it is
+  pub fn is_running(&self) -> bool {
+    self.process.is_some()
+  }
+
+  /// Send a request, stopping the process if it crashed, hung or replied with garbage
+  #[instrument(skip_all)]
+  pub async fn request(&mut self, request: &SidecarRequest) -> Result<SidecarResponse> {
+    let process = self.ensure_started()?;
+    let result = tokio::time::timeout(REQUEST_TIMEOUT, process.exchange(request)).await;
+    match result {
+      Ok(Ok(response)) => Ok(response),
+      Ok(Err(e)) => {
+        warn!(error = %e, "Model process failed, it will be restarted on the next request");
+        self.shutdown().await;
+        Err(e)
+      }
+      Err(_) => {
+        warn!("Model process doesn't respond, stopping it");
+        self.shutdown().await;
+        Err(anyhow!("Model process did not respond within {} seconds", REQUEST_TIMEOUT.as_secs()))
+      }
+    }
+  }
+
+  /// Stop the process, freeing the memory of the loaded model
+  pub async fn shutdown(&mut self) {
+    if let Some(mut process) = self.process.take() {
+      let _ = process.child.kill().await;
+    }
+  }
+
+  fn ensure_started(&mut self) -> Result<&mut SidecarProcess> {
+    if self.process.is_none() {
+      info!(program = %self.command.program.display(), "Starting model process");
+      let mut child = Command::new(&self.command.program)
+        .args(&self.command.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to start model process")?;
+      let stdin = child.stdin.take().ok_or_else(|| anyhow!("Model process has no stdin"))?;
+      let stdout = child.stdout.take().ok_or_else(|| anyhow!("Model process has no stdout"))?;
+      self.process = Some(SidecarProcess {
+        child,
+        stdin,
+        stdout: BufReader::new(stdout).lines(),
+      });
+    }
+    Ok(self.process.as_mut().expect("process is started above"))
+  }
+}
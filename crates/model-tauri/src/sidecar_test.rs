@@ -0,0 +1,123 @@
+use crate::sidecar::{ModelSidecar, SidecarCommand, SidecarRequest, SidecarResponse, serve};
+use model_core::config::ModelConfig;
+use sync_types::commit_grouping::{CommitGroupingInput, UngroupedCommit};
+use tempfile::TempDir;
+use test_log::test;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Send raw request lines to an in-process server and collect its responses
+async fn exchange(lines: &[String]) -> Vec<SidecarResponse> {
+  let (client, server) = tokio::io::duplex(64 * 1024);
+  let (server_read, server_write) = tokio::io::split(server);
+  let server = tokio::spawn(serve(BufReader::new(server_read), server_write));
+
+  let (client_read, mut client_write) = tokio::io::split(client);
+  for line in lines {
+    client_write.write_all(format!("{line}\n").as_bytes()).await.unwrap();
+  }
+  client_write.shutdown().await.unwrap();
+
+  let mut responses = Vec::new();
+  let mut reader = BufReader::new(client_read).lines();
+  while let Some(line) = reader.next_line().await.unwrap() {
+    responses.push(serde_json::from_str(&line).unwrap());
+  }
+  server.await.unwrap().unwrap();
+  responses
+}
+
+#[test(tokio::test)]
+async fn test_serve_reports_errors_as_responses() {
+  let cache_dir = TempDir::new().unwrap();
+  let lines = vec![
+    serde_json::to_string(&SidecarRequest::Generate {
+      git_output: "Add parser".to_string(),
+      previous_suggestion: None,
+    })
+    .unwrap(),
+    "not json".to_string(),
+    serde_json::to_string(&SidecarRequest::Load {
+      model_config: ModelConfig::Qwen3_17B,
+      cache_dir: cache_dir.path().to_path_buf(),
+    })
+    .unwrap(),
+  ];
+
+  let responses = exchange(&lines).await;
+  assert_eq!(responses.len(), 3);
+  assert!(
+    matches!(&responses[0], SidecarResponse::Error { message } if message == "Model not loaded"),
+    "{responses:?}"
+  );
+  assert!(
+    matches!(&responses[1], SidecarResponse::Error { message } if message.starts_with("Invalid request")),
+    "{responses:?}"
+  );
+  // The app relies on this message to offer a download
+  assert!(
+    matches!(&responses[2], SidecarResponse::Error { message } if message.contains("Model not downloaded")),
+    "{responses:?}"
+  );
+}
+
+#[test(tokio::test)]
+async fn test_serve_requires_loaded_model_for_commit_messages() {
+  let lines = vec![
+    serde_json::to_string(&SidecarRequest::GenerateCommitMessage {
+      original_message: "fixed stuff".to_string(),
+      diff: "+fn parse() {}".to_string(),
+    })
+    .unwrap(),
+  ];
+
+  let responses = exchange(&lines).await;
+  assert!(
+    matches!(responses.as_slice(), [SidecarResponse::Error { message }] if message == "Model not loaded"),
+    "{responses:?}"
+  );
+}
+
+#[test(tokio::test)]
+async fn test_serve_requires_loaded_model_for_commit_grouping() {
+  let lines = vec![
+    serde_json::to_string(&SidecarRequest::GenerateCommitGrouping {
+      input: CommitGroupingInput {
+        commits: vec![UngroupedCommit {
+          id: "abc123".to_string(),
+          subject: "Add parser".to_string(),
+          files: vec!["parser.rs".to_string()],
+        }],
+        existing_branches: Vec::new(),
+      },
+    })
+    .unwrap(),
+  ];
+
+  let responses = exchange(&lines).await;
+  assert!(
+    matches!(responses.as_slice(), [SidecarResponse::Error { message }] if message == "Model not loaded"),
+    "{responses:?}"
+  );
+}
+
+#[cfg(unix)]
+#[test(tokio::test)]
+async fn test_crashed_process_is_restarted() {
+  // Process crashing while handling the request
+  let mut sidecar = ModelSidecar::new(SidecarCommand {
+    program: "sh".into(),
+    args: vec!["-c".to_string(), "read line; exit 3".to_string()],
+  });
+  let request = SidecarRequest::Generate {
+    git_output: "Add parser".to_string(),
+    previous_suggestion: None,
+  };
+
+  let error = sidecar.request(&request).await.unwrap_err();
+  assert!(error.to_string().contains("exited unexpectedly"), "{error}");
+  assert!(!sidecar.is_running());
+
+  // The next request starts a new process
+  assert!(sidecar.request(&request).await.is_err());
+  assert!(!sidecar.is_running());
+}
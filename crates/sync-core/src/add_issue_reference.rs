@@ -95,12 +95,10 @@ pub fn add_issue_reference_to_commits_core(git_executor: &GitCommandExecutor, pa
 
   // Reword commits using plumbing commands
   match reword_commits_batch(git_executor, &params.repository_path, rewrites) {
-    Ok(mapping) => {
+    Ok(_) => {
       info!(
         "Successfully added issue reference '{}' to {} commits (skipped {})",
-        params.issue_reference,
-        mapping.len(),
-        skipped_count
+        params.issue_reference, updated_count, skipped_count
       );
 
       Ok(AddIssueReferenceResult {
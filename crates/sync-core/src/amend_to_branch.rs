@@ -1,6 +1,7 @@
 use git_executor::git_command_executor::GitCommandExecutor;
-use git_ops::amend_operations::{AmendToCommitParams, amend_to_commit_in_main};
+use git_ops::amend_operations::{AmendPreview, AmendToCommitParams, amend_to_commit_in_main, preview_amend};
 use git_ops::copy_commit::CopyCommitError;
+use git_ops::diff_options::load_diff_options;
 use git_ops::model::BranchError;
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +15,15 @@ pub struct AmendUncommittedToBranchParams {
   pub files: Vec<String>,
 }
 
+#[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewAmendParams {
+  pub repository_path: String,
+  pub original_commit_id: String,
+  pub files: Vec<String>,
+}
+
 #[derive(Debug, Serialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
 #[serde(rename_all = "camelCase")]
@@ -52,3 +62,15 @@ pub fn amend_uncommitted_to_branch_core(git_executor: &GitCommandExecutor, param
     Err(CopyCommitError::Other(other_err)) => Err(format!("Failed to amend commit: {}", other_err)),
   }
 }
+
+/// Core function for previewing the amend of uncommitted changes, nothing is written
+pub fn preview_amend_core(git_executor: &GitCommandExecutor, params: PreviewAmendParams) -> Result<AmendPreview, String> {
+  let PreviewAmendParams {
+    repository_path,
+    original_commit_id,
+    files,
+  } = params;
+
+  let diff_options = load_diff_options(git_executor, &repository_path);
+  preview_amend(git_executor, &repository_path, &AmendToCommitParams { original_commit_id, files }, &diff_options).map_err(|e| format!("Failed to preview amend: {}", e))
+}
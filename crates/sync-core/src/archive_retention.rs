@@ -0,0 +1,39 @@
+use anyhow::Result;
+use branch_integration::archive::{ArchiveRetentionPolicy, ArchiveRetentionReport, apply_archive_retention, load_archive_retention_policy};
+use git_executor::git_command_executor::GitCommandExecutor;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyArchiveRetentionParams {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  /// Policy to apply; the repository configuration is used when not provided
+  pub policy: Option<ArchiveRetentionPolicy>,
+  /// Only report which branches would be deleted
+  pub dry_run: bool,
+}
+
+/// Apply the archive retention policy to archived branches of a repository
+#[instrument(skip(git_executor), fields(repo = %params.repository_path, dry_run = params.dry_run))]
+pub fn apply_archive_retention_core(git_executor: &GitCommandExecutor, params: ApplyArchiveRetentionParams) -> Result<ArchiveRetentionReport> {
+  let ApplyArchiveRetentionParams {
+    repository_path,
+    branch_prefix,
+    policy,
+    dry_run,
+  } = params;
+
+  let branch_prefix = branch_prefix.trim_end_matches('/');
+  if branch_prefix.is_empty() {
+    return Err(anyhow::anyhow!("Branch prefix is not configured"));
+  }
+
+  let policy = match policy {
+    Some(policy) => policy,
+    None => load_archive_retention_policy(git_executor, &repository_path, ArchiveRetentionPolicy::default())?,
+  };
+  apply_archive_retention(git_executor, &repository_path, branch_prefix, &policy, dry_run)
+}
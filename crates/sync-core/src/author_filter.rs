@@ -0,0 +1,97 @@
+//! Grouping only the user's own commits in repositories shared with other people.
+//!
+//! With `branchdeck.onlyMyCommits` enabled, prefixed commits of other authors are not grouped into virtual branches,
+//! they are listed as unassigned. Commits are matched by author email against `user.email` and the aliases in
+//! `branchdeck.myEmails`; the authors listed in `branchdeck.includeAuthors` are grouped as well. Lists are separated by
+//! commas or whitespace, emails match ignoring case.
+
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::git_config::{get_config_bool, get_config_value};
+use std::collections::HashSet;
+use tracing::{instrument, warn};
+
+/// Git config key enabling the filter
+pub const ONLY_MY_COMMITS_CONFIG_KEY: &str = "branchdeck.onlyMyCommits";
+
+/// Git config key with other emails of the user, e.g., a personal and a work address
+pub const MY_EMAILS_CONFIG_KEY: &str = "branchdeck.myEmails";
+
+/// Git config key with emails of other authors whose commits are grouped anyway
+pub const INCLUDE_AUTHORS_CONFIG_KEY: &str = "branchdeck.includeAuthors";
+
+/// Authors whose prefixed commits are grouped
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthorFilter {
+  my_emails: HashSet<String>,
+  included_authors: HashSet<String>,
+}
+
+fn parse_emails(value: &str) -> HashSet<String> {
+  value
+    .split(|c: char| c == ',' || c.is_whitespace())
+    .filter(|email| !email.is_empty())
+    .map(str::to_lowercase)
+    .collect()
+}
+
+impl AuthorFilter {
+  /// Build the filter from the config values: the user's emails (`user.email` and aliases) and the included other authors
+  pub fn from_config_values(my_emails: &[&str], included_authors: &str) -> Self {
+    Self {
+      my_emails: my_emails.iter().flat_map(|value| parse_emails(value)).collect(),
+      included_authors: parse_emails(included_authors),
+    }
+  }
+
+  pub fn is_mine(&self, author_email: &str) -> bool {
+    self.my_emails.contains(&author_email.to_lowercase())
+  }
+
+  /// Whether the commits of the author are grouped
+  pub fn accepts(&self, author_email: &str) -> bool {
+    let author_email = author_email.to_lowercase();
+    self.my_emails.contains(&author_email) || self.included_authors.contains(&author_email)
+  }
+
+  /// Included authors other than the user among the given emails, sorted and deduplicated
+  pub fn other_authors<'a>(&self, author_emails: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut other_authors: Vec<String> = author_emails
+      .into_iter()
+      .filter(|email| !email.is_empty() && !self.is_mine(email))
+      .map(str::to_string)
+      .collect();
+    other_authors.sort();
+    other_authors.dedup();
+    other_authors
+  }
+}
+
+fn read_config_value(git_executor: &GitCommandExecutor, repository_path: &str, key: &str) -> String {
+  get_config_value(git_executor, repository_path, key)
+    .unwrap_or_else(|e| {
+      warn!(error = %e, key, "Failed to read author filter setting");
+      None
+    })
+    .unwrap_or_default()
+}
+
+/// Read the author filter; `None` unless enabled, or if the user's email is unknown so their commits can't be told apart
+#[instrument(skip(git_executor))]
+pub fn load_author_filter(git_executor: &GitCommandExecutor, repository_path: &str) -> Option<AuthorFilter> {
+  let enabled = get_config_bool(git_executor, repository_path, ONLY_MY_COMMITS_CONFIG_KEY).unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to read only-my-commits setting, grouping commits of all authors");
+    None
+  });
+  if enabled != Some(true) {
+    return None;
+  }
+
+  let user_email = read_config_value(git_executor, repository_path, "user.email");
+  let aliases = read_config_value(git_executor, repository_path, MY_EMAILS_CONFIG_KEY);
+  let filter = AuthorFilter::from_config_values(&[&user_email, &aliases], &read_config_value(git_executor, repository_path, INCLUDE_AUTHORS_CONFIG_KEY));
+  if filter.my_emails.is_empty() {
+    warn!("{ONLY_MY_COMMITS_CONFIG_KEY} is enabled but neither user.email nor {MY_EMAILS_CONFIG_KEY} is set, grouping commits of all authors");
+    return None;
+  }
+  Some(filter)
+}
@@ -0,0 +1,166 @@
+//! Auto-archiving of virtual branches that stay fully integrated into the baseline.
+//!
+//! A branch counts as integrated in a sync when every one of its commits is already applied to the baseline.
+//! Once that happened for the configured number of consecutive syncs, the virtual branch is moved to the archive and,
+//! optionally, its commits are dropped from HEAD so the branch doesn't come back on the next sync.
+
+use crate::branch_protection::check_delete_allowed;
+use crate::repo_state::update_repo_state;
+use anyhow::Result;
+use branch_integration::archive::archive_branch;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::amend_operations::drop_commits_from_head;
+use git_ops::git_config::{get_config_bool, get_config_value};
+use git_ops::model::to_final_branch_name;
+use indexmap::IndexMap;
+use std::collections::HashSet;
+use std::num::NonZeroU32;
+use sync_types::{ProgressReporter, SyncEvent};
+use tracing::{debug, instrument, warn};
+
+/// Git config key with the number of consecutive syncs a branch must be fully integrated before it is archived (0 disables auto-archiving)
+pub const AUTO_ARCHIVE_AFTER_SYNCS_CONFIG_KEY: &str = "branchdeck.autoArchiveAfterSyncs";
+/// Git config key enabling dropping the commits of auto-archived branches from HEAD
+pub const AUTO_ARCHIVE_PRUNE_COMMITS_CONFIG_KEY: &str = "branchdeck.autoArchivePruneCommits";
+
+/// When sync archives fully integrated branches on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AutoArchivePolicy {
+  /// Consecutive syncs a branch must be fully integrated, `None` disables auto-archiving
+  pub after_syncs: Option<NonZeroU32>,
+  /// Drop the commits of archived branches from HEAD via the rewrite engine
+  pub prune_commits: bool,
+}
+
+/// Read the auto-archive policy from git config; missing or invalid values disable auto-archiving
+#[instrument(skip(git_executor))]
+pub fn load_auto_archive_policy(git_executor: &GitCommandExecutor, repository_path: &str) -> AutoArchivePolicy {
+  let after_syncs = match get_config_value(git_executor, repository_path, AUTO_ARCHIVE_AFTER_SYNCS_CONFIG_KEY) {
+    Ok(Some(value)) => value.trim().parse::<u32>().map(NonZeroU32::new).unwrap_or_else(|_| {
+      warn!(value, "Invalid {AUTO_ARCHIVE_AFTER_SYNCS_CONFIG_KEY} value, not archiving integrated branches");
+      None
+    }),
+    Ok(None) => None,
+    Err(e) => {
+      warn!(error = %e, "Failed to read auto-archive setting, not archiving integrated branches");
+      None
+    }
+  };
+  let prune_commits = after_syncs.is_some()
+    && get_config_bool(git_executor, repository_path, AUTO_ARCHIVE_PRUNE_COMMITS_CONFIG_KEY)
+      .ok()
+      .flatten()
+      .unwrap_or(false);
+  AutoArchivePolicy { after_syncs, prune_commits }
+}
+
+pub(crate) struct AutoArchiveParams<'a, P: ProgressReporter> {
+  pub git_executor: &'a GitCommandExecutor,
+  pub repository_path: &'a str,
+  pub branch_prefix: &'a str,
+  pub policy: AutoArchivePolicy,
+  /// Original commit IDs of every synced branch
+  pub branch_commit_ids: &'a IndexMap<String, Vec<String>>,
+  /// Synced branches whose commits were all already applied to the baseline
+  pub integrated_branches: &'a HashSet<String>,
+  pub progress: &'a P,
+}
+
+/// Count the consecutive syncs each branch was fully integrated and archive the branches that reached the policy threshold.
+/// Counts of branches that are not fully integrated anymore, or are gone, are reset.
+#[instrument(skip(params), fields(integrated_count = params.integrated_branches.len()))]
+pub(crate) fn auto_archive_integrated_branches<P: ProgressReporter>(params: AutoArchiveParams<'_, P>) -> Result<()> {
+  let AutoArchiveParams {
+    git_executor,
+    repository_path,
+    branch_prefix,
+    policy,
+    branch_commit_ids,
+    integrated_branches,
+    progress,
+  } = params;
+
+  let mut due_branches: Vec<(String, u32)> = Vec::new();
+  update_repo_state(git_executor, repository_path, |state| {
+    let previous_counts = std::mem::take(&mut state.integrated_sync_counts);
+    // Keep the sync order, so branches are archived in the order they are shown
+    for branch_name in branch_commit_ids.keys().filter(|name| integrated_branches.contains(*name)) {
+      let count = previous_counts.get(branch_name).copied().unwrap_or(0) + 1;
+      match policy.after_syncs {
+        Some(after_syncs) if count >= after_syncs.get() => due_branches.push((branch_name.clone(), count)),
+        _ => {
+          state.integrated_sync_counts.insert(branch_name.clone(), count);
+        }
+      }
+    }
+  })?;
+
+  if due_branches.is_empty() {
+    return Ok(());
+  }
+
+  let mut archived_branches = Vec::with_capacity(due_branches.len());
+  for (branch_name, integrated_sync_count) in due_branches {
+    let full_branch_name = to_final_branch_name(branch_prefix, &branch_name)?;
+    if let Err(e) = check_delete_allowed(git_executor, repository_path, branch_prefix, std::slice::from_ref(&full_branch_name)) {
+      debug!(branch_name, error = %e, "Not auto-archiving integrated branch");
+      continue;
+    }
+    let branch_ref = format!("refs/heads/{full_branch_name}");
+    // The empty branch policy may have archived or never created the branch
+    let archived_branch = if git_executor.execute_command(&["show-ref", "--verify", "--quiet", &branch_ref], repository_path).is_ok() {
+      match archive_branch(git_executor, repository_path, &full_branch_name, branch_prefix) {
+        Ok(archived_branch) => Some(archived_branch),
+        Err(e) => {
+          warn!(branch_name, error = %e, "Failed to auto-archive integrated branch");
+          continue;
+        }
+      }
+    } else {
+      None
+    };
+    archived_branches.push((branch_name, archived_branch, integrated_sync_count));
+  }
+
+  // Commits of all archived branches are dropped in one rewrite; a failure leaves HEAD as it was
+  let mut pruned = false;
+  if policy.prune_commits {
+    let commit_ids: Vec<String> = archived_branches
+      .iter()
+      .filter_map(|(branch_name, ..)| branch_commit_ids.get(branch_name))
+      .flatten()
+      .cloned()
+      .collect();
+    if !commit_ids.is_empty() {
+      // The rewrite moves the checked out branch, never the baseline (which may be a remote-tracking branch)
+      match git_executor.execute_command(&["symbolic-ref", "--short", "HEAD"], repository_path) {
+        Ok(current_branch) => match drop_commits_from_head(git_executor, repository_path, &commit_ids, &current_branch) {
+          Ok(rewritten) => {
+            debug!(
+              new_head = rewritten.new_head,
+              commit_count = commit_ids.len(),
+              "Dropped commits of auto-archived branches from HEAD"
+            );
+            pruned = true;
+          }
+          Err(e) => warn!(error = %e, "Failed to drop commits of auto-archived branches from HEAD"),
+        },
+        Err(e) => warn!(error = %e, "HEAD is detached, not dropping commits of auto-archived branches"),
+      }
+    }
+  }
+
+  for (branch_name, archived_branch, integrated_sync_count) in archived_branches {
+    let pruned_commit_count = if pruned { branch_commit_ids.get(&branch_name).map_or(0, Vec::len) as u32 } else { 0 };
+    if archived_branch.is_none() && pruned_commit_count == 0 {
+      continue;
+    }
+    progress.send(SyncEvent::BranchAutoArchived {
+      branch_name,
+      archived_branch,
+      integrated_sync_count,
+      pruned_commit_count,
+    })?;
+  }
+  Ok(())
+}
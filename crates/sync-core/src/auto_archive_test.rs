@@ -0,0 +1,170 @@
+use crate::auto_archive::{AUTO_ARCHIVE_AFTER_SYNCS_CONFIG_KEY, AUTO_ARCHIVE_PRUNE_COMMITS_CONFIG_KEY, AutoArchivePolicy, load_auto_archive_policy};
+use crate::repo_state::load_repo_state;
+use crate::sync::{SyncOptions, sync_branches};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+use sync_test_utils::TestReporter;
+use sync_types::SyncEvent;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// The fork point already has the change of the only `feature` commit, reverted in between by an unassigned commit
+fn create_integrated_group_repo() -> TestRepo {
+  let test_repo = TestRepo::new();
+  let initial_id = test_repo.create_commit("Initial commit", "config.txt", "timeout=30\n");
+  test_repo.create_branch_at("origin/main", &initial_id).unwrap();
+  test_repo.create_commit("Lower timeout", "config.txt", "timeout=10\n");
+  test_repo.create_commit("(feature) Restore timeout", "config.txt", "timeout=30\n");
+  test_repo
+}
+
+async fn sync(test_repo: &TestRepo, policy: AutoArchivePolicy) -> anyhow::Result<Vec<SyncEvent>> {
+  let progress = TestReporter::new();
+  sync_branches(
+    &GitCommandExecutor::new(),
+    test_repo.path().to_str().unwrap(),
+    "test",
+    progress.clone(),
+    SyncOptions {
+      auto_archive: Some(policy),
+      ..Default::default()
+    },
+  )
+  .await?;
+  Ok(progress.get_events())
+}
+
+/// `(branch name, archived branch, integrated sync count, pruned commit count)` of the auto-archived branches
+fn auto_archived(events: &[SyncEvent]) -> Vec<(String, Option<String>, u32, u32)> {
+  events
+    .iter()
+    .filter_map(|event| match event {
+      SyncEvent::BranchAutoArchived {
+        branch_name,
+        archived_branch,
+        integrated_sync_count,
+        pruned_commit_count,
+      } => Some((branch_name.clone(), archived_branch.clone(), *integrated_sync_count, *pruned_commit_count)),
+      _ => None,
+    })
+    .collect()
+}
+
+#[test]
+fn test_load_auto_archive_policy() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  assert_eq!(load_auto_archive_policy(&git_executor, repo_path), AutoArchivePolicy::default());
+
+  // Pruning alone doesn't enable auto-archiving
+  test_repo.set_config(AUTO_ARCHIVE_PRUNE_COMMITS_CONFIG_KEY, "true").unwrap();
+  assert_eq!(load_auto_archive_policy(&git_executor, repo_path), AutoArchivePolicy::default());
+
+  test_repo.set_config(AUTO_ARCHIVE_AFTER_SYNCS_CONFIG_KEY, "3").unwrap();
+  assert_eq!(
+    load_auto_archive_policy(&git_executor, repo_path),
+    AutoArchivePolicy {
+      after_syncs: NonZeroU32::new(3),
+      prune_commits: true,
+    }
+  );
+
+  test_repo.set_config(AUTO_ARCHIVE_AFTER_SYNCS_CONFIG_KEY, "0").unwrap();
+  assert_eq!(load_auto_archive_policy(&git_executor, repo_path), AutoArchivePolicy::default());
+}
+
+#[test(tokio::test)]
+async fn test_branch_archived_after_consecutive_integrated_syncs() -> anyhow::Result<()> {
+  let test_repo = create_integrated_group_repo();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let policy = AutoArchivePolicy {
+    after_syncs: NonZeroU32::new(2),
+    prune_commits: false,
+  };
+
+  let events = sync(&test_repo, policy).await?;
+  assert_eq!(auto_archived(&events), Vec::new());
+  assert!(test_repo.branch_exists("test/virtual/feature"));
+  assert_eq!(
+    load_repo_state(&git_executor, repo_path)?.integrated_sync_counts,
+    BTreeMap::from([("feature".to_string(), 1)])
+  );
+
+  let events = sync(&test_repo, policy).await?;
+  let archived = auto_archived(&events);
+  assert_eq!(archived.len(), 1);
+  let (branch_name, archived_branch, integrated_sync_count, pruned_commit_count) = &archived[0];
+  assert_eq!((branch_name.as_str(), *integrated_sync_count, *pruned_commit_count), ("feature", 2, 0));
+  let archived_branch = archived_branch.as_deref().expect("archived branch");
+  assert!(archived_branch.starts_with("test/archived/") && archived_branch.ends_with("/feature"), "{archived_branch}");
+  assert!(test_repo.branch_exists(archived_branch));
+  assert!(!test_repo.branch_exists("test/virtual/feature"));
+  assert_eq!(load_repo_state(&git_executor, repo_path)?.integrated_sync_counts, BTreeMap::new());
+
+  // The commits are still in HEAD, so the branch is recreated and counted again
+  let events = sync(&test_repo, policy).await?;
+  assert_eq!(auto_archived(&events), Vec::new());
+  assert!(test_repo.branch_exists("test/virtual/feature"));
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_auto_archive_prunes_commits_from_head() -> anyhow::Result<()> {
+  let test_repo = create_integrated_group_repo();
+  test_repo.create_commit("(docs) Add readme", "README.md", "# Test");
+
+  let events = sync(
+    &test_repo,
+    AutoArchivePolicy {
+      after_syncs: NonZeroU32::new(1),
+      prune_commits: true,
+    },
+  )
+  .await?;
+
+  let archived = auto_archived(&events);
+  assert_eq!(archived.len(), 1);
+  assert_eq!((archived[0].0.as_str(), archived[0].2, archived[0].3), ("feature", 1, 1));
+  assert!(!test_repo.branch_exists("test/virtual/feature"));
+  // Branches that are not integrated are left alone
+  assert!(test_repo.branch_exists("test/virtual/docs"));
+
+  let subjects = test_repo.log(&["--pretty=format:%s", "HEAD"]).expect("git log should succeed");
+  assert_eq!(subjects.lines().collect::<Vec<_>>(), vec!["(docs) Add readme", "Lower timeout", "Initial commit"]);
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_auto_archive_prunes_current_branch_with_remote_baseline() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  let initial_id = test_repo.create_commit("Initial commit", "config.txt", "timeout=30\n");
+  test_repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+  test_repo
+    .git_executor()
+    .execute_command(&["update-ref", "refs/remotes/origin/master", &initial_id], test_repo.path().to_str().unwrap())?;
+  test_repo.create_commit("Lower timeout", "config.txt", "timeout=10\n");
+  test_repo.create_commit("(feature) Restore timeout", "config.txt", "timeout=30\n");
+  test_repo.create_commit("(docs) Add readme", "README.md", "# Test");
+
+  let events = sync(
+    &test_repo,
+    AutoArchivePolicy {
+      after_syncs: NonZeroU32::new(1),
+      prune_commits: true,
+    },
+  )
+  .await?;
+
+  assert_eq!(auto_archived(&events).len(), 1);
+  // The checked out branch is rewritten, the remote-tracking baseline is neither moved nor shadowed by a local branch
+  assert_eq!(test_repo.current_branch().unwrap(), "master");
+  let subjects = test_repo.log(&["--pretty=format:%s", "HEAD"]).expect("git log should succeed");
+  assert_eq!(subjects.lines().collect::<Vec<_>>(), vec!["(docs) Add readme", "Lower timeout", "Initial commit"]);
+  assert_eq!(test_repo.rev_parse("refs/remotes/origin/master").unwrap(), initial_id);
+  assert!(!test_repo.branch_exists("origin/master"));
+  Ok(())
+}
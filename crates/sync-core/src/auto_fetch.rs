@@ -0,0 +1,132 @@
+use crate::network::{offline_error, remote_network_status};
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::git_config::get_config_value;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use sync_types::{NetworkStatus, ProgressReporter, SyncEvent};
+use tracing::{debug, instrument, warn};
+
+/// Git config key holding the per-repository auto-fetch policy
+pub const AUTO_FETCH_CONFIG_KEY: &str = "branchdeck.autoFetch";
+
+/// Whether sync fetches the remote (with prune) before grouping the commits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AutoFetchPolicy {
+  /// Sync with the remote-tracking refs as they are
+  #[default]
+  Never,
+  Always,
+  /// Fetch only if the last fetch is older, so syncing several times in a row doesn't wait for the network each time
+  IfOlderThan(Duration),
+}
+
+impl AutoFetchPolicy {
+  /// Parse a config value (`never`, `always` or the maximum age of the last fetch in minutes, e.g. `15`)
+  pub fn from_config_value(value: &str) -> Option<Self> {
+    match value.trim().to_ascii_lowercase().as_str() {
+      "never" => Some(Self::Never),
+      "always" => Some(Self::Always),
+      minutes => minutes.parse::<u64>().ok().map(|minutes| Self::IfOlderThan(Duration::from_secs(minutes * 60))),
+    }
+  }
+}
+
+/// Read the auto-fetch policy from git config, falling back to the default for missing or unknown values
+#[instrument(skip(git_executor))]
+pub fn load_auto_fetch_policy(git_executor: &GitCommandExecutor, repository_path: &str) -> AutoFetchPolicy {
+  match get_config_value(git_executor, repository_path, AUTO_FETCH_CONFIG_KEY) {
+    Ok(Some(value)) => AutoFetchPolicy::from_config_value(&value).unwrap_or_else(|| {
+      warn!(value, "Unknown {AUTO_FETCH_CONFIG_KEY} value, not fetching before sync");
+      AutoFetchPolicy::default()
+    }),
+    Ok(None) => AutoFetchPolicy::default(),
+    Err(e) => {
+      warn!(error = %e, "Failed to read auto-fetch policy, not fetching before sync");
+      AutoFetchPolicy::default()
+    }
+  }
+}
+
+/// Time since the last fetch from the modification time of `FETCH_HEAD`, `None` if the repository was never fetched
+fn last_fetch_age(git_executor: &GitCommandExecutor, repository_path: &str) -> Option<Duration> {
+  let fetch_head = git_executor.execute_command(&["rev-parse", "--git-path", "FETCH_HEAD"], repository_path).ok()?;
+  let modified = std::fs::metadata(Path::new(repository_path).join(fetch_head)).ok()?.modified().ok()?;
+  Some(SystemTime::now().duration_since(modified).unwrap_or_default())
+}
+
+/// Phase and percentage of a progress line like `Receiving objects:  45% (45/100), 1.00 KiB | 1.00 MiB/s`
+pub fn parse_fetch_progress(line: &str) -> (String, Option<u32>) {
+  let line = line.trim();
+  let line = line.strip_prefix("remote:").map_or(line, str::trim_start);
+  match line.split_once(':') {
+    Some((phase, rest)) => {
+      let percent = rest
+        .split_whitespace()
+        .next()
+        .and_then(|token| token.strip_suffix('%'))
+        .and_then(|percent| percent.parse().ok());
+      (phase.trim().to_string(), percent)
+    }
+    None => (line.to_string(), None),
+  }
+}
+
+/// Fetch the default remote with prune if the policy asks for it, forwarding the progress as [`SyncEvent::FetchProgress`].
+/// A failed fetch (e.g. offline) is reported with [`SyncEvent::FetchCompleted`] and not returned, the sync goes on with the refs it has.
+#[instrument(skip(git_executor, progress))]
+pub fn fetch_before_sync<P: ProgressReporter>(git_executor: &GitCommandExecutor, repository_path: &str, policy: AutoFetchPolicy, progress: &P) -> Result<()> {
+  let max_age = match policy {
+    AutoFetchPolicy::Never => return Ok(()),
+    AutoFetchPolicy::Always => None,
+    AutoFetchPolicy::IfOlderThan(max_age) => Some(max_age),
+  };
+  if git_executor.execute_command_lines(&["remote"], repository_path)?.is_empty() {
+    return Ok(());
+  }
+  if let Some(max_age) = max_age
+    && let Some(age) = last_fetch_age(git_executor, repository_path)
+    && age < max_age
+  {
+    debug!(age_secs = age.as_secs(), "Fetched recently, not fetching before sync");
+    return Ok(());
+  }
+  match remote_network_status(git_executor, repository_path, None) {
+    NetworkStatus::Online => {}
+    NetworkStatus::OfflineMode => {
+      debug!("Offline mode, not fetching before sync");
+      return Ok(());
+    }
+    // Reported like a failed fetch, without waiting for it to time out
+    status @ NetworkStatus::Offline => {
+      return progress.send(SyncEvent::FetchCompleted {
+        error: Some(offline_error(status).message),
+      });
+    }
+  }
+
+  let mut last_progress = None;
+  let mut send_error = None;
+  // Sync must never wait for credentials
+  let result = git_executor.execute_command_with_progress(&["fetch", "--prune", "--progress"], repository_path, &[("GIT_TERMINAL_PROMPT", "0")], |line| {
+    let current = parse_fetch_progress(line);
+    // Percentages are printed more often than they change
+    if send_error.is_some() || last_progress.as_ref() == Some(&current) {
+      return;
+    }
+    let (phase, percent) = current.clone();
+    last_progress = Some(current);
+    if let Err(e) = progress.send(SyncEvent::FetchProgress { phase, percent }) {
+      send_error = Some(e);
+    }
+  });
+  if let Some(e) = send_error {
+    return Err(e);
+  }
+
+  let error = result.err().map(|e| {
+    warn!(error = %e, "Fetch before sync failed, syncing with the local remote-tracking refs");
+    format!("{e:#}")
+  });
+  progress.send(SyncEvent::FetchCompleted { error })
+}
@@ -0,0 +1,116 @@
+use crate::auto_fetch::{AutoFetchPolicy, fetch_before_sync, parse_fetch_progress};
+use crate::sync::{SyncOptions, sync_branches};
+use pretty_assertions::assert_eq;
+use std::time::Duration;
+use sync_test_utils::TestReporter;
+use sync_types::SyncEvent;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn fetch_errors(progress: &TestReporter) -> Vec<Option<String>> {
+  progress
+    .get_events()
+    .into_iter()
+    .filter_map(|event| match event {
+      SyncEvent::FetchCompleted { error } => Some(error),
+      _ => None,
+    })
+    .collect()
+}
+
+#[test]
+fn test_auto_fetch_policy_from_config_value() {
+  assert_eq!(AutoFetchPolicy::from_config_value("never"), Some(AutoFetchPolicy::Never));
+  assert_eq!(AutoFetchPolicy::from_config_value(" Always "), Some(AutoFetchPolicy::Always));
+  assert_eq!(AutoFetchPolicy::from_config_value("15"), Some(AutoFetchPolicy::IfOlderThan(Duration::from_secs(15 * 60))));
+  assert_eq!(AutoFetchPolicy::from_config_value("sometimes"), None);
+}
+
+#[test]
+fn test_parse_fetch_progress() {
+  assert_eq!(
+    parse_fetch_progress("Receiving objects:  45% (45/100), 1.00 KiB | 1.00 MiB/s"),
+    ("Receiving objects".to_string(), Some(45))
+  );
+  assert_eq!(
+    parse_fetch_progress("remote: Counting objects: 100% (3/3), done."),
+    ("Counting objects".to_string(), Some(100))
+  );
+  assert_eq!(parse_fetch_progress("From /tmp/origin"), ("From /tmp/origin".to_string(), None));
+}
+
+#[test(tokio::test)]
+async fn test_fetch_before_sync() -> anyhow::Result<()> {
+  let origin = TestRepo::new();
+  origin.create_commit("Initial commit", "README.md", "# Test");
+  origin.git_executor().execute_command(&["branch", "stale"], origin.path().to_str().unwrap())?;
+  let local = TestRepo::new_empty();
+  local.clone_from(origin.path()).unwrap();
+  local.create_commit("(feature) Add feature", "feature.txt", "feature");
+  let repository_path = local.path().to_str().unwrap();
+
+  let remote_head = origin.create_commit("Upstream change", "upstream.txt", "upstream");
+  origin.git_executor().execute_command(&["branch", "-D", "stale"], origin.path().to_str().unwrap())?;
+
+  let progress = TestReporter::new();
+  let options = SyncOptions {
+    auto_fetch: Some(AutoFetchPolicy::Always),
+    ..Default::default()
+  };
+  sync_branches(local.git_executor(), repository_path, "test", progress.clone(), options).await?;
+
+  assert_eq!(fetch_errors(&progress), vec![None]);
+  assert_eq!(local.rev_parse("origin/master").unwrap(), remote_head);
+  // Pruned
+  assert!(local.rev_parse("origin/stale").is_err());
+  // The virtual branch is based on the fetched baseline
+  assert_eq!(local.rev_parse("test/virtual/feature^").unwrap(), remote_head);
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_fetch_skipped_by_policy() -> anyhow::Result<()> {
+  let origin = TestRepo::new();
+  let initial = origin.create_commit("Initial commit", "README.md", "# Test");
+  let local = TestRepo::new_empty();
+  local.clone_from(origin.path()).unwrap();
+  let repository_path = local.path().to_str().unwrap();
+  fetch_before_sync(local.git_executor(), repository_path, AutoFetchPolicy::Always, &TestReporter::new())?;
+  origin.create_commit("Upstream change", "upstream.txt", "upstream");
+
+  let progress = TestReporter::new();
+  fetch_before_sync(local.git_executor(), repository_path, AutoFetchPolicy::Never, &progress)?;
+  // Fetched just now
+  fetch_before_sync(local.git_executor(), repository_path, AutoFetchPolicy::IfOlderThan(Duration::from_secs(3600)), &progress)?;
+
+  assert_eq!(progress.event_count(), 0);
+  assert_eq!(local.rev_parse("origin/master").unwrap(), initial);
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_failed_fetch_syncs_offline() -> anyhow::Result<()> {
+  let origin = TestRepo::new();
+  let initial = origin.create_commit("Initial commit", "README.md", "# Test");
+  let local = TestRepo::new_empty();
+  local.clone_from(origin.path()).unwrap();
+  local.create_commit("(feature) Add feature", "feature.txt", "feature");
+  let repository_path = local.path().to_str().unwrap();
+  local
+    .git_executor()
+    .execute_command(&["remote", "set-url", "origin", "/nonexistent/branch-deck-origin"], repository_path)?;
+
+  let progress = TestReporter::new();
+  let options = SyncOptions {
+    auto_fetch: Some(AutoFetchPolicy::Always),
+    ..Default::default()
+  };
+  sync_branches(local.git_executor(), repository_path, "test", progress.clone(), options).await?;
+
+  let errors = fetch_errors(&progress);
+  assert_eq!(errors.len(), 1);
+  assert!(errors[0].is_some());
+  assert_eq!(local.rev_parse("origin/master").unwrap(), initial);
+  assert!(local.rev_parse("test/virtual/feature").is_ok());
+  Ok(())
+}
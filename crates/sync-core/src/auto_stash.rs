@@ -0,0 +1,183 @@
+//! Stash of uncommitted changes around a sync, so flows moving HEAD (e.g., pruning the commits of auto-archived branches)
+//! don't run on a dirty working tree.
+//!
+//! Built with plumbing instead of `git stash`: the reflog of `refs/stash` is left alone and nothing prompts or runs hooks.
+//! The stash commit has the layout of `git stash` (the working tree with HEAD and the index commit as parents) and is kept
+//! under [`AUTO_STASH_REF`] until restored, so changes that can't be restored cleanly are applied with `git stash apply`.
+
+use anyhow::{Context, Result, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::diff_options::DiffOptions;
+use git_ops::git_config::get_config_bool;
+use git_ops::merge_tree::merge_trees;
+use git_ops::temp_dir::TempDirGuard;
+use std::collections::BTreeSet;
+use tracing::{debug, info, instrument, warn};
+
+/// Git config key enabling the auto-stash around sync
+pub const AUTO_STASH_CONFIG_KEY: &str = "branchdeck.autoStash";
+
+/// Ref holding the stashed changes until they are restored
+pub const AUTO_STASH_REF: &str = "refs/branch-deck/autostash";
+
+/// Read whether sync stashes uncommitted changes; disabled unless set
+#[instrument(skip(git_executor))]
+pub fn load_auto_stash(git_executor: &GitCommandExecutor, repository_path: &str) -> bool {
+  get_config_bool(git_executor, repository_path, AUTO_STASH_CONFIG_KEY).unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to read auto-stash setting, not stashing uncommitted changes");
+    None
+  }) == Some(true)
+}
+
+/// Uncommitted changes stashed before a sync
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoStash {
+  /// Stash commit, referenced by [`AUTO_STASH_REF`] until restored
+  pub stash_commit: String,
+  /// Number of changed tracked files, staged or not
+  pub file_count: u32,
+  head_commit: String,
+  index_tree: String,
+  worktree_tree: String,
+}
+
+/// How the stashed changes were brought back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StashRestore {
+  Restored,
+  /// HEAD moved and the changes conflict with it: the working tree is at the new HEAD and the changes stay in [`AUTO_STASH_REF`]
+  Conflicted {
+    conflicting_files: Vec<String>,
+  },
+}
+
+fn split_paths(output: &str) -> impl Iterator<Item = &str> {
+  output.split('\0').filter(|path| !path.is_empty())
+}
+
+/// Stash the changes of tracked files and reset the index and the working tree to HEAD; untracked files are left alone.
+/// `None` if there is nothing to stash.
+#[instrument(skip(git_executor))]
+pub fn stash_uncommitted_changes(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<Option<AutoStash>> {
+  let (_, exit_code) = git_executor.execute_command_with_status(&["rev-parse", "--verify", "--quiet", AUTO_STASH_REF], repository_path)?;
+  if exit_code == 0 {
+    bail!(
+      "Uncommitted changes stashed by a previous sync were not restored, apply them with `git stash apply {AUTO_STASH_REF}` and delete the ref with `git update-ref -d {AUTO_STASH_REF}`"
+    );
+  }
+
+  let (head_commit, exit_code) = git_executor.execute_command_with_status(&["rev-parse", "--verify", "--quiet", "HEAD"], repository_path)?;
+  if exit_code != 0 {
+    // Unborn branch, there is nothing a sync could rewrite
+    return Ok(None);
+  }
+  let head_commit = head_commit.trim().to_string();
+
+  // Exit code 1 only means that some files need an update, which diff-files reports below
+  git_executor.execute_command_with_status(&["update-index", "-q", "--refresh"], repository_path)?;
+  let unstaged_output = git_executor.execute_command(&["diff-files", "--name-only", "-z"], repository_path)?;
+  let staged_output = git_executor.execute_command(&["diff-index", "--cached", "--name-only", "-z", &head_commit], repository_path)?;
+  let unstaged: Vec<&str> = split_paths(&unstaged_output).collect();
+  let changed_files: BTreeSet<&str> = unstaged.iter().copied().chain(split_paths(&staged_output)).collect();
+  if changed_files.is_empty() {
+    return Ok(None);
+  }
+
+  let index_tree = git_executor
+    .execute_command(&["write-tree"], repository_path)
+    .context("Can't stash uncommitted changes with unresolved conflicts")?
+    .trim()
+    .to_string();
+
+  // The working tree is the index plus the unstaged changes, written from a copy of the index
+  let temp_dir = TempDirGuard::new(repository_path, "autostash")?;
+  let index_path = temp_dir.join("index");
+  let env = [("GIT_INDEX_FILE", index_path.as_str())];
+  git_executor.execute_command_with_env(&["read-tree", &index_tree], repository_path, &env)?;
+  if !unstaged.is_empty() {
+    let input: String = unstaged.iter().map(|path| format!("{path}\0")).collect();
+    git_executor.execute_command_with_env_and_stdin(&["update-index", "--add", "--remove", "-z", "--stdin"], repository_path, &env, &input)?;
+  }
+  let worktree_tree = git_executor.execute_command_with_env(&["write-tree"], repository_path, &env)?.trim().to_string();
+
+  let index_commit = git_executor.execute_command(
+    &["commit-tree", "--no-gpg-sign", &index_tree, "-p", &head_commit, "-m", "Index stashed before sync"],
+    repository_path,
+  )?;
+  let stash_commit = git_executor
+    .execute_command(
+      &[
+        "commit-tree",
+        "--no-gpg-sign",
+        &worktree_tree,
+        "-p",
+        &head_commit,
+        "-p",
+        index_commit.trim(),
+        "-m",
+        "Uncommitted changes stashed before sync",
+      ],
+      repository_path,
+    )?
+    .trim()
+    .to_string();
+  // The empty old value makes the update fail if the ref was created meanwhile
+  git_executor.execute_command(&["update-ref", AUTO_STASH_REF, &stash_commit, ""], repository_path)?;
+
+  git_executor.execute_command(&["read-tree", "--reset", "-u", &head_commit], repository_path)?;
+  info!(file_count = changed_files.len(), %stash_commit, "Stashed uncommitted changes");
+  Ok(Some(AutoStash {
+    stash_commit,
+    file_count: changed_files.len() as u32,
+    head_commit,
+    index_tree,
+    worktree_tree,
+  }))
+}
+
+/// Bring the stashed changes back. If HEAD moved meanwhile, the changes are merged onto it; staged changes stay staged
+/// if they merge cleanly as well, otherwise all changes are restored unstaged.
+#[instrument(skip(git_executor, stash), fields(stash_commit = %stash.stash_commit))]
+pub fn restore_stashed_changes(git_executor: &GitCommandExecutor, repository_path: &str, stash: &AutoStash) -> Result<StashRestore> {
+  let head_commit = git_executor.execute_command(&["rev-parse", "HEAD"], repository_path)?.trim().to_string();
+  let (worktree_tree, index_tree, conflicting_files) = if head_commit == stash.head_commit {
+    (stash.worktree_tree.clone(), stash.index_tree.clone(), Vec::new())
+  } else {
+    let head_tree = git_executor.resolve_tree_id(repository_path, &head_commit)?;
+    let worktree_merge = merge_trees(
+      git_executor,
+      repository_path,
+      &stash.head_commit,
+      &head_commit,
+      &stash.worktree_tree,
+      &DiffOptions::default(),
+    )?;
+    if worktree_merge.has_conflicts() {
+      let conflicting_files: BTreeSet<String> = worktree_merge.conflicts.into_iter().map(|conflict| conflict.path).collect();
+      (head_tree.clone(), head_tree, conflicting_files.into_iter().collect())
+    } else {
+      let index_merge = merge_trees(git_executor, repository_path, &stash.head_commit, &head_commit, &stash.index_tree, &DiffOptions::default())?;
+      let index_tree = if index_merge.has_conflicts() { head_tree } else { index_merge.tree_id };
+      (worktree_merge.tree_id, index_tree, Vec::new())
+    }
+  };
+
+  // The working tree is still at the stashed HEAD (a rewrite of HEAD only resets the index), move it with a two-tree merge,
+  // which refuses to overwrite files changed during the sync
+  git_executor.execute_command(&["read-tree", &stash.head_commit], repository_path)?;
+  git_executor.execute_command_with_status(&["update-index", "-q", "--refresh"], repository_path)?;
+  git_executor
+    .execute_command(&["read-tree", "-m", "-u", &stash.head_commit, &worktree_tree], repository_path)
+    .with_context(|| format!("Failed to restore uncommitted changes, they are kept in {AUTO_STASH_REF}"))?;
+  git_executor.execute_command(&["read-tree", &index_tree], repository_path)?;
+  git_executor.execute_command_with_status(&["update-index", "-q", "--refresh"], repository_path)?;
+
+  if !conflicting_files.is_empty() {
+    warn!(?conflicting_files, "Stashed changes conflict with the new HEAD, kept in {AUTO_STASH_REF}");
+    return Ok(StashRestore::Conflicted { conflicting_files });
+  }
+
+  git_executor.execute_command(&["update-ref", "-d", AUTO_STASH_REF, &stash.stash_commit], repository_path)?;
+  debug!("Restored stashed changes");
+  Ok(StashRestore::Restored)
+}
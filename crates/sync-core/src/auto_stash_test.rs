@@ -0,0 +1,135 @@
+use crate::auto_stash::{AUTO_STASH_REF, StashRestore, restore_stashed_changes, stash_uncommitted_changes};
+use crate::sync::{SyncOptions, sync_branches};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::amend_operations::drop_commits_from_head;
+use pretty_assertions::assert_eq;
+use sync_test_utils::TestReporter;
+use sync_types::SyncEvent;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_stash_and_restore_keeps_staged_changes() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "staged.txt", "base\n");
+  test_repo.create_commit("Add notes", "unstaged.txt", "base\n");
+  test_repo.write_file("staged.txt", "staged\n");
+  test_repo.git(&["add", "staged.txt"]);
+  test_repo.write_file("unstaged.txt", "unstaged\n");
+  test_repo.write_file("untracked.txt", "untracked\n");
+
+  let git_executor = GitCommandExecutor::new();
+  let stash = stash_uncommitted_changes(&git_executor, test_repo.path_str()).unwrap().unwrap();
+  assert_eq!(stash.file_count, 2);
+  assert!(test_repo.rev_parse(AUTO_STASH_REF).is_ok());
+  // Untracked files are left alone
+  assert_eq!(test_repo.git(&["status", "--porcelain"]), "?? untracked.txt");
+  assert_eq!(test_repo.read_file("staged.txt"), "base\n");
+
+  // Only one stash at a time
+  assert!(stash_uncommitted_changes(&git_executor, test_repo.path_str()).is_err());
+
+  assert_eq!(restore_stashed_changes(&git_executor, test_repo.path_str(), &stash).unwrap(), StashRestore::Restored);
+  assert_eq!(test_repo.read_file("staged.txt"), "staged\n");
+  assert_eq!(test_repo.read_file("unstaged.txt"), "unstaged\n");
+  assert_eq!(test_repo.git(&["diff", "--cached", "--name-only"]), "staged.txt");
+  assert_eq!(test_repo.git(&["diff", "--name-only"]), "unstaged.txt");
+  assert!(test_repo.rev_parse(AUTO_STASH_REF).is_err());
+}
+
+#[test]
+fn test_nothing_to_stash() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  // Unborn branch
+  assert_eq!(stash_uncommitted_changes(&git_executor, test_repo.path_str()).unwrap(), None);
+
+  test_repo.create_commit("Initial commit", "file.txt", "content\n");
+  test_repo.write_file("untracked.txt", "untracked\n");
+  assert_eq!(stash_uncommitted_changes(&git_executor, test_repo.path_str()).unwrap(), None);
+  assert!(test_repo.rev_parse(AUTO_STASH_REF).is_err());
+}
+
+#[test]
+fn test_restore_onto_rewritten_head() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "config.txt", "timeout=30\n");
+  let dropped = test_repo.create_commit("(feature) Add flag", "flag.txt", "on\n");
+  test_repo.create_commit("(other) Change timeout", "config.txt", "timeout=10\n");
+  test_repo.write_file("config.txt", "timeout=10\nretries=3\n");
+
+  let git_executor = GitCommandExecutor::new();
+  let stash = stash_uncommitted_changes(&git_executor, test_repo.path_str()).unwrap().unwrap();
+  let current_branch = test_repo.current_branch().unwrap();
+  drop_commits_from_head(&git_executor, test_repo.path_str(), &[dropped], &current_branch).unwrap();
+
+  assert_eq!(restore_stashed_changes(&git_executor, test_repo.path_str(), &stash).unwrap(), StashRestore::Restored);
+  assert_eq!(test_repo.read_file("config.txt"), "timeout=10\nretries=3\n");
+  // The working tree follows the new HEAD instead of showing the dropped commit as a local deletion
+  assert!(!test_repo.path().join("flag.txt").exists());
+  assert_eq!(test_repo.git(&["status", "--porcelain"]), "M config.txt");
+}
+
+#[test]
+fn test_conflicting_changes_are_kept_in_stash_ref() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "config.txt", "timeout=30\n");
+  let dropped = test_repo.create_commit("(feature) Lower timeout", "config.txt", "timeout=10\n");
+  test_repo.write_file("config.txt", "timeout=5\n");
+
+  let git_executor = GitCommandExecutor::new();
+  let stash = stash_uncommitted_changes(&git_executor, test_repo.path_str()).unwrap().unwrap();
+  let current_branch = test_repo.current_branch().unwrap();
+  drop_commits_from_head(&git_executor, test_repo.path_str(), &[dropped], &current_branch).unwrap();
+
+  assert_eq!(
+    restore_stashed_changes(&git_executor, test_repo.path_str(), &stash).unwrap(),
+    StashRestore::Conflicted {
+      conflicting_files: vec!["config.txt".to_string()]
+    }
+  );
+  assert_eq!(test_repo.read_file("config.txt"), "timeout=30\n");
+  assert_eq!(test_repo.git(&["status", "--porcelain"]), "");
+  assert!(test_repo.rev_parse(AUTO_STASH_REF).is_ok());
+  assert_eq!(test_repo.git(&["rev-parse", AUTO_STASH_REF]), stash.stash_commit);
+}
+
+#[test(tokio::test)]
+async fn test_sync_with_auto_stash() {
+  let test_repo = TestRepo::new();
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+  test_repo.git(&["update-ref", "refs/remotes/origin/master", &baseline]);
+  test_repo.create_commit("(feature) Add feature", "feature.txt", "feature\n");
+  test_repo.write_file("feature.txt", "feature\nwork in progress\n");
+
+  let progress = TestReporter::new();
+  sync_branches(
+    &GitCommandExecutor::new(),
+    test_repo.path_str(),
+    "test",
+    progress.clone(),
+    SyncOptions {
+      auto_stash: Some(true),
+      ..Default::default()
+    },
+  )
+  .await
+  .unwrap();
+
+  let events = progress.get_events();
+  let stash_commit = events
+    .iter()
+    .find_map(|event| match event {
+      SyncEvent::UncommittedChangesStashed { stash_commit, file_count: 1 } => Some(stash_commit.clone()),
+      _ => None,
+    })
+    .expect("changes should be stashed");
+  // Only the performance report follows the restore
+  assert!(matches!(
+    events.iter().rev().nth(1),
+    Some(SyncEvent::UncommittedChangesRestored { stash_commit: restored, conflicting_files }) if *restored == stash_commit && conflicting_files.is_empty()
+  ));
+  assert_eq!(test_repo.read_file("feature.txt"), "feature\nwork in progress\n");
+  assert!(test_repo.rev_parse(AUTO_STASH_REF).is_err());
+}
@@ -0,0 +1,90 @@
+//! Detection of the baseline branch advancing on the remote, so the user knows a re-sync would pick up new upstream commits.
+//!
+//! The remote head of the baseline is queried with `ls-remote` and compared against the baseline commit of the last
+//! successful sync, recorded in the sync history. Only the baseline branch is fetched, and only when its new head isn't local yet.
+//! It is fetched into [`BASELINE_FETCH_REF_PREFIX`], the remote-tracking branch only moves when the user fetches or syncs.
+
+use crate::network::ensure_remote_reachable;
+use crate::sync::detect_baseline_branch;
+use crate::sync_performance::load_sync_history;
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use serde::Deserialize;
+use sync_types::BaselineAdvanced;
+use tracing::{debug, instrument};
+
+/// Refs holding the fetched heads of remote baselines, `<prefix>/<remote>/<branch>`
+pub const BASELINE_FETCH_REF_PREFIX: &str = "refs/branch-deck/baseline";
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct CheckBaselineUpdateParams {
+  pub repository_path: String,
+}
+
+/// Commit of the baseline branch, `None` if it can't be resolved (e.g. an empty repository)
+pub fn resolve_baseline_commit(git_executor: &GitCommandExecutor, repository_path: &str) -> Option<String> {
+  let baseline_branch = detect_baseline_branch(git_executor, repository_path, "master").ok()?;
+  git_executor
+    .execute_command(&["rev-parse", "--verify", "--quiet", &format!("{baseline_branch}^{{commit}}")], repository_path)
+    .ok()
+}
+
+/// Baseline commit of the last successful sync that recorded one
+pub fn last_sync_baseline(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<Option<String>> {
+  Ok(
+    load_sync_history(git_executor, repository_path)?
+      .into_iter()
+      .rev()
+      .filter(|entry| entry.success)
+      .find_map(|entry| entry.baseline_commit),
+  )
+}
+
+/// New upstream commits on the remote baseline branch since the last successful sync.
+/// `None` if the baseline is a local branch, the repository was never synced or the remote baseline didn't advance.
+/// Fails with `ErrorCode::Offline` without network.
+#[instrument(skip(git_executor))]
+pub fn check_baseline_update(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<Option<BaselineAdvanced>> {
+  let baseline_branch = detect_baseline_branch(git_executor, repository_path, "master")?;
+  let remotes = git_executor.execute_command_lines(&["remote"], repository_path)?;
+  let Some((remote, branch)) = baseline_branch.split_once('/').filter(|(remote, _)| remotes.iter().any(|name| name == remote)) else {
+    return Ok(None);
+  };
+  let Some(last_sync_baseline) = last_sync_baseline(git_executor, repository_path)? else {
+    return Ok(None);
+  };
+
+  ensure_remote_reachable(git_executor, repository_path, Some(remote))?;
+  // A background check must never wait for credentials
+  let no_prompt = [("GIT_TERMINAL_PROMPT", "0")];
+  let output = git_executor.execute_command_with_env(&["ls-remote", "--quiet", remote, &format!("refs/heads/{branch}")], repository_path, &no_prompt)?;
+  let Some(remote_head) = output.split_whitespace().next().map(str::to_string) else {
+    return Ok(None);
+  };
+  if remote_head == last_sync_baseline {
+    return Ok(None);
+  }
+
+  if !matches!(git_executor.cat_file_info(repository_path, &remote_head), Ok(Some(_))) {
+    let refspec = format!("+refs/heads/{branch}:{BASELINE_FETCH_REF_PREFIX}/{remote}/{branch}");
+    git_executor.execute_command_with_env(&["fetch", "--quiet", "--no-tags", remote, &refspec], repository_path, &no_prompt)?;
+  }
+  let new_commit_count: u32 = git_executor
+    .execute_command(&["rev-list", "--count", &format!("{last_sync_baseline}..{remote_head}")], repository_path)?
+    .parse()
+    .unwrap_or(0);
+  debug!(%remote_head, %last_sync_baseline, new_commit_count, "Checked remote baseline");
+  // The remote baseline was reset to an older commit, there is nothing new to sync
+  if new_commit_count == 0 {
+    return Ok(None);
+  }
+
+  Ok(Some(BaselineAdvanced {
+    baseline_branch,
+    last_sync_baseline,
+    remote_head,
+    new_commit_count,
+  }))
+}
@@ -0,0 +1,56 @@
+use crate::baseline_update::{check_baseline_update, last_sync_baseline};
+use crate::sync::{SyncOptions, sync_branches};
+use pretty_assertions::assert_eq;
+use sync_test_utils::TestReporter;
+use sync_types::BaselineAdvanced;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+#[test(tokio::test)]
+async fn test_baseline_advanced_since_last_sync() -> anyhow::Result<()> {
+  let origin = TestRepo::new();
+  let synced_baseline = origin.create_commit("Initial commit", "README.md", "# Test");
+  let local = TestRepo::new_empty();
+  local.clone_from(origin.path()).unwrap();
+  local.create_commit("(feature) Add feature", "feature.txt", "feature");
+  let repository_path = local.path().to_str().unwrap();
+  let git_executor = local.git_executor();
+
+  // Never synced, nothing to compare against
+  assert_eq!(check_baseline_update(git_executor, repository_path)?, None);
+
+  sync_branches(git_executor, repository_path, "test", TestReporter::new(), SyncOptions::default()).await?;
+  assert_eq!(last_sync_baseline(git_executor, repository_path)?, Some(synced_baseline.clone()));
+  assert_eq!(check_baseline_update(git_executor, repository_path)?, None);
+
+  origin.create_commit("Upstream change", "upstream.txt", "one");
+  let remote_head = origin.create_commit("Another upstream change", "upstream.txt", "two");
+
+  assert_eq!(
+    check_baseline_update(git_executor, repository_path)?,
+    Some(BaselineAdvanced {
+      baseline_branch: "origin/master".to_string(),
+      last_sync_baseline: synced_baseline.clone(),
+      remote_head: remote_head.clone(),
+      new_commit_count: 2,
+    })
+  );
+  // Only the baseline branch was fetched to count the commits, the remote-tracking branch stays where the sync left it
+  assert_eq!(local.rev_parse("refs/branch-deck/baseline/origin/master").unwrap(), remote_head);
+  assert_eq!(local.rev_parse("origin/master").unwrap(), synced_baseline);
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_local_baseline_is_not_checked() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+  let repository_path = test_repo.path().to_str().unwrap();
+
+  sync_branches(test_repo.git_executor(), repository_path, "test", TestReporter::new(), SyncOptions::default()).await?;
+
+  assert!(last_sync_baseline(test_repo.git_executor(), repository_path)?.is_some());
+  assert_eq!(check_baseline_update(test_repo.git_executor(), repository_path)?, None);
+  Ok(())
+}
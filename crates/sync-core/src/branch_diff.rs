@@ -0,0 +1,69 @@
+use crate::sync::detect_baseline_branch;
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::conflict_analysis::{FileChangeStat, FileDiff, get_file_change_stats, get_tree_file_diffs};
+use git_ops::diff_options::load_diff_options;
+use git_ops::model::to_final_branch_name;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct GetBranchDiffParams {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  pub branch_name: String,
+  /// File to compute the hunks of; without it only the file list is returned
+  pub file_path: Option<String>,
+}
+
+/// Cumulative changes of a virtual branch, as the "Files changed" view of its pull request would show them
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct BranchDiff {
+  /// Merge base of the branch and the baseline, the old side of the diff
+  pub base_commit: String,
+  pub head_commit: String,
+  /// Changed files sorted by path
+  pub files: Vec<FileChangeStat>,
+  /// Hunks of the requested file, `None` if no file was requested or it is unchanged
+  pub file_diff: Option<FileDiff>,
+}
+
+/// Diff of the synced virtual branch against its merge base with the baseline
+#[instrument(skip(git_executor, params), fields(repository_path = %params.repository_path, branch_name = %params.branch_name, file_path = ?params.file_path))]
+pub fn get_branch_diff_core(git_executor: &GitCommandExecutor, params: GetBranchDiffParams) -> Result<BranchDiff> {
+  let GetBranchDiffParams {
+    repository_path,
+    branch_prefix,
+    branch_name,
+    file_path,
+  } = params;
+  let repo = repository_path.as_str();
+
+  let full_branch_name = to_final_branch_name(&branch_prefix, &branch_name)?;
+  let head_commit = git_executor
+    .execute_command(&["rev-parse", "--verify", "--quiet", &format!("refs/heads/{full_branch_name}^{{commit}}")], repo)
+    .map_err(|_| anyhow!("Branch '{branch_name}' was not synced yet"))?;
+  let baseline_branch = detect_baseline_branch(git_executor, repo, "master")?;
+  let base_commit = git_executor.execute_command(&["merge-base", &baseline_branch, &head_commit], repo)?;
+
+  let diff_options = load_diff_options(git_executor, repo);
+  let files = get_file_change_stats(git_executor, repo, &base_commit, &head_commit, &diff_options)?;
+  let file_diff = match file_path {
+    Some(path) => get_tree_file_diffs(git_executor, repo, &base_commit, &head_commit, Some(&path), &diff_options)?
+      .into_iter()
+      .next(),
+    None => None,
+  };
+
+  debug!(file_count = files.len(), "Computed branch diff");
+  Ok(BranchDiff {
+    base_commit,
+    head_commit,
+    files,
+    file_diff,
+  })
+}
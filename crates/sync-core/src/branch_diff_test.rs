@@ -0,0 +1,69 @@
+use crate::branch_diff::{GetBranchDiffParams, get_branch_diff_core};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::conflict_analysis::{FileChangeKind, FileChangeStat};
+use pretty_assertions::assert_eq;
+use std::fs;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn diff_params(test_repo: &TestRepo, file_path: Option<&str>) -> GetBranchDiffParams {
+  GetBranchDiffParams {
+    repository_path: test_repo.path_str().to_string(),
+    branch_prefix: "test".to_string(),
+    branch_name: "parser".to_string(),
+    file_path: file_path.map(str::to_string),
+  }
+}
+
+#[test]
+fn test_branch_diff() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  let baseline = test_repo.create_commit("Add notes", "notes.txt", "one\ntwo\n");
+  test_repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+  test_repo.git(&["update-ref", "refs/remotes/origin/master", &baseline]);
+  test_repo.create_commit("(parser) Add parser", "parser.rs", "fn parse() {}\n");
+  test_repo.create_commit("(parser) Update notes", "notes.txt", "one\n2\nthree\n");
+  fs::remove_file(test_repo.path().join("README.md")).unwrap();
+  test_repo.git(&["commit", "-qam", "(parser) Remove readme"]);
+  let head = test_repo.rev_parse("HEAD").unwrap();
+  // The synced virtual branch
+  test_repo.create_branch_at("test/virtual/parser", &head).unwrap();
+
+  let diff = get_branch_diff_core(&GitCommandExecutor::new(), diff_params(&test_repo, None)).unwrap();
+
+  assert_eq!(diff.base_commit, baseline);
+  assert_eq!(diff.head_commit, head);
+  let stat = |path: &str, kind, additions, deletions| FileChangeStat {
+    path: path.to_string(),
+    kind,
+    additions,
+    deletions,
+    binary: false,
+    rename: None,
+  };
+  assert_eq!(
+    diff.files,
+    vec![
+      stat("README.md", FileChangeKind::Deleted, 0, 1),
+      stat("notes.txt", FileChangeKind::Modified, 2, 1),
+      stat("parser.rs", FileChangeKind::Added, 1, 0),
+    ]
+  );
+  assert!(diff.file_diff.is_none());
+
+  let diff = get_branch_diff_core(&GitCommandExecutor::new(), diff_params(&test_repo, Some("notes.txt"))).unwrap();
+  let file_diff = diff.file_diff.unwrap();
+  assert_eq!(file_diff.old_file.content, "one\ntwo\n");
+  assert_eq!(file_diff.new_file.content, "one\n2\nthree\n");
+  assert_eq!(file_diff.hunks.len(), 1);
+  assert!(file_diff.hunks[0].contains("-two\n+2\n+three"));
+}
+
+#[test]
+fn test_branch_diff_of_unsynced_branch() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("(parser) Add parser", "parser.rs", "fn parse() {}\n");
+
+  assert!(get_branch_diff_core(&GitCommandExecutor::new(), diff_params(&test_repo, None)).is_err());
+}
@@ -0,0 +1,304 @@
+//! Graph of the virtual branches for a graph view: the local commits ahead of the baseline, the commits of the virtual
+//! branches, and which local commit was copied to which virtual branch commit.
+//!
+//! Only commits ahead of the baseline are read. The copies come from the sync mapping notes, which are read together with
+//! the local commits, so neither the history of the baseline nor the content of the commits is walked.
+
+use crate::commit_grouper::load_commit_grouper;
+use crate::sync::detect_baseline_branch;
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::commit_list::get_commit_list;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, instrument};
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct GetBranchGraphParams {
+  pub repository_path: String,
+  pub branch_prefix: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum BranchGraphNodeKind {
+  /// Commit of the baseline that local or virtual branch commits are based on, or the head of the baseline
+  Baseline,
+  /// Local commit ahead of the baseline
+  Original,
+  /// Commit of a virtual branch
+  Copied,
+  VirtualBranch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct BranchGraphNode {
+  /// Commit hash, the full ref name for a virtual branch
+  pub id: String,
+  pub kind: BranchGraphNodeKind,
+  /// Commit subject or virtual branch name
+  pub label: String,
+  /// Virtual branch of a copied commit or of the branch node itself, for a local commit the branch it is grouped into.
+  /// A copied commit shared by stacked branches belongs to the one with the fewest commits, the bottom of the stack.
+  pub branch_name: Option<String>,
+  /// Every virtual branch a copied commit is part of, more than one for a commit shared by stacked branches
+  pub branches: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum BranchGraphEdgeKind {
+  /// From a commit to its parent
+  Parent,
+  /// From a local commit to its copy in a virtual branch
+  Mapping,
+  /// From a virtual branch to its head commit
+  Head,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct BranchGraphEdge {
+  pub from: String,
+  pub to: String,
+  pub kind: BranchGraphEdgeKind,
+}
+
+/// Directed acyclic graph of the virtual branches. Every edge connects nodes of the graph.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct BranchGraph {
+  pub baseline_branch: String,
+  /// Head commit of the baseline, also a node
+  pub baseline_head: String,
+  /// Baseline commits first, then the local commits and each virtual branch followed by its commits, oldest first
+  pub nodes: Vec<BranchGraphNode>,
+  pub edges: Vec<BranchGraphEdge>,
+}
+
+/// Commit of a virtual branch as read by the log
+struct CopiedCommit {
+  id: String,
+  parents: Vec<String>,
+  subject: String,
+}
+
+/// Commits of the virtual branches ahead of the baseline, oldest first, and the indices of the commits of each branch
+struct BranchCommits {
+  commits: Vec<CopiedCommit>,
+  members: Vec<Vec<usize>>,
+}
+
+/// Graph of the virtual branches under the prefix and the local commits they were synced from
+#[instrument(skip(git_executor, params), fields(repository_path = %params.repository_path, branch_prefix = %params.branch_prefix))]
+pub fn get_branch_graph_core(git_executor: &GitCommandExecutor, params: GetBranchGraphParams) -> Result<BranchGraph> {
+  let GetBranchGraphParams { repository_path, branch_prefix } = params;
+  let repo = repository_path.as_str();
+  let baseline_branch = detect_baseline_branch(git_executor, repo, "master")?;
+  let baseline_head = git_executor.execute_command(&["rev-parse", "--verify", &format!("{baseline_branch}^{{commit}}")], repo)?;
+
+  let originals = get_commit_list(git_executor, repo, &baseline_branch)?;
+  // Grouped like a sync does, so a local commit skipped by the author filter or out of its path scope gets no branch
+  let mut grouper = load_commit_grouper(git_executor, repo);
+  grouper.add_commits(git_executor, repo, originals.clone());
+  let (grouped_commits, _, _) = grouper.finish();
+  let original_branches: HashMap<String, String> = grouped_commits
+    .into_iter()
+    .flat_map(|(branch_name, commits)| commits.into_iter().map(move |commit| (commit.id, branch_name.clone())))
+    .collect();
+
+  let virtual_refs = format!("refs/heads/{branch_prefix}/virtual/");
+  let mut branches: Vec<(String, String)> = git_executor
+    .execute_command_lines(&["for-each-ref", "--format=%(refname) %(objectname)", &virtual_refs], repo)?
+    .iter()
+    .filter_map(|line| line.split_once(' ').map(|(ref_name, head)| (ref_name.to_string(), head.to_string())))
+    .collect();
+  branches.sort();
+  let branch_commits = read_branch_commits(git_executor, repo, &baseline_branch, &branches)?;
+  let branch_names: Vec<String> = branches
+    .iter()
+    .map(|(ref_name, _)| ref_name.strip_prefix(&virtual_refs).unwrap_or(ref_name).to_string())
+    .collect();
+  // Branches of each commit, the one with the fewest commits first
+  let mut commit_branches: Vec<Vec<usize>> = vec![Vec::new(); branch_commits.commits.len()];
+  let mut branch_order: Vec<usize> = (0..branches.len()).collect();
+  branch_order.sort_by_key(|&branch| branch_commits.members[branch].len());
+  for &branch in &branch_order {
+    for &commit in &branch_commits.members[branch] {
+      commit_branches[commit].push(branch);
+    }
+  }
+
+  let mut nodes = Vec::new();
+  let mut edges = Vec::new();
+  let copied_ids: HashSet<&str> = branch_commits.commits.iter().map(|commit| commit.id.as_str()).collect();
+  for original in &originals {
+    nodes.push(BranchGraphNode {
+      id: original.id.clone(),
+      kind: BranchGraphNodeKind::Original,
+      label: original.subject.clone(),
+      branch_name: original_branches.get(&original.id).cloned(),
+      branches: Vec::new(),
+    });
+    if let Some(parent_id) = &original.parent_id {
+      edges.push(parent_edge(&original.id, parent_id));
+    }
+    // A mapping to a commit no longer on a virtual branch is stale (e.g. the branch was unapplied)
+    if let Some(mapped_id) = &original.mapped_commit_id
+      && copied_ids.contains(mapped_id.as_str())
+    {
+      edges.push(BranchGraphEdge {
+        from: original.id.clone(),
+        to: mapped_id.clone(),
+        kind: BranchGraphEdgeKind::Mapping,
+      });
+    }
+  }
+
+  for (branch, ((ref_name, head), branch_name)) in branches.iter().zip(&branch_names).enumerate() {
+    nodes.push(BranchGraphNode {
+      id: ref_name.clone(),
+      kind: BranchGraphNodeKind::VirtualBranch,
+      label: branch_name.clone(),
+      branch_name: Some(branch_name.clone()),
+      branches: vec![branch_name.clone()],
+    });
+    edges.push(BranchGraphEdge {
+      from: ref_name.clone(),
+      to: head.clone(),
+      kind: BranchGraphEdgeKind::Head,
+    });
+    // A shared commit is listed once, after the branch it belongs to
+    for &index in &branch_commits.members[branch] {
+      if commit_branches[index].first() != Some(&branch) {
+        continue;
+      }
+      let commit = &branch_commits.commits[index];
+      nodes.push(BranchGraphNode {
+        id: commit.id.clone(),
+        kind: BranchGraphNodeKind::Copied,
+        label: commit.subject.clone(),
+        branch_name: Some(branch_name.clone()),
+        branches: commit_branches[index].iter().map(|&branch| branch_names[branch].clone()).collect(),
+      });
+      for parent_id in &commit.parents {
+        edges.push(parent_edge(&commit.id, parent_id));
+      }
+    }
+  }
+
+  // Parents outside the graph are the baseline commits the branches fork from
+  let known_ids: HashSet<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+  let mut baseline_ids: Vec<String> = Vec::new();
+  for edge in &edges {
+    if !known_ids.contains(edge.to.as_str()) && !baseline_ids.contains(&edge.to) {
+      baseline_ids.push(edge.to.clone());
+    }
+  }
+  // On the baseline branch itself its head is the newest local commit
+  if !known_ids.contains(baseline_head.as_str()) && !baseline_ids.contains(&baseline_head) {
+    baseline_ids.push(baseline_head.clone());
+  }
+  let baseline_nodes = read_baseline_nodes(git_executor, repo, &baseline_ids)?;
+
+  debug!(
+    node_count = nodes.len() + baseline_nodes.len(),
+    edge_count = edges.len(),
+    branch_count = branches.len(),
+    "Built branch graph"
+  );
+  Ok(BranchGraph {
+    baseline_branch,
+    baseline_head,
+    nodes: baseline_nodes.into_iter().chain(nodes).collect(),
+    edges,
+  })
+}
+
+fn parent_edge(from: &str, to: &str) -> BranchGraphEdge {
+  BranchGraphEdge {
+    from: from.to_string(),
+    to: to.to_string(),
+    kind: BranchGraphEdgeKind::Parent,
+  }
+}
+
+/// Commits of the virtual branches ahead of the baseline, read with a single log. Stacked branches share commits, so the
+/// commits of each branch are the ones reachable from its head, not the ref the log first reached them from.
+fn read_branch_commits(git_executor: &GitCommandExecutor, repo: &str, baseline_branch: &str, branches: &[(String, String)]) -> Result<BranchCommits> {
+  if branches.is_empty() {
+    return Ok(BranchCommits {
+      commits: Vec::new(),
+      members: Vec::new(),
+    });
+  }
+
+  let mut args = vec!["--no-pager", "log", "--reverse", "--format=%H%x1f%P%x1f%s"];
+  args.extend(branches.iter().map(|(ref_name, _)| ref_name.as_str()));
+  args.extend(["--not", baseline_branch, "--"]);
+  let mut commits = Vec::new();
+  for line in git_executor.execute_command_lines(&args, repo)? {
+    let mut fields = line.splitn(3, '\x1f');
+    let (Some(id), Some(parents), Some(subject)) = (fields.next(), fields.next(), fields.next()) else {
+      continue;
+    };
+    commits.push(CopiedCommit {
+      id: id.to_string(),
+      parents: parents.split_whitespace().map(str::to_string).collect(),
+      subject: subject.to_string(),
+    });
+  }
+
+  let indices: HashMap<&str, usize> = commits.iter().enumerate().map(|(index, commit)| (commit.id.as_str(), index)).collect();
+  let members: Vec<Vec<usize>> = branches
+    .iter()
+    .map(|(_, head)| {
+      let mut reachable = vec![false; commits.len()];
+      let mut pending = vec![head.as_str()];
+      while let Some(id) = pending.pop() {
+        // Commits of the baseline are not listed
+        let Some(&index) = indices.get(id) else {
+          continue;
+        };
+        if !std::mem::replace(&mut reachable[index], true) {
+          pending.extend(commits[index].parents.iter().map(String::as_str));
+        }
+      }
+      // Oldest first, in the order of the log
+      (0..commits.len()).filter(|&index| reachable[index]).collect()
+    })
+    .collect();
+  Ok(BranchCommits { commits, members })
+}
+
+/// Nodes of the given baseline commits with their subjects, in the given order
+fn read_baseline_nodes(git_executor: &GitCommandExecutor, repo: &str, commit_ids: &[String]) -> Result<Vec<BranchGraphNode>> {
+  if commit_ids.is_empty() {
+    return Ok(Vec::new());
+  }
+  let mut args = vec!["--no-pager", "log", "--no-walk=unsorted", "--format=%H%x1f%s"];
+  args.extend(commit_ids.iter().map(String::as_str));
+  args.push("--");
+  Ok(
+    git_executor
+      .execute_command_lines(&args, repo)?
+      .iter()
+      .filter_map(|line| line.split_once('\x1f'))
+      .map(|(id, subject)| BranchGraphNode {
+        id: id.to_string(),
+        kind: BranchGraphNodeKind::Baseline,
+        label: subject.to_string(),
+        branch_name: None,
+        branches: Vec::new(),
+      })
+      .collect(),
+  )
+}
@@ -0,0 +1,138 @@
+use crate::branch_graph::{BranchGraphEdge, BranchGraphEdgeKind, BranchGraphNodeKind, GetBranchGraphParams, get_branch_graph_core};
+use crate::sync::{SyncOptions, sync_branches};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use std::collections::HashSet;
+use sync_test_utils::TestReporter;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn graph_params(repository_path: &str) -> GetBranchGraphParams {
+  GetBranchGraphParams {
+    repository_path: repository_path.to_string(),
+    branch_prefix: "test".to_string(),
+  }
+}
+
+#[test(tokio::test)]
+async fn test_branch_graph() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+  let repository_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+  git_executor.execute_command(&["update-ref", "refs/remotes/origin/master", &baseline], repository_path)?;
+  let feature = test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+  let fix = test_repo.create_commit("(fix) Fix bug", "fix.txt", "fix");
+
+  sync_branches(&git_executor, repository_path, "test", TestReporter::new(), SyncOptions::default()).await?;
+  let graph = get_branch_graph_core(&git_executor, graph_params(repository_path))?;
+
+  assert_eq!(graph.baseline_branch, "origin/master");
+  assert_eq!(graph.baseline_head, baseline);
+  let nodes: Vec<(BranchGraphNodeKind, &str, Option<&str>)> = graph.nodes.iter().map(|node| (node.kind, node.label.as_str(), node.branch_name.as_deref())).collect();
+  assert_eq!(
+    nodes,
+    vec![
+      (BranchGraphNodeKind::Baseline, "Initial commit", None),
+      (BranchGraphNodeKind::Original, "(feature) Add feature", Some("feature")),
+      (BranchGraphNodeKind::Original, "(fix) Fix bug", Some("fix")),
+      (BranchGraphNodeKind::VirtualBranch, "feature", Some("feature")),
+      (BranchGraphNodeKind::Copied, "Add feature", Some("feature")),
+      (BranchGraphNodeKind::VirtualBranch, "fix", Some("fix")),
+      (BranchGraphNodeKind::Copied, "Fix bug", Some("fix")),
+    ]
+  );
+
+  let feature_head = test_repo.rev_parse("test/virtual/feature").unwrap();
+  let fix_head = test_repo.rev_parse("test/virtual/fix").unwrap();
+  let edge = |from: &str, to: &str, kind| BranchGraphEdge {
+    from: from.to_string(),
+    to: to.to_string(),
+    kind,
+  };
+  let edges: HashSet<&BranchGraphEdge> = graph.edges.iter().collect();
+  for expected in [
+    edge(&feature, &baseline, BranchGraphEdgeKind::Parent),
+    edge(&fix, &feature, BranchGraphEdgeKind::Parent),
+    edge(&feature, &feature_head, BranchGraphEdgeKind::Mapping),
+    edge(&fix, &fix_head, BranchGraphEdgeKind::Mapping),
+    edge("refs/heads/test/virtual/feature", &feature_head, BranchGraphEdgeKind::Head),
+    edge(&feature_head, &baseline, BranchGraphEdgeKind::Parent),
+    edge(&fix_head, &baseline, BranchGraphEdgeKind::Parent),
+  ] {
+    assert!(edges.contains(&expected), "missing edge {expected:?}");
+  }
+  assert_eq!(graph.edges.len(), 8);
+
+  let node_ids: HashSet<&str> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+  assert!(graph.edges.iter().all(|edge| node_ids.contains(edge.from.as_str()) && node_ids.contains(edge.to.as_str())));
+  Ok(())
+}
+
+#[test]
+fn test_branch_graph_without_virtual_branches() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  let head = test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+  let repository_path = test_repo.path().to_str().unwrap();
+
+  let graph = get_branch_graph_core(&GitCommandExecutor::new(), graph_params(repository_path)).unwrap();
+
+  // Local repository, the baseline is the current branch and its head is the local commit
+  assert_eq!(graph.baseline_branch, "master");
+  assert_eq!(graph.baseline_head, head);
+  assert_eq!(
+    graph.nodes.iter().map(|node| node.kind).collect::<Vec<_>>(),
+    vec![BranchGraphNodeKind::Baseline, BranchGraphNodeKind::Original]
+  );
+  assert_eq!(graph.edges.len(), 1);
+}
+
+#[test]
+fn test_branch_graph_of_stacked_branches() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  let repository_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+  // The stacked branch is based on the commits of the other one
+  test_repo.checkout_new_branch("work").unwrap();
+  let base = test_repo.create_commit("Add base", "base.txt", "base");
+  let stacked = test_repo.create_commit("Add stacked", "stacked.txt", "stacked");
+  test_repo.checkout("master").unwrap();
+  git_executor
+    .execute_command(&["update-ref", "refs/heads/test/virtual/z-base", &base], repository_path)
+    .unwrap();
+  git_executor
+    .execute_command(&["update-ref", "refs/heads/test/virtual/a-stacked", &stacked], repository_path)
+    .unwrap();
+
+  let graph = get_branch_graph_core(&git_executor, graph_params(repository_path)).unwrap();
+
+  let nodes: Vec<(BranchGraphNodeKind, &str, Option<&str>, Vec<&str>)> = graph
+    .nodes
+    .iter()
+    .map(|node| {
+      (
+        node.kind,
+        node.label.as_str(),
+        node.branch_name.as_deref(),
+        node.branches.iter().map(String::as_str).collect(),
+      )
+    })
+    .collect();
+  // The shared commit is listed once, with the branch it was copied for
+  assert_eq!(
+    nodes,
+    vec![
+      (BranchGraphNodeKind::Baseline, "Initial commit", None, vec![]),
+      (BranchGraphNodeKind::VirtualBranch, "a-stacked", Some("a-stacked"), vec!["a-stacked"]),
+      (BranchGraphNodeKind::Copied, "Add stacked", Some("a-stacked"), vec!["a-stacked"]),
+      (BranchGraphNodeKind::VirtualBranch, "z-base", Some("z-base"), vec!["z-base"]),
+      (BranchGraphNodeKind::Copied, "Add base", Some("z-base"), vec!["z-base", "a-stacked"]),
+    ]
+  );
+  assert_eq!(graph.edges.len(), 4);
+  let node_ids: HashSet<&str> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+  assert!(graph.edges.iter().all(|edge| node_ids.contains(edge.from.as_str()) && node_ids.contains(edge.to.as_str())));
+}
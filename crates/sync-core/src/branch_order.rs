@@ -0,0 +1,108 @@
+use crate::repo_state::{RepoState, load_repo_state, update_repo_state};
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use sync_types::GroupedBranchInfo;
+use tracing::instrument;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct GetBranchOrderParams {
+  pub repository_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SetBranchPinnedParams {
+  pub repository_path: String,
+  pub branch_name: String,
+  pub pinned: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderBranchesParams {
+  pub repository_path: String,
+  /// Branch names in the desired display order
+  pub branch_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct BranchOrder {
+  pub pinned_branches: Vec<String>,
+  pub branch_order: Vec<String>,
+}
+
+impl From<RepoState> for BranchOrder {
+  fn from(state: RepoState) -> Self {
+    Self {
+      pinned_branches: state.pinned_branches,
+      branch_order: state.branch_order,
+    }
+  }
+}
+
+#[instrument(skip(git_executor))]
+pub fn get_branch_order_core(git_executor: &GitCommandExecutor, params: GetBranchOrderParams) -> Result<BranchOrder> {
+  Ok(load_repo_state(git_executor, &params.repository_path)?.into())
+}
+
+/// Pin or unpin a branch. Newly pinned branches are appended after already pinned ones.
+#[instrument(skip(git_executor))]
+pub fn set_branch_pinned_core(git_executor: &GitCommandExecutor, params: SetBranchPinnedParams) -> Result<BranchOrder> {
+  let state = update_repo_state(git_executor, &params.repository_path, |state| {
+    state.pinned_branches.retain(|name| name != &params.branch_name);
+    if params.pinned {
+      state.pinned_branches.push(params.branch_name.clone());
+    }
+  })?;
+  Ok(state.into())
+}
+
+/// Persist a manual display order. Pinned branches keep being shown first, but their relative order follows the new order too.
+#[instrument(skip(git_executor))]
+pub fn reorder_branches_core(git_executor: &GitCommandExecutor, params: ReorderBranchesParams) -> Result<BranchOrder> {
+  let state = update_repo_state(git_executor, &params.repository_path, |state| {
+    let mut branch_order: Vec<String> = Vec::with_capacity(params.branch_names.len());
+    for name in params.branch_names {
+      if !branch_order.contains(&name) {
+        branch_order.push(name);
+      }
+    }
+
+    let position: HashMap<&str, usize> = branch_order.iter().enumerate().map(|(i, name)| (name.as_str(), i)).collect();
+    // Stable sort: pinned branches missing from the new order keep their place at the end
+    state.pinned_branches.sort_by_key(|name| position.get(name.as_str()).copied().unwrap_or(usize::MAX));
+    state.branch_order = branch_order;
+  })?;
+  Ok(state.into())
+}
+
+/// Order branches for display:
+/// pinned branches first (in pin order), then branches without a manual position (keeping the incoming order, newest first),
+/// then manually ordered branches.
+pub fn apply_branch_order(branches: &mut [GroupedBranchInfo], state: &RepoState) {
+  if state.pinned_branches.is_empty() && state.branch_order.is_empty() {
+    return;
+  }
+
+  let pinned: HashMap<&str, usize> = state.pinned_branches.iter().enumerate().map(|(i, name)| (name.as_str(), i)).collect();
+  let ordered: HashMap<&str, usize> = state.branch_order.iter().enumerate().map(|(i, name)| (name.as_str(), i)).collect();
+
+  for branch in branches.iter_mut() {
+    branch.pinned = pinned.contains_key(branch.name.as_str());
+  }
+
+  // sort_by_key is stable, so unordered branches keep the incoming order
+  branches.sort_by_key(|branch| match (pinned.get(branch.name.as_str()), ordered.get(branch.name.as_str())) {
+    (Some(index), _) => (0, *index),
+    (None, None) => (1, 0),
+    (None, Some(index)) => (2, *index),
+  });
+}
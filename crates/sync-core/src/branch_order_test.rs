@@ -0,0 +1,112 @@
+use crate::branch_order::{
+  BranchOrder, GetBranchOrderParams, ReorderBranchesParams, SetBranchPinnedParams, apply_branch_order, get_branch_order_core, reorder_branches_core, set_branch_pinned_core,
+};
+use crate::repo_state::{RepoState, STATE_REF, load_repo_state};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use sync_types::GroupedBranchInfo;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn branch(name: &str) -> GroupedBranchInfo {
+  GroupedBranchInfo {
+    name: name.to_string(),
+    commits: Vec::new(),
+    latest_commit_time: 0,
+    summary: String::new(),
+    all_commits_have_issue_references: false,
+    my_email: None,
+    pinned: false,
+    protected: false,
+    other_authors: Vec::new(),
+    commit_order: Default::default(),
+  }
+}
+
+fn pin(git_executor: &GitCommandExecutor, repo_path: &str, branch_name: &str, pinned: bool) -> BranchOrder {
+  set_branch_pinned_core(
+    git_executor,
+    SetBranchPinnedParams {
+      repository_path: repo_path.to_string(),
+      branch_name: branch_name.to_string(),
+      pinned,
+    },
+  )
+  .unwrap()
+}
+
+#[test]
+fn test_branch_order_defaults_to_empty() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+
+  let order = get_branch_order_core(
+    &GitCommandExecutor::new(),
+    GetBranchOrderParams {
+      repository_path: test_repo.path().to_str().unwrap().to_string(),
+    },
+  )
+  .unwrap();
+  assert_eq!(
+    order,
+    BranchOrder {
+      pinned_branches: vec![],
+      branch_order: vec![],
+    }
+  );
+}
+
+#[test]
+fn test_pin_and_reorder_persisted_in_state_ref() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  let repo_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+
+  pin(&git_executor, repo_path, "auth", true);
+  pin(&git_executor, repo_path, "ui", true);
+  let order = pin(&git_executor, repo_path, "auth", false);
+  assert_eq!(order.pinned_branches, vec!["ui".to_string()]);
+
+  pin(&git_executor, repo_path, "auth", true);
+  let order = reorder_branches_core(
+    &git_executor,
+    ReorderBranchesParams {
+      repository_path: repo_path.to_string(),
+      branch_names: vec!["auth".to_string(), "docs".to_string(), "ui".to_string(), "docs".to_string()],
+    },
+  )
+  .unwrap();
+  assert_eq!(order.branch_order, vec!["auth".to_string(), "docs".to_string(), "ui".to_string()]);
+  // Pinned branches follow the new relative order
+  assert_eq!(order.pinned_branches, vec!["auth".to_string(), "ui".to_string()]);
+
+  // State is stored in the ref and each update is a commit on top of the previous one
+  assert_eq!(
+    load_repo_state(&git_executor, repo_path).unwrap(),
+    RepoState {
+      pinned_branches: order.pinned_branches.clone(),
+      branch_order: order.branch_order.clone(),
+      ..Default::default()
+    }
+  );
+  let history = git_executor.execute_command_lines(&["rev-list", STATE_REF], repo_path).unwrap();
+  assert_eq!(history.len(), 5);
+}
+
+#[test]
+fn test_apply_branch_order() {
+  let state = RepoState {
+    pinned_branches: vec!["docs".to_string()],
+    branch_order: vec!["ui".to_string(), "auth".to_string()],
+    ..Default::default()
+  };
+  // Incoming order is newest first
+  let mut branches = vec![branch("auth"), branch("new-feature"), branch("docs"), branch("ui"), branch("other")];
+  apply_branch_order(&mut branches, &state);
+
+  let names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
+  assert_eq!(names, vec!["docs", "new-feature", "other", "ui", "auth"]);
+  let pinned: Vec<bool> = branches.iter().map(|b| b.pinned).collect();
+  assert_eq!(pinned, vec![true, false, false, false, false]);
+}
@@ -1,7 +1,29 @@
 use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::{CommandError, ErrorCode};
+use serde::{Deserialize, Serialize};
 use tracing::{instrument, warn};
 
-// No complex types needed - just return the prefix string directly!
+/// Where a value of `branchdeck.branchPrefix` comes from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct BranchPrefixSource {
+  /// `system`, `global`, `local`, `worktree` or `command`; a file included with `include` or `includeIf` has the scope of the including file
+  pub scope: String,
+  /// Config file setting the value, or the kind of origin if it's not a file (e.g. `command line:`)
+  pub origin: String,
+  pub value: String,
+}
+
+/// The branch prefix and every value of `branchdeck.branchPrefix` in the order git reads them, the last one wins
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct BranchPrefixResolution {
+  /// Empty if not configured
+  pub prefix: String,
+  pub sources: Vec<BranchPrefixSource>,
+}
 
 /// Get the branch prefix from git config
 /// Reads from "branchdeck.branchPrefix" config key (case-insensitive)
@@ -9,22 +31,30 @@ use tracing::{instrument, warn};
 /// Behaves like git: returns error if directory doesn't exist or isn't accessible
 #[instrument(skip(git_executor))]
 pub fn get_branch_prefix_from_git_config_sync(git_executor: &GitCommandExecutor, repository_path: &str) -> anyhow::Result<String> {
+  resolve_branch_prefix(git_executor, repository_path).map(|resolution| resolution.prefix)
+}
+
+/// Resolve the branch prefix like [`get_branch_prefix_from_git_config_sync`], reporting where each value comes from.
+/// Inside a repository git also reads files included with `includeIf` (e.g. a work identity for `gitdir:~/work/`) and
+/// `config.worktree` of a linked worktree if `extensions.worktreeConfig` is enabled.
+#[instrument(skip(git_executor))]
+pub fn resolve_branch_prefix(git_executor: &GitCommandExecutor, repository_path: &str) -> anyhow::Result<BranchPrefixResolution> {
   // If no repository path provided, use global config directly
   if repository_path.is_empty() {
     return Ok(get_global_branch_prefix(git_executor));
   }
 
-  // Use git's built-in config precedence (local → global → system)
-  match git_executor.execute_command_with_status(&["config", "branchdeck.branchPrefix"], repository_path) {
+  // Use git's built-in config precedence (system → global → local → worktree), the last value wins
+  match git_executor.execute_command_with_status(&["config", "--show-scope", "--show-origin", "-z", "--get-all", "branchdeck.branchPrefix"], repository_path) {
     Ok((output, exit_code)) => match exit_code {
-      0 => Ok(output.trim().to_string()),
+      0 => Ok(parse_branch_prefix_sources(&output)),
       1 => {
-        // Not found in any config (local, global, or system) - return empty string
-        Ok(String::new())
+        // Not found in any config (local, global, or system) - return empty prefix
+        Ok(BranchPrefixResolution::default())
       }
       128 => {
         // Directory not accessible: return error (like git does)
-        Err(anyhow::anyhow!("Repository not accessible: {}", repository_path))
+        Err(CommandError::new(ErrorCode::RepositoryNotAccessible, format!("Repository not accessible: {}", repository_path)).into_anyhow())
       }
       code => {
         // Unexpected exit code: return error
@@ -35,24 +65,52 @@ pub fn get_branch_prefix_from_git_config_sync(git_executor: &GitCommandExecutor,
     Err(e) => {
       // Executor failed (e.g., OS error on cwd): return error (like git does)
       warn!(repository_path, error = %e, "Failed to execute git config");
-      Err(anyhow::anyhow!("Failed to access repository {}: {}", repository_path, e))
+      Err(CommandError::new(ErrorCode::RepositoryNotAccessible, format!("Failed to access repository {}: {}", repository_path, e)).into_anyhow())
     }
   }
 }
 
-fn get_global_branch_prefix(git_executor: &GitCommandExecutor) -> String {
-  match git_executor.execute_command_with_status(&["config", "--global", "branchdeck.branchPrefix"], ".") {
+/// Parse the NUL-separated `scope origin value` triples of `git config --show-scope --show-origin -z --get-all`
+fn parse_branch_prefix_sources(output: &str) -> BranchPrefixResolution {
+  let fields: Vec<&str> = output.split('\0').collect();
+  let sources: Vec<BranchPrefixSource> = fields
+    .chunks_exact(3)
+    .map(|source| BranchPrefixSource {
+      scope: source[0].to_string(),
+      origin: source[1].strip_prefix("file:").unwrap_or(source[1]).to_string(),
+      value: source[2].trim().to_string(),
+    })
+    .collect();
+  BranchPrefixResolution {
+    prefix: sources.last().map(|source| source.value.clone()).unwrap_or_default(),
+    sources,
+  }
+}
+
+fn get_global_branch_prefix(git_executor: &GitCommandExecutor) -> BranchPrefixResolution {
+  // Includes are only followed by default when reading all config files
+  let args = [
+    "config",
+    "--global",
+    "--includes",
+    "--show-scope",
+    "--show-origin",
+    "-z",
+    "--get-all",
+    "branchdeck.branchPrefix",
+  ];
+  match git_executor.execute_command_with_status(&args, ".") {
     Ok((output, exit_code)) => match exit_code {
-      0 => output.trim().to_string(),
-      1 => String::new(), // No global prefix configured
+      0 => parse_branch_prefix_sources(&output),
+      1 => BranchPrefixResolution::default(), // No global prefix configured
       code => {
         warn!(code, "Unexpected git config exit code for global");
-        String::new()
+        BranchPrefixResolution::default()
       }
     },
     Err(e) => {
       warn!(error = %e, "Failed to execute git --global config");
-      String::new()
+      BranchPrefixResolution::default()
     }
   }
 }
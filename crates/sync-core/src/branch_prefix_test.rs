@@ -1,4 +1,4 @@
-use crate::branch_prefix::get_branch_prefix_from_git_config_sync;
+use crate::branch_prefix::{get_branch_prefix_from_git_config_sync, resolve_branch_prefix};
 use git_executor::git_command_executor::GitCommandExecutor;
 use test_utils::git_test_utils::TestRepo;
 use tracing::{debug, warn};
@@ -87,3 +87,48 @@ fn test_get_branch_prefix_invalid_directory() {
   debug!(error_message, "Actual error message");
   assert!(error_message.contains("Failed to access repository"), "Error should mention failed to access repository");
 }
+
+#[test]
+fn test_resolve_branch_prefix_from_conditional_include() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  // A work identity included for repositories under a directory
+  let include_dir = tempfile::tempdir().unwrap();
+  let include_path = include_dir.path().join("work.gitconfig");
+  std::fs::write(&include_path, "[branchdeck]\n\tbranchPrefix = work\n").unwrap();
+  test_repo.set_config("branchdeck.branchPrefix", "personal").unwrap();
+  let condition = format!("includeIf.gitdir:{}/.path", test_repo.path().canonicalize().unwrap().display());
+  test_repo.set_config(&condition, include_path.to_str().unwrap()).unwrap();
+
+  let resolution = resolve_branch_prefix(&git_executor, repo_path).unwrap();
+  assert_eq!(resolution.prefix, "work");
+  let sources: Vec<(&str, &str)> = resolution
+    .sources
+    .iter()
+    .rev()
+    .take(2)
+    .map(|source| (source.scope.as_str(), source.value.as_str()))
+    .collect();
+  assert_eq!(sources, vec![("local", "work"), ("local", "personal")]);
+  assert!(resolution.sources.last().unwrap().origin.ends_with("work.gitconfig"), "{resolution:?}");
+}
+
+#[test]
+fn test_resolve_branch_prefix_from_worktree_config() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  test_repo.set_config("branchdeck.branchPrefix", "shared").unwrap();
+  test_repo.set_config("extensions.worktreeConfig", "true").unwrap();
+  git_executor
+    .execute_command(&["config", "--worktree", "branchdeck.branchPrefix", "main-worktree"], repo_path)
+    .unwrap();
+
+  let resolution = resolve_branch_prefix(&git_executor, repo_path).unwrap();
+  assert_eq!(resolution.prefix, "main-worktree");
+  assert_eq!(resolution.sources.last().unwrap().scope, "worktree");
+  assert_eq!(get_branch_prefix_from_git_config_sync(&git_executor, repo_path).unwrap(), "main-worktree");
+}
@@ -1,22 +1,119 @@
+use crate::conflict_mode::ConflictMode;
+use crate::empty_branch_policy::EmptyBranchPolicy;
+use crate::hooks::BranchHookQueue;
 use crate::remote_status::compute_remote_status_for_branch;
-use anyhow::Result;
+use crate::sync_performance::SyncTimings;
+use anyhow::{Result, anyhow};
+use branch_integration::archive::archive_branch;
 use git_executor::git_command_executor::GitCommandExecutor;
 use git_ops::cache::TreeIdCache;
 use git_ops::commit_list::Commit;
+use git_ops::commit_utils::{CommitSigning, render_trailer_templates};
 use git_ops::copy_commit::{CopyCommitError, CreateCommitParams, create_or_update_commit};
+use git_ops::diff_options::DiffOptions;
+use git_ops::empty_commit_policy::EmptyCommitPolicy;
+use git_ops::git_config::get_config_value;
 use git_ops::model::{BranchError, BranchSyncStatus, CommitSyncStatus, to_final_branch_name};
-use git_ops::notes::{CommitNoteInfo, write_commit_notes};
+use git_ops::notes::{CommitNoteInfo, copy_user_notes, write_commit_notes};
+use git_ops::partial_clone::PartialCloneInfo;
 use git_ops::progress::ProgressCallback;
-use std::collections::HashSet;
+use git_ops::ref_transaction::RefTransaction;
+use std::collections::{HashSet, VecDeque};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use sync_types::branch_integration::{BranchIntegrationInfo, BranchIntegrationStatus, IntegrationConfidence, IntegrationEvidence, IntegrationStrategy};
 use sync_types::{ProgressReporter, SyncEvent};
+use tokio::task::JoinSet;
 use tracing::{debug, error, instrument, warn};
 
+/// Git config key limiting how many branches are synced at once (`1` syncs branches one by one)
+pub const SYNC_CONCURRENCY_CONFIG_KEY: &str = "branchdeck.syncConcurrency";
+
+/// Number of branches synced at once by default
+pub fn default_sync_concurrency() -> NonZeroUsize {
+  std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)
+}
+
+/// Read the branch concurrency from git config, falling back to the number of CPUs for missing or invalid values
+#[instrument(skip(git_executor))]
+pub fn load_sync_concurrency(git_executor: &GitCommandExecutor, repository_path: &str) -> NonZeroUsize {
+  match get_config_value(git_executor, repository_path, SYNC_CONCURRENCY_CONFIG_KEY) {
+    Ok(Some(value)) => value.trim().parse::<NonZeroUsize>().unwrap_or_else(|_| {
+      warn!(value, "Invalid {SYNC_CONCURRENCY_CONFIG_KEY} value, syncing as many branches at once as there are CPUs");
+      default_sync_concurrency()
+    }),
+    Ok(None) => default_sync_concurrency(),
+    Err(e) => {
+      warn!(error = %e, "Failed to read sync concurrency, syncing as many branches at once as there are CPUs");
+      default_sync_concurrency()
+    }
+  }
+}
+
+/// Process branches on at most `max_concurrency` blocking workers.
+/// Branches are independent: commits are rebuilt with `merge-tree` without touching the index or the working tree.
+/// Returns the number of branches that failed.
+#[instrument(skip(branches), fields(branch_count = branches.len()))]
+pub(crate) async fn process_branches<P: ProgressReporter + Clone + Send + 'static>(branches: Vec<BranchProcessingParams<P>>, max_concurrency: NonZeroUsize) -> usize {
+  // Error status has already been sent by process_single_branch
+  run_work_queue(branches, max_concurrency, process_single_branch).await
+}
+
+/// Run `work` for every item on at most `max_concurrency` blocking workers.
+/// Each worker takes the next item from a shared queue once it is done with the previous one, so a long item doesn't
+/// hold up the others. Returns the number of items that failed.
+pub(crate) async fn run_work_queue<T, F>(items: Vec<T>, max_concurrency: NonZeroUsize, work: F) -> usize
+where
+  T: Send + 'static,
+  F: Fn(T) -> Result<()> + Send + Sync + 'static,
+{
+  let worker_count = max_concurrency.get().min(items.len());
+  let queue = Arc::new(Mutex::new(VecDeque::from(items)));
+  let work = Arc::new(work);
+
+  let mut set = JoinSet::new();
+  for _ in 0..worker_count {
+    let queue = queue.clone();
+    let work = work.clone();
+    // Use spawn_blocking since the work is sync and does blocking I/O
+    set.spawn_blocking(move || {
+      let mut failed = 0;
+      loop {
+        // Separate statement so that the lock is released before the item is processed
+        let next = queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+        let Some(item) = next else { break };
+        if let Err(e) = work(item) {
+          error!(error = ?e, "Work queue item failed");
+          failed += 1;
+        }
+      }
+      failed
+    });
+  }
+
+  let mut failed = 0;
+  while let Some(result) = set.join_next().await {
+    match result {
+      Ok(worker_failed) => failed += worker_failed,
+      Err(e) => {
+        error!(error = %e, "JoinSet spawn_blocking error during work queue processing");
+        failed += 1;
+      }
+    }
+  }
+  failed
+}
+
 /// Parameters for processing a single branch
 pub(crate) struct BranchProcessingParams<P: ProgressReporter> {
   pub repository_path: String,
   pub branch_prefix: String,
   pub branch_name: String,
+  /// Full name of the branch to create instead of the virtual branch (e.g., "user/release-1.2/feature-x").
+  /// Its copies are not recorded in the commit notes, those map the original commits to the virtual branch.
+  pub target_branch: Option<String>,
   pub commits: Vec<Commit>,
   pub parent_commit_hash: String,
   pub current_branch_idx: usize,
@@ -27,6 +124,25 @@ pub(crate) struct BranchProcessingParams<P: ProgressReporter> {
   pub git_notes_mutex: Arc<Mutex<()>>,
   pub my_email: Option<String>,
   pub baseline_branch: String,
+  pub partial_clone: Option<Arc<PartialCloneInfo>>,
+  pub signing: Option<Arc<CommitSigning>>,
+  /// Templates of the trailers added to copied commits (`branchdeck.commitTrailer`)
+  pub trailer_templates: Arc<[String]>,
+  /// Receives the branch once its ref was created or updated, `postBranchCreate` runs after the sync
+  pub branch_hooks: Arc<BranchHookQueue>,
+  pub copy_user_notes: bool,
+  pub conflict_mode: ConflictMode,
+  pub empty_branch_policy: EmptyBranchPolicy,
+  pub empty_commit_policy: EmptyCommitPolicy,
+  pub diff_options: DiffOptions,
+  /// `core.ignorecase`, copied commits must not create paths differing only in case
+  pub ignore_case: bool,
+  /// Set once a conflict aborts the whole sync (`ConflictMode::AbortSync`)
+  pub sync_aborted: Arc<AtomicBool>,
+  /// Names of the branches whose commits are all already in the baseline, for auto-archiving
+  pub integrated_branches: Arc<Mutex<HashSet<String>>>,
+  /// Time spent copying commits and computing the remote status is added to the sync's performance report
+  pub timings: SyncTimings,
 }
 
 /// Result of processing a single commit
@@ -36,7 +152,7 @@ enum CommitProcessingResult {
     sync_status: CommitSyncStatus,
     mapping_info: Option<CommitNoteInfo>,
   },
-  BranchError,
+  BranchError(BranchError),
 }
 
 /// Progress adapter that implements ProgressCallback for the ProgressReporter trait
@@ -56,10 +172,13 @@ impl<'a, P: ProgressReporter> ProgressCallback for ProgressReporterAdapter<'a, P
   }
 }
 
-/// Check if a local branch exists (refs/heads/<name>) quickly via show-ref --verify --quiet
-fn branch_exists(git: &GitCommandExecutor, repo: &str, branch_name: &str) -> bool {
-  let branch_ref = format!("refs/heads/{branch_name}");
-  git.execute_command(&["show-ref", "--verify", "--quiet", &branch_ref], repo).is_ok()
+/// Commit of a local branch (refs/heads/<name>), `None` if it doesn't exist
+fn branch_head(git: &GitCommandExecutor, repo: &str, branch_name: &str) -> Option<String> {
+  let branch_ref = format!("refs/heads/{branch_name}^{{commit}}");
+  match git.execute_command_with_status(&["rev-parse", "--verify", "--quiet", &branch_ref], repo) {
+    Ok((output, 0)) => Some(output.trim().to_string()),
+    _ => None,
+  }
 }
 
 #[instrument(
@@ -76,6 +195,7 @@ pub(crate) fn process_single_branch<P: ProgressReporter + Clone>(params: BranchP
     repository_path,
     branch_prefix,
     branch_name,
+    target_branch,
     commits,
     parent_commit_hash,
     current_branch_idx,
@@ -86,12 +206,30 @@ pub(crate) fn process_single_branch<P: ProgressReporter + Clone>(params: BranchP
     git_notes_mutex,
     my_email,
     baseline_branch,
+    partial_clone,
+    signing,
+    trailer_templates,
+    branch_hooks,
+    copy_user_notes: should_copy_user_notes,
+    conflict_mode,
+    empty_branch_policy,
+    empty_commit_policy,
+    diff_options,
+    ignore_case,
+    sync_aborted,
+    integrated_branches,
+    timings,
   } = params;
 
   let task_index = current_branch_idx as i16;
-  let full_branch_name = to_final_branch_name(&branch_prefix, &branch_name)?;
+  let write_notes = target_branch.is_none();
+  let full_branch_name = match target_branch {
+    Some(target_branch) => target_branch,
+    None => to_final_branch_name(&branch_prefix, &branch_name)?,
+  };
 
-  let is_existing_branch = branch_exists(&git_executor, &repository_path, &full_branch_name);
+  let existing_head = branch_head(&git_executor, &repository_path, &full_branch_name);
+  let is_existing_branch = existing_head.is_some();
   debug!(name = %full_branch_name, exists = is_existing_branch, "Checking if branch exists");
 
   // If branch exists, get all its commits in one call for efficient reuse checking
@@ -111,9 +249,12 @@ pub(crate) fn process_single_branch<P: ProgressReporter + Clone>(params: BranchP
     None
   };
 
+  let trailers = render_trailer_templates(&trailer_templates, &branch_name);
+
   let mut current_parent_hash = parent_commit_hash;
   let mut last_commit_hash = String::new();
   let mut is_any_commit_changed = false;
+  let mut already_applied_count = 0;
   let mut pending_notes: Vec<CommitNoteInfo> = Vec::new();
 
   // recreate each commit on top of the last one
@@ -122,7 +263,16 @@ pub(crate) fn process_single_branch<P: ProgressReporter + Clone>(params: BranchP
   // Collect all commit hashes for potential blocking notifications
   let all_commit_hashes: Vec<String> = commits.iter().map(|c| c.id.to_string()).collect();
 
+  // Conflicts collected so far (`ConflictMode::CollectAll`): the first error and the commits that failed
+  let mut first_conflict: Option<(usize, BranchError)> = None;
+  let mut failed_hashes: HashSet<String> = HashSet::new();
+
   for (current_commit_idx, commit) in commits.into_iter().enumerate() {
+    if sync_aborted.load(Ordering::Acquire) {
+      report_sync_aborted(&progress, &branch_name, &all_commit_hashes[current_commit_idx..]);
+      return Ok(());
+    }
+
     // If any commit in the branch's history up to this point has changed, we still need to copy this commit —
     // even if its own content didn't change — so that its parent reference is updated.
     let reuse_if_possible = is_existing_branch && !is_any_commit_changed;
@@ -147,24 +297,40 @@ pub(crate) fn process_single_branch<P: ProgressReporter + Clone>(params: BranchP
       git_executor: &git_executor,
       tree_id_cache: &tree_id_cache,
       existing_virtual_commits: existing_virtual_commits.as_ref(),
+      partial_clone: partial_clone.as_deref(),
+      signing: signing.as_deref(),
+      diff_options: &diff_options,
+      trailers: &trailers,
+      empty_commit_policy,
+      ignore_case,
     };
 
     let original_hash = commit.id.to_string();
 
-    match process_single_commit(commit_params, &branch_name, &original_hash, &all_commit_hashes, progress.clone(), &progress_info)? {
+    // Commits applied after a collected conflict are only checked for conflicts, they never end up in the branch
+    let report_success = first_conflict.is_none();
+    let cherry_pick_started = Instant::now();
+    let commit_result = process_single_commit(commit_params, &branch_name, &original_hash, progress.clone(), report_success);
+    timings.add_cherry_pick(cherry_pick_started.elapsed());
+    match commit_result? {
+      CommitProcessingResult::Success { new_commit_hash, .. } if !report_success => {
+        current_parent_hash = new_commit_hash;
+      }
       CommitProcessingResult::Success {
         new_commit_hash,
         sync_status,
         mapping_info,
       } => {
-        if sync_status == CommitSyncStatus::Created {
-          is_any_commit_changed = true;
+        match sync_status {
+          CommitSyncStatus::Created | CommitSyncStatus::KeptEmpty => is_any_commit_changed = true,
+          CommitSyncStatus::AlreadyApplied | CommitSyncStatus::Integrated => already_applied_count += 1,
+          _ => {}
         }
 
         // Collect mapping info if present for git notes
         if let Some(mapping) = mapping_info {
           // Only write notes for created commits
-          if sync_status == CommitSyncStatus::Created {
+          if matches!(sync_status, CommitSyncStatus::Created | CommitSyncStatus::KeptEmpty) {
             pending_notes.push(mapping);
           }
         }
@@ -172,13 +338,59 @@ pub(crate) fn process_single_branch<P: ProgressReporter + Clone>(params: BranchP
         current_parent_hash = new_commit_hash.clone();
         last_commit_hash = new_commit_hash;
       }
-      CommitProcessingResult::BranchError => {
-        // Error already handled and events sent by process_single_commit
+      CommitProcessingResult::BranchError(branch_error) => {
+        let is_conflict = matches!(branch_error, BranchError::MergeConflict(_));
+        if is_conflict && conflict_mode == ConflictMode::CollectAll {
+          // Skip the conflicted commit and try the next ones on top of the last applied commit
+          failed_hashes.insert(original_hash);
+          first_conflict.get_or_insert((current_commit_idx, branch_error));
+          is_any_commit_changed = true;
+          continue;
+        }
+
+        failed_hashes.insert(original_hash);
+        let (failed_idx, branch_error) = first_conflict.take().unwrap_or((current_commit_idx, branch_error));
+        report_branch_failure(&progress, &branch_name, blocked_commits(&all_commit_hashes, failed_idx, &failed_hashes), branch_error);
+        if is_conflict && conflict_mode == ConflictMode::AbortSync {
+          sync_aborted.store(true, Ordering::Release);
+          return Err(anyhow!("Merge conflict in branch \"{branch_name}\", sync aborted"));
+        }
         return Ok(());
       }
     }
   }
 
+  if let Some((failed_idx, branch_error)) = first_conflict {
+    debug!(name = %branch_name, conflict_count = failed_hashes.len(), "Collected all conflicts of the branch");
+    report_branch_failure(&progress, &branch_name, blocked_commits(&all_commit_hashes, failed_idx, &failed_hashes), branch_error);
+    return Ok(());
+  }
+
+  // Don't move the branch if another branch aborted the sync in the meantime
+  if sync_aborted.load(Ordering::Acquire) {
+    report_sync_aborted(&progress, &branch_name, &[]);
+    return Ok(());
+  }
+
+  // Every commit is already in the baseline, so the branch would have no commits of its own
+  let is_fully_integrated = already_applied_count == total_commits_in_branch;
+  if is_fully_integrated {
+    integrated_branches.lock().unwrap().insert(branch_name.clone());
+  }
+  if is_fully_integrated && empty_branch_policy != EmptyBranchPolicy::Keep {
+    return handle_empty_branch(EmptyBranchParams {
+      git_executor: &git_executor,
+      repository_path: &repository_path,
+      branch_prefix: &branch_prefix,
+      branch_name: &branch_name,
+      full_branch_name: &full_branch_name,
+      is_existing_branch,
+      commit_count: total_commits_in_branch,
+      policy: empty_branch_policy,
+      progress: &progress,
+    });
+  }
+
   let branch_sync_status: BranchSyncStatus;
   if is_existing_branch {
     if is_any_commit_changed {
@@ -195,15 +407,25 @@ pub(crate) fn process_single_branch<P: ProgressReporter + Clone>(params: BranchP
 
   // only update the branch if it's new or changed
   if branch_sync_status != BranchSyncStatus::Unchanged {
-    // Use git CLI to update branch reference
+    // Fails instead of overwriting the branch if it was moved during the sync
     let commit_hash_str = last_commit_hash.to_string();
-    let args = vec!["branch", "-f", &full_branch_name, &commit_hash_str];
-    git_executor.execute_command(&args, &repository_path)?;
+    let branch_ref = format!("refs/heads/{full_branch_name}");
+    let mut transaction = RefTransaction::new();
+    match &existing_head {
+      Some(existing_head) => transaction.update(&branch_ref, &commit_hash_str, Some(existing_head)),
+      None => transaction.create(&branch_ref, &commit_hash_str),
+    };
+    transaction.commit(&git_executor, &repository_path, "branch-deck: sync")?;
+    branch_hooks.push(&branch_name, &full_branch_name, branch_sync_status.clone(), commit_hash_str);
   }
 
   // Write all commit notes after successful branch sync
-  if !pending_notes.is_empty() {
+  if write_notes && !pending_notes.is_empty() {
     debug!(count = pending_notes.len(), name = %branch_name, "Writing commit notes for branch");
+    // Not fatal: the branch itself is synced, only annotations are missing
+    if should_copy_user_notes && let Err(e) = copy_user_notes(&git_executor, &repository_path, &pending_notes, &git_notes_mutex) {
+      warn!(name = %branch_name, error = %e, "Failed to copy user notes to rewritten commits");
+    }
     if let Err(e) = write_commit_notes(&git_executor, &repository_path, pending_notes, &git_notes_mutex) {
       error!(name = %branch_name, error = %e, "Failed to write commit notes");
       // Send error status for git notes failure
@@ -227,7 +449,8 @@ pub(crate) fn process_single_branch<P: ProgressReporter + Clone>(params: BranchP
 
   // Compute and emit remote status for this branch
   let local_ref = full_branch_name.clone(); // e.g., "prefix/virtual/name"
-  if let Ok(remote_status) = compute_remote_status_for_branch(
+  let remote_status_started = Instant::now();
+  let remote_status = compute_remote_status_for_branch(
     &git_executor,
     &repository_path,
     &local_ref,
@@ -235,19 +458,81 @@ pub(crate) fn process_single_branch<P: ProgressReporter + Clone>(params: BranchP
     my_email.as_deref(),
     total_commits_in_branch as u32,
     &baseline_branch,
-  ) {
+  );
+  timings.add_remote_status(remote_status_started.elapsed());
+  if let Ok(remote_status) = remote_status {
     let _ = progress.send(SyncEvent::RemoteStatusUpdate(remote_status));
   }
 
   Ok(())
 }
 
+struct EmptyBranchParams<'a, P: ProgressReporter> {
+  git_executor: &'a GitCommandExecutor,
+  repository_path: &'a str,
+  branch_prefix: &'a str,
+  branch_name: &'a str,
+  full_branch_name: &'a str,
+  is_existing_branch: bool,
+  commit_count: usize,
+  policy: EmptyBranchPolicy,
+  progress: &'a P,
+}
+
+/// Leave the branch of an already integrated group alone, or archive it with `EmptyBranchPolicy::Archive`.
+/// The archived branch is reported like one found by integration detection.
+#[instrument(skip(params), fields(branch_name = %params.branch_name, policy = ?params.policy))]
+fn handle_empty_branch<P: ProgressReporter>(params: EmptyBranchParams<'_, P>) -> Result<()> {
+  let EmptyBranchParams {
+    git_executor,
+    repository_path,
+    branch_prefix,
+    branch_name,
+    full_branch_name,
+    is_existing_branch,
+    commit_count,
+    policy,
+    progress,
+  } = params;
+
+  if policy == EmptyBranchPolicy::Archive && is_existing_branch {
+    let archived_branch = archive_branch(git_executor, repository_path, full_branch_name, branch_prefix)?;
+    let _ = progress.send(SyncEvent::BranchIntegrationDetected {
+      info: BranchIntegrationInfo {
+        name: archived_branch,
+        summary: String::new(),
+        status: BranchIntegrationStatus::Integrated {
+          integrated_at: None,
+          // Only the content of each commit was found in the baseline
+          confidence: IntegrationConfidence::Low,
+          commit_count: commit_count as u32,
+        },
+        lookback_limited: false,
+        detection_truncated: false,
+        evidence: Some(IntegrationEvidence {
+          strategy: IntegrationStrategy::Squash,
+          matched_commits: Vec::new(),
+        }),
+      },
+    });
+  } else {
+    debug!(name = %branch_name, exists = is_existing_branch, "All commits are integrated, skipping branch");
+  }
+
+  let _ = progress.send(SyncEvent::BranchStatusUpdate {
+    branch_name: branch_name.to_string(),
+    status: BranchSyncStatus::Integrated,
+    error: None,
+  });
+  Ok(())
+}
+
 #[instrument(
-  skip(commit_params, all_commit_hashes, progress, progress_info),
+  skip(commit_params, progress),
   fields(
     commit_hash = %original_hash,
-    commit_idx = progress_info.current_commit_idx + 1,
-    total_commits = progress_info.total_commits_in_branch,
+    commit_idx = commit_params.progress_info.current_commit_idx + 1,
+    total_commits = commit_params.progress_info.total_commits_in_branch,
     branch_name = %branch_name,
   )
 )]
@@ -255,21 +540,22 @@ fn process_single_commit<P: ProgressReporter>(
   commit_params: CreateCommitParams<'_>,
   branch_name: &str,
   original_hash: &str,
-  all_commit_hashes: &[String],
   progress: P,
-  progress_info: &git_ops::copy_commit::ProgressInfo<'_>,
+  report_success: bool,
 ) -> Result<CommitProcessingResult> {
   let result = create_or_update_commit(commit_params);
 
   match result {
     Ok((new_commit_hash, sync_status, mapping_info)) => {
       // Send success event with status
-      let _ = progress.send(SyncEvent::CommitSynced {
-        branch_name: branch_name.to_string(),
-        commit_hash: original_hash.to_string(),
-        new_hash: new_commit_hash.clone(),
-        status: sync_status.clone(),
-      });
+      if report_success {
+        let _ = progress.send(SyncEvent::CommitSynced {
+          branch_name: branch_name.to_string(),
+          commit_hash: original_hash.to_string(),
+          new_hash: new_commit_hash.clone(),
+          status: sync_status.clone(),
+        });
+      }
 
       Ok(CommitProcessingResult::Success {
         new_commit_hash,
@@ -285,30 +571,51 @@ fn process_single_commit<P: ProgressReporter>(
         error: branch_error.clone(),
       });
 
-      // Send blocked events for remaining commits
-      let blocked_hashes: Vec<String> = all_commit_hashes.iter().skip(progress_info.current_commit_idx + 1).cloned().collect();
+      Ok(CommitProcessingResult::BranchError(branch_error))
+    }
+    Err(CopyCommitError::Other(e)) => Err(e),
+  }
+}
 
-      if !blocked_hashes.is_empty() {
-        let _ = progress.send(SyncEvent::CommitsBlocked {
-          branch_name: branch_name.to_string(),
-          blocked_commit_hashes: blocked_hashes,
-        });
-      }
+/// Commits that are not synced because of a failure at `failed_idx`, except the ones that already got their own error event
+fn blocked_commits(all_commit_hashes: &[String], failed_idx: usize, failed_hashes: &HashSet<String>) -> Vec<String> {
+  all_commit_hashes
+    .iter()
+    .skip(failed_idx + 1)
+    .filter(|hash| !failed_hashes.contains(*hash))
+    .cloned()
+    .collect()
+}
 
-      // Send branch completed event with appropriate error status
-      let status = match &branch_error {
-        BranchError::MergeConflict(_) => BranchSyncStatus::MergeConflict,
-        BranchError::Generic(_) => BranchSyncStatus::Error,
-      };
+/// Report the remaining commits as blocked and the branch as failed
+fn report_branch_failure<P: ProgressReporter>(progress: &P, branch_name: &str, blocked_hashes: Vec<String>, branch_error: BranchError) {
+  if !blocked_hashes.is_empty() {
+    let _ = progress.send(SyncEvent::CommitsBlocked {
+      branch_name: branch_name.to_string(),
+      blocked_commit_hashes: blocked_hashes,
+    });
+  }
 
-      let _ = progress.send(SyncEvent::BranchStatusUpdate {
-        branch_name: branch_name.to_string(),
-        status,
-        error: Some(branch_error),
-      });
+  // Send branch completed event with appropriate error status
+  let status = match &branch_error {
+    BranchError::MergeConflict(_) => BranchSyncStatus::MergeConflict,
+    BranchError::Generic(_) | BranchError::CaseCollision(_) => BranchSyncStatus::Error,
+  };
 
-      Ok(CommitProcessingResult::BranchError)
-    }
-    Err(CopyCommitError::Other(e)) => Err(e),
-  }
+  let _ = progress.send(SyncEvent::BranchStatusUpdate {
+    branch_name: branch_name.to_string(),
+    status,
+    error: Some(branch_error),
+  });
+}
+
+/// Report a branch left untouched because a conflict in another branch aborted the sync
+fn report_sync_aborted<P: ProgressReporter>(progress: &P, branch_name: &str, remaining_hashes: &[String]) {
+  debug!(name = %branch_name, "Sync aborted by a conflict in another branch, leaving branch untouched");
+  report_branch_failure(
+    progress,
+    branch_name,
+    remaining_hashes.to_vec(),
+    BranchError::Generic("Sync aborted because of a merge conflict in another branch".to_string()),
+  );
 }
@@ -0,0 +1,188 @@
+//! Protected virtual branches: never force-pushed, never deleted, and only pushed while the remote has no commits
+//! that the push would drop.
+//!
+//! Protected branches are listed in `branchdeck.protectedBranches`, separated by commas or whitespace.
+//! A trailing `*` matches every branch starting with the rest, e.g. `release-*`.
+
+use crate::remote_status::compute_remote_status_for_branch;
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::{CommandError, ErrorCode};
+use git_ops::git_config::get_config_value;
+use git_ops::model::{extract_branch_name_from_final, to_final_branch_name};
+use serde::{Deserialize, Serialize};
+use sync_types::RemoteStatusUpdate;
+use tracing::{instrument, warn};
+
+/// Git config key with the names (or `name-*` patterns) of the protected virtual branches
+pub const PROTECTED_BRANCHES_CONFIG_KEY: &str = "branchdeck.protectedBranches";
+
+/// Rule a protected branch would break, reported in the `rule` context of a `branchProtected` error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum ProtectionRule {
+  /// The push would replace commits on the remote
+  NoForcePush,
+  /// The branch (or an archived copy of it) would be deleted
+  NoDelete,
+  /// The remote has commits missing locally
+  CleanRemoteStatus,
+}
+
+impl ProtectionRule {
+  fn as_str(self) -> &'static str {
+    match self {
+      ProtectionRule::NoForcePush => "noForcePush",
+      ProtectionRule::NoDelete => "noDelete",
+      ProtectionRule::CleanRemoteStatus => "cleanRemoteStatus",
+    }
+  }
+}
+
+/// Patterns of the protected branches
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtectedBranches {
+  patterns: Vec<String>,
+}
+
+impl ProtectedBranches {
+  pub fn from_config_value(value: &str) -> Self {
+    let patterns = value
+      .split(|c: char| c == ',' || c.is_whitespace())
+      .filter(|pattern| !pattern.is_empty() && *pattern != "*")
+      .map(str::to_string)
+      .collect();
+    Self { patterns }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.patterns.is_empty()
+  }
+
+  /// Whether the virtual branch with the given simple name is protected
+  pub fn is_protected(&self, branch_name: &str) -> bool {
+    self.patterns.iter().any(|pattern| match pattern.strip_suffix('*') {
+      Some(prefix) => branch_name.starts_with(prefix),
+      None => branch_name == pattern,
+    })
+  }
+
+  /// Whether an archived branch (e.g., "user/archived/2025-08-11/feature" or its collision variant "…/feature-1")
+  /// is an archived copy of a protected branch
+  pub fn is_archived_protected(&self, archived_branch: &str, branch_prefix: &str) -> bool {
+    let Some(name) = archived_branch
+      .strip_prefix(&format!("{}/archived/", branch_prefix.trim_end_matches('/')))
+      .and_then(|rest| rest.split_once('/'))
+      .map(|(_, name)| name)
+    else {
+      return false;
+    };
+    let without_suffix = name
+      .rsplit_once('-')
+      .filter(|(_, suffix)| !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit()))
+      .map(|(base, _)| base);
+    self.is_protected(name) || without_suffix.is_some_and(|base| self.is_protected(base))
+  }
+}
+
+/// Read the protected branches from git config; on error no branch is protected
+#[instrument(skip(git_executor))]
+pub fn load_protected_branches(git_executor: &GitCommandExecutor, repository_path: &str) -> ProtectedBranches {
+  match get_config_value(git_executor, repository_path, PROTECTED_BRANCHES_CONFIG_KEY) {
+    Ok(Some(value)) => ProtectedBranches::from_config_value(&value),
+    Ok(None) => ProtectedBranches::default(),
+    Err(e) => {
+      warn!(error = %e, "Failed to read protected branches, no branch is protected");
+      ProtectedBranches::default()
+    }
+  }
+}
+
+/// Error with the `branchProtected` code and the branch and rule as context
+pub fn protection_error(branch_name: &str, rule: ProtectionRule, message: impl Into<String>) -> anyhow::Error {
+  CommandError::new(ErrorCode::BranchProtected, message)
+    .with_context("branchName", branch_name)
+    .with_context("rule", rule.as_str())
+    .into_anyhow()
+}
+
+/// Fail with a [`ProtectionRule::NoDelete`] error if any of the branches, about to be deleted locally or on the remote, is
+/// protected. Names are full branch names of virtual branches (`user/virtual/feature`) or archived copies of them.
+/// Every operation deleting a branch checks it before anything is deleted.
+#[instrument(skip(git_executor))]
+pub fn check_delete_allowed(git_executor: &GitCommandExecutor, repository_path: &str, branch_prefix: &str, full_branch_names: &[String]) -> Result<()> {
+  let protected = load_protected_branches(git_executor, repository_path);
+  if protected.is_empty() {
+    return Ok(());
+  }
+  for full_branch_name in full_branch_names {
+    if let Some(branch_name) = extract_branch_name_from_final(full_branch_name, branch_prefix)
+      && protected.is_protected(&branch_name)
+    {
+      return Err(protection_error(
+        &branch_name,
+        ProtectionRule::NoDelete,
+        format!("Branch '{branch_name}' is protected and can't be deleted"),
+      ));
+    }
+    if protected.is_archived_protected(full_branch_name, branch_prefix) {
+      return Err(protection_error(
+        full_branch_name,
+        ProtectionRule::NoDelete,
+        format!("Branch '{full_branch_name}' is an archived copy of a protected branch and can't be deleted"),
+      ));
+    }
+  }
+  Ok(())
+}
+
+/// Check that a protected branch can be pushed without force: the remote has no commits missing locally
+/// and the local branch contains the remote head
+pub fn check_protected_push(git_executor: &GitCommandExecutor, repository_path: &str, full_branch_name: &str, remote_status: &RemoteStatusUpdate) -> Result<()> {
+  let branch_name = &remote_status.branch_name;
+  if !remote_status.remote_exists {
+    return Ok(());
+  }
+  if remote_status.commits_behind > 0 {
+    return Err(protection_error(
+      branch_name,
+      ProtectionRule::CleanRemoteStatus,
+      format!(
+        "Branch '{branch_name}' is protected and the remote has {} commit(s) missing locally",
+        remote_status.commits_behind
+      ),
+    ));
+  }
+
+  // Rewritten commits are not counted as behind, but replacing them still needs a force push
+  let (_, exit_code) = git_executor.execute_command_with_status(
+    &[
+      "merge-base",
+      "--is-ancestor",
+      &format!("refs/remotes/origin/{full_branch_name}"),
+      &format!("refs/heads/{full_branch_name}"),
+    ],
+    repository_path,
+  )?;
+  if exit_code != 0 {
+    return Err(protection_error(
+      branch_name,
+      ProtectionRule::NoForcePush,
+      format!("Branch '{branch_name}' is protected and its commits were rewritten, pushing would need a force push"),
+    ));
+  }
+  Ok(())
+}
+
+/// Whether a virtual branch may be force-pushed; for a protected branch the push rules are checked first
+#[instrument(skip(git_executor))]
+pub fn check_push_allowed(git_executor: &GitCommandExecutor, repository_path: &str, branch_prefix: &str, branch_name: &str, baseline_branch: &str) -> Result<bool> {
+  if !load_protected_branches(git_executor, repository_path).is_protected(branch_name) {
+    return Ok(true);
+  }
+  let full_branch_name = to_final_branch_name(branch_prefix, branch_name)?;
+  let remote_status = compute_remote_status_for_branch(git_executor, repository_path, &full_branch_name, branch_name, None, 0, baseline_branch)?;
+  check_protected_push(git_executor, repository_path, &full_branch_name, &remote_status)?;
+  Ok(false)
+}
@@ -0,0 +1,147 @@
+use crate::branch_protection::{PROTECTED_BRANCHES_CONFIG_KEY, ProtectedBranches, check_delete_allowed, check_push_allowed, load_protected_branches};
+use crate::credentials::AskpassEnv;
+use crate::delete_archived_branch::{DeleteArchivedBranchParams, delete_archived_branch_core};
+use crate::unapply_branch::{UnapplyBranchParams, unapply_branch_core};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::{CommandError, ErrorCode};
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// `test/release` with one commit on top of `main`, the remote-tracking ref of the branch is set by each case
+fn setup_release_branch(test_repo: &TestRepo) -> (String, String) {
+  let initial = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.create_branch_at("main", &initial).unwrap();
+  let release = test_repo.create_commit("(release) Bump version", "version.txt", "1.1\n");
+  test_repo.create_branch_at("test/release", &release).unwrap();
+  test_repo.set_config(PROTECTED_BRANCHES_CONFIG_KEY, "release, hotfix-*").unwrap();
+  (initial, release)
+}
+
+fn set_remote_head(test_repo: &TestRepo, commit: &str) {
+  let repo_path = test_repo.path().to_str().unwrap();
+  test_repo
+    .git_executor()
+    .execute_command(&["update-ref", "refs/remotes/origin/test/release", commit], repo_path)
+    .unwrap();
+}
+
+fn rule_of(error: anyhow::Error) -> String {
+  let error = CommandError::from(error);
+  assert_eq!(error.code, ErrorCode::BranchProtected);
+  assert_eq!(error.context.get("branchName").map(String::as_str), Some("release"));
+  error.context["rule"].clone()
+}
+
+#[test]
+fn test_protected_branch_patterns() {
+  let protected = ProtectedBranches::from_config_value("release, hotfix-*\n  *");
+  assert!(!protected.is_empty());
+  assert!(protected.is_protected("release"));
+  assert!(!protected.is_protected("release-notes"));
+  assert!(protected.is_protected("hotfix-login"));
+  assert!(!protected.is_protected("hotfix"));
+
+  assert!(protected.is_archived_protected("user/archived/2025-08-11/release", "user"));
+  assert!(protected.is_archived_protected("user/archived/2025-08-11/release-2", "user"));
+  assert!(protected.is_archived_protected("user/archived/2025-08-11/hotfix-login", "user"));
+  assert!(!protected.is_archived_protected("user/archived/2025-08-11/feature", "user"));
+  assert!(!protected.is_archived_protected("other/archived/2025-08-11/release", "user"));
+
+  // A lone `*` would protect everything, it's ignored
+  assert!(ProtectedBranches::from_config_value(" * ").is_empty());
+}
+
+#[test]
+fn test_load_protected_branches() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  assert!(load_protected_branches(&git_executor, repo_path).is_empty());
+
+  test_repo.set_config(PROTECTED_BRANCHES_CONFIG_KEY, "release,main").unwrap();
+  assert_eq!(load_protected_branches(&git_executor, repo_path), ProtectedBranches::from_config_value("release main"));
+}
+
+#[test]
+fn test_protected_branch_is_pushed_without_force() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let (initial, release) = setup_release_branch(&test_repo);
+
+  // Not on the remote yet
+  assert!(!check_push_allowed(&git_executor, repo_path, "test", "release", "main").unwrap());
+  // Unprotected branches keep being force-pushed
+  assert!(check_push_allowed(&git_executor, repo_path, "test", "feature", "main").unwrap());
+
+  set_remote_head(&test_repo, &initial);
+  assert!(!check_push_allowed(&git_executor, repo_path, "test", "release", "main").unwrap());
+
+  // The remote has a commit missing locally
+  let remote_only = test_repo.create_commit("(release) Update changelog", "CHANGELOG.md", "1.1\n");
+  set_remote_head(&test_repo, &remote_only);
+  let error = check_push_allowed(&git_executor, repo_path, "test", "release", "main").unwrap_err();
+  assert_eq!(rule_of(error), "cleanRemoteStatus");
+
+  // The remote has the same change under another commit, replacing it needs a force push
+  test_repo.reset_hard(&initial).unwrap();
+  let rewritten = test_repo.create_commit("(release) Bump version to 1.1", "version.txt", "1.1\n");
+  assert_ne!(rewritten, release);
+  set_remote_head(&test_repo, &rewritten);
+  let error = check_push_allowed(&git_executor, repo_path, "test", "release", "main").unwrap_err();
+  assert_eq!(rule_of(error), "noForcePush");
+}
+
+#[test]
+fn test_archived_protected_branch_is_not_deleted() {
+  let test_repo = TestRepo::new();
+  let (_, release) = setup_release_branch(&test_repo);
+  let archived = "test/archived/2025-08-11/release-1";
+  test_repo.create_branch_at(archived, &release).unwrap();
+
+  let error = delete_archived_branch_core(
+    &GitCommandExecutor::new(),
+    DeleteArchivedBranchParams {
+      repository_path: test_repo.path().to_str().unwrap().to_string(),
+      branch_name: archived.to_string(),
+      branch_prefix: "test".to_string(),
+      delete_remote: false,
+    },
+    &AskpassEnv::none(),
+  )
+  .unwrap_err();
+
+  let error = CommandError::from(error);
+  assert_eq!(error.code, ErrorCode::BranchProtected);
+  assert_eq!(error.context["rule"], "noDelete");
+  assert!(test_repo.branch_exists(archived));
+}
+
+#[test]
+fn test_protected_branch_is_not_deleted() {
+  let test_repo = TestRepo::new();
+  let (_, release) = setup_release_branch(&test_repo);
+  test_repo.create_branch_at("test/virtual/release", &release).unwrap();
+  test_repo.create_branch_at("test/virtual/feature", &release).unwrap();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+
+  let error = unapply_branch_core(
+    &git_executor,
+    UnapplyBranchParams {
+      repository_path: repo_path.to_string(),
+      branch_name: "test/virtual/release".to_string(),
+      branch_prefix: "test".to_string(),
+      original_commit_ids: vec![release.clone()],
+    },
+    "main",
+  )
+  .unwrap_err();
+  assert_eq!(rule_of(error), "noDelete");
+  assert!(test_repo.branch_exists("test/virtual/release"));
+
+  assert!(check_delete_allowed(&git_executor, repo_path, "test", &["test/virtual/feature".to_string()]).is_ok());
+  let error = check_delete_allowed(&git_executor, repo_path, "test", &["test/virtual/hotfix-1".to_string()]).unwrap_err();
+  assert_eq!(CommandError::from(error).context["rule"], "noDelete");
+}
@@ -0,0 +1,71 @@
+//! CI status of pushed branches from the hosting provider, queried by `hosting_api::ci_status`.
+//!
+//! Statuses are cached by commit: a final status (success, failure, cancelled) is not queried again. The cache of a
+//! repository is shared, so the remote status sent by a sync or a push keeps the status the last refresh queried.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use sync_types::CiStatus;
+
+/// Git config key enabling CI status of pushed branches
+pub const CI_STATUS_CONFIG_KEY: &str = "branchdeck.ciStatus";
+
+/// CI status caches by repository path
+static CI_STATUS_CACHES: LazyLock<Mutex<HashMap<String, Arc<Mutex<CiStatusCache>>>>> = LazyLock::new(Default::default);
+
+/// Cache of the repository, filled by the remote status refresh
+pub fn repository_ci_status_cache(repository_path: &str) -> Arc<Mutex<CiStatusCache>> {
+  let mut caches = CI_STATUS_CACHES.lock().unwrap_or_else(PoisonError::into_inner);
+  caches.entry(repository_path.to_string()).or_default().clone()
+}
+
+/// Cached CI status of a remote branch head, `None` if no refresh queried it yet
+pub fn cached_ci_status(repository_path: &str, commit_hash: &str) -> Option<CiStatus> {
+  let cache = CI_STATUS_CACHES.lock().unwrap_or_else(PoisonError::into_inner).get(repository_path)?.clone();
+  cache.lock().unwrap_or_else(PoisonError::into_inner).get(commit_hash).cloned()
+}
+
+/// CI statuses of commits kept across refreshes, and the end of the current rate limit
+#[derive(Debug, Default)]
+pub struct CiStatusCache {
+  statuses: HashMap<String, CiStatus>,
+  rate_limited_until: Option<Instant>,
+}
+
+impl CiStatusCache {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn get(&self, commit_hash: &str) -> Option<&CiStatus> {
+    self.statuses.get(commit_hash)
+  }
+
+  /// Commits whose status has to be queried: not known yet, or still pending
+  pub fn commits_to_query<'a>(&self, commits: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    commits
+      .into_iter()
+      .filter(|commit| seen.insert(*commit) && !self.statuses.get(*commit).is_some_and(|status| status.state.is_final()))
+      .map(str::to_string)
+      .collect()
+  }
+
+  pub fn insert(&mut self, status: CiStatus) {
+    self.statuses.insert(status.commit_hash.clone(), status);
+  }
+
+  /// Forget the statuses of commits that are no longer a branch head
+  pub fn retain_commits(&mut self, commits: &HashSet<&str>) {
+    self.statuses.retain(|commit, _| commits.contains(commit.as_str()));
+  }
+
+  pub fn is_rate_limited(&self, now: Instant) -> bool {
+    self.rate_limited_until.is_some_and(|until| now < until)
+  }
+
+  pub fn set_rate_limited(&mut self, now: Instant, retry_after: Duration) {
+    self.rate_limited_until = Some(now + retry_after);
+  }
+}
@@ -0,0 +1,46 @@
+use crate::ci_status::{CiStatusCache, cached_ci_status, repository_ci_status_cache};
+use pretty_assertions::assert_eq;
+use std::time::{Duration, Instant};
+use sync_types::{CiState, CiStatus};
+
+#[test]
+fn test_ci_status_cache() {
+  let status = |commit: &str, state| CiStatus {
+    commit_hash: commit.to_string(),
+    state,
+    failed_checks: Vec::new(),
+    url: String::new(),
+  };
+  let mut cache = CiStatusCache::new();
+  cache.insert(status("done", CiState::Success));
+  cache.insert(status("running", CiState::Pending));
+
+  // Final statuses are not queried again, duplicates are queried once
+  assert_eq!(cache.commits_to_query(["done", "running", "new", "new"]), vec!["running".to_string(), "new".to_string()]);
+
+  cache.retain_commits(&["running"].into_iter().collect());
+  assert!(cache.get("done").is_none());
+  assert_eq!(cache.get("running"), Some(&status("running", CiState::Pending)));
+
+  let now = Instant::now();
+  assert!(!cache.is_rate_limited(now));
+  cache.set_rate_limited(now, Duration::from_secs(30));
+  assert!(cache.is_rate_limited(now + Duration::from_secs(29)));
+  assert!(!cache.is_rate_limited(now + Duration::from_secs(30)));
+}
+
+#[test]
+fn test_cached_ci_status_of_repository() {
+  let status = CiStatus {
+    commit_hash: "head".to_string(),
+    state: CiState::Failure,
+    failed_checks: vec!["test".to_string()],
+    url: String::new(),
+  };
+  assert_eq!(cached_ci_status("/repositories/ci-cached", "head"), None);
+
+  repository_ci_status_cache("/repositories/ci-cached").lock().unwrap().insert(status.clone());
+  assert_eq!(cached_ci_status("/repositories/ci-cached", "head"), Some(status));
+  assert_eq!(cached_ci_status("/repositories/ci-cached", "other"), None);
+  assert_eq!(cached_ci_status("/repositories/other", "head"), None);
+}
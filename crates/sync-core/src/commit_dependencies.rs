@@ -0,0 +1,159 @@
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::commit_dependencies::{get_commit_dependencies, set_commit_dependencies};
+use git_ops::commit_list::Commit;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::instrument;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct GetCommitDependenciesParams {
+  pub repository_path: String,
+  pub commit_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SetCommitDependenciesParams {
+  pub repository_path: String,
+  pub commit_id: String,
+  /// Commits that must be synced before this one (an empty list removes all dependencies)
+  pub depends_on: Vec<String>,
+}
+
+#[instrument(skip(git_executor))]
+pub fn get_commit_dependencies_core(git_executor: &GitCommandExecutor, params: GetCommitDependenciesParams) -> Result<Vec<String>> {
+  get_commit_dependencies(git_executor, &params.repository_path, &params.commit_id)
+}
+
+/// Declare that a commit depends on other commits; returns the stored dependencies as full hashes
+#[instrument(skip(git_executor))]
+pub fn set_commit_dependencies_core(git_executor: &GitCommandExecutor, params: SetCommitDependenciesParams) -> Result<Vec<String>> {
+  set_commit_dependencies(git_executor, &params.repository_path, &params.commit_id, &params.depends_on)
+}
+
+/// Why a dependency constraint doesn't hold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyViolationKind {
+  /// The dependency belongs to another virtual branch
+  DifferentBranch(String),
+  /// The dependency is not assigned to any virtual branch
+  Unassigned,
+  /// The dependency comes after the dependent commit in the same branch
+  WrongOrder,
+  /// The dependency is neither ahead of the baseline nor part of it
+  NotInBaseline,
+}
+
+/// Dependency constraint that doesn't hold for a branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyViolation {
+  pub branch_name: String,
+  pub commit_id: String,
+  pub commit_subject: String,
+  pub dependency_id: String,
+  /// Subject of the dependency if it is among the synced commits
+  pub dependency_subject: Option<String>,
+  pub kind: DependencyViolationKind,
+}
+
+impl DependencyViolation {
+  /// Human-readable explanation shown as the branch error
+  pub fn explanation(&self) -> String {
+    let commit = format!("{} \"{}\"", short_hash(&self.commit_id), self.commit_subject);
+    let dependency = match &self.dependency_subject {
+      Some(subject) => format!("{} \"{}\"", short_hash(&self.dependency_id), subject),
+      None => short_hash(&self.dependency_id).to_string(),
+    };
+    match &self.kind {
+      DependencyViolationKind::DifferentBranch(other) => format!(
+        "Commit {commit} depends on {dependency}, which belongs to branch \"{other}\". Move both commits to the same branch \"{}\".",
+        self.branch_name
+      ),
+      DependencyViolationKind::Unassigned => {
+        format!(
+          "Commit {commit} depends on {dependency}, which is not assigned to any branch. Add it to branch \"{}\".",
+          self.branch_name
+        )
+      }
+      DependencyViolationKind::WrongOrder => {
+        format!(
+          "Commit {commit} depends on {dependency}, which comes after it in branch \"{}\". Reorder the commits so the dependency comes first.",
+          self.branch_name
+        )
+      }
+      DependencyViolationKind::NotInBaseline => format!("Commit {commit} depends on {dependency}, which is neither in this branch nor in the baseline."),
+    }
+  }
+}
+
+fn short_hash(hash: &str) -> &str {
+  &hash[..hash.len().min(7)]
+}
+
+/// Check declared dependencies against the grouping: a dependency must be in the same branch before the dependent commit,
+/// or already part of the baseline (`is_in_baseline` is only asked about commits that are not among the synced ones).
+pub fn validate_commit_dependencies<F>(
+  grouped_commits: &IndexMap<String, Vec<Commit>>,
+  unassigned_commits: &[Commit],
+  dependencies: &HashMap<String, Vec<String>>,
+  mut is_in_baseline: F,
+) -> Vec<DependencyViolation>
+where
+  F: FnMut(&str) -> bool,
+{
+  let mut violations = Vec::new();
+  if dependencies.is_empty() {
+    return violations;
+  }
+
+  // commit -> (branch, position in branch, subject)
+  let mut locations: HashMap<&str, (&str, usize, &str)> = HashMap::new();
+  for (branch_name, commits) in grouped_commits {
+    for (index, commit) in commits.iter().enumerate() {
+      locations.insert(commit.id.as_str(), (branch_name.as_str(), index, commit.stripped_subject.as_str()));
+    }
+  }
+
+  for (branch_name, commits) in grouped_commits {
+    for (index, commit) in commits.iter().enumerate() {
+      let Some(commit_dependencies) = dependencies.get(&commit.id) else {
+        continue;
+      };
+
+      for dependency_id in commit_dependencies {
+        let (kind, dependency_subject) = match locations.get(dependency_id.as_str()) {
+          Some((dependency_branch, dependency_index, subject)) => {
+            let kind = if dependency_branch != branch_name {
+              DependencyViolationKind::DifferentBranch(dependency_branch.to_string())
+            } else if *dependency_index > index {
+              DependencyViolationKind::WrongOrder
+            } else {
+              continue;
+            };
+            (kind, Some(subject.to_string()))
+          }
+          None => match unassigned_commits.iter().find(|unassigned| &unassigned.id == dependency_id) {
+            Some(unassigned) => (DependencyViolationKind::Unassigned, Some(unassigned.stripped_subject.clone())),
+            None if is_in_baseline(dependency_id) => continue,
+            None => (DependencyViolationKind::NotInBaseline, None),
+          },
+        };
+
+        violations.push(DependencyViolation {
+          branch_name: branch_name.clone(),
+          commit_id: commit.id.clone(),
+          commit_subject: commit.stripped_subject.clone(),
+          dependency_id: dependency_id.clone(),
+          dependency_subject,
+          kind,
+        });
+      }
+    }
+  }
+  violations
+}
@@ -0,0 +1,143 @@
+use crate::commit_dependencies::{DependencyViolation, DependencyViolationKind, SetCommitDependenciesParams, set_commit_dependencies_core, validate_commit_dependencies};
+use crate::sync::sync_branches_core;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::commit_list::Commit;
+use git_ops::model::{BranchError, BranchSyncStatus};
+use indexmap::IndexMap;
+use pretty_assertions::assert_eq;
+use std::collections::HashMap;
+use sync_test_utils::TestReporter;
+use sync_types::SyncEvent;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn commit(id: &str, subject: &str) -> Commit {
+  Commit {
+    id: id.to_string(),
+    stripped_subject: subject.to_string(),
+    message: subject.to_string(),
+    author_name: String::new(),
+    author_email: String::new(),
+    author_timestamp: 0,
+    committer_timestamp: 0,
+    subject: subject.to_string(),
+    parent_id: None,
+    tree_id: String::new(),
+    note: None,
+    mapped_commit_id: None,
+  }
+}
+
+fn violation(branch_name: &str, commit_id: &str, dependency_id: &str, dependency_subject: Option<&str>, kind: DependencyViolationKind) -> DependencyViolation {
+  DependencyViolation {
+    branch_name: branch_name.to_string(),
+    commit_id: commit_id.to_string(),
+    commit_subject: format!("subject {commit_id}"),
+    dependency_id: dependency_id.to_string(),
+    dependency_subject: dependency_subject.map(str::to_string),
+    kind,
+  }
+}
+
+#[test]
+fn test_validate_commit_dependencies() {
+  let grouped: IndexMap<String, Vec<Commit>> = IndexMap::from([
+    ("auth".to_string(), vec![commit("a1", "subject a1"), commit("a2", "subject a2"), commit("a3", "subject a3")]),
+    ("cache".to_string(), vec![commit("c1", "subject c1")]),
+  ]);
+  let unassigned = vec![commit("u1", "subject u1")];
+  let dependencies = HashMap::from([
+    // satisfied: earlier in the same branch, in the baseline
+    ("a2".to_string(), vec!["a1".to_string(), "base".to_string()]),
+    // violated: later in the same branch, other branch
+    ("a1".to_string(), vec!["a3".to_string(), "c1".to_string()]),
+    // violated: unassigned, unknown
+    ("c1".to_string(), vec!["u1".to_string(), "gone".to_string()]),
+  ]);
+
+  let violations = validate_commit_dependencies(&grouped, &unassigned, &dependencies, |commit_id| commit_id == "base");
+  assert_eq!(
+    violations,
+    vec![
+      violation("auth", "a1", "a3", Some("subject a3"), DependencyViolationKind::WrongOrder),
+      violation("auth", "a1", "c1", Some("subject c1"), DependencyViolationKind::DifferentBranch("cache".to_string())),
+      violation("cache", "c1", "u1", Some("subject u1"), DependencyViolationKind::Unassigned),
+      violation("cache", "c1", "gone", None, DependencyViolationKind::NotInBaseline),
+    ]
+  );
+  assert_eq!(
+    violations[1].explanation(),
+    "Commit a1 \"subject a1\" depends on c1 \"subject c1\", which belongs to branch \"cache\". Move both commits to the same branch \"auth\"."
+  );
+}
+
+#[tokio::test]
+async fn test_sync_skips_branch_with_unsatisfied_dependency() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let initial_id = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_branch_at("origin/main", &initial_id).unwrap();
+
+  let parser_id = test_repo.create_commit("(parser) Add parser", "parser.rs", "parser");
+  let cli_id = test_repo.create_commit("(cli) Use parser in CLI", "cli.rs", "cli");
+  let docs_id = test_repo.create_commit("(cli) Document CLI", "cli.md", "docs");
+
+  set_commit_dependencies_core(
+    &git_executor,
+    SetCommitDependenciesParams {
+      repository_path: repo_path.to_string(),
+      commit_id: cli_id.clone(),
+      depends_on: vec![parser_id],
+    },
+  )?;
+
+  let progress = TestReporter::new();
+  sync_branches_core(&git_executor, repo_path, "test", progress.clone()).await?;
+
+  // The parser branch doesn't depend on anything and is synced
+  assert!(test_repo.branch_exists("test/virtual/parser"));
+  // The cli branch would be missing the parser commit, so it is not synced
+  assert!(!test_repo.branch_exists("test/virtual/cli"));
+
+  let events = progress.get_events();
+  let commit_errors: Vec<&String> = events
+    .iter()
+    .filter_map(|event| match event {
+      SyncEvent::CommitError { branch_name, commit_hash, .. } if branch_name == "cli" => Some(commit_hash),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(commit_errors, vec![&cli_id]);
+
+  let cli_error = events.iter().find_map(|event| match event {
+    SyncEvent::BranchStatusUpdate {
+      branch_name,
+      status: BranchSyncStatus::Error,
+      error: Some(BranchError::Generic(message)),
+    } if branch_name == "cli" => Some(message.clone()),
+    _ => None,
+  });
+  let cli_error = cli_error.expect("cli branch should report a dependency error");
+  assert!(cli_error.contains("which belongs to branch \"parser\""), "unexpected error: {cli_error}");
+
+  // A dependency within the branch in the right order is satisfied
+  let set_dependencies = |commit_id: &str, depends_on: Vec<String>| {
+    set_commit_dependencies_core(
+      &git_executor,
+      SetCommitDependenciesParams {
+        repository_path: repo_path.to_string(),
+        commit_id: commit_id.to_string(),
+        depends_on,
+      },
+    )
+  };
+  set_dependencies(&cli_id, Vec::new())?;
+  set_dependencies(&docs_id, vec![cli_id.clone()])?;
+
+  sync_branches_core(&git_executor, repo_path, "test", TestReporter::new()).await?;
+  assert!(test_repo.branch_exists("test/virtual/cli"));
+
+  Ok(())
+}
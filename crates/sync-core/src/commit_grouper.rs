@@ -1,9 +1,15 @@
+use crate::author_filter::{AuthorFilter, load_author_filter};
+use crate::grouping_diagnostics::{MAX_GROUPING_DIAGNOSTICS, add_path_scope_violation, diagnose_grouping};
+use crate::path_scope::{PathScopeViolation, PathScopes, load_changed_paths, load_path_scopes};
+use crate::prefix_typo::find_prefix_typos;
+use git_executor::git_command_executor::GitCommandExecutor;
 use git_ops::commit_list::Commit;
 use git_ops::model::sanitize_branch_name;
 use indexmap::IndexMap;
 use std::collections::HashMap;
+use sync_types::{GroupingDiagnostic, PrefixTypo};
 use sync_utils::issue_pattern::find_issue_number;
-use tracing::info;
+use tracing::{info, instrument, warn};
 
 /// Branch data combining commits and author frequency tracking
 #[derive(Debug)]
@@ -34,6 +40,10 @@ impl BranchData {
   }
 }
 
+/// Unassigned commits kept with all details (newest ones). Only the newest are shown, so older ones keep just what dependency
+/// validation needs, capping memory on histories with 100k+ commits ahead of the baseline.
+pub const MAX_DETAILED_UNASSIGNED_COMMITS: usize = 5000;
+
 // Type alias for grouped commits result with author emails
 pub type GroupedCommitsResult = (IndexMap<String, Vec<Commit>>, Vec<Commit>, HashMap<String, Option<String>>);
 
@@ -44,6 +54,51 @@ pub struct CommitGrouper {
   unassigned_commits: Vec<Commit>,
   pub oldest_commit: Option<Commit>,
   pub commit_count: usize,
+  /// Commits whose subject could have been grouped differently, at most [`MAX_GROUPING_DIAGNOSTICS`]
+  pub diagnostics: Vec<GroupingDiagnostic>,
+  /// Prefixed commits of other authors are skipped unless accepted by the filter
+  author_filter: Option<AuthorFilter>,
+  /// Prefixed commits left unassigned by the author filter
+  pub skipped_commit_count: usize,
+  /// Paths the commits of a branch may touch, see [`crate::path_scope`]
+  path_scopes: PathScopes,
+  /// Prefixed commits not grouped because they touch no path in the scope of their branch
+  pub out_of_scope_commit_count: usize,
+}
+
+/// `(branch)` prefix of a subject as grouped, after an autosquash marker (`fixup!`, `squash!`, `amend!`)
+fn subject_branch_prefix(subject: &str) -> Option<&str> {
+  let subject = ["fixup!", "squash!", "amend!"]
+    .iter()
+    .find_map(|marker| subject.strip_prefix(marker))
+    .map_or(subject, str::trim_start);
+  let close_paren_pos = subject.strip_prefix('(')?.find(')')? + 1;
+  (close_paren_pos > 1).then(|| &subject[..=close_paren_pos])
+}
+
+/// New message for a commit that keeps it in its branch, e.g. for a suggested message that doesn't know about grouping:
+/// the `(branch)` prefix of the old message replaces the one of the new message, an issue reference grouping the old
+/// message is prepended if the new message lacks it
+pub fn keep_grouping_prefix(old_message: &str, new_message: &str) -> String {
+  let old_subject = old_message.lines().next().unwrap_or_default();
+  if let Some(prefix) = subject_branch_prefix(old_subject) {
+    let text = match subject_branch_prefix(new_message) {
+      Some(new_prefix) if new_message.starts_with(new_prefix) => new_message[new_prefix.len()..].trim_start(),
+      _ => new_message,
+    };
+    return format!("{prefix} {text}");
+  }
+  match find_issue_number(old_subject) {
+    Some(issue_number) if find_issue_number(new_message) != Some(issue_number) => format!("{issue_number} {new_message}"),
+    _ => new_message.to_string(),
+  }
+}
+
+/// Grouper configured from the git config of the repository like the one of a sync (author filter and path scopes),
+/// so every operation sees the same branches. Add commits with [`CommitGrouper::add_commits`] to enforce the path scopes.
+#[instrument(skip(git_executor))]
+pub fn load_commit_grouper(git_executor: &GitCommandExecutor, repository_path: &str) -> CommitGrouper {
+  CommitGrouper::with_author_filter(load_author_filter(git_executor, repository_path)).with_path_scopes(load_path_scopes(git_executor, repository_path))
 }
 
 impl Default for CommitGrouper {
@@ -59,10 +114,66 @@ impl CommitGrouper {
       unassigned_commits: Vec::new(),
       oldest_commit: None,
       commit_count: 0,
+      diagnostics: Vec::new(),
+      author_filter: None,
+      skipped_commit_count: 0,
+      path_scopes: PathScopes::default(),
+      out_of_scope_commit_count: 0,
     }
   }
 
-  pub fn add_commit(&mut self, mut commit: Commit) {
+  /// Grouper that only groups the prefixed commits of authors accepted by the filter, see [`crate::author_filter`]
+  pub fn with_author_filter(author_filter: Option<AuthorFilter>) -> Self {
+    Self { author_filter, ..Self::new() }
+  }
+
+  /// Limit branches to the paths of their scope, enforced for commits added with their changed paths
+  pub fn with_path_scopes(self, path_scopes: PathScopes) -> Self {
+    Self { path_scopes, ..self }
+  }
+
+  pub fn author_filter(&self) -> Option<&AuthorFilter> {
+    self.author_filter.as_ref()
+  }
+
+  /// Whether any branch has a path scope, so the changed paths of commits are needed
+  pub fn has_path_scopes(&self) -> bool {
+    !self.path_scopes.is_empty()
+  }
+
+  /// Whether a commit that would be grouped into a branch is skipped by the author filter
+  fn skip_author(&mut self, commit: &Commit) -> bool {
+    let skip = self.author_filter.as_ref().is_some_and(|filter| !filter.accepts(&commit.author_email));
+    if skip {
+      self.skipped_commit_count += 1;
+    }
+    skip
+  }
+
+  /// Add commits read together, e.g. a page of the commit list. With path scopes the changed paths of all of them are read
+  /// with one git call; not fatal, if that fails the commits are grouped regardless of the path scopes.
+  pub fn add_commits(&mut self, git_executor: &GitCommandExecutor, repository_path: &str, commits: Vec<Commit>) {
+    let mut changed_paths = if self.has_path_scopes() {
+      let commit_ids: Vec<&str> = commits.iter().map(|commit| commit.id.as_str()).collect();
+      load_changed_paths(git_executor, repository_path, &commit_ids).unwrap_or_else(|e| {
+        warn!(error = %e, "Failed to read changed paths, path scopes are not enforced for these commits");
+        HashMap::new()
+      })
+    } else {
+      HashMap::new()
+    };
+    for commit in commits {
+      let commit_changed_paths = changed_paths.remove(&commit.id);
+      self.add_commit_with_changed_paths(commit, commit_changed_paths.as_deref());
+    }
+  }
+
+  pub fn add_commit(&mut self, commit: Commit) {
+    self.add_commit_with_changed_paths(commit, None);
+  }
+
+  /// Add a commit, checking its changed paths against the scope of its branch. Without changed paths the scope is not enforced.
+  pub fn add_commit_with_changed_paths(&mut self, mut commit: Commit, changed_paths: Option<&[String]>) {
     // Track the oldest commit (first one we see)
     if self.oldest_commit.is_none() {
       self.oldest_commit = Some(commit.clone());
@@ -98,8 +209,21 @@ impl CommitGrouper {
         let rest = &subject_for_grouping[close_paren_pos + 1..];
         let message_text = rest.trim_start();
 
+        if self.skip_author(&commit) {
+          self.add_unassigned(commit);
+          return;
+        }
+
+        let violation = self.check_path_scope(&sanitized_prefix, changed_paths);
+        if violation.as_ref().is_some_and(PathScopeViolation::excludes_commit) {
+          self.diagnose(&commit.id, subject, subject_for_grouping, None, violation);
+          self.add_unassigned(commit);
+          return;
+        }
+
         // Set the stripped subject
         commit.stripped_subject = message_text.to_string();
+        self.diagnose(&commit.id, subject, subject_for_grouping, Some(&sanitized_prefix), violation);
 
         // Add commit to unified branch data structure
         self.branch_data.entry(sanitized_prefix).or_insert_with(BranchData::new).add_commit(commit);
@@ -110,8 +234,21 @@ impl CommitGrouper {
     // If no explicit parentheses prefix, look for issue number pattern in the subject line
     // Manual parsing for issue pattern (e.g., JIRA-123, ABC-4567)
     if let Some(issue_number) = find_issue_number(subject_for_grouping) {
+      if self.skip_author(&commit) {
+        self.add_unassigned(commit);
+        return;
+      }
+
+      let violation = self.check_path_scope(issue_number, changed_paths);
+      if violation.as_ref().is_some_and(PathScopeViolation::excludes_commit) {
+        self.diagnose(&commit.id, subject, subject_for_grouping, None, violation);
+        self.add_unassigned(commit);
+        return;
+      }
+
       // For issue-based grouping, we don't strip anything
       // The subject remains as-is
+      self.diagnose(&commit.id, subject, subject_for_grouping, Some(issue_number), violation);
 
       // Add commit to unified branch data structure
       self.branch_data.entry(issue_number.to_owned()).or_insert_with(BranchData::new).add_commit(commit);
@@ -119,7 +256,62 @@ impl CommitGrouper {
     }
 
     // If no prefix found, add to unassigned commits
+    self.diagnose(&commit.id, subject, subject_for_grouping, None, None);
+    self.add_unassigned(commit);
+  }
+
+  fn add_unassigned(&mut self, commit: Commit) {
     self.unassigned_commits.push(commit);
+    if let Some(index) = self.unassigned_commits.len().checked_sub(MAX_DETAILED_UNASSIGNED_COMMITS + 1) {
+      let commit = &mut self.unassigned_commits[index];
+      commit.message = String::new();
+      commit.note = None;
+    }
+  }
+
+  /// Path scope violation of a commit that would be grouped into the branch, counts the commits it keeps out of the branch
+  fn check_path_scope(&mut self, branch_name: &str, changed_paths: Option<&[String]>) -> Option<PathScopeViolation> {
+    let violation = self.path_scopes.check(branch_name, changed_paths?)?;
+    if violation.excludes_commit() {
+      self.out_of_scope_commit_count += 1;
+    }
+    Some(violation)
+  }
+
+  fn diagnose(&mut self, commit_id: &str, subject: &str, subject_for_grouping: &str, branch_name: Option<&str>, violation: Option<PathScopeViolation>) {
+    if self.diagnostics.len() >= MAX_GROUPING_DIAGNOSTICS {
+      return;
+    }
+    let diagnostic = diagnose_grouping(commit_id, subject, subject_for_grouping, branch_name);
+    let diagnostic = match violation {
+      Some(violation) => Some(add_path_scope_violation(diagnostic, commit_id, subject, branch_name, violation)),
+      None => diagnostic,
+    };
+    if let Some(diagnostic) = diagnostic {
+      self.diagnostics.push(diagnostic);
+    }
+  }
+
+  /// Grouped branches whose name is probably a misspelling of another one, see [`crate::prefix_typo`]
+  pub fn prefix_typos(&self) -> Vec<PrefixTypo> {
+    find_prefix_typos(
+      self
+        .branch_data
+        .iter()
+        .map(|(branch_name, branch_data)| (branch_name.as_str(), branch_data.commits.iter().map(|commit| commit.id.clone()).collect())),
+    )
+  }
+
+  /// Branches grouped so far and the newest detailed unassigned commits (newest first), for progress updates during a long commit scan
+  pub fn snapshot(&self) -> GroupedCommitsResult {
+    let mut grouped_commits = IndexMap::with_capacity(self.branch_data.len());
+    let mut branch_emails = HashMap::with_capacity(self.branch_data.len());
+    for (branch_name, branch_data) in &self.branch_data {
+      branch_emails.insert(branch_name.clone(), branch_data.most_frequent_author());
+      grouped_commits.insert(branch_name.clone(), branch_data.commits.clone());
+    }
+    let unassigned_commits = self.unassigned_commits.iter().rev().take(MAX_DETAILED_UNASSIGNED_COMMITS).cloned().collect();
+    (grouped_commits, unassigned_commits, branch_emails)
   }
 
   pub fn finish(self) -> GroupedCommitsResult {
@@ -138,6 +330,8 @@ impl CommitGrouper {
     info!(
       branches = %grouped_commits.len(),
       unassigned = %self.unassigned_commits.len(),
+      skipped = %self.skipped_commit_count,
+      out_of_scope = %self.out_of_scope_commit_count,
       branch_details = ?branch_details,
       "Commit grouping completed"
     );
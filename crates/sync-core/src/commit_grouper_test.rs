@@ -1,5 +1,8 @@
-use crate::commit_grouper::CommitGrouper;
+use crate::author_filter::AuthorFilter;
+use crate::commit_grouper::{CommitGrouper, MAX_DETAILED_UNASSIGNED_COMMITS, keep_grouping_prefix};
+use crate::path_scope::PathScopes;
 use git_ops::commit_list::Commit;
+use sync_types::GroupingAmbiguity;
 use test_log::test;
 
 /// Helper function to create a test commit with minimal required fields
@@ -469,3 +472,120 @@ fn test_autosquash_commits_preserve_original_subject() {
   // The stripped_subject should only contain the message without prefix
   assert_eq!(commits[0].stripped_subject, "Add login");
 }
+
+#[test]
+fn test_only_newest_unassigned_commits_keep_details() {
+  let mut grouper = CommitGrouper::new();
+  let total = MAX_DETAILED_UNASSIGNED_COMMITS + 2;
+  for i in 0..total {
+    grouper.add_commit(create_test_commit(&format!("commit{i}"), &format!("Change {i}")));
+  }
+
+  let (_, snapshot, _) = grouper.snapshot();
+  assert_eq!(snapshot.len(), MAX_DETAILED_UNASSIGNED_COMMITS);
+  assert_eq!(snapshot[0].id, format!("commit{}", total - 1));
+  assert!(snapshot.iter().all(|commit| !commit.message.is_empty()));
+
+  // The oldest ones are still listed for dependency validation, without the message
+  let (_, unassigned, _) = grouper.finish();
+  assert_eq!(unassigned.len(), total);
+  assert_eq!(unassigned[0].stripped_subject, "Change 0");
+  assert!(unassigned[0].message.is_empty());
+  assert!(unassigned[1].message.is_empty());
+  assert_eq!(unassigned[2].message, "Change 2");
+}
+
+#[test]
+fn test_author_filter() {
+  let filter = AuthorFilter::from_config_values(&["me@example.com", "me@home.example, ME@old.example"], "teammate@example.com");
+  let mut grouper = CommitGrouper::with_author_filter(Some(filter.clone()));
+  let commit_by = |id: &str, subject: &str, author_email: &str| Commit {
+    author_email: author_email.to_string(),
+    ..create_test_commit(id, subject)
+  };
+  grouper.add_commit(commit_by("c1", "(parser) Add parser", "me@example.com"));
+  grouper.add_commit(commit_by("c2", "(parser) Handle errors", "Me@Old.Example"));
+  grouper.add_commit(commit_by("c3", "(parser) Add tests", "teammate@example.com"));
+  grouper.add_commit(commit_by("c4", "(lexer) Add lexer", "other@example.com"));
+  grouper.add_commit(commit_by("c5", "ABC-123 Fix crash", "other@example.com"));
+  grouper.add_commit(commit_by("c6", "Merge fixes", "other@example.com"));
+
+  assert_eq!(grouper.skipped_commit_count, 2);
+  let (grouped, unassigned, _) = grouper.finish();
+  assert_eq!(grouped.keys().collect::<Vec<_>>(), vec!["parser"]);
+  let parser_commits = &grouped["parser"];
+  assert_eq!(parser_commits.iter().map(|commit| commit.id.as_str()).collect::<Vec<_>>(), vec!["c1", "c2", "c3"]);
+  // Skipped commits are still ahead of the baseline, so they are listed as unassigned like unprefixed ones
+  assert_eq!(unassigned.iter().map(|commit| commit.id.as_str()).collect::<Vec<_>>(), vec!["c4", "c5", "c6"]);
+
+  assert_eq!(
+    filter.other_authors(parser_commits.iter().map(|commit| commit.author_email.as_str())),
+    vec!["teammate@example.com".to_string()]
+  );
+}
+
+#[test]
+fn test_path_scopes() {
+  let scopes = PathScopes::from_config_values(&["frontend-* web/"]);
+  let mut grouper = CommitGrouper::new().with_path_scopes(scopes);
+  assert!(grouper.has_path_scopes());
+  let changed_paths = |paths: &[&str]| paths.iter().map(|path| path.to_string()).collect::<Vec<_>>();
+  grouper.add_commit_with_changed_paths(create_test_commit("c1", "(frontend-login) Add form"), Some(changed_paths(&["web/login.vue"]).as_slice()));
+  grouper.add_commit_with_changed_paths(
+    create_test_commit("c2", "(frontend-login) Validate input"),
+    Some(changed_paths(&["web/login.vue", "server/login.rs"]).as_slice()),
+  );
+  grouper.add_commit_with_changed_paths(
+    create_test_commit("c3", "(frontend-login) Fix server"),
+    Some(changed_paths(&["server/login.rs"]).as_slice()),
+  );
+  // Branches without a scope and commits without known changed paths are grouped as usual
+  grouper.add_commit_with_changed_paths(create_test_commit("c4", "(backend) Fix server"), Some(changed_paths(&["server/login.rs"]).as_slice()));
+  grouper.add_commit(create_test_commit("c5", "(frontend-login) Update docs"));
+
+  assert_eq!(grouper.out_of_scope_commit_count, 1);
+  let diagnostics: Vec<(&str, Option<&str>, &[GroupingAmbiguity])> = grouper
+    .diagnostics
+    .iter()
+    .map(|diagnostic| (diagnostic.commit_id.as_str(), diagnostic.branch_name.as_deref(), diagnostic.ambiguities.as_slice()))
+    .collect();
+  assert_eq!(
+    diagnostics,
+    vec![
+      ("c2", Some("frontend-login"), [GroupingAmbiguity::PartlyOutsidePathScope].as_slice()),
+      ("c3", None, [GroupingAmbiguity::OutsidePathScope].as_slice()),
+    ]
+  );
+
+  let (grouped, unassigned, _) = grouper.finish();
+  let ids = |commits: &[Commit]| commits.iter().map(|commit| commit.id.clone()).collect::<Vec<_>>();
+  assert_eq!(ids(&grouped["frontend-login"]), vec!["c1", "c2", "c5"]);
+  assert_eq!(ids(&grouped["backend"]), vec!["c4"]);
+  assert_eq!(ids(&unassigned), vec!["c3"]);
+  assert_eq!(unassigned[0].stripped_subject, "(frontend-login) Fix server");
+}
+
+#[test]
+fn test_keep_grouping_prefix() {
+  assert_eq!(
+    keep_grouping_prefix("(parser) Add parser\n\nDetails", "Add a recursive descent parser"),
+    "(parser) Add a recursive descent parser"
+  );
+  assert_eq!(keep_grouping_prefix("fixup! (parser) Fix", "Handle empty input"), "(parser) Handle empty input");
+  // A prefix the suggestion came up with is replaced, the commit stays in its branch
+  assert_eq!(keep_grouping_prefix("(parser) Add parser", "(lexer) Add parser"), "(parser) Add parser");
+  assert_eq!(keep_grouping_prefix("ABC-123 Fix crash", "Fix crash on empty input"), "ABC-123 Fix crash on empty input");
+  assert_eq!(keep_grouping_prefix("Fix crash for ABC-123", "Fix ABC-123 crash"), "Fix ABC-123 crash");
+  assert_eq!(keep_grouping_prefix("Update readme", "(docs) Update readme"), "(docs) Update readme");
+
+  // Grouped into the same branch as before
+  for (old_message, new_message) in [("(parser) Add parser", "Add a parser"), ("ABC-123 Fix crash", "Fix crash")] {
+    let mut grouper = CommitGrouper::new();
+    grouper.add_commit(create_test_commit("old", old_message));
+    grouper.add_commit(create_test_commit("new", &keep_grouping_prefix(old_message, new_message)));
+    let (grouped, unassigned, _) = grouper.finish();
+    assert_eq!(grouped.len(), 1, "{new_message}");
+    assert_eq!(grouped[0].len(), 2, "{new_message}");
+    assert!(unassigned.is_empty(), "{new_message}");
+  }
+}
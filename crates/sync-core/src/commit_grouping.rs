@@ -0,0 +1,76 @@
+use crate::commit_grouper::load_commit_grouper;
+use crate::sync::detect_baseline_branch;
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::commit_list::{CommitFilter, DEFAULT_COMMIT_PAGE_SIZE, get_paged_commit_list_with_handler};
+use std::collections::HashMap;
+use sync_types::commit_grouping::{CommitGroupingInput, UngroupedCommit};
+use tracing::{info, instrument};
+
+/// More commits make the prompt too long for the local model, the rest stays unassigned
+pub const MAX_GROUPING_COMMITS: usize = 50;
+
+/// Files listed per commit in the prompt
+const MAX_FILES_PER_COMMIT: usize = 10;
+
+/// Collect the unassigned commits between the baseline and HEAD together with the existing branch names
+#[instrument(skip(git_executor))]
+pub fn collect_commit_grouping_input(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<CommitGroupingInput> {
+  let baseline_branch = detect_baseline_branch(git_executor, repository_path, "master")?;
+
+  let mut grouper = load_commit_grouper(git_executor, repository_path);
+  get_paged_commit_list_with_handler(
+    git_executor,
+    repository_path,
+    &baseline_branch,
+    &CommitFilter::default(),
+    DEFAULT_COMMIT_PAGE_SIZE,
+    |page| {
+      grouper.add_commits(git_executor, repository_path, page);
+      Ok(())
+    },
+  )?;
+  let (grouped_commits, unassigned_commits, _) = grouper.finish();
+
+  if unassigned_commits.len() > MAX_GROUPING_COMMITS {
+    info!(
+      unassigned = unassigned_commits.len(),
+      "Too many unassigned commits, only the oldest {MAX_GROUPING_COMMITS} are grouped"
+    );
+  }
+  let commits: Vec<_> = unassigned_commits.into_iter().take(MAX_GROUPING_COMMITS).collect();
+  let commit_ids: Vec<&str> = commits.iter().map(|c| c.id.as_str()).collect();
+  let mut files_by_commit = get_changed_files(git_executor, repository_path, &commit_ids)?;
+
+  Ok(CommitGroupingInput {
+    commits: commits
+      .into_iter()
+      .map(|commit| UngroupedCommit {
+        files: files_by_commit.remove(&commit.id).unwrap_or_default(),
+        id: commit.id,
+        subject: commit.subject,
+      })
+      .collect(),
+    existing_branches: grouped_commits.into_keys().collect(),
+  })
+}
+
+/// Changed files of each commit, in a single git call
+fn get_changed_files(git_executor: &GitCommandExecutor, repository_path: &str, commit_ids: &[&str]) -> Result<HashMap<String, Vec<String>>> {
+  if commit_ids.is_empty() {
+    return Ok(HashMap::new());
+  }
+
+  let mut args = vec!["--no-pager", "show", "--no-renames", "--name-only", "--format=%x1e%H"];
+  args.extend(commit_ids);
+
+  let output = git_executor.execute_command(&args, repository_path)?;
+  let mut result = HashMap::new();
+  for record in output.split('\x1e').filter(|record| !record.trim().is_empty()) {
+    let mut lines = record.lines().map(str::trim).filter(|line| !line.is_empty());
+    if let Some(commit_id) = lines.next() {
+      result.insert(commit_id.to_string(), lines.take(MAX_FILES_PER_COMMIT).map(str::to_string).collect());
+    }
+  }
+  Ok(result)
+}
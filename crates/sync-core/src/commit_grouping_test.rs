@@ -0,0 +1,42 @@
+use crate::commit_grouping::collect_commit_grouping_input;
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use sync_types::commit_grouping::{CommitGroupingInput, UngroupedCommit};
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_collect_commit_grouping_input() {
+  let test_repo = TestRepo::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+  test_repo
+    .git_executor()
+    .execute_command(&["update-ref", "refs/remotes/origin/master", &baseline], repo_path)
+    .unwrap();
+  let parser_commit = test_repo.create_commit("Add parser", "src/parser.rs", "fn parse() {}\n");
+  test_repo.create_commit("(lexer) Add lexer", "src/lexer.rs", "fn lex() {}\n");
+  let docs_commit = test_repo.create_commit_with_files("Document parser", &[("README.md", "# Parser\n"), ("docs/parser.md", "Parser\n")]);
+
+  let input = collect_commit_grouping_input(&GitCommandExecutor::new(), repo_path).unwrap();
+
+  assert_eq!(
+    input,
+    CommitGroupingInput {
+      commits: vec![
+        UngroupedCommit {
+          id: parser_commit,
+          subject: "Add parser".to_string(),
+          files: vec!["src/parser.rs".to_string()],
+        },
+        UngroupedCommit {
+          id: docs_commit,
+          subject: "Document parser".to_string(),
+          files: vec!["README.md".to_string(), "docs/parser.md".to_string()],
+        },
+      ],
+      existing_branches: vec!["lexer".to_string()],
+    }
+  );
+}
@@ -0,0 +1,234 @@
+//! Translation between original commits and their copies on virtual branches.
+//!
+//! A sync records the copy of each original commit in its mapping note (`v-commit-v1:<copied commit>`). A later sync
+//! overwrites the note, so the mappings of prior syncs are read from the history of the notes ref.
+//!
+//! Notes are keyed by the original commit, so the history of a single note is read for a forward lookup. A reverse
+//! lookup needs the whole history, which is indexed by copied commit once per notes ref head, i.e. once per sync.
+
+use crate::verify_metadata::MAPPING_NOTES_REF;
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::notes::PREFIX;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use tracing::{debug, instrument};
+
+/// Reverse mapping index of each repository, by repository path
+static REVERSE_MAPPING_INDEXES: LazyLock<Mutex<HashMap<String, Arc<ReverseMappingIndex>>>> = LazyLock::new(Default::default);
+
+/// Mapping history of the whole notes ref by copied commit, oldest first
+struct ReverseMappingIndex {
+  /// Notes commit the index was built from, a sync writing notes moves the ref and the index is built again
+  notes_head: String,
+  entries_by_copied: HashMap<String, Vec<CommitMappingEntry>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct GetCommitMappingParams {
+  pub repository_path: String,
+  /// Original commit for `get_commit_mapping`, copied commit for `get_reverse_mapping`; any revision git understands
+  pub commit_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct CommitMappingEntry {
+  pub original_commit: String,
+  pub copied_commit: String,
+  /// Unix timestamp of the sync that first recorded the mapping
+  pub recorded_at: u64,
+  /// Mapping of the latest sync, the others are from prior syncs
+  pub current: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct CommitMapping {
+  /// Full hash of the requested commit
+  pub commit_id: String,
+  /// Newest first, empty if the commit was never synced
+  pub entries: Vec<CommitMappingEntry>,
+}
+
+/// Copies of an original commit made by the current and prior syncs
+#[instrument(skip(git_executor, params), fields(repository_path = %params.repository_path, commit_id = %params.commit_id))]
+pub fn get_commit_mapping_core(git_executor: &GitCommandExecutor, params: GetCommitMappingParams) -> Result<CommitMapping> {
+  let repo = params.repository_path.as_str();
+  let commit_id = resolve_commit(git_executor, repo, &params.commit_id)?;
+  let Some(notes_head) = resolve_notes_head(git_executor, repo) else {
+    return Ok(CommitMapping { commit_id, entries: Vec::new() });
+  };
+  // Notes are stored under the annotated commit hash, split into directories once the notes tree grows (fanout)
+  let pathspecs = [
+    commit_id.clone(),
+    format!("{}/{}", &commit_id[..2], &commit_id[2..]),
+    format!("{}/{}/{}", &commit_id[..2], &commit_id[2..4], &commit_id[4..]),
+  ];
+  let mut entries = read_mapping_history(git_executor, repo, &notes_head, &pathspecs)?;
+  entries.retain(|entry| entry.original_commit == commit_id);
+  let entries = newest_first(git_executor, repo, entries)?;
+  Ok(CommitMapping { commit_id, entries })
+}
+
+/// Original commits a copied commit was made from, by the current or a prior sync
+#[instrument(skip(git_executor, params), fields(repository_path = %params.repository_path, commit_id = %params.commit_id))]
+pub fn get_reverse_mapping_core(git_executor: &GitCommandExecutor, params: GetCommitMappingParams) -> Result<CommitMapping> {
+  let repo = params.repository_path.as_str();
+  let commit_id = resolve_commit(git_executor, repo, &params.commit_id)?;
+  let Some(notes_head) = resolve_notes_head(git_executor, repo) else {
+    return Ok(CommitMapping { commit_id, entries: Vec::new() });
+  };
+  let index = reverse_mapping_index(git_executor, repo, &notes_head)?;
+  let entries = index.entries_by_copied.get(&commit_id).cloned().unwrap_or_default();
+  let entries = newest_first(git_executor, repo, entries)?;
+  Ok(CommitMapping { commit_id, entries })
+}
+
+/// Index of the repository at the given notes head, built if the notes ref moved since the last lookup
+fn reverse_mapping_index(git_executor: &GitCommandExecutor, repo: &str, notes_head: &str) -> Result<Arc<ReverseMappingIndex>> {
+  if let Some(index) = REVERSE_MAPPING_INDEXES.lock().unwrap_or_else(PoisonError::into_inner).get(repo)
+    && index.notes_head == notes_head
+  {
+    return Ok(index.clone());
+  }
+
+  let mut entries_by_copied: HashMap<String, Vec<CommitMappingEntry>> = HashMap::new();
+  for entry in read_mapping_history(git_executor, repo, notes_head, &[])? {
+    entries_by_copied.entry(entry.copied_commit.clone()).or_default().push(entry);
+  }
+  debug!(copied_count = entries_by_copied.len(), "Built reverse mapping index");
+  let index = Arc::new(ReverseMappingIndex {
+    notes_head: notes_head.to_string(),
+    entries_by_copied,
+  });
+  REVERSE_MAPPING_INDEXES
+    .lock()
+    .unwrap_or_else(PoisonError::into_inner)
+    .insert(repo.to_string(), index.clone());
+  Ok(index)
+}
+
+fn resolve_commit(git_executor: &GitCommandExecutor, repo: &str, commit: &str) -> Result<String> {
+  git_executor
+    .execute_command(&["rev-parse", "--verify", "--quiet", &format!("{commit}^{{commit}}")], repo)
+    .map_err(|_| anyhow!("Commit '{commit}' not found"))
+}
+
+/// Commit of the mapping notes ref, `None` if nothing was synced yet
+fn resolve_notes_head(git_executor: &GitCommandExecutor, repo: &str) -> Option<String> {
+  git_executor.execute_command(&["rev-parse", "--verify", "--quiet", MAPPING_NOTES_REF], repo).ok()
+}
+
+/// Mappings across the history of the notes ref up to `notes_head`, oldest first, limited to notes at `pathspecs` if not
+/// empty. A note blob is read once even if several notes commits contain it.
+fn read_mapping_history(git_executor: &GitCommandExecutor, repo: &str, notes_head: &str, pathspecs: &[String]) -> Result<Vec<CommitMappingEntry>> {
+  // Each notes commit is followed by the notes it added or changed, `:<old mode> <new mode> <old blob> <new blob> <status>\t<path>`
+  let mut args = vec![
+    "--no-pager",
+    "log",
+    "--reverse",
+    "--root",
+    "--raw",
+    "--no-abbrev",
+    "--no-renames",
+    "--format=%x1e%ct",
+    notes_head,
+  ];
+  if !pathspecs.is_empty() {
+    args.push("--");
+    args.extend(pathspecs.iter().map(String::as_str));
+  }
+
+  let mut blob_mappings: HashMap<String, Option<String>> = HashMap::new();
+  let mut entries: Vec<CommitMappingEntry> = Vec::new();
+  let mut seen: HashSet<(String, String)> = HashSet::new();
+  let mut recorded_at = 0;
+  for line in git_executor.execute_command_lines(&args, repo)? {
+    if let Some(timestamp) = line.strip_prefix('\x1e') {
+      recorded_at = timestamp.trim().parse().unwrap_or_default();
+      continue;
+    }
+    let Some((meta, path)) = line.strip_prefix(':').and_then(|raw| raw.split_once('\t')) else {
+      continue;
+    };
+    let fields: Vec<&str> = meta.split_whitespace().collect();
+    let [_, _, _, blob_id, status] = fields[..] else {
+      continue;
+    };
+    if status == "D" {
+      continue;
+    }
+
+    let original = path.replace('/', "");
+    let copied = match blob_mappings.get(blob_id) {
+      Some(copied) => copied.clone(),
+      None => {
+        let copied = read_mapped_commit(git_executor, repo, blob_id)?;
+        blob_mappings.insert(blob_id.to_string(), copied.clone());
+        copied
+      }
+    };
+    // Notes without a mapping line are the user's own
+    let Some(copied) = copied else {
+      continue;
+    };
+    if seen.insert((original.clone(), copied.clone())) {
+      entries.push(CommitMappingEntry {
+        original_commit: original,
+        copied_commit: copied,
+        recorded_at,
+        current: false,
+      });
+    }
+  }
+
+  debug!(entry_count = entries.len(), read_notes = blob_mappings.len(), "Read mapping history");
+  Ok(entries)
+}
+
+/// Entries marked against the current notes, newest first
+fn newest_first(git_executor: &GitCommandExecutor, repo: &str, mut entries: Vec<CommitMappingEntry>) -> Result<Vec<CommitMappingEntry>> {
+  mark_current(git_executor, repo, &mut entries)?;
+  entries.reverse();
+  Ok(entries)
+}
+
+/// Mark the entries matching the current note of their original commit
+fn mark_current(git_executor: &GitCommandExecutor, repo: &str, entries: &mut [CommitMappingEntry]) -> Result<()> {
+  if entries.is_empty() {
+    return Ok(());
+  }
+  let originals: HashSet<&str> = entries.iter().map(|entry| entry.original_commit.as_str()).collect();
+  // Output format: "<note blob> <annotated commit>"
+  let mut current: HashMap<String, String> = HashMap::new();
+  for line in git_executor.execute_command_lines(&["notes", "--ref", MAPPING_NOTES_REF, "list"], repo)? {
+    if let Some((blob_id, object_id)) = line.split_once(' ')
+      && originals.contains(object_id)
+      && let Some(copied) = read_mapped_commit(git_executor, repo, blob_id)?
+    {
+      current.insert(object_id.to_string(), copied);
+    }
+  }
+  for entry in entries {
+    entry.current = current.get(&entry.original_commit) == Some(&entry.copied_commit);
+  }
+  Ok(())
+}
+
+fn read_mapped_commit(git_executor: &GitCommandExecutor, repo: &str, blob_id: &str) -> Result<Option<String>> {
+  let Some((_, content)) = git_executor.cat_file_contents(repo, blob_id)? else {
+    return Ok(None);
+  };
+  Ok(
+    String::from_utf8_lossy(&content)
+      .lines()
+      .find_map(|line| line.trim().strip_prefix(PREFIX))
+      .map(|copied| copied.trim().to_string()),
+  )
+}
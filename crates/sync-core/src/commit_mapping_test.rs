@@ -0,0 +1,98 @@
+use crate::commit_mapping::{GetCommitMappingParams, get_commit_mapping_core, get_reverse_mapping_core};
+use crate::sync::{SyncOptions, sync_branches};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::notes::{CommitNoteInfo, write_commit_notes};
+use pretty_assertions::assert_eq;
+use std::sync::Mutex;
+use sync_test_utils::TestReporter;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn mapping_params(test_repo: &TestRepo, commit_id: &str) -> GetCommitMappingParams {
+  GetCommitMappingParams {
+    repository_path: test_repo.path().to_str().unwrap().to_string(),
+    commit_id: commit_id.to_string(),
+  }
+}
+
+fn write_mapping(test_repo: &TestRepo, original: &str, copied: &str) {
+  let note = CommitNoteInfo {
+    original_oid: original.to_string(),
+    new_oid: copied.to_string(),
+    author: String::new(),
+    author_email: String::new(),
+    tree_id: String::new(),
+    subject: String::new(),
+    user_note: None,
+  };
+  write_commit_notes(&GitCommandExecutor::new(), test_repo.path().to_str().unwrap(), vec![note], &Mutex::new(())).unwrap();
+}
+
+#[test(tokio::test)]
+async fn test_commit_mapping_after_sync() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  let original = test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+  let git_executor = GitCommandExecutor::new();
+  sync_branches(&git_executor, test_repo.path().to_str().unwrap(), "test", TestReporter::new(), SyncOptions::default()).await?;
+  let copied = test_repo.rev_parse("test/virtual/feature").unwrap();
+
+  let mapping = get_commit_mapping_core(&git_executor, mapping_params(&test_repo, &original[..10]))?;
+  assert_eq!(mapping.commit_id, original);
+  let entries: Vec<(&str, &str, bool)> = mapping
+    .entries
+    .iter()
+    .map(|entry| (entry.original_commit.as_str(), entry.copied_commit.as_str(), entry.current))
+    .collect();
+  assert_eq!(entries, vec![(original.as_str(), copied.as_str(), true)]);
+  assert!(mapping.entries[0].recorded_at > 0);
+
+  let reverse = get_reverse_mapping_core(&git_executor, mapping_params(&test_repo, "test/virtual/feature"))?;
+  assert_eq!(reverse.commit_id, copied);
+  assert_eq!(reverse.entries, mapping.entries);
+  Ok(())
+}
+
+#[test]
+fn test_commit_mapping_history() {
+  let test_repo = TestRepo::new();
+  let original = test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+  let first_copy = test_repo.create_commit("Add feature", "feature.txt", "feature 1");
+  let second_copy = test_repo.create_commit("Add feature", "feature.txt", "feature 2");
+  let other = test_repo.create_commit("(fix) Fix bug", "fix.txt", "fix");
+  let git_executor = GitCommandExecutor::new();
+
+  write_mapping(&test_repo, &original, &first_copy);
+  write_mapping(&test_repo, &other, &first_copy);
+  write_mapping(&test_repo, &original, &second_copy);
+  // The user's own note isn't a mapping
+  git_executor
+    .execute_command(&["notes", "add", "-f", "-m", "Reviewed", &other], test_repo.path().to_str().unwrap())
+    .unwrap();
+
+  let mapping = get_commit_mapping_core(&git_executor, mapping_params(&test_repo, &original)).unwrap();
+  let entries: Vec<(&str, bool)> = mapping.entries.iter().map(|entry| (entry.copied_commit.as_str(), entry.current)).collect();
+  assert_eq!(entries, vec![(second_copy.as_str(), true), (first_copy.as_str(), false)]);
+
+  // Both originals were mapped to the first copy by prior syncs
+  let reverse = get_reverse_mapping_core(&git_executor, mapping_params(&test_repo, &first_copy)).unwrap();
+  let entries: Vec<(&str, bool)> = reverse.entries.iter().map(|entry| (entry.original_commit.as_str(), entry.current)).collect();
+  assert_eq!(entries, vec![(other.as_str(), false), (original.as_str(), false)]);
+
+  // The index of the reverse lookup is built again once a sync moved the notes ref
+  write_mapping(&test_repo, &other, &second_copy);
+  let reverse = get_reverse_mapping_core(&git_executor, mapping_params(&test_repo, &second_copy)).unwrap();
+  let entries: Vec<(&str, bool)> = reverse.entries.iter().map(|entry| (entry.original_commit.as_str(), entry.current)).collect();
+  assert_eq!(entries, vec![(other.as_str(), true), (original.as_str(), true)]);
+}
+
+#[test]
+fn test_commit_mapping_not_synced() {
+  let test_repo = TestRepo::new();
+  let commit = test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+  let git_executor = GitCommandExecutor::new();
+
+  assert!(get_commit_mapping_core(&git_executor, mapping_params(&test_repo, &commit)).unwrap().entries.is_empty());
+  assert!(get_reverse_mapping_core(&git_executor, mapping_params(&test_repo, &commit)).unwrap().entries.is_empty());
+  assert!(get_commit_mapping_core(&git_executor, mapping_params(&test_repo, "missing")).is_err());
+}
@@ -0,0 +1,77 @@
+use crate::repo_state::update_repo_state;
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::commit_list::Commit;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use sync_types::CommitOrder;
+use tracing::{instrument, warn};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SetBranchCommitOrderParams {
+  pub repository_path: String,
+  pub branch_name: String,
+  pub commit_order: CommitOrder,
+}
+
+/// Persist the commit order of a branch; it is applied on the next sync
+#[instrument(skip(git_executor))]
+pub fn set_branch_commit_order_core(git_executor: &GitCommandExecutor, params: SetBranchCommitOrderParams) -> Result<()> {
+  update_repo_state(git_executor, &params.repository_path, |state| {
+    // Only deviations from the source order are stored
+    if params.commit_order == CommitOrder::Source {
+      state.commit_order.remove(&params.branch_name);
+    } else {
+      state.commit_order.insert(params.branch_name.clone(), params.commit_order);
+    }
+  })?;
+  Ok(())
+}
+
+/// Reorder the commits of a branch (oldest first) in the order they are applied.
+/// `dependencies` maps a commit to the commits it depends on, only used by [`CommitOrder::Topo`].
+pub fn order_commits(commits: &mut [Commit], order: CommitOrder, dependencies: &HashMap<String, Vec<String>>) {
+  match order {
+    CommitOrder::Source => {}
+    // Stable, so commits with the same author date keep the source order
+    CommitOrder::AuthorDate => commits.sort_by_key(|commit| commit.author_timestamp),
+    CommitOrder::Topo => order_topologically(commits, dependencies),
+  }
+}
+
+/// Move each commit after the commits of the same branch it depends on, keeping the source order otherwise
+fn order_topologically(commits: &mut [Commit], dependencies: &HashMap<String, Vec<String>>) {
+  let ids: HashSet<&str> = commits.iter().map(|commit| commit.id.as_str()).collect();
+  // Dependencies outside the branch are validated separately
+  let branch_dependencies: Vec<Vec<usize>> = commits
+    .iter()
+    .map(|commit| {
+      dependencies
+        .get(&commit.id)
+        .into_iter()
+        .flatten()
+        .filter(|id| ids.contains(id.as_str()))
+        .filter_map(|id| commits.iter().position(|other| &other.id == id))
+        .collect()
+    })
+    .collect();
+
+  // Repeatedly take the first commit in source order whose dependencies are placed
+  let mut placed = vec![false; commits.len()];
+  let mut order: Vec<usize> = Vec::with_capacity(commits.len());
+  while order.len() < commits.len() {
+    let next = (0..commits.len()).find(|&i| !placed[i] && branch_dependencies[i].iter().all(|&dependency| placed[dependency]));
+    let Some(next) = next else {
+      warn!("Commit dependencies form a cycle, keeping the source order for the remaining commits");
+      order.extend((0..commits.len()).filter(|&i| !placed[i]));
+      break;
+    };
+    placed[next] = true;
+    order.push(next);
+  }
+
+  let ordered: Vec<Commit> = order.into_iter().map(|i| commits[i].clone()).collect();
+  commits.clone_from_slice(&ordered);
+}
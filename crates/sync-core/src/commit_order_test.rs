@@ -0,0 +1,137 @@
+use crate::commit_order::{SetBranchCommitOrderParams, order_commits, set_branch_commit_order_core};
+use crate::repo_state::load_repo_state;
+use crate::sync::sync_branches_core;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::commit_list::Commit;
+use pretty_assertions::assert_eq;
+use std::collections::{BTreeMap, HashMap};
+use sync_test_utils::TestReporter;
+use sync_types::{CommitOrder, SyncEvent};
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn commit(id: &str, author_timestamp: u32) -> Commit {
+  Commit {
+    id: id.to_string(),
+    stripped_subject: format!("subject {id}"),
+    message: format!("subject {id}"),
+    author_name: String::new(),
+    author_email: String::new(),
+    author_timestamp,
+    committer_timestamp: 0,
+    subject: format!("subject {id}"),
+    parent_id: None,
+    tree_id: String::new(),
+    note: None,
+    mapped_commit_id: None,
+  }
+}
+
+fn ids(commits: &[Commit]) -> Vec<&str> {
+  commits.iter().map(|commit| commit.id.as_str()).collect()
+}
+
+#[test]
+fn test_order_commits_by_author_date() {
+  let mut commits = vec![commit("a", 300), commit("b", 100), commit("c", 300), commit("d", 200)];
+  order_commits(&mut commits, CommitOrder::AuthorDate, &HashMap::new());
+  // Commits with the same author date keep the source order
+  assert_eq!(ids(&commits), vec!["b", "d", "a", "c"]);
+
+  order_commits(&mut commits, CommitOrder::Source, &HashMap::new());
+  assert_eq!(ids(&commits), vec!["b", "d", "a", "c"]);
+}
+
+#[test]
+fn test_order_commits_topologically() {
+  let mut commits = vec![commit("a", 0), commit("b", 0), commit("c", 0), commit("d", 0)];
+  let dependencies = HashMap::from([
+    ("a".to_string(), vec!["c".to_string()]),
+    // Dependencies outside the branch don't affect the order
+    ("b".to_string(), vec!["outside".to_string()]),
+    ("c".to_string(), vec!["d".to_string()]),
+  ]);
+  order_commits(&mut commits, CommitOrder::Topo, &dependencies);
+  assert_eq!(ids(&commits), vec!["b", "d", "c", "a"]);
+}
+
+#[test]
+fn test_order_commits_topologically_with_cycle() {
+  let mut commits = vec![commit("a", 0), commit("b", 0), commit("c", 0)];
+  let dependencies = HashMap::from([("a".to_string(), vec!["b".to_string()]), ("b".to_string(), vec!["a".to_string()])]);
+  order_commits(&mut commits, CommitOrder::Topo, &dependencies);
+  assert_eq!(ids(&commits), vec!["c", "a", "b"]);
+}
+
+#[test]
+fn test_set_branch_commit_order() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let set_order = |branch_name: &str, commit_order: CommitOrder| {
+    set_branch_commit_order_core(
+      &git_executor,
+      SetBranchCommitOrderParams {
+        repository_path: repo_path.to_string(),
+        branch_name: branch_name.to_string(),
+        commit_order,
+      },
+    )
+    .unwrap()
+  };
+
+  set_order("auth", CommitOrder::AuthorDate);
+  set_order("parser", CommitOrder::Topo);
+  assert_eq!(
+    load_repo_state(&git_executor, repo_path).unwrap().commit_order,
+    BTreeMap::from([("auth".to_string(), CommitOrder::AuthorDate), ("parser".to_string(), CommitOrder::Topo)])
+  );
+
+  // The source order is the default and is not stored
+  set_order("auth", CommitOrder::Source);
+  assert_eq!(
+    load_repo_state(&git_executor, repo_path).unwrap().commit_order,
+    BTreeMap::from([("parser".to_string(), CommitOrder::Topo)])
+  );
+}
+
+#[test(tokio::test)]
+async fn test_sync_applies_author_date_order() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit_with_timestamp("Initial commit", "README.md", "# Test", Some(1_000));
+  // Committed in a different order than authored, e.g. after an interactive rebase
+  let later = test_repo.create_commit_with_timestamp("(auth) Add session", "session.txt", "session", Some(3_000));
+  let earlier = test_repo.create_commit_with_timestamp("(auth) Add login", "login.txt", "login", Some(2_000));
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  set_branch_commit_order_core(
+    &git_executor,
+    SetBranchCommitOrderParams {
+      repository_path: repo_path.to_string(),
+      branch_name: "auth".to_string(),
+      commit_order: CommitOrder::AuthorDate,
+    },
+  )?;
+
+  let progress = TestReporter::new();
+  sync_branches_core(&git_executor, repo_path, "test", progress.clone()).await?;
+
+  let branch = progress
+    .get_events()
+    .into_iter()
+    .find_map(|event| match event {
+      SyncEvent::BranchesGrouped { branches, .. } => branches.into_iter().find(|branch| branch.name == "auth"),
+      _ => None,
+    })
+    .expect("auth branch");
+  assert_eq!(branch.commit_order, CommitOrder::AuthorDate);
+  // Newest first for display
+  assert_eq!(branch.commits.iter().map(|commit| commit.id.clone()).collect::<Vec<_>>(), vec![later, earlier]);
+
+  let subjects = test_repo.log(&["--reverse", "--pretty=format:%s", "-2", "test/virtual/auth"]).unwrap();
+  assert_eq!(subjects.lines().collect::<Vec<_>>(), vec!["Add login", "Add session"]);
+  Ok(())
+}
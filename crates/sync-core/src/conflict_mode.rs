@@ -0,0 +1,47 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::git_config::get_config_value;
+use tracing::{instrument, warn};
+
+/// Git config key holding the per-repository conflict mode
+pub const CONFLICT_MODE_CONFIG_KEY: &str = "branchdeck.conflictMode";
+
+/// What sync does when a commit can't be applied because of a merge conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictMode {
+  /// Stop the branch at the first conflicted commit, other branches are synced as usual
+  #[default]
+  StopBranch,
+  /// Keep applying the remaining commits of the branch to report all of its conflicts at once.
+  /// The branch itself is not updated.
+  CollectAll,
+  /// Stop the whole sync at the first conflict: branches that are not finished yet are not updated
+  AbortSync,
+}
+
+impl ConflictMode {
+  /// Parse a config value (`stop-branch`, `collect-all` or `abort-sync`)
+  pub fn from_config_value(value: &str) -> Option<Self> {
+    match value.trim().to_ascii_lowercase().as_str() {
+      "stop-branch" => Some(Self::StopBranch),
+      "collect-all" => Some(Self::CollectAll),
+      "abort-sync" => Some(Self::AbortSync),
+      _ => None,
+    }
+  }
+}
+
+/// Read the conflict mode from git config, falling back to the default for missing or unknown values
+#[instrument(skip(git_executor))]
+pub fn load_conflict_mode(git_executor: &GitCommandExecutor, repository_path: &str) -> ConflictMode {
+  match get_config_value(git_executor, repository_path, CONFLICT_MODE_CONFIG_KEY) {
+    Ok(Some(value)) => ConflictMode::from_config_value(&value).unwrap_or_else(|| {
+      warn!(value, "Unknown {CONFLICT_MODE_CONFIG_KEY} value, stopping branches at the first conflict");
+      ConflictMode::default()
+    }),
+    Ok(None) => ConflictMode::default(),
+    Err(e) => {
+      warn!(error = %e, "Failed to read conflict mode, stopping branches at the first conflict");
+      ConflictMode::default()
+    }
+  }
+}
@@ -0,0 +1,201 @@
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::cache::TreeIdCache;
+use git_ops::cherry_pick::perform_fast_cherry_pick_with_context;
+use git_ops::copy_commit::CopyCommitError;
+use git_ops::diff_options::load_diff_options;
+use git_ops::model::{BranchError, MergeConflictInfo};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, instrument};
+
+/// Branch prefix used when the caller doesn't provide one
+const DEFAULT_BRANCH_PREFIX: &str = "playground";
+
+/// Name of the virtual branch whose commit conflicts on sync
+const CONFLICT_BRANCH_NAME: &str = "conflict-demo";
+
+/// Kind of conflict to fabricate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictScenarioKind {
+  /// Both sides edit the same lines of a single file
+  EditEdit,
+  /// Unassigned commit edits a file that the branch commit deletes
+  EditDelete,
+  /// Both sides edit the same lines in several files
+  MultipleFiles,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateConflictParams {
+  pub scenario: ConflictScenarioKind,
+  /// Directory to create the scratch repository in (defaults to the system temp directory)
+  pub parent_dir: Option<String>,
+  pub branch_prefix: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateConflictResult {
+  /// Path of the scratch repository; syncing it reproduces the conflict
+  pub repository_path: String,
+  pub branch_prefix: String,
+  /// Virtual branch that fails to sync
+  pub branch_name: String,
+  /// Conflict details as the sync would report them
+  pub conflict: MergeConflictInfo,
+}
+
+/// Files of one side of the scenario
+struct ScenarioStep {
+  message: &'static str,
+  files: Vec<(&'static str, Option<&'static str>)>, // None deletes the file
+}
+
+/// Commits making up a scenario: baseline, an unassigned commit on top of it and a branch commit that depends on the unassigned one
+struct ScenarioSpec {
+  initial: ScenarioStep,
+  unassigned: ScenarioStep,
+  branch: ScenarioStep,
+}
+
+const CALCULATOR_INITIAL: &str = "function calculateTotal(items) {\n  let total = 0;\n  for (let item of items) {\n    total += item.price;\n  }\n  return total;\n}\n";
+const CALCULATOR_QUANTITY: &str =
+  "function calculateTotal(items) {\n  let total = 0;\n  for (let item of items) {\n    total += item.price * item.quantity;\n  }\n  return Math.round(total * 100) / 100;\n}\n";
+const CALCULATOR_TAX: &str =
+  "function calculateTotal(items) {\n  let total = 0;\n  for (let item of items) {\n    total += item.price * item.quantity + item.tax;\n  }\n  return total.toFixed(2);\n}\n";
+const CONFIG_INITIAL: &str = "export const config = {\n  currency: 'USD',\n  precision: 2,\n};\n";
+const CONFIG_LOCALE: &str = "export const config = {\n  currency: 'USD',\n  precision: 2,\n  locale: 'en-US',\n};\n";
+const CONFIG_TAX: &str = "export const config = {\n  currency: 'USD',\n  precision: 2,\n  locale: 'en-US',\n  taxRate: 0.2,\n};\n";
+
+fn scenario_spec(kind: ConflictScenarioKind) -> ScenarioSpec {
+  let initial = ScenarioStep {
+    message: "Initial commit",
+    files: vec![
+      ("README.md", Some("# Conflict playground\n")),
+      ("src/calculator.js", Some(CALCULATOR_INITIAL)),
+      ("src/config.js", Some(CONFIG_INITIAL)),
+    ],
+  };
+
+  match kind {
+    ConflictScenarioKind::EditEdit => ScenarioSpec {
+      initial,
+      unassigned: ScenarioStep {
+        message: "Support item quantity",
+        files: vec![("src/calculator.js", Some(CALCULATOR_QUANTITY))],
+      },
+      branch: ScenarioStep {
+        message: "Add tax to total",
+        files: vec![("src/calculator.js", Some(CALCULATOR_TAX))],
+      },
+    },
+    ConflictScenarioKind::EditDelete => ScenarioSpec {
+      initial,
+      unassigned: ScenarioStep {
+        message: "Support item quantity",
+        files: vec![("src/calculator.js", Some(CALCULATOR_QUANTITY))],
+      },
+      branch: ScenarioStep {
+        message: "Remove legacy calculator",
+        files: vec![("src/calculator.js", None)],
+      },
+    },
+    ConflictScenarioKind::MultipleFiles => ScenarioSpec {
+      initial,
+      unassigned: ScenarioStep {
+        message: "Support item quantity and locale",
+        files: vec![("src/calculator.js", Some(CALCULATOR_QUANTITY)), ("src/config.js", Some(CONFIG_LOCALE))],
+      },
+      branch: ScenarioStep {
+        message: "Add configurable tax",
+        files: vec![("src/calculator.js", Some(CALCULATOR_TAX)), ("src/config.js", Some(CONFIG_TAX))],
+      },
+    },
+  }
+}
+
+/// Write (or delete) files of a step and commit them, returning the new commit hash
+fn commit_step(git_executor: &GitCommandExecutor, repo_path: &Path, message: &str, files: &[(&str, Option<&str>)]) -> Result<String> {
+  let repo = repo_path.to_str().ok_or_else(|| anyhow!("Repository path is not valid UTF-8"))?;
+  for (file, content) in files {
+    let full_path = repo_path.join(file);
+    match content {
+      Some(content) => {
+        if let Some(parent) = full_path.parent() {
+          std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&full_path, content)?;
+        git_executor.execute_command(&["add", file], repo)?;
+      }
+      None => {
+        git_executor.execute_command(&["rm", "-q", file], repo)?;
+      }
+    }
+  }
+  git_executor.execute_command(&["commit", "-q", "-m", message], repo)?;
+  Ok(git_executor.execute_command(&["rev-parse", "HEAD"], repo)?.trim().to_string())
+}
+
+/// Fabricate a scratch repository containing a realistic sync conflict.
+/// The repository has `origin/master` at the initial commit, an unassigned commit and a prefixed commit that depends on it,
+/// so syncing the repository reports the same conflict (with the unassigned commit listed as missing).
+#[instrument(skip(git_executor))]
+pub fn simulate_conflict_core(git_executor: &GitCommandExecutor, params: SimulateConflictParams) -> Result<SimulateConflictResult> {
+  let branch_prefix = params.branch_prefix.filter(|p| !p.trim().is_empty()).unwrap_or_else(|| DEFAULT_BRANCH_PREFIX.to_string());
+  let parent_dir = params.parent_dir.map(PathBuf::from).unwrap_or_else(std::env::temp_dir);
+  let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+  let mut repo_path = parent_dir.join(format!("branch-deck-conflict-{timestamp}"));
+  let mut suffix = 1;
+  while repo_path.exists() {
+    repo_path = parent_dir.join(format!("branch-deck-conflict-{timestamp}-{suffix}"));
+    suffix += 1;
+  }
+  std::fs::create_dir_all(&repo_path)?;
+  let repo = repo_path.to_str().ok_or_else(|| anyhow!("Repository path is not valid UTF-8"))?.to_string();
+
+  git_executor.execute_command(&["init", "-q", "--initial-branch=master"], &repo)?;
+  git_executor.execute_command(&["config", "user.name", "Branch Deck"], &repo)?;
+  git_executor.execute_command(&["config", "user.email", "playground@branch-deck.local"], &repo)?;
+  git_executor.execute_command(&["config", "commit.gpgsign", "false"], &repo)?;
+  git_executor.execute_command(&["config", "branchdeck.branchPrefix", &branch_prefix], &repo)?;
+
+  let spec = scenario_spec(params.scenario);
+  let initial = commit_step(git_executor, &repo_path, spec.initial.message, &spec.initial.files)?;
+  commit_step(git_executor, &repo_path, spec.unassigned.message, &spec.unassigned.files)?;
+  let branch_message = format!("({CONFLICT_BRANCH_NAME}) {}", spec.branch.message);
+  let branch_commit = commit_step(git_executor, &repo_path, &branch_message, &spec.branch.files)?;
+
+  // Baseline for sync: a fake origin pointing to the initial commit
+  git_executor.execute_command(&["remote", "add", "origin", "."], &repo)?;
+  git_executor.execute_command(&["update-ref", "refs/remotes/origin/master", &initial], &repo)?;
+
+  // Same cherry-pick the sync performs for the branch commit
+  let conflict = match perform_fast_cherry_pick_with_context(
+    git_executor,
+    &repo,
+    &branch_commit,
+    &initial,
+    None,
+    &TreeIdCache::new(),
+    &load_diff_options(git_executor, &repo),
+  ) {
+    Err(CopyCommitError::BranchError(BranchError::MergeConflict(info))) => *info,
+    Err(e) => return Err(anyhow!("Failed to simulate conflict: {e}")),
+    Ok(_) => return Err(anyhow!("Scenario {:?} did not produce a conflict", params.scenario)),
+  };
+
+  info!(repository_path = %repo, scenario = ?params.scenario, "Created conflict playground repository");
+  Ok(SimulateConflictResult {
+    repository_path: repo,
+    branch_prefix,
+    branch_name: CONFLICT_BRANCH_NAME.to_string(),
+    conflict,
+  })
+}
@@ -0,0 +1,54 @@
+use crate::conflict_simulation::{ConflictScenarioKind, SimulateConflictParams, simulate_conflict_core};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+
+fn simulate(scenario: ConflictScenarioKind, parent_dir: &tempfile::TempDir) -> crate::conflict_simulation::SimulateConflictResult {
+  let params = SimulateConflictParams {
+    scenario,
+    parent_dir: Some(parent_dir.path().to_string_lossy().to_string()),
+    branch_prefix: None,
+  };
+  simulate_conflict_core(&GitCommandExecutor::new(), params).unwrap()
+}
+
+#[test]
+fn test_simulate_edit_edit_conflict() {
+  let parent_dir = tempfile::tempdir().unwrap();
+  let result = simulate(ConflictScenarioKind::EditEdit, &parent_dir);
+
+  assert_eq!(result.branch_prefix, "playground");
+  assert_eq!(result.branch_name, "conflict-demo");
+  assert!(result.repository_path.starts_with(parent_dir.path().to_str().unwrap()));
+  assert_eq!(result.conflict.commit_message, "(conflict-demo) Add tax to total");
+  let files: Vec<&str> = result.conflict.conflicting_files.iter().map(|f| f.file.as_str()).collect();
+  assert_eq!(files, vec!["src/calculator.js"]);
+
+  // Repository is configured so that sync picks up the prefix and baseline
+  let git_executor = GitCommandExecutor::new();
+  let prefix = git_executor
+    .execute_command(&["config", "--get", "branchdeck.branchPrefix"], &result.repository_path)
+    .unwrap();
+  assert_eq!(prefix.trim(), "playground");
+  let baseline = git_executor.execute_command(&["rev-parse", "origin/master"], &result.repository_path).unwrap();
+  assert_eq!(baseline.trim(), result.conflict.target_branch_hash);
+}
+
+#[test]
+fn test_simulate_multiple_files_conflict() {
+  let parent_dir = tempfile::tempdir().unwrap();
+  let result = simulate(ConflictScenarioKind::MultipleFiles, &parent_dir);
+
+  let mut files: Vec<&str> = result.conflict.conflicting_files.iter().map(|f| f.file.as_str()).collect();
+  files.sort();
+  assert_eq!(files, vec!["src/calculator.js", "src/config.js"]);
+}
+
+#[test]
+fn test_simulate_edit_delete_conflict() {
+  let parent_dir = tempfile::tempdir().unwrap();
+  let result = simulate(ConflictScenarioKind::EditDelete, &parent_dir);
+
+  assert_eq!(result.conflict.conflicting_files.len(), 1);
+  assert_eq!(result.conflict.conflicting_files[0].file, "src/calculator.js");
+}
@@ -1,4 +1,5 @@
 use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::model::validate_branch_name;
 use git_ops::reword_commits::{RewordCommitParams, reword_commits_batch};
 use tracing::{info, instrument};
 
@@ -24,20 +25,7 @@ pub struct RewordResult {
 pub fn do_create_branch_from_commits(git_executor: &GitCommandExecutor, params: CreateBranchFromCommitsParams) -> Result<RewordResult, String> {
   info!("Assigning {} commits to branch '{}'", params.commit_ids.len(), params.branch_name);
 
-  // Validate branch name
-  if params.branch_name.is_empty() {
-    return Err("Branch name cannot be empty".to_string());
-  }
-
-  // Check for invalid characters (allow dots for version numbers like "1.21.2")
-  if !params.branch_name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.') {
-    return Err("Branch name can only contain letters, numbers, hyphens, underscores, and dots".to_string());
-  }
-
-  // Git-specific rule: cannot start with a dot
-  if params.branch_name.starts_with('.') {
-    return Err("Branch name cannot start with a dot".to_string());
-  }
+  validate_branch_name(&params.branch_name)?;
 
   let prefix = format!("({}) ", params.branch_name);
 
@@ -76,8 +64,8 @@ pub fn do_create_branch_from_commits(git_executor: &GitCommandExecutor, params:
   let reworded_count = rewrites.len() as u32;
 
   match reword_commits_batch(git_executor, &params.repository_path, rewrites) {
-    Ok(mapping) => {
-      info!("Successfully reworded {} commits with branch prefix '{}'", mapping.len(), params.branch_name);
+    Ok(_) => {
+      info!("Successfully reworded {} commits with branch prefix '{}'", reworded_count, params.branch_name);
 
       Ok(RewordResult {
         success: true,
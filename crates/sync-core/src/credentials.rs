@@ -0,0 +1,238 @@
+//! Credentials of HTTPS remotes from the git credential helpers.
+//!
+//! Push asks the helpers with `git credential fill` first, so missing credentials fail with
+//! [`ErrorCode::CredentialsRequired`] instead of an opaque push error. The credential is passed to the push through the
+//! environment, then stored with `git credential approve`, or removed with `git credential reject` if the remote
+//! refused it. When a helper needs input, or no helper has the credential, git asks the askpass program of
+//! [`AskpassEnv`]; the app answers such prompts in its UI.
+//!
+//! SSH remotes don't use the helpers, but ssh asks the same askpass program (`SSH_ASKPASS`) for a key passphrase when
+//! no agent holds the key, and to confirm an unknown host key. A rejected key fails with
+//! [`ErrorCode::SshAuthenticationFailed`].
+
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_executor::git_error::GitCommandError;
+use git_ops::error::{CommandError, ErrorCode};
+use std::fmt;
+use tracing::{debug, instrument, warn};
+
+/// Environment variables the filled credential is passed to git in
+const USERNAME_ENV: &str = "BRANCH_DECK_GIT_USERNAME";
+const PASSWORD_ENV: &str = "BRANCH_DECK_GIT_PASSWORD";
+/// Replaces the configured helpers for a command, answering with the credential from the environment
+const ENV_CREDENTIAL_HELPER: &str =
+  "credential.helper=!f() { test \"$1\" = get && printf 'username=%s\\npassword=%s\\n' \"$BRANCH_DECK_GIT_USERNAME\" \"$BRANCH_DECK_GIT_PASSWORD\"; }; f";
+
+/// How git asks for missing credentials: never in a terminal, through the askpass program if there is one
+#[derive(Debug, Clone, Default)]
+pub struct AskpassEnv {
+  program: Option<String>,
+  vars: Vec<(String, String)>,
+}
+
+impl AskpassEnv {
+  /// Missing credentials fail instead of being asked for
+  pub fn none() -> Self {
+    Self::default()
+  }
+
+  /// Ask by running `program`, with the environment variables it needs (e.g. how to reach the UI)
+  pub fn new(program: String, vars: Vec<(String, String)>) -> Self {
+    Self { program: Some(program), vars }
+  }
+
+  /// Environment of a git command that may ask for credentials
+  pub fn env(&self) -> Vec<(&str, &str)> {
+    let mut env = vec![("GIT_TERMINAL_PROMPT", "0"), ("GIT_ASKPASS", self.program.as_deref().unwrap_or_default())];
+    // A key passphrase or host key confirmation is asked by ssh itself, not by git
+    if let Some(program) = &self.program {
+      env.push(("SSH_ASKPASS", program));
+    }
+    env.extend(self.vars.iter().map(|(key, value)| (key.as_str(), value.as_str())));
+    env
+  }
+}
+
+/// Credential as returned by `git credential fill`, kept verbatim to be passed back to `approve` or `reject`
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credential {
+  attributes: Vec<(String, String)>,
+}
+
+impl Credential {
+  pub fn get(&self, key: &str) -> Option<&str> {
+    self.attributes.iter().find(|(name, _)| name == key).map(|(_, value)| value.as_str())
+  }
+
+  pub fn host(&self) -> &str {
+    self.get("host").unwrap_or_default()
+  }
+
+  fn to_input(&self) -> String {
+    let mut input: String = self.attributes.iter().map(|(key, value)| format!("{key}={value}\n")).collect();
+    input.push('\n');
+    input
+  }
+}
+
+/// Without the secrets
+impl fmt::Debug for Credential {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Credential")
+      .field("protocol", &self.get("protocol"))
+      .field("host", &self.get("host"))
+      .field("username", &self.get("username"))
+      .finish_non_exhaustive()
+  }
+}
+
+/// Whether git uses the credential helpers for the remote URL
+pub fn is_http_url(url: &str) -> bool {
+  let url = url.trim();
+  url.starts_with("https://") || url.starts_with("http://")
+}
+
+/// Host of a remote URL, for error messages
+fn url_host(url: &str) -> &str {
+  let url = url.trim();
+  let authority = match url.split_once("://") {
+    Some((_, rest)) => rest.split('/').next().unwrap_or(rest),
+    // scp-like `user@host:path`
+    None => url.split(':').next().unwrap_or(url),
+  };
+  authority.rsplit_once('@').map_or(authority, |(_, host)| host)
+}
+
+/// Credential for the URL from the helpers, asking through the askpass program if they have none.
+/// Fails with [`ErrorCode::CredentialsRequired`] if no credential was found or entered.
+#[instrument(skip(git_executor, askpass))]
+pub fn credential_fill(git_executor: &GitCommandExecutor, repository_path: &str, url: &str, askpass: &AskpassEnv) -> Result<Credential> {
+  let missing = || {
+    let host = url_host(url);
+    CommandError::new(ErrorCode::CredentialsRequired, format!("No credentials for {host}"))
+      .with_context("host", host)
+      .into_anyhow()
+  };
+  let input = format!("url={}\n\n", url.trim());
+  // Without an askpass program git fails trying to prompt
+  let output = git_executor
+    .execute_command_with_env_and_stdin(&["credential", "fill"], repository_path, &askpass.env(), &input)
+    .map_err(|e| {
+      debug!(error = %e, "Credential fill failed");
+      missing()
+    })?;
+  let credential = Credential {
+    attributes: output
+      .lines()
+      .filter_map(|line| line.split_once('='))
+      .map(|(key, value)| (key.to_string(), value.to_string()))
+      .collect(),
+  };
+  if credential.get("password").is_none_or(str::is_empty) {
+    return Err(missing());
+  }
+  Ok(credential)
+}
+
+/// Store the credential in the helpers after the remote accepted it
+#[instrument(skip(git_executor))]
+pub fn credential_approve(git_executor: &GitCommandExecutor, repository_path: &str, credential: &Credential) -> Result<()> {
+  git_executor.execute_command_with_env_and_stdin(&["credential", "approve"], repository_path, &AskpassEnv::none().env(), &credential.to_input())?;
+  Ok(())
+}
+
+/// Remove the credential from the helpers after the remote refused it, so it isn't used again
+#[instrument(skip(git_executor))]
+pub fn credential_reject(git_executor: &GitCommandExecutor, repository_path: &str, credential: &Credential) -> Result<()> {
+  git_executor.execute_command_with_env_and_stdin(&["credential", "reject"], repository_path, &AskpassEnv::none().env(), &credential.to_input())?;
+  Ok(())
+}
+
+/// Whether git failed because the remote refused the credential
+pub fn is_authentication_failure(error: &anyhow::Error) -> bool {
+  error.chain().filter_map(|cause| cause.downcast_ref::<GitCommandError>()).any(|error| {
+    let stderr = error.stderr.to_ascii_lowercase();
+    [
+      "authentication failed",
+      "invalid username or password",
+      "the requested url returned error: 401",
+      "the requested url returned error: 403",
+    ]
+    .iter()
+    .any(|message| stderr.contains(message))
+  })
+}
+
+/// Why ssh refused to connect: `publickey` if the server accepted no key, `hostKey` if the host key couldn't be verified
+pub fn ssh_failure_reason(error: &anyhow::Error) -> Option<&'static str> {
+  error.chain().filter_map(|cause| cause.downcast_ref::<GitCommandError>()).find_map(|error| {
+    if error.stderr.contains("Permission denied (publickey") {
+      Some("publickey")
+    } else if error.stderr.contains("Host key verification failed") {
+      Some("hostKey")
+    } else {
+      None
+    }
+  })
+}
+
+/// [`ErrorCode::SshAuthenticationFailed`] if ssh refused to connect, the error as is otherwise
+fn classify_ssh_failure(error: anyhow::Error, url: &str) -> anyhow::Error {
+  let Some(reason) = ssh_failure_reason(&error) else {
+    return error;
+  };
+  let host = url_host(url);
+  let message = match reason {
+    "hostKey" => format!("The host key of {host} couldn't be verified"),
+    _ => format!("{host} accepted none of the SSH keys"),
+  };
+  CommandError::new(ErrorCode::SshAuthenticationFailed, message)
+    .with_context("host", host)
+    .with_context("reason", reason)
+    .into_anyhow()
+}
+
+/// Run a git command against a remote with the credential of its URL. For an HTTP(S) URL the credential is filled
+/// first, then approved if the command succeeds and rejected if the remote refused it. Other URLs (SSH, local paths)
+/// don't use credential helpers and run with the askpass environment only, a refused SSH connection fails with
+/// [`ErrorCode::SshAuthenticationFailed`].
+pub fn run_with_credentials(git_executor: &GitCommandExecutor, repository_path: &str, url: &str, args: &[&str], askpass: &AskpassEnv) -> Result<String> {
+  let askpass_env = askpass.env();
+  if !is_http_url(url) {
+    return git_executor
+      .execute_command_with_env(args, repository_path, &askpass_env)
+      .map_err(|e| classify_ssh_failure(e, url));
+  }
+
+  let credential = credential_fill(git_executor, repository_path, url, askpass)?;
+  // The helpers were already asked, git must use the filled credential
+  let command_args: Vec<&str> = ["-c", "credential.helper=", "-c", ENV_CREDENTIAL_HELPER].iter().chain(args).copied().collect();
+  let mut env = askpass_env;
+  env.push((USERNAME_ENV, credential.get("username").unwrap_or_default()));
+  env.push((PASSWORD_ENV, credential.get("password").unwrap_or_default()));
+  match git_executor.execute_command_with_env(&command_args, repository_path, &env) {
+    Ok(output) => {
+      // Not fatal, the command succeeded; the credential is asked for again next time
+      if let Err(e) = credential_approve(git_executor, repository_path, &credential) {
+        warn!(error = %e, "Failed to store the credential");
+      }
+      Ok(output)
+    }
+    Err(e) if is_authentication_failure(&e) => {
+      if let Err(e) = credential_reject(git_executor, repository_path, &credential) {
+        warn!(error = %e, "Failed to remove the refused credential");
+      }
+      let host = credential.host().to_string();
+      Err(
+        CommandError::new(
+          ErrorCode::AuthenticationFailed,
+          format!("{host} refused the credentials of {}", credential.get("username").unwrap_or_default()),
+        )
+        .with_context("host", host)
+        .into_anyhow(),
+      )
+    }
+    Err(e) => Err(e),
+  }
+}
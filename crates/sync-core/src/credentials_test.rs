@@ -0,0 +1,114 @@
+use crate::credentials::{AskpassEnv, credential_fill, is_http_url, run_with_credentials, ssh_failure_reason};
+use git_executor::git_error::GitCommandError;
+use git_ops::error::{CommandError, ErrorCode};
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// Only the helpers of the test repository, so the credentials of the machine running the tests are never used
+fn set_credential_helper(test_repo: &TestRepo, helper: &str) {
+  let repo_path = test_repo.path().to_str().unwrap();
+  for value in ["", helper] {
+    test_repo
+      .git_executor()
+      .execute_command(&["config", "--add", "credential.helper", value], repo_path)
+      .unwrap();
+  }
+}
+
+#[test]
+fn test_is_http_url() {
+  assert!(is_http_url("https://github.com/develar/branch-deck.git"));
+  assert!(is_http_url("http://localhost:8080/repo.git\n"));
+  assert!(!is_http_url("git@github.com:develar/branch-deck.git"));
+  assert!(!is_http_url("ssh://git@github.com/develar/branch-deck.git"));
+  assert!(!is_http_url("/tmp/origin"));
+}
+
+#[test]
+fn test_credential_fill_from_helper() {
+  let test_repo = TestRepo::new();
+  set_credential_helper(&test_repo, "!f() { test \"$1\" = get && printf 'username=dev\\npassword=secret\\n'; }; f");
+
+  let credential = credential_fill(
+    test_repo.git_executor(),
+    test_repo.path().to_str().unwrap(),
+    "https://git.example.com/team/repo.git",
+    &AskpassEnv::none(),
+  )
+  .unwrap();
+
+  assert_eq!(credential.host(), "git.example.com");
+  assert_eq!(credential.get("username"), Some("dev"));
+  assert_eq!(credential.get("password"), Some("secret"));
+  // Secrets never end up in logs
+  assert!(!format!("{credential:?}").contains("secret"));
+}
+
+#[test]
+fn test_credential_fill_without_credentials() {
+  let test_repo = TestRepo::new();
+  set_credential_helper(&test_repo, "!f() { :; }; f");
+
+  let error = credential_fill(
+    test_repo.git_executor(),
+    test_repo.path().to_str().unwrap(),
+    "https://user@git.example.com/team/repo.git",
+    &AskpassEnv::none(),
+  )
+  .unwrap_err();
+
+  let error = CommandError::from(error);
+  assert_eq!(error.code, ErrorCode::CredentialsRequired);
+  assert_eq!(error.context.get("host").map(String::as_str), Some("git.example.com"));
+}
+
+#[test]
+fn test_local_remote_runs_without_credentials() {
+  let origin = TestRepo::new();
+  origin.create_commit("Initial commit", "README.md", "# Test");
+  let local = TestRepo::new_empty();
+  local.clone_from(origin.path()).unwrap();
+  let repo_path = local.path().to_str().unwrap();
+  // Would fail if asked
+  set_credential_helper(&local, "!f() { exit 1; }; f");
+
+  let url = local.git_executor().execute_command(&["ls-remote", "--get-url", "origin"], repo_path).unwrap();
+  let output = run_with_credentials(local.git_executor(), repo_path, &url, &["ls-remote", "--heads", "origin"], &AskpassEnv::none()).unwrap();
+
+  assert!(output.contains("refs/heads/master"));
+}
+
+fn git_failure(stderr: &str) -> anyhow::Error {
+  GitCommandError {
+    git_path: "git".to_string(),
+    args: vec!["push".to_string()],
+    exit_code: Some(128),
+    stderr: stderr.to_string(),
+  }
+  .into()
+}
+
+#[test]
+fn test_ssh_failure_reason() {
+  assert_eq!(
+    ssh_failure_reason(&git_failure(
+      "git@github.com: Permission denied (publickey).\nfatal: Could not read from remote repository."
+    )),
+    Some("publickey")
+  );
+  assert_eq!(
+    ssh_failure_reason(&git_failure("Host key verification failed.\nfatal: Could not read from remote repository.")),
+    Some("hostKey")
+  );
+  assert_eq!(ssh_failure_reason(&git_failure("fatal: repository not found")), None);
+}
+
+#[test]
+fn test_ssh_askpass_only_with_program() {
+  assert!(!AskpassEnv::none().env().iter().any(|(key, _)| *key == "SSH_ASKPASS"));
+
+  let askpass = AskpassEnv::new("/opt/branch-deck".to_string(), Vec::new());
+  assert!(askpass.env().contains(&("SSH_ASKPASS", "/opt/branch-deck")));
+  assert!(askpass.env().contains(&("GIT_ASKPASS", "/opt/branch-deck")));
+}
@@ -1,7 +1,17 @@
-use anyhow::Result;
+use crate::branch_protection::check_delete_allowed;
+use crate::credentials::{AskpassEnv, run_with_credentials};
+use crate::remote_status::{PUSH_REMOTE, compute_remote_status_for_archived_branches};
+use anyhow::{Context, Result};
+use branch_integration::archive::ARCHIVE_MUTEX;
+use branch_integration::cache::CacheOps;
 use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::model::validate_branch_name;
+use git_ops::notes::{collect_branch_notes, remove_commit_notes};
 use serde::{Deserialize, Serialize};
-use tracing::instrument;
+use std::collections::HashMap;
+use std::fmt::Write;
+use sync_types::branch_integration::BranchIntegrationStatus;
+use tracing::{info, instrument, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
@@ -10,27 +20,77 @@ pub struct DeleteArchivedBranchParams {
   pub repository_path: String,
   pub branch_name: String,
   pub branch_prefix: String,
+  /// Also delete the branches still present on the remote for this archived branch.
+  /// Refused unless the branch is integrated and the remote has no commits beyond the archived branch.
+  pub delete_remote: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteArchivedBranchesParams {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  /// Full archived branch names (e.g., "user/archived/2025-08-11/feature")
+  pub branch_names: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteArchivedBranchesResult {
+  pub deleted_branches: Vec<String>,
+}
+
+/// Progress events for bulk deletion of archived branches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "type", content = "data")]
+pub enum DeleteArchivedBranchesProgress {
+  Started {
+    total: u32,
+  },
+  /// All refs were deleted in a single transaction
+  #[serde(rename_all = "camelCase")]
+  BranchesDeleted {
+    branch_names: Vec<String>,
+  },
+  Completed,
+  Error {
+    message: String,
+  },
+}
+
+/// Safety checks: only allow deleting refs under <prefix>/archived/, every path segment after it (the archive date and
+/// the virtual branch name) is a valid branch name as when the branch was created
+fn validate_archived_branch_name(branch_name: &str, branch_prefix: &str) -> Result<()> {
+  let required_prefix = format!("{}/archived/", branch_prefix);
+  let Some(archived_name) = branch_name.strip_prefix(&required_prefix) else {
+    return Err(anyhow::anyhow!("Can only delete archived branches under the configured branch prefix"));
+  };
+
+  if branch_name.starts_with('-') {
+    return Err(anyhow::anyhow!("Invalid branch name: {branch_name}"));
+  }
+  for segment in archived_name.split('/') {
+    validate_branch_name(segment).map_err(|e| anyhow::anyhow!("Invalid branch name {branch_name}: {e}"))?;
+  }
+  Ok(())
 }
 
 /// Core function to delete an archived branch
 /// This function contains all the safety checks and deletion logic
-#[instrument(skip(git_executor), fields(repo = %params.repository_path, branch = %params.branch_name))]
-pub fn delete_archived_branch_core(git_executor: &GitCommandExecutor, params: DeleteArchivedBranchParams) -> Result<()> {
+#[instrument(skip(git_executor, askpass), fields(repo = %params.repository_path, branch = %params.branch_name))]
+pub fn delete_archived_branch_core(git_executor: &GitCommandExecutor, params: DeleteArchivedBranchParams, askpass: &AskpassEnv) -> Result<()> {
   let DeleteArchivedBranchParams {
     repository_path,
     branch_name,
     branch_prefix,
+    delete_remote,
   } = params;
 
-  // Safety checks: only allow deleting refs under <prefix>/archived/
-  let required_prefix = format!("{}/archived/", branch_prefix);
-  if !branch_name.starts_with(&required_prefix) {
-    return Err(anyhow::anyhow!("Can only delete archived branches under the configured branch prefix"));
-  }
-
-  if branch_name.starts_with('-') || branch_name.contains("..") || branch_name.contains('\n') || branch_name.contains('\r') {
-    return Err(anyhow::anyhow!("Invalid branch name"));
-  }
+  validate_archived_branch_name(&branch_name, &branch_prefix)?;
+  check_delete_allowed(git_executor, &repository_path, &branch_prefix, std::slice::from_ref(&branch_name))?;
 
   // Verify branch exists before attempting deletion
   let exists = git_executor
@@ -40,10 +100,140 @@ pub fn delete_archived_branch_core(git_executor: &GitCommandExecutor, params: De
     return Err(anyhow::anyhow!("Branch does not exist"));
   }
 
+  // Delete the remote branches first, so a failed push keeps the local branch for another attempt
+  if delete_remote {
+    let remote_branches: Vec<String> = compute_remote_status_for_archived_branches(git_executor, &repository_path, &branch_prefix)?
+      .into_iter()
+      .filter(|status| status.branch_name == branch_name)
+      .map(|status| status.remote_branch)
+      .collect();
+    if !remote_branches.is_empty() {
+      check_delete_allowed(git_executor, &repository_path, &branch_prefix, &remote_branches)?;
+      ensure_remote_branches_integrated(git_executor, &repository_path, &branch_name, &remote_branches)?;
+
+      let refspecs: Vec<String> = remote_branches.iter().map(|remote_branch| format!(":refs/heads/{remote_branch}")).collect();
+      let mut args = vec!["push", PUSH_REMOTE];
+      args.extend(refspecs.iter().map(String::as_str));
+      let url = git_executor.execute_command(&["ls-remote", "--get-url", PUSH_REMOTE], &repository_path)?;
+      run_with_credentials(git_executor, &repository_path, &url, &args, askpass).context("Failed to delete remote branch")?;
+      info!(?remote_branches, "Deleted remote branches of archived branch");
+    }
+  }
+
+  // Notes copied to the branch commits would otherwise outlive them
+  let noted_commits = collect_branch_notes(git_executor, &repository_path, std::slice::from_ref(&branch_name)).unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to collect notes of the deleted branch");
+    Vec::new()
+  });
+
   // Delete branch
   git_executor
     .execute_command(&["branch", "-D", &branch_name], &repository_path)
     .map_err(|e| anyhow::anyhow!("Failed to delete branch: {}", e))?;
 
+  if let Err(e) = remove_commit_notes(git_executor, &repository_path, &noted_commits) {
+    warn!(error = %e, "Failed to remove notes of the deleted branch");
+  }
+
+  Ok(())
+}
+
+/// Preflight check before deleting remote branches: the detection cache must report the archived branch as integrated,
+/// and the remote branches must not contain commits the archived branch doesn't have (e.g. pushed from another machine).
+fn ensure_remote_branches_integrated(git_executor: &GitCommandExecutor, repository_path: &str, branch_name: &str, remote_branches: &[String]) -> Result<()> {
+  let tip = git_executor.execute_command(&["rev-parse", &format!("refs/heads/{branch_name}")], repository_path)?;
+  let integrated = CacheOps::new(git_executor, repository_path)
+    .read(&tip)
+    .is_some_and(|info| matches!(info.status, BranchIntegrationStatus::Integrated { .. }));
+  if !integrated {
+    return Err(anyhow::anyhow!("Branch is not integrated, the remote branch is kept"));
+  }
+
+  for remote_branch in remote_branches {
+    let range = format!("{tip}..refs/remotes/origin/{remote_branch}");
+    let unique_commits = git_executor.execute_command(&["rev-list", "--count", &range], repository_path)?;
+    if unique_commits != "0" {
+      return Err(anyhow::anyhow!(
+        "Remote branch {remote_branch} has {unique_commits} commits that are not in the archived branch, the remote branch is kept"
+      ));
+    }
+  }
   Ok(())
 }
+
+/// Delete many archived branches at once.
+/// All refs are deleted in a single `git update-ref --stdin` transaction, so either every branch is deleted or none.
+#[instrument(skip(git_executor, params, on_progress), fields(repo = %params.repository_path, count = params.branch_names.len()))]
+pub fn delete_archived_branches_core(
+  git_executor: &GitCommandExecutor,
+  params: DeleteArchivedBranchesParams,
+  on_progress: impl Fn(DeleteArchivedBranchesProgress),
+) -> Result<DeleteArchivedBranchesResult> {
+  let result = delete_archived_branches(git_executor, params, &on_progress);
+  match &result {
+    Ok(_) => on_progress(DeleteArchivedBranchesProgress::Completed),
+    Err(e) => on_progress(DeleteArchivedBranchesProgress::Error { message: e.to_string() }),
+  }
+  result
+}
+
+fn delete_archived_branches(
+  git_executor: &GitCommandExecutor,
+  params: DeleteArchivedBranchesParams,
+  on_progress: &impl Fn(DeleteArchivedBranchesProgress),
+) -> Result<DeleteArchivedBranchesResult> {
+  let DeleteArchivedBranchesParams {
+    repository_path,
+    branch_prefix,
+    mut branch_names,
+  } = params;
+
+  branch_names.sort();
+  branch_names.dedup();
+  for branch_name in &branch_names {
+    validate_archived_branch_name(branch_name, &branch_prefix)?;
+  }
+  check_delete_allowed(git_executor, &repository_path, &branch_prefix, &branch_names)?;
+  on_progress(DeleteArchivedBranchesProgress::Started { total: branch_names.len() as u32 });
+  if branch_names.is_empty() {
+    return Ok(DeleteArchivedBranchesResult { deleted_branches: Vec::new() });
+  }
+
+  // One call resolves all archived refs; the expected ids make the transaction fail if a branch moved meanwhile
+  let archived_refs = format!("refs/heads/{branch_prefix}/archived/");
+  let ref_ids: HashMap<String, String> = git_executor
+    .execute_command_lines(&["for-each-ref", "--format=%(refname) %(objectname)", &archived_refs], &repository_path)?
+    .into_iter()
+    .filter_map(|line| line.split_once(' ').map(|(ref_name, id)| (ref_name.to_string(), id.to_string())))
+    .collect();
+
+  let mut transaction = String::from("start\n");
+  for branch_name in &branch_names {
+    let ref_name = format!("refs/heads/{branch_name}");
+    let id = ref_ids.get(&ref_name).ok_or_else(|| anyhow::anyhow!("Branch does not exist: {branch_name}"))?;
+    writeln!(transaction, "delete {ref_name} {id}")?;
+  }
+  transaction.push_str("commit\n");
+
+  let _guard = ARCHIVE_MUTEX.lock().map_err(|e| anyhow::anyhow!("Failed to acquire archive mutex: {}", e))?;
+
+  // Notes copied to the branch commits would otherwise outlive them
+  let noted_commits = collect_branch_notes(git_executor, &repository_path, &branch_names).unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to collect notes of the deleted branches");
+    Vec::new()
+  });
+
+  git_executor
+    .execute_command_with_input(&["update-ref", "--stdin"], &repository_path, &transaction)
+    .map_err(|e| anyhow::anyhow!("Failed to delete branches: {}", e))?;
+  info!(count = branch_names.len(), "Deleted archived branches");
+  on_progress(DeleteArchivedBranchesProgress::BranchesDeleted {
+    branch_names: branch_names.clone(),
+  });
+
+  if let Err(e) = remove_commit_notes(git_executor, &repository_path, &noted_commits) {
+    warn!(error = %e, "Failed to remove notes of the deleted branches");
+  }
+
+  Ok(DeleteArchivedBranchesResult { deleted_branches: branch_names })
+}
@@ -0,0 +1,82 @@
+use crate::delete_archived_branch::{DeleteArchivedBranchesParams, DeleteArchivedBranchesProgress, delete_archived_branches_core};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use std::sync::Mutex;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+const ARCHIVED: [&str; 3] = ["user/archived/2025-01-01/parser", "user/archived/2025-01-01/lexer", "user/archived/2025-02-01/parser"];
+
+/// Archived branches with one commit each, not reachable from master
+fn setup_archived_branches(test_repo: &TestRepo) {
+  let initial = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  for (index, branch_name) in ARCHIVED.iter().enumerate() {
+    let commit = test_repo.create_commit(&format!("Change {index}"), "file.txt", &format!("{index}\n"));
+    test_repo.create_branch_at(branch_name, &commit).unwrap();
+    test_repo.reset_hard(&initial).unwrap();
+  }
+}
+
+fn params(test_repo: &TestRepo, branch_names: &[&str]) -> DeleteArchivedBranchesParams {
+  DeleteArchivedBranchesParams {
+    repository_path: test_repo.path().to_str().unwrap().to_string(),
+    branch_prefix: "user".to_string(),
+    branch_names: branch_names.iter().map(|name| name.to_string()).collect(),
+  }
+}
+
+#[test]
+fn test_delete_archived_branches_in_one_transaction() {
+  let test_repo = TestRepo::new();
+  setup_archived_branches(&test_repo);
+  test_repo.add_note(&test_repo.rev_parse(ARCHIVED[0]).unwrap(), "deleted").unwrap();
+  let kept_commit = test_repo.rev_parse(ARCHIVED[2]).unwrap();
+  test_repo.add_note(&kept_commit, "kept").unwrap();
+
+  let events = Mutex::new(Vec::new());
+  let result = delete_archived_branches_core(&GitCommandExecutor::new(), params(&test_repo, &ARCHIVED[..2]), |event| events.lock().unwrap().push(event)).unwrap();
+
+  assert_eq!(result.deleted_branches, vec![ARCHIVED[1].to_string(), ARCHIVED[0].to_string()]);
+  assert!(!test_repo.branch_exists(ARCHIVED[0]));
+  assert!(!test_repo.branch_exists(ARCHIVED[1]));
+  assert!(test_repo.branch_exists(ARCHIVED[2]));
+  // Notes of deleted commits are removed
+  assert_eq!(test_repo.show_note(&kept_commit).unwrap(), "kept");
+  assert_eq!(test_repo.list_notes_with_ref("refs/notes/commits").unwrap().len(), 1);
+
+  let events = events.into_inner().unwrap();
+  assert!(matches!(events.as_slice(), [
+    DeleteArchivedBranchesProgress::Started { total: 2 },
+    DeleteArchivedBranchesProgress::BranchesDeleted { branch_names },
+    DeleteArchivedBranchesProgress::Completed,
+  ] if branch_names.len() == 2));
+}
+
+#[test]
+fn test_delete_archived_branches_keeps_all_on_error() {
+  let test_repo = TestRepo::new();
+  setup_archived_branches(&test_repo);
+
+  let events = Mutex::new(Vec::new());
+  let error = delete_archived_branches_core(
+    &GitCommandExecutor::new(),
+    params(&test_repo, &[ARCHIVED[0], "user/archived/2025-01-01/missing"]),
+    |event| events.lock().unwrap().push(event),
+  )
+  .unwrap_err();
+  assert_eq!(error.to_string(), "Branch does not exist: user/archived/2025-01-01/missing");
+  assert!(matches!(events.into_inner().unwrap().last(), Some(DeleteArchivedBranchesProgress::Error { .. })));
+
+  // Branches outside the archive namespace are refused
+  let error = delete_archived_branches_core(&GitCommandExecutor::new(), params(&test_repo, &[ARCHIVED[0], "master"]), |_| {}).unwrap_err();
+  assert_eq!(error.to_string(), "Can only delete archived branches under the configured branch prefix");
+  // As are names a virtual branch can't have
+  for branch_name in ["user/archived/2025-01-01/../parser", "user/archived/2025-01-01/my parser", "user/archived//parser"] {
+    let error = delete_archived_branches_core(&GitCommandExecutor::new(), params(&test_repo, &[ARCHIVED[0], branch_name]), |_| {}).unwrap_err();
+    assert!(error.to_string().starts_with(&format!("Invalid branch name {branch_name}: ")), "{error}");
+  }
+
+  for branch_name in ARCHIVED {
+    assert!(test_repo.branch_exists(branch_name));
+  }
+}
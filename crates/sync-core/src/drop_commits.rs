@@ -0,0 +1,201 @@
+//! Dropping commits from the current branch, with a pre-flight report of what the drop would change downstream.
+//!
+//! The report replays the later commits with merge-tree on top of the remaining history, so it predicts the same
+//! conflicts as the rewrite without writing anything.
+
+use crate::commit_grouper::load_commit_grouper;
+use crate::remote_status::compute_remote_status_for_branch;
+use crate::sync::detect_baseline_branch;
+use anyhow::{Result, anyhow, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::amend_operations::drop_commits_from_head;
+use git_ops::commit_list::{Commit, get_commit_list};
+use git_ops::diff_options::DiffOptions;
+use git_ops::merge_tree::merge_trees;
+use git_ops::model::{CommitInfo, to_final_branch_name};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tracing::{debug, info, instrument};
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct DropCommitsParams {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  pub commit_ids: Vec<String>,
+  /// Only compute the report, HEAD is left as is
+  pub dry_run: bool,
+}
+
+/// Virtual branch losing some of its commits
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedBranchImpact {
+  pub branch_name: String,
+  pub dropped_count: u32,
+  /// Commits left in the branch; the next sync archives an empty branch according to the empty branch policy
+  pub remaining_count: u32,
+  /// Some dropped commits were pushed, so the remote branch can only be updated with a force push
+  pub requires_force_push: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct DropCommitsReport {
+  /// Later commits whose tree changes, i.e., all commits after the oldest dropped one unless the dropped changes are undone later
+  pub changed_commits: Vec<CommitInfo>,
+  /// Later commit that can't be replayed without the dropped ones; the drop fails if set
+  pub conflicting_commit: Option<CommitInfo>,
+  /// Branches in grouping order
+  pub affected_branches: Vec<DroppedBranchImpact>,
+  /// Dropped commits not assigned to any virtual branch
+  pub unassigned_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct DropCommitsResult {
+  pub report: DropCommitsReport,
+  /// New HEAD commit; `None` for a dry run or if a conflict was predicted
+  pub new_head: Option<String>,
+}
+
+fn to_commit_info(commit: &Commit) -> CommitInfo {
+  CommitInfo {
+    hash: commit.id.clone(),
+    message: commit.subject.clone(),
+  }
+}
+
+/// Report the downstream impact of dropping commits ahead of the baseline from the current branch and, unless it's a dry run
+/// or a conflict is predicted, drop them while preserving the working directory.
+#[instrument(skip(git_executor, params), fields(repository_path = %params.repository_path, commit_count = params.commit_ids.len(), dry_run = params.dry_run))]
+pub fn drop_commits_core(git_executor: &GitCommandExecutor, params: DropCommitsParams) -> Result<DropCommitsResult> {
+  let DropCommitsParams {
+    repository_path,
+    branch_prefix,
+    commit_ids,
+    dry_run,
+  } = params;
+  let repo = repository_path.as_str();
+  if commit_ids.is_empty() {
+    bail!("No commits specified to drop");
+  }
+
+  let current_branch = git_executor
+    .execute_command(&["symbolic-ref", "--short", "HEAD"], repo)
+    .map_err(|_| anyhow!("Can't drop commits with a detached HEAD"))?;
+  let baseline_branch = detect_baseline_branch(git_executor, repo, "master")?;
+  let commits = get_commit_list(git_executor, repo, &baseline_branch)?;
+
+  let drop_set: HashSet<&str> = commit_ids.iter().map(String::as_str).collect();
+  let commit_set: HashSet<&str> = commits.iter().map(|commit| commit.id.as_str()).collect();
+  if let Some(missing) = commit_ids.iter().find(|id| !commit_set.contains(id.as_str())) {
+    bail!("Commit {missing} is not ahead of {baseline_branch}");
+  }
+
+  let report = build_report(git_executor, repo, &branch_prefix, &baseline_branch, commits, &drop_set)?;
+  if dry_run || report.conflicting_commit.is_some() {
+    return Ok(DropCommitsResult { report, new_head: None });
+  }
+
+  let new_head = drop_commits_from_head(git_executor, repo, &commit_ids, &current_branch)
+    .map_err(|e| e.into_anyhow("Failed to drop commits from HEAD"))?
+    .new_head;
+  info!(%new_head, "Dropped commits from HEAD");
+  Ok(DropCommitsResult { report, new_head: Some(new_head) })
+}
+
+fn build_report(
+  git_executor: &GitCommandExecutor,
+  repo: &str,
+  branch_prefix: &str,
+  baseline_branch: &str,
+  commits: Vec<Commit>,
+  drop_set: &HashSet<&str>,
+) -> Result<DropCommitsReport> {
+  let (changed_commits, conflicting_commit) = replay_later_commits(git_executor, repo, &commits, drop_set)?;
+
+  let mut grouper = load_commit_grouper(git_executor, repo);
+  grouper.add_commits(git_executor, repo, commits);
+  let (grouped_commits, unassigned_commits, _) = grouper.finish();
+
+  let mut affected_branches = Vec::new();
+  for (branch_name, branch_commits) in grouped_commits {
+    // Position of the oldest dropped commit, as the branch is rebuilt from there on
+    let Some(first_dropped) = branch_commits.iter().position(|commit| drop_set.contains(commit.id.as_str())) else {
+      continue;
+    };
+    let dropped_count = branch_commits.iter().filter(|commit| drop_set.contains(commit.id.as_str())).count() as u32;
+    let commit_count = branch_commits.len() as u32;
+
+    let full_branch_name = to_final_branch_name(branch_prefix, &branch_name)?;
+    let (_, exit_code) = git_executor.execute_command_with_status(&["rev-parse", "--verify", "--quiet", &format!("refs/heads/{full_branch_name}")], repo)?;
+    // A branch that was never synced was never pushed either
+    let requires_force_push = exit_code == 0 && {
+      let remote_status = compute_remote_status_for_branch(git_executor, repo, &full_branch_name, &branch_name, None, commit_count, baseline_branch)?;
+      // Only the newest commits of the branch can be unpushed, dropping an older one rewrites pushed history
+      let pushed_count = commit_count.saturating_sub(remote_status.unpushed_commits.len() as u32);
+      remote_status.remote_exists && (first_dropped as u32) < pushed_count
+    };
+    affected_branches.push(DroppedBranchImpact {
+      branch_name,
+      dropped_count,
+      remaining_count: commit_count - dropped_count,
+      requires_force_push,
+    });
+  }
+
+  let unassigned_count = unassigned_commits.iter().filter(|commit| drop_set.contains(commit.id.as_str())).count() as u32;
+  Ok(DropCommitsReport {
+    changed_commits,
+    conflicting_commit,
+    affected_branches,
+    unassigned_count,
+  })
+}
+
+/// Replay the commits after the oldest dropped one as the rewrite does, returning the commits whose tree changes and the
+/// first commit that conflicts (the replay stops there)
+fn replay_later_commits(git_executor: &GitCommandExecutor, repo: &str, commits: &[Commit], drop_set: &HashSet<&str>) -> Result<(Vec<CommitInfo>, Option<CommitInfo>)> {
+  let Some(start) = commits.iter().position(|commit| drop_set.contains(commit.id.as_str())) else {
+    return Ok((Vec::new(), None));
+  };
+  let mut original_parent_tree = match start.checked_sub(1) {
+    Some(index) => commits[index].tree_id.clone(),
+    None => {
+      let parent = commits[start].parent_id.as_deref().ok_or_else(|| anyhow!("Can't drop the root commit"))?;
+      git_executor.resolve_tree_id(repo, parent)?
+    }
+  };
+  let mut current_tree = original_parent_tree.clone();
+
+  let mut changed_commits = Vec::new();
+  for commit in &commits[start..] {
+    if drop_set.contains(commit.id.as_str()) {
+      original_parent_tree = commit.tree_id.clone();
+      continue;
+    }
+
+    let new_tree = if current_tree == original_parent_tree {
+      commit.tree_id.clone()
+    } else {
+      let merge = merge_trees(git_executor, repo, &original_parent_tree, &current_tree, &commit.tree_id, &DiffOptions::default())?;
+      if merge.has_conflicts() {
+        debug!(commit_id = %commit.id, "Commit can't be replayed without the dropped commits");
+        return Ok((changed_commits, Some(to_commit_info(commit))));
+      }
+      merge.tree_id
+    };
+    if new_tree != commit.tree_id {
+      changed_commits.push(to_commit_info(commit));
+    }
+    original_parent_tree = commit.tree_id.clone();
+    current_tree = new_tree;
+  }
+  Ok((changed_commits, None))
+}
@@ -0,0 +1,87 @@
+use crate::drop_commits::{DropCommitsParams, drop_commits_core};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn setup_baseline(test_repo: &TestRepo) {
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+  test_repo.git(&["update-ref", "refs/remotes/origin/master", &baseline]);
+}
+
+fn drop_params(test_repo: &TestRepo, commit_ids: &[&str], dry_run: bool) -> DropCommitsParams {
+  DropCommitsParams {
+    repository_path: test_repo.path_str().to_string(),
+    branch_prefix: "test".to_string(),
+    commit_ids: commit_ids.iter().map(|id| id.to_string()).collect(),
+    dry_run,
+  }
+}
+
+#[test]
+fn test_drop_commits_report() {
+  let test_repo = TestRepo::new();
+  setup_baseline(&test_repo);
+  let parser = test_repo.create_commit("(parser) Add parser", "parser.rs", "fn parse() {}\n");
+  let lexer = test_repo.create_commit("(lexer) Add lexer", "lexer.rs", "fn lex() {}\n");
+  test_repo.create_commit("(parser) Handle errors", "parser.rs", "fn parse() -> Result<()> {}\n");
+  // `test/virtual/lexer` was synced and pushed
+  test_repo.create_branch_at("test/virtual/lexer", &lexer).unwrap();
+  test_repo.git(&["update-ref", "refs/remotes/origin/test/virtual/lexer", &lexer]);
+  let head = test_repo.rev_parse("HEAD").unwrap();
+
+  let result = drop_commits_core(&GitCommandExecutor::new(), drop_params(&test_repo, &[&lexer], true)).unwrap();
+
+  assert_eq!(result.new_head, None);
+  assert_eq!(test_repo.rev_parse("HEAD").unwrap(), head);
+  let report = result.report;
+  assert_eq!(
+    report.changed_commits.iter().map(|commit| commit.message.as_str()).collect::<Vec<_>>(),
+    vec!["(parser) Handle errors"]
+  );
+  assert!(report.conflicting_commit.is_none());
+  assert_eq!(report.unassigned_count, 0);
+  assert_eq!(report.affected_branches.len(), 1);
+  let lexer_impact = &report.affected_branches[0];
+  assert_eq!(lexer_impact.branch_name, "lexer");
+  assert_eq!((lexer_impact.dropped_count, lexer_impact.remaining_count), (1, 0));
+  assert!(lexer_impact.requires_force_push);
+
+  // Dropping an unpushed commit of a never synced branch
+  let result = drop_commits_core(&GitCommandExecutor::new(), drop_params(&test_repo, &[&parser], true)).unwrap();
+  assert!(!result.report.affected_branches[0].requires_force_push);
+}
+
+#[test]
+fn test_drop_commits() {
+  let test_repo = TestRepo::new();
+  setup_baseline(&test_repo);
+  test_repo.create_commit("(parser) Add parser", "parser.rs", "fn parse() {}\n");
+  let lexer = test_repo.create_commit("(lexer) Add lexer", "lexer.rs", "fn lex() {}\n");
+  test_repo.create_commit("(parser) Handle errors", "parser.rs", "fn parse() -> Result<()> {}\n");
+
+  let result = drop_commits_core(&GitCommandExecutor::new(), drop_params(&test_repo, &[&lexer], false)).unwrap();
+
+  assert_eq!(result.new_head, Some(test_repo.rev_parse("HEAD").unwrap()));
+  assert_eq!(
+    test_repo.get_commit_messages(3),
+    vec!["(parser) Handle errors".to_string(), "(parser) Add parser".to_string(), "Initial commit".to_string()]
+  );
+  assert_eq!(test_repo.git(&["ls-tree", "--name-only", "HEAD"]), "README.md\nparser.rs");
+}
+
+#[test]
+fn test_drop_commits_with_predicted_conflict() {
+  let test_repo = TestRepo::new();
+  setup_baseline(&test_repo);
+  let config = test_repo.create_commit("(config) Add config", "config.txt", "timeout=30\n");
+  let change = test_repo.create_commit("(config) Lower timeout", "config.txt", "timeout=10\n");
+  let head = test_repo.rev_parse("HEAD").unwrap();
+
+  let result = drop_commits_core(&GitCommandExecutor::new(), drop_params(&test_repo, &[&config], false)).unwrap();
+
+  assert_eq!(result.new_head, None);
+  assert_eq!(result.report.conflicting_commit.map(|commit| commit.hash), Some(change));
+  assert_eq!(test_repo.rev_parse("HEAD").unwrap(), head);
+}
@@ -0,0 +1,47 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::git_config::get_config_value;
+use tracing::{instrument, warn};
+
+/// Git config key holding the per-repository empty branch policy
+pub const EMPTY_BRANCH_POLICY_CONFIG_KEY: &str = "branchdeck.emptyBranchPolicy";
+
+/// What sync does with a group whose commits are all already integrated into the baseline,
+/// so that the virtual branch would contain no commits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmptyBranchPolicy {
+  /// Create or keep the virtual branch as usual
+  #[default]
+  Keep,
+  /// Don't create or update the virtual branch, the branch is reported as integrated
+  Skip,
+  /// Like `Skip`, and an existing virtual branch is moved to the archive
+  Archive,
+}
+
+impl EmptyBranchPolicy {
+  /// Parse a config value (`keep`, `skip` or `archive`)
+  pub fn from_config_value(value: &str) -> Option<Self> {
+    match value.trim().to_ascii_lowercase().as_str() {
+      "keep" => Some(Self::Keep),
+      "skip" => Some(Self::Skip),
+      "archive" => Some(Self::Archive),
+      _ => None,
+    }
+  }
+}
+
+/// Read the empty branch policy from git config, falling back to the default for missing or unknown values
+#[instrument(skip(git_executor))]
+pub fn load_empty_branch_policy(git_executor: &GitCommandExecutor, repository_path: &str) -> EmptyBranchPolicy {
+  match get_config_value(git_executor, repository_path, EMPTY_BRANCH_POLICY_CONFIG_KEY) {
+    Ok(Some(value)) => EmptyBranchPolicy::from_config_value(&value).unwrap_or_else(|| {
+      warn!(value, "Unknown {EMPTY_BRANCH_POLICY_CONFIG_KEY} value, keeping empty branches");
+      EmptyBranchPolicy::default()
+    }),
+    Ok(None) => EmptyBranchPolicy::default(),
+    Err(e) => {
+      warn!(error = %e, "Failed to read empty branch policy, keeping empty branches");
+      EmptyBranchPolicy::default()
+    }
+  }
+}
@@ -0,0 +1,123 @@
+//! Diagnostics for commit subjects whose branch prefix is ambiguous, so surprising group assignments can be explained.
+//!
+//! Grouping itself stays as is: the first parenthesized prefix wins, then a leading issue reference. The diagnostic only
+//! describes how the subject was read and which other branch names it could have been read as.
+
+use crate::path_scope::PathScopeViolation;
+use git_ops::model::sanitize_branch_name;
+use sync_types::{GroupingAmbiguity, GroupingDiagnostic};
+use sync_utils::issue_pattern::find_issue_number;
+
+/// Diagnostics kept per sync, a history full of ambiguous subjects shouldn't bloat the event
+pub const MAX_GROUPING_DIAGNOSTICS: usize = 1000;
+
+/// Describe how the prefix of `subject_for_grouping` (the subject without autosquash markers) was parsed, `None` if it is unambiguous
+pub(crate) fn diagnose_grouping(commit_id: &str, subject: &str, subject_for_grouping: &str, branch_name: Option<&str>) -> Option<GroupingDiagnostic> {
+  let mut ambiguities = Vec::new();
+  let mut alternatives: Vec<String> = Vec::new();
+  let mut notes = Vec::new();
+
+  if let Some(rest) = subject_for_grouping.strip_prefix('(') {
+    match rest.find(')') {
+      None => {
+        ambiguities.push(GroupingAmbiguity::MalformedParentheses);
+        notes.push("the opening parenthesis is never closed, so it is not a prefix".to_string());
+      }
+      Some(0) => {
+        ambiguities.push(GroupingAmbiguity::EmptyPrefix);
+        notes.push("the parentheses are empty, so they are not a prefix".to_string());
+      }
+      Some(close_paren_pos) => {
+        let prefix = &rest[..close_paren_pos];
+        if prefix.contains('(') {
+          ambiguities.push(GroupingAmbiguity::MalformedParentheses);
+          notes.push("the parentheses are nested, the prefix ends at the first closing parenthesis".to_string());
+          alternatives.push(sanitize_branch_name(prefix.trim_start_matches('(').trim()));
+        } else if sanitize_branch_name(prefix.trim()) != prefix {
+          ambiguities.push(GroupingAmbiguity::SanitizedPrefix);
+          notes.push(format!("\"{prefix}\" is not a valid branch name and was adjusted"));
+        }
+
+        let message = rest[close_paren_pos + 1..].trim_start();
+        if let Some(second_prefix) = parenthesized_name(message) {
+          ambiguities.push(GroupingAmbiguity::MultiplePrefixes);
+          notes.push(format!("only the first prefix is used, \"({second_prefix})\" is part of the message"));
+          alternatives.push(sanitize_branch_name(second_prefix));
+        } else if let Some(issue_number) = find_issue_number(message) {
+          ambiguities.push(GroupingAmbiguity::PrefixWithIssueReference);
+          notes.push(format!("the prefix takes precedence over the issue reference {issue_number}"));
+          alternatives.push(issue_number.to_string());
+        }
+      }
+    }
+  } else if let Some(name) = leading_parenthesized_name(subject_for_grouping) {
+    ambiguities.push(GroupingAmbiguity::PrefixNotAtStart);
+    notes.push(format!("\"({name})\" is not at the start of the subject, so it is not a prefix"));
+    alternatives.push(sanitize_branch_name(name));
+  }
+
+  if ambiguities.is_empty() {
+    return None;
+  }
+
+  alternatives.retain(|alternative| !alternative.is_empty() && Some(alternative.as_str()) != branch_name);
+  alternatives.dedup();
+  Some(GroupingDiagnostic {
+    commit_id: commit_id.to_string(),
+    subject: subject.to_string(),
+    branch_name: branch_name.map(str::to_string),
+    ambiguities,
+    alternatives,
+    explanation: format!("{}: {}", describe_outcome(branch_name), notes.join("; ")),
+  })
+}
+
+/// Add a path scope violation to the diagnostic of the commit, or describe it on its own if the subject is unambiguous
+pub(crate) fn add_path_scope_violation(
+  diagnostic: Option<GroupingDiagnostic>,
+  commit_id: &str,
+  subject: &str,
+  branch_name: Option<&str>,
+  violation: PathScopeViolation,
+) -> GroupingDiagnostic {
+  match diagnostic {
+    Some(mut diagnostic) => {
+      diagnostic.ambiguities.push(violation.ambiguity);
+      diagnostic.explanation = format!("{}; {}", diagnostic.explanation, violation.note);
+      diagnostic
+    }
+    None => GroupingDiagnostic {
+      commit_id: commit_id.to_string(),
+      subject: subject.to_string(),
+      branch_name: branch_name.map(str::to_string),
+      ambiguities: vec![violation.ambiguity],
+      alternatives: Vec::new(),
+      explanation: format!("{}: {}", describe_outcome(branch_name), violation.note),
+    },
+  }
+}
+
+fn describe_outcome(branch_name: Option<&str>) -> String {
+  match branch_name {
+    Some(branch_name) => format!("Grouped into \"{branch_name}\""),
+    None => "Not grouped".to_string(),
+  }
+}
+
+/// Name in parentheses at the start of `text`, if any
+fn parenthesized_name(text: &str) -> Option<&str> {
+  let rest = text.strip_prefix('(')?;
+  let name = rest[..rest.find(')')?].trim();
+  (!name.is_empty()).then_some(name)
+}
+
+/// Single-word name in parentheses within or right after the first word, e.g. `fix(ui): …`, `feat: (ui) …` or `ABC-1 (ui) …`.
+/// Parentheses later in the subject are usually just part of the message.
+fn leading_parenthesized_name(subject: &str) -> Option<&str> {
+  let first_word_len = subject.find(char::is_whitespace).unwrap_or(subject.len());
+  let candidate = match subject[..first_word_len].find('(') {
+    Some(open_paren_pos) => &subject[open_paren_pos..],
+    None => subject[first_word_len..].trim_start(),
+  };
+  parenthesized_name(candidate).filter(|name| !name.contains(char::is_whitespace))
+}
@@ -0,0 +1,96 @@
+use crate::commit_grouper::CommitGrouper;
+use crate::grouping_diagnostics::diagnose_grouping;
+use git_ops::commit_list::Commit;
+use pretty_assertions::assert_eq;
+use sync_types::{GroupingAmbiguity, GroupingDiagnostic};
+use test_log::test;
+
+fn create_test_commit(id: &str, subject: &str) -> Commit {
+  Commit {
+    id: id.to_string(),
+    subject: subject.to_string(),
+    stripped_subject: subject.to_string(),
+    message: subject.to_string(),
+    author_name: "Test Author".to_string(),
+    author_email: "test@example.com".to_string(),
+    author_timestamp: 1234567890,
+    committer_timestamp: 1234567890,
+    parent_id: None,
+    tree_id: "tree123".to_string(),
+    note: None,
+    mapped_commit_id: None,
+  }
+}
+
+/// `(branch name, ambiguities, alternatives)` of the diagnostic of a subject grouped into `branch_name`
+fn diagnose(subject: &str, branch_name: Option<&str>) -> Option<(Option<String>, Vec<GroupingAmbiguity>, Vec<String>)> {
+  diagnose_grouping("abc", subject, subject, branch_name).map(|diagnostic| (diagnostic.branch_name, diagnostic.ambiguities, diagnostic.alternatives))
+}
+
+#[test]
+fn test_unambiguous_subjects_have_no_diagnostic() {
+  assert_eq!(diagnose("(ui) Add button", Some("ui")), None);
+  assert_eq!(diagnose("ABC-123 Fix crash", Some("ABC-123")), None);
+  assert_eq!(diagnose("Update dependencies (minor)", None), None);
+  assert_eq!(diagnose("Plain message", None), None);
+}
+
+#[test]
+fn test_ambiguous_subjects() {
+  assert_eq!(
+    diagnose("(ui)(api) Share the client", Some("ui")),
+    Some((Some("ui".to_string()), vec![GroupingAmbiguity::MultiplePrefixes], vec!["api".to_string()]))
+  );
+  assert_eq!(
+    diagnose("(ui) ABC-123 Fix layout", Some("ui")),
+    Some((Some("ui".to_string()), vec![GroupingAmbiguity::PrefixWithIssueReference], vec!["ABC-123".to_string()]))
+  );
+  assert_eq!(
+    diagnose("((nested)) message", Some("(nested")),
+    Some((Some("(nested".to_string()), vec![GroupingAmbiguity::MalformedParentheses], vec!["nested".to_string()]))
+  );
+  assert_eq!(diagnose("(no closing paren", None), Some((None, vec![GroupingAmbiguity::MalformedParentheses], Vec::new())));
+  assert_eq!(diagnose("() message", None), Some((None, vec![GroupingAmbiguity::EmptyPrefix], Vec::new())));
+  assert_eq!(
+    diagnose("(ui dispatcher) Add queue", Some("ui-dispatcher")),
+    Some((Some("ui-dispatcher".to_string()), vec![GroupingAmbiguity::SanitizedPrefix], Vec::new()))
+  );
+  assert_eq!(
+    diagnose("fix(ui): Align button", None),
+    Some((None, vec![GroupingAmbiguity::PrefixNotAtStart], vec!["ui".to_string()]))
+  );
+  assert_eq!(
+    diagnose("ABC-123 (ui) Fix layout", Some("ABC-123")),
+    Some((Some("ABC-123".to_string()), vec![GroupingAmbiguity::PrefixNotAtStart], vec!["ui".to_string()]))
+  );
+}
+
+#[test]
+fn test_grouper_collects_diagnostics() {
+  let mut grouper = CommitGrouper::new();
+  grouper.add_commit(create_test_commit("c1", "(ui) Add button"));
+  grouper.add_commit(create_test_commit("c2", "fixup! (ui)(api) Share the client"));
+  grouper.add_commit(create_test_commit("c3", "feat: (api) Add endpoint"));
+
+  assert_eq!(
+    grouper.diagnostics,
+    vec![
+      GroupingDiagnostic {
+        commit_id: "c2".to_string(),
+        subject: "fixup! (ui)(api) Share the client".to_string(),
+        branch_name: Some("ui".to_string()),
+        ambiguities: vec![GroupingAmbiguity::MultiplePrefixes],
+        alternatives: vec!["api".to_string()],
+        explanation: "Grouped into \"ui\": only the first prefix is used, \"(api)\" is part of the message".to_string(),
+      },
+      GroupingDiagnostic {
+        commit_id: "c3".to_string(),
+        subject: "feat: (api) Add endpoint".to_string(),
+        branch_name: None,
+        ambiguities: vec![GroupingAmbiguity::PrefixNotAtStart],
+        alternatives: vec!["api".to_string()],
+        explanation: "Not grouped: \"(api)\" is not at the start of the subject, so it is not a prefix".to_string(),
+      },
+    ]
+  );
+}
@@ -0,0 +1,246 @@
+//! User scripts run at fixed points of a sync, configured per repository in git config:
+//!
+//! - `branchdeck.hook.preSync`: before anything is read, a non-zero exit aborts the sync
+//! - `branchdeck.hook.postGroup`: after commits are grouped into virtual branches
+//! - `branchdeck.hook.postBranchCreate`: for every virtual branch ref the sync created or updated
+//! - `branchdeck.hook.postSync`: after the sync finished, successfully or not
+//!
+//! `postBranchCreate` and `postSync` run once the phases the sync continues in the background are done, on a blocking
+//! thread, so a slow hook neither stalls the branch workers nor delays the sync result. The next sync waits for them.
+//!
+//! Each script is run by the shell in the repository directory, with the hook context as JSON on stdin and the hook name
+//! in `BRANCH_DECK_HOOK`. Only a failing `preSync` hook fails the sync, failures of the other hooks are logged.
+
+use crate::pre_push::shell;
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::{CommandError, ErrorCode};
+use git_ops::git_config::get_config_value;
+use git_ops::model::BranchSyncStatus;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::Stdio;
+use std::sync::{Mutex, PoisonError};
+use tracing::{debug, info, instrument, warn};
+
+/// Number of trailing output lines kept in the `output` context of a `hookFailed` error
+const FAILURE_OUTPUT_LINES: usize = 20;
+
+/// Point of the sync lifecycle where a hook runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HookPoint {
+  PreSync,
+  PostGroup,
+  PostBranchCreate,
+  PostSync,
+}
+
+impl HookPoint {
+  pub const ALL: [HookPoint; 4] = [HookPoint::PreSync, HookPoint::PostGroup, HookPoint::PostBranchCreate, HookPoint::PostSync];
+
+  pub fn as_str(self) -> &'static str {
+    match self {
+      HookPoint::PreSync => "preSync",
+      HookPoint::PostGroup => "postGroup",
+      HookPoint::PostBranchCreate => "postBranchCreate",
+      HookPoint::PostSync => "postSync",
+    }
+  }
+
+  /// Git config key with the command of the hook, e.g. `branchdeck.hook.preSync`
+  pub fn config_key(self) -> String {
+    format!("branchdeck.hook.{}", self.as_str())
+  }
+}
+
+/// Summary of a virtual branch passed to the `postGroup` hook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupedBranchSummary {
+  pub name: String,
+  pub commit_count: usize,
+}
+
+/// Context written as JSON to the stdin of a hook, tagged with the hook name
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "hook", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum HookContext<'a> {
+  PreSync {
+    repository_path: &'a str,
+    branch_prefix: &'a str,
+  },
+  PostGroup {
+    repository_path: &'a str,
+    branch_prefix: &'a str,
+    baseline_branch: &'a str,
+    branches: Vec<GroupedBranchSummary>,
+    unassigned_commit_count: usize,
+  },
+  PostBranchCreate {
+    repository_path: &'a str,
+    branch_name: &'a str,
+    full_branch_name: &'a str,
+    status: &'a BranchSyncStatus,
+    head_commit: &'a str,
+  },
+  PostSync {
+    repository_path: &'a str,
+    branch_prefix: &'a str,
+    success: bool,
+    error: Option<String>,
+  },
+}
+
+impl HookContext<'_> {
+  pub fn point(&self) -> HookPoint {
+    match self {
+      HookContext::PreSync { .. } => HookPoint::PreSync,
+      HookContext::PostGroup { .. } => HookPoint::PostGroup,
+      HookContext::PostBranchCreate { .. } => HookPoint::PostBranchCreate,
+      HookContext::PostSync { .. } => HookPoint::PostSync,
+    }
+  }
+
+  fn repository_path(&self) -> &str {
+    match self {
+      HookContext::PreSync { repository_path, .. }
+      | HookContext::PostGroup { repository_path, .. }
+      | HookContext::PostBranchCreate { repository_path, .. }
+      | HookContext::PostSync { repository_path, .. } => repository_path,
+    }
+  }
+}
+
+/// Commands of the configured hooks
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncHooks {
+  commands: HashMap<HookPoint, String>,
+}
+
+impl SyncHooks {
+  pub fn with_command(mut self, point: HookPoint, command: impl Into<String>) -> Self {
+    self.commands.insert(point, command.into());
+    self
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.commands.is_empty()
+  }
+
+  /// Run the hook of the context point if one is configured.
+  /// Fails with a `hookFailed` error if the hook exits with a non-zero code.
+  #[instrument(skip(self, context), fields(hook = context.point().as_str()))]
+  pub fn run(&self, context: &HookContext<'_>) -> Result<()> {
+    let point = context.point();
+    let Some(command) = self.commands.get(&point) else {
+      return Ok(());
+    };
+
+    let input = serde_json::to_string(context)?;
+    info!(command, "Running sync hook");
+    let (exit_code, output) = run_hook_command(command, context.repository_path(), point, input)?;
+    if exit_code != 0 {
+      return Err(
+        CommandError::new(ErrorCode::HookFailed, format!("Hook {} failed with exit code {exit_code}", point.as_str()))
+          .with_context("hook", point.as_str())
+          .with_context("exitCode", exit_code.to_string())
+          .with_context("output", output)
+          .into_anyhow(),
+      );
+    }
+    debug!(output, "Sync hook succeeded");
+    Ok(())
+  }
+
+  /// Run a hook whose failure must not fail the sync
+  pub fn run_logged(&self, context: &HookContext<'_>) {
+    if let Err(e) = self.run(context) {
+      warn!(hook = context.point().as_str(), error = %e, "Sync hook failed");
+    }
+  }
+}
+
+/// Branch created or updated by a sync, its `postBranchCreate` hook runs after the sync
+#[derive(Debug)]
+struct CreatedBranch {
+  branch_name: String,
+  full_branch_name: String,
+  status: BranchSyncStatus,
+  head_commit: String,
+}
+
+/// `postBranchCreate` hooks queued by the branch workers
+#[derive(Debug, Default)]
+pub struct BranchHookQueue {
+  branches: Mutex<Vec<CreatedBranch>>,
+}
+
+impl BranchHookQueue {
+  pub fn push(&self, branch_name: &str, full_branch_name: &str, status: BranchSyncStatus, head_commit: String) {
+    self.branches.lock().unwrap_or_else(PoisonError::into_inner).push(CreatedBranch {
+      branch_name: branch_name.to_string(),
+      full_branch_name: full_branch_name.to_string(),
+      status,
+      head_commit,
+    });
+  }
+
+  /// Run the queued hooks in the order the branches were synced, failures are logged
+  pub fn run_logged(&self, hooks: &SyncHooks, repository_path: &str) {
+    let branches = std::mem::take(&mut *self.branches.lock().unwrap_or_else(PoisonError::into_inner));
+    for branch in &branches {
+      hooks.run_logged(&HookContext::PostBranchCreate {
+        repository_path,
+        branch_name: &branch.branch_name,
+        full_branch_name: &branch.full_branch_name,
+        status: &branch.status,
+        head_commit: &branch.head_commit,
+      });
+    }
+  }
+}
+
+/// Load the hook commands from git config, unreadable keys are treated as not set
+#[instrument(skip(git_executor))]
+pub fn load_sync_hooks(git_executor: &GitCommandExecutor, repository_path: &str) -> SyncHooks {
+  let mut hooks = SyncHooks::default();
+  for point in HookPoint::ALL {
+    match get_config_value(git_executor, repository_path, &point.config_key()) {
+      Ok(Some(command)) if !command.trim().is_empty() => hooks = hooks.with_command(point, command),
+      Ok(_) => {}
+      Err(e) => warn!(hook = point.as_str(), error = %e, "Failed to read sync hook config"),
+    }
+  }
+  hooks
+}
+
+/// Run the hook command with the JSON input, returns the exit code and the last lines of the combined output
+fn run_hook_command(command: &str, repository_path: &str, point: HookPoint, input: String) -> Result<(i32, String)> {
+  let mut child = shell(command)
+    .current_dir(repository_path)
+    .env("BRANCH_DECK_HOOK", point.as_str())
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|e| anyhow!("Failed to start hook {}: {e}", point.as_str()))?;
+
+  // Written from another thread, so a hook producing a lot of output before reading its input can't deadlock.
+  // The hook may exit without reading the input, a broken pipe is expected then.
+  let stdin = child.stdin.take();
+  let writer = std::thread::spawn(move || {
+    if let Some(mut stdin) = stdin {
+      let _ = stdin.write_all(input.as_bytes());
+    }
+  });
+  let output = child.wait_with_output()?;
+  let _ = writer.join();
+
+  let combined = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+  let lines: Vec<&str> = combined.lines().collect();
+  let tail = lines[lines.len().saturating_sub(FAILURE_OUTPUT_LINES)..].join("\n");
+  // Killed by a signal has no exit code
+  Ok((output.status.code().unwrap_or(-1), tail))
+}
@@ -0,0 +1,139 @@
+use crate::hooks::{GroupedBranchSummary, HookContext, HookPoint, SyncHooks, load_sync_hooks};
+use crate::phase_budget::wait_for_background_phases;
+use crate::sync::{SyncOptions, sync_branches};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::{CommandError, ErrorCode};
+use git_ops::model::BranchSyncStatus;
+use pretty_assertions::assert_eq;
+use sync_test_utils::TestReporter;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_load_sync_hooks() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  assert!(load_sync_hooks(&git_executor, test_repo.path().to_str().unwrap()).is_empty());
+
+  test_repo.set_config("branchdeck.hook.preSync", "./scripts/validate.sh").unwrap();
+  test_repo.set_config("branchdeck.hook.postSync", "  ").unwrap();
+  assert_eq!(
+    load_sync_hooks(&git_executor, test_repo.path().to_str().unwrap()),
+    SyncHooks::default().with_command(HookPoint::PreSync, "./scripts/validate.sh")
+  );
+}
+
+#[test]
+fn test_hook_context_json() {
+  let context = HookContext::PostGroup {
+    repository_path: "/repo",
+    branch_prefix: "user",
+    baseline_branch: "origin/main",
+    branches: vec![GroupedBranchSummary {
+      name: "feature".to_string(),
+      commit_count: 2,
+    }],
+    unassigned_commit_count: 1,
+  };
+  assert_eq!(
+    serde_json::to_string(&context).unwrap(),
+    r#"{"hook":"postGroup","repositoryPath":"/repo","branchPrefix":"user","baselineBranch":"origin/main","branches":[{"name":"feature","commitCount":2}],"unassignedCommitCount":1}"#
+  );
+
+  let context = HookContext::PostBranchCreate {
+    repository_path: "/repo",
+    branch_name: "feature",
+    full_branch_name: "user/virtual/feature",
+    status: &BranchSyncStatus::Created,
+    head_commit: "abc",
+  };
+  assert_eq!(
+    serde_json::to_string(&context).unwrap(),
+    r#"{"hook":"postBranchCreate","repositoryPath":"/repo","branchName":"feature","fullBranchName":"user/virtual/feature","status":"Created","headCommit":"abc"}"#
+  );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_failing_hook() {
+  let test_repo = TestRepo::new();
+  let repository_path = test_repo.path().to_str().unwrap();
+  let hooks = SyncHooks::default().with_command(HookPoint::PreSync, "echo \"$BRANCH_DECK_HOOK rejected\" >&2; exit 2");
+
+  let error = CommandError::from(
+    hooks
+      .run(&HookContext::PreSync {
+        repository_path,
+        branch_prefix: "test",
+      })
+      .unwrap_err(),
+  );
+  assert_eq!(error.code, ErrorCode::HookFailed);
+  assert_eq!(error.context["hook"], "preSync");
+  assert_eq!(error.context["exitCode"], "2");
+  assert_eq!(error.context["output"], "preSync rejected");
+
+  // Hooks that aren't configured don't run
+  hooks
+    .run(&HookContext::PostSync {
+      repository_path,
+      branch_prefix: "test",
+      success: true,
+      error: None,
+    })
+    .unwrap();
+}
+
+#[cfg(unix)]
+#[test(tokio::test)]
+async fn test_sync_runs_hooks() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+  let repository_path = test_repo.path().to_str().unwrap();
+  let log_path = test_repo.path().join(".git/hooks.log");
+  // Every hook appends its JSON context to the log
+  let log_command = format!("cat >> '{}'; echo >> '{}'", log_path.display(), log_path.display());
+  let hooks = HookPoint::ALL
+    .into_iter()
+    .fold(SyncHooks::default(), |hooks, point| hooks.with_command(point, &log_command));
+
+  let options = SyncOptions {
+    hooks: Some(hooks),
+    ..Default::default()
+  };
+  sync_branches(&GitCommandExecutor::new(), repository_path, "test", TestReporter::new(), options).await?;
+  // postBranchCreate and postSync run after the sync returned
+  wait_for_background_phases(repository_path).await;
+
+  let log = std::fs::read_to_string(&log_path)?;
+  let hooks: Vec<String> = log
+    .lines()
+    .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["hook"].as_str().unwrap().to_string())
+    .collect();
+  assert_eq!(hooks, vec!["preSync", "postGroup", "postBranchCreate", "postSync"]);
+  assert!(log.contains(r#""fullBranchName":"test/virtual/feature","status":"Created""#), "{log}");
+  assert!(log.contains(r#""success":true"#), "{log}");
+  Ok(())
+}
+
+#[cfg(unix)]
+#[test(tokio::test)]
+async fn test_failing_pre_sync_hook_aborts_sync() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+  test_repo.set_config("branchdeck.hook.preSync", "exit 1").unwrap();
+
+  let result = sync_branches(
+    &GitCommandExecutor::new(),
+    test_repo.path().to_str().unwrap(),
+    "test",
+    TestReporter::new(),
+    SyncOptions::default(),
+  )
+  .await;
+
+  assert_eq!(CommandError::from(result.unwrap_err()).code, ErrorCode::HookFailed);
+  assert!(!test_repo.branch_exists("test/virtual/feature"));
+}
@@ -0,0 +1,270 @@
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use git_ops::git_config::{get_config_value, set_local_config_value};
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, warn};
+
+/// Per-remote git config keys (`remote.<name>.<key>`), so renaming or removing the remote carries them along
+pub const PROVIDER_CONFIG_KEY: &str = "branchdeckProvider";
+pub const API_BASE_URL_CONFIG_KEY: &str = "branchdeckApiBaseUrl";
+pub const CA_CERT_PATH_CONFIG_KEY: &str = "branchdeckCaCertPath";
+
+/// Code hosting service behind a remote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "lowercase")]
+pub enum HostingProviderKind {
+  GitHub,
+  GitLab,
+}
+
+impl HostingProviderKind {
+  /// Parse a config value (`github` or `gitlab`)
+  pub fn from_config_value(value: &str) -> Option<Self> {
+    match value.trim().to_ascii_lowercase().as_str() {
+      "github" => Some(Self::GitHub),
+      "gitlab" => Some(Self::GitLab),
+      _ => None,
+    }
+  }
+
+  fn config_value(self) -> &'static str {
+    match self {
+      Self::GitHub => "github",
+      Self::GitLab => "gitlab",
+    }
+  }
+
+  /// Detect the provider from the host name: github.com and gitlab.com, and self-hosted instances named after them
+  /// (e.g. `github.example.com`, `gitlab.internal`)
+  pub fn from_host(host: &str) -> Option<Self> {
+    let host = host.to_ascii_lowercase();
+    if host.split('.').any(|label| label == "github" || label.starts_with("github-")) {
+      Some(Self::GitHub)
+    } else if host.split('.').any(|label| label == "gitlab" || label.starts_with("gitlab-")) {
+      Some(Self::GitLab)
+    } else {
+      None
+    }
+  }
+
+  /// Default REST API base URL of an instance
+  fn api_base_url(self, web_base_url: &str, host: &str) -> String {
+    match self {
+      Self::GitHub if host.eq_ignore_ascii_case("github.com") => "https://api.github.com".to_string(),
+      // GitHub Enterprise Server
+      Self::GitHub => format!("{web_base_url}/api/v3"),
+      Self::GitLab => format!("{web_base_url}/api/v4"),
+    }
+  }
+}
+
+/// Resolved hosting provider settings of a remote, used by provider integrations to talk to the right instance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteHostingConfig {
+  pub remote_name: String,
+  pub provider: HostingProviderKind,
+  pub host: String,
+  /// Project path on the host, e.g. `develar/branch-deck` or `group/subgroup/project`
+  pub project_path: String,
+  /// Web URL of the project
+  pub web_url: String,
+  /// REST API base URL, e.g. `https://api.github.com` or `https://github.example.com/api/v3`
+  pub api_base_url: String,
+  /// PEM file with extra CA certificates for instances using a private CA
+  pub ca_cert_path: Option<String>,
+  /// Whether the provider or API base URL comes from git config instead of detection
+  pub overridden: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct GetRemoteHostingConfigsParams {
+  pub repository_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SetRemoteHostingOverrideParams {
+  pub repository_path: String,
+  pub remote_name: String,
+  /// `None` detects the provider from the remote host
+  pub provider: Option<HostingProviderKind>,
+  /// `None` uses the default API URL of the provider
+  pub api_base_url: Option<String>,
+  /// `None` falls back to git's `http.<url>.sslCAInfo`
+  pub ca_cert_path: Option<String>,
+}
+
+/// Parts of a remote URL needed to address the hosting instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteUrl {
+  /// `https` for SSH remotes, the web UI and API are served over HTTPS
+  pub scheme: String,
+  pub host: String,
+  /// Port of HTTP(S) remotes; SSH ports say nothing about the web port and are dropped
+  pub port: Option<u16>,
+  pub path: String,
+}
+
+impl RemoteUrl {
+  /// Parse `https://host[:port]/path`, `ssh://[user@]host[:port]/path`, `git://host/path` and scp-like `[user@]host:path`
+  pub fn parse(url: &str) -> Option<Self> {
+    let url = url.trim();
+    let (scheme, rest) = match url.split_once("://") {
+      Some((scheme, rest)) => (scheme.to_ascii_lowercase(), rest),
+      None => {
+        // scp-like syntax, a local path has no colon before the first slash
+        let (authority, path) = url.split_once(':')?;
+        if authority.contains('/') || path.starts_with("//") {
+          return None;
+        }
+        let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+        return Self::new("https", host, None, path);
+      }
+    };
+
+    let (authority, path) = rest.split_once('/')?;
+    let host_port = authority.rsplit_once('@').map_or(authority, |(_, host_port)| host_port);
+    let (host, port) = match host_port.rsplit_once(':') {
+      Some((host, port)) => (host, port.parse::<u16>().ok()),
+      None => (host_port, None),
+    };
+    match scheme.as_str() {
+      "http" | "https" => Self::new(&scheme, host, port, path),
+      "ssh" | "git" | "git+ssh" | "ssh+git" => Self::new("https", host, None, path),
+      _ => None,
+    }
+  }
+
+  fn new(scheme: &str, host: &str, port: Option<u16>, path: &str) -> Option<Self> {
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    if host.is_empty() || path.is_empty() {
+      return None;
+    }
+    Some(Self {
+      scheme: scheme.to_string(),
+      host: host.to_string(),
+      port,
+      path: path.to_string(),
+    })
+  }
+
+  /// Base URL of the instance, e.g. `https://gitlab.example.com:8443`
+  pub fn web_base_url(&self) -> String {
+    match self.port {
+      Some(port) => format!("{}://{}:{port}", self.scheme, self.host),
+      None => format!("{}://{}", self.scheme, self.host),
+    }
+  }
+}
+
+fn remote_config_key(remote_name: &str, key: &str) -> String {
+  format!("remote.{remote_name}.{key}")
+}
+
+fn get_remote_config_value(git_executor: &GitCommandExecutor, repository_path: &str, remote_name: &str, key: &str) -> Option<String> {
+  match get_config_value(git_executor, repository_path, &remote_config_key(remote_name, key)) {
+    Ok(value) => value.filter(|value| !value.is_empty()),
+    Err(e) => {
+      warn!(remote_name, key, error = %e, "Failed to read remote hosting config");
+      None
+    }
+  }
+}
+
+/// Resolve the hosting provider of a remote from its URL and the per-remote overrides.
+/// Returns `None` for remotes on unknown hosts without a provider override (e.g. a plain SSH server).
+#[instrument(skip(git_executor))]
+pub fn resolve_remote_hosting_config(git_executor: &GitCommandExecutor, repository_path: &str, remote_name: &str) -> Result<Option<RemoteHostingConfig>> {
+  // `get-url` applies `url.<base>.insteadOf` rewrites
+  let url = git_executor.execute_command(&["remote", "get-url", remote_name], repository_path)?;
+  let Some(remote_url) = RemoteUrl::parse(&url) else {
+    return Ok(None);
+  };
+
+  let provider_override = get_remote_config_value(git_executor, repository_path, remote_name, PROVIDER_CONFIG_KEY).and_then(|value| {
+    HostingProviderKind::from_config_value(&value).or_else(|| {
+      warn!(remote_name, value, "Unknown hosting provider, detecting it from the remote host");
+      None
+    })
+  });
+  let Some(provider) = provider_override.or_else(|| HostingProviderKind::from_host(&remote_url.host)) else {
+    return Ok(None);
+  };
+
+  let web_base_url = remote_url.web_base_url();
+  let api_base_url_override = get_remote_config_value(git_executor, repository_path, remote_name, API_BASE_URL_CONFIG_KEY).map(|url| url.trim_end_matches('/').to_string());
+  let overridden = provider_override.is_some() || api_base_url_override.is_some();
+  let api_base_url = api_base_url_override.unwrap_or_else(|| provider.api_base_url(&web_base_url, &remote_url.host));
+
+  // Same CA git itself uses for HTTPS remotes on this host
+  let ca_cert_path = get_remote_config_value(git_executor, repository_path, remote_name, CA_CERT_PATH_CONFIG_KEY).or_else(|| {
+    git_executor
+      .execute_command(&["config", "--get-urlmatch", "http.sslCAInfo", &web_base_url], repository_path)
+      .ok()
+      .map(|path| path.trim().to_string())
+      .filter(|path| !path.is_empty())
+  });
+
+  Ok(Some(RemoteHostingConfig {
+    remote_name: remote_name.to_string(),
+    provider,
+    web_url: format!("{web_base_url}/{}", remote_url.path),
+    host: remote_url.host,
+    project_path: remote_url.path,
+    api_base_url,
+    ca_cert_path,
+    overridden,
+  }))
+}
+
+/// Hosting provider settings of all remotes with a known provider
+#[instrument(skip(git_executor))]
+pub fn get_remote_hosting_configs_core(git_executor: &GitCommandExecutor, params: GetRemoteHostingConfigsParams) -> Result<Vec<RemoteHostingConfig>> {
+  let remotes = git_executor.execute_command_lines(&["remote"], &params.repository_path)?;
+  let mut configs = Vec::with_capacity(remotes.len());
+  for remote_name in remotes {
+    if let Some(config) = resolve_remote_hosting_config(git_executor, &params.repository_path, &remote_name)? {
+      configs.push(config);
+    }
+  }
+  Ok(configs)
+}
+
+/// Store manual provider, API base URL and CA overrides of a remote, unset values are removed.
+/// Returns the resolved settings of the remote.
+#[instrument(skip(git_executor))]
+pub fn set_remote_hosting_override_core(git_executor: &GitCommandExecutor, params: SetRemoteHostingOverrideParams) -> Result<Option<RemoteHostingConfig>> {
+  let repository_path = params.repository_path.as_str();
+  let remotes = git_executor.execute_command_lines(&["remote"], repository_path)?;
+  if !remotes.contains(&params.remote_name) {
+    return Err(CommandError::not_found(format!("Remote '{}' does not exist", params.remote_name)).into_anyhow());
+  }
+
+  let api_base_url = params.api_base_url.as_deref().map(str::trim).filter(|url| !url.is_empty());
+  if let Some(url) = api_base_url
+    && !(url.starts_with("https://") || url.starts_with("http://"))
+  {
+    return Err(CommandError::validation("API base URL must start with https:// or http://").into_anyhow());
+  }
+  let ca_cert_path = params.ca_cert_path.as_deref().map(str::trim).filter(|path| !path.is_empty());
+
+  let remote_name = params.remote_name.as_str();
+  set_local_config_value(
+    git_executor,
+    repository_path,
+    &remote_config_key(remote_name, PROVIDER_CONFIG_KEY),
+    params.provider.map(HostingProviderKind::config_value),
+  )?;
+  set_local_config_value(git_executor, repository_path, &remote_config_key(remote_name, API_BASE_URL_CONFIG_KEY), api_base_url)?;
+  set_local_config_value(git_executor, repository_path, &remote_config_key(remote_name, CA_CERT_PATH_CONFIG_KEY), ca_cert_path)?;
+
+  resolve_remote_hosting_config(git_executor, repository_path, remote_name)
+}
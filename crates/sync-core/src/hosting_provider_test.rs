@@ -0,0 +1,156 @@
+use crate::hosting_provider::{
+  GetRemoteHostingConfigsParams, HostingProviderKind, RemoteHostingConfig, RemoteUrl, SetRemoteHostingOverrideParams, get_remote_hosting_configs_core,
+  resolve_remote_hosting_config, set_remote_hosting_override_core,
+};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn parse(url: &str) -> Option<(String, String, Option<u16>, String)> {
+  RemoteUrl::parse(url).map(|url| (url.scheme, url.host, url.port, url.path))
+}
+
+#[test]
+fn test_parse_remote_url() {
+  let expected = Some(("https".to_string(), "github.com".to_string(), None, "develar/branch-deck".to_string()));
+  assert_eq!(parse("https://github.com/develar/branch-deck.git"), expected);
+  assert_eq!(parse("git@github.com:develar/branch-deck.git"), expected);
+  assert_eq!(parse("ssh://git@github.com:2222/develar/branch-deck"), expected);
+
+  assert_eq!(
+    parse("http://gitlab.internal:8080/group/subgroup/project.git/"),
+    Some(("http".to_string(), "gitlab.internal".to_string(), Some(8080), "group/subgroup/project".to_string()))
+  );
+
+  // Local paths
+  assert_eq!(parse("/srv/git/project.git"), None);
+  assert_eq!(parse("."), None);
+  assert_eq!(parse("file:///srv/git/project.git"), None);
+}
+
+#[test]
+fn test_provider_from_host() {
+  assert_eq!(HostingProviderKind::from_host("github.com"), Some(HostingProviderKind::GitHub));
+  assert_eq!(HostingProviderKind::from_host("GitHub.Example.com"), Some(HostingProviderKind::GitHub));
+  assert_eq!(HostingProviderKind::from_host("gitlab.com"), Some(HostingProviderKind::GitLab));
+  assert_eq!(HostingProviderKind::from_host("gitlab-ee.corp.net"), Some(HostingProviderKind::GitLab));
+  assert_eq!(HostingProviderKind::from_host("git.example.com"), None);
+  assert_eq!(HostingProviderKind::from_host("notgithub.com"), None);
+}
+
+fn repo_with_remotes(remotes: &[(&str, &str)]) -> TestRepo {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  for (name, url) in remotes {
+    test_repo.add_remote(name, url).unwrap();
+  }
+  test_repo
+}
+
+#[test]
+fn test_detect_remote_hosting_configs() {
+  let test_repo = repo_with_remotes(&[
+    ("origin", "git@github.example.com:team/app.git"),
+    ("upstream", "https://github.com/develar/branch-deck.git"),
+    ("mirror", "ssh://git@git.example.com/team/app.git"),
+  ]);
+  let repository_path = test_repo.path().to_str().unwrap().to_string();
+
+  let configs = get_remote_hosting_configs_core(&GitCommandExecutor::new(), GetRemoteHostingConfigsParams { repository_path }).unwrap();
+  let mut apis: Vec<(&str, HostingProviderKind, &str, &str)> = configs
+    .iter()
+    .map(|config| (config.remote_name.as_str(), config.provider, config.api_base_url.as_str(), config.web_url.as_str()))
+    .collect();
+  apis.sort_by_key(|api| api.0);
+  // The mirror is on an unknown host
+  assert_eq!(
+    apis,
+    vec![
+      (
+        "origin",
+        HostingProviderKind::GitHub,
+        "https://github.example.com/api/v3",
+        "https://github.example.com/team/app"
+      ),
+      ("upstream", HostingProviderKind::GitHub, "https://api.github.com", "https://github.com/develar/branch-deck"),
+    ]
+  );
+}
+
+#[test]
+fn test_remote_hosting_override() {
+  let test_repo = repo_with_remotes(&[("origin", "ssh://git@git.example.com:2222/group/project.git")]);
+  let git_executor = GitCommandExecutor::new();
+  let repository_path = test_repo.path().to_str().unwrap().to_string();
+  assert_eq!(resolve_remote_hosting_config(&git_executor, &repository_path, "origin").unwrap(), None);
+
+  let config = set_remote_hosting_override_core(
+    &git_executor,
+    SetRemoteHostingOverrideParams {
+      repository_path: repository_path.clone(),
+      remote_name: "origin".to_string(),
+      provider: Some(HostingProviderKind::GitLab),
+      api_base_url: None,
+      ca_cert_path: Some("/etc/ssl/corp-ca.pem".to_string()),
+    },
+  )
+  .unwrap();
+  assert_eq!(
+    config,
+    Some(RemoteHostingConfig {
+      remote_name: "origin".to_string(),
+      provider: HostingProviderKind::GitLab,
+      host: "git.example.com".to_string(),
+      project_path: "group/project".to_string(),
+      web_url: "https://git.example.com/group/project".to_string(),
+      api_base_url: "https://git.example.com/api/v4".to_string(),
+      ca_cert_path: Some("/etc/ssl/corp-ca.pem".to_string()),
+      overridden: true,
+    })
+  );
+
+  // Custom API URL, CA from git's own HTTP config
+  test_repo.set_config("http.https://git.example.com.sslCAInfo", "/etc/ssl/git-ca.pem").unwrap();
+  let config = set_remote_hosting_override_core(
+    &git_executor,
+    SetRemoteHostingOverrideParams {
+      repository_path: repository_path.clone(),
+      remote_name: "origin".to_string(),
+      provider: Some(HostingProviderKind::GitLab),
+      api_base_url: Some("https://git-api.example.com/api/v4/".to_string()),
+      ca_cert_path: None,
+    },
+  )
+  .unwrap()
+  .unwrap();
+  assert_eq!(config.api_base_url, "https://git-api.example.com/api/v4");
+  assert_eq!(config.ca_cert_path.as_deref(), Some("/etc/ssl/git-ca.pem"));
+
+  // Removing the overrides restores detection
+  let config = set_remote_hosting_override_core(
+    &git_executor,
+    SetRemoteHostingOverrideParams {
+      repository_path: repository_path.clone(),
+      remote_name: "origin".to_string(),
+      provider: None,
+      api_base_url: None,
+      ca_cert_path: None,
+    },
+  )
+  .unwrap();
+  assert_eq!(config, None);
+
+  let error = set_remote_hosting_override_core(
+    &git_executor,
+    SetRemoteHostingOverrideParams {
+      repository_path,
+      remote_name: "unknown".to_string(),
+      provider: Some(HostingProviderKind::GitHub),
+      api_base_url: None,
+      ca_cert_path: None,
+    },
+  )
+  .unwrap_err();
+  assert_eq!(error.to_string(), "Remote 'unknown' does not exist");
+}
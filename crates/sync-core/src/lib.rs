@@ -1,24 +1,155 @@
 pub mod add_issue_reference;
 pub mod amend_to_branch;
+pub mod archive_retention;
+pub mod author_filter;
+pub mod auto_archive;
+pub mod auto_fetch;
+pub mod auto_stash;
+pub mod baseline_update;
+pub mod branch_diff;
+pub mod branch_graph;
+pub mod branch_order;
 pub mod branch_prefix;
 mod branch_processor;
+pub mod branch_protection;
+pub mod ci_status;
+pub mod commit_dependencies;
 pub mod commit_grouper;
+pub mod commit_grouping;
+pub mod commit_mapping;
+pub mod commit_order;
+pub mod conflict_mode;
+pub mod conflict_simulation;
 pub mod create_branch;
+pub mod credentials;
 pub mod delete_archived_branch;
+pub mod drop_commits;
+pub mod empty_branch_policy;
+pub mod grouping_diagnostics;
+pub mod hooks;
+pub mod hosting_provider;
 pub mod issue_navigation;
+pub mod network;
+pub mod operation_plan;
+pub mod path_scope;
+pub mod phase_budget;
+pub mod pre_push;
+pub mod prefix_typo;
+pub mod reassign_commit;
+pub mod rebase_branch_onto;
+pub mod recovery;
 pub mod remote_status;
+pub mod rename_virtual_branch;
+pub mod repo_state;
 pub mod repository_validation;
+pub mod review_status;
+pub mod reword_commits;
+pub mod send_email;
+pub mod settings_transfer;
+pub mod shallow_clone;
+pub mod squash_branch;
 pub mod sync;
+pub mod sync_dry_run;
+pub mod sync_performance;
 pub mod unapply_branch;
 pub mod uncommitted_changes;
+pub mod undo_snapshot;
+pub mod usage_stats;
+pub mod verify_metadata;
+pub mod webhook;
+pub mod workspace_sync;
 
+#[cfg(test)]
+mod auto_archive_test;
+#[cfg(test)]
+mod auto_fetch_test;
+#[cfg(test)]
+mod auto_stash_test;
+#[cfg(test)]
+mod baseline_update_test;
+#[cfg(test)]
+mod branch_diff_test;
+#[cfg(test)]
+mod branch_graph_test;
+#[cfg(test)]
+mod branch_order_test;
 #[cfg(test)]
 mod branch_prefix_test;
 #[cfg(test)]
+mod branch_protection_test;
+#[cfg(test)]
+mod ci_status_test;
+#[cfg(test)]
+mod commit_dependencies_test;
+#[cfg(test)]
+mod commit_grouping_test;
+#[cfg(test)]
+mod commit_mapping_test;
+#[cfg(test)]
+mod commit_order_test;
+#[cfg(test)]
+mod conflict_simulation_test;
+#[cfg(test)]
 mod create_branch_test;
 #[cfg(test)]
+mod credentials_test;
+#[cfg(test)]
+mod delete_archived_branch_test;
+#[cfg(test)]
+mod drop_commits_test;
+#[cfg(test)]
+mod grouping_diagnostics_test;
+#[cfg(test)]
+mod hooks_test;
+#[cfg(test)]
+mod hosting_provider_test;
+#[cfg(test)]
+mod network_test;
+#[cfg(test)]
+mod operation_plan_test;
+#[cfg(test)]
+mod path_scope_test;
+#[cfg(test)]
+mod phase_budget_test;
+#[cfg(test)]
+mod pre_push_test;
+#[cfg(test)]
+mod prefix_typo_test;
+#[cfg(test)]
+mod reassign_commit_test;
+#[cfg(test)]
+mod rebase_branch_onto_test;
+#[cfg(test)]
+mod recovery_test;
+#[cfg(test)]
+mod remote_status_test;
+#[cfg(test)]
+mod rename_virtual_branch_test;
+#[cfg(test)]
+mod reword_commits_test;
+#[cfg(test)]
+mod send_email_test;
+#[cfg(test)]
+mod settings_transfer_test;
+#[cfg(test)]
+mod shallow_clone_test;
+#[cfg(test)]
+mod squash_branch_test;
+#[cfg(test)]
+mod sync_dry_run_test;
+#[cfg(test)]
+mod sync_performance_test;
+#[cfg(test)]
 mod sync_test;
 #[cfg(test)]
 mod unapply_branch_test;
 #[cfg(test)]
 mod uncommitted_changes_test;
+#[cfg(test)]
+mod usage_stats_test;
+#[cfg(test)]
+mod verify_metadata_test;
+#[cfg(test)]
+mod webhook_test;
+#[cfg(test)]
+mod workspace_sync_test;
@@ -0,0 +1,204 @@
+//! Network availability and the explicit offline mode.
+//!
+//! The remote status refresh, hosting provider queries and the fetch before sync first probe the host they connect to
+//! (see [`url_network_status`]), so without a network (e.g. on a plane) they are skipped with an `Offline` status or an
+//! [`ErrorCode::Offline`] error instead of waiting for connect timeouts. Model downloads only check [`network_status`],
+//! the result of the last probe. Local git operations are never affected.
+
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::{CommandError, ErrorCode};
+use std::collections::HashMap;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use sync_types::NetworkStatus;
+use tracing::{debug, instrument};
+
+/// A detected status is reused for this long, so a burst of queries probes a host once
+const PROBE_TTL: Duration = Duration::from_secs(30);
+const PROBE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+static OFFLINE_MODE: AtomicBool = AtomicBool::new(false);
+/// When each host and port was last probed and whether it was reachable
+static PROBES: LazyLock<Mutex<HashMap<(String, u16), (Instant, bool)>>> = LazyLock::new(Default::default);
+/// When any host was last probed and whether it was reachable
+static LAST_PROBE: Mutex<Option<(Instant, bool)>> = Mutex::new(None);
+
+/// Switch the offline mode on or off for the whole app
+pub fn set_offline_mode(enabled: bool) {
+  OFFLINE_MODE.store(enabled, Ordering::Relaxed);
+  // Going online again must not reuse a probe made before
+  PROBES.lock().unwrap().clear();
+  *LAST_PROBE.lock().unwrap() = None;
+  debug!(enabled, "Offline mode changed");
+}
+
+pub fn is_offline_mode() -> bool {
+  OFFLINE_MODE.load(Ordering::Relaxed)
+}
+
+/// Offline mode if switched on, otherwise the result of the last probe of a remote or hosting provider made within the
+/// probe TTL. Doesn't probe itself: without a recent failed probe the network is assumed to be there.
+pub fn network_status() -> NetworkStatus {
+  if is_offline_mode() {
+    return NetworkStatus::OfflineMode;
+  }
+  match *LAST_PROBE.lock().unwrap() {
+    Some((probed_at, false)) if probed_at.elapsed() < PROBE_TTL => NetworkStatus::Offline,
+    _ => NetworkStatus::Online,
+  }
+}
+
+/// Network status for reaching the host of a URL, probing the host and port requests to it connect to. A URL on the
+/// local file system is always reachable, one that can't be parsed is assumed to be reachable and left to the request
+/// itself. Blocks up to the probe timeout, call it from a blocking task.
+#[instrument]
+pub fn url_network_status(url: &str) -> NetworkStatus {
+  if is_offline_mode() {
+    return NetworkStatus::OfflineMode;
+  }
+  if is_local_url(url) {
+    return NetworkStatus::Online;
+  }
+  match network_endpoint(url) {
+    Some((host, port)) if !probe(host, port) => NetworkStatus::Offline,
+    _ => NetworkStatus::Online,
+  }
+}
+
+const SSH_PORT: u16 = 22;
+
+/// Network status for reaching a remote, `None` for the remote `git fetch` uses by default, see [`url_network_status`]
+pub fn remote_network_status(git_executor: &GitCommandExecutor, repository_path: &str, remote: Option<&str>) -> NetworkStatus {
+  let mut args = vec!["ls-remote", "--get-url"];
+  args.extend(remote);
+  match git_executor.execute_command(&args, repository_path) {
+    Ok(url) => url_network_status(&url),
+    Err(_) => network_status(),
+  }
+}
+
+/// Whether a TCP connection to the host and port can be opened. Held while probing, so concurrent callers wait for the
+/// same probe.
+fn probe(host: &str, port: u16) -> bool {
+  let mut probes = PROBES.lock().unwrap();
+  let key = (host.to_ascii_lowercase(), port);
+  if let Some((probed_at, reachable)) = probes.get(&key)
+    && probed_at.elapsed() < PROBE_TTL
+  {
+    return *reachable;
+  }
+  let reachable = match (host, port).to_socket_addrs() {
+    Ok(mut addresses) => addresses.any(|address| TcpStream::connect_timeout(&address, PROBE_TIMEOUT).is_ok()),
+    // An SSH host may be an alias from the SSH config that only ssh resolves, a failing lookup is left to ssh itself
+    Err(_) if port == SSH_PORT => {
+      debug!(host, "SSH host not resolved, not probing it");
+      return true;
+    }
+    Err(_) => false,
+  };
+  debug!(host, port, reachable, "Probed network");
+  let now = Instant::now();
+  probes.insert(key, (now, reachable));
+  *LAST_PROBE.lock().unwrap() = Some((now, reachable));
+  reachable
+}
+
+/// Host and port a request to the URL connects to, with the default port of its scheme. Scp-like `[user@]host:path`
+/// URLs use SSH. `None` for local paths and unknown schemes.
+pub fn network_endpoint(url: &str) -> Option<(&str, u16)> {
+  let url = url.trim();
+  let (default_port, authority) = match url.split_once("://") {
+    Some((scheme, rest)) => {
+      let default_port = match scheme.to_ascii_lowercase().as_str() {
+        "https" => 443,
+        "http" => 80,
+        "ssh" | "git+ssh" | "ssh+git" => SSH_PORT,
+        "git" => 9418,
+        _ => return None,
+      };
+      (default_port, rest.split('/').next().unwrap_or(rest))
+    }
+    None if is_local_url(url) => return None,
+    None => (SSH_PORT, url.split_once(':').map_or(url, |(authority, _)| authority)),
+  };
+  let host_port = authority.rsplit_once('@').map_or(authority, |(_, host_port)| host_port);
+  let (host, port) = match host_port.strip_prefix('[') {
+    // IPv6 address
+    Some(rest) => {
+      let (host, port) = rest.split_once(']')?;
+      (host, port.strip_prefix(':'))
+    }
+    None => match host_port.split_once(':') {
+      Some((host, port)) => (host, Some(port)),
+      None => (host_port, None),
+    },
+  };
+  let port = match port.filter(|port| !port.is_empty()) {
+    Some(port) => port.parse().ok()?,
+    None => default_port,
+  };
+  (!host.is_empty()).then_some((host, port))
+}
+
+/// Whether a remote URL points to the local file system rather than a host
+pub fn is_local_url(url: &str) -> bool {
+  let url = url.trim();
+  if url.starts_with("file://") {
+    return true;
+  }
+  if url.contains("://") {
+    return false;
+  }
+  // Windows drive letter, not the host of an scp-like `host:path`
+  let bytes = url.as_bytes();
+  if bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && (bytes[2] == b'\\' || bytes[2] == b'/') {
+    return true;
+  }
+  // Git treats a colon before the first slash as an scp-like URL
+  match (url.find(':'), url.find('/')) {
+    (Some(colon), Some(slash)) => slash < colon,
+    (Some(_), None) => false,
+    (None, _) => true,
+  }
+}
+
+/// Fail with [`ErrorCode::Offline`] in offline mode or when the last probe found no network, see [`network_status`]
+pub fn ensure_online() -> Result<()> {
+  match network_status() {
+    NetworkStatus::Online => Ok(()),
+    status => Err(offline_error(status).into_anyhow()),
+  }
+}
+
+/// Fail with [`ErrorCode::Offline`] unless the remote is reachable, see [`remote_network_status`]
+pub fn ensure_remote_reachable(git_executor: &GitCommandExecutor, repository_path: &str, remote: Option<&str>) -> Result<()> {
+  match remote_network_status(git_executor, repository_path, remote) {
+    NetworkStatus::Online => Ok(()),
+    status => Err(offline_error(status).into_anyhow()),
+  }
+}
+
+/// [`ensure_online`] for async callers
+pub async fn ensure_online_async() -> Result<()> {
+  ensure_online()
+}
+
+/// Fail with [`ErrorCode::Offline`] unless the host of the URL is reachable (see [`url_network_status`]), probing on a
+/// blocking task
+pub async fn ensure_url_reachable_async(url: String) -> Result<()> {
+  match tokio::task::spawn_blocking(move || url_network_status(&url)).await? {
+    NetworkStatus::Online => Ok(()),
+    status => Err(offline_error(status).into_anyhow()),
+  }
+}
+
+pub fn offline_error(status: NetworkStatus) -> CommandError {
+  let message = match status {
+    NetworkStatus::OfflineMode => "Skipped in offline mode",
+    _ => "Skipped, no network connection",
+  };
+  CommandError::new(ErrorCode::Offline, message)
+}
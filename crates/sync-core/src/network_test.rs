@@ -0,0 +1,53 @@
+use crate::network::{ensure_online, is_local_url, network_endpoint, remote_network_status, set_offline_mode};
+use git_ops::error::{CommandError, ErrorCode};
+use pretty_assertions::assert_eq;
+use sync_types::NetworkStatus;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_is_local_url() {
+  assert!(is_local_url("/home/user/repo.git"));
+  assert!(is_local_url("../repo"));
+  assert!(is_local_url("file:///home/user/repo.git"));
+  assert!(is_local_url(r"C:\Users\user\repo"));
+  assert!(is_local_url("./dir:with-colon"));
+
+  assert!(!is_local_url("https://github.com/develar/branch-deck.git"));
+  assert!(!is_local_url("ssh://git@example.com/repo.git"));
+  assert!(!is_local_url("git@github.com:develar/branch-deck.git"));
+  assert!(!is_local_url("example.com:repo"));
+}
+
+#[test]
+fn test_network_endpoint() {
+  assert_eq!(network_endpoint("https://github.com/develar/branch-deck.git"), Some(("github.com", 443)));
+  assert_eq!(network_endpoint("http://git.example.com:8080/repo.git"), Some(("git.example.com", 8080)));
+  assert_eq!(network_endpoint("ssh://git@example.com:2222/repo.git"), Some(("example.com", 2222)));
+  assert_eq!(network_endpoint("git://example.com/repo.git"), Some(("example.com", 9418)));
+  assert_eq!(network_endpoint("git@github.com:develar/branch-deck.git"), Some(("github.com", 22)));
+  assert_eq!(network_endpoint("https://[::1]:8443/repo.git"), Some(("::1", 8443)));
+  assert_eq!(network_endpoint("https://api.github.com"), Some(("api.github.com", 443)));
+
+  assert_eq!(network_endpoint("/home/user/repo.git"), None);
+  assert_eq!(network_endpoint("file:///home/user/repo.git"), None);
+  assert_eq!(network_endpoint("foo://example.com/repo.git"), None);
+}
+
+#[test]
+fn test_offline_mode() {
+  let origin = TestRepo::new();
+  origin.create_commit("Initial commit", "README.md", "# Test");
+  let local = TestRepo::new_empty();
+  local.clone_from(origin.path()).unwrap();
+  let repository_path = local.path().to_str().unwrap();
+
+  set_offline_mode(true);
+  let error = ensure_online().map_err(CommandError::from);
+  // A remote on the local file system doesn't need the network
+  let local_remote_status = remote_network_status(local.git_executor(), repository_path, Some("origin"));
+  set_offline_mode(false);
+
+  assert_eq!(error.unwrap_err().code, ErrorCode::Offline);
+  assert_eq!(local_remote_status, NetworkStatus::Online);
+}
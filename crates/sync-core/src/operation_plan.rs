@@ -0,0 +1,266 @@
+//! Ordered list of operations executed as one refactor, e.g. "move these commits, rename the branch, sync and push".
+//!
+//! The local refs are snapshotted before the first operation (see [`crate::undo_snapshot`]), so a plan is undone in one step
+//! no matter how many operations it ran. Progress of all operations goes to one reporter, framed by
+//! [`SyncEvent::PlanOperationStarted`] and [`SyncEvent::PlanOperationCompleted`].
+
+use crate::add_issue_reference::{AddIssueReferenceParams, add_issue_reference_to_commits_core};
+use crate::branch_protection::check_push_allowed;
+use crate::credentials::AskpassEnv;
+use crate::phase_budget::wait_for_background_phases;
+use crate::pre_push::{PrePushCheck, run_pre_push_check};
+use crate::remote_status::push_virtual_branch;
+use crate::rename_virtual_branch::{RenameVirtualBranchParams, rename_virtual_branch_core};
+use crate::sync::{SyncOptions, detect_baseline_branch, sync_branches};
+use crate::undo_snapshot::create_undo_snapshot;
+use anyhow::{Result, anyhow, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::model::{CommitInfo, to_final_branch_name, validate_branch_name};
+use git_ops::reword_commits::{RewordCommitParams, reword_commits_batch};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use sync_types::{ProgressReporter, SyncEvent};
+use tracing::{info, instrument, warn};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PlanOperation {
+  #[serde(rename_all = "camelCase")]
+  RenameBranch {
+    old_name: String,
+    new_name: String,
+  },
+  /// Replace the `(branch-name)` prefix of the commits (adding it if missing) with the target branch
+  #[serde(rename_all = "camelCase")]
+  MoveCommits {
+    commit_ids: Vec<String>,
+    target_branch: String,
+  },
+  #[serde(rename_all = "camelCase")]
+  AddIssueReference {
+    branch_name: String,
+    issue_reference: String,
+  },
+  Sync,
+  #[serde(rename_all = "camelCase")]
+  Push {
+    branch_name: String,
+  },
+}
+
+impl PlanOperation {
+  /// Short description for progress display
+  pub fn describe(&self) -> String {
+    match self {
+      PlanOperation::RenameBranch { old_name, new_name } => format!("Rename branch '{old_name}' to '{new_name}'"),
+      PlanOperation::MoveCommits { commit_ids, target_branch } => format!("Move {} commit(s) to '{target_branch}'", commit_ids.len()),
+      PlanOperation::AddIssueReference { branch_name, issue_reference } => format!("Add issue reference {issue_reference} to '{branch_name}'"),
+      PlanOperation::Sync => "Sync branches".to_string(),
+      PlanOperation::Push { branch_name } => format!("Push '{branch_name}'"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteOperationPlanParams {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  pub operations: Vec<PlanOperation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct OperationPlanResult {
+  pub completed_operations: u32,
+  /// New ID of every commit rewritten by the plan, keyed by its ID before the plan
+  pub commit_mapping: BTreeMap<String, String>,
+}
+
+/// Execute the operations in order after taking an undo snapshot. Commit IDs passed to later operations may be the IDs
+/// before the plan, they are mapped to the rewritten commits. The plan stops at the first failed operation;
+/// operations executed until then are kept and can be undone with [`crate::undo_snapshot::restore_undo_snapshot`].
+#[instrument(skip(git_executor, progress), fields(operation_count = params.operations.len()))]
+pub async fn execute_operation_plan<P: ProgressReporter + Clone + 'static>(
+  git_executor: &GitCommandExecutor,
+  params: ExecuteOperationPlanParams,
+  progress: P,
+) -> Result<OperationPlanResult> {
+  let ExecuteOperationPlanParams {
+    repository_path,
+    branch_prefix,
+    operations,
+  } = params;
+  let repo = repository_path.as_str();
+  if operations.is_empty() {
+    bail!("Operation plan is empty");
+  }
+
+  let descriptions: Vec<String> = operations.iter().map(PlanOperation::describe).collect();
+  create_undo_snapshot(git_executor, repo, &branch_prefix, &descriptions.join(", "))?;
+  let baseline_branch = detect_baseline_branch(git_executor, repo, "master")?;
+
+  let total = operations.len() as u32;
+  let mut commit_mapping = BTreeMap::new();
+  for (index, (operation, description)) in operations.into_iter().zip(descriptions).enumerate() {
+    let index = index as u32;
+    progress.send(SyncEvent::PlanOperationStarted {
+      index,
+      total,
+      description: description.clone(),
+    })?;
+
+    // Including the phases a sync step earlier in the plan continued in the background
+    wait_for_background_phases(repo).await;
+    let head_before = first_parent_commits(git_executor, repo, &baseline_branch)?;
+    let result = execute_operation(git_executor, repo, &branch_prefix, &baseline_branch, operation, &commit_mapping, progress.clone()).await;
+    progress.send(SyncEvent::PlanOperationCompleted {
+      index,
+      error: result.as_ref().err().map(|e| e.to_string()),
+    })?;
+    if let Err(e) = result {
+      warn!(index, %description, error = %e, "Operation plan stopped");
+      return Err(e.context(format!("{description} failed, the operations before it can be undone")));
+    }
+
+    let head_after = first_parent_commits(git_executor, repo, &baseline_branch)?;
+    record_rewrites(&mut commit_mapping, &head_before, &head_after);
+  }
+
+  info!(total, rewritten_count = commit_mapping.len(), "Operation plan completed");
+  Ok(OperationPlanResult {
+    completed_operations: total,
+    commit_mapping,
+  })
+}
+
+async fn execute_operation<P: ProgressReporter + Clone + 'static>(
+  git_executor: &GitCommandExecutor,
+  repo: &str,
+  branch_prefix: &str,
+  baseline_branch: &str,
+  operation: PlanOperation,
+  commit_mapping: &BTreeMap<String, String>,
+  progress: P,
+) -> Result<()> {
+  match operation {
+    PlanOperation::RenameBranch { old_name, new_name } => {
+      rename_virtual_branch_core(
+        git_executor,
+        RenameVirtualBranchParams {
+          repository_path: repo.to_string(),
+          branch_prefix: branch_prefix.to_string(),
+          old_name,
+          new_name,
+        },
+        &AskpassEnv::none(),
+      )?;
+    }
+    PlanOperation::MoveCommits { commit_ids, target_branch } => {
+      validate_branch_name(&target_branch).map_err(|e| anyhow!(e))?;
+      let new_prefix = format!("({target_branch}) ");
+      let mut rewrites = Vec::with_capacity(commit_ids.len());
+      for commit_id in commit_ids {
+        let commit_id = commit_mapping.get(&commit_id).cloned().unwrap_or(commit_id);
+        let message = git_executor.execute_command(&["--no-pager", "log", "-1", "--format=%B", &commit_id], repo)?;
+        let message = message.trim();
+        rewrites.push(RewordCommitParams {
+          new_message: format!("{new_prefix}{}", strip_branch_prefix(message)),
+          commit_id,
+        });
+      }
+      reword_commits_batch(git_executor, repo, rewrites)?;
+    }
+    PlanOperation::AddIssueReference { branch_name, issue_reference } => {
+      let commits = branch_commits(git_executor, repo, baseline_branch, &branch_name)?;
+      if commits.is_empty() {
+        bail!("No commits with prefix '({branch_name})' found");
+      }
+      add_issue_reference_to_commits_core(
+        git_executor,
+        AddIssueReferenceParams {
+          repository_path: repo.to_string(),
+          branch_name,
+          commits,
+          issue_reference,
+        },
+      )
+      .map_err(|e| anyhow!(e))?;
+    }
+    PlanOperation::Sync => sync_branches(git_executor, repo, branch_prefix, progress, SyncOptions::default()).await?,
+    PlanOperation::Push { branch_name } => {
+      let force = check_push_allowed(git_executor, repo, branch_prefix, &branch_name, baseline_branch)?;
+      let full_branch_name = to_final_branch_name(branch_prefix, &branch_name)?;
+      let pre_push_check = run_pre_push_check(git_executor, repo, &full_branch_name, &|_| {})?;
+      let no_verify = matches!(pre_push_check, Some(PrePushCheck::Hook(_)));
+      // Plans run unattended, missing credentials fail the step instead of prompting
+      push_virtual_branch(git_executor, repo, branch_prefix, &branch_name, force, no_verify, &AskpassEnv::none())?;
+    }
+  }
+  Ok(())
+}
+
+/// Message without its `(branch-name) ` prefix, if any
+pub(crate) fn strip_branch_prefix(message: &str) -> &str {
+  message
+    .strip_prefix('(')
+    .and_then(|rest| rest.split_once(") "))
+    .filter(|(name, _)| !name.is_empty() && !name.contains('('))
+    .map_or(message, |(_, rest)| rest)
+}
+
+/// Commits of HEAD ahead of the baseline whose message has the `(branch-name) ` prefix
+fn branch_commits(git_executor: &GitCommandExecutor, repo: &str, baseline_branch: &str, branch_name: &str) -> Result<Vec<CommitInfo>> {
+  let output = git_executor.execute_command(&["--no-pager", "log", "--no-merges", "--format=%H%x1f%B%x1e", &format!("{baseline_branch}..HEAD")], repo)?;
+  let prefix = format!("({branch_name}) ");
+  let mut commits = Vec::new();
+  for record in output.split('\x1e').map(str::trim).filter(|record| !record.is_empty()) {
+    let (hash, message) = record.split_once('\x1f').ok_or_else(|| anyhow!("Unexpected git log output"))?;
+    if message.trim().starts_with(&prefix) {
+      commits.push(CommitInfo {
+        hash: hash.to_string(),
+        message: message.trim().to_string(),
+      });
+    }
+  }
+  Ok(commits)
+}
+
+/// First-parent commits of HEAD ahead of the baseline, oldest first
+fn first_parent_commits(git_executor: &GitCommandExecutor, repo: &str, baseline_branch: &str) -> Result<Vec<String>> {
+  git_executor.execute_command_lines(&["rev-list", "--first-parent", "--reverse", &format!("{baseline_branch}..HEAD")], repo)
+}
+
+/// Extend the mapping with the commits an operation rewrote. Rewrites keep the commit count and order, so commits are
+/// matched by position; if an operation dropped commits (e.g., auto-archive pruning during sync), the mapping is left as is.
+fn record_rewrites(commit_mapping: &mut BTreeMap<String, String>, before: &[String], after: &[String]) {
+  if before.len() != after.len() {
+    return;
+  }
+  let step: HashMap<&str, &str> = before
+    .iter()
+    .zip(after)
+    .filter(|(old_id, new_id)| old_id != new_id)
+    .map(|(old_id, new_id)| (old_id.as_str(), new_id.as_str()))
+    .collect();
+  if step.is_empty() {
+    return;
+  }
+
+  // Commits rewritten by an earlier operation are mapped from their ID before the plan
+  let mut rewritten_again = Vec::new();
+  for current_id in commit_mapping.values_mut() {
+    if let Some(new_id) = step.get(current_id.as_str()) {
+      rewritten_again.push(current_id.clone());
+      *current_id = new_id.to_string();
+    }
+  }
+  for (old_id, new_id) in step {
+    if !rewritten_again.iter().any(|id| id == old_id) {
+      commit_mapping.insert(old_id.to_string(), new_id.to_string());
+    }
+  }
+}
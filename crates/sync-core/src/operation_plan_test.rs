@@ -0,0 +1,142 @@
+use crate::operation_plan::{ExecuteOperationPlanParams, PlanOperation, execute_operation_plan};
+use crate::undo_snapshot::{load_undo_snapshot, restore_undo_snapshot};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use sync_test_utils::TestReporter;
+use sync_types::SyncEvent;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// Commits of `parser` and `lexer` on top of `origin/master`, returns the ID of the `lexer` commit
+fn setup_commits(test_repo: &TestRepo) -> String {
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+  test_repo
+    .git_executor()
+    .execute_command(&["update-ref", "refs/remotes/origin/master", &baseline], test_repo.path_str())
+    .unwrap();
+  test_repo.create_commit("(parser) Add parser", "parser.rs", "fn parse() {}\n");
+  let lexer_id = test_repo.create_commit("(lexer) Add lexer", "lexer.rs", "fn lex() {}\n");
+  test_repo.create_commit("(parser) Test parser", "parser_test.rs", "fn test_parse() {}\n");
+  lexer_id
+}
+
+fn plan_params(test_repo: &TestRepo, operations: Vec<PlanOperation>) -> ExecuteOperationPlanParams {
+  ExecuteOperationPlanParams {
+    repository_path: test_repo.path_str().to_string(),
+    branch_prefix: "user".to_string(),
+    operations,
+  }
+}
+
+/// `(index, error)` of the completed operations
+fn completed_operations(events: &[SyncEvent]) -> Vec<(u32, Option<String>)> {
+  events
+    .iter()
+    .filter_map(|event| match event {
+      SyncEvent::PlanOperationCompleted { index, error } => Some((*index, error.clone())),
+      _ => None,
+    })
+    .collect()
+}
+
+#[test(tokio::test)]
+async fn test_plan_executes_operations_and_undoes_them_at_once() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  let lexer_id = setup_commits(&test_repo);
+  let original_head = test_repo.head();
+  let git_executor = GitCommandExecutor::new();
+  let progress = TestReporter::new();
+
+  let result = execute_operation_plan(
+    &git_executor,
+    plan_params(
+      &test_repo,
+      vec![
+        PlanOperation::MoveCommits {
+          commit_ids: vec![lexer_id.clone()],
+          target_branch: "parser".to_string(),
+        },
+        PlanOperation::RenameBranch {
+          old_name: "parser".to_string(),
+          new_name: "syntax".to_string(),
+        },
+        PlanOperation::AddIssueReference {
+          branch_name: "syntax".to_string(),
+          issue_reference: "ABC-1".to_string(),
+        },
+        PlanOperation::Sync,
+      ],
+    ),
+    progress.clone(),
+  )
+  .await?;
+
+  assert_eq!(result.completed_operations, 4);
+  assert_eq!(
+    test_repo.get_commit_messages(3),
+    vec![
+      "(syntax) ABC-1 Test parser".to_string(),
+      "(syntax) ABC-1 Add lexer".to_string(),
+      "(syntax) ABC-1 Add parser".to_string()
+    ]
+  );
+  // Commits are mapped from their IDs before the plan, across all rewrites
+  assert_eq!(result.commit_mapping.len(), 3);
+  assert_eq!(result.commit_mapping.get(&lexer_id), Some(&test_repo.rev_parse("HEAD~1").unwrap()));
+  assert!(test_repo.branch_exists("user/virtual/syntax"));
+
+  let events = progress.get_events();
+  assert_eq!(completed_operations(&events), (0..4).map(|index| (index, None)).collect::<Vec<_>>());
+  // Events of the sync are part of the same stream
+  assert!(events.iter().any(|event| matches!(event, SyncEvent::BranchesGrouped { .. })));
+
+  let snapshot = restore_undo_snapshot(&git_executor, test_repo.path_str())?;
+  assert_eq!(snapshot.head_commit, original_head);
+  assert_eq!(test_repo.head(), original_head);
+  assert!(!test_repo.branch_exists("user/virtual/syntax"));
+  // An undo can't be applied twice
+  assert!(load_undo_snapshot(&git_executor, test_repo.path_str())?.is_none());
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_plan_stops_at_failed_operation() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  setup_commits(&test_repo);
+  let original_head = test_repo.head();
+  let git_executor = GitCommandExecutor::new();
+  let progress = TestReporter::new();
+
+  let result = execute_operation_plan(
+    &git_executor,
+    plan_params(
+      &test_repo,
+      vec![
+        PlanOperation::RenameBranch {
+          old_name: "lexer".to_string(),
+          new_name: "tokens".to_string(),
+        },
+        PlanOperation::AddIssueReference {
+          branch_name: "missing".to_string(),
+          issue_reference: "ABC-1".to_string(),
+        },
+        PlanOperation::Sync,
+      ],
+    ),
+    progress.clone(),
+  )
+  .await;
+
+  assert!(result.is_err());
+  let completed = completed_operations(&progress.get_events());
+  assert_eq!(completed.len(), 2);
+  assert_eq!(completed[0], (0, None));
+  assert_eq!(completed[1].1.as_deref(), Some("No commits with prefix '(missing)' found"));
+  assert_eq!(test_repo.get_commit_messages(2)[1], "(tokens) Add lexer");
+
+  restore_undo_snapshot(&git_executor, test_repo.path_str())?;
+  assert_eq!(test_repo.head(), original_head);
+  assert_eq!(test_repo.get_commit_messages(2)[1], "(lexer) Add lexer");
+  Ok(())
+}
@@ -0,0 +1,177 @@
+//! Path scopes for monorepos: virtual branches that only consider commits touching some directories.
+//!
+//! Each value of the multi-valued `branchdeck.pathScope` is a branch name (or a `name-*` pattern) followed by the paths
+//! of its scope, separated by whitespace, e.g. `frontend-* web/ shared/ui/`. A path matches itself and everything below
+//! it. The first scope whose pattern matches a branch applies.
+//!
+//! A commit that touches no path in the scope of its branch is not grouped into it. A grouped commit that also touches
+//! paths outside the scope stays in the branch, but gets a grouping diagnostic, so the mixed commit can be split.
+
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use std::collections::HashMap;
+use sync_types::GroupingAmbiguity;
+use tracing::{instrument, warn};
+
+/// Git config key, one value per scope
+pub const PATH_SCOPE_CONFIG_KEY: &str = "branchdeck.pathScope";
+
+/// Out-of-scope paths listed in a diagnostic, the rest is only counted
+const MAX_LISTED_PATHS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PathScope {
+  branch_pattern: String,
+  paths: Vec<String>,
+}
+
+impl PathScope {
+  fn matches_branch(&self, branch_name: &str) -> bool {
+    match self.branch_pattern.strip_suffix('*') {
+      Some(prefix) => branch_name.starts_with(prefix),
+      None => branch_name == self.branch_pattern,
+    }
+  }
+
+  fn contains(&self, path: &str) -> bool {
+    self
+      .paths
+      .iter()
+      .any(|scope_path| path.strip_prefix(scope_path.as_str()).is_some_and(|rest| rest.is_empty() || rest.starts_with('/')))
+  }
+}
+
+/// Path scopes of the virtual branches
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathScopes {
+  scopes: Vec<PathScope>,
+}
+
+/// Commit grouped into a branch with a path scope that touches paths outside of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathScopeViolation {
+  /// [`GroupingAmbiguity::OutsidePathScope`] if the commit touches nothing in the scope and is not grouped,
+  /// [`GroupingAmbiguity::PartlyOutsidePathScope`] if it is grouped anyway
+  pub ambiguity: GroupingAmbiguity,
+  /// What is out of scope, for display
+  pub note: String,
+}
+
+impl PathScopeViolation {
+  /// Whether the commit is kept out of the branch
+  pub fn excludes_commit(&self) -> bool {
+    self.ambiguity == GroupingAmbiguity::OutsidePathScope
+  }
+}
+
+impl PathScopes {
+  /// Parse the values of [`PATH_SCOPE_CONFIG_KEY`], invalid ones are skipped with a warning
+  pub fn from_config_values<S: AsRef<str>>(values: &[S]) -> Self {
+    let mut scopes = Vec::with_capacity(values.len());
+    for value in values {
+      let value = value.as_ref();
+      let mut parts = value.split_whitespace();
+      let Some(branch_pattern) = parts.next() else {
+        continue;
+      };
+      let paths: Vec<String> = parts.map(normalize_scope_path).filter(|path| !path.is_empty()).collect();
+      if branch_pattern == "*" || paths.is_empty() {
+        warn!(
+          value,
+          "Ignoring {PATH_SCOPE_CONFIG_KEY}, expected a branch name or pattern followed by the paths of its scope"
+        );
+        continue;
+      }
+      scopes.push(PathScope {
+        branch_pattern: branch_pattern.to_string(),
+        paths,
+      });
+    }
+    Self { scopes }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.scopes.is_empty()
+  }
+
+  fn find_scope(&self, branch_name: &str) -> Option<&PathScope> {
+    self.scopes.iter().find(|scope| scope.matches_branch(branch_name))
+  }
+
+  /// Check the changed paths of a commit against the scope of the branch it is grouped into.
+  /// A commit without changes (e.g., an empty commit) is within any scope.
+  pub fn check(&self, branch_name: &str, changed_paths: &[String]) -> Option<PathScopeViolation> {
+    let scope = self.find_scope(branch_name)?;
+    let outside: Vec<&str> = changed_paths.iter().map(String::as_str).filter(|path| !scope.contains(path)).collect();
+    if outside.is_empty() {
+      return None;
+    }
+
+    let scope_description = scope.paths.join(", ");
+    if outside.len() == changed_paths.len() {
+      return Some(PathScopeViolation {
+        ambiguity: GroupingAmbiguity::OutsidePathScope,
+        note: format!("\"{branch_name}\" is limited to {scope_description} and the commit touches none of it"),
+      });
+    }
+
+    let mut listed = outside[..outside.len().min(MAX_LISTED_PATHS)].join(", ");
+    if outside.len() > MAX_LISTED_PATHS {
+      listed.push_str(&format!(" and {} more", outside.len() - MAX_LISTED_PATHS));
+    }
+    Some(PathScopeViolation {
+      ambiguity: GroupingAmbiguity::PartlyOutsidePathScope,
+      note: format!("the commit also touches paths outside the scope of \"{branch_name}\" ({scope_description}): {listed}"),
+    })
+  }
+}
+
+/// `./web/` and `web` are the same scope as `web`
+fn normalize_scope_path(path: &str) -> String {
+  let path = path.strip_prefix("./").unwrap_or(path);
+  path.trim_matches('/').to_string()
+}
+
+/// Read the path scopes, none if the config can't be read
+#[instrument(skip(git_executor))]
+pub fn load_path_scopes(git_executor: &GitCommandExecutor, repository_path: &str) -> PathScopes {
+  match git_executor.execute_command_with_status(&["config", "--get-all", PATH_SCOPE_CONFIG_KEY], repository_path) {
+    Ok((output, 0)) => PathScopes::from_config_values(&output.lines().collect::<Vec<_>>()),
+    Ok(_) => PathScopes::default(),
+    Err(e) => {
+      warn!(error = %e, "Failed to read path scopes, grouping commits regardless of the paths they touch");
+      PathScopes::default()
+    }
+  }
+}
+
+/// Paths changed by each of the commits (compared to their first parent), keyed by commit id
+#[instrument(skip(git_executor, commit_ids), fields(commit_count = commit_ids.len()))]
+pub fn load_changed_paths(git_executor: &GitCommandExecutor, repository_path: &str, commit_ids: &[&str]) -> Result<HashMap<String, Vec<String>>> {
+  if commit_ids.is_empty() {
+    return Ok(HashMap::new());
+  }
+
+  let input = format!("{}\n", commit_ids.join("\n"));
+  // `--always` prints the id of empty commits too, so every commit starts a section in the order of the input
+  let output = git_executor.execute_command_with_input(
+    &["diff-tree", "--stdin", "-r", "--root", "--name-only", "--no-renames", "--always", "-z"],
+    repository_path,
+    &input,
+  )?;
+
+  let mut expected_ids = commit_ids.iter();
+  let mut next_id = expected_ids.next();
+  let mut sections: Vec<(String, Vec<String>)> = Vec::with_capacity(commit_ids.len());
+  for token in output.split('\0').filter(|token| !token.is_empty()) {
+    if next_id.is_some_and(|id| *id == token) {
+      next_id = expected_ids.next();
+      sections.push((token.to_string(), Vec::new()));
+    } else if let Some((_, paths)) = sections.last_mut() {
+      paths.push(token.to_string());
+    } else {
+      return Err(anyhow!("Unexpected git diff-tree output before the first commit: {token}"));
+    }
+  }
+  Ok(sections.into_iter().collect())
+}
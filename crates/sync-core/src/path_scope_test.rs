@@ -0,0 +1,62 @@
+use crate::path_scope::{PATH_SCOPE_CONFIG_KEY, PathScopes, load_changed_paths, load_path_scopes};
+use pretty_assertions::assert_eq;
+use sync_types::GroupingAmbiguity;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn paths(paths: &[&str]) -> Vec<String> {
+  paths.iter().map(|path| path.to_string()).collect()
+}
+
+#[test]
+fn test_path_scope_check() {
+  let scopes = PathScopes::from_config_values(&["frontend-* ./web/ shared/ui", "api server/", "* everything/", "docs-only"]);
+  assert!(!scopes.is_empty());
+
+  // Paths below the scope, the scope directory itself is not a prefix of a sibling
+  assert_eq!(scopes.check("frontend-login", &paths(&["web/login.vue", "shared/ui/button.vue"])), None);
+  assert_eq!(scopes.check("frontend-login", &paths(&[])), None);
+  // Branches without a scope may touch anything; the invalid values are ignored
+  assert_eq!(scopes.check("backend", &paths(&["server/main.rs"])), None);
+  assert_eq!(scopes.check("docs-only", &paths(&["docs/readme.md"])), None);
+
+  let outside = scopes.check("frontend-login", &paths(&["webapp/index.ts"])).unwrap();
+  assert_eq!(outside.ambiguity, GroupingAmbiguity::OutsidePathScope);
+  assert!(outside.excludes_commit());
+  assert_eq!(outside.note, "\"frontend-login\" is limited to web, shared/ui and the commit touches none of it");
+
+  let partly = scopes.check("api", &paths(&["server/routes.rs", "web/a.ts", "web/b.ts", "web/c.ts", "web/d.ts"])).unwrap();
+  assert_eq!(partly.ambiguity, GroupingAmbiguity::PartlyOutsidePathScope);
+  assert!(!partly.excludes_commit());
+  assert_eq!(
+    partly.note,
+    "the commit also touches paths outside the scope of \"api\" (server): web/a.ts, web/b.ts, web/c.ts and 1 more"
+  );
+}
+
+#[test]
+fn test_load_path_scopes_and_changed_paths() {
+  let test_repo = TestRepo::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let git_executor = test_repo.git_executor();
+  assert!(load_path_scopes(git_executor, repo_path).is_empty());
+
+  let initial = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  let mixed = test_repo.create_commit_with_files("(frontend-login) Add form", &[("web/login.vue", "<form/>"), ("server/login.rs", "fn login() {}")]);
+  git_executor
+    .execute_command(&["commit", "--allow-empty", "-m", "(frontend-login) Empty"], repo_path)
+    .unwrap();
+  let empty = test_repo.head();
+
+  git_executor
+    .execute_command(&["config", "--add", PATH_SCOPE_CONFIG_KEY, "frontend-* web/"], repo_path)
+    .unwrap();
+  git_executor.execute_command(&["config", "--add", PATH_SCOPE_CONFIG_KEY, "api server/"], repo_path).unwrap();
+  let scopes = load_path_scopes(git_executor, repo_path);
+  assert_eq!(scopes, PathScopes::from_config_values(&["frontend-* web/", "api server/"]));
+
+  let changed_paths = load_changed_paths(git_executor, repo_path, &[initial.as_str(), mixed.as_str(), empty.as_str()]).unwrap();
+  assert_eq!(changed_paths[&initial], paths(&["README.md"]));
+  assert_eq!(changed_paths[&mixed], paths(&["server/login.rs", "web/login.vue"]));
+  assert_eq!(changed_paths[&empty], paths(&[]));
+}
@@ -0,0 +1,143 @@
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::git_config::get_config_value;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use sync_types::{ProgressReporter, SyncEvent, SyncPhase};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, instrument, warn};
+
+/// Git config key with the soft time budget of rebuilding the virtual branches, in milliseconds (0 disables it)
+pub const BRANCH_SYNC_TIME_BUDGET_CONFIG_KEY: &str = "branchdeck.branchSyncTimeBudgetMs";
+/// Git config key with the soft time budget of archiving and integration detection, in milliseconds (0 disables it)
+pub const DETECTION_TIME_BUDGET_CONFIG_KEY: &str = "branchdeck.detectionTimeBudgetMs";
+
+/// Soft time budgets of sync phases. A phase exceeding its budget is not cancelled: the sync returns with the results
+/// sent so far and the phase continues in the background. `None` waits for the phase as long as it takes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseTimeBudgets {
+  pub branch_sync: Option<Duration>,
+  pub integration_detection: Option<Duration>,
+}
+
+/// Read the phase time budgets from git config; missing, zero and invalid values leave a phase without a budget
+#[instrument(skip(git_executor))]
+pub fn load_phase_time_budgets(git_executor: &GitCommandExecutor, repository_path: &str) -> PhaseTimeBudgets {
+  let load = |key: &str| -> Option<Duration> {
+    match get_config_value(git_executor, repository_path, key) {
+      Ok(Some(value)) => match value.trim().parse::<u64>() {
+        Ok(0) => None,
+        Ok(millis) => Some(Duration::from_millis(millis)),
+        Err(_) => {
+          warn!(value, "Invalid {key} value, the phase has no time budget");
+          None
+        }
+      },
+      Ok(None) => None,
+      Err(e) => {
+        warn!(error = %e, "Failed to read {key}, the phase has no time budget");
+        None
+      }
+    }
+  };
+  PhaseTimeBudgets {
+    branch_sync: load(BRANCH_SYNC_TIME_BUDGET_CONFIG_KEY),
+    integration_detection: load(DETECTION_TIME_BUDGET_CONFIG_KEY),
+  }
+}
+
+/// Phases still running in the background, by repository path
+static BACKGROUND_PHASES: LazyLock<Mutex<HashMap<String, Vec<JoinHandle<()>>>>> = LazyLock::new(Default::default);
+
+/// Wait until the phases of earlier syncs of the repository that continued in the background are done,
+/// so neither two syncs nor a sync and a command rewriting commits or branches (push, amend, reword, rename, delete)
+/// change the same refs at once
+pub async fn wait_for_background_phases(repository_path: &str) {
+  let handles = BACKGROUND_PHASES.lock().unwrap().remove(repository_path).unwrap_or_default();
+  if handles.is_empty() {
+    return;
+  }
+  debug!(count = handles.len(), "Waiting for background phases of the previous sync");
+  for handle in handles {
+    if let Err(e) = handle.await {
+      warn!(error = %e, "Background sync phase panicked");
+    }
+  }
+}
+
+/// Run `task` once the phases of the repository continuing in the background are done, right away if there are none.
+/// The next sync waits for the task like for the phases.
+pub(crate) fn after_background_phases(repository_path: &str, task: impl FnOnce() + Send + 'static) {
+  let mut background_phases = BACKGROUND_PHASES.lock().unwrap();
+  let handles = background_phases.entry(repository_path.to_string()).or_default();
+  handles.retain(|handle| !handle.is_finished());
+  if handles.is_empty() {
+    drop(background_phases);
+    task();
+    return;
+  }
+  let phases = std::mem::take(handles);
+  handles.push(tokio::spawn(async move {
+    for phase in phases {
+      let _ = phase.await;
+    }
+    task();
+  }));
+}
+
+/// Run `task` on a blocking thread without waiting for it, the next sync of the repository waits for it like for the phases
+pub(crate) fn spawn_background_task(repository_path: &str, task: impl FnOnce() + Send + 'static) {
+  let handle = tokio::task::spawn_blocking(task);
+  let mut background_phases = BACKGROUND_PHASES.lock().unwrap();
+  let handles = background_phases.entry(repository_path.to_string()).or_default();
+  handles.retain(|handle| !handle.is_finished());
+  handles.push(handle);
+}
+
+/// Wait for a phase within its time budget. Past the budget a [`SyncEvent::PhaseContinuesInBackground`] is sent and
+/// `Ok` is returned while the phase keeps running; its outcome is reported with [`SyncEvent::BackgroundPhaseCompleted`].
+pub(crate) async fn await_phase<P: ProgressReporter + Clone + 'static>(
+  phase: SyncPhase,
+  mut handle: JoinHandle<Result<()>>,
+  budget: Option<Duration>,
+  repository_path: &str,
+  progress: &P,
+) -> Result<()> {
+  let Some(budget) = budget else {
+    return handle.await?;
+  };
+  match tokio::time::timeout(budget, &mut handle).await {
+    Ok(result) => result?,
+    Err(_) => {
+      info!(
+        ?phase,
+        budget_ms = budget.as_millis() as u64,
+        "Sync phase exceeded its time budget, continuing in the background"
+      );
+      progress.send(SyncEvent::PhaseContinuesInBackground {
+        phase,
+        budget_ms: budget.as_millis() as u64,
+      })?;
+
+      let progress = progress.clone();
+      let watcher = tokio::spawn(async move {
+        let error = match handle.await {
+          Ok(Ok(())) => None,
+          Ok(Err(e)) => Some(e.to_string()),
+          Err(e) => Some(format!("Task failed: {e}")),
+        };
+        if let Some(error) = &error {
+          warn!(?phase, error, "Background sync phase failed");
+        }
+        let _ = progress.send(SyncEvent::BackgroundPhaseCompleted { phase, error });
+      });
+
+      let mut background_phases = BACKGROUND_PHASES.lock().unwrap();
+      let handles = background_phases.entry(repository_path.to_string()).or_default();
+      handles.retain(|handle| !handle.is_finished());
+      handles.push(watcher);
+      Ok(())
+    }
+  }
+}
@@ -0,0 +1,83 @@
+use crate::phase_budget::{DETECTION_TIME_BUDGET_CONFIG_KEY, PhaseTimeBudgets, load_phase_time_budgets, wait_for_background_phases};
+use crate::sync::{SyncOptions, sync_branches};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use std::time::Duration;
+use sync_test_utils::TestReporter;
+use sync_types::{SyncEvent, SyncPhase};
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_load_phase_time_budgets() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  assert_eq!(load_phase_time_budgets(&git_executor, repo_path), PhaseTimeBudgets::default());
+
+  test_repo.set_config(DETECTION_TIME_BUDGET_CONFIG_KEY, "5000").unwrap();
+  test_repo.set_config("branchdeck.branchSyncTimeBudgetMs", "0").unwrap();
+  assert_eq!(
+    load_phase_time_budgets(&git_executor, repo_path),
+    PhaseTimeBudgets {
+      branch_sync: None,
+      integration_detection: Some(Duration::from_secs(5)),
+    }
+  );
+
+  test_repo.set_config(DETECTION_TIME_BUDGET_CONFIG_KEY, "5s").unwrap();
+  assert_eq!(load_phase_time_budgets(&git_executor, repo_path), PhaseTimeBudgets::default());
+}
+
+#[test(tokio::test)]
+async fn test_detection_continues_in_background_past_budget() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(api) Add endpoint", "api.rs", "api");
+  test_repo.create_commit("(ui) Add button", "ui.rs", "ui");
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let progress = TestReporter::new();
+  sync_branches(
+    &GitCommandExecutor::new(),
+    repo_path,
+    "test",
+    progress.clone(),
+    SyncOptions {
+      phase_time_budgets: Some(PhaseTimeBudgets {
+        branch_sync: None,
+        integration_detection: Some(Duration::ZERO),
+      }),
+      ..Default::default()
+    },
+  )
+  .await?;
+  wait_for_background_phases(repo_path).await;
+
+  let phase_events: Vec<SyncEvent> = progress
+    .get_events()
+    .into_iter()
+    .filter(|event| matches!(event, SyncEvent::PhaseContinuesInBackground { .. } | SyncEvent::BackgroundPhaseCompleted { .. }))
+    .collect();
+  assert!(
+    matches!(
+      phase_events.as_slice(),
+      [
+        SyncEvent::PhaseContinuesInBackground {
+          phase: SyncPhase::IntegrationDetection,
+          budget_ms: 0
+        },
+        SyncEvent::BackgroundPhaseCompleted {
+          phase: SyncPhase::IntegrationDetection,
+          error: None
+        },
+      ]
+    ),
+    "{phase_events:?}"
+  );
+
+  // The branches were synced within the sync itself
+  assert!(test_repo.branch_exists("test/virtual/api"));
+  assert!(test_repo.branch_exists("test/virtual/ui"));
+  Ok(())
+}
@@ -0,0 +1,263 @@
+//! Pre-push check of a virtual branch: the repository's `pre-push` hook, or a configured command such as a linter,
+//! runs in a temporary worktree checked out at the virtual branch, so it sees the tree that is going to be pushed
+//! rather than the current working copy.
+
+use anyhow::{Context, Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::{CommandError, ErrorCode};
+use git_ops::git_config::{get_config_bool, get_config_value};
+use git_ops::temp_dir::TempDirGuard;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tracing::{debug, info, instrument, warn};
+
+/// Git config key enabling the pre-push check before a virtual branch is pushed
+pub const PRE_PUSH_CHECK_CONFIG_KEY: &str = "branchdeck.prePushCheck";
+
+/// Git config key with a shell command (e.g. `cargo clippy`) run as the pre-push check instead of the `pre-push` hook
+pub const PRE_PUSH_COMMAND_CONFIG_KEY: &str = "branchdeck.prePushCommand";
+
+/// Number of trailing output lines kept in the `output` context of a `prePushCheckFailed` error
+const FAILURE_OUTPUT_LINES: usize = 20;
+
+const NULL_OID: &str = "0000000000000000000000000000000000000000";
+
+/// Output stream of the pre-push check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum OutputStream {
+  Stdout,
+  Stderr,
+}
+
+/// Progress of the pre-push check, streamed while the hook or command runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "type", content = "data")]
+pub enum PrePushEvent {
+  /// The hook or command was started in a worktree of the virtual branch
+  Started { command: String },
+  /// A line written by the hook or command
+  Output { stream: OutputStream, line: String },
+  #[serde(rename_all = "camelCase")]
+  Finished { exit_code: i32 },
+}
+
+/// What runs as the pre-push check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrePushCheck {
+  /// The repository's `pre-push` hook
+  Hook(PathBuf),
+  /// Command from `branchdeck.prePushCommand`, run by the shell
+  Command(String),
+}
+
+impl PrePushCheck {
+  fn description(&self) -> String {
+    match self {
+      PrePushCheck::Hook(path) => path.to_string_lossy().into_owned(),
+      PrePushCheck::Command(command) => command.clone(),
+    }
+  }
+}
+
+/// Resolve the pre-push check of the repository, `None` if it is disabled or there is nothing to run
+#[instrument(skip(git_executor))]
+pub fn resolve_pre_push_check(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<Option<PrePushCheck>> {
+  if get_config_bool(git_executor, repository_path, PRE_PUSH_CHECK_CONFIG_KEY)? != Some(true) {
+    return Ok(None);
+  }
+  if let Some(command) = get_config_value(git_executor, repository_path, PRE_PUSH_COMMAND_CONFIG_KEY)?.filter(|command| !command.trim().is_empty()) {
+    return Ok(Some(PrePushCheck::Command(command)));
+  }
+
+  let hook_path = find_pre_push_hook(git_executor, repository_path)?;
+  if hook_path.is_none() {
+    debug!("pre-push check is enabled, but the repository has no pre-push hook");
+  }
+  Ok(hook_path.map(PrePushCheck::Hook))
+}
+
+/// Path of the executable `pre-push` hook, `core.hooksPath` is respected
+fn find_pre_push_hook(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<Option<PathBuf>> {
+  let hooks_dir = match get_config_value(git_executor, repository_path, "core.hooksPath")? {
+    Some(hooks_path) => PathBuf::from(hooks_path),
+    None => PathBuf::from(git_executor.execute_command(&["rev-parse", "--git-path", "hooks"], repository_path)?.trim()),
+  };
+  let hook_path = Path::new(repository_path).join(hooks_dir).join("pre-push");
+  Ok(is_executable(&hook_path).then_some(hook_path))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+  use std::os::unix::fs::PermissionsExt;
+  path.metadata().is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+  path.is_file()
+}
+
+/// Removes the temporary worktree from the repository, the directory itself is removed by the `TempDirGuard`
+struct WorktreeGuard<'a> {
+  git_executor: &'a GitCommandExecutor,
+  repository_path: &'a str,
+  path: String,
+}
+
+impl Drop for WorktreeGuard<'_> {
+  fn drop(&mut self) {
+    if let Err(e) = self.git_executor.execute_command(&["worktree", "remove", "--force", &self.path], self.repository_path) {
+      warn!(error = %e, path = %self.path, "Failed to remove pre-push worktree");
+    }
+  }
+}
+
+/// Run the configured pre-push check against the virtual branch `full_branch_name`.
+/// Returns the check that ran (`None` if none is configured), fails with a `prePushCheckFailed` error if the check fails.
+#[instrument(skip(git_executor, on_event))]
+pub fn run_pre_push_check(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  full_branch_name: &str,
+  on_event: &(impl Fn(PrePushEvent) + Sync),
+) -> Result<Option<PrePushCheck>> {
+  let Some(check) = resolve_pre_push_check(git_executor, repository_path)? else {
+    return Ok(None);
+  };
+
+  let local_ref = format!("refs/heads/{full_branch_name}");
+  let local_oid = git_executor.execute_command(&["rev-parse", "--verify", &local_ref], repository_path)?.trim().to_string();
+
+  let temp_dir = TempDirGuard::new(repository_path, "pre_push")?;
+  let worktree_path = temp_dir.join("worktree");
+  git_executor
+    .execute_command(&["worktree", "add", "--detach", &worktree_path, &local_oid], repository_path)
+    .context("Failed to check out the virtual branch for the pre-push check")?;
+  let _worktree = WorktreeGuard {
+    git_executor,
+    repository_path,
+    path: worktree_path.clone(),
+  };
+
+  let mut command = match &check {
+    PrePushCheck::Hook(hook_path) => {
+      // Same arguments and input as `git push` passes to the hook
+      let remote_url = git_executor
+        .execute_command(&["remote", "get-url", "origin"], repository_path)
+        .map(|url| url.trim().to_string())
+        .unwrap_or_default();
+      let mut command = hook_command(hook_path);
+      command.args(["origin", remote_url.as_str()]);
+      command
+    }
+    PrePushCheck::Command(shell_command) => shell(shell_command),
+  };
+  let hook_input = match &check {
+    PrePushCheck::Hook(_) => {
+      let remote_oid = git_executor
+        .execute_command(&["rev-parse", "--verify", "--quiet", &format!("refs/remotes/origin/{full_branch_name}")], repository_path)
+        .map(|oid| oid.trim().to_string())
+        .unwrap_or_else(|_| NULL_OID.to_string());
+      format!("{local_ref} {local_oid} {local_ref} {remote_oid}\n")
+    }
+    PrePushCheck::Command(_) => String::new(),
+  };
+
+  let description = check.description();
+  info!(command = %description, "Running pre-push check");
+  on_event(PrePushEvent::Started { command: description.clone() });
+  let (exit_code, output_tail) = run_streaming(command.current_dir(&worktree_path), &hook_input, on_event)?;
+  on_event(PrePushEvent::Finished { exit_code });
+
+  if exit_code != 0 {
+    return Err(
+      CommandError::new(ErrorCode::PrePushCheckFailed, format!("Pre-push check failed with exit code {exit_code}"))
+        .with_context("command", description)
+        .with_context("exitCode", exit_code.to_string())
+        .with_context("output", output_tail.join("\n"))
+        .into_anyhow(),
+    );
+  }
+  Ok(Some(check))
+}
+
+#[cfg(unix)]
+fn hook_command(hook_path: &Path) -> Command {
+  Command::new(hook_path)
+}
+
+/// Hooks are shell scripts, git for Windows runs them with its bundled `sh`
+#[cfg(not(unix))]
+fn hook_command(hook_path: &Path) -> Command {
+  let mut command = Command::new("sh");
+  command.arg(hook_path);
+  command
+}
+
+/// Command running a user-configured shell command line
+#[cfg(unix)]
+pub(crate) fn shell(shell_command: &str) -> Command {
+  let mut command = Command::new("sh");
+  command.args(["-c", shell_command]);
+  command
+}
+
+#[cfg(not(unix))]
+pub(crate) fn shell(shell_command: &str) -> Command {
+  let mut command = Command::new("cmd");
+  command.args(["/C", shell_command]);
+  command
+}
+
+/// Run the command, reporting its output line by line. Returns the exit code and the last lines of the output.
+fn run_streaming(command: &mut Command, input: &str, on_event: &(impl Fn(PrePushEvent) + Sync)) -> Result<(i32, Vec<String>)> {
+  let mut child = command
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|e| anyhow!("Failed to start pre-push check: {e}"))?;
+
+  // The hook may exit without reading its input, a broken pipe is expected then
+  if let Some(mut stdin) = child.stdin.take() {
+    let _ = stdin.write_all(input.as_bytes());
+  }
+  let stdout = child.stdout.take().ok_or_else(|| anyhow!("Failed to capture pre-push check output"))?;
+  let stderr = child.stderr.take().ok_or_else(|| anyhow!("Failed to capture pre-push check output"))?;
+
+  let (mut stdout_lines, stderr_lines) = std::thread::scope(|scope| {
+    let stderr_reader = scope.spawn(|| read_lines(stderr, OutputStream::Stderr, on_event));
+    let stdout_lines = read_lines(stdout, OutputStream::Stdout, on_event);
+    (stdout_lines, stderr_reader.join().unwrap_or_default())
+  });
+
+  let status = child.wait()?;
+  // Killed by a signal has no exit code
+  let exit_code = status.code().unwrap_or(-1);
+  // Failures are usually explained on stderr, so it goes last
+  stdout_lines.extend(stderr_lines);
+  let skip = stdout_lines.len().saturating_sub(FAILURE_OUTPUT_LINES);
+  Ok((exit_code, stdout_lines.split_off(skip)))
+}
+
+fn read_lines(reader: impl Read, stream: OutputStream, on_event: &impl Fn(PrePushEvent)) -> Vec<String> {
+  let mut tail = Vec::new();
+  // Split on raw bytes: the output isn't guaranteed to be UTF-8, and the pipe must be drained to the end
+  for line in BufReader::new(reader).split(b'\n') {
+    let Ok(line) = line else {
+      break;
+    };
+    let line = String::from_utf8_lossy(&line).trim_end_matches('\r').to_string();
+    on_event(PrePushEvent::Output { stream, line: line.clone() });
+    tail.push(line);
+    if tail.len() > FAILURE_OUTPUT_LINES {
+      tail.remove(0);
+    }
+  }
+  tail
+}
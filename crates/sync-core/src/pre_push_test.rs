@@ -0,0 +1,108 @@
+use crate::pre_push::{OutputStream, PRE_PUSH_CHECK_CONFIG_KEY, PRE_PUSH_COMMAND_CONFIG_KEY, PrePushCheck, PrePushEvent, run_pre_push_check};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::{CommandError, ErrorCode};
+use pretty_assertions::assert_eq;
+use std::sync::Mutex;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// `test/virtual/feature` with `feature.txt`, while the working copy has an unrelated uncommitted file
+fn setup_virtual_branch(test_repo: &TestRepo) -> String {
+  test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  let feature = test_repo.create_commit("(feature) Add feature", "feature.txt", "feature\n");
+  test_repo.create_branch_at("test/virtual/feature", &feature).unwrap();
+  std::fs::write(test_repo.path().join("local.txt"), "not pushed\n").unwrap();
+  feature
+}
+
+#[cfg(unix)]
+fn install_hook(test_repo: &TestRepo, script: &str) {
+  use std::os::unix::fs::PermissionsExt;
+  let hook_path = test_repo.path().join(".git/hooks/pre-push");
+  std::fs::create_dir_all(hook_path.parent().unwrap()).unwrap();
+  std::fs::write(&hook_path, format!("#!/bin/sh\n{script}")).unwrap();
+  std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn run_check(test_repo: &TestRepo) -> (anyhow::Result<Option<PrePushCheck>>, Vec<PrePushEvent>) {
+  let events = Mutex::new(Vec::new());
+  let result = run_pre_push_check(&GitCommandExecutor::new(), test_repo.path().to_str().unwrap(), "test/virtual/feature", &|event| {
+    events.lock().unwrap().push(event);
+  });
+  (result, events.into_inner().unwrap())
+}
+
+fn output_lines(events: &[PrePushEvent], expected_stream: OutputStream) -> Vec<String> {
+  events
+    .iter()
+    .filter_map(|event| match event {
+      PrePushEvent::Output { stream, line } if *stream == expected_stream => Some(line.clone()),
+      _ => None,
+    })
+    .collect()
+}
+
+#[test]
+fn test_pre_push_check_disabled_by_default() {
+  let test_repo = TestRepo::new();
+  setup_virtual_branch(&test_repo);
+  test_repo.set_config(PRE_PUSH_COMMAND_CONFIG_KEY, "exit 1").unwrap();
+
+  let (result, events) = run_check(&test_repo);
+  assert_eq!(result.unwrap(), None);
+  assert_eq!(events, vec![]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pre_push_hook_runs_against_virtual_branch() {
+  let test_repo = TestRepo::new();
+  let feature = setup_virtual_branch(&test_repo);
+  test_repo.set_config(PRE_PUSH_CHECK_CONFIG_KEY, "true").unwrap();
+  // The worktree has the virtual branch tree, not the uncommitted file of the working copy
+  install_hook(
+    &test_repo,
+    "read local_ref local_oid remote_ref remote_oid\necho \"$1 $local_ref $local_oid\"\nls\necho checked >&2\n",
+  );
+
+  let (result, events) = run_check(&test_repo);
+  assert!(matches!(result.unwrap(), Some(PrePushCheck::Hook(_))));
+  assert_eq!(
+    output_lines(&events, OutputStream::Stdout),
+    vec![
+      format!("origin refs/heads/test/virtual/feature {feature}"),
+      "README.md".to_string(),
+      "feature.txt".to_string()
+    ]
+  );
+  assert_eq!(output_lines(&events, OutputStream::Stderr), vec!["checked".to_string()]);
+  assert_eq!(events.last(), Some(&PrePushEvent::Finished { exit_code: 0 }));
+  // The temporary worktree is removed
+  let worktrees = test_repo
+    .git_executor()
+    .execute_command_lines(&["worktree", "list"], test_repo.path().to_str().unwrap())
+    .unwrap();
+  assert_eq!(worktrees.len(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pre_push_command_failure() {
+  let test_repo = TestRepo::new();
+  setup_virtual_branch(&test_repo);
+  test_repo.set_config(PRE_PUSH_CHECK_CONFIG_KEY, "true").unwrap();
+  test_repo.set_config(PRE_PUSH_COMMAND_CONFIG_KEY, "echo 'lint: 2 warnings' >&2; exit 3").unwrap();
+
+  let (result, events) = run_check(&test_repo);
+  let error = CommandError::from(result.unwrap_err());
+  assert_eq!(error.code, ErrorCode::PrePushCheckFailed);
+  assert_eq!(error.context["exitCode"], "3");
+  assert_eq!(error.context["output"], "lint: 2 warnings");
+  assert_eq!(
+    events.first(),
+    Some(&PrePushEvent::Started {
+      command: "echo 'lint: 2 warnings' >&2; exit 3".to_string()
+    })
+  );
+  assert_eq!(events.last(), Some(&PrePushEvent::Finished { exit_code: 3 }));
+}
@@ -0,0 +1,164 @@
+//! Detection of probable typos in branch prefixes, e.g. `(feature-auht)` next to an existing `(feature-auth)`, and the
+//! fix that moves the commits of the misspelled branch to the suggested one.
+//!
+//! Names are compared word by word (words are separated by `-`, `_`, `/` or `.`). Two names are probably the same if they
+//! differ only in separators or case, or in a single word by one edit (insertion, deletion, substitution or swap of
+//! adjacent characters). Short words and words with digits are never considered typos, `api-v1` and `api-v2` or `ui` and
+//! `ux` are different branches. Issue-based branches (`ABC-123`) are not checked either.
+//!
+//! Of two such names, the one with fewer commits is the typo; with as many commits, the one grouped later.
+
+use crate::commit_grouper::load_commit_grouper;
+use crate::reword_commits::{RewordCommitsParams, RewordCommitsResult, reword_commits_core};
+use crate::sync::detect_baseline_branch;
+use anyhow::{Result, anyhow, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::commit_list::get_commit_list;
+use git_ops::model::validate_branch_name;
+use git_ops::reword_commits::RewordCommitParams;
+use serde::{Deserialize, Serialize};
+use sync_types::PrefixTypo;
+use sync_utils::issue_pattern::find_issue_number;
+use tracing::{info, instrument};
+
+/// Words shorter than this are never considered misspelled, one edit changes too much of them
+const MIN_WORD_LENGTH: usize = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct FixPrefixTypoParams {
+  pub repository_path: String,
+  /// Misspelled branch name
+  pub branch_name: String,
+  /// Branch name the commits get instead
+  pub suggested_branch_name: String,
+}
+
+/// Probable typos among the grouped branches, given as name and commit IDs in grouping order
+pub(crate) fn find_prefix_typos<'a>(branches: impl IntoIterator<Item = (&'a str, Vec<String>)>) -> Vec<PrefixTypo> {
+  let branches: Vec<(&str, Vec<String>)> = branches.into_iter().filter(|(name, _)| find_issue_number(name) != Some(*name)).collect();
+
+  let mut typos = Vec::new();
+  for (index, (name, commit_ids)) in branches.iter().enumerate() {
+    // The most used of the similar names that this one is the typo of
+    let suggestion = branches
+      .iter()
+      .enumerate()
+      .filter(|(other_index, (other_name, other_commit_ids))| {
+        let is_more_established = other_commit_ids.len() > commit_ids.len() || (other_commit_ids.len() == commit_ids.len() && *other_index < index);
+        is_more_established && is_probable_typo(name, other_name)
+      })
+      .max_by_key(|(other_index, (_, other_commit_ids))| (other_commit_ids.len(), std::cmp::Reverse(*other_index)));
+    if let Some((_, (suggested_name, _))) = suggestion {
+      typos.push(PrefixTypo {
+        branch_name: name.to_string(),
+        suggested_branch_name: suggested_name.to_string(),
+        commit_ids: commit_ids.clone(),
+      });
+    }
+  }
+  typos
+}
+
+fn is_word_separator(c: char) -> bool {
+  matches!(c, '-' | '_' | '/' | '.')
+}
+
+/// Whether `name` is probably a misspelling of `other`, or the other way around
+pub fn is_probable_typo(name: &str, other: &str) -> bool {
+  if name == other {
+    return false;
+  }
+  let words: Vec<String> = name.split(is_word_separator).map(str::to_lowercase).collect();
+  let other_words: Vec<String> = other.split(is_word_separator).map(str::to_lowercase).collect();
+  if words.len() != other_words.len() {
+    return false;
+  }
+
+  let mut differing = words.iter().zip(&other_words).filter(|(word, other_word)| word != other_word);
+  match (differing.next(), differing.next()) {
+    // Only separators or case differ
+    (None, _) => true,
+    (Some((word, other_word)), None) => {
+      let is_checked = |word: &str| word.chars().count() >= MIN_WORD_LENGTH && !word.contains(|c: char| c.is_ascii_digit());
+      is_checked(word) && is_checked(other_word) && edit_distance(word, other_word) <= 1
+    }
+    _ => false,
+  }
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions and swaps of adjacent characters
+fn edit_distance(a: &str, b: &str) -> usize {
+  let a: Vec<char> = a.chars().collect();
+  let b: Vec<char> = b.chars().collect();
+  let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+  for (i, row) in rows.iter_mut().enumerate() {
+    row[0] = i;
+  }
+  for (j, cell) in rows[0].iter_mut().enumerate() {
+    *cell = j;
+  }
+  for i in 1..=a.len() {
+    for j in 1..=b.len() {
+      let cost = usize::from(a[i - 1] != b[j - 1]);
+      let mut distance = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+      if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+        distance = distance.min(rows[i - 2][j - 2] + 1);
+      }
+      rows[i][j] = distance;
+    }
+  }
+  rows[a.len()][b.len()]
+}
+
+/// Replace the branch prefix of a commit message, keeping an autosquash marker (`fixup!`, `squash!`, `amend!`) in front of it
+pub(crate) fn replace_branch_prefix(message: &str, new_branch_name: &str) -> Option<String> {
+  let marker_len = ["fixup!", "squash!", "amend!"]
+    .iter()
+    .find(|marker| message.starts_with(*marker))
+    .map_or(0, |marker| message.len() - message[marker.len()..].trim_start().len());
+  let (marker, rest) = message.split_at(marker_len);
+  let close_paren_pos = rest.strip_prefix('(')?.find(')')?;
+  let text = rest[close_paren_pos + 2..].trim_start();
+  Some(format!("{marker}({new_branch_name}) {text}"))
+}
+
+/// Move the commits of a misspelled branch to the suggested branch by rewording their prefix.
+/// Commits are found by grouping, so commits whose prefix is sanitized to the misspelled name are fixed as well.
+#[instrument(skip(git_executor, params), fields(repository_path = %params.repository_path, branch_name = %params.branch_name, suggested_branch_name = %params.suggested_branch_name))]
+pub fn fix_prefix_typo_core(git_executor: &GitCommandExecutor, params: FixPrefixTypoParams) -> Result<RewordCommitsResult> {
+  let FixPrefixTypoParams {
+    repository_path,
+    branch_name,
+    suggested_branch_name,
+  } = params;
+  validate_branch_name(&suggested_branch_name).map_err(|e| anyhow!(e))?;
+  if find_issue_number(&branch_name) == Some(branch_name.as_str()) {
+    bail!("'{branch_name}' is grouped by issue reference, it has no prefix to fix");
+  }
+
+  let baseline_branch = detect_baseline_branch(git_executor, &repository_path, "master")?;
+  let mut grouper = load_commit_grouper(git_executor, &repository_path);
+  grouper.add_commits(git_executor, &repository_path, get_commit_list(git_executor, &repository_path, &baseline_branch)?);
+  let (mut grouped_commits, _, _) = grouper.finish();
+  let commits = grouped_commits
+    .shift_remove(&branch_name)
+    .ok_or_else(|| anyhow!("No commits with prefix '({branch_name})' ahead of {baseline_branch}"))?;
+
+  let rewrites = commits
+    .into_iter()
+    .map(|commit| {
+      let new_message = replace_branch_prefix(&commit.message, &suggested_branch_name).ok_or_else(|| anyhow!("Commit {} has no branch prefix", commit.id))?;
+      Ok(RewordCommitParams {
+        commit_id: commit.id,
+        new_message,
+      })
+    })
+    .collect::<Result<Vec<_>>>()?;
+
+  let reworded_count = rewrites.len();
+  let result = reword_commits_core(git_executor, RewordCommitsParams { repository_path, rewrites })?;
+  info!(reworded_count, "Fixed branch prefix typo");
+  Ok(result)
+}
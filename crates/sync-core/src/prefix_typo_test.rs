@@ -0,0 +1,106 @@
+use crate::prefix_typo::{FixPrefixTypoParams, find_prefix_typos, fix_prefix_typo_core, is_probable_typo, replace_branch_prefix};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use sync_types::PrefixTypo;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn ids(ids: &[&str]) -> Vec<String> {
+  ids.iter().map(|id| id.to_string()).collect()
+}
+
+#[test]
+fn test_is_probable_typo() {
+  assert!(is_probable_typo("feature-auht", "feature-auth"));
+  assert!(is_probable_typo("feature-logn", "feature-login"));
+  assert!(is_probable_typo("parsre", "parser"));
+  assert!(is_probable_typo("feature_auth", "feature-auth"));
+  assert!(is_probable_typo("Feature-Auth", "feature-auth"));
+
+  assert!(!is_probable_typo("feature-auth", "feature-auth"));
+  assert!(!is_probable_typo("api-v1", "api-v2"));
+  assert!(!is_probable_typo("ui", "ux"));
+  assert!(!is_probable_typo("feature-a", "feature-b"));
+  assert!(!is_probable_typo("fix-login-form", "fix-logon-from"));
+  assert!(!is_probable_typo("feature-auth", "feature-auth-v2"));
+  assert!(!is_probable_typo("frontend-login", "frontend-logout"));
+}
+
+#[test]
+fn test_find_prefix_typos() {
+  let typos = find_prefix_typos([
+    ("feature-auth", ids(&["c1", "c2"])),
+    ("feature-auht", ids(&["c3"])),
+    ("parser", ids(&["c4"])),
+    ("parsre", ids(&["c5"])),
+    ("ABC-123", ids(&["c6"])),
+    ("ABC-124", ids(&["c7"])),
+  ]);
+  assert_eq!(
+    typos,
+    vec![
+      PrefixTypo {
+        branch_name: "feature-auht".to_string(),
+        suggested_branch_name: "feature-auth".to_string(),
+        commit_ids: ids(&["c3"]),
+      },
+      // As many commits: the branch grouped later is the typo
+      PrefixTypo {
+        branch_name: "parsre".to_string(),
+        suggested_branch_name: "parser".to_string(),
+        commit_ids: ids(&["c5"]),
+      },
+    ]
+  );
+}
+
+#[test]
+fn test_replace_branch_prefix() {
+  assert_eq!(
+    replace_branch_prefix("(feature-auht) Add login\n\nBody", "feature-auth").as_deref(),
+    Some("(feature-auth) Add login\n\nBody")
+  );
+  assert_eq!(
+    replace_branch_prefix("fixup! (feature-auht) Add login", "feature-auth").as_deref(),
+    Some("fixup! (feature-auth) Add login")
+  );
+  assert_eq!(replace_branch_prefix("Add login", "feature-auth"), None);
+}
+
+#[test]
+fn test_fix_prefix_typo() {
+  let test_repo = TestRepo::new();
+  let repo_path = test_repo.path().to_str().unwrap().to_string();
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+  test_repo
+    .git_executor()
+    .execute_command(&["update-ref", "refs/remotes/origin/master", &baseline], &repo_path)
+    .unwrap();
+  test_repo.create_commit("(feature-auth) Add login", "login.rs", "fn login() {}\n");
+  test_repo.create_commit("(feature auht) Add logout", "logout.rs", "fn logout() {}\n");
+  test_repo.create_commit("(parser) Add parser", "parser.rs", "fn parse() {}\n");
+  let head_tree = test_repo.rev_parse("HEAD^{tree}").unwrap();
+
+  let result = fix_prefix_typo_core(
+    &GitCommandExecutor::new(),
+    FixPrefixTypoParams {
+      repository_path: repo_path,
+      branch_name: "feature-auht".to_string(),
+      suggested_branch_name: "feature-auth".to_string(),
+    },
+  )
+  .unwrap();
+
+  // The reworded commit and the one recreated on top of it
+  assert_eq!(result.commit_mapping.len(), 2);
+  assert_eq!(
+    test_repo.get_commit_messages(3),
+    vec![
+      "(parser) Add parser".to_string(),
+      "(feature-auth) Add logout".to_string(),
+      "(feature-auth) Add login".to_string()
+    ]
+  );
+  assert_eq!(test_repo.rev_parse("HEAD^{tree}").unwrap(), head_tree);
+}
@@ -0,0 +1,118 @@
+//! Moving a commit to another virtual branch by replacing its `(branch-name)` prefix.
+//!
+//! Before the commit is reworded, both branches are replayed with merge-tree as the next sync would build them: the source
+//! branch without the commit and the target branch with it. The reword only runs if both replay cleanly.
+
+use crate::commit_grouper::load_commit_grouper;
+use crate::operation_plan::strip_branch_prefix;
+use crate::sync::{detect_baseline_branch, get_parent_commit_hash};
+use crate::sync_dry_run::{BranchSyncPrediction, predict_branch_sync};
+use anyhow::{Result, anyhow, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::cache::TreeIdCache;
+use git_ops::commit_list::{Commit, get_commit_list};
+use git_ops::diff_options::load_diff_options;
+use git_ops::model::validate_branch_name;
+use git_ops::reword_commits::{RewordCommitParams, reword_commits_batch};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{info, instrument};
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ReassignCommitParams {
+  pub repository_path: String,
+  pub commit_id: String,
+  pub target_branch: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ReassignCommitResult {
+  /// Branch the commit leaves without it; `None` if the commit was unassigned
+  pub source: Option<BranchSyncPrediction>,
+  /// Target branch with the commit
+  pub target: BranchSyncPrediction,
+  /// New ID of the reworded commit; `None` if a predicted conflict or error prevented the rewrite
+  pub new_commit_id: Option<String>,
+}
+
+impl ReassignCommitResult {
+  fn is_clean(&self) -> bool {
+    self.source.iter().chain([&self.target]).all(|branch| branch.conflict.is_none() && branch.error.is_none())
+  }
+}
+
+/// Predict whether both branches still build after moving the commit to `target_branch` and, if they do, reword the commit.
+/// Commits keep their history order in both branches.
+#[instrument(skip(git_executor, params), fields(repository_path = %params.repository_path, commit_id = %params.commit_id, target_branch = %params.target_branch))]
+pub fn reassign_commit_core(git_executor: &GitCommandExecutor, params: ReassignCommitParams) -> Result<ReassignCommitResult> {
+  let ReassignCommitParams {
+    repository_path,
+    commit_id,
+    target_branch,
+  } = params;
+  let repo = repository_path.as_str();
+  validate_branch_name(&target_branch).map_err(|e| anyhow!(e))?;
+
+  let baseline_branch = detect_baseline_branch(git_executor, repo, "master")?;
+  let mut grouper = load_commit_grouper(git_executor, repo);
+  let commits = get_commit_list(git_executor, repo, &baseline_branch)?;
+  let positions: HashMap<String, usize> = commits.iter().enumerate().map(|(position, commit)| (commit.id.clone(), position)).collect();
+  grouper.add_commits(git_executor, repo, commits);
+  let oldest_commit = grouper.oldest_commit.clone();
+  let (mut grouped_commits, unassigned_commits, _) = grouper.finish();
+
+  let source_branch = grouped_commits
+    .iter()
+    .find(|(_, commits)| commits.iter().any(|commit| commit.id == commit_id))
+    .map(|(branch_name, _)| branch_name.clone());
+  if source_branch.as_deref() == Some(target_branch.as_str()) {
+    bail!("Commit {commit_id} already belongs to branch '{target_branch}'");
+  }
+  let moved_commit = match &source_branch {
+    Some(branch_name) => grouped_commits[branch_name].iter().find(|commit| commit.id == commit_id).cloned(),
+    None => unassigned_commits.into_iter().find(|commit| commit.id == commit_id),
+  }
+  .ok_or_else(|| anyhow!("Commit {commit_id} is not ahead of {baseline_branch}"))?;
+
+  let parent_commit_hash = get_parent_commit_hash(git_executor, repo, oldest_commit.as_ref().map(|commit| commit.id.as_str()))?;
+  let diff_options = load_diff_options(git_executor, repo);
+  let tree_id_cache = TreeIdCache::new();
+  let predict = |branch_name: String, commits: &[Commit]| predict_branch_sync(git_executor, repo, &parent_commit_hash, branch_name, commits, &tree_id_cache, &diff_options);
+
+  let source = source_branch.map(|branch_name| {
+    let commits: Vec<Commit> = grouped_commits[&branch_name].iter().filter(|commit| commit.id != commit_id).cloned().collect();
+    predict(branch_name, &commits)
+  });
+  let mut target_commits = grouped_commits.shift_remove(&target_branch).unwrap_or_default();
+  target_commits.push(moved_commit);
+  target_commits.sort_by_key(|commit| positions[&commit.id]);
+  let target = predict(target_branch.clone(), &target_commits);
+
+  let mut result = ReassignCommitResult {
+    source,
+    target,
+    new_commit_id: None,
+  };
+  if !result.is_clean() {
+    info!("Not reassigning commit, predicted conflicts");
+    return Ok(result);
+  }
+
+  let message = git_executor.execute_command(&["--no-pager", "log", "-1", "--format=%B", &commit_id], repo)?;
+  let new_message = format!("({target_branch}) {}", strip_branch_prefix(message.trim()));
+  let mut mapping = reword_commits_batch(
+    git_executor,
+    repo,
+    vec![RewordCommitParams {
+      commit_id: commit_id.clone(),
+      new_message,
+    }],
+  )?;
+  result.new_commit_id = mapping.remove(&commit_id);
+  info!(new_commit_id = ?result.new_commit_id, "Reassigned commit");
+  Ok(result)
+}
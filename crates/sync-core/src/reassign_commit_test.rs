@@ -0,0 +1,74 @@
+use crate::reassign_commit::{ReassignCommitParams, reassign_commit_core};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn setup_baseline(test_repo: &TestRepo) {
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+  test_repo
+    .git_executor()
+    .execute_command(&["update-ref", "refs/remotes/origin/master", &baseline], test_repo.path_str())
+    .unwrap();
+}
+
+fn reassign_params(test_repo: &TestRepo, commit_id: &str, target_branch: &str) -> ReassignCommitParams {
+  ReassignCommitParams {
+    repository_path: test_repo.path_str().to_string(),
+    commit_id: commit_id.to_string(),
+    target_branch: target_branch.to_string(),
+  }
+}
+
+#[test]
+fn test_reassign_commit() {
+  let test_repo = TestRepo::new();
+  setup_baseline(&test_repo);
+  test_repo.create_commit("(parser) Add parser", "parser.rs", "fn parse() {}\n");
+  test_repo.create_commit("(lexer) Add lexer", "lexer.rs", "fn lex() {}\n");
+  let tokens = test_repo.create_commit("(parser) Add tokens", "tokens.rs", "enum Token {}\n");
+  let head_tree = test_repo.rev_parse("HEAD^{tree}").unwrap();
+
+  let result = reassign_commit_core(&GitCommandExecutor::new(), reassign_params(&test_repo, &tokens, "lexer")).unwrap();
+
+  let source = result.source.unwrap();
+  assert_eq!((source.branch_name.as_str(), source.commit_count), ("parser", 1));
+  assert!(source.conflict.is_none() && source.error.is_none());
+  assert_eq!((result.target.branch_name.as_str(), result.target.commit_count), ("lexer", 2));
+  assert!(result.target.conflict.is_none() && result.target.error.is_none());
+  assert_eq!(result.new_commit_id, Some(test_repo.rev_parse("HEAD").unwrap()));
+  assert_eq!(
+    test_repo.get_commit_messages(3),
+    vec!["(lexer) Add tokens".to_string(), "(lexer) Add lexer".to_string(), "(parser) Add parser".to_string()]
+  );
+  assert_eq!(test_repo.rev_parse("HEAD^{tree}").unwrap(), head_tree);
+}
+
+#[test]
+fn test_reassign_commit_with_predicted_conflict() {
+  let test_repo = TestRepo::new();
+  setup_baseline(&test_repo);
+  test_repo.create_commit("(config) Add config", "config.txt", "timeout=30\n");
+  let change = test_repo.create_commit("(config) Lower timeout", "config.txt", "timeout=10\n");
+  let head = test_repo.rev_parse("HEAD").unwrap();
+
+  let result = reassign_commit_core(&GitCommandExecutor::new(), reassign_params(&test_repo, &change, "network")).unwrap();
+
+  // The change depends on the commit that stays in `config`, so `network` can't be built on the baseline
+  let source = result.source.unwrap();
+  assert!(source.conflict.is_none() && source.error.is_none());
+  assert_eq!((result.target.branch_name.as_str(), result.target.commit_count), ("network", 1));
+  assert!(result.target.conflict.is_some());
+  assert_eq!(result.new_commit_id, None);
+  assert_eq!(test_repo.rev_parse("HEAD").unwrap(), head);
+}
+
+#[test]
+fn test_reassign_commit_to_same_branch() {
+  let test_repo = TestRepo::new();
+  setup_baseline(&test_repo);
+  let commit = test_repo.create_commit("(parser) Add parser", "parser.rs", "fn parse() {}\n");
+
+  assert!(reassign_commit_core(&GitCommandExecutor::new(), reassign_params(&test_repo, &commit, "parser")).is_err());
+}
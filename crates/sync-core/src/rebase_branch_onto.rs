@@ -0,0 +1,140 @@
+//! Rebuilding a virtual branch on an arbitrary ref (e.g., a release branch) instead of the baseline.
+//!
+//! The commits are copied exactly like sync does it, so progress and conflicts are reported with the same events,
+//! but into a separately named branch (`<prefix>/<ref>/<name>`) that sync never touches.
+
+use crate::branch_processor::{BranchProcessingParams, process_single_branch};
+use crate::commit_grouper::load_commit_grouper;
+use crate::commit_order::order_commits;
+use crate::conflict_mode::{ConflictMode, load_conflict_mode};
+use crate::empty_branch_policy::EmptyBranchPolicy;
+use crate::repo_state::load_repo_state;
+use crate::sync::detect_baseline_branch;
+use crate::sync_performance::SyncTimings;
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::cache::TreeIdCache;
+use git_ops::case_collision::load_ignore_case;
+use git_ops::commit_list::get_commit_list;
+use git_ops::commit_utils::{load_commit_trailer_templates, resolve_commit_signing};
+use git_ops::diff_options::load_diff_options;
+use git_ops::empty_commit_policy::load_empty_commit_policy;
+use git_ops::model::to_rebased_branch_name;
+use git_ops::partial_clone::detect_partial_clone;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use sync_types::ProgressReporter;
+use tracing::{info, instrument, warn};
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct RebaseBranchOntoParams {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  pub branch_name: String,
+  /// Ref to rebuild the branch on (e.g., "origin/release-1.2")
+  pub onto: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct RebaseBranchOntoResult {
+  /// Full name of the rebuilt branch (e.g., "user/release-1.2/feature-x"), created or updated unless a conflict was reported
+  pub target_branch: String,
+  pub commit_count: u32,
+}
+
+/// Copy the commits of a virtual branch on top of `onto` into `<prefix>/<ref>/<name>`.
+/// Commit and branch status events are sent for `branch_name`, as during sync.
+#[instrument(skip(git_executor, progress), fields(repository_path = %params.repository_path, branch_name = %params.branch_name, onto = %params.onto))]
+pub fn rebase_branch_onto_core<P: ProgressReporter + Clone>(git_executor: &GitCommandExecutor, params: RebaseBranchOntoParams, progress: P) -> Result<RebaseBranchOntoResult> {
+  let RebaseBranchOntoParams {
+    repository_path,
+    branch_prefix,
+    branch_name,
+    onto,
+  } = params;
+  let repo = repository_path.as_str();
+
+  let target_branch = to_rebased_branch_name(&branch_prefix, &onto, &branch_name)?;
+  let onto_commit = git_executor
+    .execute_command(&["rev-parse", "--verify", "--quiet", &format!("{onto}^{{commit}}")], repo)
+    .map_err(|_| anyhow!("Ref '{onto}' not found"))?
+    .trim()
+    .to_string();
+
+  let baseline_branch = detect_baseline_branch(git_executor, repo, "master")?;
+  let mut grouper = load_commit_grouper(git_executor, repo);
+  grouper.add_commits(git_executor, repo, get_commit_list(git_executor, repo, &baseline_branch)?);
+  let (mut grouped_commits, _, _) = grouper.finish();
+  let mut commits = grouped_commits.shift_remove(&branch_name).ok_or_else(|| anyhow!("Branch '{branch_name}' has no commits"))?;
+
+  // Same commit order as the virtual branch
+  match load_repo_state(git_executor, repo) {
+    Ok(state) => {
+      if let Some(order) = state.commit_order.get(&branch_name) {
+        order_commits(&mut commits, *order, &HashMap::new());
+      }
+    }
+    Err(e) => warn!(error = %e, "Failed to load repository state, using source commit order"),
+  }
+
+  let commit_count = commits.len() as u32;
+  // There are no other branches to abort
+  let conflict_mode = match load_conflict_mode(git_executor, repo) {
+    ConflictMode::AbortSync => ConflictMode::StopBranch,
+    conflict_mode => conflict_mode,
+  };
+  let partial_clone = detect_partial_clone(git_executor, repo).unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to detect partial clone, assuming full clone");
+    None
+  });
+  let signing = resolve_commit_signing(git_executor, repo).unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to resolve commit signing settings, commits will not be signed");
+    None
+  });
+  let trailer_templates = load_commit_trailer_templates(git_executor, repo).unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to load commit trailer templates, no trailers will be added");
+    Vec::new()
+  });
+
+  process_single_branch(BranchProcessingParams {
+    repository_path: repository_path.clone(),
+    branch_prefix,
+    branch_name,
+    target_branch: Some(target_branch.clone()),
+    commits,
+    parent_commit_hash: onto_commit,
+    current_branch_idx: 0,
+    total_branches: 1,
+    progress,
+    git_executor: git_executor.clone(),
+    tree_id_cache: TreeIdCache::new(),
+    git_notes_mutex: Arc::new(Mutex::new(())),
+    my_email: None,
+    baseline_branch: onto,
+    partial_clone: partial_clone.map(Arc::new),
+    signing: signing.map(Arc::new),
+    trailer_templates: trailer_templates.into(),
+    // Sync hooks are not run for an explicit rebuild
+    branch_hooks: Arc::default(),
+    copy_user_notes: false,
+    conflict_mode,
+    // The rebuilt branch is requested explicitly, so it is kept even if the ref already has all of its commits
+    empty_branch_policy: EmptyBranchPolicy::Keep,
+    empty_commit_policy: load_empty_commit_policy(git_executor, repo),
+    diff_options: load_diff_options(git_executor, repo),
+    ignore_case: load_ignore_case(git_executor, repo),
+    sync_aborted: Arc::new(AtomicBool::new(false)),
+    integrated_branches: Arc::new(Mutex::new(HashSet::new())),
+    // Not a sync run, so the timings are not reported
+    timings: SyncTimings::default(),
+  })?;
+
+  info!(%target_branch, commit_count, "Rebuilt branch on another ref");
+  Ok(RebaseBranchOntoResult { target_branch, commit_count })
+}
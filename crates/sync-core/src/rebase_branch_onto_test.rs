@@ -0,0 +1,104 @@
+use crate::rebase_branch_onto::{RebaseBranchOntoParams, rebase_branch_onto_core};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::model::BranchSyncStatus;
+use pretty_assertions::assert_eq;
+use sync_test_utils::TestReporter;
+use sync_types::SyncEvent;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// `feature` commits on top of `origin/main`, and a `release-1.2` branch forked from the initial commit with a change of its own
+fn create_release_repo(release_file: &str, release_content: &str) -> TestRepo {
+  let test_repo = TestRepo::new();
+  let initial_id = test_repo.create_commit("Initial commit", "config.txt", "timeout=30\n");
+  test_repo.create_branch_at("origin/main", &initial_id).unwrap();
+  let main_branch = test_repo.current_branch().unwrap();
+  test_repo.checkout_new_branch("release-1.2").unwrap();
+  test_repo.create_commit("Release settings", release_file, release_content);
+  test_repo.checkout(&main_branch).unwrap();
+
+  test_repo.create_commit("(feature) Add parser", "parser.rs", "fn parse() {}\n");
+  test_repo.create_commit("(feature) Lower timeout", "config.txt", "timeout=10\n");
+  test_repo
+}
+
+fn rebase(test_repo: &TestRepo, progress: &TestReporter) -> anyhow::Result<String> {
+  let result = rebase_branch_onto_core(
+    &GitCommandExecutor::new(),
+    RebaseBranchOntoParams {
+      repository_path: test_repo.path().to_str().unwrap().to_string(),
+      branch_prefix: "test".to_string(),
+      branch_name: "feature".to_string(),
+      onto: "release-1.2".to_string(),
+    },
+    progress.clone(),
+  )?;
+  assert_eq!(result.commit_count, 2);
+  Ok(result.target_branch)
+}
+
+fn branch_statuses(events: &[SyncEvent]) -> Vec<BranchSyncStatus> {
+  events
+    .iter()
+    .filter_map(|event| match event {
+      SyncEvent::BranchStatusUpdate { branch_name, status, .. } if branch_name == "feature" => Some(status.clone()),
+      _ => None,
+    })
+    .collect()
+}
+
+#[test]
+fn test_rebase_branch_onto_release_branch() -> anyhow::Result<()> {
+  // The release only changes an unrelated file, so the branch applies cleanly
+  let test_repo = create_release_repo("release.txt", "1.2\n");
+  let progress = TestReporter::new();
+
+  let target_branch = rebase(&test_repo, &progress)?;
+
+  assert_eq!(target_branch, "test/release-1.2/feature");
+  let subjects = test_repo.log(&["--pretty=format:%s", &target_branch]).unwrap();
+  assert_eq!(
+    subjects.lines().collect::<Vec<_>>(),
+    vec!["(feature) Lower timeout", "(feature) Add parser", "Release settings", "Initial commit"]
+  );
+  assert_eq!(branch_statuses(&progress.get_events()), vec![BranchSyncStatus::Created]);
+  // The virtual branch is left to sync, the notes keep mapping to it
+  assert!(!test_repo.branch_exists("test/virtual/feature"));
+  assert_eq!(test_repo.log(&["--pretty=format:%N", "HEAD~2..HEAD"]).unwrap().trim(), "");
+  Ok(())
+}
+
+#[test]
+fn test_rebase_branch_onto_reports_conflict_like_sync() -> anyhow::Result<()> {
+  let test_repo = create_release_repo("config.txt", "timeout=60\n");
+  let progress = TestReporter::new();
+
+  let target_branch = rebase(&test_repo, &progress)?;
+
+  let events = progress.get_events();
+  assert_eq!(branch_statuses(&events).last(), Some(&BranchSyncStatus::MergeConflict));
+  assert!(
+    events
+      .iter()
+      .any(|event| matches!(event, SyncEvent::CommitError { branch_name, .. } if branch_name == "feature"))
+  );
+  assert!(!test_repo.branch_exists(&target_branch));
+  Ok(())
+}
+
+#[test]
+fn test_rebase_branch_onto_rejects_reserved_ref_name() {
+  let test_repo = create_release_repo("release.txt", "1.2\n");
+  let error = rebase_branch_onto_core(
+    &GitCommandExecutor::new(),
+    RebaseBranchOntoParams {
+      repository_path: test_repo.path().to_str().unwrap().to_string(),
+      branch_prefix: "test".to_string(),
+      branch_name: "feature".to_string(),
+      onto: "archived".to_string(),
+    },
+    TestReporter::new(),
+  )
+  .unwrap_err();
+  assert_eq!(error.to_string(), "'archived' is reserved and can't be used as the name of the ref");
+}
@@ -0,0 +1,229 @@
+//! Operations interrupted by a crash and the ways to recover from them.
+//! Nothing is recovered on its own (except the refs of an interrupted sync other than the checked out branch, rolled
+//! back by the next sync): the UI shows what was found and the user picks one of the [`RecoveryOption`]s.
+
+use anyhow::{Result, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::ref_transaction::{RefTransaction, checked_out_branch_to_roll_back, finish_ref_journal, load_ref_journal, roll_back_ref_journal};
+use git_ops::repository_lock::{is_process_alive, lock_repository};
+use git_ops::reword_commits::REWRITE_REFLOG_MESSAGE;
+use git_ops::temp_dir::{repository_temp_prefix, temp_name_owner_pid};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, instrument, warn};
+
+/// Age after which lock and temporary files are considered left behind by a crash, running operations hold them for seconds
+pub const STALE_TEMP_FILE_AGE: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum InterruptedOperationKind {
+  /// A sync crashed while moving refs
+  Sync,
+  /// The checked out branch was rewritten (amend, reword, drop), but the index wasn't updated to the rewritten commit
+  Rewrite,
+  /// Lock and temporary index files left behind
+  TempFiles,
+}
+
+/// An operation interrupted by a crash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedOperation {
+  pub kind: InterruptedOperationKind,
+  /// What was interrupted, for display
+  pub description: String,
+  /// Ways to recover, the recommended one first
+  pub options: Vec<RecoveryOption>,
+}
+
+/// One way to recover from an interrupted operation, passed back as is to [`apply_recovery_option`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum RecoveryOption {
+  /// Move the refs of the interrupted sync back to where they were before it started, the checked out branch too
+  RollBackSync,
+  /// Keep the refs as the interrupted sync left them, the next sync brings the rest up to date
+  KeepSyncRefs,
+  /// Move the branch back to the commit before the rewrite
+  RestorePreviousRef {
+    branch_ref: String,
+    previous_commit: String,
+    rewritten_commit: String,
+  },
+  /// Finish the rewrite by updating the index to the rewritten commit, the working tree is left alone
+  ContinueRewrite { branch_ref: String, rewritten_commit: String },
+  /// Delete the lock and temporary files
+  RemoveTempFiles,
+}
+
+/// Find the operations interrupted by a crash, empty if the repository is in a consistent state
+#[instrument(skip(git_executor))]
+pub fn detect_interrupted_operations(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<Vec<InterruptedOperation>> {
+  let mut operations = Vec::new();
+
+  // A journal of a sync that is still running (it holds the lock) is not interrupted
+  if let Some(journal) = load_ref_journal(git_executor, repository_path)?
+    && lock_repository(git_executor, repository_path).is_ok()
+  {
+    let mut description = format!("\"{}\" was interrupted while moving {} refs", journal.description, journal.ref_prefixes.join(", "));
+    if let Some(branch_ref) = checked_out_branch_to_roll_back(git_executor, repository_path, &journal)? {
+      let _ = write!(description, ", rolling back moves the checked out branch {branch_ref} and resets the index");
+    }
+    operations.push(InterruptedOperation {
+      kind: InterruptedOperationKind::Sync,
+      description,
+      options: vec![RecoveryOption::RollBackSync, RecoveryOption::KeepSyncRefs],
+    });
+  }
+
+  if let Some((branch_ref, previous_commit, rewritten_commit)) = detect_interrupted_rewrite(git_executor, repository_path)? {
+    operations.push(InterruptedOperation {
+      kind: InterruptedOperationKind::Rewrite,
+      description: format!("Rewrite of {branch_ref} was interrupted, the index still matches the commit before the rewrite"),
+      options: vec![
+        RecoveryOption::ContinueRewrite {
+          branch_ref: branch_ref.clone(),
+          rewritten_commit: rewritten_commit.clone(),
+        },
+        RecoveryOption::RestorePreviousRef {
+          branch_ref,
+          previous_commit,
+          rewritten_commit,
+        },
+      ],
+    });
+  }
+
+  let temp_files = find_stale_temp_files(git_executor, repository_path)?;
+  if !temp_files.is_empty() {
+    let paths: Vec<String> = temp_files.iter().map(|path| path.display().to_string()).collect();
+    operations.push(InterruptedOperation {
+      kind: InterruptedOperationKind::TempFiles,
+      description: format!("Lock and temporary files were left behind: {}", paths.join(", ")),
+      options: vec![RecoveryOption::RemoveTempFiles],
+    });
+  }
+
+  debug!(count = operations.len(), "Detected interrupted operations");
+  Ok(operations)
+}
+
+/// The checked out branch, its commit before the last rewrite and the rewritten commit, if the branch was moved by a
+/// rewrite and the index still matches the commit before it
+fn detect_interrupted_rewrite(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<Option<(String, String, String)>> {
+  let (head_ref, exit_code) = git_executor.execute_command_with_status(&["symbolic-ref", "--quiet", "HEAD"], repository_path)?;
+  if exit_code != 0 {
+    return Ok(None);
+  }
+  let head_ref = head_ref.trim();
+
+  let (last_entry, exit_code) = git_executor.execute_command_with_status(&["reflog", "show", "-n", "1", "--format=%H %gs", head_ref], repository_path)?;
+  let Some((rewritten_commit, message)) = last_entry.trim().split_once(' ').filter(|_| exit_code == 0) else {
+    return Ok(None);
+  };
+  if message != REWRITE_REFLOG_MESSAGE {
+    return Ok(None);
+  }
+
+  let (previous_commit, exit_code) = git_executor.execute_command_with_status(&["rev-parse", "--verify", "--quiet", &format!("{head_ref}@{{1}}")], repository_path)?;
+  if exit_code != 0 {
+    return Ok(None);
+  }
+  let previous_commit = previous_commit.trim();
+
+  // The rewrite resets the index last, unless the rewritten commit has the same tree
+  let index_matches = |commit: &str| -> Result<bool> {
+    let (_, exit_code) = git_executor.execute_command_with_status(&["diff-index", "--cached", "--quiet", commit, "--"], repository_path)?;
+    Ok(exit_code == 0)
+  };
+  if index_matches(rewritten_commit)? || !index_matches(previous_commit)? {
+    return Ok(None);
+  }
+  Ok(Some((head_ref.to_string(), previous_commit.to_string(), rewritten_commit.to_string())))
+}
+
+/// Index lock of the repository and temporary files of Branch Deck created for it by processes that are gone, older than
+/// [`STALE_TEMP_FILE_AGE`]. A directory is as old as the newest entry in it, e.g. the files of a pre-push worktree.
+fn find_stale_temp_files(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<Vec<PathBuf>> {
+  let git_dir = git_executor.execute_command(&["rev-parse", "--absolute-git-dir"], repository_path)?;
+  let mut candidates = vec![Path::new(git_dir.trim()).join("index.lock")];
+  let prefix = repository_temp_prefix(repository_path);
+  if let Ok(entries) = fs::read_dir(std::env::temp_dir()) {
+    candidates.extend(
+      entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| temp_name_owner_pid(&entry.file_name().to_string_lossy(), &prefix).is_some_and(|pid| !is_process_alive(pid)))
+        .map(|entry| entry.path()),
+    );
+  }
+
+  let is_stale = |path: &PathBuf| latest_modification(path).is_some_and(|modified| modified.elapsed().is_ok_and(|age| age >= STALE_TEMP_FILE_AGE));
+  Ok(candidates.into_iter().filter(is_stale).collect())
+}
+
+/// Latest modification time of the path and, for a directory, of everything in it; symlinks are not followed
+fn latest_modification(path: &Path) -> Option<SystemTime> {
+  let metadata = fs::symlink_metadata(path).ok()?;
+  let mut latest = metadata.modified().ok()?;
+  if metadata.is_dir() {
+    for entry in fs::read_dir(path).ok()?.filter_map(|entry| entry.ok()) {
+      latest = latest.max(latest_modification(&entry.path())?);
+    }
+  }
+  Some(latest)
+}
+
+/// Recover from an interrupted operation with one of the options returned by [`detect_interrupted_operations`]
+#[instrument(skip(git_executor))]
+pub fn apply_recovery_option(git_executor: &GitCommandExecutor, repository_path: &str, option: &RecoveryOption) -> Result<()> {
+  match option {
+    RecoveryOption::RollBackSync => {
+      let lock = lock_repository(git_executor, repository_path)?;
+      if roll_back_ref_journal(git_executor, repository_path, &lock, true)?.is_none() {
+        bail!("There is no interrupted sync to roll back");
+      }
+    }
+    RecoveryOption::KeepSyncRefs => {
+      let _lock = lock_repository(git_executor, repository_path)?;
+      match load_ref_journal(git_executor, repository_path)? {
+        Some(journal) => finish_ref_journal(git_executor, repository_path, &journal)?,
+        None => bail!("There is no interrupted sync to keep"),
+      }
+    }
+    RecoveryOption::RestorePreviousRef {
+      branch_ref,
+      previous_commit,
+      rewritten_commit,
+    } => {
+      // The index already matches the previous commit, so only the branch moves
+      RefTransaction::new()
+        .update(branch_ref, previous_commit, Some(rewritten_commit))
+        .commit(git_executor, repository_path, "branch-deck: restore before interrupted rewrite")?;
+    }
+    RecoveryOption::ContinueRewrite { branch_ref, rewritten_commit } => {
+      let head_commit = git_executor.execute_command(&["rev-parse", branch_ref], repository_path)?;
+      if head_commit.trim() != rewritten_commit {
+        bail!("{branch_ref} was moved since the rewrite, it's not at {rewritten_commit} anymore");
+      }
+      git_executor.execute_command(&["reset", "--mixed", "-q", rewritten_commit], repository_path)?;
+    }
+    RecoveryOption::RemoveTempFiles => {
+      // Found again, so only files that are still stale are removed
+      for path in find_stale_temp_files(git_executor, repository_path)? {
+        let result = if path.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+        if let Err(e) = result {
+          warn!(path = %path.display(), error = %e, "Failed to remove temporary file");
+        }
+      }
+    }
+  }
+  info!(?option, "Recovered from interrupted operation");
+  Ok(())
+}
@@ -0,0 +1,206 @@
+use crate::recovery::{InterruptedOperation, InterruptedOperationKind, RecoveryOption, STALE_TEMP_FILE_AGE, apply_recovery_option, detect_interrupted_operations};
+use git_ops::ref_transaction::{RefTransaction, begin_ref_journal};
+use git_ops::repository_lock::lock_repository;
+use git_ops::reword_commits::update_branch_ref;
+use pretty_assertions::assert_eq;
+use std::time::SystemTime;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn repository_operations(test_repo: &TestRepo) -> Vec<InterruptedOperation> {
+  detect_interrupted_operations(test_repo.git_executor(), test_repo.path().to_str().unwrap()).unwrap()
+}
+
+/// Repository where the checked out branch was rewritten, but the crash happened before the index was reset
+fn interrupted_rewrite() -> (TestRepo, String, String) {
+  let test_repo = TestRepo::new();
+  let first = test_repo.create_commit("First", "a.txt", "a");
+  let previous = test_repo.create_commit("Second", "b.txt", "b");
+  let first_tree = test_repo.rev_parse(&format!("{first}^{{tree}}")).unwrap();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let rewritten = test_repo
+    .git_executor()
+    .execute_command(&["commit-tree", &first_tree, "-p", &first, "-m", "Second without b.txt"], repo_path)
+    .unwrap()
+    .trim()
+    .to_string();
+  let branch = test_repo.current_branch().unwrap();
+  update_branch_ref(test_repo.git_executor(), repo_path, &branch, &rewritten).unwrap();
+  (test_repo, previous, rewritten)
+}
+
+#[test]
+fn test_nothing_to_recover() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("First", "a.txt", "a");
+  assert_eq!(repository_operations(&test_repo), vec![]);
+}
+
+#[test]
+fn test_roll_back_interrupted_sync() {
+  let test_repo = TestRepo::new();
+  let first = test_repo.create_commit("First", "a.txt", "a");
+  let second = test_repo.create_commit("Second", "b.txt", "b");
+  test_repo.create_branch_at("user/virtual/a", &first).unwrap();
+  let git_executor = test_repo.git_executor();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  // The sync crashed, so its lock is gone
+  let lock = lock_repository(git_executor, repo_path).unwrap();
+  begin_ref_journal(git_executor, repo_path, &lock, "sync user", &["refs/heads/user/"]).unwrap();
+  drop(lock);
+  RefTransaction::new()
+    .update("refs/heads/user/virtual/a", &second, None)
+    .commit(git_executor, repo_path, "test")
+    .unwrap();
+
+  let operations = repository_operations(&test_repo);
+  assert_eq!(operations.len(), 1);
+  assert_eq!(operations[0].kind, InterruptedOperationKind::Sync);
+  assert_eq!(operations[0].options, vec![RecoveryOption::RollBackSync, RecoveryOption::KeepSyncRefs]);
+
+  apply_recovery_option(git_executor, repo_path, &operations[0].options[0]).unwrap();
+  assert_eq!(test_repo.rev_parse("user/virtual/a").unwrap(), first);
+  assert_eq!(repository_operations(&test_repo), vec![]);
+}
+
+#[test]
+fn test_roll_back_interrupted_sync_restores_checked_out_branch() {
+  let test_repo = TestRepo::new();
+  let first = test_repo.create_commit("First", "a.txt", "a");
+  let second = test_repo.create_commit("Second", "b.txt", "b");
+  let head_ref = format!("refs/heads/{}", test_repo.current_branch().unwrap());
+  let git_executor = test_repo.git_executor();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  // The sync crashed after dropping a commit from the checked out branch
+  let lock = lock_repository(git_executor, repo_path).unwrap();
+  begin_ref_journal(git_executor, repo_path, &lock, "sync user", &["refs/heads/user/", &head_ref]).unwrap();
+  drop(lock);
+  RefTransaction::new().update(&head_ref, &first, None).commit(git_executor, repo_path, "test").unwrap();
+
+  let operations = repository_operations(&test_repo);
+  assert_eq!(operations.len(), 1);
+  assert!(operations[0].description.contains(&format!("checked out branch {head_ref}")));
+
+  apply_recovery_option(git_executor, repo_path, &RecoveryOption::RollBackSync).unwrap();
+  assert_eq!(test_repo.head(), second);
+  assert_eq!(repository_operations(&test_repo), vec![]);
+}
+
+#[test]
+fn test_continue_interrupted_rewrite() {
+  let (test_repo, _, rewritten) = interrupted_rewrite();
+  let git_executor = test_repo.git_executor();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let operations = repository_operations(&test_repo);
+  assert_eq!(operations.len(), 1);
+  assert_eq!(operations[0].kind, InterruptedOperationKind::Rewrite);
+  let continue_rewrite = &operations[0].options[0];
+  assert!(matches!(continue_rewrite, RecoveryOption::ContinueRewrite { rewritten_commit, .. } if *rewritten_commit == rewritten));
+
+  apply_recovery_option(git_executor, repo_path, continue_rewrite).unwrap();
+  assert_eq!(test_repo.head(), rewritten);
+  assert_eq!(repository_operations(&test_repo), vec![]);
+}
+
+#[test]
+fn test_restore_ref_before_interrupted_rewrite() {
+  let (test_repo, previous, _) = interrupted_rewrite();
+  let git_executor = test_repo.git_executor();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  let operations = repository_operations(&test_repo);
+  let restore = operations[0]
+    .options
+    .iter()
+    .find(|option| matches!(option, RecoveryOption::RestorePreviousRef { .. }))
+    .unwrap();
+
+  apply_recovery_option(git_executor, repo_path, restore).unwrap();
+  assert_eq!(test_repo.head(), previous);
+  assert_eq!(repository_operations(&test_repo), vec![]);
+}
+
+#[test]
+fn test_remove_stale_index_lock() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("First", "a.txt", "a");
+  let git_executor = test_repo.git_executor();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let lock_path = test_repo.path().join(".git").join("index.lock");
+
+  // A lock held right now belongs to a running git command
+  let lock = std::fs::File::create(&lock_path).unwrap();
+  assert!(
+    !detect_interrupted_operations(git_executor, repo_path)
+      .unwrap()
+      .iter()
+      .any(|operation| operation.description.contains("index.lock"))
+  );
+
+  lock.set_modified(SystemTime::now() - STALE_TEMP_FILE_AGE * 2).unwrap();
+  drop(lock);
+  let operations = detect_interrupted_operations(git_executor, repo_path).unwrap();
+  let temp_files = operations.iter().find(|operation| operation.kind == InterruptedOperationKind::TempFiles).unwrap();
+  assert!(temp_files.description.contains("index.lock"), "{}", temp_files.description);
+
+  apply_recovery_option(git_executor, repo_path, &RecoveryOption::RemoveTempFiles).unwrap();
+  assert!(!lock_path.exists());
+}
+
+/// Temporary directory with a file, both modified `age` ago
+#[cfg(unix)]
+fn create_temp_dir(name: &str, age: std::time::Duration) -> std::path::PathBuf {
+  use std::fs::{self, File};
+
+  let path = std::env::temp_dir().join(name);
+  fs::create_dir_all(&path).unwrap();
+  let file = File::create(path.join("file.txt")).unwrap();
+  file.set_modified(SystemTime::now() - age).unwrap();
+  File::open(&path).unwrap().set_modified(SystemTime::now() - age).unwrap();
+  path
+}
+
+#[cfg(unix)]
+#[test]
+fn test_stale_temp_files_of_repository() {
+  use git_ops::temp_dir::repository_temp_prefix;
+  use std::fs::{self, File};
+  use std::process::{Command, Stdio};
+
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("First", "a.txt", "a");
+  let other_repo = TestRepo::new();
+  let git_executor = test_repo.git_executor();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let prefix = repository_temp_prefix(repo_path);
+  let other_prefix = repository_temp_prefix(other_repo.path().to_str().unwrap());
+
+  let mut process = Command::new("git").arg("--version").stdout(Stdio::null()).spawn().unwrap();
+  process.wait().unwrap();
+  let dead_pid = process.id();
+  let own_pid = std::process::id();
+  let stale = create_temp_dir(&format!("{prefix}{dead_pid}_pre_push_1"), STALE_TEMP_FILE_AGE * 2);
+  // Used by a running process, e.g. another window running a long pre-push check
+  let running = create_temp_dir(&format!("{prefix}{own_pid}_pre_push_2"), STALE_TEMP_FILE_AGE * 2);
+  // The directory is old, but a file in it was just written
+  let recently_written = create_temp_dir(&format!("{prefix}{dead_pid}_pre_push_3"), STALE_TEMP_FILE_AGE * 2);
+  File::create(recently_written.join("new.txt")).unwrap();
+  File::open(&recently_written).unwrap().set_modified(SystemTime::now() - STALE_TEMP_FILE_AGE * 2).unwrap();
+  let other_repository = create_temp_dir(&format!("{other_prefix}{dead_pid}_pre_push_4"), STALE_TEMP_FILE_AGE * 2);
+
+  let operations = repository_operations(&test_repo);
+  assert_eq!(operations.len(), 1);
+  assert_eq!(operations[0].kind, InterruptedOperationKind::TempFiles);
+  assert_eq!(operations[0].description, format!("Lock and temporary files were left behind: {}", stale.display()));
+
+  apply_recovery_option(git_executor, repo_path, &RecoveryOption::RemoveTempFiles).unwrap();
+  assert!(!stale.exists());
+  let kept: Vec<bool> = [&running, &recently_written, &other_repository].iter().map(|path| path.exists()).collect();
+  assert_eq!(kept, vec![true, true, true]);
+  for path in [running, recently_written, other_repository] {
+    fs::remove_dir_all(path).unwrap();
+  }
+}
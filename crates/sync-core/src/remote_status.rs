@@ -1,15 +1,128 @@
+use crate::ci_status::cached_ci_status;
+use crate::credentials::{AskpassEnv, run_with_credentials};
+use crate::usage_stats::{UsageCounter, record_usage};
 use anyhow::Result;
 use git_executor::git_command_executor::GitCommandExecutor;
-use sync_types::RemoteStatusUpdate;
-use tracing::instrument;
+use git_ops::model::to_final_branch_name;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use sync_types::{ArchivedRemoteStatus, RemoteStatusUpdate};
+use tracing::{debug, instrument};
 
-/// Check if remote branch exists using show-ref (faster than reflog)
+/// Git config key enabling remote status collection for archived branches during sync
+pub const ARCHIVED_REMOTE_STATUS_CONFIG_KEY: &str = "branchdeck.archivedRemoteStatus";
+
+/// Remote virtual branches are pushed to, their remote status is computed from its remote-tracking refs
+pub const PUSH_REMOTE: &str = "origin";
+
+/// Commit of remote branch heads, keyed by branch name without `refs/heads/` (e.g. `prefix/virtual/name`)
+pub type RemoteHeads = HashMap<String, String>;
+
+/// Push a virtual branch to `origin`. For an HTTPS remote the credential is filled from the git credential helpers first,
+/// asking through `askpass` if they have none (see [`crate::credentials`]); git never prompts in a terminal.
+/// Without `force` the push is rejected unless it fast-forwards the remote branch.
+/// `no_verify` skips the `pre-push` hook, when it already ran against the virtual branch (see [`crate::pre_push`]).
+#[instrument(skip(git_executor, askpass))]
+pub fn push_virtual_branch(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  branch_prefix: &str,
+  branch_name: &str,
+  force: bool,
+  no_verify: bool,
+  askpass: &AskpassEnv,
+) -> Result<String> {
+  let final_branch_name = to_final_branch_name(branch_prefix, branch_name)?;
+  let refspec = format!("refs/heads/{final_branch_name}:{final_branch_name}");
+  let mut args = vec!["-c", "log.showSignature=false", "push", "--porcelain"];
+  if force {
+    args.push("--force");
+  }
+  if no_verify {
+    args.push("--no-verify");
+  }
+  args.extend([PUSH_REMOTE, refspec.as_str()]);
+  let url = git_executor.execute_command(&["ls-remote", "--get-url", PUSH_REMOTE], repository_path)?;
+  run_with_credentials(git_executor, repository_path, &url, &args, askpass)?;
+  record_usage(git_executor, repository_path, UsageCounter::Push);
+  Ok(final_branch_name)
+}
+
+/// Resolve the remote-tracking ref of a branch, `None` if the branch was never pushed or fetched
 #[inline]
-fn remote_branch_exists(git_executor: &GitCommandExecutor, repository_path: &str, remote_ref: &str) -> bool {
+fn resolve_remote_head(git_executor: &GitCommandExecutor, repository_path: &str, remote_ref: &str) -> Option<String> {
   git_executor
-    .execute_command_with_status(&["--no-pager", "show-ref", "--verify", "--quiet", &format!("refs/remotes/{}", remote_ref)], repository_path)
-    .map(|(_, exit_code)| exit_code == 0)
-    .unwrap_or(false)
+    .execute_command_with_status(&["--no-pager", "show-ref", "--verify", "--hash", &format!("refs/remotes/{}", remote_ref)], repository_path)
+    .ok()
+    .filter(|(_, exit_code)| *exit_code == 0)
+    .map(|(output, _)| output.trim().to_string())
+}
+
+/// Heads of the remote-tracking refs under the prefix, in a single local call
+pub fn tracking_heads(git_executor: &GitCommandExecutor, repository_path: &str, branch_prefix: &str) -> Result<RemoteHeads> {
+  let remote_ref_prefix = format!("refs/remotes/{PUSH_REMOTE}/");
+  let remote_refs = format!("{remote_ref_prefix}{branch_prefix}/");
+  let lines = git_executor.execute_command_lines(&["--no-pager", "for-each-ref", "--format=%(objectname) %(refname)", &remote_refs], repository_path)?;
+  Ok(
+    lines
+      .iter()
+      .filter_map(|line| {
+        let (commit, ref_name) = line.split_once(' ')?;
+        Some((ref_name.strip_prefix(remote_ref_prefix.as_str())?.to_string(), commit.to_string()))
+      })
+      .collect(),
+  )
+}
+
+/// Heads of all branches under the prefix on origin, with a single `ls-remote` round trip
+#[instrument(skip(git_executor))]
+pub fn list_remote_heads(git_executor: &GitCommandExecutor, repository_path: &str, branch_prefix: &str) -> Result<RemoteHeads> {
+  let pattern = format!("refs/heads/{branch_prefix}/*");
+  // A background query must never wait for credentials
+  let output = git_executor.execute_command_with_env(&["ls-remote", "--quiet", PUSH_REMOTE, &pattern], repository_path, &[("GIT_TERMINAL_PROMPT", "0")])?;
+  Ok(
+    output
+      .lines()
+      .filter_map(|line| {
+        let (commit, ref_name) = line.split_once('\t')?;
+        Some((ref_name.strip_prefix("refs/heads/")?.to_string(), commit.to_string()))
+      })
+      .collect(),
+  )
+}
+
+/// Bring the remote-tracking refs under the prefix in line with `remote_heads` as `fetch --prune` would:
+/// heads whose commits are already local are updated without network access, only the others are fetched.
+#[instrument(skip(git_executor, remote_heads), fields(remote_head_count = remote_heads.len()))]
+pub fn update_tracking_refs(git_executor: &GitCommandExecutor, repository_path: &str, branch_prefix: &str, remote_heads: &RemoteHeads) -> Result<()> {
+  let current = tracking_heads(git_executor, repository_path, branch_prefix)?;
+
+  let mut commands = String::new();
+  let mut refspecs = Vec::new();
+  for (branch, commit) in remote_heads {
+    if current.get(branch) == Some(commit) {
+      continue;
+    }
+    if matches!(git_executor.cat_file_info(repository_path, commit), Ok(Some(_))) {
+      commands.push_str(&format!("update refs/remotes/{PUSH_REMOTE}/{branch} {commit}\n"));
+    } else {
+      refspecs.push(format!("+refs/heads/{branch}:refs/remotes/{PUSH_REMOTE}/{branch}"));
+    }
+  }
+  for branch in current.keys().filter(|branch| !remote_heads.contains_key(*branch)) {
+    commands.push_str(&format!("delete refs/remotes/{PUSH_REMOTE}/{branch}\n"));
+  }
+
+  if !commands.is_empty() {
+    git_executor.execute_command_with_input(&["update-ref", "-m", "branch-deck: update from ls-remote", "--stdin"], repository_path, &commands)?;
+  }
+  if !refspecs.is_empty() {
+    debug!(count = refspecs.len(), "Fetching remote heads missing locally");
+    let mut args = vec!["fetch", "--no-tags", "--quiet", PUSH_REMOTE];
+    args.extend(refspecs.iter().map(String::as_str));
+    git_executor.execute_command_with_env(&args, repository_path, &[("GIT_TERMINAL_PROMPT", "0")])?;
+  }
+  Ok(())
 }
 
 /// Get last push time from reflog if available
@@ -50,10 +163,34 @@ pub fn compute_remote_status_for_branch(
   total_commits_in_branch: u32,
   baseline_branch: &str, // Used to exclude commits already in master
 ) -> Result<RemoteStatusUpdate> {
-  let remote_ref = format!("origin/{}", local_ref);
+  let remote_head = resolve_remote_head(git_executor, repository_path, &format!("{PUSH_REMOTE}/{local_ref}"));
+  compute_remote_status_with_head(
+    git_executor,
+    repository_path,
+    local_ref,
+    branch_name,
+    my_email,
+    total_commits_in_branch,
+    baseline_branch,
+    remote_head.as_deref(),
+  )
+}
 
-  // Fast check if remote exists
-  if !remote_branch_exists(git_executor, repository_path, &remote_ref) {
+/// Same as [`compute_remote_status_for_branch`] with the remote head already known, `None` if the branch is not on the remote
+#[allow(clippy::too_many_arguments)]
+fn compute_remote_status_with_head(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  local_ref: &str,
+  branch_name: &str,
+  my_email: Option<&str>,
+  total_commits_in_branch: u32,
+  baseline_branch: &str,
+  remote_head: Option<&str>,
+) -> Result<RemoteStatusUpdate> {
+  let remote_ref = format!("{PUSH_REMOTE}/{local_ref}");
+
+  let Some(remote_head) = remote_head else {
     return Ok(RemoteStatusUpdate {
       branch_name: branch_name.to_string(),
       remote_exists: false,
@@ -61,11 +198,13 @@ pub fn compute_remote_status_for_branch(
       commits_behind: 0,
       my_unpushed_count: total_commits_in_branch,
       last_push_time: 0,
+      review: None,
+      ci_status: None,
     });
-  }
+  };
 
   // Get ahead/behind counts
-  let range = format!("{}...{}", remote_ref, local_ref);
+  let range = format!("{}...{}", remote_head, local_ref);
   let counts = git_executor.execute_command(&["--no-pager", "rev-list", "--left-right", "--count", &range], repository_path)?;
   let counts = counts.trim();
   let mut parts = counts.split_whitespace();
@@ -87,11 +226,13 @@ pub fn compute_remote_status_for_branch(
       commits_behind: behind,
       my_unpushed_count: 0,
       last_push_time,
+      review: None,
+      ci_status: cached_ci_status(repository_path, remote_head),
     });
   }
 
   // Get unpushed commits (all commits ahead, including patch-equivalent)
-  let unpushed_range = format!("{}..{}", remote_ref, local_ref);
+  let unpushed_range = format!("{}..{}", remote_head, local_ref);
   let unpushed_commits = git_executor.execute_command_lines(&["--no-pager", "rev-list", "--reverse", &unpushed_range], repository_path)?;
 
   // Calculate my_unpushed_count only if we have an email to filter by
@@ -120,5 +261,280 @@ pub fn compute_remote_status_for_branch(
     commits_behind: behind,
     my_unpushed_count,
     last_push_time,
+    review: None,
+    ci_status: cached_ci_status(repository_path, remote_head),
   })
 }
+
+/// Compute remote status for all local virtual branches under the prefix, used to refresh the status outside of sync.
+/// `my_email` filters the unpushed commits counted as the user's own.
+#[instrument(skip(git_executor))]
+pub fn compute_remote_status_for_virtual_branches(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  branch_prefix: &str,
+  baseline_branch: &str,
+  my_email: Option<&str>,
+) -> Result<Vec<RemoteStatusUpdate>> {
+  let virtual_refs = format!("refs/heads/{branch_prefix}/virtual/");
+  let local_refs = git_executor.execute_command_lines(&["--no-pager", "for-each-ref", "--format=%(refname:short)", &virtual_refs], repository_path)?;
+
+  let remote_heads = tracking_heads(git_executor, repository_path, branch_prefix)?;
+  compute_remote_status_with_heads(git_executor, repository_path, branch_prefix, baseline_branch, my_email, &local_refs, &remote_heads)
+}
+
+/// Query the heads of the pushed branches with a single `ls-remote`, update the remote-tracking refs from it and compute the
+/// status of all local virtual branches locally. Only heads with commits not available locally (e.g. pushed from another
+/// machine) need a fetch.
+#[instrument(skip(git_executor))]
+pub fn refresh_remote_status_for_virtual_branches(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  branch_prefix: &str,
+  baseline_branch: &str,
+  my_email: Option<&str>,
+) -> Result<Vec<RemoteStatusUpdate>> {
+  let remote_heads = list_remote_heads(git_executor, repository_path, branch_prefix)?;
+  update_tracking_refs(git_executor, repository_path, branch_prefix, &remote_heads)?;
+
+  let virtual_refs = format!("refs/heads/{branch_prefix}/virtual/");
+  let local_refs = git_executor.execute_command_lines(&["--no-pager", "for-each-ref", "--format=%(refname:short)", &virtual_refs], repository_path)?;
+  compute_remote_status_with_heads(git_executor, repository_path, branch_prefix, baseline_branch, my_email, &local_refs, &remote_heads)
+}
+
+fn compute_remote_status_with_heads(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  branch_prefix: &str,
+  baseline_branch: &str,
+  my_email: Option<&str>,
+  local_refs: &[String],
+  remote_heads: &RemoteHeads,
+) -> Result<Vec<RemoteStatusUpdate>> {
+  let virtual_prefix = format!("{branch_prefix}/virtual/");
+  let mut result = Vec::with_capacity(local_refs.len());
+  for local_ref in local_refs {
+    let Some(branch_name) = local_ref.strip_prefix(&virtual_prefix) else {
+      continue;
+    };
+    let range = format!("{baseline_branch}..{local_ref}");
+    let total_commits: u32 = git_executor
+      .execute_command(&["--no-pager", "rev-list", "--count", &range], repository_path)?
+      .parse()
+      .unwrap_or(0);
+    result.push(compute_remote_status_with_head(
+      git_executor,
+      repository_path,
+      local_ref,
+      branch_name,
+      my_email,
+      total_commits,
+      baseline_branch,
+      remote_heads.get(local_ref).map(String::as_str),
+    )?);
+  }
+  Ok(result)
+}
+
+/// Commit on only one side of a virtual branch and its remote counterpart
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct DivergentCommit {
+  pub hash: String,
+  pub subject: String,
+  pub author: String,
+  pub author_time: u32,
+  /// Stable patch ID, `None` for a commit without changes
+  pub patch_id: Option<String>,
+  /// The other side has a commit with the same patch ID, i.e., the commit was rewritten without changing its diff
+  pub has_equivalent: bool,
+}
+
+/// Commits that differ between a local virtual branch and its remote-tracking ref
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct BranchRemoteDivergence {
+  pub branch_name: String,
+  pub remote_exists: bool,
+  /// Commits missing on the remote, oldest first
+  pub local_commits: Vec<DivergentCommit>,
+  /// Commits missing locally, oldest first
+  pub remote_commits: Vec<DivergentCommit>,
+}
+
+/// List the commits behind the ahead/behind counts of [`compute_remote_status_for_branch`] for a detailed out-of-date view.
+/// Uses the remote-tracking ref as is, so it's as fresh as the last fetch or remote status refresh.
+#[instrument(skip(git_executor))]
+pub fn compute_branch_remote_divergence(git_executor: &GitCommandExecutor, repository_path: &str, branch_prefix: &str, branch_name: &str) -> Result<BranchRemoteDivergence> {
+  let local_ref = to_final_branch_name(branch_prefix, branch_name)?;
+  let Some(remote_head) = resolve_remote_head(git_executor, repository_path, &format!("{PUSH_REMOTE}/{local_ref}")) else {
+    return Ok(BranchRemoteDivergence {
+      branch_name: branch_name.to_string(),
+      remote_exists: false,
+      local_commits: Vec::new(),
+      remote_commits: Vec::new(),
+    });
+  };
+
+  let range = format!("{remote_head}...refs/heads/{local_ref}");
+  let commits_output = git_executor.execute_command(
+    &[
+      "--no-pager",
+      "log",
+      "--left-right",
+      "--reverse",
+      "--no-merges",
+      "--format=%m%x1f%H%x1f%an%x1f%at%x1f%s",
+      &range,
+    ],
+    repository_path,
+  )?;
+  let patch_ids = divergent_patch_ids(git_executor, repository_path, &range)?;
+
+  let mut local_commits = Vec::new();
+  let mut remote_commits = Vec::new();
+  for line in commits_output.lines() {
+    let mut fields = line.splitn(5, '\x1f');
+    let (Some(side), Some(hash), Some(author), Some(author_time), Some(subject)) = (fields.next(), fields.next(), fields.next(), fields.next(), fields.next()) else {
+      continue;
+    };
+    let commit = DivergentCommit {
+      hash: hash.to_string(),
+      subject: subject.to_string(),
+      author: author.to_string(),
+      author_time: author_time.parse().unwrap_or(0),
+      patch_id: patch_ids.get(hash).cloned(),
+      has_equivalent: false,
+    };
+    if side == ">" {
+      local_commits.push(commit);
+    } else {
+      remote_commits.push(commit);
+    }
+  }
+
+  let local_patch_ids: HashSet<String> = local_commits.iter().filter_map(|commit| commit.patch_id.clone()).collect();
+  let remote_patch_ids: HashSet<String> = remote_commits.iter().filter_map(|commit| commit.patch_id.clone()).collect();
+  for commit in &mut local_commits {
+    commit.has_equivalent = commit.patch_id.as_ref().is_some_and(|patch_id| remote_patch_ids.contains(patch_id));
+  }
+  for commit in &mut remote_commits {
+    commit.has_equivalent = commit.patch_id.as_ref().is_some_and(|patch_id| local_patch_ids.contains(patch_id));
+  }
+
+  debug!(local_count = local_commits.len(), remote_count = remote_commits.len(), "Computed remote divergence");
+  Ok(BranchRemoteDivergence {
+    branch_name: branch_name.to_string(),
+    remote_exists: true,
+    local_commits,
+    remote_commits,
+  })
+}
+
+/// Stable patch IDs of the commits in a symmetric range, keyed by commit
+fn divergent_patch_ids(git_executor: &GitCommandExecutor, repository_path: &str, range: &str) -> Result<HashMap<String, String>> {
+  // Untrimmed, as patch-id needs the diffs exactly as git printed them
+  let patches = git_executor.execute_command_raw(
+    &["--no-pager", "log", "-p", "--no-merges", "--no-color", "--no-ext-diff", "--format=%H", range],
+    repository_path,
+  )?;
+  if patches.trim().is_empty() {
+    return Ok(HashMap::new());
+  }
+  // Format: <patch-id> <commit>
+  let output = git_executor.execute_command_with_input(&["patch-id", "--stable"], repository_path, &patches)?;
+  Ok(
+    output
+      .lines()
+      .filter_map(|line| line.split_once(' '))
+      .map(|(patch_id, commit)| (commit.to_string(), patch_id.to_string()))
+      .collect(),
+  )
+}
+
+/// Names of the virtual branch an archived branch may have been created from.
+/// Archiving appends `-N` to the name on collisions, so the name without the suffix is a candidate too, but only if
+/// the archived branch it collided with exists: otherwise `api-2` is a branch of its own, not a second `api`.
+fn archived_source_names<'a>(archived_branch: &'a str, archived_branches: &HashSet<&str>) -> Vec<&'a str> {
+  let Some((archive_dir, name)) = archived_branch.rsplit_once('/') else {
+    return Vec::new();
+  };
+  let mut names = vec![name];
+  if let Some((base, suffix)) = name.rsplit_once('-')
+    && !base.is_empty()
+    && !suffix.is_empty()
+    && suffix.bytes().all(|b| b.is_ascii_digit())
+    && archived_branches.contains(format!("{archive_dir}/{base}").as_str())
+  {
+    names.push(base);
+  }
+  names
+}
+
+/// Find archived branches that still have a branch on the remote: the archived ref itself if it was pushed,
+/// or the virtual branch it was archived from unless a local virtual branch with that name exists again.
+/// Each remote branch is reported once, for the newest archived branch.
+#[instrument(skip(git_executor))]
+pub fn compute_remote_status_for_archived_branches(git_executor: &GitCommandExecutor, repository_path: &str, branch_prefix: &str) -> Result<Vec<ArchivedRemoteStatus>> {
+  let local_refs = format!("refs/heads/{branch_prefix}/");
+  let remote_ref_prefix = format!("refs/remotes/{PUSH_REMOTE}/");
+  let remote_refs = format!("{remote_ref_prefix}{branch_prefix}/");
+  let lines = git_executor.execute_command_lines(
+    &["--no-pager", "for-each-ref", "--format=%(objectname) %(refname)", &local_refs, &remote_refs],
+    repository_path,
+  )?;
+
+  let archived_prefix = format!("{branch_prefix}/archived/");
+  let virtual_prefix = format!("{branch_prefix}/virtual/");
+  let mut archived_branches: Vec<(&str, &str)> = Vec::new();
+  let mut local_virtual_names: HashSet<&str> = HashSet::new();
+  let mut remote_branches: HashMap<&str, &str> = HashMap::new();
+  for line in &lines {
+    let Some((commit, ref_name)) = line.split_once(' ') else {
+      continue;
+    };
+    if let Some(name) = ref_name.strip_prefix(remote_ref_prefix.as_str()) {
+      remote_branches.insert(name, commit);
+    } else if let Some(name) = ref_name.strip_prefix("refs/heads/") {
+      if name.starts_with(&archived_prefix) {
+        archived_branches.push((name, commit));
+      } else if let Some(simple_name) = name.strip_prefix(&virtual_prefix) {
+        local_virtual_names.insert(simple_name);
+      }
+    }
+  }
+
+  if remote_branches.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  // Archive paths start with the date, so the newest archived branches come first
+  archived_branches.sort_unstable_by(|a, b| b.0.cmp(a.0));
+
+  let archived_names: HashSet<&str> = archived_branches.iter().map(|(branch_name, _)| *branch_name).collect();
+  let mut claimed: HashSet<String> = HashSet::new();
+  let mut result = Vec::new();
+  for (branch_name, commit) in &archived_branches {
+    let candidates = std::iter::once(branch_name.to_string()).chain(
+      archived_source_names(branch_name, &archived_names)
+        .into_iter()
+        .filter(|name| !local_virtual_names.contains(name))
+        .map(|name| format!("{virtual_prefix}{name}")),
+    );
+    for remote_branch in candidates {
+      let Some(remote_commit) = remote_branches.get(remote_branch.as_str()) else {
+        continue;
+      };
+      if claimed.insert(remote_branch.clone()) {
+        result.push(ArchivedRemoteStatus {
+          branch_name: branch_name.to_string(),
+          same_commit: remote_commit == commit,
+          remote_branch,
+        });
+      }
+    }
+  }
+  Ok(result)
+}
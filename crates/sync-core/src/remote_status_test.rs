@@ -0,0 +1,35 @@
+use crate::remote_status::{DivergentCommit, compute_branch_remote_divergence};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_branch_remote_divergence() {
+  let test_repo = TestRepo::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  let local_rewritten = test_repo.create_commit("(feature) Add parser", "parser.rs", "fn parse() {}\n");
+  let local_only = test_repo.create_commit("(feature) Add lexer", "lexer.rs", "fn lex() {}\n");
+  test_repo.create_branch_at("test/virtual/feature", &local_only).unwrap();
+
+  assert!(!compute_branch_remote_divergence(&git_executor, repo_path, "test", "feature").unwrap().remote_exists);
+
+  // The remote has the parser commit with another message and a commit pushed from another machine
+  test_repo.reset_hard(&baseline).unwrap();
+  let remote_rewritten = test_repo.create_commit("(feature) Add parser module", "parser.rs", "fn parse() {}\n");
+  let remote_only = test_repo.create_commit("(feature) Add docs", "docs.md", "# Docs\n");
+  git_executor
+    .execute_command(&["update-ref", "refs/remotes/origin/test/virtual/feature", &remote_only], repo_path)
+    .unwrap();
+
+  let divergence = compute_branch_remote_divergence(&git_executor, repo_path, "test", "feature").unwrap();
+
+  assert!(divergence.remote_exists);
+  let summary = |commits: &[DivergentCommit]| commits.iter().map(|commit| (commit.hash.clone(), commit.has_equivalent)).collect::<Vec<_>>();
+  assert_eq!(summary(&divergence.local_commits), vec![(local_rewritten, true), (local_only, false)]);
+  assert_eq!(summary(&divergence.remote_commits), vec![(remote_rewritten, true), (remote_only, false)]);
+  assert_eq!(divergence.local_commits[0].patch_id, divergence.remote_commits[0].patch_id);
+  assert_eq!(divergence.remote_commits[1].subject, "(feature) Add docs");
+}
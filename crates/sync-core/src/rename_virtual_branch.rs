@@ -0,0 +1,229 @@
+use crate::branch_protection::check_delete_allowed;
+use crate::credentials::{AskpassEnv, run_with_credentials};
+use crate::remote_status::PUSH_REMOTE;
+use crate::repo_state::update_repo_state;
+use crate::sync::detect_baseline_branch;
+use anyhow::{Context, Result, anyhow, bail};
+use branch_integration::archive::ARCHIVE_MUTEX;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::model::{to_final_branch_name, validate_branch_name};
+use git_ops::reword_commits::{RewordCommitParams, reword_commits_batch};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use tracing::{info, instrument};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct RenameVirtualBranchParams {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  pub old_name: String,
+  pub new_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct RenameVirtualBranchResult {
+  /// Number of commits whose `(old-name)` prefix was rewritten
+  pub reworded_count: u32,
+  /// New names of the migrated archived branches (e.g., "user/archived/2025-08-11/new-name")
+  pub archived_branches: Vec<String>,
+  /// Whether the branch was renamed on the remote as well
+  pub remote_updated: bool,
+}
+
+/// Rename a virtual branch: rewrite the `(old-name)` prefix of its commits, move the virtual and archived refs,
+/// migrate per-branch repository state and, if the branch was pushed, rename it on the remote.
+/// Credentials of the remote are filled like for a push (see [`crate::credentials`]).
+#[instrument(skip(git_executor, askpass))]
+pub fn rename_virtual_branch_core(git_executor: &GitCommandExecutor, params: RenameVirtualBranchParams, askpass: &AskpassEnv) -> Result<RenameVirtualBranchResult> {
+  let RenameVirtualBranchParams {
+    repository_path,
+    branch_prefix,
+    old_name,
+    new_name,
+  } = params;
+  let repo = repository_path.as_str();
+
+  validate_branch_name(&new_name).map_err(|e| anyhow!(e))?;
+  if old_name == new_name {
+    bail!("New branch name is the same as the current one");
+  }
+  let old_final = to_final_branch_name(&branch_prefix, &old_name)?;
+  let new_final = to_final_branch_name(&branch_prefix, &new_name)?;
+  if ref_oid(git_executor, repo, &format!("refs/heads/{new_final}"))?.is_some() {
+    bail!("Branch '{new_name}' already exists");
+  }
+  // Renaming a pushed branch deletes the old remote branch
+  let remote_oid = ref_oid(git_executor, repo, &format!("refs/remotes/origin/{old_final}"))?;
+  if remote_oid.is_some() {
+    check_delete_allowed(git_executor, repo, &branch_prefix, std::slice::from_ref(&old_final))?;
+  }
+
+  let rewrites = collect_rewrites(git_executor, repo, &old_name, &new_name)?;
+  let reworded_count = rewrites.len() as u32;
+
+  let _guard = ARCHIVE_MUTEX.lock().map_err(|e| anyhow!("Failed to acquire archive mutex: {e}"))?;
+
+  // Plan all ref moves before touching anything, so the transaction below either moves all refs or none
+  let mut moves = Vec::new();
+  let virtual_oid = ref_oid(git_executor, repo, &format!("refs/heads/{old_final}"))?;
+  if let Some(oid) = &virtual_oid {
+    moves.push((old_final.clone(), new_final.clone(), oid.clone()));
+  }
+  let archived_moves = plan_archived_moves(git_executor, repo, &branch_prefix, &old_name, &new_name)?;
+  let archived_branches = archived_moves.iter().map(|(_, new_ref, _)| new_ref.clone()).collect();
+  moves.extend(archived_moves);
+
+  reword_commits_batch(git_executor, repo, rewrites).context("Failed to rewrite commit prefixes")?;
+
+  if !moves.is_empty() {
+    let mut batch = String::from("start\n");
+    for (old_ref, new_ref, oid) in &moves {
+      writeln!(batch, "create refs/heads/{new_ref} {oid}")?;
+      writeln!(batch, "delete refs/heads/{old_ref} {oid}")?;
+    }
+    batch.push_str("commit\n");
+    git_executor.execute_command_with_input(&["update-ref", "--stdin"], repo, &batch)?;
+  }
+
+  update_repo_state(git_executor, repo, |state| {
+    for name in state.pinned_branches.iter_mut().chain(state.branch_order.iter_mut()) {
+      if *name == old_name {
+        name.clone_from(&new_name);
+      }
+    }
+    if let Some(template) = state.cover_letter_templates.remove(&old_name) {
+      state.cover_letter_templates.insert(new_name.clone(), template);
+    }
+    if let Some(order) = state.commit_order.remove(&old_name) {
+      state.commit_order.insert(new_name.clone(), order);
+    }
+  })?;
+
+  let remote_updated = match (&virtual_oid, remote_oid) {
+    (Some(_), Some(remote_oid)) => {
+      rename_remote_branch(git_executor, repo, &old_final, &new_final, &remote_oid, askpass).context("Branch was renamed locally, but renaming it on the remote failed")?;
+      true
+    }
+    _ => false,
+  };
+
+  info!(
+    reworded_count,
+    moved_refs = moves.len(),
+    remote_updated,
+    "Renamed virtual branch '{old_name}' to '{new_name}'"
+  );
+  Ok(RenameVirtualBranchResult {
+    reworded_count,
+    archived_branches,
+    remote_updated,
+  })
+}
+
+fn ref_oid(git_executor: &GitCommandExecutor, repo: &str, full_ref: &str) -> Result<Option<String>> {
+  let (output, exit_code) = git_executor.execute_command_with_status(&["rev-parse", "--verify", "--quiet", full_ref], repo)?;
+  Ok(if exit_code == 0 { Some(output.trim().to_string()) } else { None })
+}
+
+/// Commits since the baseline with the `(old-name)` prefix, reworded to `(new-name)`
+fn collect_rewrites(git_executor: &GitCommandExecutor, repo: &str, old_name: &str, new_name: &str) -> Result<Vec<RewordCommitParams>> {
+  let baseline_branch = detect_baseline_branch(git_executor, repo, "master")?;
+  let output = git_executor.execute_command(&["--no-pager", "log", "--no-merges", "--format=%H%x1f%B%x1e", &format!("{baseline_branch}..HEAD")], repo)?;
+
+  let old_prefix = format!("({old_name}) ");
+  let new_prefix = format!("({new_name}) ");
+  let mut rewrites = Vec::new();
+  for record in output.split('\x1e').map(str::trim).filter(|record| !record.is_empty()) {
+    let (commit_id, message) = record.split_once('\x1f').ok_or_else(|| anyhow!("Unexpected git log output"))?;
+    let message = message.trim();
+    if message.starts_with(&new_prefix) {
+      bail!("Commits with prefix '({new_name})' already exist, renaming would merge the branches");
+    }
+    if let Some(rest) = message.strip_prefix(&old_prefix) {
+      rewrites.push(RewordCommitParams {
+        commit_id: commit_id.to_string(),
+        new_message: format!("{new_prefix}{rest}"),
+      });
+    }
+  }
+
+  if rewrites.is_empty() {
+    bail!("No commits with prefix '({old_name})' found");
+  }
+  Ok(rewrites)
+}
+
+/// Archived branches of the old name as (old name, new name, commit) moves.
+/// `{date}/old-N` is only a collision suffix when `{date}/old` exists, otherwise it is a different branch.
+fn plan_archived_moves(git_executor: &GitCommandExecutor, repo: &str, branch_prefix: &str, old_name: &str, new_name: &str) -> Result<Vec<(String, String, String)>> {
+  let archive_root = format!("{branch_prefix}/archived/");
+  let lines = git_executor.execute_command_lines(&["for-each-ref", "--format=%(refname:strip=2) %(objectname)", &format!("refs/heads/{archive_root}")], repo)?;
+  let existing: BTreeMap<String, String> = lines
+    .iter()
+    .filter_map(|line| line.split_once(' '))
+    .map(|(name, oid)| (name.to_string(), oid.to_string()))
+    .collect();
+
+  let mut to_move = Vec::new();
+  for (ref_name, oid) in &existing {
+    let Some((date_dir, name)) = ref_name.strip_prefix(&archive_root).and_then(|rest| rest.split_once('/')) else {
+      continue;
+    };
+    let suffix = match name.strip_prefix(old_name) {
+      Some("") => 0,
+      Some(rest) => match rest.strip_prefix('-').and_then(|n| n.parse::<u32>().ok()) {
+        Some(n) if existing.contains_key(&format!("{archive_root}{date_dir}/{old_name}")) => n,
+        _ => continue,
+      },
+      None => continue,
+    };
+    to_move.push((date_dir.to_string(), suffix, ref_name.clone(), oid.clone()));
+  }
+  to_move.sort();
+
+  // Refs moved away are still taken, a ref can't be deleted and created in the same transaction
+  let mut taken: HashSet<String> = existing.keys().cloned().collect();
+  let mut moves = Vec::with_capacity(to_move.len());
+  for (date_dir, _, ref_name, oid) in &to_move {
+    // Same numbering scheme as archiving
+    let base = format!("{archive_root}{date_dir}/{new_name}");
+    let mut target = base.clone();
+    let mut suffix = 1;
+    while taken.contains(&target) {
+      target = format!("{base}-{suffix}");
+      suffix += 1;
+    }
+    taken.insert(target.clone());
+    moves.push((ref_name.clone(), target, oid.clone()));
+  }
+  Ok(moves)
+}
+
+/// Push the branch under the new name and delete the old remote branch in one atomic push.
+/// The lease makes the push fail if somebody else updated the old remote branch.
+fn rename_remote_branch(git_executor: &GitCommandExecutor, repo: &str, old_final: &str, new_final: &str, remote_oid: &str, askpass: &AskpassEnv) -> Result<()> {
+  let url = git_executor.execute_command(&["ls-remote", "--get-url", PUSH_REMOTE], repo)?;
+  run_with_credentials(
+    git_executor,
+    repo,
+    &url,
+    &[
+      "-c",
+      "log.showSignature=false",
+      "push",
+      "--porcelain",
+      "--atomic",
+      &format!("--force-with-lease=refs/heads/{old_final}:{remote_oid}"),
+      PUSH_REMOTE,
+      &format!("refs/heads/{new_final}:refs/heads/{new_final}"),
+      &format!(":refs/heads/{old_final}"),
+    ],
+    askpass,
+  )?;
+  Ok(())
+}
@@ -0,0 +1,109 @@
+use crate::branch_order::{SetBranchPinnedParams, set_branch_pinned_core};
+use crate::credentials::AskpassEnv;
+use crate::rename_virtual_branch::{RenameVirtualBranchParams, rename_virtual_branch_core};
+use crate::repo_state::load_repo_state;
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn rename_params(test_repo: &TestRepo, old_name: &str, new_name: &str) -> RenameVirtualBranchParams {
+  RenameVirtualBranchParams {
+    repository_path: test_repo.path_str().to_string(),
+    branch_prefix: "user".to_string(),
+    old_name: old_name.to_string(),
+    new_name: new_name.to_string(),
+  }
+}
+
+/// Commits of `parser` and `lexer` on top of `origin/master`
+fn setup_commits(test_repo: &TestRepo) {
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+  test_repo
+    .git_executor()
+    .execute_command(&["update-ref", "refs/remotes/origin/master", &baseline], test_repo.path_str())
+    .unwrap();
+  test_repo.create_commit("(parser) Add parser", "parser.rs", "fn parse() {}\n");
+  test_repo.create_commit("(lexer) Add lexer", "lexer.rs", "fn lex() {}\n");
+  test_repo.create_commit("(parser) Test parser", "parser_test.rs", "fn test_parse() {}\n");
+}
+
+#[test]
+fn test_rename_virtual_branch_migrates_refs_and_state() {
+  let test_repo = TestRepo::new();
+  setup_commits(&test_repo);
+  let head = test_repo.head();
+  test_repo.create_branch_at("user/virtual/parser", &head).unwrap();
+  for archived in [
+    "user/archived/2025-01-01/parser",
+    "user/archived/2025-01-01/parser-1",
+    "user/archived/2025-01-01/parser-tools",
+  ] {
+    test_repo.create_branch_at(archived, &head).unwrap();
+  }
+  let git_executor = GitCommandExecutor::new();
+  set_branch_pinned_core(
+    &git_executor,
+    SetBranchPinnedParams {
+      repository_path: test_repo.path_str().to_string(),
+      branch_name: "parser".to_string(),
+      pinned: true,
+    },
+  )
+  .unwrap();
+
+  let result = rename_virtual_branch_core(&git_executor, rename_params(&test_repo, "parser", "syntax"), &AskpassEnv::none()).unwrap();
+
+  assert_eq!(result.reworded_count, 2);
+  assert!(!result.remote_updated);
+  assert_eq!(
+    result.archived_branches,
+    vec!["user/archived/2025-01-01/syntax".to_string(), "user/archived/2025-01-01/syntax-1".to_string()]
+  );
+  assert_eq!(
+    test_repo.get_commit_messages(3),
+    vec!["(syntax) Test parser".to_string(), "(lexer) Add lexer".to_string(), "(syntax) Add parser".to_string()]
+  );
+
+  assert!(test_repo.branch_exists("user/virtual/syntax"));
+  assert!(!test_repo.branch_exists("user/virtual/parser"));
+  assert!(test_repo.branch_exists("user/archived/2025-01-01/syntax-1"));
+  assert!(!test_repo.branch_exists("user/archived/2025-01-01/parser"));
+  // Another branch whose name merely starts with the old one
+  assert!(test_repo.branch_exists("user/archived/2025-01-01/parser-tools"));
+
+  assert_eq!(load_repo_state(&git_executor, test_repo.path_str()).unwrap().pinned_branches, vec!["syntax".to_string()]);
+}
+
+#[test]
+fn test_rename_virtual_branch_rejects_existing_prefix() {
+  let test_repo = TestRepo::new();
+  setup_commits(&test_repo);
+  let head = test_repo.head();
+
+  let error = rename_virtual_branch_core(&GitCommandExecutor::new(), rename_params(&test_repo, "parser", "lexer"), &AskpassEnv::none()).unwrap_err();
+
+  assert!(error.to_string().contains("would merge the branches"), "{error}");
+  // Nothing is rewritten
+  assert_eq!(test_repo.head(), head);
+}
+
+#[test]
+fn test_rename_virtual_branch_renames_pushed_branch() {
+  let upstream_repo = TestRepo::new();
+  upstream_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  let local_repo = TestRepo::new_empty();
+  local_repo.clone_from(upstream_repo.path()).unwrap();
+  local_repo.create_commit("(parser) Add parser", "parser.rs", "fn parse() {}\n");
+  let head = local_repo.head();
+  local_repo.create_branch_at("user/virtual/parser", &head).unwrap();
+  local_repo.push("origin", "user/virtual/parser").unwrap();
+
+  let result = rename_virtual_branch_core(&GitCommandExecutor::new(), rename_params(&local_repo, "parser", "syntax"), &AskpassEnv::none()).unwrap();
+
+  assert!(result.remote_updated);
+  assert!(upstream_repo.branch_exists("user/virtual/syntax"));
+  assert!(!upstream_repo.branch_exists("user/virtual/parser"));
+  assert_eq!(upstream_repo.rev_parse("user/virtual/syntax").unwrap(), head);
+}
@@ -0,0 +1,89 @@
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use sync_types::CommitOrder;
+use tracing::{debug, instrument};
+
+/// Ref holding per-repository Branch Deck state.
+/// It points to a commit whose tree contains a single `state.json`, so it can be pushed and fetched like any other ref
+/// (`git push origin refs/branch-deck/state`) to share the state across machines.
+pub const STATE_REF: &str = "refs/branch-deck/state";
+
+const STATE_FILE: &str = "state.json";
+
+/// Per-repository state owned by the backend
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase", default)]
+pub struct RepoState {
+  /// Pinned branch names (without prefix), shown first in this order
+  pub pinned_branches: Vec<String>,
+  /// Manual display order of branch names (without prefix)
+  pub branch_order: Vec<String>,
+  /// Cover letter templates for sending patches by email, keyed by branch name (without prefix)
+  pub cover_letter_templates: BTreeMap<String, String>,
+  /// Commit order of branches not using the source order, keyed by branch name (without prefix)
+  pub commit_order: BTreeMap<String, CommitOrder>,
+  /// Number of consecutive syncs that found every commit of the branch integrated, keyed by branch name (without prefix).
+  /// See `sync_core::auto_archive`.
+  pub integrated_sync_counts: BTreeMap<String, u32>,
+}
+
+/// Resolve the current state commit, `None` if the state was never written
+fn resolve_state_commit(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<Option<String>> {
+  let (output, exit_code) = git_executor.execute_command_with_status(&["rev-parse", "--verify", "--quiet", &format!("{STATE_REF}^{{commit}}")], repo_path)?;
+  Ok(if exit_code == 0 { Some(output.trim().to_string()) } else { None })
+}
+
+fn read_state(git_executor: &GitCommandExecutor, repo_path: &str, commit_id: &str) -> Result<RepoState> {
+  let content = git_executor.execute_command(&["cat-file", "blob", &format!("{commit_id}:{STATE_FILE}")], repo_path)?;
+  serde_json::from_str(&content).map_err(|e| anyhow!("Failed to parse {STATE_REF}:{STATE_FILE}: {e}"))
+}
+
+/// Load the repository state; returns the default state if it was never written
+#[instrument(skip(git_executor))]
+pub fn load_repo_state(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<RepoState> {
+  match resolve_state_commit(git_executor, repo_path)? {
+    Some(commit_id) => read_state(git_executor, repo_path, &commit_id),
+    None => Ok(RepoState::default()),
+  }
+}
+
+/// Apply `update` to the repository state and record the result as a new commit on [`STATE_REF`].
+/// The ref is updated atomically against the previously read value, so concurrent writers fail instead of losing updates.
+#[instrument(skip(git_executor, update))]
+pub fn update_repo_state<F>(git_executor: &GitCommandExecutor, repo_path: &str, update: F) -> Result<RepoState>
+where
+  F: FnOnce(&mut RepoState),
+{
+  let old_commit = resolve_state_commit(git_executor, repo_path)?;
+  let mut state = match &old_commit {
+    Some(commit_id) => read_state(git_executor, repo_path, commit_id)?,
+    None => RepoState::default(),
+  };
+
+  let previous = state.clone();
+  update(&mut state);
+  if state == previous && old_commit.is_some() {
+    debug!("repository state unchanged");
+    return Ok(state);
+  }
+
+  let content = serde_json::to_string_pretty(&state)? + "\n";
+  let blob_id = git_executor.execute_command_with_input(&["hash-object", "-w", "--stdin"], repo_path, &content)?;
+  let tree_id = git_executor.execute_command_with_input(&["mktree"], repo_path, &format!("100644 blob {}\t{STATE_FILE}\n", blob_id.trim()))?;
+
+  let mut args = vec!["commit-tree", tree_id.trim(), "-m", "Update Branch Deck state"];
+  if let Some(parent) = &old_commit {
+    args.push("-p");
+    args.push(parent);
+  }
+  let new_commit = git_executor.execute_command(&args, repo_path)?;
+  let new_commit = new_commit.trim();
+
+  // Empty old value means the ref must not exist yet
+  git_executor.execute_command(&["update-ref", STATE_REF, new_commit, old_commit.as_deref().unwrap_or("")], repo_path)?;
+  debug!(commit = %new_commit, "repository state updated");
+  Ok(state)
+}
@@ -1,4 +1,5 @@
 use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use tracing::instrument;
@@ -41,6 +42,13 @@ pub fn validate_path(path: &str) -> Result<()> {
   Ok(())
 }
 
+/// Whether the repository is a shallow clone (`git clone --depth`), its history ends at the shallow boundary commits
+#[instrument(skip(git_executor))]
+pub fn is_shallow_repository(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<bool> {
+  let output = git_executor.execute_command(&["rev-parse", "--is-shallow-repository"], repository_path)?;
+  Ok(output.trim() == "true")
+}
+
 /// Validates a path and creates a BrowseResult
 pub fn validate_and_create_result(path: String) -> BrowseResult {
   match validate_path(&path) {
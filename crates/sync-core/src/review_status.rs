@@ -0,0 +1,4 @@
+//! Review activity of pushed branches from the hosting provider, queried by `hosting_api::review_status`
+
+/// Git config key enabling review status of pushed branches
+pub const REVIEW_STATUS_CONFIG_KEY: &str = "branchdeck.reviewStatus";
@@ -0,0 +1,57 @@
+use anyhow::{Result, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::reword_commits::{RewordCommitParams, reword_commits_batch};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tracing::{info, instrument};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct RewordCommitsParams {
+  pub repository_path: String,
+  /// New message for each commit, commits not listed keep their message
+  pub rewrites: Vec<RewordCommitParams>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct RewordCommitsResult {
+  /// Old commit ID to new commit ID for every reworded commit and every descendant recreated on top of them
+  pub commit_mapping: HashMap<String, String>,
+}
+
+/// Change the messages of commits on the current branch.
+/// History is rewritten with plumbing commands only, the working tree and index are not touched.
+#[instrument(skip(git_executor, params), fields(repository_path = %params.repository_path, count = params.rewrites.len()))]
+pub fn reword_commits_core(git_executor: &GitCommandExecutor, params: RewordCommitsParams) -> Result<RewordCommitsResult> {
+  let RewordCommitsParams { repository_path, rewrites } = params;
+
+  if rewrites.is_empty() {
+    bail!("No commits to reword");
+  }
+  let mut seen = HashSet::new();
+  for rewrite in &rewrites {
+    if rewrite.new_message.trim().is_empty() {
+      bail!("Commit message of {} must not be empty", rewrite.commit_id);
+    }
+    if !seen.insert(rewrite.commit_id.as_str()) {
+      bail!("Commit {} is listed more than once", rewrite.commit_id);
+    }
+  }
+
+  // Trailing whitespace is dropped like `git commit` does
+  let rewrites: Vec<RewordCommitParams> = rewrites
+    .into_iter()
+    .map(|rewrite| RewordCommitParams {
+      commit_id: rewrite.commit_id,
+      new_message: rewrite.new_message.trim_end().to_string(),
+    })
+    .collect();
+
+  let reworded_count = rewrites.len();
+  let commit_mapping = reword_commits_batch(git_executor, &repository_path, rewrites)?;
+  info!(reworded_count, recreated_count = commit_mapping.len(), "Reworded commits");
+  Ok(RewordCommitsResult { commit_mapping })
+}
@@ -0,0 +1,78 @@
+use crate::reword_commits::{RewordCommitsParams, reword_commits_core};
+use git_ops::reword_commits::RewordCommitParams;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn reword_params(test_repo: &TestRepo, rewrites: &[(&str, &str)]) -> RewordCommitsParams {
+  RewordCommitsParams {
+    repository_path: test_repo.path().to_str().unwrap().to_string(),
+    rewrites: rewrites
+      .iter()
+      .map(|(commit_id, new_message)| RewordCommitParams {
+        commit_id: commit_id.to_string(),
+        new_message: new_message.to_string(),
+      })
+      .collect(),
+  }
+}
+
+#[test]
+fn test_reword_multiple_commits_keeps_worktree() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  let first = test_repo.create_commit("(parser) Add parser", "parser.rs", "fn parse() {}\n");
+  let second = test_repo.create_commit("(lexer) Add lexer", "lexer.rs", "fn lex() {}\n");
+  let third = test_repo.create_commit("(parser) Test parser", "parser_test.rs", "fn test_parse() {}\n");
+  std::fs::write(test_repo.path().join("parser.rs"), "fn parse() { todo!() }\n").unwrap();
+
+  let result = reword_commits_core(
+    test_repo.git_executor(),
+    reword_params(
+      &test_repo,
+      &[(&first, "(parser) Add recursive descent parser"), (&third, "(parser) Cover parser with tests\n\n")],
+    ),
+  )
+  .unwrap();
+
+  assert_eq!(result.commit_mapping.len(), 3);
+  assert_eq!(result.commit_mapping[&third], test_repo.head());
+  assert_ne!(result.commit_mapping[&first], first);
+  // Not reworded, but recreated on top of the new parent
+  assert_eq!(result.commit_mapping[&second], test_repo.rev_parse("HEAD~1").unwrap());
+  assert_eq!(
+    test_repo.get_commit_messages(4),
+    vec![
+      "(parser) Cover parser with tests".to_string(),
+      "(lexer) Add lexer".to_string(),
+      "(parser) Add recursive descent parser".to_string(),
+      "Initial commit".to_string(),
+    ]
+  );
+  // Uncommitted changes are left as they were
+  assert_eq!(std::fs::read_to_string(test_repo.path().join("parser.rs")).unwrap(), "fn parse() { todo!() }\n");
+}
+
+#[test]
+fn test_reword_rejects_invalid_rewrites() {
+  let test_repo = TestRepo::new();
+  let initial = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  let commit = test_repo.create_commit("(parser) Add parser", "parser.rs", "fn parse() {}\n");
+  let main_branch = test_repo.current_branch().unwrap();
+  test_repo.checkout_new_branch("side").unwrap();
+  let side_commit = test_repo.create_commit("Side change", "side.rs", "fn side() {}\n");
+  test_repo.checkout(&main_branch).unwrap();
+  let head = test_repo.head();
+  let git_executor = test_repo.git_executor();
+
+  let error = reword_commits_core(git_executor, reword_params(&test_repo, &[(&commit, "  \n")])).unwrap_err();
+  assert!(error.to_string().contains("must not be empty"), "{error}");
+
+  let error = reword_commits_core(git_executor, reword_params(&test_repo, &[(&commit, "One"), (&commit, "Two")])).unwrap_err();
+  assert!(error.to_string().contains("more than once"), "{error}");
+
+  // Commit of another branch: nothing is rewritten, even the valid rewrite
+  let error = reword_commits_core(git_executor, reword_params(&test_repo, &[(&initial, "Initial"), (&side_commit, "Side")])).unwrap_err();
+  assert!(error.to_string().contains("not on the first-parent history"), "{error}");
+  assert_eq!(test_repo.head(), head);
+}
@@ -0,0 +1,472 @@
+use crate::repo_state::{load_repo_state, update_repo_state};
+use crate::sync::detect_baseline_branch;
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::git_config::get_config_value;
+use git_ops::model::to_final_branch_name;
+use git_ops::patch_export::{ExportBranchPatchesParams, PatchExportFormat, export_branch_patches};
+use git_ops::temp_dir::TempDirGuard;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, instrument};
+
+/// Notes ref recording the `Message-Id` of every mail a commit was sent with
+pub const SENT_EMAIL_NOTES_REF: &str = "refs/notes/branch-deck/sent";
+
+/// Cover letter template used when a branch doesn't define its own.
+/// The first line is the subject, the rest is the body (git appends the shortlog and diffstat after it).
+pub const DEFAULT_COVER_LETTER_TEMPLATE: &str = "{summary}\n\nThis series contains {patches} from branch \"{branch}\".";
+
+/// Connection security of the SMTP server (`sendemail.smtpEncryption`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum SmtpEncryption {
+  /// STARTTLS
+  Tls,
+  /// SMTP over SSL
+  Ssl,
+}
+
+impl SmtpEncryption {
+  fn config_value(self) -> &'static str {
+    match self {
+      Self::Tls => "tls",
+      Self::Ssl => "ssl",
+    }
+  }
+}
+
+/// SMTP settings, stored in the standard `sendemail.*` git config keys so `git send-email` uses them as is.
+/// The password is not stored: `git send-email` asks the git credential helper for it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SmtpConfig {
+  /// SMTP host, or the absolute path of a sendmail-like program
+  pub server: Option<String>,
+  pub port: Option<u16>,
+  pub encryption: Option<SmtpEncryption>,
+  pub user: Option<String>,
+  /// Sender address, the git identity is used when not set
+  pub from: Option<String>,
+  /// Default recipients
+  pub to: Vec<String>,
+  /// Default carbon copy recipients
+  pub cc: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct GetSmtpConfigParams {
+  pub repository_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SetSmtpConfigParams {
+  pub repository_path: String,
+  pub config: SmtpConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct GetCoverLetterTemplateParams {
+  pub repository_path: String,
+  pub branch_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SetCoverLetterTemplateParams {
+  pub repository_path: String,
+  pub branch_name: String,
+  /// Template with `{branch}`, `{summary}`, `{count}`, `{patches}` (e.g. "1 patch") and `{shortlog}` placeholders; `None` restores the default
+  pub template: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SendBranchPatchesParams {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  /// Virtual branch name (without prefix)
+  pub branch_name: String,
+  /// Recipients, the configured defaults are used when empty
+  pub to: Vec<String>,
+  /// Carbon copy recipients, the configured defaults are used when empty
+  pub cc: Vec<String>,
+  /// Send a cover letter rendered from the branch template as the first message
+  pub cover_letter: bool,
+  /// Branch summary used for the `{summary}` placeholder (falls back to the branch name)
+  pub summary: Option<String>,
+  /// Subject prefix, `PATCH` by default (e.g. `PATCH v2` or `RFC PATCH`)
+  pub subject_prefix: Option<String>,
+}
+
+/// A mail handed to the SMTP client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SentMessage {
+  pub subject: String,
+  pub message_id: String,
+  /// Commit the patch was generated from, `None` for the cover letter
+  pub commit_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SendBranchPatchesResult {
+  /// Sent mails in order, the first one starts the thread
+  pub messages: Vec<SentMessage>,
+}
+
+/// Progress events for sending patches
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "type", content = "data")]
+pub enum SendEmailProgress {
+  Started { total: u32 },
+  MessageSent { index: u32, message: SentMessage },
+  Completed,
+  Error { message: String },
+}
+
+/// Client delivering a single mail file (RFC 2822 message as written by `git format-patch`)
+pub trait MailTransport {
+  fn send(&self, repository_path: &str, mail_file: &str, to: &[String], cc: &[String]) -> Result<()>;
+}
+
+/// Delivers mails with `git send-email` using the `sendemail.*` configuration of the repository
+pub struct GitSendEmailTransport<'a> {
+  pub git_executor: &'a GitCommandExecutor,
+}
+
+impl MailTransport for GitSendEmailTransport<'_> {
+  fn send(&self, repository_path: &str, mail_file: &str, to: &[String], cc: &[String]) -> Result<()> {
+    // Threading headers are already written by format-patch
+    let mut args = vec!["send-email".to_string(), "--confirm=never".to_string(), "--quiet".to_string(), "--no-thread".to_string()];
+    args.extend(to.iter().map(|address| format!("--to={address}")));
+    args.extend(cc.iter().map(|address| format!("--cc={address}")));
+    args.push(mail_file.to_string());
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    self.git_executor.execute_command(&args, repository_path)?;
+    Ok(())
+  }
+}
+
+/// Values available to cover letter templates
+pub struct CoverLetterContext<'a> {
+  pub branch_name: &'a str,
+  pub summary: &'a str,
+  /// Subjects of the patches, oldest first
+  pub subjects: &'a [String],
+}
+
+/// Render a cover letter template into a subject (first line) and body
+pub fn render_cover_letter(template: &str, context: &CoverLetterContext) -> (String, String) {
+  let shortlog: Vec<String> = context.subjects.iter().map(|subject| format!("  {subject}")).collect();
+  let count = context.subjects.len();
+  let patches = if count == 1 { "1 patch".to_string() } else { format!("{count} patches") };
+  let rendered = template
+    .replace("{branch}", context.branch_name)
+    .replace("{summary}", context.summary)
+    .replace("{count}", &count.to_string())
+    .replace("{patches}", &patches)
+    .replace("{shortlog}", &shortlog.join("\n"));
+
+  let (subject, body) = rendered.split_once('\n').unwrap_or((rendered.as_str(), ""));
+  let subject = subject.trim();
+  let subject = if subject.is_empty() { context.summary } else { subject };
+  (subject.to_string(), body.trim().to_string())
+}
+
+fn config_list(git_executor: &GitCommandExecutor, repo_path: &str, key: &str) -> Result<Vec<String>> {
+  let (output, exit_code) = git_executor.execute_command_with_status(&["config", "--get-all", key], repo_path)?;
+  Ok(if exit_code == 0 { output.lines().map(str::to_string).collect() } else { Vec::new() })
+}
+
+#[instrument(skip(git_executor))]
+pub fn get_smtp_config_core(git_executor: &GitCommandExecutor, params: GetSmtpConfigParams) -> Result<SmtpConfig> {
+  let repo_path = params.repository_path.as_str();
+  let encryption = match get_config_value(git_executor, repo_path, "sendemail.smtpEncryption")?.as_deref() {
+    Some("tls") => Some(SmtpEncryption::Tls),
+    Some("ssl") => Some(SmtpEncryption::Ssl),
+    _ => None,
+  };
+  Ok(SmtpConfig {
+    server: get_config_value(git_executor, repo_path, "sendemail.smtpServer")?,
+    port: get_config_value(git_executor, repo_path, "sendemail.smtpServerPort")?.and_then(|port| port.parse().ok()),
+    encryption,
+    user: get_config_value(git_executor, repo_path, "sendemail.smtpUser")?,
+    from: get_config_value(git_executor, repo_path, "sendemail.from")?,
+    to: config_list(git_executor, repo_path, "sendemail.to")?,
+    cc: config_list(git_executor, repo_path, "sendemail.cc")?,
+  })
+}
+
+/// Store SMTP settings in the repository config; unset values are removed
+#[instrument(skip(git_executor))]
+pub fn set_smtp_config_core(git_executor: &GitCommandExecutor, params: SetSmtpConfigParams) -> Result<SmtpConfig> {
+  let repo_path = params.repository_path.as_str();
+  let config = params.config;
+
+  let port = config.port.map(|port| port.to_string());
+  let single_values = [
+    ("sendemail.smtpServer", config.server.as_deref()),
+    ("sendemail.smtpServerPort", port.as_deref()),
+    ("sendemail.smtpEncryption", config.encryption.map(SmtpEncryption::config_value)),
+    ("sendemail.smtpUser", config.user.as_deref()),
+    ("sendemail.from", config.from.as_deref()),
+  ];
+  for (key, value) in single_values {
+    match value.map(str::trim).filter(|value| !value.is_empty()) {
+      Some(value) => {
+        git_executor.execute_command(&["config", "--local", key, value], repo_path)?;
+      }
+      None => {
+        // Exit code 5 means the key was not set
+        git_executor.execute_command_with_status(&["config", "--local", "--unset-all", key], repo_path)?;
+      }
+    }
+  }
+
+  for (key, values) in [("sendemail.to", &config.to), ("sendemail.cc", &config.cc)] {
+    git_executor.execute_command_with_status(&["config", "--local", "--unset-all", key], repo_path)?;
+    for value in values.iter().map(|value| value.trim()).filter(|value| !value.is_empty()) {
+      git_executor.execute_command(&["config", "--local", "--add", key, value], repo_path)?;
+    }
+  }
+
+  get_smtp_config_core(
+    git_executor,
+    GetSmtpConfigParams {
+      repository_path: params.repository_path,
+    },
+  )
+}
+
+/// Cover letter template of a branch, the default template if the branch has none
+#[instrument(skip(git_executor))]
+pub fn get_cover_letter_template_core(git_executor: &GitCommandExecutor, params: GetCoverLetterTemplateParams) -> Result<String> {
+  let state = load_repo_state(git_executor, &params.repository_path)?;
+  Ok(
+    state
+      .cover_letter_templates
+      .get(&params.branch_name)
+      .cloned()
+      .unwrap_or_else(|| DEFAULT_COVER_LETTER_TEMPLATE.to_string()),
+  )
+}
+
+#[instrument(skip(git_executor))]
+pub fn set_cover_letter_template_core(git_executor: &GitCommandExecutor, params: SetCoverLetterTemplateParams) -> Result<String> {
+  let state = update_repo_state(git_executor, &params.repository_path, |state| {
+    match params
+      .template
+      .as_deref()
+      .filter(|template| !template.trim().is_empty() && *template != DEFAULT_COVER_LETTER_TEMPLATE)
+    {
+      Some(template) => state.cover_letter_templates.insert(params.branch_name.clone(), template.to_string()),
+      None => state.cover_letter_templates.remove(&params.branch_name),
+    };
+  })?;
+  Ok(
+    state
+      .cover_letter_templates
+      .get(&params.branch_name)
+      .cloned()
+      .unwrap_or_else(|| DEFAULT_COVER_LETTER_TEMPLATE.to_string()),
+  )
+}
+
+/// Headers of a mail written by format-patch
+struct MailHeaders {
+  commit_id: String,
+  subject: String,
+  message_id: String,
+}
+
+fn parse_mail_headers(content: &str) -> Result<MailHeaders> {
+  // The mbox separator line is "From <commit> Mon Sep 17 00:00:00 2001"
+  let mut lines = content.lines();
+  let commit_id = lines
+    .next()
+    .and_then(|line| line.strip_prefix("From "))
+    .and_then(|line| line.split_whitespace().next())
+    .ok_or_else(|| anyhow!("Generated patch doesn't start with an mbox \"From\" line"))?
+    .to_string();
+
+  let mut subject = String::new();
+  let mut message_id = String::new();
+  let mut in_subject = false;
+  for line in lines.take_while(|line| !line.is_empty()) {
+    if in_subject && line.starts_with([' ', '\t']) {
+      // Folded header continuation
+      subject.push(' ');
+      subject.push_str(line.trim());
+      continue;
+    }
+    in_subject = false;
+    let Some((name, value)) = line.split_once(':') else {
+      continue;
+    };
+    if name.eq_ignore_ascii_case("Subject") {
+      subject = value.trim().to_string();
+      in_subject = true;
+    } else if name.eq_ignore_ascii_case("Message-Id") {
+      message_id = value.trim().to_string();
+    }
+  }
+
+  if message_id.is_empty() {
+    return Err(anyhow!("Generated patch has no Message-Id header"));
+  }
+  Ok(MailHeaders { commit_id, subject, message_id })
+}
+
+/// Record the `Message-Id` a commit was sent with; notes accumulate when a branch is sent several times
+fn record_sent_message(git_executor: &GitCommandExecutor, repo_path: &str, commit_id: &str, message_id: &str, thread_id: &str) -> Result<()> {
+  let sent_at = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
+  let note = format!("Message-Id: {message_id}\nThread: {thread_id}\nSent: {sent_at}");
+  git_executor.execute_command(&["notes", "--ref", SENT_EMAIL_NOTES_REF, "append", "-m", &note, commit_id], repo_path)?;
+  Ok(())
+}
+
+/// Generate the patches of a virtual branch and send them one by one as a single thread.
+/// The `Message-Id` of every sent patch is recorded in [`SENT_EMAIL_NOTES_REF`] on the virtual branch commit.
+#[instrument(skip(git_executor, transport, on_progress))]
+pub fn send_branch_patches_core<T, F>(git_executor: &GitCommandExecutor, params: SendBranchPatchesParams, transport: &T, on_progress: F) -> Result<SendBranchPatchesResult>
+where
+  T: MailTransport,
+  F: Fn(SendEmailProgress),
+{
+  let result = send_branch_patches(git_executor, params, transport, &on_progress);
+  match &result {
+    Ok(_) => on_progress(SendEmailProgress::Completed),
+    Err(e) => on_progress(SendEmailProgress::Error { message: e.to_string() }),
+  }
+  result
+}
+
+fn send_branch_patches<T, F>(git_executor: &GitCommandExecutor, params: SendBranchPatchesParams, transport: &T, on_progress: &F) -> Result<SendBranchPatchesResult>
+where
+  T: MailTransport,
+  F: Fn(SendEmailProgress),
+{
+  let repo_path = params.repository_path.as_str();
+  let smtp_config = get_smtp_config_core(
+    git_executor,
+    GetSmtpConfigParams {
+      repository_path: params.repository_path.clone(),
+    },
+  )?;
+  if smtp_config.server.is_none() {
+    return Err(anyhow!("SMTP server is not configured"));
+  }
+  let to = if params.to.is_empty() { smtp_config.to } else { params.to };
+  let cc = if params.cc.is_empty() { smtp_config.cc } else { params.cc };
+  if to.is_empty() {
+    return Err(anyhow!("No recipients specified"));
+  }
+
+  let baseline_branch = detect_baseline_branch(git_executor, repo_path, "master")?;
+  let full_branch_name = to_final_branch_name(&params.branch_prefix, &params.branch_name)?;
+
+  let (cover_subject, cover_body) = if params.cover_letter {
+    let subjects = git_executor.execute_command_lines(
+      &[
+        "log",
+        "--reverse",
+        "--no-merges",
+        "--format=%s",
+        &format!("{baseline_branch}..refs/heads/{full_branch_name}"),
+      ],
+      repo_path,
+    )?;
+    let summary = params
+      .summary
+      .as_deref()
+      .map(str::trim)
+      .filter(|summary| !summary.is_empty())
+      .unwrap_or(&params.branch_name);
+    let template = get_cover_letter_template_core(
+      git_executor,
+      GetCoverLetterTemplateParams {
+        repository_path: params.repository_path.clone(),
+        branch_name: params.branch_name.clone(),
+      },
+    )?;
+    let (subject, body) = render_cover_letter(
+      &template,
+      &CoverLetterContext {
+        branch_name: &params.branch_name,
+        summary,
+        subjects: &subjects,
+      },
+    );
+    (Some(subject), Some(body))
+  } else {
+    (None, None)
+  };
+
+  let output_dir = TempDirGuard::new(repo_path, "send_email")?;
+  let exported = export_branch_patches(
+    git_executor,
+    ExportBranchPatchesParams {
+      repository_path: params.repository_path.clone(),
+      branch_prefix: params.branch_prefix.clone(),
+      branch_name: params.branch_name.clone(),
+      format: PatchExportFormat::Series,
+      output_path: output_dir.path().to_string_lossy().into_owned(),
+      cover_letter: params.cover_letter,
+      summary: cover_subject,
+      subject_prefix: params.subject_prefix.clone(),
+      cover_letter_body: cover_body,
+      thread: true,
+    },
+    &baseline_branch,
+  )?;
+
+  on_progress(SendEmailProgress::Started {
+    total: exported.files.len() as u32,
+  });
+
+  let mut messages = Vec::with_capacity(exported.files.len());
+  for (index, file) in exported.files.iter().enumerate() {
+    let headers = parse_mail_headers(&std::fs::read_to_string(file)?)?;
+    transport
+      .send(repo_path, file, &to, &cc)
+      .map_err(|e| anyhow!("Failed to send \"{}\": {e}", headers.subject))?;
+    debug!(message_id = %headers.message_id, subject = %headers.subject, "Sent patch");
+
+    // The first message starts the thread, the others reply to it
+    let thread_id = messages.first().map_or(headers.message_id.as_str(), |first| first.message_id.as_str()).to_string();
+    // The cover letter is written first and carries the tip commit in its "From" line
+    let commit_id = if params.cover_letter && index == 0 { None } else { Some(headers.commit_id) };
+    if let Some(commit_id) = &commit_id {
+      record_sent_message(git_executor, repo_path, commit_id, &headers.message_id, &thread_id)?;
+    }
+
+    let message = SentMessage {
+      subject: headers.subject,
+      message_id: headers.message_id,
+      commit_id,
+    };
+    on_progress(SendEmailProgress::MessageSent {
+      index: index as u32,
+      message: message.clone(),
+    });
+    messages.push(message);
+  }
+
+  info!(branch = %full_branch_name, count = messages.len(), "Sent branch patches");
+  Ok(SendBranchPatchesResult { messages })
+}
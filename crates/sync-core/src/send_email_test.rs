@@ -0,0 +1,223 @@
+use crate::send_email::{
+  CoverLetterContext, DEFAULT_COVER_LETTER_TEMPLATE, GetCoverLetterTemplateParams, MailTransport, SENT_EMAIL_NOTES_REF, SendBranchPatchesParams, SendEmailProgress,
+  SetCoverLetterTemplateParams, SetSmtpConfigParams, SmtpConfig, SmtpEncryption, get_cover_letter_template_core, render_cover_letter, send_branch_patches_core,
+  set_cover_letter_template_core, set_smtp_config_core,
+};
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use std::sync::Mutex;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// Transport keeping the sent mails instead of delivering them
+#[derive(Default)]
+struct RecordingTransport {
+  sent: Mutex<Vec<(String, Vec<String>, Vec<String>)>>,
+}
+
+impl MailTransport for RecordingTransport {
+  fn send(&self, _repository_path: &str, mail_file: &str, to: &[String], cc: &[String]) -> Result<()> {
+    let content = std::fs::read_to_string(mail_file)?;
+    self.sent.lock().unwrap().push((content, to.to_vec(), cc.to_vec()));
+    Ok(())
+  }
+}
+
+/// Repository with a two-commit virtual branch `user/virtual/parser` ahead of `origin/master`
+fn setup_branch(test_repo: &TestRepo) {
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+  test_repo.git(&["update-ref", "refs/remotes/origin/master", &baseline]);
+  test_repo.create_commit("Add parser", "parser.rs", "fn parse() {}\n");
+  test_repo.create_commit("Test parser", "parser_test.rs", "fn test_parse() {}\n");
+  let head = test_repo.head();
+  test_repo.create_branch_at("user/virtual/parser", &head).unwrap();
+}
+
+fn send_params(test_repo: &TestRepo) -> SendBranchPatchesParams {
+  SendBranchPatchesParams {
+    repository_path: test_repo.path_str().to_string(),
+    branch_prefix: "user".to_string(),
+    branch_name: "parser".to_string(),
+    to: Vec::new(),
+    cc: Vec::new(),
+    cover_letter: true,
+    summary: Some("Add a parser".to_string()),
+    subject_prefix: None,
+  }
+}
+
+fn smtp_config() -> SmtpConfig {
+  SmtpConfig {
+    server: Some("smtp.example.com".to_string()),
+    port: Some(587),
+    encryption: Some(SmtpEncryption::Tls),
+    user: Some("dev".to_string()),
+    from: None,
+    to: vec!["list@example.com".to_string()],
+    cc: vec!["maintainer@example.com".to_string(), "reviewer@example.com".to_string()],
+  }
+}
+
+#[test]
+fn test_smtp_config_round_trip() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  let git_executor = GitCommandExecutor::new();
+
+  let stored = set_smtp_config_core(
+    &git_executor,
+    SetSmtpConfigParams {
+      repository_path: test_repo.path_str().to_string(),
+      config: smtp_config(),
+    },
+  )
+  .unwrap();
+  assert_eq!(stored, smtp_config());
+  assert_eq!(git_executor.execute_command(&["config", "sendemail.smtpEncryption"], test_repo.path_str()).unwrap(), "tls");
+
+  // Unset values are removed
+  let cleared = set_smtp_config_core(
+    &git_executor,
+    SetSmtpConfigParams {
+      repository_path: test_repo.path_str().to_string(),
+      config: SmtpConfig {
+        server: Some("smtp.example.com".to_string()),
+        ..Default::default()
+      },
+    },
+  )
+  .unwrap();
+  assert_eq!(
+    cleared,
+    SmtpConfig {
+      server: Some("smtp.example.com".to_string()),
+      ..Default::default()
+    }
+  );
+}
+
+#[test]
+fn test_cover_letter_template_per_branch() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  let git_executor = GitCommandExecutor::new();
+  let get_params = |branch_name: &str| GetCoverLetterTemplateParams {
+    repository_path: test_repo.path_str().to_string(),
+    branch_name: branch_name.to_string(),
+  };
+
+  assert_eq!(get_cover_letter_template_core(&git_executor, get_params("parser")).unwrap(), DEFAULT_COVER_LETTER_TEMPLATE);
+
+  let set = |template: Option<&str>| {
+    set_cover_letter_template_core(
+      &git_executor,
+      SetCoverLetterTemplateParams {
+        repository_path: test_repo.path_str().to_string(),
+        branch_name: "parser".to_string(),
+        template: template.map(str::to_string),
+      },
+    )
+    .unwrap()
+  };
+  set(Some("{summary}\n\nCustom text"));
+  assert_eq!(get_cover_letter_template_core(&git_executor, get_params("parser")).unwrap(), "{summary}\n\nCustom text");
+  assert_eq!(get_cover_letter_template_core(&git_executor, get_params("other")).unwrap(), DEFAULT_COVER_LETTER_TEMPLATE);
+
+  assert_eq!(set(None), DEFAULT_COVER_LETTER_TEMPLATE);
+  assert_eq!(get_cover_letter_template_core(&git_executor, get_params("parser")).unwrap(), DEFAULT_COVER_LETTER_TEMPLATE);
+}
+
+#[test]
+fn test_render_cover_letter() {
+  let subjects = vec!["Add parser".to_string(), "Test parser".to_string()];
+  let context = CoverLetterContext {
+    branch_name: "parser",
+    summary: "Add a parser",
+    subjects: &subjects,
+  };
+
+  let (subject, body) = render_cover_letter(DEFAULT_COVER_LETTER_TEMPLATE, &context);
+  assert_eq!(subject, "Add a parser");
+  assert_eq!(body, "This series contains 2 patches from branch \"parser\".");
+
+  let (subject, body) = render_cover_letter("[{branch}] {summary}\n\nChanges:\n{shortlog}", &context);
+  assert_eq!(subject, "[parser] Add a parser");
+  assert_eq!(body, "Changes:\n  Add parser\n  Test parser");
+}
+
+#[test]
+fn test_render_cover_letter_of_single_patch() {
+  let subjects = vec!["Add parser".to_string()];
+  let context = CoverLetterContext {
+    branch_name: "parser",
+    summary: "Add a parser",
+    subjects: &subjects,
+  };
+
+  let (_, body) = render_cover_letter(DEFAULT_COVER_LETTER_TEMPLATE, &context);
+  assert_eq!(body, "This series contains 1 patch from branch \"parser\".");
+}
+
+#[test]
+fn test_send_branch_patches_records_message_ids() {
+  let test_repo = TestRepo::new();
+  setup_branch(&test_repo);
+  let git_executor = GitCommandExecutor::new();
+  set_smtp_config_core(
+    &git_executor,
+    SetSmtpConfigParams {
+      repository_path: test_repo.path_str().to_string(),
+      config: smtp_config(),
+    },
+  )
+  .unwrap();
+
+  let transport = RecordingTransport::default();
+  let events = Mutex::new(Vec::new());
+  let result = send_branch_patches_core(&git_executor, send_params(&test_repo), &transport, |event| events.lock().unwrap().push(event)).unwrap();
+
+  // Cover letter first, then the patches replying to it
+  let subjects: Vec<&str> = result.messages.iter().map(|message| message.subject.as_str()).collect();
+  assert_eq!(subjects, vec!["[PATCH 0/2] Add a parser", "[PATCH 1/2] Add parser", "[PATCH 2/2] Test parser"]);
+  assert_eq!(result.messages[0].commit_id, None);
+
+  let sent = transport.sent.lock().unwrap();
+  assert_eq!(sent.len(), 3);
+  // Configured default recipients are used
+  assert_eq!(sent[0].1, vec!["list@example.com".to_string()]);
+  assert_eq!(sent[0].2.len(), 2);
+  assert!(sent[0].0.contains("This series contains 2 patches from branch \"parser\"."), "cover letter: {}", sent[0].0);
+  let cover_message_id = &result.messages[0].message_id;
+  assert!(sent[1].0.contains(&format!("In-Reply-To: {cover_message_id}")), "patch: {}", sent[1].0);
+
+  // Message ids are recorded on the virtual branch commits
+  for message in &result.messages[1..] {
+    let commit_id = message.commit_id.as_deref().unwrap();
+    let note = git_executor
+      .execute_command(&["notes", "--ref", SENT_EMAIL_NOTES_REF, "show", commit_id], test_repo.path_str())
+      .unwrap();
+    assert!(note.contains(&format!("Message-Id: {}", message.message_id)), "note: {note}");
+    assert!(note.contains(&format!("Thread: {cover_message_id}")), "note: {note}");
+  }
+
+  let events = events.into_inner().unwrap();
+  assert!(matches!(events.first(), Some(SendEmailProgress::Started { total: 3 })));
+  assert_eq!(events.iter().filter(|event| matches!(event, SendEmailProgress::MessageSent { .. })).count(), 3);
+  assert!(matches!(events.last(), Some(SendEmailProgress::Completed)));
+}
+
+#[test]
+fn test_send_branch_patches_requires_smtp_server() {
+  let test_repo = TestRepo::new();
+  setup_branch(&test_repo);
+
+  let transport = RecordingTransport::default();
+  let events = Mutex::new(Vec::new());
+  let error = send_branch_patches_core(&GitCommandExecutor::new(), send_params(&test_repo), &transport, |event| events.lock().unwrap().push(event)).unwrap_err();
+
+  assert_eq!(error.to_string(), "SMTP server is not configured");
+  assert!(transport.sent.lock().unwrap().is_empty());
+  assert!(matches!(events.into_inner().unwrap().as_slice(), [SendEmailProgress::Error { .. }]));
+}
@@ -0,0 +1,67 @@
+use crate::repository_validation::is_shallow_repository;
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use sync_types::{ProgressReporter, SyncEvent};
+use tracing::{debug, info, instrument};
+
+/// Commits fetched by the first `fetch --deepen`, doubled on every further attempt
+const INITIAL_DEEPEN_BY: u32 = 50;
+/// Attempts before giving up on deepening step by step and fetching the whole history
+const MAX_DEEPEN_ATTEMPTS: u32 = 5;
+
+fn has_merge_base(git_executor: &GitCommandExecutor, repository_path: &str, baseline_branch: &str) -> Result<bool> {
+  // Exit code 1 means no common ancestor in the available history
+  let (_, exit_code) = git_executor.execute_command_with_status(&["merge-base", baseline_branch, "HEAD"], repository_path)?;
+  Ok(exit_code == 0)
+}
+
+/// Remote of the baseline branch (`origin` for `origin/master`), the first remote for a local baseline
+fn baseline_remote(git_executor: &GitCommandExecutor, repository_path: &str, baseline_branch: &str) -> Result<Option<String>> {
+  let remotes = git_executor.execute_command_lines(&["remote"], repository_path)?;
+  let remote = remotes
+    .iter()
+    .find(|remote| baseline_branch.strip_prefix(remote.as_str()).is_some_and(|rest| rest.starts_with('/')))
+    .or_else(|| remotes.first());
+  Ok(remote.cloned())
+}
+
+/// In a shallow clone the merge base of HEAD and the baseline may be cut off, so `baseline..HEAD` would list
+/// baseline commits as well. Fetch just enough history to reach the merge base, doubling the depth on every attempt and
+/// fetching the whole history as a last resort; a [`SyncEvent::HistoryDeepening`] is sent before every fetch.
+/// Returns whether history was fetched.
+#[instrument(skip(git_executor, progress))]
+pub fn deepen_to_merge_base<P: ProgressReporter>(git_executor: &GitCommandExecutor, repository_path: &str, baseline_branch: &str, progress: &P) -> Result<bool> {
+  if !is_shallow_repository(git_executor, repository_path)? || has_merge_base(git_executor, repository_path, baseline_branch)? {
+    return Ok(false);
+  }
+  let Some(remote) = baseline_remote(git_executor, repository_path, baseline_branch)? else {
+    debug!("Shallow repository without remotes, nothing to deepen from");
+    return Ok(false);
+  };
+
+  let mut deepen_by = INITIAL_DEEPEN_BY;
+  for _ in 0..MAX_DEEPEN_ATTEMPTS {
+    progress.send(SyncEvent::HistoryDeepening {
+      remote: remote.clone(),
+      deepen_by: Some(deepen_by),
+    })?;
+    git_executor.execute_command(&["fetch", "--no-tags", &format!("--deepen={deepen_by}"), &remote], repository_path)?;
+    if has_merge_base(git_executor, repository_path, baseline_branch)? {
+      info!(remote, deepen_by, "Deepened shallow clone to the merge base");
+      return Ok(true);
+    }
+    // The whole history is there, HEAD and the baseline are unrelated
+    if !is_shallow_repository(git_executor, repository_path)? {
+      return Ok(true);
+    }
+    deepen_by *= 2;
+  }
+
+  progress.send(SyncEvent::HistoryDeepening {
+    remote: remote.clone(),
+    deepen_by: None,
+  })?;
+  git_executor.execute_command(&["fetch", "--no-tags", "--unshallow", &remote], repository_path)?;
+  info!(remote, "Fetched the whole history of shallow clone");
+  Ok(true)
+}
@@ -0,0 +1,80 @@
+use crate::repository_validation::is_shallow_repository;
+use crate::shallow_clone::deepen_to_merge_base;
+use crate::sync::detect_baseline_branch;
+use pretty_assertions::assert_eq;
+use sync_test_utils::TestReporter;
+use sync_types::SyncEvent;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn create_commits(repo: &TestRepo, range: std::ops::RangeInclusive<u32>) {
+  for i in range {
+    repo.create_commit(&format!("Baseline commit {i}"), &format!("file{i}.txt"), &i.to_string());
+  }
+}
+
+fn deepen_events(reporter: &TestReporter) -> Vec<Option<u32>> {
+  reporter
+    .get_events()
+    .into_iter()
+    .filter_map(|event| match event {
+      SyncEvent::HistoryDeepening { remote, deepen_by } => {
+        assert_eq!(remote, "origin");
+        Some(deepen_by)
+      }
+      _ => None,
+    })
+    .collect()
+}
+
+#[test]
+fn test_deepen_shallow_clone_to_merge_base() {
+  let origin = TestRepo::new();
+  create_commits(&origin, 1..=5);
+
+  let local = TestRepo::new_empty();
+  local.shallow_clone_from(origin.path(), 3).unwrap();
+  local.create_commit("(feature) Local change", "feature.txt", "feature");
+
+  // The baseline moves on and a shallow fetch cuts it off from the fork point
+  create_commits(&origin, 6..=70);
+  local
+    .git_executor()
+    .execute_command(&["fetch", "--depth=1", "origin"], local.path().to_str().unwrap())
+    .unwrap();
+
+  let repo_path = local.path().to_str().unwrap();
+  let baseline = detect_baseline_branch(local.git_executor(), repo_path, "master").unwrap();
+  let range = format!("{baseline}..HEAD");
+  assert!(is_shallow_repository(local.git_executor(), repo_path).unwrap());
+  // Baseline commits 3 to 5 look like local ones
+  assert_eq!(local.log(&["--format=%s", &range]).unwrap().lines().count(), 4);
+
+  let reporter = TestReporter::new();
+  assert!(deepen_to_merge_base(local.git_executor(), repo_path, &baseline, &reporter).unwrap());
+  // 50 commits don't reach the fork point, 100 more do
+  assert_eq!(deepen_events(&reporter), vec![Some(50), Some(100)]);
+  assert_eq!(local.log(&["--format=%s", &range]).unwrap().trim(), "(feature) Local change");
+
+  // Nothing left to do
+  let reporter = TestReporter::new();
+  assert!(!deepen_to_merge_base(local.git_executor(), repo_path, &baseline, &reporter).unwrap());
+  assert_eq!(deepen_events(&reporter), Vec::new());
+}
+
+#[test]
+fn test_full_clone_is_not_deepened() {
+  let origin = TestRepo::new();
+  create_commits(&origin, 1..=3);
+
+  let local = TestRepo::new_empty();
+  local.clone_from(origin.path()).unwrap();
+  local.create_commit("(feature) Local change", "feature.txt", "feature");
+
+  let repo_path = local.path().to_str().unwrap();
+  let baseline = detect_baseline_branch(local.git_executor(), repo_path, "master").unwrap();
+  assert!(!is_shallow_repository(local.git_executor(), repo_path).unwrap());
+  let reporter = TestReporter::new();
+  assert!(!deepen_to_merge_base(local.git_executor(), repo_path, &baseline, &reporter).unwrap());
+  assert_eq!(reporter.event_count(), 0);
+}
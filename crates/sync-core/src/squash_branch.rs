@@ -0,0 +1,112 @@
+use crate::sync::detect_baseline_branch;
+use anyhow::{Result, anyhow, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::amend_operations::squash_commits_in_head;
+use git_ops::commit_utils::{Trailer, add_trailers, parse_trailers};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SquashBranchCommitsParams {
+  pub repository_path: String,
+  pub branch_name: String,
+  /// Message of the squashed commit (e.g., AI-generated), combined from the squashed commits if not set.
+  /// The `(branch-name)` prefix is added if missing.
+  pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SquashBranchCommitsResult {
+  pub squashed_count: u32,
+  pub squashed_commit_id: String,
+  pub message: String,
+}
+
+/// Rewrite the `(branch-name)` commits of the current branch into a single commit at the position of the oldest one
+#[instrument(skip(git_executor), fields(repository_path = %params.repository_path, branch_name = %params.branch_name))]
+pub fn squash_branch_commits_core(git_executor: &GitCommandExecutor, params: SquashBranchCommitsParams) -> Result<SquashBranchCommitsResult> {
+  let SquashBranchCommitsParams {
+    repository_path,
+    branch_name,
+    message,
+  } = params;
+  let repo = repository_path.as_str();
+
+  let current_branch = git_executor
+    .execute_command(&["symbolic-ref", "--short", "HEAD"], repo)
+    .map_err(|_| anyhow!("HEAD is detached, check out a branch first"))?
+    .trim()
+    .to_string();
+
+  let commits = branch_commit_messages(git_executor, repo, &branch_name)?;
+  if commits.len() < 2 {
+    bail!("Branch '{branch_name}' has {} commit(s), nothing to squash", commits.len());
+  }
+
+  let prefix = format!("({branch_name}) ");
+  let message = match message.as_deref().map(str::trim) {
+    Some("") => bail!("Commit message cannot be empty"),
+    Some(message) if message.starts_with(&prefix) => message.to_string(),
+    Some(message) => format!("{prefix}{message}"),
+    None => combine_messages(&prefix, commits.iter().map(|(_, message)| message.as_str())),
+  };
+
+  let commit_ids: Vec<String> = commits.into_iter().map(|(commit_id, _)| commit_id).collect();
+  let squashed = squash_commits_in_head(git_executor, repo, &commit_ids, &message, &current_branch).map_err(|e| e.into_anyhow("Failed to squash commits"))?;
+  info!(squashed_count = commit_ids.len(), squashed_commit_id = %squashed.squashed_commit_id, "Squashed branch commits");
+
+  Ok(SquashBranchCommitsResult {
+    squashed_count: commit_ids.len() as u32,
+    squashed_commit_id: squashed.squashed_commit_id,
+    message,
+  })
+}
+
+/// `(commit ID, message)` of the commits with the `(branch-name)` prefix ahead of the baseline, oldest first
+fn branch_commit_messages(git_executor: &GitCommandExecutor, repo: &str, branch_name: &str) -> Result<Vec<(String, String)>> {
+  let baseline_branch = detect_baseline_branch(git_executor, repo, "master")?;
+  let output = git_executor.execute_command(
+    &[
+      "--no-pager",
+      "log",
+      "--first-parent",
+      "--no-merges",
+      "--reverse",
+      "--format=%H%x1f%B%x1e",
+      &format!("{baseline_branch}..HEAD"),
+    ],
+    repo,
+  )?;
+
+  let prefix = format!("({branch_name}) ");
+  let mut commits = Vec::new();
+  for record in output.split('\x1e').map(str::trim).filter(|record| !record.is_empty()) {
+    let (commit_id, message) = record.split_once('\x1f').ok_or_else(|| anyhow!("Unexpected git log output"))?;
+    if message.trim().starts_with(&prefix) {
+      commits.push((commit_id.to_string(), message.trim().to_string()));
+    }
+  }
+  Ok(commits)
+}
+
+/// Subject of the oldest commit followed by the list of all squashed subjects, e.g.
+/// "(parser) Add parser\n\n* Add parser\n* Test parser".
+/// Trailers of the squashed commits (`Co-authored-by`, ...) are kept once at the end.
+fn combine_messages<'a>(prefix: &str, messages: impl Iterator<Item = &'a str>) -> String {
+  let mut subjects: Vec<&str> = Vec::new();
+  let mut trailers: Vec<Trailer> = Vec::new();
+  for message in messages {
+    let subject = message.lines().next().unwrap_or_default();
+    subjects.push(subject.strip_prefix(prefix).unwrap_or(subject).trim());
+    trailers.extend(parse_trailers(message));
+  }
+  let mut combined = format!("{prefix}{}\n", subjects.first().copied().unwrap_or_default());
+  for subject in &subjects {
+    combined.push_str(&format!("\n* {subject}"));
+  }
+  add_trailers(&combined, &trailers)
+}
@@ -0,0 +1,101 @@
+use crate::squash_branch::{SquashBranchCommitsParams, squash_branch_commits_core};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+/// Commits of `parser` and `lexer` on top of `origin/master`
+fn setup_commits(test_repo: &TestRepo) {
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+  test_repo
+    .git_executor()
+    .execute_command(&["update-ref", "refs/remotes/origin/master", &baseline], test_repo.path_str())
+    .unwrap();
+  test_repo.create_commit("(parser) Add parser", "parser.rs", "fn parse() {}\n");
+  test_repo.create_commit("(lexer) Add lexer", "lexer.rs", "fn lex() {}\n");
+  test_repo.create_commit("(parser) Handle errors", "parser.rs", "fn parse() -> Result<()> {}\n");
+  test_repo.create_commit("(parser) Test parser", "parser_test.rs", "fn test_parse() {}\n");
+}
+
+fn squash_params(test_repo: &TestRepo, branch_name: &str, message: Option<&str>) -> SquashBranchCommitsParams {
+  SquashBranchCommitsParams {
+    repository_path: test_repo.path_str().to_string(),
+    branch_name: branch_name.to_string(),
+    message: message.map(str::to_string),
+  }
+}
+
+#[test]
+fn test_squash_branch_commits_with_combined_message() {
+  let test_repo = TestRepo::new();
+  setup_commits(&test_repo);
+  let head_tree = test_repo.rev_parse("HEAD^{tree}").unwrap();
+
+  let result = squash_branch_commits_core(&GitCommandExecutor::new(), squash_params(&test_repo, "parser", None)).unwrap();
+
+  assert_eq!(result.squashed_count, 3);
+  assert_eq!(result.message, "(parser) Add parser\n\n* Add parser\n* Handle errors\n* Test parser");
+  assert_eq!(
+    test_repo.get_commit_messages(3),
+    vec!["(lexer) Add lexer".to_string(), "(parser) Add parser".to_string(), "Initial commit".to_string()]
+  );
+  assert_eq!(test_repo.rev_parse("HEAD~1").unwrap(), result.squashed_commit_id);
+  // Squashing only moves changes, the resulting tree stays the same
+  assert_eq!(test_repo.rev_parse("HEAD^{tree}").unwrap(), head_tree);
+  let files = test_repo
+    .git_executor()
+    .execute_command_lines(&["diff-tree", "--no-commit-id", "--name-only", "-r", &result.squashed_commit_id], test_repo.path_str())
+    .unwrap();
+  assert_eq!(files, vec!["parser.rs".to_string(), "parser_test.rs".to_string()]);
+}
+
+#[test]
+fn test_squash_branch_commits_with_custom_message() {
+  let test_repo = TestRepo::new();
+  setup_commits(&test_repo);
+
+  let result = squash_branch_commits_core(&GitCommandExecutor::new(), squash_params(&test_repo, "parser", Some("Implement parser\n\nWith tests"))).unwrap();
+
+  assert_eq!(result.message, "(parser) Implement parser\n\nWith tests");
+  assert_eq!(
+    test_repo.get_commit_messages(2),
+    vec!["(lexer) Add lexer".to_string(), "(parser) Implement parser".to_string()]
+  );
+}
+
+#[test]
+fn test_squash_branch_with_single_commit_fails() {
+  let test_repo = TestRepo::new();
+  setup_commits(&test_repo);
+  let head = test_repo.head();
+
+  let error = squash_branch_commits_core(&GitCommandExecutor::new(), squash_params(&test_repo, "lexer", None)).unwrap_err();
+
+  assert_eq!(error.to_string(), "Branch 'lexer' has 1 commit(s), nothing to squash");
+  assert_eq!(test_repo.head(), head);
+}
+
+#[test]
+fn test_squash_branch_keeps_trailers() {
+  let test_repo = TestRepo::new();
+  let baseline = test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.add_remote("origin", "https://example.com/repo.git").unwrap();
+  test_repo
+    .git_executor()
+    .execute_command(&["update-ref", "refs/remotes/origin/master", &baseline], test_repo.path_str())
+    .unwrap();
+  test_repo.create_commit("(parser) Add parser\n\nCo-authored-by: Jane <jane@example.com>", "parser.rs", "fn parse() {}\n");
+  test_repo.create_commit(
+    "(parser) Test parser\n\nCo-authored-by: Jane <jane@example.com>\nReviewed-by: John <john@example.com>",
+    "parser_test.rs",
+    "fn test_parse() {}\n",
+  );
+
+  let result = squash_branch_commits_core(&GitCommandExecutor::new(), squash_params(&test_repo, "parser", None)).unwrap();
+
+  assert_eq!(
+    result.message,
+    "(parser) Add parser\n\n* Add parser\n* Test parser\n\nCo-authored-by: Jane <jane@example.com>\nReviewed-by: John <john@example.com>"
+  );
+}
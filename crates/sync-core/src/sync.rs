@@ -1,20 +1,56 @@
-use crate::branch_processor::{BranchProcessingParams, process_single_branch};
-use crate::commit_grouper::CommitGrouper;
+use crate::auto_archive::{AutoArchiveParams, AutoArchivePolicy, auto_archive_integrated_branches, load_auto_archive_policy};
+use crate::auto_fetch::{AutoFetchPolicy, fetch_before_sync, load_auto_fetch_policy};
+use crate::auto_stash::{StashRestore, load_auto_stash, restore_stashed_changes, stash_uncommitted_changes};
+use crate::baseline_update::resolve_baseline_commit;
+use crate::branch_order::apply_branch_order;
+use crate::branch_processor::{BranchProcessingParams, load_sync_concurrency, process_branches};
+use crate::branch_protection::load_protected_branches;
+use crate::commit_dependencies::{DependencyViolation, validate_commit_dependencies};
+use crate::commit_grouper::{CommitGrouper, MAX_DETAILED_UNASSIGNED_COMMITS, load_commit_grouper};
+use crate::commit_order::order_commits;
+use crate::conflict_mode::{ConflictMode, load_conflict_mode};
+use crate::empty_branch_policy::{EmptyBranchPolicy, load_empty_branch_policy};
+use crate::hooks::{BranchHookQueue, GroupedBranchSummary, HookContext, SyncHooks, load_sync_hooks};
 use crate::issue_navigation::load_issue_navigation_config;
+use crate::phase_budget::{PhaseTimeBudgets, after_background_phases, await_phase, load_phase_time_budgets, spawn_background_task, wait_for_background_phases};
+use crate::remote_status::{ARCHIVED_REMOTE_STATUS_CONFIG_KEY, compute_remote_status_for_archived_branches};
+use crate::repo_state::{RepoState, STATE_REF, load_repo_state};
+use crate::shallow_clone::deepen_to_merge_base;
+use crate::sync_performance::{SyncHistoryEntry, SyncTimings, append_sync_history};
+use crate::usage_stats::{MergeConflictCounter, UsageCounter, update_usage_stats};
+use crate::verify_metadata::MAPPING_NOTES_REF;
+use crate::webhook::{SyncSummary, SyncSummaryCollector, SyncSummaryNotifier};
 use anyhow::{Result, anyhow};
+use branch_integration::budget::DetectionBudget;
+use branch_integration::common::is_only_prefixed_branch;
+use branch_integration::lookback::LookbackWindow;
 use branch_integration::{detector::detect_integrated_branches, strategy::DetectionStrategy};
 use git_executor::git_command_executor::GitCommandExecutor;
-use git_ops::cache::TreeIdCache;
-use git_ops::commit_list::{Commit, get_commit_list_with_handler};
+use git_ops::binary_conflict::{BINARY_RESOLUTION_RETENTION_DAYS, prune_binary_resolutions};
+use git_ops::cache::{PERSISTENT_TREE_CACHE_CONFIG_KEY, TreeIdCache};
+use git_ops::case_collision::load_ignore_case;
+use git_ops::commit_dependencies::load_commit_dependencies;
+use git_ops::commit_list::{Commit, CommitFilter, DEFAULT_COMMIT_PAGE_SIZE, get_oldest_commit_id, get_paged_branch_commit_list_with_handler, get_paged_commit_list_with_handler};
+use git_ops::commit_utils::{load_commit_trailer_templates, resolve_commit_signing};
+use git_ops::diff_options::{DiffOptions, load_diff_options};
+use git_ops::empty_commit_policy::{EmptyCommitPolicy, load_empty_commit_policy};
+use git_ops::git_config::get_config_bool;
+use git_ops::model::{BranchError, BranchSyncStatus};
+use git_ops::notes::COPY_NOTES_CONFIG_KEY;
+use git_ops::partial_clone::{PREFETCH_CONFIG_KEY, detect_partial_clone, prefetch_objects_for_range};
+use git_ops::ref_transaction::{begin_ref_journal, finish_ref_journal, roll_back_ref_journal};
+use git_ops::repository_lock::lock_repository;
 use indexmap::IndexMap;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use sync_types::issue_navigation::IssueNavigationConfig;
 use sync_types::ordered_progress_reporter::OrderedProgressReporter;
-use sync_types::{GroupedBranchInfo, ProgressReporter, SyncEvent};
+use sync_types::{CommitOrder, GroupedBranchInfo, ProgressReporter, SyncEvent, SyncPhase};
 use sync_utils::issue_pattern::{find_issue_range, has_issue_reference};
-use tokio::task::JoinSet;
-use tracing::{debug, error, info, instrument, warn};
+use tracing::{debug, info, instrument, warn};
 
 /// Options for configuring sync behavior
 pub struct SyncOptions {
@@ -25,6 +61,62 @@ pub struct SyncOptions {
   /// Archive cleanup retention in days (older archived branches will be deleted)
   /// Defaults to the current retention used by branch-integration (7 days).
   pub archive_retention_days: u64,
+  /// How far back in baseline history integration detection looks for integrated commits.
+  /// `branchdeck.integrationLookbackCommits` and `branchdeck.integrationLookbackDays` in git config take precedence.
+  pub integration_lookback: LookbackWindow,
+  /// Limits of the deep integration detection steps; branches past them are reported as truncated.
+  /// `branchdeck.detectionDeepScanBudgetMs` and `branchdeck.detectionMaxBranchCommits` in git config take precedence.
+  pub detection_budget: DetectionBudget,
+  /// Batch-prefetch missing objects for all commits of the run when the repository is a partial clone.
+  /// `None` reads `branchdeck.prefetchMissingObjects` from git config.
+  pub prefetch_missing_objects: Option<bool>,
+  /// Copy user notes (`refs/notes/commits`) of original commits to the rewritten commits.
+  /// `None` reads `branchdeck.copyNotes` from git config.
+  pub copy_user_notes: Option<bool>,
+  /// What to do when a commit conflicts.
+  /// `None` reads `branchdeck.conflictMode` from git config.
+  pub conflict_mode: Option<ConflictMode>,
+  /// What to do with branches whose commits are all already integrated into the baseline.
+  /// `None` reads `branchdeck.emptyBranchPolicy` from git config.
+  pub empty_branch_policy: Option<EmptyBranchPolicy>,
+  /// What to do with commits whose changes are already in the baseline when copied onto it.
+  /// `None` reads `branchdeck.emptyCommitPolicy` from git config.
+  pub empty_commit_policy: Option<EmptyCommitPolicy>,
+  /// Context lines, diff algorithm and rename detection used for conflict diffs.
+  /// `None` reads `branchdeck.diffContextLines`, `branchdeck.diffAlgorithm` and `branchdeck.renameSimilarity` from git config.
+  pub diff_options: Option<DiffOptions>,
+  /// Report archived branches that are still present on the remote.
+  /// `None` reads `branchdeck.archivedRemoteStatus` from git config.
+  pub archived_remote_status: Option<bool>,
+  /// Keep resolved tree IDs and clean cherry-pick results under `.git/branch-deck/cache` for the next sync, so unchanged branches rebuild without merging.
+  /// `None` reads `branchdeck.persistentTreeCache` from git config.
+  pub persistent_tree_cache: Option<bool>,
+  /// Maximum number of branches synced at once, `1` syncs them one by one.
+  /// `None` reads `branchdeck.syncConcurrency` from git config, defaulting to the number of CPUs.
+  pub sync_concurrency: Option<NonZeroUsize>,
+  /// Only rebuild the virtual branches with a commit matching the filter (date, count, paths), e.g. in huge histories.
+  /// It never removes commits from a branch. Other virtual branches are left alone: archiving and integration detection are skipped.
+  pub commit_filter: CommitFilter,
+  /// Commits read per page; after every full page the grouping so far is sent, so huge histories show up incrementally.
+  pub commit_page_size: NonZeroUsize,
+  /// Soft time budgets of branch sync and integration detection, past which the sync returns and the phase continues in the background.
+  /// `None` reads `branchdeck.branchSyncTimeBudgetMs` and `branchdeck.detectionTimeBudgetMs` from git config.
+  pub phase_time_budgets: Option<PhaseTimeBudgets>,
+  /// Archive branches that stay fully integrated for a number of consecutive syncs, optionally dropping their commits from HEAD.
+  /// `None` reads `branchdeck.autoArchiveAfterSyncs` and `branchdeck.autoArchivePruneCommits` from git config.
+  pub auto_archive: Option<AutoArchivePolicy>,
+  /// Fetch the remote with prune before the sync: always, if the last fetch is older than some minutes, or never.
+  /// `None` reads `branchdeck.autoFetch` from git config.
+  pub auto_fetch: Option<AutoFetchPolicy>,
+  /// Stash uncommitted changes of tracked files before the sync and restore them afterwards.
+  /// `None` reads `branchdeck.autoStash` from git config.
+  pub auto_stash: Option<bool>,
+  /// Scripts run before and after the sync phases.
+  /// `None` reads `branchdeck.hook.preSync`, `postGroup`, `postBranchCreate` and `postSync` from git config.
+  pub hooks: Option<SyncHooks>,
+  /// Receives the [`SyncSummary`] once the sync and the phases it continues in the background are done, e.g. to post it
+  /// to the webhook of the repository.
+  pub summary_notifier: Option<Arc<dyn SyncSummaryNotifier>>,
 }
 
 impl Default for SyncOptions {
@@ -32,8 +124,26 @@ impl Default for SyncOptions {
     Self {
       cached_issue_config: None,
       detection_strategy: branch_integration::strategy::get_detection_strategy(),
-      // Keep in sync with branch_integration::archive::ARCHIVE_RETENTION_DAYS (currently 7)
-      archive_retention_days: 7,
+      archive_retention_days: branch_integration::archive::ARCHIVE_RETENTION_DAYS,
+      integration_lookback: LookbackWindow::default(),
+      detection_budget: DetectionBudget::default(),
+      prefetch_missing_objects: None,
+      copy_user_notes: None,
+      conflict_mode: None,
+      empty_branch_policy: None,
+      empty_commit_policy: None,
+      diff_options: None,
+      archived_remote_status: None,
+      persistent_tree_cache: None,
+      sync_concurrency: None,
+      commit_filter: CommitFilter::default(),
+      commit_page_size: DEFAULT_COMMIT_PAGE_SIZE,
+      phase_time_budgets: None,
+      auto_archive: None,
+      auto_fetch: None,
+      auto_stash: None,
+      hooks: None,
+      summary_notifier: None,
     }
   }
 }
@@ -230,6 +340,10 @@ pub(crate) fn prepare_branches_for_ui(grouped_commits: &IndexMap<String, Vec<Com
         }
       },
       my_email: branch_my_email,
+      pinned: false,
+      protected: false,
+      other_authors: Vec::new(),
+      commit_order: CommitOrder::default(),
       commits: commits
         .iter()
         .rev() // Reverse to show newest commits first within branch
@@ -264,22 +378,299 @@ pub(crate) fn prepare_branches_for_ui(grouped_commits: &IndexMap<String, Vec<Com
   grouped_branches_for_ui
 }
 
+/// Report a branch that can't be synced because of unsatisfied commit dependencies
+fn report_dependency_violations<P: ProgressReporter>(progress: &P, branch_name: &str, violations: &[DependencyViolation]) {
+  let explanations: Vec<String> = violations.iter().map(DependencyViolation::explanation).collect();
+  warn!(branch_name, violations = ?explanations, "Commit dependencies are not satisfied, skipping branch");
+
+  let mut reported_commits = HashSet::new();
+  for violation in violations {
+    if reported_commits.insert(violation.commit_id.as_str()) {
+      let _ = progress.send(SyncEvent::CommitError {
+        branch_name: branch_name.to_string(),
+        commit_hash: violation.commit_id.clone(),
+        error: BranchError::Generic(violation.explanation()),
+      });
+    }
+  }
+
+  let _ = progress.send(SyncEvent::BranchStatusUpdate {
+    branch_name: branch_name.to_string(),
+    status: BranchSyncStatus::Error,
+    error: Some(BranchError::Generic(explanations.join("\n"))),
+  });
+}
+
+/// Commits matching the commit filter, grouped the same way as all commits
+pub(crate) struct MatchingCommits {
+  pub commit_ids: HashSet<String>,
+  /// Branches with at least one matching commit, rebuilt with all their commits
+  pub branch_names: Vec<String>,
+  /// Matching commits not grouped into a branch, oldest first
+  pub unassigned_commits: Vec<Commit>,
+}
+
+/// Read and group the commits matching the commit filter to pick the branches to rebuild
+pub(crate) fn find_matching_commits(git_executor: &GitCommandExecutor, repository_path: &str, baseline_branch: &str, commit_filter: &CommitFilter) -> Result<MatchingCommits> {
+  let mut grouper = load_commit_grouper(git_executor, repository_path);
+  let mut commit_ids = HashSet::new();
+  get_paged_commit_list_with_handler(git_executor, repository_path, baseline_branch, commit_filter, DEFAULT_COMMIT_PAGE_SIZE, |page| {
+    commit_ids.extend(page.iter().map(|commit| commit.id.clone()));
+    grouper.add_commits(git_executor, repository_path, page);
+    Ok(())
+  })?;
+  let (grouped_commits, unassigned_commits, _) = grouper.finish();
+  Ok(MatchingCommits {
+    commit_ids,
+    branch_names: grouped_commits.into_keys().collect(),
+    unassigned_commits,
+  })
+}
+
+/// Keep the branches with at least one commit matching the commit filter, with all their commits, and the matching unassigned commits
+fn retain_matching_branches(grouped_commits: &mut IndexMap<String, Vec<Commit>>, unassigned_commits: &mut Vec<Commit>, matching_commit_ids: &HashSet<String>) {
+  grouped_commits.retain(|_, commits| commits.iter().any(|commit| matching_commit_ids.contains(&commit.id)));
+  unassigned_commits.retain(|commit| matching_commit_ids.contains(&commit.id));
+}
+
+/// Send which archived branches are still on the remote, once archiving is done. Not fatal, only logged if it fails.
+fn send_archived_remote_status<P: ProgressReporter>(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  branch_prefix: &str,
+  progress: &P,
+  timings: &SyncTimings,
+) -> Result<()> {
+  let started = Instant::now();
+  match compute_remote_status_for_archived_branches(git_executor, repository_path, branch_prefix) {
+    Ok(branches) => progress.send(SyncEvent::ArchivedRemoteStatus { branches })?,
+    Err(e) => warn!(error = %e, "Failed to compute remote status of archived branches"),
+  }
+  timings.add_remote_status(started.elapsed());
+  Ok(())
+}
+
+/// Send the grouping of the commits read so far during a long commit scan
+fn send_grouping_progress<P: ProgressReporter>(
+  grouper: &CommitGrouper,
+  baseline_branch: &str,
+  commit_filter: &CommitFilter,
+  matching_commit_ids: Option<&HashSet<String>>,
+  progress: &P,
+) -> Result<()> {
+  let (mut grouped_commits, mut unassigned_commits, branch_emails) = grouper.snapshot();
+  if let Some(matching_commit_ids) = matching_commit_ids {
+    retain_matching_branches(&mut grouped_commits, &mut unassigned_commits, matching_commit_ids);
+  }
+  debug!(commit_count = grouper.commit_count, branches = grouped_commits.len(), "Sending partial grouping");
+  progress.send(SyncEvent::BranchesGrouped {
+    branches: prepare_branches_for_ui(&grouped_commits, &branch_emails),
+    baseline_branch: baseline_branch.to_string(),
+    commit_filter: (!commit_filter.is_empty()).then(|| commit_filter.clone()),
+  })?;
+  progress.send(SyncEvent::UnassignedCommits { commits: unassigned_commits })
+}
+
 /// Get the parent commit hash of the oldest commit
-fn get_parent_commit_hash(git_executor: &GitCommandExecutor, repository_path: &str, oldest_commit: Option<&Commit>) -> Result<String> {
-  let oldest_head_commit = oldest_commit.ok_or_else(|| anyhow::anyhow!("No oldest commit found despite having commits"))?;
+pub(crate) fn get_parent_commit_hash(git_executor: &GitCommandExecutor, repository_path: &str, oldest_commit_id: Option<&str>) -> Result<String> {
+  let oldest_head_commit_id = oldest_commit_id.ok_or_else(|| anyhow::anyhow!("No oldest commit found despite having commits"))?;
 
-  let parent_ref = format!("{}^", oldest_head_commit.id);
+  let parent_ref = format!("{oldest_head_commit_id}^");
   Ok(git_executor.execute_command(&["rev-parse", &parent_ref], repository_path)?.trim().to_string())
 }
 
 /// Core sync branches logic
 #[instrument(skip(git_executor, progress, options), fields(repository_path = %repository_path, branch_prefix = %branch_prefix, cached_issue_config = options.cached_issue_config.is_some()))]
 pub async fn sync_branches<P: ProgressReporter + Clone + 'static>(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  branch_prefix: &str,
+  progress: P,
+  mut options: SyncOptions,
+) -> Result<()> {
+  let Some(summary_notifier) = options.summary_notifier.take() else {
+    return run_sync_branches(git_executor, repository_path, branch_prefix, progress, options).await;
+  };
+
+  let collector = SyncSummaryCollector::new(progress, SyncSummary::new(repository_path, branch_prefix));
+  let result = run_sync_branches(git_executor, repository_path, branch_prefix, collector.clone(), options).await;
+  // Queued after the task finishing the sync, so the summary has the results of the phases continuing in the background
+  let error = result.as_ref().err().map(|e| format!("{e:#}"));
+  after_background_phases(repository_path, move || {
+    let result = match error {
+      Some(error) => Err(anyhow!(error)),
+      None => Ok(()),
+    };
+    summary_notifier.notify(collector.finish(&result));
+  });
+  result
+}
+
+async fn run_sync_branches<P: ProgressReporter + Clone + 'static>(
   git_executor: &GitCommandExecutor,
   repository_path: &str,
   branch_prefix: &str,
   progress: P,
   options: SyncOptions,
+) -> Result<()> {
+  // A phase of the previous sync may still be running in the background
+  wait_for_background_phases(repository_path).await;
+
+  // Held until the phases continuing in the background are done too
+  let repository_lock = lock_repository(git_executor, repository_path)?;
+
+  // Only left behind if the app crashed while a sync was moving refs, put them back before syncing again.
+  // The checked out branch is never moved back here, the sync fails until the user recovers it.
+  if let Some(journal) = roll_back_ref_journal(git_executor, repository_path, &repository_lock, false)? {
+    warn!(description = %journal.description, "Rolled back the ref updates of an interrupted sync");
+  }
+
+  let hooks = Arc::new(options.hooks.clone().unwrap_or_else(|| load_sync_hooks(git_executor, repository_path)));
+  hooks.run(&HookContext::PreSync { repository_path, branch_prefix })?;
+  let branch_hooks = Arc::new(BranchHookQueue::default());
+
+  let auto_fetch = options.auto_fetch.unwrap_or_else(|| load_auto_fetch_policy(git_executor, repository_path));
+  fetch_before_sync(git_executor, repository_path, auto_fetch, &progress)?;
+
+  let ref_prefixes = sync_ref_prefixes(git_executor, repository_path, branch_prefix);
+  let journal = begin_ref_journal(
+    git_executor,
+    repository_path,
+    &repository_lock,
+    &format!("sync {branch_prefix}"),
+    &ref_prefixes.iter().map(String::as_str).collect::<Vec<_>>(),
+  )?;
+  let timings = SyncTimings::default();
+  let conflict_counter = MergeConflictCounter::new(progress.clone());
+  let result = sync_with_auto_stash(
+    git_executor,
+    repository_path,
+    branch_prefix,
+    conflict_counter.clone(),
+    options,
+    hooks.clone(),
+    branch_hooks.clone(),
+    timings.clone(),
+  )
+  .await;
+  let report = timings.report();
+  info!(?report, "Sync finished");
+  // Conflicts of branches still syncing in the background are not counted
+  if let Err(e) = update_usage_stats(git_executor, repository_path, |stats| {
+    stats.add(UsageCounter::Sync, 1);
+    stats.add(UsageCounter::MergeConflict, conflict_counter.count());
+  }) {
+    warn!(error = %e, "Failed to record usage statistics");
+  }
+  let _ = progress.send(SyncEvent::SyncPerformanceReport { report });
+
+  // Branches that failed are reported as such, so the journal is dropped even on error; phases in the background still
+  // move refs, so the history entry and the postBranchCreate and postSync hooks wait for them too
+  after_background_phases(repository_path, {
+    let git_executor = git_executor.clone();
+    let repository_path = repository_path.to_string();
+    let branch_prefix = branch_prefix.to_string();
+    let success = result.is_ok();
+    let error = result.as_ref().err().map(|e| format!("{e:#}"));
+    move || {
+      if let Err(e) = finish_ref_journal(&git_executor, &repository_path, &journal) {
+        warn!(error = %e, "Failed to finish the ref journal of the sync");
+      }
+      // Only after a sync that applied every resolution still in use
+      if success && let Err(e) = prune_binary_resolutions(&git_executor, &repository_path, BINARY_RESOLUTION_RETENTION_DAYS) {
+        warn!(error = %e, "Failed to prune binary conflict resolutions");
+      }
+      // Not fatal: the history is only for comparing runs
+      let history_entry = SyncHistoryEntry::new(&branch_prefix, success, resolve_baseline_commit(&git_executor, &repository_path), timings.report());
+      if let Err(e) = append_sync_history(&git_executor, &repository_path, &history_entry) {
+        warn!(error = %e, "Failed to append to the sync history");
+      }
+      drop(repository_lock);
+
+      if hooks.is_empty() {
+        return;
+      }
+      let background_repository_path = repository_path.clone();
+      spawn_background_task(&background_repository_path, move || {
+        branch_hooks.run_logged(&hooks, &repository_path);
+        hooks.run_logged(&HookContext::PostSync {
+          repository_path: &repository_path,
+          branch_prefix: &branch_prefix,
+          success,
+          error,
+        });
+      });
+    }
+  });
+  result
+}
+
+/// Refs moved by a sync: the branches under the prefix (virtual and archived), the notes written for rewritten commits,
+/// the repository state and the checked out branch, rewritten when auto-archiving drops commits from HEAD.
+/// The auto-stash ref is not covered, rolling it back would delete stashed changes that were not restored.
+fn sync_ref_prefixes(git_executor: &GitCommandExecutor, repository_path: &str, branch_prefix: &str) -> Vec<String> {
+  let mut ref_prefixes = vec![
+    format!("refs/heads/{branch_prefix}/"),
+    MAPPING_NOTES_REF.to_string(),
+    branch_integration::cache::NOTES_REF.to_string(),
+    STATE_REF.to_string(),
+  ];
+  match git_executor.execute_command_with_status(&["symbolic-ref", "--quiet", "HEAD"], repository_path) {
+    Ok((head_ref, 0)) => ref_prefixes.push(head_ref.trim().to_string()),
+    Ok(_) => {}
+    Err(e) => warn!(error = %e, "Failed to resolve the checked out branch, it is not covered by the ref journal"),
+  }
+  ref_prefixes
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn sync_with_auto_stash<P: ProgressReporter + Clone + 'static>(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  branch_prefix: &str,
+  progress: P,
+  options: SyncOptions,
+  hooks: Arc<SyncHooks>,
+  branch_hooks: Arc<BranchHookQueue>,
+  timings: SyncTimings,
+) -> Result<()> {
+  let auto_stash = options.auto_stash.unwrap_or_else(|| load_auto_stash(git_executor, repository_path));
+  let stash = if auto_stash { stash_uncommitted_changes(git_executor, repository_path)? } else { None };
+  let Some(stash) = stash else {
+    return sync_virtual_branches(git_executor, repository_path, branch_prefix, progress, options, hooks, branch_hooks, timings).await;
+  };
+  progress.send(SyncEvent::UncommittedChangesStashed {
+    stash_commit: stash.stash_commit.clone(),
+    file_count: stash.file_count,
+  })?;
+
+  let result = sync_virtual_branches(git_executor, repository_path, branch_prefix, progress.clone(), options, hooks, branch_hooks, timings).await;
+  // Pruning may continue in the background, the changes are restored onto the final HEAD
+  wait_for_background_phases(repository_path).await;
+  let restore_result = restore_stashed_changes(git_executor, repository_path, &stash).and_then(|restore| {
+    let conflicting_files = match restore {
+      StashRestore::Restored => Vec::new(),
+      StashRestore::Conflicted { conflicting_files } => conflicting_files,
+    };
+    progress.send(SyncEvent::UncommittedChangesRestored {
+      stash_commit: stash.stash_commit.clone(),
+      conflicting_files,
+    })
+  });
+  // An error of the sync itself takes precedence
+  result.and(restore_result)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn sync_virtual_branches<P: ProgressReporter + Clone + 'static>(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  branch_prefix: &str,
+  progress: P,
+  options: SyncOptions,
+  hooks: Arc<SyncHooks>,
+  branch_hooks: Arc<BranchHookQueue>,
+  timings: SyncTimings,
 ) -> Result<()> {
   // Use cached issue config if available, otherwise load it
   let issue_config = if let Some(cached) = options.cached_issue_config {
@@ -296,28 +687,118 @@ pub async fn sync_branches<P: ProgressReporter + Clone + 'static>(
   // Detect the baseline branch (origin/master, origin/main, or local master/main)
   let baseline_branch = detect_baseline_branch(git_executor, repository_path, "master")?;
 
-  // Use streaming commit processing
-  let mut grouper = CommitGrouper::new();
+  // Not fatal: without the merge base commits of the baseline are grouped as well, as before
+  if let Err(e) = deepen_to_merge_base(git_executor, repository_path, &baseline_branch, &progress) {
+    warn!(error = %e, "Failed to deepen shallow clone");
+  }
 
-  get_commit_list_with_handler(git_executor, repository_path, &baseline_branch, |commit| {
-    grouper.add_commit(commit);
+  let phase_time_budgets = options.phase_time_budgets.unwrap_or_else(|| load_phase_time_budgets(git_executor, repository_path));
+  let archived_remote_status = options.archived_remote_status.unwrap_or_else(|| {
+    get_config_bool(git_executor, repository_path, ARCHIVED_REMOTE_STATUS_CONFIG_KEY)
+      .ok()
+      .flatten()
+      .unwrap_or(false)
+  });
+
+  // Use streaming commit processing
+  let grouping_started = Instant::now();
+  let mut grouper = load_commit_grouper(git_executor, repository_path);
+  let author_filter = grouper.author_filter().cloned();
+
+  // The filter selects the branches to rebuild, the branches themselves are grouped from all their commits:
+  // only the commits that may belong to them are read, not the whole history
+  let mut matching_commits = if options.commit_filter.is_empty() {
+    None
+  } else {
+    Some(find_matching_commits(git_executor, repository_path, &baseline_branch, &options.commit_filter)?)
+  };
+  let matching_commit_ids = matching_commits.as_ref().map(|matching_commits| &matching_commits.commit_ids);
+
+  let commit_page_size = options.commit_page_size;
+  let handle_page = |page: Vec<Commit>| -> Result<()> {
+    let is_full_page = page.len() == commit_page_size.get();
+    grouper.add_commits(git_executor, repository_path, page);
+    // More commits may follow, show what is grouped so far; the final events below replace it
+    if is_full_page {
+      send_grouping_progress(&grouper, &baseline_branch, &options.commit_filter, matching_commit_ids, &progress)?;
+    }
     Ok(())
+  };
+  match &matching_commits {
+    Some(matching_commits) => get_paged_branch_commit_list_with_handler(
+      git_executor,
+      repository_path,
+      &baseline_branch,
+      &matching_commits.branch_names,
+      commit_page_size,
+      handle_page,
+    )?,
+    None => get_paged_commit_list_with_handler(git_executor, repository_path, &baseline_branch, &CommitFilter::default(), commit_page_size, handle_page)?,
+  }
+
+  // Sent even if empty to clear the diagnostics of the previous sync
+  progress.send(SyncEvent::GroupingDiagnostics {
+    diagnostics: std::mem::take(&mut grouper.diagnostics),
   })?;
+  for typo in grouper.prefix_typos() {
+    warn!(branch_name = %typo.branch_name, suggested_branch_name = %typo.suggested_branch_name, "Branch name is probably misspelled");
+    progress.send(SyncEvent::PossiblePrefixTypo(typo))?;
+  }
+
+  // Branches left out by the commit filter would otherwise look inactive and get archived
+  let commit_filter = (!options.commit_filter.is_empty()).then_some(options.commit_filter);
+  let commits_filtered = commit_filter.is_some();
 
   // Check if we have any commits
+  if grouper.commit_count == 0 {
+    timings.record_grouping(grouping_started.elapsed(), 0, 0);
+  }
+  if let Some(matching_commits) = matching_commits.take_if(|matching_commits| matching_commits.branch_names.is_empty()) {
+    info!(commit_count = matching_commits.commit_ids.len(), "No branches match the commit filter");
+    progress.send(SyncEvent::UnassignedCommits {
+      commits: matching_commits.unassigned_commits,
+    })?;
+    return Ok(());
+  }
   if grouper.commit_count == 0 {
     info!(commit_count = 0, "No commits ahead of baseline, checking for integrated branches");
-    detect_integrated_branches(
-      git_executor,
+    let integration_detection_handle = tokio::spawn({
+      let git_executor = git_executor.clone();
+      let repository_path = repository_path.to_string();
+      let branch_prefix = branch_prefix.to_string();
+      let baseline_branch = baseline_branch.clone();
+      let progress = progress.clone();
+      let timings = timings.clone();
+      async move {
+        let started = Instant::now();
+        detect_integrated_branches(
+          &git_executor,
+          &repository_path,
+          &branch_prefix,
+          &baseline_branch,
+          branch_integration::detector::DetectConfig {
+            grouped_commits: &IndexMap::new(),
+            progress: &progress,
+            strategy: options.detection_strategy,
+            retention_days: options.archive_retention_days,
+            lookback: options.integration_lookback,
+            budget: options.detection_budget,
+          },
+        )
+        .await?;
+        timings.record_integration_detection(started.elapsed());
+        if archived_remote_status {
+          send_archived_remote_status(&git_executor, &repository_path, &branch_prefix, &progress, &timings)?;
+        }
+        Ok(())
+      }
+    });
+    await_phase(
+      SyncPhase::IntegrationDetection,
+      integration_detection_handle,
+      phase_time_budgets.integration_detection,
       repository_path,
-      branch_prefix,
-      &baseline_branch,
-      branch_integration::detector::DetectConfig {
-        grouped_commits: &IndexMap::new(),
-        progress: &progress,
-        strategy: options.detection_strategy,
-        retention_days: options.archive_retention_days,
-      },
+      &progress,
     )
     .await?;
 
@@ -327,16 +808,71 @@ pub async fn sync_branches<P: ProgressReporter + Clone + 'static>(
     return Ok(());
   }
 
-  // Extract oldest commit before consuming grouper
-  let oldest_commit = grouper.oldest_commit.clone();
+  // Extract oldest commit before consuming grouper, the commits of a filtered sync may start later
+  let oldest_commit_id = match &matching_commits {
+    Some(_) => get_oldest_commit_id(git_executor, repository_path, &baseline_branch)?,
+    None => grouper.oldest_commit.as_ref().map(|commit| commit.id.clone()),
+  };
 
   // group commits by prefix first to get all branch names
-  let (grouped_commits, unassigned_commits, branch_emails) = grouper.finish();
+  let (mut grouped_commits, mut unassigned_commits, branch_emails) = grouper.finish();
+  if let Some(matching_commits) = matching_commits {
+    retain_matching_branches(&mut grouped_commits, &mut unassigned_commits, &matching_commits.commit_ids);
+    // Only the commits of the matching branches were read
+    unassigned_commits = matching_commits.unassigned_commits;
+  }
 
   let total_branches = grouped_commits.len();
+  let grouped_commit_count = grouped_commits.values().map(Vec::len).sum::<usize>() + unassigned_commits.len();
+  timings.record_grouping(grouping_started.elapsed(), grouped_commit_count, total_branches);
 
   info!(total_branches, "Fetched and grouped commits");
 
+  hooks.run_logged(&HookContext::PostGroup {
+    repository_path,
+    branch_prefix,
+    baseline_branch: &baseline_branch,
+    branches: grouped_commits
+      .iter()
+      .map(|(name, commits)| GroupedBranchSummary {
+        name: name.clone(),
+        commit_count: commits.len(),
+      })
+      .collect(),
+    unassigned_commit_count: unassigned_commits.len(),
+  });
+
+  // Fast path for the common tiny case: a single virtual branch and no other branches under the prefix,
+  // so archiving, retention cleanup and integration detection have nothing to do
+  let single_branch_fast_path = total_branches == 1
+    && grouped_commits.keys().next().is_some_and(|branch_name| {
+      is_only_prefixed_branch(git_executor, repository_path, branch_prefix, branch_name).unwrap_or_else(|e| {
+        warn!(error = %e, "Failed to list prefixed branches, using full sync");
+        false
+      })
+    });
+
+  // Hard dependencies declared between commits must be satisfied by the grouping
+  let commit_dependencies = {
+    let commit_ids: HashSet<&str> = grouped_commits.values().flatten().map(|commit| commit.id.as_str()).collect();
+    load_commit_dependencies(git_executor, repository_path, &commit_ids).unwrap_or_else(|e| {
+      warn!(error = %e, "Failed to load commit dependencies, skipping validation");
+      HashMap::new()
+    })
+  };
+  let dependency_unassigned_commits = if commit_dependencies.is_empty() { Vec::new() } else { unassigned_commits.clone() };
+
+  // Pinned branches, manual order and commit order are persisted in the repository state ref
+  let repo_state = load_repo_state(git_executor, repository_path).unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to load repository state, using default branch and commit order");
+    RepoState::default()
+  });
+  for (branch_name, order) in &repo_state.commit_order {
+    if let Some(commits) = grouped_commits.get_mut(branch_name) {
+      order_commits(commits, *order, &commit_dependencies);
+    }
+  }
+
   // Always send unassigned commits (even if empty) to ensure frontend updates
   let unassigned_commits_for_ui: Vec<Commit> = if unassigned_commits.is_empty() {
     Vec::new()
@@ -344,6 +880,7 @@ pub async fn sync_branches<P: ProgressReporter + Clone + 'static>(
     unassigned_commits
       .into_iter()
       .rev() // Reverse to show newest commits first
+      .take(MAX_DETAILED_UNASSIGNED_COMMITS)
       .collect()
   };
 
@@ -354,6 +891,31 @@ pub async fn sync_branches<P: ProgressReporter + Clone + 'static>(
   // Create ordered progress reporter to ensure correct event ordering
   let ordered_progress = OrderedProgressReporter::new(progress.clone());
 
+  let prefetch_missing_objects = options
+    .prefetch_missing_objects
+    .unwrap_or_else(|| get_config_bool(git_executor, repository_path, PREFETCH_CONFIG_KEY).ok().flatten().unwrap_or(false));
+  let copy_user_notes = options
+    .copy_user_notes
+    .unwrap_or_else(|| get_config_bool(git_executor, repository_path, COPY_NOTES_CONFIG_KEY).ok().flatten().unwrap_or(false));
+  let conflict_mode = options.conflict_mode.unwrap_or_else(|| load_conflict_mode(git_executor, repository_path));
+  let empty_branch_policy = options.empty_branch_policy.unwrap_or_else(|| load_empty_branch_policy(git_executor, repository_path));
+  let empty_commit_policy = options.empty_commit_policy.unwrap_or_else(|| load_empty_commit_policy(git_executor, repository_path));
+  let diff_options = options.diff_options.unwrap_or_else(|| load_diff_options(git_executor, repository_path));
+  let ignore_case = load_ignore_case(git_executor, repository_path);
+
+  let persistent_tree_cache = options.persistent_tree_cache.unwrap_or_else(|| {
+    get_config_bool(git_executor, repository_path, PERSISTENT_TREE_CACHE_CONFIG_KEY)
+      .ok()
+      .flatten()
+      .unwrap_or(false)
+  });
+  let sync_concurrency = options.sync_concurrency.unwrap_or_else(|| load_sync_concurrency(git_executor, repository_path));
+  let auto_archive_policy = options.auto_archive.unwrap_or_else(|| load_auto_archive_policy(git_executor, repository_path));
+  // Branches whose commits were filtered out are missing from the grouping, which would reset their integrated sync counts
+  let auto_archive = auto_archive_policy.after_syncs.is_some() && !commits_filtered;
+
+  let protected_branches = load_protected_branches(git_executor, repository_path);
+
   let ui_preparation_handle = tokio::spawn({
     let grouped_commits = grouped_commits.clone();
     let branch_emails = branch_emails.clone();
@@ -361,10 +923,19 @@ pub async fn sync_branches<P: ProgressReporter + Clone + 'static>(
     let ordered_progress = ordered_progress.clone();
 
     async move {
-      let grouped_branches_for_ui = prepare_branches_for_ui(&grouped_commits, &branch_emails);
+      let mut grouped_branches_for_ui = prepare_branches_for_ui(&grouped_commits, &branch_emails);
+      for branch in &mut grouped_branches_for_ui {
+        branch.commit_order = repo_state.commit_order.get(&branch.name).copied().unwrap_or_default();
+        branch.protected = protected_branches.is_protected(&branch.name);
+        if let Some(author_filter) = &author_filter {
+          branch.other_authors = author_filter.other_authors(branch.commits.iter().map(|commit| commit.author_email.as_str()));
+        }
+      }
+      apply_branch_order(&mut grouped_branches_for_ui, &repo_state);
       ordered_progress.send(SyncEvent::BranchesGrouped {
         branches: grouped_branches_for_ui,
         baseline_branch,
+        commit_filter,
       })
     }
   });
@@ -372,27 +943,90 @@ pub async fn sync_branches<P: ProgressReporter + Clone + 'static>(
   let branch_processing_handle = tokio::spawn({
     let repository_path = repository_path.to_string();
     let branch_prefix = branch_prefix.to_string();
-    let oldest_commit = oldest_commit.clone();
+    let oldest_commit_id = oldest_commit_id.clone();
     let ordered_progress = ordered_progress.clone();
     let git_executor = git_executor.clone();
     let grouped_commits = grouped_commits.clone();
     let baseline_branch = baseline_branch.to_string();
     let branch_emails = branch_emails.clone();
+    let timings = timings.clone();
 
     async move {
+      let started = Instant::now();
       // Compute parent commit hash inside the spawned task
-      let parent_commit_hash = get_parent_commit_hash(&git_executor, &repository_path, oldest_commit.as_ref())?;
+      let parent_commit_hash = get_parent_commit_hash(&git_executor, &repository_path, oldest_commit_id.as_deref())?;
+
+      // Partial clones: missing blobs are fetched before merge-tree, optionally all at once up front
+      let partial_clone = match detect_partial_clone(&git_executor, &repository_path) {
+        Ok(info) => info.map(Arc::new),
+        Err(e) => {
+          warn!(error = %e, "Failed to detect partial clone, assuming full clone");
+          None
+        }
+      };
+      if let Some(info) = &partial_clone
+        && prefetch_missing_objects
+        && let Err(e) = prefetch_objects_for_range(&git_executor, &repository_path, info, &parent_commit_hash, "HEAD")
+      {
+        // Not fatal: objects are still fetched lazily per commit
+        warn!(error = %e, "Failed to prefetch missing objects");
+      }
+
+      // Opt-in signing of rewritten commits; a broken config must not block the sync
+      let signing = match resolve_commit_signing(&git_executor, &repository_path) {
+        Ok(signing) => signing.map(Arc::new),
+        Err(e) => {
+          warn!(error = %e, "Failed to resolve commit signing settings, commits will not be signed");
+          None
+        }
+      };
+      let trailer_templates: Arc<[String]> = load_commit_trailer_templates(&git_executor, &repository_path)
+        .unwrap_or_else(|e| {
+          warn!(error = %e, "Failed to load commit trailer templates, no trailers will be added");
+          Vec::new()
+        })
+        .into();
+
+      // Branches violating declared commit dependencies are reported as failed instead of being synced
+      let mut dependency_violations: HashMap<String, Vec<DependencyViolation>> = HashMap::new();
+      for violation in validate_commit_dependencies(&grouped_commits, &dependency_unassigned_commits, &commit_dependencies, |commit_id| {
+        matches!(
+          git_executor.execute_command_with_status(&["merge-base", "--is-ancestor", commit_id, &parent_commit_hash], &repository_path),
+          Ok((_, 0))
+        )
+      }) {
+        dependency_violations.entry(violation.branch_name.clone()).or_default().push(violation);
+      }
 
       // Create git notes mutex inside the spawned task
       let git_notes_mutex = Arc::new(Mutex::new(()));
 
-      // Create tree ID cache inside the spawned task
-      let tree_id_cache = TreeIdCache::new();
-
-      // Process branches in parallel using JoinSet
-      let mut set = JoinSet::new();
+      // Create tree ID cache inside the spawned task, optionally seeded from previous syncs
+      let tree_id_cache = if persistent_tree_cache {
+        TreeIdCache::load_persistent(&git_executor, &repository_path)
+      } else {
+        TreeIdCache::new()
+      };
+
+      // Shared between branches so a conflict in one can stop the others (`ConflictMode::AbortSync`)
+      let sync_aborted = Arc::new(AtomicBool::new(false));
+      let integrated_branches = Arc::new(Mutex::new(HashSet::new()));
+      let branch_commit_ids: IndexMap<String, Vec<String>> = if auto_archive {
+        grouped_commits
+          .iter()
+          .map(|(branch_name, commits)| (branch_name.clone(), commits.iter().map(|commit| commit.id.clone()).collect()))
+          .collect()
+      } else {
+        IndexMap::new()
+      };
 
+      let mut branches = Vec::with_capacity(grouped_commits.len());
       for (current_branch_idx, (branch_name, commits)) in grouped_commits.into_iter().enumerate() {
+        if let Some(violations) = dependency_violations.remove(&branch_name) {
+          report_dependency_violations(&ordered_progress, &branch_name, &violations);
+          continue;
+        }
+
         // Use pre-computed author email with O(1) HashMap lookup
         let branch_my_email = branch_emails.get(&branch_name).cloned().flatten();
 
@@ -400,6 +1034,7 @@ pub async fn sync_branches<P: ProgressReporter + Clone + 'static>(
           repository_path: repository_path.clone(),
           branch_prefix: branch_prefix.clone(),
           branch_name,
+          target_branch: None,
           commits,
           parent_commit_hash: parent_commit_hash.clone(),
           current_branch_idx,
@@ -410,32 +1045,52 @@ pub async fn sync_branches<P: ProgressReporter + Clone + 'static>(
           git_notes_mutex: git_notes_mutex.clone(),
           my_email: branch_my_email,
           baseline_branch: baseline_branch.clone(),
+          partial_clone: partial_clone.clone(),
+          signing: signing.clone(),
+          trailer_templates: trailer_templates.clone(),
+          branch_hooks: branch_hooks.clone(),
+          copy_user_notes,
+          conflict_mode,
+          empty_branch_policy,
+          empty_commit_policy,
+          diff_options,
+          ignore_case,
+          sync_aborted: sync_aborted.clone(),
+          integrated_branches: integrated_branches.clone(),
+          timings: timings.clone(),
         };
 
-        // Use spawn_blocking since process_single_branch is a sync function doing blocking I/O
-        set.spawn_blocking(move || process_single_branch(params));
+        branches.push(params);
+      }
+
+      // Branches are processed in parallel, ordered progress keeps their events after BranchesGrouped
+      let failed_count = process_branches(branches, sync_concurrency).await;
+
+      // Not fatal: the next sync just resolves the tree IDs again
+      if let Err(e) = tree_id_cache.save() {
+        warn!(error = %e, "Failed to persist tree ID cache");
       }
 
-      // Wait for all branches to complete
-      let mut has_error = false;
-      while let Some(result) = set.join_next().await {
-        match result {
-          Ok(Ok(())) => {
-            // Branch processed successfully
-          }
-          Ok(Err(e)) => {
-            // Error status has already been sent by process_single_branch
-            error!(error = ?e, "Branch processing failed");
-            has_error = true;
-          }
-          Err(e) => {
-            error!(error = %e, "JoinSet spawn_blocking error during branch processing");
-            has_error = true;
-          }
+      // Not fatal: the branches are synced, auto-archiving is retried on the next sync
+      if auto_archive && !sync_aborted.load(Ordering::Acquire) {
+        let integrated_branches = std::mem::take(&mut *integrated_branches.lock().unwrap());
+        if let Err(e) = auto_archive_integrated_branches(AutoArchiveParams {
+          git_executor: &git_executor,
+          repository_path: &repository_path,
+          branch_prefix: &branch_prefix,
+          policy: auto_archive_policy,
+          branch_commit_ids: &branch_commit_ids,
+          integrated_branches: &integrated_branches,
+          progress: &ordered_progress,
+        }) {
+          warn!(error = %e, "Failed to auto-archive integrated branches");
         }
       }
 
-      if has_error {
+      timings.record_branch_sync(started.elapsed());
+      if sync_aborted.load(Ordering::Acquire) {
+        Err(anyhow!("Sync aborted because of a merge conflict"))
+      } else if failed_count > 0 {
         Err(anyhow!("One or more branch processing tasks failed"))
       } else {
         Ok(())
@@ -450,9 +1105,28 @@ pub async fn sync_branches<P: ProgressReporter + Clone + 'static>(
   let branch_prefix_str = branch_prefix.to_string();
   let baseline_branch_str = baseline_branch.to_string();
   let progress_clone = progress.clone();
+  let timings_clone = timings.clone();
 
   // Spawn integration detection task - runs concurrently with branch processing
   let integration_detection_handle = tokio::spawn(async move {
+    if commits_filtered {
+      debug!("Commits are filtered, skipping archiving and integration detection");
+      // Nothing was archived, the archived branches are the ones of the previous sync
+      if archived_remote_status {
+        send_archived_remote_status(&git_executor_clone, &repository_path_str, &branch_prefix_str, &progress_clone, &timings_clone)?;
+      }
+      return Ok(());
+    }
+    if single_branch_fast_path {
+      debug!("Single virtual branch and nothing archived, skipping integration detection");
+      // Still clear archived branches shown after a previous sync, none of them is left on the remote either
+      if archived_remote_status {
+        progress_clone.send(SyncEvent::ArchivedRemoteStatus { branches: Vec::new() })?;
+      }
+      return progress_clone.send(SyncEvent::ArchivedBranchesFound { branch_names: Vec::new() });
+    }
+
+    let detection_started = Instant::now();
     detect_integrated_branches(
       &git_executor_clone,
       &repository_path_str,
@@ -463,17 +1137,36 @@ pub async fn sync_branches<P: ProgressReporter + Clone + 'static>(
         progress: &progress_clone,
         strategy: options.detection_strategy,
         retention_days: options.archive_retention_days,
+        lookback: options.integration_lookback,
+        budget: options.detection_budget,
       },
     )
-    .await
+    .await?;
+    timings_clone.record_integration_detection(detection_started.elapsed());
+
+    // Archiving has already happened, so the archived refs are final
+    if archived_remote_status {
+      send_archived_remote_status(&git_executor_clone, &repository_path_str, &branch_prefix_str, &progress_clone, &timings_clone)?;
+    }
+    Ok(())
   });
 
-  // Wait for all three tasks to complete using try_join
-  let (branch_result, ui_result, integration_result) = tokio::try_join!(branch_processing_handle, ui_preparation_handle, integration_detection_handle)?;
+  // Wait for all three tasks, phases exceeding their time budget continue in the background
+  let (branch_result, ui_result, integration_result) = tokio::join!(
+    await_phase(SyncPhase::BranchSync, branch_processing_handle, phase_time_budgets.branch_sync, repository_path, &progress),
+    ui_preparation_handle,
+    await_phase(
+      SyncPhase::IntegrationDetection,
+      integration_detection_handle,
+      phase_time_budgets.integration_detection,
+      repository_path,
+      &progress
+    ),
+  );
 
   // Check results
   branch_result?;
-  ui_result?;
+  ui_result??;
   integration_result?;
 
   Ok(())
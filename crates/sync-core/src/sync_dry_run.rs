@@ -0,0 +1,179 @@
+use crate::commit_grouper::load_commit_grouper;
+use crate::commit_order::order_commits;
+use crate::repo_state::{RepoState, load_repo_state};
+use crate::sync::{detect_baseline_branch, find_matching_commits, get_parent_commit_hash};
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::cache::TreeIdCache;
+use git_ops::cherry_pick::perform_fast_cherry_pick_with_context;
+use git_ops::commit_dependencies::load_commit_dependencies;
+use git_ops::commit_list::{Commit, CommitFilter, DEFAULT_COMMIT_PAGE_SIZE, get_oldest_commit_id, get_paged_branch_commit_list_with_handler, get_paged_commit_list_with_handler};
+use git_ops::commit_utils::commit_tree;
+use git_ops::copy_commit::CopyCommitError;
+use git_ops::diff_options::{DiffOptions, load_diff_options};
+use git_ops::model::{BranchError, MergeConflictInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info, instrument, warn};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SyncDryRunParams {
+  pub repository_path: String,
+  /// Only predict the branches with a commit matching the filter (date, count, paths), see `SyncOptions::commit_filter`
+  pub commit_filter: Option<CommitFilter>,
+}
+
+/// Predicted outcome of syncing one virtual branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct BranchSyncPrediction {
+  pub branch_name: String,
+  pub commit_count: u32,
+  /// First commit that would conflict; commits after it are not checked as they would be applied on top of it
+  pub conflict: Option<MergeConflictInfo>,
+  /// Failure other than a conflict, e.g. a missing object
+  pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SyncDryRunResult {
+  pub baseline_branch: String,
+  /// Branches in grouping order
+  pub branches: Vec<BranchSyncPrediction>,
+}
+
+/// Group commits and predict merge-tree conflicts of every virtual branch without creating or moving any refs.
+/// Commits are replayed as dangling commit objects, so a later sync reuses their trees but nothing else changes.
+#[instrument(skip(git_executor, params), fields(repository_path = %params.repository_path))]
+pub fn sync_branches_dry_run_core(git_executor: &GitCommandExecutor, params: SyncDryRunParams) -> Result<SyncDryRunResult> {
+  let repository_path = params.repository_path.as_str();
+  let baseline_branch = detect_baseline_branch(git_executor, repository_path, "master")?;
+
+  // As in sync, the filter selects branches and never removes commits from them, only their commits are read
+  let matching_commits = match params.commit_filter.filter(|filter| !filter.is_empty()) {
+    Some(filter) => Some(find_matching_commits(git_executor, repository_path, &baseline_branch, &filter)?),
+    None => None,
+  };
+  let mut grouper = load_commit_grouper(git_executor, repository_path);
+  let handle_page = |page: Vec<Commit>| -> Result<()> {
+    grouper.add_commits(git_executor, repository_path, page);
+    Ok(())
+  };
+  match &matching_commits {
+    Some(matching_commits) => get_paged_branch_commit_list_with_handler(
+      git_executor,
+      repository_path,
+      &baseline_branch,
+      &matching_commits.branch_names,
+      DEFAULT_COMMIT_PAGE_SIZE,
+      handle_page,
+    )?,
+    None => get_paged_commit_list_with_handler(
+      git_executor,
+      repository_path,
+      &baseline_branch,
+      &CommitFilter::default(),
+      DEFAULT_COMMIT_PAGE_SIZE,
+      handle_page,
+    )?,
+  }
+
+  if grouper.commit_count == 0 {
+    return Ok(SyncDryRunResult {
+      baseline_branch,
+      branches: Vec::new(),
+    });
+  }
+
+  // The commits of the matching branches may start later than the first commit
+  let oldest_commit_id = match &matching_commits {
+    Some(_) => get_oldest_commit_id(git_executor, repository_path, &baseline_branch)?,
+    None => grouper.oldest_commit.as_ref().map(|commit| commit.id.clone()),
+  };
+  let (mut grouped_commits, _, _) = grouper.finish();
+  if let Some(matching_commits) = &matching_commits {
+    grouped_commits.retain(|_, commits| commits.iter().any(|commit| matching_commits.commit_ids.contains(&commit.id)));
+  }
+  let parent_commit_hash = get_parent_commit_hash(git_executor, repository_path, oldest_commit_id.as_deref())?;
+
+  // Same commit order as the real sync
+  let repo_state = load_repo_state(git_executor, repository_path).unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to load repository state, using default commit order");
+    RepoState::default()
+  });
+  if !repo_state.commit_order.is_empty() {
+    let commit_ids: HashSet<&str> = grouped_commits.values().flatten().map(|commit| commit.id.as_str()).collect();
+    let commit_dependencies = load_commit_dependencies(git_executor, repository_path, &commit_ids).unwrap_or_else(|e| {
+      warn!(error = %e, "Failed to load commit dependencies");
+      HashMap::new()
+    });
+    for (branch_name, order) in &repo_state.commit_order {
+      if let Some(commits) = grouped_commits.get_mut(branch_name) {
+        order_commits(commits, *order, &commit_dependencies);
+      }
+    }
+  }
+
+  let diff_options = load_diff_options(git_executor, repository_path);
+  let tree_id_cache = TreeIdCache::new();
+  let branches: Vec<BranchSyncPrediction> = grouped_commits
+    .into_iter()
+    .map(|(branch_name, commits)| predict_branch_sync(git_executor, repository_path, &parent_commit_hash, branch_name, &commits, &tree_id_cache, &diff_options))
+    .collect();
+
+  info!(
+    branch_count = branches.len(),
+    conflict_count = branches.iter().filter(|branch| branch.conflict.is_some()).count(),
+    "Predicted sync conflicts"
+  );
+  Ok(SyncDryRunResult { baseline_branch, branches })
+}
+
+/// Predict the outcome of syncing a branch made of `commits`
+pub(crate) fn predict_branch_sync(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  parent_commit_hash: &str,
+  branch_name: String,
+  commits: &[Commit],
+  tree_id_cache: &TreeIdCache,
+  diff_options: &DiffOptions,
+) -> BranchSyncPrediction {
+  let (conflict, error) = match predict_branch(git_executor, repository_path, parent_commit_hash, commits, tree_id_cache, diff_options) {
+    Ok(()) => (None, None),
+    Err(CopyCommitError::BranchError(BranchError::MergeConflict(info))) => (Some(*info), None),
+    Err(CopyCommitError::BranchError(BranchError::Generic(message))) => (None, Some(message)),
+    Err(CopyCommitError::BranchError(BranchError::CaseCollision(info))) => (None, Some(info.explanation())),
+    Err(CopyCommitError::Other(e)) => (None, Some(e.to_string())),
+  };
+  BranchSyncPrediction {
+    branch_name,
+    commit_count: commits.len() as u32,
+    conflict,
+    error,
+  }
+}
+
+/// Replay the commits of a branch on top of the parent commit, stopping at the first conflict
+fn predict_branch(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  parent_commit_hash: &str,
+  commits: &[Commit],
+  tree_id_cache: &TreeIdCache,
+  diff_options: &DiffOptions,
+) -> Result<(), CopyCommitError> {
+  let mut current_parent = parent_commit_hash.to_string();
+  for commit in commits {
+    let tree_id = perform_fast_cherry_pick_with_context(git_executor, repository_path, &commit.id, &current_parent, None, tree_id_cache, diff_options)?;
+    // The next commit is merged onto this one, merge-tree needs a commit for that
+    current_parent = commit_tree(git_executor, repository_path, &tree_id, Some(&current_parent), &commit.message, &[], None)?;
+    debug!(commit_id = %commit.id, predicted_commit_id = %current_parent, "Commit applies cleanly");
+  }
+  Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::path_scope::PATH_SCOPE_CONFIG_KEY;
+use crate::sync_dry_run::{SyncDryRunParams, sync_branches_dry_run_core};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn dry_run(test_repo: &TestRepo) -> crate::sync_dry_run::SyncDryRunResult {
+  let params = SyncDryRunParams {
+    repository_path: test_repo.path().to_str().unwrap().to_string(),
+    commit_filter: None,
+  };
+  sync_branches_dry_run_core(&GitCommandExecutor::new(), params).unwrap()
+}
+
+fn all_refs(test_repo: &TestRepo) -> String {
+  test_repo
+    .git_executor()
+    .execute_command(&["for-each-ref", "--format=%(refname) %(objectname)"], test_repo.path().to_str().unwrap())
+    .unwrap()
+}
+
+#[test]
+fn test_dry_run_predicts_conflicts_without_touching_refs() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "config.txt", "timeout=30\n");
+  // Unassigned commit the conflicting branch depends on
+  test_repo.create_commit("Lower timeout", "config.txt", "timeout=10\n");
+  test_repo.create_commit("(timeout) Raise timeout", "config.txt", "timeout=60\n");
+  test_repo.create_commit("(docs) Add readme", "README.md", "# Docs\n");
+  test_repo.create_commit("(docs) Extend readme", "README.md", "# Docs\n\nMore\n");
+
+  let refs_before = all_refs(&test_repo);
+  let result = dry_run(&test_repo);
+  assert_eq!(all_refs(&test_repo), refs_before);
+
+  assert_eq!(result.baseline_branch, "master");
+  let branches: Vec<(&str, u32, bool)> = result
+    .branches
+    .iter()
+    .map(|branch| (branch.branch_name.as_str(), branch.commit_count, branch.conflict.is_some()))
+    .collect();
+  assert_eq!(branches, vec![("timeout", 1, true), ("docs", 2, false)]);
+  assert!(result.branches.iter().all(|branch| branch.error.is_none()));
+
+  let conflict = result.branches[0].conflict.as_ref().unwrap();
+  assert_eq!(conflict.commit_message, "(timeout) Raise timeout");
+  let files: Vec<&str> = conflict.conflicting_files.iter().map(|file| file.file.as_str()).collect();
+  assert_eq!(files, vec!["config.txt"]);
+  assert!(!test_repo.branch_exists("test/virtual/timeout"));
+  assert!(!test_repo.branch_exists("test/virtual/docs"));
+}
+
+#[test]
+fn test_dry_run_applies_path_scopes() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+  test_repo.create_commit("(docs) Add guide", "docs/guide.md", "# Guide\n");
+  test_repo.create_commit("(docs) Bump version", "version.txt", "2\n");
+  test_repo.set_config(PATH_SCOPE_CONFIG_KEY, "docs docs/").unwrap();
+
+  // Like sync, the commit touching nothing in the scope is not grouped into the branch
+  let result = dry_run(&test_repo);
+  let branches: Vec<(&str, u32)> = result.branches.iter().map(|branch| (branch.branch_name.as_str(), branch.commit_count)).collect();
+  assert_eq!(branches, vec![("docs", 1)]);
+}
+
+#[test]
+fn test_dry_run_without_commits() {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test\n");
+
+  let result = dry_run(&test_repo);
+  assert_eq!(result.baseline_branch, "master");
+  assert!(result.branches.is_empty());
+}
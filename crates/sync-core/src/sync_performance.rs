@@ -0,0 +1,141 @@
+//! Timing of the phases of a sync run. The resulting [`SyncPerformanceReport`] is sent as the last event of the run
+//! and appended to the sync history under `.git/branch-deck/sync-history.jsonl`, so slowdowns on big repositories
+//! can be compared across runs.
+
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::cache::resolve_common_git_dir;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sync_types::SyncPerformanceReport;
+use tracing::{debug, instrument};
+
+/// Number of runs kept in the sync history, older ones are dropped
+pub const MAX_SYNC_HISTORY_ENTRIES: usize = 200;
+
+/// Phase timings of a running sync, shared by its tasks
+#[derive(Debug, Clone)]
+pub struct SyncTimings {
+  started: Instant,
+  report: Arc<Mutex<SyncPerformanceReport>>,
+  // Summed per commit and per branch from parallel workers, so kept outside the mutex
+  cherry_pick_micros: Arc<AtomicU64>,
+  remote_status_micros: Arc<AtomicU64>,
+}
+
+impl Default for SyncTimings {
+  fn default() -> Self {
+    Self {
+      started: Instant::now(),
+      report: Arc::default(),
+      cherry_pick_micros: Arc::default(),
+      remote_status_micros: Arc::default(),
+    }
+  }
+}
+
+impl SyncTimings {
+  pub fn record_grouping(&self, duration: Duration, commit_count: usize, branch_count: usize) {
+    let mut report = self.report.lock().unwrap();
+    report.grouping_ms = duration.as_millis() as u64;
+    report.commit_count = commit_count as u32;
+    report.branch_count = branch_count as u32;
+  }
+
+  pub fn record_branch_sync(&self, duration: Duration) {
+    self.report.lock().unwrap().branch_sync_ms = Some(duration.as_millis() as u64);
+  }
+
+  pub fn record_integration_detection(&self, duration: Duration) {
+    self.report.lock().unwrap().integration_detection_ms = Some(duration.as_millis() as u64);
+  }
+
+  pub fn add_cherry_pick(&self, duration: Duration) {
+    self.cherry_pick_micros.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+  }
+
+  pub fn add_remote_status(&self, duration: Duration) {
+    self.remote_status_micros.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+  }
+
+  /// Report of the run so far. Phases still running in the background are left out.
+  pub fn report(&self) -> SyncPerformanceReport {
+    let mut report = self.report.lock().unwrap().clone();
+    report.total_ms = self.started.elapsed().as_millis() as u64;
+    report.cherry_pick_ms = self.cherry_pick_micros.load(Ordering::Relaxed) / 1000;
+    report.remote_status_ms = self.remote_status_micros.load(Ordering::Relaxed) / 1000;
+    report
+  }
+}
+
+/// One sync run in the sync history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SyncHistoryEntry {
+  /// Unix timestamp of the end of the run
+  pub timestamp: u64,
+  pub branch_prefix: String,
+  pub success: bool,
+  /// Commit of the baseline branch at the end of the run, `None` if it couldn't be resolved or for runs of older versions
+  #[serde(default)]
+  pub baseline_commit: Option<String>,
+  pub report: SyncPerformanceReport,
+}
+
+impl SyncHistoryEntry {
+  pub fn new(branch_prefix: &str, success: bool, baseline_commit: Option<String>, report: SyncPerformanceReport) -> Self {
+    Self {
+      timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or_default(),
+      branch_prefix: branch_prefix.to_string(),
+      success,
+      baseline_commit,
+      report,
+    }
+  }
+}
+
+fn sync_history_path(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<PathBuf> {
+  Ok(resolve_common_git_dir(git_executor, repository_path)?.join("branch-deck").join("sync-history.jsonl"))
+}
+
+/// Append the run to the sync history, dropping the oldest runs past [`MAX_SYNC_HISTORY_ENTRIES`]
+#[instrument(skip(git_executor, entry))]
+pub fn append_sync_history(git_executor: &GitCommandExecutor, repository_path: &str, entry: &SyncHistoryEntry) -> Result<()> {
+  let path = sync_history_path(git_executor, repository_path)?;
+  let mut lines: Vec<String> = std::fs::read_to_string(&path)
+    .map(|content| content.lines().map(str::to_string).collect())
+    .unwrap_or_default();
+  lines.push(serde_json::to_string(entry)?);
+  let skip = lines.len().saturating_sub(MAX_SYNC_HISTORY_ENTRIES);
+
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  // Written to a temporary file first, so an interrupted write doesn't lose the history
+  let temp_path = path.with_extension("jsonl.tmp");
+  let mut file = std::fs::File::create(&temp_path)?;
+  for line in &lines[skip..] {
+    writeln!(file, "{line}")?;
+  }
+  file.sync_all()?;
+  std::fs::rename(&temp_path, &path)?;
+  debug!(entries = lines.len() - skip, "Sync history updated");
+  Ok(())
+}
+
+/// Runs of the sync history, oldest first; unreadable lines (e.g. written by a newer version) are skipped
+#[instrument(skip(git_executor))]
+pub fn load_sync_history(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<Vec<SyncHistoryEntry>> {
+  let path = sync_history_path(git_executor, repository_path)?;
+  let content = match std::fs::read_to_string(&path) {
+    Ok(content) => content,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(e) => return Err(e.into()),
+  };
+  Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+}
@@ -0,0 +1,55 @@
+use crate::sync::{SyncOptions, sync_branches};
+use crate::sync_performance::{MAX_SYNC_HISTORY_ENTRIES, SyncHistoryEntry, append_sync_history, load_sync_history};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use sync_test_utils::TestReporter;
+use sync_types::{SyncEvent, SyncPerformanceReport};
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+#[test(tokio::test)]
+async fn test_sync_reports_performance() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+  test_repo.create_commit("(fix) Fix bug", "fix.txt", "fix");
+  let repository_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+
+  let progress = TestReporter::new();
+  sync_branches(&git_executor, repository_path, "test", progress.clone(), SyncOptions::default()).await?;
+
+  // The report is the last event of the run
+  let Some(SyncEvent::SyncPerformanceReport { report }) = progress.get_events().pop() else {
+    panic!("Expected a performance report as the last event");
+  };
+  assert_eq!(report.branch_count, 2);
+  assert!(report.commit_count >= 2, "{report:?}");
+  assert!(report.branch_sync_ms.is_some());
+  assert!(report.total_ms >= report.grouping_ms);
+
+  let history = load_sync_history(&git_executor, repository_path)?;
+  assert_eq!(history.len(), 1);
+  assert!(history[0].success);
+  assert_eq!(history[0].branch_prefix, "test");
+  assert_eq!(history[0].report, report);
+  Ok(())
+}
+
+#[test]
+fn test_sync_history_keeps_latest_runs() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repository_path = test_repo.path().to_str().unwrap();
+  assert_eq!(load_sync_history(&git_executor, repository_path).unwrap(), vec![]);
+
+  for total_ms in 0..(MAX_SYNC_HISTORY_ENTRIES as u64 + 5) {
+    let report = SyncPerformanceReport { total_ms, ..Default::default() };
+    append_sync_history(&git_executor, repository_path, &SyncHistoryEntry::new("test", true, None, report)).unwrap();
+  }
+
+  let history = load_sync_history(&git_executor, repository_path).unwrap();
+  assert_eq!(history.len(), MAX_SYNC_HISTORY_ENTRIES);
+  assert_eq!(history[0].report.total_ms, 5);
+  assert_eq!(history.last().unwrap().report.total_ms, MAX_SYNC_HISTORY_ENTRIES as u64 + 4);
+}
@@ -387,6 +387,25 @@ fn test_prefix_stripping_in_sync_branches() {
   // Test validates that prefixes are stripped from copied commits during sync
 }
 
+#[test(tokio::test)]
+async fn test_sync_adds_configured_trailers() -> anyhow::Result<()> {
+  use crate::sync::sync_branches_core;
+  use git_ops::commit_utils::COMMIT_TRAILER_CONFIG_KEY;
+  use sync_test_utils::TestReporter;
+
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(feature-x) Add feature\n\nCo-authored-by: Jane <jane@example.com>", "feature.txt", "feature");
+  test_repo.set_config(COMMIT_TRAILER_CONFIG_KEY, "Branch-Deck-Branch: {branch}").unwrap();
+
+  sync_branches_core(&GitCommandExecutor::new(), test_repo.path().to_str().unwrap(), "test", TestReporter::new()).await?;
+
+  // Existing trailers are kept, configured ones are appended to the same block
+  let message = test_repo.log(&["-1", "--pretty=format:%B", "test/virtual/feature-x"]).expect("git log should succeed");
+  assert_eq!(message.trim(), "Add feature\n\nCo-authored-by: Jane <jane@example.com>\nBranch-Deck-Branch: feature-x");
+  Ok(())
+}
+
 // Database-based commit reuse tests
 
 use sync_test_utils::TestReporter;
@@ -470,6 +489,619 @@ async fn test_commit_reuse_via_git_notes() -> anyhow::Result<()> {
   Ok(())
 }
 
+#[tokio::test]
+async fn test_already_applied_commit_is_skipped() -> anyhow::Result<()> {
+  use crate::sync::sync_branches_core;
+  use git_ops::model::CommitSyncStatus;
+  use sync_types::SyncEvent;
+
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+
+  let initial_id = test_repo.create_commit("Initial commit", "config.txt", "timeout=10\n");
+  test_repo.create_branch_at("origin/main", &initial_id).unwrap();
+
+  // The second feature commit re-applies a change that is already on the branch (reverted in between by an unassigned commit)
+  let first_id = test_repo.create_commit("(feature) Increase timeout", "config.txt", "timeout=30\n");
+  test_repo.create_commit("Revert timeout", "config.txt", "timeout=10\n");
+  let reapplied_id = test_repo.create_commit("(feature) Increase timeout again", "config.txt", "timeout=30\n");
+  let last_id = test_repo.create_commit("(feature) Add retries", "retries.txt", "retries=3\n");
+
+  let progress = TestReporter::new();
+  sync_branches_core(&git_executor, test_repo.path().to_str().unwrap(), "test", progress.clone()).await?;
+
+  let statuses: Vec<(String, CommitSyncStatus)> = progress
+    .get_events()
+    .into_iter()
+    .filter_map(|event| match event {
+      SyncEvent::CommitSynced { commit_hash, status, .. } => Some((commit_hash, status)),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(
+    statuses,
+    vec![
+      (first_id, CommitSyncStatus::Created),
+      (reapplied_id, CommitSyncStatus::AlreadyApplied),
+      (last_id, CommitSyncStatus::Created),
+    ]
+  );
+
+  // No empty commit on the virtual branch
+  let subjects = test_repo
+    .log(&["--reverse", "--pretty=format:%s", "origin/main..test/virtual/feature"])
+    .expect("git log should succeed");
+  assert_eq!(subjects.lines().collect::<Vec<_>>(), vec!["Increase timeout", "Add retries"]);
+
+  Ok(())
+}
+
+/// Sync with the given empty commit policy, returns the statuses of the synced commits and the subjects of the virtual branch
+async fn sync_with_empty_commit_policy(
+  test_repo: &TestRepo,
+  policy: Option<git_ops::empty_commit_policy::EmptyCommitPolicy>,
+) -> anyhow::Result<(Vec<(String, git_ops::model::CommitSyncStatus)>, Vec<String>)> {
+  use crate::sync::{SyncOptions, sync_branches};
+  use sync_types::SyncEvent;
+
+  let progress = TestReporter::new();
+  sync_branches(
+    &GitCommandExecutor::new(),
+    test_repo.path().to_str().unwrap(),
+    "test",
+    progress.clone(),
+    SyncOptions {
+      empty_commit_policy: policy,
+      ..Default::default()
+    },
+  )
+  .await?;
+
+  let statuses = progress
+    .get_events()
+    .into_iter()
+    .filter_map(|event| match event {
+      SyncEvent::CommitSynced { commit_hash, status, .. } => Some((commit_hash, status)),
+      _ => None,
+    })
+    .collect();
+  let subjects = test_repo
+    .log(&["--reverse", "--pretty=format:%s", "origin/main..test/virtual/feature"])
+    .expect("git log should succeed");
+  Ok((statuses, subjects.lines().map(str::to_string).collect()))
+}
+
+/// The second `feature` commit re-applies a change of the first one, reverted in between by an unassigned commit.
+/// Returns the repository and the IDs of both `feature` commits.
+fn create_reapplied_commit_repo() -> (TestRepo, String, String) {
+  let test_repo = TestRepo::new();
+  let initial_id = test_repo.create_commit("Initial commit", "config.txt", "timeout=10\n");
+  test_repo.create_branch_at("origin/main", &initial_id).unwrap();
+  let first_id = test_repo.create_commit("(feature) Increase timeout", "config.txt", "timeout=30\n");
+  test_repo.create_commit("Revert timeout", "config.txt", "timeout=10\n");
+  let reapplied_id = test_repo.create_commit("(feature) Increase timeout again", "config.txt", "timeout=30\n");
+  (test_repo, first_id, reapplied_id)
+}
+
+#[test(tokio::test)]
+async fn test_empty_commit_policy_keep_empty() -> anyhow::Result<()> {
+  use git_ops::empty_commit_policy::EmptyCommitPolicy;
+  use git_ops::model::CommitSyncStatus;
+
+  let (test_repo, first_id, reapplied_id) = create_reapplied_commit_repo();
+  let (statuses, subjects) = sync_with_empty_commit_policy(&test_repo, Some(EmptyCommitPolicy::KeepEmpty)).await?;
+  assert_eq!(statuses, vec![(first_id, CommitSyncStatus::Created), (reapplied_id, CommitSyncStatus::KeptEmpty)]);
+  assert_eq!(subjects, vec!["Increase timeout", "Increase timeout again"]);
+
+  // The kept commit changes nothing
+  let changed_files = test_repo
+    .log(&["-1", "--pretty=format:", "--name-only", "test/virtual/feature"])
+    .expect("git log should succeed");
+  assert_eq!(changed_files.trim(), "");
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_empty_commit_policy_mark_integrated_from_config() -> anyhow::Result<()> {
+  use git_ops::empty_commit_policy::EMPTY_COMMIT_POLICY_CONFIG_KEY;
+  use git_ops::model::CommitSyncStatus;
+
+  let (test_repo, first_id, reapplied_id) = create_reapplied_commit_repo();
+  test_repo.set_config(EMPTY_COMMIT_POLICY_CONFIG_KEY, "markIntegrated").unwrap();
+  let (statuses, subjects) = sync_with_empty_commit_policy(&test_repo, None).await?;
+  assert_eq!(statuses, vec![(first_id, CommitSyncStatus::Created), (reapplied_id, CommitSyncStatus::Integrated)]);
+  assert_eq!(subjects, vec!["Increase timeout"]);
+  Ok(())
+}
+
+/// The fork point already has the change of the only `feature` commit, reverted in between by an unassigned commit
+fn create_integrated_group_repo() -> TestRepo {
+  let test_repo = TestRepo::new();
+  let initial_id = test_repo.create_commit("Initial commit", "config.txt", "timeout=30\n");
+  test_repo.create_branch_at("origin/main", &initial_id).unwrap();
+  test_repo.create_commit("Lower timeout", "config.txt", "timeout=10\n");
+  test_repo.create_commit("(feature) Restore timeout", "config.txt", "timeout=30\n");
+  test_repo
+}
+
+fn branch_statuses(events: &[sync_types::SyncEvent]) -> Vec<(String, git_ops::model::BranchSyncStatus)> {
+  events
+    .iter()
+    .filter_map(|event| match event {
+      sync_types::SyncEvent::BranchStatusUpdate { branch_name, status, .. } => Some((branch_name.clone(), status.clone())),
+      _ => None,
+    })
+    .collect()
+}
+
+#[test(tokio::test)]
+async fn test_empty_branch_policy_skip() -> anyhow::Result<()> {
+  use crate::empty_branch_policy::EmptyBranchPolicy;
+  use crate::sync::{SyncOptions, sync_branches};
+  use git_ops::model::BranchSyncStatus;
+
+  let test_repo = create_integrated_group_repo();
+  let progress = TestReporter::new();
+  sync_branches(
+    &GitCommandExecutor::new(),
+    test_repo.path().to_str().unwrap(),
+    "test",
+    progress.clone(),
+    SyncOptions {
+      empty_branch_policy: Some(EmptyBranchPolicy::Skip),
+      ..Default::default()
+    },
+  )
+  .await?;
+
+  assert_eq!(branch_statuses(&progress.get_events()), vec![("feature".to_string(), BranchSyncStatus::Integrated)]);
+  assert!(!test_repo.branch_exists("test/virtual/feature"));
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_empty_branch_policy_archive() -> anyhow::Result<()> {
+  use crate::sync::{SyncOptions, sync_branches, sync_branches_core};
+  use git_ops::model::BranchSyncStatus;
+  use sync_types::SyncEvent;
+  use sync_types::branch_integration::BranchIntegrationStatus;
+
+  let test_repo = create_integrated_group_repo();
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  // Empty branches are kept by default
+  let progress = TestReporter::new();
+  sync_branches_core(&git_executor, repo_path, "test", progress.clone()).await?;
+  assert_eq!(branch_statuses(&progress.get_events()), vec![("feature".to_string(), BranchSyncStatus::Created)]);
+  assert!(test_repo.branch_exists("test/virtual/feature"));
+
+  test_repo.set_config("branchdeck.emptyBranchPolicy", "archive").unwrap();
+  let progress = TestReporter::new();
+  sync_branches(&git_executor, repo_path, "test", progress.clone(), SyncOptions::default()).await?;
+
+  let events = progress.get_events();
+  assert_eq!(branch_statuses(&events), vec![("feature".to_string(), BranchSyncStatus::Integrated)]);
+  assert!(!test_repo.branch_exists("test/virtual/feature"));
+
+  let archived = events
+    .into_iter()
+    .find_map(|event| match event {
+      SyncEvent::BranchIntegrationDetected { info } => Some(info),
+      _ => None,
+    })
+    .expect("BranchIntegrationDetected event");
+  assert!(archived.name.starts_with("test/archived/"), "{}", archived.name);
+  assert!(archived.name.ends_with("/feature"), "{}", archived.name);
+  assert!(test_repo.branch_exists(&archived.name));
+  assert!(matches!(archived.status, BranchIntegrationStatus::Integrated { commit_count: 1, .. }));
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_filtered_progress_reporter() -> anyhow::Result<()> {
+  use crate::sync::sync_branches_core;
+  use sync_types::SyncEvent;
+  use sync_types::event_filter::{FilteredProgressReporter, SyncEventFilter};
+
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let initial_id = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_branch_at("origin/main", &initial_id).unwrap();
+  test_repo.create_commit("(auth) Add authentication", "auth.txt", "auth");
+  test_repo.create_commit("(cache) Add caching", "cache.txt", "cache");
+
+  let progress = TestReporter::new();
+  let filter = SyncEventFilter {
+    branch_names: Some(vec!["auth".to_string()]),
+    errors_only: false,
+  };
+  sync_branches_core(
+    &git_executor,
+    test_repo.path().to_str().unwrap(),
+    "test",
+    FilteredProgressReporter::new(progress.clone(), filter),
+  )
+  .await?;
+
+  let events = progress.get_events();
+  for event in &events {
+    match event {
+      SyncEvent::BranchesGrouped { branches, .. } => {
+        assert_eq!(branches.iter().map(|branch| branch.name.as_str()).collect::<Vec<_>>(), vec!["auth"]);
+      }
+      SyncEvent::CommitSynced { branch_name, .. }
+      | SyncEvent::BranchStatusUpdate { branch_name, .. }
+      | SyncEvent::RemoteStatusUpdate(sync_types::RemoteStatusUpdate { branch_name, .. }) => assert_eq!(branch_name, "auth"),
+      event => panic!("Unexpected event for filtered subscriber: {event:?}"),
+    }
+  }
+  assert!(events.iter().any(|event| matches!(event, SyncEvent::CommitSynced { .. })));
+
+  // Nothing fails, so an errors-only subscriber receives no events
+  let progress = TestReporter::new();
+  let filter = SyncEventFilter {
+    branch_names: None,
+    errors_only: true,
+  };
+  sync_branches_core(
+    &git_executor,
+    test_repo.path().to_str().unwrap(),
+    "test",
+    FilteredProgressReporter::new(progress.clone(), filter),
+  )
+  .await?;
+  assert_eq!(progress.get_events().len(), 0);
+  Ok(())
+}
+#[tokio::test]
+async fn test_single_branch_fast_path_skips_detection() -> anyhow::Result<()> {
+  use crate::sync::sync_branches_core;
+  use sync_types::SyncEvent;
+
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+
+  let initial_id = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_branch_at("origin/main", &initial_id).unwrap();
+  test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+
+  let progress = TestReporter::new();
+  sync_branches_core(&git_executor, test_repo.path().to_str().unwrap(), "test", progress.clone()).await?;
+
+  assert!(test_repo.branch_exists("test/virtual/feature"));
+  let events = progress.get_events();
+  let archived: Vec<Vec<String>> = events
+    .iter()
+    .filter_map(|event| match event {
+      SyncEvent::ArchivedBranchesFound { branch_names } => Some(branch_names.clone()),
+      _ => None,
+    })
+    .collect();
+  // Archived branches are still reported (empty) so the UI clears stale entries
+  assert_eq!(archived, vec![Vec::<String>::new()]);
+  assert!(
+    events
+      .iter()
+      .any(|event| matches!(event, SyncEvent::BranchesGrouped { branches, .. } if branches.len() == 1))
+  );
+  Ok(())
+}
+
+#[tokio::test]
+async fn test_single_branch_with_inactive_branch_uses_full_sync() -> anyhow::Result<()> {
+  use crate::sync::sync_branches_core;
+  use sync_types::SyncEvent;
+
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+
+  let initial_id = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_branch_at("origin/main", &initial_id).unwrap();
+  let old_id = test_repo.create_commit("(old) Old work", "old.txt", "old");
+  test_repo.create_branch_at("test/virtual/old", &old_id).unwrap();
+  test_repo.reset_hard(&initial_id).unwrap();
+  test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+
+  let progress = TestReporter::new();
+  sync_branches_core(&git_executor, test_repo.path().to_str().unwrap(), "test", progress.clone()).await?;
+
+  // The branch that is no longer in the grouping must still be archived
+  assert!(!test_repo.branch_exists("test/virtual/old"));
+  let archived = progress.get_events().into_iter().find_map(|event| match event {
+    SyncEvent::ArchivedBranchesFound { branch_names } => Some(branch_names),
+    _ => None,
+  });
+  assert_eq!(archived.map(|names| names.len()), Some(1));
+  Ok(())
+}
+
+/// Two conflicting feature commits (each depends on an unassigned commit) with an independent one in between
+fn create_conflicting_branch(test_repo: &TestRepo) -> (String, String, String) {
+  test_repo.create_commit("Initial commit", "c.txt", "c0\n");
+
+  test_repo.create_commit("Change c", "c.txt", "c1\n");
+  let first_conflict = test_repo.create_commit("(feature) Edit c", "c.txt", "c2\n");
+  let independent = test_repo.create_commit("(feature) Add d", "d.txt", "d\n");
+  test_repo.create_commit("Change c again", "c.txt", "c3\n");
+  let second_conflict = test_repo.create_commit("(feature) Edit c again", "c.txt", "c4\n");
+  (first_conflict, independent, second_conflict)
+}
+
+fn commit_errors_and_blocked(events: &[sync_types::SyncEvent]) -> (Vec<String>, Vec<String>) {
+  use sync_types::SyncEvent;
+
+  let mut errors = Vec::new();
+  let mut blocked = Vec::new();
+  for event in events {
+    match event {
+      SyncEvent::CommitError { commit_hash, .. } => errors.push(commit_hash.clone()),
+      SyncEvent::CommitsBlocked { blocked_commit_hashes, .. } => blocked.extend(blocked_commit_hashes.iter().cloned()),
+      _ => {}
+    }
+  }
+  (errors, blocked)
+}
+
+#[tokio::test]
+async fn test_conflict_mode_stop_branch() -> anyhow::Result<()> {
+  use crate::sync::sync_branches_core;
+
+  let test_repo = TestRepo::new();
+  let (first_conflict, independent, second_conflict) = create_conflicting_branch(&test_repo);
+
+  let progress = TestReporter::new();
+  sync_branches_core(&GitCommandExecutor::new(), test_repo.path().to_str().unwrap(), "test", progress.clone()).await?;
+
+  let (errors, blocked) = commit_errors_and_blocked(&progress.get_events());
+  assert_eq!(errors, vec![first_conflict]);
+  assert_eq!(blocked, vec![independent, second_conflict]);
+  assert!(!test_repo.branch_exists("test/virtual/feature"));
+  Ok(())
+}
+
+#[tokio::test]
+async fn test_conflict_mode_collect_all() -> anyhow::Result<()> {
+  use crate::sync::sync_branches_core;
+  use git_ops::model::BranchSyncStatus;
+  use sync_types::SyncEvent;
+
+  let test_repo = TestRepo::new();
+  let (first_conflict, independent, second_conflict) = create_conflicting_branch(&test_repo);
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  git_executor.execute_command(&["config", crate::conflict_mode::CONFLICT_MODE_CONFIG_KEY, "collect-all"], repo_path)?;
+
+  let progress = TestReporter::new();
+  sync_branches_core(&git_executor, repo_path, "test", progress.clone()).await?;
+
+  let events = progress.get_events();
+  let (errors, blocked) = commit_errors_and_blocked(&events);
+  assert_eq!(errors, vec![first_conflict, second_conflict]);
+  // The independent commit applies cleanly but can't be synced without the conflicted one
+  assert_eq!(blocked, vec![independent]);
+  assert!(!events.iter().any(|event| matches!(event, SyncEvent::CommitSynced { .. })));
+  assert!(events.iter().any(|event| matches!(
+    event,
+    SyncEvent::BranchStatusUpdate {
+      status: BranchSyncStatus::MergeConflict,
+      ..
+    }
+  )));
+  assert!(!test_repo.branch_exists("test/virtual/feature"));
+  Ok(())
+}
+
+#[tokio::test]
+async fn test_conflict_mode_abort_sync() -> anyhow::Result<()> {
+  use crate::conflict_mode::ConflictMode;
+  use crate::sync::{SyncOptions, sync_branches};
+
+  let test_repo = TestRepo::new();
+  create_conflicting_branch(&test_repo);
+
+  let progress = TestReporter::new();
+  let result = sync_branches(
+    &GitCommandExecutor::new(),
+    test_repo.path().to_str().unwrap(),
+    "test",
+    progress.clone(),
+    SyncOptions {
+      conflict_mode: Some(ConflictMode::AbortSync),
+      ..Default::default()
+    },
+  )
+  .await;
+
+  assert_eq!(result.unwrap_err().to_string(), "Sync aborted because of a merge conflict");
+  assert!(!test_repo.branch_exists("test/virtual/feature"));
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_sync_with_commit_filter() -> anyhow::Result<()> {
+  use crate::sync::{SyncOptions, sync_branches, sync_branches_core};
+  use git_ops::commit_list::CommitFilter;
+  use sync_types::SyncEvent;
+
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(docs) Describe the API", "docs/api.md", "API");
+  test_repo.create_commit("(parser) Add parser", "src/parser.rs", "parser");
+  test_repo.create_commit("(parser) Document the parser", "docs/parser.md", "Parser");
+  test_repo.create_commit("(pasrer) Tweak the lexer", "src/lexer.rs", "lexer");
+  let repo_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+  let is_typo_event = |event: &SyncEvent| matches!(event, SyncEvent::PossiblePrefixTypo(typo) if typo.branch_name == "pasrer");
+  let unfiltered_progress = TestReporter::new();
+  sync_branches_core(&git_executor, repo_path, "test", unfiltered_progress.clone()).await?;
+  assert!(unfiltered_progress.get_events().iter().any(is_typo_event));
+
+  let commit_filter = CommitFilter {
+    pathspec: vec!["src/parser.rs".to_string()],
+    ..Default::default()
+  };
+  let progress = TestReporter::new();
+  sync_branches(
+    &git_executor,
+    repo_path,
+    "test",
+    progress.clone(),
+    SyncOptions {
+      commit_filter: commit_filter.clone(),
+      archived_remote_status: Some(true),
+      ..Default::default()
+    },
+  )
+  .await?;
+
+  let events = progress.get_events();
+  let (branches, reported_filter) = events
+    .iter()
+    .find_map(|event| match event {
+      SyncEvent::BranchesGrouped { branches, commit_filter, .. } => Some((
+        branches.iter().map(|branch| (branch.name.clone(), branch.commits.len())).collect::<Vec<_>>(),
+        commit_filter.clone(),
+      )),
+      _ => None,
+    })
+    .expect("BranchesGrouped event");
+  // The filter selects the branch, which keeps its commit outside the filtered paths
+  assert_eq!(branches, vec![("parser".to_string(), 2)]);
+  assert_eq!(reported_filter, Some(commit_filter));
+  let parser_commit_count = git_executor.execute_command(&["rev-list", "--count", "master..test/virtual/parser"], repo_path)?;
+  assert_eq!(parser_commit_count, "2");
+
+  // Commits that can't belong to the branch are never read, so the misspelled branch is not even reported
+  assert!(!events.iter().any(is_typo_event));
+
+  // The filtered-out branches are not archived as inactive
+  assert!(test_repo.branch_exists("test/virtual/docs"));
+  assert!(test_repo.branch_exists("test/virtual/pasrer"));
+  assert!(!events.iter().any(|event| matches!(event, SyncEvent::ArchivedBranchesFound { .. })));
+  // Still reported without archiving
+  assert!(
+    events
+      .iter()
+      .any(|event| matches!(event, SyncEvent::ArchivedRemoteStatus { branches } if branches.is_empty()))
+  );
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_sync_with_bounded_concurrency() -> anyhow::Result<()> {
+  use crate::sync::{SyncOptions, sync_branches};
+  use git_ops::model::BranchSyncStatus;
+  use std::num::NonZeroUsize;
+  use sync_types::SyncEvent;
+
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  for name in ["auth", "parser", "ui", "docs"] {
+    test_repo.create_commit(&format!("({name}) Add {name}"), &format!("{name}.txt"), name);
+    test_repo.create_commit(&format!("({name}) Update {name}"), &format!("{name}.txt"), &format!("{name} v2"));
+  }
+
+  let progress = TestReporter::new();
+  sync_branches(
+    &GitCommandExecutor::new(),
+    test_repo.path().to_str().unwrap(),
+    "test",
+    progress.clone(),
+    SyncOptions {
+      sync_concurrency: Some(NonZeroUsize::new(2).unwrap()),
+      ..Default::default()
+    },
+  )
+  .await?;
+
+  let events = progress.get_events();
+  let grouped_idx = events
+    .iter()
+    .position(|event| matches!(event, SyncEvent::BranchesGrouped { .. }))
+    .expect("BranchesGrouped event");
+  // Branch events of all workers are reported after the branches are grouped
+  assert!(
+    events[..grouped_idx]
+      .iter()
+      .all(|event| !matches!(event, SyncEvent::BranchStatusUpdate { .. } | SyncEvent::CommitSynced { .. }))
+  );
+
+  let mut created: Vec<String> = events
+    .iter()
+    .filter_map(|event| match event {
+      SyncEvent::BranchStatusUpdate {
+        branch_name,
+        status: BranchSyncStatus::Created,
+        ..
+      } => Some(branch_name.clone()),
+      _ => None,
+    })
+    .collect();
+  created.sort();
+  assert_eq!(created, vec!["auth", "docs", "parser", "ui"]);
+  for name in ["auth", "docs", "parser", "ui"] {
+    assert!(test_repo.branch_exists(&format!("test/virtual/{name}")));
+  }
+  Ok(())
+}
+
+#[test]
+fn test_load_sync_concurrency() -> anyhow::Result<()> {
+  use crate::branch_processor::{SYNC_CONCURRENCY_CONFIG_KEY, default_sync_concurrency, load_sync_concurrency};
+
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  assert_eq!(load_sync_concurrency(&git_executor, repo_path), default_sync_concurrency());
+
+  git_executor.execute_command(&["config", SYNC_CONCURRENCY_CONFIG_KEY, "1"], repo_path)?;
+  assert_eq!(load_sync_concurrency(&git_executor, repo_path).get(), 1);
+
+  // Zero would never sync anything
+  git_executor.execute_command(&["config", SYNC_CONCURRENCY_CONFIG_KEY, "0"], repo_path)?;
+  assert_eq!(load_sync_concurrency(&git_executor, repo_path), default_sync_concurrency());
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_work_queue_runs_items_concurrently() {
+  use crate::branch_processor::run_work_queue;
+  use std::num::NonZeroUsize;
+  use std::sync::Mutex;
+  use std::sync::mpsc;
+  use std::time::Duration;
+
+  // The first item only finishes once the second one has started, which never happens if the workers run one by one
+  let (sender, receiver) = mpsc::channel::<()>();
+  let receiver = Mutex::new(receiver);
+  let failed = run_work_queue(vec![0, 1], NonZeroUsize::new(2).unwrap(), move |item| {
+    if item == 0 {
+      receiver.lock().unwrap().recv_timeout(Duration::from_secs(10))?;
+    } else {
+      sender.send(())?;
+    }
+    Ok(())
+  })
+  .await;
+  assert_eq!(failed, 0);
+
+  let failed = run_work_queue(vec![1, 2, 3], NonZeroUsize::MIN, |item| if item == 2 { Err(anyhow::anyhow!("failed")) } else { Ok(()) }).await;
+  assert_eq!(failed, 1);
+}
+
+#[test]
+fn test_conflict_mode_from_config_value() {
+  use crate::conflict_mode::ConflictMode;
+
+  assert_eq!(ConflictMode::from_config_value("stop-branch"), Some(ConflictMode::StopBranch));
+  assert_eq!(ConflictMode::from_config_value(" Collect-All "), Some(ConflictMode::CollectAll));
+  assert_eq!(ConflictMode::from_config_value("abort-sync"), Some(ConflictMode::AbortSync));
+  assert_eq!(ConflictMode::from_config_value("unknown"), None);
+}
+
 #[test]
 fn test_detect_baseline_branch_scenarios() {
   let test_repo = TestRepo::new();
@@ -634,3 +1266,243 @@ fn test_prepare_branches_for_ui_mixed_commits() {
     "feature-auth should have all_commits_have_issue_references = false because only one of two commits has an issue reference"
   );
 }
+
+#[test(tokio::test)]
+async fn test_sync_refs_snapshot() -> anyhow::Result<()> {
+  use crate::sync::sync_branches_core;
+  use test_utils::ref_snapshot::SnapshotOptions;
+
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(auth) Add authentication", "auth.txt", "auth");
+  test_repo.create_commit("(cache) Add caching", "cache.txt", "cache");
+  test_repo.create_commit("(auth) Add user model", "user.txt", "user");
+
+  sync_branches_core(&GitCommandExecutor::new(), test_repo.path().to_str().unwrap(), "test", TestReporter::new()).await?;
+
+  let snapshot = test_repo
+    .snapshot_refs(SnapshotOptions {
+      ref_prefix: "refs/heads/test/",
+      base: Some("master"),
+    })
+    .unwrap();
+  insta::assert_yaml_snapshot!(snapshot);
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_sync_sends_partial_grouping_per_commit_page() -> anyhow::Result<()> {
+  use crate::sync::{SyncOptions, sync_branches};
+  use std::num::NonZeroUsize;
+  use sync_types::SyncEvent;
+
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(api) Add endpoint", "api.rs", "api");
+  test_repo.create_commit("Update readme", "README.md", "# Test 2");
+  test_repo.create_commit("(ui) Add button", "ui.rs", "ui");
+  test_repo.create_commit("(api) Add handler", "handler.rs", "handler");
+  test_repo.create_commit("Bump version", "VERSION", "2");
+
+  let progress = TestReporter::new();
+  sync_branches(
+    &GitCommandExecutor::new(),
+    test_repo.path().to_str().unwrap(),
+    "test",
+    progress.clone(),
+    SyncOptions {
+      commit_page_size: NonZeroUsize::new(2).unwrap(),
+      ..Default::default()
+    },
+  )
+  .await?;
+
+  // Two full pages of two commits are reported as they are read, the last partial page only with the final events
+  let events = progress.get_events();
+  let grouped: Vec<Vec<(String, usize)>> = events
+    .iter()
+    .filter_map(|event| match event {
+      SyncEvent::BranchesGrouped { branches, .. } => Some(branches.iter().map(|branch| (branch.name.clone(), branch.commits.len())).collect()),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(
+    grouped,
+    vec![
+      vec![("api".to_string(), 1)],
+      vec![("api".to_string(), 2), ("ui".to_string(), 1)],
+      vec![("api".to_string(), 2), ("ui".to_string(), 1)],
+    ]
+  );
+
+  let unassigned: Vec<Vec<String>> = events
+    .iter()
+    .filter_map(|event| match event {
+      SyncEvent::UnassignedCommits { commits } => Some(commits.iter().map(|commit| commit.stripped_subject.clone()).collect()),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(
+    unassigned,
+    vec![
+      vec!["Update readme".to_string()],
+      vec!["Update readme".to_string()],
+      vec!["Bump version".to_string(), "Update readme".to_string()],
+    ]
+  );
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_branch_name_edge_cases_template() -> anyhow::Result<()> {
+  use crate::sync::sync_branches_core;
+  use sync_types::SyncEvent;
+  use tempfile::TempDir;
+  use test_utils::repo_template::templates;
+
+  let temp_dir = TempDir::new()?;
+  templates::branch_name_edge_cases().build(temp_dir.path())?;
+  let repo_path = temp_dir.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+
+  let progress = TestReporter::new();
+  let result = sync_branches_core(&git_executor, repo_path, "user-name", progress.clone()).await;
+  // Branches git can't create fail, the others are still synced
+  assert_eq!(result.map_err(|e| e.to_string()), Err("One or more branch processing tasks failed".to_string()));
+
+  let mut grouped: Vec<(String, usize)> = progress
+    .get_events()
+    .into_iter()
+    .find_map(|event| match event {
+      SyncEvent::BranchesGrouped { branches, .. } => Some(branches),
+      _ => None,
+    })
+    .expect("branches grouped")
+    .into_iter()
+    .map(|branch| (branch.name, branch.commits.len()))
+    .collect();
+  grouped.sort();
+  let mut expected = vec![
+    (String::new(), 1),
+    ("fix-bug-v2".to_string(), 1),
+    (templates::long_branch_name(), 1),
+    ("release..next".to_string(), 1),
+    (templates::too_long_branch_name(), 1),
+    ("ui-dispatcher".to_string(), 3),
+  ];
+  expected.sort();
+  assert_eq!(grouped, expected);
+
+  let branches = git_executor.execute_command_lines(&["for-each-ref", "--format=%(refname:short)", "refs/heads/user-name/virtual/"], repo_path)?;
+  assert_eq!(
+    branches,
+    vec![
+      "user-name/virtual/fix-bug-v2".to_string(),
+      format!("user-name/virtual/{}", templates::long_branch_name()),
+      "user-name/virtual/ui-dispatcher".to_string(),
+    ]
+  );
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_sync_rolls_back_refs_of_interrupted_sync() -> anyhow::Result<()> {
+  use crate::sync::sync_branches_core;
+  use git_ops::ref_transaction::{REF_JOURNAL_REF, RefTransaction, begin_ref_journal, load_ref_journal};
+  use git_ops::repository_lock::lock_repository;
+  use sync_test_utils::TestReporter;
+
+  let test_repo = TestRepo::new();
+  let initial = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(auth) Add authentication", "auth.txt", "auth");
+  test_repo.create_commit("(cache) Add caching", "cache.txt", "cache");
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+
+  sync_branches_core(&git_executor, repo_path, "test", TestReporter::new()).await?;
+  let auth_head = test_repo.rev_parse("test/virtual/auth").unwrap();
+  assert_eq!(load_ref_journal(&git_executor, repo_path)?, None);
+
+  // A sync that crashed after moving some of the refs
+  let lock = lock_repository(&git_executor, repo_path)?;
+  begin_ref_journal(&git_executor, repo_path, &lock, "sync test", &["refs/heads/test/"])?;
+  drop(lock);
+  RefTransaction::new()
+    .update("refs/heads/test/virtual/auth", &initial, None)
+    .create("refs/heads/test/virtual/stray", &initial)
+    .commit(&git_executor, repo_path, "test")?;
+
+  sync_branches_core(&git_executor, repo_path, "test", TestReporter::new()).await?;
+
+  // Unchanged commits are reused, so the branch ends up where it was before the crash
+  assert_eq!(test_repo.rev_parse("test/virtual/auth").unwrap(), auth_head);
+  assert_eq!(test_repo.list_branches("*stray*").unwrap(), Vec::<String>::new());
+  assert!(test_repo.rev_parse(REF_JOURNAL_REF).is_err());
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_sync_keeps_user_changes_after_interrupted_sync() -> anyhow::Result<()> {
+  use crate::sync::sync_branches_core;
+  use git_ops::ref_transaction::{RefTransaction, begin_ref_journal, load_ref_journal};
+  use git_ops::repository_lock::lock_repository;
+  use sync_test_utils::TestReporter;
+
+  let test_repo = TestRepo::new();
+  let initial = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(auth) Add authentication", "auth.txt", "auth");
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let head_ref = format!("refs/heads/{}", test_repo.current_branch().unwrap());
+
+  sync_branches_core(&git_executor, repo_path, "test", TestReporter::new()).await?;
+  let auth_head = test_repo.rev_parse("test/virtual/auth").unwrap();
+
+  // A sync that crashed after moving a virtual branch, the journal covers the checked out branch too
+  let lock = lock_repository(&git_executor, repo_path)?;
+  begin_ref_journal(&git_executor, repo_path, &lock, "sync test", &["refs/heads/test/", &head_ref])?;
+  drop(lock);
+  RefTransaction::new()
+    .update("refs/heads/test/virtual/auth", &initial, None)
+    .commit(&git_executor, repo_path, "test")?;
+
+  // The user keeps working before the next sync
+  let user_commit = test_repo.create_commit("(cache) Add caching", "cache.txt", "cache");
+  test_repo.create_branch_at("test/mine", &initial).unwrap();
+
+  sync_branches_core(&git_executor, repo_path, "test", TestReporter::new()).await?;
+
+  assert_eq!(test_repo.head(), user_commit);
+  assert_eq!(test_repo.rev_parse("test/mine").unwrap(), initial);
+  assert_eq!(test_repo.rev_parse("test/virtual/auth").unwrap(), auth_head);
+  assert!(test_repo.rev_parse("test/virtual/cache").is_ok());
+  assert_eq!(load_ref_journal(&git_executor, repo_path)?, None);
+  Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_sync_does_not_roll_back_checked_out_branch() -> anyhow::Result<()> {
+  use crate::sync::sync_branches_core;
+  use git_ops::ref_transaction::{RefTransaction, begin_ref_journal, load_ref_journal};
+  use git_ops::repository_lock::lock_repository;
+  use sync_test_utils::TestReporter;
+
+  let test_repo = TestRepo::new();
+  let initial = test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(auth) Add authentication", "auth.txt", "auth");
+  let git_executor = GitCommandExecutor::new();
+  let repo_path = test_repo.path().to_str().unwrap();
+  let head_ref = format!("refs/heads/{}", test_repo.current_branch().unwrap());
+
+  // A sync that crashed right after dropping a commit from the checked out branch
+  let lock = lock_repository(&git_executor, repo_path)?;
+  begin_ref_journal(&git_executor, repo_path, &lock, "sync test", &["refs/heads/test/", &head_ref])?;
+  drop(lock);
+  RefTransaction::new().update(&head_ref, &initial, None).commit(&git_executor, repo_path, "test")?;
+
+  let result = sync_branches_core(&git_executor, repo_path, "test", TestReporter::new()).await;
+  assert!(result.is_err_and(|e| format!("{e:#}").contains("recover it first")));
+  assert_eq!(test_repo.head(), initial);
+  assert!(load_ref_journal(&git_executor, repo_path)?.is_some());
+  Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::branch_protection::check_delete_allowed;
 use anyhow::{Result, anyhow};
 use git_executor::git_command_executor::GitCommandExecutor;
 use git_ops::amend_operations::drop_commits_from_head;
@@ -117,6 +118,8 @@ pub fn unapply_branch_core(git_executor: &GitCommandExecutor, params: UnapplyBra
     return Err(anyhow!("Can only unapply virtual branches under the configured branch prefix"));
   }
 
+  check_delete_allowed(git_executor, &repository_path, &branch_prefix, std::slice::from_ref(&branch_name))?;
+
   // Verify branch exists
   let branch_ref = format!("refs/heads/{}", branch_name);
   let exists = git_executor.execute_command(&["show-ref", "--verify", &branch_ref], &repository_path).is_ok();
@@ -140,7 +143,7 @@ pub fn unapply_branch_core(git_executor: &GitCommandExecutor, params: UnapplyBra
   debug!(unapplied_branch = %unapplied_branch_name, "Moved virtual branch to unapplied");
 
   // Drop the specified commits from HEAD
-  let _new_head = drop_commits_from_head(git_executor, &repository_path, &original_commit_ids, baseline_branch).map_err(|e| anyhow!("Failed to drop commits from HEAD: {}", e))?;
+  let _new_head = drop_commits_from_head(git_executor, &repository_path, &original_commit_ids, baseline_branch).map_err(|e| e.into_anyhow("Failed to drop commits from HEAD"))?;
 
   debug!(commits_dropped = original_commit_ids.len(), "Successfully dropped commits from HEAD");
 
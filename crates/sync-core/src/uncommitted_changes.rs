@@ -1,6 +1,8 @@
 use git_executor::git_command_executor::GitCommandExecutor;
 use git_ops::conflict_analysis::{FileDiff, FileInfo};
+use git_ops::file_language::{PLAIN_TEXT_LANG, language_from_content, language_from_path};
 use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader};
 use tracing::instrument;
 
 #[derive(Debug, Deserialize)]
@@ -121,8 +123,16 @@ pub fn get_file_content_for_diff(git_executor: &GitCommandExecutor, params: GetF
   let repo_path = params.repository_path;
   let file_path = params.file_path;
 
-  // Determine file language from extension
-  let file_lang = std::path::Path::new(&file_path).extension().and_then(|ext| ext.to_str()).unwrap_or("txt").to_string();
+  // Determine file language from the name, for extensionless scripts from the shebang of the working tree file
+  let file_lang = language_from_path(&file_path)
+    .or_else(|| {
+      let file = std::fs::File::open(std::path::Path::new(&repo_path).join(&file_path)).ok()?;
+      let mut first_line = String::new();
+      BufReader::new(file).read_line(&mut first_line).ok()?;
+      language_from_content(&first_line)
+    })
+    .unwrap_or(PLAIN_TEXT_LANG)
+    .to_string();
 
   // Get unified diff with 15 lines of context
   let diff_output = git_executor
@@ -142,5 +152,8 @@ pub fn get_file_content_for_diff(git_executor: &GitCommandExecutor, params: GetF
       content: String::new(), // Empty - git-diff-view will extract from hunks
     },
     hunks: vec![diff_output], // Unified diff output from git
+    whitespace_ignored: false,
+    rename: None,
+    word_diffs: None,
   })
 }
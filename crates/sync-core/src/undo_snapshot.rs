@@ -0,0 +1,121 @@
+use crate::repo_state::STATE_REF;
+use anyhow::{Result, anyhow, bail};
+use git_executor::git_command_executor::GitCommandExecutor;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use tracing::{debug, info, instrument};
+
+/// Ref holding the snapshot taken before the last operation plan, so the whole plan can be undone at once.
+/// Like [`STATE_REF`], it points to a commit whose tree contains a single `snapshot.json`.
+pub const UNDO_SNAPSHOT_REF: &str = "refs/branch-deck/undo";
+
+const SNAPSHOT_FILE: &str = "snapshot.json";
+
+/// Local refs before a multi-step operation: the checked out branch, the branches under the prefix and the repository state.
+/// Remote branches are not part of it, a push can't be undone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct UndoSnapshot {
+  /// What the snapshot was taken for, for display
+  pub description: String,
+  pub branch_prefix: String,
+  /// Checked out branch (e.g., "refs/heads/master")
+  pub head_ref: String,
+  pub head_commit: String,
+  /// Commit of every other snapshotted ref, keyed by full ref name
+  pub refs: BTreeMap<String, String>,
+}
+
+/// Full names and commits of the refs under `refs/heads/<prefix>/` and the repository state ref
+fn list_snapshot_refs(git_executor: &GitCommandExecutor, repo_path: &str, branch_prefix: &str) -> Result<BTreeMap<String, String>> {
+  let lines = git_executor.execute_command_lines(
+    &["for-each-ref", "--format=%(refname) %(objectname)", &format!("refs/heads/{branch_prefix}/"), STATE_REF],
+    repo_path,
+  )?;
+  Ok(
+    lines
+      .iter()
+      .filter_map(|line| line.split_once(' '))
+      .map(|(name, oid)| (name.to_string(), oid.to_string()))
+      .collect(),
+  )
+}
+
+/// Record the current local refs as the undo snapshot, replacing the previous one
+#[instrument(skip(git_executor))]
+pub fn create_undo_snapshot(git_executor: &GitCommandExecutor, repo_path: &str, branch_prefix: &str, description: &str) -> Result<UndoSnapshot> {
+  let head_ref = git_executor
+    .execute_command(&["symbolic-ref", "--quiet", "HEAD"], repo_path)
+    .map_err(|_| anyhow!("HEAD is detached, check out a branch first"))?
+    .trim()
+    .to_string();
+  let head_commit = git_executor.execute_command(&["rev-parse", "HEAD"], repo_path)?.trim().to_string();
+  let mut refs = list_snapshot_refs(git_executor, repo_path, branch_prefix)?;
+  refs.remove(&head_ref);
+
+  let snapshot = UndoSnapshot {
+    description: description.to_string(),
+    branch_prefix: branch_prefix.to_string(),
+    head_ref,
+    head_commit,
+    refs,
+  };
+
+  let content = serde_json::to_string_pretty(&snapshot)? + "\n";
+  let blob_id = git_executor.execute_command_with_input(&["hash-object", "-w", "--stdin"], repo_path, &content)?;
+  let tree_id = git_executor.execute_command_with_input(&["mktree"], repo_path, &format!("100644 blob {}\t{SNAPSHOT_FILE}\n", blob_id.trim()))?;
+  let commit_id = git_executor.execute_command(&["commit-tree", tree_id.trim(), "-m", description], repo_path)?;
+  git_executor.execute_command(&["update-ref", UNDO_SNAPSHOT_REF, commit_id.trim()], repo_path)?;
+  debug!(ref_count = snapshot.refs.len(), "Undo snapshot created");
+  Ok(snapshot)
+}
+
+/// The snapshot of the last operation plan, `None` if there is nothing to undo
+#[instrument(skip(git_executor))]
+pub fn load_undo_snapshot(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<Option<UndoSnapshot>> {
+  let (output, exit_code) = git_executor.execute_command_with_status(&["rev-parse", "--verify", "--quiet", &format!("{UNDO_SNAPSHOT_REF}^{{commit}}")], repo_path)?;
+  if exit_code != 0 {
+    return Ok(None);
+  }
+  let content = git_executor.execute_command(&["cat-file", "blob", &format!("{}:{SNAPSHOT_FILE}", output.trim())], repo_path)?;
+  let snapshot = serde_json::from_str(&content).map_err(|e| anyhow!("Failed to parse {UNDO_SNAPSHOT_REF}:{SNAPSHOT_FILE}: {e}"))?;
+  Ok(Some(snapshot))
+}
+
+/// Move every snapshotted ref back, delete the refs created since and reset the checked out branch (keeping local changes).
+/// The snapshot is consumed, so an undo can't be applied twice.
+#[instrument(skip(git_executor))]
+pub fn restore_undo_snapshot(git_executor: &GitCommandExecutor, repo_path: &str) -> Result<UndoSnapshot> {
+  let snapshot = load_undo_snapshot(git_executor, repo_path)?.ok_or_else(|| anyhow!("Nothing to undo"))?;
+
+  let head_ref = git_executor.execute_command(&["symbolic-ref", "--quiet", "HEAD"], repo_path).unwrap_or_default();
+  if head_ref.trim() != snapshot.head_ref {
+    bail!("{} is not checked out anymore, check it out to undo \"{}\"", snapshot.head_ref, snapshot.description);
+  }
+
+  let mut current_refs = list_snapshot_refs(git_executor, repo_path, &snapshot.branch_prefix)?;
+  current_refs.remove(&snapshot.head_ref);
+  let mut batch = String::from("start\n");
+  for (ref_name, oid) in &snapshot.refs {
+    if current_refs.get(ref_name) != Some(oid) {
+      writeln!(batch, "update {ref_name} {oid}")?;
+    }
+  }
+  for ref_name in current_refs.keys().filter(|ref_name| !snapshot.refs.contains_key(*ref_name)) {
+    writeln!(batch, "delete {ref_name}")?;
+  }
+  batch.push_str("commit\n");
+  git_executor.execute_command_with_input(&["update-ref", "--stdin"], repo_path, &batch)?;
+
+  // Unlike moving the ref, `--keep` updates the working tree and refuses to overwrite local changes
+  let head_commit = git_executor.execute_command(&["rev-parse", "HEAD"], repo_path)?;
+  if head_commit.trim() != snapshot.head_commit {
+    git_executor.execute_command(&["reset", "--keep", &snapshot.head_commit], repo_path)?;
+  }
+
+  git_executor.execute_command(&["update-ref", "-d", UNDO_SNAPSHOT_REF], repo_path)?;
+  info!(description = %snapshot.description, "Restored undo snapshot");
+  Ok(snapshot)
+}
@@ -0,0 +1,159 @@
+//! Local usage statistics of a repository: how many syncs ran, how many merge conflicts they hit, how many branches were
+//! pushed and how many AI suggestions were accepted. Kept in `.git/branch-deck/usage-stats.json` for the user to see
+//! their own workflow (`get_usage_stats`); nothing is sent anywhere.
+
+use anyhow::Result;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::cache::resolve_common_git_dir;
+use git_ops::model::BranchSyncStatus;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use sync_types::{ProgressReporter, SyncEvent};
+use tracing::{instrument, warn};
+
+/// Serializes the read-modify-write of the stats file, updates come from concurrent commands
+static USAGE_STATS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Operation counted in the usage statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum UsageCounter {
+  Sync,
+  /// A branch that failed to sync because of a merge conflict
+  MergeConflict,
+  Push,
+  /// A suggested branch name or commit message that was used
+  AiSuggestionAccepted,
+}
+
+/// Operation only the frontend knows about, the only counters `record_usage` may be called with from there.
+/// The backend counts syncs, conflicts and pushes itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum UiUsageCounter {
+  /// A suggested branch name or commit message that was used
+  AiSuggestionAccepted,
+}
+
+impl From<UiUsageCounter> for UsageCounter {
+  fn from(counter: UiUsageCounter) -> Self {
+    match counter {
+      UiUsageCounter::AiSuggestionAccepted => UsageCounter::AiSuggestionAccepted,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase", default)]
+pub struct UsageStats {
+  /// Unix timestamp of the first recorded operation, `None` if nothing was recorded yet
+  pub since: Option<u64>,
+  pub syncs: u64,
+  /// Branches that hit a merge conflict, once per sync
+  pub merge_conflicts: u64,
+  pub pushes: u64,
+  pub ai_suggestions_accepted: u64,
+}
+
+impl UsageStats {
+  pub fn add(&mut self, counter: UsageCounter, count: u64) {
+    let value = match counter {
+      UsageCounter::Sync => &mut self.syncs,
+      UsageCounter::MergeConflict => &mut self.merge_conflicts,
+      UsageCounter::Push => &mut self.pushes,
+      UsageCounter::AiSuggestionAccepted => &mut self.ai_suggestions_accepted,
+    };
+    *value = value.saturating_add(count);
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct RecordUsageParams {
+  pub repository_path: String,
+  pub counter: UiUsageCounter,
+}
+
+fn usage_stats_path(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<PathBuf> {
+  Ok(resolve_common_git_dir(git_executor, repository_path)?.join("branch-deck").join("usage-stats.json"))
+}
+
+/// Usage statistics of the repository, all zero if nothing was recorded yet or the file is unreadable
+#[instrument(skip(git_executor))]
+pub fn load_usage_stats(git_executor: &GitCommandExecutor, repository_path: &str) -> Result<UsageStats> {
+  let path = usage_stats_path(git_executor, repository_path)?;
+  let content = match std::fs::read_to_string(&path) {
+    Ok(content) => content,
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(UsageStats::default()),
+    Err(e) => return Err(e.into()),
+  };
+  Ok(serde_json::from_str(&content).unwrap_or_else(|e| {
+    warn!(error = %e, "Invalid usage statistics, starting over");
+    UsageStats::default()
+  }))
+}
+
+/// Apply `update` to the stored statistics
+#[instrument(skip(git_executor, update))]
+pub fn update_usage_stats(git_executor: &GitCommandExecutor, repository_path: &str, update: impl FnOnce(&mut UsageStats)) -> Result<()> {
+  let _guard = USAGE_STATS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+  let mut stats = load_usage_stats(git_executor, repository_path)?;
+  update(&mut stats);
+  if stats.since.is_none() {
+    stats.since = Some(SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or_default());
+  }
+
+  let path = usage_stats_path(git_executor, repository_path)?;
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+  // Written to a temporary file first, so an interrupted write doesn't reset the counts
+  let temp_path = path.with_extension("json.tmp");
+  std::fs::write(&temp_path, serde_json::to_string_pretty(&stats)?)?;
+  std::fs::rename(&temp_path, &path)?;
+  Ok(())
+}
+
+/// Count one operation. Not fatal for the operation itself, so failures are only logged.
+pub fn record_usage(git_executor: &GitCommandExecutor, repository_path: &str, counter: UsageCounter) {
+  if let Err(e) = update_usage_stats(git_executor, repository_path, |stats| stats.add(counter, 1)) {
+    warn!(error = %e, ?counter, "Failed to record usage statistics");
+  }
+}
+
+/// Progress reporter forwarding events to another reporter while counting the branches that hit a merge conflict
+#[derive(Clone)]
+pub struct MergeConflictCounter<P: ProgressReporter> {
+  inner: P,
+  count: Arc<AtomicU64>,
+}
+
+impl<P: ProgressReporter> MergeConflictCounter<P> {
+  pub fn new(inner: P) -> Self {
+    Self { inner, count: Arc::default() }
+  }
+
+  pub fn count(&self) -> u64 {
+    self.count.load(Ordering::Relaxed)
+  }
+}
+
+impl<P: ProgressReporter> ProgressReporter for MergeConflictCounter<P> {
+  fn send(&self, event: SyncEvent) -> Result<()> {
+    if let SyncEvent::BranchStatusUpdate {
+      status: BranchSyncStatus::MergeConflict,
+      ..
+    } = &event
+    {
+      self.count.fetch_add(1, Ordering::Relaxed);
+    }
+    self.inner.send(event)
+  }
+}
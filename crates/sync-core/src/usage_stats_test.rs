@@ -0,0 +1,84 @@
+use crate::sync::{SyncOptions, sync_branches};
+use crate::usage_stats::{MergeConflictCounter, RecordUsageParams, UiUsageCounter, UsageCounter, UsageStats, load_usage_stats, record_usage};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::model::BranchSyncStatus;
+use pretty_assertions::assert_eq;
+use sync_test_utils::TestReporter;
+use sync_types::{ProgressReporter, SyncEvent};
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+#[test]
+fn test_record_usage() {
+  let test_repo = TestRepo::new();
+  let git_executor = GitCommandExecutor::new();
+  let repository_path = test_repo.path().to_str().unwrap();
+  assert_eq!(load_usage_stats(&git_executor, repository_path).unwrap(), UsageStats::default());
+
+  record_usage(&git_executor, repository_path, UsageCounter::Push);
+  record_usage(&git_executor, repository_path, UsageCounter::Push);
+  record_usage(&git_executor, repository_path, UsageCounter::AiSuggestionAccepted);
+
+  let stats = load_usage_stats(&git_executor, repository_path).unwrap();
+  assert!(stats.since.is_some());
+  assert_eq!(
+    stats,
+    UsageStats {
+      since: stats.since,
+      pushes: 2,
+      ai_suggestions_accepted: 1,
+      ..Default::default()
+    }
+  );
+}
+
+#[test]
+fn test_record_usage_params_only_accept_ui_counters() {
+  let params: RecordUsageParams = serde_json::from_str(r#"{"repositoryPath":"/repo","counter":"aiSuggestionAccepted"}"#).unwrap();
+  assert_eq!(UsageCounter::from(params.counter), UsageCounter::AiSuggestionAccepted);
+  assert_eq!(params.counter, UiUsageCounter::AiSuggestionAccepted);
+  // Counted by the backend itself
+  for counter in ["sync", "mergeConflict", "push"] {
+    let json = format!(r#"{{"repositoryPath":"/repo","counter":"{counter}"}}"#);
+    assert!(serde_json::from_str::<RecordUsageParams>(&json).is_err(), "{counter}");
+  }
+}
+
+#[test(tokio::test)]
+async fn test_sync_records_usage() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+  let repository_path = test_repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+
+  sync_branches(&git_executor, repository_path, "test", TestReporter::new(), SyncOptions::default()).await?;
+  sync_branches(&git_executor, repository_path, "test", TestReporter::new(), SyncOptions::default()).await?;
+
+  let stats = load_usage_stats(&git_executor, repository_path)?;
+  assert_eq!((stats.syncs, stats.merge_conflicts), (2, 0));
+  Ok(())
+}
+
+#[test]
+fn test_merge_conflict_counter() {
+  let reporter = TestReporter::new();
+  let counter = MergeConflictCounter::new(reporter.clone());
+  for (branch_name, status) in [
+    ("a", BranchSyncStatus::MergeConflict),
+    ("b", BranchSyncStatus::Updated),
+    ("c", BranchSyncStatus::MergeConflict),
+  ] {
+    counter
+      .send(SyncEvent::BranchStatusUpdate {
+        branch_name: branch_name.to_string(),
+        status,
+        error: None,
+      })
+      .unwrap();
+  }
+
+  assert_eq!(counter.count(), 2);
+  // Events are forwarded
+  assert_eq!(reporter.get_events().len(), 3);
+}
@@ -0,0 +1,265 @@
+use crate::repo_state::{STATE_REF, load_repo_state};
+use anyhow::Result;
+use branch_integration::cache::{NOTES_REF as DETECTION_NOTES_REF, parse_cached_note};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::commit_dependencies::DEPENDENCIES_NOTES_REF;
+use git_ops::notes::{PREFIX, user_note_content};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument, warn};
+
+/// Notes ref of the sync mapping notes (`v-commit-v1:<rewritten commit>`), shared with the user's own notes
+pub const MAPPING_NOTES_REF: &str = "refs/notes/commits";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyMetadataParams {
+  pub repository_path: String,
+  /// Remove or rewrite broken entries; otherwise only report them
+  pub repair: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum MetadataIssueKind {
+  /// Note attached to an object that no longer exists (e.g. after `git gc` of a rewritten history)
+  OrphanedNote,
+  /// Sync mapping pointing to a rewritten commit that no longer exists, the next sync would copy the commit again anyway
+  DanglingMapping,
+  /// Detection cache entry that can't be parsed
+  InvalidCacheEntry,
+  /// Dependency on a commit that no longer exists
+  DanglingDependency,
+  /// The repository state can't be read, it's never repaired automatically as it holds user settings
+  InvalidRepoState,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataIssue {
+  pub kind: MetadataIssueKind,
+  /// Notes ref or state ref holding the entry
+  pub reference: String,
+  /// Annotated object of a note
+  pub object_id: Option<String>,
+  pub detail: String,
+  pub repaired: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyMetadataResult {
+  pub checked_notes: u32,
+  pub issues: Vec<MetadataIssue>,
+  pub repaired_count: u32,
+}
+
+/// A note as listed by `git notes list`
+struct NoteEntry {
+  blob_id: String,
+  object_id: String,
+}
+
+/// What to do with a broken note
+enum NoteRepair {
+  Remove,
+  Rewrite(String),
+}
+
+struct Verifier<'a> {
+  git_executor: &'a GitCommandExecutor,
+  repository_path: &'a str,
+  repair: bool,
+  checked_notes: u32,
+  issues: Vec<MetadataIssue>,
+}
+
+impl Verifier<'_> {
+  fn object_exists(&self, object_id: &str) -> bool {
+    matches!(self.git_executor.cat_file_info(self.repository_path, object_id), Ok(Some(_)))
+  }
+
+  fn list_notes(&self, notes_ref: &str) -> Result<Vec<NoteEntry>> {
+    let lines = self.git_executor.execute_command_lines(&["notes", "--ref", notes_ref, "list"], self.repository_path)?;
+    Ok(
+      lines
+        .into_iter()
+        .filter_map(|line| {
+          let (blob_id, object_id) = line.split_once(' ')?;
+          Some(NoteEntry {
+            blob_id: blob_id.to_string(),
+            object_id: object_id.to_string(),
+          })
+        })
+        .collect(),
+    )
+  }
+
+  fn read_note(&self, note: &NoteEntry) -> Result<String> {
+    match self.git_executor.cat_file_contents(self.repository_path, &note.blob_id)? {
+      Some((_, content)) => Ok(String::from_utf8_lossy(&content).into_owned()),
+      None => Err(anyhow::anyhow!("Note blob {} does not exist", note.blob_id)),
+    }
+  }
+
+  /// Check the notes of a ref: orphaned notes are detected for every ref, `check` validates the content of the others
+  fn verify_notes<F>(&mut self, notes_ref: &str, mut check: F) -> Result<()>
+  where
+    F: FnMut(&Self, &str) -> Option<(MetadataIssueKind, String, NoteRepair)>,
+  {
+    let notes = self.list_notes(notes_ref)?;
+    self.checked_notes += notes.len() as u32;
+
+    let mut orphaned = Vec::new();
+    for note in notes {
+      if !self.object_exists(&note.object_id) {
+        orphaned.push(note.object_id);
+        continue;
+      }
+
+      let content = match self.read_note(&note) {
+        Ok(content) => content,
+        Err(e) => {
+          warn!(notes_ref, object_id = %note.object_id, error = %e, "Failed to read note");
+          continue;
+        }
+      };
+      if let Some((kind, detail, repair)) = check(self, &content) {
+        let repaired = self.repair && self.apply_repair(notes_ref, &note.object_id, repair);
+        self.issues.push(MetadataIssue {
+          kind,
+          reference: notes_ref.to_string(),
+          object_id: Some(note.object_id),
+          detail,
+          repaired,
+        });
+      }
+    }
+
+    if orphaned.is_empty() {
+      return Ok(());
+    }
+    let repaired = self.repair && self.remove_notes(notes_ref, &orphaned);
+    self.issues.extend(orphaned.into_iter().map(|object_id| MetadataIssue {
+      kind: MetadataIssueKind::OrphanedNote,
+      reference: notes_ref.to_string(),
+      detail: format!("Annotated object {object_id} does not exist"),
+      object_id: Some(object_id),
+      repaired,
+    }));
+    Ok(())
+  }
+
+  fn apply_repair(&self, notes_ref: &str, object_id: &str, repair: NoteRepair) -> bool {
+    match repair {
+      NoteRepair::Remove => self.remove_notes(notes_ref, &[object_id.to_string()]),
+      NoteRepair::Rewrite(content) => match self
+        .git_executor
+        .execute_command(&["notes", "--ref", notes_ref, "add", "-f", "-m", &content, object_id], self.repository_path)
+      {
+        Ok(_) => true,
+        Err(e) => {
+          warn!(notes_ref, object_id, error = %e, "Failed to rewrite note");
+          false
+        }
+      },
+    }
+  }
+
+  fn remove_notes(&self, notes_ref: &str, object_ids: &[String]) -> bool {
+    let input = object_ids.join("\n") + "\n";
+    match self
+      .git_executor
+      .execute_command_with_input(&["notes", "--ref", notes_ref, "remove", "--ignore-missing", "--stdin"], self.repository_path, &input)
+    {
+      Ok(_) => true,
+      Err(e) => {
+        warn!(notes_ref, error = %e, "Failed to remove notes");
+        false
+      }
+    }
+  }
+
+  fn verify_repo_state(&mut self) {
+    if let Err(e) = load_repo_state(self.git_executor, self.repository_path) {
+      self.issues.push(MetadataIssue {
+        kind: MetadataIssueKind::InvalidRepoState,
+        reference: STATE_REF.to_string(),
+        object_id: None,
+        detail: format!("{e:#}"),
+        repaired: false,
+      });
+    }
+  }
+}
+
+/// Mapping line pointing to a missing commit: drop the line, keeping the user's note if there is one
+fn check_mapping_note(verifier: &Verifier, content: &str) -> Option<(MetadataIssueKind, String, NoteRepair)> {
+  let mapped_id = content.lines().find_map(|line| line.trim().strip_prefix(PREFIX))?.trim();
+  if verifier.object_exists(mapped_id) {
+    return None;
+  }
+  let repair = match user_note_content(content) {
+    Some(user_note) => NoteRepair::Rewrite(user_note),
+    None => NoteRepair::Remove,
+  };
+  Some((MetadataIssueKind::DanglingMapping, format!("Mapped commit {mapped_id} does not exist"), repair))
+}
+
+/// Unparsable cache entries are dropped, the detection recomputes them
+fn check_detection_note(_verifier: &Verifier, content: &str) -> Option<(MetadataIssueKind, String, NoteRepair)> {
+  match parse_cached_note(content.trim()) {
+    Some(_) => None,
+    None => Some((MetadataIssueKind::InvalidCacheEntry, "Cache entry can't be parsed".to_string(), NoteRepair::Remove)),
+  }
+}
+
+/// Dependencies on missing commits are dropped, the note is removed when none are left
+fn check_dependencies_note(verifier: &Verifier, content: &str) -> Option<(MetadataIssueKind, String, NoteRepair)> {
+  let dependencies: Vec<&str> = content.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+  let (existing, missing): (Vec<&str>, Vec<&str>) = dependencies.into_iter().partition(|dependency| verifier.object_exists(dependency));
+  if missing.is_empty() {
+    return None;
+  }
+  let repair = if existing.is_empty() {
+    NoteRepair::Remove
+  } else {
+    NoteRepair::Rewrite(existing.join("\n"))
+  };
+  Some((MetadataIssueKind::DanglingDependency, format!("Dependencies {} do not exist", missing.join(", ")), repair))
+}
+
+/// Check Branch Deck metadata for entries referencing objects that no longer exist (e.g. after aggressive `git gc`
+/// or history rewrites done outside the app): sync mapping notes, detection cache notes, commit dependencies and the repository state.
+/// With `repair`, broken notes are removed or rewritten; the repository state is only reported.
+#[instrument(skip(git_executor), fields(repository_path = %params.repository_path, repair = params.repair))]
+pub fn verify_metadata_core(git_executor: &GitCommandExecutor, params: VerifyMetadataParams) -> Result<VerifyMetadataResult> {
+  let mut verifier = Verifier {
+    git_executor,
+    repository_path: &params.repository_path,
+    repair: params.repair,
+    checked_notes: 0,
+    issues: Vec::new(),
+  };
+
+  verifier.verify_notes(MAPPING_NOTES_REF, check_mapping_note)?;
+  verifier.verify_notes(DETECTION_NOTES_REF, check_detection_note)?;
+  verifier.verify_notes(DEPENDENCIES_NOTES_REF, check_dependencies_note)?;
+  verifier.verify_repo_state();
+
+  let repaired_count = verifier.issues.iter().filter(|issue| issue.repaired).count() as u32;
+  info!(
+    checked_notes = verifier.checked_notes,
+    issue_count = verifier.issues.len(),
+    repaired_count,
+    "Verified metadata"
+  );
+  Ok(VerifyMetadataResult {
+    checked_notes: verifier.checked_notes,
+    issues: verifier.issues,
+    repaired_count,
+  })
+}
@@ -0,0 +1,88 @@
+use crate::verify_metadata::{MAPPING_NOTES_REF, MetadataIssueKind, VerifyMetadataParams, verify_metadata_core};
+use branch_integration::cache::NOTES_REF as DETECTION_NOTES_REF;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::commit_dependencies::DEPENDENCIES_NOTES_REF;
+use pretty_assertions::assert_eq;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+const MISSING_COMMIT: &str = "1234567890123456789012345678901234567890";
+
+fn add_note(git_executor: &GitCommandExecutor, repo_path: &str, notes_ref: &str, object_id: &str, content: &str) {
+  git_executor
+    .execute_command(&["notes", "--ref", notes_ref, "add", "-f", "-m", content, object_id], repo_path)
+    .unwrap();
+}
+
+fn show_note(git_executor: &GitCommandExecutor, repo_path: &str, notes_ref: &str, object_id: &str) -> Option<String> {
+  let (output, exit_code) = git_executor
+    .execute_command_with_status(&["notes", "--ref", notes_ref, "show", object_id], repo_path)
+    .unwrap();
+  if exit_code == 0 { Some(output.trim().to_string()) } else { None }
+}
+
+fn verify(git_executor: &GitCommandExecutor, repo_path: &str, repair: bool) -> Vec<(String, String, MetadataIssueKind, bool)> {
+  let result = verify_metadata_core(
+    git_executor,
+    VerifyMetadataParams {
+      repository_path: repo_path.to_string(),
+      repair,
+    },
+  )
+  .unwrap();
+  let mut issues: Vec<_> = result
+    .issues
+    .into_iter()
+    .map(|issue| (issue.reference, issue.object_id.unwrap_or_default(), issue.kind, issue.repaired))
+    .collect();
+  issues.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+  issues
+}
+
+#[test]
+fn test_verify_and_repair_metadata() {
+  let repo = TestRepo::new();
+  let first = repo.create_commit("First commit", "a.txt", "a");
+  let second = repo.create_commit("Second commit", "b.txt", "b");
+  let repo_path = repo.path().to_str().unwrap();
+  let git_executor = GitCommandExecutor::new();
+
+  // Valid mapping, mapping to a collected commit with a user note, note of a collected commit
+  add_note(&git_executor, repo_path, MAPPING_NOTES_REF, &second, &format!("v-commit-v1:{first}"));
+  add_note(
+    &git_executor,
+    repo_path,
+    MAPPING_NOTES_REF,
+    &first,
+    &format!("v-commit-v1:{MISSING_COMMIT}\n\nReviewed by Alice"),
+  );
+  add_note(&git_executor, repo_path, DETECTION_NOTES_REF, MISSING_COMMIT, r#"{"s":{"k":"n"}}"#);
+  add_note(&git_executor, repo_path, DETECTION_NOTES_REF, &second, "not json");
+  add_note(&git_executor, repo_path, DEPENDENCIES_NOTES_REF, &second, &format!("{first}\n{MISSING_COMMIT}"));
+
+  let mut expected = vec![
+    (MAPPING_NOTES_REF.to_string(), first.clone(), MetadataIssueKind::DanglingMapping, false),
+    (DETECTION_NOTES_REF.to_string(), MISSING_COMMIT.to_string(), MetadataIssueKind::OrphanedNote, false),
+    (DETECTION_NOTES_REF.to_string(), second.clone(), MetadataIssueKind::InvalidCacheEntry, false),
+    (DEPENDENCIES_NOTES_REF.to_string(), second.clone(), MetadataIssueKind::DanglingDependency, false),
+  ];
+  expected.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+
+  // Report only
+  assert_eq!(verify(&git_executor, repo_path, false), expected);
+  assert_eq!(
+    show_note(&git_executor, repo_path, DEPENDENCIES_NOTES_REF, &second),
+    Some(format!("{first}\n{MISSING_COMMIT}"))
+  );
+
+  let repaired: Vec<_> = expected.into_iter().map(|(reference, object_id, kind, _)| (reference, object_id, kind, true)).collect();
+  assert_eq!(verify(&git_executor, repo_path, true), repaired);
+
+  // The user's note and the remaining dependency are kept
+  assert_eq!(show_note(&git_executor, repo_path, MAPPING_NOTES_REF, &first), Some("Reviewed by Alice".to_string()));
+  assert_eq!(show_note(&git_executor, repo_path, MAPPING_NOTES_REF, &second), Some(format!("v-commit-v1:{first}")));
+  assert_eq!(show_note(&git_executor, repo_path, DETECTION_NOTES_REF, &second), None);
+  assert_eq!(show_note(&git_executor, repo_path, DEPENDENCIES_NOTES_REF, &second), Some(first.clone()));
+
+  assert_eq!(verify(&git_executor, repo_path, false), Vec::new());
+}
@@ -0,0 +1,193 @@
+//! Notification of sync results to a webhook (Slack, Microsoft Teams or any endpoint accepting JSON).
+//!
+//! Opt-in per repository: the summary is only sent when `branchdeck.webhookUrl` is set. The payload format is taken
+//! from `branchdeck.webhookFormat` (`slack`, `teams` or `generic`), or derived from the URL host when not set.
+//! The summary is posted by the notifier in the `hosting-api` crate.
+
+use anyhow::Result;
+use git_ops::model::BranchSyncStatus;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use sync_types::branch_integration::BranchIntegrationStatus;
+use sync_types::{ProgressReporter, SyncEvent};
+
+/// Git config key with the URL the sync summary is posted to
+pub const WEBHOOK_URL_CONFIG_KEY: &str = "branchdeck.webhookUrl";
+/// Git config key with the payload format (`slack`, `teams` or `generic`)
+pub const WEBHOOK_FORMAT_CONFIG_KEY: &str = "branchdeck.webhookFormat";
+
+/// Payload format of the webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookFormat {
+  /// Slack incoming webhook: `{"text": ...}` with mrkdwn
+  Slack,
+  /// Microsoft Teams incoming webhook: `{"text": ...}` with markdown
+  Teams,
+  /// The [`SyncSummary`] as is
+  Generic,
+}
+
+impl WebhookFormat {
+  pub fn from_config_value(value: &str) -> Option<Self> {
+    match value.trim().to_ascii_lowercase().as_str() {
+      "slack" => Some(WebhookFormat::Slack),
+      "teams" => Some(WebhookFormat::Teams),
+      "generic" => Some(WebhookFormat::Generic),
+      _ => None,
+    }
+  }
+
+  /// Format matching the host of the webhook URL, `Generic` for unknown hosts
+  pub fn detect(host: &str) -> Self {
+    if host == "hooks.slack.com" {
+      WebhookFormat::Slack
+    } else if host.ends_with(".webhook.office.com") || host.ends_with(".logic.azure.com") {
+      WebhookFormat::Teams
+    } else {
+      WebhookFormat::Generic
+    }
+  }
+}
+
+/// Outcome of one sync run, collected from its events
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSummary {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  pub success: bool,
+  pub error: Option<String>,
+  pub created_branches: Vec<String>,
+  pub updated_branches: Vec<String>,
+  /// Branches that failed to sync, mostly because of merge conflicts
+  pub failed_branches: Vec<String>,
+  /// Branches whose commits are all in the baseline
+  pub integrated_branches: Vec<String>,
+}
+
+impl SyncSummary {
+  pub fn new(repository_path: &str, branch_prefix: &str) -> Self {
+    Self {
+      repository_path: repository_path.to_string(),
+      branch_prefix: branch_prefix.to_string(),
+      ..Default::default()
+    }
+  }
+
+  fn add_event(&mut self, event: &SyncEvent) {
+    match event {
+      SyncEvent::BranchStatusUpdate { branch_name, status, .. } => {
+        let branches = match status {
+          BranchSyncStatus::Created => &mut self.created_branches,
+          BranchSyncStatus::Updated => &mut self.updated_branches,
+          BranchSyncStatus::Error | BranchSyncStatus::MergeConflict => &mut self.failed_branches,
+          BranchSyncStatus::Integrated => &mut self.integrated_branches,
+          BranchSyncStatus::Unchanged | BranchSyncStatus::AnalyzingConflict => return,
+        };
+        push_unique(branches, branch_name);
+      }
+      SyncEvent::BranchIntegrationDetected { info } if matches!(info.status, BranchIntegrationStatus::Integrated { .. }) => {
+        push_unique(&mut self.integrated_branches, &info.name);
+      }
+      SyncEvent::BackgroundPhaseCompleted { phase, error: Some(error) } => {
+        self.error.get_or_insert_with(|| format!("{phase:?} failed in the background: {error}"));
+      }
+      _ => {}
+    }
+  }
+
+  /// One-line description, e.g. "2 created, 1 updated, 1 failed"
+  pub fn headline(&self) -> String {
+    if !self.success {
+      return format!("Sync failed: {}", self.error.as_deref().unwrap_or("unknown error"));
+    }
+    let counts: Vec<String> = [
+      (self.created_branches.len(), "created"),
+      (self.updated_branches.len(), "updated"),
+      (self.failed_branches.len(), "failed"),
+      (self.integrated_branches.len(), "integrated"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, label)| format!("{count} {label}"))
+    .collect();
+    if counts.is_empty() {
+      "Sync finished, no branch changed".to_string()
+    } else {
+      format!("Sync finished: {}", counts.join(", "))
+    }
+  }
+}
+
+fn push_unique(branches: &mut Vec<String>, branch_name: &str) {
+  if !branches.iter().any(|name| name == branch_name) {
+    branches.push(branch_name.to_string());
+  }
+}
+
+/// Receiver of the [`SyncSummary`] of a sync, see [`crate::sync::SyncOptions::summary_notifier`]. Called once the phases
+/// continuing in the background are done too, it must not block: the next sync of the repository waits for it.
+pub trait SyncSummaryNotifier: Send + Sync {
+  fn notify(&self, summary: SyncSummary);
+}
+
+/// Progress reporter forwarding events to another reporter while collecting the [`SyncSummary`]
+#[derive(Clone)]
+pub struct SyncSummaryCollector<P: ProgressReporter> {
+  inner: P,
+  summary: Arc<Mutex<SyncSummary>>,
+}
+
+impl<P: ProgressReporter> SyncSummaryCollector<P> {
+  pub fn new(inner: P, summary: SyncSummary) -> Self {
+    Self {
+      inner,
+      summary: Arc::new(Mutex::new(summary)),
+    }
+  }
+
+  /// Summary with the outcome of the sync, failed if a phase continuing in the background failed
+  pub fn finish(&self, result: &Result<()>) -> SyncSummary {
+    let mut summary = self.summary.lock().unwrap().clone();
+    if let Err(e) = result {
+      summary.error = Some(format!("{e:#}"));
+    }
+    summary.success = summary.error.is_none();
+    summary
+  }
+}
+
+impl<P: ProgressReporter> ProgressReporter for SyncSummaryCollector<P> {
+  fn send(&self, event: SyncEvent) -> Result<()> {
+    self.summary.lock().unwrap().add_event(&event);
+    self.inner.send(event)
+  }
+}
+
+/// Body posted to the webhook for the summary
+pub fn summary_payload(format: WebhookFormat, summary: &SyncSummary) -> Value {
+  match format {
+    WebhookFormat::Generic => json!(summary),
+    WebhookFormat::Slack | WebhookFormat::Teams => {
+      let bold = if format == WebhookFormat::Slack { "*" } else { "**" };
+      let mut lines = vec![format!("{bold}{}{bold} ({})", summary.headline(), summary.repository_path)];
+      for (label, branches) in [
+        ("Created", &summary.created_branches),
+        ("Updated", &summary.updated_branches),
+        ("Failed", &summary.failed_branches),
+        ("Integrated", &summary.integrated_branches),
+      ] {
+        if !branches.is_empty() {
+          lines.push(format!("{label}: {}", branches.join(", ")));
+        }
+      }
+      // Teams markdown needs an empty line for a line break
+      let separator = if format == WebhookFormat::Slack { "\n" } else { "\n\n" };
+      json!({ "text": lines.join(separator) })
+    }
+  }
+}
@@ -0,0 +1,103 @@
+use crate::phase_budget::{PhaseTimeBudgets, wait_for_background_phases};
+use crate::sync::{SyncOptions, sync_branches};
+use crate::webhook::{SyncSummary, SyncSummaryCollector, SyncSummaryNotifier, WebhookFormat, summary_payload};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sync_test_utils::TestReporter;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+
+fn summary() -> SyncSummary {
+  SyncSummary {
+    success: true,
+    created_branches: vec!["feature".to_string()],
+    failed_branches: vec!["fix".to_string()],
+    ..SyncSummary::new("/repo", "user")
+  }
+}
+
+#[test]
+fn test_detect_format() {
+  assert_eq!(WebhookFormat::detect("hooks.slack.com"), WebhookFormat::Slack);
+  assert_eq!(WebhookFormat::detect("acme.webhook.office.com"), WebhookFormat::Teams);
+  assert_eq!(WebhookFormat::detect("example.com"), WebhookFormat::Generic);
+}
+
+#[test]
+fn test_summary_payload() {
+  assert_eq!(
+    summary_payload(WebhookFormat::Slack, &summary())["text"],
+    "*Sync finished: 1 created, 1 failed* (/repo)\nCreated: feature\nFailed: fix"
+  );
+  assert_eq!(
+    summary_payload(WebhookFormat::Generic, &summary()),
+    serde_json::json!({
+      "repositoryPath": "/repo",
+      "branchPrefix": "user",
+      "success": true,
+      "error": null,
+      "createdBranches": ["feature"],
+      "updatedBranches": [],
+      "failedBranches": ["fix"],
+      "integratedBranches": [],
+    })
+  );
+}
+
+#[test(tokio::test)]
+async fn test_sync_summary_collector() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+  let repository_path = test_repo.path().to_str().unwrap();
+
+  let collector = SyncSummaryCollector::new(TestReporter::new(), SyncSummary::new(repository_path, "test"));
+  let result = sync_branches(&GitCommandExecutor::new(), repository_path, "test", collector.clone(), SyncOptions::default()).await;
+
+  let summary = collector.finish(&result);
+  assert!(summary.success);
+  assert_eq!(summary.created_branches, vec!["feature".to_string()]);
+  assert_eq!(summary.failed_branches, Vec::<String>::new());
+  Ok(())
+}
+
+/// Keeps the summaries it is notified of
+#[derive(Default)]
+struct RecordingNotifier {
+  summaries: Mutex<Vec<SyncSummary>>,
+}
+
+impl SyncSummaryNotifier for RecordingNotifier {
+  fn notify(&self, summary: SyncSummary) {
+    self.summaries.lock().unwrap().push(summary);
+  }
+}
+
+#[test(tokio::test)]
+async fn test_summary_notified_after_background_phases() -> anyhow::Result<()> {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit("(feature) Add feature", "feature.txt", "feature");
+  let repository_path = test_repo.path().to_str().unwrap();
+
+  let notifier = Arc::new(RecordingNotifier::default());
+  let options = SyncOptions {
+    // Both phases continue in the background, the branch is created after the sync returned
+    phase_time_budgets: Some(PhaseTimeBudgets {
+      branch_sync: Some(Duration::ZERO),
+      integration_detection: Some(Duration::ZERO),
+    }),
+    summary_notifier: Some(notifier.clone()),
+    ..Default::default()
+  };
+  sync_branches(&GitCommandExecutor::new(), repository_path, "test", TestReporter::new(), options).await?;
+  wait_for_background_phases(repository_path).await;
+
+  let summaries = notifier.summaries.lock().unwrap().clone();
+  assert_eq!(summaries.len(), 1);
+  assert!(summaries[0].success, "{:?}", summaries[0].error);
+  assert_eq!(summaries[0].created_branches, vec!["feature".to_string()]);
+  Ok(())
+}
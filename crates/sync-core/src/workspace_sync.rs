@@ -0,0 +1,139 @@
+//! Sync of a workspace: a group of repositories synced one after another, with the events of every sync sent over a
+//! single stream and tagged by the repository they come from.
+//!
+//! Repositories are synced one at a time, a sync already runs several git processes in parallel.
+
+use crate::branch_prefix::resolve_branch_prefix;
+use crate::sync::{SyncOptions, sync_branches};
+use crate::webhook::{SyncSummary, SyncSummaryCollector};
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use sync_types::{ProgressReporter, SyncEvent};
+use tracing::{info, instrument, warn};
+
+/// Member of a workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceRepository {
+  pub repository_path: String,
+  /// Branch prefix for this repository, `branchdeck.branchPrefix` from its git config when not set
+  pub branch_prefix: Option<String>,
+}
+
+/// Progress events of a workspace sync
+#[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+pub enum WorkspaceSyncEvent {
+  /// Sent before the sync of a repository starts
+  #[serde(rename_all = "camelCase")]
+  RepositorySyncStarted { repository_path: String, branch_prefix: String },
+  /// Event of the sync of a repository
+  #[serde(rename_all = "camelCase")]
+  RepositorySyncEvent { repository_path: String, event: SyncEvent },
+  /// Sent after the sync of a repository, whether it succeeded or not
+  RepositorySyncFinished { summary: SyncSummary },
+}
+
+/// Receiver of the events of a workspace sync, like [`ProgressReporter`] for the sync of a single repository
+pub trait WorkspaceProgressReporter: Send + Sync {
+  fn send(&self, event: WorkspaceSyncEvent) -> Result<()>;
+}
+
+/// Reporter of the sync of one workspace member, tags its events with the repository
+#[derive(Clone)]
+pub struct RepositoryProgressReporter<W: WorkspaceProgressReporter> {
+  workspace: W,
+  repository_path: String,
+}
+
+impl<W: WorkspaceProgressReporter> ProgressReporter for RepositoryProgressReporter<W> {
+  fn send(&self, event: SyncEvent) -> Result<()> {
+    self.workspace.send(WorkspaceSyncEvent::RepositorySyncEvent {
+      repository_path: self.repository_path.clone(),
+      event,
+    })
+  }
+}
+
+/// Sync a repository and collect the [`SyncSummary`] of the run
+pub async fn sync_with_summary<P: ProgressReporter + Clone + 'static>(
+  git_executor: &GitCommandExecutor,
+  repository_path: &str,
+  branch_prefix: &str,
+  progress: P,
+  options: SyncOptions,
+) -> (SyncSummary, Result<()>) {
+  let collector = SyncSummaryCollector::new(progress, SyncSummary::new(repository_path, branch_prefix));
+  let result = sync_branches(git_executor, repository_path, branch_prefix, collector.clone(), options).await;
+  (collector.finish(&result), result)
+}
+
+/// Sync every repository of a workspace with `sync_repository`, which gets the repository path, the branch prefix and
+/// the reporter of the repository and returns the summary of the run.
+/// A failed repository doesn't stop the others, its error is in its summary.
+#[instrument(skip(git_executor, repositories, progress, sync_repository), fields(repository_count = repositories.len()))]
+pub async fn sync_workspace<W, F, Fut>(git_executor: &GitCommandExecutor, repositories: &[WorkspaceRepository], progress: W, mut sync_repository: F) -> Vec<SyncSummary>
+where
+  W: WorkspaceProgressReporter + Clone + 'static,
+  F: FnMut(String, String, RepositoryProgressReporter<W>) -> Fut,
+  Fut: Future<Output = SyncSummary>,
+{
+  let mut summaries = Vec::with_capacity(repositories.len());
+  for repository in repositories {
+    let repository_path = &repository.repository_path;
+    let summary = match workspace_branch_prefix(git_executor, repository) {
+      Ok(branch_prefix) => {
+        send_workspace_event(
+          &progress,
+          WorkspaceSyncEvent::RepositorySyncStarted {
+            repository_path: repository_path.clone(),
+            branch_prefix: branch_prefix.clone(),
+          },
+        );
+        let reporter = RepositoryProgressReporter {
+          workspace: progress.clone(),
+          repository_path: repository_path.clone(),
+        };
+        sync_repository(repository_path.clone(), branch_prefix, reporter).await
+      }
+      Err(e) => SyncSummary {
+        error: Some(format!("{e:#}")),
+        ..SyncSummary::new(repository_path, "")
+      },
+    };
+
+    if let Some(error) = &summary.error {
+      warn!(repository_path, error, "Sync of workspace repository failed");
+    }
+    send_workspace_event(&progress, WorkspaceSyncEvent::RepositorySyncFinished { summary: summary.clone() });
+    summaries.push(summary);
+  }
+
+  info!(failed = summaries.iter().filter(|summary| !summary.success).count(), "Workspace sync finished");
+  summaries
+}
+
+fn workspace_branch_prefix(git_executor: &GitCommandExecutor, repository: &WorkspaceRepository) -> Result<String> {
+  if let Some(branch_prefix) = repository.branch_prefix.as_deref().map(str::trim).filter(|prefix| !prefix.is_empty()) {
+    return Ok(branch_prefix.to_string());
+  }
+  let prefix = resolve_branch_prefix(git_executor, &repository.repository_path)?.prefix;
+  if prefix.is_empty() {
+    return Err(anyhow!(
+      "No branch prefix configured for {}, set branchdeck.branchPrefix in its git config",
+      repository.repository_path
+    ));
+  }
+  Ok(prefix)
+}
+
+fn send_workspace_event<W: WorkspaceProgressReporter>(progress: &W, event: WorkspaceSyncEvent) {
+  // The sync goes on even if nobody listens anymore
+  if let Err(e) = progress.send(event) {
+    warn!(error = %e, "Failed to send workspace sync event");
+  }
+}
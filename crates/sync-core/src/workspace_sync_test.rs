@@ -0,0 +1,103 @@
+use crate::sync::SyncOptions;
+use crate::workspace_sync::{WorkspaceProgressReporter, WorkspaceRepository, WorkspaceSyncEvent, sync_with_summary, sync_workspace};
+use git_executor::git_command_executor::GitCommandExecutor;
+use pretty_assertions::assert_eq;
+use sync_types::SyncEvent;
+use test_log::test;
+use test_utils::git_test_utils::TestRepo;
+use test_utils::progress_reporter::TestProgressReporter;
+
+impl WorkspaceProgressReporter for TestProgressReporter<WorkspaceSyncEvent> {
+  fn send(&self, event: WorkspaceSyncEvent) -> anyhow::Result<()> {
+    self.push_event(event);
+    Ok(())
+  }
+}
+
+fn repository_with_branch(branch_name: &str) -> TestRepo {
+  let test_repo = TestRepo::new();
+  test_repo.create_commit("Initial commit", "README.md", "# Test");
+  test_repo.create_commit(&format!("({branch_name}) Add {branch_name}"), &format!("{branch_name}.txt"), branch_name);
+  test_repo
+}
+
+#[test(tokio::test)]
+async fn test_sync_workspace_tags_events_by_repository() {
+  let first = repository_with_branch("auth");
+  let second = repository_with_branch("cache");
+  second
+    .git_executor()
+    .execute_command(&["config", "branchdeck.branchPrefix", "from-config"], second.path().to_str().unwrap())
+    .unwrap();
+  let first_path = first.path().to_str().unwrap().to_string();
+  let second_path = second.path().to_str().unwrap().to_string();
+  let missing_path = first.path().join("missing").to_str().unwrap().to_string();
+
+  let git_executor = GitCommandExecutor::new();
+  let repositories = vec![
+    WorkspaceRepository {
+      repository_path: first_path.clone(),
+      branch_prefix: Some("user".to_string()),
+    },
+    WorkspaceRepository {
+      repository_path: missing_path.clone(),
+      branch_prefix: Some("user".to_string()),
+    },
+    WorkspaceRepository {
+      repository_path: second_path.clone(),
+      branch_prefix: None,
+    },
+  ];
+  let progress = TestProgressReporter::new();
+  let summaries = sync_workspace(&git_executor, &repositories, progress.clone(), |repository_path, branch_prefix, reporter| {
+    let git_executor = git_executor.clone();
+    async move { sync_with_summary(&git_executor, &repository_path, &branch_prefix, reporter, SyncOptions::default()).await.0 }
+  })
+  .await;
+
+  // A failed repository doesn't stop the others
+  let outcomes: Vec<(&str, &str, bool, Vec<String>)> = summaries
+    .iter()
+    .map(|summary| {
+      (
+        summary.repository_path.as_str(),
+        summary.branch_prefix.as_str(),
+        summary.success,
+        summary.created_branches.clone(),
+      )
+    })
+    .collect();
+  assert_eq!(
+    outcomes,
+    vec![
+      (first_path.as_str(), "user", true, vec!["auth".to_string()]),
+      (missing_path.as_str(), "user", false, vec![]),
+      (second_path.as_str(), "from-config", true, vec!["cache".to_string()]),
+    ]
+  );
+  assert!(summaries[1].error.is_some());
+  assert!(first.branch_exists("user/virtual/auth"));
+  assert!(second.branch_exists("from-config/virtual/cache"));
+
+  let events = progress.get_events();
+  let grouped_in: Vec<&str> = events
+    .iter()
+    .filter_map(|event| match event {
+      WorkspaceSyncEvent::RepositorySyncEvent {
+        repository_path,
+        event: SyncEvent::BranchesGrouped { .. },
+      } => Some(repository_path.as_str()),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(grouped_in, vec![first_path.as_str(), second_path.as_str()]);
+  assert!(matches!(events.first(), Some(WorkspaceSyncEvent::RepositorySyncStarted { repository_path, .. }) if *repository_path == first_path));
+  let finished: Vec<&str> = events
+    .iter()
+    .filter_map(|event| match event {
+      WorkspaceSyncEvent::RepositorySyncFinished { summary } => Some(summary.repository_path.as_str()),
+      _ => None,
+    })
+    .collect();
+  assert_eq!(finished, vec![first_path.as_str(), missing_path.as_str(), second_path.as_str()]);
+}
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 pub enum IntegrationConfidence {
   Exact, // Git confirms via branch --merged (100% confident)
   High,  // Cherry-pick detection found (90% confident - likely rebase)
+  Low,   // The changes of each commit were found in the baseline, but not the commits that brought them in, or the matched commits only mostly agree
 }
 
 impl PartialOrd for IntegrationConfidence {
@@ -17,12 +18,12 @@ impl PartialOrd for IntegrationConfidence {
 impl Ord for IntegrationConfidence {
   fn cmp(&self, other: &Self) -> std::cmp::Ordering {
     use IntegrationConfidence::*;
-    match (self, other) {
-      (Exact, Exact) => std::cmp::Ordering::Equal,
-      (Exact, High) => std::cmp::Ordering::Greater,
-      (High, Exact) => std::cmp::Ordering::Less,
-      (High, High) => std::cmp::Ordering::Equal,
-    }
+    let rank = |confidence: &Self| match confidence {
+      Exact => 2,
+      High => 1,
+      Low => 0,
+    };
+    rank(self).cmp(&rank(other))
   }
 }
 
@@ -50,6 +51,44 @@ pub enum BranchIntegrationStatus {
   Partial { missing: u32 },
 }
 
+/// How branch commits were matched to baseline history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum IntegrationStrategy {
+  /// The branch tip is reachable from the baseline (merge or fast-forward)
+  Merge,
+  /// Every branch commit has a patch-equivalent commit in the baseline
+  Rebase,
+  /// Only some branch commits have patch-equivalent commits in the baseline
+  PatchId,
+  /// The baseline contains the combined changes of the branch
+  Squash,
+  /// Branch commits match baseline commits by changed lines, ignoring whitespace, line offsets and context
+  FuzzyPatch,
+}
+
+/// Baseline commit that integrated branch changes
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedCommit {
+  pub id: String,
+  pub subject: String,
+  pub committed_at: u32,
+}
+
+/// Why a branch got its integration status, so the user can verify it before deleting the branch
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrationEvidence {
+  pub strategy: IntegrationStrategy,
+  /// Matched baseline commits, newest first. Capped, so it can be shorter than the integrated commit count.
+  /// Empty for a fast-forward merge or when only the content matched.
+  pub matched_commits: Vec<MatchedCommit>,
+}
+
 /// Unified branch integration info
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "specta", derive(specta::Type))]
@@ -58,4 +97,15 @@ pub struct BranchIntegrationInfo {
   pub name: String,
   pub summary: String,
   pub status: BranchIntegrationStatus,
+  /// Baseline history outside the integration lookback window wasn't searched,
+  /// so commits integrated long ago may be reported as orphaned
+  #[serde(default)]
+  pub lookback_limited: bool,
+  /// The detection budget ran out before the deep detection steps, so the status may miss integrated commits.
+  /// Never cached; resuming detection of the branch runs the full analysis.
+  #[serde(default)]
+  pub detection_truncated: bool,
+  /// `None` if no baseline commit matched, or for results cached by older versions
+  #[serde(default)]
+  pub evidence: Option<IntegrationEvidence>,
 }
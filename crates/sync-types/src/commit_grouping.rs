@@ -0,0 +1,45 @@
+//! Grouping of unassigned commits into branches proposed by the model, see `sync_core::commit_grouping`
+
+use serde::{Deserialize, Serialize};
+
+/// Commit without a branch prefix, as presented to the model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct UngroupedCommit {
+  pub id: String,
+  pub subject: String,
+  pub files: Vec<String>,
+}
+
+/// Everything the model needs to propose a grouping
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct CommitGroupingInput {
+  /// Unassigned commits, oldest first
+  pub commits: Vec<UngroupedCommit>,
+  /// Branch names already used as commit prefixes
+  pub existing_branches: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedCommitGroup {
+  pub branch_name: String,
+  /// Commits are added to an existing branch rather than creating a new one
+  pub is_existing_branch: bool,
+  /// Commits in history order, ready for `create_branch_from_commits`
+  pub commit_ids: Vec<String>,
+}
+
+/// Proposed assignment of unassigned commits to branch prefixes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct CommitGroupingPlan {
+  pub groups: Vec<SuggestedCommitGroup>,
+  /// Commits the model didn't assign to any branch
+  pub ungrouped_commit_ids: Vec<String>,
+}
@@ -0,0 +1,116 @@
+use crate::{PrefixTypo, ProgressReporter, RemoteStatusUpdate, SyncEvent};
+use git_ops::model::BranchSyncStatus;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Which sync events a subscriber receives, e.g. a sub-window only interested in the conflicts of one branch
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEventFilter {
+  /// Only events of these virtual branches (e.g. "feature-auth"); `BranchesGrouped` is narrowed to them
+  /// and repository-wide events are dropped. `None` keeps events of all branches.
+  #[serde(default)]
+  pub branch_names: Option<Vec<String>>,
+  /// Only commit errors, blocked commits and failed or conflicted branch statuses
+  #[serde(default)]
+  pub errors_only: bool,
+}
+
+impl SyncEventFilter {
+  /// Whether the filter lets every event through
+  pub fn is_empty(&self) -> bool {
+    self.branch_names.is_none() && !self.errors_only
+  }
+
+  fn includes_branch(&self, branch_name: &str) -> bool {
+    self.branch_names.as_ref().is_none_or(|names| names.iter().any(|name| name == branch_name))
+  }
+
+  /// Whether the subscriber receives the event
+  pub fn accepts(&self, event: &SyncEvent) -> bool {
+    if self.is_empty() {
+      return true;
+    }
+
+    match event {
+      SyncEvent::BranchesGrouped { .. } => !self.errors_only,
+      SyncEvent::CommitSynced { branch_name, .. }
+      | SyncEvent::BranchAutoArchived { branch_name, .. }
+      | SyncEvent::RemoteStatusUpdate(RemoteStatusUpdate { branch_name, .. })
+      | SyncEvent::PossiblePrefixTypo(PrefixTypo { branch_name, .. }) => !self.errors_only && self.includes_branch(branch_name),
+      SyncEvent::CommitError { branch_name, .. } | SyncEvent::CommitsBlocked { branch_name, .. } => self.includes_branch(branch_name),
+      SyncEvent::BranchStatusUpdate { branch_name, status, .. } => self.includes_branch(branch_name) && (!self.errors_only || is_error_status(status)),
+      // Repository-wide events (issue config, unassigned commits, grouping diagnostics, archived branches, background phases, operation plans, auto-stash, performance reports, baseline updates, fetch, network status) are only sent to unfiltered subscribers
+      SyncEvent::IssueNavigationConfig { .. }
+      | SyncEvent::HistoryDeepening { .. }
+      | SyncEvent::UnassignedCommits { .. }
+      | SyncEvent::GroupingDiagnostics { .. }
+      | SyncEvent::BranchIntegrationDetected { .. }
+      | SyncEvent::ArchivedBranchesFound { .. }
+      | SyncEvent::ArchivedRemoteStatus { .. }
+      | SyncEvent::PhaseContinuesInBackground { .. }
+      | SyncEvent::BackgroundPhaseCompleted { .. }
+      | SyncEvent::PlanOperationStarted { .. }
+      | SyncEvent::PlanOperationCompleted { .. }
+      | SyncEvent::UncommittedChangesStashed { .. }
+      | SyncEvent::UncommittedChangesRestored { .. }
+      | SyncEvent::SyncPerformanceReport { .. }
+      | SyncEvent::BaselineAdvanced(_)
+      | SyncEvent::FetchProgress { .. }
+      | SyncEvent::FetchCompleted { .. }
+      | SyncEvent::NetworkStatusChanged { .. } => false,
+    }
+  }
+
+  /// Return the event as the subscriber should see it, or `None` if it is filtered out
+  pub fn apply(&self, event: SyncEvent) -> Option<SyncEvent> {
+    if !self.accepts(&event) {
+      return None;
+    }
+
+    Some(match event {
+      SyncEvent::BranchesGrouped {
+        branches,
+        baseline_branch,
+        commit_filter,
+      } if self.branch_names.is_some() => SyncEvent::BranchesGrouped {
+        branches: branches.into_iter().filter(|branch| self.includes_branch(&branch.name)).collect(),
+        baseline_branch,
+        commit_filter,
+      },
+      event => event,
+    })
+  }
+}
+
+fn is_error_status(status: &BranchSyncStatus) -> bool {
+  matches!(status, BranchSyncStatus::Error | BranchSyncStatus::MergeConflict)
+}
+
+/// Progress reporter wrapper that drops events not matching a filter before they reach the inner reporter,
+/// so filtered-out events are never serialized
+#[derive(Clone)]
+pub struct FilteredProgressReporter<P: ProgressReporter> {
+  inner: P,
+  filter: Arc<SyncEventFilter>,
+}
+
+impl<P: ProgressReporter> FilteredProgressReporter<P> {
+  pub fn new(inner: P, filter: SyncEventFilter) -> Self {
+    Self { inner, filter: Arc::new(filter) }
+  }
+
+  pub fn filter(&self) -> &SyncEventFilter {
+    &self.filter
+  }
+}
+
+impl<P: ProgressReporter> ProgressReporter for FilteredProgressReporter<P> {
+  fn send(&self, event: SyncEvent) -> anyhow::Result<()> {
+    match self.filter.apply(event) {
+      Some(event) => self.inner.send(event),
+      None => Ok(()),
+    }
+  }
+}
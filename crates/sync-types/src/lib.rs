@@ -1,9 +1,11 @@
 use crate::issue_navigation::IssueNavigationConfig;
-use git_ops::commit_list::Commit;
+use git_ops::commit_list::{Commit, CommitFilter};
 use git_ops::model::{BranchError, BranchSyncStatus, CommitSyncStatus};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 pub mod branch_integration;
+pub mod commit_grouping;
+pub mod event_filter;
 pub mod issue_navigation;
 
 /// Remote branch status information
@@ -19,6 +21,118 @@ pub struct RemoteStatusUpdate {
   pub my_unpushed_count: u32,
   /// Last time this branch was pushed to the remote (Unix timestamp, 0 = never pushed)
   pub last_push_time: u32,
+  /// Review activity on the open pull request of the pushed branch.
+  /// Only the background refresh queries the hosting provider, and only with `branchdeck.reviewStatus` enabled.
+  pub review: Option<ReviewSummary>,
+  /// CI status of the pushed head commit.
+  /// Only the background refresh queries the hosting provider, and only with `branchdeck.ciStatus` enabled.
+  pub ci_status: Option<CiStatus>,
+}
+
+/// Combined state of the CI checks (GitHub) or of the latest pipeline (GitLab) of a commit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum CiState {
+  /// Checks are queued or running
+  Pending,
+  Success,
+  Failure,
+  /// The pipeline was canceled or skipped (GitLab)
+  Cancelled,
+}
+
+impl CiState {
+  /// Whether the state can't change anymore without a new run
+  pub fn is_final(self) -> bool {
+    self != CiState::Pending
+  }
+}
+
+/// CI status of the head commit of a pushed branch
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct CiStatus {
+  pub commit_hash: String,
+  pub state: CiState,
+  /// Names of the failed checks (GitHub) or jobs (GitLab)
+  pub failed_checks: Vec<String>,
+  /// Page with the details: the first failed check, the pipeline, or the checks of the commit
+  pub url: String,
+}
+
+/// Review activity on the open pull request (GitHub) or merge request (GitLab) of a branch
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewSummary {
+  /// Pull request number, merge request IID on GitLab
+  pub number: u64,
+  pub url: String,
+  /// Comments on the pull request and in review threads
+  pub comment_count: u32,
+  pub unresolved_thread_count: u32,
+  /// Reviewers whose latest review approves the changes
+  pub approval_count: u32,
+  /// `None` if the provider doesn't tell, e.g. no approval rules on GitLab
+  pub review_decision: Option<ReviewDecision>,
+}
+
+/// Whether a pull request can be merged as far as reviews are concerned
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum ReviewDecision {
+  Approved,
+  ChangesRequested,
+  /// More approvals are required
+  ReviewRequired,
+}
+
+/// Remote branch still present for an archived branch
+#[derive(Clone, Debug, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedRemoteStatus {
+  /// Archived branch (e.g., "user/archived/2025-08-11/feature")
+  pub branch_name: String,
+  /// Branch on the remote without the remote name (e.g., "user/virtual/feature")
+  pub remote_branch: String,
+  /// Whether the remote branch points at the archived commit (nothing would be lost by deleting it)
+  pub same_commit: bool,
+}
+
+/// Baseline branch that advanced on the remote since the last successful sync
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineAdvanced {
+  /// Remote-tracking branch (e.g., "origin/master")
+  pub baseline_branch: String,
+  /// Baseline commit of the last successful sync
+  pub last_sync_baseline: String,
+  pub remote_head: String,
+  /// Commits on the remote baseline that the last sync didn't include
+  pub new_commit_count: u32,
+}
+
+/// Whether network requests are made, see `sync_core::network`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum NetworkStatus {
+  Online,
+  /// No network connection was detected
+  Offline,
+  /// Offline mode was switched on by the user
+  OfflineMode,
+}
+
+impl NetworkStatus {
+  pub fn is_offline(self) -> bool {
+    self != NetworkStatus::Online
+  }
 }
 
 /// Progress events for sync operations
@@ -28,15 +142,28 @@ pub struct RemoteStatusUpdate {
 pub enum SyncEvent {
   /// Sent at the beginning with issue navigation configuration if found
   IssueNavigationConfig { config: Option<IssueNavigationConfig> },
+  /// Sent before fetching more history of a shallow clone to reach the merge base with the baseline
+  #[serde(rename_all = "camelCase")]
+  HistoryDeepening {
+    remote: String,
+    /// Number of commits to fetch beyond the shallow boundary, `None` when fetching the whole history
+    deepen_by: Option<u32>,
+  },
   /// Sent immediately after grouping commits
   #[serde(rename_all = "camelCase")]
   BranchesGrouped {
     branches: Vec<GroupedBranchInfo>,
     /// Repository's baseline branch (e.g., "origin/master", "master")
     baseline_branch: String,
+    /// Filter selecting the rebuilt branches, `None` when all branches were synced
+    commit_filter: Option<CommitFilter>,
   },
   /// Sent for commits that don't match any prefix pattern
   UnassignedCommits { commits: Vec<Commit> },
+  /// Sent after grouping with the commits whose subject could have been grouped differently (empty if none)
+  GroupingDiagnostics { diagnostics: Vec<GroupingDiagnostic> },
+  /// Sent after grouping for each branch whose name is probably a misspelling of another one
+  PossiblePrefixTypo(PrefixTypo),
   /// Sent when a commit is successfully cherry-picked
   #[serde(rename_all = "camelCase")]
   CommitSynced {
@@ -65,9 +192,99 @@ pub enum SyncEvent {
   /// Sent immediately when archived branches are found (before expensive detection)
   #[serde(rename_all = "camelCase")]
   ArchivedBranchesFound { branch_names: Vec<String> },
+  /// Sent when a branch fully integrated for the configured number of consecutive syncs is archived automatically
+  #[serde(rename_all = "camelCase")]
+  BranchAutoArchived {
+    branch_name: String,
+    /// Name of the archived branch, `None` if there was no virtual branch to archive
+    archived_branch: Option<String>,
+    /// Consecutive syncs the branch was found fully integrated
+    integrated_sync_count: u32,
+    /// Commits of the branch dropped from HEAD, 0 unless pruning is enabled
+    pruned_commit_count: u32,
+  },
   /// Sent when remote branch status is checked
   #[serde(rename_all = "camelCase")]
   RemoteStatusUpdate(RemoteStatusUpdate),
+  /// Sent after archiving when remote status collection for archived branches is enabled
+  #[serde(rename_all = "camelCase")]
+  ArchivedRemoteStatus { branches: Vec<ArchivedRemoteStatus> },
+  /// Sent when a phase exceeds its time budget: the results sent so far are partial,
+  /// the sync returns and the phase continues in the background
+  #[serde(rename_all = "camelCase")]
+  PhaseContinuesInBackground { phase: SyncPhase, budget_ms: u64 },
+  /// Sent when a phase that continued in the background is done
+  #[serde(rename_all = "camelCase")]
+  BackgroundPhaseCompleted { phase: SyncPhase, error: Option<String> },
+  /// Sent before each operation of an operation plan; events of the operation itself (e.g., a sync) follow
+  #[serde(rename_all = "camelCase")]
+  PlanOperationStarted {
+    /// 0-based position of the operation in the plan
+    index: u32,
+    total: u32,
+    description: String,
+  },
+  /// Sent after each executed operation of an operation plan, the plan stops at the first error
+  #[serde(rename_all = "camelCase")]
+  PlanOperationCompleted { index: u32, error: Option<String> },
+  /// Sent before the sync when uncommitted changes of tracked files were stashed (see `sync_core::auto_stash`)
+  #[serde(rename_all = "camelCase")]
+  UncommittedChangesStashed { stash_commit: String, file_count: u32 },
+  /// Sent after the sync when the stashed changes were brought back
+  #[serde(rename_all = "camelCase")]
+  UncommittedChangesRestored {
+    stash_commit: String,
+    /// Files conflicting with the new HEAD; if any, nothing was restored and the changes are kept in `refs/branch-deck/autostash`
+    conflicting_files: Vec<String>,
+  },
+  /// Sent at the end of every sync run, successful or not; the report is also kept in the sync history
+  SyncPerformanceReport { report: SyncPerformanceReport },
+  /// Sent by the remote status refresh when the baseline advanced on the remote since the last sync, once per new remote head
+  BaselineAdvanced(BaselineAdvanced),
+  /// Sent when the phase or percentage of the fetch before the sync changes (see `sync_core::auto_fetch`)
+  #[serde(rename_all = "camelCase")]
+  FetchProgress {
+    /// Like "Receiving objects", without the "remote: " prefix of server-side phases
+    phase: String,
+    percent: Option<u32>,
+  },
+  /// Sent when the fetch before the sync is done. On error the sync goes on offline with the remote-tracking refs fetched earlier.
+  #[serde(rename_all = "camelCase")]
+  FetchCompleted { error: Option<String> },
+  /// Sent by the remote status refresh when the network status changes; while offline the refresh is skipped
+  #[serde(rename_all = "camelCase")]
+  NetworkStatusChanged { status: NetworkStatus },
+}
+
+/// Durations of the phases of one sync run, in milliseconds
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct SyncPerformanceReport {
+  pub total_ms: u64,
+  /// Reading commits ahead of the baseline and grouping them into branches
+  pub grouping_ms: u64,
+  /// Rebuilding the virtual branches, `None` if the phase continued in the background
+  pub branch_sync_ms: Option<u64>,
+  /// Time spent copying commits, summed over branches synced in parallel
+  pub cherry_pick_ms: u64,
+  /// Time spent computing the remote status of branches, summed over branches synced in parallel
+  pub remote_status_ms: u64,
+  /// Archiving and integration detection, `None` if skipped or continued in the background
+  pub integration_detection_ms: Option<u64>,
+  pub commit_count: u32,
+  pub branch_count: u32,
+}
+
+/// Sync phase that can continue in the background when it exceeds its time budget
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum SyncPhase {
+  /// Rebuilding the virtual branches
+  BranchSync,
+  /// Archiving inactive branches and detecting integrated ones
+  IntegrationDetection,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -81,6 +298,80 @@ pub struct GroupedBranchInfo {
   pub all_commits_have_issue_references: bool,
   /// Most frequent author email in this branch's commits
   pub my_email: Option<String>,
+  /// Whether the branch is pinned (see `sync_core::branch_order`)
+  pub pinned: bool,
+  /// Whether the branch is protected against force-push and deletion (see `sync_core::branch_protection`)
+  pub protected: bool,
+  /// Authors other than the user whose commits are grouped in `branchdeck.onlyMyCommits` mode (see `sync_core::author_filter`)
+  pub other_authors: Vec<String>,
+  /// Order in which the commits are applied to the branch (see `sync_core::commit_order`)
+  pub commit_order: CommitOrder,
+}
+
+/// Why the branch prefix of a commit subject is ambiguous
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum GroupingAmbiguity {
+  /// Several parenthesized prefixes, only the first one is used
+  MultiplePrefixes,
+  /// A parenthesized prefix followed by an issue reference, the prefix takes precedence
+  PrefixWithIssueReference,
+  /// Nested or unclosed parentheses
+  MalformedParentheses,
+  /// Parentheses without a name, the commit is not grouped by them
+  EmptyPrefix,
+  /// A parenthesized name near the start of the subject that is not recognized as a prefix, e.g. `fix(ui): …`
+  PrefixNotAtStart,
+  /// The prefix was changed to a valid branch name
+  SanitizedPrefix,
+  /// The commit touches no path in the scope of its branch (`branchdeck.pathScope`), so it is not grouped
+  OutsidePathScope,
+  /// The commit is grouped, but also touches paths outside the scope of its branch
+  PartlyOutsidePathScope,
+}
+
+/// How the branch prefix of a commit with an ambiguous subject was parsed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct GroupingDiagnostic {
+  pub commit_id: String,
+  pub subject: String,
+  /// Branch the commit is grouped into, `None` if it is unassigned
+  pub branch_name: Option<String>,
+  pub ambiguities: Vec<GroupingAmbiguity>,
+  /// Other branch names the subject could be read as
+  pub alternatives: Vec<String>,
+  /// How the prefix was parsed, for display
+  pub explanation: String,
+}
+
+/// Branch whose name is probably a misspelling of another branch name, e.g. `feature-auht` next to `feature-auth`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub struct PrefixTypo {
+  /// Probably misspelled branch name
+  pub branch_name: String,
+  /// Branch the commits were probably meant for
+  pub suggested_branch_name: String,
+  /// Commits grouped into the misspelled branch
+  pub commit_ids: Vec<String>,
+}
+
+/// Order of commits in a virtual branch
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "camelCase")]
+pub enum CommitOrder {
+  /// Order of the commits in the source branch
+  #[default]
+  Source,
+  /// Oldest author date first, e.g. to restore the original order after an interactive rebase
+  AuthorDate,
+  /// Commits after the ones they depend on (see `git_ops::commit_dependencies`), otherwise in source order
+  Topo,
 }
 
 /// Progress reporter trait that abstracts away Tauri-specific channel
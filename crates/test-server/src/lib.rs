@@ -69,11 +69,32 @@ pub fn create_app(state: Arc<AppState>) -> Router {
     .route("/invoke/validate_repository_path", post(tauri_command_bridge::validate_repository_path))
     .route("/invoke/get_branch_prefix_from_git_config", post(tauri_command_bridge::get_branch_prefix_from_git_config))
     .route("/invoke/sync_branches", post(tauri_command_bridge::sync_branches))
+    .route("/invoke/sync_branches_dry_run", post(tauri_command_bridge::sync_branches_dry_run))
     .route("/invoke/add_issue_reference_to_commits", post(tauri_command_bridge::add_issue_reference_to_commits))
     .route("/invoke/create_branch_from_commits", post(tauri_command_bridge::create_branch_from_commits))
     .route("/invoke/delete_archived_branch", post(tauri_command_bridge::delete_archived_branch))
+    .route("/invoke/delete_archived_branches", post(tauri_command_bridge::delete_archived_branches))
+    .route("/invoke/apply_archive_retention", post(tauri_command_bridge::apply_archive_retention))
     .route("/invoke/amend_uncommitted_to_branch", post(tauri_command_bridge::amend_uncommitted_to_branch))
     .route("/invoke/get_uncommitted_changes", post(tauri_command_bridge::get_uncommitted_changes))
+    .route("/invoke/simulate_conflict", post(tauri_command_bridge::simulate_conflict))
+    .route("/invoke/get_branch_order", post(tauri_command_bridge::get_branch_order))
+    .route("/invoke/set_branch_pinned", post(tauri_command_bridge::set_branch_pinned))
+    .route("/invoke/reorder_branches", post(tauri_command_bridge::reorder_branches))
+    .route("/invoke/get_commit_dependencies", post(tauri_command_bridge::get_commit_dependencies))
+    .route("/invoke/set_commit_dependencies", post(tauri_command_bridge::set_commit_dependencies))
+    .route("/invoke/export_branch_patches", post(tauri_command_bridge::export_branch_patches))
+    .route("/invoke/apply_patch_series", post(tauri_command_bridge::apply_patch_series))
+    .route("/invoke/get_smtp_config", post(tauri_command_bridge::get_smtp_config))
+    .route("/invoke/set_smtp_config", post(tauri_command_bridge::set_smtp_config))
+    .route("/invoke/get_cover_letter_template", post(tauri_command_bridge::get_cover_letter_template))
+    .route("/invoke/set_cover_letter_template", post(tauri_command_bridge::set_cover_letter_template))
+    .route("/invoke/rename_virtual_branch", post(tauri_command_bridge::rename_virtual_branch))
+    .route("/invoke/reword_commits", post(tauri_command_bridge::reword_commits))
+    .route("/invoke/fix_prefix_typo", post(tauri_command_bridge::fix_prefix_typo))
+    .route("/invoke/resolve_binary_conflict", post(tauri_command_bridge::resolve_binary_conflict))
+    .route("/invoke/launch_merge_tool", post(tauri_command_bridge::launch_merge_tool))
+    .route("/invoke/get_branch_review_status", post(tauri_command_bridge::get_branch_review_status))
     .route("/invoke/browse_repository/{repo_id}", post(tauri_command_bridge::browse_repository))
     // AI command endpoints
     .route("/invoke/suggest_branch_name_stream", post(tauri_command_bridge::suggest_branch_name_stream))
@@ -114,6 +135,7 @@ pub async fn create_test_templates(target_dir: &std::path::Path) -> anyhow::Resu
     ("conflict_branches", templates::conflict_branches()),
     ("single_unassigned", templates::single_unassigned()),
     ("issue_links", templates::issue_links()),
+    ("branch_name_edge_cases", templates::branch_name_edge_cases()),
   ];
 
   // Build all templates in parallel
@@ -6,21 +6,52 @@ use axum::{
     sse::{Event, KeepAlive, Sse},
   },
 };
+use branch_integration::archive::ArchiveRetentionReport;
+use dashmap::DashMap;
 use futures::stream::{Stream, StreamExt};
+use git_ops::binary_conflict::{ResolveBinaryConflictParams, ResolveBinaryConflictResult, resolve_binary_conflict as resolve_binary_conflict_core};
+use git_ops::commit_list::CommitFilter;
+use git_ops::merge_tool::{LaunchMergeToolParams, LaunchMergeToolResult, launch_merge_tool as launch_merge_tool_core};
 use git_ops::model::{BranchError, BranchSyncStatus};
+use git_ops::patch_export::{ExportBranchPatchesParams, ExportBranchPatchesResult, export_branch_patches as export_branch_patches_core};
+use git_ops::patch_import::{ApplyPatchSeriesParams, ApplyPatchSeriesResult, apply_patch_series as apply_patch_series_core};
+use hosting_api::review_status::{BranchReviewStatus, GetBranchReviewStatusParams, fetch_branch_review_status, load_review_client};
+use hosting_api::webhook::WebhookSummaryNotifier;
 use model_ai::types::{BranchSuggestion, DownloadProgress, SuggestBranchNameParams, SuggestionProgress};
 use serde::Deserialize;
 use std::convert::Infallible;
-use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, RwLock};
+use svix_ksuid::{Ksuid, KsuidLike};
 use sync_core::amend_to_branch::{AmendCommandResult, AmendUncommittedToBranchParams, amend_uncommitted_to_branch_core};
-use sync_core::delete_archived_branch::{DeleteArchivedBranchParams, delete_archived_branch_core};
-use sync_core::sync::sync_branches_core_with_cache;
+use sync_core::archive_retention::{ApplyArchiveRetentionParams, apply_archive_retention_core};
+use sync_core::branch_order::{
+  BranchOrder, GetBranchOrderParams, ReorderBranchesParams, SetBranchPinnedParams, get_branch_order_core, reorder_branches_core, set_branch_pinned_core,
+};
+use sync_core::commit_dependencies::{GetCommitDependenciesParams, SetCommitDependenciesParams, get_commit_dependencies_core, set_commit_dependencies_core};
+use sync_core::conflict_simulation::{SimulateConflictParams, SimulateConflictResult, simulate_conflict_core};
+use sync_core::credentials::AskpassEnv;
+use sync_core::delete_archived_branch::{
+  DeleteArchivedBranchParams, DeleteArchivedBranchesParams, DeleteArchivedBranchesResult, delete_archived_branch_core, delete_archived_branches_core,
+};
+use sync_core::phase_budget::wait_for_background_phases;
+use sync_core::prefix_typo::{FixPrefixTypoParams, fix_prefix_typo_core};
+use sync_core::remote_status::PUSH_REMOTE;
+use sync_core::rename_virtual_branch::{RenameVirtualBranchParams, RenameVirtualBranchResult, rename_virtual_branch_core};
+use sync_core::reword_commits::{RewordCommitsParams, RewordCommitsResult, reword_commits_core};
+use sync_core::send_email::{
+  GetCoverLetterTemplateParams, GetSmtpConfigParams, SetCoverLetterTemplateParams, SetSmtpConfigParams, SmtpConfig, get_cover_letter_template_core, get_smtp_config_core,
+  set_cover_letter_template_core, set_smtp_config_core,
+};
+use sync_core::sync::{self, SyncOptions};
+use sync_core::sync_dry_run::{SyncDryRunParams, SyncDryRunResult, sync_branches_dry_run_core};
 use sync_core::uncommitted_changes::{GetUncommittedChangesParams, UncommittedChangesResult, get_uncommitted_changes as core_get_uncommitted_changes};
+use sync_types::event_filter::{FilteredProgressReporter, SyncEventFilter};
 use sync_types::{ProgressReporter, SyncEvent};
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
-use crate::state::AppState;
+use crate::state::{AppState, ModelState, TestRepository};
 
 // Helper function to find a repository by its path
 fn find_repository_by_path(state: &AppState, path: &str) -> Option<String> {
@@ -89,7 +120,7 @@ pub async fn get_branch_prefix_from_git_config(State(state): State<Arc<AppState>
     tracing::debug!("Empty path detected, returning empty branch prefix for global config");
     return Json(serde_json::json!({
       "status": "ok",
-      "data": ""
+      "data": { "prefix": "", "sources": [] }
     }));
   }
 
@@ -110,16 +141,21 @@ pub async fn get_branch_prefix_from_git_config(State(state): State<Arc<AppState>
 
   // For deterministic tests, ignore user's global git config.
   // Only read the local repo config for branchdeck.branchPrefix.
-  // If not set locally, return empty string instead of falling back to global.
+  // If not set locally, return empty prefix instead of falling back to global.
   let repo_path = &request.repository_path;
+  let empty = serde_json::json!({ "prefix": "", "sources": [] });
   match state.git_executor.execute_command_with_status(&["config", "--local", "branchdeck.branchPrefix"], repo_path) {
     Ok((output, exit_code)) => match exit_code {
-      0 => Json(serde_json::json!({ "status": "ok", "data": output.trim() })),
-      1 => Json(serde_json::json!({ "status": "ok", "data": "" })),
+      0 => {
+        let prefix = output.trim();
+        let source = serde_json::json!({ "scope": "local", "origin": ".git/config", "value": prefix });
+        Json(serde_json::json!({ "status": "ok", "data": { "prefix": prefix, "sources": [source] } }))
+      }
+      1 => Json(serde_json::json!({ "status": "ok", "data": empty })),
       128 => Json(serde_json::json!({ "status": "error", "error": format!("Repository not accessible: {}", repo_path) })),
       code => {
         tracing::warn!(code, repo_path, "Unexpected exit code reading local branch prefix");
-        Json(serde_json::json!({ "status": "ok", "data": "" }))
+        Json(serde_json::json!({ "status": "ok", "data": empty }))
       }
     },
     Err(e) => {
@@ -148,6 +184,10 @@ pub struct SyncBranchesRequest {
   repository_path: String,
   #[serde(rename = "branchPrefix")]
   branch_prefix: String,
+  #[serde(rename = "commitFilter", default)]
+  commit_filter: Option<CommitFilter>,
+  #[serde(rename = "eventFilter", default)]
+  event_filter: Option<SyncEventFilter>,
 }
 
 pub async fn sync_branches(State(state): State<Arc<AppState>>, Json(request): Json<SyncBranchesRequest>) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, StatusCode> {
@@ -164,8 +204,13 @@ pub async fn sync_branches(State(state): State<Arc<AppState>>, Json(request): Js
   let git_executor = &state.git_executor;
   let repository_path = &request.repository_path;
   let branch_prefix = &request.branch_prefix;
-  let progress = reporter.clone();
-  match sync_branches_core_with_cache(git_executor, repository_path, branch_prefix, progress, None).await {
+  let progress = FilteredProgressReporter::new(reporter.clone(), request.event_filter.unwrap_or_default());
+  let options = SyncOptions {
+    commit_filter: request.commit_filter.unwrap_or_default(),
+    summary_notifier: Some(Arc::new(WebhookSummaryNotifier::new(git_executor.clone()))),
+    ..Default::default()
+  };
+  match sync::sync_branches(git_executor, repository_path, branch_prefix, progress, options).await {
     Ok(_) => {
       // Core function completed successfully
     }
@@ -465,9 +510,10 @@ pub async fn cancel_model_download(
 pub async fn delete_archived_branch(State(state): State<Arc<AppState>>, Json(params): Json<DeleteArchivedBranchParams>) -> Result<StatusCode, StatusCode> {
   // Validate that the repository path belongs to a test repository
   ensure_repository_exists(&state, &params.repository_path)?;
+  wait_for_background_phases(&params.repository_path).await;
 
   // Use the shared git executor from state
-  match delete_archived_branch_core(&state.git_executor, params) {
+  match delete_archived_branch_core(&state.git_executor, params, &AskpassEnv::none()) {
     Ok(()) => Ok(StatusCode::OK),
     Err(e) => {
       tracing::error!("Failed to delete archived branch: {}", e);
@@ -476,9 +522,33 @@ pub async fn delete_archived_branch(State(state): State<Arc<AppState>>, Json(par
   }
 }
 
+pub async fn delete_archived_branches(
+  State(state): State<Arc<AppState>>,
+  Json(params): Json<DeleteArchivedBranchesParams>,
+) -> Result<Json<DeleteArchivedBranchesResult>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+  wait_for_background_phases(&params.repository_path).await;
+
+  delete_archived_branches_core(&state.git_executor, params, |_| {}).map(Json).map_err(|e| {
+    tracing::error!("Failed to delete archived branches: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn apply_archive_retention(State(state): State<Arc<AppState>>, Json(params): Json<ApplyArchiveRetentionParams>) -> Result<Json<ArchiveRetentionReport>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+  wait_for_background_phases(&params.repository_path).await;
+
+  apply_archive_retention_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to apply archive retention: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
 pub async fn amend_uncommitted_to_branch(State(state): State<Arc<AppState>>, Json(params): Json<AmendUncommittedToBranchParams>) -> Result<Json<AmendCommandResult>, StatusCode> {
   // Validate that the repository path belongs to a test repository
   ensure_repository_exists(&state, &params.repository_path)?;
+  wait_for_background_phases(&params.repository_path).await;
 
   // Use the shared git executor from state and spawn blocking task
   match tokio::task::spawn_blocking({
@@ -534,3 +604,221 @@ pub async fn update_menu_checkbox(Json(request): Json<UpdateMenuCheckboxRequest>
   tracing::debug!("Menu checkbox updated: {} = {}", request.menu_id, request.checked);
   StatusCode::OK
 }
+
+pub async fn simulate_conflict(State(state): State<Arc<AppState>>, Json(mut params): Json<SimulateConflictParams>) -> Result<Json<SimulateConflictResult>, StatusCode> {
+  // Scratch repositories always live in the test root so they are cleaned up with the server
+  params.parent_dir = Some(state.test_root_dir.path().to_string_lossy().to_string());
+
+  let result = match tokio::task::spawn_blocking({
+    let git_executor = state.git_executor.clone();
+    move || simulate_conflict_core(&git_executor, params)
+  })
+  .await
+  {
+    Ok(Ok(result)) => result,
+    Ok(Err(error_msg)) => {
+      tracing::error!("Failed to simulate conflict: {}", error_msg);
+      return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    Err(join_error) => {
+      tracing::error!("Task failed: {}", join_error);
+      return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+  };
+
+  // Register the scratch repository so other commands accept its path
+  let id = Ksuid::new(None, None).to_string();
+  state.repositories.insert(
+    id.clone(),
+    TestRepository {
+      id: id.clone(),
+      path: result.repository_path.clone(),
+      store: DashMap::new(),
+      model_state: Arc::new(RwLock::new(ModelState::NotDownloaded)),
+      download_cancelled: Arc::new(AtomicBool::new(false)),
+    },
+  );
+  state.path_to_id.insert(result.repository_path.clone(), id);
+
+  Ok(Json(result))
+}
+
+pub async fn get_branch_order(State(state): State<Arc<AppState>>, Json(params): Json<GetBranchOrderParams>) -> Result<Json<BranchOrder>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  get_branch_order_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to get branch order: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn set_branch_pinned(State(state): State<Arc<AppState>>, Json(params): Json<SetBranchPinnedParams>) -> Result<Json<BranchOrder>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  set_branch_pinned_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to set branch pinned: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn reorder_branches(State(state): State<Arc<AppState>>, Json(params): Json<ReorderBranchesParams>) -> Result<Json<BranchOrder>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  reorder_branches_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to reorder branches: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn get_commit_dependencies(State(state): State<Arc<AppState>>, Json(params): Json<GetCommitDependenciesParams>) -> Result<Json<Vec<String>>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  get_commit_dependencies_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to get commit dependencies: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn set_commit_dependencies(State(state): State<Arc<AppState>>, Json(params): Json<SetCommitDependenciesParams>) -> Result<Json<Vec<String>>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  set_commit_dependencies_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to set commit dependencies: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn export_branch_patches(State(state): State<Arc<AppState>>, Json(params): Json<ExportBranchPatchesParams>) -> Result<Json<ExportBranchPatchesResult>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  let baseline_branch = sync_core::sync::detect_baseline_branch(&state.git_executor, &params.repository_path, "master").map_err(|e| {
+    tracing::error!("Failed to detect baseline branch: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })?;
+  export_branch_patches_core(&state.git_executor, params, &baseline_branch).map(Json).map_err(|e| {
+    tracing::error!("Failed to export branch patches: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn apply_patch_series(State(state): State<Arc<AppState>>, Json(params): Json<ApplyPatchSeriesParams>) -> Result<Json<ApplyPatchSeriesResult>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  apply_patch_series_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to apply patch series: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn get_smtp_config(State(state): State<Arc<AppState>>, Json(params): Json<GetSmtpConfigParams>) -> Result<Json<SmtpConfig>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  get_smtp_config_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to get SMTP config: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn set_smtp_config(State(state): State<Arc<AppState>>, Json(params): Json<SetSmtpConfigParams>) -> Result<Json<SmtpConfig>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  set_smtp_config_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to set SMTP config: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn get_cover_letter_template(State(state): State<Arc<AppState>>, Json(params): Json<GetCoverLetterTemplateParams>) -> Result<Json<String>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  get_cover_letter_template_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to get cover letter template: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn set_cover_letter_template(State(state): State<Arc<AppState>>, Json(params): Json<SetCoverLetterTemplateParams>) -> Result<Json<String>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  set_cover_letter_template_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to set cover letter template: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn rename_virtual_branch(State(state): State<Arc<AppState>>, Json(params): Json<RenameVirtualBranchParams>) -> Result<Json<RenameVirtualBranchResult>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+  wait_for_background_phases(&params.repository_path).await;
+
+  rename_virtual_branch_core(&state.git_executor, params, &AskpassEnv::none()).map(Json).map_err(|e| {
+    tracing::error!("Failed to rename virtual branch: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn reword_commits(State(state): State<Arc<AppState>>, Json(params): Json<RewordCommitsParams>) -> Result<Json<RewordCommitsResult>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+  wait_for_background_phases(&params.repository_path).await;
+
+  reword_commits_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to reword commits: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn fix_prefix_typo(State(state): State<Arc<AppState>>, Json(params): Json<FixPrefixTypoParams>) -> Result<Json<RewordCommitsResult>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+  wait_for_background_phases(&params.repository_path).await;
+
+  fix_prefix_typo_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to fix branch prefix typo: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+/// No file dialog in tests, the replacement is given by path
+pub async fn resolve_binary_conflict(
+  State(state): State<Arc<AppState>>,
+  Json(params): Json<ResolveBinaryConflictParams>,
+) -> Result<Json<Option<ResolveBinaryConflictResult>>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  resolve_binary_conflict_core(&state.git_executor, params).map(|result| Json(Some(result))).map_err(|e| {
+    tracing::error!("Failed to resolve binary conflict: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn launch_merge_tool(State(state): State<Arc<AppState>>, Json(params): Json<LaunchMergeToolParams>) -> Result<Json<LaunchMergeToolResult>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  launch_merge_tool_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to launch merge tool: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
+
+pub async fn get_branch_review_status(State(state): State<Arc<AppState>>, Json(params): Json<GetBranchReviewStatusParams>) -> Result<Json<Vec<BranchReviewStatus>>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  let client = load_review_client(&state.git_executor, &params.repository_path, PUSH_REMOTE).map_err(|e| {
+    tracing::error!("Failed to create review client: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })?;
+  fetch_branch_review_status(client.as_ref(), &params.branch_prefix, params.branch_names)
+    .await
+    .map(Json)
+    .map_err(|e| {
+      tracing::error!("Failed to get branch review status: {}", e);
+      StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+pub async fn sync_branches_dry_run(State(state): State<Arc<AppState>>, Json(params): Json<SyncDryRunParams>) -> Result<Json<SyncDryRunResult>, StatusCode> {
+  ensure_repository_exists(&state, &params.repository_path)?;
+
+  sync_branches_dry_run_core(&state.git_executor, params).map(Json).map_err(|e| {
+    tracing::error!("Failed to predict sync conflicts: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+  })
+}
@@ -1,3 +1,4 @@
+use crate::ref_snapshot::{RefsSnapshot, SnapshotOptions, snapshot_refs};
 use git_executor::git_command_executor::GitCommandExecutor;
 use std::fs;
 use std::path::Path;
@@ -56,10 +57,28 @@ impl TestRepo {
   }
 
   /// Get the repository path as a string
-  fn path_str(&self) -> &str {
+  pub fn path_str(&self) -> &str {
     self.dir.path().to_str().unwrap()
   }
 
+  /// Run a git command in the repository and return its output, panics if it fails
+  pub fn git(&self, args: &[&str]) -> String {
+    self
+      .git_executor
+      .execute_command(args, self.path_str())
+      .unwrap_or_else(|e| panic!("git {} failed: {e}", args.join(" ")))
+  }
+
+  /// Read a file of the working tree
+  pub fn read_file(&self, filename: &str) -> String {
+    fs::read_to_string(self.path().join(filename)).unwrap()
+  }
+
+  /// Write a file of the working tree without staging it
+  pub fn write_file(&self, filename: &str, content: &str) {
+    fs::write(self.path().join(filename), content).unwrap();
+  }
+
   /// Get a reference to the git executor
   pub fn git_executor(&self) -> &GitCommandExecutor {
     &self.git_executor
@@ -341,6 +360,20 @@ impl TestRepo {
     Ok(())
   }
 
+  /// Shallow clone a repository into this directory, keeping `depth` commits of each branch
+  pub fn shallow_clone_from(&self, source_path: &Path, depth: u32) -> Result<(), String> {
+    // Local clones ignore --depth unless the source is given as a file:// URL
+    let url = format!("file://{}", source_path.to_str().unwrap());
+    self
+      .git_executor
+      .execute_command(&["clone", &format!("--depth={depth}"), &url, "."], self.path_str())
+      .map_err(|e| e.to_string())?;
+
+    Self::configure_git_user(&self.git_executor, self.path_str()).map_err(|e| e.to_string())?;
+
+    Ok(())
+  }
+
   /// Create and checkout a new branch
   pub fn checkout_new_branch(&self, branch_name: &str) -> Result<(), String> {
     self
@@ -503,6 +536,11 @@ impl TestRepo {
       .map(|_| ())
       .map_err(|e| e.to_string())
   }
+
+  /// Snapshot refs with their commits for `insta` assertions of the complete repository state
+  pub fn snapshot_refs(&self, options: SnapshotOptions<'_>) -> Result<RefsSnapshot, String> {
+    snapshot_refs(&self.git_executor, self.path_str(), options).map_err(|e| e.to_string())
+  }
 }
 
 /// Builder for creating conflict test scenarios
@@ -2,6 +2,7 @@
 
 pub mod git_test_utils;
 pub mod progress_reporter;
+pub mod ref_snapshot;
 pub mod repo_template;
 pub mod test_repo_generator;
 
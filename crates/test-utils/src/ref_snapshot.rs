@@ -0,0 +1,108 @@
+use anyhow::{Result, anyhow};
+use git_executor::git_command_executor::GitCommandExecutor;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Normalized state of the refs of a repository, meant for `insta` snapshot assertions.
+/// Commit IDs depend on timestamps and differ from run to run, so commits are described by message, tree and changed files;
+/// archive dates in ref names are replaced with `<date>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RefsSnapshot {
+  /// Commits of each ref (newest first), keyed by ref name without the snapshotted prefix
+  pub refs: BTreeMap<String, Vec<CommitSnapshot>>,
+}
+
+impl RefsSnapshot {
+  /// Commit messages of a ref (newest first)
+  pub fn messages(&self, name: &str) -> Result<Vec<&str>> {
+    let commits = self.refs.get(name).ok_or_else(|| anyhow!("Ref {name} is not in the snapshot"))?;
+    Ok(commits.iter().map(|commit| commit.message.as_str()).collect())
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommitSnapshot {
+  pub message: String,
+  /// Tree ID, stable across runs as it only depends on the content
+  pub tree: String,
+  /// Changed paths with their status letter as printed by `git diff-tree --name-status`, e.g. `M src/lib.rs`
+  pub changes: Vec<String>,
+}
+
+/// Options for [`snapshot_refs`]
+#[derive(Debug, Clone, Copy)]
+pub struct SnapshotOptions<'a> {
+  /// Only refs under this prefix, e.g. `refs/heads/user/virtual/`
+  pub ref_prefix: &'a str,
+  /// Stop at commits reachable from this revision (typically the baseline), `None` includes the whole history
+  pub base: Option<&'a str>,
+}
+
+impl Default for SnapshotOptions<'_> {
+  fn default() -> Self {
+    Self {
+      ref_prefix: "refs/heads/",
+      base: None,
+    }
+  }
+}
+
+/// Snapshot all refs matching the options
+pub fn snapshot_refs(git_executor: &GitCommandExecutor, repo_path: &str, options: SnapshotOptions<'_>) -> Result<RefsSnapshot> {
+  let ref_names = git_executor.execute_command_lines(&["for-each-ref", "--format=%(refname)", options.ref_prefix], repo_path)?;
+
+  let mut refs = BTreeMap::new();
+  for ref_name in ref_names {
+    let range = match options.base {
+      Some(base) => format!("{base}..{ref_name}"),
+      None => ref_name.clone(),
+    };
+    let commit_ids = git_executor.execute_command_lines(&["rev-list", &range], repo_path)?;
+    let commits = commit_ids
+      .iter()
+      .map(|commit_id| snapshot_commit(git_executor, repo_path, commit_id))
+      .collect::<Result<Vec<_>>>()?;
+
+    let name = ref_name.strip_prefix(options.ref_prefix).unwrap_or(&ref_name);
+    refs.insert(normalize_ref_name(name), commits);
+  }
+  Ok(RefsSnapshot { refs })
+}
+
+fn snapshot_commit(git_executor: &GitCommandExecutor, repo_path: &str, commit_id: &str) -> Result<CommitSnapshot> {
+  let commit = git_executor.read_commit(repo_path, commit_id)?;
+  let changes = git_executor
+    .execute_command_lines(&["diff-tree", "--root", "--no-commit-id", "-r", "--name-status", commit_id], repo_path)?
+    .into_iter()
+    .map(|line| line.replacen('\t', " ", 1))
+    .collect();
+  Ok(CommitSnapshot {
+    message: commit.message.trim_end().to_string(),
+    tree: commit.tree_id,
+    changes,
+  })
+}
+
+/// Replace the `YYYY-MM-DD` segment following `archived` with `<date>`
+fn normalize_ref_name(name: &str) -> String {
+  let segments: Vec<&str> = name.split('/').collect();
+  segments
+    .iter()
+    .enumerate()
+    .map(|(index, segment)| {
+      if index > 0 && segments[index - 1] == "archived" && is_date(segment) {
+        "<date>"
+      } else {
+        *segment
+      }
+    })
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+fn is_date(segment: &str) -> bool {
+  segment.len() == 10
+    && segment
+      .char_indices()
+      .all(|(index, c)| if index == 4 || index == 7 { c == '-' } else { c.is_ascii_digit() })
+}
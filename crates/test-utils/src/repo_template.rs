@@ -914,6 +914,43 @@ data class User(
       )
   }
 
+  /// Branch name of 200 characters, the leaf ref file and its `.lock` file still fit the 255 byte file name limit
+  pub fn long_branch_name() -> String {
+    format!("long-{}", "x".repeat(195))
+  }
+
+  /// Branch name of 300 characters, git fails to create its ref file
+  pub fn too_long_branch_name() -> String {
+    format!("too-long-{}", "x".repeat(291))
+  }
+
+  /// Repository with commit prefixes exercising branch name normalization and validation:
+  /// - `ui dispatcher`, `ui-dispatcher` and ` ui--dispatcher ` all normalize to `ui-dispatcher`
+  /// - `fix~bug:v2?` has illegal ref characters, normalized to `fix-bug-v2`
+  /// - `release..next` passes normalization, but git rejects the ref name
+  /// - `~^~` normalizes to an empty name
+  /// - [`long_branch_name`] is synced, [`too_long_branch_name`] exceeds the file name limit
+  pub fn branch_name_edge_cases() -> RepoTemplate {
+    let long_subject = format!("({}) Add long feature", long_branch_name());
+    let too_long_subject = format!("({}) Add too long feature", too_long_branch_name());
+    // Use fixed timestamps: Jan 1, 2024 starting at 14:00:00 UTC, incrementing by 30 minutes
+    RepoTemplate::new("branch_name_edge_cases")
+      .branch_prefix("user-name")
+      .commit_with_timestamp("Initial setup", &[("README.md", "# Project\n\nInitial project setup.")], Some(1704117600))
+      .commit_with_timestamp("(ui dispatcher) Add dispatcher", &[("ui/dispatcher.js", "export function dispatch() {}")], Some(1704119400))
+      .commit_with_timestamp("(ui-dispatcher) Add dispatcher queue", &[("ui/queue.js", "export const queue = []")], Some(1704121200))
+      .commit_with_timestamp(
+        "( ui--dispatcher ) Add dispatcher tests",
+        &[("ui/dispatcher.test.js", "test('dispatches', () => {})")],
+        Some(1704123000),
+      )
+      .commit_with_timestamp("(fix~bug:v2?) Fix parser", &[("parser.js", "export function parse() {}")], Some(1704124800))
+      .commit_with_timestamp("(release..next) Prepare release", &[("release.txt", "next")], Some(1704126600))
+      .commit_with_timestamp("(~^~) Tweak config", &[("config.txt", "tweaked")], Some(1704128400))
+      .commit_with_timestamp(long_subject, &[("long.txt", "long")], Some(1704130200))
+      .commit_with_timestamp(too_long_subject, &[("too-long.txt", "too long")], Some(1704132000))
+  }
+
   /// Directory without git initialization - for testing invalid repository paths
   pub fn empty_non_git() -> EmptyNonGitTemplate {
     EmptyNonGitTemplate
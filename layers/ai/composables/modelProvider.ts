@@ -61,6 +61,17 @@ function handleDownloadProgress(
       break
     }
 
+    case "FileResumed":
+    case "FileVerifying": {
+      toast.update(progressToastId, {
+        description: h(ModelDownloadProgress, {
+          downloadProgress: event,
+          showCancelButton: false,
+        }),
+      })
+      break
+    }
+
     case "Progress": {
       const now = Date.now()
       // Throttle updates to prevent UI jank
@@ -198,7 +209,17 @@ async function performModelDownload(
     }
 
     // Start download with progress channel
-    await commands.downloadModel(channel)
+    const result = await commands.downloadModel(channel)
+    // Offline the download doesn't start, so no progress event closes the toast
+    if (result.status === "error" && result.error.code === "offline") {
+      toast.remove(progressToastId)
+      toast.add({
+        title: "AI model not downloaded",
+        description: `${result.error.message}. Download the model once you are online.`,
+        color: "warning",
+      })
+      state.isDownloading.value = false
+    }
   }
   catch (error) {
     // Remove progress toast
@@ -133,6 +133,15 @@ export function useBranchSuggestions(options: UseBranchSuggestionsOptions) {
             break
           }
 
+          case "PartialSuggestion": {
+            // Remote providers stream the name as it is generated
+            const { text, index } = progress.data
+            if (index < suggestions.value.length) {
+              suggestions.value[index] = markRaw({ name: text, reason: null })
+            }
+            break
+          }
+
           case "SuggestionReady": {
             const { suggestion, index } = progress.data
             if (index < suggestions.value.length) {
@@ -177,7 +186,7 @@ export function useBranchSuggestions(options: UseBranchSuggestionsOptions) {
       const result = await commands.suggestBranchNameStream(params, channel)
 
       if (result.status === "error") {
-        throw new Error(result.error)
+        throw new Error(result.error.message)
       }
     }
     catch (err) {
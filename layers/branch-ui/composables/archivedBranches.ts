@@ -1,5 +1,6 @@
-import type { Commit, IntegrationConfidence, BranchIntegrationInfo } from "~/utils/bindings"
+import type { Commit, IntegrationConfidence, IntegrationEvidence, BranchIntegrationInfo, SyncEvent } from "~/utils/bindings"
 import { commands } from "~/utils/bindings"
+import { Channel } from "@tauri-apps/api/core"
 import type { createRepositoryState } from "~/composables/repositoryProvider"
 import { createReactiveIndexedCollection } from "~/utils/reactiveIndexedCollection"
 
@@ -11,12 +12,17 @@ export interface ReactiveArchivedBranch {
   type: "integrated" | "not-integrated" | "placeholder"
   commitCount: number
   confidence: IntegrationConfidence | undefined
+  // Why the branch got its status: matching strategy and matched baseline commits
+  evidence: IntegrationEvidence | undefined
   integratedAt: number
   // Additional fields for not-integrated branches with partial integration
   integratedCount: number
   orphanedCount: number
   // Summary extracted from first commit for issue-based branches
   summary: string
+  // Detection budget ran out before the deep detection steps, the status may miss integrated commits
+  detectionTruncated: boolean
+  isResumingDetection: boolean
   // Dynamic state for commit loading
   commits: Commit[] | undefined
   isLoadingCommits: boolean
@@ -44,10 +50,13 @@ export function createArchivedBranchesState(repository: ReturnType<typeof create
         type: "placeholder" as const,
         commitCount: 0, // Unknown until detection completes
         confidence: undefined,
+        evidence: undefined,
         integratedAt: 0,
         integratedCount: 0,
         orphanedCount: 0,
         summary: "",
+        detectionTruncated: false,
+        isResumingDetection: false,
         commits: undefined,
         isLoadingCommits: false,
         hasLoadedCommits: false,
@@ -67,10 +76,13 @@ export function createArchivedBranchesState(repository: ReturnType<typeof create
         type: "placeholder" as const,
         commitCount: 0,
         confidence: undefined,
+        evidence: undefined,
         integratedAt: 0,
         integratedCount: 0,
         orphanedCount: 0,
         summary: "",
+        detectionTruncated: false,
+        isResumingDetection: false,
         commits: undefined,
         isLoadingCommits: false,
         hasLoadedCommits: false,
@@ -100,7 +112,9 @@ export function createArchivedBranchesState(repository: ReturnType<typeof create
     // Base properties common to all cases
     const baseUpdate = {
       summary: info.summary,
+      detectionTruncated: info.detectionTruncated,
       confidence: undefined as IntegrationConfidence | undefined,
+      evidence: info.evidence ?? undefined,
       integratedCount: 0,
       orphanedCount: 0,
     }
@@ -141,6 +155,38 @@ export function createArchivedBranchesState(repository: ReturnType<typeof create
     }
   }
 
+  /**
+   * Run the full integration detection of a branch whose detection was truncated
+   */
+  async function resumeDetection(branchName: string): Promise<void> {
+    const reactiveBranch = archivedBranchCollection.get(branchName)
+    if (!reactiveBranch || reactiveBranch.isResumingDetection) {
+      return
+    }
+
+    reactiveBranch.isResumingDetection = true
+    try {
+      const channel = new Channel<SyncEvent>()
+      channel.onmessage = (event) => {
+        if (event.type === "branchIntegrationDetected") {
+          updateFromIntegrationInfo(event.data.info)
+        }
+      }
+
+      const vcsRequest = vcsRequestFactory.createRequest()
+      const result = await commands.resumeIntegrationDetection({
+        repositoryPath: vcsRequest.repositoryPath,
+        branchNames: [branchName],
+      }, channel)
+      if (result.status !== "ok") {
+        throw new Error(result.error.message)
+      }
+    }
+    finally {
+      reactiveBranch.isResumingDetection = false
+    }
+  }
+
   /**
    * Load commits for a specific branch
    */
@@ -165,7 +211,7 @@ export function createArchivedBranchesState(repository: ReturnType<typeof create
         reactiveBranch.hasLoadedCommits = true
       }
       else {
-        reactiveBranch.loadError = result.error
+        reactiveBranch.loadError = result.error.message
       }
     }
     catch (error) {
@@ -219,5 +265,6 @@ export function createArchivedBranchesState(repository: ReturnType<typeof create
     updateFromArchivedNames,
     updateFromIntegrationInfo,
     loadCommitsForBranch,
+    resumeDetection,
   }
 }
@@ -1,9 +1,10 @@
 import type { RemoteStatus } from "~/composables/branchSyncProvider"
 import { formatTimestamp } from "#layers/shared-ui/utils/time"
+import type { CiStatus, ReviewSummary } from "~/utils/bindings"
 import type { UIColor } from "~/utils/uiTypes"
 
 export interface StatusTexts {
-  text: string // compact display text, e.g. "1 (3)↑ · 2↓" | "0 (3)↑" | "2↓" | "up to date" | "not pushed"
+  text: string // compact display text, e.g. "1 (3)↑ · 2↓" | "0 (3)↑" | "2↓" | "up to date" | "not pushed" | "up to date · 2 unresolved"
   tooltip: string // full descriptive text
   color: UIColor // appropriate UI color for this status
 }
@@ -13,6 +14,53 @@ function plural(n: number, one: string, many: string) {
 }
 
 export function buildRemoteStatusTexts(remote: RemoteStatus): StatusTexts {
+  return addCiStatusTexts(addReviewTexts(buildPushStatusTexts(remote), remote.review), remote.ciStatus)
+}
+
+function addReviewTexts(texts: StatusTexts, review: ReviewSummary | null): StatusTexts {
+  if (review == null) {
+    return texts
+  }
+
+  const reviewTooltip = `Pull request #${review.number}: ${plural(review.commentCount, "comment", "comments")}, ${plural(review.unresolvedThreadCount, "unresolved thread", "unresolved threads")}, ${plural(review.approvalCount, "approval", "approvals")}.`
+  let text = review.unresolvedThreadCount > 0 ? `${texts.text} · ${review.unresolvedThreadCount} unresolved` : texts.text
+  let color = texts.color
+  switch (review.reviewDecision) {
+    case "approved":
+      text = `${text} · approved`
+      break
+    case "changesRequested":
+      text = `${text} · changes requested`
+      color = "warning"
+      break
+  }
+  return { text, tooltip: `${texts.tooltip}\n\n${reviewTooltip}`, color }
+}
+
+function addCiStatusTexts(texts: StatusTexts, ciStatus: CiStatus | null): StatusTexts {
+  if (ciStatus == null) {
+    return texts
+  }
+
+  switch (ciStatus.state) {
+    case "failure": {
+      const failed = ciStatus.failedChecks.length > 0 ? `: ${ciStatus.failedChecks.join(", ")}` : ""
+      return {
+        text: `${texts.text} · CI failed`,
+        tooltip: `${texts.tooltip}\n\nCI failed${failed}.`,
+        color: "error",
+      }
+    }
+    case "pending":
+      return { text: `${texts.text} · CI running`, tooltip: `${texts.tooltip}\n\nCI is running.`, color: texts.color }
+    case "success":
+      return { ...texts, tooltip: `${texts.tooltip}\n\nCI passed.` }
+    case "cancelled":
+      return { ...texts, tooltip: `${texts.tooltip}\n\nCI was cancelled.` }
+  }
+}
+
+function buildPushStatusTexts(remote: RemoteStatus): StatusTexts {
   const { exists, commitsAhead, commitsBehind, lastPushTime, myCommitsAhead } = remote
   const my = myCommitsAhead ?? 0
 
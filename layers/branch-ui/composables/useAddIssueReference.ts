@@ -33,7 +33,7 @@ export function useAddIssueReference() {
           issueReference,
         })
         if (result.status !== "ok") {
-          throw new Error(result.error)
+          throw new Error(result.error.message)
         }
         return result.data
       },
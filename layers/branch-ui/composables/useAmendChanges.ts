@@ -31,7 +31,7 @@ export function useAmendChanges() {
         diffData.value = result.data
       }
       else {
-        diffError.value = result.error
+        diffError.value = result.error.message
       }
     }
     catch (error) {
@@ -76,7 +76,7 @@ export function useAmendChanges() {
         })
 
         if (result.status !== "ok") {
-          throw new Error(result.error)
+          throw new Error(result.error.message)
         }
 
         // Handle the new AmendCommandResult structure
@@ -86,6 +86,10 @@ export function useAmendChanges() {
             // For conflicts, we need special handling
             throw { type: "conflict", data: branchError.MergeConflict }
           }
+          else if ("CaseCollision" in branchError) {
+            const paths = branchError.CaseCollision.collisions.map(group => group.join(" and ")).join(", ")
+            throw new Error(`Rebasing would create paths that differ only in case: ${paths}`)
+          }
           else {
             throw new Error(branchError.Generic)
           }
@@ -1,3 +1,12 @@
+import { commands, type CommitOrder } from "~/utils/bindings"
+
+// Order in which the commits of a branch are applied on sync
+const commitOrderOptions: Array<{ value: CommitOrder, label: string, icon: string }> = [
+  { value: "source", label: "Source Order", icon: "i-lucide-list-ordered" },
+  { value: "authorDate", label: "Author Date", icon: "i-lucide-calendar" },
+  { value: "topo", label: "Dependencies First", icon: "i-lucide-git-fork" },
+]
+
 export interface BranchContextActionsReturn {
   // Methods
   getContextMenuItems: (branch: ReactiveBranch) => Array<Array<{ label: string, icon: string, disabled?: boolean, onSelect: () => void }>>
@@ -7,6 +16,8 @@ export function useBranchContextActions(options: { setExpanded: (item: ReactiveB
   const { getCopyMenuItems } = useBranchCopyActions()
 
   const inline = useInlineRowAction()
+  const { selectedProject } = useRepository()
+  const { syncBranches } = useBranchSync()
 
   // Context menu items
   const getContextMenuItems = (branch: ReactiveBranch) => {
@@ -53,6 +64,16 @@ export function useBranchContextActions(options: { setExpanded: (item: ReactiveB
       items.push(commitActions)
     }
 
+    // Commit order (current one is checked)
+    if (hasCommits && branch.status !== "Syncing") {
+      items.push(commitOrderOptions.map(option => ({
+        label: `Commit Order: ${option.label}`,
+        icon: branch.commitOrder === option.value ? "i-lucide-check" : option.icon,
+        disabled: branch.commitOrder === option.value,
+        onSelect: () => handleCommitOrderAction(branch, option.value),
+      })))
+    }
+
     return items
   }
 
@@ -64,6 +85,21 @@ export function useBranchContextActions(options: { setExpanded: (item: ReactiveB
     inline.openInline("amend-changes", branch.name)
   }
 
+  // Handle commit order change: persisted in the repository, applied by re-syncing
+  const handleCommitOrderAction = async (branch: ReactiveBranch, commitOrder: CommitOrder) => {
+    const result = await commands.setBranchCommitOrder({
+      repositoryPath: selectedProject.value?.path || "",
+      branchName: branch.name,
+      commitOrder,
+    })
+    if (result.status !== "ok") {
+      notifyError("Failed to change commit order", result.error)
+      return
+    }
+    // noinspection ES6MissingAwait
+    syncBranches({ autoScroll: false, autoExpand: false })
+  }
+
   // Handle unapply action
   const handleUnapplyAction = (branch: ReactiveBranch) => {
     // Expand the branch to show commits that will be unapplied
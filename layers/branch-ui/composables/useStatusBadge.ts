@@ -47,6 +47,8 @@ export function useStatusBadge(branch: Ref<ReactiveBranch>) {
         return markRaw({ text: "updated", tooltip: "Branch updated", color: "primary" })
       case "Unchanged":
         return markRaw({ text: "unchanged", tooltip: "No changes", color: "neutral" })
+      case "Integrated":
+        return markRaw({ text: "integrated", tooltip: "All commits are already in the baseline", color: "success" })
       default: {
         const text = branchValue.statusText || "unknown"
         return markRaw({ text, tooltip: branchValue.statusText || "Unknown", color: "neutral" })
@@ -8,6 +8,9 @@ const AppSettingsSchema = z.object({
   radius: z.number().optional(),
   globalUserBranchPrefix: z.string().optional(),
   autoSyncOnFocus: z.boolean().default(false),
+  offlineMode: z.boolean().default(false),
+  // Custom git executable, auto-detected if not set
+  gitPath: z.string().optional(),
 })
 
 // Create the persistent store
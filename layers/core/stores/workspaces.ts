@@ -0,0 +1,21 @@
+import { z } from "zod"
+import { createPersistentStore } from "../utils/persistent-store"
+
+// A named group of repositories synced together
+const WorkspaceSchema = z.object({
+  name: z.string(),
+  repositoryPaths: z.array(z.string()).default([]),
+})
+
+const WorkspacesSchema = z.array(WorkspaceSchema).default([])
+
+// Create the persistent store
+export const workspacesStore = createPersistentStore(
+  "workspaces",
+  { schema: WorkspacesSchema, isArray: true, isMainOnly: false },
+)
+
+export type Workspace = z.infer<typeof WorkspaceSchema>
+
+// Export a composable for consistency with existing code
+export const useWorkspacesStore = () => workspacesStore
@@ -0,0 +1,52 @@
+import { onScopeDispose, toValue, watch, type MaybeRefOrGetter } from "vue"
+import { Channel } from "@tauri-apps/api/core"
+import { commands, type SyncEvent, type SyncEventFilter } from "~/utils/bindings"
+
+/**
+ * Hook to receive the events of syncs started by another window (e.g. the main window).
+ * Events are filtered by the backend before they are sent, so a sub-window only gets what it asked for.
+ * The subscription is renewed when the repository or filter changes and removed when the scope is disposed.
+ *
+ * @param repositoryPath - Repository whose syncs to follow, nothing is received while empty
+ * @param filter - Events to receive (e.g. only errors of one branch)
+ * @param onEvent - Callback for each received event
+ */
+export function useSyncEventSubscription(
+  repositoryPath: MaybeRefOrGetter<string | null | undefined>,
+  filter: MaybeRefOrGetter<SyncEventFilter>,
+  onEvent: (event: SyncEvent) => void,
+) {
+  let subscription: Promise<number | null> = Promise.resolve(null)
+
+  async function unsubscribe(previous: Promise<number | null>) {
+    const subscriptionId = await previous
+    if (subscriptionId !== null) {
+      await commands.unsubscribeSyncEvents(subscriptionId)
+    }
+  }
+
+  async function subscribe(path: string, currentFilter: SyncEventFilter): Promise<number | null> {
+    const channel = new Channel<SyncEvent>()
+    channel.onmessage = onEvent
+    const result = await commands.subscribeSyncEvents({ repositoryPath: path, filter: currentFilter }, channel)
+    if (result.status === "error") {
+      console.error("Failed to subscribe to sync events:", result.error)
+      return null
+    }
+    return result.data
+  }
+
+  watch(
+    () => [toValue(repositoryPath), toValue(filter)] as const,
+    ([path, currentFilter]) => {
+      // Chain on the previous subscription so it is always removed, even if it is still being created
+      const previous = subscription
+      subscription = unsubscribe(previous).then(() => (path ? subscribe(path, currentFilter) : null))
+    },
+    { immediate: true, deep: true },
+  )
+
+  onScopeDispose(() => {
+    void unsubscribe(subscription)
+  })
+}
@@ -4,6 +4,10 @@ export function getErrorDetails(error: unknown) {
   if (error instanceof Error) {
     return error.toString()
   }
+  // CommandError returned by Tauri commands
+  else if (typeof error === "object" && error !== null && "message" in error && typeof error.message === "string") {
+    return error.message
+  }
   else {
     return String(error)
   }
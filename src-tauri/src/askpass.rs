@@ -0,0 +1,190 @@
+//! Askpass bridge: when git or ssh needs credentials nobody stored, the prompt is answered in the UI instead of a terminal.
+//!
+//! A push starts an [`AskpassSession`] listening on a loopback port and passes the app's own executable to git as
+//! `GIT_ASKPASS` and `SSH_ASKPASS`. Started by git or ssh with [`ASKPASS_ADDRESS_ENV`] set, the executable runs
+//! [`run_askpass_client`]: it sends the session token and the prompt, prints the answer, and fails if the prompt was
+//! cancelled. The session forwards the prompt to the push channel as [`CredentialPrompt`], answered with the
+//! `answer_credential_prompt` command.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread;
+use std::time::Duration;
+use sync_core::credentials::AskpassEnv;
+use tauri::ipc::Channel;
+use tracing::{debug, instrument, warn};
+
+/// Address of the askpass session, its presence switches the executable into askpass mode
+pub const ASKPASS_ADDRESS_ENV: &str = "BRANCH_DECK_ASKPASS_ADDRESS";
+/// Proves that a connection comes from the askpass program started by the push, not another local process
+pub const ASKPASS_TOKEN_ENV: &str = "BRANCH_DECK_ASKPASS_TOKEN";
+
+/// Unanswered prompts are cancelled after this long, so a forgotten dialog doesn't hold the push forever
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(300);
+/// The askpass program sends its request right after connecting
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Prompt of git or ssh, e.g. `Password for 'https://user@github.com': ` or `Enter passphrase for key '~/.ssh/id_ed25519': `
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialPrompt {
+  pub id: u32,
+  pub prompt: String,
+  /// Password, token or key passphrase, entered in a masked field
+  pub secret: bool,
+}
+
+/// Prompts waiting for an answer from the UI
+#[derive(Clone, Default)]
+pub struct AskpassPrompts {
+  next_id: Arc<AtomicU32>,
+  pending: Arc<Mutex<HashMap<u32, mpsc::Sender<Option<String>>>>>,
+}
+
+impl AskpassPrompts {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Answer a prompt, `None` cancels it. Returns false if the prompt is no longer pending (e.g. timed out).
+  #[instrument(skip(self, answer))]
+  pub fn answer(&self, id: u32, answer: Option<String>) -> bool {
+    match self.pending.lock().unwrap().remove(&id) {
+      Some(sender) => sender.send(answer).is_ok(),
+      None => false,
+    }
+  }
+
+  /// Send the prompt to the UI and wait for the answer, `None` if cancelled, timed out or the UI is gone
+  fn ask(&self, channel: &Channel<CredentialPrompt>, prompt: String) -> Option<String> {
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = mpsc::channel();
+    self.pending.lock().unwrap().insert(id, sender);
+    let secret = is_secret_prompt(&prompt);
+    let answer = match channel.send(CredentialPrompt { id, prompt, secret }) {
+      Ok(()) => receiver.recv_timeout(ANSWER_TIMEOUT).ok().flatten(),
+      Err(e) => {
+        warn!(error = %e, "Failed to send credential prompt");
+        None
+      }
+    };
+    self.pending.lock().unwrap().remove(&id);
+    answer
+  }
+}
+
+/// Password, token or key passphrase, as opposed to a username or the host key confirmation of ssh
+/// (`Are you sure you want to continue connecting (yes/no/[fingerprint])?`)
+fn is_secret_prompt(prompt: &str) -> bool {
+  let prompt = prompt.to_ascii_lowercase();
+  ["password", "passphrase", "token", "pin for"].iter().any(|word| prompt.contains(word))
+}
+
+/// Loopback listener answering the askpass program for the duration of one git operation, closed on drop
+pub struct AskpassSession {
+  address: SocketAddr,
+  token: String,
+  closed: Arc<AtomicBool>,
+}
+
+impl AskpassSession {
+  #[instrument(skip_all)]
+  pub fn start(prompts: AskpassPrompts, channel: Channel<CredentialPrompt>) -> Result<Self> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).context("Failed to start askpass listener")?;
+    let address = listener.local_addr()?;
+    let random = RandomState::new();
+    let token = format!("{:016x}{:016x}", random.hash_one(address), random.hash_one(std::process::id()));
+    let closed = Arc::new(AtomicBool::new(false));
+
+    let session_token = token.clone();
+    let session_closed = closed.clone();
+    thread::Builder::new().name("askpass".to_string()).spawn(move || {
+      // git asks one prompt at a time
+      for stream in listener.incoming() {
+        if session_closed.load(Ordering::Relaxed) {
+          break;
+        }
+        let Ok(stream) = stream else {
+          continue;
+        };
+        if let Err(e) = serve(stream, &session_token, &prompts, &channel) {
+          warn!(error = %e, "Failed to serve askpass request");
+        }
+      }
+      debug!("Askpass session closed");
+    })?;
+    Ok(Self { address, token, closed })
+  }
+
+  /// Environment making git ask through this session
+  pub fn askpass_env(&self) -> Result<AskpassEnv> {
+    let program = std::env::current_exe().context("Failed to resolve the app executable")?;
+    Ok(AskpassEnv::new(
+      program.to_string_lossy().into_owned(),
+      vec![
+        (ASKPASS_ADDRESS_ENV.to_string(), self.address.to_string()),
+        (ASKPASS_TOKEN_ENV.to_string(), self.token.clone()),
+      ],
+    ))
+  }
+}
+
+impl Drop for AskpassSession {
+  fn drop(&mut self) {
+    self.closed.store(true, Ordering::Relaxed);
+    // Wakes up the accept loop to see the flag
+    let _ = TcpStream::connect(self.address);
+  }
+}
+
+/// Request is the token and the prompt, one per line. The answer is written as a line, closing without one cancels.
+fn serve(stream: TcpStream, token: &str, prompts: &AskpassPrompts, channel: &Channel<CredentialPrompt>) -> Result<()> {
+  stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+  let mut reader = BufReader::new(stream.try_clone()?);
+  let mut line = String::new();
+  reader.read_line(&mut line)?;
+  if line.trim_end() != token {
+    warn!("Askpass request with a wrong token");
+    return Ok(());
+  }
+  line.clear();
+  reader.read_line(&mut line)?;
+  if let Some(answer) = prompts.ask(channel, line.trim_end().to_string()) {
+    writeln!(&stream, "{answer}")?;
+  }
+  Ok(())
+}
+
+/// Entry point of the executable started by git as askpass program, returns the exit code
+pub fn run_askpass_client() -> i32 {
+  match ask_app() {
+    Ok(Some(answer)) => {
+      println!("{answer}");
+      0
+    }
+    Ok(None) => 1,
+    Err(e) => {
+      eprintln!("Askpass failed: {e:#}");
+      1
+    }
+  }
+}
+
+fn ask_app() -> Result<Option<String>> {
+  let address = std::env::var(ASKPASS_ADDRESS_ENV)?;
+  let token = std::env::var(ASKPASS_TOKEN_ENV).with_context(|| format!("{ASKPASS_TOKEN_ENV} is not set"))?;
+  let prompt = std::env::args().nth(1).unwrap_or_default();
+  let stream = TcpStream::connect(&address).with_context(|| format!("Failed to connect to {address}"))?;
+  writeln!(&stream, "{token}\n{}", prompt.replace(['\r', '\n'], " "))?;
+  let mut answer = String::new();
+  if BufReader::new(stream).read_line(&mut answer)? == 0 {
+    return Ok(None);
+  }
+  Ok(Some(answer.trim_end_matches(['\r', '\n']).to_string()))
+}
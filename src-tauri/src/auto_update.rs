@@ -1,3 +1,4 @@
+use git_ops::error::{CommandError, ErrorCode};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 #[cfg(feature = "auto-update")]
@@ -152,7 +153,7 @@ pub type SharedUpdateState = std::sync::Mutex<UpdateState>;
 #[specta::specta]
 #[instrument(skip_all)]
 #[allow(unused_variables)]
-pub async fn check_for_updates(app_handle: tauri::AppHandle, update_state: tauri::State<'_, SharedUpdateState>) -> Result<UpdateInfo, String> {
+pub async fn check_for_updates(app_handle: tauri::AppHandle, update_state: tauri::State<'_, SharedUpdateState>) -> Result<UpdateInfo, CommandError> {
   #[cfg(feature = "auto-update")]
   {
     info!("Checking for updates");
@@ -174,7 +175,7 @@ pub async fn check_for_updates(app_handle: tauri::AppHandle, update_state: tauri
         let m = format!("Update check failed: {e:?}");
         let mut state = update_state.lock().map_err(|_| "Failed to lock state".to_string())?;
         state.info.status = UpdateStatus::Error(m.clone());
-        Err(m)
+        Err(m.into())
       }
     }
   }
@@ -182,7 +183,7 @@ pub async fn check_for_updates(app_handle: tauri::AppHandle, update_state: tauri
   #[cfg(not(feature = "auto-update"))]
   {
     error!("Auto-update feature is not enabled");
-    Err("Auto-update feature is not enabled in this build".to_string())
+    Err(CommandError::new(ErrorCode::Unsupported, "Auto-update feature is not enabled in this build"))
   }
 }
 
@@ -190,7 +191,7 @@ pub async fn check_for_updates(app_handle: tauri::AppHandle, update_state: tauri
 #[specta::specta]
 #[instrument(skip_all)]
 #[allow(unused_variables)]
-pub async fn install_update(app_handle: tauri::AppHandle, update_state: tauri::State<'_, SharedUpdateState>) -> Result<(), String> {
+pub async fn install_update(app_handle: tauri::AppHandle, update_state: tauri::State<'_, SharedUpdateState>) -> Result<(), CommandError> {
   #[cfg(feature = "auto-update")]
   {
     // Get the pending update and downloaded bytes
@@ -200,7 +201,7 @@ pub async fn install_update(app_handle: tauri::AppHandle, update_state: tauri::S
       if state.pending_update.is_none() || state.downloaded_bytes.is_none() {
         let error = "No update available to install".to_string();
         state.info.status = UpdateStatus::Error(error.clone());
-        return Err(error);
+        return Err(error.into());
       }
 
       state.info.status = UpdateStatus::Installing;
@@ -227,7 +228,7 @@ pub async fn install_update(app_handle: tauri::AppHandle, update_state: tauri::S
   #[cfg(not(feature = "auto-update"))]
   {
     error!("Auto-update feature is not enabled");
-    Err("Auto-update feature is not enabled in this build".to_string())
+    Err(CommandError::new(ErrorCode::Unsupported, "Auto-update feature is not enabled in this build"))
   }
 }
 
@@ -235,7 +236,7 @@ pub async fn install_update(app_handle: tauri::AppHandle, update_state: tauri::S
 #[specta::specta]
 #[instrument(skip_all)]
 #[allow(unused_variables)]
-pub async fn get_update_status(update_state: tauri::State<'_, SharedUpdateState>) -> Result<UpdateInfo, String> {
+pub async fn get_update_status(update_state: tauri::State<'_, SharedUpdateState>) -> Result<UpdateInfo, CommandError> {
   #[cfg(feature = "auto-update")]
   {
     let state = update_state.lock().map_err(|e| format!("Failed to lock state: {e:?}"))?;
@@ -245,6 +246,6 @@ pub async fn get_update_status(update_state: tauri::State<'_, SharedUpdateState>
   #[cfg(not(feature = "auto-update"))]
   {
     error!("Auto-update feature is not enabled");
-    Err("Auto-update feature is not enabled in this build".to_string())
+    Err(CommandError::new(ErrorCode::Unsupported, "Auto-update feature is not enabled in this build"))
   }
 }
@@ -1,4 +1,5 @@
 use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
 use sync_core::add_issue_reference::{AddIssueReferenceParams, AddIssueReferenceResult, add_issue_reference_to_commits_core};
 use tauri::State;
 use tracing::instrument;
@@ -8,10 +9,10 @@ use tracing::instrument;
 #[tauri::command]
 #[specta::specta]
 #[instrument(skip(git_executor))]
-pub async fn add_issue_reference_to_commits(git_executor: State<'_, GitCommandExecutor>, params: AddIssueReferenceParams) -> Result<AddIssueReferenceResult, String> {
+pub async fn add_issue_reference_to_commits(git_executor: State<'_, GitCommandExecutor>, params: AddIssueReferenceParams) -> Result<AddIssueReferenceResult, CommandError> {
   // Clone the executor since spawn_blocking requires 'static lifetime
   let git = (*git_executor).clone();
-  tokio::task::spawn_blocking(move || add_issue_reference_to_commits_core(&git, params))
+  tokio::task::spawn_blocking(move || add_issue_reference_to_commits_core(&git, params).map_err(CommandError::from))
     .await
     .map_err(|e| format!("Task failed: {e}"))?
 }
@@ -1,5 +1,8 @@
 use git_executor::git_command_executor::GitCommandExecutor;
-use sync_core::amend_to_branch::{AmendCommandResult, AmendUncommittedToBranchParams, amend_uncommitted_to_branch_core};
+use git_ops::amend_operations::AmendPreview;
+use git_ops::error::CommandError;
+use sync_core::amend_to_branch::{AmendCommandResult, AmendUncommittedToBranchParams, PreviewAmendParams, amend_uncommitted_to_branch_core, preview_amend_core};
+use sync_core::phase_budget::wait_for_background_phases;
 use tauri::State;
 use tracing::instrument;
 
@@ -8,10 +11,24 @@ use tracing::instrument;
 #[tauri::command]
 #[specta::specta]
 #[instrument(skip(git_executor))]
-pub async fn amend_uncommitted_to_branch(git_executor: State<'_, GitCommandExecutor>, params: AmendUncommittedToBranchParams) -> Result<AmendCommandResult, String> {
+pub async fn amend_uncommitted_to_branch(git_executor: State<'_, GitCommandExecutor>, params: AmendUncommittedToBranchParams) -> Result<AmendCommandResult, CommandError> {
+  wait_for_background_phases(&params.repository_path).await;
   let git = (*git_executor).clone();
 
-  tokio::task::spawn_blocking(move || amend_uncommitted_to_branch_core(&git, params))
+  tokio::task::spawn_blocking(move || amend_uncommitted_to_branch_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Compute the diff of a commit before and after amending uncommitted changes to it, without rewriting anything,
+/// so the amend can be confirmed first.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn preview_amend(git_executor: State<'_, GitCommandExecutor>, params: PreviewAmendParams) -> Result<AmendPreview, CommandError> {
+  let git = (*git_executor).clone();
+
+  tokio::task::spawn_blocking(move || preview_amend_core(&git, params).map_err(CommandError::from))
     .await
     .map_err(|e| format!("Task failed: {}", e))?
 }
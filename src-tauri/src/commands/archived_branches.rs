@@ -1,21 +1,178 @@
+use crate::askpass::{AskpassPrompts, AskpassSession, CredentialPrompt};
+use crate::progress::{SyncEvent, TauriProgressReporter};
 use branch_integration::archive::get_archived_branch_commits as get_commits;
+use branch_integration::archive::{ArchiveRetentionReport, ArchivedBranchCommitsPage, get_archived_branch_commits_page as get_commits_page};
+use branch_integration::detector::resume_integration_detection as resume_detection;
+use branch_integration::lookback::LookbackWindow;
+use branch_integration::strategy::get_detection_strategy;
 use git_executor::git_command_executor::GitCommandExecutor;
 use git_ops::commit_list::Commit;
-use sync_core::delete_archived_branch::{DeleteArchivedBranchParams, delete_archived_branch_core};
+use git_ops::conflict_analysis::{FileDiff, get_commit_file_diffs};
+use git_ops::diff_options::load_diff_options;
+use git_ops::error::CommandError;
+use serde::Deserialize;
+use sync_core::archive_retention::{ApplyArchiveRetentionParams, apply_archive_retention_core};
+use sync_core::credentials::AskpassEnv;
+use sync_core::delete_archived_branch::{
+  DeleteArchivedBranchParams, DeleteArchivedBranchesParams, DeleteArchivedBranchesProgress, DeleteArchivedBranchesResult, delete_archived_branch_core,
+  delete_archived_branches_core,
+};
+use sync_core::phase_budget::wait_for_background_phases;
 use sync_core::sync::detect_baseline_branch;
+use tauri::ipc::Channel;
 
 #[tauri::command]
 #[specta::specta]
-pub async fn get_archived_branch_commits(git_executor: tauri::State<'_, GitCommandExecutor>, repository_path: String, branch_name: String) -> Result<Vec<Commit>, String> {
+pub async fn get_archived_branch_commits(git_executor: tauri::State<'_, GitCommandExecutor>, repository_path: String, branch_name: String) -> Result<Vec<Commit>, CommandError> {
   // Detect the baseline branch (prefer "master" as default)
-  let baseline_branch = detect_baseline_branch(&git_executor, &repository_path, "master").map_err(|e| e.to_string())?;
+  let baseline_branch = detect_baseline_branch(&git_executor, &repository_path, "master").map_err(CommandError::from)?;
 
-  get_commits(&git_executor, &repository_path, &branch_name, &baseline_branch).map_err(|e| e.to_string())
+  get_commits(&git_executor, &repository_path, &branch_name, &baseline_branch).map_err(CommandError::from)
 }
 
+/// Page size used when the caller doesn't specify a limit
+const DEFAULT_COMMITS_PAGE_SIZE: u32 = 50;
+
+#[derive(Debug, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GetArchivedBranchCommitsPageParams {
+  pub repository_path: String,
+  pub branch_name: String,
+  /// Number of commits to skip, counted from the branch tip
+  pub offset: u32,
+  /// Maximum number of commits to return (50 by default)
+  pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCommitDiffParams {
+  pub repository_path: String,
+  pub commit_id: String,
+}
+
+/// Returns a page of archived branch commits without diffs, newest page first
+#[tauri::command]
+#[specta::specta]
+pub async fn get_archived_branch_commits_page(
+  git_executor: tauri::State<'_, GitCommandExecutor>,
+  params: GetArchivedBranchCommitsPageParams,
+) -> Result<ArchivedBranchCommitsPage, CommandError> {
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || {
+    let baseline_branch = detect_baseline_branch(&git, &params.repository_path, "master")?;
+    let limit = params.limit.unwrap_or(DEFAULT_COMMITS_PAGE_SIZE);
+    get_commits_page(&git, &params.repository_path, &params.branch_name, &baseline_branch, params.offset, limit)
+  })
+  .await
+  .map_err(|e| format!("Task failed: {e}"))?
+  .map_err(CommandError::from)
+}
+
+/// Returns the file diffs of a single commit, loaded when the user expands it
+#[tauri::command]
+#[specta::specta]
+pub async fn get_commit_diff(git_executor: tauri::State<'_, GitCommandExecutor>, params: GetCommitDiffParams) -> Result<Vec<FileDiff>, CommandError> {
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || {
+    let diff_options = load_diff_options(&git, &params.repository_path);
+    get_commit_file_diffs(&git, &params.repository_path, &params.commit_id, &diff_options)
+  })
+  .await
+  .map_err(|e| format!("Task failed: {e}"))?
+  .map_err(CommandError::from)
+}
+
+#[derive(Debug, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeIntegrationDetectionParams {
+  pub repository_path: String,
+  /// Full names of the archived branches
+  pub branch_names: Vec<String>,
+}
+
+/// Runs the full integration detection of archived branches whose detection was truncated by the budget,
+/// sending `branchIntegrationDetected` events over the channel
+#[tauri::command]
+#[specta::specta]
+pub async fn resume_integration_detection(
+  git_executor: tauri::State<'_, GitCommandExecutor>,
+  params: ResumeIntegrationDetectionParams,
+  progress: Channel<SyncEvent>,
+) -> Result<(), CommandError> {
+  let baseline_branch = detect_baseline_branch(&git_executor, &params.repository_path, "master").map_err(CommandError::from)?;
+  let progress = TauriProgressReporter::new(progress);
+  resume_detection(
+    &git_executor,
+    &params.repository_path,
+    &baseline_branch,
+    params.branch_names,
+    get_detection_strategy(),
+    LookbackWindow::default(),
+    &progress,
+  )
+  .await
+  .map_err(CommandError::from)
+}
+
+/// Deletes an archived branch and, with `deleteRemote`, its remote branches.
+/// Credentials for deleting the remote branches are asked for over `credential_prompts`, like for `push_branch`.
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_archived_branch(
+  git_executor: tauri::State<'_, GitCommandExecutor>,
+  askpass_prompts: tauri::State<'_, AskpassPrompts>,
+  params: DeleteArchivedBranchParams,
+  credential_prompts: Channel<CredentialPrompt>,
+) -> Result<(), CommandError> {
+  wait_for_background_phases(&params.repository_path).await;
+  let git = (*git_executor).clone();
+  let askpass_prompts = (*askpass_prompts).clone();
+  tokio::task::spawn_blocking(move || {
+    // Open only if the remote is contacted
+    let askpass_session = if params.delete_remote {
+      Some(AskpassSession::start(askpass_prompts, credential_prompts).map_err(CommandError::from)?)
+    } else {
+      None
+    };
+    let askpass = match &askpass_session {
+      Some(session) => session.askpass_env().map_err(CommandError::from)?,
+      None => AskpassEnv::none(),
+    };
+    delete_archived_branch_core(&git, params, &askpass).map_err(CommandError::from)
+  })
+  .await
+  .map_err(|e| format!("Task failed: {e}"))?
+}
+
+/// Deletes many archived branches in one ref transaction, reporting progress over the channel
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_archived_branches(
+  git_executor: tauri::State<'_, GitCommandExecutor>,
+  params: DeleteArchivedBranchesParams,
+  progress: Channel<DeleteArchivedBranchesProgress>,
+) -> Result<DeleteArchivedBranchesResult, CommandError> {
+  wait_for_background_phases(&params.repository_path).await;
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || {
+    delete_archived_branches_core(&git, params, |event| {
+      let _ = progress.send(event);
+    })
+  })
+  .await
+  .map_err(|e| format!("Task failed: {e}"))?
+  .map_err(CommandError::from)
+}
+
+/// Deletes archived branches matching the retention policy, or only reports them in dry-run mode
 #[tauri::command]
 #[specta::specta]
-pub async fn delete_archived_branch(git_executor: tauri::State<'_, GitCommandExecutor>, params: DeleteArchivedBranchParams) -> Result<(), String> {
-  delete_archived_branch_core(&git_executor, params).map_err(|e| e.to_string())?;
-  Ok(())
+pub async fn apply_archive_retention(git_executor: tauri::State<'_, GitCommandExecutor>, params: ApplyArchiveRetentionParams) -> Result<ArchiveRetentionReport, CommandError> {
+  wait_for_background_phases(&params.repository_path).await;
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || apply_archive_retention_core(&git, params))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+    .map_err(CommandError::from)
 }
@@ -0,0 +1,17 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::baseline_update::{self, CheckBaselineUpdateParams};
+use sync_types::BaselineAdvanced;
+use tauri::State;
+use tokio::task;
+
+/// Checks whether the baseline advanced on the remote since the last sync (e.g. when the window gets focus),
+/// returns `None` if a re-sync wouldn't pick up new upstream commits
+#[tauri::command]
+#[specta::specta]
+pub async fn check_baseline_update(git_executor: State<'_, GitCommandExecutor>, params: CheckBaselineUpdateParams) -> Result<Option<BaselineAdvanced>, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || baseline_update::check_baseline_update(&git, &params.repository_path).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
@@ -0,0 +1,31 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::binary_conflict::{BinaryConflictResolution, ResolveBinaryConflictParams, ResolveBinaryConflictResult, resolve_binary_conflict as resolve_binary_conflict_core};
+use git_ops::error::CommandError;
+use tauri::State;
+use tauri_plugin_dialog::DialogExt;
+use tokio::task;
+use tracing::instrument;
+
+/// Records which version resolves a binary file conflict, the next sync applies it.
+/// A replacement with an empty path asks for the file; returns `None` if the user cancelled.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(app_handle, git_executor))]
+pub async fn resolve_binary_conflict(
+  app_handle: tauri::AppHandle,
+  git_executor: State<'_, GitCommandExecutor>,
+  mut params: ResolveBinaryConflictParams,
+) -> Result<Option<ResolveBinaryConflictResult>, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || {
+    if params.resolution == BinaryConflictResolution::Replacement(String::new()) {
+      let Some(path) = app_handle.dialog().file().set_title(format!("Choose Replacement for {}", params.file)).blocking_pick_file() else {
+        return Ok(None);
+      };
+      params.resolution = BinaryConflictResolution::Replacement(path.to_string());
+    }
+    resolve_binary_conflict_core(&git, params).map(Some).map_err(CommandError::from)
+  })
+  .await
+  .map_err(|e| format!("Task error: {}", e))?
+}
@@ -0,0 +1,17 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::branch_diff::{BranchDiff, GetBranchDiffParams, get_branch_diff_core};
+use tauri::State;
+use tracing::instrument;
+
+/// Returns the files changed by a virtual branch against the baseline with their line counts.
+/// The hunks of a file are only computed when it is requested, so the file list of a large branch loads fast.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn get_branch_diff(git_executor: State<'_, GitCommandExecutor>, params: GetBranchDiffParams) -> Result<BranchDiff, CommandError> {
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || get_branch_diff_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
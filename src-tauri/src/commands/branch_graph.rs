@@ -0,0 +1,16 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::branch_graph::{BranchGraph, GetBranchGraphParams, get_branch_graph_core};
+use tauri::State;
+use tracing::instrument;
+
+/// Returns the graph of the virtual branches, their commits and the local commits they were copied from, for a graph view.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn get_branch_graph(git_executor: State<'_, GitCommandExecutor>, params: GetBranchGraphParams) -> Result<BranchGraph, CommandError> {
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || get_branch_graph_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
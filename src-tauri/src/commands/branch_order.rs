@@ -0,0 +1,48 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::branch_order::{
+  BranchOrder, GetBranchOrderParams, ReorderBranchesParams, SetBranchPinnedParams, get_branch_order_core, reorder_branches_core, set_branch_pinned_core,
+};
+use sync_core::commit_order::{SetBranchCommitOrderParams, set_branch_commit_order_core};
+use tauri::State;
+use tokio::task;
+
+/// Returns pinned branches and the manual branch order persisted in the repository state
+#[tauri::command]
+#[specta::specta]
+pub async fn get_branch_order(git_executor: State<'_, GitCommandExecutor>, params: GetBranchOrderParams) -> Result<BranchOrder, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || get_branch_order_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Pins or unpins a branch
+#[tauri::command]
+#[specta::specta]
+pub async fn set_branch_pinned(git_executor: State<'_, GitCommandExecutor>, params: SetBranchPinnedParams) -> Result<BranchOrder, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || set_branch_pinned_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Persists a manual display order for branches
+#[tauri::command]
+#[specta::specta]
+pub async fn reorder_branches(git_executor: State<'_, GitCommandExecutor>, params: ReorderBranchesParams) -> Result<BranchOrder, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || reorder_branches_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Sets the order in which the commits of a branch are applied, effective on the next sync
+#[tauri::command]
+#[specta::specta]
+pub async fn set_branch_commit_order(git_executor: State<'_, GitCommandExecutor>, params: SetBranchCommitOrderParams) -> Result<(), CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || set_branch_commit_order_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
@@ -1,5 +1,7 @@
 use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
 use serde::Deserialize;
+use sync_core::branch_prefix::{BranchPrefixResolution, resolve_branch_prefix};
 use tauri::State;
 
 #[derive(Debug, Deserialize, specta::Type)]
@@ -8,8 +10,9 @@ pub struct GetBranchPrefixParams {
   pub repository_path: String,
 }
 
+/// Returns the branch prefix with the config files its values come from, in the order git reads them
 #[tauri::command]
 #[specta::specta]
-pub async fn get_branch_prefix_from_git_config(git_executor: State<'_, GitCommandExecutor>, params: GetBranchPrefixParams) -> Result<String, String> {
-  sync_core::branch_prefix::get_branch_prefix_from_git_config_sync(&git_executor, &params.repository_path).map_err(|e| e.to_string())
+pub async fn get_branch_prefix_from_git_config(git_executor: State<'_, GitCommandExecutor>, params: GetBranchPrefixParams) -> Result<BranchPrefixResolution, CommandError> {
+  resolve_branch_prefix(&git_executor, &params.repository_path).map_err(CommandError::from)
 }
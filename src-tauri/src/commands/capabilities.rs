@@ -0,0 +1,36 @@
+use model_ai::remote::AiProvider;
+use serde::Serialize;
+use specta::Type;
+use sync_core::hosting_provider::HostingProviderKind;
+
+/// Optional subsystems of this build, so the frontend can hide features instead of calling commands that fail at runtime
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+  /// Checking for and installing updates (`auto-update` feature)
+  pub auto_update: bool,
+  /// Exporting traces via OpenTelemetry (`telemetry` feature)
+  pub telemetry: bool,
+  /// Development-only commands such as `simulate_conflict`
+  pub development_tools: bool,
+  /// AI backends that can be selected in the AI provider settings
+  pub ai_providers: Vec<AiProvider>,
+  /// Hosting providers recognized for remotes
+  pub hosting_providers: Vec<HostingProviderKind>,
+  /// Periodic fetch and remote status refresh (`start_remote_status_refresh`)
+  pub remote_status_refresh: bool,
+}
+
+/// Lists optional subsystems compiled into or enabled in this build
+#[tauri::command]
+#[specta::specta]
+pub fn get_capabilities() -> Capabilities {
+  Capabilities {
+    auto_update: cfg!(feature = "auto-update"),
+    telemetry: cfg!(feature = "telemetry"),
+    development_tools: cfg!(debug_assertions),
+    ai_providers: vec![AiProvider::Local, AiProvider::OpenAi, AiProvider::Anthropic, AiProvider::Ollama],
+    hosting_providers: vec![HostingProviderKind::GitHub, HostingProviderKind::GitLab],
+    remote_status_refresh: true,
+  }
+}
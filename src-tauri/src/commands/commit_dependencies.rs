@@ -0,0 +1,25 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::commit_dependencies::{GetCommitDependenciesParams, SetCommitDependenciesParams, get_commit_dependencies_core, set_commit_dependencies_core};
+use tauri::State;
+use tokio::task;
+
+/// Returns the commits a commit is declared to depend on
+#[tauri::command]
+#[specta::specta]
+pub async fn get_commit_dependencies(git_executor: State<'_, GitCommandExecutor>, params: GetCommitDependenciesParams) -> Result<Vec<String>, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || get_commit_dependencies_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Declares that a commit depends on other commits; sync refuses to sync a branch that doesn't contain them before it
+#[tauri::command]
+#[specta::specta]
+pub async fn set_commit_dependencies(git_executor: State<'_, GitCommandExecutor>, params: SetCommitDependenciesParams) -> Result<Vec<String>, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || set_commit_dependencies_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
@@ -0,0 +1,25 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::commit_mapping::{CommitMapping, GetCommitMappingParams, get_commit_mapping_core, get_reverse_mapping_core};
+use tauri::State;
+use tokio::task;
+
+/// Returns the copies of an original commit on virtual branches, made by the latest and prior syncs
+#[tauri::command]
+#[specta::specta]
+pub async fn get_commit_mapping(git_executor: State<'_, GitCommandExecutor>, params: GetCommitMappingParams) -> Result<CommitMapping, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || get_commit_mapping_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Returns the original commits a virtual branch commit was copied from
+#[tauri::command]
+#[specta::specta]
+pub async fn get_reverse_mapping(git_executor: State<'_, GitCommandExecutor>, params: GetCommitMappingParams) -> Result<CommitMapping, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || get_reverse_mapping_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
@@ -0,0 +1,19 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::{CommandError, ErrorCode};
+use sync_core::conflict_simulation::{SimulateConflictParams, SimulateConflictResult, simulate_conflict_core};
+use tauri::State;
+use tokio::task;
+
+/// Dev-only: fabricate a scratch repository with a conflicting branch to explore the conflict viewer
+#[tauri::command]
+#[specta::specta]
+pub async fn simulate_conflict(git_executor: State<'_, GitCommandExecutor>, params: SimulateConflictParams) -> Result<SimulateConflictResult, CommandError> {
+  if !cfg!(debug_assertions) {
+    return Err(CommandError::new(ErrorCode::Unsupported, "Conflict simulation is only available in development builds"));
+  }
+
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || simulate_conflict_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
@@ -0,0 +1,57 @@
+use crate::crash_report::{CrashReport, crash_reports_dir, format_crash_report, read_crash_reports};
+use anyhow::anyhow;
+use git_ops::error::CommandError;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+use tokio::task;
+use tracing::instrument;
+
+fn crash_dir(app_handle: &AppHandle) -> Result<std::path::PathBuf, CommandError> {
+  let app_log_dir = app_handle
+    .path()
+    .app_log_dir()
+    .map_err(|e| CommandError::from(anyhow!("Failed to resolve log directory: {e}")))?;
+  Ok(crash_reports_dir(&app_log_dir))
+}
+
+/// Returns the crash reports written by the panic hook, newest first
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(app_handle))]
+pub async fn get_crash_reports(app_handle: AppHandle) -> Result<Vec<CrashReport>, CommandError> {
+  let crash_dir = crash_dir(&app_handle)?;
+  task::spawn_blocking(move || read_crash_reports(&crash_dir).map_err(|e| CommandError::from(anyhow!("Failed to read crash reports: {e}"))))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Asks where to save and writes a crash report as text, e.g. to attach to an issue. Nothing is sent anywhere.
+/// Returns the path of the file, `None` if the user cancelled.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(app_handle))]
+pub async fn export_crash_report(app_handle: AppHandle, id: String) -> Result<Option<String>, CommandError> {
+  let crash_dir = crash_dir(&app_handle)?;
+  task::spawn_blocking(move || {
+    let reports = read_crash_reports(&crash_dir).map_err(|e| CommandError::from(anyhow!("Failed to read crash reports: {e}")))?;
+    let report = reports
+      .into_iter()
+      .find(|report| report.id == id)
+      .ok_or_else(|| CommandError::from(anyhow!("Crash report {id} not found")))?;
+    let Some(path) = app_handle
+      .dialog()
+      .file()
+      .set_title("Export Crash Report")
+      .set_file_name(format!("branch-deck-{id}.txt"))
+      .add_filter("Text", &["txt"])
+      .blocking_save_file()
+    else {
+      return Ok(None);
+    };
+    let path = path.to_string();
+    std::fs::write(&path, format_crash_report(&report)).map_err(|e| CommandError::from(anyhow!("Failed to write {path}: {e}")))?;
+    Ok(Some(path))
+  })
+  .await
+  .map_err(|e| format!("Task error: {}", e))?
+}
@@ -0,0 +1,20 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::drop_commits::{DropCommitsParams, DropCommitsResult, drop_commits_core};
+use sync_core::phase_budget::wait_for_background_phases;
+use tauri::State;
+use tracing::instrument;
+
+/// Drops commits from the current branch, keeping the working directory as is.
+/// The report lists the later commits that change, the affected virtual branches and which of them need a force push;
+/// with `dryRun` only the report is computed.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn drop_commits(git_executor: State<'_, GitCommandExecutor>, params: DropCommitsParams) -> Result<DropCommitsResult, CommandError> {
+  wait_for_background_phases(&params.repository_path).await;
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || drop_commits_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
@@ -0,0 +1,19 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use git_ops::patch_export::{ExportBranchPatchesParams, ExportBranchPatchesResult, export_branch_patches as export_patches_core};
+use sync_core::sync::detect_baseline_branch;
+use tauri::State;
+use tokio::task;
+
+/// Exports a virtual branch as a `git format-patch` series or a single mbox file
+#[tauri::command]
+#[specta::specta]
+pub async fn export_branch_patches(git_executor: State<'_, GitCommandExecutor>, params: ExportBranchPatchesParams) -> Result<ExportBranchPatchesResult, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || {
+    let baseline_branch = detect_baseline_branch(&git, &params.repository_path, "master").map_err(CommandError::from)?;
+    export_patches_core(&git, params, &baseline_branch).map_err(CommandError::from)
+  })
+  .await
+  .map_err(|e| format!("Task error: {}", e))?
+}
@@ -0,0 +1,22 @@
+use git_executor::audit_log::{AuditLogFormat, GitAuditEntry};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use tauri::State;
+use tokio::task;
+
+/// Returns the latest git commands run by the app, oldest first
+#[tauri::command]
+#[specta::specta]
+pub async fn get_git_audit_log(git_executor: State<'_, GitCommandExecutor>, limit: Option<u32>) -> Result<Vec<GitAuditEntry>, CommandError> {
+  Ok(git_executor.audit_log().entries(limit.map(|limit| limit as usize)))
+}
+
+/// Writes the git audit log to a file, e.g. to attach to a bug report about a wrong rewrite
+#[tauri::command]
+#[specta::specta]
+pub async fn export_git_audit_log(git_executor: State<'_, GitCommandExecutor>, path: String, format: AuditLogFormat) -> Result<(), CommandError> {
+  let content = git_executor.audit_log().export(format);
+  task::spawn_blocking(move || std::fs::write(&path, content).map_err(|e| CommandError::from(anyhow::anyhow!("Failed to write {path}: {e}"))))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
@@ -0,0 +1,58 @@
+use crate::repository_state::RepositoryStateCache;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_executor::git_info::{GitInstallation, MIN_GIT_VERSION, detect_git_installations as detect_git_installations_core};
+use git_ops::error::{CommandError, ErrorCode};
+use std::path::Path;
+use tauri::State;
+use tokio::task;
+use tracing::warn;
+
+/// Lists the git executables found at the usual install locations and in `PATH`, with their versions
+#[tauri::command]
+#[specta::specta]
+pub async fn detect_git_installations() -> Result<Vec<GitInstallation>, CommandError> {
+  Ok(task::spawn_blocking(detect_git_installations_core).await.map_err(|e| format!("Task error: {}", e))?)
+}
+
+/// Switches the git executable used for all repositories, `null` goes back to the auto-detected one.
+/// An executable that doesn't run or is older than the minimum version is rejected and the current one is kept.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_git_path(git_executor: State<'_, GitCommandExecutor>, cache: State<'_, RepositoryStateCache>, path: Option<String>) -> Result<GitInstallation, CommandError> {
+  let git = (*git_executor).clone();
+  let installation = task::spawn_blocking(move || -> Result<GitInstallation, CommandError> {
+    if let Some(path) = &path {
+      let installation = GitInstallation::probe(path, "Custom");
+      if let Some(error) = installation.error {
+        return Err(match installation.version {
+          Some(version) => {
+            let (min_major, min_minor) = MIN_GIT_VERSION;
+            CommandError::new(ErrorCode::UnsupportedGitVersion, error)
+              .with_context("gitPath", path)
+              .with_context("version", version)
+              .with_context("minimumVersion", format!("{min_major}.{min_minor}"))
+          }
+          // Doesn't run, the message has the reason
+          None => CommandError::new(ErrorCode::Internal, error).with_context("gitPath", path),
+        });
+      }
+    }
+    let info = git.set_git_path(path.as_deref()).map_err(CommandError::from)?;
+    Ok(GitInstallation::probe(&info.path, if path.is_some() { "Custom" } else { "Auto-detected" }))
+  })
+  .await
+  .map_err(|e| format!("Task error: {}", e))??;
+
+  // Version checks of repositories were made with the previous executable
+  cache.clear_all();
+  Ok(installation)
+}
+
+/// Custom git executable from the app settings (`appSettings.gitPath` in `settings.json`), applied before the UI loads
+pub fn load_git_path_setting(settings_path: &Path) -> Option<String> {
+  let content = std::fs::read_to_string(settings_path).ok()?;
+  let settings: serde_json::Value = serde_json::from_str(&content)
+    .inspect_err(|e| warn!(error = %e, "Invalid settings, using the auto-detected git"))
+    .ok()?;
+  settings["appSettings"]["gitPath"].as_str().filter(|path| !path.trim().is_empty()).map(str::to_string)
+}
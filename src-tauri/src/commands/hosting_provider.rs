@@ -0,0 +1,27 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::hosting_provider::{
+  GetRemoteHostingConfigsParams, RemoteHostingConfig, SetRemoteHostingOverrideParams, get_remote_hosting_configs_core, set_remote_hosting_override_core,
+};
+use tauri::State;
+use tokio::task;
+
+/// Returns the hosting provider, API base URL and CA override of every remote on GitHub or GitLab (including self-hosted instances)
+#[tauri::command]
+#[specta::specta]
+pub async fn get_remote_hosting_configs(git_executor: State<'_, GitCommandExecutor>, params: GetRemoteHostingConfigsParams) -> Result<Vec<RemoteHostingConfig>, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || get_remote_hosting_configs_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Overrides the detected hosting provider settings of a remote
+#[tauri::command]
+#[specta::specta]
+pub async fn set_remote_hosting_override(git_executor: State<'_, GitCommandExecutor>, params: SetRemoteHostingOverrideParams) -> Result<Option<RemoteHostingConfig>, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || set_remote_hosting_override_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
@@ -0,0 +1,15 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use git_ops::patch_import::{ApplyPatchSeriesParams, ApplyPatchSeriesResult, apply_patch_series as apply_patch_series_core};
+use tauri::State;
+use tokio::task;
+
+/// Applies a patch series onto the current branch with subjects prefixed to group into a virtual branch
+#[tauri::command]
+#[specta::specta]
+pub async fn apply_patch_series(git_executor: State<'_, GitCommandExecutor>, params: ApplyPatchSeriesParams) -> Result<ApplyPatchSeriesResult, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || apply_patch_series_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
@@ -1,3 +1,4 @@
+use git_ops::error::CommandError;
 use tauri::{AppHandle, Manager, State};
 use tracing::instrument;
 
@@ -6,10 +7,10 @@ use crate::menu_state::MenuState;
 #[tauri::command]
 #[specta::specta]
 #[instrument(skip(app))]
-pub async fn update_menu_checkbox(app: AppHandle, menu_id: String, checked: bool) -> Result<(), String> {
+pub async fn update_menu_checkbox(app: AppHandle, menu_id: String, checked: bool) -> Result<(), CommandError> {
   // Currently we only support the auto_sync_on_focus checkbox
   if menu_id != "auto_sync_on_focus" {
-    return Err(format!("Unsupported menu_id: {}", menu_id));
+    return Err(CommandError::validation(format!("Unsupported menu_id: {}", menu_id)));
   }
 
   // Get MenuState from app state
@@ -0,0 +1,18 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use git_ops::merge_tool::{LaunchMergeToolParams, LaunchMergeToolResult, launch_merge_tool as launch_merge_tool_core};
+use tauri::State;
+use tokio::task;
+use tracing::instrument;
+
+/// Opens a conflicting file in the merge tool configured for git and waits for it to exit.
+/// The merged file is recorded as the resolution of the conflict, the next sync applies it.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn launch_merge_tool(git_executor: State<'_, GitCommandExecutor>, params: LaunchMergeToolParams) -> Result<LaunchMergeToolResult, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || launch_merge_tool_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
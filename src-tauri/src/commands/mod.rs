@@ -1,14 +1,47 @@
 pub mod add_issue_reference;
 pub mod amend_to_branch;
 pub mod archived_branches;
+pub mod baseline_update;
+pub mod binary_conflict;
+pub mod branch_diff;
+pub mod branch_graph;
+pub mod branch_order;
 pub mod branch_prefix;
+pub mod capabilities;
 pub mod clear_model_cache;
+pub mod commit_dependencies;
+pub mod commit_mapping;
+pub mod conflict_simulation;
+pub mod crash_reports;
 pub mod create_branch;
+pub mod drop_commits;
+pub mod export_patches;
+pub mod git_audit_log;
+pub mod git_installation;
+pub mod hosting_provider;
+pub mod import_patches;
 pub mod menu_commands;
+pub mod merge_tool;
+pub mod network;
+pub mod operation_plan;
+pub mod prefix_typo;
 pub mod push;
+pub mod reassign_commit;
+pub mod rebase_branch_onto;
+pub mod recovery;
+pub mod remote_status_refresh;
+pub mod rename_virtual_branch;
 pub mod repository_browser;
+pub mod review_status;
+pub mod reword_commits;
+pub mod send_email;
+pub mod settings_transfer;
+pub mod squash_branch;
 pub mod suggest_branch_name;
 pub mod sync_branches;
 pub mod unapply_branch;
 pub mod uncommitted_changes;
+pub mod usage_stats;
+pub mod verify_metadata;
+pub mod webhook;
 pub mod window_management;
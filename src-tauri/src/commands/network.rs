@@ -0,0 +1,20 @@
+use git_ops::error::CommandError;
+use sync_core::network::{network_status, set_offline_mode as set_offline_mode_core};
+use sync_types::NetworkStatus;
+use tokio::task;
+
+/// Returns whether network requests are made: offline mode, detected offline or online
+#[tauri::command]
+#[specta::specta]
+pub async fn get_network_status() -> Result<NetworkStatus, CommandError> {
+  Ok(task::spawn_blocking(network_status).await.map_err(|e| format!("Task error: {}", e))?)
+}
+
+/// Switches the offline mode for the whole app: remote status, hosting provider queries, the fetch before sync and model
+/// downloads are skipped while it's on
+#[tauri::command]
+#[specta::specta]
+pub async fn set_offline_mode(enabled: bool) -> Result<NetworkStatus, CommandError> {
+  set_offline_mode_core(enabled);
+  get_network_status().await
+}
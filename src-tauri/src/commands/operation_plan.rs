@@ -0,0 +1,54 @@
+use crate::progress::{SyncEvent, TauriProgressReporter};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use serde::Deserialize;
+use sync_core::operation_plan::{ExecuteOperationPlanParams, OperationPlanResult, execute_operation_plan as execute_operation_plan_core};
+use sync_core::phase_budget::wait_for_background_phases;
+use sync_core::undo_snapshot::{UndoSnapshot, load_undo_snapshot, restore_undo_snapshot};
+use tauri::State;
+use tauri::ipc::Channel;
+use tracing::{error, instrument};
+
+#[derive(Debug, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoSnapshotParams {
+  pub repository_path: String,
+}
+
+/// Executes an ordered list of operations (rename, move commits, add issue references, sync, push) with a single undo snapshot.
+/// Progress of all operations is sent over one channel.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor, progress), fields(repository_path = %params.repository_path, operation_count = params.operations.len()))]
+pub async fn execute_operation_plan(
+  git_executor: State<'_, GitCommandExecutor>,
+  params: ExecuteOperationPlanParams,
+  progress: Channel<SyncEvent>,
+) -> Result<OperationPlanResult, CommandError> {
+  execute_operation_plan_core(&git_executor, params, TauriProgressReporter::new(progress)).await.map_err(|e| {
+    error!(error = ?e, "Operation plan failed");
+    CommandError::from(e)
+  })
+}
+
+/// Returns the snapshot the last operation plan can be undone to, `None` if there is nothing to undo
+#[tauri::command]
+#[specta::specta]
+pub async fn get_undo_snapshot(git_executor: State<'_, GitCommandExecutor>, params: UndoSnapshotParams) -> Result<Option<UndoSnapshot>, CommandError> {
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || load_undo_snapshot(&git, &params.repository_path).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
+
+/// Restores the local branches to the state before the last operation plan; pushed branches are not reverted on the remote
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn undo_operation_plan(git_executor: State<'_, GitCommandExecutor>, params: UndoSnapshotParams) -> Result<UndoSnapshot, CommandError> {
+  wait_for_background_phases(&params.repository_path).await;
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || restore_undo_snapshot(&git, &params.repository_path).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
@@ -0,0 +1,18 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::prefix_typo::{FixPrefixTypoParams, fix_prefix_typo_core};
+use sync_core::reword_commits::RewordCommitsResult;
+use tauri::State;
+use tracing::instrument;
+
+/// Moves the commits of a probably misspelled branch (see the `possiblePrefixTypo` sync event) to the suggested branch
+/// by rewording their prefix. Returns the old to new commit ID mapping.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn fix_prefix_typo(git_executor: State<'_, GitCommandExecutor>, params: FixPrefixTypoParams) -> Result<RewordCommitsResult, CommandError> {
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || fix_prefix_typo_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
@@ -1,9 +1,15 @@
+use crate::askpass::{AskpassPrompts, AskpassSession, CredentialPrompt};
 use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
 use git_ops::model::to_final_branch_name;
 use serde::Deserialize;
-use sync_core::remote_status::compute_remote_status_for_branch;
+use sync_core::branch_protection::check_push_allowed;
+use sync_core::phase_budget::wait_for_background_phases;
+use sync_core::pre_push::{PrePushCheck, PrePushEvent, run_pre_push_check};
+use sync_core::remote_status::{compute_remote_status_for_branch, push_virtual_branch};
 use sync_types::RemoteStatusUpdate;
 use tauri::State;
+use tauri::ipc::Channel;
 
 #[derive(Debug, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
@@ -16,36 +22,41 @@ pub struct PushBranchParams {
   pub baseline_branch: String,
 }
 
-/// Pushes a specific branch to the remote repository and returns updated remote status
+/// Pushes a specific branch to the remote repository and returns updated remote status.
+/// The pre-push check (if enabled) runs first, its output is streamed over the channel.
+/// Credentials missing from the git credential helpers are asked for over `credential_prompts`,
+/// answered with `answer_credential_prompt`.
 #[tauri::command]
 #[specta::specta]
-pub async fn push_branch(git_executor: State<'_, GitCommandExecutor>, params: PushBranchParams) -> Result<RemoteStatusUpdate, String> {
+pub async fn push_branch(
+  git_executor: State<'_, GitCommandExecutor>,
+  askpass_prompts: State<'_, AskpassPrompts>,
+  params: PushBranchParams,
+  pre_push_output: Channel<PrePushEvent>,
+  credential_prompts: Channel<CredentialPrompt>,
+) -> Result<RemoteStatusUpdate, CommandError> {
+  wait_for_background_phases(&params.repository_path).await;
   // Clone the executor since spawn_blocking requires 'static lifetime
   let git = (*git_executor).clone();
+  let askpass_prompts = (*askpass_prompts).clone();
 
   tokio::task::spawn_blocking(move || {
     let repository_path = &params.repository_path;
     let branch_prefix = &params.branch_prefix;
     let branch_name = &params.branch_name;
-    let final_branch_name = to_final_branch_name(branch_prefix, branch_name).map_err(|e| format!("{e:?}"))?;
-
-    // Perform the push
-    git
-      .execute_command(
-        &[
-          "-c",
-          "credential.helper=",
-          "-c",
-          "log.showSignature=false",
-          "push",
-          "--porcelain",
-          "--force",
-          "origin",
-          &format!("refs/heads/{final_branch_name}:{final_branch_name}"),
-        ],
-        repository_path,
-      )
-      .map_err(|e| e.to_string())?;
+    let force = check_push_allowed(&git, repository_path, branch_prefix, branch_name, &params.baseline_branch).map_err(CommandError::from)?;
+    let full_branch_name = to_final_branch_name(branch_prefix, branch_name).map_err(CommandError::from)?;
+    let pre_push_check = run_pre_push_check(&git, repository_path, &full_branch_name, &|event| {
+      let _ = pre_push_output.send(event);
+    })
+    .map_err(CommandError::from)?;
+    // The hook already ran against the virtual branch, git must not run it again against the working copy
+    let no_verify = matches!(pre_push_check, Some(PrePushCheck::Hook(_)));
+    // Open only while pushing
+    let askpass_session = AskpassSession::start(askpass_prompts, credential_prompts).map_err(CommandError::from)?;
+    let askpass = askpass_session.askpass_env().map_err(CommandError::from)?;
+    let final_branch_name = push_virtual_branch(&git, repository_path, branch_prefix, branch_name, force, no_verify, &askpass).map_err(CommandError::from)?;
+    drop(askpass_session);
 
     // Compute and return updated remote status
     let remote_status = compute_remote_status_for_branch(
@@ -64,3 +75,10 @@ pub async fn push_branch(git_executor: State<'_, GitCommandExecutor>, params: Pu
   .await
   .map_err(|e| format!("Task failed: {e}"))?
 }
+
+/// Answers a credential prompt of `push_branch`, `null` cancels it. Returns false if the prompt is no longer pending.
+#[tauri::command]
+#[specta::specta]
+pub async fn answer_credential_prompt(askpass_prompts: State<'_, AskpassPrompts>, id: u32, answer: Option<String>) -> Result<bool, CommandError> {
+  Ok(askpass_prompts.answer(id, answer))
+}
@@ -0,0 +1,19 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::phase_budget::wait_for_background_phases;
+use sync_core::reassign_commit::{ReassignCommitParams, ReassignCommitResult, reassign_commit_core};
+use tauri::State;
+use tracing::instrument;
+
+/// Moves a commit to another virtual branch by changing its prefix.
+/// The commit is only reworded if both branches are predicted to sync without conflicts.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn reassign_commit(git_executor: State<'_, GitCommandExecutor>, params: ReassignCommitParams) -> Result<ReassignCommitResult, CommandError> {
+  wait_for_background_phases(&params.repository_path).await;
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || reassign_commit_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
@@ -0,0 +1,25 @@
+use crate::progress::{SyncEvent, TauriProgressReporter};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::phase_budget::wait_for_background_phases;
+use sync_core::rebase_branch_onto::{RebaseBranchOntoParams, RebaseBranchOntoResult, rebase_branch_onto_core};
+use tauri::State;
+use tauri::ipc::Channel;
+use tracing::instrument;
+
+/// Rebuilds a virtual branch on another ref (e.g., a release branch) as `<prefix>/<ref>/<name>`.
+/// Commit and conflict events are sent over the channel as during sync.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor, progress))]
+pub async fn rebase_branch_onto(
+  git_executor: State<'_, GitCommandExecutor>,
+  params: RebaseBranchOntoParams,
+  progress: Channel<SyncEvent>,
+) -> Result<RebaseBranchOntoResult, CommandError> {
+  wait_for_background_phases(&params.repository_path).await;
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || rebase_branch_onto_core(&git, params, TauriProgressReporter::new(progress)).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
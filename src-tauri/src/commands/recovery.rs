@@ -0,0 +1,29 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::recovery::{InterruptedOperation, RecoveryOption, apply_recovery_option as apply_recovery_option_core, detect_interrupted_operations};
+use tauri::State;
+use tokio::task;
+use tracing::instrument;
+
+/// Inspects the repository for operations interrupted by a crash (sync moving refs, rewrite of the checked out branch,
+/// leftover lock files) and returns them with the ways to recover, empty if the repository is in a consistent state
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn recover_repository_state(git_executor: State<'_, GitCommandExecutor>, repository_path: String) -> Result<Vec<InterruptedOperation>, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || detect_interrupted_operations(&git, &repository_path).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Recovers from an interrupted operation with one of the options returned by `recover_repository_state`
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn apply_recovery_option(git_executor: State<'_, GitCommandExecutor>, repository_path: String, option: RecoveryOption) -> Result<(), CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || apply_recovery_option_core(&git, &repository_path, &option).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
@@ -0,0 +1,68 @@
+use crate::progress::SyncEvent;
+use crate::remote_status_scheduler::{RefreshConfig, RemoteStatusScheduler, refresh_interval};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use serde::Deserialize;
+use sync_core::remote_status::{BranchRemoteDivergence, compute_branch_remote_divergence};
+use tauri::State;
+use tauri::ipc::Channel;
+
+#[derive(Debug, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct StartRemoteStatusRefreshParams {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  /// Seconds between refreshes (5 minutes by default, from 1 to 30 minutes)
+  pub interval_seconds: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct StopRemoteStatusRefreshParams {
+  pub repository_path: String,
+}
+
+/// Starts periodically fetching the remote and sending updated remote status of all virtual branches over the channel.
+/// Replaces a refresh already running for the repository. Fails if the interval is out of range.
+#[tauri::command]
+#[specta::specta]
+pub async fn start_remote_status_refresh(
+  git_executor: State<'_, GitCommandExecutor>,
+  scheduler: State<'_, RemoteStatusScheduler>,
+  params: StartRemoteStatusRefreshParams,
+  progress: Channel<SyncEvent>,
+) -> Result<(), CommandError> {
+  let config = RefreshConfig {
+    repository_path: params.repository_path,
+    branch_prefix: params.branch_prefix,
+    interval: refresh_interval(params.interval_seconds)?,
+  };
+  scheduler.start((*git_executor).clone(), config, progress);
+  Ok(())
+}
+
+/// Stops the periodic remote status refresh of a repository, returns false if none was running
+#[tauri::command]
+#[specta::specta]
+pub async fn stop_remote_status_refresh(scheduler: State<'_, RemoteStatusScheduler>, params: StopRemoteStatusRefreshParams) -> Result<bool, CommandError> {
+  Ok(scheduler.stop(&params.repository_path))
+}
+
+#[derive(Debug, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBranchRemoteDivergenceParams {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  pub branch_name: String,
+}
+
+/// Lists the commits that differ between a virtual branch and its remote branch, with patch IDs telling rewritten commits
+/// from new ones. Uses the remote-tracking ref, so it's as fresh as the last refresh.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_branch_remote_divergence(git_executor: State<'_, GitCommandExecutor>, params: GetBranchRemoteDivergenceParams) -> Result<BranchRemoteDivergence, CommandError> {
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || compute_branch_remote_divergence(&git, &params.repository_path, &params.branch_prefix, &params.branch_name).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
@@ -0,0 +1,31 @@
+use crate::askpass::{AskpassPrompts, AskpassSession, CredentialPrompt};
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::phase_budget::wait_for_background_phases;
+use sync_core::rename_virtual_branch::{RenameVirtualBranchParams, RenameVirtualBranchResult, rename_virtual_branch_core};
+use tauri::State;
+use tauri::ipc::Channel;
+use tracing::instrument;
+
+/// Renames a virtual branch: rewrites the commit prefixes and migrates the virtual, archived and remote branches.
+/// Credentials for renaming the remote branch are asked for over `credential_prompts`, like for `push_branch`.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor, askpass_prompts, credential_prompts))]
+pub async fn rename_virtual_branch(
+  git_executor: State<'_, GitCommandExecutor>,
+  askpass_prompts: State<'_, AskpassPrompts>,
+  params: RenameVirtualBranchParams,
+  credential_prompts: Channel<CredentialPrompt>,
+) -> Result<RenameVirtualBranchResult, CommandError> {
+  wait_for_background_phases(&params.repository_path).await;
+  let git = (*git_executor).clone();
+  let askpass_prompts = (*askpass_prompts).clone();
+  tokio::task::spawn_blocking(move || {
+    let askpass_session = AskpassSession::start(askpass_prompts, credential_prompts).map_err(CommandError::from)?;
+    let askpass = askpass_session.askpass_env().map_err(CommandError::from)?;
+    rename_virtual_branch_core(&git, params, &askpass).map_err(CommandError::from)
+  })
+  .await
+  .map_err(|e| format!("Task failed: {e}"))?
+}
@@ -0,0 +1,21 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use hosting_api::review_status::{BranchReviewStatus, GetBranchReviewStatusParams, fetch_branch_review_status, load_review_client};
+use sync_core::remote_status::PUSH_REMOTE;
+use tauri::State;
+use tokio::task;
+use tracing::instrument;
+
+/// Returns the pull request review status (comments, unresolved threads, approvals) of virtual branches pushed to origin.
+/// Branches have no review unless `branchdeck.reviewStatus` is enabled, like for the periodic remote status refresh.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor, params), fields(repository_path = %params.repository_path))]
+pub async fn get_branch_review_status(git_executor: State<'_, GitCommandExecutor>, params: GetBranchReviewStatusParams) -> Result<Vec<BranchReviewStatus>, CommandError> {
+  let git = (*git_executor).clone();
+  let repository_path = params.repository_path.clone();
+  let client = task::spawn_blocking(move || load_review_client(&git, &repository_path, PUSH_REMOTE))
+    .await
+    .map_err(|e| format!("Task error: {}", e))??;
+  Ok(fetch_branch_review_status(client.as_ref(), &params.branch_prefix, params.branch_names).await?)
+}
@@ -0,0 +1,19 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::phase_budget::wait_for_background_phases;
+use sync_core::reword_commits::{RewordCommitsParams, RewordCommitsResult, reword_commits_core};
+use tauri::State;
+use tracing::instrument;
+
+/// Changes the messages of one or more commits without touching the working tree.
+/// Returns the old to new commit ID mapping so displayed hashes can be updated.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor, params))]
+pub async fn reword_commits(git_executor: State<'_, GitCommandExecutor>, params: RewordCommitsParams) -> Result<RewordCommitsResult, CommandError> {
+  wait_for_background_phases(&params.repository_path).await;
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || reword_commits_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
@@ -0,0 +1,71 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::send_email::{
+  GetCoverLetterTemplateParams, GetSmtpConfigParams, GitSendEmailTransport, SendBranchPatchesParams, SendBranchPatchesResult, SendEmailProgress, SetCoverLetterTemplateParams,
+  SetSmtpConfigParams, SmtpConfig, get_cover_letter_template_core, get_smtp_config_core, send_branch_patches_core, set_cover_letter_template_core, set_smtp_config_core,
+};
+use tauri::State;
+use tauri::ipc::Channel;
+use tokio::task;
+use tracing::instrument;
+
+/// Returns the SMTP settings used to send patches (`sendemail.*` git config)
+#[tauri::command]
+#[specta::specta]
+pub async fn get_smtp_config(git_executor: State<'_, GitCommandExecutor>, params: GetSmtpConfigParams) -> Result<SmtpConfig, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || get_smtp_config_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Stores the SMTP settings in the repository config
+#[tauri::command]
+#[specta::specta]
+pub async fn set_smtp_config(git_executor: State<'_, GitCommandExecutor>, params: SetSmtpConfigParams) -> Result<SmtpConfig, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || set_smtp_config_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Returns the cover letter template of a branch
+#[tauri::command]
+#[specta::specta]
+pub async fn get_cover_letter_template(git_executor: State<'_, GitCommandExecutor>, params: GetCoverLetterTemplateParams) -> Result<String, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || get_cover_letter_template_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Sets or resets the cover letter template of a branch
+#[tauri::command]
+#[specta::specta]
+pub async fn set_cover_letter_template(git_executor: State<'_, GitCommandExecutor>, params: SetCoverLetterTemplateParams) -> Result<String, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || set_cover_letter_template_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Sends the patches of a virtual branch with `git send-email`, reporting each sent mail
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor, progress), fields(repository_path = %params.repository_path, branch_name = %params.branch_name))]
+pub async fn send_branch_patches(
+  git_executor: State<'_, GitCommandExecutor>,
+  params: SendBranchPatchesParams,
+  progress: Channel<SendEmailProgress>,
+) -> Result<SendBranchPatchesResult, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || {
+    let transport = GitSendEmailTransport { git_executor: &git };
+    send_branch_patches_core(&git, params, &transport, |event| {
+      let _ = progress.send(event);
+    })
+    .map_err(CommandError::from)
+  })
+  .await
+  .map_err(|e| format!("Task error: {}", e))?
+}
@@ -0,0 +1,80 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use serde::Deserialize;
+use sync_core::settings_transfer::{
+  ImportSettingsResult, export_settings as export_settings_core, import_settings as import_settings_core, parse_settings_bundle, settings_bundle_to_json,
+};
+use tauri::State;
+use tauri_plugin_dialog::DialogExt;
+use tokio::task;
+use tracing::instrument;
+
+#[derive(Debug, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSettingsParams {
+  /// Content of the app store as a JSON object
+  pub app_settings: String,
+  /// Repositories whose `branchdeck.*` config is exported
+  pub repository_paths: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSettingsParams {
+  /// Local repositories the exported repository config is matched to
+  pub repository_paths: Vec<String>,
+}
+
+/// Asks where to save and writes the app settings and the `branchdeck.*` git config to a JSON file.
+/// Returns the path of the file, `None` if the user cancelled.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(app_handle, git_executor, params))]
+pub async fn export_settings(app_handle: tauri::AppHandle, git_executor: State<'_, GitCommandExecutor>, params: ExportSettingsParams) -> Result<Option<String>, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || {
+    let Some(path) = app_handle
+      .dialog()
+      .file()
+      .set_title("Export Settings")
+      .set_file_name("branch-deck-settings.json")
+      .add_filter("JSON", &["json"])
+      .blocking_save_file()
+    else {
+      return Ok(None);
+    };
+    let path = path.to_string();
+    let bundle = export_settings_core(&git, &params.app_settings, &params.repository_paths).map_err(CommandError::from)?;
+    let content = settings_bundle_to_json(&bundle).map_err(CommandError::from)?;
+    std::fs::write(&path, content).map_err(|e| CommandError::from(anyhow::anyhow!("Failed to write {path}: {e}")))?;
+    Ok(Some(path))
+  })
+  .await
+  .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Asks for a settings file, migrates it if it was written by an older version and applies its git config.
+/// The app settings are returned for the frontend to put into the app store, `None` if the user cancelled.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(app_handle, git_executor, params))]
+pub async fn import_settings(
+  app_handle: tauri::AppHandle,
+  git_executor: State<'_, GitCommandExecutor>,
+  params: ImportSettingsParams,
+) -> Result<Option<ImportSettingsResult>, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || {
+    let Some(path) = app_handle.dialog().file().set_title("Import Settings").add_filter("JSON", &["json"]).blocking_pick_file() else {
+      return Ok(None);
+    };
+    let path = path.to_string();
+    let content = std::fs::read_to_string(&path).map_err(|e| CommandError::from(anyhow::anyhow!("Failed to read {path}: {e}")))?;
+    let (bundle, migrated_from_version) = parse_settings_bundle(&content).map_err(CommandError::from)?;
+    import_settings_core(&git, &bundle, migrated_from_version, &params.repository_paths)
+      .map(Some)
+      .map_err(CommandError::from)
+  })
+  .await
+  .map_err(|e| format!("Task error: {}", e))?
+}
@@ -0,0 +1,18 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::phase_budget::wait_for_background_phases;
+use sync_core::squash_branch::{SquashBranchCommitsParams, SquashBranchCommitsResult, squash_branch_commits_core};
+use tauri::State;
+use tracing::instrument;
+
+/// Squashes the prefixed commits of a branch into a single commit on the current branch
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn squash_branch_commits(git_executor: State<'_, GitCommandExecutor>, params: SquashBranchCommitsParams) -> Result<SquashBranchCommitsResult, CommandError> {
+  wait_for_background_phases(&params.repository_path).await;
+  let git = (*git_executor).clone();
+  tokio::task::spawn_blocking(move || squash_branch_commits_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task failed: {e}"))?
+}
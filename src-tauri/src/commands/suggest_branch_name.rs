@@ -1,4 +1,6 @@
 use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::{CommandError, ErrorCode};
+use model_ai::remote::{RemoteGenerator, get_ai_api_key, load_ai_provider_settings};
 use model_ai::types::{SuggestBranchNameParams, SuggestionProgress};
 use model_tauri::{generator::ModelGeneratorState, path_provider::TauriModelPathProvider};
 use tauri::{AppHandle, State};
@@ -13,7 +15,7 @@ pub async fn suggest_branch_name_stream(
   app: AppHandle,
   params: SuggestBranchNameParams,
   progress: tauri::ipc::Channel<SuggestionProgress>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
   // Get the generation ID counter and increment it
   let generation_id_counter = {
     let guard = model_state.generator.lock().await;
@@ -36,11 +38,31 @@ pub async fn suggest_branch_name_stream(
     return Ok(());
   }
 
+  // A remote provider selected for the repository replaces the local model
+  let settings = load_ai_provider_settings(&git_executor, &params.repository_path);
+  let remote = if settings.provider.is_remote() {
+    let remote = get_ai_api_key(&git_executor, &params.repository_path, &settings).and_then(|api_key| RemoteGenerator::new(&settings, api_key));
+    match remote {
+      Ok(remote) => Some(remote),
+      Err(e) => {
+        let error_message = format!("Failed to set up AI provider {}: {e}", settings.provider.config_value());
+        progress.send(SuggestionProgress::Error { message: error_message.clone() }).ok();
+        return Err(CommandError::new(ErrorCode::Model, error_message));
+      }
+    }
+  } else {
+    None
+  };
+
   // Create provider for model path
   let provider = TauriModelPathProvider::new(app);
 
   // Ensure model is loaded - send download progress instead of error when files are missing
-  if let Err(e) = model_gen.ensure_model_loaded(&provider).await {
+  let load_result = match remote {
+    Some(_) => Ok(()),
+    None => model_gen.ensure_model_loaded(&provider).await,
+  };
+  if let Err(e) = load_result {
     let error_str = e.to_string();
 
     if error_str.contains("Model not downloaded") {
@@ -56,17 +78,17 @@ pub async fn suggest_branch_name_stream(
     } else if error_str.contains("Model loading previously failed") {
       let error_message = format!("Model loading failed. Please check your internet connection and try again. ({e})");
       progress.send(SuggestionProgress::Error { message: error_message.clone() }).ok();
-      return Err(error_message);
+      return Err(CommandError::new(ErrorCode::Model, error_message));
     } else {
       let error_message = format!("Failed to load model: {e}");
       progress.send(SuggestionProgress::Error { message: error_message.clone() }).ok();
-      return Err(error_message);
+      return Err(CommandError::new(ErrorCode::Model, error_message));
     }
   }
 
   // Generate branch names with streaming - fail if generation fails
   match model_gen
-    .generate_branch_names_stream(&git_executor, &params.commits, &params.repository_path, &progress, my_generation_id)
+    .generate_branch_names_stream(&git_executor, &params.commits, &params.repository_path, remote.as_ref(), &progress, my_generation_id)
     .await
   {
     Ok(_) => {
@@ -76,7 +98,7 @@ pub async fn suggest_branch_name_stream(
     Err(e) => {
       let error_msg = format!("Failed to generate branch names: {e}");
       progress.send(SuggestionProgress::Error { message: error_msg.clone() }).ok();
-      Err(error_msg)
+      Err(CommandError::new(ErrorCode::Model, error_msg))
     }
   }
 }
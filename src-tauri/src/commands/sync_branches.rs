@@ -1,10 +1,23 @@
-use crate::progress::{SyncEvent, TauriProgressReporter};
+use crate::progress::{ProgressReporter, SyncEvent, TauriProgressReporter, TauriWorkspaceProgressReporter};
 use crate::repository_state::RepositoryStateCache;
+use crate::sync_event_subscriptions::SyncEventSubscriptions;
 use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::commit_list::CommitFilter;
+use git_ops::error::CommandError;
+use git_ops::ref_transaction::{RefJournal, load_ref_journal, roll_back_ref_journal};
+use git_ops::repository_lock::lock_repository;
+use hosting_api::webhook::WebhookSummaryNotifier;
 use serde::Deserialize;
-use sync_core::sync::sync_branches_core_with_cache;
+use std::sync::Arc;
+use sync_core::sync::SyncOptions;
+use sync_core::sync_dry_run::{SyncDryRunParams, SyncDryRunResult, sync_branches_dry_run_core};
+use sync_core::sync_performance::{SyncHistoryEntry, load_sync_history};
+use sync_core::webhook::SyncSummary;
+use sync_core::workspace_sync::{self, WorkspaceRepository, WorkspaceSyncEvent, sync_with_summary};
+use sync_types::event_filter::{FilteredProgressReporter, SyncEventFilter};
 use tauri::State;
 use tauri::ipc::Channel;
+use tokio::task;
 use tracing::{error, instrument};
 
 #[derive(Debug, Deserialize, specta::Type)]
@@ -12,38 +25,179 @@ use tracing::{error, instrument};
 pub struct SyncBranchesParams {
   pub repository_path: String,
   pub branch_prefix: String,
+  /// Only rebuild the virtual branches with a commit matching the filter, their other commits are kept
+  pub commit_filter: Option<CommitFilter>,
+  /// Only send matching events over the channel, all events when not set
+  pub event_filter: Option<SyncEventFilter>,
+}
+
+#[derive(Debug, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncWorkspaceParams {
+  /// Repositories of the workspace, synced in this order
+  pub repositories: Vec<WorkspaceRepository>,
+}
+
+#[derive(Debug, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscribeSyncEventsParams {
+  pub repository_path: String,
+  pub filter: SyncEventFilter,
 }
 
 /// Synchronizes branches by grouping commits by prefix and creating/updating branches
 #[tauri::command]
 #[specta::specta]
-#[instrument(skip(git_executor, cache, progress), fields(repository_path = %params.repository_path, branch_prefix = %params.branch_prefix))]
+#[instrument(skip(git_executor, cache, subscriptions, progress), fields(repository_path = %params.repository_path, branch_prefix = %params.branch_prefix))]
 pub async fn sync_branches(
   git_executor: State<'_, GitCommandExecutor>,
   cache: State<'_, RepositoryStateCache>,
+  subscriptions: State<'_, SyncEventSubscriptions>,
   params: SyncBranchesParams,
   progress: Channel<SyncEvent>,
-) -> Result<(), String> {
-  let repository_path = &params.repository_path;
-  let branch_prefix = &params.branch_prefix;
+) -> Result<(), CommandError> {
+  let primary = FilteredProgressReporter::new(TauriProgressReporter::new(progress), params.event_filter.unwrap_or_default());
+  let (_, result) = run_sync(
+    &git_executor,
+    &cache,
+    &subscriptions,
+    &params.repository_path,
+    &params.branch_prefix,
+    params.commit_filter.unwrap_or_default(),
+    primary,
+  )
+  .await;
+  result
+}
 
+/// Synchronizes every repository of a workspace one after another, events are tagged by repository.
+/// A failed repository doesn't stop the others, returns the summary of each repository in the order of `repositories`.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor, cache, subscriptions, params, progress), fields(repository_count = params.repositories.len()))]
+pub async fn sync_workspace(
+  git_executor: State<'_, GitCommandExecutor>,
+  cache: State<'_, RepositoryStateCache>,
+  subscriptions: State<'_, SyncEventSubscriptions>,
+  params: SyncWorkspaceParams,
+  progress: Channel<WorkspaceSyncEvent>,
+) -> Result<Vec<SyncSummary>, CommandError> {
+  let (git_executor, cache, subscriptions) = (&*git_executor, &*cache, &*subscriptions);
+  let summaries = workspace_sync::sync_workspace(
+    git_executor,
+    &params.repositories,
+    TauriWorkspaceProgressReporter::new(progress),
+    |repository_path, branch_prefix, reporter| async move {
+      let (summary, _) = run_sync(git_executor, cache, subscriptions, &repository_path, &branch_prefix, CommitFilter::default(), reporter).await;
+      summary
+    },
+  )
+  .await;
+  Ok(summaries)
+}
+
+/// Sync of one repository, shared by `sync_branches` and `sync_workspace`.
+/// Events also go to the subscribed sub-windows, and the summary to the webhook if one is configured.
+async fn run_sync<P: ProgressReporter + Clone + 'static>(
+  git_executor: &GitCommandExecutor,
+  cache: &RepositoryStateCache,
+  subscriptions: &SyncEventSubscriptions,
+  repository_path: &str,
+  branch_prefix: &str,
+  commit_filter: CommitFilter,
+  primary: P,
+) -> (SyncSummary, Result<(), CommandError>) {
   // Get or create cached repository state (includes Git version validation)
-  let cached_issue_config = match cache.get_or_create(repository_path, &git_executor).await {
+  let cached_issue_config = match cache.get_or_create(repository_path, git_executor).await {
     Ok(state) => state.issue_config.clone(),
     Err(e) => {
       error!("Failed to initialize repository cache: {}.", e);
-      return Err(format!("{}", e));
+      let summary = SyncSummary {
+        error: Some(format!("{e:#}")),
+        ..SyncSummary::new(repository_path, branch_prefix)
+      };
+      return (summary, Err(CommandError::from(e)));
     }
   };
 
-  // Use the branch-sync implementation with TauriProgressReporter adapter
-  let progress_adapter = TauriProgressReporter::new(progress);
+  let options = SyncOptions {
+    cached_issue_config,
+    commit_filter,
+    summary_notifier: Some(Arc::new(WebhookSummaryNotifier::new(git_executor.clone()))),
+    ..Default::default()
+  };
+  let (summary, result) = sync_with_summary(git_executor, repository_path, branch_prefix, subscriptions.broadcast(repository_path, primary), options).await;
+
+  let result = result.map_err(|e| {
+    error!(error = ?e, "Branch synchronization failed");
+    CommandError::from(e)
+  });
+  (summary, result)
+}
+
+/// Predicts per-branch merge conflicts of a sync without creating or moving any refs
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor), fields(repository_path = %params.repository_path, branch_prefix = %params.branch_prefix))]
+pub async fn sync_branches_dry_run(git_executor: State<'_, GitCommandExecutor>, params: SyncDryRunParams) -> Result<SyncDryRunResult, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || sync_branches_dry_run_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
 
-  // Use the version with cache support
-  sync_branches_core_with_cache(&git_executor, repository_path, branch_prefix, progress_adapter, cached_issue_config)
+/// Returns the recorded sync runs of a repository with their phase timings, oldest first
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn get_sync_history(git_executor: State<'_, GitCommandExecutor>, repository_path: String) -> Result<Vec<SyncHistoryEntry>, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || load_sync_history(&git, &repository_path).map_err(CommandError::from))
     .await
-    .map_err(|e| {
-      error!(error = ?e, "Branch synchronization failed");
-      format!("{e:?}")
-    })
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Returns the ref journal left behind by a sync interrupted by a crash, `None` if there is none or the sync is still running
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn get_interrupted_sync(git_executor: State<'_, GitCommandExecutor>, repository_path: String) -> Result<Option<RefJournal>, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || match lock_repository(&git, &repository_path) {
+    Ok(_lock) => load_ref_journal(&git, &repository_path).map_err(CommandError::from),
+    Err(_) => Ok(None),
+  })
+  .await
+  .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Moves the refs of a sync interrupted by a crash back to where they were before it started, the checked out branch too.
+/// The next sync does this on its own except for the checked out branch, returns the rolled back journal or `None` if
+/// there was nothing to recover.
+#[tauri::command]
+#[specta::specta]
+#[instrument(skip(git_executor))]
+pub async fn recover_interrupted_sync(git_executor: State<'_, GitCommandExecutor>, repository_path: String) -> Result<Option<RefJournal>, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || {
+    let lock = lock_repository(&git, &repository_path)?;
+    roll_back_ref_journal(&git, &repository_path, &lock, true).map_err(CommandError::from)
+  })
+  .await
+  .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Subscribes to the events of syncs of a repository, e.g. for a sub-window that doesn't start syncs itself.
+/// Events are filtered before they are sent. Returns the subscription ID for `unsubscribe_sync_events`.
+#[tauri::command]
+#[specta::specta]
+pub async fn subscribe_sync_events(subscriptions: State<'_, SyncEventSubscriptions>, params: SubscribeSyncEventsParams, events: Channel<SyncEvent>) -> Result<u32, CommandError> {
+  Ok(subscriptions.subscribe(params.repository_path, params.filter, events))
+}
+
+/// Stops sending sync events to a subscriber, returns false if there was no such subscription
+#[tauri::command]
+#[specta::specta]
+pub async fn unsubscribe_sync_events(subscriptions: State<'_, SyncEventSubscriptions>, subscription_id: u32) -> Result<bool, CommandError> {
+  Ok(subscriptions.unsubscribe(subscription_id))
 }
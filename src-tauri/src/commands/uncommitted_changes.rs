@@ -1,5 +1,6 @@
 use git_executor::git_command_executor::GitCommandExecutor;
 use git_ops::conflict_analysis::FileDiff;
+use git_ops::error::CommandError;
 use sync_core::uncommitted_changes::{
   GetFileContentForDiffParams, GetUncommittedChangesParams, UncommittedChangesResult, get_file_content_for_diff as core_get_file_content_for_diff,
   get_uncommitted_changes as core_get_uncommitted_changes,
@@ -11,10 +12,10 @@ use tracing::instrument;
 #[tauri::command]
 #[specta::specta]
 #[instrument(skip(git_executor))]
-pub async fn get_uncommitted_changes(git_executor: State<'_, GitCommandExecutor>, params: GetUncommittedChangesParams) -> Result<UncommittedChangesResult, String> {
+pub async fn get_uncommitted_changes(git_executor: State<'_, GitCommandExecutor>, params: GetUncommittedChangesParams) -> Result<UncommittedChangesResult, CommandError> {
   let git = (*git_executor).clone();
 
-  tokio::task::spawn_blocking(move || core_get_uncommitted_changes(&git, params))
+  tokio::task::spawn_blocking(move || core_get_uncommitted_changes(&git, params).map_err(CommandError::from))
     .await
     .map_err(|e| format!("Task failed: {}", e))?
 }
@@ -23,10 +24,10 @@ pub async fn get_uncommitted_changes(git_executor: State<'_, GitCommandExecutor>
 #[tauri::command]
 #[specta::specta]
 #[instrument(skip(git_executor))]
-pub async fn get_file_content_for_diff(git_executor: State<'_, GitCommandExecutor>, params: GetFileContentForDiffParams) -> Result<FileDiff, String> {
+pub async fn get_file_content_for_diff(git_executor: State<'_, GitCommandExecutor>, params: GetFileContentForDiffParams) -> Result<FileDiff, CommandError> {
   let git = (*git_executor).clone();
 
-  tokio::task::spawn_blocking(move || core_get_file_content_for_diff(&git, params))
+  tokio::task::spawn_blocking(move || core_get_file_content_for_diff(&git, params).map_err(CommandError::from))
     .await
     .map_err(|e| format!("Task failed: {}", e))?
 }
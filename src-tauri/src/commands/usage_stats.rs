@@ -0,0 +1,27 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::usage_stats::{RecordUsageParams, UsageStats, load_usage_stats, record_usage as record_usage_core};
+use tauri::State;
+use tokio::task;
+
+/// Returns the local usage statistics of the repository. They are never sent anywhere.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_usage_stats(git_executor: State<'_, GitCommandExecutor>, repository_path: String) -> Result<UsageStats, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || load_usage_stats(&git, &repository_path).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
+
+/// Counts an operation only the frontend knows about, e.g. a branch created with a suggested name.
+/// Counters of operations the backend counts itself can't be recorded.
+#[tauri::command]
+#[specta::specta]
+pub async fn record_usage(git_executor: State<'_, GitCommandExecutor>, params: RecordUsageParams) -> Result<(), CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || record_usage_core(&git, &params.repository_path, params.counter.into()))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?;
+  Ok(())
+}
@@ -0,0 +1,15 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use sync_core::verify_metadata::{VerifyMetadataParams, VerifyMetadataResult, verify_metadata_core};
+use tauri::State;
+use tokio::task;
+
+/// Checks notes mappings, detection cache and commit dependencies for entries referencing missing objects and repairs them on request
+#[tauri::command]
+#[specta::specta]
+pub async fn verify_metadata(git_executor: State<'_, GitCommandExecutor>, params: VerifyMetadataParams) -> Result<VerifyMetadataResult, CommandError> {
+  let git = (*git_executor).clone();
+  task::spawn_blocking(move || verify_metadata_core(&git, params).map_err(CommandError::from))
+    .await
+    .map_err(|e| format!("Task error: {}", e))?
+}
@@ -0,0 +1,12 @@
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::error::CommandError;
+use hosting_api::webhook::{TestWebhookConnectionParams, test_webhook_connection_core};
+use sync_core::webhook::WebhookFormat;
+use tauri::State;
+
+/// Sends a test message to the configured webhook (or the given URL), returns the payload format that was used
+#[tauri::command]
+#[specta::specta]
+pub async fn test_webhook_connection(git_executor: State<'_, GitCommandExecutor>, params: TestWebhookConnectionParams) -> Result<WebhookFormat, CommandError> {
+  test_webhook_connection_core(&git_executor, params).await.map_err(CommandError::from)
+}
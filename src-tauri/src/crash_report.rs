@@ -0,0 +1,226 @@
+//! Local crash reports. A panic hook writes the panic message, backtrace, latest log events and the app, OS and git
+//! versions to a JSON file in the `crashes` subdirectory of the app log directory.
+//!
+//! Nothing is uploaded: reports are listed by `get_crash_reports` and the user exports one to attach it to an issue.
+
+use serde::{Deserialize, Serialize};
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, TryLockError};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, io};
+
+/// Log events kept for the next crash report
+const MAX_RECENT_EVENTS: usize = 200;
+/// Crash reports kept on disk, the oldest ones are deleted
+const MAX_CRASH_REPORTS: usize = 20;
+
+static RECENT_EVENTS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+/// Discovered after the hook is installed, as finding git may take a while
+static GIT_VERSION: OnceLock<String> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+  /// Name of the report file without extension
+  pub id: String,
+  /// Unix timestamp in milliseconds
+  pub crashed_at: u64,
+  pub message: String,
+  /// `file:line:column` of the panic
+  pub location: Option<String>,
+  pub thread: Option<String>,
+  pub backtrace: String,
+  /// Log events before the panic, oldest first
+  pub recent_events: Vec<String>,
+  pub app_version: String,
+  /// Operating system name, version and architecture
+  pub os: String,
+  /// `None` if git was not discovered yet
+  pub git_version: Option<String>,
+}
+
+/// Keeps the latest log events in memory, added as a target of the log plugin
+struct RecentEventsLog;
+
+impl log::Log for RecentEventsLog {
+  fn enabled(&self, _metadata: &log::Metadata) -> bool {
+    true
+  }
+
+  fn log(&self, record: &log::Record) {
+    // The log plugin formats the message with the time, level and target before passing it to its targets
+    let event = record.args().to_string();
+    let mut events = RECENT_EVENTS.lock().unwrap_or_else(|e| e.into_inner());
+    if events.len() == MAX_RECENT_EVENTS {
+      events.pop_front();
+    }
+    events.push_back(event);
+  }
+
+  fn flush(&self) {}
+}
+
+/// Log plugin target recording the events for crash reports
+pub fn recent_events_target() -> tauri_plugin_log::Target {
+  let dispatch = fern::Dispatch::new().chain(Box::new(RecentEventsLog) as Box<dyn log::Log>);
+  tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Dispatch(dispatch))
+}
+
+pub fn set_git_version(version: String) {
+  let _ = GIT_VERSION.set(version);
+}
+
+/// Directory the crash reports are written to
+pub fn crash_reports_dir(app_log_dir: &Path) -> PathBuf {
+  app_log_dir.join("crashes")
+}
+
+/// Write a crash report on panic, then run the previous hook (which prints the panic to stderr)
+pub fn install_panic_hook(crash_dir: PathBuf, app_version: String) {
+  let os = os_description();
+  let previous_hook = panic::take_hook();
+  panic::set_hook(Box::new(move |info| {
+    let report = capture_crash_report(info, &app_version, &os);
+    match write_crash_report(&crash_dir, &report) {
+      Ok(path) => eprintln!("Crash report written to {}", path.display()),
+      Err(e) => eprintln!("Failed to write crash report: {e}"),
+    }
+    previous_hook(info);
+  }));
+}
+
+fn capture_crash_report(info: &PanicHookInfo, app_version: &str, os: &str) -> CrashReport {
+  let crashed_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_millis() as u64);
+  let payload = info.payload();
+  let message = payload
+    .downcast_ref::<&str>()
+    .map(|message| message.to_string())
+    .or_else(|| payload.downcast_ref::<String>().cloned())
+    .unwrap_or_else(|| "Box<dyn Any>".to_string());
+  // The panic may have happened while an event was recorded, the lock must not be waited for then
+  let recent_events = match RECENT_EVENTS.try_lock() {
+    Ok(events) => events.iter().cloned().collect(),
+    Err(TryLockError::Poisoned(e)) => e.into_inner().iter().cloned().collect(),
+    Err(TryLockError::WouldBlock) => Vec::new(),
+  };
+  CrashReport {
+    id: format!("crash-{crashed_at}-{}", std::process::id()),
+    crashed_at,
+    message,
+    location: info.location().map(|location| location.to_string()),
+    thread: std::thread::current().name().map(str::to_string),
+    backtrace: Backtrace::force_capture().to_string(),
+    recent_events,
+    app_version: app_version.to_string(),
+    os: os.to_string(),
+    git_version: GIT_VERSION.get().cloned(),
+  }
+}
+
+/// Write the report as `<id>.json` and delete the oldest reports over [`MAX_CRASH_REPORTS`]
+pub fn write_crash_report(crash_dir: &Path, report: &CrashReport) -> io::Result<PathBuf> {
+  fs::create_dir_all(crash_dir)?;
+  let path = crash_dir.join(format!("{}.json", report.id));
+  fs::write(&path, serde_json::to_vec_pretty(report)?)?;
+
+  let mut report_files = crash_report_files(crash_dir)?;
+  if report_files.len() > MAX_CRASH_REPORTS {
+    report_files.sort();
+    for old_file in &report_files[..report_files.len() - MAX_CRASH_REPORTS] {
+      let _ = fs::remove_file(old_file);
+    }
+  }
+  Ok(path)
+}
+
+/// Crash reports in the directory, newest first. Unreadable files are skipped.
+pub fn read_crash_reports(crash_dir: &Path) -> io::Result<Vec<CrashReport>> {
+  if !crash_dir.exists() {
+    return Ok(Vec::new());
+  }
+  let mut reports: Vec<CrashReport> = crash_report_files(crash_dir)?
+    .iter()
+    .filter_map(|path| {
+      let content = fs::read(path).ok()?;
+      serde_json::from_slice(&content)
+        .inspect_err(|e| tracing::warn!(path = %path.display(), error = %e, "Skipping invalid crash report"))
+        .ok()
+    })
+    .collect();
+  reports.sort_by(|a, b| b.crashed_at.cmp(&a.crashed_at));
+  Ok(reports)
+}
+
+fn crash_report_files(crash_dir: &Path) -> io::Result<Vec<PathBuf>> {
+  let mut files = Vec::new();
+  for entry in fs::read_dir(crash_dir)? {
+    let path = entry?.path();
+    let is_report = path.extension().is_some_and(|extension| extension == "json") && path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with("crash-"));
+    if is_report {
+      files.push(path);
+    }
+  }
+  Ok(files)
+}
+
+/// Plain text version of a report for attaching to an issue
+pub fn format_crash_report(report: &CrashReport) -> String {
+  let mut text = String::new();
+  let _ = writeln!(text, "BranchDeck crash report {}", report.id);
+  let _ = writeln!(text);
+  let _ = writeln!(text, "App version: {}", report.app_version);
+  let _ = writeln!(text, "OS: {}", report.os);
+  let _ = writeln!(text, "Git version: {}", report.git_version.as_deref().unwrap_or("unknown"));
+  let _ = writeln!(text, "Crashed at: {} (Unix time in ms)", report.crashed_at);
+  let _ = writeln!(text, "Thread: {}", report.thread.as_deref().unwrap_or("<unnamed>"));
+  let _ = writeln!(text, "Location: {}", report.location.as_deref().unwrap_or("unknown"));
+  let _ = writeln!(text);
+  let _ = writeln!(text, "Message:\n{}", report.message);
+  let _ = writeln!(text);
+  let _ = writeln!(text, "Backtrace:\n{}", report.backtrace.trim_end());
+  let _ = writeln!(text);
+  let _ = writeln!(text, "Recent events:");
+  for event in &report.recent_events {
+    let _ = writeln!(text, "{event}");
+  }
+  text
+}
+
+/// Like `macos 15.1 (aarch64)`, the version is left out if it can't be determined
+fn os_description() -> String {
+  let arch = std::env::consts::ARCH;
+  match os_version() {
+    Some(version) => format!("{} {version} ({arch})", std::env::consts::OS),
+    None => format!("{} ({arch})", std::env::consts::OS),
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn os_version() -> Option<String> {
+  let output = std::process::Command::new("sw_vers").arg("-productVersion").output().ok()?;
+  Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|version| !version.is_empty())
+}
+
+#[cfg(target_os = "linux")]
+fn os_version() -> Option<String> {
+  let os_release = fs::read_to_string("/etc/os-release").ok()?;
+  os_release
+    .lines()
+    .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+    .map(|name| name.trim_matches('"').to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn os_version() -> Option<String> {
+  let output = std::process::Command::new("cmd").args(["/C", "ver"]).output().ok()?;
+  Some(String::from_utf8_lossy(&output.stdout).trim().to_string()).filter(|version| !version.is_empty())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn os_version() -> Option<String> {
+  None
+}
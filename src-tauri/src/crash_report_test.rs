@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+  use super::super::crash_report::{CrashReport, format_crash_report, read_crash_reports, write_crash_report};
+  use tempfile::TempDir;
+
+  fn crash_report(crashed_at: u64) -> CrashReport {
+    CrashReport {
+      id: format!("crash-{crashed_at}-1"),
+      crashed_at,
+      message: "index out of bounds".to_string(),
+      location: Some("src/lib.rs:10:5".to_string()),
+      thread: Some("main".to_string()),
+      backtrace: "0: branch_deck_lib::run\n".to_string(),
+      recent_events: vec!["[INFO] sync started".to_string()],
+      app_version: "1.2.3".to_string(),
+      os: "linux (x86_64)".to_string(),
+      git_version: Some("2.47.0".to_string()),
+    }
+  }
+
+  #[test]
+  fn test_read_crash_reports_newest_first() {
+    let temp_dir = TempDir::new().unwrap();
+    let crash_dir = temp_dir.path().join("crashes");
+    assert!(read_crash_reports(&crash_dir).unwrap().is_empty());
+
+    write_crash_report(&crash_dir, &crash_report(1000)).unwrap();
+    write_crash_report(&crash_dir, &crash_report(3000)).unwrap();
+    // Files that are not reports are ignored
+    std::fs::write(crash_dir.join("crash-2000-1.json"), "{").unwrap();
+    std::fs::write(crash_dir.join("notes.json"), "{}").unwrap();
+
+    let reports = read_crash_reports(&crash_dir).unwrap();
+    assert_eq!(reports, vec![crash_report(3000), crash_report(1000)]);
+  }
+
+  #[test]
+  fn test_write_crash_report_deletes_oldest_reports() {
+    let temp_dir = TempDir::new().unwrap();
+    for crashed_at in 1000..1025 {
+      write_crash_report(temp_dir.path(), &crash_report(crashed_at)).unwrap();
+    }
+
+    let reports = read_crash_reports(temp_dir.path()).unwrap();
+    assert_eq!(reports.len(), 20);
+    assert_eq!(reports.last().unwrap().crashed_at, 1005);
+  }
+
+  #[test]
+  fn test_format_crash_report() {
+    let text = format_crash_report(&crash_report(1000));
+    assert!(text.starts_with("BranchDeck crash report crash-1000-1\n"), "{text}");
+    assert!(text.contains("Git version: 2.47.0\n"), "{text}");
+    assert!(text.contains("Message:\nindex out of bounds\n"), "{text}");
+    assert!(text.ends_with("Recent events:\n[INFO] sync started\n"), "{text}");
+  }
+}
@@ -1,38 +1,91 @@
+pub mod askpass;
 pub mod auto_update;
 pub mod commands;
+pub mod crash_report;
 pub mod menu;
 pub mod menu_state;
 pub mod progress;
+pub mod remote_status_scheduler;
 pub mod repository_state;
+pub mod sync_event_subscriptions;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
 
 // ONNX tests disabled since ONNX is disabled
 // #[cfg(test)]
 // mod onnx_branch_name_generator_test;
 
+#[cfg(test)]
+mod crash_report_test;
+#[cfg(test)]
+mod remote_status_scheduler_test;
 #[cfg(test)]
 mod repository_state_test;
+#[cfg(all(test, feature = "telemetry"))]
+mod telemetry_test;
 
+use askpass::AskpassPrompts;
 use auto_update::{SharedUpdateState, UpdateState, check_for_updates, get_update_status, install_update};
 use commands::add_issue_reference::add_issue_reference_to_commits;
-use commands::amend_to_branch::amend_uncommitted_to_branch;
-use commands::archived_branches::{delete_archived_branch, get_archived_branch_commits};
+use commands::amend_to_branch::{amend_uncommitted_to_branch, preview_amend};
+use commands::archived_branches::{
+  apply_archive_retention, delete_archived_branch, delete_archived_branches, get_archived_branch_commits, get_archived_branch_commits_page, get_commit_diff,
+  resume_integration_detection,
+};
+use commands::baseline_update::check_baseline_update;
+use commands::binary_conflict::resolve_binary_conflict;
+use commands::branch_diff::get_branch_diff;
+use commands::branch_graph::get_branch_graph;
+use commands::branch_order::{get_branch_order, reorder_branches, set_branch_commit_order, set_branch_pinned};
 use commands::branch_prefix::get_branch_prefix_from_git_config;
+use commands::capabilities::get_capabilities;
 use commands::clear_model_cache::clear_model_cache;
+use commands::commit_dependencies::{get_commit_dependencies, set_commit_dependencies};
+use commands::commit_mapping::{get_commit_mapping, get_reverse_mapping};
+use commands::conflict_simulation::simulate_conflict;
+use commands::crash_reports::{export_crash_report, get_crash_reports};
 use commands::create_branch::create_branch_from_commits;
+use commands::drop_commits::drop_commits;
+use commands::export_patches::export_branch_patches;
+use commands::git_audit_log::{export_git_audit_log, get_git_audit_log};
+use commands::git_installation::{detect_git_installations, load_git_path_setting, set_git_path};
+use commands::hosting_provider::{get_remote_hosting_configs, set_remote_hosting_override};
+use commands::import_patches::apply_patch_series;
 use commands::menu_commands::update_menu_checkbox;
-use commands::push::push_branch;
+use commands::merge_tool::launch_merge_tool;
+use commands::network::{get_network_status, set_offline_mode};
+use commands::operation_plan::{execute_operation_plan, get_undo_snapshot, undo_operation_plan};
+use commands::prefix_typo::fix_prefix_typo;
+use commands::push::{answer_credential_prompt, push_branch};
+use commands::reassign_commit::reassign_commit;
+use commands::rebase_branch_onto::rebase_branch_onto;
+use commands::recovery::{apply_recovery_option, recover_repository_state};
+use commands::remote_status_refresh::{get_branch_remote_divergence, start_remote_status_refresh, stop_remote_status_refresh};
+use commands::rename_virtual_branch::rename_virtual_branch;
 use commands::repository_browser::{browse_repository, validate_repository_path};
+use commands::review_status::get_branch_review_status;
+use commands::reword_commits::reword_commits;
+use commands::send_email::{get_cover_letter_template, get_smtp_config, send_branch_patches, set_cover_letter_template, set_smtp_config};
+use commands::settings_transfer::{export_settings, import_settings};
+use commands::squash_branch::squash_branch_commits;
 use commands::suggest_branch_name::suggest_branch_name_stream;
-use commands::sync_branches::sync_branches;
+use commands::sync_branches::{
+  get_interrupted_sync, get_sync_history, recover_interrupted_sync, subscribe_sync_events, sync_branches, sync_branches_dry_run, sync_workspace, unsubscribe_sync_events,
+};
 use commands::unapply_branch::unapply_branch;
 use commands::uncommitted_changes::{get_file_content_for_diff, get_uncommitted_changes};
+use commands::usage_stats::{get_usage_stats, record_usage};
+use commands::verify_metadata::verify_metadata;
+use commands::webhook::test_webhook_connection;
 use commands::window_management::open_sub_window;
 use tauri_specta::{Builder, collect_commands};
 
 use git_executor::git_command_executor::GitCommandExecutor;
 use menu::{configure_app_menu, handle_menu_event};
 use menu_state::MenuState;
+use remote_status_scheduler::RemoteStatusScheduler;
 use repository_state::RepositoryStateCache;
+use sync_event_subscriptions::SyncEventSubscriptions;
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -52,7 +105,12 @@ pub fn run() {
     amend_uncommitted_to_branch,
     suggest_branch_name_stream,
     get_archived_branch_commits,
+    get_archived_branch_commits_page,
+    get_commit_diff,
     delete_archived_branch,
+    delete_archived_branches,
+    apply_archive_retention,
+    resume_integration_detection,
     unapply_branch,
     get_uncommitted_changes,
     get_file_content_for_diff,
@@ -61,6 +119,76 @@ pub fn run() {
     model_tauri::commands::check_model_status,
     model_tauri::commands::cancel_model_download,
     clear_model_cache,
+    simulate_conflict,
+    get_branch_order,
+    set_branch_pinned,
+    reorder_branches,
+    set_branch_commit_order,
+    get_commit_dependencies,
+    set_commit_dependencies,
+    export_branch_patches,
+    apply_patch_series,
+    get_smtp_config,
+    set_smtp_config,
+    get_cover_letter_template,
+    set_cover_letter_template,
+    send_branch_patches,
+    start_remote_status_refresh,
+    stop_remote_status_refresh,
+    rename_virtual_branch,
+    reword_commits,
+    model_tauri::commands::suggest_commit_message_stream,
+    model_tauri::commands::apply_suggested_message,
+    model_tauri::commands::suggest_commit_grouping,
+    model_tauri::commands::get_ai_provider_settings,
+    model_tauri::commands::set_ai_provider_settings,
+    model_tauri::commands::set_ai_provider_api_key,
+    model_tauri::commands::verify_model_cache,
+    subscribe_sync_events,
+    unsubscribe_sync_events,
+    sync_branches_dry_run,
+    get_remote_hosting_configs,
+    set_remote_hosting_override,
+    verify_metadata,
+    get_capabilities,
+    execute_operation_plan,
+    get_undo_snapshot,
+    undo_operation_plan,
+    squash_branch_commits,
+    rebase_branch_onto,
+    preview_amend,
+    reassign_commit,
+    drop_commits,
+    get_branch_diff,
+    get_branch_remote_divergence,
+    test_webhook_connection,
+    get_sync_history,
+    get_git_audit_log,
+    export_git_audit_log,
+    get_interrupted_sync,
+    recover_interrupted_sync,
+    recover_repository_state,
+    apply_recovery_option,
+    export_settings,
+    import_settings,
+    sync_workspace,
+    fix_prefix_typo,
+    resolve_binary_conflict,
+    launch_merge_tool,
+    get_branch_review_status,
+    get_crash_reports,
+    export_crash_report,
+    get_usage_stats,
+    record_usage,
+    get_branch_graph,
+    get_commit_mapping,
+    get_reverse_mapping,
+    check_baseline_update,
+    get_network_status,
+    set_offline_mode,
+    answer_credential_prompt,
+    detect_git_installations,
+    set_git_path,
   ]);
 
   // only export on non-release builds
@@ -93,6 +221,7 @@ pub fn run() {
         }
         true
       })
+      .target(crash_report::recent_events_target())
       .build(),
   );
 
@@ -111,9 +240,32 @@ pub fn run() {
     .setup(move |app| {
       ts_builder.mount_events(app);
 
+      if let Ok(app_log_dir) = app.path().app_log_dir() {
+        crash_report::install_panic_hook(crash_report::crash_reports_dir(&app_log_dir), app.package_info().version.to_string());
+      }
+
+      // Applied before any command can run git, so no repository is opened with the auto-detected executable first.
+      // An unusable custom executable falls back to the auto-detected one, the settings show the error when opened.
+      let git_executor = GitCommandExecutor::new();
+      let settings_path = app.path().app_data_dir().unwrap_or_default().join("settings.json");
+      if let Some(path) = load_git_path_setting(&settings_path)
+        && let Err(e) = git_executor.set_git_path(Some(&path))
+      {
+        tracing::warn!(error = %e, path, "Custom git executable is unusable, using the auto-detected one");
+      }
+      let git = git_executor.clone();
+      tauri::async_runtime::spawn_blocking(move || {
+        if let Ok(info) = git.get_info() {
+          crash_report::set_git_version(info.version);
+        }
+      });
+
       app.manage(MenuState::new());
-      app.manage(GitCommandExecutor::new());
+      app.manage(git_executor);
       app.manage(RepositoryStateCache::new());
+      app.manage(RemoteStatusScheduler::new());
+      app.manage(SyncEventSubscriptions::new());
+      app.manage(AskpassPrompts::new());
       app.manage(model_tauri::generator::ModelGeneratorState::new(
         model_tauri::generator::ModelBasedBranchGenerator::with_config(model_core::config::ModelConfig::default()).expect("Failed to create model-based generator"),
       ));
@@ -137,6 +289,9 @@ pub fn run() {
       let app_data_dir = app.path().app_data_dir().unwrap_or_default();
       let store_path = app_data_dir.join("settings.json");
 
+      #[cfg(feature = "telemetry")]
+      telemetry::init_telemetry(&app.package_info().name, telemetry::TelemetrySettings::load(&store_path).with_env_overrides());
+
       let store_data = if store_path.exists() {
         std::fs::read_to_string(&store_path).unwrap_or_else(|_| "{}".to_string())
       } else {
@@ -156,5 +311,10 @@ pub fn run() {
     })
     .build(tauri::generate_context!())
     .expect("error while running tauri application")
-    .run(|_app_handle, _event| {});
+    .run(|_app_handle, _event| {
+      #[cfg(feature = "telemetry")]
+      if let tauri::RunEvent::Exit = _event {
+        telemetry::shutdown_telemetry();
+      }
+    });
 }
@@ -2,5 +2,19 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+  // git starts the app as askpass program to ask for credentials through the running app
+  if std::env::var_os(branch_deck_lib::askpass::ASKPASS_ADDRESS_ENV).is_some() {
+    std::process::exit(branch_deck_lib::askpass::run_askpass_client());
+  }
+
+  // The app starts itself in this mode to run model inference in a separate process
+  if std::env::args().nth(1).as_deref() == Some(model_tauri::sidecar::SIDECAR_ARG) {
+    if let Err(e) = model_tauri::sidecar::run_sidecar() {
+      eprintln!("Model process failed: {e:#}");
+      std::process::exit(1);
+    }
+    return;
+  }
+
   branch_deck_lib::run();
 }
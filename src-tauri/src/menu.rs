@@ -42,7 +42,17 @@ pub fn configure_app_menu(app: &mut App) -> Result<(), Box<dyn Error>> {
     *menu_state.auto_sync_checkbox.write().await = Some(checkbox_clone);
   });
 
-  let file_menu = &SubmenuBuilder::new(app, "File").item(&sync_branches).separator().item(&auto_sync_checkbox).build()?;
+  let export_settings = MenuItemBuilder::with_id("export_settings", "Export Settings…").build(app)?;
+  let import_settings = MenuItemBuilder::with_id("import_settings", "Import Settings…").build(app)?;
+
+  let file_menu = &SubmenuBuilder::new(app, "File")
+    .item(&sync_branches)
+    .separator()
+    .item(&auto_sync_checkbox)
+    .separator()
+    .item(&export_settings)
+    .item(&import_settings)
+    .build()?;
 
   #[cfg(not(target_os = "linux"))]
   let edit_menu_builder = SubmenuBuilder::new(app, "Edit").items(&[
@@ -78,7 +88,14 @@ pub fn configure_app_menu(app: &mut App) -> Result<(), Box<dyn Error>> {
     .build()?;
 
   let github_link = MenuItemBuilder::with_id("github_link", "GitHub Repository").build(app)?;
-  let help_menu = &SubmenuBuilder::new(app, "Help").item(&github_link).build()?;
+  let export_crash_report = MenuItemBuilder::with_id("export_crash_report", "Export Crash Report…").build(app)?;
+  let usage_stats = MenuItemBuilder::with_id("show_usage_stats", "Usage Statistics").build(app)?;
+  let help_menu = &SubmenuBuilder::new(app, "Help")
+    .item(&github_link)
+    .separator()
+    .item(&usage_stats)
+    .item(&export_crash_report)
+    .build()?;
 
   let menu = Menu::with_items(
     app,
@@ -126,6 +143,24 @@ pub fn handle_menu_event(app: &tauri::AppHandle, event: MenuEvent) {
         tracing::error!(error = ?result.err(), "error while triggering sync branches");
       }
     }
+    "show_usage_stats" => {
+      let result = app.emit("show_usage_stats", ());
+      if result.is_err() {
+        tracing::error!(error = ?result.err(), "error while showing usage statistics");
+      }
+    }
+    "export_crash_report" => {
+      let result = app.emit("export_crash_report", ());
+      if result.is_err() {
+        tracing::error!(error = ?result.err(), "error while triggering crash report export");
+      }
+    }
+    "export_settings" | "import_settings" => {
+      let result = app.emit(event.id().as_ref(), ());
+      if result.is_err() {
+        tracing::error!(error = ?result.err(), "error while triggering settings transfer");
+      }
+    }
     "auto_sync_on_focus" => {
       // Get the checkbox's current state after toggle
       if let Some(menu) = app.menu()
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use git_ops::model::{BranchError, BranchSyncStatus};
 use git_ops::progress::ProgressCallback;
+use sync_core::workspace_sync::{WorkspaceProgressReporter, WorkspaceSyncEvent};
 use tauri::ipc::Channel;
 
 pub use sync_types::{ProgressReporter, SyncEvent};
@@ -24,6 +25,25 @@ impl ProgressReporter for TauriProgressReporter {
   }
 }
 
+/// Sends the events of a workspace sync over a Tauri Channel
+#[derive(Clone)]
+pub struct TauriWorkspaceProgressReporter {
+  channel: Channel<WorkspaceSyncEvent>,
+}
+
+impl TauriWorkspaceProgressReporter {
+  pub fn new(channel: Channel<WorkspaceSyncEvent>) -> Self {
+    Self { channel }
+  }
+}
+
+impl WorkspaceProgressReporter for TauriWorkspaceProgressReporter {
+  fn send(&self, event: WorkspaceSyncEvent) -> anyhow::Result<()> {
+    self.channel.send(event)?;
+    Ok(())
+  }
+}
+
 /// Adapter that implements ProgressCallback for Tauri Channel
 /// This allows git-ops to send progress updates through Tauri IPC
 pub struct TauriChannelProgress<'a> {
@@ -0,0 +1,267 @@
+use crate::progress::SyncEvent;
+use git_executor::git_command_executor::GitCommandExecutor;
+use git_ops::repository_lock::lock_repository;
+use hosting_api::ci_status::{CiClient, attach_ci_statuses, load_ci_client};
+use hosting_api::review_status::{ReviewClient, attach_review_summaries, load_review_client};
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use sync_core::baseline_update::check_baseline_update;
+use sync_core::ci_status::repository_ci_status_cache;
+use sync_core::network::remote_network_status;
+use sync_core::remote_status::{PUSH_REMOTE, RemoteHeads, refresh_remote_status_for_virtual_branches, tracking_heads};
+use sync_core::sync::detect_baseline_branch;
+use sync_types::{BaselineAdvanced, NetworkStatus, RemoteStatusUpdate};
+use tauri::ipc::Channel;
+use tokio::sync::Semaphore;
+use tokio::task::AbortHandle;
+use tracing::{debug, info, instrument, warn};
+
+/// Default time between two refreshes of a repository
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+/// Refreshing more often would mostly put load on the remote
+pub const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// Longest interval that can be configured, also the upper bound of the backoff after failed refreshes
+pub const MAX_REFRESH_INTERVAL: Duration = Duration::from_secs(1800);
+/// Fetches running at the same time across all repositories
+const MAX_CONCURRENT_FETCHES: usize = 2;
+
+/// What to refresh and how often
+#[derive(Clone, Debug)]
+pub struct RefreshConfig {
+  pub repository_path: String,
+  pub branch_prefix: String,
+  pub interval: Duration,
+}
+
+/// Interval between refreshes, [`DEFAULT_REFRESH_INTERVAL`] if not set.
+/// Fails if it's shorter than [`MIN_REFRESH_INTERVAL`] or longer than [`MAX_REFRESH_INTERVAL`].
+pub fn refresh_interval(interval_seconds: Option<u32>) -> anyhow::Result<Duration> {
+  let Some(seconds) = interval_seconds else {
+    return Ok(DEFAULT_REFRESH_INTERVAL);
+  };
+  let interval = Duration::from_secs(seconds.into());
+  if !(MIN_REFRESH_INTERVAL..=MAX_REFRESH_INTERVAL).contains(&interval) {
+    anyhow::bail!(
+      "Refresh interval must be between {} and {} seconds, got {seconds}",
+      MIN_REFRESH_INTERVAL.as_secs(),
+      MAX_REFRESH_INTERVAL.as_secs()
+    );
+  }
+  Ok(interval)
+}
+
+/// Periodically queries the remote heads and recomputes the remote status of virtual branches, one background task per repository.
+/// With `branchdeck.reviewStatus` enabled, the review activity of the pull requests of pushed branches is queried as well,
+/// with `branchdeck.ciStatus` the CI status of their head commits.
+/// Updates are sent as `SyncEvent::RemoteStatusUpdate`, the same event sync uses. When the baseline advanced on the remote
+/// since the last sync, `SyncEvent::BaselineAdvanced` is sent once per new remote head.
+/// Without network the refresh is skipped, and `SyncEvent::NetworkStatusChanged` tells the UI why nothing is updated.
+/// A refresh moves remote-tracking refs, so it's skipped as well while a sync or its background phases hold the repository lock.
+pub struct RemoteStatusScheduler {
+  tasks: Mutex<HashMap<String, AbortHandle>>,
+  fetch_permits: Arc<Semaphore>,
+}
+
+impl RemoteStatusScheduler {
+  pub fn new() -> Self {
+    Self {
+      tasks: Mutex::new(HashMap::new()),
+      fetch_permits: Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES)),
+    }
+  }
+
+  /// Start refreshing a repository, replacing the task previously started for it (e.g. by an earlier sync with another channel)
+  #[instrument(skip(self, git_executor, channel), fields(repository_path = %config.repository_path))]
+  pub fn start(&self, git_executor: GitCommandExecutor, config: RefreshConfig, channel: Channel<SyncEvent>) {
+    let repository_path = config.repository_path.clone();
+    let handle = tokio::spawn(refresh_loop(git_executor, config, channel, self.fetch_permits.clone()));
+    if let Some(previous) = self.tasks.lock().unwrap().insert(repository_path, handle.abort_handle()) {
+      previous.abort();
+    }
+  }
+
+  /// Stop refreshing a repository, returns false if no refresh was running
+  #[instrument(skip(self))]
+  pub fn stop(&self, repository_path: &str) -> bool {
+    match self.tasks.lock().unwrap().remove(repository_path) {
+      Some(handle) => {
+        handle.abort();
+        true
+      }
+      None => false,
+    }
+  }
+}
+
+impl Default for RemoteStatusScheduler {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Spread refreshes by ±20%, so repositories opened together don't fetch in lockstep
+pub(crate) fn jittered(interval: Duration) -> Duration {
+  let random = RandomState::new().hash_one(Instant::now());
+  interval.mul_f64(0.8 + (random % 1000) as f64 / 2500.0)
+}
+
+/// Interval after a failed refresh, doubled up to [`MAX_REFRESH_INTERVAL`]
+pub(crate) fn backoff(interval: Duration) -> Duration {
+  (interval * 2).min(MAX_REFRESH_INTERVAL)
+}
+
+async fn refresh_loop(git_executor: GitCommandExecutor, config: RefreshConfig, channel: Channel<SyncEvent>, fetch_permits: Arc<Semaphore>) {
+  let base_interval = config.interval;
+  let mut interval = base_interval;
+  // Kept across refreshes, so final CI statuses are not queried again, and shared with the sync
+  let ci_status_cache = repository_ci_status_cache(&config.repository_path);
+  // Remote baseline head already reported, so an unchanged remote is not reported on every refresh
+  let mut reported_baseline_head: Option<String> = None;
+  let mut reported_network_status: Option<NetworkStatus> = None;
+  loop {
+    tokio::time::sleep(jittered(interval)).await;
+
+    let network_status = {
+      let git = git_executor.clone();
+      let repository_path = config.repository_path.clone();
+      tokio::task::spawn_blocking(move || remote_network_status(&git, &repository_path, Some(PUSH_REMOTE)))
+        .await
+        .unwrap_or(NetworkStatus::Online)
+    };
+    if reported_network_status != Some(network_status) {
+      reported_network_status = Some(network_status);
+      if channel.send(SyncEvent::NetworkStatusChanged { status: network_status }).is_err() {
+        debug!("Event channel closed, stopping remote status refresh");
+        return;
+      }
+    }
+    // Not a failure, so no backoff: the refresh resumes at the next interval once back online
+    if network_status.is_offline() {
+      debug!(?network_status, "Offline, skipping remote status refresh");
+      interval = base_interval;
+      continue;
+    }
+
+    let result = {
+      // The semaphore is never closed
+      let _permit = fetch_permits.acquire().await.expect("fetch semaphore closed");
+      let git = git_executor.clone();
+      let config = config.clone();
+      tokio::task::spawn_blocking(move || refresh_once(&git, &config)).await
+    };
+
+    match result {
+      Ok(Ok(RefreshOutcome::Refreshed(refreshed))) => {
+        interval = base_interval;
+        let RefreshResult {
+          mut updates,
+          review_client,
+          ci_client,
+          baseline_advanced,
+        } = *refreshed;
+        // Not fatal: the remote status is still up to date
+        if let Some(review_client) = review_client
+          && let Err(e) = attach_review_summaries(&review_client, &config.branch_prefix, &mut updates).await
+        {
+          warn!(error = %e, "Failed to fetch review status");
+        }
+        if let Some((ci_client, remote_heads)) = ci_client
+          && let Err(e) = attach_ci_statuses(&ci_client, &ci_status_cache, &config.branch_prefix, &remote_heads, &mut updates).await
+        {
+          warn!(error = %e, "Failed to fetch CI status");
+        }
+        debug!(branch_count = updates.len(), "Refreshed remote status");
+        let baseline_event = baseline_advanced
+          .filter(|advanced| reported_baseline_head.as_ref() != Some(&advanced.remote_head))
+          .map(|advanced| {
+            reported_baseline_head = Some(advanced.remote_head.clone());
+            SyncEvent::BaselineAdvanced(advanced)
+          });
+        for event in updates.into_iter().map(SyncEvent::RemoteStatusUpdate).chain(baseline_event) {
+          if channel.send(event).is_err() {
+            debug!("Event channel closed, stopping remote status refresh");
+            return;
+          }
+        }
+      }
+      // Not a failure either, the next refresh is likely to find the repository unlocked
+      Ok(Ok(RefreshOutcome::Locked)) => {
+        debug!("Repository is locked by another operation, skipping remote status refresh");
+        interval = base_interval;
+      }
+      Ok(Ok(RefreshOutcome::NoOrigin)) => {
+        info!(repository_path = %config.repository_path, "Repository has no origin remote, stopping remote status refresh");
+        return;
+      }
+      Ok(Err(e)) => {
+        // Back off while the remote is unreachable
+        interval = backoff(interval);
+        warn!(error = %e, next_attempt_secs = interval.as_secs(), "Failed to refresh remote status");
+      }
+      Err(e) => warn!(error = %e, "Remote status refresh task failed"),
+    }
+  }
+}
+
+/// What a refresh did
+pub(crate) enum RefreshOutcome {
+  Refreshed(Box<RefreshResult>),
+  /// There is no origin remote, so nothing to refresh ever
+  NoOrigin,
+  /// Another operation, e.g. a sync, holds the repository lock
+  Locked,
+}
+
+/// Remote status of every virtual branch, with the clients of the hosting provider queries that are enabled
+pub(crate) struct RefreshResult {
+  updates: Vec<RemoteStatusUpdate>,
+  review_client: Option<ReviewClient>,
+  /// Client for the CI status, with the heads of the pushed branches
+  ci_client: Option<(CiClient, RemoteHeads)>,
+  /// `None` if the baseline didn't advance on the remote since the last sync
+  baseline_advanced: Option<BaselineAdvanced>,
+}
+
+/// Query the pushed branch heads on origin and compute the status of every virtual branch
+pub(crate) fn refresh_once(git_executor: &GitCommandExecutor, config: &RefreshConfig) -> anyhow::Result<RefreshOutcome> {
+  let repository_path = config.repository_path.as_str();
+  let remotes = git_executor.execute_command_lines(&["remote"], repository_path)?;
+  if !remotes.iter().any(|remote| remote == PUSH_REMOTE) {
+    return Ok(RefreshOutcome::NoOrigin);
+  }
+  // Held until the refs are updated, the hosting provider queries run without it
+  let Ok(_lock) = lock_repository(git_executor, repository_path) else {
+    return Ok(RefreshOutcome::Locked);
+  };
+
+  let baseline_branch = detect_baseline_branch(git_executor, repository_path, "master")?;
+  let my_email = git_executor.execute_command(&["config", "user.email"], repository_path).ok();
+  // One ls-remote round trip instead of fetching the whole remote
+  let updates = refresh_remote_status_for_virtual_branches(git_executor, repository_path, &config.branch_prefix, &baseline_branch, my_email.as_deref())?;
+  let review_client = load_review_client(git_executor, repository_path, PUSH_REMOTE).unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to set up review status");
+    None
+  });
+  let ci_client = load_ci_client(git_executor, repository_path, PUSH_REMOTE).unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to set up CI status");
+    None
+  });
+  // The remote-tracking refs were just updated from the remote heads
+  let ci_client = match ci_client {
+    Some(ci_client) => Some((ci_client, tracking_heads(git_executor, repository_path, &config.branch_prefix)?)),
+    None => None,
+  };
+  // Not fatal either, the remote status was refreshed
+  let baseline_advanced = check_baseline_update(git_executor, repository_path).unwrap_or_else(|e| {
+    warn!(error = %e, "Failed to check the remote baseline");
+    None
+  });
+  Ok(RefreshOutcome::Refreshed(Box::new(RefreshResult {
+    updates,
+    review_client,
+    ci_client,
+    baseline_advanced,
+  })))
+}
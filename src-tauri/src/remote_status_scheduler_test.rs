@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+  use super::super::remote_status_scheduler::{
+    DEFAULT_REFRESH_INTERVAL, MAX_REFRESH_INTERVAL, MIN_REFRESH_INTERVAL, RefreshConfig, RefreshOutcome, RemoteStatusScheduler, backoff, jittered, refresh_interval, refresh_once,
+  };
+  use git_executor::git_command_executor::GitCommandExecutor;
+  use git_ops::repository_lock::lock_repository;
+  use std::time::Duration;
+  use tauri::ipc::Channel;
+  use tempfile::TempDir;
+
+  #[test]
+  fn test_refresh_interval() {
+    assert_eq!(refresh_interval(None).unwrap(), DEFAULT_REFRESH_INTERVAL);
+    assert_eq!(refresh_interval(Some(60)).unwrap(), MIN_REFRESH_INTERVAL);
+    assert_eq!(refresh_interval(Some(1800)).unwrap(), MAX_REFRESH_INTERVAL);
+    // Out of range intervals are rejected rather than silently changed
+    let error = refresh_interval(Some(10)).unwrap_err();
+    assert_eq!(error.to_string(), "Refresh interval must be between 60 and 1800 seconds, got 10");
+    assert!(refresh_interval(Some(0)).is_err());
+    assert!(refresh_interval(Some(3600)).is_err());
+  }
+
+  #[test]
+  fn test_jittered_interval() {
+    for _ in 0..100 {
+      let interval = jittered(Duration::from_secs(300));
+      assert!(interval >= Duration::from_secs(240) && interval <= Duration::from_secs(360), "{interval:?}");
+    }
+  }
+
+  #[test]
+  fn test_backoff() {
+    assert_eq!(backoff(Duration::from_secs(300)), Duration::from_secs(600));
+    assert_eq!(backoff(Duration::from_secs(1200)), MAX_REFRESH_INTERVAL);
+    assert_eq!(backoff(MAX_REFRESH_INTERVAL), MAX_REFRESH_INTERVAL);
+  }
+
+  #[tokio::test]
+  async fn test_start_and_stop() {
+    let scheduler = RemoteStatusScheduler::new();
+    let git_executor = GitCommandExecutor::new();
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().to_str().unwrap();
+    git_executor.execute_command(&["init"], repo_path).unwrap();
+
+    let config = RefreshConfig {
+      repository_path: repo_path.to_string(),
+      branch_prefix: "user".to_string(),
+      interval: DEFAULT_REFRESH_INTERVAL,
+    };
+    assert!(!scheduler.stop(repo_path));
+    scheduler.start(git_executor.clone(), config.clone(), Channel::new(|_| Ok(())));
+    // Starting again replaces the running refresh
+    scheduler.start(git_executor, config, Channel::new(|_| Ok(())));
+    assert!(scheduler.stop(repo_path));
+    assert!(!scheduler.stop(repo_path));
+  }
+
+  #[test]
+  fn test_refresh_skipped_while_repository_is_locked() {
+    let git_executor = GitCommandExecutor::new();
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().to_str().unwrap();
+    git_executor.execute_command(&["init"], repo_path).unwrap();
+    let config = RefreshConfig {
+      repository_path: repo_path.to_string(),
+      branch_prefix: "user".to_string(),
+      interval: DEFAULT_REFRESH_INTERVAL,
+    };
+    assert!(matches!(refresh_once(&git_executor, &config).unwrap(), RefreshOutcome::NoOrigin));
+
+    // A sync holds the lock, the unreachable remote is not even queried
+    git_executor
+      .execute_command(&["remote", "add", "origin", "https://example.invalid/repo.git"], repo_path)
+      .unwrap();
+    let _lock = lock_repository(&git_executor, repo_path).unwrap();
+    assert!(matches!(refresh_once(&git_executor, &config).unwrap(), RefreshOutcome::Locked));
+  }
+}
@@ -1,4 +1,6 @@
 use git_executor::git_command_executor::GitCommandExecutor;
+use git_executor::git_info::{GitInfo, MIN_GIT_VERSION};
+use git_ops::error::{CommandError, ErrorCode};
 use moka::sync::Cache;
 use std::sync::Arc;
 use std::time::Duration;
@@ -40,7 +42,7 @@ impl RepositoryStateCache {
       // If Git version was previously validated as invalid, return the cached error
       if !cached.git_version_valid {
         let error_msg = cached.git_version_error.as_deref().unwrap_or("Git version validation failed (cached)");
-        return Err(anyhow::anyhow!("{}", error_msg));
+        return Err(unsupported_git_version(&git_executor.get_info()?, error_msg));
       }
       return Ok(cached);
     }
@@ -63,7 +65,7 @@ impl RepositoryStateCache {
           git_version_error: Some(error_msg.clone()),
         });
         self.cache.insert(repository_path.to_string(), state);
-        return Err(anyhow::anyhow!("{}", error_msg));
+        return Err(unsupported_git_version(&git_info, &error_msg));
       }
     };
 
@@ -120,3 +122,13 @@ impl Default for RepositoryStateCache {
     Self::new()
   }
 }
+
+/// Coded error, so the UI can offer to choose another git installation
+fn unsupported_git_version(git_info: &GitInfo, message: &str) -> anyhow::Error {
+  let (min_major, min_minor) = MIN_GIT_VERSION;
+  CommandError::new(ErrorCode::UnsupportedGitVersion, message)
+    .with_context("gitPath", &git_info.path)
+    .with_context("version", &git_info.version)
+    .with_context("minimumVersion", format!("{min_major}.{min_minor}"))
+    .into_anyhow()
+}
@@ -0,0 +1,81 @@
+use crate::progress::{SyncEvent, TauriProgressReporter};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use sync_types::ProgressReporter;
+use sync_types::event_filter::{FilteredProgressReporter, SyncEventFilter};
+use tauri::ipc::Channel;
+use tracing::{debug, instrument};
+
+struct Subscription {
+  repository_path: String,
+  reporter: FilteredProgressReporter<TauriProgressReporter>,
+}
+
+/// Sync event subscriptions of sub-windows (e.g. the conflict viewer), each with its own filter.
+/// Events are filtered before they are sent, so a subscriber only deserializes the events it asked for.
+#[derive(Default)]
+pub struct SyncEventSubscriptions {
+  next_id: AtomicU32,
+  subscriptions: Arc<Mutex<HashMap<u32, Subscription>>>,
+}
+
+impl SyncEventSubscriptions {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Receive the events of syncs of a repository that match the filter, returns the subscription ID
+  #[instrument(skip(self, channel))]
+  pub fn subscribe(&self, repository_path: String, filter: SyncEventFilter, channel: Channel<SyncEvent>) -> u32 {
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+    let reporter = FilteredProgressReporter::new(TauriProgressReporter::new(channel), filter);
+    self.subscriptions.lock().unwrap().insert(id, Subscription { repository_path, reporter });
+    id
+  }
+
+  /// Stop sending events to a subscriber, returns false if there was no such subscription
+  #[instrument(skip(self))]
+  pub fn unsubscribe(&self, id: u32) -> bool {
+    self.subscriptions.lock().unwrap().remove(&id).is_some()
+  }
+
+  /// Reporter for a sync of `repository_path`: events go to the channel of the sync command
+  /// and to the current subscribers of the repository
+  pub fn broadcast<P: ProgressReporter>(&self, repository_path: &str, primary: P) -> BroadcastProgressReporter<P> {
+    BroadcastProgressReporter {
+      primary,
+      repository_path: repository_path.to_string(),
+      subscriptions: self.subscriptions.clone(),
+    }
+  }
+}
+
+#[derive(Clone)]
+pub struct BroadcastProgressReporter<P: ProgressReporter> {
+  primary: P,
+  repository_path: String,
+  subscriptions: Arc<Mutex<HashMap<u32, Subscription>>>,
+}
+
+impl<P: ProgressReporter> ProgressReporter for BroadcastProgressReporter<P> {
+  fn send(&self, event: SyncEvent) -> anyhow::Result<()> {
+    {
+      let mut subscriptions = self.subscriptions.lock().unwrap();
+      // A subscriber whose window is gone without unsubscribing is dropped on the first failed send
+      subscriptions.retain(|id, subscription| {
+        if subscription.repository_path != self.repository_path || !subscription.reporter.filter().accepts(&event) {
+          return true;
+        }
+        match subscription.reporter.send(event.clone()) {
+          Ok(()) => true,
+          Err(e) => {
+            debug!(id, error = %e, "Failed to send sync event to subscriber, unsubscribing");
+            false
+          }
+        }
+      });
+    }
+    self.primary.send(event)
+  }
+}
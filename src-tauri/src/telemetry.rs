@@ -1,84 +1,136 @@
+//! Export of tracing spans to an OpenTelemetry collector over OTLP/HTTP, e.g. to profile long syncs of large
+//! repositories: every git command and branch step has its own span.
+//!
+//! Configured in the `telemetry` object of `settings.json`. The standard `OTEL_EXPORTER_OTLP_ENDPOINT` and
+//! `OTEL_SERVICE_NAME` environment variables take precedence, and setting the endpoint variable enables the export.
+
 use opentelemetry::global;
-use opentelemetry_otlp::{WithExportConfig, WithHttpConfig};
-use opentelemetry_sdk::trace::Tracer;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::{BatchSpanProcessor, Sampler, SdkTracerProvider, Tracer};
+use serde::Deserialize;
 use std::env;
 use std::error::Error;
+use std::path::Path;
+use std::sync::OnceLock;
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 
-use opentelemetry::trace::TracerProvider;
-use opentelemetry_otlp::SpanExporter;
-use opentelemetry_sdk::trace::{BatchSpanProcessor, SdkTracerProvider};
+/// HTTP endpoint of a local collector, gRPC would use port 4317
+const DEFAULT_ENDPOINT: &str = "http://127.0.0.1:4318";
 
-/// Initialize telemetry with immediate OpenTelemetry setup using Tauri's runtime
-pub fn init_telemetry(app_name: &str) {
-  // Only initialize if no global subscriber is set yet
-  if tracing::dispatcher::has_been_set() {
-    return;
-  }
+static TRACER_PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
 
-  // Initialize LogTracer to convert log records to tracing events
-  let _ = tracing_log::LogTracer::init();
+/// The `telemetry` object of `settings.json`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TelemetrySettings {
+  pub enabled: bool,
+  /// Base URL of the OTLP/HTTP collector, `/v1/traces` is appended
+  pub endpoint: Option<String>,
+  pub service_name: Option<String>,
+  /// Fraction of traces exported, from 0 to 1. All traces when not set.
+  pub sampling_ratio: Option<f64>,
+}
 
-  // Set up tracing subscriber with environment filter
-  let filter = tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
-    #[cfg(debug_assertions)]
-    return tracing_subscriber::EnvFilter::new("debug,hyper=info,reqwest=info,tokenizers=off,candle=off,candle_core=off,candle_nn=off");
-    #[cfg(not(debug_assertions))]
-    return tracing_subscriber::EnvFilter::new("info,tokenizers=off,candle=off,candle_core=off,candle_nn=off");
-  });
+impl TelemetrySettings {
+  /// Read the settings from `settings.json`, a missing or invalid file disables the export
+  pub fn load(settings_path: &Path) -> Self {
+    let Ok(content) = std::fs::read_to_string(settings_path) else {
+      return Self::default();
+    };
+    Self::parse(&content).unwrap_or_else(|e| {
+      eprintln!("Invalid telemetry settings: {e}");
+      Self::default()
+    })
+  }
 
-  // Initialize OpenTelemetry tracer if OTLP endpoint is configured
-  let telemetry_layer = if env::var("OTEL_EXPORTER_OTLP_ENDPOINT").is_ok() {
-    match init_opentelemetry(app_name) {
-      Ok(tracer) => Some(tracing_opentelemetry::OpenTelemetryLayer::new(tracer)),
-      Err(e) => {
-        eprintln!("Failed to initialize OpenTelemetry: {e}");
-        None
-      }
+  pub fn parse(settings_json: &str) -> serde_json::Result<Self> {
+    let mut settings: serde_json::Value = serde_json::from_str(settings_json)?;
+    match settings.get_mut("telemetry") {
+      Some(telemetry) => serde_json::from_value(telemetry.take()),
+      None => Ok(Self::default()),
     }
-  } else {
-    None
-  };
+  }
+
+  /// Apply the standard OpenTelemetry environment variables
+  pub fn with_env_overrides(mut self) -> Self {
+    if let Ok(endpoint) = env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+      self.enabled = true;
+      self.endpoint = Some(endpoint);
+    }
+    if let Ok(service_name) = env::var("OTEL_SERVICE_NAME") {
+      self.service_name = Some(service_name);
+    }
+    self
+  }
 
-  // Build the subscriber with all layers
-  let subscriber = tracing_subscriber::registry().with(filter).with(tracing_subscriber::fmt::layer());
+  pub fn traces_endpoint(&self) -> String {
+    let endpoint = self.endpoint.as_deref().map(str::trim).filter(|endpoint| !endpoint.is_empty()).unwrap_or(DEFAULT_ENDPOINT);
+    format!("{}/v1/traces", endpoint.trim_end_matches('/'))
+  }
 
-  if let Some(telemetry) = telemetry_layer {
-    use tracing_subscriber::util::SubscriberInitExt;
-    subscriber.with(telemetry).try_init().ok();
-  } else {
-    use tracing_subscriber::util::SubscriberInitExt;
-    subscriber.try_init().ok();
+  pub fn sampler(&self) -> Sampler {
+    match self.sampling_ratio {
+      Some(ratio) if ratio < 1.0 => Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(ratio.max(0.0)))),
+      _ => Sampler::AlwaysOn,
+    }
   }
 }
 
-fn init_opentelemetry(service_name: &str) -> Result<Tracer, Box<dyn Error>> {
-  // Configure the OpenTelemetry exporter for HTTP endpoint
-  // Note: HTTP endpoint typically uses port 4318, gRPC uses 4317
-  let endpoint = env::var("OTEL_EXPORTER_OTLP_ENDPOINT").unwrap_or_else(|_| "http://127.0.0.1:4318".to_string());
-
-  // Create a reqwest client that will use the current Tokio runtime (Tauri's)
-  // Since this function is called after Tauri setup, we're in Tauri's runtime context
-  let client = reqwest::blocking::ClientBuilder::new().build()?;
+/// Install a tracing subscriber exporting spans if telemetry is enabled.
+/// Does nothing if it's disabled or another subscriber is already set (e.g. by the devtools plugin).
+pub fn init_telemetry(app_name: &str, settings: TelemetrySettings) {
+  if !settings.enabled || tracing::dispatcher::has_been_set() {
+    return;
+  }
 
-  let exporter = SpanExporter::builder()
-    .with_http()
-    .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
-    .with_http_client(client)
-    .build()?;
+  let tracer = match init_opentelemetry(app_name, &settings) {
+    Ok(tracer) => tracer,
+    Err(e) => {
+      eprintln!("Failed to initialize OpenTelemetry: {e}");
+      return;
+    }
+  };
 
-  // Create a simple batch processor that will use the current runtime (Tauri's)
-  let batch_processor = BatchSpanProcessor::builder(exporter).build();
+  let filter = tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+    #[cfg(debug_assertions)]
+    return tracing_subscriber::EnvFilter::new("debug,hyper=info,reqwest=info,tokenizers=off,candle=off,candle_core=off,candle_nn=off");
+    #[cfg(not(debug_assertions))]
+    return tracing_subscriber::EnvFilter::new("info,tokenizers=off,candle=off,candle_core=off,candle_nn=off");
+  });
 
-  // Create the provider with the batch processor
-  // Use default resource for now to avoid API complexity
-  let provider = SdkTracerProvider::builder().with_span_processor(batch_processor).build();
+  // Events of an installed subscriber no longer reach the log plugin, so they are printed here
+  tracing_subscriber::registry()
+    .with(filter)
+    .with(tracing_subscriber::fmt::layer())
+    .with(tracing_opentelemetry::OpenTelemetryLayer::new(tracer))
+    .try_init()
+    .ok();
+}
 
-  // Set as global provider
-  let _ = global::set_tracer_provider(provider.clone());
+fn init_opentelemetry(app_name: &str, settings: &TelemetrySettings) -> Result<Tracer, Box<dyn Error>> {
+  let exporter = SpanExporter::builder().with_http().with_endpoint(settings.traces_endpoint()).build()?;
 
-  // Get a tracer
-  let tracer = provider.tracer(service_name.to_string());
+  let service_name = settings.service_name.clone().unwrap_or_else(|| app_name.to_string());
+  let provider = SdkTracerProvider::builder()
+    .with_span_processor(BatchSpanProcessor::builder(exporter).build())
+    .with_sampler(settings.sampler())
+    .with_resource(Resource::builder().with_service_name(service_name.clone()).build())
+    .build();
 
+  global::set_tracer_provider(provider.clone());
+  let tracer = provider.tracer(service_name);
+  let _ = TRACER_PROVIDER.set(provider);
   Ok(tracer)
 }
+
+/// Export the spans still in the batch, called on app exit
+pub fn shutdown_telemetry() {
+  if let Some(provider) = TRACER_PROVIDER.get()
+    && let Err(e) = provider.shutdown()
+  {
+    eprintln!("Failed to shut down OpenTelemetry: {e}");
+  }
+}
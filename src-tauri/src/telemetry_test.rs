@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod tests {
+  use super::super::telemetry::TelemetrySettings;
+  use opentelemetry_sdk::trace::Sampler;
+
+  #[test]
+  fn test_parse_telemetry_settings() {
+    let settings = TelemetrySettings::parse(r#"{"theme":"dark","telemetry":{"enabled":true,"endpoint":"http://collector:4318/","samplingRatio":0.25}}"#).unwrap();
+    assert_eq!(
+      settings,
+      TelemetrySettings {
+        enabled: true,
+        endpoint: Some("http://collector:4318/".to_string()),
+        service_name: None,
+        sampling_ratio: Some(0.25),
+      }
+    );
+    assert_eq!(settings.traces_endpoint(), "http://collector:4318/v1/traces");
+    assert!(matches!(settings.sampler(), Sampler::ParentBased(_)));
+  }
+
+  #[test]
+  fn test_telemetry_disabled_without_settings() {
+    let settings = TelemetrySettings::parse(r#"{"theme":"dark"}"#).unwrap();
+    assert!(!settings.enabled);
+    assert_eq!(settings.traces_endpoint(), "http://127.0.0.1:4318/v1/traces");
+    assert!(matches!(settings.sampler(), Sampler::AlwaysOn));
+  }
+}
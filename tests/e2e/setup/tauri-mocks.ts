@@ -85,7 +85,7 @@ export const tauriMockScript = () => {
         // Return a Result type with error status to match Tauri bindings
         const result = {
           status: "error",
-          error: `${response.statusText} - ${error}`,
+          error: { code: "internal", message: `${response.statusText} - ${error}`, context: {} },
         }
         return result
       }
@@ -120,7 +120,7 @@ export const tauriMockScript = () => {
       // Network errors should also be wrapped in Result type
       return {
         status: "error",
-        error: `Network error: ${networkError.message}`,
+        error: { code: "internal", message: `Network error: ${networkError.message}`, context: {} },
       }
     }
   }